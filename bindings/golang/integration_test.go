@@ -11,6 +11,7 @@ package smg
 
 import (
 	"context"
+	"errors"
 	"io"
 	"os"
 	"testing"
@@ -219,6 +220,64 @@ func TestIntegrationContextCancellation(t *testing.T) {
 }
 
 // Helper functions
+// TestIntegrationMultiClientCallbackStreaming tests push-based streaming via callback
+func TestIntegrationMultiClientCallbackStreaming(t *testing.T) {
+	config := getTestConfig(t)
+
+	client, err := NewMultiClient(MultiClientConfig{
+		Endpoints:     config.Endpoint,
+		TokenizerPath: config.TokenizerPath,
+	})
+	if err != nil {
+		t.Fatalf("Failed to create multi client: %v", err)
+	}
+	defer client.Close()
+
+	ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+
+	req := ChatCompletionRequest{
+		Model: "default",
+		Messages: []ChatMessage{
+			{Role: "user", Content: "Count from 1 to 5"},
+		},
+		Stream:              true,
+		Temperature:         float32Ptr(0.0),
+		MaxCompletionTokens: intPtr(50),
+	}
+
+	chunkCount := 0
+	err = client.StreamChatCompletion(ctx, req, func(chunkJSON string) error {
+		chunkCount++
+		return nil
+	})
+	if err != nil {
+		t.Fatalf("StreamChatCompletion failed: %v", err)
+	}
+	if chunkCount == 0 {
+		t.Error("No chunks received")
+	}
+
+	// A handler error stops the stream and is returned as-is
+	stopErr := errors.New("stop")
+	err = client.StreamChatCompletion(ctx, req, func(chunkJSON string) error {
+		return stopErr
+	})
+	if !errors.Is(err, stopErr) {
+		t.Errorf("Expected handler error, got %v", err)
+	}
+
+	// Cancelling the context aborts the stream
+	cancelCtx, cancelNow := context.WithCancel(context.Background())
+	err = client.StreamChatCompletion(cancelCtx, req, func(chunkJSON string) error {
+		cancelNow()
+		return nil
+	})
+	if err != nil && !errors.Is(err, context.Canceled) {
+		t.Errorf("Expected context.Canceled or completion, got %v", err)
+	}
+}
+
 func float32Ptr(f float32) *float32 {
 	return &f
 }
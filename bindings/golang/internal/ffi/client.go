@@ -36,11 +36,25 @@ SglErrorCode sgl_client_chat_completion_stream(SglangClientHandle* client_handle
 SglErrorCode sgl_stream_read_next(SglangStreamHandle* stream_handle, char** response_json_out, int* is_done_out, char** error_out);
 void sgl_stream_free(SglangStreamHandle* handle);
 void sgl_free_string(char* s);
+
+// Streaming callback API
+typedef int (*SglStreamCallback)(void* user_data, const char* chunk_json, int is_done, const char* error);
+SglErrorCode sgl_stream_set_callback(SglangStreamHandle* stream_handle, SglStreamCallback callback, void* user_data, char** error_out);
+void sgl_stream_cancel(SglangStreamHandle* stream_handle);
+
+// Implemented in Go (stream_callback.go)
+extern int goStreamChunkCallback(void* user_data, char* chunk_json, int is_done, char* error);
+
+// Registers the Go trampoline; user_data carries a cgo.Handle.
+static inline SglErrorCode sgl_stream_set_go_callback(SglangStreamHandle* stream_handle, uintptr_t user_data, char** error_out) {
+    return sgl_stream_set_callback(stream_handle, (SglStreamCallback)goStreamChunkCallback, (void*)user_data, error_out);
+}
 */
 import "C"
 
 import (
 	"fmt"
+	"runtime/cgo"
 	"unsafe"
 )
 
@@ -219,7 +233,52 @@ func (h *SglangStreamHandle) ReadNext() (string, bool, error) {
 	return responseStr, isDone == 1, nil
 }
 
-// Free releases the stream handle
+// SetCallback switches the stream to push mode: the Rust runtime drives the
+// stream and invokes cb for every chunk, so no ReadNext calls are needed.
+//
+// cb runs on a Rust runtime thread, not a goroutine started by the caller. It is
+// called exactly once with isDone=true when the stream completes, fails or is
+// cancelled, unless it returned false earlier. ReadNext fails once a callback
+// is registered.
+func (h *SglangStreamHandle) SetCallback(cb StreamCallback) error {
+	if h.handle == nil {
+		return fmt.Errorf("stream handle is nil")
+	}
+
+	userData := cgo.NewHandle(cb)
+	var errorPtr *C.char
+
+	result := C.sgl_stream_set_go_callback(h.handle, C.uintptr_t(userData), &errorPtr)
+
+	if ErrorCode(result) != ErrorSuccess {
+		userData.Delete()
+		errorMsg := ""
+		if errorPtr != nil {
+			errorMsg = C.GoString(errorPtr)
+			C.sgl_free_string(errorPtr)
+		}
+		if errorMsg == "" {
+			errorMsg = fmt.Sprintf("error code %d", result)
+		}
+		return fmt.Errorf("%s", errorMsg)
+	}
+
+	return nil
+}
+
+// Cancel stops the stream. A registered callback receives a final call with
+// isDone=true and a cancellation error; the request is aborted on the server
+// when the handle is freed. Cancel must not race with Free.
+func (h *SglangStreamHandle) Cancel() {
+	if h.handle != nil {
+		C.sgl_stream_cancel(h.handle)
+	}
+}
+
+// Free releases the stream handle.
+//
+// A running callback is cancelled and waited for, so it is never invoked after
+// Free returns. Free must not be called from within the callback.
 func (h *SglangStreamHandle) Free() {
 	if h.handle != nil {
 		C.sgl_stream_free(h.handle)
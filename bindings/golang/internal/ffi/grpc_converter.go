@@ -38,6 +38,7 @@ void sgl_grpc_response_converter_free(GrpcResponseConverterHandle* handle);
 
 // Tokenizer functions
 TokenizerHandle* sgl_tokenizer_create_from_file(const char* tokenizer_path, char** error_out);
+TokenizerHandle* sgl_tokenizer_clone_handle(TokenizerHandle* handle);
 void sgl_tokenizer_free(TokenizerHandle* handle);
 
 // Memory management
@@ -214,7 +215,12 @@ func FreeGrpcResponseConverter(handle *GrpcResponseConverterHandle) {
 	}
 }
 
-// TokenizerHandle wraps the Rust tokenizer FFI handle
+// TokenizerHandle wraps the Rust tokenizer FFI handle.
+//
+// A handle is safe for concurrent use by multiple goroutines: the underlying
+// tokenizer is immutable and reference counted on the Rust side. Use
+// CloneTokenizerHandle to give a component its own handle to the same
+// tokenizer; every handle must be freed exactly once, after its last use.
 type TokenizerHandle struct {
 	handle *C.TokenizerHandle
 }
@@ -244,6 +250,20 @@ func CreateTokenizerHandle(tokenizerPath string) (*TokenizerHandle, error) {
 	}, nil
 }
 
+// CloneTokenizerHandle returns a new handle sharing the tokenizer of handle.
+//
+// The clone can be freed independently of the original; the tokenizer is
+// released when its last handle is freed.
+func CloneTokenizerHandle(handle *TokenizerHandle) (*TokenizerHandle, error) {
+	if handle == nil || handle.handle == nil {
+		return nil, fmt.Errorf("tokenizer handle is nil")
+	}
+
+	return &TokenizerHandle{
+		handle: C.sgl_tokenizer_clone_handle(handle.handle),
+	}, nil
+}
+
 // FreeTokenizerHandle frees a tokenizer handle
 func FreeTokenizerHandle(handle *TokenizerHandle) {
 	if handle != nil && handle.handle != nil {
@@ -42,6 +42,14 @@ SglErrorCode sgl_preprocess_chat_request_with_tokenizer(
     char** error_out
 );
 
+SglErrorCode sgl_preprocess_chat_requests_batch_with_tokenizer(
+    const char* requests_json_array,
+    void* tokenizer_handle,
+    char** results_json_array_out,
+    size_t* count_out,
+    char** error_out
+);
+
 SglErrorCode sgl_chat_requires_reasoning_with_tokenizer(
     const char* request_json,
     void* tokenizer_handle,
@@ -63,6 +71,7 @@ void sgl_free_token_ids(uint32_t* ptr, size_t count);
 import "C"
 
 import (
+	"encoding/json"
 	"fmt"
 	"unsafe"
 )
@@ -254,6 +263,75 @@ func ChatRequiresReasoningWithTokenizer(requestJSON string, tokenizerHandle *Tok
 	return requireReasoningOut != 0, nil
 }
 
+// PreprocessChatRequestsBatchWithTokenizer preprocesses many chat completion
+// requests in a single FFI call, for bulk offline preprocessing.
+//
+// Results are in input order and own no Rust memory, so Free is a no-op on them.
+// The whole batch fails on the first request that cannot be preprocessed.
+func PreprocessChatRequestsBatchWithTokenizer(requestsJSON []string, tokenizerHandle *TokenizerHandle) ([]*PreprocessedRequest, error) {
+	if tokenizerHandle == nil || tokenizerHandle.handle == nil {
+		return nil, fmt.Errorf("invalid tokenizer handle")
+	}
+
+	raw := make([]json.RawMessage, len(requestsJSON))
+	for i, requestJSON := range requestsJSON {
+		raw[i] = json.RawMessage(requestJSON)
+	}
+	requestsJSONArray, err := json.Marshal(raw)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal requests: %w", err)
+	}
+	requestsJSONArrayC := C.CString(string(requestsJSONArray))
+	defer C.free(unsafe.Pointer(requestsJSONArrayC))
+
+	var resultsOut *C.char
+	var countOut C.size_t
+	var errorOut *C.char
+
+	errorCode := C.sgl_preprocess_chat_requests_batch_with_tokenizer(
+		requestsJSONArrayC,
+		unsafe.Pointer(tokenizerHandle.handle),
+		&resultsOut,
+		&countOut,
+		&errorOut,
+	)
+
+	if errorCode != C.SGL_ERROR_SUCCESS {
+		errorMsg := ""
+		if errorOut != nil {
+			errorMsg = C.GoString(errorOut)
+			C.sgl_free_string(errorOut)
+		}
+		return nil, fmt.Errorf("batch preprocessing failed: %s", errorMsg)
+	}
+
+	resultsJSON := C.GoString(resultsOut)
+	C.sgl_free_string(resultsOut)
+
+	var items []struct {
+		PromptText      string   `json:"prompt_text"`
+		TokenIDs        []uint32 `json:"token_ids"`
+		ToolConstraints *string  `json:"tool_constraints"`
+		PromptTokens    int32    `json:"prompt_tokens"`
+	}
+	if err := json.Unmarshal([]byte(resultsJSON), &items); err != nil {
+		return nil, fmt.Errorf("failed to unmarshal batch preprocessing results: %w", err)
+	}
+
+	results := make([]*PreprocessedRequest, len(items))
+	for i, item := range items {
+		results[i] = &PreprocessedRequest{
+			PromptText:   item.PromptText,
+			TokenIDs:     item.TokenIDs,
+			PromptTokens: item.PromptTokens,
+		}
+		if item.ToolConstraints != nil {
+			results[i].ToolConstraintsJSON = *item.ToolConstraints
+		}
+	}
+	return results, nil
+}
+
 // Free frees the memory allocated for a preprocessed request
 func (p *PreprocessedRequest) Free() {
 	if p.promptTextPtr != nil || p.tokenIDsPtr != nil || p.toolConstraintsJSONPtr != nil {
@@ -0,0 +1,51 @@
+// Package ffi provides Go bindings for SMG's Rust FFI (Foreign Function Interface).
+//
+// This file provides the Go side of the push-based streaming callback API.
+package ffi
+
+/*
+#include <stdint.h>
+*/
+import "C"
+
+import (
+	"errors"
+	"runtime/cgo"
+	"unsafe"
+)
+
+// StreamCallback receives chunks pushed by a stream registered with SetCallback.
+//
+// chunkJSON is an OpenAI format chunk, or empty when the call only carries an
+// error or signals the end of the stream. Return false to stop receiving chunks
+// and abort the request; no further calls follow.
+type StreamCallback func(chunkJSON string, isDone bool, err error) bool
+
+// goStreamChunkCallback is the C trampoline invoked by the Rust runtime for
+// every chunk. userData holds the cgo.Handle of the registered StreamCallback,
+// which is released after the final call.
+//
+//export goStreamChunkCallback
+func goStreamChunkCallback(userData unsafe.Pointer, chunkJSON *C.char, isDone C.int, errMsg *C.char) C.int {
+	handle := cgo.Handle(uintptr(userData))
+	cb := handle.Value().(StreamCallback)
+
+	chunk := ""
+	if chunkJSON != nil {
+		chunk = C.GoString(chunkJSON)
+	}
+	var err error
+	if errMsg != nil {
+		err = errors.New(C.GoString(errMsg))
+	}
+
+	done := isDone != 0
+	keepGoing := cb(chunk, done, err)
+	if done || !keepGoing {
+		handle.Delete()
+	}
+	if keepGoing {
+		return 0
+	}
+	return 1
+}
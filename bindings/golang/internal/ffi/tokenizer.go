@@ -0,0 +1,202 @@
+// Package ffi provides Go bindings for SMG's Rust FFI (Foreign Function Interface).
+//
+// This file provides batched tokenizer operations that amortize FFI overhead
+// across many inputs, for bulk offline preprocessing.
+package ffi
+
+/*
+#cgo LDFLAGS: -lsmg_go -ldl
+#include <stdlib.h>
+#include <stdint.h>
+
+// Error codes (must match client.go)
+typedef enum {
+    SGL_ERROR_SUCCESS = 0,
+    SGL_ERROR_INVALID_ARGUMENT = 1,
+    SGL_ERROR_TOKENIZATION_ERROR = 2,
+    SGL_ERROR_PARSING_ERROR = 3,
+    SGL_ERROR_MEMORY_ERROR = 4,
+    SGL_ERROR_UNKNOWN = 99
+} SglErrorCode;
+
+// Opaque handle (must match grpc_converter.go)
+typedef void* TokenizerHandle;
+
+// Batch tokenizer functions
+SglErrorCode sgl_tokenizer_encode_batch(
+    TokenizerHandle* handle,
+    const char* texts_json_array,
+    int add_special_tokens,
+    char** token_ids_json_array_out,
+    size_t* count_out,
+    char** error_out
+);
+
+SglErrorCode sgl_tokenizer_decode_batch(
+    TokenizerHandle* handle,
+    const char* token_ids_json_array,
+    int skip_special_tokens,
+    char** texts_json_array_out,
+    size_t* count_out,
+    char** error_out
+);
+
+SglErrorCode sgl_tokenizer_apply_chat_template_batch(
+    TokenizerHandle* handle,
+    const char* conversations_json_array,
+    const char* tools_json,
+    char** results_json_array_out,
+    size_t* count_out,
+    char** error_out
+);
+
+// Memory management
+void sgl_free_string(char* s);
+*/
+import "C"
+
+import (
+	"encoding/json"
+	"fmt"
+	"unsafe"
+)
+
+// EncodeBatch encodes texts to token IDs in a single FFI call.
+//
+// Results are in input order. The whole batch fails on the first text that
+// cannot be encoded.
+func EncodeBatch(handle *TokenizerHandle, texts []string, addSpecialTokens bool) ([][]uint32, error) {
+	if handle == nil || handle.handle == nil {
+		return nil, fmt.Errorf("invalid tokenizer handle")
+	}
+
+	textsJSON, err := json.Marshal(texts)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal texts: %w", err)
+	}
+	textsJSONC := C.CString(string(textsJSON))
+	defer C.free(unsafe.Pointer(textsJSONC))
+
+	var resultOut *C.char
+	var countOut C.size_t
+	var errorOut *C.char
+
+	errorCode := C.sgl_tokenizer_encode_batch(
+		handle.handle,
+		textsJSONC,
+		C.int(boolToInt(addSpecialTokens)),
+		&resultOut,
+		&countOut,
+		&errorOut,
+	)
+
+	var tokenIDs [][]uint32
+	if err := decodeBatchResult(errorCode, resultOut, errorOut, "batch encode", &tokenIDs); err != nil {
+		return nil, err
+	}
+	return tokenIDs, nil
+}
+
+// DecodeBatch decodes token ID sequences to text in a single FFI call.
+//
+// Results are in input order. The whole batch fails on the first sequence that
+// cannot be decoded.
+func DecodeBatch(handle *TokenizerHandle, tokenIDs [][]uint32, skipSpecialTokens bool) ([]string, error) {
+	if handle == nil || handle.handle == nil {
+		return nil, fmt.Errorf("invalid tokenizer handle")
+	}
+
+	tokenIDsJSON, err := json.Marshal(tokenIDs)
+	if err != nil {
+		return nil, fmt.Errorf("failed to marshal token IDs: %w", err)
+	}
+	tokenIDsJSONC := C.CString(string(tokenIDsJSON))
+	defer C.free(unsafe.Pointer(tokenIDsJSONC))
+
+	var resultOut *C.char
+	var countOut C.size_t
+	var errorOut *C.char
+
+	errorCode := C.sgl_tokenizer_decode_batch(
+		handle.handle,
+		tokenIDsJSONC,
+		C.int(boolToInt(skipSpecialTokens)),
+		&resultOut,
+		&countOut,
+		&errorOut,
+	)
+
+	var texts []string
+	if err := decodeBatchResult(errorCode, resultOut, errorOut, "batch decode", &texts); err != nil {
+		return nil, err
+	}
+	return texts, nil
+}
+
+// ApplyChatTemplateBatch renders the chat template for many conversations in a
+// single FFI call.
+//
+// conversationsJSON is a JSON array of messages arrays; toolsJSON is an optional
+// JSON array of tools shared by all conversations (empty for no tools). Results
+// are in input order.
+func ApplyChatTemplateBatch(handle *TokenizerHandle, conversationsJSON string, toolsJSON string) ([]string, error) {
+	if handle == nil || handle.handle == nil {
+		return nil, fmt.Errorf("invalid tokenizer handle")
+	}
+
+	conversationsJSONC := C.CString(conversationsJSON)
+	defer C.free(unsafe.Pointer(conversationsJSONC))
+
+	var toolsJSONC *C.char
+	if toolsJSON != "" {
+		toolsJSONC = C.CString(toolsJSON)
+		defer C.free(unsafe.Pointer(toolsJSONC))
+	}
+
+	var resultOut *C.char
+	var countOut C.size_t
+	var errorOut *C.char
+
+	errorCode := C.sgl_tokenizer_apply_chat_template_batch(
+		handle.handle,
+		conversationsJSONC,
+		toolsJSONC,
+		&resultOut,
+		&countOut,
+		&errorOut,
+	)
+
+	var prompts []string
+	if err := decodeBatchResult(errorCode, resultOut, errorOut, "batch chat template", &prompts); err != nil {
+		return nil, err
+	}
+	return prompts, nil
+}
+
+// decodeBatchResult frees the strings returned by a batch FFI call and
+// unmarshals the JSON array result into out.
+func decodeBatchResult(errorCode C.SglErrorCode, resultOut *C.char, errorOut *C.char, operation string, out interface{}) error {
+	if errorCode != C.SGL_ERROR_SUCCESS {
+		errorMsg := ""
+		if errorOut != nil {
+			errorMsg = C.GoString(errorOut)
+			C.sgl_free_string(errorOut)
+		}
+		return fmt.Errorf("%s failed: %s", operation, errorMsg)
+	}
+
+	resultJSON := C.GoString(resultOut)
+	C.sgl_free_string(resultOut)
+
+	if err := json.Unmarshal([]byte(resultJSON), out); err != nil {
+		return fmt.Errorf("failed to unmarshal %s results: %w", operation, err)
+	}
+	return nil
+}
+
+func boolToInt(b bool) int {
+	if b {
+		return 1
+	}
+	return 0
+}
@@ -285,6 +285,71 @@ func (s *MultiClientStream) Close() error {
 //
 // The request is routed to a healthy worker using the configured load balancing policy.
 func (c *MultiClient) CreateChatCompletionStream(ctx context.Context, req ChatCompletionRequest) (*MultiClientStream, error) {
+	ffiStream, err := c.openStream(req)
+	if err != nil {
+		return nil, err
+	}
+
+	streamCtx, cancel := context.WithCancel(ctx)
+	return &MultiClientStream{
+		ffiStream: ffiStream,
+		ctx:       streamCtx,
+		cancel:    cancel,
+	}, nil
+}
+
+// StreamChatCompletion creates a streaming chat completion and invokes handler
+// for every chunk, returning once the stream ends.
+//
+// Unlike CreateChatCompletionStream, chunks are pushed from the Rust runtime,
+// avoiding one cgo call per chunk. handler runs on a runtime thread and should
+// return quickly; returning an error stops the stream and aborts the request.
+// The final chunk (carrying finish_reason and usage) is delivered as well.
+//
+// Cancelling ctx aborts the request and returns ctx.Err().
+func (c *MultiClient) StreamChatCompletion(ctx context.Context, req ChatCompletionRequest, handler func(chunkJSON string) error) error {
+	ffiStream, err := c.openStream(req)
+	if err != nil {
+		return err
+	}
+	defer ffiStream.Free()
+
+	// The callback sends exactly one value: it stops after the first error or
+	// the final chunk, and Rust invokes it no further.
+	done := make(chan error, 1)
+	err = ffiStream.SetCallback(func(chunkJSON string, isDone bool, err error) bool {
+		if err != nil {
+			done <- err
+			return false
+		}
+		if chunkJSON != "" {
+			if err := handler(chunkJSON); err != nil {
+				done <- err
+				return false
+			}
+		}
+		if isDone {
+			done <- nil
+			return false
+		}
+		return true
+	})
+	if err != nil {
+		return fmt.Errorf("failed to register stream callback: %w", err)
+	}
+
+	select {
+	case err := <-done:
+		return err
+	case <-ctx.Done():
+		ffiStream.Cancel()
+		<-done
+		return ctx.Err()
+	}
+}
+
+// openStream routes the request to a worker and returns the raw FFI stream.
+func (c *MultiClient) openStream(req ChatCompletionRequest) (*ffi.SglangStreamHandle, error) {
 	c.mu.RLock()
 	ffiClient := c.ffiClient
 	c.mu.RUnlock()
@@ -316,11 +381,5 @@ func (c *MultiClient) CreateChatCompletionStream(ctx context.Context, req ChatCo
 	if err != nil {
 		return nil, fmt.Errorf("failed to create stream: %w", err)
 	}
-
-	streamCtx, cancel := context.WithCancel(ctx)
-	return &MultiClientStream{
-		ffiStream: ffiStream,
-		ctx:       streamCtx,
-		cancel:    cancel,
-	}, nil
+	return ffiStream, nil
 }
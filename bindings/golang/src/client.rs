@@ -4,7 +4,7 @@ use std::{
     ffi::{CStr, CString},
     os::raw::c_char,
     ptr,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use llm_tokenizer::{create_tokenizer_from_file, traits::Tokenizer};
@@ -338,6 +338,8 @@ pub unsafe extern "C" fn sgl_client_chat_completion_stream(
         client: Arc::clone(&client),
         prompt_tokens,
         worker: None, // Single-client doesn't need load tracking
+        cancel: Arc::default(),
+        callback_task: Mutex::default(),
     }));
 
     SglErrorCode::Success
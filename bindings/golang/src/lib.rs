@@ -38,15 +38,19 @@ pub use postprocessor::{sgl_postprocess_stream_chunk, sgl_postprocess_stream_chu
 // Re-export preprocessor functions
 pub use preprocessor::{
     sgl_preprocess_chat_request, sgl_preprocess_chat_request_with_tokenizer,
-    sgl_preprocessed_request_free,
+    sgl_preprocess_chat_requests_batch_with_tokenizer, sgl_preprocessed_request_free,
 };
 // Re-export stream functions
-pub use stream::{sgl_stream_free, sgl_stream_read_next, SglangStreamHandle};
+pub use stream::{
+    sgl_stream_cancel, sgl_stream_free, sgl_stream_read_next, sgl_stream_set_callback,
+    SglStreamCallback, SglangStreamHandle,
+};
 // Re-export tokenizer functions
 pub use tokenizer::{
-    sgl_tokenizer_apply_chat_template, sgl_tokenizer_apply_chat_template_with_tools,
-    sgl_tokenizer_create_from_file, sgl_tokenizer_decode, sgl_tokenizer_encode, sgl_tokenizer_free,
-    TokenizerHandle,
+    sgl_tokenizer_apply_chat_template, sgl_tokenizer_apply_chat_template_batch,
+    sgl_tokenizer_apply_chat_template_with_tools, sgl_tokenizer_clone_handle,
+    sgl_tokenizer_create_from_file, sgl_tokenizer_decode, sgl_tokenizer_decode_batch,
+    sgl_tokenizer_encode, sgl_tokenizer_encode_batch, sgl_tokenizer_free, TokenizerHandle,
 };
 // Re-export tool parser functions
 pub use tool_parser::{
@@ -12,7 +12,7 @@ use std::{
     ptr,
     sync::{
         atomic::{AtomicU8, AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 
@@ -752,6 +752,8 @@ pub unsafe extern "C" fn sgl_multi_client_chat_completion_stream(
         client: Arc::clone(&client),
         prompt_tokens,
         worker: Some(Arc::clone(&worker)),
+        cancel: Arc::default(),
+        callback_task: Mutex::default(),
     }));
 
     SglErrorCode::Success
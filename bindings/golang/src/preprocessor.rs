@@ -15,20 +15,23 @@ use std::{
 
 use llm_tokenizer::{create_tokenizer_from_file, traits::Tokenizer};
 use openai_protocol::chat::ChatCompletionRequest;
+use serde::Serialize;
 use smg::routers::grpc::utils::process_chat_messages;
 
 use super::{
     error::{set_error_message, SglErrorCode},
     memory::{sgl_free_string, sgl_free_token_ids},
     tokenizer::TokenizerHandle,
-    utils::chat_requires_reasoning,
+    utils::{chat_requires_reasoning, parse_json_array_arg, write_json_array_out},
 };
 
 /// Result of preprocessing a chat request
+#[derive(Serialize)]
 struct PreprocessResult {
-    prompt_text: CString,
+    prompt_text: String,
     token_ids: Vec<u32>,
-    tool_constraints: Option<CString>,
+    /// JSON-encoded `(type, value)` tool constraint tuple
+    tool_constraints: Option<String>,
     prompt_tokens: i32,
 }
 
@@ -65,20 +68,12 @@ fn preprocess_impl(
         chat_request.tool_choice.as_ref(),
     ) {
         match registry.generate_tool_constraint(None, tools, tool_choice) {
-            Ok(Some(c)) => {
-                let json_str = serde_json::to_string(&c.to_tuple()).map_err(|e| {
-                    (
-                        SglErrorCode::ParsingError,
-                        format!("Failed to serialize tool constraints: {e}"),
-                    )
-                })?;
-                Some(CString::new(json_str).map_err(|e| {
-                    (
-                        SglErrorCode::MemoryError,
-                        format!("Failed to create C string: {e}"),
-                    )
-                })?)
-            }
+            Ok(Some(c)) => Some(serde_json::to_string(&c.to_tuple()).map_err(|e| {
+                (
+                    SglErrorCode::ParsingError,
+                    format!("Failed to serialize tool constraints: {e}"),
+                )
+            })?),
             Ok(None) => None,
             Err(e) => {
                 return Err((
@@ -91,16 +86,8 @@ fn preprocess_impl(
         None
     };
 
-    // Create prompt text CString
-    let prompt_text = CString::new(processed_messages.text).map_err(|e| {
-        (
-            SglErrorCode::MemoryError,
-            format!("Failed to create C string: {e}"),
-        )
-    })?;
-
     Ok(PreprocessResult {
-        prompt_text,
+        prompt_text: processed_messages.text,
         token_ids,
         tool_constraints,
         prompt_tokens,
@@ -109,6 +96,8 @@ fn preprocess_impl(
 
 /// Write preprocess results to FFI output pointers
 ///
+/// Nothing is written if the strings cannot be converted to C strings.
+///
 /// # Safety
 /// All output pointers must be valid and writable
 unsafe fn write_preprocess_outputs(
@@ -118,8 +107,19 @@ unsafe fn write_preprocess_outputs(
     token_ids_len_out: *mut usize,
     tool_constraints_json_out: *mut *mut c_char,
     prompt_tokens_out: *mut c_int,
-) {
-    *prompt_text_out = result.prompt_text.into_raw();
+) -> Result<(), (SglErrorCode, String)> {
+    let to_cstring = |s: String| {
+        CString::new(s).map_err(|e| {
+            (
+                SglErrorCode::MemoryError,
+                format!("Failed to create C string: {e}"),
+            )
+        })
+    };
+    let prompt_text = to_cstring(result.prompt_text)?;
+    let tool_constraints = result.tool_constraints.map(to_cstring).transpose()?;
+
+    *prompt_text_out = prompt_text.into_raw();
     *token_ids_len_out = result.token_ids.len();
     *prompt_tokens_out = result.prompt_tokens;
 
@@ -131,11 +131,11 @@ unsafe fn write_preprocess_outputs(
     };
 
     if !tool_constraints_json_out.is_null() {
-        *tool_constraints_json_out = result
-            .tool_constraints
+        *tool_constraints_json_out = tool_constraints
             .map(|c| c.into_raw())
             .unwrap_or(ptr::null_mut());
     }
+    Ok(())
 }
 
 /// Preprocess a chat completion request
@@ -218,18 +218,18 @@ pub unsafe extern "C" fn sgl_preprocess_chat_request(
         }
     };
 
-    match preprocess_impl(&chat_request, tokenizer.as_ref()) {
-        Ok(result) => {
-            write_preprocess_outputs(
-                result,
-                prompt_text_out,
-                token_ids_out,
-                token_ids_len_out,
-                tool_constraints_json_out,
-                prompt_tokens_out,
-            );
-            SglErrorCode::Success
-        }
+    let result = preprocess_impl(&chat_request, tokenizer.as_ref()).and_then(|result| {
+        write_preprocess_outputs(
+            result,
+            prompt_text_out,
+            token_ids_out,
+            token_ids_len_out,
+            tool_constraints_json_out,
+            prompt_tokens_out,
+        )
+    });
+    match result {
+        Ok(()) => SglErrorCode::Success,
         Err((code, msg)) => {
             set_error_message(error_out, &msg);
             code
@@ -303,18 +303,18 @@ pub unsafe extern "C" fn sgl_preprocess_chat_request_with_tokenizer(
 
     let handle_ref = &*tokenizer_handle;
 
-    match preprocess_impl(&chat_request, handle_ref.tokenizer.as_ref()) {
-        Ok(result) => {
-            write_preprocess_outputs(
-                result,
-                prompt_text_out,
-                token_ids_out,
-                token_ids_len_out,
-                tool_constraints_json_out,
-                prompt_tokens_out,
-            );
-            SglErrorCode::Success
-        }
+    let result = preprocess_impl(&chat_request, handle_ref.tokenizer.as_ref()).and_then(|result| {
+        write_preprocess_outputs(
+            result,
+            prompt_text_out,
+            token_ids_out,
+            token_ids_len_out,
+            tool_constraints_json_out,
+            prompt_tokens_out,
+        )
+    });
+    match result {
+        Ok(()) => SglErrorCode::Success,
         Err((code, msg)) => {
             set_error_message(error_out, &msg);
             code
@@ -322,6 +322,70 @@ pub unsafe extern "C" fn sgl_preprocess_chat_request_with_tokenizer(
     }
 }
 
+/// Preprocess multiple chat completion requests in one call (reduces FFI overhead)
+///
+/// Batch variant of `sgl_preprocess_chat_request_with_tokenizer` for bulk offline
+/// preprocessing. Each result is a JSON object with `prompt_text`, `token_ids`,
+/// `tool_constraints` (JSON string of the constraint tuple, or null) and `prompt_tokens`.
+///
+/// # Arguments
+/// * `requests_json_array` - JSON array of OpenAI ChatCompletionRequest objects
+/// * `tokenizer_handle` - Existing tokenizer handle (must be valid)
+/// * `results_json_array_out` - Pointer to receive a JSON array of results, in input
+///   order (must be freed with sgl_free_string)
+/// * `count_out` - Pointer to receive the number of preprocessed requests
+/// * `error_out` - Optional pointer to receive error message
+///
+/// # Returns
+/// * SglErrorCode::Success on success, error code on failure. The whole batch
+///   fails on the first request that cannot be preprocessed; the message names its index.
+///
+/// # Safety
+/// - `requests_json_array` must be a valid null-terminated C string
+/// - `tokenizer_handle` must be a valid pointer returned by `sgl_tokenizer_create`
+/// - `results_json_array_out` and `count_out` must be valid pointers to writable memory
+/// - `error_out` may be null; if non-null, must point to writable memory
+#[no_mangle]
+pub unsafe extern "C" fn sgl_preprocess_chat_requests_batch_with_tokenizer(
+    requests_json_array: *const c_char,
+    tokenizer_handle: *mut TokenizerHandle,
+    results_json_array_out: *mut *mut c_char,
+    count_out: *mut usize,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    if requests_json_array.is_null()
+        || tokenizer_handle.is_null()
+        || results_json_array_out.is_null()
+        || count_out.is_null()
+    {
+        set_error_message(error_out, "Invalid arguments: null pointer");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    let requests: Vec<ChatCompletionRequest> =
+        match parse_json_array_arg(requests_json_array, "requests_json_array") {
+            Ok(requests) => requests,
+            Err((code, msg)) => {
+                set_error_message(error_out, &msg);
+                return code;
+            }
+        };
+
+    let handle_ref = &*tokenizer_handle;
+    let mut results = Vec::with_capacity(requests.len());
+    for (index, chat_request) in requests.iter().enumerate() {
+        match preprocess_impl(chat_request, handle_ref.tokenizer.as_ref()) {
+            Ok(result) => results.push(result),
+            Err((code, msg)) => {
+                set_error_message(error_out, &format!("Request {index}: {msg}"));
+                return code;
+            }
+        }
+    }
+
+    write_json_array_out(&results, results_json_array_out, count_out, error_out)
+}
+
 /// Free a preprocessed request handle (cleanup function)
 ///
 /// This function frees the memory allocated by sgl_preprocess_chat_request.
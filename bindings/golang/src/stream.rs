@@ -1,10 +1,13 @@
 //! Stream handling FFI functions
 
 use std::{
-    ffi::CString,
+    ffi::{c_void, CString},
     os::raw::{c_char, c_int},
     ptr,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
 };
 
 use futures_util::StreamExt;
@@ -21,6 +24,8 @@ use super::{
     runtime::RUNTIME,
 };
 
+type ResponseConverter = tokio::sync::Mutex<GrpcResponseConverterHandle>;
+
 /// Handle for an active streaming request.
 ///
 /// This struct manages the stream and response converter for a single request.
@@ -32,15 +37,161 @@ use super::{
 /// * `converter` - Response converter that transforms proto messages to OpenAI format
 /// * `client` - The underlying gRPC client connection
 /// * `prompt_tokens` - Number of prompt tokens from the original request
+/// * `cancel` - Cancellation signal shared with a registered stream callback
+/// * `callback_task` - Task driving a registered stream callback, if any
 pub struct SglangStreamHandle {
     pub(crate) stream: Arc<tokio::sync::Mutex<AbortOnDropStream>>,
-    pub(crate) converter: Arc<tokio::sync::Mutex<GrpcResponseConverterHandle>>,
+    pub(crate) converter: Arc<ResponseConverter>,
     #[expect(dead_code)]
     pub(crate) client: Arc<SglangSchedulerClient>,
     #[expect(dead_code)]
     pub(crate) prompt_tokens: u32, // Number of prompt tokens for this request
     /// Worker that owns this stream (for load tracking). None for single-client streams.
     pub(crate) worker: Option<Arc<GrpcWorker>>,
+    pub(crate) cancel: Arc<StreamCancel>,
+    pub(crate) callback_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+/// Cancellation state for a stream.
+///
+/// A cancelled stream is not marked completed when freed, so dropping it
+/// sends an abort to the server.
+#[derive(Default)]
+pub struct StreamCancel {
+    cancelled: AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+impl StreamCancel {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        // notify_one stores a permit, so a callback task that is not
+        // currently waiting still observes the cancellation.
+        self.notify.notify_one();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Callback invoked for each chunk of a stream registered with
+/// `sgl_stream_set_callback`.
+///
+/// # Arguments
+///
+/// * `user_data` - The pointer passed to `sgl_stream_set_callback`
+/// * `chunk_json` - OpenAI format JSON chunk, or NULL for terminal/error calls
+/// * `is_done` - 1 if this is the last invocation for the stream, 0 otherwise
+/// * `error` - Error message, or NULL if no error occurred
+///
+/// Both strings are owned by Rust and only valid for the duration of the call.
+/// Return 0 to keep receiving chunks, or nonzero to stop and abort the request;
+/// no further invocations follow a nonzero return.
+pub type SglStreamCallback = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    chunk_json: *const c_char,
+    is_done: c_int,
+    error: *const c_char,
+) -> c_int;
+
+/// Outcome of reading one proto message from a stream.
+enum StreamEvent {
+    /// A converted chunk; `is_done` is set for the complete response.
+    Chunk { json: CString, is_done: bool },
+    /// The message produced no OpenAI chunk (e.g., an empty delta).
+    Empty,
+    /// The stream ended without a further chunk.
+    End,
+    /// Reading or converting failed; `is_done` is set if the stream is over.
+    Error {
+        code: SglErrorCode,
+        message: String,
+        is_done: bool,
+    },
+}
+
+/// Read the next message from the stream and convert it to an OpenAI chunk.
+///
+/// The stream is marked completed once it ends, errors or yields the complete
+/// response, so dropping it afterwards does not send an abort.
+async fn next_event(
+    stream: &tokio::sync::Mutex<AbortOnDropStream>,
+    converter: &ResponseConverter,
+) -> StreamEvent {
+    let chunk_result = stream.lock().await.next().await;
+
+    let proto_response = match chunk_result {
+        Some(Ok(proto_response)) => proto_response,
+        Some(Err(e)) => {
+            // Stream error - mark as completed to prevent abort
+            stream.lock().await.mark_completed();
+            return StreamEvent::Error {
+                code: SglErrorCode::UnknownError,
+                message: format!("Stream error: {e}"),
+                is_done: true,
+            };
+        }
+        None => {
+            // Stream ended naturally - mark as completed to prevent abort
+            stream.lock().await.mark_completed();
+            return StreamEvent::End;
+        }
+    };
+
+    // Check if this is a complete response (stream done)
+    let is_complete = matches!(
+        proto_response.response,
+        Some(proto::generate_response::Response::Complete(_))
+    );
+
+    let conversion_result = {
+        let mut converter_guard = converter.lock().await;
+        let tokenizer = Arc::clone(&converter_guard.tokenizer);
+        convert_proto_chunk_to_openai(proto_response, &mut converter_guard, &tokenizer).await
+    };
+
+    match conversion_result {
+        Ok(Some(openai_response)) => {
+            let result_str = match serde_json::to_string(&openai_response) {
+                Ok(s) => s,
+                Err(e) => {
+                    return StreamEvent::Error {
+                        code: SglErrorCode::ParsingError,
+                        message: format!("Failed to serialize response: {e}"),
+                        is_done: false,
+                    };
+                }
+            };
+            let json = match CString::new(result_str) {
+                Ok(s) => s,
+                Err(e) => {
+                    return StreamEvent::Error {
+                        code: SglErrorCode::MemoryError,
+                        message: format!("Failed to create result string: {e}"),
+                        is_done: false,
+                    };
+                }
+            };
+
+            if is_complete {
+                // Mark stream as completed to prevent abort on drop
+                stream.lock().await.mark_completed();
+            }
+            StreamEvent::Chunk {
+                json,
+                is_done: is_complete,
+            }
+        }
+        // No response to send (e.g., empty chunk); the stream might continue
+        Ok(None) => StreamEvent::Empty,
+        // Conversion error - don't mark as completed, let the caller decide
+        Err(e) => StreamEvent::Error {
+            code: SglErrorCode::ParsingError,
+            message: format!("Conversion error: {e}"),
+            is_done: false,
+        },
+    }
 }
 
 /// Read next chunk from stream and convert to OpenAI format.
@@ -78,6 +229,8 @@ pub struct SglangStreamHandle {
 /// - Complete messages are identified by the presence of `proto::GenerateResponse::Complete`
 /// - When is_done=1, this may be the last readable chunk or the stream may be ending
 /// - Subsequent calls after is_done=1 will mark the stream as complete internally
+/// - Fails with `InvalidArgument` once a callback is registered with
+///   `sgl_stream_set_callback`
 #[no_mangle]
 pub unsafe extern "C" fn sgl_stream_read_next(
     stream_handle: *mut SglangStreamHandle,
@@ -91,112 +244,211 @@ pub unsafe extern "C" fn sgl_stream_read_next(
     }
 
     let handle_ref = &*stream_handle;
-    let stream = Arc::clone(&handle_ref.stream);
-    let converter = Arc::clone(&handle_ref.converter);
+    if handle_ref
+        .callback_task
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+    {
+        set_error_message(error_out, "Stream is consumed by a registered callback");
+        return SglErrorCode::InvalidArgument;
+    }
 
     // Read next chunk from stream
-    let chunk_result = RUNTIME.block_on(async {
-        let mut stream_guard = stream.lock().await;
-        stream_guard.next().await
-    });
+    let event = RUNTIME.block_on(next_event(&handle_ref.stream, &handle_ref.converter));
 
-    match chunk_result {
-        Some(Ok(proto_response)) => {
-            // Convert proto response to OpenAI format
-            // We need to get the converter lock first
-            let conversion_result = RUNTIME.block_on(async {
-                let mut converter_guard = converter.lock().await;
-
-                let tokenizer = Arc::clone(&converter_guard.tokenizer);
-                convert_proto_chunk_to_openai(
-                    proto_response.clone(),
-                    &mut converter_guard,
-                    &tokenizer,
-                )
-                .await
-            });
-
-            match conversion_result {
-                Ok(Some(openai_response)) => {
-                    // Serialize to JSON
-                    let result_str = match serde_json::to_string(&openai_response) {
-                        Ok(s) => s,
-                        Err(e) => {
-                            set_error_message(
-                                error_out,
-                                &format!("Failed to serialize response: {e}"),
-                            );
-                            return SglErrorCode::ParsingError;
-                        }
-                    };
+    match event {
+        StreamEvent::Chunk { json, is_done } => {
+            *response_json_out = json.into_raw();
+            *is_done_out = c_int::from(is_done);
+            SglErrorCode::Success
+        }
+        StreamEvent::Empty => {
+            // Just return null and let caller read more
+            *response_json_out = ptr::null_mut();
+            *is_done_out = 0;
+            SglErrorCode::Success
+        }
+        StreamEvent::End => {
+            *response_json_out = ptr::null_mut();
+            *is_done_out = 1;
+            SglErrorCode::Success
+        }
+        StreamEvent::Error {
+            code,
+            message,
+            is_done,
+        } => {
+            set_error_message(error_out, &message);
+            *response_json_out = ptr::null_mut();
+            *is_done_out = c_int::from(is_done);
+            code
+        }
+    }
+}
 
-                    let result_cstr = match CString::new(result_str) {
-                        Ok(s) => s,
-                        Err(e) => {
-                            set_error_message(
-                                error_out,
-                                &format!("Failed to create result string: {e}"),
-                            );
-                            return SglErrorCode::MemoryError;
-                        }
-                    };
+/// Raw user-data pointer handed back to the stream callback.
+struct UserData(*mut c_void);
 
-                    // Check if this is a complete response (stream done)
-                    let is_complete = matches!(
-                        proto_response.response,
-                        Some(proto::generate_response::Response::Complete(_))
-                    );
+// SAFETY: the pointer is opaque to Rust and only passed back to the caller's
+// callback; `sgl_stream_set_callback` requires it to be usable from any thread.
+unsafe impl Send for UserData {}
 
-                    *response_json_out = result_cstr.into_raw();
-                    *is_done_out = if is_complete { 1 } else { 0 };
+/// Invoke the callback, keeping the strings alive for the duration of the call.
+/// Returns true if the callback asked to stop.
+fn invoke_callback(
+    callback: SglStreamCallback,
+    user_data: &UserData,
+    chunk_json: Option<&CString>,
+    is_done: bool,
+    error: Option<&str>,
+) -> bool {
+    let error = error.map(|message| {
+        CString::new(message).unwrap_or_else(|_| c"Invalid error message".to_owned())
+    });
+    // SAFETY: `sgl_stream_set_callback` requires the callback and user data
+    // to stay valid until the final invocation or until the stream is freed.
+    let status = unsafe {
+        callback(
+            user_data.0,
+            chunk_json.map_or(ptr::null(), |json| json.as_ptr()),
+            c_int::from(is_done),
+            error
+                .as_ref()
+                .map_or(ptr::null(), |message| message.as_ptr()),
+        )
+    };
+    status != 0
+}
 
-                    if is_complete {
-                        // Mark stream as completed to prevent abort on drop
-                        RUNTIME.block_on(async {
-                            stream.lock().await.mark_completed();
-                        });
-                    }
+/// Drive the stream to completion, invoking `callback` for every chunk.
+async fn run_stream_callback(
+    stream: Arc<tokio::sync::Mutex<AbortOnDropStream>>,
+    converter: Arc<ResponseConverter>,
+    cancel: Arc<StreamCancel>,
+    callback: SglStreamCallback,
+    user_data: UserData,
+) {
+    loop {
+        let event = tokio::select! {
+            biased;
+            () = cancel.notify.notified() => {
+                invoke_callback(callback, &user_data, None, true, Some("Stream cancelled"));
+                return;
+            }
+            event = next_event(&stream, &converter) => event,
+        };
 
-                    SglErrorCode::Success
-                }
-                Ok(None) => {
-                    // No response to send (e.g., empty chunk)
-                    // Don't mark as completed - stream might continue
-                    // Just return null and let caller read more
-                    *response_json_out = ptr::null_mut();
-                    *is_done_out = 0; // Keep stream open, not done yet
-                    SglErrorCode::Success
+        let stop = match event {
+            StreamEvent::Chunk { json, is_done } => {
+                let stop = invoke_callback(callback, &user_data, Some(&json), is_done, None);
+                if is_done {
+                    return;
                 }
-                Err(e) => {
-                    // Conversion error - don't mark as completed
-                    // Let the stream end naturally or return error without stopping stream
-                    set_error_message(error_out, &format!("Conversion error: {e}"));
-                    *response_json_out = ptr::null_mut();
-                    *is_done_out = 0; // Don't mark as done - let caller decide
-                    SglErrorCode::ParsingError
+                stop
+            }
+            StreamEvent::Empty => false,
+            StreamEvent::End => {
+                invoke_callback(callback, &user_data, None, true, None);
+                return;
+            }
+            StreamEvent::Error {
+                message, is_done, ..
+            } => {
+                let stop = invoke_callback(callback, &user_data, None, is_done, Some(&message));
+                if is_done {
+                    return;
                 }
+                stop
             }
-        }
-        Some(Err(e)) => {
-            // Stream error - mark as completed to prevent abort
-            RUNTIME.block_on(async {
-                stream.lock().await.mark_completed();
-            });
+        };
 
-            set_error_message(error_out, &format!("Stream error: {e}"));
-            *is_done_out = 1;
-            SglErrorCode::UnknownError
+        if stop {
+            // Leave the stream unmarked so freeing it aborts the request
+            cancel.cancel();
+            return;
         }
-        None => {
-            // Stream ended naturally - mark as completed to prevent abort
-            RUNTIME.block_on(async {
-                stream.lock().await.mark_completed();
-            });
+    }
+}
 
-            *response_json_out = ptr::null_mut();
-            *is_done_out = 1;
-            SglErrorCode::Success
-        }
+/// Register a callback that receives every chunk of the stream.
+///
+/// Instead of polling `sgl_stream_read_next`, the stream is driven on the
+/// shared runtime and `callback` is invoked once per OpenAI format chunk,
+/// saving one FFI crossing and one string free per chunk.
+///
+/// # Arguments
+///
+/// * `stream_handle` - Mutable pointer to the stream handle
+/// * `callback` - Function invoked per chunk (see `SglStreamCallback`)
+///   - Invoked exactly once with is_done=1 when the stream completes, fails,
+///     or is cancelled, unless it returned nonzero earlier
+/// * `user_data` - Opaque pointer passed to every invocation of `callback`
+/// * `error_out` - Optional pointer to receive error message
+///
+/// # Returns
+///
+/// * `SglErrorCode::Success` - The callback is registered and the stream is running
+/// * `SglErrorCode::InvalidArgument` - Null handle or a callback is already registered
+///
+/// # Safety
+///
+/// - `stream_handle` must point to a valid `SglangStreamHandle`
+/// - `callback` and `user_data` must remain valid until the final invocation
+///   or until `sgl_stream_free` returns
+/// - `callback` runs on a runtime worker thread; it must not block for long
+///   and must not call `sgl_stream_free` on the same stream
+#[no_mangle]
+pub unsafe extern "C" fn sgl_stream_set_callback(
+    stream_handle: *mut SglangStreamHandle,
+    callback: Option<SglStreamCallback>,
+    user_data: *mut c_void,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    let Some(callback) = callback else {
+        set_error_message(error_out, "Invalid arguments: null callback");
+        return SglErrorCode::InvalidArgument;
+    };
+    if stream_handle.is_null() {
+        set_error_message(error_out, "Invalid arguments: null pointer");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    let handle_ref = &*stream_handle;
+    let mut task = handle_ref
+        .callback_task
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if task.is_some() {
+        set_error_message(error_out, "Stream already has a registered callback");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    *task = Some(RUNTIME.spawn(run_stream_callback(
+        Arc::clone(&handle_ref.stream),
+        Arc::clone(&handle_ref.converter),
+        Arc::clone(&handle_ref.cancel),
+        callback,
+        UserData(user_data),
+    )));
+
+    SglErrorCode::Success
+}
+
+/// Cancel a stream.
+///
+/// A registered callback receives a final invocation with is_done=1 and a
+/// "Stream cancelled" error. The request is aborted on the server when the
+/// handle is freed with `sgl_stream_free`.
+///
+/// # Safety
+///
+/// - `stream_handle` must be NULL or point to a valid `SglangStreamHandle`
+/// - May be called from any thread, including from within the callback
+#[no_mangle]
+pub unsafe extern "C" fn sgl_stream_cancel(stream_handle: *mut SglangStreamHandle) {
+    if let Some(handle_ref) = stream_handle.as_ref() {
+        handle_ref.cancel.cancel();
     }
 }
 
@@ -220,7 +472,10 @@ pub unsafe extern "C" fn sgl_stream_read_next(
 /// # Notes
 ///
 /// - This function calls `mark_completed()` before freeing to ensure
-///   the stream cleanup doesn't trigger an abort RPC to the server
+///   the stream cleanup doesn't trigger an abort RPC to the server, unless the
+///   stream was cancelled
+/// - A running stream callback is cancelled and awaited before the handle is
+///   released, so it is never invoked after this function returns
 #[no_mangle]
 pub unsafe extern "C" fn sgl_stream_free(handle: *mut SglangStreamHandle) {
     if !handle.is_null() {
@@ -232,11 +487,26 @@ pub unsafe extern "C" fn sgl_stream_free(handle: *mut SglangStreamHandle) {
             worker.increment_processed();
         }
 
+        let callback_task = handle_ref
+            .callback_task
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(task) = callback_task {
+            if !task.is_finished() {
+                handle_ref.cancel.cancel();
+            }
+            let _ = RUNTIME.block_on(task);
+        }
+
         // Mark stream as completed to prevent abort on drop
-        // (should already be marked by ReadNext, but ensure it for safety)
-        RUNTIME.block_on(async {
-            handle_ref.stream.lock().await.mark_completed();
-        });
+        // (should already be marked by ReadNext, but ensure it for safety).
+        // Cancelled streams stay unmarked so the request is aborted.
+        if !handle_ref.cancel.is_cancelled() {
+            RUNTIME.block_on(async {
+                handle_ref.stream.lock().await.mark_completed();
+            });
+        }
 
         // Drop handle - mark_completed() ensures no abort signal is sent
         drop(handle_ref);
@@ -12,7 +12,10 @@ use llm_tokenizer::{
 };
 use serde_json::Value;
 
-use super::error::{clear_error_message, set_error_message, SglErrorCode};
+use super::{
+    error::{clear_error_message, set_error_message, SglErrorCode},
+    utils::{parse_json_array_arg, write_json_array_out},
+};
 
 #[cfg(target_os = "macos")]
 type BooleanT = libc::boolean_t;
@@ -20,11 +23,27 @@ type BooleanT = libc::boolean_t;
 type BooleanT = libc::c_int;
 
 /// Opaque handle for a tokenizer instance
+///
+/// # Thread safety
+///
+/// A handle is a reference to a shared, immutable tokenizer. Every function
+/// taking a handle except `sgl_tokenizer_free` may be called concurrently
+/// from any number of threads on the same handle without external locking.
+///
+/// `sgl_tokenizer_clone_handle` returns an independent handle to the same
+/// tokenizer. Each handle must be freed exactly once, after all calls using
+/// that handle have returned; the tokenizer is released with the last handle.
 #[repr(C)]
 pub struct TokenizerHandle {
     pub(crate) tokenizer: Arc<dyn TokenizerTrait>,
 }
 
+// Enforce the documented contract: handles are shared across threads as-is.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TokenizerHandle>();
+};
+
 /// Internal helper to apply chat template with optional tools
 fn apply_chat_template_impl(
     tokenizer: &dyn TokenizerTrait,
@@ -375,13 +394,407 @@ pub unsafe extern "C" fn sgl_tokenizer_decode(
     }
 }
 
+/// Encode multiple texts to token IDs in one call (reduces FFI overhead)
+///
+/// # Arguments
+/// * `handle` - Tokenizer handle
+/// * `texts_json_array` - JSON array of input strings
+/// * `add_special_tokens` - Whether to add special tokens
+/// * `token_ids_json_array_out` - Pointer to receive a JSON array of token ID arrays, in
+///   input order (must be freed with sgl_free_string)
+/// * `count_out` - Pointer to receive the number of encoded texts
+/// * `error_out` - Optional pointer to receive error message
+///
+/// # Returns
+/// * SglErrorCode::Success on success, error code on failure. The whole batch
+///   fails on the first text that cannot be encoded; the message names its index.
+///
+/// # Safety
+/// - `handle` must be a valid pointer returned by `sgl_tokenizer_create_from_file`
+/// - `texts_json_array` must be a valid null-terminated C string
+/// - `token_ids_json_array_out` and `count_out` must be valid pointers to writable memory
+/// - `error_out` may be null; if non-null, must point to writable memory
+#[no_mangle]
+pub unsafe extern "C" fn sgl_tokenizer_encode_batch(
+    handle: *mut TokenizerHandle,
+    texts_json_array: *const c_char,
+    add_special_tokens: BooleanT,
+    token_ids_json_array_out: *mut *mut c_char,
+    count_out: *mut usize,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    if handle.is_null()
+        || texts_json_array.is_null()
+        || token_ids_json_array_out.is_null()
+        || count_out.is_null()
+    {
+        set_error_message(error_out, "Invalid arguments: null pointer");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    let texts: Vec<String> = match parse_json_array_arg(texts_json_array, "texts_json_array") {
+        Ok(texts) => texts,
+        Err((code, msg)) => {
+            set_error_message(error_out, &msg);
+            return code;
+        }
+    };
+
+    let tokenizer = &(*handle).tokenizer;
+    let mut results = Vec::with_capacity(texts.len());
+    for (index, text) in texts.iter().enumerate() {
+        match tokenizer.encode(text, add_special_tokens != 0) {
+            Ok(encoding) => results.push(encoding.token_ids().to_vec()),
+            Err(e) => {
+                set_error_message(error_out, &format!("Failed to encode text {index}: {e}"));
+                return SglErrorCode::TokenizationError;
+            }
+        }
+    }
+
+    write_json_array_out(&results, token_ids_json_array_out, count_out, error_out)
+}
+
+/// Decode multiple token ID sequences to text in one call (reduces FFI overhead)
+///
+/// # Arguments
+/// * `handle` - Tokenizer handle
+/// * `token_ids_json_array` - JSON array of token ID arrays
+/// * `skip_special_tokens` - Whether to skip special tokens
+/// * `texts_json_array_out` - Pointer to receive a JSON array of decoded strings, in
+///   input order (must be freed with sgl_free_string)
+/// * `count_out` - Pointer to receive the number of decoded sequences
+/// * `error_out` - Optional pointer to receive error message
+///
+/// # Returns
+/// * SglErrorCode::Success on success, error code on failure. The whole batch
+///   fails on the first sequence that cannot be decoded; the message names its index.
+///
+/// # Safety
+/// - `handle` must be a valid pointer returned by `sgl_tokenizer_create_from_file`
+/// - `token_ids_json_array` must be a valid null-terminated C string
+/// - `texts_json_array_out` and `count_out` must be valid pointers to writable memory
+/// - `error_out` may be null; if non-null, must point to writable memory
+#[no_mangle]
+pub unsafe extern "C" fn sgl_tokenizer_decode_batch(
+    handle: *mut TokenizerHandle,
+    token_ids_json_array: *const c_char,
+    skip_special_tokens: c_int,
+    texts_json_array_out: *mut *mut c_char,
+    count_out: *mut usize,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    if handle.is_null()
+        || token_ids_json_array.is_null()
+        || texts_json_array_out.is_null()
+        || count_out.is_null()
+    {
+        set_error_message(error_out, "Invalid arguments: null pointer");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    let sequences: Vec<Vec<u32>> =
+        match parse_json_array_arg(token_ids_json_array, "token_ids_json_array") {
+            Ok(sequences) => sequences,
+            Err((code, msg)) => {
+                set_error_message(error_out, &msg);
+                return code;
+            }
+        };
+
+    let tokenizer = &(*handle).tokenizer;
+    let mut results = Vec::with_capacity(sequences.len());
+    for (index, token_ids) in sequences.iter().enumerate() {
+        match tokenizer.decode(token_ids, skip_special_tokens != 0) {
+            Ok(text) => results.push(text),
+            Err(e) => {
+                set_error_message(
+                    error_out,
+                    &format!("Failed to decode sequence {index}: {e}"),
+                );
+                return SglErrorCode::TokenizationError;
+            }
+        }
+    }
+
+    write_json_array_out(&results, texts_json_array_out, count_out, error_out)
+}
+
+/// Apply the chat template to multiple conversations in one call (reduces FFI overhead)
+///
+/// # Arguments
+/// * `handle` - Tokenizer handle
+/// * `conversations_json_array` - JSON array of messages arrays, one per conversation
+/// * `tools_json` - Optional JSON string of tools array shared by all conversations
+///   (null or empty string for no tools)
+/// * `results_json_array_out` - Pointer to receive a JSON array of prompt strings, in
+///   input order (must be freed with sgl_free_string)
+/// * `count_out` - Pointer to receive the number of rendered conversations
+/// * `error_out` - Optional pointer to receive error message
+///
+/// # Returns
+/// * SglErrorCode::Success on success, error code on failure. The whole batch
+///   fails on the first conversation that cannot be rendered; the message names its index.
+///
+/// # Safety
+/// - `handle` must be a valid pointer returned by `sgl_tokenizer_create_from_file`
+/// - `conversations_json_array` must be a valid null-terminated C string
+/// - `tools_json` may be null; if non-null, must be a valid null-terminated C string
+/// - `results_json_array_out` and `count_out` must be valid pointers to writable memory
+/// - `error_out` may be null; if non-null, must point to writable memory
+#[no_mangle]
+pub unsafe extern "C" fn sgl_tokenizer_apply_chat_template_batch(
+    handle: *mut TokenizerHandle,
+    conversations_json_array: *const c_char,
+    tools_json: *const c_char,
+    results_json_array_out: *mut *mut c_char,
+    count_out: *mut usize,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    if handle.is_null()
+        || conversations_json_array.is_null()
+        || results_json_array_out.is_null()
+        || count_out.is_null()
+    {
+        set_error_message(error_out, "Invalid arguments: null pointer");
+        return SglErrorCode::InvalidArgument;
+    }
+
+    let conversations: Vec<Vec<Value>> =
+        match parse_json_array_arg(conversations_json_array, "conversations_json_array") {
+            Ok(conversations) => conversations,
+            Err((code, msg)) => {
+                set_error_message(error_out, &msg);
+                return code;
+            }
+        };
+
+    let tools: Option<Vec<Value>> = if tools_json.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(tools_json).to_str() {
+            Ok("") => None,
+            Ok(_) => match parse_json_array_arg(tools_json, "tools_json") {
+                Ok(t) => Some(t),
+                Err((code, msg)) => {
+                    set_error_message(error_out, &msg);
+                    return code;
+                }
+            },
+            Err(_) => {
+                set_error_message(error_out, "Invalid UTF-8 in tools_json");
+                return SglErrorCode::InvalidArgument;
+            }
+        }
+    };
+
+    let handle_ref = &*handle;
+    let mut results = Vec::with_capacity(conversations.len());
+    for (index, messages) in conversations.into_iter().enumerate() {
+        match apply_chat_template_impl(handle_ref.tokenizer.as_ref(), messages, tools.as_deref()) {
+            Ok(prompt) => results.push(prompt),
+            Err((code, msg)) => {
+                set_error_message(error_out, &format!("{msg} for conversation {index}"));
+                return code;
+            }
+        }
+    }
+
+    write_json_array_out(&results, results_json_array_out, count_out, error_out)
+}
+
+/// Clone a tokenizer handle
+///
+/// The returned handle shares the underlying tokenizer with `handle` but has
+/// its own lifetime, so e.g. each goroutine or component can own and free its
+/// handle independently without reloading the tokenizer.
+///
+/// # Arguments
+/// * `handle` - Tokenizer handle to clone
+///
+/// # Returns
+/// * Pointer to a new TokenizerHandle, or null if `handle` is null
+///
+/// # Safety
+/// - `handle` must be null or a valid pointer returned by `sgl_tokenizer_create_from_file`
+///   or `sgl_tokenizer_clone_handle` that has not been freed
+/// - The returned handle must be freed with `sgl_tokenizer_free`
+#[no_mangle]
+pub unsafe extern "C" fn sgl_tokenizer_clone_handle(
+    handle: *mut TokenizerHandle,
+) -> *mut TokenizerHandle {
+    match handle.as_ref() {
+        Some(handle_ref) => Box::into_raw(Box::new(TokenizerHandle {
+            tokenizer: Arc::clone(&handle_ref.tokenizer),
+        })),
+        None => ptr::null_mut(),
+    }
+}
+
 /// Free a tokenizer handle
 ///
+/// Releases this handle only; the tokenizer stays alive while other handles
+/// cloned from it exist.
+///
 /// # Safety
 /// This function must only be called once per handle, and the handle must not be used after calling.
+/// No other call using this handle may be in flight on another thread.
 #[no_mangle]
 pub unsafe extern "C" fn sgl_tokenizer_free(handle: *mut TokenizerHandle) {
     if !handle.is_null() {
         let _ = Box::from_raw(handle);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use llm_tokenizer::MockTokenizer;
+
+    use super::*;
+    use crate::memory::{sgl_free_string, sgl_free_token_ids};
+
+    fn mock_handle() -> *mut TokenizerHandle {
+        Box::into_raw(Box::new(TokenizerHandle {
+            tokenizer: Arc::new(MockTokenizer::new()),
+        }))
+    }
+
+    /// Round-trip `text` through encode and decode via the FFI surface.
+    unsafe fn round_trip(handle: *mut TokenizerHandle, text: &CStr) -> (Vec<u32>, String) {
+        let mut ids_ptr = ptr::null_mut();
+        let mut count = 0;
+        let mut error = ptr::null_mut();
+        let code = sgl_tokenizer_encode(
+            handle,
+            text.as_ptr(),
+            0,
+            &mut ids_ptr,
+            &mut count,
+            &mut error,
+        );
+        assert_eq!(code, SglErrorCode::Success);
+        let ids = std::slice::from_raw_parts(ids_ptr, count).to_vec();
+
+        let mut decoded_ptr = ptr::null_mut();
+        let code = sgl_tokenizer_decode(handle, ids_ptr, count, 0, &mut decoded_ptr, &mut error);
+        assert_eq!(code, SglErrorCode::Success);
+        let decoded = CStr::from_ptr(decoded_ptr).to_str().unwrap().to_string();
+
+        sgl_free_token_ids(ids_ptr, count);
+        sgl_free_string(decoded_ptr);
+        (ids, decoded)
+    }
+
+    #[derive(Clone, Copy)]
+    struct SendPtr(*mut TokenizerHandle);
+    unsafe impl Send for SendPtr {}
+
+    #[test]
+    fn test_concurrent_encode_decode_on_shared_handle() {
+        let handle = SendPtr(mock_handle());
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(move || {
+                    let handle = handle;
+                    for _ in 0..500 {
+                        let (ids, text) = unsafe { round_trip(handle.0, c"Hello world test") };
+                        assert_eq!(ids, vec![1, 2, 3]);
+                        assert_eq!(text, "Hello world test");
+                    }
+                });
+            }
+        });
+
+        unsafe { sgl_tokenizer_free(handle.0) };
+    }
+
+    /// Read and free a JSON array returned by a batch function.
+    unsafe fn take_json_array(ptr: *mut c_char) -> Value {
+        let value = serde_json::from_str(CStr::from_ptr(ptr).to_str().unwrap()).unwrap();
+        sgl_free_string(ptr);
+        value
+    }
+
+    #[test]
+    fn test_encode_decode_batch() {
+        let handle = mock_handle();
+        let mut out = ptr::null_mut();
+        let mut count = 0;
+        let mut error = ptr::null_mut();
+
+        unsafe {
+            let code = sgl_tokenizer_encode_batch(
+                handle,
+                cr#"["Hello world", "", "test token"]"#.as_ptr(),
+                0,
+                &mut out,
+                &mut count,
+                &mut error,
+            );
+            assert_eq!(code, SglErrorCode::Success);
+            assert_eq!(count, 3);
+            assert_eq!(
+                take_json_array(out),
+                serde_json::json!([[1, 2], [], [3, 4]])
+            );
+
+            let code = sgl_tokenizer_decode_batch(
+                handle,
+                c"[[1, 2], [], [1000, 3]]".as_ptr(),
+                1,
+                &mut out,
+                &mut count,
+                &mut error,
+            );
+            assert_eq!(code, SglErrorCode::Success);
+            assert_eq!(count, 3);
+            assert_eq!(
+                take_json_array(out),
+                serde_json::json!(["Hello world", "", "test"])
+            );
+
+            let code = sgl_tokenizer_encode_batch(
+                handle,
+                cr#"{"text": "Hello"}"#.as_ptr(),
+                0,
+                &mut out,
+                &mut count,
+                &mut error,
+            );
+            assert_eq!(code, SglErrorCode::ParsingError);
+            assert!(!error.is_null());
+            sgl_free_string(error);
+
+            sgl_tokenizer_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_cloned_handles_outlive_original() {
+        let original = mock_handle();
+        let clones: Vec<SendPtr> = (0..4)
+            .map(|_| SendPtr(unsafe { sgl_tokenizer_clone_handle(original) }))
+            .collect();
+        assert!(clones.iter().all(|clone| !clone.0.is_null()));
+        unsafe { sgl_tokenizer_free(original) };
+
+        thread::scope(|scope| {
+            for clone in &clones {
+                let clone = *clone;
+                scope.spawn(move || {
+                    let clone = clone;
+                    for _ in 0..200 {
+                        let (ids, _) = unsafe { round_trip(clone.0, c"test token") };
+                        assert_eq!(ids, vec![3, 4]);
+                    }
+                    unsafe { sgl_tokenizer_free(clone.0) };
+                });
+            }
+        });
+
+        assert!(unsafe { sgl_tokenizer_clone_handle(ptr::null_mut()) }.is_null());
+    }
+}
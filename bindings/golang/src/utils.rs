@@ -1,10 +1,18 @@
 //! Utility functions for FFI
 
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
 use llm_tokenizer::traits::Tokenizer;
 use openai_protocol::chat::ChatCompletionRequest;
+use serde::{de::DeserializeOwned, Serialize};
 use smg::routers::grpc::utils::{resolve_user_thinking, should_mark_reasoning_started};
 use uuid::Uuid;
 
+use super::error::{clear_error_message, set_error_message, SglErrorCode};
+
 /// Helper function to generate tool call ID (matches router implementation)
 pub fn generate_tool_call_id(
     model: &str,
@@ -40,3 +48,62 @@ pub(crate) fn chat_requires_reasoning(
         tokenizer,
     )
 }
+
+/// Parse a C string argument holding a JSON array, as taken by the batch FFI
+/// functions. On failure the error message names `arg_name`.
+///
+/// # Safety
+/// - `json` must be a valid null-terminated C string
+pub(crate) unsafe fn parse_json_array_arg<T: DeserializeOwned>(
+    json: *const c_char,
+    arg_name: &str,
+) -> Result<Vec<T>, (SglErrorCode, String)> {
+    let json_str = CStr::from_ptr(json).to_str().map_err(|_| {
+        (
+            SglErrorCode::InvalidArgument,
+            format!("Invalid UTF-8 in {arg_name}"),
+        )
+    })?;
+    serde_json::from_str(json_str).map_err(|e| {
+        (
+            SglErrorCode::ParsingError,
+            format!("Failed to parse {arg_name} JSON array: {e}"),
+        )
+    })
+}
+
+/// Serialize batch results as a JSON array into the FFI output pointers.
+///
+/// # Safety
+/// - `json_array_out` and `count_out` must be valid pointers to writable memory
+/// - `error_out` may be null; if non-null, must point to writable memory
+pub(crate) unsafe fn write_json_array_out<T: Serialize>(
+    results: &[T],
+    json_array_out: *mut *mut c_char,
+    count_out: *mut usize,
+    error_out: *mut *mut c_char,
+) -> SglErrorCode {
+    let results_json = match serde_json::to_string(results) {
+        Ok(s) => s,
+        Err(e) => {
+            set_error_message(
+                error_out,
+                &format!("Failed to serialize results JSON array: {e}"),
+            );
+            return SglErrorCode::ParsingError;
+        }
+    };
+
+    let results_cstr = match CString::new(results_json) {
+        Ok(s) => s,
+        Err(e) => {
+            set_error_message(error_out, &format!("Failed to create C string: {e}"));
+            return SglErrorCode::MemoryError;
+        }
+    };
+
+    *json_array_out = results_cstr.into_raw();
+    *count_out = results.len();
+    clear_error_message(error_out);
+    SglErrorCode::Success
+}
@@ -382,6 +382,9 @@ struct Router {
     block_size: usize,
     balance_token_usage_threshold: f32,
     overload_token_usage_threshold: f32,
+    tree_window_secs: u64,
+    cache_decay_half_life_secs: u64,
+    consistent_hashing_load_epsilon: Option<f64>,
     least_load_kv_pressure_weight: f64,
     least_load_default_throughput: f64,
     least_load_mean_prefill_tokens: u32,
@@ -560,6 +563,8 @@ impl Router {
                     block_size: self.block_size,
                     balance_token_usage_threshold: self.balance_token_usage_threshold,
                     overload_token_usage_threshold: self.overload_token_usage_threshold,
+                    tree_window_secs: self.tree_window_secs,
+                    cache_decay_half_life_secs: self.cache_decay_half_life_secs,
                 },
                 PolicyType::PowerOfTwo => ConfigPolicyConfig::PowerOfTwo {
                     load_check_interval_secs: 5,
@@ -580,10 +585,14 @@ impl Router {
                     max_idle_secs: self.max_idle_secs,
                     assignment_mode: self.parse_assignment_mode()?,
                 },
-                PolicyType::ConsistentHashing => ConfigPolicyConfig::ConsistentHashing,
+                PolicyType::ConsistentHashing => ConfigPolicyConfig::ConsistentHashing {
+                    load_epsilon: self.consistent_hashing_load_epsilon,
+                },
                 PolicyType::PrefixHash => ConfigPolicyConfig::PrefixHash {
                     prefix_token_count: 256,
                     load_factor: 1.25,
+                    block_size: 1,
+                    block_count: None,
                 },
             })
         };
@@ -848,6 +857,9 @@ impl Router {
         block_size = 16,
         balance_token_usage_threshold = 1.0,
         overload_token_usage_threshold = 1.0,
+        tree_window_secs = 0,
+        cache_decay_half_life_secs = 0,
+        consistent_hashing_load_epsilon = None,
         least_load_kv_pressure_weight = 0.15,
         least_load_default_throughput = 2000.0,
         least_load_mean_prefill_tokens = 1024,
@@ -975,6 +987,9 @@ impl Router {
         block_size: usize,
         balance_token_usage_threshold: f32,
         overload_token_usage_threshold: f32,
+        tree_window_secs: u64,
+        cache_decay_half_life_secs: u64,
+        consistent_hashing_load_epsilon: Option<f64>,
         least_load_kv_pressure_weight: f64,
         least_load_default_throughput: f64,
         least_load_mean_prefill_tokens: u32,
@@ -1118,6 +1133,9 @@ impl Router {
             block_size,
             balance_token_usage_threshold,
             overload_token_usage_threshold,
+            tree_window_secs,
+            cache_decay_half_life_secs,
+            consistent_hashing_load_epsilon,
             least_load_kv_pressure_weight,
             least_load_default_throughput,
             least_load_mean_prefill_tokens,
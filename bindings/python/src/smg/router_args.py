@@ -61,6 +61,9 @@ class RouterArgs:
     balance_rel_threshold: float = 1.5
     balance_token_usage_threshold: float = 1.0
     overload_token_usage_threshold: float = 1.0
+    tree_window_secs: int = 0
+    cache_decay_half_life_secs: int = 0
+    consistent_hashing_load_epsilon: float | None = None
     eviction_interval_secs: int = 60
     max_tree_size: int = 2**26
     block_size: int = 16
@@ -442,6 +445,35 @@ class RouterArgs:
                 " Backend must report token_usage. Defaults to 1.0 (disabled)."
             ),
         )
+        routing_group.add_argument(
+            f"--{prefix}tree-window-secs",
+            type=int,
+            default=RouterArgs.tree_window_secs,
+            help=(
+                "History window in seconds for cache-aware approximate trees. Older"
+                " routing history is dropped so stale prefixes stop attracting"
+                " traffic. Defaults to 0 (unbounded)."
+            ),
+        )
+        routing_group.add_argument(
+            f"--{prefix}cache-decay-half-life-secs",
+            type=int,
+            default=RouterArgs.cache_decay_half_life_secs,
+            help=(
+                "Half-life in seconds for cache-aware matches found in older tree"
+                " generations. Defaults to 0 (no decay)."
+            ),
+        )
+        routing_group.add_argument(
+            f"--{prefix}consistent-hashing-load-epsilon",
+            type=float,
+            default=RouterArgs.consistent_hashing_load_epsilon,
+            help=(
+                "Bounded-load slack for consistent_hashing: a worker above"
+                " (1 + epsilon) x average load spills keys to the next worker on the"
+                " ring. Unset routes every key to its ring owner."
+            ),
+        )
         routing_group.add_argument(
             f"--{prefix}bucket-adjust-interval-secs",
             type=int,
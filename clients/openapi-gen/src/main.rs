@@ -427,6 +427,34 @@ fn main() -> anyhow::Result<()> {
         post_endpoint("classify", "Classify text", &req_name, &resp_name),
     );
 
+    // ---- Score ----
+    use openai_protocol::score::*;
+    let req_name = collect_schema(schema_for!(ScoreRequest), &mut schemas)?;
+    let resp_name = collect_schema(schema_for!(ScoreResponse), &mut schemas)?;
+    paths.insert(
+        "/v1/score".to_string(),
+        post_endpoint(
+            "score",
+            "Score label tokens against items",
+            &req_name,
+            &resp_name,
+        ),
+    );
+
+    // ---- Images ----
+    use openai_protocol::images::*;
+    let req_name = collect_schema(schema_for!(ImageGenerationRequest), &mut schemas)?;
+    let resp_name = collect_schema(schema_for!(ImageGenerationResponse), &mut schemas)?;
+    paths.insert(
+        "/v1/images/generations".to_string(),
+        post_endpoint(
+            "createImage",
+            "Generate images from a prompt",
+            &req_name,
+            &resp_name,
+        ),
+    );
+
     // ---- Parser ----
     use openai_protocol::parser::*;
     let req_name = collect_schema(schema_for!(ParseFunctionCallRequest), &mut schemas)?;
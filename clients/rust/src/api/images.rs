@@ -0,0 +1,27 @@
+use openai_protocol::images::{ImageGenerationRequest, ImageGenerationResponse};
+
+use crate::{transport::Transport, SmgError};
+
+/// Images API (`/v1/images/generations`).
+pub struct Images {
+    transport: Transport,
+}
+
+impl Images {
+    pub(crate) fn new(transport: Transport) -> Self {
+        Self { transport }
+    }
+
+    /// Generate images from a text prompt.
+    pub async fn generate(
+        &self,
+        request: &ImageGenerationRequest,
+    ) -> Result<ImageGenerationResponse, SmgError> {
+        let resp = self
+            .transport
+            .post("/v1/images/generations", request)
+            .await?;
+        let body = resp.text().await.map_err(SmgError::Connection)?;
+        serde_json::from_str(&body).map_err(SmgError::from)
+    }
+}
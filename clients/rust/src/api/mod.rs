@@ -2,9 +2,11 @@ pub mod chat;
 pub mod classify;
 pub mod completions;
 pub mod embeddings;
+pub mod images;
 pub mod messages;
 pub mod models;
 pub mod parser;
 pub mod rerank;
 pub mod responses;
+pub mod score;
 pub mod workers;
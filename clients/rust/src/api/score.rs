@@ -0,0 +1,21 @@
+use openai_protocol::score::{ScoreRequest, ScoreResponse};
+
+use crate::{transport::Transport, SmgError};
+
+/// Score API (`/v1/score`).
+pub struct Score {
+    transport: Transport,
+}
+
+impl Score {
+    pub(crate) fn new(transport: Transport) -> Self {
+        Self { transport }
+    }
+
+    /// Score label-token probabilities for each item against a query.
+    pub async fn create(&self, request: &ScoreRequest) -> Result<ScoreResponse, SmgError> {
+        let resp = self.transport.post("/v1/score", request).await?;
+        let body = resp.text().await.map_err(SmgError::Connection)?;
+        serde_json::from_str(&body).map_err(SmgError::from)
+    }
+}
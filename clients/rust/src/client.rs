@@ -1,8 +1,8 @@
 use crate::{
     api::{
         chat::Chat, classify::Classify, completions::Completions, embeddings::Embeddings,
-        messages::Messages, models::Models, parser::Parser, rerank::Rerank, responses::Responses,
-        workers::Workers,
+        images::Images, messages::Messages, models::Models, parser::Parser, rerank::Rerank,
+        responses::Responses, score::Score, workers::Workers,
     },
     config::ClientConfig,
     transport::Transport,
@@ -78,6 +78,16 @@ impl SmgClient {
         Classify::new(self.transport.clone())
     }
 
+    /// Access the score API (`/v1/score`).
+    pub fn score(&self) -> Score {
+        Score::new(self.transport.clone())
+    }
+
+    /// Access the images API (`/v1/images/generations`).
+    pub fn images(&self) -> Images {
+        Images::new(self.transport.clone())
+    }
+
     /// Access the responses API (`/v1/responses`).
     pub fn responses(&self) -> Responses {
         Responses::new(self.transport.clone())
@@ -12,7 +12,7 @@ mod jwks;
 mod jwt;
 mod middleware;
 
-pub use audit::{AuditEvent, AuditLogger, AuditOutcome};
+pub use audit::{AuditContext, AuditEvent, AuditLogger, AuditOutcome};
 pub use config::{ApiKeyEntry, ControlPlaneAuthConfig, JwtConfig, Role};
 pub use jwt::{JwtValidator, JwtValidatorError};
 pub use middleware::{
@@ -0,0 +1,633 @@
+//! Protocol-level checks run against a backend.
+//!
+//! Each check sends one small request and validates the response shape
+//! against the OpenAI chat completions protocol as the gateway consumes it.
+//! Validation is split from I/O so the rules can be tested on canned bodies.
+
+use std::{collections::BTreeMap, time::Instant};
+
+use openai_protocol::{
+    chat::{ChatCompletionResponse, ChatCompletionStreamResponse},
+    common::{ErrorResponse, Usage},
+};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    sse::{data_events, DONE},
+    target::Target,
+};
+
+const CHAT_PATH: &str = "/v1/chat/completions";
+const PROMPT: &str = "Reply with the single word: hello";
+
+/// A protocol check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// Non-streaming response deserializes with the expected fields
+    ChatCompletion,
+    /// Non-streaming usage is present and consistent
+    Usage,
+    /// Streamed chunks share an id, open with the assistant role, finish
+    /// each choice once and end with `[DONE]`
+    StreamingChunks,
+    /// `stream_options.include_usage` yields one trailing usage-only chunk
+    StreamingUsage,
+    /// Tool call deltas carry id/type/name first, then argument fragments
+    /// that concatenate to valid JSON
+    ToolCallDeltas,
+    /// Invalid requests fail with a 4xx and an OpenAI error body
+    ErrorShape,
+}
+
+impl Check {
+    pub const ALL: [Check; 6] = [
+        Check::ChatCompletion,
+        Check::Usage,
+        Check::StreamingChunks,
+        Check::StreamingUsage,
+        Check::ToolCallDeltas,
+        Check::ErrorShape,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::ChatCompletion => "chat_completion",
+            Check::Usage => "usage",
+            Check::StreamingChunks => "streaming_chunks",
+            Check::StreamingUsage => "streaming_usage",
+            Check::ToolCallDeltas => "tool_call_deltas",
+            Check::ErrorShape => "error_shape",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|check| check.name() == name)
+    }
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The backend does not support the feature under test
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub check: Check,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub duration_ms: u64,
+}
+
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+impl From<Result<(), String>> for Outcome {
+    fn from(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Outcome::Pass,
+            Err(detail) => Outcome::Fail(detail),
+        }
+    }
+}
+
+/// Run `check` against `target` using `model`.
+pub async fn run_check(client: &Client, target: &Target, model: &str, check: Check) -> CheckResult {
+    let started = Instant::now();
+    let outcome = match check {
+        Check::ChatCompletion => chat_completion(client, target, model).await.into(),
+        Check::Usage => usage(client, target, model).await.into(),
+        Check::StreamingChunks => streaming_chunks(client, target, model).await.into(),
+        Check::StreamingUsage => streaming_usage(client, target, model).await.into(),
+        Check::ToolCallDeltas => tool_call_deltas(client, target, model).await,
+        Check::ErrorShape => error_shape(client, target, model).await.into(),
+    };
+    let (status, detail) = match outcome {
+        Outcome::Pass => (Status::Pass, None),
+        Outcome::Fail(detail) => (Status::Fail, Some(detail)),
+        Outcome::Skip(detail) => (Status::Skip, Some(detail)),
+    };
+    CheckResult {
+        check,
+        status,
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn chat_body(model: &str, stream: bool) -> Value {
+    json!({
+        "model": model,
+        "messages": [{"role": "user", "content": PROMPT}],
+        "max_tokens": 16,
+        "temperature": 0,
+        "stream": stream,
+    })
+}
+
+async fn send(
+    client: &Client,
+    target: &Target,
+    body: &Value,
+) -> Result<(StatusCode, String), String> {
+    let response = target
+        .post(client, CHAT_PATH)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("reading response body failed: {e}"))?;
+    Ok((status, text))
+}
+
+async fn send_ok(client: &Client, target: &Target, body: &Value) -> Result<String, String> {
+    let (status, text) = send(client, target, body).await?;
+    if !status.is_success() {
+        return Err(format!("HTTP {status}: {}", truncate(&text)));
+    }
+    Ok(text)
+}
+
+fn truncate(text: &str) -> &str {
+    let end = text
+        .char_indices()
+        .nth(200)
+        .map_or(text.len(), |(index, _)| index);
+    &text[..end]
+}
+
+async fn chat_completion(client: &Client, target: &Target, model: &str) -> Result<(), String> {
+    let text = send_ok(client, target, &chat_body(model, false)).await?;
+    validate_chat_response(&text).map(|_| ())
+}
+
+async fn usage(client: &Client, target: &Target, model: &str) -> Result<(), String> {
+    let text = send_ok(client, target, &chat_body(model, false)).await?;
+    let response = validate_chat_response(&text)?;
+    validate_usage(response.usage.as_ref())
+}
+
+async fn streaming_chunks(client: &Client, target: &Target, model: &str) -> Result<(), String> {
+    let text = send_ok(client, target, &chat_body(model, true)).await?;
+    validate_stream(&data_events(&text)).map(|_| ())
+}
+
+async fn streaming_usage(client: &Client, target: &Target, model: &str) -> Result<(), String> {
+    let mut body = chat_body(model, true);
+    body["stream_options"] = json!({"include_usage": true});
+    let text = send_ok(client, target, &body).await?;
+    let chunks = validate_stream(&data_events(&text))?;
+    validate_stream_usage(&chunks)
+}
+
+async fn tool_call_deltas(client: &Client, target: &Target, model: &str) -> Outcome {
+    let body = json!({
+        "model": model,
+        "messages": [{
+            "role": "user",
+            "content": "What is the weather in Paris? Use the get_weather tool."
+        }],
+        "tools": [{
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "description": "Get the current weather for a location",
+                "parameters": {
+                    "type": "object",
+                    "properties": {"location": {"type": "string"}},
+                    "required": ["location"]
+                }
+            }
+        }],
+        "tool_choice": "required",
+        "max_tokens": 128,
+        "temperature": 0,
+        "stream": true,
+    });
+    let (status, text) = match send(client, target, &body).await {
+        Ok(response) => response,
+        Err(detail) => return Outcome::Fail(detail),
+    };
+    if status.is_client_error() {
+        return Outcome::Skip(format!(
+            "backend rejected the tool request (HTTP {status}): {}",
+            truncate(&text)
+        ));
+    }
+    if !status.is_success() {
+        return Outcome::Fail(format!("HTTP {status}: {}", truncate(&text)));
+    }
+    validate_stream(&data_events(&text))
+        .and_then(|chunks| validate_tool_call_deltas(&chunks))
+        .into()
+}
+
+async fn error_shape(client: &Client, target: &Target, model: &str) -> Result<(), String> {
+    let body = json!({"model": model, "messages": "not a list"});
+    let (status, text) = send(client, target, &body).await?;
+    validate_error_response(status, &text)
+}
+
+/// Parse and check a non-streaming chat completion body.
+pub fn validate_chat_response(text: &str) -> Result<ChatCompletionResponse, String> {
+    let response: ChatCompletionResponse = serde_json::from_str(text)
+        .map_err(|e| format!("response does not match the chat completion schema: {e}"))?;
+    if response.object != "chat.completion" {
+        return Err(format!(
+            "object is '{}', expected 'chat.completion'",
+            response.object
+        ));
+    }
+    let choice = response.choices.first().ok_or("response has no choices")?;
+    if choice.finish_reason.is_none() {
+        return Err("choice has no finish_reason".to_string());
+    }
+    Ok(response)
+}
+
+/// Check that usage is present and its totals add up.
+pub fn validate_usage(usage: Option<&Usage>) -> Result<(), String> {
+    let usage = usage.ok_or("usage is missing")?;
+    if usage.prompt_tokens == 0 {
+        return Err("usage.prompt_tokens is 0 for a non-empty prompt".to_string());
+    }
+    if usage.total_tokens != usage.prompt_tokens + usage.completion_tokens {
+        return Err(format!(
+            "usage.total_tokens {} != prompt_tokens {} + completion_tokens {}",
+            usage.total_tokens, usage.prompt_tokens, usage.completion_tokens
+        ));
+    }
+    Ok(())
+}
+
+/// Parse and check the `data:` payloads of a streamed chat completion,
+/// returning the chunks (without the `[DONE]` sentinel).
+pub fn validate_stream(events: &[String]) -> Result<Vec<ChatCompletionStreamResponse>, String> {
+    let (last, payloads) = events.split_last().ok_or("stream has no events")?;
+    if last != DONE {
+        return Err("stream does not end with data: [DONE]".to_string());
+    }
+
+    let mut chunks = Vec::with_capacity(payloads.len());
+    for (index, payload) in payloads.iter().enumerate() {
+        if payload == DONE {
+            return Err(format!(
+                "event {index} is [DONE] before the end of the stream"
+            ));
+        }
+        let chunk: ChatCompletionStreamResponse = serde_json::from_str(payload)
+            .map_err(|e| format!("chunk {index} does not match the chunk schema: {e}"))?;
+        if chunk.object != "chat.completion.chunk" {
+            return Err(format!(
+                "chunk {index} object is '{}', expected 'chat.completion.chunk'",
+                chunk.object
+            ));
+        }
+        chunks.push(chunk);
+    }
+
+    let first = chunks.first().ok_or("stream has no chunks")?;
+    if let Some(chunk) = chunks.iter().find(|chunk| chunk.id != first.id) {
+        return Err(format!(
+            "chunk ids differ: '{}' and '{}'",
+            first.id, chunk.id
+        ));
+    }
+    let opening_role = chunks
+        .iter()
+        .find_map(|chunk| chunk.choices.first())
+        .and_then(|choice| choice.delta.role.as_deref());
+    if opening_role != Some("assistant") {
+        return Err(format!(
+            "first choice delta has role {opening_role:?}, expected \"assistant\""
+        ));
+    }
+
+    let mut finished = BTreeMap::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        for choice in &chunk.choices {
+            if finished.contains_key(&choice.index) {
+                return Err(format!(
+                    "chunk {index} continues choice {} after its finish_reason",
+                    choice.index
+                ));
+            }
+            if let Some(reason) = &choice.finish_reason {
+                finished.insert(choice.index, reason.clone());
+            }
+        }
+    }
+    if finished.is_empty() {
+        return Err("no chunk carries a finish_reason".to_string());
+    }
+    Ok(chunks)
+}
+
+/// Check that exactly the final chunk carries usage, with no choices.
+pub fn validate_stream_usage(chunks: &[ChatCompletionStreamResponse]) -> Result<(), String> {
+    let with_usage: Vec<usize> = chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, chunk)| chunk.usage.is_some().then_some(index))
+        .collect();
+    let last = chunks.len().saturating_sub(1);
+    match with_usage.as_slice() {
+        [] => return Err("no chunk carries usage".to_string()),
+        [index] if *index == last => {}
+        _ => {
+            return Err(format!(
+                "usage must appear only on the final chunk, found on chunks {with_usage:?} of {}",
+                chunks.len()
+            ))
+        }
+    }
+    let usage_chunk = &chunks[last];
+    if !usage_chunk.choices.is_empty() {
+        return Err("the usage chunk must have an empty choices array".to_string());
+    }
+    validate_usage(usage_chunk.usage.as_ref())
+}
+
+#[derive(Default)]
+struct ToolCallAccumulator {
+    id: Option<String>,
+    name: Option<String>,
+    arguments: String,
+}
+
+/// Check streamed tool call deltas: each call opens with id, type and
+/// function name, then streams argument fragments forming a JSON object.
+pub fn validate_tool_call_deltas(chunks: &[ChatCompletionStreamResponse]) -> Result<(), String> {
+    let mut calls: BTreeMap<u32, ToolCallAccumulator> = BTreeMap::new();
+    let mut finish_reason = None;
+    for chunk in chunks {
+        for choice in &chunk.choices {
+            if choice.finish_reason.is_some() {
+                finish_reason.clone_from(&choice.finish_reason);
+            }
+            for delta in choice.delta.tool_calls.iter().flatten() {
+                let call = calls.entry(delta.index).or_default();
+                let function = delta.function.as_ref();
+                if call.id.is_none() {
+                    let id = delta
+                        .id
+                        .as_deref()
+                        .filter(|id| !id.is_empty())
+                        .ok_or_else(|| {
+                            format!("first delta of tool call {} has no id", delta.index)
+                        })?;
+                    if delta.tool_type.as_deref() != Some("function") {
+                        return Err(format!(
+                            "first delta of tool call {} has type {:?}, expected \"function\"",
+                            delta.index, delta.tool_type
+                        ));
+                    }
+                    let name = function
+                        .and_then(|f| f.name.as_deref())
+                        .filter(|name| !name.is_empty())
+                        .ok_or_else(|| {
+                            format!(
+                                "first delta of tool call {} has no function name",
+                                delta.index
+                            )
+                        })?;
+                    call.id = Some(id.to_string());
+                    call.name = Some(name.to_string());
+                } else {
+                    if delta
+                        .id
+                        .as_ref()
+                        .is_some_and(|id| Some(id) != call.id.as_ref())
+                    {
+                        return Err(format!(
+                            "tool call {} changed its id mid-stream",
+                            delta.index
+                        ));
+                    }
+                    if function
+                        .and_then(|f| f.name.as_ref())
+                        .is_some_and(|name| Some(name) != call.name.as_ref())
+                    {
+                        return Err(format!(
+                            "tool call {} changed its function name mid-stream",
+                            delta.index
+                        ));
+                    }
+                }
+                if let Some(arguments) = function.and_then(|f| f.arguments.as_deref()) {
+                    call.arguments.push_str(arguments);
+                }
+            }
+        }
+    }
+
+    if calls.is_empty() {
+        return Err("no tool call deltas were streamed".to_string());
+    }
+    for (index, call) in &calls {
+        let arguments: Value = serde_json::from_str(&call.arguments).map_err(|e| {
+            format!(
+                "tool call {index} arguments are not valid JSON ({e}): {}",
+                call.arguments
+            )
+        })?;
+        if !arguments.is_object() {
+            return Err(format!("tool call {index} arguments are not a JSON object"));
+        }
+    }
+    match finish_reason.as_deref() {
+        Some("tool_calls" | "stop") => Ok(()),
+        other => Err(format!(
+            "tool call stream finished with {other:?}, expected \"tool_calls\""
+        )),
+    }
+}
+
+/// Check that an invalid request failed with a 4xx and an OpenAI error body.
+pub fn validate_error_response(status: StatusCode, text: &str) -> Result<(), String> {
+    if !status.is_client_error() {
+        return Err(format!(
+            "invalid request returned HTTP {status}, expected 4xx"
+        ));
+    }
+    let error: ErrorResponse = serde_json::from_str(text).map_err(|e| {
+        format!(
+            "error body is not {{\"error\": {{\"message\", \"type\", ...}}}} ({e}): {}",
+            truncate(text)
+        )
+    })?;
+    if error.error.message.is_empty() {
+        return Err("error.message is empty".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, delta: Value, finish_reason: Option<&str>) -> String {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "m",
+            "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}]
+        })
+        .to_string()
+    }
+
+    fn usage_chunk(id: &str) -> String {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "m",
+            "choices": [],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_valid_stream_with_usage() {
+        let events = vec![
+            chunk("a", json!({"role": "assistant", "content": ""}), None),
+            chunk("a", json!({"content": "hello"}), None),
+            chunk("a", json!({}), Some("stop")),
+            usage_chunk("a"),
+            DONE.to_string(),
+        ];
+        let chunks = validate_stream(&events).unwrap();
+        assert!(validate_stream_usage(&chunks).is_ok());
+    }
+
+    #[test]
+    fn test_stream_violations() {
+        let opening = chunk("a", json!({"role": "assistant"}), None);
+        let finish = chunk("a", json!({}), Some("stop"));
+
+        let missing_done = vec![opening.clone(), finish.clone()];
+        assert!(validate_stream(&missing_done).is_err());
+
+        let mixed_ids = vec![
+            opening.clone(),
+            chunk("b", json!({}), Some("stop")),
+            DONE.into(),
+        ];
+        assert!(validate_stream(&mixed_ids)
+            .unwrap_err()
+            .contains("ids differ"));
+
+        let after_finish = vec![
+            opening.clone(),
+            finish.clone(),
+            chunk("a", json!({"content": "x"}), None),
+            DONE.into(),
+        ];
+        assert!(validate_stream(&after_finish)
+            .unwrap_err()
+            .contains("after its finish_reason"));
+
+        let no_role = vec![
+            chunk("a", json!({"content": "x"}), Some("stop")),
+            DONE.into(),
+        ];
+        assert!(validate_stream(&no_role).unwrap_err().contains("role"));
+
+        // Usage requested but never sent
+        let chunks = validate_stream(&[opening, finish, DONE.into()]).unwrap();
+        assert!(validate_stream_usage(&chunks).is_err());
+    }
+
+    #[test]
+    fn test_tool_call_deltas() {
+        let opening = chunk(
+            "a",
+            json!({"role": "assistant", "tool_calls": [{
+                "index": 0, "id": "call_1", "type": "function",
+                "function": {"name": "get_weather", "arguments": ""}
+            }]}),
+            None,
+        );
+        let args = |fragment: &str| {
+            chunk(
+                "a",
+                json!({"tool_calls": [{"index": 0, "function": {"arguments": fragment}}]}),
+                None,
+            )
+        };
+        let events = vec![
+            opening.clone(),
+            args("{\"location\": "),
+            args("\"Paris\"}"),
+            chunk("a", json!({}), Some("tool_calls")),
+            DONE.into(),
+        ];
+        let chunks = validate_stream(&events).unwrap();
+        assert!(validate_tool_call_deltas(&chunks).is_ok());
+
+        let truncated = vec![
+            opening,
+            args("{\"location\": "),
+            chunk("a", json!({}), Some("tool_calls")),
+            DONE.into(),
+        ];
+        let chunks = validate_stream(&truncated).unwrap();
+        assert!(validate_tool_call_deltas(&chunks)
+            .unwrap_err()
+            .contains("not valid JSON"));
+
+        let no_id = vec![
+            chunk(
+                "a",
+                json!({"role": "assistant", "tool_calls": [{
+                    "index": 0, "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{}"}
+                }]}),
+                Some("tool_calls"),
+            ),
+            DONE.into(),
+        ];
+        let chunks = validate_stream(&no_id).unwrap();
+        assert!(validate_tool_call_deltas(&chunks)
+            .unwrap_err()
+            .contains("no id"));
+    }
+
+    #[test]
+    fn test_error_response() {
+        let openai =
+            r#"{"error": {"message": "messages must be a list", "type": "invalid_request_error"}}"#;
+        assert!(validate_error_response(StatusCode::BAD_REQUEST, openai).is_ok());
+
+        let flat =
+            r#"{"object": "error", "message": "bad", "type": "BadRequestError", "code": 400}"#;
+        assert!(validate_error_response(StatusCode::BAD_REQUEST, flat).is_err());
+        assert!(validate_error_response(StatusCode::INTERNAL_SERVER_ERROR, openai).is_err());
+    }
+
+    #[test]
+    fn test_usage_totals() {
+        assert!(validate_usage(Some(&Usage::from_counts(5, 2))).is_ok());
+        let mut usage = Usage::from_counts(5, 2);
+        usage.total_tokens = 6;
+        assert!(validate_usage(Some(&usage)).is_err());
+        assert!(validate_usage(None).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+//! Protocol conformance harness for OpenAI-compatible inference backends.
+//!
+//! Runs a suite of protocol-level [`checks`] (streaming chunk shape, tool
+//! call deltas, usage reporting, error bodies) against one or more
+//! [`Target`]s and collects a [`Report`]. Used by the `smg-conformance`
+//! binary in CI and by operators validating a new engine before putting it
+//! behind the gateway.
+
+pub mod checks;
+pub mod report;
+pub mod sse;
+pub mod target;
+
+pub use checks::{run_check, Check, CheckResult, Status};
+pub use report::{Report, TargetReport};
+pub use target::{discover_workers, Target};
+
+use reqwest::Client;
+
+/// Run `checks` against `target`. If no model can be resolved every check
+/// fails with the resolution error.
+pub async fn run_suite(client: &Client, target: &Target, checks: &[Check]) -> TargetReport {
+    let model = target.resolve_model(client).await;
+    let mut results = Vec::with_capacity(checks.len());
+    for &check in checks {
+        let result = match &model {
+            Ok(model) => run_check(client, target, model, check).await,
+            Err(detail) => CheckResult {
+                check,
+                status: Status::Fail,
+                detail: Some(detail.clone()),
+                duration_ms: 0,
+            },
+        };
+        results.push(result);
+    }
+    TargetReport {
+        base_url: target.base_url.clone(),
+        model: model.ok(),
+        results,
+    }
+}
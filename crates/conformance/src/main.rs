@@ -0,0 +1,171 @@
+//! smg-conformance: run the protocol conformance suite against OpenAI-compatible
+//! backends and print a compatibility report.
+//!
+//! Exits 0 when no check failed, 1 when any check failed and 2 on usage or
+//! worker discovery errors.
+
+use std::{process::ExitCode, time::Duration};
+
+use reqwest::Client;
+use smg_conformance::{discover_workers, run_suite, Check, Report, Target};
+
+struct Args {
+    workers: Vec<String>,
+    gateway: Option<String>,
+    gateway_api_key: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    checks: Vec<Check>,
+    json: bool,
+    timeout: Duration,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut parsed = Self {
+            workers: Vec::new(),
+            gateway: None,
+            gateway_api_key: None,
+            api_key: None,
+            model: None,
+            checks: Vec::new(),
+            json: false,
+            timeout: Duration::from_secs(60),
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--worker" => parsed.workers.push(value(&mut args, &flag)?),
+                "--gateway" => parsed.gateway = Some(value(&mut args, &flag)?),
+                "--gateway-api-key" => parsed.gateway_api_key = Some(value(&mut args, &flag)?),
+                "--api-key" => parsed.api_key = Some(value(&mut args, &flag)?),
+                "--model" => parsed.model = Some(value(&mut args, &flag)?),
+                "--check" => {
+                    let name = value(&mut args, &flag)?;
+                    let check = Check::from_name(&name)
+                        .ok_or_else(|| format!("unknown check: {name}\n\n{}", usage()))?;
+                    parsed.checks.push(check);
+                }
+                "--format" => {
+                    parsed.json = match value(&mut args, &flag)?.as_str() {
+                        "json" => true,
+                        "text" => false,
+                        other => return Err(format!("--format must be text|json, got {other}")),
+                    }
+                }
+                "--timeout-secs" => {
+                    let secs = value(&mut args, &flag)?;
+                    let secs = secs
+                        .parse()
+                        .map_err(|_| format!("invalid value for {flag}: {secs}"))?;
+                    parsed.timeout = Duration::from_secs(secs);
+                }
+                "-h" | "--help" => return Err(usage()),
+                other => return Err(format!("unknown flag: {other}\n\n{}", usage())),
+            }
+        }
+
+        if parsed.workers.is_empty() && parsed.gateway.is_none() {
+            return Err(format!(
+                "nothing to check: pass --worker and/or --gateway\n\n{}",
+                usage()
+            ));
+        }
+        if parsed.checks.is_empty() {
+            parsed.checks = Check::ALL.to_vec();
+        }
+        Ok(parsed)
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {flag}"))
+}
+
+fn usage() -> String {
+    let checks: Vec<&str> = Check::ALL.iter().map(|check| check.name()).collect();
+    format!(
+        "smg-conformance — protocol conformance checks for OpenAI-compatible backends\n\n\
+         Flags:\n\
+           --worker <url>             backend base URL to check (repeatable)\n\
+           --gateway <url>            also check every HTTP worker registered with this gateway\n\
+           --gateway-api-key <key>    bearer token for the gateway's /workers API\n\
+           --api-key <key>            bearer token sent to backends\n\
+           --model <id>               model to request (default: first of GET /v1/models)\n\
+           --check <name>             run only this check (repeatable; default all)\n\
+           --format <text|json>       report format (default text)\n\
+           --timeout-secs <n>         per-request timeout (default 60)\n\
+         \n\
+         Checks: {}",
+        checks.join(", ")
+    )
+}
+
+#[expect(clippy::print_stdout, reason = "the report is the CLI's output")]
+fn print_report(report: &Report, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(json) => println!("{json}"),
+            Err(e) => println!("{{\"error\": \"failed to serialize report: {e}\"}}"),
+        }
+    } else {
+        print!("{}", report.to_text());
+    }
+}
+
+#[expect(
+    clippy::print_stderr,
+    reason = "usage and discovery errors go to stderr"
+)]
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::from(2);
+        }
+    };
+    let client = match Client::builder().timeout(args.timeout).build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("failed to build HTTP client: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut targets: Vec<Target> = args.workers.iter().map(Target::new).collect();
+    if let Some(gateway) = &args.gateway {
+        match discover_workers(&client, gateway, args.gateway_api_key.as_deref()).await {
+            Ok(workers) if workers.is_empty() => {
+                eprintln!("gateway {gateway} has no registered HTTP workers");
+            }
+            Ok(workers) => targets.extend(workers),
+            Err(e) => {
+                eprintln!("worker discovery failed: {e}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if targets.is_empty() {
+        return ExitCode::from(2);
+    }
+
+    let mut report = Report::default();
+    for target in targets {
+        let model = args.model.clone().or_else(|| target.model.clone());
+        let target = target.with_model(model).with_api_key(args.api_key.clone());
+        report
+            .targets
+            .push(run_suite(&client, &target, &args.checks).await);
+    }
+
+    print_report(&report, args.json);
+    if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
@@ -0,0 +1,114 @@
+//! Compatibility report across targets.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::checks::{CheckResult, Status};
+
+/// Check results for one target.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetReport {
+    pub base_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub results: Vec<CheckResult>,
+}
+
+impl TargetReport {
+    /// No check failed; skipped checks do not count as failures.
+    pub fn passed(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| result.status != Status::Fail)
+    }
+
+    fn count(&self, status: Status) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    pub targets: Vec<TargetReport>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.targets.iter().all(TargetReport::passed)
+    }
+
+    /// Human-readable report, one block per target.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for target in &self.targets {
+            let model = target.model.as_deref().unwrap_or("unknown model");
+            let _ = writeln!(
+                out,
+                "{} ({model}): {} passed, {} failed, {} skipped",
+                target.base_url,
+                target.count(Status::Pass),
+                target.count(Status::Fail),
+                target.count(Status::Skip),
+            );
+            for result in &target.results {
+                let label = match result.status {
+                    Status::Pass => "PASS",
+                    Status::Fail => "FAIL",
+                    Status::Skip => "SKIP",
+                };
+                let _ = write!(out, "  {label}  {}", result.check.name());
+                if let Some(detail) = &result.detail {
+                    let _ = write!(out, ": {detail}");
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::Check;
+
+    fn result(check: Check, status: Status, detail: Option<&str>) -> CheckResult {
+        CheckResult {
+            check,
+            status,
+            detail: detail.map(str::to_string),
+            duration_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_report_text_and_verdict() {
+        let mut report = Report {
+            targets: vec![TargetReport {
+                base_url: "http://w1:8000".to_string(),
+                model: Some("m".to_string()),
+                results: vec![
+                    result(Check::ChatCompletion, Status::Pass, None),
+                    result(Check::ToolCallDeltas, Status::Skip, Some("no tools")),
+                ],
+            }],
+        };
+        assert!(report.passed());
+        assert_eq!(
+            report.to_text(),
+            "http://w1:8000 (m): 1 passed, 0 failed, 1 skipped\n  \
+             PASS  chat_completion\n  SKIP  tool_call_deltas: no tools\n"
+        );
+
+        report.targets[0].results.push(result(
+            Check::Usage,
+            Status::Fail,
+            Some("usage is missing"),
+        ));
+        assert!(!report.passed());
+    }
+}
@@ -0,0 +1,36 @@
+//! Minimal server-sent events parsing for buffered streaming responses.
+
+/// Terminal `data:` payload of an OpenAI-compatible stream.
+pub const DONE: &str = "[DONE]";
+
+/// The `data:` payloads of each event in `body`, in order. Multi-line data
+/// fields are joined with `\n`; comments and other fields are ignored.
+pub fn data_events(body: &str) -> Vec<String> {
+    let body = body.replace("\r\n", "\n");
+    body.split("\n\n")
+        .filter_map(|event| {
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            (!data.is_empty()).then(|| data.join("\n"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_events() {
+        let body = ": keep-alive\r\n\r\ndata: {\"a\":1}\r\n\r\nevent: x\ndata:{\"b\":2}\n\ndata: [DONE]\n\n";
+        assert_eq!(data_events(body), vec!["{\"a\":1}", "{\"b\":2}", DONE]);
+    }
+
+    #[test]
+    fn test_multiline_data_is_joined() {
+        assert_eq!(data_events("data: a\ndata: b\n\n"), vec!["a\nb"]);
+    }
+}
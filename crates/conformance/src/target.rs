@@ -0,0 +1,99 @@
+//! Backends under test: explicit worker URLs or the HTTP workers registered
+//! with a running gateway.
+
+use openai_protocol::worker::{ConnectionMode, WorkerListResponse};
+use reqwest::Client;
+use serde::Serialize;
+
+/// One OpenAI-compatible backend to run the suite against.
+#[derive(Debug, Clone, Serialize)]
+pub struct Target {
+    /// Base URL, without a trailing slash (e.g. `http://10.0.0.5:8000`).
+    pub base_url: String,
+    /// Model to request; resolved from `GET /v1/models` when unset.
+    pub model: Option<String>,
+    #[serde(skip)]
+    pub api_key: Option<String>,
+}
+
+impl Target {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: None,
+            api_key: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    pub(crate) fn post(&self, client: &Client, path: &str) -> reqwest::RequestBuilder {
+        let request = client.post(self.url(path));
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    /// The configured model, or the first model the backend lists.
+    pub(crate) async fn resolve_model(&self, client: &Client) -> Result<String, String> {
+        if let Some(model) = &self.model {
+            return Ok(model.clone());
+        }
+        let mut request = client.get(self.url("/v1/models"));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let body: serde_json::Value = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("GET /v1/models failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| format!("GET /v1/models returned invalid JSON: {e}"))?;
+        body["data"][0]["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "GET /v1/models listed no models".to_string())
+    }
+}
+
+/// HTTP workers registered with the gateway at `gateway_url`. gRPC workers
+/// speak token ids rather than the OpenAI protocol and are skipped.
+pub async fn discover_workers(
+    client: &Client,
+    gateway_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<Target>, String> {
+    let mut request = client.get(format!("{}/workers", gateway_url.trim_end_matches('/')));
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    let list: WorkerListResponse = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("GET /workers failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("GET /workers returned an unexpected body: {e}"))?;
+    Ok(list
+        .workers
+        .into_iter()
+        .filter(|worker| worker.spec.connection_mode == ConnectionMode::Http)
+        .map(|worker| Target::new(worker.spec.url.clone()).with_model(worker.model_id))
+        .collect())
+}
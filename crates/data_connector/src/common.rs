@@ -20,6 +20,7 @@ pub(super) const RESPONSE_COLUMNS: &[&str] = &[
     "safety_identifier",
     "model",
     "raw_response",
+    "tenant_key",
 ];
 
 /// Build the `SELECT col1, col2, ... FROM table` base query for responses.
@@ -6,7 +6,7 @@ use url::Url;
 use crate::schema::SchemaConfig;
 
 /// History backend configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum HistoryBackend {
     #[default]
@@ -18,7 +18,7 @@ pub enum HistoryBackend {
 }
 
 /// Oracle history backend configuration
-#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct OracleConfig {
     /// ATP wallet or TLS config files directory
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -81,7 +81,7 @@ impl std::fmt::Debug for OracleConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct PostgresConfig {
     // Database connection URL,
     // postgres://[user[:password]@][netloc][:port][/dbname][?param1=value1&...]
@@ -132,7 +132,7 @@ impl PostgresConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct RedisConfig {
     // Redis connection URL
     // redis://[:password@]host[:port][/db]
@@ -364,6 +364,11 @@ pub struct StoredResponse {
     /// Raw OpenAI response payload
     #[serde(default)]
     pub raw_response: Value,
+
+    /// Tenant that created the response. `None` for responses stored before
+    /// ownership was recorded.
+    #[serde(default)]
+    pub tenant_key: Option<String>,
 }
 
 impl StoredResponse {
@@ -377,6 +382,7 @@ impl StoredResponse {
             model: None,
             conversation_id: None,
             raw_response: Value::Null,
+            tenant_key: None,
         }
     }
 }
@@ -444,6 +450,14 @@ pub trait ResponseStorage: Send + Sync {
     /// Delete a response
     async fn delete_response(&self, response_id: &ResponseId) -> ResponseResult<()>;
 
+    /// Replace the raw response body of a stored response in place. Returns
+    /// `false` if no response with that ID exists.
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: Value,
+    ) -> ResponseResult<bool>;
+
     /// Get the chain of responses leading to a given response.
     ///
     /// Walks `previous_response_id` links from the given response backwards,
@@ -509,6 +523,604 @@ impl Default for StoredResponse {
     }
 }
 
+// ============================================================================
+// PART 4: Debug Capture Storage
+// ============================================================================
+
+/// Debug capture identifier
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DebugCaptureId(pub String);
+
+impl DebugCaptureId {
+    pub fn new() -> Self {
+        Self(format!("cap_{}", ulid::Ulid::new()))
+    }
+}
+
+impl Default for DebugCaptureId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for DebugCaptureId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for DebugCaptureId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for DebugCaptureId {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A sampled request/response pair kept for debugging.
+///
+/// Bodies are stored after redaction; storage backends never see raw
+/// payloads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugCapture {
+    pub id: DebugCaptureId,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub status: u16,
+    pub latency_ms: u64,
+    /// Redacted request body (JSON when parseable, otherwise a string)
+    #[serde(default)]
+    pub request_body: Value,
+    /// Redacted response body; `Null` for streaming responses
+    #[serde(default)]
+    pub response_body: Value,
+    /// True when either body was cut at the capture size limit
+    #[serde(default)]
+    pub truncated: bool,
+    /// True when the response was streamed and its body was not captured
+    #[serde(default)]
+    pub streaming: bool,
+}
+
+/// Filters for listing debug captures. All set fields must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugCaptureFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl DebugCaptureFilter {
+    pub fn matches(&self, capture: &DebugCapture) -> bool {
+        if let Some(model) = &self.model {
+            if capture.model.as_deref() != Some(model.as_str()) {
+                return false;
+            }
+        }
+        if self.status.is_some_and(|s| s != capture.status) {
+            return false;
+        }
+        if self.min_latency_ms.is_some_and(|m| capture.latency_ms < m) {
+            return false;
+        }
+        if self.max_latency_ms.is_some_and(|m| capture.latency_ms > m) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Error type for debug capture storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum DebugCaptureStorageError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+pub type DebugCaptureResult<T> = Result<T, DebugCaptureStorageError>;
+
+/// Trait for debug capture storage
+#[async_trait]
+pub trait DebugCaptureStorage: Send + Sync + 'static {
+    /// Store a capture, evicting the oldest entries if the backend is bounded
+    async fn store_capture(&self, capture: DebugCapture) -> DebugCaptureResult<DebugCaptureId>;
+
+    /// Get a capture by ID
+    async fn get_capture(&self, id: &DebugCaptureId) -> DebugCaptureResult<Option<DebugCapture>>;
+
+    /// List captures matching the filter, newest first
+    async fn list_captures(
+        &self,
+        filter: &DebugCaptureFilter,
+    ) -> DebugCaptureResult<Vec<DebugCapture>>;
+
+    /// Delete all captures, returning how many were removed
+    async fn clear_captures(&self) -> DebugCaptureResult<usize>;
+}
+
+// ============================================================================
+// PART 5: File Storage
+// ============================================================================
+
+/// Stored file identifier
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FileId(pub String);
+
+impl FileId {
+    pub fn new() -> Self {
+        Self(format!("file-{}", ulid::Ulid::new()))
+    }
+}
+
+impl Default for FileId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for FileId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for FileId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for FileId {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A binary artifact produced by the gateway (e.g. a generated image).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub id: FileId,
+    pub created_at: DateTime<Utc>,
+    pub filename: String,
+    /// What produced the file (e.g. `image_generation`)
+    pub purpose: String,
+    pub content_type: String,
+    #[serde(skip)]
+    pub bytes: Vec<u8>,
+}
+
+impl StoredFile {
+    pub fn new(
+        filename: impl Into<String>,
+        purpose: impl Into<String>,
+        content_type: impl Into<String>,
+        bytes: Vec<u8>,
+    ) -> Self {
+        Self {
+            id: FileId::new(),
+            created_at: Utc::now(),
+            filename: filename.into(),
+            purpose: purpose.into(),
+            content_type: content_type.into(),
+            bytes,
+        }
+    }
+}
+
+/// Error type for file storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum FileStorageError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type FileResult<T> = Result<T, FileStorageError>;
+
+/// Trait for file storage
+#[async_trait]
+pub trait FileStorage: Send + Sync + 'static {
+    /// Store a file, evicting the oldest entries if the backend is bounded
+    async fn store_file(&self, file: StoredFile) -> FileResult<FileId>;
+
+    /// Get a file by ID
+    async fn get_file(&self, id: &FileId) -> FileResult<Option<StoredFile>>;
+
+    /// Delete a file, returning whether it existed
+    async fn delete_file(&self, id: &FileId) -> FileResult<bool>;
+}
+
+// ============================================================================
+// PART 6: Chat Completion Storage
+// ============================================================================
+
+/// A chat completion stored because the request set `store: true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredChatCompletion {
+    /// Completion ID from the response (`chatcmpl-...`)
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub model: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Original request body
+    #[serde(default)]
+    pub request: Value,
+    /// Chat completion response body
+    #[serde(default)]
+    pub response: Value,
+    /// Tenant that created the completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_key: Option<String>,
+}
+
+/// Filters for listing stored chat completions. All set fields must match.
+#[derive(Debug, Clone)]
+pub struct ChatCompletionFilter {
+    pub model: Option<String>,
+    /// Every pair must be present in the completion's metadata
+    pub metadata: HashMap<String, String>,
+    pub tenant_key: Option<String>,
+    /// Cursor: return completions after this ID in list order
+    pub after: Option<String>,
+    pub limit: usize,
+    pub order: SortOrder,
+}
+
+impl Default for ChatCompletionFilter {
+    fn default() -> Self {
+        Self {
+            model: None,
+            metadata: HashMap::new(),
+            tenant_key: None,
+            after: None,
+            limit: 20,
+            order: SortOrder::Asc,
+        }
+    }
+}
+
+impl ChatCompletionFilter {
+    pub fn matches(&self, completion: &StoredChatCompletion) -> bool {
+        if let Some(model) = &self.model {
+            if &completion.model != model {
+                return false;
+            }
+        }
+        if self.tenant_key.is_some() && completion.tenant_key != self.tenant_key {
+            return false;
+        }
+        self.metadata
+            .iter()
+            .all(|(key, value)| completion.metadata.get(key) == Some(value))
+    }
+}
+
+/// Error type for chat completion storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum ChatCompletionStorageError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type ChatCompletionResult<T> = Result<T, ChatCompletionStorageError>;
+
+/// Trait for stored chat completions
+#[async_trait]
+pub trait ChatCompletionStorage: Send + Sync + 'static {
+    /// Store a completion, evicting the oldest entries if the backend is bounded
+    async fn store_completion(&self, completion: StoredChatCompletion) -> ChatCompletionResult<()>;
+
+    /// Get a completion by ID
+    async fn get_completion(&self, id: &str) -> ChatCompletionResult<Option<StoredChatCompletion>>;
+
+    /// List completions matching the filter
+    async fn list_completions(
+        &self,
+        filter: &ChatCompletionFilter,
+    ) -> ChatCompletionResult<Vec<StoredChatCompletion>>;
+
+    /// Delete a completion, returning whether it existed
+    async fn delete_completion(&self, id: &str) -> ChatCompletionResult<bool>;
+}
+
+// ============================================================================
+// PART 7: Generation Job Storage
+// ============================================================================
+
+/// Lifecycle of an asynchronous generation job.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationJobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl GenerationJobStatus {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A generation request submitted for asynchronous execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationJob {
+    /// Job ID (`gen_...`)
+    pub id: String,
+    pub tenant_key: String,
+    /// Target endpoint, e.g. `/v1/chat/completions`
+    pub endpoint: String,
+    pub model: String,
+    /// Request body as submitted
+    pub request: Value,
+    pub status: GenerationJobStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Response body of a succeeded job
+    #[serde(default)]
+    pub result: Option<Value>,
+    /// Error body of a failed job
+    #[serde(default)]
+    pub error: Option<Value>,
+    /// Webhook URL supplied with the request, notified when the job finishes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+}
+
+/// Error type for generation job storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum GenerationJobStorageError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type GenerationJobResult<T> = Result<T, GenerationJobStorageError>;
+
+/// Trait for asynchronous generation job records
+#[async_trait]
+pub trait GenerationJobStorage: Send + Sync + 'static {
+    /// Insert or replace a job. Bounded backends evict finished jobs first.
+    async fn put_job(&self, job: GenerationJob) -> GenerationJobResult<()>;
+
+    /// Get a job by ID
+    async fn get_job(&self, id: &str) -> GenerationJobResult<Option<GenerationJob>>;
+
+    /// Jobs that have not finished, oldest first; used to resume after restart
+    async fn list_unfinished_jobs(&self) -> GenerationJobResult<Vec<GenerationJob>>;
+}
+
+// ============================================================================
+// PART 8: Vector Store Storage
+// ============================================================================
+
+/// A chunk of an ingested file together with its embedding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStoreChunk {
+    pub vector_store_id: String,
+    pub file_id: FileId,
+    pub filename: String,
+    /// Position of the chunk within its file
+    pub index: usize,
+    pub text: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A chunk returned by a similarity search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorSearchHit {
+    pub chunk: VectorStoreChunk,
+    /// Cosine similarity to the query embedding
+    pub score: f32,
+}
+
+/// Error type for vector store storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum VectorStoreStorageError {
+    #[error("Vector store '{0}' is full")]
+    CapacityExceeded(String),
+
+    #[error("Embedding has {actual} dimensions, vector store expects {expected}")]
+    DimensionMismatch { expected: usize, actual: usize },
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type VectorStoreResult<T> = Result<T, VectorStoreStorageError>;
+
+/// Trait for vector store chunk storage
+#[async_trait]
+pub trait VectorStoreStorage: Send + Sync + 'static {
+    /// Insert chunks, replacing chunks with the same store, file and index
+    async fn upsert_chunks(&self, chunks: Vec<VectorStoreChunk>) -> VectorStoreResult<()>;
+
+    /// Remove a file's chunks from a vector store, returning how many were removed
+    async fn delete_file_chunks(
+        &self,
+        vector_store_id: &str,
+        file_id: &FileId,
+    ) -> VectorStoreResult<usize>;
+
+    /// The `limit` chunks most similar to `embedding`, best first
+    async fn search(
+        &self,
+        vector_store_id: &str,
+        embedding: &[f32],
+        limit: usize,
+    ) -> VectorStoreResult<Vec<VectorSearchHit>>;
+}
+
+// ============================================================================
+// PART 9: Tenant MCP Server Storage
+// ============================================================================
+
+/// An MCP server a tenant registered for its own requests.
+///
+/// Responses requests of the tenant that name the server's `label` in an
+/// `mcp` tool without a `server_url` are connected to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TenantMcpServer {
+    pub tenant_key: String,
+    pub label: String,
+    pub server_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Tools exposed to the model; all tools when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Error type for tenant MCP server storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum TenantMcpServerStorageError {
+    #[error("Tenant already has {0} MCP servers registered")]
+    LimitExceeded(usize),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type TenantMcpServerResult<T> = Result<T, TenantMcpServerStorageError>;
+
+/// Trait for tenant-scoped MCP server registrations
+#[async_trait]
+pub trait TenantMcpServerStorage: Send + Sync + 'static {
+    /// Insert or replace the server with the same tenant and label.
+    /// Returns the previous registration, if any.
+    async fn put_server(
+        &self,
+        server: TenantMcpServer,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>>;
+
+    /// Get a tenant's server by label
+    async fn get_server(
+        &self,
+        tenant_key: &str,
+        label: &str,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>>;
+
+    /// A tenant's servers, ordered by label
+    async fn list_servers(&self, tenant_key: &str) -> TenantMcpServerResult<Vec<TenantMcpServer>>;
+
+    /// Remove a tenant's server, returning it if it existed
+    async fn delete_server(
+        &self,
+        tenant_key: &str,
+        label: &str,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>>;
+}
+
+// ============================================================================
+// PART 10: Prompt Storage
+// ============================================================================
+
+/// One message of a prompt template, e.g. a few-shot example.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptMessage {
+    pub role: String,
+    /// Template text; `{{name}}` placeholders are filled from the request's
+    /// prompt variables
+    pub content: String,
+}
+
+/// One version of a tenant's named prompt template.
+///
+/// Versions are numbered from 1 in publish order and never change once
+/// stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredPrompt {
+    pub tenant_key: String,
+    pub id: String,
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Template for the request's instructions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Templates for messages placed before the request's input
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<PromptMessage>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Error type for prompt storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum PromptStorageError {
+    #[error("Tenant already has {0} prompts")]
+    TooManyPrompts(usize),
+
+    #[error("Prompt already has {0} versions")]
+    TooManyVersions(usize),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+pub type PromptResult<T> = Result<T, PromptStorageError>;
+
+/// Trait for tenant-scoped, versioned prompt templates
+#[async_trait]
+pub trait PromptStorage: Send + Sync + 'static {
+    /// Store `prompt` as the next version of its tenant and id, ignoring
+    /// `prompt.version`. Returns the stored prompt.
+    async fn publish_version(&self, prompt: StoredPrompt) -> PromptResult<StoredPrompt>;
+
+    /// Get a version of a tenant's prompt; the latest when `version` is `None`
+    async fn get_prompt(
+        &self,
+        tenant_key: &str,
+        id: &str,
+        version: Option<u32>,
+    ) -> PromptResult<Option<StoredPrompt>>;
+
+    /// The latest version of each of a tenant's prompts, ordered by id
+    async fn list_prompts(&self, tenant_key: &str) -> PromptResult<Vec<StoredPrompt>>;
+
+    /// All versions of a tenant's prompt, oldest first
+    async fn list_versions(&self, tenant_key: &str, id: &str) -> PromptResult<Vec<StoredPrompt>>;
+
+    /// Remove every version of a tenant's prompt, returning how many existed
+    async fn delete_prompt(&self, tenant_key: &str, id: &str) -> PromptResult<usize>;
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
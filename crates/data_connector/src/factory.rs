@@ -7,18 +7,22 @@ use url::Url;
 
 use crate::{
     config::{HistoryBackend, OracleConfig, PostgresConfig, RedisConfig},
-    core::{ConversationItemStorage, ConversationStorage, ResponseStorage},
+    core::{ConversationItemStorage, ConversationStorage, GenerationJobStorage, ResponseStorage},
     hooked::{HookedConversationItemStorage, HookedConversationStorage, HookedResponseStorage},
     hooks::StorageHook,
     memory::{MemoryConversationItemStorage, MemoryConversationStorage, MemoryResponseStorage},
     noop::{NoOpConversationItemStorage, NoOpConversationStorage, NoOpResponseStorage},
-    oracle::{OracleConversationItemStorage, OracleConversationStorage, OracleResponseStorage},
+    oracle::{
+        OracleConversationItemStorage, OracleConversationStorage, OracleGenerationJobStorage,
+        OracleResponseStorage,
+    },
     postgres::{
-        PostgresConversationItemStorage, PostgresConversationStorage, PostgresResponseStorage,
-        PostgresStore,
+        PostgresConversationItemStorage, PostgresConversationStorage, PostgresGenerationJobStorage,
+        PostgresResponseStorage, PostgresStore,
     },
     redis::{
-        RedisConversationItemStorage, RedisConversationStorage, RedisResponseStorage, RedisStore,
+        RedisConversationItemStorage, RedisConversationStorage, RedisGenerationJobStorage,
+        RedisResponseStorage, RedisStore,
     },
 };
 
@@ -27,6 +31,9 @@ pub struct StorageBundle {
     pub response_storage: Arc<dyn ResponseStorage>,
     pub conversation_storage: Arc<dyn ConversationStorage>,
     pub conversation_item_storage: Arc<dyn ConversationItemStorage>,
+    /// Durable generation job storage; `None` for the memory and none
+    /// backends, where callers keep jobs in a bounded in-memory store.
+    pub generation_job_storage: Option<Arc<dyn GenerationJobStorage>>,
 }
 
 /// Configuration for creating storage backends
@@ -53,6 +60,7 @@ pub async fn create_storage(config: StorageFactoryConfig<'_>) -> Result<StorageB
                 response_storage: Arc::new(MemoryResponseStorage::new()),
                 conversation_storage: Arc::new(MemoryConversationStorage::new()),
                 conversation_item_storage: Arc::new(MemoryConversationItemStorage::new()),
+                generation_job_storage: None,
             }
         }
         HistoryBackend::None => {
@@ -61,6 +69,7 @@ pub async fn create_storage(config: StorageFactoryConfig<'_>) -> Result<StorageB
                 response_storage: Arc::new(NoOpResponseStorage::new()),
                 conversation_storage: Arc::new(NoOpConversationStorage::new()),
                 conversation_item_storage: Arc::new(NoOpConversationItemStorage::new()),
+                generation_job_storage: None,
             }
         }
         HistoryBackend::Oracle => {
@@ -146,6 +155,7 @@ pub async fn create_storage(config: StorageFactoryConfig<'_>) -> Result<StorageB
                 bundle.conversation_item_storage,
                 hook,
             )),
+            generation_job_storage: bundle.generation_job_storage,
         })
     } else {
         Ok(bundle)
@@ -162,13 +172,15 @@ fn create_oracle_storage(oracle_cfg: &OracleConfig) -> Result<StorageBundle, Str
             OracleConversationStorage::init_schema,
             OracleConversationItemStorage::init_schema,
             OracleResponseStorage::init_schema,
+            OracleGenerationJobStorage::init_schema,
         ],
     )?;
 
     Ok(StorageBundle {
         response_storage: Arc::new(OracleResponseStorage::new(store.clone())),
         conversation_storage: Arc::new(OracleConversationStorage::new(store.clone())),
-        conversation_item_storage: Arc::new(OracleConversationItemStorage::new(store)),
+        conversation_item_storage: Arc::new(OracleConversationItemStorage::new(store.clone())),
+        generation_job_storage: Some(Arc::new(OracleGenerationJobStorage::new(store))),
     })
 }
 
@@ -183,6 +195,9 @@ async fn create_postgres_storage(postgres_cfg: &PostgresConfig) -> Result<Storag
     let postgres_item = PostgresConversationItemStorage::new(store.clone())
         .await
         .map_err(|err| format!("failed to initialize Postgres conversation item storage: {err}"))?;
+    let postgres_jobs = PostgresGenerationJobStorage::new(store.clone())
+        .await
+        .map_err(|err| format!("failed to initialize Postgres generation job storage: {err}"))?;
 
     // Run versioned migrations after all tables are created
     let applied = store.run_migrations().await?;
@@ -197,6 +212,7 @@ async fn create_postgres_storage(postgres_cfg: &PostgresConfig) -> Result<Storag
         response_storage: Arc::new(postgres_resp),
         conversation_storage: Arc::new(postgres_conv),
         conversation_item_storage: Arc::new(postgres_item),
+        generation_job_storage: Some(Arc::new(postgres_jobs)),
     })
 }
 
@@ -204,12 +220,14 @@ fn create_redis_storage(redis_cfg: &RedisConfig) -> Result<StorageBundle, String
     let store = RedisStore::new(redis_cfg.clone())?;
     let redis_resp = RedisResponseStorage::new(store.clone());
     let redis_conv = RedisConversationStorage::new(store.clone());
-    let redis_item = RedisConversationItemStorage::new(store);
+    let redis_item = RedisConversationItemStorage::new(store.clone());
+    let redis_jobs = RedisGenerationJobStorage::new(store);
 
     Ok(StorageBundle {
         response_storage: Arc::new(redis_resp),
         conversation_storage: Arc::new(redis_conv),
         conversation_item_storage: Arc::new(redis_item),
+        generation_job_storage: Some(Arc::new(redis_jobs)),
     })
 }
 
@@ -230,6 +248,7 @@ mod tests {
             hook: None,
         };
         let bundle = create_storage(config).await.unwrap();
+        assert!(bundle.generation_job_storage.is_none());
         let (resp, conv, items) = (
             bundle.response_storage,
             bundle.conversation_storage,
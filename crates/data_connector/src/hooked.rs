@@ -483,6 +483,39 @@ impl ResponseStorage for HookedResponseStorage {
         Ok(result)
     }
 
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: serde_json::Value,
+    ) -> ResponseResult<bool> {
+        let payload =
+            serde_json::json!({ "response_id": response_id, "raw_response": &raw_response });
+        let extra = run_before(
+            &*self.hook,
+            StorageOperation::UpdateResponse,
+            &payload,
+            ResponseStorageError::StorageError,
+        )
+        .await?;
+
+        let result = with_extra_columns(
+            extra.clone(),
+            self.inner.update_raw_response(response_id, raw_response),
+        )
+        .await?;
+
+        run_after(
+            &*self.hook,
+            StorageOperation::UpdateResponse,
+            &payload,
+            &serde_json::Value::Bool(result),
+            &extra,
+        )
+        .await;
+
+        Ok(result)
+    }
+
     async fn delete_response(&self, response_id: &ResponseId) -> ResponseResult<()> {
         let payload = serde_json::to_value(response_id).unwrap_or_default();
         let extra = run_before(
@@ -1035,6 +1068,14 @@ mod tests {
                 self.inner.get_response(id).await
             }
 
+            async fn update_raw_response(
+                &self,
+                id: &ResponseId,
+                raw_response: serde_json::Value,
+            ) -> Result<bool, ResponseStorageError> {
+                self.inner.update_raw_response(id, raw_response).await
+            }
+
             async fn delete_response(&self, id: &ResponseId) -> Result<(), ResponseStorageError> {
                 self.inner.delete_response(id).await
             }
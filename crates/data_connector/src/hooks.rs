@@ -51,6 +51,7 @@ pub enum StorageOperation {
     // ── ResponseStorage ──────────────────────────────────────────────────
     StoreResponse,
     GetResponse,
+    UpdateResponse,
     DeleteResponse,
     GetResponseChain,
     ListIdentifierResponses,
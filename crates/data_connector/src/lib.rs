@@ -4,6 +4,10 @@
 //! - Conversations
 //! - Conversation items
 //! - Responses
+//! - Debug captures (sampled request/response pairs, memory only)
+//! - Tenant MCP server registrations (memory only)
+//! - Versioned prompt templates (memory only)
+//! - Asynchronous generation jobs
 //!
 //! Supported backends:
 //! - Memory (default)
@@ -32,9 +36,16 @@ pub(crate) mod versioning;
 // Re-export config types
 // Re-export core types and traits
 pub use core::{
-    Conversation, ConversationId, ConversationItem, ConversationItemId, ConversationItemStorage,
-    ConversationStorage, ListParams, NewConversation, NewConversationItem, ResponseId,
-    ResponseStorage, ResponseStorageError, SortOrder, StoredResponse,
+    ChatCompletionFilter, ChatCompletionStorage, ChatCompletionStorageError, Conversation,
+    ConversationId, ConversationItem, ConversationItemId, ConversationItemStorage,
+    ConversationStorage, DebugCapture, DebugCaptureFilter, DebugCaptureId, DebugCaptureStorage,
+    DebugCaptureStorageError, FileId, FileStorage, FileStorageError, GenerationJob,
+    GenerationJobStatus, GenerationJobStorage, GenerationJobStorageError, ListParams,
+    NewConversation, NewConversationItem, PromptMessage, PromptStorage, PromptStorageError,
+    ResponseId, ResponseStorage, ResponseStorageError, SortOrder, StoredChatCompletion, StoredFile,
+    StoredPrompt, StoredResponse, TenantMcpServer, TenantMcpServerStorage,
+    TenantMcpServerStorageError, VectorSearchHit, VectorStoreChunk, VectorStoreStorage,
+    VectorStoreStorageError,
 };
 
 pub use config::{HistoryBackend, OracleConfig, PostgresConfig, RedisConfig};
@@ -47,6 +58,13 @@ pub use context::{
 pub use factory::{create_storage, StorageBundle, StorageFactoryConfig};
 pub use hooks::{BeforeHookResult, ExtraColumns, HookError, StorageHook, StorageOperation};
 // Re-export memory implementations for testing
-pub use memory::{MemoryConversationItemStorage, MemoryConversationStorage, MemoryResponseStorage};
+pub use memory::{
+    MemoryChatCompletionStorage, MemoryConversationItemStorage, MemoryConversationStorage,
+    MemoryDebugCaptureStorage, MemoryFileStorage, MemoryGenerationJobStorage, MemoryPromptStorage,
+    MemoryResponseStorage, MemoryTenantMcpServerStorage, MemoryVectorStoreStorage,
+    DEFAULT_CHAT_COMPLETION_STORE_CAPACITY, DEFAULT_DEBUG_CAPTURE_CAPACITY,
+    DEFAULT_FILE_STORE_CAPACITY, DEFAULT_PROMPT_LIMIT, DEFAULT_PROMPT_VERSION_LIMIT,
+    DEFAULT_TENANT_MCP_SERVER_LIMIT, DEFAULT_VECTOR_STORE_CHUNK_CAPACITY,
+};
 // Re-export schema config types
 pub use schema::{ColumnDef, SchemaConfig, TableConfig};
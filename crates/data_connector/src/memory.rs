@@ -3,7 +3,7 @@
 //! Used for development and testing - no persistence.
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     sync::Arc,
 };
 
@@ -359,6 +359,19 @@ impl ResponseStorage for MemoryResponseStorage {
         Ok(result)
     }
 
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: serde_json::Value,
+    ) -> ResponseResult<bool> {
+        let mut store = self.store.write();
+        let Some(response) = store.responses.get_mut(response_id) else {
+            return Ok(false);
+        };
+        response.raw_response = raw_response;
+        Ok(true)
+    }
+
     async fn delete_response(&self, response_id: &ResponseId) -> ResponseResult<()> {
         let mut store = self.store.write();
 
@@ -461,6 +474,592 @@ impl ResponseStorage for MemoryResponseStorage {
     }
 }
 
+// ============================================================================
+// PART 4: MemoryDebugCaptureStorage
+// ============================================================================
+
+/// Default number of captures retained by [`MemoryDebugCaptureStorage`]
+pub const DEFAULT_DEBUG_CAPTURE_CAPACITY: usize = 1000;
+
+/// Bounded in-memory debug capture storage.
+///
+/// Keeps at most `capacity` captures; the oldest entry is evicted on insert
+/// once the buffer is full.
+#[derive(Clone)]
+pub struct MemoryDebugCaptureStorage {
+    inner: Arc<RwLock<VecDeque<DebugCapture>>>,
+    capacity: usize,
+}
+
+impl MemoryDebugCaptureStorage {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new(RwLock::new(VecDeque::with_capacity(capacity.min(1024)))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Default for MemoryDebugCaptureStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEBUG_CAPTURE_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl DebugCaptureStorage for MemoryDebugCaptureStorage {
+    async fn store_capture(&self, capture: DebugCapture) -> DebugCaptureResult<DebugCaptureId> {
+        let id = capture.id.clone();
+        let mut inner = self.inner.write();
+        while inner.len() >= self.capacity {
+            inner.pop_front();
+        }
+        inner.push_back(capture);
+        Ok(id)
+    }
+
+    async fn get_capture(&self, id: &DebugCaptureId) -> DebugCaptureResult<Option<DebugCapture>> {
+        let inner = self.inner.read();
+        Ok(inner.iter().rev().find(|c| &c.id == id).cloned())
+    }
+
+    async fn list_captures(
+        &self,
+        filter: &DebugCaptureFilter,
+    ) -> DebugCaptureResult<Vec<DebugCapture>> {
+        let inner = self.inner.read();
+        let limit = filter.limit.unwrap_or(usize::MAX);
+        Ok(inner
+            .iter()
+            .rev()
+            .filter(|c| filter.matches(c))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn clear_captures(&self) -> DebugCaptureResult<usize> {
+        let mut inner = self.inner.write();
+        let count = inner.len();
+        inner.clear();
+        Ok(count)
+    }
+}
+
+// ============================================================================
+// PART 5: MemoryFileStorage
+// ============================================================================
+
+/// Default number of files retained by [`MemoryFileStorage`]
+pub const DEFAULT_FILE_STORE_CAPACITY: usize = 1000;
+
+/// Bounded in-memory file storage.
+///
+/// Keeps at most `capacity` files; the oldest file is evicted on insert once
+/// the store is full.
+#[derive(Clone)]
+pub struct MemoryFileStorage {
+    inner: Arc<RwLock<VecDeque<StoredFile>>>,
+    capacity: usize,
+}
+
+impl MemoryFileStorage {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new(RwLock::new(VecDeque::with_capacity(capacity.min(1024)))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Default for MemoryFileStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_FILE_STORE_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl FileStorage for MemoryFileStorage {
+    async fn store_file(&self, file: StoredFile) -> FileResult<FileId> {
+        let id = file.id.clone();
+        let mut inner = self.inner.write();
+        while inner.len() >= self.capacity {
+            inner.pop_front();
+        }
+        inner.push_back(file);
+        Ok(id)
+    }
+
+    async fn get_file(&self, id: &FileId) -> FileResult<Option<StoredFile>> {
+        let inner = self.inner.read();
+        Ok(inner.iter().find(|f| &f.id == id).cloned())
+    }
+
+    async fn delete_file(&self, id: &FileId) -> FileResult<bool> {
+        let mut inner = self.inner.write();
+        let before = inner.len();
+        inner.retain(|f| &f.id != id);
+        Ok(inner.len() != before)
+    }
+}
+
+// ============================================================================
+// PART 6: MemoryChatCompletionStorage
+// ============================================================================
+
+/// Default number of completions retained by [`MemoryChatCompletionStorage`]
+pub const DEFAULT_CHAT_COMPLETION_STORE_CAPACITY: usize = 10_000;
+
+/// Bounded in-memory chat completion storage.
+///
+/// Keeps at most `capacity` completions in insertion order; the oldest is
+/// evicted on insert once the store is full.
+#[derive(Clone)]
+pub struct MemoryChatCompletionStorage {
+    inner: Arc<RwLock<VecDeque<StoredChatCompletion>>>,
+    capacity: usize,
+}
+
+impl MemoryChatCompletionStorage {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new(RwLock::new(VecDeque::with_capacity(capacity.min(1024)))),
+            capacity,
+        }
+    }
+}
+
+impl Default for MemoryChatCompletionStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHAT_COMPLETION_STORE_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl ChatCompletionStorage for MemoryChatCompletionStorage {
+    async fn store_completion(&self, completion: StoredChatCompletion) -> ChatCompletionResult<()> {
+        let mut inner = self.inner.write();
+        inner.retain(|c| c.id != completion.id);
+        while inner.len() >= self.capacity {
+            inner.pop_front();
+        }
+        inner.push_back(completion);
+        Ok(())
+    }
+
+    async fn get_completion(&self, id: &str) -> ChatCompletionResult<Option<StoredChatCompletion>> {
+        let inner = self.inner.read();
+        Ok(inner.iter().find(|c| c.id == id).cloned())
+    }
+
+    async fn list_completions(
+        &self,
+        filter: &ChatCompletionFilter,
+    ) -> ChatCompletionResult<Vec<StoredChatCompletion>> {
+        let inner = self.inner.read();
+        let ordered: Box<dyn Iterator<Item = &StoredChatCompletion>> = match filter.order {
+            SortOrder::Asc => Box::new(inner.iter()),
+            SortOrder::Desc => Box::new(inner.iter().rev()),
+        };
+        let mut ordered = ordered.filter(|c| filter.matches(c));
+        if let Some(after) = &filter.after {
+            // Skip through the cursor; an unknown cursor yields an empty page.
+            for c in ordered.by_ref() {
+                if &c.id == after {
+                    break;
+                }
+            }
+        }
+        Ok(ordered.take(filter.limit).cloned().collect())
+    }
+
+    async fn delete_completion(&self, id: &str) -> ChatCompletionResult<bool> {
+        let mut inner = self.inner.write();
+        let before = inner.len();
+        inner.retain(|c| c.id != id);
+        Ok(inner.len() != before)
+    }
+}
+
+// ============================================================================
+// PART 7: MemoryGenerationJobStorage
+// ============================================================================
+
+/// Bounded in-memory generation job storage.
+///
+/// Holds at most `capacity` jobs. When full, the oldest finished job is
+/// evicted; unfinished jobs are never evicted, so callers must bound the
+/// number of queued and running jobs themselves.
+#[derive(Clone)]
+pub struct MemoryGenerationJobStorage {
+    inner: Arc<RwLock<VecDeque<GenerationJob>>>,
+    capacity: usize,
+}
+
+impl MemoryGenerationJobStorage {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(VecDeque::new())),
+            capacity: capacity.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl GenerationJobStorage for MemoryGenerationJobStorage {
+    async fn put_job(&self, job: GenerationJob) -> GenerationJobResult<()> {
+        let mut inner = self.inner.write();
+        if let Some(existing) = inner.iter_mut().find(|j| j.id == job.id) {
+            *existing = job;
+            return Ok(());
+        }
+        while inner.len() >= self.capacity {
+            let Some(finished) = inner.iter().position(|j| j.status.is_terminal()) else {
+                break;
+            };
+            inner.remove(finished);
+        }
+        inner.push_back(job);
+        Ok(())
+    }
+
+    async fn get_job(&self, id: &str) -> GenerationJobResult<Option<GenerationJob>> {
+        let inner = self.inner.read();
+        Ok(inner.iter().find(|j| j.id == id).cloned())
+    }
+
+    async fn list_unfinished_jobs(&self) -> GenerationJobResult<Vec<GenerationJob>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .iter()
+            .filter(|j| !j.status.is_terminal())
+            .cloned()
+            .collect())
+    }
+}
+
+// ============================================================================
+// PART 8: MemoryVectorStoreStorage
+// ============================================================================
+
+/// Default number of chunks retained per vector store by [`MemoryVectorStoreStorage`]
+pub const DEFAULT_VECTOR_STORE_CHUNK_CAPACITY: usize = 100_000;
+
+/// Chunks keyed by vector store id, then by `(file_id, chunk_index)`.
+type StoreChunks = HashMap<String, BTreeMap<(String, usize), VectorStoreChunk>>;
+
+/// Bounded in-memory vector store with brute-force cosine search.
+///
+/// Each vector store holds at most `max_chunks` chunks; upserts beyond that
+/// fail instead of evicting. All chunks of a store must share one embedding
+/// dimension, fixed by the first chunk.
+#[derive(Clone)]
+pub struct MemoryVectorStoreStorage {
+    inner: Arc<RwLock<StoreChunks>>,
+    max_chunks: usize,
+}
+
+impl MemoryVectorStoreStorage {
+    pub fn new(max_chunks: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            max_chunks: max_chunks.max(1),
+        }
+    }
+}
+
+impl Default for MemoryVectorStoreStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_VECTOR_STORE_CHUNK_CAPACITY)
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+#[async_trait]
+impl VectorStoreStorage for MemoryVectorStoreStorage {
+    async fn upsert_chunks(&self, chunks: Vec<VectorStoreChunk>) -> VectorStoreResult<()> {
+        let mut inner = self.inner.write();
+        for chunk in chunks {
+            let store = inner.entry(chunk.vector_store_id.clone()).or_default();
+            if let Some(existing) = store.values().next() {
+                if existing.embedding.len() != chunk.embedding.len() {
+                    return Err(VectorStoreStorageError::DimensionMismatch {
+                        expected: existing.embedding.len(),
+                        actual: chunk.embedding.len(),
+                    });
+                }
+            }
+            let key = (chunk.file_id.0.clone(), chunk.index);
+            if !store.contains_key(&key) && store.len() >= self.max_chunks {
+                return Err(VectorStoreStorageError::CapacityExceeded(
+                    chunk.vector_store_id,
+                ));
+            }
+            store.insert(key, chunk);
+        }
+        Ok(())
+    }
+
+    async fn delete_file_chunks(
+        &self,
+        vector_store_id: &str,
+        file_id: &FileId,
+    ) -> VectorStoreResult<usize> {
+        let mut inner = self.inner.write();
+        let Some(store) = inner.get_mut(vector_store_id) else {
+            return Ok(0);
+        };
+        let before = store.len();
+        store.retain(|(id, _), _| *id != file_id.0);
+        let removed = before - store.len();
+        if store.is_empty() {
+            inner.remove(vector_store_id);
+        }
+        Ok(removed)
+    }
+
+    async fn search(
+        &self,
+        vector_store_id: &str,
+        embedding: &[f32],
+        limit: usize,
+    ) -> VectorStoreResult<Vec<VectorSearchHit>> {
+        let inner = self.inner.read();
+        let Some(store) = inner.get(vector_store_id) else {
+            return Ok(Vec::new());
+        };
+        let mut hits: Vec<VectorSearchHit> = store
+            .values()
+            .map(|chunk| VectorSearchHit {
+                score: cosine_similarity(&chunk.embedding, embedding),
+                chunk: chunk.clone(),
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+}
+
+// ============================================================================
+// PART 9: MemoryTenantMcpServerStorage
+// ============================================================================
+
+/// Default number of MCP servers a tenant may register in [`MemoryTenantMcpServerStorage`]
+pub const DEFAULT_TENANT_MCP_SERVER_LIMIT: usize = 32;
+
+/// In-memory tenant MCP server registry.
+///
+/// Each tenant holds at most `max_per_tenant` servers; registering another
+/// label beyond that fails, replacing an existing one does not.
+#[derive(Clone)]
+pub struct MemoryTenantMcpServerStorage {
+    inner: Arc<RwLock<HashMap<String, BTreeMap<String, TenantMcpServer>>>>,
+    max_per_tenant: usize,
+}
+
+impl MemoryTenantMcpServerStorage {
+    pub fn new(max_per_tenant: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            max_per_tenant: max_per_tenant.max(1),
+        }
+    }
+}
+
+impl Default for MemoryTenantMcpServerStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_TENANT_MCP_SERVER_LIMIT)
+    }
+}
+
+#[async_trait]
+impl TenantMcpServerStorage for MemoryTenantMcpServerStorage {
+    async fn put_server(
+        &self,
+        server: TenantMcpServer,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>> {
+        let mut inner = self.inner.write();
+        let servers = inner.entry(server.tenant_key.clone()).or_default();
+        if !servers.contains_key(&server.label) && servers.len() >= self.max_per_tenant {
+            return Err(TenantMcpServerStorageError::LimitExceeded(
+                self.max_per_tenant,
+            ));
+        }
+        Ok(servers.insert(server.label.clone(), server))
+    }
+
+    async fn get_server(
+        &self,
+        tenant_key: &str,
+        label: &str,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .get(tenant_key)
+            .and_then(|servers| servers.get(label))
+            .cloned())
+    }
+
+    async fn list_servers(&self, tenant_key: &str) -> TenantMcpServerResult<Vec<TenantMcpServer>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .get(tenant_key)
+            .map(|servers| servers.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn delete_server(
+        &self,
+        tenant_key: &str,
+        label: &str,
+    ) -> TenantMcpServerResult<Option<TenantMcpServer>> {
+        let mut inner = self.inner.write();
+        let Some(servers) = inner.get_mut(tenant_key) else {
+            return Ok(None);
+        };
+        let removed = servers.remove(label);
+        if servers.is_empty() {
+            inner.remove(tenant_key);
+        }
+        Ok(removed)
+    }
+}
+
+// ============================================================================
+// PART 10: MemoryPromptStorage
+// ============================================================================
+
+/// Default number of prompts a tenant may keep in [`MemoryPromptStorage`]
+pub const DEFAULT_PROMPT_LIMIT: usize = 128;
+
+/// Default number of versions one prompt may have in [`MemoryPromptStorage`]
+pub const DEFAULT_PROMPT_VERSION_LIMIT: usize = 100;
+
+/// Prompt versions keyed by tenant, then by prompt id.
+type TenantPrompts = HashMap<String, BTreeMap<String, Vec<StoredPrompt>>>;
+
+/// In-memory prompt library.
+///
+/// Each tenant keeps at most `max_per_tenant` prompts of at most
+/// `max_versions` versions each; publishing beyond either limit fails.
+#[derive(Clone)]
+pub struct MemoryPromptStorage {
+    inner: Arc<RwLock<TenantPrompts>>,
+    max_per_tenant: usize,
+    max_versions: usize,
+}
+
+impl MemoryPromptStorage {
+    pub fn new(max_per_tenant: usize, max_versions: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            max_per_tenant: max_per_tenant.max(1),
+            max_versions: max_versions.max(1),
+        }
+    }
+}
+
+impl Default for MemoryPromptStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROMPT_LIMIT, DEFAULT_PROMPT_VERSION_LIMIT)
+    }
+}
+
+#[async_trait]
+impl PromptStorage for MemoryPromptStorage {
+    async fn publish_version(&self, mut prompt: StoredPrompt) -> PromptResult<StoredPrompt> {
+        let mut inner = self.inner.write();
+        let prompts = inner.entry(prompt.tenant_key.clone()).or_default();
+        if !prompts.contains_key(&prompt.id) && prompts.len() >= self.max_per_tenant {
+            return Err(PromptStorageError::TooManyPrompts(self.max_per_tenant));
+        }
+        let versions = prompts.entry(prompt.id.clone()).or_default();
+        if versions.len() >= self.max_versions {
+            return Err(PromptStorageError::TooManyVersions(self.max_versions));
+        }
+        prompt.version = versions.len() as u32 + 1;
+        versions.push(prompt.clone());
+        Ok(prompt)
+    }
+
+    async fn get_prompt(
+        &self,
+        tenant_key: &str,
+        id: &str,
+        version: Option<u32>,
+    ) -> PromptResult<Option<StoredPrompt>> {
+        let inner = self.inner.read();
+        let Some(versions) = inner.get(tenant_key).and_then(|prompts| prompts.get(id)) else {
+            return Ok(None);
+        };
+        Ok(match version {
+            Some(version) => version
+                .checked_sub(1)
+                .and_then(|i| versions.get(i as usize))
+                .cloned(),
+            None => versions.last().cloned(),
+        })
+    }
+
+    async fn list_prompts(&self, tenant_key: &str) -> PromptResult<Vec<StoredPrompt>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .get(tenant_key)
+            .map(|prompts| {
+                prompts
+                    .values()
+                    .filter_map(|versions| versions.last().cloned())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn list_versions(&self, tenant_key: &str, id: &str) -> PromptResult<Vec<StoredPrompt>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .get(tenant_key)
+            .and_then(|prompts| prompts.get(id))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn delete_prompt(&self, tenant_key: &str, id: &str) -> PromptResult<usize> {
+        let mut inner = self.inner.write();
+        let Some(prompts) = inner.get_mut(tenant_key) else {
+            return Ok(0);
+        };
+        let removed = prompts.remove(id).map_or(0, |versions| versions.len());
+        if prompts.is_empty() {
+            inner.remove(tenant_key);
+        }
+        Ok(removed)
+    }
+}
+
 /// Statistics for the memory store
 #[cfg(test)]
 #[derive(Debug, Clone)]
@@ -990,4 +1589,389 @@ mod tests {
         // But item data itself is still retrievable
         assert!(store.get_item(&item.id).await.unwrap().is_some());
     }
+
+    // ========================================================================
+    // DebugCapture Tests
+    // ========================================================================
+
+    fn make_capture(model: &str, status: u16, latency_ms: u64) -> DebugCapture {
+        DebugCapture {
+            id: DebugCaptureId::new(),
+            created_at: Utc::now(),
+            request_id: None,
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            model: Some(model.to_string()),
+            status,
+            latency_ms,
+            request_body: json!({"model": model}),
+            response_body: serde_json::Value::Null,
+            truncated: false,
+            streaming: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_capture_evicts_oldest_when_full() {
+        let store = MemoryDebugCaptureStorage::new(2);
+        let first = store
+            .store_capture(make_capture("a", 200, 10))
+            .await
+            .unwrap();
+        store
+            .store_capture(make_capture("b", 200, 10))
+            .await
+            .unwrap();
+        store
+            .store_capture(make_capture("c", 200, 10))
+            .await
+            .unwrap();
+
+        assert!(store.get_capture(&first).await.unwrap().is_none());
+        let all = store
+            .list_captures(&DebugCaptureFilter::default())
+            .await
+            .unwrap();
+        let models: Vec<_> = all.iter().filter_map(|c| c.model.as_deref()).collect();
+        assert_eq!(models, vec!["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_debug_capture_filters() {
+        let store = MemoryDebugCaptureStorage::new(10);
+        store
+            .store_capture(make_capture("llama", 200, 50))
+            .await
+            .unwrap();
+        store
+            .store_capture(make_capture("llama", 500, 900))
+            .await
+            .unwrap();
+        store
+            .store_capture(make_capture("qwen", 200, 1200))
+            .await
+            .unwrap();
+
+        let by_model = DebugCaptureFilter {
+            model: Some("llama".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.list_captures(&by_model).await.unwrap().len(), 2);
+
+        let by_status = DebugCaptureFilter {
+            status: Some(500),
+            ..Default::default()
+        };
+        assert_eq!(store.list_captures(&by_status).await.unwrap().len(), 1);
+
+        let slow = DebugCaptureFilter {
+            min_latency_ms: Some(800),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let slow = store.list_captures(&slow).await.unwrap();
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].model.as_deref(), Some("qwen"));
+
+        assert_eq!(store.clear_captures().await.unwrap(), 3);
+        assert!(store
+            .list_captures(&DebugCaptureFilter::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_vector_store_search_ranks_by_similarity() {
+        let store = MemoryVectorStoreStorage::new(3);
+        let file = FileId::from("file-a");
+        let chunk = |index: usize, embedding: Vec<f32>| VectorStoreChunk {
+            vector_store_id: "vs_1".to_string(),
+            file_id: file.clone(),
+            filename: "a.md".to_string(),
+            index,
+            text: format!("chunk {index}"),
+            embedding,
+        };
+        store
+            .upsert_chunks(vec![chunk(0, vec![1.0, 0.0]), chunk(1, vec![0.0, 1.0])])
+            .await
+            .unwrap();
+        // Re-ingesting an index replaces it instead of counting against capacity.
+        store
+            .upsert_chunks(vec![chunk(1, vec![0.6, 0.8]), chunk(2, vec![-1.0, 0.0])])
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.upsert_chunks(vec![chunk(3, vec![1.0, 1.0])]).await,
+            Err(VectorStoreStorageError::CapacityExceeded(_))
+        ));
+        assert!(matches!(
+            store.upsert_chunks(vec![chunk(0, vec![1.0])]).await,
+            Err(VectorStoreStorageError::DimensionMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+
+        let hits = store.search("vs_1", &[1.0, 0.0], 2).await.unwrap();
+        let indexes: Vec<usize> = hits.iter().map(|h| h.chunk.index).collect();
+        assert_eq!(indexes, vec![0, 1]);
+        assert!((hits[1].score - 0.6).abs() < 1e-6);
+        assert!(store
+            .search("vs_2", &[1.0, 0.0], 2)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(store.delete_file_chunks("vs_1", &file).await.unwrap(), 3);
+        assert!(store
+            .search("vs_1", &[1.0, 0.0], 2)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_evicts_oldest_and_deletes() {
+        let store = MemoryFileStorage::new(2);
+        let make = |name: &str| StoredFile::new(name, "image_generation", "image/png", vec![1]);
+
+        let first = store.store_file(make("a.png")).await.unwrap();
+        let second = store.store_file(make("b.png")).await.unwrap();
+        let third = store.store_file(make("c.png")).await.unwrap();
+
+        assert!(store.get_file(&first).await.unwrap().is_none());
+        let file = store.get_file(&third).await.unwrap().unwrap();
+        assert_eq!(file.filename, "c.png");
+        assert_eq!(file.bytes, vec![1]);
+
+        assert!(store.delete_file(&second).await.unwrap());
+        assert!(!store.delete_file(&second).await.unwrap());
+        assert!(store.get_file(&second).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_storage_filters_and_paginates() {
+        let store = MemoryChatCompletionStorage::new(3);
+        let make = |id: &str, model: &str, suite: &str| StoredChatCompletion {
+            id: id.to_string(),
+            created_at: Utc::now(),
+            model: model.to_string(),
+            metadata: HashMap::from([("suite".to_string(), suite.to_string())]),
+            request: json!(null),
+            response: json!(null),
+            tenant_key: Some("t1".to_string()),
+        };
+        for (id, model, suite) in [
+            ("c0", "m1", "a"),
+            ("c1", "m1", "a"),
+            ("c2", "m2", "a"),
+            ("c3", "m1", "b"),
+        ] {
+            store
+                .store_completion(make(id, model, suite))
+                .await
+                .unwrap();
+        }
+        // Capacity 3 evicted the oldest.
+        assert!(store.get_completion("c0").await.unwrap().is_none());
+
+        let ids = |completions: Vec<StoredChatCompletion>| {
+            completions.into_iter().map(|c| c.id).collect::<Vec<_>>()
+        };
+        let mut filter = ChatCompletionFilter {
+            limit: 10,
+            order: SortOrder::Asc,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(store.list_completions(&filter).await.unwrap()),
+            ["c1", "c2", "c3"]
+        );
+
+        filter.metadata = HashMap::from([("suite".to_string(), "a".to_string())]);
+        filter.order = SortOrder::Desc;
+        assert_eq!(
+            ids(store.list_completions(&filter).await.unwrap()),
+            ["c2", "c1"]
+        );
+
+        filter.after = Some("c2".to_string());
+        assert_eq!(ids(store.list_completions(&filter).await.unwrap()), ["c1"]);
+
+        filter = ChatCompletionFilter {
+            model: Some("m1".to_string()),
+            tenant_key: Some("t2".to_string()),
+            limit: 10,
+            order: SortOrder::Asc,
+            ..Default::default()
+        };
+        assert!(store.list_completions(&filter).await.unwrap().is_empty());
+
+        assert!(store.delete_completion("c1").await.unwrap());
+        assert!(!store.delete_completion("c1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_generation_jobs_evict_only_finished() {
+        let store = MemoryGenerationJobStorage::new(2);
+        let make = |id: &str, status| GenerationJob {
+            id: id.to_string(),
+            tenant_key: "t1".to_string(),
+            endpoint: "/v1/chat/completions".to_string(),
+            model: "m".to_string(),
+            request: json!({}),
+            status,
+            created_at: Utc::now(),
+            started_at: None,
+            completed_at: None,
+            result: None,
+            error: None,
+            webhook_url: None,
+        };
+        store
+            .put_job(make("g1", GenerationJobStatus::Succeeded))
+            .await
+            .unwrap();
+        store
+            .put_job(make("g2", GenerationJobStatus::Queued))
+            .await
+            .unwrap();
+        store
+            .put_job(make("g3", GenerationJobStatus::Queued))
+            .await
+            .unwrap();
+        assert!(store.get_job("g1").await.unwrap().is_none());
+
+        // Nothing finished to evict: the store grows rather than drop work.
+        store
+            .put_job(make("g4", GenerationJobStatus::Queued))
+            .await
+            .unwrap();
+        assert_eq!(store.list_unfinished_jobs().await.unwrap().len(), 3);
+
+        store
+            .put_job(make("g2", GenerationJobStatus::Cancelled))
+            .await
+            .unwrap();
+        let unfinished = store.list_unfinished_jobs().await.unwrap();
+        let ids: Vec<_> = unfinished.iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(ids, ["g3", "g4"]);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_mcp_servers_are_scoped_and_bounded() {
+        let store = MemoryTenantMcpServerStorage::new(2);
+        let make = |tenant: &str, label: &str| TenantMcpServer {
+            tenant_key: tenant.to_string(),
+            label: label.to_string(),
+            server_url: format!("https://{label}.example/mcp"),
+            authorization: None,
+            headers: HashMap::new(),
+            allowed_tools: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert!(store.put_server(make("t1", "b")).await.unwrap().is_none());
+        assert!(store.put_server(make("t1", "a")).await.unwrap().is_none());
+        assert!(matches!(
+            store.put_server(make("t1", "c")).await,
+            Err(TenantMcpServerStorageError::LimitExceeded(2))
+        ));
+        // Replacing an existing label is allowed at the limit.
+        assert!(store.put_server(make("t1", "a")).await.unwrap().is_some());
+        store.put_server(make("t2", "a")).await.unwrap();
+
+        let labels: Vec<_> = store
+            .list_servers("t1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.label)
+            .collect();
+        assert_eq!(labels, ["a", "b"]);
+        assert!(store.get_server("t2", "b").await.unwrap().is_none());
+
+        assert!(store.delete_server("t1", "a").await.unwrap().is_some());
+        assert!(store.delete_server("t1", "a").await.unwrap().is_none());
+        assert_eq!(store.list_servers("t2").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prompts_are_versioned_scoped_and_bounded() {
+        let store = MemoryPromptStorage::new(2, 2);
+        let make = |tenant: &str, id: &str, instructions: &str| StoredPrompt {
+            tenant_key: tenant.to_string(),
+            id: id.to_string(),
+            version: 0,
+            description: None,
+            instructions: Some(instructions.to_string()),
+            messages: Vec::new(),
+            created_at: Utc::now(),
+        };
+        let v1 = store
+            .publish_version(make("t1", "greet", "Hi"))
+            .await
+            .unwrap();
+        let v2 = store
+            .publish_version(make("t1", "greet", "Hello"))
+            .await
+            .unwrap();
+        assert_eq!((v1.version, v2.version), (1, 2));
+        assert!(matches!(
+            store.publish_version(make("t1", "greet", "Hey")).await,
+            Err(PromptStorageError::TooManyVersions(2))
+        ));
+        store
+            .publish_version(make("t1", "bye", "Bye"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            store.publish_version(make("t1", "third", "x")).await,
+            Err(PromptStorageError::TooManyPrompts(2))
+        ));
+
+        let latest = store
+            .get_prompt("t1", "greet", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.instructions.as_deref(), Some("Hello"));
+        let pinned = store
+            .get_prompt("t1", "greet", Some(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pinned.instructions.as_deref(), Some("Hi"));
+        assert!(store
+            .get_prompt("t1", "greet", Some(0))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .get_prompt("t1", "greet", Some(3))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .get_prompt("t2", "greet", None)
+            .await
+            .unwrap()
+            .is_none());
+
+        let ids: Vec<_> = store
+            .list_prompts("t1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.id, p.version))
+            .collect();
+        assert_eq!(ids, [("bye".to_string(), 1), ("greet".to_string(), 2)]);
+        assert_eq!(store.list_versions("t1", "greet").await.unwrap().len(), 2);
+
+        assert_eq!(store.delete_prompt("t1", "greet").await.unwrap(), 2);
+        assert_eq!(store.delete_prompt("t1", "greet").await.unwrap(), 0);
+        assert!(store.list_versions("t1", "greet").await.unwrap().is_empty());
+    }
 }
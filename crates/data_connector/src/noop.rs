@@ -158,6 +158,14 @@ impl ResponseStorage for NoOpResponseStorage {
         Ok(None)
     }
 
+    async fn update_raw_response(
+        &self,
+        _response_id: &ResponseId,
+        _raw_response: serde_json::Value,
+    ) -> ResponseResult<bool> {
+        Ok(false)
+    }
+
     async fn delete_response(&self, _response_id: &ResponseId) -> ResponseResult<()> {
         Ok(())
     }
@@ -14,7 +14,8 @@ use serde_json::Value;
 use super::core::{
     make_item_id, Conversation, ConversationId, ConversationItem, ConversationItemId,
     ConversationItemStorage, ConversationItemStorageError, ConversationMetadata,
-    ConversationStorage, ConversationStorageError, ListParams, NewConversation,
+    ConversationStorage, ConversationStorageError, GenerationJob, GenerationJobResult,
+    GenerationJobStorage, GenerationJobStorageError, ListParams, NewConversation,
     NewConversationItem, ResponseId, ResponseStorage, ResponseStorageError, SortOrder,
     StoredResponse,
 };
@@ -1173,7 +1174,7 @@ impl OracleResponseStorage {
 
         if exists == 0 {
             let mut col_defs = vec![format!("{} VARCHAR2(64) PRIMARY KEY", s.col("id"))];
-            let core_cols: [(&str, &str); 7] = [
+            let core_cols: [(&str, &str); 8] = [
                 ("conversation_id", "VARCHAR2(64)"),
                 ("previous_response_id", "VARCHAR2(64)"),
                 ("input", "CLOB"),
@@ -1181,6 +1182,7 @@ impl OracleResponseStorage {
                 ("safety_identifier", "VARCHAR2(128)"),
                 ("model", "VARCHAR2(128)"),
                 ("raw_response", "CLOB"),
+                ("tenant_key", "VARCHAR2(256)"),
             ];
             for (logical, sql_type) in &core_cols {
                 if !s.is_skipped(logical) {
@@ -1266,6 +1268,11 @@ impl OracleResponseStorage {
         } else {
             row.get(s.col("raw_response")).map_err(map_oracle_error)?
         };
+        let tenant_key: Option<String> = if s.is_skipped("tenant_key") {
+            None
+        } else {
+            row.get(s.col("tenant_key")).map_err(map_oracle_error)?
+        };
 
         let previous_response_id = previous.map(ResponseId);
         let raw_response = parse_raw_response(raw_response_json)?;
@@ -1280,6 +1287,7 @@ impl OracleResponseStorage {
             model,
             conversation_id,
             raw_response,
+            tenant_key,
         })
     }
 }
@@ -1299,6 +1307,7 @@ impl ResponseStorage for OracleResponseStorage {
             model,
             conversation_id,
             raw_response,
+            tenant_key,
         } = response;
 
         let return_id = id.clone();
@@ -1326,6 +1335,7 @@ impl ResponseStorage for OracleResponseStorage {
                     ("model", &model),
                     ("conversation_id", &conversation_id),
                     ("raw_response", &json_raw_response),
+                    ("tenant_key", &tenant_key),
                 ];
 
                 let mut columns = Vec::new();
@@ -1390,6 +1400,39 @@ impl ResponseStorage for OracleResponseStorage {
             .map_err(ResponseStorageError::StorageError)
     }
 
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: Value,
+    ) -> Result<bool, ResponseStorageError> {
+        let id = response_id.0.clone();
+        let json_raw_response = serde_json::to_string(&raw_response)?;
+        let schema = self.store.schema.clone();
+
+        self.store
+            .execute(move |conn| {
+                let s = &schema.responses;
+                let table = s.qualified_table(schema.owner.as_deref());
+                let col_id = s.col("id");
+
+                if s.is_skipped("raw_response") {
+                    // Nothing to update — just verify the row exists
+                    let sql = format!("SELECT COUNT(*) FROM {table} WHERE {col_id} = :1");
+                    let count: i64 = conn.query_row_as(&sql, &[&id]).map_err(map_oracle_error)?;
+                    return Ok(count > 0);
+                }
+
+                let col_raw = s.col("raw_response");
+                let sql = format!("UPDATE {table} SET {col_raw} = :1 WHERE {col_id} = :2");
+                let stmt = conn
+                    .execute(&sql, &[&json_raw_response, &id])
+                    .map_err(map_oracle_error)?;
+                Ok(stmt.row_count().map_err(map_oracle_error)? > 0)
+            })
+            .await
+            .map_err(ResponseStorageError::StorageError)
+    }
+
     async fn delete_response(&self, response_id: &ResponseId) -> Result<(), ResponseStorageError> {
         let id = response_id.0.clone();
         let schema = self.store.schema.clone();
@@ -1534,3 +1577,133 @@ fn create_index_if_missing(
 
     Ok(())
 }
+
+// ============================================================================
+// PART 5: OracleGenerationJobStorage
+// ============================================================================
+
+const GENERATION_JOBS_TABLE: &str = "GENERATION_JOBS";
+
+/// Generation jobs live in a fixed `GENERATION_JOBS` table: the job is kept
+/// whole in `BODY`, with `STATUS` and `CREATED_AT` broken out so unfinished
+/// jobs can be listed on startup.
+#[derive(Clone)]
+pub(super) struct OracleGenerationJobStorage {
+    store: OracleStore,
+    table: String,
+}
+
+impl OracleGenerationJobStorage {
+    pub fn new(store: OracleStore) -> Self {
+        let table = Self::qualified_table(&store.schema);
+        Self { store, table }
+    }
+
+    fn qualified_table(schema: &SchemaConfig) -> String {
+        match schema.owner.as_deref() {
+            Some(owner) => format!("{owner}.\"{GENERATION_JOBS_TABLE}\""),
+            None => GENERATION_JOBS_TABLE.to_string(),
+        }
+    }
+
+    pub(crate) fn init_schema(conn: &Connection, schema: &SchemaConfig) -> Result<(), String> {
+        let exists: i64 = conn
+            .query_row_as(
+                &format!(
+                    "SELECT COUNT(*) FROM user_tables WHERE table_name = '{GENERATION_JOBS_TABLE}'"
+                ),
+                &[],
+            )
+            .map_err(map_oracle_error)?;
+
+        if exists == 0 {
+            let table = Self::qualified_table(schema);
+            conn.execute(
+                &format!(
+                    "CREATE TABLE {table} (\
+                        ID VARCHAR2(64) PRIMARY KEY, \
+                        STATUS VARCHAR2(16) NOT NULL, \
+                        CREATED_AT TIMESTAMP WITH TIME ZONE NOT NULL, \
+                        BODY CLOB NOT NULL)"
+                ),
+                &[],
+            )
+            .map_err(map_oracle_error)?;
+        }
+
+        Ok(())
+    }
+
+    fn parse_job(row: &Row) -> Result<GenerationJob, String> {
+        let body: String = row.get("BODY").map_err(map_oracle_error)?;
+        serde_json::from_str(&body).map_err(|e| format!("invalid job body: {e}"))
+    }
+}
+
+#[async_trait]
+impl GenerationJobStorage for OracleGenerationJobStorage {
+    async fn put_job(&self, job: GenerationJob) -> GenerationJobResult<()> {
+        let body = serde_json::to_string(&job)
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let table = self.table.clone();
+
+        self.store
+            .execute(move |conn| {
+                let sql = format!(
+                    "MERGE INTO {table} t \
+                     USING (SELECT :id AS ID FROM dual) s ON (t.ID = s.ID) \
+                     WHEN MATCHED THEN UPDATE SET t.STATUS = :status, t.BODY = :body \
+                     WHEN NOT MATCHED THEN INSERT (ID, STATUS, CREATED_AT, BODY) \
+                     VALUES (:id, :status, :created_at, :body)"
+                );
+                let status = job.status.as_str().to_string();
+                let params: &[(&str, &dyn ToSql)] = &[
+                    ("id", &job.id),
+                    ("status", &status),
+                    ("created_at", &job.created_at),
+                    ("body", &body),
+                ];
+                conn.execute_named(&sql, params)
+                    .map(|_| ())
+                    .map_err(map_oracle_error)
+            })
+            .await
+            .map_err(GenerationJobStorageError::StorageError)
+    }
+
+    async fn get_job(&self, id: &str) -> GenerationJobResult<Option<GenerationJob>> {
+        let id = id.to_string();
+        let table = self.table.clone();
+
+        self.store
+            .execute(move |conn| {
+                let sql = format!("SELECT BODY FROM {table} WHERE ID = :1");
+                let mut stmt = conn.statement(&sql).build().map_err(map_oracle_error)?;
+                let mut rows = stmt.query(&[&id]).map_err(map_oracle_error)?;
+                match rows.next() {
+                    Some(row) => Self::parse_job(&row.map_err(map_oracle_error)?).map(Some),
+                    None => Ok(None),
+                }
+            })
+            .await
+            .map_err(GenerationJobStorageError::StorageError)
+    }
+
+    async fn list_unfinished_jobs(&self) -> GenerationJobResult<Vec<GenerationJob>> {
+        let table = self.table.clone();
+
+        self.store
+            .execute(move |conn| {
+                let sql = format!(
+                    "SELECT BODY FROM {table} WHERE STATUS IN ('queued', 'running') \
+                     ORDER BY CREATED_AT"
+                );
+                let mut stmt = conn.statement(&sql).build().map_err(map_oracle_error)?;
+                let rows = stmt.query(&[]).map_err(map_oracle_error)?;
+                rows.map(|row| Self::parse_job(&row.map_err(map_oracle_error)?))
+                    .collect()
+            })
+            .await
+            .map_err(GenerationJobStorageError::StorageError)
+    }
+}
@@ -23,9 +23,16 @@ const ORACLE_V3: Migration = Migration {
     up: oracle_v3_up,
 };
 
+const ORACLE_V4: Migration = Migration {
+    version: 4,
+    description: "Add tenant_key column to responses",
+    up: oracle_v4_up,
+};
+
 /// Core history-backend migrations required by the SQL response/conversation
 /// storage path during normal gateway startup.
-pub(crate) static ORACLE_HISTORY_MIGRATIONS: [Migration; 3] = [ORACLE_V1, ORACLE_V2, ORACLE_V3];
+pub(crate) static ORACLE_HISTORY_MIGRATIONS: [Migration; 4] =
+    [ORACLE_V1, ORACLE_V2, ORACLE_V3, ORACLE_V4];
 
 fn oracle_v1_up(schema: &SchemaConfig) -> Vec<String> {
     let s = &schema.responses;
@@ -96,6 +103,20 @@ fn oracle_v3_up(schema: &SchemaConfig) -> Vec<String> {
         .collect()
 }
 
+fn oracle_v4_up(schema: &SchemaConfig) -> Vec<String> {
+    let s = &schema.responses;
+    if s.is_skipped("tenant_key") {
+        return vec![];
+    }
+    let table = s.qualified_table(schema.owner.as_deref());
+    let col = s.col("tenant_key");
+    // PL/SQL block: ORA-01430 = "column already exists" (idempotent)
+    vec![format!(
+        "BEGIN EXECUTE IMMEDIATE 'ALTER TABLE {table} ADD ({col} VARCHAR2(256))'; \
+         EXCEPTION WHEN OTHERS THEN IF SQLCODE != -1430 THEN RAISE; END IF; END;"
+    )]
+}
+
 // ── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -109,7 +130,7 @@ mod tests {
             .iter()
             .map(|migration| migration.version)
             .collect();
-        assert_eq!(versions, vec![1, 2, 3]);
+        assert_eq!(versions, vec![1, 2, 3, 4]);
     }
 
     #[test]
@@ -295,4 +316,20 @@ mod tests {
             "guard regressed — must detect >30-char identifiers inside EXECUTE IMMEDIATE"
         );
     }
+
+    #[test]
+    fn oracle_v4_up_adds_tenant_key_unless_skipped() {
+        let stmts = oracle_v4_up(&SchemaConfig::default());
+        assert_eq!(stmts.len(), 1);
+        assert!(stmts[0].contains("-1430"), "got: {}", stmts[0]);
+
+        let schema = SchemaConfig {
+            responses: TableConfig {
+                skip_columns: ["tenant_key".to_string()].into_iter().collect(),
+                ..TableConfig::with_table("responses")
+            },
+            ..Default::default()
+        };
+        assert!(oracle_v4_up(&schema).is_empty());
+    }
 }
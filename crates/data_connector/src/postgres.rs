@@ -16,6 +16,7 @@ use crate::{
         make_item_id, Conversation, ConversationId, ConversationItem, ConversationItemId,
         ConversationItemResult, ConversationItemStorage, ConversationItemStorageError,
         ConversationMetadata, ConversationResult, ConversationStorage, ConversationStorageError,
+        GenerationJob, GenerationJobResult, GenerationJobStorage, GenerationJobStorageError,
         ListParams, NewConversation, NewConversationItem, ResponseId, ResponseResult,
         ResponseStorage, ResponseStorageError, SortOrder, StoredResponse,
     },
@@ -864,7 +865,7 @@ impl PostgresResponseStorage {
 
         // Build DDL column definitions, filtering out skip_columns and appending extras
         let mut col_defs = vec![format!("{} VARCHAR(64) PRIMARY KEY", s.col("id"))];
-        let core_cols: [(&str, &str); 7] = [
+        let core_cols: [(&str, &str); 8] = [
             ("conversation_id", "VARCHAR(64)"),
             ("previous_response_id", "VARCHAR(64)"),
             ("input", "JSON"),
@@ -872,6 +873,7 @@ impl PostgresResponseStorage {
             ("safety_identifier", "VARCHAR(128)"),
             ("model", "VARCHAR(128)"),
             ("raw_response", "JSON"),
+            ("tenant_key", "VARCHAR(256)"),
         ];
         for (logical, sql_type) in &core_cols {
             if !s.is_skipped(logical) {
@@ -965,6 +967,11 @@ impl PostgresResponseStorage {
                 ))
             })?
         };
+        let tenant_key: Option<String> = if s.is_skipped("tenant_key") {
+            None
+        } else {
+            row.get(s.col("tenant_key"))
+        };
 
         let previous_response_id = previous.map(ResponseId);
         let raw_response = raw_response_json.unwrap_or(Value::Null);
@@ -979,6 +986,7 @@ impl PostgresResponseStorage {
             model,
             conversation_id,
             raw_response,
+            tenant_key,
         })
     }
 }
@@ -998,6 +1006,7 @@ impl ResponseStorage for PostgresResponseStorage {
             model,
             conversation_id,
             raw_response,
+            tenant_key,
         } = response;
         let previous_id = previous_response_id.map(|r| r.0);
 
@@ -1036,6 +1045,10 @@ impl ResponseStorage for PostgresResponseStorage {
             col_names.push(s.col("raw_response"));
             params.push(&raw_response);
         }
+        if !s.is_skipped("tenant_key") {
+            col_names.push(s.col("tenant_key"));
+            params.push(&tenant_key);
+        }
 
         // Append extra columns from hooks or defaults
         let hook_extra = current_extra_columns().unwrap_or_default();
@@ -1091,6 +1104,41 @@ impl ResponseStorage for PostgresResponseStorage {
         Self::build_response_from_row(&rows[0], &self.store.schema).map(Some)
     }
 
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: Value,
+    ) -> Result<bool, ResponseStorageError> {
+        let s = &self.store.schema.responses;
+        let table = s.qualified_table(self.store.schema.owner.as_deref());
+        let col_id = s.col("id");
+
+        let client = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+
+        if s.is_skipped("raw_response") {
+            // Nothing to update — just verify the row exists
+            let sql = format!("SELECT 1 FROM {table} WHERE {col_id} = $1");
+            let rows = client
+                .query(&sql, &[&response_id.0.as_str()])
+                .await
+                .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+            return Ok(!rows.is_empty());
+        }
+
+        let col_raw = s.col("raw_response");
+        let sql = format!("UPDATE {table} SET {col_raw} = $1 WHERE {col_id} = $2");
+        let rows_affected = client
+            .execute(&sql, &[&raw_response, &response_id.0.as_str()])
+            .await
+            .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+        Ok(rows_affected > 0)
+    }
+
     async fn delete_response(&self, response_id: &ResponseId) -> ResponseResult<()> {
         let s = &self.store.schema.responses;
         let table = s.qualified_table(self.store.schema.owner.as_deref());
@@ -1192,6 +1240,115 @@ impl ResponseStorage for PostgresResponseStorage {
     }
 }
 
+// ── Generation jobs ──────────────────────────────────────────────────────
+
+/// Generation jobs live in a fixed `generation_jobs` table: the job is kept
+/// whole in `body`, with `status` and `created_at` broken out so unfinished
+/// jobs can be listed on startup.
+pub(super) struct PostgresGenerationJobStorage {
+    store: PostgresStore,
+    table: String,
+}
+
+impl PostgresGenerationJobStorage {
+    pub async fn new(store: PostgresStore) -> Result<Self, GenerationJobStorageError> {
+        let table = match store.schema.owner.as_deref() {
+            Some(owner) => format!("{owner}.\"generation_jobs\""),
+            None => "generation_jobs".to_string(),
+        };
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {table} (\
+                id VARCHAR(64) PRIMARY KEY, \
+                status VARCHAR(16) NOT NULL, \
+                created_at TIMESTAMPTZ NOT NULL, \
+                body JSON NOT NULL);"
+        );
+
+        let client = store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        client
+            .batch_execute(&ddl)
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+
+        Ok(Self { store, table })
+    }
+
+    fn parse_job(row: &Row) -> GenerationJobResult<GenerationJob> {
+        let body: Value = row.try_get("body").map_err(|e| {
+            GenerationJobStorageError::StorageError(format!("failed to decode job body: {e}"))
+        })?;
+        serde_json::from_value(body)
+            .map_err(|e| GenerationJobStorageError::StorageError(format!("invalid job body: {e}")))
+    }
+}
+
+#[async_trait]
+impl GenerationJobStorage for PostgresGenerationJobStorage {
+    async fn put_job(&self, job: GenerationJob) -> GenerationJobResult<()> {
+        let body = serde_json::to_value(&job)
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let sql = format!(
+            "INSERT INTO {} (id, status, created_at, body) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (id) DO UPDATE SET status = EXCLUDED.status, body = EXCLUDED.body",
+            self.table
+        );
+
+        let client = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        client
+            .execute(
+                &sql,
+                &[&job.id, &job.status.as_str(), &job.created_at, &body],
+            )
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_job(&self, id: &str) -> GenerationJobResult<Option<GenerationJob>> {
+        let sql = format!("SELECT body FROM {} WHERE id = $1", self.table);
+
+        let client = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let rows = client
+            .query(&sql, &[&id])
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        rows.first().map(Self::parse_job).transpose()
+    }
+
+    async fn list_unfinished_jobs(&self) -> GenerationJobResult<Vec<GenerationJob>> {
+        let sql = format!(
+            "SELECT body FROM {} WHERE status IN ('queued', 'running') ORDER BY created_at",
+            self.table
+        );
+
+        let client = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let rows = client
+            .query(&sql, &[])
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        rows.iter().map(Self::parse_job).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
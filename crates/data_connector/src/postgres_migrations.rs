@@ -23,10 +23,16 @@ const POSTGRES_V3: Migration = Migration {
     up: pg_v3_up,
 };
 
+const POSTGRES_V4: Migration = Migration {
+    version: 4,
+    description: "Add tenant_key column to responses",
+    up: pg_v4_up,
+};
+
 /// Core history-backend migrations required by the SQL response/conversation
 /// storage path during normal gateway startup.
-pub(crate) static POSTGRES_HISTORY_MIGRATIONS: [Migration; 3] =
-    [POSTGRES_V1, POSTGRES_V2, POSTGRES_V3];
+pub(crate) static POSTGRES_HISTORY_MIGRATIONS: [Migration; 4] =
+    [POSTGRES_V1, POSTGRES_V2, POSTGRES_V3, POSTGRES_V4];
 
 fn pg_v1_up(schema: &SchemaConfig) -> Vec<String> {
     let s = &schema.responses;
@@ -92,6 +98,18 @@ fn pg_v3_up(schema: &SchemaConfig) -> Vec<String> {
     vec![format!("ALTER TABLE {table} {}", cols_to_drop.join(", "))]
 }
 
+fn pg_v4_up(schema: &SchemaConfig) -> Vec<String> {
+    let s = &schema.responses;
+    if s.is_skipped("tenant_key") {
+        return vec![];
+    }
+    let table = s.qualified_table(schema.owner.as_deref());
+    let col = s.col("tenant_key");
+    vec![format!(
+        "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS {col} VARCHAR(256)"
+    )]
+}
+
 // ── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -105,7 +123,7 @@ mod tests {
             .iter()
             .map(|migration| migration.version)
             .collect();
-        assert_eq!(versions, vec![1, 2, 3]);
+        assert_eq!(versions, vec![1, 2, 3, 4]);
     }
 
     #[test]
@@ -244,4 +262,24 @@ mod tests {
             "should not use logical name: {stmts:?}"
         );
     }
+
+    #[test]
+    fn pg_v4_up_adds_tenant_key_unless_skipped() {
+        let stmts = pg_v4_up(&SchemaConfig::default());
+        assert_eq!(stmts.len(), 1);
+        assert!(
+            stmts[0].contains("ADD COLUMN IF NOT EXISTS tenant_key"),
+            "got: {}",
+            stmts[0]
+        );
+
+        let schema = SchemaConfig {
+            responses: TableConfig {
+                skip_columns: ["tenant_key".to_string()].into_iter().collect(),
+                ..TableConfig::with_table("responses")
+            },
+            ..Default::default()
+        };
+        assert!(pg_v4_up(&schema).is_empty());
+    }
 }
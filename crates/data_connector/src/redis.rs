@@ -16,6 +16,7 @@ use crate::{
         make_item_id, Conversation, ConversationId, ConversationItem, ConversationItemId,
         ConversationItemResult, ConversationItemStorage, ConversationItemStorageError,
         ConversationMetadata, ConversationResult, ConversationStorage, ConversationStorageError,
+        GenerationJob, GenerationJobResult, GenerationJobStorage, GenerationJobStorageError,
         ListParams, NewConversation, NewConversationItem, ResponseId, ResponseResult,
         ResponseStorage, ResponseStorageError, SortOrder, StoredResponse,
     },
@@ -775,6 +776,11 @@ impl RedisResponseStorage {
             parse_raw_response(map.get(s.col("raw_response")).cloned())
                 .map_err(ResponseStorageError::StorageError)?
         };
+        let tenant_key = if s.is_skipped("tenant_key") {
+            None
+        } else {
+            map.get(s.col("tenant_key")).cloned()
+        };
 
         Ok(StoredResponse {
             id,
@@ -785,6 +791,7 @@ impl RedisResponseStorage {
             model,
             conversation_id,
             raw_response,
+            tenant_key,
         })
     }
 }
@@ -842,6 +849,11 @@ impl ResponseStorage for RedisResponseStorage {
         if !sr.is_skipped("raw_response") {
             pipe.hset(&key, sr.col("raw_response"), &json_raw_response);
         }
+        if !sr.is_skipped("tenant_key") {
+            if let Some(tenant_key) = &response.tenant_key {
+                pipe.hset(&key, sr.col("tenant_key"), tenant_key);
+            }
+        }
 
         // Append extra columns from hooks or defaults
         let hook_extra = current_extra_columns().unwrap_or_default();
@@ -898,6 +910,36 @@ impl ResponseStorage for RedisResponseStorage {
         self.build_response_from_map(map, id).map(Some)
     }
 
+    async fn update_raw_response(
+        &self,
+        response_id: &ResponseId,
+        raw_response: Value,
+    ) -> ResponseResult<bool> {
+        let sr = &self.store.schema.responses;
+
+        let key = self.response_key(response_id.0.as_str());
+        let mut conn = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+
+        let exists: bool = conn
+            .exists(&key)
+            .await
+            .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+        if !exists || sr.is_skipped("raw_response") {
+            return Ok(exists);
+        }
+
+        let json_raw_response = serde_json::to_string(&raw_response)?;
+        conn.hset::<_, _, _, ()>(&key, sr.col("raw_response"), json_raw_response)
+            .await
+            .map_err(|e| ResponseStorageError::StorageError(e.to_string()))?;
+        Ok(true)
+    }
+
     async fn delete_response(&self, response_id: &ResponseId) -> ResponseResult<()> {
         let sr = &self.store.schema.responses;
 
@@ -1026,3 +1068,114 @@ impl ResponseStorage for RedisResponseStorage {
         Ok(count)
     }
 }
+
+/// Each job is a JSON string under `generation_job:{id}`. Unfinished jobs
+/// are also indexed in a sorted set scored by creation time so they can be
+/// resumed oldest first; retention only applies once a job has finished.
+pub(super) struct RedisGenerationJobStorage {
+    store: RedisStore,
+}
+
+impl RedisGenerationJobStorage {
+    pub fn new(store: RedisStore) -> Self {
+        Self { store }
+    }
+
+    fn job_key(&self, id: &str) -> String {
+        match &self.store.schema.owner {
+            Some(owner) => format!("{owner}:generation_job:{id}"),
+            None => format!("generation_job:{id}"),
+        }
+    }
+
+    fn unfinished_key(&self) -> String {
+        match &self.store.schema.owner {
+            Some(owner) => format!("{owner}:generation_jobs:unfinished"),
+            None => "generation_jobs:unfinished".to_string(),
+        }
+    }
+
+    fn parse_job(raw: &str) -> GenerationJobResult<GenerationJob> {
+        serde_json::from_str(raw)
+            .map_err(|e| GenerationJobStorageError::StorageError(format!("invalid job body: {e}")))
+    }
+}
+
+#[async_trait]
+impl GenerationJobStorage for RedisGenerationJobStorage {
+    async fn put_job(&self, job: GenerationJob) -> GenerationJobResult<()> {
+        let body = serde_json::to_string(&job)
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let key = self.job_key(&job.id);
+        let unfinished = self.unfinished_key();
+
+        let mut conn = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        pipe.set(&key, body).ignore();
+        if job.status.is_terminal() {
+            pipe.zrem(&unfinished, &job.id).ignore();
+            if let Some(days) = self.store.retention_days {
+                pipe.expire(&key, (days * 24 * 60 * 60) as i64).ignore();
+            }
+        } else {
+            pipe.zadd(&unfinished, &job.id, job.created_at.timestamp_millis())
+                .ignore();
+        }
+
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))
+    }
+
+    async fn get_job(&self, id: &str) -> GenerationJobResult<Option<GenerationJob>> {
+        let mut conn = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let raw: Option<String> = conn
+            .get(self.job_key(id))
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        raw.as_deref().map(Self::parse_job).transpose()
+    }
+
+    async fn list_unfinished_jobs(&self) -> GenerationJobResult<Vec<GenerationJob>> {
+        let mut conn = self
+            .store
+            .pool
+            .get()
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        let ids: Vec<String> = conn
+            .zrange(self.unfinished_key(), 0, -1)
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for id in &ids {
+            pipe.get(self.job_key(id));
+        }
+        let bodies: Vec<Option<String>> = pipe
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| GenerationJobStorageError::StorageError(e.to_string()))?;
+
+        bodies
+            .iter()
+            .flatten()
+            .map(|raw| Self::parse_job(raw))
+            .collect()
+    }
+}
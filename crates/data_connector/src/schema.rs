@@ -15,7 +15,7 @@ use serde_json::Value;
 ///
 /// Every field defaults to its logical name, so omitting the entire `schema:`
 /// section in YAML uses the default table and column names.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 #[serde(default)]
 pub struct SchemaConfig {
     /// Schema owner / key prefix (e.g. `"ADMIN"` for Oracle, `"myapp"` for Redis).
@@ -44,7 +44,7 @@ pub struct SchemaConfig {
 }
 
 /// Per-table schema configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 #[serde(default)]
 pub struct TableConfig {
     /// Physical table name (or Redis key component).
@@ -69,7 +69,7 @@ pub struct TableConfig {
 }
 
 /// Column definition for extra (user-defined) columns declared in schema config.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct ColumnDef {
     /// SQL type string, e.g. `"VARCHAR2(256)"` or `"TEXT"`.
     pub sql_type: String,
@@ -291,6 +291,7 @@ fn core_columns_for(label: &str) -> &'static [&'static str] {
             "safety_identifier",
             "model",
             "raw_response",
+            "tenant_key",
         ],
         "conversation_items" => &[
             "id",
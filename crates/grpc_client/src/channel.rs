@@ -6,10 +6,22 @@
 //! `Channel` with the same keep-alive / window-size profile. This module
 //! centralises that pipeline so adding a new engine — or tuning the
 //! transport profile — touches one file instead of four.
+//!
+//! TLS channels read their CA bundle and client identity from the files
+//! named in the worker's [`GrpcTlsConfig`]. With a reload interval the
+//! channel is a single-endpoint balance channel; when the files change, an
+//! endpoint built from the new material is swapped in and the old one is
+//! retired, so existing clients keep working without reconnecting by hand.
 
 use std::time::Duration;
 
-use tonic::transport::Channel;
+use openai_protocol::worker::GrpcTlsConfig;
+use tokio::sync::mpsc;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tower::discover::Change;
+use tracing::{info, warn};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 /// Convert a `grpc://` or `grpcs://` endpoint to a tonic-compatible
 /// `http://` or `https://` URI. Other schemes (or schemeless inputs) are
@@ -23,17 +35,20 @@ pub fn normalize_grpc_endpoint(endpoint: &str) -> String {
     }
 }
 
-/// Connect a `tonic::Channel` to the given endpoint with the SMG-standard
-/// keep-alive and HTTP/2 window profile applied.
-///
-/// The endpoint may use any of `http://`, `https://`, `grpc://`, or
-/// `grpcs://` — gRPC schemes are normalised to their HTTP(S) equivalents
-/// before tonic parses them.
-pub async fn connect_channel(
-    endpoint: &str,
-) -> Result<Channel, Box<dyn std::error::Error + Send + Sync>> {
-    let http_endpoint = normalize_grpc_endpoint(endpoint);
-    let channel = Channel::from_shared(http_endpoint)?
+/// Normalise `endpoint` and force the `https` scheme, for workers with TLS
+/// configured regardless of how their URL is spelled.
+fn tls_endpoint(endpoint: &str) -> String {
+    let normalized = normalize_grpc_endpoint(endpoint);
+    match normalized.split_once("://") {
+        Some(("http", rest)) => format!("https://{rest}"),
+        Some(_) => normalized,
+        None => format!("https://{normalized}"),
+    }
+}
+
+/// Apply the SMG-standard keep-alive and HTTP/2 window profile.
+fn with_transport_profile(endpoint: Endpoint) -> Endpoint {
+    endpoint
         .http2_keep_alive_interval(Duration::from_secs(30))
         .keep_alive_timeout(Duration::from_secs(10))
         .keep_alive_while_idle(true)
@@ -45,14 +60,166 @@ pub async fn connect_channel(
         // streaming chunks) without head-of-line blocking.
         .initial_stream_window_size(Some(16 * 1024 * 1024))
         .initial_connection_window_size(Some(32 * 1024 * 1024))
-        .connect()
-        .await?;
+}
+
+/// PEM material named by a [`GrpcTlsConfig`], as last read from disk.
+#[derive(PartialEq, Eq)]
+struct TlsMaterial {
+    ca: Option<Vec<u8>>,
+    identity: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl TlsMaterial {
+    async fn load(tls: &GrpcTlsConfig) -> Result<Self, BoxError> {
+        let ca = match &tls.ca_cert_path {
+            Some(path) => Some(read_pem(path).await?),
+            None => None,
+        };
+        let identity = match (&tls.client_cert_path, &tls.client_key_path) {
+            (Some(cert), Some(key)) => Some((read_pem(cert).await?, read_pem(key).await?)),
+            (None, None) => None,
+            _ => {
+                return Err("client_cert_path and client_key_path must be set together".into());
+            }
+        };
+        Ok(Self { ca, identity })
+    }
+
+    fn client_config(&self, tls: &GrpcTlsConfig) -> ClientTlsConfig {
+        let mut config = ClientTlsConfig::new().assume_http2(tls.assume_http2);
+        config = match &self.ca {
+            Some(ca) => config.ca_certificate(Certificate::from_pem(ca)),
+            None => config.with_native_roots(),
+        };
+        if let Some((cert, key)) = &self.identity {
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+        if let Some(server_name) = &tls.server_name {
+            config = config.domain_name(server_name.clone());
+        }
+        config
+    }
+
+    fn endpoint(&self, uri: &str, tls: &GrpcTlsConfig) -> Result<Endpoint, BoxError> {
+        let endpoint = with_transport_profile(Channel::from_shared(uri.to_string())?)
+            .tls_config(self.client_config(tls))?;
+        Ok(endpoint)
+    }
+}
+
+async fn read_pem(path: &str) -> Result<Vec<u8>, BoxError> {
+    tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("failed to read {path}: {e}").into())
+}
+
+/// Connect a `tonic::Channel` to the given endpoint with the SMG-standard
+/// keep-alive and HTTP/2 window profile applied.
+///
+/// The endpoint may use any of `http://`, `https://`, `grpc://`, or
+/// `grpcs://` — gRPC schemes are normalised to their HTTP(S) equivalents
+/// before tonic parses them.
+pub async fn connect_channel(endpoint: &str) -> Result<Channel, BoxError> {
+    connect_channel_with_tls(endpoint, None).await
+}
+
+/// [`connect_channel`] with the worker's TLS configuration.
+///
+/// `https://` and `grpcs://` endpoints without a configuration verify the
+/// worker against the system roots.
+pub async fn connect_channel_with_tls(
+    endpoint: &str,
+    tls: Option<&GrpcTlsConfig>,
+) -> Result<Channel, BoxError> {
+    let http_endpoint = normalize_grpc_endpoint(endpoint);
+    let default_tls;
+    let tls = match tls {
+        Some(tls) => tls,
+        None if http_endpoint.starts_with("https://") => {
+            default_tls = GrpcTlsConfig::default();
+            &default_tls
+        }
+        None => {
+            let channel = with_transport_profile(Channel::from_shared(http_endpoint)?)
+                .connect()
+                .await?;
+            return Ok(channel);
+        }
+    };
+
+    let uri = tls_endpoint(endpoint);
+    let material = TlsMaterial::load(tls).await?;
+    let endpoint = material.endpoint(&uri, tls)?;
+    let has_files = tls.ca_cert_path.is_some() || tls.client_cert_path.is_some();
+    if tls.reload_interval_secs == 0 || !has_files {
+        return Ok(endpoint.connect().await?);
+    }
+
+    // Connect once up front so a bad certificate or an unreachable worker
+    // fails here, as it does for a plaintext channel; the balance channel
+    // itself only connects on first use.
+    endpoint.connect().await?;
+    let (channel, changes) = Channel::balance_channel(1);
+    changes
+        .send(Change::Insert(0, endpoint))
+        .await
+        .map_err(|_| "gRPC balance channel closed")?;
+    let reload = reload_tls(uri, tls.clone(), material, changes);
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "reload loop exits once every clone of the channel is dropped"
+    )]
+    tokio::spawn(reload);
     Ok(channel)
 }
 
+/// Swap in an endpoint built from fresh material whenever the configured
+/// files change, until the channel is dropped.
+async fn reload_tls(
+    uri: String,
+    tls: GrpcTlsConfig,
+    mut current: TlsMaterial,
+    changes: mpsc::Sender<Change<u64, Endpoint>>,
+) {
+    let interval = Duration::from_secs(tls.reload_interval_secs);
+    let mut generation = 0u64;
+    loop {
+        tokio::select! {
+            () = changes.closed() => return,
+            () = tokio::time::sleep(interval) => {}
+        }
+        let material = match TlsMaterial::load(&tls).await {
+            Ok(material) if material == current => continue,
+            Ok(material) => material,
+            Err(e) => {
+                warn!(endpoint = %uri, "Keeping current gRPC TLS material: {e}");
+                continue;
+            }
+        };
+        let endpoint = match material.endpoint(&uri, &tls) {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                warn!(endpoint = %uri, "Rejected reloaded gRPC TLS material: {e}");
+                continue;
+            }
+        };
+        generation += 1;
+        if changes
+            .send(Change::Insert(generation, endpoint))
+            .await
+            .is_err()
+            || changes.send(Change::Remove(generation - 1)).await.is_err()
+        {
+            return;
+        }
+        info!(endpoint = %uri, "Reloaded gRPC TLS material");
+        current = material;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::normalize_grpc_endpoint;
+    use super::{normalize_grpc_endpoint, tls_endpoint};
 
     #[test]
     fn normalize_grpc_to_http() {
@@ -118,4 +285,12 @@ mod tests {
             "GRPC://worker:8080"
         );
     }
+
+    #[test]
+    fn tls_endpoint_forces_https() {
+        assert_eq!(tls_endpoint("grpc://worker:8443"), "https://worker:8443");
+        assert_eq!(tls_endpoint("grpcs://worker:8443"), "https://worker:8443");
+        assert_eq!(tls_endpoint("http://worker:8443"), "https://worker:8443");
+        assert_eq!(tls_endpoint("worker:8443"), "https://worker:8443");
+    }
 }
@@ -0,0 +1,244 @@
+//! Proto revision negotiation and compatibility shims.
+//!
+//! The SMG servicers advertise the revision of the proto contract they were
+//! built against in `GetServerInfoResponse.proto_revision`. After connecting,
+//! callers fetch server info and call [`ProtoCompat::negotiate`]; the
+//! resulting [`ProtoCompat`] rewrites requests and responses so a gateway
+//! built against [`PROTO_REVISION`] keeps working with older servicers, and
+//! backends can be upgraded without a lockstep gateway release.
+//!
+//! Servicers that predate negotiation leave the field unset (revision 0).
+//! Newer servicers are expected to stay wire-compatible with older gateways
+//! (proto3 ignores unknown fields), so revisions above ours are treated as
+//! ours.
+//!
+//! Revision history:
+//! - 0: before negotiation. vLLM reads Mooncake handoffs only from the typed
+//!   `kv_transfer_params` field; SGLang reports sampling defaults only in
+//!   `preferred_sampling_params`.
+//! - 1: `proto_revision` advertised; `kv_transfer_params_json` and
+//!   `default_sampling_params_json` are authoritative.
+//! - 2: SGLang reads `SamplingParams.sampling_seed`.
+
+use tracing::debug;
+
+use crate::{mlx_proto, sglang_proto, tokenspeed_proto, trtllm_proto, vllm_proto};
+
+/// Proto revision this crate is compiled against.
+pub const PROTO_REVISION: u32 = 2;
+
+/// Revision assumed for servicers that do not advertise one.
+pub const LEGACY_PROTO_REVISION: u32 = 0;
+
+/// Negotiated proto revision for one backend connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtoCompat {
+    revision: u32,
+}
+
+impl Default for ProtoCompat {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl ProtoCompat {
+    /// No shims: the backend speaks the same revision as this crate. Used
+    /// until negotiation runs, and for backends that cannot advertise.
+    pub const CURRENT: Self = Self {
+        revision: PROTO_REVISION,
+    };
+
+    /// Pick the effective revision from the one the server advertised.
+    pub fn negotiate(advertised: u32) -> Self {
+        if advertised > PROTO_REVISION {
+            debug!(
+                advertised,
+                supported = PROTO_REVISION,
+                "Backend advertises a newer proto revision; using ours"
+            );
+        }
+        Self {
+            revision: advertised.min(PROTO_REVISION),
+        }
+    }
+
+    /// Effective revision spoken on this connection.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Whether the backend predates proto revision negotiation.
+    pub fn is_legacy(&self) -> bool {
+        self.revision == LEGACY_PROTO_REVISION
+    }
+
+    /// Whether an SGLang servicer honors `SamplingParams.sampling_seed`.
+    /// Older ones drop the field, so seeded requests must be refused.
+    pub fn supports_sglang_sampling_seed(&self) -> bool {
+        self.revision >= 2
+    }
+
+    /// Rewrite a vLLM generate request for the negotiated revision.
+    ///
+    /// Revision 0 ignores `kv_transfer_params_json`, so Mooncake handoffs
+    /// that carry `remote_bootstrap_addr` are mirrored into the typed
+    /// `kv_transfer_params` field.
+    pub fn shim_vllm_generate_request(&self, req: &mut vllm_proto::GenerateRequest) {
+        if !self.is_legacy() || req.kv_transfer_params.is_some() {
+            return;
+        }
+        let Some(json) = req.kv_transfer_params_json.as_deref() else {
+            return;
+        };
+        if let Some((remote_host, remote_port)) = mooncake_remote_addr(json) {
+            req.kv_transfer_params = Some(vllm_proto::KvTransferParams {
+                remote_host,
+                remote_port,
+            });
+        }
+    }
+
+    /// Rewrite an SGLang model info response for the negotiated revision.
+    ///
+    /// Revision 0 reports sampling defaults only in
+    /// `preferred_sampling_params`; copy them into
+    /// `default_sampling_params_json`, which the gateway reads.
+    pub fn shim_sglang_model_info(&self, info: &mut sglang_proto::GetModelInfoResponse) {
+        if self.is_legacy()
+            && info.default_sampling_params_json.is_empty()
+            && !info.preferred_sampling_params.is_empty()
+        {
+            info.default_sampling_params_json = info.preferred_sampling_params.clone();
+        }
+    }
+}
+
+/// Parse `remote_bootstrap_addr` (`http://host:port`) out of a Mooncake
+/// KV-transfer params object.
+fn mooncake_remote_addr(json: &str) -> Option<(String, u32)> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let addr = value.get("remote_bootstrap_addr")?.as_str()?;
+    let authority = addr
+        .split_once("://")
+        .map_or(addr, |(_, rest)| rest)
+        .trim_end_matches('/');
+    let (host, port) = authority.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+/// Server info responses that advertise the servicer's proto revision.
+pub trait AdvertisedProtoRevision {
+    /// Revision advertised by the server, [`LEGACY_PROTO_REVISION`] if unset.
+    fn proto_revision(&self) -> u32;
+}
+
+macro_rules! impl_advertised_proto_revision {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl AdvertisedProtoRevision for $ty {
+                fn proto_revision(&self) -> u32 {
+                    self.proto_revision
+                }
+            }
+        )+
+    };
+}
+
+impl_advertised_proto_revision!(
+    sglang_proto::GetServerInfoResponse,
+    vllm_proto::GetServerInfoResponse,
+    mlx_proto::GetServerInfoResponse,
+    tokenspeed_proto::GetServerInfoResponse,
+);
+
+/// TensorRT-LLM's servicer is maintained upstream against the current proto
+/// and does not advertise a revision.
+impl AdvertisedProtoRevision for trtllm_proto::GetServerInfoResponse {
+    fn proto_revision(&self) -> u32 {
+        PROTO_REVISION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mooncake_request(json: &str) -> vllm_proto::GenerateRequest {
+        vllm_proto::GenerateRequest {
+            kv_transfer_params_json: Some(json.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_negotiate_clamps_to_supported_revision() {
+        assert!(ProtoCompat::negotiate(0).is_legacy());
+        assert_eq!(ProtoCompat::negotiate(PROTO_REVISION), ProtoCompat::CURRENT);
+        assert_eq!(
+            ProtoCompat::negotiate(PROTO_REVISION + 5).revision(),
+            PROTO_REVISION
+        );
+    }
+
+    #[test]
+    fn test_sglang_sampling_seed_needs_revision_2() {
+        assert!(!ProtoCompat::negotiate(0).supports_sglang_sampling_seed());
+        assert!(!ProtoCompat::negotiate(1).supports_sglang_sampling_seed());
+        assert!(ProtoCompat::negotiate(2).supports_sglang_sampling_seed());
+        assert!(ProtoCompat::CURRENT.supports_sglang_sampling_seed());
+    }
+
+    #[test]
+    fn test_legacy_vllm_gets_typed_mooncake_params() {
+        let mut req = mooncake_request(
+            r#"{"do_remote_prefill":true,"remote_bootstrap_addr":"http://10.0.0.1:8998"}"#,
+        );
+        ProtoCompat::negotiate(0).shim_vllm_generate_request(&mut req);
+        let typed = req.kv_transfer_params.expect("typed params");
+        assert_eq!(typed.remote_host, "10.0.0.1");
+        assert_eq!(typed.remote_port, 8998);
+        // The JSON form is kept for servicers that understand both.
+        assert!(req.kv_transfer_params_json.is_some());
+    }
+
+    #[test]
+    fn test_current_vllm_request_is_untouched() {
+        let mut req = mooncake_request(r#"{"remote_bootstrap_addr":"http://10.0.0.1:8998"}"#);
+        ProtoCompat::CURRENT.shim_vllm_generate_request(&mut req);
+        assert!(req.kv_transfer_params.is_none());
+    }
+
+    #[test]
+    fn test_legacy_vllm_without_mooncake_addr_is_untouched() {
+        let mut req = mooncake_request(r#"{"do_remote_decode":true}"#);
+        ProtoCompat::negotiate(0).shim_vllm_generate_request(&mut req);
+        assert!(req.kv_transfer_params.is_none());
+    }
+
+    #[test]
+    fn test_mooncake_remote_addr_handles_ipv6() {
+        assert_eq!(
+            mooncake_remote_addr(r#"{"remote_bootstrap_addr":"http://[::1]:9000"}"#),
+            Some(("::1".to_string(), 9000))
+        );
+    }
+
+    #[test]
+    fn test_legacy_sglang_model_info_gets_sampling_defaults() {
+        let mut info = sglang_proto::GetModelInfoResponse {
+            preferred_sampling_params: r#"{"temperature":0.6}"#.to_string(),
+            ..Default::default()
+        };
+        ProtoCompat::negotiate(0).shim_sglang_model_info(&mut info);
+        assert_eq!(info.default_sampling_params_json, r#"{"temperature":0.6}"#);
+
+        let mut info = sglang_proto::GetModelInfoResponse {
+            preferred_sampling_params: r#"{"temperature":0.6}"#.to_string(),
+            ..Default::default()
+        };
+        ProtoCompat::CURRENT.shim_sglang_model_info(&mut info);
+        assert!(info.default_sampling_params_json.is_empty());
+    }
+}
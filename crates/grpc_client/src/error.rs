@@ -0,0 +1,274 @@
+//! Typed errors for backend gRPC calls.
+//!
+//! Engine clients surface failures as `tonic::Status`. [`GrpcClientError`]
+//! classifies a status by code and parses the backend details carried in
+//! its metadata, so callers can decide whether a retry on another attempt
+//! can succeed ([`GrpcClientError::is_retryable`]) instead of retrying
+//! every failure.
+
+use std::{fmt, time::Duration};
+
+use tonic::{metadata::MetadataMap, Code, Status};
+
+/// Metadata key for server retry pushback, per the gRPC retry design.
+/// A non-negative value asks the client to wait that many milliseconds
+/// before retrying; a negative or malformed value means "do not retry".
+pub const RETRY_PUSHBACK_METADATA_KEY: &str = "grpc-retry-pushback-ms";
+
+/// Metadata key under which SMG servicers report a backend-specific error
+/// type (e.g. `kv_cache_full`, `prompt_too_long`).
+pub const ERROR_TYPE_METADATA_KEY: &str = "x-smg-error-type";
+
+/// Backend error types that fail every attempt with the same input.
+const PERMANENT_ERROR_TYPES: &[&str] = &["prompt_too_long", "invalid_request", "model_not_found"];
+
+/// Server pushback parsed from [`RETRY_PUSHBACK_METADATA_KEY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryPushback {
+    /// Retry after at least this long
+    After(Duration),
+    /// The server asked not to retry
+    DoNotRetry,
+}
+
+/// Backend-supplied details carried in status metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendErrorDetails {
+    /// Backend-specific error type from [`ERROR_TYPE_METADATA_KEY`]
+    pub error_type: Option<String>,
+    /// Server retry pushback from [`RETRY_PUSHBACK_METADATA_KEY`]
+    pub retry_pushback: Option<RetryPushback>,
+}
+
+impl BackendErrorDetails {
+    /// Parse backend details from status metadata. Absent or non-ASCII
+    /// values are ignored.
+    pub fn from_metadata(metadata: &MetadataMap) -> Self {
+        let get = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let retry_pushback =
+            get(RETRY_PUSHBACK_METADATA_KEY).map(|value| match value.parse::<i64>() {
+                Ok(ms) if ms >= 0 => RetryPushback::After(Duration::from_millis(ms as u64)),
+                _ => RetryPushback::DoNotRetry,
+            });
+        Self {
+            error_type: get(ERROR_TYPE_METADATA_KEY)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            retry_pushback,
+        }
+    }
+}
+
+/// A classified backend gRPC error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrpcClientError {
+    /// The backend is out of capacity (queue full, KV cache exhausted)
+    ResourceExhausted {
+        message: String,
+        details: BackendErrorDetails,
+    },
+    /// The request was rejected as malformed; retrying cannot help
+    InvalidArgument {
+        message: String,
+        details: BackendErrorDetails,
+    },
+    /// The backend could not be reached or is shutting down
+    Unavailable {
+        message: String,
+        details: BackendErrorDetails,
+    },
+    /// The call did not finish within its deadline
+    DeadlineExceeded {
+        message: String,
+        details: BackendErrorDetails,
+    },
+    /// Any other status code
+    Other {
+        code: Code,
+        message: String,
+        details: BackendErrorDetails,
+    },
+}
+
+impl GrpcClientError {
+    /// The gRPC status code this error was classified from.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::ResourceExhausted { .. } => Code::ResourceExhausted,
+            Self::InvalidArgument { .. } => Code::InvalidArgument,
+            Self::Unavailable { .. } => Code::Unavailable,
+            Self::DeadlineExceeded { .. } => Code::DeadlineExceeded,
+            Self::Other { code, .. } => *code,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::ResourceExhausted { message, .. }
+            | Self::InvalidArgument { message, .. }
+            | Self::Unavailable { message, .. }
+            | Self::DeadlineExceeded { message, .. }
+            | Self::Other { message, .. } => message,
+        }
+    }
+
+    pub fn details(&self) -> &BackendErrorDetails {
+        match self {
+            Self::ResourceExhausted { details, .. }
+            | Self::InvalidArgument { details, .. }
+            | Self::Unavailable { details, .. }
+            | Self::DeadlineExceeded { details, .. }
+            | Self::Other { details, .. } => details,
+        }
+    }
+
+    /// Whether another attempt (possibly on another worker) can succeed.
+    ///
+    /// Server pushback and permanent backend error types take precedence
+    /// over the status code. Codes describing the request itself (bad
+    /// arguments, auth, missing resources, unimplemented RPCs) and data
+    /// loss are permanent; capacity, availability, deadline, abort and
+    /// internal failures are transient.
+    pub fn is_retryable(&self) -> bool {
+        let details = self.details();
+        if details.retry_pushback == Some(RetryPushback::DoNotRetry) {
+            return false;
+        }
+        if details
+            .error_type
+            .as_deref()
+            .is_some_and(|error_type| PERMANENT_ERROR_TYPES.contains(&error_type))
+        {
+            return false;
+        }
+        matches!(
+            self.code(),
+            Code::ResourceExhausted
+                | Code::Unavailable
+                | Code::DeadlineExceeded
+                | Code::Aborted
+                | Code::Internal
+                | Code::Unknown
+        )
+    }
+
+    /// Minimum delay the backend asked for before the next attempt.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.details().retry_pushback {
+            Some(RetryPushback::After(delay)) => Some(delay),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GrpcClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code(), self.message())?;
+        if let Some(error_type) = &self.details().error_type {
+            write!(f, " ({error_type})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GrpcClientError {}
+
+impl From<&Status> for GrpcClientError {
+    fn from(status: &Status) -> Self {
+        let message = status.message().to_string();
+        let details = BackendErrorDetails::from_metadata(status.metadata());
+        match status.code() {
+            Code::ResourceExhausted => Self::ResourceExhausted { message, details },
+            Code::InvalidArgument => Self::InvalidArgument { message, details },
+            Code::Unavailable => Self::Unavailable { message, details },
+            Code::DeadlineExceeded => Self::DeadlineExceeded { message, details },
+            code => Self::Other {
+                code,
+                message,
+                details,
+            },
+        }
+    }
+}
+
+impl From<Status> for GrpcClientError {
+    fn from(status: Status) -> Self {
+        Self::from(&status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_with(code: Code, metadata: &[(&'static str, &str)]) -> Status {
+        let mut status = Status::new(code, "boom");
+        for (key, value) in metadata {
+            status
+                .metadata_mut()
+                .insert(*key, value.parse().expect("ascii metadata"));
+        }
+        status
+    }
+
+    #[test]
+    fn test_classifies_status_codes() {
+        let err = GrpcClientError::from(status_with(Code::ResourceExhausted, &[]));
+        assert!(matches!(err, GrpcClientError::ResourceExhausted { .. }));
+        assert!(err.is_retryable());
+
+        let err = GrpcClientError::from(status_with(Code::InvalidArgument, &[]));
+        assert!(matches!(err, GrpcClientError::InvalidArgument { .. }));
+        assert!(!err.is_retryable());
+
+        assert!(GrpcClientError::from(status_with(Code::Unavailable, &[])).is_retryable());
+        assert!(GrpcClientError::from(status_with(Code::DeadlineExceeded, &[])).is_retryable());
+        assert!(GrpcClientError::from(status_with(Code::Internal, &[])).is_retryable());
+        assert!(!GrpcClientError::from(status_with(Code::NotFound, &[])).is_retryable());
+        assert!(!GrpcClientError::from(status_with(Code::Unimplemented, &[])).is_retryable());
+        assert!(!GrpcClientError::from(status_with(Code::DataLoss, &[])).is_retryable());
+    }
+
+    #[test]
+    fn test_retry_pushback_overrides_code() {
+        let err = GrpcClientError::from(status_with(
+            Code::ResourceExhausted,
+            &[(RETRY_PUSHBACK_METADATA_KEY, "250")],
+        ));
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(250)));
+
+        let err = GrpcClientError::from(status_with(
+            Code::Unavailable,
+            &[(RETRY_PUSHBACK_METADATA_KEY, "-1")],
+        ));
+        assert_eq!(
+            err.details().retry_pushback,
+            Some(RetryPushback::DoNotRetry)
+        );
+        assert!(!err.is_retryable());
+        assert_eq!(err.retry_after(), None);
+    }
+
+    #[test]
+    fn test_permanent_backend_error_type() {
+        let err = GrpcClientError::from(status_with(
+            Code::Internal,
+            &[(ERROR_TYPE_METADATA_KEY, "prompt_too_long")],
+        ));
+        assert_eq!(err.details().error_type.as_deref(), Some("prompt_too_long"));
+        assert!(!err.is_retryable());
+        assert_eq!(err.to_string(), "Internal: boom (prompt_too_long)");
+
+        let err = GrpcClientError::from(status_with(
+            Code::ResourceExhausted,
+            &[(ERROR_TYPE_METADATA_KEY, "kv_cache_full")],
+        ));
+        assert!(err.is_retryable());
+    }
+}
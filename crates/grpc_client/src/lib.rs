@@ -15,6 +15,8 @@ pub mod common_proto {
 }
 pub mod abort_on_drop;
 pub mod channel;
+pub mod compat;
+pub mod error;
 pub mod mlx_engine;
 pub mod sglang_scheduler;
 pub mod tokenizer_bundle;
@@ -27,7 +29,9 @@ pub mod vllm_engine;
 use std::sync::Arc;
 
 pub use abort_on_drop::{AbortOnDropClient, AbortOnDropStream};
-pub use channel::{connect_channel, normalize_grpc_endpoint};
+pub use channel::{connect_channel, connect_channel_with_tls, normalize_grpc_endpoint};
+pub use compat::{AdvertisedProtoRevision, ProtoCompat, PROTO_REVISION};
+pub use error::{BackendErrorDetails, GrpcClientError, RetryPushback};
 pub use mlx_engine::{proto as mlx_proto, MlxEngineClient};
 pub use sglang_scheduler::{
     proto as sglang_proto, SglangGenerateRequestOptions, SglangSchedulerClient,
@@ -215,8 +219,8 @@ impl TraceInjector for NoopTraceInjector {
 pub type BoxedTraceInjector = Arc<dyn TraceInjector>;
 
 /// Generates the boilerplate that every engine client shares: the two
-/// `connect` constructors, `with_trace_injector`, and the three "standard"
-/// RPCs (`health_check`, `get_model_info`, `get_server_info`) whose
+/// `connect` constructors, `with_trace_injector`, proto revision
+/// negotiation, and the three "standard" RPCs (`health_check`, `get_model_info`, `get_server_info`) whose
 /// request/response types are uniform across the generated proto crates.
 ///
 /// `$proto_client` is the fully-qualified path of the generated tonic
@@ -242,18 +246,34 @@ macro_rules! impl_engine_client_basics {
         pub async fn connect_with_trace_injector(
             endpoint: &str,
             trace_injector: $crate::BoxedTraceInjector,
+        ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(Self::connect_with_tls(endpoint, None)
+                .await?
+                .with_trace_injector(trace_injector))
+        }
+
+        /// Create a new client over the worker's TLS configuration, if any.
+        pub async fn connect_with_tls(
+            endpoint: &str,
+            tls: Option<&openai_protocol::worker::GrpcTlsConfig>,
         ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
             tracing::debug!(
                 "Connecting to {} gRPC server at {}",
                 $display_name,
                 endpoint
             );
-            let channel = $crate::channel::connect_channel(endpoint).await?;
-            let client = <$proto_client>::new(channel);
-            Ok(Self {
-                client,
-                trace_injector,
-            })
+            let channel = $crate::channel::connect_channel_with_tls(endpoint, tls).await?;
+            Ok(Self::from_channel(channel))
+        }
+
+        /// Wrap an already-built channel, e.g. a lazily connected one that
+        /// defers the handshake to the first RPC.
+        pub fn from_channel(channel: tonic::transport::Channel) -> Self {
+            Self {
+                client: <$proto_client>::new(channel),
+                trace_injector: std::sync::Arc::new($crate::NoopTraceInjector),
+                compat: $crate::ProtoCompat::CURRENT,
+            }
         }
 
         /// Set or replace the trace injector.
@@ -263,6 +283,22 @@ macro_rules! impl_engine_client_basics {
             self
         }
 
+        /// Proto revision negotiated with the backend. Current until
+        /// [`Self::negotiate_proto`] runs.
+        pub fn proto_compat(&self) -> $crate::ProtoCompat {
+            self.compat
+        }
+
+        /// Adopt the proto revision advertised in the backend's server info.
+        pub fn negotiate_proto(
+            &mut self,
+            info: &proto::GetServerInfoResponse,
+        ) -> $crate::ProtoCompat {
+            use $crate::AdvertisedProtoRevision as _;
+            self.compat = $crate::ProtoCompat::negotiate(info.proto_revision());
+            self.compat
+        }
+
         /// Perform a health check.
         pub async fn health_check(&self) -> Result<proto::HealthCheckResponse, tonic::Status> {
             tracing::debug!("Sending health check request");
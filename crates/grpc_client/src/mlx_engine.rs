@@ -8,7 +8,7 @@ use openai_protocol::{
 use tonic::{transport::Channel, Request};
 use tracing::{debug, warn};
 
-use crate::{AbortOnDropClient, BoxedTraceInjector};
+use crate::{AbortOnDropClient, BoxedTraceInjector, ProtoCompat};
 
 // Include the generated protobuf code
 #[expect(clippy::allow_attributes)]
@@ -26,6 +26,7 @@ pub type AbortOnDropStream = crate::AbortOnDropStream<proto::GenerateResponse, M
 pub struct MlxEngineClient {
     client: proto::mlx_engine_client::MlxEngineClient<Channel>,
     trace_injector: BoxedTraceInjector,
+    compat: ProtoCompat,
 }
 
 impl AbortOnDropClient for MlxEngineClient {
@@ -314,7 +315,6 @@ impl MlxEngineClient {
 
     // ── Private sampling param builders ─────────────────────────────────
 
-    #[expect(deprecated, reason = "seed is legacy but still forwarded to backends")]
     fn build_sampling_params_from_chat(request: &ChatCompletionRequest) -> proto::SamplingParams {
         let logprobs = if request.logprobs {
             Some(request.top_logprobs.unwrap_or(1).min(20) as i32)
@@ -335,7 +335,7 @@ impl MlxEngineClient {
             ignore_eos: request.ignore_eos,
             logprobs,
             logit_bias: convert_logit_bias(request.logit_bias.as_ref()),
-            seed: request.seed.and_then(|s| i32::try_from(s).ok()),
+            seed: request.effective_seed().and_then(|s| i32::try_from(s).ok()),
         }
     }
 
@@ -355,7 +355,7 @@ impl MlxEngineClient {
             ignore_eos: request.ignore_eos,
             logprobs,
             logit_bias: convert_logit_bias(request.logit_bias.as_ref()),
-            seed: request.seed.and_then(|s| i32::try_from(s).ok()),
+            seed: request.effective_seed().and_then(|s| i32::try_from(s).ok()),
         }
     }
 
@@ -368,6 +368,7 @@ impl MlxEngineClient {
             top_k: request.top_k.unwrap_or(0),
             max_tokens: Some(request.max_tokens),
             repetition_penalty: 1.0, // 1.0 = no penalty (0.0 would penalize everything)
+            seed: request.seed().and_then(|s| i32::try_from(s).ok()),
             ..Default::default()
         }
     }
@@ -437,7 +438,7 @@ impl MlxEngineClient {
             stop_token_ids: vec![],
             ignore_eos: false,
             logprobs: request.top_logprobs.map(|v| v as i32),
-            seed: None,
+            seed: request.seed.and_then(|s| i32::try_from(s).ok()),
         }
     }
 }
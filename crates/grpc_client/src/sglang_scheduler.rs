@@ -12,7 +12,7 @@ use openai_protocol::{
 use tonic::{transport::Channel, Request};
 use tracing::{debug, warn};
 
-use crate::{AbortOnDropClient, BoxedTraceInjector};
+use crate::{AbortOnDropClient, BoxedTraceInjector, ProtoCompat};
 
 // Include the generated protobuf code
 #[expect(clippy::allow_attributes)]
@@ -31,6 +31,7 @@ pub type AbortOnDropStream =
 pub struct SglangSchedulerClient {
     client: proto::sglang_scheduler_client::SglangSchedulerClient<Channel>,
     trace_injector: BoxedTraceInjector,
+    compat: ProtoCompat,
 }
 
 /// Optional request data used when building SGLang generation requests.
@@ -172,11 +173,35 @@ impl SglangSchedulerClient {
         }
     }
 
-    /// Build a single SGLang GenerateRequest from OpenAI ChatCompletionRequest
+    /// Build a cross-encoder EmbedRequest scoring one (query, document) pair.
+    /// `token_ids`/`token_type_ids` are the pair encoding; the scheduler
+    /// returns the relevance logit as a one-element embedding.
     #[expect(
         clippy::unused_self,
         reason = "method receiver kept for consistent public API"
     )]
+    pub fn build_rerank_request(
+        &self,
+        request_id: String,
+        query: &str,
+        document: &str,
+        token_ids: Vec<u32>,
+        token_type_ids: Vec<i32>,
+    ) -> proto::EmbedRequest {
+        proto::EmbedRequest {
+            request_id,
+            tokenized: Some(proto::TokenizedInput {
+                original_text: format!("{query}{document}"),
+                input_ids: token_ids,
+            }),
+            token_type_ids,
+            is_cross_encoder: true,
+            texts: vec![query.to_string(), document.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// Build a single SGLang GenerateRequest from OpenAI ChatCompletionRequest
     pub fn build_generate_request_from_chat(
         &self,
         request_id: String,
@@ -185,13 +210,15 @@ impl SglangSchedulerClient {
         token_ids: Vec<u32>,
         options: SglangGenerateRequestOptions,
     ) -> Result<proto::GenerateRequest, String> {
-        Self::build_generate_request_from_chat_parts(
+        let request = Self::build_generate_request_from_chat_parts(
             request_id,
             body,
             processed_text,
             token_ids,
             options,
-        )
+        )?;
+        self.ensure_sampling_seed_supported(&request)?;
+        Ok(request)
     }
 
     fn build_generate_request_from_chat_parts(
@@ -226,10 +253,6 @@ impl SglangSchedulerClient {
     }
 
     /// Build a basic GenerateRequest from the SGLang spec GenerateRequest
-    #[expect(
-        clippy::unused_self,
-        reason = "method receiver kept for consistent public API"
-    )]
     pub fn build_plain_generate_request(
         &self,
         request_id: String,
@@ -237,7 +260,10 @@ impl SglangSchedulerClient {
         original_text: Option<String>,
         token_ids: Vec<u32>,
     ) -> Result<proto::GenerateRequest, String> {
-        Self::build_plain_generate_request_parts(request_id, body, original_text, token_ids)
+        let request =
+            Self::build_plain_generate_request_parts(request_id, body, original_text, token_ids)?;
+        self.ensure_sampling_seed_supported(&request)?;
+        Ok(request)
     }
 
     fn build_plain_generate_request_parts(
@@ -279,10 +305,6 @@ impl SglangSchedulerClient {
     ///
     /// NOTE: This is used by the Harmony router only. The Regular router uses
     /// responses_to_chat() conversion and goes through the chat pipeline.
-    #[expect(
-        clippy::unused_self,
-        reason = "method receiver kept for consistent public API"
-    )]
     pub fn build_generate_request_from_responses(
         &self,
         request_id: String,
@@ -312,9 +334,29 @@ impl SglangSchedulerClient {
             ..Default::default()
         };
 
+        self.ensure_sampling_seed_supported(&grpc_request)?;
         Ok(grpc_request)
     }
 
+    /// Servicers before proto revision 2 ignore `sampling_seed`; refuse a
+    /// seeded request rather than let it sample unseeded.
+    fn ensure_sampling_seed_supported(
+        &self,
+        request: &proto::GenerateRequest,
+    ) -> Result<(), String> {
+        let seeded = request
+            .sampling_params
+            .as_ref()
+            .is_some_and(|params| params.sampling_seed.is_some());
+        if seeded && !self.compat.supports_sglang_sampling_seed() {
+            return Err(format!(
+                "seed is not supported by this SGLang worker (proto revision {})",
+                self.compat.revision()
+            ));
+        }
+        Ok(())
+    }
+
     /// Build gRPC SamplingParams from ChatCompletionRequest
     fn build_grpc_sampling_params_from_chat(
         request: &ChatCompletionRequest,
@@ -345,6 +387,7 @@ impl SglangSchedulerClient {
             no_stop_trim: request.no_stop_trim,
             n: request.n.unwrap_or(1),
             constraint: Self::build_constraint_for_chat(request, tool_call_constraint)?,
+            sampling_seed: request.effective_seed(),
             ..Default::default()
         })
     }
@@ -450,6 +493,7 @@ impl SglangSchedulerClient {
             no_stop_trim: false,
             n: 1, // Responses API doesn't support n>1
             constraint: Self::build_constraint_for_responses(constraint)?,
+            sampling_seed: request.seed,
             ..Default::default()
         })
     }
@@ -481,10 +525,6 @@ impl SglangSchedulerClient {
     }
 
     /// Build a GenerateRequest from CreateMessageRequest (Anthropic Messages API)
-    #[expect(
-        clippy::unused_self,
-        reason = "method receiver kept for consistent public API"
-    )]
     pub fn build_generate_request_from_messages(
         &self,
         request_id: String,
@@ -493,13 +533,15 @@ impl SglangSchedulerClient {
         token_ids: Vec<u32>,
         options: SglangGenerateRequestOptions,
     ) -> Result<proto::GenerateRequest, String> {
-        Self::build_generate_request_from_messages_parts(
+        let request = Self::build_generate_request_from_messages_parts(
             request_id,
             body,
             processed_text,
             token_ids,
             options,
-        )
+        )?;
+        self.ensure_sampling_seed_supported(&request)?;
+        Ok(request)
     }
 
     fn build_generate_request_from_messages_parts(
@@ -559,15 +601,12 @@ impl SglangSchedulerClient {
             no_stop_trim: false,
             n: 1,
             constraint: Self::build_constraint_for_responses(tool_call_constraint)?,
+            sampling_seed: request.seed(),
             ..Default::default()
         })
     }
 
     /// Build a GenerateRequest from CompletionRequest (`/v1/completions`)
-    #[expect(
-        clippy::unused_self,
-        reason = "method receiver kept for consistent public API"
-    )]
     pub fn build_generate_request_from_completion(
         &self,
         request_id: String,
@@ -594,6 +633,7 @@ impl SglangSchedulerClient {
             ..Default::default()
         };
 
+        self.ensure_sampling_seed_supported(&grpc_request)?;
         Ok(grpc_request)
     }
 
@@ -626,6 +666,7 @@ impl SglangSchedulerClient {
             no_stop_trim: request.no_stop_trim,
             n: request.n.unwrap_or(1),
             constraint,
+            sampling_seed: request.effective_seed(),
             ..Default::default()
         })
     }
@@ -744,6 +785,12 @@ impl SglangSchedulerClient {
             sampling.n = n;
         }
 
+        if let Some(seed) = p.sampling_seed {
+            sampling.sampling_seed = Some(i64::try_from(seed).map_err(|_| {
+                format!("sampling_seed {seed} does not fit in a signed 64-bit integer")
+            })?);
+        }
+
         // Handle constraints (exactly one allowed)
         sampling.constraint = Self::build_single_constraint_from_plain(p)?;
 
@@ -986,6 +1033,33 @@ mod tests {
         assert_eq!(disabled_params.top_k, -1);
     }
 
+    #[test]
+    fn test_seeds_are_passed_through() {
+        let completion = CompletionRequest {
+            sampling_seed: Some(7),
+            ..serde_json::from_value(json!({"model": "m", "prompt": "hi"}))
+                .expect("parse completion request")
+        };
+        let params = SglangSchedulerClient::build_grpc_sampling_params_from_completion(&completion)
+            .expect("build sampling params");
+        assert_eq!(params.sampling_seed, Some(7));
+
+        let responses = ResponsesRequest {
+            seed: Some(-3),
+            ..Default::default()
+        };
+        let params =
+            SglangSchedulerClient::build_grpc_sampling_params_from_responses(&responses, None)
+                .expect("build sampling params");
+        assert_eq!(params.sampling_seed, Some(-3));
+
+        let plain = GenerateSamplingParams {
+            sampling_seed: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert!(SglangSchedulerClient::build_sampling_params_from_plain(Some(&plain)).is_err());
+    }
+
     #[test]
     fn test_plain_generate_request_forwards_require_reasoning_bool_only() {
         let enabled: GenerateRequest = serde_json::from_value(json!({
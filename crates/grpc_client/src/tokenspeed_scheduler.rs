@@ -17,11 +17,14 @@ use openai_protocol::{
     messages::CreateMessageRequest,
     responses::ResponsesRequest,
     sampling_params::SamplingParams as GenerateSamplingParams,
+    worker::GrpcTlsConfig,
 };
 use tonic::{transport::Channel, Request};
 use tracing::{debug, warn};
 
-use crate::{AbortOnDropClient, BoxedTraceInjector, NoopTraceInjector};
+use crate::{
+    AbortOnDropClient, AdvertisedProtoRevision, BoxedTraceInjector, NoopTraceInjector, ProtoCompat,
+};
 
 #[expect(clippy::allow_attributes)]
 pub mod tokenspeed_proto {
@@ -44,6 +47,7 @@ pub type AbortOnDropStream =
 pub struct TokenSpeedSchedulerClient {
     client: tokenspeed_proto::token_speed_scheduler_client::TokenSpeedSchedulerClient<Channel>,
     trace_injector: BoxedTraceInjector,
+    compat: ProtoCompat,
 }
 
 impl AbortOnDropClient for TokenSpeedSchedulerClient {
@@ -66,15 +70,26 @@ impl TokenSpeedSchedulerClient {
     pub async fn connect_with_trace_injector(
         endpoint: &str,
         trace_injector: BoxedTraceInjector,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::connect_with_tls(endpoint, None)
+            .await?
+            .with_trace_injector(trace_injector))
+    }
+
+    /// Connect over the worker's TLS configuration, if any.
+    pub async fn connect_with_tls(
+        endpoint: &str,
+        tls: Option<&GrpcTlsConfig>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         debug!("Connecting to TokenSpeed scheduler at {}", endpoint);
-        let channel = crate::channel::connect_channel(endpoint).await?;
+        let channel = crate::channel::connect_channel_with_tls(endpoint, tls).await?;
         let client =
             tokenspeed_proto::token_speed_scheduler_client::TokenSpeedSchedulerClient::new(channel);
 
         Ok(Self {
             client,
-            trace_injector,
+            trace_injector: Arc::new(NoopTraceInjector),
+            compat: ProtoCompat::CURRENT,
         })
     }
 
@@ -84,6 +99,21 @@ impl TokenSpeedSchedulerClient {
         self
     }
 
+    /// Proto revision negotiated with the scheduler. Current until
+    /// [`Self::negotiate_proto`] runs.
+    pub fn proto_compat(&self) -> ProtoCompat {
+        self.compat
+    }
+
+    /// Adopt the proto revision advertised in the scheduler's server info.
+    pub fn negotiate_proto(
+        &mut self,
+        info: &tokenspeed_proto::GetServerInfoResponse,
+    ) -> ProtoCompat {
+        self.compat = ProtoCompat::negotiate(info.proto_revision());
+        self.compat
+    }
+
     /// Submit a generation request.
     pub async fn generate(
         &self,
@@ -1,4 +1,4 @@
-use std::{future::Future, pin::Pin};
+use std::{collections::HashMap, future::Future, pin::Pin};
 
 use openai_protocol::{
     chat::ChatCompletionRequest,
@@ -9,10 +9,11 @@ use openai_protocol::{
     responses::ResponsesRequest,
     sampling_params::SamplingParams as GenerateSamplingParams,
 };
+use serde_json::{Map, Value};
 use tonic::{transport::Channel, Request};
 use tracing::{debug, warn};
 
-use crate::{AbortOnDropClient, BoxedTraceInjector};
+use crate::{AbortOnDropClient, BoxedTraceInjector, ProtoCompat};
 
 // Include the generated protobuf code
 #[expect(clippy::allow_attributes)]
@@ -35,6 +36,7 @@ pub type AbortOnDropStream = crate::AbortOnDropStream<proto::GenerateResponse, T
 pub struct TrtllmServiceClient {
     client: proto::trtllm_service_client::TrtllmServiceClient<Channel>,
     trace_injector: BoxedTraceInjector,
+    compat: ProtoCompat,
 }
 
 impl AbortOnDropClient for TrtllmServiceClient {
@@ -122,8 +124,12 @@ impl TrtllmServiceClient {
         multimodal_input: Option<proto::MultimodalInput>,
         tool_call_constraint: Option<(String, String)>, // (constraint_type, constraint_value)
     ) -> Result<proto::GenerateRequest, String> {
+        reject_logit_bias(body.logit_bias.as_ref())?;
+
         // Build sampling config
-        let sampling_config = Self::build_sampling_config_from_chat(body);
+        let mut sampling_config = Self::build_sampling_config_from_chat(body);
+        let extensions = SamplingExtensions::from_extra(&body.other)?;
+        extensions.apply(&mut sampling_config, None)?;
 
         // Build output config
         let output_config = Self::build_output_config_from_chat(body);
@@ -147,10 +153,10 @@ impl TrtllmServiceClient {
             max_tokens,
             streaming: body.stream,
             stop,
-            stop_token_ids: vec![],
+            stop_token_ids: body.stop_token_ids.clone().unwrap_or_default(),
             ignore_eos: body.ignore_eos,
-            bad: vec![],
-            bad_token_ids: vec![],
+            bad: extensions.bad,
+            bad_token_ids: extensions.bad_token_ids,
             guided_decoding,
             embedding_bias: vec![],
             lora_config: None,
@@ -161,7 +167,7 @@ impl TrtllmServiceClient {
             lookahead_config: None,
             cache_salt_id: None,
             arrival_time: None,
-            include_stop_token_in_output: false,
+            include_stop_token_in_output: body.no_stop_trim,
         };
 
         Ok(grpc_request)
@@ -172,10 +178,6 @@ impl TrtllmServiceClient {
         clippy::unused_self,
         reason = "method receiver kept for consistent public API across gRPC backends"
     )]
-    #[expect(
-        clippy::unnecessary_wraps,
-        reason = "returns Result for API consistency with sglang/vllm backends which can fail"
-    )]
     pub fn build_plain_generate_request(
         &self,
         request_id: String,
@@ -183,7 +185,10 @@ impl TrtllmServiceClient {
         original_text: Option<String>,
         token_ids: Vec<u32>,
     ) -> Result<proto::GenerateRequest, String> {
-        let sampling_config = Self::build_sampling_config_from_plain(body.sampling_params.as_ref());
+        let mut sampling_config =
+            Self::build_sampling_config_from_plain(body.sampling_params.as_ref());
+        let extensions = SamplingExtensions::from_extra(&body.other)?;
+        extensions.apply(&mut sampling_config, None)?;
         let output_config = proto::OutputConfig {
             logprobs: if body.return_logprob.unwrap_or(false) {
                 Some(body.top_logprobs_num.unwrap_or(0))
@@ -226,14 +231,18 @@ impl TrtllmServiceClient {
             max_tokens,
             streaming: body.stream,
             stop,
-            stop_token_ids: vec![],
+            stop_token_ids: body
+                .sampling_params
+                .as_ref()
+                .and_then(|p| p.stop_token_ids.clone())
+                .unwrap_or_default(),
             ignore_eos: body
                 .sampling_params
                 .as_ref()
                 .and_then(|p| p.ignore_eos)
                 .unwrap_or(false),
-            bad: vec![],
-            bad_token_ids: vec![],
+            bad: extensions.bad,
+            bad_token_ids: extensions.bad_token_ids,
             guided_decoding,
             embedding_bias: vec![],
             lora_config: None,
@@ -244,7 +253,11 @@ impl TrtllmServiceClient {
             lookahead_config: None,
             cache_salt_id: None,
             arrival_time: None,
-            include_stop_token_in_output: false,
+            include_stop_token_in_output: body
+                .sampling_params
+                .as_ref()
+                .and_then(|p| p.no_stop_trim)
+                .unwrap_or(false),
         };
 
         Ok(grpc_request)
@@ -289,7 +302,7 @@ impl TrtllmServiceClient {
             output_config: Some(output_config),
             max_tokens,
             streaming: body.stream.unwrap_or(false),
-            stop: vec![], // Does not pass through body.stop yet (follow-up fix)
+            stop: Self::extract_stop_strings(body.stop.as_ref()),
             stop_token_ids: vec![],
             ignore_eos: false,
             bad: vec![],
@@ -334,9 +347,9 @@ impl TrtllmServiceClient {
             top_p_min: None,
             top_p_reset_ids: None,
             top_p_decay: None,
-            seed: None,
+            seed: request.effective_seed().map(|s| s as u64),
             temperature: Some(request.temperature.unwrap_or(1.0)),
-            min_tokens: None,
+            min_tokens: request.min_tokens,
             beam_search_diversity_rate: None,
             repetition_penalty: Some(request.repetition_penalty.unwrap_or(1.0)),
             presence_penalty: request.presence_penalty,
@@ -447,7 +460,7 @@ impl TrtllmServiceClient {
             top_p_min: None,
             top_p_reset_ids: None,
             top_p_decay: None,
-            seed: None,
+            seed: request.seed.map(|s| s as u64),
             temperature: Some(request.temperature.unwrap_or(1.0)),
             min_tokens: None,
             beam_search_diversity_rate: None,
@@ -558,7 +571,7 @@ impl TrtllmServiceClient {
             top_p_min: None,
             top_p_reset_ids: None,
             top_p_decay: None,
-            seed: None,
+            seed: request.seed().map(|s| s as u64),
             temperature: Some(request.temperature.unwrap_or(1.0) as f32),
             min_tokens: None,
             beam_search_diversity_rate: None,
@@ -586,7 +599,11 @@ impl TrtllmServiceClient {
         original_text: String,
         token_ids: Vec<u32>,
     ) -> Result<proto::GenerateRequest, String> {
-        let sampling_config = Self::build_sampling_config_from_completion(body);
+        reject_logit_bias(body.logit_bias.as_ref())?;
+
+        let mut sampling_config = Self::build_sampling_config_from_completion(body);
+        let extensions = SamplingExtensions::from_extra(&body.other)?;
+        extensions.apply(&mut sampling_config, body.best_of)?;
         let output_config = proto::OutputConfig {
             logprobs: body.logprobs.map(|v| v.min(5) as i32),
             prompt_logprobs: None,
@@ -619,8 +636,8 @@ impl TrtllmServiceClient {
             stop,
             stop_token_ids: body.stop_token_ids.clone().unwrap_or_default(),
             ignore_eos: body.ignore_eos,
-            bad: vec![],
-            bad_token_ids: vec![],
+            bad: extensions.bad,
+            bad_token_ids: extensions.bad_token_ids,
             guided_decoding,
             embedding_bias: vec![],
             lora_config: None,
@@ -646,7 +663,7 @@ impl TrtllmServiceClient {
             top_p_min: None,
             top_p_reset_ids: None,
             top_p_decay: None,
-            seed: request.seed.map(|s| s as u64),
+            seed: request.effective_seed().map(|s| s as u64),
             temperature: Some(request.temperature.unwrap_or(1.0)),
             min_tokens: request.min_tokens,
             beam_search_diversity_rate: None,
@@ -755,6 +772,7 @@ impl TrtllmServiceClient {
         if let Some(n) = p.n {
             config.num_return_sequences = n;
         }
+        config.seed = p.sampling_seed;
 
         config
     }
@@ -784,10 +802,162 @@ impl TrtllmServiceClient {
     }
 }
 
+/// Reject OpenAI `logit_bias`. TensorRT-LLM only takes a dense
+/// vocab-sized `embedding_bias`, which the gateway cannot build without
+/// knowing the model's vocabulary size.
+fn reject_logit_bias(logit_bias: Option<&HashMap<String, f32>>) -> Result<(), String> {
+    match logit_bias {
+        Some(bias) if !bias.is_empty() => Err(
+            "logit_bias is not supported by the TensorRT-LLM backend; use bad_token_ids to ban tokens"
+                .to_string(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// TensorRT-LLM sampling knobs outside the OpenAI schema, read from a
+/// request's extra fields under the names `trtllm-serve` accepts.
+#[derive(Debug, Default)]
+struct SamplingExtensions {
+    bad: Vec<String>,
+    bad_token_ids: Vec<u32>,
+    use_beam_search: bool,
+    length_penalty: Option<f32>,
+    early_stopping: Option<i32>,
+    beam_search_diversity_rate: Option<f32>,
+    no_repeat_ngram_size: Option<i32>,
+}
+
+impl SamplingExtensions {
+    fn from_extra(extra: &Map<String, Value>) -> Result<Self, String> {
+        let mut ext = Self::default();
+        for (key, value) in extra {
+            match key.as_str() {
+                "bad" | "bad_words" => ext.bad.extend(string_list(key, value)?),
+                "bad_token_ids" => ext.bad_token_ids = token_id_list(key, value)?,
+                "use_beam_search" => {
+                    ext.use_beam_search = value
+                        .as_bool()
+                        .ok_or_else(|| format!("{key} must be a boolean"))?;
+                }
+                "length_penalty" => ext.length_penalty = Some(float_value(key, value)?),
+                // trtllm-serve takes a bool; the executor takes 0/1 or a
+                // beam count ("stop after N finished beams").
+                "early_stopping" => {
+                    ext.early_stopping = Some(match value {
+                        Value::Bool(b) => i32::from(*b),
+                        _ => int_value(key, value)?,
+                    });
+                }
+                "beam_search_diversity_rate" => {
+                    ext.beam_search_diversity_rate = Some(float_value(key, value)?);
+                }
+                "no_repeat_ngram_size" => {
+                    ext.no_repeat_ngram_size = Some(int_value(key, value)?);
+                }
+                _ => {}
+            }
+        }
+        Ok(ext)
+    }
+
+    /// Apply beam search and n-gram settings to `config`.
+    ///
+    /// With beam search the beam width covers `best_of` (or the number of
+    /// returned sequences when larger). Beam-only knobs and `best_of` without
+    /// beam search are rejected: TensorRT-LLM would otherwise ignore them.
+    fn apply(
+        &self,
+        config: &mut proto::SamplingConfig,
+        best_of: Option<u32>,
+    ) -> Result<(), String> {
+        if self.use_beam_search {
+            let width = best_of.unwrap_or(0).max(config.num_return_sequences).max(1);
+            config.beam_width =
+                i32::try_from(width).map_err(|_| format!("beam width {width} is too large"))?;
+        } else if self.length_penalty.is_some()
+            || self.early_stopping.is_some()
+            || self.beam_search_diversity_rate.is_some()
+        {
+            return Err(
+                "length_penalty, early_stopping and beam_search_diversity_rate require use_beam_search"
+                    .to_string(),
+            );
+        } else if best_of.is_some_and(|b| b > config.num_return_sequences) {
+            return Err(
+                "best_of is only supported together with use_beam_search on the TensorRT-LLM backend"
+                    .to_string(),
+            );
+        }
+
+        config.length_penalty = self.length_penalty;
+        config.early_stopping = self.early_stopping;
+        config.beam_search_diversity_rate = self.beam_search_diversity_rate;
+        config.no_repeat_ngram_size = self.no_repeat_ngram_size;
+        Ok(())
+    }
+}
+
+fn string_list(key: &str, value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::String(s) => Ok(vec![s.clone()]),
+        Value::Array(items) => items
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("{key} must be a string or an array of strings")),
+        _ => Err(format!("{key} must be a string or an array of strings")),
+    }
+}
+
+fn token_id_list(key: &str, value: &Value) -> Result<Vec<u32>, String> {
+    value
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|v| v.as_u64().and_then(|id| u32::try_from(id).ok()))
+                .collect::<Option<_>>()
+        })
+        .ok_or_else(|| format!("{key} must be an array of token ids"))
+}
+
+fn float_value(key: &str, value: &Value) -> Result<f32, String> {
+    value
+        .as_f64()
+        .map(|v| v as f32)
+        .ok_or_else(|| format!("{key} must be a number"))
+}
+
+fn int_value(key: &str, value: &Value) -> Result<i32, String> {
+    value
+        .as_i64()
+        .and_then(|v| i32::try_from(v).ok())
+        .ok_or_else(|| format!("{key} must be an integer"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Client over a lazy channel; request builders never touch the network.
+    fn test_client() -> TrtllmServiceClient {
+        TrtllmServiceClient {
+            client: proto::trtllm_service_client::TrtllmServiceClient::new(
+                Channel::from_static("http://127.0.0.1:1").connect_lazy(),
+            ),
+            trace_injector: std::sync::Arc::new(crate::NoopTraceInjector),
+            compat: ProtoCompat::CURRENT,
+        }
+    }
+
+    fn completion_request(body: serde_json::Value) -> CompletionRequest {
+        let mut body = body;
+        body["model"] = serde_json::json!("m");
+        body["prompt"] = serde_json::json!("hi");
+        serde_json::from_value(body).unwrap()
+    }
+
     #[test]
     fn test_proto_types_compilation() {
         let _health_req = proto::HealthCheckRequest {};
@@ -835,6 +1005,147 @@ mod tests {
         assert_eq!(config.top_p, Some(0.9));
     }
 
+    #[test]
+    fn test_sampling_seed_is_passed_through() {
+        let chat = ChatCompletionRequest {
+            sampling_seed: Some(11),
+            ..Default::default()
+        };
+        let config = TrtllmServiceClient::build_sampling_config_from_chat(&chat);
+        assert_eq!(config.seed, Some(11));
+
+        let plain = GenerateSamplingParams {
+            sampling_seed: Some(12),
+            ..Default::default()
+        };
+        let config = TrtllmServiceClient::build_sampling_config_from_plain(Some(&plain));
+        assert_eq!(config.seed, Some(12));
+        assert_eq!(
+            TrtllmServiceClient::build_sampling_config_from_plain(None).seed,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_request_maps_extended_sampling_knobs() {
+        let mut chat = ChatCompletionRequest {
+            n: Some(2),
+            min_tokens: Some(4),
+            stop_token_ids: Some(vec![7, 8]),
+            no_stop_trim: true,
+            ..Default::default()
+        };
+        chat.other = serde_json::json!({
+            "bad_words": ["foo", "bar"],
+            "bad_token_ids": [13],
+            "use_beam_search": true,
+            "length_penalty": 0.8,
+            "early_stopping": true,
+            "no_repeat_ngram_size": 3,
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        let client = test_client();
+        let req = client
+            .build_generate_request_from_chat(
+                "r".to_string(),
+                &chat,
+                String::new(),
+                vec![1],
+                None,
+                None,
+            )
+            .unwrap();
+        let cfg = req.sampling_config.unwrap();
+
+        assert_eq!(cfg.beam_width, 2);
+        assert_eq!(cfg.num_return_sequences, 2);
+        assert_eq!(cfg.min_tokens, Some(4));
+        assert_eq!(cfg.length_penalty, Some(0.8));
+        assert_eq!(cfg.early_stopping, Some(1));
+        assert_eq!(cfg.no_repeat_ngram_size, Some(3));
+        assert_eq!(req.bad, vec!["foo", "bar"]);
+        assert_eq!(req.bad_token_ids, vec![13]);
+        assert_eq!(req.stop_token_ids, vec![7, 8]);
+        assert!(req.include_stop_token_in_output);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_sampling_options_are_rejected() {
+        let client = test_client();
+
+        let chat = ChatCompletionRequest {
+            logit_bias: Some(HashMap::from([("42".to_string(), -100.0)])),
+            ..Default::default()
+        };
+        let err = client
+            .build_generate_request_from_chat(
+                "r".to_string(),
+                &chat,
+                String::new(),
+                vec![1],
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert!(err.contains("logit_bias"));
+
+        let mut chat = ChatCompletionRequest::default();
+        chat.other
+            .insert("length_penalty".to_string(), serde_json::json!(1.1));
+        let err = client
+            .build_generate_request_from_chat(
+                "r".to_string(),
+                &chat,
+                String::new(),
+                vec![1],
+                None,
+                None,
+            )
+            .unwrap_err();
+        assert!(err.contains("use_beam_search"));
+
+        let completion = completion_request(serde_json::json!({"best_of": 3}));
+        let err = client
+            .build_generate_request_from_completion(
+                "r".to_string(),
+                &completion,
+                String::new(),
+                vec![1],
+            )
+            .unwrap_err();
+        assert!(err.contains("best_of"));
+    }
+
+    #[tokio::test]
+    async fn test_completion_best_of_sets_beam_width() {
+        let completion = completion_request(serde_json::json!({
+            "best_of": 4,
+            "n": 2,
+            "use_beam_search": true,
+        }));
+        let req = test_client()
+            .build_generate_request_from_completion(
+                "r".to_string(),
+                &completion,
+                String::new(),
+                vec![1],
+            )
+            .unwrap();
+        let cfg = req.sampling_config.unwrap();
+        assert_eq!(cfg.beam_width, 4);
+        assert_eq!(cfg.num_return_sequences, 2);
+    }
+
+    #[test]
+    fn test_sampling_extensions_reject_malformed_values() {
+        let extra = serde_json::json!({"bad_token_ids": ["x"]});
+        let err = SamplingExtensions::from_extra(extra.as_object().unwrap()).unwrap_err();
+        assert!(err.contains("bad_token_ids"));
+    }
+
     #[test]
     fn test_health_check_request() {
         let _health_req = proto::HealthCheckRequest {};
@@ -12,7 +12,7 @@ use openai_protocol::{
 use tonic::{transport::Channel, Request};
 use tracing::{debug, warn};
 
-use crate::{AbortOnDropClient, BoxedTraceInjector};
+use crate::{AbortOnDropClient, BoxedTraceInjector, ProtoCompat};
 
 // Include the generated protobuf code
 #[expect(clippy::allow_attributes)]
@@ -30,6 +30,7 @@ pub type AbortOnDropStream = crate::AbortOnDropStream<proto::GenerateResponse, V
 pub struct VllmEngineClient {
     client: proto::vllm_engine_client::VllmEngineClient<Channel>,
     trace_injector: BoxedTraceInjector,
+    compat: ProtoCompat,
 }
 
 impl AbortOnDropClient for VllmEngineClient {
@@ -55,8 +56,9 @@ impl VllmEngineClient {
     /// unnecessary abort RPCs.
     pub async fn generate(
         &self,
-        req: proto::GenerateRequest,
+        mut req: proto::GenerateRequest,
     ) -> Result<AbortOnDropStream, tonic::Status> {
+        self.compat.shim_vllm_generate_request(&mut req);
         let request_id = req.request_id.clone();
         let mut client = self.client.clone();
         let mut request = Request::new(req);
@@ -224,10 +226,6 @@ impl VllmEngineClient {
     }
 
     /// Build gRPC SamplingParams from ChatCompletionRequest
-    #[expect(
-        deprecated,
-        reason = "ChatCompletionRequest.seed is marked Legacy by openai-protocol, but vLLM still honors it"
-    )]
     fn build_grpc_sampling_params_from_chat(
         request: &ChatCompletionRequest,
         tool_call_constraint: Option<(String, String)>,
@@ -264,13 +262,7 @@ impl VllmEngineClient {
             ignore_eos: request.ignore_eos,
             n: request.n.unwrap_or(1),
             logprobs,
-            // Proto seed is i32 (line 48 of generated vllm.grpc.engine.rs);
-            // OpenAI request seed is i64. Saturating cast keeps "set" vs
-            // "unset" distinction (None stays None — vLLM will pick a random
-            // seed itself, matching prior behaviour).
-            seed: request
-                .seed
-                .map(|s| s.clamp(i32::MIN as i64, i32::MAX as i64) as i32),
+            seed: request.effective_seed().map(saturating_seed),
             constraint: Self::build_constraint_for_chat(request, tool_call_constraint)?,
             ..Default::default()
         })
@@ -374,6 +366,7 @@ impl VllmEngineClient {
             spaces_between_special_tokens: true,
             ignore_eos: false,
             n: 1, // Responses API doesn't support n>1
+            seed: request.seed.map(saturating_seed),
             constraint: Self::build_constraint_for_responses(constraint)?,
             ..Default::default()
         })
@@ -466,6 +459,7 @@ impl VllmEngineClient {
             ignore_eos: false,
             n: 1,
             logprobs: None,
+            seed: request.seed().map(saturating_seed),
             constraint: Self::build_constraint_for_responses(tool_call_constraint)?,
             ..Default::default()
         })
@@ -571,9 +565,7 @@ impl VllmEngineClient {
             include_stop_str_in_output: request.no_stop_trim,
             n: request.n.unwrap_or(1),
             logprobs,
-            seed: request
-                .seed
-                .map(|s| s.clamp(i32::MIN as i64, i32::MAX as i64) as i32),
+            seed: request.effective_seed().map(saturating_seed),
             constraint,
             ..Default::default()
         })
@@ -703,6 +695,10 @@ impl VllmEngineClient {
             sampling.n = n;
         }
 
+        if let Some(seed) = p.sampling_seed {
+            sampling.seed = Some(seed.min(i32::MAX as u64) as i32);
+        }
+
         // Handle constraints (exactly one allowed)
         sampling.constraint = Self::build_single_constraint_from_plain(p)?;
 
@@ -710,6 +706,13 @@ impl VllmEngineClient {
     }
 }
 
+/// Proto seed is i32 while OpenAI request seeds are i64. Saturating keeps the
+/// "set" vs "unset" distinction (None stays None — vLLM picks a random seed
+/// itself).
+fn saturating_seed(seed: i64) -> i32 {
+    seed.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
 // ---------------------------------------------------------------------------
 // Proto → protocol type conversions (load metrics)
 // ---------------------------------------------------------------------------
@@ -989,6 +992,26 @@ mod tests {
         assert_eq!(huge_params.seed, Some(i32::MAX));
     }
 
+    #[test]
+    fn test_sampling_seed_extension_is_passed_through() {
+        // `sampling_seed` is the fallback when the OpenAI `seed` is absent.
+        let request = CompletionRequest {
+            sampling_seed: Some(7),
+            ..minimal_completion_request()
+        };
+        let params = VllmEngineClient::build_grpc_sampling_params_from_completion(&request)
+            .expect("build sampling params");
+        assert_eq!(params.seed, Some(7));
+
+        let plain = GenerateSamplingParams {
+            sampling_seed: Some(u64::MAX),
+            ..Default::default()
+        };
+        let params = VllmEngineClient::build_sampling_params_from_plain(Some(&plain))
+            .expect("build sampling params");
+        assert_eq!(params.seed, Some(i32::MAX));
+    }
+
     #[test]
     fn test_embed_request() {
         let embed_req = proto::EmbedRequest {
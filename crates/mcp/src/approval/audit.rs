@@ -13,6 +13,8 @@ const DEFAULT_MAX_ENTRIES: usize = 10000;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DecisionSource {
     UserInteractive,
+    /// An earlier interactive decision that is still in effect.
+    CachedUserDecision,
     PolicyEngine,
     ExplicitToolPolicy,
     ServerPolicy,
@@ -0,0 +1,733 @@
+//! Expression conditions for policy rules.
+//!
+//! A small CEL-like language evaluated against a tool call:
+//!
+//! ```text
+//! destructive && args.path.startsWith("/prod")
+//! tenant.id in ["acme", "globex"] && time.hour >= 18
+//! ```
+//!
+//! Identifiers:
+//! - `server`, `tool`: server key and tool name
+//! - `args`: tool call arguments
+//! - `tenant`: `tenant.id`, `tenant.session`
+//! - `annotations`, plus the shorthands `read_only`, `destructive`,
+//!   `idempotent` and `open_world`
+//! - `time`: `time.hour`, `time.minute`, `time.weekday` (UTC, Monday = 1)
+//!
+//! Operators are `!`, `-`, `&&`, `||`, `==`, `!=`, `<`, `<=`, `>`, `>=` and
+//! `in`; strings support `startsWith`, `endsWith`, `contains`, `matches` and
+//! `size`. Missing fields evaluate to `null`. Unknown identifiers and
+//! methods, wrong arities and invalid regexes are rejected at compile time.
+
+use std::fmt;
+
+use chrono::{Datelike, Timelike};
+use regex::Regex;
+use serde_json::{json, Number, Value};
+
+use super::policy::RuleContext;
+
+/// A compiled policy expression.
+#[derive(Debug, Clone)]
+pub struct PolicyExpression {
+    source: String,
+    root: Expr,
+}
+
+impl PolicyExpression {
+    /// Parse and validate an expression.
+    pub fn compile(source: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(ExpressionError::new(format!("unexpected {token}")));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate the expression; non-boolean results are errors.
+    pub fn evaluate(&self, ctx: &RuleContext<'_>) -> Result<bool, ExpressionError> {
+        match eval(&self.root, ctx)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(ExpressionError::new(format!(
+                "expression returned {}, expected bool",
+                type_name(&other)
+            ))),
+        }
+    }
+}
+
+/// Compile or evaluation error in a policy expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionError(String);
+
+impl ExpressionError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(Number),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{name}'"),
+            Token::Str(s) => write!(f, "string {s:?}"),
+            Token::Num(n) => write!(f, "number {n}"),
+            Token::Op(op) => write!(f, "'{op}'"),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "-", "(", ")", "[", "]", ".", ",",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(source[start..end].to_string()));
+        } else if c.is_ascii_digit() {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            let text = &source[start..end];
+            let n = match text.parse::<u64>() {
+                Ok(n) => Number::from(n),
+                Err(_) => text
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .ok_or_else(|| ExpressionError::new(format!("invalid number '{text}'")))?,
+            };
+            tokens.push(Token::Num(n));
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, q)) if q == c => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => s.push('\n'),
+                        Some((_, 't')) => s.push('\t'),
+                        Some((_, e)) => s.push(e),
+                        None => return Err(ExpressionError::new("unterminated string")),
+                    },
+                    Some((_, ch)) => s.push(ch),
+                    None => return Err(ExpressionError::new("unterminated string")),
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else {
+            let rest = &source[start..];
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| ExpressionError::new(format!("unexpected character '{c}'")))?;
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    Server,
+    Tool,
+    Args,
+    Tenant,
+    Annotations,
+    Time,
+    ReadOnly,
+    Destructive,
+    Idempotent,
+    OpenWorld,
+}
+
+impl Var {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "server" => Var::Server,
+            "tool" => Var::Tool,
+            "args" => Var::Args,
+            "tenant" => Var::Tenant,
+            "annotations" => Var::Annotations,
+            "time" => Var::Time,
+            "read_only" => Var::ReadOnly,
+            "destructive" => Var::Destructive,
+            "idempotent" => Var::Idempotent,
+            "open_world" => Var::OpenWorld,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Method {
+    StartsWith,
+    EndsWith,
+    Contains,
+    Matches(Regex),
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    List(Vec<Expr>),
+    Var(Var),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Method, Vec<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), ExpressionError> {
+        if self.eat(op) {
+            return Ok(());
+        }
+        Err(match self.peek() {
+            Some(token) => ExpressionError::new(format!("expected '{op}', found {token}")),
+            None => ExpressionError::new(format!("expected '{op}', found end of expression")),
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ExpressionError> {
+        let mut lhs = self.parse_and()?;
+        while self.eat("||") {
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ExpressionError> {
+        let mut lhs = self.parse_comparison()?;
+        while self.eat("&&") {
+            let rhs = self.parse_comparison()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ExpressionError> {
+        let lhs = self.parse_unary()?;
+        let op = match self.peek() {
+            Some(Token::Op("==")) => BinOp::Eq,
+            Some(Token::Op("!=")) => BinOp::Ne,
+            Some(Token::Op("<")) => BinOp::Lt,
+            Some(Token::Op("<=")) => BinOp::Le,
+            Some(Token::Op(">")) => BinOp::Gt,
+            Some(Token::Op(">=")) => BinOp::Ge,
+            Some(Token::Ident(name)) if name == "in" => BinOp::In,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_unary()?;
+        Ok(Expr::Binary(Box::new(lhs), op, Box::new(rhs)))
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ExpressionError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, ExpressionError> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            if self.eat(".") {
+                let name = match self.next() {
+                    Some(Token::Ident(name)) => name,
+                    Some(token) => {
+                        return Err(ExpressionError::new(format!(
+                            "expected field name, found {token}"
+                        )))
+                    }
+                    None => return Err(ExpressionError::new("expected field name")),
+                };
+                if self.eat("(") {
+                    let args = self.parse_list(")")?;
+                    expr = Self::method_call(expr, &name, args)?;
+                } else {
+                    expr = Expr::Field(Box::new(expr), name);
+                }
+            } else if self.eat("[") {
+                let index = self.parse_or()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ExpressionError> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Literal(Value::Number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ => Var::parse(&name)
+                    .map(Expr::Var)
+                    .ok_or_else(|| ExpressionError::new(format!("unknown identifier '{name}'"))),
+            },
+            Some(Token::Op("(")) => {
+                let expr = self.parse_or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Op("[")) => Ok(Expr::List(self.parse_list("]")?)),
+            Some(token) => Err(ExpressionError::new(format!("unexpected {token}"))),
+            None => Err(ExpressionError::new("unexpected end of expression")),
+        }
+    }
+
+    /// Parse comma-separated expressions up to the closing `close`.
+    fn parse_list(&mut self, close: &str) -> Result<Vec<Expr>, ExpressionError> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.parse_or()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn method_call(receiver: Expr, name: &str, args: Vec<Expr>) -> Result<Expr, ExpressionError> {
+        let (method, arity) = match name {
+            "startsWith" => (Method::StartsWith, 1),
+            "endsWith" => (Method::EndsWith, 1),
+            "contains" => (Method::Contains, 1),
+            "size" => (Method::Size, 0),
+            "matches" => {
+                let pattern = match args.as_slice() {
+                    [Expr::Literal(Value::String(pattern))] => pattern,
+                    _ => {
+                        return Err(ExpressionError::new(
+                            "matches() takes a single string literal",
+                        ))
+                    }
+                };
+                let regex = Regex::new(pattern).map_err(|e| {
+                    ExpressionError::new(format!("invalid regex in matches(): {e}"))
+                })?;
+                return Ok(Expr::Call(
+                    Box::new(receiver),
+                    Method::Matches(regex),
+                    Vec::new(),
+                ));
+            }
+            _ => return Err(ExpressionError::new(format!("unknown method '{name}'"))),
+        };
+        if args.len() != arity {
+            return Err(ExpressionError::new(format!(
+                "{name}() takes {arity} argument(s), got {}",
+                args.len()
+            )));
+        }
+        Ok(Expr::Call(Box::new(receiver), method, args))
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "map",
+    }
+}
+
+fn type_error(op: &str, value: &Value) -> ExpressionError {
+    ExpressionError::new(format!("'{op}' not supported on {}", type_name(value)))
+}
+
+fn resolve_var(var: Var, ctx: &RuleContext<'_>) -> Value {
+    let hints = ctx.hints;
+    match var {
+        Var::Server => Value::String(ctx.server_key.to_string()),
+        Var::Tool => Value::String(ctx.tool_name.to_string()),
+        Var::Args => ctx.arguments.cloned().unwrap_or_else(|| json!({})),
+        Var::Tenant => json!({
+            "id": ctx.tenant_ctx.tenant_id.as_str(),
+            "session": ctx.tenant_ctx.session_id.as_str(),
+        }),
+        Var::Annotations => json!({
+            "read_only": hints.read_only,
+            "destructive": hints.destructive,
+            "idempotent": hints.idempotent,
+            "open_world": hints.open_world,
+        }),
+        Var::Time => json!({
+            "hour": ctx.now.hour(),
+            "minute": ctx.now.minute(),
+            "weekday": ctx.now.weekday().number_from_monday(),
+        }),
+        Var::ReadOnly => Value::Bool(hints.read_only),
+        Var::Destructive => Value::Bool(hints.destructive),
+        Var::Idempotent => Value::Bool(hints.idempotent),
+        Var::OpenWorld => Value::Bool(hints.open_world),
+    }
+}
+
+fn eval(expr: &Expr, ctx: &RuleContext<'_>) -> Result<Value, ExpressionError> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::List(items) => items
+            .iter()
+            .map(|item| eval(item, ctx))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Expr::Var(var) => Ok(resolve_var(*var, ctx)),
+        Expr::Field(target, name) => Ok(match eval(target, ctx)? {
+            Value::Object(mut map) => map.remove(name).unwrap_or(Value::Null),
+            _ => Value::Null,
+        }),
+        Expr::Index(target, index) => {
+            let target = eval(target, ctx)?;
+            let index = eval(index, ctx)?;
+            Ok(match (target, &index) {
+                (Value::Object(mut map), Value::String(key)) => {
+                    map.remove(key).unwrap_or(Value::Null)
+                }
+                (Value::Array(mut items), Value::Number(n)) => n
+                    .as_u64()
+                    .and_then(|i| usize::try_from(i).ok())
+                    .filter(|i| *i < items.len())
+                    .map(|i| items.swap_remove(i))
+                    .unwrap_or(Value::Null),
+                _ => Value::Null,
+            })
+        }
+        Expr::Call(receiver, method, args) => {
+            let receiver = eval(receiver, ctx)?;
+            let arg = args.first().map(|arg| eval(arg, ctx)).transpose()?;
+            call_method(&receiver, method, arg.as_ref())
+        }
+        Expr::Not(inner) => match eval(inner, ctx)? {
+            Value::Bool(b) => Ok(Value::Bool(!b)),
+            other => Err(type_error("!", &other)),
+        },
+        Expr::Neg(inner) => match eval(inner, ctx)? {
+            Value::Number(n) => n
+                .as_f64()
+                .and_then(|n| Number::from_f64(-n))
+                .map(Value::Number)
+                .ok_or_else(|| ExpressionError::new("invalid number")),
+            other => Err(type_error("-", &other)),
+        },
+        Expr::And(lhs, rhs) => {
+            if !eval_bool(lhs, ctx, "&&")? {
+                return Ok(Value::Bool(false));
+            }
+            eval_bool(rhs, ctx, "&&").map(Value::Bool)
+        }
+        Expr::Or(lhs, rhs) => {
+            if eval_bool(lhs, ctx, "||")? {
+                return Ok(Value::Bool(true));
+            }
+            eval_bool(rhs, ctx, "||").map(Value::Bool)
+        }
+        Expr::Binary(lhs, op, rhs) => {
+            let lhs = eval(lhs, ctx)?;
+            let rhs = eval(rhs, ctx)?;
+            compare(&lhs, *op, &rhs).map(Value::Bool)
+        }
+    }
+}
+
+fn eval_bool(expr: &Expr, ctx: &RuleContext<'_>, op: &str) -> Result<bool, ExpressionError> {
+    match eval(expr, ctx)? {
+        Value::Bool(b) => Ok(b),
+        other => Err(type_error(op, &other)),
+    }
+}
+
+fn values_equal(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => lhs == rhs,
+    }
+}
+
+fn compare(lhs: &Value, op: BinOp, rhs: &Value) -> Result<bool, ExpressionError> {
+    use std::cmp::Ordering;
+
+    let ordering = |lhs: &Value, rhs: &Value| -> Result<Ordering, ExpressionError> {
+        match (lhs, rhs) {
+            (Value::Number(a), Value::Number(b)) => a
+                .as_f64()
+                .zip(b.as_f64())
+                .and_then(|(a, b)| a.partial_cmp(&b))
+                .ok_or_else(|| ExpressionError::new("numbers are not comparable")),
+            (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+            _ => Err(ExpressionError::new(format!(
+                "cannot compare {} with {}",
+                type_name(lhs),
+                type_name(rhs)
+            ))),
+        }
+    };
+
+    match op {
+        BinOp::Eq => Ok(values_equal(lhs, rhs)),
+        BinOp::Ne => Ok(!values_equal(lhs, rhs)),
+        BinOp::Lt => Ok(ordering(lhs, rhs)?.is_lt()),
+        BinOp::Le => Ok(ordering(lhs, rhs)?.is_le()),
+        BinOp::Gt => Ok(ordering(lhs, rhs)?.is_gt()),
+        BinOp::Ge => Ok(ordering(lhs, rhs)?.is_ge()),
+        BinOp::In => match rhs {
+            Value::Array(items) => Ok(items.iter().any(|item| values_equal(lhs, item))),
+            Value::Object(map) => match lhs {
+                Value::String(key) => Ok(map.contains_key(key)),
+                other => Err(type_error("in", other)),
+            },
+            Value::String(haystack) => match lhs {
+                Value::String(needle) => Ok(haystack.contains(needle.as_str())),
+                other => Err(type_error("in", other)),
+            },
+            other => Err(type_error("in", other)),
+        },
+    }
+}
+
+fn call_method(
+    receiver: &Value,
+    method: &Method,
+    arg: Option<&Value>,
+) -> Result<Value, ExpressionError> {
+    let string_arg = |name: &str| match arg {
+        Some(Value::String(s)) => Ok(s.as_str()),
+        Some(other) => Err(ExpressionError::new(format!(
+            "{name}() expects a string argument, got {}",
+            type_name(other)
+        ))),
+        None => Err(ExpressionError::new(format!(
+            "{name}() expects an argument"
+        ))),
+    };
+
+    let result = match (method, receiver) {
+        (Method::StartsWith, Value::String(s)) => s.starts_with(string_arg("startsWith")?),
+        (Method::EndsWith, Value::String(s)) => s.ends_with(string_arg("endsWith")?),
+        (Method::Contains, Value::String(s)) => s.contains(string_arg("contains")?),
+        (Method::Contains, Value::Array(items)) => {
+            let needle = arg.unwrap_or(&Value::Null);
+            items.iter().any(|item| values_equal(item, needle))
+        }
+        (Method::Matches(regex), Value::String(s)) => regex.is_match(s),
+        (Method::Size, Value::String(s)) => return Ok(json!(s.chars().count())),
+        (Method::Size, Value::Array(items)) => return Ok(json!(items.len())),
+        (Method::Size, Value::Object(map)) => return Ok(json!(map.len())),
+        (method, other) => {
+            let name = match method {
+                Method::StartsWith => "startsWith",
+                Method::EndsWith => "endsWith",
+                Method::Contains => "contains",
+                Method::Matches(_) => "matches",
+                Method::Size => "size",
+            };
+            return Err(type_error(name, other));
+        }
+    };
+    Ok(Value::Bool(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{tenant::TenantContext, ToolAnnotations};
+
+    fn check(source: &str, args: Value, hints: &ToolAnnotations) -> Result<bool, ExpressionError> {
+        let tenant = TenantContext::new("acme").with_session("s1");
+        let ctx = RuleContext {
+            server_key: "files",
+            tool_name: "delete_file",
+            hints,
+            arguments: Some(&args),
+            tenant_ctx: &tenant,
+            now: Utc.with_ymd_and_hms(2026, 3, 4, 22, 30, 0).unwrap(),
+        };
+        PolicyExpression::compile(source)?.evaluate(&ctx)
+    }
+
+    #[test]
+    fn test_argument_conditions() {
+        let destructive = ToolAnnotations::new().with_destructive(true);
+        let expr = r#"destructive && args.path.startsWith("/prod")"#;
+
+        assert!(check(expr, json!({ "path": "/prod/db" }), &destructive).unwrap());
+        assert!(!check(expr, json!({ "path": "/tmp/x" }), &destructive).unwrap());
+        assert!(!check(expr, json!({ "path": "/prod/db" }), &ToolAnnotations::new()).unwrap());
+        // Missing argument: the method call fails instead of matching.
+        assert!(check(expr, json!({}), &destructive).is_err());
+
+        let hints = ToolAnnotations::new();
+        assert!(check(
+            "args.paths[1] == 'b'",
+            json!({ "paths": ["a", "b"] }),
+            &hints
+        )
+        .unwrap());
+        assert!(check(
+            "args.count >= 10 && args.count < 20",
+            json!({ "count": 12 }),
+            &hints
+        )
+        .unwrap());
+        assert!(check("args.missing == null", json!({}), &hints).unwrap());
+        assert!(check("'force' in args", json!({ "force": true }), &hints).unwrap());
+        assert!(check(
+            "args.name.matches('^[a-z]+$')",
+            json!({ "name": "abc" }),
+            &hints
+        )
+        .unwrap());
+        assert!(check("args.tags.size() == 2", json!({ "tags": [1, 2] }), &hints).unwrap());
+    }
+
+    #[test]
+    fn test_context_conditions() {
+        let hints = ToolAnnotations::new();
+        let args = json!({});
+        assert!(check("tenant.id in ['acme', 'globex']", args.clone(), &hints).unwrap());
+        assert!(check("tenant.session == 's1'", args.clone(), &hints).unwrap());
+        assert!(check(
+            "server == 'files' && tool.startsWith('delete_')",
+            args.clone(),
+            &hints
+        )
+        .unwrap());
+        assert!(check("time.hour >= 18 || time.hour < 6", args.clone(), &hints).unwrap());
+        assert!(check("time.weekday == 3", args.clone(), &hints).unwrap());
+        assert!(check("!annotations.read_only", args, &hints).unwrap());
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        for source in [
+            "",
+            "destructive &&",
+            "user.id == 'x'",
+            "args.path.lower()",
+            "args.path.startsWith()",
+            "args.path.matches('(')",
+            "args.path.matches(args.pattern)",
+            "'unterminated",
+            "args.x == 1 == 2",
+            "args # 1",
+        ] {
+            assert!(
+                PolicyExpression::compile(source).is_err(),
+                "expected compile error for {source:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_boolean_result_is_error() {
+        let hints = ToolAnnotations::new();
+        assert!(check("args.path", json!({ "path": "/x" }), &hints).is_err());
+        assert!(check("args.flag && true", json!({ "flag": "yes" }), &hints).is_err());
+    }
+}
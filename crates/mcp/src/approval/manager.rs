@@ -3,15 +3,17 @@
 //! Provides a dual-mode approval system:
 //! - **Interactive mode**: Returns approval requests to the caller for user confirmation
 //! - **Policy-only mode**: Auto-decides using the PolicyEngine
+//!
+//! Interactive decisions can be kept for longer than one call (see
+//! [`ApprovalScope`]). Kept decisions are stored per (tenant, server, tool) and
+//! answer later interactive requests without asking the user again.
 
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{sync::Arc, time::Duration};
 
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap};
 use serde::{Deserialize, Serialize};
-use tokio::sync::oneshot;
+use serde_json::Value;
+use tokio::{sync::oneshot, time::Instant};
 use tracing::info;
 
 use super::{
@@ -22,7 +24,7 @@ use crate::{
     annotations::ToolAnnotations,
     error::{ApprovalError, McpResult},
     inventory::QualifiedToolName,
-    tenant::TenantContext,
+    tenant::{SessionId, TenantContext, TenantId},
 };
 
 const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
@@ -91,6 +93,44 @@ impl ApprovalDecision {
     }
 }
 
+/// How long an interactive decision stays in effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ApprovalScope {
+    /// This call only.
+    #[default]
+    Once,
+    /// The rest of the conversation (the tenant context's session).
+    Conversation,
+    /// The next `n` calls, including this one.
+    Calls(u32),
+    /// Until the duration has elapsed.
+    For(Duration),
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+struct DecisionKey {
+    tenant_id: TenantId,
+    server_key: String,
+    tool_name: String,
+}
+
+/// An interactive decision kept for later calls.
+#[derive(Debug)]
+struct CachedDecision {
+    decision: ApprovalDecision,
+    /// Only applies within this session.
+    session_id: Option<SessionId>,
+    expires_at: Option<Instant>,
+    /// Calls left before the decision runs out.
+    remaining_calls: Option<u32>,
+}
+
+impl CachedDecision {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| now >= at) || self.remaining_calls == Some(0)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ApprovalMode {
     Interactive,
@@ -105,6 +145,8 @@ pub struct ApprovalParams<'a> {
     pub elicitation_id: &'a str,
     pub tool_name: &'a str,
     pub hints: &'a ToolAnnotations,
+    /// Call arguments, for expression policy rules.
+    pub arguments: Option<&'a Value>,
     pub message: &'a str,
     pub tenant_ctx: &'a TenantContext,
 }
@@ -139,6 +181,7 @@ pub enum ApprovalOutcome {
 pub struct ApprovalManager {
     policy_engine: Arc<PolicyEngine>,
     pending: DashMap<ApprovalKey, PendingApproval>,
+    cached: DashMap<DecisionKey, CachedDecision>,
     audit_log: Arc<AuditLog>,
     approval_timeout: Duration,
 }
@@ -148,6 +191,7 @@ impl ApprovalManager {
         Self {
             policy_engine,
             pending: DashMap::new(),
+            cached: DashMap::new(),
             audit_log,
             approval_timeout: DEFAULT_APPROVAL_TIMEOUT,
         }
@@ -171,10 +215,11 @@ impl ApprovalManager {
         match mode {
             ApprovalMode::Interactive => self.request_interactive(&params).await,
             ApprovalMode::PolicyOnly => {
-                let decision = self.policy_engine.evaluate(
+                let decision = self.policy_engine.evaluate_call(
                     params.server_key,
                     params.tool_name,
                     params.hints,
+                    params.arguments,
                     params.tenant_ctx,
                     params.request_id,
                 );
@@ -188,6 +233,26 @@ impl ApprovalManager {
         reason = "async kept for API consistency and future async backends"
     )]
     async fn request_interactive(&self, params: &ApprovalParams<'_>) -> McpResult<ApprovalOutcome> {
+        if let Some(decision) = self.take_cached_decision(params) {
+            let (result, decision) = match decision {
+                ApprovalDecision::Approved => (DecisionResult::Approved, PolicyDecision::Allow),
+                ApprovalDecision::Denied { reason } => (
+                    DecisionResult::Denied {
+                        reason: reason.clone(),
+                    },
+                    PolicyDecision::deny_with_reason(reason),
+                ),
+            };
+            self.audit_log.record_decision(
+                &QualifiedToolName::new(params.server_key, params.tool_name),
+                &params.tenant_ctx.tenant_id,
+                params.request_id,
+                result,
+                DecisionSource::CachedUserDecision,
+            );
+            return Ok(ApprovalOutcome::Decided(decision));
+        }
+
         let key = ApprovalKey::new(params.request_id, params.server_key, params.elicitation_id);
 
         if self.pending.contains_key(&key) {
@@ -229,17 +294,44 @@ impl ApprovalManager {
         })
     }
 
+    pub async fn resolve(
+        &self,
+        request_id: &str,
+        server_key: &str,
+        elicitation_id: &str,
+        approved: bool,
+        reason: Option<String>,
+        tenant_ctx: &TenantContext,
+    ) -> McpResult<()> {
+        self.resolve_with_scope(
+            request_id,
+            server_key,
+            elicitation_id,
+            approved,
+            reason,
+            ApprovalScope::Once,
+            tenant_ctx,
+        )
+        .await
+    }
+
+    /// Resolve a pending approval and keep the decision for `scope`.
     #[expect(
         clippy::unused_async,
         reason = "public async API kept for future async resolution backends"
     )]
-    pub async fn resolve(
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors resolve() plus the scope"
+    )]
+    pub async fn resolve_with_scope(
         &self,
         request_id: &str,
         server_key: &str,
         elicitation_id: &str,
         approved: bool,
         reason: Option<String>,
+        scope: ApprovalScope,
         tenant_ctx: &TenantContext,
     ) -> McpResult<()> {
         let key = ApprovalKey::new(request_id, server_key, elicitation_id);
@@ -277,6 +369,8 @@ impl ApprovalManager {
             DecisionSource::UserInteractive,
         );
 
+        self.cache_decision(server_key, &pending.tool_name, &decision, scope, tenant_ctx);
+
         // Send response to waiting handler
         pending
             .response_tx
@@ -286,6 +380,78 @@ impl ApprovalManager {
         Ok(())
     }
 
+    fn cache_decision(
+        &self,
+        server_key: &str,
+        tool_name: &str,
+        decision: &ApprovalDecision,
+        scope: ApprovalScope,
+        tenant_ctx: &TenantContext,
+    ) {
+        let mut cached = CachedDecision {
+            decision: decision.clone(),
+            session_id: None,
+            expires_at: None,
+            remaining_calls: None,
+        };
+        match scope {
+            ApprovalScope::Once => return,
+            ApprovalScope::Conversation => cached.session_id = Some(tenant_ctx.session_id.clone()),
+            // The call being resolved uses one of the `n`.
+            ApprovalScope::Calls(n) if n > 1 => cached.remaining_calls = Some(n - 1),
+            ApprovalScope::Calls(_) => return,
+            ApprovalScope::For(duration) => cached.expires_at = Some(Instant::now() + duration),
+        }
+        let key = DecisionKey {
+            tenant_id: tenant_ctx.tenant_id.clone(),
+            server_key: server_key.to_string(),
+            tool_name: tool_name.to_string(),
+        };
+        self.cached.insert(key, cached);
+    }
+
+    /// Use up a kept decision for this call, if one applies.
+    fn take_cached_decision(&self, params: &ApprovalParams<'_>) -> Option<ApprovalDecision> {
+        let key = DecisionKey {
+            tenant_id: params.tenant_ctx.tenant_id.clone(),
+            server_key: params.server_key.to_string(),
+            tool_name: params.tool_name.to_string(),
+        };
+        let Entry::Occupied(mut entry) = self.cached.entry(key) else {
+            return None;
+        };
+
+        if entry.get().is_expired(Instant::now()) {
+            entry.remove();
+            return None;
+        }
+        let cached = entry.get_mut();
+        if cached
+            .session_id
+            .as_ref()
+            .is_some_and(|session| session != &params.tenant_ctx.session_id)
+        {
+            return None;
+        }
+        if let Some(remaining) = &mut cached.remaining_calls {
+            *remaining -= 1;
+        }
+        let decision = cached.decision.clone();
+        if entry.get().remaining_calls == Some(0) {
+            entry.remove();
+        }
+        Some(decision)
+    }
+
+    /// Forget kept decisions for a tenant, e.g. when the user revokes them.
+    pub fn clear_cached_decisions(&self, tenant_id: &TenantId) {
+        self.cached.retain(|key, _| &key.tenant_id != tenant_id);
+    }
+
+    pub fn cached_decision_count(&self) -> usize {
+        self.cached.len()
+    }
+
     pub fn has_pending(&self, key: &ApprovalKey) -> bool {
         self.pending.contains_key(key)
     }
@@ -299,6 +465,7 @@ impl ApprovalManager {
         let timeout = self.approval_timeout;
         self.pending
             .retain(|_, pending| now.duration_since(pending.created_at) < timeout);
+        self.cached.retain(|_, cached| !cached.is_expired(now));
     }
 
     pub fn policy_engine(&self) -> &Arc<PolicyEngine> {
@@ -351,6 +518,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "read_tool",
             hints: &hints,
+            arguments: None,
             message: "Allow read?",
             tenant_ctx: &tenant,
         };
@@ -378,6 +546,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "delete_tool",
             hints: &hints,
+            arguments: None,
             message: "Allow delete?",
             tenant_ctx: &tenant,
         };
@@ -413,6 +582,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "tool",
             hints: &hints,
+            arguments: None,
             message: "Allow?",
             tenant_ctx: &tenant,
         };
@@ -460,6 +630,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "tool",
             hints: &hints,
+            arguments: None,
             message: "Allow?",
             tenant_ctx: &tenant,
         };
@@ -475,6 +646,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "tool",
             hints: &hints,
+            arguments: None,
             message: "Allow?",
             tenant_ctx: &tenant,
         };
@@ -485,6 +657,118 @@ mod tests {
         assert!(result.is_err());
     }
 
+    async fn approve_with_scope(
+        manager: &ApprovalManager,
+        request_id: &str,
+        scope: ApprovalScope,
+        tenant: &TenantContext,
+    ) {
+        let hints = ToolAnnotations::new();
+        let params = ApprovalParams {
+            request_id,
+            server_key: "server",
+            elicitation_id: "elicit-1",
+            tool_name: "tool",
+            hints: &hints,
+            arguments: None,
+            message: "Allow?",
+            tenant_ctx: tenant,
+        };
+        let outcome = manager
+            .handle_approval(ApprovalMode::Interactive, params)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, ApprovalOutcome::Pending { .. }));
+        manager
+            .resolve_with_scope(request_id, "server", "elicit-1", true, None, scope, tenant)
+            .await
+            .unwrap();
+    }
+
+    async fn is_decided(
+        manager: &ApprovalManager,
+        request_id: &str,
+        tenant: &TenantContext,
+    ) -> bool {
+        let hints = ToolAnnotations::new();
+        let params = ApprovalParams {
+            request_id,
+            server_key: "server",
+            elicitation_id: "elicit-1",
+            tool_name: "tool",
+            hints: &hints,
+            arguments: None,
+            message: "Allow?",
+            tenant_ctx: tenant,
+        };
+        match manager
+            .handle_approval(ApprovalMode::Interactive, params)
+            .await
+            .unwrap()
+        {
+            ApprovalOutcome::Decided(decision) => decision.is_allowed(),
+            ApprovalOutcome::Pending { .. } => false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_decision_for_n_calls() {
+        let manager = test_manager();
+        let tenant = TenantContext::new("test");
+
+        approve_with_scope(&manager, "req-1", ApprovalScope::Calls(3), &tenant).await;
+        assert!(is_decided(&manager, "req-2", &tenant).await);
+        assert!(is_decided(&manager, "req-3", &tenant).await);
+        // Three calls used up; the next one asks again.
+        assert!(!is_decided(&manager, "req-4", &tenant).await);
+        assert_eq!(manager.cached_decision_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cached_decision_scoped_to_conversation_and_tenant() {
+        let manager = test_manager();
+        let tenant = TenantContext::new("test").with_session("conv-1");
+
+        approve_with_scope(&manager, "req-1", ApprovalScope::Conversation, &tenant).await;
+        assert!(is_decided(&manager, "req-2", &tenant).await);
+        assert!(is_decided(&manager, "req-3", &tenant).await);
+
+        let other_conversation = TenantContext::new("test").with_session("conv-2");
+        assert!(!is_decided(&manager, "req-4", &other_conversation).await);
+        let other_tenant = TenantContext::new("other").with_session("conv-1");
+        assert!(!is_decided(&manager, "req-5", &other_tenant).await);
+
+        manager.clear_cached_decisions(&tenant.tenant_id);
+        assert!(!is_decided(&manager, "req-6", &tenant).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cached_decision_expires() {
+        let manager = test_manager();
+        let tenant = TenantContext::new("test");
+
+        approve_with_scope(
+            &manager,
+            "req-1",
+            ApprovalScope::For(Duration::from_millis(20)),
+            &tenant,
+        )
+        .await;
+        assert!(is_decided(&manager, "req-2", &tenant).await);
+        tokio::time::advance(Duration::from_millis(30)).await;
+        assert!(!is_decided(&manager, "req-3", &tenant).await);
+    }
+
+    #[tokio::test]
+    async fn test_once_scope_not_cached() {
+        let manager = test_manager();
+        let tenant = TenantContext::new("test");
+
+        approve_with_scope(&manager, "req-1", ApprovalScope::Once, &tenant).await;
+        assert_eq!(manager.cached_decision_count(), 0);
+        assert!(!is_decided(&manager, "req-2", &tenant).await);
+    }
+
     #[test]
     fn test_evict_expired() {
         let manager = test_manager().with_timeout(Duration::from_millis(1));
@@ -1,14 +1,17 @@
 //! Approval system for MCP tool execution.
 
 pub mod audit;
+pub mod expression;
 pub mod manager;
 pub mod policy;
 
 pub use audit::{AuditEntry, AuditLog, DecisionResult, DecisionSource};
+pub use expression::{ExpressionError, PolicyExpression};
 pub use manager::{
     ApprovalDecision, ApprovalKey, ApprovalManager, ApprovalMode, ApprovalOutcome, ApprovalParams,
-    McpApprovalRequest, McpApprovalResponse,
+    ApprovalScope, McpApprovalRequest, McpApprovalResponse,
 };
 pub use policy::{
-    PolicyDecision, PolicyEngine, PolicyRule, RuleCondition, RulePattern, ServerPolicy, TrustLevel,
+    PolicyDecision, PolicyEngine, PolicyRule, RuleCondition, RuleContext, RulePattern,
+    ServerPolicy, TrustLevel,
 };
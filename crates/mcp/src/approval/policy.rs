@@ -2,11 +2,16 @@
 
 use std::{collections::HashMap, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tracing::warn;
 
-use super::audit::{AuditLog, DecisionResult, DecisionSource};
+use super::{
+    audit::{AuditLog, DecisionResult, DecisionSource},
+    expression::PolicyExpression,
+};
 use crate::{
     annotations::AnnotationType, inventory::QualifiedToolName, tenant::TenantContext,
     ToolAnnotations,
@@ -143,20 +148,45 @@ impl RulePattern {
     }
 }
 
+/// Tool call a rule is evaluated against.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    pub server_key: &'a str,
+    pub tool_name: &'a str,
+    pub hints: &'a ToolAnnotations,
+    pub arguments: Option<&'a Value>,
+    pub tenant_ctx: &'a TenantContext,
+    pub now: DateTime<Utc>,
+}
+
 /// Condition for policy rules.
 #[derive(Debug, Clone)]
 pub enum RuleCondition {
     Always,
     HasAnnotation(AnnotationType),
     LacksAnnotation(AnnotationType),
+    /// Expression over arguments, tenant, time and annotations.
+    Expression(PolicyExpression),
 }
 
 impl RuleCondition {
-    pub fn evaluate(&self, hints: &ToolAnnotations) -> bool {
+    /// Expressions that fail to evaluate (e.g. a method called on a missing
+    /// argument) do not match.
+    pub fn evaluate(&self, ctx: &RuleContext<'_>) -> bool {
         match self {
             RuleCondition::Always => true,
-            RuleCondition::HasAnnotation(ann_type) => ann_type.matches(hints),
-            RuleCondition::LacksAnnotation(ann_type) => !ann_type.matches(hints),
+            RuleCondition::HasAnnotation(ann_type) => ann_type.matches(ctx.hints),
+            RuleCondition::LacksAnnotation(ann_type) => !ann_type.matches(ctx.hints),
+            RuleCondition::Expression(expr) => expr.evaluate(ctx).unwrap_or_else(|e| {
+                warn!(
+                    "Policy expression '{}' failed for {}:{}: {}",
+                    expr.source(),
+                    ctx.server_key,
+                    ctx.tool_name,
+                    e
+                );
+                false
+            }),
         }
     }
 }
@@ -185,13 +215,8 @@ impl PolicyRule {
         }
     }
 
-    pub fn evaluate(
-        &self,
-        server_key: &str,
-        tool_name: &str,
-        hints: &ToolAnnotations,
-    ) -> Option<PolicyDecision> {
-        if self.pattern.matches(server_key, tool_name) && self.condition.evaluate(hints) {
+    pub fn evaluate(&self, ctx: &RuleContext<'_>) -> Option<PolicyDecision> {
+        if self.pattern.matches(ctx.server_key, ctx.tool_name) && self.condition.evaluate(ctx) {
             Some(self.decision.clone())
         } else {
             None
@@ -255,6 +280,20 @@ impl PolicyEngine {
         hints: &ToolAnnotations,
         tenant_ctx: &TenantContext,
         request_id: &str,
+    ) -> PolicyDecision {
+        self.evaluate_call(server_key, tool_name, hints, None, tenant_ctx, request_id)
+    }
+
+    /// Like [`evaluate`](Self::evaluate), with the call arguments available
+    /// to expression rules.
+    pub fn evaluate_call(
+        &self,
+        server_key: &str,
+        tool_name: &str,
+        hints: &ToolAnnotations,
+        arguments: Option<&Value>,
+        tenant_ctx: &TenantContext,
+        request_id: &str,
     ) -> PolicyDecision {
         let qualified = QualifiedToolName::new(server_key, tool_name);
 
@@ -289,8 +328,16 @@ impl PolicyEngine {
         }
 
         // 3. Evaluate pattern-based rules in order
+        let ctx = RuleContext {
+            server_key,
+            tool_name,
+            hints,
+            arguments,
+            tenant_ctx,
+            now: Utc::now(),
+        };
         for rule in &self.rules {
-            if let Some(decision) = rule.evaluate(server_key, tool_name, hints) {
+            if let Some(decision) = rule.evaluate(&ctx) {
                 self.log_decision(
                     &qualified,
                     tenant_ctx,
@@ -465,6 +512,19 @@ impl PolicyEngine {
             }
         }
 
+        // Add expression rules; McpConfig::validate rejects invalid ones first
+        for rule in &config.rules {
+            match PolicyExpression::compile(&rule.when) {
+                Ok(expr) => engine.rules.push(PolicyRule::new(
+                    rule.name.clone(),
+                    RulePattern::Any,
+                    RuleCondition::Expression(expr),
+                    rule.decision.clone().into(),
+                )),
+                Err(e) => warn!("Skipping policy rule '{}': {}", rule.name, e),
+            }
+        }
+
         engine
     }
 }
@@ -472,6 +532,7 @@ impl PolicyEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::config::PolicyRuleConfig;
 
     fn test_engine() -> PolicyEngine {
         PolicyEngine::new(Arc::new(AuditLog::new()))
@@ -583,6 +644,63 @@ mod tests {
         assert!(decision.is_allowed());
     }
 
+    #[test]
+    fn test_expression_rule() {
+        let engine = test_engine().with_rule(PolicyRule::new(
+            "protect_prod",
+            RulePattern::Any,
+            RuleCondition::Expression(
+                PolicyExpression::compile(r#"destructive && args.path.startsWith("/prod")"#)
+                    .unwrap(),
+            ),
+            PolicyDecision::deny_with_reason("Production paths are protected"),
+        ));
+
+        let tenant = TenantContext::new("test");
+        let hints = ToolAnnotations::new().with_destructive(true);
+        let prod = serde_json::json!({ "path": "/prod/data" });
+        let tmp = serde_json::json!({ "path": "/tmp/data" });
+
+        let decision = engine.evaluate_call("fs", "delete", &hints, Some(&prod), &tenant, "req-1");
+        assert_eq!(
+            decision.denial_reason(),
+            Some("Production paths are protected")
+        );
+
+        // Falls through to the annotation default for other paths.
+        let decision = engine.evaluate_call("fs", "delete", &hints, Some(&tmp), &tenant, "req-2");
+        assert_eq!(
+            decision.denial_reason(),
+            Some("Destructive operation requires explicit policy")
+        );
+
+        // Without arguments the expression errors and the rule does not match.
+        let decision = engine.evaluate("fs", "delete", &hints, &tenant, "req-3");
+        assert_eq!(
+            decision.denial_reason(),
+            Some("Destructive operation requires explicit policy")
+        );
+    }
+
+    #[test]
+    fn test_from_yaml_config_rules() {
+        let config = PolicyConfig {
+            rules: vec![PolicyRuleConfig {
+                name: "acme_only".to_string(),
+                when: "tenant.id != 'acme'".to_string(),
+                decision: PolicyDecisionConfig::Deny,
+            }],
+            ..Default::default()
+        };
+        let engine = PolicyEngine::from_yaml_config(&config, Arc::new(AuditLog::new()));
+        let hints = ToolAnnotations::new();
+
+        let decision = engine.evaluate("s", "t", &hints, &TenantContext::new("acme"), "req-1");
+        assert!(decision.is_allowed());
+        let decision = engine.evaluate("s", "t", &hints, &TenantContext::new("other"), "req-2");
+        assert!(!decision.is_allowed());
+    }
+
     #[test]
     fn test_annotation_based_decision() {
         let engine = test_engine().with_default_policy(PolicyDecision::Deny);
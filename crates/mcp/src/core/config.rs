@@ -7,6 +7,8 @@ use std::{collections::HashMap, fmt};
 pub use rmcp::model::{Prompt, RawResource, Tool};
 use serde::{Deserialize, Serialize};
 
+use crate::approval::PolicyExpression;
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct McpConfig {
     /// Static MCP servers (loaded at startup)
@@ -33,6 +35,133 @@ pub struct McpConfig {
     /// Default: allow all tools
     #[serde(default)]
     pub policy: PolicyConfig,
+
+    /// Tool argument and result size limits
+    /// Default: unlimited
+    #[serde(default)]
+    pub limits: ToolLimitsConfig,
+
+    /// Server-initiated LLM calls (MCP sampling)
+    /// Default: disabled for every server
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+}
+
+/// MCP sampling configuration.
+///
+/// Sampling lets a connected server ask the gateway for an LLM completion.
+/// It is opt-in per static server; dynamic servers never get it.
+///
+/// Example:
+/// ```yaml
+/// sampling:
+///   servers:
+///     researcher:
+///       models: ["llama-3.1-8b-instruct"]
+///       max_tokens: 1024
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SamplingConfig {
+    /// Server name -> sampling policy
+    #[serde(default)]
+    pub servers: HashMap<String, ServerSamplingConfig>,
+}
+
+/// Sampling policy for one server.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServerSamplingConfig {
+    /// Models the server may sample from. The server's model hints pick
+    /// among these; the first is used when no hint matches.
+    pub models: Vec<String>,
+
+    /// Upper bound on `max_tokens` for a single sampling request
+    #[serde(default = "default_sampling_max_tokens")]
+    pub max_tokens: u32,
+}
+
+/// Size caps on tool calls, measured on the serialized JSON in bytes.
+///
+/// Oversized arguments are always rejected. Oversized results are truncated
+/// with a marker or rejected, depending on `on_oversized_output`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ToolLimitsConfig {
+    /// Maximum size of a tool call's arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_argument_bytes: Option<usize>,
+
+    /// Maximum size of a tool call's result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+
+    /// What to do with a result over `max_output_bytes`
+    #[serde(default)]
+    pub on_oversized_output: OversizedOutputAction,
+
+    /// Token caps on tool output fed back to the model
+    #[serde(default)]
+    pub output_tokens: OutputTokenLimitsConfig,
+}
+
+/// Token caps on tool output inserted into the continuation prompt.
+///
+/// Counted with the request model's tokenizer, so they only apply on routers
+/// that have one. The output returned to the client is not shortened.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OutputTokenLimitsConfig {
+    /// Maximum tokens from a single tool result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_call: Option<usize>,
+
+    /// Maximum tokens of tool output across all calls in one response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+
+    /// Per-tool overrides of `per_call`, keyed by "server:tool"
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, usize>,
+
+    /// How an output over its cap is shortened
+    #[serde(default)]
+    pub strategy: TruncationStrategy,
+}
+
+impl OutputTokenLimitsConfig {
+    /// Cap for a single result of `server_key:tool_name`.
+    pub fn limit_for(&self, server_key: &str, tool_name: &str) -> Option<usize> {
+        self.tools
+            .get(&format!("{server_key}:{tool_name}"))
+            .copied()
+            .or(self.per_call)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.per_call.is_some() || self.total.is_some() || !self.tools.is_empty()
+    }
+}
+
+/// How tool output over its token cap is shortened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Keep the leading tokens.
+    #[default]
+    Head,
+    /// Keep the leading and trailing tokens, dropping the middle.
+    HeadTail,
+    /// Replace JSON output with a structural outline (keys, shortened
+    /// strings, item counts); other output falls back to `head_tail`.
+    Summary,
+}
+
+/// Handling for tool results over the configured size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedOutputAction {
+    /// Keep the leading content and append a truncation marker.
+    #[default]
+    Truncate,
+    /// Replace the result with an error.
+    Reject,
 }
 
 /// Policy configuration for tool approval decisions.
@@ -40,7 +169,8 @@ pub struct McpConfig {
 /// Evaluation order:
 /// 1. Explicit tool policies (server:tool → decision)
 /// 2. Server policies with trust levels
-/// 3. Default policy (fallback)
+/// 3. Expression rules, in order
+/// 4. Default policy (fallback)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PolicyConfig {
     /// Default policy when no other rules match.
@@ -55,6 +185,21 @@ pub struct PolicyConfig {
     /// Explicit per-tool policies (qualified name: "server:tool").
     #[serde(default)]
     pub tools: HashMap<String, PolicyDecisionConfig>,
+
+    /// Rules with expression conditions; the first matching rule decides.
+    #[serde(default)]
+    pub rules: Vec<PolicyRuleConfig>,
+}
+
+/// Policy rule with an expression condition.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PolicyRuleConfig {
+    pub name: String,
+
+    /// Condition, e.g. `destructive && args.path.startsWith("/prod")`.
+    pub when: String,
+
+    pub decision: PolicyDecisionConfig,
 }
 
 /// Server-level policy configuration.
@@ -155,6 +300,7 @@ impl Default for PolicyConfig {
             default: PolicyDecisionConfig::Allow,
             servers: HashMap::new(),
             tools: HashMap::new(),
+            rules: Vec::new(),
         }
     }
 }
@@ -255,6 +401,10 @@ pub enum ConfigValidationError {
         first_server: String,
         second_server: String,
     },
+    /// Sampling is enabled for a server without any allowed model.
+    EmptySamplingModels { server: String },
+    /// A policy rule condition does not compile.
+    InvalidPolicyRule { rule: String, error: String },
 }
 
 impl fmt::Display for ConfigValidationError {
@@ -282,6 +432,12 @@ impl fmt::Display for ConfigValidationError {
                     "duplicate builtin_type '{builtin_type}': configured on both '{first_server}' and '{second_server}'"
                 )
             }
+            ConfigValidationError::EmptySamplingModels { server } => {
+                write!(f, "sampling for server '{server}' lists no models")
+            }
+            ConfigValidationError::InvalidPolicyRule { rule, error } => {
+                write!(f, "policy rule '{rule}': invalid condition: {error}")
+            }
         }
     }
 }
@@ -556,6 +712,10 @@ fn default_refresh_interval() -> u64 {
     60 // 1 minute
 }
 
+fn default_sampling_max_tokens() -> u32 {
+    1024
+}
+
 // Default implementations
 impl Default for McpPoolConfig {
     fn default() -> Self {
@@ -634,6 +794,7 @@ impl McpConfig {
     /// Checks:
     /// - Each server's builtin_type/builtin_tool_name pairing
     /// - No duplicate builtin_type across servers
+    /// - Every sampling policy allows at least one model
     pub fn validate(&self) -> Result<(), ConfigValidationError> {
         let mut builtin_types: HashMap<BuiltinToolType, &str> = HashMap::new();
 
@@ -654,6 +815,23 @@ impl McpConfig {
             }
         }
 
+        for (server, sampling) in &self.sampling.servers {
+            if sampling.models.is_empty() {
+                return Err(ConfigValidationError::EmptySamplingModels {
+                    server: server.clone(),
+                });
+            }
+        }
+
+        for rule in &self.policy.rules {
+            if let Err(e) = PolicyExpression::compile(&rule.when) {
+                return Err(ConfigValidationError::InvalidPolicyRule {
+                    rule: rule.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+
         Ok(())
     }
 }
@@ -727,6 +905,26 @@ servers:
         assert!(config.servers[0].proxy.is_none()); // Should default to None
         assert_eq!(config.pool.max_connections, 100); // Should use default
         assert_eq!(config.inventory.tool_ttl, 300); // Should use default
+        assert!(config.limits.max_output_bytes.is_none()); // Unlimited by default
+    }
+
+    #[test]
+    fn test_yaml_tool_limits() {
+        let yaml = r"
+servers: []
+limits:
+  max_argument_bytes: 4096
+  max_output_bytes: 65536
+  on_oversized_output: reject
+";
+
+        let config: McpConfig = serde_yaml::from_str(yaml).expect("Failed to parse YAML");
+        assert_eq!(config.limits.max_argument_bytes, Some(4096));
+        assert_eq!(config.limits.max_output_bytes, Some(65536));
+        assert_eq!(
+            config.limits.on_oversized_output,
+            OversizedOutputAction::Reject
+        );
     }
 
     #[tokio::test]
@@ -1558,6 +1756,58 @@ servers:
         assert!(err.to_string().contains("web_search_preview"));
     }
 
+    #[test]
+    fn test_output_token_limits_yaml() {
+        let yaml = r#"
+servers: []
+limits:
+  output_tokens:
+    per_call: 4000
+    total: 16000
+    tools:
+      "github:list_commits": 1000
+    strategy: head_tail
+"#;
+        let config: McpConfig = serde_yaml::from_str(yaml).expect("Failed to parse");
+        let limits = &config.limits.output_tokens;
+        assert!(limits.is_enabled());
+        assert_eq!(limits.strategy, TruncationStrategy::HeadTail);
+        assert_eq!(limits.limit_for("github", "list_commits"), Some(1000));
+        assert_eq!(limits.limit_for("github", "get_issue"), Some(4000));
+        assert!(!OutputTokenLimitsConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_mcp_config_validate_policy_rules() {
+        let yaml = r#"
+servers: []
+policy:
+  rules:
+    - name: protect_prod
+      when: 'destructive && args.path.startsWith("/prod")'
+      decision: deny
+"#;
+        let config: McpConfig = serde_yaml::from_str(yaml).expect("Failed to parse");
+        assert_eq!(config.policy.rules.len(), 1);
+        assert!(config.validate().is_ok());
+
+        let yaml = r"
+servers: []
+policy:
+  rules:
+    - name: broken
+      when: 'user.id == 1'
+      decision: deny
+";
+        let config: McpConfig = serde_yaml::from_str(yaml).expect("Failed to parse");
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigValidationError::InvalidPolicyRule { ref rule, .. } if rule == "broken"
+        ));
+        assert!(err.to_string().contains("unknown identifier 'user'"));
+    }
+
     #[test]
     fn test_builtin_tool_type_display() {
         assert_eq!(
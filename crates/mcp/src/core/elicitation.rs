@@ -0,0 +1,366 @@
+//! Forwarding MCP elicitation to the API client.
+//!
+//! When the caller can pause (the Responses API), a tool call whose server
+//! sends `elicitation/create` is not failed: the call keeps running in the
+//! background, the elicitation is handed back to the caller, and the server
+//! gets the client's answer when the caller resumes. Paused calls expire
+//! after [`DEFAULT_ELICITATION_TIMEOUT`].
+//!
+//! Elicitations carry no reference to the tool call that caused them, so they
+//! go to the forwarding call currently waiting on that server. With several
+//! concurrent forwarding calls on one server, the most recent one receives
+//! them.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use rmcp::model::CallToolResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tracing::info;
+
+use crate::{
+    error::{ApprovalError, McpError, McpResult},
+    inventory::QualifiedToolName,
+    tenant::TenantId,
+};
+
+const DEFAULT_ELICITATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Elicitation sent to the client on behalf of an MCP server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpElicitationRequest {
+    /// Identifier the client echoes back in its response.
+    pub elicitation_id: String,
+    pub server_key: String,
+    /// User-facing message from the server.
+    pub message: String,
+    /// JSON schema of the requested form data (form elicitation).
+    pub requested_schema: Option<Value>,
+    /// Page the user should visit (URL elicitation).
+    pub url: Option<String>,
+}
+
+/// Client action on an elicitation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElicitationAction {
+    Accept,
+    Decline,
+    Cancel,
+}
+
+/// Client answer to an elicitation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElicitationResponse {
+    pub action: ElicitationAction,
+    /// Submitted form data, for accepted form elicitations.
+    pub content: Option<Value>,
+}
+
+impl ElicitationResponse {
+    pub fn cancel() -> Self {
+        Self {
+            action: ElicitationAction::Cancel,
+            content: None,
+        }
+    }
+
+    /// Wire form of an `elicitation/create` result.
+    pub(crate) fn result_json(&self) -> Value {
+        let mut result = json!({ "action": self.action });
+        if let (ElicitationAction::Accept, Some(content)) = (self.action, &self.content) {
+            result["content"] = content.clone();
+        }
+        result
+    }
+}
+
+/// Tool call details reported when a paused call completes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ToolCallInfo {
+    pub call_id: String,
+    pub tool_name: String,
+    pub server_key: String,
+    pub server_label: String,
+    pub arguments_str: String,
+}
+
+/// A tool call paused on an elicitation.
+pub(crate) struct SuspendedCall {
+    pub call: JoinHandle<McpResult<CallToolResult>>,
+    pub waiter: ElicitationWaiter,
+    pub qualified: QualifiedToolName,
+    pub tenant_id: TenantId,
+    pub info: ToolCallInfo,
+    pub started: Instant,
+}
+
+/// Receives elicitations for one forwarding call; unregisters on drop.
+pub(crate) struct ElicitationWaiter {
+    manager: Arc<ElicitationManager>,
+    server_key: String,
+    id: u64,
+    rx: mpsc::UnboundedReceiver<McpElicitationRequest>,
+}
+
+impl ElicitationWaiter {
+    pub async fn recv(&mut self) -> Option<McpElicitationRequest> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for ElicitationWaiter {
+    fn drop(&mut self) {
+        self.manager
+            .waiters
+            .remove_if(&self.server_key, |_, (id, _)| *id == self.id);
+    }
+}
+
+struct PendingElicitation {
+    created_at: Instant,
+    response_tx: oneshot::Sender<ElicitationResponse>,
+    /// Set once the call that triggered the elicitation has been paused.
+    call: Option<SuspendedCall>,
+}
+
+/// Tracks forwarding calls and elicitations awaiting a client answer.
+#[derive(Default)]
+pub struct ElicitationManager {
+    waiters: DashMap<String, (u64, mpsc::UnboundedSender<McpElicitationRequest>)>,
+    pending: DashMap<String, PendingElicitation>,
+    next_waiter_id: AtomicU64,
+}
+
+impl ElicitationManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route elicitations from `server_key` to the returned waiter.
+    pub(crate) fn watch(self: &Arc<Self>, server_key: &str) -> ElicitationWaiter {
+        let id = self.next_waiter_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.waiters.insert(server_key.to_string(), (id, tx));
+        ElicitationWaiter {
+            manager: Arc::clone(self),
+            server_key: server_key.to_string(),
+            id,
+            rx,
+        }
+    }
+
+    /// Hand an elicitation to the call waiting on `server_key`. Returns the
+    /// receiver for the client's answer, or `None` if no call is waiting.
+    pub(crate) fn forward(
+        &self,
+        server_key: &str,
+        message: String,
+        requested_schema: Option<Value>,
+        url: Option<String>,
+    ) -> Option<oneshot::Receiver<ElicitationResponse>> {
+        self.evict_expired();
+
+        let tx = self.waiters.get(server_key)?.1.clone();
+        let request = McpElicitationRequest {
+            elicitation_id: format!("elicit_{}", uuid::Uuid::now_v7().simple()),
+            server_key: server_key.to_string(),
+            message,
+            requested_schema,
+            url,
+        };
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending.insert(
+            request.elicitation_id.clone(),
+            PendingElicitation {
+                created_at: Instant::now(),
+                response_tx,
+                call: None,
+            },
+        );
+
+        let elicitation_id = request.elicitation_id.clone();
+        if tx.send(request).is_err() {
+            self.pending.remove(&elicitation_id);
+            return None;
+        }
+        Some(response_rx)
+    }
+
+    /// Park the call that triggered `elicitation_id` until the client answers.
+    pub(crate) fn suspend(&self, elicitation_id: &str, call: SuspendedCall) {
+        match self.pending.get_mut(elicitation_id) {
+            Some(mut pending) => pending.call = Some(call),
+            None => call.call.abort(),
+        }
+    }
+
+    /// Record the tool call details of a paused call.
+    pub(crate) fn describe(&self, elicitation_id: &str, info: ToolCallInfo) {
+        if let Some(mut pending) = self.pending.get_mut(elicitation_id) {
+            if let Some(call) = pending.call.as_mut() {
+                call.info = info;
+            }
+        }
+    }
+
+    /// Deliver the client's answer and return the paused call.
+    pub(crate) fn resolve(
+        &self,
+        elicitation_id: &str,
+        response: ElicitationResponse,
+        tenant_id: &TenantId,
+    ) -> McpResult<SuspendedCall> {
+        let owned_by_tenant = self
+            .pending
+            .get(elicitation_id)
+            .and_then(|pending| pending.call.as_ref().map(|c| &c.tenant_id == tenant_id));
+        match owned_by_tenant {
+            Some(true) => {}
+            Some(false) => {
+                return Err(McpError::ServerAccessDenied(format!(
+                    "elicitation '{elicitation_id}' belongs to another tenant"
+                )))
+            }
+            None => return Err(ApprovalError::NotFound(elicitation_id.to_string()).into()),
+        }
+
+        let (_, pending) = self
+            .pending
+            .remove(elicitation_id)
+            .ok_or_else(|| ApprovalError::NotFound(elicitation_id.to_string()))?;
+        let call = pending
+            .call
+            .ok_or_else(|| ApprovalError::NotFound(elicitation_id.to_string()))?;
+        if pending.response_tx.send(response).is_err() {
+            call.call.abort();
+            return Err(ApprovalError::ChannelClosed.into());
+        }
+        Ok(call)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Drop expired elicitations; the server sees them as cancelled.
+    pub fn evict_expired(&self) {
+        let now = Instant::now();
+        self.pending.retain(|_, pending| {
+            let live = now.duration_since(pending.created_at) < DEFAULT_ELICITATION_TIMEOUT;
+            if !live {
+                if let Some(call) = &pending.call {
+                    call.call.abort();
+                }
+            }
+            live
+        });
+    }
+
+    /// Cancels all pending elicitations and their paused calls.
+    pub fn cancel_all_pending(&self) {
+        info!(
+            "Cancelling {} pending elicitations due to shutdown",
+            self.pending.len()
+        );
+        self.pending.retain(|_, pending| {
+            if let Some(call) = &pending.call {
+                call.call.abort();
+            }
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "test call handle is aborted by the manager or the test"
+    )]
+    fn suspended(waiter: ElicitationWaiter, tenant: &str) -> SuspendedCall {
+        SuspendedCall {
+            call: tokio::spawn(std::future::pending()),
+            waiter,
+            qualified: QualifiedToolName::new("forms", "signup"),
+            tenant_id: TenantId::new(tenant),
+            info: ToolCallInfo::default(),
+            started: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forward_and_resolve() {
+        let manager = Arc::new(ElicitationManager::new());
+        assert!(manager.forward("forms", "hi".into(), None, None).is_none());
+
+        let mut waiter = manager.watch("forms");
+        let response_rx = manager
+            .forward(
+                "forms",
+                "Your email?".into(),
+                Some(json!({ "type": "object" })),
+                None,
+            )
+            .unwrap();
+        let request = waiter.recv().await.unwrap();
+        assert_eq!(request.message, "Your email?");
+        manager.suspend(&request.elicitation_id, suspended(waiter, "acme"));
+
+        let response = ElicitationResponse {
+            action: ElicitationAction::Accept,
+            content: Some(json!({ "email": "a@b.c" })),
+        };
+        let err = manager
+            .resolve(
+                &request.elicitation_id,
+                response.clone(),
+                &TenantId::new("other"),
+            )
+            .err()
+            .unwrap();
+        assert!(matches!(err, McpError::ServerAccessDenied(_)));
+
+        let call = manager
+            .resolve(&request.elicitation_id, response, &TenantId::new("acme"))
+            .unwrap();
+        call.call.abort();
+        let answer = response_rx.await.unwrap();
+        assert_eq!(answer.result_json()["content"]["email"], "a@b.c");
+        assert_eq!(manager.pending_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_waiter_unregisters_on_drop() {
+        let manager = Arc::new(ElicitationManager::new());
+        let first = manager.watch("forms");
+        let second = manager.watch("forms");
+        // Dropping a replaced waiter leaves the newer one registered.
+        drop(first);
+        assert!(manager.waiters.contains_key("forms"));
+        drop(second);
+        assert!(!manager.waiters.contains_key("forms"));
+    }
+
+    #[test]
+    fn test_declined_result_omits_content() {
+        let response = ElicitationResponse {
+            action: ElicitationAction::Decline,
+            content: Some(json!({ "email": "a@b.c" })),
+        };
+        assert_eq!(response.result_json(), json!({ "action": "decline" }));
+    }
+}
@@ -1,7 +1,8 @@
 //! SMG client handler for MCP server notifications and elicitation.
 //!
 //! Implements RMCP's `ClientHandler` trait to handle:
-//! - Elicitation requests (approval flow)
+//! - Elicitation requests (forwarded to the API client, or the approval flow)
+//! - Sampling requests (server-initiated LLM calls, when granted)
 //! - Tool/resource/prompt list change notifications
 //! - Progress and logging notifications
 
@@ -11,7 +12,8 @@ use parking_lot::RwLock;
 use rmcp::{
     model::{
         CancelledNotificationParam, ClientInfo, CreateElicitationRequestParams,
-        CreateElicitationResult, ElicitationAction, LoggingLevel, LoggingMessageNotificationParam,
+        CreateElicitationResult, CreateMessageRequestParams, CreateMessageResult,
+        ElicitationAction, LoggingLevel, LoggingMessageNotificationParam,
         ProgressNotificationParam, ResourceUpdatedNotificationParam,
     },
     service::{NotificationContext, RequestContext},
@@ -20,6 +22,11 @@ use rmcp::{
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+use super::{
+    config::ServerSamplingConfig,
+    elicitation::{ElicitationManager, ElicitationResponse},
+    sampling::{self, SamplingBackendSlot, SamplingGrant},
+};
 use crate::{
     approval::{ApprovalManager, ApprovalMode, ApprovalOutcome, ApprovalParams},
     inventory::ToolInventory,
@@ -66,6 +73,8 @@ pub struct SmgClientHandler {
     client_info: ClientInfo,
     request_ctx: Arc<RwLock<Option<HandlerRequestContext>>>,
     refresh_tx: Option<mpsc::Sender<RefreshRequest>>,
+    sampling: Option<SamplingGrant>,
+    elicitations: Option<Arc<ElicitationManager>>,
 }
 
 impl SmgClientHandler {
@@ -85,6 +94,8 @@ impl SmgClientHandler {
             client_info,
             request_ctx: Arc::new(RwLock::new(None)),
             refresh_tx: None,
+            sampling: None,
+            elicitations: None,
         }
     }
 
@@ -94,6 +105,26 @@ impl SmgClientHandler {
         self
     }
 
+    /// Allow this server to request LLM completions under `policy`.
+    #[must_use]
+    pub(crate) fn with_sampling(
+        mut self,
+        policy: ServerSamplingConfig,
+        backend: SamplingBackendSlot,
+    ) -> Self {
+        self.client_info.capabilities.sampling = Some(Default::default());
+        self.sampling = Some(SamplingGrant { policy, backend });
+        self
+    }
+
+    /// Forward elicitations to the API client when a paused-call-capable
+    /// request is waiting on this server.
+    #[must_use]
+    pub(crate) fn with_elicitation_forwarding(mut self, manager: Arc<ElicitationManager>) -> Self {
+        self.elicitations = Some(manager);
+        self
+    }
+
     #[must_use]
     pub fn with_client_info(mut self, info: ClientInfo) -> Self {
         self.client_info = info;
@@ -141,6 +172,23 @@ impl ClientHandler for SmgClientHandler {
     ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
         use crate::annotations::ToolAnnotations;
 
+        if let Some(manager) = &self.elicitations {
+            let params = serde_json::to_value(&request)
+                .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+            if let Some(rx) = manager.forward(
+                &self.server_key,
+                params["message"].as_str().unwrap_or_default().to_string(),
+                params.get("requestedSchema").cloned(),
+                params["url"].as_str().map(str::to_string),
+            ) {
+                info!(server_key = %self.server_key, "Forwarding MCP elicitation to client");
+                // A dropped sender means the paused call expired or was cancelled.
+                let response = rx.await.unwrap_or_else(|_| ElicitationResponse::cancel());
+                return serde_json::from_value(response.result_json())
+                    .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None));
+            }
+        }
+
         let elicitation_id = match &context.id {
             rmcp::model::RequestId::String(s) => s.to_string(),
             rmcp::model::RequestId::Number(n) => n.to_string(),
@@ -168,6 +216,7 @@ impl ClientHandler for SmgClientHandler {
             elicitation_id: &elicitation_id,
             tool_name: "elicitation",
             hints: &hints,
+            arguments: None,
             message,
             tenant_ctx: &req_ctx.tenant_ctx,
         };
@@ -201,6 +250,43 @@ impl ClientHandler for SmgClientHandler {
         }
     }
 
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let grant = self.sampling.as_ref().ok_or_else(|| {
+            rmcp::ErrorData::invalid_request("Sampling is not enabled for this server", None)
+        })?;
+        let backend = grant.backend.get().cloned().ok_or_else(|| {
+            rmcp::ErrorData::internal_error("No sampling backend registered", None)
+        })?;
+
+        let params = serde_json::to_value(&params)
+            .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))?;
+        let tenant_id = self
+            .request_ctx
+            .read()
+            .as_ref()
+            .map(|ctx| ctx.tenant_ctx.tenant_id.clone());
+        let request = sampling::build_request(&params, &self.server_key, &grant.policy, tenant_id)
+            .map_err(|e| rmcp::ErrorData::invalid_params(e, None))?;
+
+        info!(
+            server_key = %self.server_key,
+            model = %request.model,
+            max_tokens = request.max_tokens,
+            "MCP server requested sampling"
+        );
+        let response = backend.create_message(request).await.map_err(|e| {
+            warn!(server_key = %self.server_key, error = %e, "MCP sampling failed");
+            rmcp::ErrorData::internal_error(e, None)
+        })?;
+
+        serde_json::from_value(sampling::result_json(&response))
+            .map_err(|e| rmcp::ErrorData::internal_error(e.to_string(), None))
+    }
+
     async fn on_cancelled(
         &self,
         params: CancelledNotificationParam,
@@ -362,6 +448,20 @@ mod tests {
         assert_eq!(info.client_info.name, "smg");
     }
 
+    #[test]
+    fn test_sampling_grant_advertises_capability() {
+        let handler = test_handler();
+        assert!(handler.get_info().capabilities.sampling.is_none());
+
+        let policy = ServerSamplingConfig {
+            models: vec!["llama".to_string()],
+            max_tokens: 128,
+        };
+        let handler = handler.with_sampling(policy, Arc::default());
+        assert!(handler.get_info().capabilities.sampling.is_some());
+        assert!(handler.sampling.is_some());
+    }
+
     #[test]
     fn test_with_refresh_channel() {
         let (tx, _rx) = mpsc::channel(10);
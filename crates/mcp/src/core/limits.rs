@@ -0,0 +1,161 @@
+//! Size limits on tool arguments and results.
+//!
+//! Sizes are measured on the serialized JSON. Results are the serialized
+//! `CallToolResult` content list; truncation keeps whole leading items, cuts
+//! the first text item that does not fit, and appends a text marker so the
+//! model can tell the result is incomplete.
+
+use std::io;
+
+use serde_json::{json, Value};
+
+use super::config::{OversizedOutputAction, ToolLimitsConfig};
+use crate::error::{McpError, McpResult};
+
+/// Reject arguments over `max_argument_bytes`.
+pub(crate) fn check_arguments(arguments: &Value, limits: &ToolLimitsConfig) -> McpResult<()> {
+    let Some(max) = limits.max_argument_bytes else {
+        return Ok(());
+    };
+    let size = json_size(arguments);
+    if size > max {
+        return Err(McpError::InvalidArguments(format!(
+            "arguments are {size} bytes, over the {max} byte limit"
+        )));
+    }
+    Ok(())
+}
+
+/// Apply `max_output_bytes` to a tool result. `Err` carries the rejection
+/// message when the configured action is `reject`.
+pub(crate) fn limit_output(output: Value, limits: &ToolLimitsConfig) -> Result<Value, String> {
+    let Some(max) = limits.max_output_bytes else {
+        return Ok(output);
+    };
+    let size = json_size(&output);
+    if size <= max {
+        return Ok(output);
+    }
+    match limits.on_oversized_output {
+        OversizedOutputAction::Reject => Err(format!(
+            "tool output is {size} bytes, over the {max} byte limit"
+        )),
+        OversizedOutputAction::Truncate => Ok(truncate(output, size, max)),
+    }
+}
+
+fn truncate(output: Value, size: usize, max: usize) -> Value {
+    let marker = json!({
+        "type": "text",
+        "text": format!("[output truncated: {size} bytes exceeds the {max} byte limit]"),
+    });
+    let Value::Array(items) = output else {
+        return Value::Array(vec![marker]);
+    };
+
+    // Brackets plus the marker; each kept item also costs a separating comma.
+    let mut budget = max.saturating_sub(json_size(&marker) + 2);
+    let mut kept = Vec::new();
+    for mut item in items {
+        let item_size = json_size(&item) + 1;
+        if item_size <= budget {
+            budget -= item_size;
+            kept.push(item);
+            continue;
+        }
+        if let Some(Value::String(text)) = item.get_mut("text") {
+            // Escaping only grows the text, so this overestimates the overhead.
+            let overhead = item_size.saturating_sub(text.len());
+            let mut end = budget.saturating_sub(overhead).min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            if end > 0 {
+                text.truncate(end);
+                kept.push(item);
+            }
+        }
+        break;
+    }
+    kept.push(marker);
+    Value::Array(kept)
+}
+
+fn json_size(value: &Value) -> usize {
+    struct Counter(usize);
+
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    // Writing a `Value` to an infallible writer cannot fail.
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_output_bytes: usize, action: OversizedOutputAction) -> ToolLimitsConfig {
+        ToolLimitsConfig {
+            max_argument_bytes: Some(32),
+            max_output_bytes: Some(max_output_bytes),
+            on_oversized_output: action,
+            ..Default::default()
+        }
+    }
+
+    fn text(s: &str) -> Value {
+        json!({ "type": "text", "text": s })
+    }
+
+    #[test]
+    fn test_arguments_over_limit_rejected() {
+        let limits = limits(1024, OversizedOutputAction::Truncate);
+
+        assert!(check_arguments(&json!({ "q": "short" }), &limits).is_ok());
+        let err = check_arguments(&json!({ "q": "x".repeat(64) }), &limits).unwrap_err();
+        assert!(matches!(err, McpError::InvalidArguments(_)));
+        assert!(check_arguments(&json!({ "q": "x".repeat(64) }), &Default::default()).is_ok());
+    }
+
+    #[test]
+    fn test_output_within_limit_unchanged() {
+        let output = json!([text("hello")]);
+        let limited = limit_output(output.clone(), &limits(1024, OversizedOutputAction::Reject));
+        assert_eq!(limited.unwrap(), output);
+    }
+
+    #[test]
+    fn test_oversized_output_truncated_with_marker() {
+        let output = json!([text("intro"), text(&"é".repeat(500)), text("tail")]);
+        let limited = limit_output(output, &limits(300, OversizedOutputAction::Truncate)).unwrap();
+
+        let items = limited.as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], text("intro"));
+        let cut = items[1]["text"].as_str().unwrap();
+        assert!(!cut.is_empty() && cut.len() < 1000);
+        assert!(items[2]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("[output truncated:"));
+        assert!(json_size(&limited) <= 300);
+    }
+
+    #[test]
+    fn test_oversized_output_rejected() {
+        let output = json!([text(&"x".repeat(500))]);
+        let err = limit_output(output, &limits(300, OversizedOutputAction::Reject)).unwrap_err();
+        assert!(err.contains("over the 300 byte limit"));
+    }
+}
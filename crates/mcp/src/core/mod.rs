@@ -5,25 +5,36 @@
 pub const UNKNOWN_SERVER_KEY: &str = "unknown";
 
 pub mod config;
+pub mod elicitation;
 pub mod handler;
+mod limits;
 pub mod metrics;
 pub mod orchestrator;
 pub mod pool;
 pub mod proxy;
 pub mod reconnect;
+pub mod sampling;
 pub mod session;
+pub mod trace;
 
 pub use config::{
     ArgMappingConfig, BuiltinToolType, ConfigValidationError, McpConfig, McpServerConfig,
-    McpTransport, PolicyConfig, PolicyDecisionConfig, ResponseFormatConfig, ServerPolicyConfig,
-    Tool, ToolConfig, TrustLevelConfig,
+    McpTransport, OutputTokenLimitsConfig, OversizedOutputAction, PolicyConfig,
+    PolicyDecisionConfig, PolicyRuleConfig, ResponseFormatConfig, SamplingConfig,
+    ServerPolicyConfig, ServerSamplingConfig, Tool, ToolConfig, ToolLimitsConfig,
+    TruncationStrategy, TrustLevelConfig,
 };
+pub use elicitation::{ElicitationAction, ElicitationResponse, McpElicitationRequest};
 pub use handler::{HandlerRequestContext, RefreshRequest, SmgClientHandler};
 pub use metrics::{LatencySnapshot, McpMetrics, MetricsSnapshot};
 pub use orchestrator::{
-    McpOrchestrator, McpRequestContext, PendingToolExecution, ToolExecutionInput,
-    ToolExecutionOutput, ToolExecutionResult,
+    McpOrchestrator, McpRequestContext, PendingElicitationExecution, PendingToolExecution,
+    ToolExecutionInput, ToolExecutionOutput, ToolExecutionResult,
 };
 pub use pool::{McpConnectionPool, PoolKey};
 pub use reconnect::ReconnectionManager;
+pub use sampling::{
+    BoxedSamplingBackend, SamplingBackend, SamplingMessage, SamplingRequest, SamplingResponse,
+};
 pub use session::{McpServerBinding, McpToolSession, DEFAULT_SERVER_LABEL};
+pub use trace::{BoxedTraceInjector, NoopTraceInjector, TraceInjector};
@@ -45,26 +45,34 @@ use rmcp::{
 use serde_json::Value;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
 use super::{
-    config::{BuiltinToolType, McpConfig, McpProxyConfig, McpServerConfig, McpTransport},
+    config::{
+        BuiltinToolType, McpConfig, McpProxyConfig, McpServerConfig, McpTransport, ToolLimitsConfig,
+    },
+    elicitation::{
+        ElicitationManager, ElicitationResponse, McpElicitationRequest, SuspendedCall, ToolCallInfo,
+    },
     handler::{HandlerRequestContext, RefreshRequest, SmgClientHandler},
+    limits,
     metrics::McpMetrics,
     pool::{McpConnectionPool, PoolKey},
     reconnect::ReconnectionManager,
+    sampling::{BoxedSamplingBackend, SamplingBackendSlot},
+    trace::{trace_meta, BoxedTraceInjector, NoopTraceInjector, TracingHttpClient},
 };
 use crate::{
     approval::{
         audit::AuditLog, policy::PolicyEngine, ApprovalDecision, ApprovalManager, ApprovalMode,
-        ApprovalOutcome, ApprovalParams, McpApprovalRequest,
+        ApprovalOutcome, ApprovalParams, ApprovalScope, McpApprovalRequest,
     },
     error::{McpError, McpResult},
     inventory::{
         AliasTarget, ArgMapping, QualifiedToolName, ToolCategory, ToolEntry, ToolInventory,
         ALIAS_SERVER_KEY,
     },
-    tenant::TenantContext,
+    tenant::{TenantContext, TenantId},
 };
 
 /// Build request headers from token and custom headers.
@@ -145,6 +153,8 @@ enum ApprovalExecutionResult {
     Success(CallToolResult),
     /// Pending approval from user (interactive mode only).
     PendingApproval(McpApprovalRequest),
+    /// Call paused on an elicitation forwarded to the client.
+    PendingElicitation(McpElicitationRequest),
 }
 
 // ============================================================================
@@ -233,6 +243,7 @@ impl ToolExecutionOutput {
 pub enum ToolExecutionResult {
     Executed(ToolExecutionOutput),
     PendingApproval(PendingToolExecution),
+    PendingElicitation(PendingElicitationExecution),
 }
 
 /// Pending approval from resolved tool execution.
@@ -248,6 +259,20 @@ pub struct PendingToolExecution {
     pub duration: Duration,
 }
 
+/// Tool call paused on an elicitation, resumed with
+/// [`McpOrchestrator::resume_elicitation`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PendingElicitationExecution {
+    pub call_id: String,
+    pub tool_name: String,
+    pub server_key: String,
+    pub server_label: String,
+    pub arguments_str: String,
+    pub elicitation: McpElicitationRequest,
+    pub duration: Duration,
+}
+
 impl ToolExecutionResult {
     /// Convert the result to the legacy flattened output shape.
     #[must_use]
@@ -269,6 +294,21 @@ impl ToolExecutionResult {
                 ),
                 duration: pending.duration,
             },
+            Self::PendingElicitation(pending) => ToolExecutionOutput {
+                call_id: pending.call_id,
+                tool_name: pending.tool_name,
+                server_key: pending.server_key,
+                server_label: pending.server_label,
+                arguments_str: pending.arguments_str,
+                output: serde_json::json!({
+                    "error": "Tool requested user input (not supported in this context)"
+                }),
+                is_error: true,
+                error_message: Some(
+                    "Tool requested user input (not supported in this context)".to_string(),
+                ),
+                duration: pending.duration,
+            },
         }
     }
 }
@@ -292,6 +332,12 @@ pub struct McpOrchestrator {
     active_executions: Arc<AtomicUsize>,
     shutdown_token: CancellationToken,
     reconnection_locks: DashMap<String, Arc<tokio::sync::Mutex<()>>>,
+    /// Injects trace context into outgoing tool calls.
+    trace_injector: BoxedTraceInjector,
+    /// Gateway backend for server-initiated LLM calls, registered later.
+    sampling_backend: SamplingBackendSlot,
+    /// Elicitations forwarded to clients and the calls paused on them.
+    elicitations: Arc<ElicitationManager>,
     /// Original config for reference.
     config: McpConfig,
 }
@@ -324,11 +370,11 @@ impl McpOrchestrator {
         let inventory_clone = Arc::clone(&tool_inventory);
         connection_pool.set_eviction_callback(move |key: &PoolKey| {
             debug!(
-                "LRU evicted dynamic server '{}' (tenant: {:?}) - clearing tools from inventory",
+                "LRU evicted dynamic server '{}' (tenant: {:?}) - releasing tenant access",
                 key.url, key.tenant_id
             );
-            // Tools are registered by URL, so clear by URL
-            inventory_clone.clear_server_tools(&key.url);
+            // Tools are registered by URL and cleared once no tenant holds it
+            inventory_clone.release_server(&key.url, &key.tenant(), key.auth_hash);
         });
 
         let connection_pool = Arc::new(connection_pool);
@@ -346,6 +392,9 @@ impl McpOrchestrator {
             active_executions: Arc::new(AtomicUsize::new(0)),
             shutdown_token: CancellationToken::new(),
             reconnection_locks: DashMap::new(),
+            trace_injector: Arc::new(NoopTraceInjector),
+            sampling_backend: Arc::default(),
+            elicitations: Arc::new(ElicitationManager::new()),
             config: config.clone(),
         };
 
@@ -399,10 +448,29 @@ impl McpOrchestrator {
             active_executions: Arc::new(AtomicUsize::new(0)),
             shutdown_token: CancellationToken::new(),
             reconnection_locks: DashMap::new(),
+            trace_injector: Arc::new(NoopTraceInjector),
+            sampling_backend: Arc::default(),
+            elicitations: Arc::new(ElicitationManager::new()),
             config,
         }
     }
 
+    /// Register the backend that serves MCP sampling requests. Servers
+    /// connected earlier pick it up too; only the first registration wins.
+    pub fn set_sampling_backend(&self, backend: BoxedSamplingBackend) {
+        if self.sampling_backend.set(backend).is_err() {
+            warn!("MCP sampling backend already registered, ignoring");
+        }
+    }
+
+    /// Set the trace injector used to propagate trace context and baggage
+    /// into tool calls.
+    #[must_use]
+    pub fn with_trace_injector(mut self, trace_injector: BoxedTraceInjector) -> Self {
+        self.trace_injector = trace_injector;
+        self
+    }
+
     // ========================================================================
     // Server Connection
     // ========================================================================
@@ -428,14 +496,17 @@ impl McpOrchestrator {
 
         info!("Connecting to static server '{}'", config.name);
 
-        let handler = Arc::new(
-            SmgClientHandler::new(
-                &config.name,
-                Arc::clone(&self.approval_manager),
-                Arc::clone(&self.tool_inventory),
-            )
-            .with_refresh_channel(self.refresh_tx.clone()),
-        );
+        let mut handler = SmgClientHandler::new(
+            &config.name,
+            Arc::clone(&self.approval_manager),
+            Arc::clone(&self.tool_inventory),
+        )
+        .with_refresh_channel(self.refresh_tx.clone())
+        .with_elicitation_forwarding(Arc::clone(&self.elicitations));
+        if let Some(policy) = self.config.sampling.servers.get(&config.name) {
+            handler = handler.with_sampling(policy.clone(), Arc::clone(&self.sampling_backend));
+        }
+        let handler = Arc::new(handler);
 
         let client = self.connect_server_impl(config, (*handler).clone()).await?;
         let client = Arc::new(client);
@@ -566,7 +637,10 @@ impl McpOrchestrator {
                     build_http_client(proxy_config, token.as_deref(), custom_headers)?;
                 let cfg = StreamableHttpClientTransportConfig::with_uri(url.as_str());
 
-                let transport = StreamableHttpClientTransport::with_client(http_client, cfg);
+                let transport = StreamableHttpClientTransport::with_client(
+                    TracingHttpClient::new(http_client),
+                    cfg,
+                );
 
                 handler.serve(transport).await.map_err(|e| {
                     McpError::ConnectionFailed(format!("initialize streamable client: {e}"))
@@ -790,30 +864,29 @@ impl McpOrchestrator {
         let arguments_str = input.arguments.to_string();
 
         let qualified = QualifiedToolName::new(server_key, &input.tool_name);
-        let entry = self.tool_inventory.get_entry(server_key, &input.tool_name);
+        let entry = self.tool_inventory.get_entry_for_tenant(
+            server_key,
+            &input.tool_name,
+            &request_ctx.tenant_ctx.tenant_id,
+        );
 
         match entry {
-            Some(entry) => match self
-                .execute_tool_entry_result(&entry, qualified, input.arguments, request_ctx)
-                .await
-            {
-                ToolExecutionResult::Executed(mut output) => {
-                    output.call_id = input.call_id;
-                    output.tool_name = input.tool_name;
-                    output.server_key = server_key.to_string();
-                    output.server_label = server_label.to_string();
-                    output.arguments_str = arguments_str;
-                    ToolExecutionResult::Executed(output)
-                }
-                ToolExecutionResult::PendingApproval(mut pending) => {
-                    pending.call_id = input.call_id;
-                    pending.tool_name = input.tool_name;
-                    pending.server_key = server_key.to_string();
-                    pending.server_label = server_label.to_string();
-                    pending.arguments_str = arguments_str;
-                    ToolExecutionResult::PendingApproval(pending)
-                }
-            },
+            Some(entry) => {
+                let mut result = self
+                    .execute_tool_entry_result(&entry, qualified, input.arguments, request_ctx)
+                    .await;
+                self.attach_call_info(
+                    &mut result,
+                    ToolCallInfo {
+                        call_id: input.call_id,
+                        tool_name: input.tool_name,
+                        server_key: server_key.to_string(),
+                        server_label: server_label.to_string(),
+                        arguments_str,
+                    },
+                );
+                result
+            }
             None => {
                 let err = format!(
                     "Tool '{}' not found on server '{}'",
@@ -848,72 +921,146 @@ impl McpOrchestrator {
         self.metrics.record_call_start(&qualified);
         let call_start_time = Instant::now();
 
-        let result = match self
+        let span = info_span!(
+            target: "smg::otel-trace",
+            "mcp_tool_call",
+            server = %entry.server_key(),
+            tool = %entry.tool_name(),
+            request_id = %request_ctx.request_id,
+        );
+        let raw = self
             .execute_tool_with_approval_raw_internal(entry, arguments, request_ctx)
-            .await
-        {
+            .instrument(span)
+            .await;
+        let result = self.execution_result(&entry.qualified_name, raw, call_start_time.elapsed());
+
+        let succeeded =
+            !matches!(&result, ToolExecutionResult::Executed(output) if output.is_error);
+        let duration_ms = call_start_time.elapsed().as_millis() as u64;
+        self.metrics
+            .record_call_end(&qualified, succeeded, duration_ms);
+        result
+    }
+
+    /// Shape a raw execution result. Caller-facing fields (call id, label,
+    /// arguments) are left empty for [`Self::attach_call_info`].
+    fn execution_result(
+        &self,
+        tool: &QualifiedToolName,
+        raw: McpResult<ApprovalExecutionResult>,
+        duration: Duration,
+    ) -> ToolExecutionResult {
+        match raw {
             Ok(ApprovalExecutionResult::Success(raw_result)) => {
+                let (output, is_error, error_message) = match limits::limit_output(
+                    Self::call_result_to_json(&raw_result),
+                    &self.config.limits,
+                ) {
+                    Ok(output) => (output, raw_result.is_error.unwrap_or(false), None),
+                    Err(err) => {
+                        warn!("Rejected output of tool '{}': {}", tool.tool_name(), err);
+                        (serde_json::json!({ "error": &err }), true, Some(err))
+                    }
+                };
                 ToolExecutionResult::Executed(ToolExecutionOutput {
                     call_id: String::new(),
-                    tool_name: entry.tool_name().to_string(),
-                    server_key: entry.server_key().to_string(),
-                    server_label: entry.server_key().to_string(),
+                    tool_name: tool.tool_name().to_string(),
+                    server_key: tool.server_key().to_string(),
+                    server_label: tool.server_key().to_string(),
                     arguments_str: String::new(),
-                    output: Self::call_result_to_json(&raw_result),
-                    is_error: raw_result.is_error.unwrap_or(false),
-                    error_message: None,
-                    duration: call_start_time.elapsed(),
+                    output,
+                    is_error,
+                    error_message,
+                    duration,
+                })
+            }
+            Ok(ApprovalExecutionResult::PendingElicitation(elicitation)) => {
+                ToolExecutionResult::PendingElicitation(PendingElicitationExecution {
+                    call_id: String::new(),
+                    tool_name: tool.tool_name().to_string(),
+                    server_key: tool.server_key().to_string(),
+                    server_label: tool.server_key().to_string(),
+                    arguments_str: String::new(),
+                    elicitation,
+                    duration,
                 })
             }
             Ok(ApprovalExecutionResult::PendingApproval(approval_request)) => {
                 ToolExecutionResult::PendingApproval(PendingToolExecution {
                     call_id: String::new(),
-                    tool_name: entry.tool_name().to_string(),
-                    server_key: entry.server_key().to_string(),
-                    server_label: entry.server_key().to_string(),
+                    tool_name: tool.tool_name().to_string(),
+                    server_key: tool.server_key().to_string(),
+                    server_label: tool.server_key().to_string(),
                     arguments_str: String::new(),
                     approval_request,
-                    duration: call_start_time.elapsed(),
+                    duration,
                 })
             }
             Err(e) => {
                 let err = format!("Tool call failed: {e}");
                 ToolExecutionResult::Executed(ToolExecutionOutput {
                     call_id: String::new(),
-                    tool_name: entry.tool_name().to_string(),
-                    server_key: entry.server_key().to_string(),
-                    server_label: entry.server_key().to_string(),
+                    tool_name: tool.tool_name().to_string(),
+                    server_key: tool.server_key().to_string(),
+                    server_label: tool.server_key().to_string(),
                     arguments_str: String::new(),
                     output: serde_json::json!({ "error": &err }),
                     is_error: true,
                     error_message: Some(err),
-                    duration: call_start_time.elapsed(),
+                    duration,
                 })
             }
-        };
+        }
+    }
 
-        let succeeded =
-            !matches!(&result, ToolExecutionResult::Executed(output) if output.is_error);
-        let duration_ms = call_start_time.elapsed().as_millis() as u64;
-        self.metrics
-            .record_call_end(&qualified, succeeded, duration_ms);
-        result
+    /// Fill in the caller's view of a call, and remember it for a paused call
+    /// so the resumed result reports it too.
+    fn attach_call_info(&self, result: &mut ToolExecutionResult, info: ToolCallInfo) {
+        match result {
+            ToolExecutionResult::Executed(output) => {
+                output.call_id = info.call_id;
+                output.tool_name = info.tool_name;
+                output.server_key = info.server_key;
+                output.server_label = info.server_label;
+                output.arguments_str = info.arguments_str;
+            }
+            ToolExecutionResult::PendingApproval(pending) => {
+                pending.call_id = info.call_id;
+                pending.tool_name = info.tool_name;
+                pending.server_key = info.server_key;
+                pending.server_label = info.server_label;
+                pending.arguments_str = info.arguments_str;
+            }
+            ToolExecutionResult::PendingElicitation(pending) => {
+                pending.call_id.clone_from(&info.call_id);
+                pending.tool_name.clone_from(&info.tool_name);
+                pending.server_key.clone_from(&info.server_key);
+                pending.server_label.clone_from(&info.server_label);
+                pending.arguments_str.clone_from(&info.arguments_str);
+                self.elicitations
+                    .describe(&pending.elicitation.elicitation_id, info);
+            }
+        }
     }
 
     /// Internal implementation of approval-checked tool execution.
-    /// Returns either the raw `CallToolResult` or a pending approval request.
+    /// Returns the raw `CallToolResult`, a pending approval request, or an
+    /// elicitation the call is paused on.
     async fn execute_tool_with_approval_raw_internal(
         &self,
         entry: &ToolEntry,
         arguments: Value,
         request_ctx: &McpRequestContext<'_>,
     ) -> McpResult<ApprovalExecutionResult> {
+        limits::check_arguments(&arguments, &self.config.limits)?;
+
         let approval_params = ApprovalParams {
             request_id: &request_ctx.request_id,
             server_key: entry.server_key(),
             elicitation_id: &format!("tool-{}", entry.tool_name()),
             tool_name: entry.tool_name(),
             hints: &entry.annotations,
+            arguments: Some(&arguments),
             message: &format!("Allow execution of '{}'?", entry.tool_name()),
             tenant_ctx: &request_ctx.tenant_ctx,
         };
@@ -930,8 +1077,8 @@ impl McpOrchestrator {
                     return Err(McpError::ToolDenied(entry.tool_name().to_string()));
                 }
                 self.metrics.record_approval_granted();
-                let result = self.execute_tool_with_reconnect(entry, arguments).await?;
-                Ok(ApprovalExecutionResult::Success(result))
+                self.execute_allowed_tool(entry, arguments, request_ctx)
+                    .await
             }
             ApprovalOutcome::Pending {
                 approval_request,
@@ -949,8 +1096,8 @@ impl McpOrchestrator {
                 match rx.await {
                     Ok(ApprovalDecision::Approved) => {
                         self.metrics.record_approval_granted();
-                        let result = self.execute_tool_with_reconnect(entry, arguments).await?;
-                        Ok(ApprovalExecutionResult::Success(result))
+                        self.execute_allowed_tool(entry, arguments, request_ctx)
+                            .await
                     }
                     Ok(ApprovalDecision::Denied { reason }) => {
                         self.metrics.record_approval_denied();
@@ -961,6 +1108,65 @@ impl McpOrchestrator {
             }
         }
     }
+
+    /// Execute an approved tool. When the request forwards elicitations and
+    /// the tool runs on a static server, the call runs in the background so
+    /// an elicitation can pause it; otherwise it is a plain call.
+    async fn execute_allowed_tool(
+        &self,
+        entry: &ToolEntry,
+        arguments: Value,
+        request_ctx: &McpRequestContext<'_>,
+    ) -> McpResult<ApprovalExecutionResult> {
+        if request_ctx.forward_elicitations {
+            let (target_server, request) = self.build_call_request(entry, arguments.clone());
+            let peer = self
+                .static_servers
+                .get(&target_server)
+                .map(|server| server.client.peer().clone());
+            if let Some(peer) = peer {
+                let waiter = self.elicitations.watch(&target_server);
+                #[expect(
+                    clippy::disallowed_methods,
+                    reason = "the handle is awaited or parked with its elicitation; expiry and shutdown abort it"
+                )]
+                let call = tokio::spawn(async move {
+                    peer.call_tool(request)
+                        .await
+                        .map_err(|e| Self::call_error(&target_server, e))
+                });
+                return self
+                    .await_call(SuspendedCall {
+                        call,
+                        waiter,
+                        qualified: entry.qualified_name.clone(),
+                        tenant_id: request_ctx.tenant_ctx.tenant_id.clone(),
+                        info: ToolCallInfo::default(),
+                        started: Instant::now(),
+                    })
+                    .await;
+            }
+        }
+
+        let result = self.execute_tool_with_reconnect(entry, arguments).await?;
+        Ok(ApprovalExecutionResult::Success(result))
+    }
+
+    /// Wait for a background call to finish, or park it on the next
+    /// elicitation its server sends.
+    async fn await_call(&self, mut call: SuspendedCall) -> McpResult<ApprovalExecutionResult> {
+        let elicitation = tokio::select! {
+            joined = &mut call.call => {
+                let result = joined
+                    .map_err(|e| McpError::ToolExecution(format!("tool call task failed: {e}")))??;
+                return Ok(ApprovalExecutionResult::Success(result));
+            }
+            Some(elicitation) = call.waiter.recv() => elicitation,
+        };
+        self.elicitations.suspend(&elicitation.elicitation_id, call);
+        Ok(ApprovalExecutionResult::PendingElicitation(elicitation))
+    }
+
     async fn execute_tool_with_reconnect(
         &self,
         entry: &ToolEntry,
@@ -1035,8 +1241,19 @@ impl McpOrchestrator {
     async fn execute_tool_impl(
         &self,
         entry: &ToolEntry,
-        mut arguments: Value,
+        arguments: Value,
     ) -> McpResult<CallToolResult> {
+        let (target_server, request) = self.build_call_request(entry, arguments);
+        self.execute_on_server(&target_server, request).await
+    }
+
+    /// Resolve aliases and argument mappings into the server to call and the
+    /// request to send it.
+    fn build_call_request(
+        &self,
+        entry: &ToolEntry,
+        mut arguments: Value,
+    ) -> (String, CallToolRequestParams) {
         // Resolve alias if needed
         let (target_server, target_tool) = if let Some(alias) = &entry.alias_target {
             // Apply argument mapping
@@ -1067,9 +1284,8 @@ impl McpOrchestrator {
         if let Value::Object(map) = arguments {
             request = request.with_arguments(map);
         }
-
-        // Execute on server
-        self.execute_on_server(&target_server, request).await
+        request.meta = trace_meta(self.trace_injector.as_ref());
+        (target_server, request)
     }
 
     /// Coerce argument types based on tool schema.
@@ -1144,13 +1360,11 @@ impl McpOrchestrator {
         request: CallToolRequestParams,
     ) -> McpResult<CallToolResult> {
         if let Some(entry) = self.static_servers.get(server_key) {
-            return entry.client.call_tool(request).await.map_err(|e| match e {
-                // Typed detection for transport-level failures
-                ServiceError::TransportClosed | ServiceError::TransportSend(_) => {
-                    McpError::ServerDisconnected(server_key.to_string())
-                }
-                _ => McpError::ToolExecution(format!("MCP call failed: {e}")),
-            });
+            return entry
+                .client
+                .call_tool(request)
+                .await
+                .map_err(|e| Self::call_error(server_key, e));
         }
 
         if let Some(client) = self.connection_pool.get_by_url(server_key) {
@@ -1167,6 +1381,16 @@ impl McpOrchestrator {
         Err(McpError::ServerNotFound(server_key.to_string()))
     }
 
+    fn call_error(server_key: &str, e: ServiceError) -> McpError {
+        match e {
+            // Typed detection for transport-level failures
+            ServiceError::TransportClosed | ServiceError::TransportSend(_) => {
+                McpError::ServerDisconnected(server_key.to_string())
+            }
+            _ => McpError::ToolExecution(format!("MCP call failed: {e}")),
+        }
+    }
+
     // ========================================================================
     // Alias Registration
     // ========================================================================
@@ -1317,6 +1541,7 @@ impl McpOrchestrator {
 
         // Extract server_key from pool_key to avoid double URL extraction
         let server_key = pool_key.url.clone();
+        let (tenant, connection) = (pool_key.tenant(), pool_key.auth_hash);
 
         // Connect via the pool
         let inventory_clone = Arc::clone(&self.tool_inventory);
@@ -1337,8 +1562,10 @@ impl McpOrchestrator {
                             build_http_client(proxy_config, token.as_deref(), custom_headers)?;
                         let cfg_http = StreamableHttpClientTransportConfig::with_uri(url.as_str());
 
-                        let transport =
-                            StreamableHttpClientTransport::with_client(http_client, cfg_http);
+                        let transport = StreamableHttpClientTransport::with_client(
+                            TracingHttpClient::new(http_client),
+                            cfg_http,
+                        );
 
                         ().serve(transport)
                             .await
@@ -1352,6 +1579,9 @@ impl McpOrchestrator {
             })
             .await?;
 
+        // Grant before inserting so the tools are never visible to everyone.
+        inventory_clone.grant_server(&server_key, &tenant, connection);
+
         // Pooled connections are keyed by URL.
         match client.peer().list_all_tools().await {
             Ok(tools) => {
@@ -1378,37 +1608,48 @@ impl McpOrchestrator {
     // ========================================================================
 
     /// List all tools visible to a tenant.
-    pub fn list_tools(&self, _tenant_ctx: Option<&TenantContext>) -> Vec<ToolEntry> {
+    ///
+    /// Dynamic servers are only visible to tenants holding a connection to
+    /// them; `None` is the default tenant.
+    pub fn list_tools(&self, tenant_ctx: Option<&TenantContext>) -> Vec<ToolEntry> {
         self.tool_inventory
-            .list_tools()
-            .into_iter()
-            .filter_map(|(tool_name, server_key, _)| {
-                self.tool_inventory.get_entry(&server_key, &tool_name)
-            })
-            .collect()
+            .list_tools_for_tenant(&Self::tenant_id(tenant_ctx))
     }
 
-    /// List tools for specific servers.
-    pub fn list_tools_for_servers(&self, server_keys: &[String]) -> Vec<ToolEntry> {
+    /// List tools for specific servers that are visible to a tenant.
+    pub fn list_tools_for_servers(
+        &self,
+        server_keys: &[String],
+        tenant_ctx: Option<&TenantContext>,
+    ) -> Vec<ToolEntry> {
         // For small server lists (typical case: 1-5), linear scan is faster than HashSet
         let is_allowed = |server_key: &str| -> bool { server_keys.iter().any(|s| s == server_key) };
 
-        self.tool_inventory
-            .list_tools()
-            .into_iter()
-            .filter_map(|(tool_name, server_key, _)| {
-                if is_allowed(&server_key) {
-                    self.tool_inventory.get_entry(&server_key, &tool_name)
-                } else {
-                    None
-                }
-            })
-            .collect()
+        let mut tools = self
+            .tool_inventory
+            .list_tools_for_tenant(&Self::tenant_id(tenant_ctx));
+        tools.retain(|entry| is_allowed(entry.server_key()));
+        tools
+    }
+
+    /// Get a tool by qualified name, if visible to a tenant.
+    pub fn get_tool(
+        &self,
+        server_key: &str,
+        tool_name: &str,
+        tenant_ctx: Option<&TenantContext>,
+    ) -> Option<ToolEntry> {
+        self.tool_inventory.get_entry_for_tenant(
+            server_key,
+            tool_name,
+            &Self::tenant_id(tenant_ctx),
+        )
     }
 
-    /// Get a tool by qualified name.
-    pub fn get_tool(&self, server_key: &str, tool_name: &str) -> Option<ToolEntry> {
-        self.tool_inventory.get_entry(server_key, tool_name)
+    fn tenant_id(tenant_ctx: Option<&TenantContext>) -> TenantId {
+        tenant_ctx
+            .map(|ctx| ctx.tenant_id.clone())
+            .unwrap_or_default()
     }
 
     /// Check if a tool exists.
@@ -1446,6 +1687,11 @@ impl McpOrchestrator {
         Arc::clone(&self.metrics)
     }
 
+    /// Get the configured tool call limits.
+    pub fn tool_limits(&self) -> &ToolLimitsConfig {
+        &self.config.limits
+    }
+
     // ========================================================================
     // Interactive Mode API (Issue #103)
     // ========================================================================
@@ -1475,11 +1721,68 @@ impl McpOrchestrator {
             .await
     }
 
+    /// Resolve a pending approval and keep the decision for later calls to
+    /// the same tool, as chosen by the user (see [`ApprovalScope`]).
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors resolve_approval() plus the scope"
+    )]
+    pub async fn resolve_approval_with_scope(
+        &self,
+        request_id: &str,
+        server_key: &str,
+        elicitation_id: &str,
+        approved: bool,
+        reason: Option<String>,
+        scope: ApprovalScope,
+        tenant_ctx: &TenantContext,
+    ) -> McpResult<()> {
+        self.approval_manager
+            .resolve_with_scope(
+                request_id,
+                server_key,
+                elicitation_id,
+                approved,
+                reason,
+                scope,
+                tenant_ctx,
+            )
+            .await
+    }
+
     /// Get the count of pending approvals for a request.
     pub fn pending_approval_count(&self) -> usize {
         self.approval_manager.pending_count()
     }
 
+    /// Answer a forwarded elicitation and continue the paused tool call.
+    ///
+    /// Returns the call's result, or the next elicitation if the server asks
+    /// for more input.
+    pub async fn resume_elicitation(
+        &self,
+        elicitation_id: &str,
+        response: ElicitationResponse,
+        tenant_ctx: &TenantContext,
+    ) -> McpResult<ToolExecutionResult> {
+        let call = self
+            .elicitations
+            .resolve(elicitation_id, response, &tenant_ctx.tenant_id)?;
+        let qualified = call.qualified.clone();
+        let info = call.info.clone();
+        let started = call.started;
+
+        let raw = self.await_call(call).await;
+        let mut result = self.execution_result(&qualified, raw, started.elapsed());
+        self.attach_call_info(&mut result, info);
+        Ok(result)
+    }
+
+    /// Get the count of elicitations awaiting a client answer.
+    pub fn pending_elicitation_count(&self) -> usize {
+        self.elicitations.pending_count()
+    }
+
     /// Determine the approval mode based on API type.
     ///
     /// | API                      | Mode         |
@@ -1520,8 +1823,9 @@ impl McpOrchestrator {
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
-        // Cancel pending approvals
+        // Cancel pending approvals and paused calls
         self.approval_manager.cancel_all_pending();
+        self.elicitations.cancel_all_pending();
 
         for _ in &self.static_servers {
             self.metrics.record_connection_closed();
@@ -1545,6 +1849,9 @@ pub struct McpRequestContext<'a> {
     pub tenant_ctx: TenantContext,
     pub approval_mode: ApprovalMode,
     pub forwarded_headers: HashMap<String, String>,
+    /// Pause tool calls on server elicitations instead of failing them. Set
+    /// by callers that can hand elicitations to the client and resume.
+    pub forward_elicitations: bool,
     /// Dynamic tools added for this request only.
     dynamic_tools: DashMap<QualifiedToolName, ToolEntry>,
     /// Dynamic server clients for this request.
@@ -1565,6 +1872,7 @@ impl<'a> McpRequestContext<'a> {
             tenant_ctx,
             approval_mode,
             forwarded_headers,
+            forward_elicitations: false,
             dynamic_tools: DashMap::new(),
             dynamic_clients: DashMap::new(),
         }
@@ -1733,6 +2041,7 @@ mod tests {
             elicitation_id: "elicit-1",
             tool_name: "test_tool",
             hints: &hints,
+            arguments: None,
             message: "Allow tool execution?",
             tenant_ctx: &ctx.tenant_ctx,
         };
@@ -1965,6 +2274,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_list_tools_scoped_to_tenant() {
+        let orchestrator = McpOrchestrator::new_test();
+        let inventory = &orchestrator.tool_inventory;
+        let acme = TenantContext::new("acme");
+
+        inventory.insert_tool(
+            "search".to_string(),
+            "static".to_string(),
+            create_test_tool("search"),
+        );
+        inventory.grant_server("http://dyn", &acme.tenant_id, 1);
+        inventory.insert_tool(
+            "lookup".to_string(),
+            "http://dyn".to_string(),
+            create_test_tool("lookup"),
+        );
+
+        assert_eq!(orchestrator.list_tools(Some(&acme)).len(), 2);
+        let others = orchestrator.list_tools(Some(&TenantContext::new("globex")));
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0].tool_name(), "search");
+        assert_eq!(orchestrator.list_tools(None).len(), 1);
+    }
+
+    #[test]
+    fn test_server_queries_scoped_to_tenant() {
+        let orchestrator = McpOrchestrator::new_test();
+        let inventory = &orchestrator.tool_inventory;
+        let (acme, globex) = (TenantContext::new("acme"), TenantContext::new("globex"));
+
+        inventory.grant_server("http://dyn", &acme.tenant_id, 1);
+        inventory.insert_tool(
+            "lookup".to_string(),
+            "http://dyn".to_string(),
+            create_test_tool("lookup"),
+        );
+        let servers = vec!["http://dyn".to_string()];
+
+        assert_eq!(
+            orchestrator
+                .list_tools_for_servers(&servers, Some(&acme))
+                .len(),
+            1
+        );
+        assert!(orchestrator
+            .list_tools_for_servers(&servers, Some(&globex))
+            .is_empty());
+        assert!(orchestrator
+            .list_tools_for_servers(&servers, None)
+            .is_empty());
+        assert!(orchestrator
+            .get_tool("http://dyn", "lookup", Some(&acme))
+            .is_some());
+        assert!(orchestrator
+            .get_tool("http://dyn", "lookup", Some(&globex))
+            .is_none());
+    }
+
     #[test]
     fn test_metrics_access() {
         let orchestrator = McpOrchestrator::new_test();
@@ -2133,6 +2501,9 @@ mod tests {
             active_executions: Arc::new(AtomicUsize::new(0)),
             shutdown_token: CancellationToken::new(),
             reconnection_locks: DashMap::new(),
+            trace_injector: Arc::new(NoopTraceInjector),
+            sampling_backend: Arc::default(),
+            elicitations: Arc::new(ElicitationManager::new()),
             config,
         };
 
@@ -2202,6 +2573,9 @@ mod tests {
             active_executions: Arc::new(AtomicUsize::new(0)),
             shutdown_token: CancellationToken::new(),
             reconnection_locks: DashMap::new(),
+            trace_injector: Arc::new(NoopTraceInjector),
+            sampling_backend: Arc::default(),
+            elicitations: Arc::new(ElicitationManager::new()),
             config,
         };
 
@@ -14,7 +14,7 @@ use parking_lot::Mutex;
 use rmcp::{service::RunningService, RoleClient};
 
 use super::config::{McpProxyConfig, McpServerConfig, McpTransport};
-use crate::error::McpResult;
+use crate::{error::McpResult, tenant::TenantId};
 
 type McpClient = RunningService<RoleClient, ()>;
 type EvictionCallback = Arc<dyn Fn(&PoolKey) + Send + Sync>;
@@ -38,6 +38,15 @@ impl PoolKey {
         }
     }
 
+    /// Tenant holding this connection; untagged connections belong to the
+    /// default tenant.
+    pub fn tenant(&self) -> TenantId {
+        self.tenant_id
+            .as_deref()
+            .map(TenantId::from)
+            .unwrap_or_default()
+    }
+
     pub fn from_config(config: &McpServerConfig, tenant_id: Option<String>) -> Self {
         let (url, auth_hash) = match &config.transport {
             McpTransport::Streamable {
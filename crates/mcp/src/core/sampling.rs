@@ -0,0 +1,246 @@
+//! MCP sampling: server-initiated LLM calls.
+//!
+//! A server with a sampling policy may ask the gateway for a completion. The
+//! crate does not route model traffic itself; the gateway registers a
+//! [`SamplingBackend`] that sends the request through its own routing. The
+//! backend is registered after the orchestrator is built (routers come up
+//! later), so handlers hold a shared slot rather than the backend itself.
+//!
+//! Only text content is supported. The model is chosen from the server's
+//! allowlist using the request's model hints, and `max_tokens` is capped.
+
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::config::ServerSamplingConfig;
+use crate::tenant::TenantId;
+
+/// A text message in a sampling conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SamplingMessage {
+    /// `user` or `assistant`.
+    pub role: String,
+    pub text: String,
+}
+
+/// Sampling request after the server's policy has been applied.
+#[derive(Debug, Clone)]
+pub struct SamplingRequest {
+    /// Server that asked for the completion.
+    pub server_key: String,
+    /// Tenant of the request that triggered the tool call, if any.
+    pub tenant_id: Option<TenantId>,
+    /// Model from the server's allowlist.
+    pub model: String,
+    pub system_prompt: Option<String>,
+    pub messages: Vec<SamplingMessage>,
+    /// Already capped by the server's policy.
+    pub max_tokens: u32,
+    pub temperature: Option<f32>,
+    pub stop_sequences: Vec<String>,
+}
+
+/// Completion returned to the server.
+#[derive(Debug, Clone)]
+pub struct SamplingResponse {
+    pub model: String,
+    pub text: String,
+    /// MCP stop reason (`endTurn`, `maxTokens`, `stopSequence`, ...).
+    pub stop_reason: Option<String>,
+}
+
+/// Runs sampling requests through the gateway's model routing.
+#[async_trait]
+pub trait SamplingBackend: Send + Sync {
+    async fn create_message(&self, request: SamplingRequest) -> Result<SamplingResponse, String>;
+}
+
+/// Type alias for a shared sampling backend.
+pub type BoxedSamplingBackend = Arc<dyn SamplingBackend>;
+
+/// Slot filled once the gateway registers its backend.
+pub(crate) type SamplingBackendSlot = Arc<OnceLock<BoxedSamplingBackend>>;
+
+/// Sampling policy and backend slot for one server's handler.
+#[derive(Clone)]
+pub(crate) struct SamplingGrant {
+    pub policy: ServerSamplingConfig,
+    pub backend: SamplingBackendSlot,
+}
+
+/// Build a [`SamplingRequest`] from `sampling/createMessage` params.
+pub(crate) fn build_request(
+    params: &Value,
+    server_key: &str,
+    policy: &ServerSamplingConfig,
+    tenant_id: Option<TenantId>,
+) -> Result<SamplingRequest, String> {
+    let messages = params
+        .get("messages")
+        .and_then(Value::as_array)
+        .ok_or("sampling request has no messages")?
+        .iter()
+        .map(parse_message)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let hints = params
+        .pointer("/modelPreferences/hints")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.get("name").and_then(Value::as_str));
+    let model = choose_model(hints, &policy.models)
+        .ok_or("no models are allowed for sampling")?
+        .to_string();
+
+    let requested = params
+        .get("maxTokens")
+        .and_then(Value::as_u64)
+        .map_or(policy.max_tokens, |n| u32::try_from(n).unwrap_or(u32::MAX));
+
+    Ok(SamplingRequest {
+        server_key: server_key.to_string(),
+        tenant_id,
+        model,
+        system_prompt: params
+            .get("systemPrompt")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        messages,
+        max_tokens: requested.min(policy.max_tokens),
+        temperature: params
+            .get("temperature")
+            .and_then(Value::as_f64)
+            .map(|t| t as f32),
+        stop_sequences: params
+            .get("stopSequences")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|s| s.as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+/// Wire form of a `sampling/createMessage` result.
+pub(crate) fn result_json(response: &SamplingResponse) -> Value {
+    let mut result = json!({
+        "role": "assistant",
+        "content": { "type": "text", "text": response.text },
+        "model": response.model,
+    });
+    if let Some(stop_reason) = &response.stop_reason {
+        result["stopReason"] = json!(stop_reason);
+    }
+    result
+}
+
+fn parse_message(message: &Value) -> Result<SamplingMessage, String> {
+    let role = message
+        .get("role")
+        .and_then(Value::as_str)
+        .ok_or("sampling message has no role")?;
+    // Content is a single block, or a list of blocks in newer revisions.
+    let blocks = match message.get("content") {
+        Some(Value::Array(blocks)) => blocks.iter().collect(),
+        Some(block) => vec![block],
+        None => Vec::new(),
+    };
+    let mut text = String::new();
+    for block in blocks {
+        match (block.get("type").and_then(Value::as_str), block.get("text")) {
+            (Some("text"), Some(Value::String(s))) => text.push_str(s),
+            (kind, _) => {
+                return Err(format!(
+                    "unsupported sampling content type '{}'; only text is supported",
+                    kind.unwrap_or("unknown")
+                ))
+            }
+        }
+    }
+    Ok(SamplingMessage {
+        role: role.to_string(),
+        text,
+    })
+}
+
+/// First allowed model matching a hint (hints are substrings, in order of
+/// preference); the first allowed model otherwise.
+fn choose_model<'a, 'h>(
+    mut hints: impl Iterator<Item = &'h str>,
+    allowed: &'a [String],
+) -> Option<&'a str> {
+    hints
+        .find_map(|hint| allowed.iter().find(|model| model.contains(hint)))
+        .or_else(|| allowed.first())
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ServerSamplingConfig {
+        ServerSamplingConfig {
+            models: vec!["llama-3.1-8b".to_string(), "qwen2.5-72b".to_string()],
+            max_tokens: 256,
+        }
+    }
+
+    #[test]
+    fn test_build_request_applies_policy() {
+        let params = json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize" } },
+                { "role": "assistant", "content": [{ "type": "text", "text": "Sure" }] }
+            ],
+            "modelPreferences": { "hints": [{ "name": "gpt-4o" }, { "name": "qwen" }] },
+            "systemPrompt": "Be brief",
+            "maxTokens": 4096,
+            "stopSequences": ["\n\n"]
+        });
+
+        let request = build_request(&params, "researcher", &policy(), None).unwrap();
+        assert_eq!(request.model, "qwen2.5-72b");
+        assert_eq!(request.max_tokens, 256);
+        assert_eq!(request.system_prompt.as_deref(), Some("Be brief"));
+        assert_eq!(request.messages.len(), 2);
+        assert_eq!(request.messages[1].text, "Sure");
+        assert_eq!(request.stop_sequences, vec!["\n\n".to_string()]);
+
+        // No matching hint falls back to the first allowed model.
+        let params = json!({
+            "messages": [{ "role": "user", "content": { "type": "text", "text": "hi" } }],
+            "maxTokens": 64
+        });
+        let request = build_request(&params, "researcher", &policy(), None).unwrap();
+        assert_eq!(request.model, "llama-3.1-8b");
+        assert_eq!(request.max_tokens, 64);
+    }
+
+    #[test]
+    fn test_non_text_content_rejected() {
+        let params = json!({
+            "messages": [{
+                "role": "user",
+                "content": { "type": "image", "data": "AAAA", "mimeType": "image/png" }
+            }],
+            "maxTokens": 64
+        });
+        let err = build_request(&params, "researcher", &policy(), None).unwrap_err();
+        assert!(err.contains("'image'"));
+    }
+
+    #[test]
+    fn test_result_json() {
+        let result = result_json(&SamplingResponse {
+            model: "llama-3.1-8b".to_string(),
+            text: "done".to_string(),
+            stop_reason: Some("endTurn".to_string()),
+        });
+        assert_eq!(result["content"]["text"], "done");
+        assert_eq!(result["stopReason"], "endTurn");
+    }
+}
@@ -12,6 +12,7 @@ use futures::stream::{self, StreamExt};
 
 use super::{
     config::BuiltinToolType,
+    elicitation::ElicitationResponse,
     orchestrator::{
         McpOrchestrator, McpRequestContext, ToolExecutionInput, ToolExecutionOutput,
         ToolExecutionResult,
@@ -20,6 +21,7 @@ use super::{
 };
 use crate::{
     approval::ApprovalMode,
+    error::McpResult,
     inventory::{QualifiedToolName, ToolCategory, ToolEntry},
     tenant::TenantContext,
 };
@@ -83,6 +85,8 @@ pub struct McpToolSession<'a> {
     builtin_server_keys: HashSet<String>,
     /// Internal, non-builtin server labels for this request snapshot.
     internal_non_builtin_server_labels: HashSet<String>,
+    /// Whether tool calls pause on server elicitations.
+    forward_elicitations: bool,
 }
 
 impl<'a> McpToolSession<'a> {
@@ -105,11 +109,29 @@ impl<'a> McpToolSession<'a> {
         mcp_servers: Vec<McpServerBinding>,
         request_id: impl Into<String>,
         forwarded_headers: HashMap<String, String>,
+    ) -> Self {
+        Self::new_for_tenant(
+            orchestrator,
+            mcp_servers,
+            request_id,
+            forwarded_headers,
+            TenantContext::default(),
+        )
+    }
+
+    /// Create a new session on behalf of `tenant_ctx`. Dynamic servers the
+    /// tenant holds no connection to contribute no tools.
+    pub fn new_for_tenant(
+        orchestrator: &'a McpOrchestrator,
+        mcp_servers: Vec<McpServerBinding>,
+        request_id: impl Into<String>,
+        forwarded_headers: HashMap<String, String>,
+        tenant_ctx: TenantContext,
     ) -> Self {
         let request_id = request_id.into();
-        let tenant_ctx = TenantContext::default();
         let server_keys: Vec<String> = mcp_servers.iter().map(|b| b.server_key.clone()).collect();
-        let mut mcp_tools = Self::collect_visible_mcp_tools(orchestrator, &server_keys);
+        let mut mcp_tools =
+            Self::collect_visible_mcp_tools(orchestrator, &server_keys, &tenant_ctx);
 
         // Build per-server allowlists from bindings that specify allowed_tools.
         let allowed_tools_by_server_key: HashMap<&str, HashSet<&str>> = mcp_servers
@@ -187,6 +209,7 @@ impl<'a> McpToolSession<'a> {
             internal_server_keys,
             builtin_server_keys,
             internal_non_builtin_server_labels,
+            forward_elicitations: false,
         }
     }
 
@@ -285,6 +308,9 @@ impl<'a> McpToolSession<'a> {
                 ToolExecutionResult::PendingApproval(pending) => {
                     pending.tool_name = invoked_name;
                 }
+                ToolExecutionResult::PendingElicitation(pending) => {
+                    pending.tool_name = invoked_name;
+                }
             }
 
             result
@@ -310,6 +336,38 @@ impl<'a> McpToolSession<'a> {
         }
     }
 
+    /// Answer a forwarded elicitation and continue the paused tool call.
+    ///
+    /// The call may have been paused by an earlier request; its result is
+    /// reported under the name this session exposes for the tool.
+    pub async fn resume_elicitation(
+        &self,
+        elicitation_id: &str,
+        response: ElicitationResponse,
+    ) -> McpResult<ToolExecutionResult> {
+        let mut result = self
+            .orchestrator
+            .resume_elicitation(elicitation_id, response, &self.tenant_ctx)
+            .await?;
+
+        let (server_key, tool_name) = match &mut result {
+            ToolExecutionResult::Executed(output) => (&output.server_key, &mut output.tool_name),
+            ToolExecutionResult::PendingApproval(pending) => {
+                (&pending.server_key, &mut pending.tool_name)
+            }
+            ToolExecutionResult::PendingElicitation(pending) => {
+                (&pending.server_key, &mut pending.tool_name)
+            }
+        };
+        if let Some(exposed) = self
+            .exposed_name_by_qualified
+            .get(&QualifiedToolName::new(server_key, tool_name.as_str()))
+        {
+            tool_name.clone_from(exposed);
+        }
+        Ok(result)
+    }
+
     /// Resolve the user-facing server label for a tool.
     ///
     /// Uses the orchestrator inventory to find the tool's server key, then maps
@@ -330,6 +388,12 @@ impl<'a> McpToolSession<'a> {
 
     /// Set the approval mode for every binding matching `server_label`,
     /// optionally narrowed to a subset of resolved tool names.
+    /// Pause tool calls on server elicitations so the caller can hand them to
+    /// the client, instead of failing them.
+    pub fn set_elicitation_forwarding(&mut self, enabled: bool) {
+        self.forward_elicitations = enabled;
+    }
+
     pub fn set_approval_mode(
         &mut self,
         server_label: &str,
@@ -533,15 +597,14 @@ impl<'a> McpToolSession<'a> {
     fn collect_visible_mcp_tools(
         orchestrator: &McpOrchestrator,
         server_keys: &[String],
+        tenant_ctx: &TenantContext,
     ) -> Vec<ToolEntry> {
-        let direct_tools = orchestrator.list_tools_for_servers(server_keys);
+        let direct_tools = orchestrator.list_tools_for_servers(server_keys, Some(tenant_ctx));
+        let inventory = orchestrator.tool_inventory();
         let server_key_set: HashSet<&str> = server_keys.iter().map(String::as_str).collect();
 
         let mut aliases_by_target: HashMap<QualifiedToolName, Vec<ToolEntry>> = HashMap::new();
-        for alias_entry in orchestrator
-            .tool_inventory()
-            .list_by_category(ToolCategory::Alias)
-        {
+        for alias_entry in inventory.list_by_category(ToolCategory::Alias) {
             let Some(target) = alias_entry
                 .alias_target
                 .as_ref()
@@ -549,7 +612,9 @@ impl<'a> McpToolSession<'a> {
             else {
                 continue;
             };
-            if !server_key_set.contains(target.server_key()) {
+            if !server_key_set.contains(target.server_key())
+                || !inventory.is_visible_to(target.server_key(), &tenant_ctx.tenant_id)
+            {
                 continue;
             }
             aliases_by_target
@@ -615,12 +680,14 @@ impl<'a> McpToolSession<'a> {
     }
 
     fn request_ctx_for(&self, approval_mode: ApprovalMode) -> McpRequestContext<'a> {
-        self.orchestrator.create_request_context_with_headers(
+        let mut request_ctx = self.orchestrator.create_request_context_with_headers(
             self.request_id.clone(),
             self.tenant_ctx.clone(),
             approval_mode,
             self.forwarded_headers.clone(),
-        )
+        );
+        request_ctx.forward_elicitations = self.forward_elicitations;
+        request_ctx
     }
 }
 
@@ -784,6 +851,9 @@ mod tests {
             ToolExecutionResult::Executed(output) => {
                 panic!("expected pending approval, got executed result: {output:?}")
             }
+            ToolExecutionResult::PendingElicitation(pending) => {
+                panic!("expected pending approval, got elicitation: {pending:?}")
+            }
         }
     }
 
@@ -1145,6 +1215,42 @@ mod tests {
         assert_eq!(listed[0].tool_name(), "web_search");
     }
 
+    #[test]
+    fn test_session_hides_dynamic_tools_and_aliases_from_other_tenants() {
+        let orchestrator = McpOrchestrator::new_test();
+        let inventory = orchestrator.tool_inventory();
+        let (acme, globex) = (TenantContext::new("acme"), TenantContext::new("globex"));
+
+        inventory.grant_server("http://dyn", &acme.tenant_id, 1);
+        inventory.insert_entry(ToolEntry::from_server_tool(
+            "http://dyn",
+            create_test_tool("lookup"),
+        ));
+        orchestrator
+            .register_alias("find", "http://dyn", "lookup", None)
+            .expect("alias registration should succeed");
+
+        let session = |tenant: &TenantContext| {
+            McpToolSession::new_for_tenant(
+                &orchestrator,
+                vec![McpServerBinding {
+                    label: "dyn".to_string(),
+                    server_key: "http://dyn".to_string(),
+                    allowed_tools: None,
+                }],
+                "test-request",
+                HashMap::new(),
+                tenant.clone(),
+            )
+        };
+
+        assert!(session(&acme).has_exposed_tool("find"));
+        let hidden = session(&globex);
+        assert!(hidden.mcp_tools().is_empty());
+        assert!(!hidden.has_exposed_tool("find"));
+        assert!(!hidden.has_exposed_tool("lookup"));
+    }
+
     #[test]
     fn test_allowed_tools_accepts_alias_name() {
         let orchestrator = McpOrchestrator::new_test();
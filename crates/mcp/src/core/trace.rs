@@ -0,0 +1,318 @@
+//! Trace context propagation into MCP tool calls.
+//!
+//! The crate does not depend on OpenTelemetry; the gateway plugs in an
+//! injector that writes W3C `traceparent` / `tracestate` / `baggage` entries
+//! for the current span. Streamable HTTP clients are pooled and shared across
+//! requests, so per-call context cannot ride on connection defaults — it is
+//! sent in the `tools/call` request's `params._meta`, the MCP convention for
+//! trace propagation, which also covers stdio servers. For HTTP servers,
+//! [`TracingHttpClient`] additionally copies those entries onto each POST as
+//! `traceparent` / `tracestate` / `baggage` headers, whether the server
+//! answers with JSON or an SSE stream. The legacy HTTP+SSE transport is
+//! rejected at connect time, so it has no request path of its own.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::stream::BoxStream;
+use reqwest::header::{HeaderName, HeaderValue};
+use rmcp::{
+    model::{ClientJsonRpcMessage, ClientRequest, GetMeta, Meta},
+    transport::streamable_http_client::{
+        SseError, StreamableHttpClient, StreamableHttpError, StreamableHttpPostResponse,
+    },
+};
+use serde_json::Value;
+use sse_stream::Sse;
+
+/// Trait for injecting trace context into an outgoing MCP request.
+///
+/// Implement this trait to enable distributed tracing across tool calls.
+/// The default implementation is a no-op.
+pub trait TraceInjector: Send + Sync {
+    /// Inject trace context entries (e.g. `traceparent`, `baggage`) into the
+    /// given carrier.
+    ///
+    /// Returns `Ok(())` on success, or an error if injection fails.
+    fn inject(
+        &self,
+        carrier: &mut HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A no-op trace injector that does nothing.
+#[derive(Clone, Default)]
+pub struct NoopTraceInjector;
+
+impl TraceInjector for NoopTraceInjector {
+    fn inject(
+        &self,
+        _carrier: &mut HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// Type alias for a boxed trace injector.
+pub type BoxedTraceInjector = Arc<dyn TraceInjector>;
+
+/// Build the `_meta` object for a tool call from the injector's output.
+/// Returns `None` when there is no active trace context to propagate.
+pub(crate) fn trace_meta(injector: &dyn TraceInjector) -> Option<Meta> {
+    let mut carrier = HashMap::new();
+    if let Err(e) = injector.inject(&mut carrier) {
+        tracing::debug!("Failed to inject trace context into MCP request: {}", e);
+        return None;
+    }
+    if carrier.is_empty() {
+        return None;
+    }
+    Some(Meta(
+        carrier
+            .into_iter()
+            .map(|(k, v)| (k, Value::String(v)))
+            .collect(),
+    ))
+}
+
+/// W3C propagation fields forwarded as HTTP headers.
+const PROPAGATION_HEADERS: [&str; 3] = ["traceparent", "tracestate", "baggage"];
+
+/// Extract the W3C propagation headers from a request's `_meta`.
+///
+/// The orchestrator places trace context in `params._meta` of `tools/call`;
+/// entries set through the request extensions are honoured as well.
+fn trace_headers(message: &ClientJsonRpcMessage) -> HashMap<HeaderName, HeaderValue> {
+    let ClientJsonRpcMessage::Request(request) = message else {
+        return HashMap::new();
+    };
+    let params_meta = match &request.request {
+        ClientRequest::CallToolRequest(call) => call.params.meta.as_ref(),
+        _ => None,
+    };
+    let extension_meta = request.request.get_meta();
+
+    PROPAGATION_HEADERS
+        .iter()
+        .filter_map(|&name| {
+            let value = extension_meta
+                .0
+                .get(name)
+                .or_else(|| params_meta.and_then(|meta| meta.0.get(name)))?
+                .as_str()?;
+            let value = HeaderValue::from_str(value).ok()?;
+            Some((HeaderName::from_static(name), value))
+        })
+        .collect()
+}
+
+/// Streamable HTTP client that mirrors a request's trace context into HTTP
+/// headers, so servers and proxies that only read `traceparent` from the
+/// transport still join the gateway's trace.
+#[derive(Clone)]
+pub(crate) struct TracingHttpClient {
+    inner: reqwest::Client,
+}
+
+impl TracingHttpClient {
+    pub(crate) fn new(inner: reqwest::Client) -> Self {
+        Self { inner }
+    }
+}
+
+impl StreamableHttpClient for TracingHttpClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_header: Option<String>,
+        mut custom_headers: HashMap<HeaderName, HeaderValue>,
+    ) -> Result<StreamableHttpPostResponse, StreamableHttpError<Self::Error>> {
+        custom_headers.extend(trace_headers(&message));
+        self.inner
+            .post_message(uri, message, session_id, auth_header, custom_headers)
+            .await
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_header: Option<String>,
+        custom_headers: HashMap<HeaderName, HeaderValue>,
+    ) -> Result<(), StreamableHttpError<Self::Error>> {
+        self.inner
+            .delete_session(uri, session_id, auth_header, custom_headers)
+            .await
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_header: Option<String>,
+        custom_headers: HashMap<HeaderName, HeaderValue>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, StreamableHttpError<Self::Error>> {
+        self.inner
+            .get_stream(uri, session_id, last_event_id, auth_header, custom_headers)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedInjector;
+
+    impl TraceInjector for FixedInjector {
+        fn inject(
+            &self,
+            carrier: &mut HashMap<String, String>,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            carrier.insert(
+                "traceparent".to_string(),
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+            );
+            carrier.insert("baggage".to_string(), "tenant=acme".to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace_meta_carries_injected_entries() {
+        let meta = trace_meta(&FixedInjector).expect("meta");
+        assert_eq!(
+            meta.0.get("traceparent").and_then(Value::as_str),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+        assert_eq!(
+            meta.0.get("baggage").and_then(Value::as_str),
+            Some("tenant=acme")
+        );
+    }
+
+    #[test]
+    fn test_trace_meta_is_none_without_context() {
+        assert!(trace_meta(&NoopTraceInjector).is_none());
+    }
+
+    fn call_tool_message(meta: Option<Meta>) -> ClientJsonRpcMessage {
+        let mut params = rmcp::model::CallToolRequestParams::new("search");
+        params.meta = meta;
+        ClientJsonRpcMessage::request(
+            ClientRequest::CallToolRequest(rmcp::model::CallToolRequest::new(params)),
+            rmcp::model::NumberOrString::Number(1),
+        )
+    }
+
+    #[test]
+    fn test_trace_headers_mirror_call_meta() {
+        let headers = trace_headers(&call_tool_message(trace_meta(&FixedInjector)));
+        assert_eq!(
+            headers
+                .get(&HeaderName::from_static("traceparent"))
+                .and_then(|v| v.to_str().ok()),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+        assert_eq!(
+            headers
+                .get(&HeaderName::from_static("baggage"))
+                .and_then(|v| v.to_str().ok()),
+            Some("tenant=acme")
+        );
+        assert!(!headers.contains_key(&HeaderName::from_static("tracestate")));
+    }
+
+    #[test]
+    fn test_trace_headers_empty_without_context() {
+        assert!(trace_headers(&call_tool_message(None)).is_empty());
+    }
+
+    /// POST `message` through [`TracingHttpClient`] to a one-route server that
+    /// answers with `content_type` / `body`, returning the headers it saw.
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "test server: runs until the test runtime shuts down"
+    )]
+    async fn sent_headers(
+        message: ClientJsonRpcMessage,
+        content_type: &'static str,
+        body: &'static str,
+    ) -> axum::http::HeaderMap {
+        use axum::{http::header::CONTENT_TYPE, routing::post, Router};
+
+        let seen = Arc::new(parking_lot::Mutex::new(None));
+        let app = Router::new().route(
+            "/mcp",
+            post({
+                let seen = Arc::clone(&seen);
+                move |headers: axum::http::HeaderMap| async move {
+                    *seen.lock() = Some(headers);
+                    ([(CONTENT_TYPE, content_type)], body)
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = TracingHttpClient::new(reqwest::Client::new());
+        let response = client
+            .post_message(
+                format!("http://{addr}/mcp").into(),
+                message,
+                None,
+                None,
+                HashMap::new(),
+            )
+            .await;
+        assert!(response.is_ok(), "post failed: {:?}", response.err());
+
+        let headers = seen.lock().take();
+        headers.expect("server saw no request")
+    }
+
+    const JSON_RESULT: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"content":[]}}"#;
+    const SSE_RESULT: &str = "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"content\":[]}}\n\n";
+
+    #[tokio::test]
+    async fn test_streamable_post_sends_trace_headers() {
+        let message = call_tool_message(trace_meta(&FixedInjector));
+        let headers = sent_headers(message, "application/json", JSON_RESULT).await;
+        assert_eq!(
+            headers.get("traceparent").and_then(|v| v.to_str().ok()),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+        assert_eq!(
+            headers.get("baggage").and_then(|v| v.to_str().ok()),
+            Some("tenant=acme")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_post_sends_trace_headers() {
+        let message = call_tool_message(trace_meta(&FixedInjector));
+        let headers = sent_headers(message, "text/event-stream", SSE_RESULT).await;
+        assert_eq!(
+            headers.get("traceparent").and_then(|v| v.to_str().ok()),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+        assert_eq!(
+            headers.get("baggage").and_then(|v| v.to_str().ok()),
+            Some("tenant=acme")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_without_context_sends_no_trace_headers() {
+        let headers = sent_headers(call_tool_message(None), "application/json", JSON_RESULT).await;
+        assert!(!headers.contains_key("traceparent"));
+        assert!(!headers.contains_key("baggage"));
+    }
+}
@@ -6,14 +6,22 @@
 //! - By simple name (with collision handling)
 //! - By server (for bulk operations)
 //! - By category (for filtering)
+//!
+//! Dynamic servers are pooled per tenant but their tools are indexed by URL.
+//! The inventory records which tenants hold a connection to each dynamic
+//! server; tenant-scoped lookups only see servers the tenant holds, plus
+//! servers with no holders recorded (static servers).
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use dashmap::DashMap;
 use tracing::warn;
 
 use super::types::{QualifiedToolName, ToolCategory, ToolEntry};
-use crate::core::config::{Prompt, RawResource, Tool};
+use crate::{
+    core::config::{Prompt, RawResource, Tool},
+    tenant::TenantId,
+};
 
 /// Cached prompt with metadata
 #[derive(Clone)]
@@ -37,6 +45,8 @@ pub struct ToolInventory {
     aliases: DashMap<String, QualifiedToolName>,
     prompts: DashMap<String, CachedPrompt>,
     resources: DashMap<String, CachedResource>,
+    /// Dynamic server key -> tenant -> pooled connections (by auth hash).
+    server_holders: DashMap<String, HashMap<TenantId, HashSet<u64>>>,
 }
 
 impl ToolInventory {
@@ -49,6 +59,7 @@ impl ToolInventory {
             aliases: DashMap::new(),
             prompts: DashMap::new(),
             resources: DashMap::new(),
+            server_holders: DashMap::new(),
         }
     }
 }
@@ -376,6 +387,7 @@ impl ToolInventory {
     }
 
     pub fn clear_all(&self) {
+        self.server_holders.clear();
         self.tools_by_qualified.clear();
         self.tools_by_simple_name.clear();
         self.tools_by_server.clear();
@@ -385,6 +397,76 @@ impl ToolInventory {
         self.resources.clear();
     }
 
+    // ========================================================================
+    // Tenant scoping
+    // ========================================================================
+
+    /// Record that `tenant` holds a pooled connection to a dynamic server.
+    /// `connection` distinguishes one tenant's connections (the pool key's
+    /// auth hash), so repeated grants for the same connection are idempotent.
+    pub fn grant_server(&self, server_key: &str, tenant: &TenantId, connection: u64) {
+        self.server_holders
+            .entry(server_key.to_string())
+            .or_default()
+            .entry(tenant.clone())
+            .or_default()
+            .insert(connection);
+    }
+
+    /// Drop one pooled connection. The tenant loses access when its last
+    /// connection goes; the server's tools are cleared when no tenant holds
+    /// it any more. Returns `true` if the tools were cleared.
+    pub fn release_server(&self, server_key: &str, tenant: &TenantId, connection: u64) -> bool {
+        let Some(mut holders) = self.server_holders.get_mut(server_key) else {
+            return false;
+        };
+        if let Some(connections) = holders.get_mut(tenant) {
+            connections.remove(&connection);
+            if connections.is_empty() {
+                holders.remove(tenant);
+            }
+        }
+        drop(holders);
+
+        let released = self
+            .server_holders
+            .remove_if(server_key, |_, holders| holders.is_empty())
+            .is_some();
+        if released {
+            self.clear_server_tools(server_key);
+        }
+        released
+    }
+
+    /// Whether `tenant` may see and call tools on `server_key`.
+    pub fn is_visible_to(&self, server_key: &str, tenant: &TenantId) -> bool {
+        self.server_holders
+            .get(server_key)
+            .is_none_or(|holders| holders.contains_key(tenant))
+    }
+
+    /// All tool entries visible to `tenant`.
+    pub fn list_tools_for_tenant(&self, tenant: &TenantId) -> Vec<ToolEntry> {
+        self.tools_by_qualified
+            .iter()
+            .filter(|entry| self.is_visible_to(entry.server_key(), tenant))
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
+    /// Tool entry by qualified name, if visible to `tenant`.
+    pub fn get_entry_for_tenant(
+        &self,
+        server_key: &str,
+        tool_name: &str,
+        tenant: &TenantId,
+    ) -> Option<ToolEntry> {
+        if !self.is_visible_to(server_key, tenant) {
+            return None;
+        }
+        self.get_entry(server_key, tool_name)
+    }
+
     pub fn index_counts(&self) -> IndexCounts {
         IndexCounts {
             tools: self.tools_by_qualified.len(),
@@ -523,6 +605,73 @@ mod tests {
         assert_eq!(tools[0].0, "tool2");
     }
 
+    #[test]
+    fn test_dynamic_tools_scoped_to_holding_tenants() {
+        let inventory = ToolInventory::new();
+        let (acme, globex) = (TenantId::new("acme"), TenantId::new("globex"));
+
+        inventory.insert_tool(
+            "search".to_string(),
+            "static".to_string(),
+            create_test_tool("search"),
+        );
+        inventory.grant_server("http://dyn", &acme, 1);
+        inventory.insert_tool(
+            "lookup".to_string(),
+            "http://dyn".to_string(),
+            create_test_tool("lookup"),
+        );
+
+        assert_eq!(inventory.list_tools_for_tenant(&acme).len(), 2);
+        let globex_tools = inventory.list_tools_for_tenant(&globex);
+        assert_eq!(globex_tools.len(), 1);
+        assert_eq!(globex_tools[0].tool_name(), "search");
+        assert!(inventory
+            .get_entry_for_tenant("http://dyn", "lookup", &globex)
+            .is_none());
+        assert!(inventory
+            .get_entry_for_tenant("http://dyn", "lookup", &acme)
+            .is_some());
+
+        // Refresh clears and reloads tools without touching holders.
+        inventory.clear_server_tools("http://dyn");
+        inventory.insert_tool(
+            "lookup".to_string(),
+            "http://dyn".to_string(),
+            create_test_tool("lookup"),
+        );
+        assert!(inventory
+            .get_entry_for_tenant("http://dyn", "lookup", &globex)
+            .is_none());
+        assert!(inventory
+            .get_entry_for_tenant("http://dyn", "lookup", &acme)
+            .is_some());
+    }
+
+    #[test]
+    fn test_release_server_clears_tools_with_last_holder() {
+        let inventory = ToolInventory::new();
+        let (acme, globex) = (TenantId::new("acme"), TenantId::new("globex"));
+
+        inventory.grant_server("http://dyn", &acme, 1);
+        inventory.grant_server("http://dyn", &acme, 1);
+        inventory.grant_server("http://dyn", &globex, 7);
+        inventory.insert_tool(
+            "lookup".to_string(),
+            "http://dyn".to_string(),
+            create_test_tool("lookup"),
+        );
+
+        // Evicting acme's connection hides the tools from acme only.
+        assert!(!inventory.release_server("http://dyn", &acme, 1));
+        assert!(!inventory.is_visible_to("http://dyn", &acme));
+        assert!(inventory.is_visible_to("http://dyn", &globex));
+        assert!(inventory.has_tool_qualified("http://dyn", "lookup"));
+
+        assert!(inventory.release_server("http://dyn", &globex, 7));
+        assert!(!inventory.has_tool_qualified("http://dyn", "lookup"));
+    }
+
     #[test]
     fn test_prompt_operations() {
         let inventory = ToolInventory::new();
@@ -17,12 +17,17 @@ pub mod inventory;
 pub mod tenant;
 // Re-export from core
 pub use core::{
-    ArgMappingConfig, BuiltinToolType, ConfigValidationError, HandlerRequestContext,
-    LatencySnapshot, McpConfig, McpMetrics, McpOrchestrator, McpRequestContext, McpServerBinding,
-    McpServerConfig, McpToolSession, McpTransport, MetricsSnapshot, PendingToolExecution,
-    PolicyConfig, PolicyDecisionConfig, PoolKey, RefreshRequest, ResponseFormatConfig,
-    ServerPolicyConfig, SmgClientHandler, Tool, ToolConfig, ToolExecutionInput,
-    ToolExecutionOutput, ToolExecutionResult, TrustLevelConfig, DEFAULT_SERVER_LABEL,
+    ArgMappingConfig, BoxedSamplingBackend, BoxedTraceInjector, BuiltinToolType,
+    ConfigValidationError, ElicitationAction, ElicitationResponse, HandlerRequestContext,
+    LatencySnapshot, McpConfig, McpElicitationRequest, McpMetrics, McpOrchestrator,
+    McpRequestContext, McpServerBinding, McpServerConfig, McpToolSession, McpTransport,
+    MetricsSnapshot, NoopTraceInjector, OutputTokenLimitsConfig, OversizedOutputAction,
+    PendingElicitationExecution, PendingToolExecution, PolicyConfig, PolicyDecisionConfig,
+    PolicyRuleConfig, PoolKey, RefreshRequest, ResponseFormatConfig, SamplingBackend,
+    SamplingConfig, SamplingMessage, SamplingRequest, SamplingResponse, ServerPolicyConfig,
+    ServerSamplingConfig, SmgClientHandler, Tool, ToolConfig, ToolExecutionInput,
+    ToolExecutionOutput, ToolExecutionResult, ToolLimitsConfig, TraceInjector, TruncationStrategy,
+    TrustLevelConfig, DEFAULT_SERVER_LABEL,
 };
 
 // Re-export shared types
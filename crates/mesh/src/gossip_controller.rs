@@ -742,12 +742,17 @@ impl GossipController {
                 .load_ca_certificate()
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to load mTLS CA certificate: {e}"))?;
+            let identity = mtls_manager
+                .load_identity()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to load mTLS identity: {e}"))?;
 
             endpoint = endpoint
                 .tls_config(
                     ClientTlsConfig::new()
                         .domain_name(tls_domain)
-                        .ca_certificate(ca_certificate),
+                        .ca_certificate(ca_certificate)
+                        .identity(identity),
                 )
                 .map_err(|e| anyhow::anyhow!("Failed to configure TLS endpoint: {e}"))?;
         }
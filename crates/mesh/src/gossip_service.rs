@@ -28,6 +28,11 @@
 //! connection with a logical mesh identity (today, pre-mTLS-derived
 //! identity) is to read the first inbound frame's `peer_id`. That
 //! learning step is what `learned_peer` exists for.
+//!
+//! With mTLS configured the server accepts TLS only. If a SPIFFE policy is
+//! set, both RPCs reject peers whose certificate SPIFFE ID is not
+//! authorized, and inbound stream entries / CRDT ops are dropped for keys
+//! the peer may not write.
 
 use std::{net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
 
@@ -45,7 +50,7 @@ use tracing::instrument;
 use super::{
     crdt_kv::CrdtWatermark,
     metrics::{record_ack, record_nack, record_peer_reconnect, update_peer_connections},
-    mtls::MTLSManager,
+    mtls::{spiffe_id_from_cert, MTLSManager, SpiffeId, SpiffePolicy},
     partition::PartitionDetector,
     service::{
         gossip::{
@@ -152,12 +157,21 @@ impl GossipService {
         signal: F,
     ) -> Result<()> {
         let listen_addr = self.listen_addr;
+        let self_mtls = self.mtls_manager.clone();
         let service = GossipServer::new(self)
             .max_decoding_message_size(MAX_MESSAGE_SIZE)
             .max_encoding_message_size(MAX_MESSAGE_SIZE)
             .accept_compressed(tonic::codec::CompressionEncoding::Gzip)
             .send_compressed(tonic::codec::CompressionEncoding::Gzip);
 
+        if let Some(mtls_manager) = self_mtls {
+            let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(mtls_manager.tls_incoming(listener), signal)
+                .await?;
+            return Ok(());
+        }
         Server::builder()
             .add_service(service)
             .serve_with_shutdown(listen_addr, signal)
@@ -170,12 +184,20 @@ impl GossipService {
         listener: tokio::net::TcpListener,
         signal: F,
     ) -> Result<()> {
-        let incoming = TcpIncoming::from(listener);
+        let mtls_manager = self.mtls_manager.clone();
         let service = GossipServer::new(self)
             .max_decoding_message_size(MAX_MESSAGE_SIZE)
             .max_encoding_message_size(MAX_MESSAGE_SIZE)
             .accept_compressed(tonic::codec::CompressionEncoding::Gzip)
             .send_compressed(tonic::codec::CompressionEncoding::Gzip);
+        if let Some(mtls_manager) = mtls_manager {
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(mtls_manager.tls_incoming(listener), signal)
+                .await?;
+            return Ok(());
+        }
+        let incoming = TcpIncoming::from(listener);
         Server::builder()
             .add_service(service)
             .serve_with_incoming_shutdown(incoming, signal)
@@ -183,6 +205,36 @@ impl GossipService {
         Ok(())
     }
 
+    /// Authorize the caller by the SPIFFE ID in its client certificate.
+    /// Returns the policy and ID when a SPIFFE policy is configured.
+    fn authorize_peer<T>(
+        &self,
+        request: &tonic::Request<T>,
+    ) -> Result<Option<PeerIdentity>, Status> {
+        let Some(policy) = self
+            .mtls_manager
+            .as_ref()
+            .and_then(|manager| manager.spiffe_policy())
+        else {
+            return Ok(None);
+        };
+        let certs = request
+            .peer_certs()
+            .ok_or_else(|| Status::unauthenticated("client certificate required"))?;
+        let leaf = certs
+            .first()
+            .ok_or_else(|| Status::unauthenticated("client certificate required"))?;
+        let id = spiffe_id_from_cert(leaf)
+            .map_err(|e| Status::unauthenticated(format!("invalid SVID: {e}")))?;
+        if !policy.authorize_peer(&id) {
+            log::warn!(spiffe_id = %id, "rejected mesh peer not authorized by SPIFFE policy");
+            return Err(Status::permission_denied(format!(
+                "SPIFFE ID {id} is not authorized"
+            )));
+        }
+        Ok(Some(PeerIdentity { policy, id }))
+    }
+
     fn merge_state(&self, incoming_nodes: Vec<NodeState>) -> bool {
         let mut state = self.state.write();
         let mut updated = false;
@@ -207,6 +259,22 @@ impl GossipService {
     }
 }
 
+/// SPIFFE identity of an authorized inbound peer.
+struct PeerIdentity {
+    policy: Arc<SpiffePolicy>,
+    id: SpiffeId,
+}
+
+impl PeerIdentity {
+    fn may_write(&self, key: &str) -> bool {
+        let allowed = self.policy.authorize_key(&self.id, key);
+        if !allowed {
+            log::debug!(spiffe_id = %self.id, key, "dropping update not authorized for peer");
+        }
+        allowed
+    }
+}
+
 #[tonic::async_trait]
 impl Gossip for GossipService {
     type SyncStreamStream =
@@ -217,6 +285,7 @@ impl Gossip for GossipService {
         &self,
         request: tonic::Request<GossipMessage>,
     ) -> std::result::Result<Response<NodeUpdate>, Status> {
+        self.authorize_peer(&request)?;
         let message = request.into_inner();
         match message.payload {
             Some(gossip::gossip_message::Payload::Ping(ping)) => {
@@ -253,6 +322,7 @@ impl Gossip for GossipService {
         &self,
         request: tonic::Request<tonic::Streaming<StreamMessage>>,
     ) -> Result<Response<Self::SyncStreamStream>, Status> {
+        let peer_identity = self.authorize_peer(&request)?;
         let mut incoming = request.into_inner();
         let self_name = self.self_name.clone();
         let mesh_kv = self.mesh_kv.clone();
@@ -433,15 +503,23 @@ impl Gossip for GossipService {
                             Some(gossip::stream_message::Payload::StreamBatch(batch)),
                         ) = (&mesh_kv, msg.payload)
                         {
-                            dispatch_stream_batch(mesh_kv, &msg.peer_id, batch.entries);
+                            let entries = batch.entries.into_iter().filter(|entry| {
+                                peer_identity
+                                    .as_ref()
+                                    .is_none_or(|p| p.may_write(&entry.key))
+                            });
+                            dispatch_stream_batch(mesh_kv, &msg.peer_id, entries);
                         }
                     }
                     StreamMessageType::CrdtBatch => {
                         if let (
                             Some(mesh_kv),
-                            Some(gossip::stream_message::Payload::CrdtBatch(batch)),
+                            Some(gossip::stream_message::Payload::CrdtBatch(mut batch)),
                         ) = (&mesh_kv, msg.payload)
                         {
+                            if let Some(peer) = &peer_identity {
+                                batch.ops.retain(|op| peer.may_write(&op.key));
+                            }
                             // Merge, then ack the per-key versions back so the
                             // peer can advance its send watermark. Ack loss is
                             // fine — the peer resends unacked keys next round —
@@ -4,11 +4,13 @@
 //! - Gossip protocol for node discovery and failure detection
 //! - CRDT-based state synchronization across cluster nodes
 //! - Partition detection and recovery
+//! - Capacity-weighted key ownership across nodes
 
 mod crdt_kv;
 mod gossip_controller;
 mod gossip_service;
 pub mod kv;
+mod membership;
 mod metrics;
 mod mtls;
 mod partition;
@@ -29,6 +31,7 @@ pub use kv::{
     CrdtNamespace, DrainHandle, MeshKV, StreamConfig, StreamDrainFn, StreamNamespace,
     StreamRouting, Subscription,
 };
+pub use membership::{node_weight, weighted_owner, NodeCapacity, DEFAULT_NODE_WEIGHT};
 pub use metrics::init_mesh_metrics;
 pub use mtls::{MTLSConfig, MTLSManager};
 pub use partition::PartitionDetector;
@@ -0,0 +1,184 @@
+//! Node capacity metadata and weighted key ownership
+//!
+//! Each node advertises a capacity weight and hardware metadata in its
+//! `NodeState.metadata`, so they travel with the regular gossip state.
+//! Keys are mapped to alive nodes by weighted rendezvous hashing: a node
+//! with twice the weight owns about twice the keys, and changing one
+//! node's weight only moves keys to or from that node.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::service::gossip::{NodeState, NodeStatus};
+
+/// Metadata key holding the node's capacity weight (decimal string)
+pub const WEIGHT_METADATA_KEY: &str = "weight";
+/// Metadata key holding the node's available CPU cores (decimal string)
+pub const CPU_CORES_METADATA_KEY: &str = "cpu_cores";
+/// Weight of nodes that do not advertise one
+pub const DEFAULT_NODE_WEIGHT: u32 = 100;
+
+fn metadata_u32(node: &NodeState, key: &str) -> Option<u32> {
+    node.metadata
+        .get(key)
+        .and_then(|value| std::str::from_utf8(value).ok())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Capacity weight of a node; `DEFAULT_NODE_WEIGHT` when not advertised.
+pub fn node_weight(node: &NodeState) -> u32 {
+    metadata_u32(node, WEIGHT_METADATA_KEY).unwrap_or(DEFAULT_NODE_WEIGHT)
+}
+
+/// Set the capacity weight advertised in `node`'s metadata.
+pub fn set_node_weight(node: &mut NodeState, weight: u32) {
+    node.metadata.insert(
+        WEIGHT_METADATA_KEY.to_string(),
+        weight.to_string().into_bytes(),
+    );
+}
+
+/// Hardware metadata of the local node, advertised at startup.
+pub fn local_hardware_metadata() -> HashMap<String, Vec<u8>> {
+    let mut metadata = HashMap::new();
+    if let Ok(cores) = std::thread::available_parallelism() {
+        metadata.insert(
+            CPU_CORES_METADATA_KEY.to_string(),
+            cores.get().to_string().into_bytes(),
+        );
+    }
+    metadata
+}
+
+/// Capacity view of a mesh node, as reported by the admin API.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NodeCapacity {
+    pub name: String,
+    pub address: String,
+    pub status: String,
+    pub weight: u32,
+    pub cpu_cores: Option<u32>,
+}
+
+impl From<&NodeState> for NodeCapacity {
+    fn from(node: &NodeState) -> Self {
+        Self {
+            name: node.name.clone(),
+            address: node.address.clone(),
+            status: NodeStatus::try_from(node.status)
+                .map(|status| status.as_str_name().to_string())
+                .unwrap_or_else(|_| format!("UNKNOWN({})", node.status)),
+            weight: node_weight(node),
+            cpu_cores: metadata_u32(node, CPU_CORES_METADATA_KEY),
+        }
+    }
+}
+
+/// Weighted rendezvous score of `node` for `key`; the highest score owns
+/// the key. Scores are `-weight / ln(h)` with `h` a stable hash of node and
+/// key mapped into (0, 1), which picks each node with probability
+/// proportional to its weight.
+fn rendezvous_score(node: &str, weight: u32, key: &str) -> f64 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(node.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(key.as_bytes());
+    let bytes = hasher.finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&bytes.as_bytes()[..8]);
+    // 53 random bits, offset by half a step so h is never 0 or 1
+    let h = ((u64::from_le_bytes(prefix) >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    -f64::from(weight) / h.ln()
+}
+
+/// The alive node owning `key` under weighted rendezvous hashing. Nodes
+/// with weight 0 never own keys; ties break on node name.
+pub fn weighted_owner<'a>(
+    nodes: impl IntoIterator<Item = &'a NodeState>,
+    key: &str,
+) -> Option<&'a NodeState> {
+    nodes
+        .into_iter()
+        .filter(|node| node.status == NodeStatus::Alive as i32)
+        .filter_map(|node| {
+            let weight = node_weight(node);
+            (weight > 0).then(|| (rendezvous_score(&node.name, weight, key), node))
+        })
+        .max_by(|(a, a_node), (b, b_node)| {
+            a.total_cmp(b).then_with(|| b_node.name.cmp(&a_node.name))
+        })
+        .map(|(_, node)| node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, weight: Option<u32>) -> NodeState {
+        let mut node = NodeState {
+            name: name.to_string(),
+            address: "127.0.0.1:0".to_string(),
+            status: NodeStatus::Alive as i32,
+            version: 1,
+            metadata: HashMap::new(),
+        };
+        if let Some(weight) = weight {
+            set_node_weight(&mut node, weight);
+        }
+        node
+    }
+
+    #[test]
+    fn test_node_weight_default_and_override() {
+        assert_eq!(node_weight(&node("a", None)), DEFAULT_NODE_WEIGHT);
+        assert_eq!(node_weight(&node("a", Some(250))), 250);
+
+        let mut garbled = node("a", None);
+        garbled
+            .metadata
+            .insert(WEIGHT_METADATA_KEY.to_string(), b"lots".to_vec());
+        assert_eq!(node_weight(&garbled), DEFAULT_NODE_WEIGHT);
+    }
+
+    #[test]
+    fn test_weighted_owner_is_stable_and_skips_unavailable_nodes() {
+        let mut nodes = vec![node("a", None), node("b", None), node("c", Some(0))];
+        let owner = weighted_owner(&nodes, "key-1").unwrap().name.clone();
+        assert_ne!(owner, "c");
+        assert_eq!(weighted_owner(&nodes, "key-1").unwrap().name, owner);
+
+        for node in &mut nodes {
+            if node.name == owner {
+                node.status = NodeStatus::Leaving as i32;
+            }
+        }
+        let fallback = weighted_owner(&nodes, "key-1").unwrap();
+        assert_ne!(fallback.name, owner);
+        assert_ne!(fallback.name, "c");
+
+        assert!(weighted_owner(&[node("c", Some(0))], "key-1").is_none());
+    }
+
+    #[test]
+    fn test_weighted_owner_distribution_follows_weights() {
+        let nodes = vec![node("small", Some(100)), node("large", Some(300))];
+        let large = (0..4000)
+            .filter(|i| weighted_owner(&nodes, &format!("key-{i}")).unwrap().name == "large")
+            .count();
+        // Expect ~3000 of 4000
+        assert!((2800..3200).contains(&large), "large owned {large}");
+    }
+
+    #[test]
+    fn test_node_capacity_view() {
+        let mut state = node("a", Some(50));
+        state
+            .metadata
+            .insert(CPU_CORES_METADATA_KEY.to_string(), b"16".to_vec());
+        let capacity = NodeCapacity::from(&state);
+        assert_eq!(capacity.weight, 50);
+        assert_eq!(capacity.cpu_cores, Some(16));
+        assert_eq!(capacity.status, "ALIVE");
+    }
+}
@@ -55,6 +55,12 @@ pub fn init_mesh_metrics() {
         "router_lb_drift_ratio",
         "Load balance drift ratio (actual vs expected)"
     );
+
+    // mTLS
+    describe_gauge!(
+        "router_mesh_mtls_cert_expiry_timestamp_seconds",
+        "Expiry (not-after) of the mesh mTLS certificate as a Unix timestamp"
+    );
 }
 
 /// Update peer connection status
@@ -134,3 +140,11 @@ pub fn record_sync_round_duration(peer: &str, duration: Duration) {
     )
     .record(duration.as_secs_f64());
 }
+
+/// Update the expiry timestamp of a mesh mTLS certificate (`server` or `ca`)
+pub fn update_cert_expiry(cert: &str, not_after_secs: i64) {
+    gauge!("router_mesh_mtls_cert_expiry_timestamp_seconds",
+        "cert" => cert.to_string()
+    )
+    .set(not_after_secs as f64);
+}
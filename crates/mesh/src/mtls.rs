@@ -1,23 +1,42 @@
 //! mTLS (mutual TLS) support for mesh cluster communication
 //!
 //! Provides optional mTLS encryption for gRPC mesh connections using rustls.
-//! Supports certificate rotation without restart.
+//! Inbound connections are accepted with the server config current at
+//! accept time, so certificate rotation takes effect without restart.
+//!
+//! Peers can additionally be authorized by the SPIFFE ID in their
+//! certificate (X.509 SVID), both for connecting and per store.
 
 use std::{
+    fmt,
+    net::SocketAddr,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use rustls::{
     pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
     ClientConfig, RootCertStore, ServerConfig,
 };
 use rustls_pemfile::{certs, pkcs8_private_keys};
-use tokio::{fs, sync::RwLock};
-use tonic::transport::Certificate;
+use tokio::{
+    fs,
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, RwLock},
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Certificate, Identity};
 use tracing::{info, warn};
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer};
+
+use crate::metrics::update_cert_expiry;
+
+/// Time allowed for an inbound TLS handshake before the connection is dropped
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// mTLS configuration
 #[derive(Debug, Clone)]
@@ -32,6 +51,8 @@ pub struct MTLSConfig {
     pub require_client_cert: bool,
     /// Certificate rotation check interval
     pub rotation_check_interval: Duration,
+    /// Authorize peers by the SPIFFE ID in their client certificate
+    pub spiffe: Option<SpiffePolicy>,
 }
 
 impl Default for MTLSConfig {
@@ -42,35 +63,237 @@ impl Default for MTLSConfig {
             server_key_path: PathBuf::from("/etc/ssl/private/server.key"),
             require_client_cert: true,
             rotation_check_interval: Duration::from_secs(300), // 5 minutes
+            spiffe: None,
+        }
+    }
+}
+
+/// A SPIFFE ID, `spiffe://<trust-domain><path>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpiffeId {
+    trust_domain: String,
+    path: String,
+}
+
+impl SpiffeId {
+    /// Parse and validate a SPIFFE ID per the SPIFFE ID specification.
+    pub fn parse(uri: &str) -> Result<Self> {
+        if uri.len() > 2048 {
+            bail!("SPIFFE ID exceeds 2048 bytes");
+        }
+        let rest = uri
+            .strip_prefix("spiffe://")
+            .ok_or_else(|| anyhow!("SPIFFE ID must start with spiffe://: {uri}"))?;
+        let (trust_domain, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        if trust_domain.is_empty()
+            || !trust_domain
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"-._".contains(&b))
+        {
+            bail!("invalid SPIFFE trust domain in {uri}");
+        }
+        if !path.is_empty() {
+            for segment in path[1..].split('/') {
+                if segment.is_empty()
+                    || segment == "."
+                    || segment == ".."
+                    || !segment
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b"-._".contains(&b))
+                {
+                    bail!("invalid SPIFFE ID path in {uri}");
+                }
+            }
+        }
+        Ok(Self {
+            trust_domain: trust_domain.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    pub fn trust_domain(&self) -> &str {
+        &self.trust_domain
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for SpiffeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "spiffe://{}{}", self.trust_domain, self.path)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathMatcher {
+    Any,
+    Exact(String),
+    /// Matches paths below this one; stored with a trailing `/`
+    Prefix(String),
+}
+
+/// Matches SPIFFE IDs of one trust domain by path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpiffeMatcher {
+    trust_domain: String,
+    path: PathMatcher,
+}
+
+impl SpiffeMatcher {
+    /// Parse a pattern: `spiffe://example.org` or `spiffe://example.org/*`
+    /// matches the whole trust domain, `spiffe://example.org/ns/mesh/*`
+    /// every path below `/ns/mesh`, anything else the exact ID.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let (id, wildcard) = match pattern.strip_suffix("/*") {
+            Some(base) => (base, true),
+            None => (pattern, false),
+        };
+        let id = SpiffeId::parse(id)?;
+        let path = match (id.path.is_empty(), wildcard) {
+            (true, _) => PathMatcher::Any,
+            (false, true) => PathMatcher::Prefix(format!("{}/", id.path)),
+            (false, false) => PathMatcher::Exact(id.path),
+        };
+        Ok(Self {
+            trust_domain: id.trust_domain,
+            path,
+        })
+    }
+
+    pub fn matches(&self, id: &SpiffeId) -> bool {
+        if self.trust_domain != id.trust_domain {
+            return false;
+        }
+        match &self.path {
+            PathMatcher::Any => true,
+            PathMatcher::Exact(path) => *path == id.path,
+            PathMatcher::Prefix(prefix) => id.path.starts_with(prefix.as_str()),
         }
     }
 }
 
+/// SPIFFE authorization of mesh peers.
+///
+/// A peer may connect when it matches `peers` or any store rule. Incoming
+/// updates to a key are accepted from peers matching the rule with the
+/// longest store prefix of the key (`worker:`, `rl:`, ...), or `peers` when
+/// no rule covers the key.
+#[derive(Debug, Clone, Default)]
+pub struct SpiffePolicy {
+    pub peers: Vec<SpiffeMatcher>,
+    /// `(store key prefix, peers allowed to write it)`
+    pub stores: Vec<(String, Vec<SpiffeMatcher>)>,
+}
+
+impl SpiffePolicy {
+    pub fn authorize_peer(&self, id: &SpiffeId) -> bool {
+        self.peers.iter().any(|m| m.matches(id))
+            || self
+                .stores
+                .iter()
+                .any(|(_, matchers)| matchers.iter().any(|m| m.matches(id)))
+    }
+
+    pub fn authorize_key(&self, id: &SpiffeId, key: &str) -> bool {
+        let matchers = self
+            .stores
+            .iter()
+            .filter(|(prefix, _)| key.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(&self.peers, |(_, matchers)| matchers);
+        matchers.iter().any(|m| m.matches(id))
+    }
+}
+
+/// The SPIFFE ID of an X.509 SVID: its single `spiffe://` URI SAN.
+pub fn spiffe_id_from_cert(der: &[u8]) -> Result<SpiffeId> {
+    let (_, cert) =
+        X509Certificate::from_der(der).map_err(|e| anyhow!("failed to parse certificate: {e}"))?;
+    let san = cert
+        .subject_alternative_name()
+        .map_err(|e| anyhow!("invalid subject alternative name: {e}"))?
+        .ok_or_else(|| anyhow!("certificate has no subject alternative name"))?;
+    let mut uris = san
+        .value
+        .general_names
+        .iter()
+        .filter_map(|name| match name {
+            GeneralName::URI(uri) => Some(*uri),
+            _ => None,
+        });
+    match (uris.next(), uris.next()) {
+        (Some(uri), None) => SpiffeId::parse(uri),
+        (None, _) => bail!("certificate has no URI SAN"),
+        (Some(_), Some(_)) => bail!("SVID must have exactly one URI SAN"),
+    }
+}
+
+/// Expiry of a certificate, as seconds since the Unix epoch.
+fn cert_not_after(der: &[u8]) -> Result<i64> {
+    let (_, cert) =
+        X509Certificate::from_der(der).map_err(|e| anyhow!("failed to parse certificate: {e}"))?;
+    Ok(cert.validity().not_after.timestamp())
+}
+
+/// Publish the earliest expiry among `certs` as the `cert` expiry gauge.
+fn record_cert_expiry(cert: &str, certs: &[CertificateDer<'_>]) {
+    match certs.iter().map(|c| cert_not_after(c)).min_by_key(|r| {
+        // Unparsable certificates sort last
+        r.as_ref().copied().unwrap_or(i64::MAX)
+    }) {
+        Some(Ok(not_after)) => update_cert_expiry(cert, not_after),
+        Some(Err(e)) => warn!("Failed to read {} certificate expiry: {}", cert, e),
+        None => {}
+    }
+}
+
 /// mTLS certificate manager
 #[derive(Debug)]
 pub struct MTLSManager {
     config: MTLSConfig,
+    spiffe: Option<Arc<SpiffePolicy>>,
     server_config: Arc<RwLock<Option<Arc<ServerConfig>>>>,
     client_config: Arc<RwLock<Option<Arc<ClientConfig>>>>,
+    /// Modification times of the certificate files at the last (re)load
+    cert_mtimes: parking_lot::Mutex<Option<[SystemTime; 3]>>,
 }
 
 impl MTLSManager {
     /// Create a new mTLS manager
     pub fn new(config: MTLSConfig) -> Self {
         Self {
+            spiffe: config.spiffe.clone().map(Arc::new),
             config,
             server_config: Arc::new(RwLock::new(None)),
             client_config: Arc::new(RwLock::new(None)),
+            cert_mtimes: parking_lot::Mutex::new(None),
         }
     }
 
+    /// SPIFFE authorization policy, if peers are authorized by SPIFFE ID
+    pub fn spiffe_policy(&self) -> Option<Arc<SpiffePolicy>> {
+        self.spiffe.clone()
+    }
+
     /// Load server TLS configuration
     pub async fn load_server_config(&self) -> Result<Arc<ServerConfig>> {
         let certs = self.load_certs(&self.config.server_cert_path).await?;
         let key = self.load_private_key(&self.config.server_key_path).await?;
+        record_cert_expiry("server", &certs);
 
+        let verifier = WebPkiClientVerifier::builder(Arc::new(self.load_root_store().await?));
+        let verifier = if self.config.require_client_cert {
+            verifier.build()?
+        } else {
+            verifier.allow_unauthenticated().build()?
+        };
         let mut server_config = ServerConfig::builder()
-            .with_no_client_auth()
+            .with_client_cert_verifier(verifier)
             .with_single_cert(certs, key)?;
 
         // Enable ALPN for HTTP/2
@@ -83,17 +306,14 @@ impl MTLSManager {
 
     /// Load client TLS configuration
     pub async fn load_client_config(&self) -> Result<Arc<ClientConfig>> {
-        let mut root_store = RootCertStore::empty();
-
-        // Load CA certificate
-        let ca_certs = self.load_certs(&self.config.ca_cert_path).await?;
-        for cert in ca_certs {
-            root_store.add(cert)?;
-        }
+        let root_store = self.load_root_store().await?;
+        let certs = self.load_certs(&self.config.server_cert_path).await?;
+        let key = self.load_private_key(&self.config.server_key_path).await?;
 
+        // Present the node certificate so peers can authorize this node
         let mut client_config = ClientConfig::builder()
             .with_root_certificates(root_store)
-            .with_no_client_auth();
+            .with_client_auth_cert(certs, key)?;
 
         // Enable ALPN for HTTP/2
         client_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
@@ -109,6 +329,24 @@ impl MTLSManager {
         Ok(Certificate::from_pem(ca_cert))
     }
 
+    /// Load the node certificate and key as the tonic client identity
+    pub async fn load_identity(&self) -> Result<Identity> {
+        let cert = fs::read(&self.config.server_cert_path).await?;
+        let key = fs::read(&self.config.server_key_path).await?;
+        Ok(Identity::from_pem(cert, key))
+    }
+
+    /// Load the CA certificates into a root store
+    async fn load_root_store(&self) -> Result<RootCertStore> {
+        let ca_certs = self.load_certs(&self.config.ca_cert_path).await?;
+        record_cert_expiry("ca", &ca_certs);
+        let mut root_store = RootCertStore::empty();
+        for cert in ca_certs {
+            root_store.add(cert)?;
+        }
+        Ok(root_store)
+    }
+
     /// Load certificates from file
     async fn load_certs(&self, path: &Path) -> Result<Vec<CertificateDer<'static>>> {
         let cert_data = fs::read(path).await?;
@@ -129,51 +367,110 @@ impl MTLSManager {
         Ok(PrivateKeyDer::Pkcs8(keys.remove(0)))
     }
 
+    /// Current server config, loading it on first use
+    async fn current_server_config(&self) -> Result<Arc<ServerConfig>> {
+        if let Some(config) = self.server_config.read().await.clone() {
+            return Ok(config);
+        }
+        self.load_server_config().await
+    }
+
+    /// Accept TLS connections on `listener`. Each handshake uses the server
+    /// config current when the connection is accepted, so rotated
+    /// certificates apply to new connections; failed handshakes are dropped.
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "accept loop ends when the returned stream is dropped; handshake tasks are bounded by HANDSHAKE_TIMEOUT"
+    )]
+    pub fn tls_incoming(
+        self: &Arc<Self>,
+        listener: TcpListener,
+    ) -> ReceiverStream<std::io::Result<TlsStream<TcpStream>>> {
+        let (tx, rx) = mpsc::channel(64);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, addr): (TcpStream, SocketAddr) = tokio::select! {
+                    _ = tx.closed() => return,
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!("Failed to accept mesh connection: {}", e);
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            continue;
+                        }
+                    },
+                };
+                let _ = stream.set_nodelay(true);
+                let config = match manager.current_server_config().await {
+                    Ok(config) => config,
+                    Err(e) => {
+                        warn!("Failed to load mTLS server config: {}", e);
+                        continue;
+                    }
+                };
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let handshake = TlsAcceptor::from(config).accept(stream);
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake).await {
+                        Ok(Ok(tls)) => {
+                            let _ = tx.send(Ok(tls)).await;
+                        }
+                        Ok(Err(e)) => warn!("mTLS handshake with {} failed: {}", addr, e),
+                        Err(_) => warn!("mTLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+        ReceiverStream::new(rx)
+    }
+
     /// Start certificate rotation monitoring
     #[expect(
         clippy::disallowed_methods,
         reason = "fire-and-forget background monitor; rotation runs for the process lifetime and does not need explicit join"
     )]
-    pub fn start_rotation_monitor(&self) {
-        let config = self.config.clone();
-        let server_config = self.server_config.clone();
-        let client_config = self.client_config.clone();
+    pub fn start_rotation_monitor(self: &Arc<Self>) {
+        let manager = self.clone();
 
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(config.rotation_check_interval);
+            let mut interval = tokio::time::interval(manager.config.rotation_check_interval);
             loop {
                 interval.tick().await;
 
                 // Check if certificates have changed
-                if let Err(e) =
-                    Self::check_and_reload_certs(&config, &server_config, &client_config).await
-                {
+                if let Err(e) = manager.check_and_reload_certs().await {
                     warn!("Error checking certificate rotation: {}", e);
                 }
             }
         });
     }
 
-    /// Check and reload certificates if they have changed
-    async fn check_and_reload_certs(
-        config: &MTLSConfig,
-        _server_config: &Arc<RwLock<Option<Arc<ServerConfig>>>>,
-        _client_config: &Arc<RwLock<Option<Arc<ClientConfig>>>>,
-    ) -> Result<()> {
-        // Get file modification times
-        let server_cert_mtime = fs::metadata(&config.server_cert_path).await?.modified()?;
-        let server_key_mtime = fs::metadata(&config.server_key_path).await?.modified()?;
-        let ca_cert_mtime = fs::metadata(&config.ca_cert_path).await?.modified()?;
-
-        // TODO: Compare with cached modification times
-        // For now, we'll just log that rotation monitoring is active
-        info!(
-            "Certificate rotation check: server_cert={:?}, server_key={:?}, ca_cert={:?}",
-            server_cert_mtime, server_key_mtime, ca_cert_mtime
-        );
-
-        // Reload if certificates have changed
-        // This is a simplified version - in production, you'd compare mtimes
+    /// Check and reload certificates if they have changed. A failed reload
+    /// keeps the previous configs and is retried on the next check.
+    async fn check_and_reload_certs(&self) -> Result<()> {
+        let mtimes = [
+            fs::metadata(&self.config.server_cert_path)
+                .await?
+                .modified()?,
+            fs::metadata(&self.config.server_key_path)
+                .await?
+                .modified()?,
+            fs::metadata(&self.config.ca_cert_path).await?.modified()?,
+        ];
+        let previous = *self.cert_mtimes.lock();
+        match previous {
+            None => {
+                *self.cert_mtimes.lock() = Some(mtimes);
+            }
+            Some(previous) if previous != mtimes => {
+                self.load_server_config().await?;
+                self.load_client_config().await?;
+                *self.cert_mtimes.lock() = Some(mtimes);
+                info!("Reloaded mesh mTLS certificates after rotation");
+            }
+            Some(_) => {}
+        }
         Ok(())
     }
 
@@ -187,3 +484,112 @@ impl MTLSManager {
         self.client_config.read().await.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rcgen::{CertificateParams, KeyPair, SanType};
+
+    use super::*;
+
+    fn id(uri: &str) -> SpiffeId {
+        SpiffeId::parse(uri).unwrap()
+    }
+
+    fn matchers(patterns: &[&str]) -> Vec<SpiffeMatcher> {
+        patterns
+            .iter()
+            .map(|p| SpiffeMatcher::parse(p).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_spiffe_id_parse() {
+        let parsed = id("spiffe://example.org/ns/mesh/sa/gateway");
+        assert_eq!(parsed.trust_domain(), "example.org");
+        assert_eq!(parsed.path(), "/ns/mesh/sa/gateway");
+        assert_eq!(
+            parsed.to_string(),
+            "spiffe://example.org/ns/mesh/sa/gateway"
+        );
+
+        assert!(SpiffeId::parse("https://example.org/a").is_err());
+        assert!(SpiffeId::parse("spiffe://Example.org/a").is_err());
+        assert!(SpiffeId::parse("spiffe://example.org/a//b").is_err());
+        assert!(SpiffeId::parse("spiffe://example.org/a/../b").is_err());
+        assert!(SpiffeId::parse("spiffe:///a").is_err());
+    }
+
+    #[test]
+    fn test_spiffe_matchers() {
+        let gateway = id("spiffe://example.org/ns/mesh/sa/gateway");
+        assert!(SpiffeMatcher::parse("spiffe://example.org")
+            .unwrap()
+            .matches(&gateway));
+        assert!(SpiffeMatcher::parse("spiffe://example.org/ns/mesh/*")
+            .unwrap()
+            .matches(&gateway));
+        assert!(!SpiffeMatcher::parse("spiffe://example.org/ns/mes/*")
+            .unwrap()
+            .matches(&gateway));
+        assert!(
+            SpiffeMatcher::parse("spiffe://example.org/ns/mesh/sa/gateway")
+                .unwrap()
+                .matches(&gateway)
+        );
+        assert!(!SpiffeMatcher::parse("spiffe://other.org/*")
+            .unwrap()
+            .matches(&gateway));
+    }
+
+    #[test]
+    fn test_spiffe_policy_per_store() {
+        let policy = SpiffePolicy {
+            peers: matchers(&["spiffe://example.org/ns/mesh/*"]),
+            stores: vec![
+                (
+                    "rl:".to_string(),
+                    matchers(&["spiffe://example.org/ns/mesh/sa/limiter"]),
+                ),
+                (
+                    "worker:".to_string(),
+                    matchers(&["spiffe://example.org/ns/ops/*"]),
+                ),
+            ],
+        };
+        let gateway = id("spiffe://example.org/ns/mesh/sa/gateway");
+        let limiter = id("spiffe://example.org/ns/mesh/sa/limiter");
+        let ops = id("spiffe://example.org/ns/ops/sa/admin");
+        let stranger = id("spiffe://example.org/ns/other/sa/x");
+
+        assert!(policy.authorize_peer(&gateway));
+        assert!(policy.authorize_peer(&ops));
+        assert!(!policy.authorize_peer(&stranger));
+
+        assert!(policy.authorize_key(&gateway, "policy:a"));
+        assert!(!policy.authorize_key(&gateway, "rl:a"));
+        assert!(policy.authorize_key(&limiter, "rl:a"));
+        assert!(policy.authorize_key(&ops, "worker:w1"));
+        assert!(!policy.authorize_key(&ops, "policy:a"));
+    }
+
+    #[test]
+    fn test_spiffe_id_from_cert() {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec![]).unwrap();
+        params.subject_alt_names = vec![SanType::URI(
+            "spiffe://example.org/ns/mesh/sa/gateway"
+                .try_into()
+                .unwrap(),
+        )];
+        let cert = params.self_signed(&key).unwrap();
+        assert_eq!(
+            spiffe_id_from_cert(cert.der()).unwrap(),
+            id("spiffe://example.org/ns/mesh/sa/gateway")
+        );
+        assert!(cert_not_after(cert.der()).unwrap() > 0);
+
+        let params = CertificateParams::new(vec!["node-1.mesh".to_string()]).unwrap();
+        let cert = params.self_signed(&key).unwrap();
+        assert!(spiffe_id_from_cert(cert.der()).is_err());
+    }
+}
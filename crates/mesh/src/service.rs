@@ -1,10 +1,4 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    net::SocketAddr,
-    str::FromStr,
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::BTreeMap, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use parking_lot::RwLock;
@@ -30,6 +24,7 @@ use gossip::{
 use crate::{
     gossip_controller::GossipController,
     gossip_service::GossipService,
+    membership::{local_hardware_metadata, set_node_weight, weighted_owner, NodeCapacity},
     mtls::{MTLSConfig, MTLSManager},
     partition::PartitionDetector,
 };
@@ -155,6 +150,29 @@ impl MeshServerHandler {
     pub fn mesh_kv(&self) -> &Arc<crate::kv::MeshKV> {
         &self.mesh_kv
     }
+
+    /// Capacity weights and hardware metadata of all known nodes
+    pub fn node_capacities(&self) -> Vec<NodeCapacity> {
+        self.state.read().values().map(NodeCapacity::from).collect()
+    }
+
+    /// Set a node's capacity weight. The change is versioned like any other
+    /// node state update, so it reaches the rest of the cluster via gossip.
+    pub fn set_node_weight(&self, name: &str, weight: u32) -> Result<NodeCapacity> {
+        let mut state = self.state.write();
+        let node = state
+            .get_mut(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown mesh node {name}"))?;
+        set_node_weight(node, weight);
+        node.version += 1;
+        log::info!("Set capacity weight of mesh node {} to {}", name, weight);
+        Ok(NodeCapacity::from(&*node))
+    }
+
+    /// Name of the alive node owning `key`, weighted by node capacity
+    pub fn owner_for_key(&self, key: &str) -> Option<String> {
+        weighted_owner(self.state.read().values(), key).map(|node| node.name.clone())
+    }
 }
 
 pub struct MeshServerBuilder {
@@ -180,7 +198,7 @@ impl MeshServerBuilder {
                 address: advertise_addr.to_string(),
                 status: NodeStatus::Alive as i32,
                 version: 1,
-                metadata: HashMap::new(),
+                metadata: local_hardware_metadata(),
             },
         )])));
         Self {
@@ -326,6 +344,7 @@ impl MeshServer {
 
         // Add mTLS support if configured
         if let Some(mtls_manager) = self.mtls_manager.clone() {
+            mtls_manager.start_rotation_monitor();
             service = service.with_mtls_manager(mtls_manager);
         }
 
@@ -483,12 +502,16 @@ pub async fn try_ping(
                 "Failed to load mTLS CA certificate for {peer_name}: {e}"
             ))
         })?;
+        let identity = mtls_manager.load_identity().await.map_err(|e| {
+            tonic::Status::unavailable(format!("Failed to load mTLS identity for {peer_name}: {e}"))
+        })?;
 
         endpoint = endpoint
             .tls_config(
                 ClientTlsConfig::new()
                     .domain_name(tls_domain)
-                    .ca_certificate(ca_certificate),
+                    .ca_certificate(ca_certificate)
+                    .identity(identity),
             )
             .map_err(|e| {
                 tonic::Status::unavailable(format!(
@@ -555,7 +578,7 @@ macro_rules! mesh_run {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Once;
+    use std::{collections::HashMap, sync::Once};
 
     use tracing as log;
     use tracing_subscriber::{
@@ -186,6 +186,7 @@ impl TokenSpeedScheduler for MockScheduler {
             max_total_num_tokens: 1_000_000,
             tokenspeed_version: "mock".to_string(),
             start_time: None,
+            proto_revision: smg_grpc_client::PROTO_REVISION,
         }))
     }
 
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{net::IpAddr, time::Duration};
 
 use thiserror::Error;
 
@@ -31,6 +31,8 @@ pub enum MediaConnectorError {
     InvalidUrl(String),
     #[error("media domain '{0}' is not in the allow list")]
     DisallowedDomain(String),
+    #[error("media host '{host}' resolves to non-public address {ip}")]
+    DisallowedAddress { host: String, ip: IpAddr },
     #[error("local media path is not allowed: {0}")]
     DisallowedLocalPath(String),
     #[error("HTTP error while fetching media: {0}")]
@@ -53,6 +55,8 @@ pub enum MediaConnectorError {
     VideoDecode(String),
     #[error("media fetch timed out after {0:?}")]
     Timeout(Duration),
+    #[error("media fetch limiter closed")]
+    LimiterClosed,
 }
 
 #[derive(Debug, Error)]
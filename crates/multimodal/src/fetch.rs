@@ -0,0 +1,269 @@
+//! Politeness and safety policy for remote media fetches.
+//!
+//! [`FetchLimiter`] bounds concurrent HTTP fetches globally and per host,
+//! [`FetchRetryConfig`] retries transient failures with exponential backoff,
+//! and [`ensure_public_host`] rejects URLs resolving to private, loopback or
+//! link-local addresses so user-supplied media URLs cannot reach internal
+//! services (SSRF).
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use reqwest::{redirect, StatusCode};
+use tokio::{
+    net::lookup_host,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+use url::{Host, Url};
+
+use crate::error::MediaConnectorError;
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Retry policy for transient remote fetch failures.
+#[derive(Clone, Copy, Debug)]
+pub struct FetchRetryConfig {
+    /// Retries after the first attempt; 0 disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on every further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for FetchRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl FetchRetryConfig {
+    /// Delay before retry number `retry` (0-based).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32.checked_shl(retry).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether a failed fetch may succeed on another attempt: timeouts,
+/// connection failures, rate limiting and server errors.
+pub(crate) fn is_transient(err: &MediaConnectorError) -> bool {
+    match err {
+        MediaConnectorError::Timeout(_) => true,
+        MediaConnectorError::Http(err) => {
+            err.is_timeout()
+                || err.is_connect()
+                || err.status().is_some_and(|status| {
+                    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Bounds concurrent fetches across all hosts and per host.
+pub(crate) struct FetchLimiter {
+    global: Arc<Semaphore>,
+    per_host_limit: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Permits held for the duration of one fetch, including its retries.
+pub(crate) struct FetchPermit {
+    _host: OwnedSemaphorePermit,
+    _global: OwnedSemaphorePermit,
+}
+
+impl FetchLimiter {
+    pub fn new(max_concurrent: usize, max_per_host: usize) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            per_host_limit: max_per_host.max(1),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn acquire(&self, host: &str) -> Result<FetchPermit, MediaConnectorError> {
+        let host_semaphore = {
+            let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+            // Drop semaphores nobody holds or waits on, so the map only
+            // tracks hosts with in-flight fetches.
+            hosts.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+            hosts
+                .entry(host.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.per_host_limit)))
+                .clone()
+        };
+        // Queue on the host first so fetches waiting on a busy host do not
+        // hold global permits other hosts could use.
+        let host = host_semaphore
+            .acquire_owned()
+            .await
+            .map_err(|_| MediaConnectorError::LimiterClosed)?;
+        let global = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| MediaConnectorError::LimiterClosed)?;
+        Ok(FetchPermit {
+            _host: host,
+            _global: global,
+        })
+    }
+}
+
+/// Whether `ip` is a publicly routable unicast address.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 0.0.0.0/8 ("this network") and 100.64.0.0/10 (carrier-grade NAT)
+    let reserved = first == 0 || (first == 100 && (second & 0xc0) == 64);
+    !(reserved
+        || ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation())
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // fc00::/7 (unique local) and fe80::/10 (link-local)
+    let unique_local = (first & 0xfe00) == 0xfc00;
+    let link_local = (first & 0xffc0) == 0xfe80;
+    !(unique_local || link_local || ip.is_loopback() || ip.is_unspecified() || ip.is_multicast())
+}
+
+/// Reject `url` unless every address its host resolves to is public.
+pub async fn ensure_public_host(url: &Url) -> Result<(), MediaConnectorError> {
+    let host = url
+        .host()
+        .ok_or_else(|| MediaConnectorError::InvalidUrl(url.to_string()))?;
+    let addrs: Vec<IpAddr> = match host {
+        Host::Ipv4(ip) => vec![ip.into()],
+        Host::Ipv6(ip) => vec![ip.into()],
+        Host::Domain(domain) => {
+            let port = url.port_or_known_default().unwrap_or(80);
+            lookup_host((domain, port))
+                .await?
+                .map(|addr| addr.ip())
+                .collect()
+        }
+    };
+    match addrs.into_iter().find(|ip| !is_public_ip(*ip)) {
+        Some(ip) => Err(MediaConnectorError::DisallowedAddress {
+            host: host.to_string(),
+            ip,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Redirect policy that refuses redirects to `localhost` or to non-public IP
+/// literals. Redirect targets named by DNS are not resolved here, so clients
+/// fetching untrusted URLs should still prefer [`redirect::Policy::none`].
+pub fn public_redirect_policy() -> redirect::Policy {
+    redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        let public = match attempt.url().host() {
+            Some(Host::Ipv4(ip)) => is_public_ip(ip.into()),
+            Some(Host::Ipv6(ip)) => is_public_ip(ip.into()),
+            Some(Host::Domain(domain)) => !domain.eq_ignore_ascii_case("localhost"),
+            None => false,
+        };
+        if public {
+            attempt.follow()
+        } else {
+            attempt.error("redirect to a non-public address")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_public_host_rejects_private_literals() {
+        let url = Url::parse("http://169.254.169.254/latest/meta-data").unwrap();
+        assert!(matches!(
+            ensure_public_host(&url).await,
+            Err(MediaConnectorError::DisallowedAddress { .. })
+        ));
+        let url = Url::parse("http://[::1]:8080/image.png").unwrap();
+        assert!(ensure_public_host(&url).await.is_err());
+        let url = Url::parse("https://8.8.8.8/image.png").unwrap();
+        assert!(ensure_public_host(&url).await.is_ok());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let retry = FetchRetryConfig {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+        assert_eq!(retry.backoff(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff(1), Duration::from_millis(200));
+        assert_eq!(retry.backoff(2), Duration::from_millis(350));
+        assert_eq!(retry.backoff(40), Duration::from_millis(350));
+    }
+
+    #[tokio::test]
+    async fn test_limiter_bounds_per_host_concurrency() {
+        let limiter = FetchLimiter::new(8, 1);
+        let held = limiter.acquire("a.example").await.unwrap();
+        // Another host is not blocked by the busy one
+        let _other = limiter.acquire("b.example").await.unwrap();
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire("a.example")).await;
+        assert!(blocked.is_err());
+        drop(held);
+        assert!(limiter.acquire("a.example").await.is_ok());
+    }
+}
@@ -1,10 +1,13 @@
 pub mod audio;
 pub mod encoder_inputs;
 pub mod error;
+pub mod fetch;
 pub mod hasher;
 pub mod hub;
 pub mod jpeg_turbo;
 pub mod media;
+pub mod media_cache;
+mod metrics;
 #[cfg(feature = "opencv-video")]
 mod opencv_buffer;
 pub mod registry;
@@ -15,10 +18,15 @@ pub mod vision;
 pub use audio::AudioPreProcessor;
 pub use encoder_inputs::{ModelSpecificValue, PreprocessedEncoderInputs};
 pub use error::{MediaConnectorError, MultiModalError, MultiModalResult, TransformError};
+pub use fetch::FetchRetryConfig;
 pub use media::{
     ImageFetchConfig, MediaConnector, MediaConnectorConfig, MediaSource, VideoFetchConfig,
 };
-pub use registry::{MediaPartOrder, ModelMetadata, ModelProcessorSpec, ModelRegistry};
+pub use media_cache::MediaCache;
+pub use metrics::init_media_metrics;
+pub use registry::{
+    CustomSpecDefinition, MediaPartOrder, ModelMetadata, ModelProcessorSpec, ModelRegistry,
+};
 pub use tracker::{AsyncMultiModalTracker, TrackerOutput};
 pub use types::{
     AudioClip, AudioSource, EncoderFieldLayouts, FieldLayout, ImageDetail, ImageFrame, ImageSize,
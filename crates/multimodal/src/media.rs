@@ -19,7 +19,7 @@ use opencv::{
 };
 use reqwest::Client;
 use tokio::{fs, io::AsyncReadExt, process::Command, task, time};
-use tracing::info;
+use tracing::{debug, info};
 use url::Url;
 
 use crate::audio::decode_audio_mono_f32;
@@ -56,6 +56,8 @@ const OPENCV_HIGH_CONCURRENCY_CPU_BUDGET_DENOMINATOR: usize = 7;
 
 use super::{
     error::MediaConnectorError,
+    fetch::{ensure_public_host, is_transient, FetchLimiter, FetchRetryConfig},
+    metrics,
     types::{
         AudioClip, AudioSource, DecodedRgbFrame, DecodedRgbVideo, ImageDetail, ImageFrame,
         ImageSource, VideoClip, VideoSource,
@@ -67,6 +69,14 @@ pub struct MediaConnectorConfig {
     pub allowed_domains: Option<Vec<String>>,
     pub allowed_local_media_path: Option<PathBuf>,
     pub fetch_timeout: Duration,
+    /// Maximum concurrent remote fetches across all hosts.
+    pub max_concurrent_fetches: usize,
+    /// Maximum concurrent remote fetches against a single host.
+    pub max_concurrent_fetches_per_host: usize,
+    /// Retry policy for transient remote fetch failures.
+    pub retry: FetchRetryConfig,
+    /// Allow URLs resolving to private, loopback or link-local addresses.
+    pub allow_private_addresses: bool,
 }
 
 impl Default for MediaConnectorConfig {
@@ -75,6 +85,10 @@ impl Default for MediaConnectorConfig {
             allowed_domains: None,
             allowed_local_media_path: None,
             fetch_timeout: Duration::from_secs(10),
+            max_concurrent_fetches: 64,
+            max_concurrent_fetches_per_host: 8,
+            retry: FetchRetryConfig::default(),
+            allow_private_addresses: false,
         }
     }
 }
@@ -123,6 +137,9 @@ pub struct MediaConnector {
     allowed_domains: Option<HashSet<String>>,
     allowed_local_media_path: Option<PathBuf>,
     fetch_timeout: Duration,
+    limiter: Arc<FetchLimiter>,
+    retry: FetchRetryConfig,
+    allow_private_addresses: bool,
 }
 
 impl MediaConnector {
@@ -145,6 +162,12 @@ impl MediaConnector {
             allowed_domains,
             allowed_local_media_path,
             fetch_timeout: config.fetch_timeout,
+            limiter: Arc::new(FetchLimiter::new(
+                config.max_concurrent_fetches,
+                config.max_concurrent_fetches_per_host,
+            )),
+            retry: config.retry,
+            allow_private_addresses: config.allow_private_addresses,
         })
     }
 
@@ -200,24 +223,9 @@ impl MediaConnector {
         url: String,
         cfg: ImageFetchConfig,
     ) -> Result<Arc<ImageFrame>, MediaConnectorError> {
-        let parsed = Url::parse(&url).map_err(|_| MediaConnectorError::InvalidUrl(url.clone()))?;
-        self.ensure_domain_allowed(&parsed)?;
-
-        let mut req = self.client.get(parsed.as_str());
-        if self.fetch_timeout > Duration::ZERO {
-            req = req.timeout(self.fetch_timeout);
-        }
-
-        let resp = req.send().await.map_err(|err| {
-            if err.is_timeout() {
-                MediaConnectorError::Timeout(self.fetch_timeout)
-            } else {
-                MediaConnectorError::Http(err)
-            }
-        })?;
-
-        let resp = resp.error_for_status()?;
-        let bytes = collect_http_body_with_limit(resp, image_max_input_bytes(), "image").await?;
+        let (parsed, bytes) = self
+            .fetch_http_bytes(&url, image_max_input_bytes(), "image")
+            .await?;
         self.decode_image(
             bytes,
             cfg.detail,
@@ -317,24 +325,9 @@ impl MediaConnector {
         url: String,
         cfg: VideoFetchConfig,
     ) -> Result<Arc<VideoClip>, MediaConnectorError> {
-        let parsed = Url::parse(&url).map_err(|_| MediaConnectorError::InvalidUrl(url.clone()))?;
-        self.ensure_domain_allowed(&parsed)?;
-
-        let mut req = self.client.get(parsed.as_str());
-        if self.fetch_timeout > Duration::ZERO {
-            req = req.timeout(self.fetch_timeout);
-        }
-
-        let resp = req.send().await.map_err(|err| {
-            if err.is_timeout() {
-                MediaConnectorError::Timeout(self.fetch_timeout)
-            } else {
-                MediaConnectorError::Http(err)
-            }
-        })?;
-
-        let resp = resp.error_for_status()?;
-        let bytes = collect_http_body_with_limit(resp, video_max_input_bytes(), "video").await?;
+        let (parsed, bytes) = self
+            .fetch_http_bytes(&url, video_max_input_bytes(), "video")
+            .await?;
         self.decode_video(
             bytes,
             cfg,
@@ -346,24 +339,9 @@ impl MediaConnector {
     }
 
     async fn fetch_http_audio(&self, url: String) -> Result<Arc<AudioClip>, MediaConnectorError> {
-        let parsed = Url::parse(&url).map_err(|_| MediaConnectorError::InvalidUrl(url.clone()))?;
-        self.ensure_domain_allowed(&parsed)?;
-
-        let mut req = self.client.get(parsed.as_str());
-        if self.fetch_timeout > Duration::ZERO {
-            req = req.timeout(self.fetch_timeout);
-        }
-
-        let resp = req.send().await.map_err(|err| {
-            if err.is_timeout() {
-                MediaConnectorError::Timeout(self.fetch_timeout)
-            } else {
-                MediaConnectorError::Http(err)
-            }
-        })?;
-
-        let resp = resp.error_for_status()?;
-        let bytes = collect_http_body_with_limit(resp, audio_max_input_bytes(), "audio").await?;
+        let (parsed, bytes) = self
+            .fetch_http_bytes(&url, audio_max_input_bytes(), "audio")
+            .await?;
         self.decode_audio(
             bytes,
             AudioSource::Url {
@@ -413,6 +391,64 @@ impl MediaConnector {
             .await
     }
 
+    /// Fetch `url` under the connector's concurrency limits, retrying
+    /// transient failures.
+    async fn fetch_http_bytes(
+        &self,
+        url: &str,
+        limit: usize,
+        media: &'static str,
+    ) -> Result<(Url, Bytes), MediaConnectorError> {
+        let parsed =
+            Url::parse(url).map_err(|_| MediaConnectorError::InvalidUrl(url.to_string()))?;
+        self.ensure_domain_allowed(&parsed)?;
+        if !self.allow_private_addresses {
+            ensure_public_host(&parsed).await?;
+        }
+
+        let host = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+        let _permit = self.limiter.acquire(&host).await?;
+        let started = Instant::now();
+        let mut retry = 0;
+        let result = loop {
+            match self.fetch_http_once(&parsed, limit, media).await {
+                Err(err) if retry < self.retry.max_retries && is_transient(&err) => {
+                    let delay = self.retry.backoff(retry);
+                    debug!(%host, media, retry, ?delay, error = %err, "retrying media fetch");
+                    metrics::record_media_fetch_retry(media);
+                    time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => break result,
+            }
+        };
+        metrics::record_media_fetch(media, result.is_ok(), started.elapsed());
+        Ok((parsed, result?))
+    }
+
+    async fn fetch_http_once(
+        &self,
+        url: &Url,
+        limit: usize,
+        media: &'static str,
+    ) -> Result<Bytes, MediaConnectorError> {
+        let mut req = self.client.get(url.as_str());
+        if self.fetch_timeout > Duration::ZERO {
+            req = req.timeout(self.fetch_timeout);
+        }
+
+        let resp = req.send().await.map_err(|err| {
+            if err.is_timeout() {
+                MediaConnectorError::Timeout(self.fetch_timeout)
+            } else {
+                MediaConnectorError::Http(err)
+            }
+        })?;
+
+        let resp = resp.error_for_status()?;
+        collect_http_body_with_limit(resp, limit, media).await
+    }
+
     fn ensure_domain_allowed(&self, url: &Url) -> Result<(), MediaConnectorError> {
         if let Some(allowed) = &self.allowed_domains {
             let host = url
@@ -0,0 +1,171 @@
+//! Cross-request cache of decoded images keyed by content hash.
+//!
+//! Chat clients resend the whole conversation every turn, so an inline image
+//! (data URL or raw bytes) from an earlier turn arrives again verbatim. The
+//! tracker hashes the inline payload and reuses the decoded [`ImageFrame`]
+//! instead of decoding it again; the frame's `hash` then lets downstream
+//! caches reuse the preprocessed tensors and placeholder expansion.
+//!
+//! Remote URLs are not cached, since the content behind a URL can change, and
+//! caller-supplied multimodal UUIDs only deduplicate within one request: they
+//! are chosen by the client and must not let one request read another's media.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use lru::LruCache;
+
+use crate::{
+    media::MediaSource,
+    types::{ImageDetail, ImageFrame},
+};
+
+/// Fixed per-entry overhead charged against the byte budget.
+const ENTRY_OVERHEAD_BYTES: usize = 128;
+
+/// Identifies a decoded image by the hash of its inline payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageCacheKey {
+    content_hash: blake3::Hash,
+    detail: ImageDetail,
+}
+
+impl ImageCacheKey {
+    /// Key for an inline image source; `None` for remote URLs and files.
+    pub fn for_source(source: &MediaSource, detail: ImageDetail) -> Option<Self> {
+        let content_hash = match source {
+            MediaSource::DataUrl(data_url) => blake3::hash(data_url.as_bytes()),
+            MediaSource::InlineBytes(bytes) => blake3::hash(bytes),
+            MediaSource::Url(_) | MediaSource::File(_) => return None,
+        };
+        Some(Self {
+            content_hash,
+            detail,
+        })
+    }
+}
+
+struct MediaCacheInner {
+    map: LruCache<ImageCacheKey, Arc<ImageFrame>>,
+    cur_bytes: usize,
+    max_bytes: usize,
+}
+
+/// Thread-safe, byte-budgeted LRU of decoded images.
+pub struct MediaCache {
+    inner: Mutex<MediaCacheInner>,
+}
+
+fn frame_bytes(frame: &ImageFrame) -> usize {
+    frame.image.as_bytes().len() + frame.raw_bytes.len() + ENTRY_OVERHEAD_BYTES
+}
+
+impl MediaCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(MediaCacheInner {
+                map: LruCache::unbounded(),
+                cur_bytes: 0,
+                max_bytes,
+            }),
+        }
+    }
+
+    pub fn get(&self, key: &ImageCacheKey) -> Option<Arc<ImageFrame>> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.map.get(key).cloned()
+    }
+
+    pub fn insert(&self, key: ImageCacheKey, frame: Arc<ImageFrame>) {
+        let entry_bytes = frame_bytes(&frame);
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if entry_bytes > inner.max_bytes {
+            return;
+        }
+        let MediaCacheInner {
+            map,
+            cur_bytes,
+            max_bytes,
+        } = &mut *inner;
+        if let Some(previous) = map.put(key, frame) {
+            *cur_bytes = cur_bytes.saturating_sub(frame_bytes(&previous));
+        }
+        *cur_bytes += entry_bytes;
+        while *cur_bytes > *max_bytes {
+            match map.pop_lru() {
+                Some((_, evicted)) => {
+                    *cur_bytes = cur_bytes.saturating_sub(frame_bytes(&evicted));
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .map
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, RgbImage};
+
+    use super::*;
+    use crate::types::ImageSource;
+
+    fn frame(side: u32) -> Arc<ImageFrame> {
+        Arc::new(ImageFrame::new(
+            DynamicImage::from(RgbImage::new(side, side)),
+            bytes::Bytes::new(),
+            ImageDetail::Auto,
+            ImageSource::InlineBytes,
+            String::new(),
+        ))
+    }
+
+    #[test]
+    fn test_key_depends_on_content_and_detail() {
+        let a = MediaSource::InlineBytes(vec![1, 2, 3]);
+        let b = MediaSource::DataUrl("data:image/png;base64,AQID".into());
+        let key = ImageCacheKey::for_source(&a, ImageDetail::Auto).unwrap();
+        assert_eq!(Some(key), ImageCacheKey::for_source(&a, ImageDetail::Auto));
+        assert_ne!(Some(key), ImageCacheKey::for_source(&a, ImageDetail::Low));
+        assert_ne!(Some(key), ImageCacheKey::for_source(&b, ImageDetail::Auto));
+        assert!(ImageCacheKey::for_source(
+            &MediaSource::Url("https://example.com/a.png".into()),
+            ImageDetail::Auto
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_within_budget() {
+        // Each 16x16 RGB frame charges 768 + overhead bytes
+        let cache = MediaCache::new(2 * (768 + ENTRY_OVERHEAD_BYTES));
+        let keys: Vec<_> = (0u8..3)
+            .map(|i| {
+                ImageCacheKey::for_source(&MediaSource::InlineBytes(vec![i]), ImageDetail::Auto)
+                    .unwrap()
+            })
+            .collect();
+        cache.insert(keys[0], frame(16));
+        cache.insert(keys[1], frame(16));
+        assert!(cache.get(&keys[0]).is_some());
+        cache.insert(keys[2], frame(16));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&keys[0]).is_some());
+        assert!(cache.get(&keys[1]).is_none());
+
+        // Larger than the whole budget: never cached
+        cache.insert(keys[1], frame(64));
+        assert!(cache.get(&keys[1]).is_none());
+    }
+}
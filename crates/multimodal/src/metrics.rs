@@ -0,0 +1,42 @@
+//! Media fetch and cache metrics for Prometheus
+
+use std::time::Duration;
+
+use metrics::{counter, describe_counter, describe_histogram, histogram};
+
+/// Initialize media metric descriptions
+pub fn init_media_metrics() {
+    describe_histogram!(
+        "smg_mm_media_fetch_duration_seconds",
+        "Remote media fetch latency including retries, by media type and outcome"
+    );
+    describe_counter!(
+        "smg_mm_media_fetch_retries_total",
+        "Remote media fetch retries after transient failures, by media type"
+    );
+    describe_counter!(
+        "smg_mm_media_cache_total",
+        "Inline image lookups in the decoded media cache, by outcome (hit/miss)"
+    );
+}
+
+/// Record a finished remote media fetch
+pub(crate) fn record_media_fetch(media: &'static str, success: bool, duration: Duration) {
+    let outcome = if success { "success" } else { "error" };
+    histogram!("smg_mm_media_fetch_duration_seconds",
+        "media" => media,
+        "outcome" => outcome
+    )
+    .record(duration.as_secs_f64());
+}
+
+/// Record a retry of a remote media fetch
+pub(crate) fn record_media_fetch_retry(media: &'static str) {
+    counter!("smg_mm_media_fetch_retries_total", "media" => media).increment(1);
+}
+
+/// Record a decoded media cache lookup
+pub(crate) fn record_media_cache(hit: bool) {
+    let outcome = if hit { "hit" } else { "miss" };
+    counter!("smg_mm_media_cache_total", "outcome" => outcome).increment(1);
+}
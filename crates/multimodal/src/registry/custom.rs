@@ -0,0 +1,280 @@
+//! Processor specs registered at runtime.
+//!
+//! A [`CustomSpecDefinition`] describes a vision model whose preprocessing
+//! fits one of the built-in processors: the HF `preprocessor_config.json`
+//! selects the processor through `image_processor_type`, and each image
+//! expands to `image_token` repeated once per vision feature token.
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    encoder_inputs::PreprocessedEncoderInputs,
+    registry::{ModelMetadata, ModelProcessorSpec, ModelRegistryError, RegistryResult},
+    types::{Modality, PromptReplacement, TokenId},
+    vision::{
+        processors::{
+            Llama4VisionProcessor, LlavaNextProcessor, LlavaProcessor, Phi3VisionProcessor,
+            PixtralProcessor, Qwen2VLProcessor, Qwen3VLProcessor,
+        },
+        PreProcessorConfig, VisionPreProcessor,
+    },
+};
+
+fn default_max_images() -> usize {
+    4
+}
+
+/// Runtime definition of a processor spec.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomSpecDefinition {
+    /// Unique name; registering the same name again replaces the spec.
+    pub name: String,
+    /// Case-insensitive substrings of the model id this spec applies to.
+    #[serde(default)]
+    pub model_id_patterns: Vec<String>,
+    /// `model_type` values from the model's `config.json` this spec applies to.
+    #[serde(default)]
+    pub model_types: Vec<String>,
+    /// Placeholder token each image expands to.
+    pub image_token: String,
+    /// Maximum images per request.
+    #[serde(default = "default_max_images")]
+    pub max_images: usize,
+    /// HF-format `preprocessor_config.json`.
+    pub preprocessor_config: Value,
+}
+
+/// Build the built-in processor named by an HF `image_processor_type`.
+fn processor_for(
+    processor_type: &str,
+    raw_config: &Value,
+    config: &PreProcessorConfig,
+) -> Option<Arc<dyn VisionPreProcessor>> {
+    let processor_type = processor_type
+        .strip_suffix("Fast")
+        .unwrap_or(processor_type);
+    let processor: Arc<dyn VisionPreProcessor> = match processor_type {
+        "CLIPImageProcessor" | "SiglipImageProcessor" => Arc::new(LlavaProcessor::new()),
+        "LlavaNextImageProcessor" => Arc::new(LlavaNextProcessor::from_config(raw_config)),
+        "Qwen2VLImageProcessor" => Arc::new(Qwen2VLProcessor::from_preprocessor_config(config)),
+        "Qwen3VLImageProcessor" => Arc::new(Qwen3VLProcessor::from_preprocessor_config(config)),
+        "Phi3VImageProcessor" => Arc::new(Phi3VisionProcessor::from_preprocessor_config(config)),
+        "PixtralImageProcessor" => Arc::new(PixtralProcessor::from_preprocessor_config(config)),
+        "Llama4ImageProcessor" => Arc::new(Llama4VisionProcessor::from_preprocessor_config(config)),
+        _ => return None,
+    };
+    Some(processor)
+}
+
+/// A processor spec built from a [`CustomSpecDefinition`].
+pub struct CustomProcessorSpec {
+    definition: CustomSpecDefinition,
+    model_id_patterns: Vec<String>,
+    preprocessor_config: PreProcessorConfig,
+    processor: Arc<dyn VisionPreProcessor>,
+}
+
+impl CustomProcessorSpec {
+    pub fn new(definition: CustomSpecDefinition) -> RegistryResult<Self> {
+        let invalid = |reason: String| ModelRegistryError::InvalidCustomSpec {
+            name: definition.name.clone(),
+            reason,
+        };
+        if definition.name.trim().is_empty() {
+            return Err(invalid("name must not be empty".to_string()));
+        }
+        if definition.image_token.is_empty() {
+            return Err(invalid("image_token must not be empty".to_string()));
+        }
+        if definition.max_images == 0 {
+            return Err(invalid("max_images must be > 0".to_string()));
+        }
+        if definition.model_id_patterns.is_empty() && definition.model_types.is_empty() {
+            return Err(invalid(
+                "at least one of model_id_patterns or model_types is required".to_string(),
+            ));
+        }
+
+        let preprocessor_config =
+            PreProcessorConfig::from_value(definition.preprocessor_config.clone())
+                .map_err(|e| invalid(format!("invalid preprocessor_config: {e}")))?;
+        let processor_type = preprocessor_config
+            .image_processor_type
+            .as_deref()
+            .ok_or_else(|| {
+                invalid("preprocessor_config has no image_processor_type".to_string())
+            })?;
+        let processor = processor_for(
+            processor_type,
+            &definition.preprocessor_config,
+            &preprocessor_config,
+        )
+        .ok_or_else(|| {
+            invalid(format!(
+                "unsupported image_processor_type '{processor_type}'"
+            ))
+        })?;
+
+        Ok(Self {
+            model_id_patterns: definition
+                .model_id_patterns
+                .iter()
+                .map(|pattern| pattern.to_ascii_lowercase())
+                .collect(),
+            definition,
+            preprocessor_config,
+            processor,
+        })
+    }
+
+    pub fn definition(&self) -> &CustomSpecDefinition {
+        &self.definition
+    }
+}
+
+impl ModelProcessorSpec for CustomProcessorSpec {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn matches(&self, metadata: &ModelMetadata) -> bool {
+        let model_id = metadata.model_id.to_ascii_lowercase();
+        self.model_id_patterns
+            .iter()
+            .any(|pattern| model_id.contains(pattern.as_str()))
+            || metadata
+                .config_model_type()
+                .is_some_and(|mt| self.definition.model_types.iter().any(|t| t == mt))
+    }
+
+    fn placeholder_token(&self, _metadata: &ModelMetadata) -> RegistryResult<String> {
+        Ok(self.definition.image_token.clone())
+    }
+
+    fn placeholder_token_id(&self, metadata: &ModelMetadata) -> RegistryResult<TokenId> {
+        metadata.token_id(&self.definition.image_token)
+    }
+
+    fn modality_limits(
+        &self,
+        _metadata: &ModelMetadata,
+    ) -> RegistryResult<HashMap<Modality, usize>> {
+        Ok(HashMap::from([(
+            Modality::Image,
+            self.definition.max_images,
+        )]))
+    }
+
+    fn processor_kwargs(&self, _metadata: &ModelMetadata) -> RegistryResult<Value> {
+        Ok(json!({}))
+    }
+
+    fn prompt_replacements(
+        &self,
+        metadata: &ModelMetadata,
+        preprocessed: &PreprocessedEncoderInputs,
+    ) -> RegistryResult<Vec<PromptReplacement>> {
+        let token_id = self.placeholder_token_id(metadata)?;
+        let token = &self.definition.image_token;
+        Ok(preprocessed
+            .feature_token_counts
+            .iter()
+            .map(|&count| PromptReplacement::repeated(Modality::Image, token, token_id, count))
+            .collect())
+    }
+
+    fn vision_processor(&self) -> Option<Arc<dyn VisionPreProcessor>> {
+        Some(self.processor.clone())
+    }
+
+    fn preprocessor_config(&self) -> Option<&PreProcessorConfig> {
+        Some(&self.preprocessor_config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::registry::{test_helpers::TestTokenizer, ModelRegistry};
+
+    fn definition() -> CustomSpecDefinition {
+        serde_json::from_value(json!({
+            "name": "acme-vl",
+            "model_id_patterns": ["Acme-VL"],
+            "model_types": ["acme_vl"],
+            "image_token": "<|acme_image|>",
+            "preprocessor_config": {
+                "image_processor_type": "Qwen2VLImageProcessorFast",
+                "min_pixels": 3136,
+                "max_pixels": 1003520,
+                "patch_size": 14,
+                "merge_size": 2,
+                "temporal_patch_size": 2
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_registered_spec_matches_model() {
+        let registry = ModelRegistry::new();
+        registry.register_custom(definition()).unwrap();
+
+        let tokenizer = TestTokenizer::new(&[("<|acme_image|>", 151655)]);
+        let config = json!({"model_type": "llama"});
+        let metadata = ModelMetadata {
+            model_id: "acme/acme-vl-7b",
+            tokenizer: &tokenizer,
+            config: &config,
+        };
+        let spec = registry.lookup(&metadata).expect("custom spec");
+        assert_eq!(spec.name(), "custom");
+        assert_eq!(spec.placeholder_token_id(&metadata).unwrap(), 151655);
+        assert_eq!(
+            spec.vision_processor().unwrap().model_name(),
+            Qwen2VLProcessor::new().model_name()
+        );
+        assert_eq!(spec.preprocessor_config().unwrap().merge_size, Some(2));
+        assert_eq!(
+            spec.modality_limits(&metadata).unwrap()[&Modality::Image],
+            4
+        );
+
+        assert!(registry.unregister_custom("acme-vl"));
+        assert!(registry.lookup(&metadata).is_none());
+    }
+
+    #[test]
+    fn test_reregistering_replaces_definition() {
+        let registry = ModelRegistry::new();
+        registry.register_custom(definition()).unwrap();
+        let mut updated = definition();
+        updated.max_images = 8;
+        registry.register_custom(updated.clone()).unwrap();
+        assert_eq!(registry.custom_definitions(), vec![updated]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_definitions() {
+        let mut unsupported = definition();
+        unsupported.preprocessor_config["image_processor_type"] = json!("MysteryProcessor");
+        assert!(matches!(
+            CustomProcessorSpec::new(unsupported),
+            Err(ModelRegistryError::InvalidCustomSpec { .. })
+        ));
+
+        let mut unmatched = definition();
+        unmatched.model_id_patterns.clear();
+        unmatched.model_types.clear();
+        assert!(CustomProcessorSpec::new(unmatched).is_err());
+
+        let mut no_token = definition();
+        no_token.image_token.clear();
+        assert!(CustomProcessorSpec::new(no_token).is_err());
+    }
+}
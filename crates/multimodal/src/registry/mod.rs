@@ -1,3 +1,4 @@
+mod custom;
 mod inkling;
 mod kimi_k25;
 mod llama4;
@@ -9,6 +10,9 @@ mod qwen3_vl;
 mod qwen_vl;
 mod traits;
 
+use std::sync::{Arc, RwLock};
+
+pub use custom::{CustomProcessorSpec, CustomSpecDefinition};
 use inkling::InklingSpec;
 use kimi_k25::KimiK25VisionSpec;
 use llama4::Llama4Spec;
@@ -26,36 +30,73 @@ pub use traits::{
 
 pub struct ModelRegistry {
     specs: Vec<LazySpec>,
+    /// Specs registered at runtime; consulted before the built-in specs.
+    custom: RwLock<Vec<Arc<CustomProcessorSpec>>>,
 }
 
 impl ModelRegistry {
     pub fn new() -> Self {
         Self {
             specs: vec![
-                LazySpec::new(|| Box::new(InklingSpec)),
-                LazySpec::new(|| Box::new(KimiK25VisionSpec)),
-                LazySpec::new(|| Box::new(Llama4Spec)),
+                LazySpec::new(|| Arc::new(InklingSpec)),
+                LazySpec::new(|| Arc::new(KimiK25VisionSpec)),
+                LazySpec::new(|| Arc::new(Llama4Spec)),
                 // LlavaNext must be registered before Llava so "llava_next" model_type matches first.
-                LazySpec::new(|| Box::new(LlavaNextSpec)),
-                LazySpec::new(|| Box::new(LlavaSpec)),
-                LazySpec::new(|| Box::new(Qwen3AsrSpec)),
-                LazySpec::new(|| Box::new(Qwen3OmniSpec)),
+                LazySpec::new(|| Arc::new(LlavaNextSpec)),
+                LazySpec::new(|| Arc::new(LlavaSpec)),
+                LazySpec::new(|| Arc::new(Qwen3AsrSpec)),
+                LazySpec::new(|| Arc::new(Qwen3OmniSpec)),
                 // Qwen3-VL must be registered before QwenVL so "qwen3" matches first.
-                LazySpec::new(|| Box::new(Qwen3VLVisionSpec)),
-                LazySpec::new(|| Box::new(QwenVLVisionSpec)),
-                LazySpec::new(|| Box::new(Phi3VisionSpec)),
+                LazySpec::new(|| Arc::new(Qwen3VLVisionSpec)),
+                LazySpec::new(|| Arc::new(QwenVLVisionSpec)),
+                LazySpec::new(|| Arc::new(Phi3VisionSpec)),
             ],
+            custom: RwLock::new(Vec::new()),
         }
     }
 
-    pub fn lookup<'a>(&'a self, metadata: &ModelMetadata) -> Option<&'a dyn ModelProcessorSpec> {
-        for spec in &self.specs {
-            let spec_ref = spec.get();
-            if spec_ref.matches(metadata) {
-                return Some(spec_ref);
-            }
+    pub fn lookup(&self, metadata: &ModelMetadata) -> Option<Arc<dyn ModelProcessorSpec>> {
+        let custom = self.custom.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(spec) = custom.iter().find(|spec| spec.matches(metadata)) {
+            return Some(spec.clone());
+        }
+        drop(custom);
+        self.specs
+            .iter()
+            .map(LazySpec::get)
+            .find(|spec| spec.matches(metadata))
+    }
+
+    /// Register a spec at runtime, replacing any spec with the same name.
+    pub fn register_custom(&self, definition: CustomSpecDefinition) -> RegistryResult<()> {
+        let spec = Arc::new(CustomProcessorSpec::new(definition)?);
+        let mut custom = self.custom.write().unwrap_or_else(|e| e.into_inner());
+        match custom
+            .iter_mut()
+            .find(|existing| existing.definition().name == spec.definition().name)
+        {
+            Some(existing) => *existing = spec,
+            None => custom.push(spec),
         }
-        None
+        Ok(())
+    }
+
+    /// Remove a runtime-registered spec. Returns whether it existed.
+    pub fn unregister_custom(&self, name: &str) -> bool {
+        let mut custom = self.custom.write().unwrap_or_else(|e| e.into_inner());
+        let before = custom.len();
+        custom.retain(|spec| spec.definition().name != name);
+        custom.len() != before
+    }
+
+    /// Definitions of all runtime-registered specs, in lookup order.
+    pub fn custom_definitions(&self) -> Vec<CustomSpecDefinition> {
+        self.custom
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|spec| spec.definition().clone())
+            .collect()
     }
 }
 
@@ -66,18 +107,18 @@ impl Default for ModelRegistry {
 }
 
 struct LazySpec {
-    inner: Lazy<Box<dyn ModelProcessorSpec>>,
+    inner: Lazy<Arc<dyn ModelProcessorSpec>>,
 }
 
 impl LazySpec {
-    fn new(factory: fn() -> Box<dyn ModelProcessorSpec>) -> Self {
+    fn new(factory: fn() -> Arc<dyn ModelProcessorSpec>) -> Self {
         Self {
             inner: Lazy::new(factory),
         }
     }
 
-    fn get(&self) -> &dyn ModelProcessorSpec {
-        self.inner.as_ref()
+    fn get(&self) -> Arc<dyn ModelProcessorSpec> {
+        self.inner.clone()
     }
 }
 
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use llm_tokenizer::TokenizerTrait;
 use serde_json::Value;
@@ -8,7 +8,7 @@ use crate::{
     audio::AudioPreProcessor,
     encoder_inputs::PreprocessedEncoderInputs,
     types::{EncoderFieldLayouts, FieldLayout, Modality, PromptReplacement, TokenId},
-    vision::PreProcessorConfig,
+    vision::{PreProcessorConfig, VisionPreProcessor},
 };
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -36,6 +36,8 @@ pub enum ModelRegistryError {
         spec: &'static str,
         modality: Modality,
     },
+    #[error("invalid custom processor spec '{name}': {reason}")]
+    InvalidCustomSpec { name: String, reason: String },
 }
 
 pub type RegistryResult<T> = Result<T, ModelRegistryError>;
@@ -188,6 +190,21 @@ pub trait ModelProcessorSpec: Send + Sync {
         None
     }
 
+    /// Vision preprocessor owned by this spec.
+    ///
+    /// Built-in specs return `None` and their processor is resolved from the
+    /// `VisionProcessorRegistry` by model id; runtime-registered specs carry
+    /// their own.
+    fn vision_processor(&self) -> Option<Arc<dyn VisionPreProcessor>> {
+        None
+    }
+
+    /// Preprocessor config owned by this spec, used instead of the
+    /// checkpoint's `preprocessor_config.json` for images.
+    fn preprocessor_config(&self) -> Option<&PreProcessorConfig> {
+        None
+    }
+
     /// Compute per-media prompt replacement token sequences.
     ///
     /// Receives the full preprocessed output so each model can extract whatever
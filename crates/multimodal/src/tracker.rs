@@ -5,6 +5,8 @@ use tokio::task::JoinHandle;
 use super::{
     error::{MultiModalError, MultiModalResult},
     media::{ImageFetchConfig, MediaConnector, MediaSource, VideoFetchConfig},
+    media_cache::{ImageCacheKey, MediaCache},
+    metrics,
     types::{
         ImageDetail, MediaContentPart, Modality, MultiModalData, MultiModalUUIDs, TrackedMedia,
     },
@@ -12,6 +14,22 @@ use super::{
 
 type PendingTask = JoinHandle<MultiModalResult<TrackedMedia>>;
 
+enum PendingMedia {
+    Task(PendingTask),
+    /// Served from the cross-request media cache
+    Ready(TrackedMedia),
+    /// Same media as an earlier item of this modality in the request
+    SameAs(usize),
+}
+
+/// Identifies repeated images within one request.
+#[derive(Hash, PartialEq, Eq)]
+enum ImageIdentity {
+    Uuid(String, ImageDetail),
+    Content(ImageCacheKey),
+    Url(String, ImageDetail),
+}
+
 #[derive(Debug)]
 pub struct TrackerOutput {
     pub data: MultiModalData,
@@ -20,7 +38,9 @@ pub struct TrackerOutput {
 
 pub struct AsyncMultiModalTracker {
     media_connector: Arc<MediaConnector>,
-    pending: HashMap<Modality, Vec<PendingTask>>,
+    media_cache: Option<Arc<MediaCache>>,
+    pending: HashMap<Modality, Vec<PendingMedia>>,
+    seen_images: HashMap<ImageIdentity, usize>,
     uuids: MultiModalUUIDs,
 }
 
@@ -28,11 +48,19 @@ impl AsyncMultiModalTracker {
     pub fn new(media_connector: Arc<MediaConnector>) -> Self {
         Self {
             media_connector,
+            media_cache: None,
             pending: HashMap::new(),
+            seen_images: HashMap::new(),
             uuids: HashMap::new(),
         }
     }
 
+    /// Reuse decoded inline images across requests through `cache`.
+    pub fn with_media_cache(mut self, cache: Arc<MediaCache>) -> Self {
+        self.media_cache = Some(cache);
+        self
+    }
+
     pub fn push_part(&mut self, part: MediaContentPart) -> MultiModalResult<()> {
         match part {
             MediaContentPart::Text { .. } => {}
@@ -92,10 +120,14 @@ impl AsyncMultiModalTracker {
 
     pub async fn finalize(mut self) -> MultiModalResult<TrackerOutput> {
         let mut data = MultiModalData::new();
-        for (modality, tasks) in self.pending.drain() {
-            let mut items = Vec::with_capacity(tasks.len());
-            for task in tasks {
-                let media = task.await??;
+        for (modality, entries) in self.pending.drain() {
+            let mut items: Vec<TrackedMedia> = Vec::with_capacity(entries.len());
+            for entry in entries {
+                let media = match entry {
+                    PendingMedia::Task(task) => task.await??,
+                    PendingMedia::Ready(media) => media,
+                    PendingMedia::SameAs(index) => items[index].clone(),
+                };
                 items.push(media);
             }
             data.insert(modality, items);
@@ -109,8 +141,34 @@ impl AsyncMultiModalTracker {
 
     fn enqueue_image(&mut self, source: MediaSource, detail: ImageDetail, uuid: Option<String>) {
         let modality = Modality::Image;
+        let cache_key = ImageCacheKey::for_source(&source, detail);
+        let identity = match (&uuid, cache_key, &source) {
+            (Some(uuid), _, _) => Some(ImageIdentity::Uuid(uuid.clone(), detail)),
+            (None, Some(key), _) => Some(ImageIdentity::Content(key)),
+            (None, None, MediaSource::Url(url)) => Some(ImageIdentity::Url(url.clone(), detail)),
+            (None, None, _) => None,
+        };
         self.uuids.entry(modality).or_default().push(uuid);
 
+        let pending = self.pending.entry(modality).or_default();
+        if let Some(identity) = identity {
+            if let Some(&index) = self.seen_images.get(&identity) {
+                pending.push(PendingMedia::SameAs(index));
+                return;
+            }
+            self.seen_images.insert(identity, pending.len());
+        }
+
+        let cache = self.media_cache.clone().zip(cache_key);
+        if let Some((cache, key)) = &cache {
+            let cached = cache.get(key);
+            metrics::record_media_cache(cached.is_some());
+            if let Some(frame) = cached {
+                pending.push(PendingMedia::Ready(TrackedMedia::Image(frame)));
+                return;
+            }
+        }
+
         let connector = Arc::clone(&self.media_connector);
         #[expect(
             clippy::disallowed_methods,
@@ -120,10 +178,13 @@ impl AsyncMultiModalTracker {
             let frame = connector
                 .fetch_image(source, ImageFetchConfig { detail })
                 .await?;
+            if let Some((cache, key)) = cache {
+                cache.insert(key, frame.clone());
+            }
             Ok(TrackedMedia::Image(frame))
         });
 
-        self.pending.entry(modality).or_default().push(handle);
+        pending.push(PendingMedia::Task(handle));
     }
 
     fn enqueue_video(&mut self, source: MediaSource, uuid: Option<String>) {
@@ -142,7 +203,10 @@ impl AsyncMultiModalTracker {
             Ok(TrackedMedia::Video(clip))
         });
 
-        self.pending.entry(modality).or_default().push(handle);
+        self.pending
+            .entry(modality)
+            .or_default()
+            .push(PendingMedia::Task(handle));
     }
 
     fn enqueue_audio(&mut self, source: MediaSource, uuid: Option<String>) {
@@ -159,6 +223,9 @@ impl AsyncMultiModalTracker {
             Ok(TrackedMedia::Audio(clip))
         });
 
-        self.pending.entry(modality).or_default().push(handle);
+        self.pending
+            .entry(modality)
+            .or_default()
+            .push(PendingMedia::Task(handle));
     }
 }
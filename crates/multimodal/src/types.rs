@@ -28,7 +28,7 @@ impl fmt::Display for Modality {
 }
 
 /// Detail level passed by OpenAI style APIs.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ImageDetail {
     #[default]
@@ -0,0 +1,168 @@
+//! OpenAI-style image `detail` handling.
+//!
+//! `detail: low` downscales an image so its longest edge fits the model's
+//! low-detail size ([`VisionPreProcessor::low_detail_size`]) before
+//! preprocessing, which bounds the number of tiles/patches it produces.
+//! `detail: auto` picks low detail for images that are at most
+//! [`AUTO_LOW_DETAIL_FACTOR`] times that size and high detail otherwise.
+//! `detail: high` leaves the image untouched.
+
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+
+use super::{preprocessor_config::PreProcessorConfig, processor::VisionPreProcessor, transforms};
+use crate::types::{ImageDetail, ImageFrame};
+
+/// `auto` resolves to low detail when the longest source edge is at most
+/// this multiple of the low-detail size.
+pub const AUTO_LOW_DETAIL_FACTOR: u32 = 2;
+
+/// Resolve `detail` for an image of the given dimensions to `Low` or `High`.
+pub fn resolve_detail(detail: ImageDetail, width: u32, height: u32, low_size: u32) -> ImageDetail {
+    if detail != ImageDetail::Auto {
+        return detail;
+    }
+    if width.max(height) <= low_size.saturating_mul(AUTO_LOW_DETAIL_FACTOR) {
+        ImageDetail::Low
+    } else {
+        ImageDetail::High
+    }
+}
+
+/// Dimensions of an image downscaled so its longest edge is `low_size`,
+/// preserving aspect ratio. Returns `None` when the image already fits.
+fn low_detail_dimensions(width: u32, height: u32, low_size: u32) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if low_size == 0 || longest <= low_size {
+        return None;
+    }
+    let scale = f64::from(low_size) / f64::from(longest);
+    let scaled = |edge: u32| ((f64::from(edge) * scale).round() as u32).clamp(1, low_size);
+    Some((scaled(width), scaled(height)))
+}
+
+/// Apply `detail` to `image`, downscaling it when it resolves to low detail.
+///
+/// `low_size` of `None` means the model has no low-detail size and the
+/// image is returned unchanged.
+pub fn apply_detail(
+    image: &DynamicImage,
+    detail: ImageDetail,
+    low_size: Option<u32>,
+) -> DynamicImage {
+    let Some(low_size) = low_size else {
+        return image.clone();
+    };
+    let (width, height) = image.dimensions();
+    if resolve_detail(detail, width, height, low_size) != ImageDetail::Low {
+        return image.clone();
+    }
+    match low_detail_dimensions(width, height, low_size) {
+        Some((w, h)) => transforms::resize(image, w, h, FilterType::Triangle),
+        None => image.clone(),
+    }
+}
+
+/// Images of `frames` with each frame's requested detail applied, ready to
+/// pass to [`VisionPreProcessor::preprocess`].
+pub fn images_for_preprocessing(
+    processor: &dyn VisionPreProcessor,
+    frames: &[impl AsRef<ImageFrame>],
+    config: &PreProcessorConfig,
+) -> Vec<DynamicImage> {
+    let low_size = processor.low_detail_size(config);
+    frames
+        .iter()
+        .map(|frame| {
+            let frame = frame.as_ref();
+            apply_detail(&frame.image, frame.detail, low_size)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use image::RgbImage;
+
+    use super::*;
+    use crate::{
+        types::ImageSource,
+        vision::processors::{Llama4VisionProcessor, LlavaProcessor},
+    };
+
+    fn image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::from(RgbImage::new(width, height))
+    }
+
+    #[test]
+    fn test_resolve_detail() {
+        assert_eq!(
+            resolve_detail(ImageDetail::Auto, 600, 400, 336),
+            ImageDetail::Low
+        );
+        assert_eq!(
+            resolve_detail(ImageDetail::Auto, 1344, 200, 336),
+            ImageDetail::High
+        );
+        assert_eq!(
+            resolve_detail(ImageDetail::High, 100, 100, 336),
+            ImageDetail::High
+        );
+        assert_eq!(
+            resolve_detail(ImageDetail::Low, 4000, 4000, 336),
+            ImageDetail::Low
+        );
+    }
+
+    #[test]
+    fn test_low_detail_preserves_aspect_ratio() {
+        let out = apply_detail(&image(1000, 500), ImageDetail::Low, Some(336));
+        assert_eq!(out.dimensions(), (336, 168));
+
+        let out = apply_detail(&image(10, 5000), ImageDetail::Low, Some(336));
+        assert_eq!(out.dimensions(), (1, 336));
+    }
+
+    #[test]
+    fn test_detail_leaves_image_unchanged() {
+        // Already within the low-detail size
+        let out = apply_detail(&image(200, 100), ImageDetail::Low, Some(336));
+        assert_eq!(out.dimensions(), (200, 100));
+        // High detail, or auto on a large image
+        let out = apply_detail(&image(2000, 1000), ImageDetail::High, Some(336));
+        assert_eq!(out.dimensions(), (2000, 1000));
+        let out = apply_detail(&image(2000, 1000), ImageDetail::Auto, Some(336));
+        assert_eq!(out.dimensions(), (2000, 1000));
+        // No low-detail size for this model
+        let out = apply_detail(&image(2000, 1000), ImageDetail::Low, None);
+        assert_eq!(out.dimensions(), (2000, 1000));
+    }
+
+    #[test]
+    fn test_images_for_preprocessing_uses_processor_low_size() {
+        let frames: Vec<Arc<ImageFrame>> = [ImageDetail::Low, ImageDetail::High]
+            .into_iter()
+            .map(|detail| {
+                Arc::new(ImageFrame::new(
+                    image(1344, 672),
+                    bytes::Bytes::new(),
+                    detail,
+                    ImageSource::InlineBytes,
+                    String::new(),
+                ))
+            })
+            .collect();
+        let config = PreProcessorConfig::default();
+
+        let tiled = Llama4VisionProcessor::new();
+        let images = images_for_preprocessing(&tiled, &frames, &config);
+        assert_eq!(images[0].dimensions(), (336, 168));
+        assert_eq!(images[1].dimensions(), (1344, 672));
+
+        // Fixed-size models ignore the requested detail
+        let fixed = LlavaProcessor::new();
+        let images = images_for_preprocessing(&fixed, &frames, &config);
+        assert_eq!(images[0].dimensions(), (1344, 672));
+    }
+}
@@ -11,6 +11,7 @@
 //! - `preprocessor_config`: HuggingFace config parsing
 //! - `processor`: Vision processor trait and registry
 //! - `processors`: Model-specific implementations (LLaVA, Qwen-VL, etc.)
+//! - `detail`: OpenAI-style `detail` handling (low-detail downscaling)
 //!
 //! Modality-neutral encoder outputs live in [`crate::encoder_inputs`], while
 //! shared errors live in [`crate::error`].
@@ -32,6 +33,7 @@
 //! let result = processor.preprocess(&images, &config)?;
 //! ```
 
+pub mod detail;
 pub(crate) mod execution;
 pub mod preprocessor_config;
 pub mod processor;
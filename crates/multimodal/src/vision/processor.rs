@@ -139,6 +139,16 @@ pub trait VisionPreProcessor: Send + Sync {
     fn get_processed_size(&self, config: &PreProcessorConfig) -> Option<(u32, u32)> {
         config.get_target_size()
     }
+
+    /// Longest edge images are downscaled to for `detail: low`.
+    ///
+    /// Models whose token count grows with input resolution (tiling or
+    /// dynamic-resolution processors) override this with their low-res
+    /// tile size. `None`, the default for fixed-size models, ignores the
+    /// requested detail.
+    fn low_detail_size(&self, _config: &PreProcessorConfig) -> Option<u32> {
+        None
+    }
 }
 
 /// Registry of available vision processors.
@@ -531,6 +531,11 @@ impl VisionPreProcessor for Llama4VisionProcessor {
         let _ = config;
         None
     }
+
+    fn low_detail_size(&self, _config: &PreProcessorConfig) -> Option<u32> {
+        // A single tile
+        Some(self.tile_size)
+    }
 }
 
 #[cfg(test)]
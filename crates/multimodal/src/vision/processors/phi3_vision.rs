@@ -399,6 +399,11 @@ impl VisionPreProcessor for Phi3VisionProcessor {
         // Phi3-Vision has dynamic size based on HD transform
         None
     }
+
+    fn low_detail_size(&self, _config: &PreProcessorConfig) -> Option<u32> {
+        // A single HD crop
+        Some(TILE_SIZE)
+    }
 }
 
 #[cfg(test)]
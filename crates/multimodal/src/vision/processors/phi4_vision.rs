@@ -584,6 +584,11 @@ impl VisionPreProcessor for Phi4VisionProcessor {
         let _ = config;
         None
     }
+
+    fn low_detail_size(&self, _config: &PreProcessorConfig) -> Option<u32> {
+        // A single crop at the base resolution
+        Some(BASE_RESOLUTION)
+    }
 }
 
 #[cfg(test)]
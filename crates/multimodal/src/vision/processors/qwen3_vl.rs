@@ -329,6 +329,10 @@ impl VisionPreProcessor for Qwen3VLProcessor {
     fn get_processed_size(&self, config: &PreProcessorConfig) -> Option<(u32, u32)> {
         self.inner.get_processed_size(config)
     }
+
+    fn low_detail_size(&self, config: &PreProcessorConfig) -> Option<u32> {
+        self.inner.low_detail_size(config)
+    }
 }
 
 #[cfg(test)]
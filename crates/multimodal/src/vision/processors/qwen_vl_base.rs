@@ -57,6 +57,10 @@ fn round_half_to_even(x: f64) -> f64 {
     rounded
 }
 
+/// Merged-token grid along the longest edge for `detail: low` images
+/// (16x16 = 256 tokens for a square image).
+const LOW_DETAIL_GRID: usize = 16;
+
 /// Configuration for a Qwen VL processor variant.
 #[derive(Debug, Clone)]
 pub struct QwenVLConfig {
@@ -1377,6 +1381,10 @@ impl VisionPreProcessor for QwenVLProcessorBase {
         // Qwen VL models have dynamic sizing, no fixed output size
         None
     }
+
+    fn low_detail_size(&self, _config: &PreProcessorConfig) -> Option<u32> {
+        Some((self.get_factor() * LOW_DETAIL_GRID) as u32)
+    }
 }
 
 #[cfg(test)]
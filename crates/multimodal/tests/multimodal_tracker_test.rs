@@ -2,8 +2,9 @@ use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use llm_multimodal::{
-    AsyncMultiModalTracker, AudioSource, ImageFetchConfig, ImageSource, MediaConnector,
-    MediaConnectorConfig, MediaContentPart, MediaSource, Modality,
+    AsyncMultiModalTracker, AudioSource, ImageFetchConfig, ImageFrame, ImageSource, MediaCache,
+    MediaConnector, MediaConnectorConfig, MediaContentPart, MediaSource, Modality, TrackedMedia,
+    TrackerOutput,
 };
 use reqwest::Client;
 use tempfile::tempdir;
@@ -58,6 +59,7 @@ fn test_connector(allowed_path: Option<PathBuf>) -> MediaConnector {
             allowed_domains: None,
             allowed_local_media_path: allowed_path,
             fetch_timeout: Duration::from_secs(5),
+            ..Default::default()
         },
     )
     .expect("media connector")
@@ -169,3 +171,59 @@ async fn tracker_fetches_images_and_records_uuids() {
     let uuids = output.uuids.get(&Modality::Image).expect("uuid entry");
     assert_eq!(uuids, &vec![Some("img-1".into())]);
 }
+
+fn image_part(uuid: Option<&str>) -> MediaContentPart {
+    MediaContentPart::ImageUrl {
+        url: format!("data:image/png;base64,{TINY_PNG_BASE64}"),
+        detail: None,
+        uuid: uuid.map(str::to_string),
+    }
+}
+
+#[expect(
+    clippy::expect_used,
+    reason = "test helper: panic on failure is intentional"
+)]
+fn tracked_images(output: &TrackerOutput) -> Vec<Arc<ImageFrame>> {
+    output
+        .data
+        .get(&Modality::Image)
+        .expect("image entry")
+        .iter()
+        .filter_map(|media| match media {
+            TrackedMedia::Image(frame) => Some(frame.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn tracker_deduplicates_repeated_images_within_request() {
+    let connector = Arc::new(test_connector(None));
+    let mut tracker = AsyncMultiModalTracker::new(connector);
+    tracker.push_part(image_part(Some("img-1"))).expect("image");
+    tracker.push_part(image_part(None)).expect("image");
+    tracker.push_part(image_part(Some("img-1"))).expect("image");
+
+    let output = tracker.finalize().await.expect("tracker finalize");
+    let images = tracked_images(&output);
+    assert_eq!(images.len(), 3);
+    assert!(Arc::ptr_eq(&images[0], &images[2]));
+    assert_eq!(images[0].hash, images[1].hash);
+}
+
+#[tokio::test]
+async fn tracker_reuses_cached_images_across_requests() {
+    let connector = Arc::new(test_connector(None));
+    let cache = Arc::new(MediaCache::new(1024 * 1024));
+
+    let mut first = AsyncMultiModalTracker::new(connector.clone()).with_media_cache(cache.clone());
+    first.push_part(image_part(None)).expect("image");
+    let first = tracked_images(&first.finalize().await.expect("first turn"));
+    assert_eq!(cache.len(), 1);
+
+    let mut second = AsyncMultiModalTracker::new(connector).with_media_cache(cache.clone());
+    second.push_part(image_part(None)).expect("image");
+    let second = tracked_images(&second.finalize().await.expect("second turn"));
+    assert!(Arc::ptr_eq(&first[0], &second[0]));
+}
@@ -1,7 +1,9 @@
-//! Builders for Chat Completion API response types
+//! Builders for Chat Completion API request and response types
 
+pub mod request;
 pub mod response;
 pub mod stream_response;
 
+pub use request::ChatCompletionRequestBuilder;
 pub use response::ChatCompletionResponseBuilder;
 pub use stream_response::ChatCompletionStreamResponseBuilder;
@@ -0,0 +1,328 @@
+//! Builder for ChatCompletionRequest
+//!
+//! Provides an ergonomic fluent API for constructing chat completion requests,
+//! normalized and validated the same way as requests arriving over HTTP.
+
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+    chat::*,
+    common::{ResponseFormat, StreamOptions, StringOrArray, Tool, ToolChoice},
+    validated::Normalizable,
+};
+
+/// Builder for ChatCompletionRequest
+///
+/// Fields not covered by a setter keep their defaults.
+#[must_use = "Builder does nothing until .build() is called"]
+#[derive(Clone, Debug)]
+pub struct ChatCompletionRequestBuilder {
+    request: ChatCompletionRequest,
+}
+
+impl ChatCompletionRequestBuilder {
+    /// Create a new builder for `model` with no messages
+    ///
+    /// Boolean flags start from their wire defaults (e.g. `skip_special_tokens`
+    /// is on), matching a deserialized request rather than `Default`.
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            request: ChatCompletionRequest {
+                model: model.into(),
+                skip_special_tokens: true,
+                separate_reasoning: true,
+                stream_reasoning: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Set the messages
+    pub fn messages(mut self, messages: Vec<ChatMessage>) -> Self {
+        self.request.messages = messages;
+        self
+    }
+
+    /// Add a single message
+    pub fn message(mut self, message: ChatMessage) -> Self {
+        self.request.messages.push(message);
+        self
+    }
+
+    /// Add a system message with text content
+    pub fn system(self, text: impl Into<String>) -> Self {
+        self.message(ChatMessage::System {
+            content: MessageContent::Text(text.into()),
+            name: None,
+        })
+    }
+
+    /// Add a user message with text content
+    pub fn user(self, text: impl Into<String>) -> Self {
+        self.message(ChatMessage::User {
+            content: MessageContent::Text(text.into()),
+            name: None,
+        })
+    }
+
+    /// Set the sampling temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the sampling temperature if provided (handles Option)
+    pub fn maybe_temperature(mut self, temperature: Option<f32>) -> Self {
+        if temperature.is_some() {
+            self.request.temperature = temperature;
+        }
+        self
+    }
+
+    /// Set nucleus sampling `top_p`
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.request.top_p = Some(top_p);
+        self
+    }
+
+    /// Set nucleus sampling `top_p` if provided (handles Option)
+    pub fn maybe_top_p(mut self, top_p: Option<f32>) -> Self {
+        if top_p.is_some() {
+            self.request.top_p = top_p;
+        }
+        self
+    }
+
+    /// Set the upper bound on generated tokens
+    pub fn max_completion_tokens(mut self, max_tokens: u32) -> Self {
+        self.request.max_completion_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set the upper bound on generated tokens if provided (handles Option)
+    pub fn maybe_max_completion_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        if max_tokens.is_some() {
+            self.request.max_completion_tokens = max_tokens;
+        }
+        self
+    }
+
+    /// Set the number of choices to generate
+    pub fn n(mut self, n: u32) -> Self {
+        self.request.n = Some(n);
+        self
+    }
+
+    /// Set the stop sequences
+    pub fn stop(mut self, stop: StringOrArray) -> Self {
+        self.request.stop = Some(stop);
+        self
+    }
+
+    /// Set the frequency penalty
+    pub fn frequency_penalty(mut self, penalty: f32) -> Self {
+        self.request.frequency_penalty = Some(penalty);
+        self
+    }
+
+    /// Set the presence penalty
+    pub fn presence_penalty(mut self, penalty: f32) -> Self {
+        self.request.presence_penalty = Some(penalty);
+        self
+    }
+
+    /// Request log probabilities, with `top_logprobs` alternatives per token
+    pub fn logprobs(mut self, top_logprobs: Option<u32>) -> Self {
+        self.request.logprobs = true;
+        self.request.top_logprobs = top_logprobs;
+        self
+    }
+
+    /// Set the number of top log probabilities if provided (handles Option).
+    /// Only valid once log probabilities are enabled.
+    pub fn maybe_top_logprobs(mut self, top_logprobs: Option<u32>) -> Self {
+        if top_logprobs.is_some() {
+            self.request.top_logprobs = top_logprobs;
+        }
+        self
+    }
+
+    /// Set the sampling seed if provided (handles Option)
+    #[expect(
+        deprecated,
+        reason = "seed is Legacy in OpenAI but still the portable way to request determinism"
+    )]
+    pub fn maybe_seed(mut self, seed: Option<i64>) -> Self {
+        if seed.is_some() {
+            self.request.seed = seed;
+        }
+        self
+    }
+
+    /// Enable or disable streaming
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.request.stream = stream;
+        self
+    }
+
+    /// Set streaming options (only valid when streaming)
+    pub fn stream_options(mut self, options: StreamOptions) -> Self {
+        self.request.stream_options = Some(options);
+        self
+    }
+
+    /// Set the tools the model may call
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.request.tools = Some(tools);
+        self
+    }
+
+    /// Set the tools if provided (handles Option)
+    pub fn maybe_tools(mut self, tools: Option<Vec<Tool>>) -> Self {
+        if tools.is_some() {
+            self.request.tools = tools;
+        }
+        self
+    }
+
+    /// Set the tool choice
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.request.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Set the tool choice if provided (handles Option)
+    pub fn maybe_tool_choice(mut self, tool_choice: Option<ToolChoice>) -> Self {
+        if tool_choice.is_some() {
+            self.request.tool_choice = tool_choice;
+        }
+        self
+    }
+
+    /// Set whether tools may be called in parallel if provided (handles Option)
+    pub fn maybe_parallel_tool_calls(mut self, parallel: Option<bool>) -> Self {
+        if parallel.is_some() {
+            self.request.parallel_tool_calls = parallel;
+        }
+        self
+    }
+
+    /// Set the response format
+    pub fn response_format(mut self, format: ResponseFormat) -> Self {
+        self.request.response_format = Some(format);
+        self
+    }
+
+    /// Set the response format if provided (handles Option)
+    pub fn maybe_response_format(mut self, format: Option<ResponseFormat>) -> Self {
+        if format.is_some() {
+            self.request.response_format = format;
+        }
+        self
+    }
+
+    /// Set the reasoning effort if provided (handles Option)
+    pub fn maybe_reasoning_effort(mut self, effort: Option<impl Into<String>>) -> Self {
+        if let Some(effort) = effort {
+            self.request.reasoning_effort = Some(effort.into());
+        }
+        self
+    }
+
+    /// Set whether special tokens are skipped during detokenization
+    pub fn skip_special_tokens(mut self, skip: bool) -> Self {
+        self.request.skip_special_tokens = skip;
+        self
+    }
+
+    /// Normalize and validate the request, as the HTTP extractor does
+    pub fn build(self) -> Result<ChatCompletionRequest, ValidationErrors> {
+        let mut request = self.request;
+        request.normalize();
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Build the request without normalization or validation, for callers
+    /// translating from a request that was already validated at its own
+    /// boundary.
+    pub fn build_unvalidated(self) -> ChatCompletionRequest {
+        self.request
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Function, ToolChoiceValue};
+
+    #[test]
+    fn test_build_minimal() {
+        let request = ChatCompletionRequest::builder("gpt-4")
+            .user("Hello")
+            .build()
+            .unwrap();
+
+        assert_eq!(request.model, "gpt-4");
+        assert_eq!(request.messages.len(), 1);
+        assert!(!request.stream);
+        assert!(request.skip_special_tokens);
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_requests() {
+        assert!(ChatCompletionRequest::builder("gpt-4").build().is_err());
+        assert!(ChatCompletionRequest::builder("gpt-4")
+            .user("Hello")
+            .temperature(3.0)
+            .build()
+            .is_err());
+        assert!(ChatCompletionRequest::builder("gpt-4")
+            .user("Hello")
+            .stream_options(StreamOptions::default())
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_normalizes_tool_choice() {
+        let tool = Tool {
+            tool_type: "function".to_string(),
+            function: Function {
+                name: "get_weather".to_string(),
+                description: None,
+                parameters: serde_json::json!({"type": "object"}),
+                strict: None,
+            },
+        };
+        let request = ChatCompletionRequest::builder("gpt-4")
+            .system("Be brief")
+            .user("Weather?")
+            .tools(vec![tool])
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            request.tool_choice,
+            Some(ToolChoice::Value(ToolChoiceValue::Auto))
+        ));
+    }
+
+    #[test]
+    fn test_maybe_setters_keep_unset_fields() {
+        let request = ChatCompletionRequest::builder("gpt-4")
+            .user("Hello")
+            .maybe_temperature(None)
+            .maybe_top_p(Some(0.5))
+            .maybe_max_completion_tokens(Some(16))
+            .build_unvalidated();
+
+        assert!(request.temperature.is_none());
+        assert_eq!(request.top_p, Some(0.5));
+        assert_eq!(request.max_completion_tokens, Some(16));
+    }
+}
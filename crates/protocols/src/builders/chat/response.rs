@@ -17,6 +17,7 @@ pub struct ChatCompletionResponseBuilder {
     choices: Vec<ChatChoice>,
     usage: Option<Usage>,
     system_fingerprint: Option<String>,
+    seed: Option<i64>,
 }
 
 impl ChatCompletionResponseBuilder {
@@ -34,14 +35,16 @@ impl ChatCompletionResponseBuilder {
             choices: Vec::new(),
             usage: None,
             system_fingerprint: None,
+            seed: None,
         }
     }
 
     /// Copy common fields from a ChatCompletionRequest
     ///
-    /// This populates the model field from the request.
+    /// This populates the model and effective seed from the request.
     pub fn copy_from_request(mut self, request: &ChatCompletionRequest) -> Self {
         self.model.clone_from(&request.model);
+        self.seed = request.effective_seed();
         self
     }
 
@@ -91,6 +94,14 @@ impl ChatCompletionResponseBuilder {
         self
     }
 
+    /// Set the sampling seed if provided (handles Option)
+    pub fn maybe_seed(mut self, seed: Option<i64>) -> Self {
+        if let Some(seed) = seed {
+            self.seed = Some(seed);
+        }
+        self
+    }
+
     /// Build the ChatCompletionResponse
     pub fn build(self) -> ChatCompletionResponse {
         ChatCompletionResponse {
@@ -101,6 +112,7 @@ impl ChatCompletionResponseBuilder {
             choices: self.choices,
             usage: self.usage,
             system_fingerprint: self.system_fingerprint,
+            seed: self.seed,
         }
     }
 }
@@ -123,6 +135,10 @@ mod tests {
         assert!(response.choices.is_empty());
         assert!(response.usage.is_none());
         assert!(response.system_fingerprint.is_none());
+        assert!(response.seed.is_none());
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("seed").is_none());
     }
 
     #[test]
@@ -147,6 +163,7 @@ mod tests {
             total_tokens: 30,
             prompt_tokens_details: None,
             completion_tokens_details: None,
+            smg: None,
         };
 
         let response = ChatCompletionResponse::builder("chatcmpl_456", "gpt-4")
@@ -207,6 +224,7 @@ mod tests {
         let request = ChatCompletionRequest {
             messages: vec![],
             model: "gpt-3.5-turbo".to_string(),
+            sampling_seed: Some(42),
             ..Default::default()
         };
 
@@ -215,5 +233,6 @@ mod tests {
             .build();
 
         assert_eq!(response.model, "gpt-3.5-turbo"); // Copied from request
+        assert_eq!(response.seed, Some(42)); // Effective seed surfaced
     }
 }
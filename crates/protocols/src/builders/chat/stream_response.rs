@@ -369,6 +369,7 @@ mod tests {
             total_tokens: 30,
             prompt_tokens_details: None,
             completion_tokens_details: None,
+            smg: None,
         };
 
         let chunk = ChatCompletionStreamResponseBuilder::new("chatcmpl_303", "gpt-4")
@@ -0,0 +1,5 @@
+//! Builders for Completions API request types
+
+pub mod request;
+
+pub use request::CompletionRequestBuilder;
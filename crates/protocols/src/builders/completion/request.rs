@@ -0,0 +1,239 @@
+//! Builder for CompletionRequest
+//!
+//! Provides an ergonomic fluent API for constructing completion requests,
+//! normalized and validated the same way as requests arriving over HTTP.
+
+use serde_json::Map;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+    common::{StreamOptions, StringOrArray},
+    completion::CompletionRequest,
+    validated::Normalizable,
+};
+
+/// Builder for CompletionRequest
+///
+/// Fields not covered by a setter keep their wire defaults.
+#[must_use = "Builder does nothing until .build() is called"]
+#[derive(Clone, Debug)]
+pub struct CompletionRequestBuilder {
+    request: CompletionRequest,
+}
+
+impl CompletionRequestBuilder {
+    /// Create a new builder with required fields
+    ///
+    /// # Arguments
+    /// - `model`: Model to generate with
+    /// - `prompt`: A single prompt or a batch of prompts
+    pub fn new(model: impl Into<String>, prompt: StringOrArray) -> Self {
+        Self {
+            request: CompletionRequest {
+                model: model.into(),
+                prompt,
+                best_of: None,
+                echo: false,
+                frequency_penalty: None,
+                logit_bias: None,
+                logprobs: None,
+                max_tokens: None,
+                n: None,
+                presence_penalty: None,
+                seed: None,
+                stop: None,
+                stream: false,
+                stream_options: None,
+                suffix: None,
+                temperature: None,
+                top_p: None,
+                user: None,
+                top_k: None,
+                min_p: None,
+                min_tokens: None,
+                repetition_penalty: None,
+                regex: None,
+                ebnf: None,
+                json_schema: None,
+                stop_token_ids: None,
+                no_stop_trim: false,
+                ignore_eos: false,
+                skip_special_tokens: true,
+                lora_path: None,
+                session_params: None,
+                return_hidden_states: false,
+                sampling_seed: None,
+                rid: None,
+                other: Map::new(),
+            },
+        }
+    }
+
+    /// Set the maximum number of tokens to generate
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.request.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set the maximum number of tokens to generate if provided (handles Option)
+    pub fn maybe_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        if max_tokens.is_some() {
+            self.request.max_tokens = max_tokens;
+        }
+        self
+    }
+
+    /// Set the sampling temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the sampling temperature if provided (handles Option)
+    pub fn maybe_temperature(mut self, temperature: Option<f32>) -> Self {
+        if temperature.is_some() {
+            self.request.temperature = temperature;
+        }
+        self
+    }
+
+    /// Set nucleus sampling `top_p`
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.request.top_p = Some(top_p);
+        self
+    }
+
+    /// Set nucleus sampling `top_p` if provided (handles Option)
+    pub fn maybe_top_p(mut self, top_p: Option<f32>) -> Self {
+        if top_p.is_some() {
+            self.request.top_p = top_p;
+        }
+        self
+    }
+
+    /// Set the number of completions per prompt
+    pub fn n(mut self, n: u32) -> Self {
+        self.request.n = Some(n);
+        self
+    }
+
+    /// Set the stop sequences
+    pub fn stop(mut self, stop: StringOrArray) -> Self {
+        self.request.stop = Some(stop);
+        self
+    }
+
+    /// Set the stop sequences if provided (handles Option)
+    pub fn maybe_stop(mut self, stop: Option<StringOrArray>) -> Self {
+        if stop.is_some() {
+            self.request.stop = stop;
+        }
+        self
+    }
+
+    /// Set the sampling seed
+    pub fn seed(mut self, seed: i64) -> Self {
+        self.request.seed = Some(seed);
+        self
+    }
+
+    /// Set the number of log probabilities to return per token
+    pub fn logprobs(mut self, logprobs: u32) -> Self {
+        self.request.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Echo the prompt back in the completion
+    pub fn echo(mut self, echo: bool) -> Self {
+        self.request.echo = echo;
+        self
+    }
+
+    /// Set the suffix that follows the inserted completion
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.request.suffix = Some(suffix.into());
+        self
+    }
+
+    /// Enable or disable streaming
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.request.stream = stream;
+        self
+    }
+
+    /// Set streaming options (only valid when streaming)
+    pub fn stream_options(mut self, options: StreamOptions) -> Self {
+        self.request.stream_options = Some(options);
+        self
+    }
+
+    /// Set the end-user identifier
+    pub fn user(mut self, user: impl Into<String>) -> Self {
+        self.request.user = Some(user.into());
+        self
+    }
+
+    /// Normalize and validate the request, as the HTTP extractor does
+    pub fn build(self) -> Result<CompletionRequest, ValidationErrors> {
+        let mut request = self.request;
+        request.normalize();
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Build the request without normalization or validation, for callers
+    /// translating from a request that was already validated at its own
+    /// boundary.
+    pub fn build_unvalidated(self) -> CompletionRequest {
+        self.request
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(text: &str) -> StringOrArray {
+        StringOrArray::String(text.to_string())
+    }
+
+    #[test]
+    fn test_build_matches_deserialized_defaults() {
+        let built = CompletionRequest::builder("llama", prompt("Once upon"))
+            .max_tokens(16)
+            .build()
+            .unwrap();
+        let parsed: CompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "llama",
+            "prompt": "Once upon",
+            "max_tokens": 16
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&parsed).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_requests() {
+        assert!(
+            CompletionRequest::builder("llama", StringOrArray::Array(vec![]))
+                .build()
+                .is_err()
+        );
+        assert!(CompletionRequest::builder("llama", prompt("hi"))
+            .n(200)
+            .build()
+            .is_err());
+        assert!(CompletionRequest::builder("llama", prompt("hi"))
+            .stream_options(StreamOptions::default())
+            .build()
+            .is_err());
+    }
+}
@@ -1,14 +1,17 @@
-//! Builder patterns for protocol response types
+//! Builder patterns for protocol request and response types
 //!
-//! This module provides ergonomic builders for response types with many optional fields.
+//! This module provides ergonomic builders for types with many optional fields.
 //! Builders help avoid telescoping constructors and make construction intent clear.
+//! Request builders normalize and validate in `build()`, exactly like requests
+//! arriving over HTTP, and offer `build_unvalidated()` for internal translation.
 //!
 //! # Organization
 //!
 //! Builders are organized by API:
-//! - `chat/` - Chat Completion API builders (response, stream_response)
+//! - `chat/` - Chat Completion API builders (request, response, stream_response)
+//! - `completion/` - Completions API builder (request)
 //! - `realtime/` - Realtime API builders (response, server_event, client_event)
-//! - `responses/` - Responses API builder (response)
+//! - `responses/` - Responses API builders (request, response)
 //!
 //! # Optional Fields
 //!
@@ -21,10 +24,15 @@
 //! ```
 
 pub mod chat;
+pub mod completion;
 pub mod realtime;
 pub mod responses;
 
 // Re-export all builders for convenient access
-pub use chat::{ChatCompletionResponseBuilder, ChatCompletionStreamResponseBuilder};
+pub use chat::{
+    ChatCompletionRequestBuilder, ChatCompletionResponseBuilder,
+    ChatCompletionStreamResponseBuilder,
+};
+pub use completion::CompletionRequestBuilder;
 pub use realtime::RealtimeResponseBuilder;
-pub use responses::ResponsesResponseBuilder;
+pub use responses::{ResponsesRequestBuilder, ResponsesResponseBuilder};
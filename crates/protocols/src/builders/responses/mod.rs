@@ -1,5 +1,7 @@
-//! Builders for Responses API response types
+//! Builders for Responses API request and response types
 
+pub mod request;
 pub mod response;
 
+pub use request::ResponsesRequestBuilder;
 pub use response::ResponsesResponseBuilder;
@@ -0,0 +1,180 @@
+//! Builder for ResponsesRequest
+//!
+//! Provides an ergonomic fluent API for constructing Responses API requests,
+//! normalized and validated the same way as requests arriving over HTTP.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use validator::{Validate, ValidationErrors};
+
+use crate::{common::ConversationRef, responses::*, validated::Normalizable};
+
+/// Builder for ResponsesRequest
+///
+/// Fields not covered by a setter keep their `Default` values.
+#[must_use = "Builder does nothing until .build() is called"]
+#[derive(Clone, Debug)]
+pub struct ResponsesRequestBuilder {
+    request: ResponsesRequest,
+}
+
+impl ResponsesRequestBuilder {
+    /// Create a new builder with required fields
+    ///
+    /// # Arguments
+    /// - `model`: Model to respond with
+    /// - `input`: Text or structured input items
+    pub fn new(model: impl Into<String>, input: ResponseInput) -> Self {
+        Self {
+            request: ResponsesRequest {
+                model: model.into(),
+                input,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Set the system instructions
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.request.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Set the system instructions if provided (handles Option)
+    pub fn maybe_instructions(mut self, instructions: Option<impl Into<String>>) -> Self {
+        if let Some(instructions) = instructions {
+            self.request.instructions = Some(instructions.into());
+        }
+        self
+    }
+
+    /// Set the maximum number of output tokens
+    pub fn max_output_tokens(mut self, max_tokens: u32) -> Self {
+        self.request.max_output_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Set the sampling temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Set nucleus sampling `top_p`
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.request.top_p = Some(top_p);
+        self
+    }
+
+    /// Set the available tools
+    pub fn tools(mut self, tools: Vec<ResponseTool>) -> Self {
+        self.request.tools = Some(tools);
+        self
+    }
+
+    /// Set the tool choice
+    pub fn tool_choice(mut self, tool_choice: ResponsesToolChoice) -> Self {
+        self.request.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Continue from a previous response
+    pub fn previous_response_id(mut self, id: impl Into<String>) -> Self {
+        self.request.previous_response_id = Some(id.into());
+        self
+    }
+
+    /// Persist input and output to a conversation
+    pub fn conversation(mut self, id: impl Into<String>) -> Self {
+        self.request.conversation = Some(ConversationRef::Id(id.into()));
+        self
+    }
+
+    /// Set the reasoning configuration
+    pub fn reasoning(mut self, reasoning: ResponseReasoningParam) -> Self {
+        self.request.reasoning = Some(reasoning);
+        self
+    }
+
+    /// Set the text output configuration
+    pub fn text(mut self, text: TextConfig) -> Self {
+        self.request.text = Some(text);
+        self
+    }
+
+    /// Set whether the response is stored (default after build: true)
+    pub fn store(mut self, store: bool) -> Self {
+        self.request.store = Some(store);
+        self
+    }
+
+    /// Enable or disable streaming
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.request.stream = Some(stream);
+        self
+    }
+
+    /// Set the request metadata
+    pub fn metadata(mut self, metadata: HashMap<String, Value>) -> Self {
+        self.request.metadata = Some(metadata);
+        self
+    }
+
+    /// Normalize and validate the request, as the HTTP extractor does
+    pub fn build(self) -> Result<ResponsesRequest, ValidationErrors> {
+        let mut request = self.request;
+        request.normalize();
+        request.validate()?;
+        Ok(request)
+    }
+
+    /// Build the request without normalization or validation, for callers
+    /// translating from a request that was already validated at its own
+    /// boundary.
+    pub fn build_unvalidated(self) -> ResponsesRequest {
+        self.request
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_input(text: &str) -> ResponseInput {
+        ResponseInput::Text(text.to_string())
+    }
+
+    #[test]
+    fn test_build_applies_normalization() {
+        let request = ResponsesRequest::builder("gpt-4o", text_input("Hi"))
+            .instructions("Be brief")
+            .max_output_tokens(64)
+            .build()
+            .unwrap();
+
+        assert_eq!(request.model, "gpt-4o");
+        assert_eq!(request.instructions.as_deref(), Some("Be brief"));
+        assert_eq!(request.store, Some(true));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_requests() {
+        assert!(ResponsesRequest::builder("gpt-4o", text_input(""))
+            .build()
+            .is_err());
+        assert!(ResponsesRequest::builder("gpt-4o", text_input("Hi"))
+            .conversation("conv_1")
+            .previous_response_id("resp_1")
+            .build()
+            .is_err());
+        assert!(ResponsesRequest::builder("gpt-4o", text_input("Hi"))
+            .max_output_tokens(0)
+            .build()
+            .is_err());
+    }
+}
@@ -35,6 +35,8 @@ pub struct ResponsesResponseBuilder {
     tool_choice: String,
     tools: Vec<ResponseTool>,
     top_p: Option<f32>,
+    seed: Option<i64>,
+    system_fingerprint: Option<String>,
     truncation: Option<String>,
     usage: Option<ResponsesUsage>,
     user: Option<String>,
@@ -71,6 +73,8 @@ impl ResponsesResponseBuilder {
             tool_choice: "auto".to_string(),
             tools: Vec::new(),
             top_p: None,
+            seed: None,
+            system_fingerprint: None,
             truncation: None,
             usage: None,
             user: None,
@@ -106,6 +110,7 @@ impl ResponsesResponseBuilder {
         };
         self.tools = request.tools.clone().unwrap_or_default();
         self.top_p = request.top_p;
+        self.seed = request.seed;
         self.user.clone_from(&request.user);
         self.metadata = request.metadata.clone().unwrap_or_default();
         self
@@ -240,6 +245,14 @@ impl ResponsesResponseBuilder {
         self
     }
 
+    /// Set system fingerprint if provided (handles Option)
+    pub fn maybe_system_fingerprint(mut self, fingerprint: Option<impl Into<String>>) -> Self {
+        if let Some(fp) = fingerprint {
+            self.system_fingerprint = Some(fp.into());
+        }
+        self
+    }
+
     /// Set usage information
     pub fn usage(mut self, usage: ResponsesUsage) -> Self {
         self.usage = Some(usage);
@@ -311,6 +324,8 @@ impl ResponsesResponseBuilder {
             tool_choice: self.tool_choice,
             tools: self.tools,
             top_p: self.top_p,
+            seed: self.seed,
+            system_fingerprint: self.system_fingerprint,
             truncation: self.truncation,
             usage: self.usage,
             user: self.user,
@@ -339,6 +354,11 @@ mod tests {
         assert!(response.output.is_empty());
         assert!(response.parallel_tool_calls);
         assert!(response.store);
+
+        // Replay metadata is omitted, not null, when the backend has none.
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("seed").is_none());
+        assert!(json.get("system_fingerprint").is_none());
     }
 
     #[test]
@@ -374,6 +394,7 @@ mod tests {
             max_output_tokens: Some(500),
             temperature: Some(0.8),
             top_p: Some(0.95),
+            seed: Some(42),
             parallel_tool_calls: Some(false),
             store: Some(false),
             user: Some("user_123".to_string()),
@@ -393,6 +414,7 @@ mod tests {
         assert_eq!(response.max_output_tokens, Some(500));
         assert_eq!(response.temperature, Some(0.8));
         assert_eq!(response.top_p, Some(0.95));
+        assert_eq!(response.seed, Some(42));
         assert!(!response.parallel_tool_calls);
         assert!(!response.store);
         assert_eq!(response.user.as_ref().unwrap(), "user_123");
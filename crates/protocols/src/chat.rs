@@ -14,7 +14,10 @@ use super::{
     sampling_params::{validate_top_k_value, validate_top_p_value},
 };
 use crate::{
-    builders::{ChatCompletionResponseBuilder, ChatCompletionStreamResponseBuilder},
+    builders::{
+        ChatCompletionRequestBuilder, ChatCompletionResponseBuilder,
+        ChatCompletionStreamResponseBuilder,
+    },
     validated::Normalizable,
 };
 
@@ -179,6 +182,9 @@ pub struct ChatCompletionRequest {
     /// Developer-defined tags and values used for filtering completions in the dashboard
     pub metadata: Option<HashMap<String, String>>,
 
+    /// Whether to store the output of this chat completion request for later retrieval
+    pub store: Option<bool>,
+
     /// Output types that you would like the model to generate for this request
     pub modalities: Option<Vec<String>>,
 
@@ -327,11 +333,34 @@ pub struct ChatCompletionRequest {
     /// Request ID forwarded to the backend for log correlation (SGLang extension)
     pub rid: Option<String>,
 
+    /// Split the final user message across several generations and combine
+    /// them in a reduce pass (gateway extension). Only honored for models the
+    /// gateway has opted in; never forwarded to backends.
+    pub map_reduce: Option<MapReduceOptions>,
+
     /// Additional fields not explicitly defined above (e.g. engine-specific parameters)
     #[serde(flatten)]
     pub other: Map<String, Value>,
 }
 
+/// Map-reduce orchestration for documents longer than the model's context.
+///
+/// The final user message is the document. Each chunk is sent with
+/// `map_prompt`, and the chunk outputs are combined with `reduce_prompt`.
+/// Earlier messages (e.g. the system prompt) are kept in every pass.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, schemars::JsonSchema)]
+pub struct MapReduceOptions {
+    /// Characters per chunk; the gateway default applies when unset
+    pub chunk_chars: Option<usize>,
+    /// Characters repeated at the start of each chunk from the previous one
+    pub overlap_chars: Option<usize>,
+    /// Instruction sent with each chunk
+    pub map_prompt: Option<String>,
+    /// Instruction sent with the combined chunk outputs
+    pub reduce_prompt: Option<String>,
+}
+
 /// Map an OpenAI `reasoning_effort` to a thinking on/off preference.
 ///
 /// This is the protocol-level interpretation of "does the caller want
@@ -626,6 +655,24 @@ impl Normalizable for ChatCompletionRequest {
     }
 }
 
+impl ChatCompletionRequest {
+    /// Create a new builder for ChatCompletionRequest
+    pub fn builder(model: impl Into<String>) -> ChatCompletionRequestBuilder {
+        ChatCompletionRequestBuilder::new(model)
+    }
+
+    /// Seed the backend should sample with: the OpenAI `seed` when set,
+    /// otherwise the `sampling_seed` extension (dropped if it overflows i64).
+    #[expect(
+        deprecated,
+        reason = "seed is Legacy in OpenAI but still the portable way to request determinism"
+    )]
+    pub fn effective_seed(&self) -> Option<i64> {
+        self.seed
+            .or_else(|| self.sampling_seed.and_then(|s| i64::try_from(s).ok()))
+    }
+}
+
 // ============================================================================
 // GenerationRequest Trait Implementation
 // ============================================================================
@@ -713,6 +760,10 @@ pub struct ChatCompletionResponse {
     pub choices: Vec<ChatChoice>,
     pub usage: Option<Usage>,
     pub system_fingerprint: Option<String>,
+    /// Seed the backend sampled with (SGLang extension); pair with
+    /// `system_fingerprint` to replay a generation
+    #[serde(default)]
+    pub seed: Option<i64>,
 }
 
 impl ChatCompletionResponse {
@@ -526,6 +526,9 @@ pub struct Usage {
     pub total_tokens: u32,
     pub prompt_tokens_details: Option<PromptTokenUsageInfo>,
     pub completion_tokens_details: Option<CompletionTokensDetails>,
+    /// SMG extension, present only when the caller asked for it.
+    #[serde(default, rename = "x-smg")]
+    pub smg: Option<SmgUsage>,
 }
 
 impl Usage {
@@ -537,6 +540,7 @@ impl Usage {
             total_tokens: prompt_tokens + completion_tokens,
             prompt_tokens_details: None,
             completion_tokens_details: None,
+            smg: None,
         }
     }
 
@@ -559,6 +563,16 @@ impl Usage {
         }
         self
     }
+
+    /// Attach a prompt token breakdown to this Usage
+    pub fn with_prompt_breakdown(mut self, breakdown: Option<PromptTokenBreakdown>) -> Self {
+        if let Some(breakdown) = breakdown {
+            self.smg = Some(SmgUsage {
+                prompt_breakdown: Some(breakdown),
+            });
+        }
+        self
+    }
 }
 
 #[serde_with::skip_serializing_none]
@@ -569,6 +583,32 @@ pub struct CompletionTokensDetails {
     pub rejected_prediction_tokens: Option<u32>,
 }
 
+/// SMG-specific usage details, serialized under `usage["x-smg"]`.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SmgUsage {
+    pub prompt_breakdown: Option<PromptTokenBreakdown>,
+}
+
+/// Where the prompt tokens went. Sections are tokenized on their own, so
+/// the counts are estimates; `template` takes whatever the sections leave
+/// of `prompt_tokens` (role markers, separators, the generation prompt).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct PromptTokenBreakdown {
+    /// System and developer messages
+    pub system: u32,
+    /// Tool definitions
+    pub tools: u32,
+    /// Messages up to and including the last assistant turn
+    pub history: u32,
+    /// Messages after the last assistant turn
+    pub current_turn: u32,
+    /// Tokens image placeholders expanded into
+    pub images: u32,
+    /// Chat template markup not attributed to a section
+    pub template: u32,
+}
+
 /// Usage information (used by rerank and other endpoints)
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
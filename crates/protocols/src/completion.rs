@@ -8,7 +8,7 @@ use super::{
     common::*,
     sampling_params::{validate_top_k_value, validate_top_p_value},
 };
-use crate::validated::Normalizable;
+use crate::{builders::CompletionRequestBuilder, validated::Normalizable};
 
 // ============================================================================
 // Completions API (v1/completions) - DEPRECATED but still supported
@@ -150,6 +150,20 @@ pub struct CompletionRequest {
 
 impl Normalizable for CompletionRequest {}
 
+impl CompletionRequest {
+    /// Create a new builder for CompletionRequest
+    pub fn builder(model: impl Into<String>, prompt: StringOrArray) -> CompletionRequestBuilder {
+        CompletionRequestBuilder::new(model, prompt)
+    }
+
+    /// Seed the backend should sample with: the OpenAI `seed` when set,
+    /// otherwise the `sampling_seed` extension (dropped if it overflows i64).
+    pub fn effective_seed(&self) -> Option<i64> {
+        self.seed
+            .or_else(|| self.sampling_seed.and_then(|s| i64::try_from(s).ok()))
+    }
+}
+
 fn validate_completion_prompt(prompt: &StringOrArray) -> Result<(), validator::ValidationError> {
     match prompt {
         StringOrArray::String(_) => {}
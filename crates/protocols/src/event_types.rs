@@ -6,18 +6,21 @@ pub enum ResponseEvent {
     Created,
     InProgress,
     Completed,
+    Failed,
 }
 
 impl ResponseEvent {
     pub const CREATED: &'static str = "response.created";
     pub const IN_PROGRESS: &'static str = "response.in_progress";
     pub const COMPLETED: &'static str = "response.completed";
+    pub const FAILED: &'static str = "response.failed";
 
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::Created => Self::CREATED,
             Self::InProgress => Self::IN_PROGRESS,
             Self::Completed => Self::COMPLETED,
+            Self::Failed => Self::FAILED,
         }
     }
 }
@@ -320,6 +323,8 @@ impl ItemType {
     pub const MCP_CALL: &'static str = "mcp_call";
     pub const FUNCTION: &'static str = "function";
     pub const MCP_LIST_TOOLS: &'static str = "mcp_list_tools";
+    pub const MCP_ELICITATION_REQUEST: &'static str = "mcp_elicitation_request";
+    pub const MCP_ELICITATION_RESPONSE: &'static str = "mcp_elicitation_response";
     pub const WEB_SEARCH_CALL: &'static str = "web_search_call";
     pub const CODE_INTERPRETER_CALL: &'static str = "code_interpreter_call";
     pub const FILE_SEARCH_CALL: &'static str = "file_search_call";
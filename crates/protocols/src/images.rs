@@ -0,0 +1,161 @@
+//! Image generation API protocol definitions.
+//!
+//! This module defines the request and response types for the OpenAI-compatible
+//! `/v1/images/generations` endpoint. Diffusion-specific knobs that OpenAI does
+//! not accept (`negative_prompt`, `num_inference_steps`, `guidance_scale`,
+//! `seed`) are carried as extensions and stripped before forwarding to OpenAI.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::common::GenerationRequest;
+
+fn default_n() -> u32 {
+    1
+}
+
+// ============================================================================
+// Image Generation API
+// ============================================================================
+
+/// Encoding of generated images in the response
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    /// A URL to the generated image
+    #[default]
+    Url,
+    /// The image bytes as a base64-encoded string
+    B64Json,
+}
+
+/// Image generation request - compatible with OpenAI's /v1/images/generations API
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, schemars::JsonSchema)]
+pub struct ImageGenerationRequest {
+    /// Model to use for image generation
+    pub model: String,
+
+    /// Text description of the desired image(s)
+    #[validate(length(min = 1))]
+    pub prompt: String,
+
+    /// Number of images to generate
+    #[serde(default = "default_n")]
+    #[validate(range(min = 1, max = 10))]
+    pub n: u32,
+
+    /// Image dimensions as `{width}x{height}` (e.g. "1024x1024")
+    #[validate(custom(function = "validate_size"))]
+    pub size: Option<String>,
+
+    /// Quality tier (provider-specific, e.g. "standard", "hd", "high")
+    pub quality: Option<String>,
+
+    /// Style hint (provider-specific, e.g. "vivid", "natural")
+    pub style: Option<String>,
+
+    /// Whether images are returned as URLs or base64 (default: url)
+    pub response_format: Option<ImageResponseFormat>,
+
+    /// Optional user identifier
+    pub user: Option<String>,
+
+    // Diffusion extensions (self-hosted workers and Stability)
+    /// Text describing what should not appear in the image
+    pub negative_prompt: Option<String>,
+
+    /// Number of denoising steps
+    #[validate(range(min = 1))]
+    pub num_inference_steps: Option<u32>,
+
+    /// Classifier-free guidance scale
+    pub guidance_scale: Option<f32>,
+
+    /// Random seed for reproducible generation
+    pub seed: Option<u64>,
+}
+
+impl ImageGenerationRequest {
+    /// Requested response format, defaulting to `url`
+    pub fn response_format(&self) -> ImageResponseFormat {
+        self.response_format.unwrap_or_default()
+    }
+}
+
+fn parse_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+fn validate_size(size: &str) -> Result<(), validator::ValidationError> {
+    // "auto" lets the provider pick (gpt-image-1)
+    if size == "auto" || parse_size(size).is_some_and(|(w, h)| w > 0 && h > 0) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new(
+            "size must be `auto` or `{width}x{height}`",
+        ))
+    }
+}
+
+impl GenerationRequest for ImageGenerationRequest {
+    fn is_stream(&self) -> bool {
+        false // Image generation is always non-streaming
+    }
+
+    fn get_model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn extract_text_for_routing(&self) -> String {
+        self.prompt.clone()
+    }
+}
+
+impl super::validated::Normalizable for ImageGenerationRequest {
+    // Use default no-op normalization
+}
+
+// ============================================================================
+// Image Generation Response
+// ============================================================================
+
+/// A single generated image
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ImageData {
+    /// URL of the image when `response_format` is `url`
+    pub url: Option<String>,
+
+    /// Base64-encoded image when `response_format` is `b64_json`
+    pub b64_json: Option<String>,
+
+    /// Prompt actually used, if the provider rewrote it
+    pub revised_prompt: Option<String>,
+}
+
+/// Token accounting reported by token-billed image models (gpt-image-1)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ImageUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// Image generation response
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ImageGenerationResponse {
+    /// Unix timestamp (seconds) of when the images were created
+    pub created: i64,
+
+    /// Generated images
+    #[serde(default)]
+    pub data: Vec<ImageData>,
+
+    /// Token usage, when the provider reports it
+    pub usage: Option<ImageUsage>,
+}
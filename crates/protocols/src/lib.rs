@@ -15,6 +15,7 @@ pub mod completion;
 pub mod embedding;
 pub mod event_types;
 pub mod generate;
+pub mod images;
 pub mod interactions;
 pub mod messages;
 pub mod model_card;
@@ -29,6 +30,7 @@ pub mod realtime_session;
 pub mod rerank;
 pub mod responses;
 pub mod sampling_params;
+pub mod score;
 pub mod tokenize;
 pub mod transcription;
 pub mod validated;
@@ -111,6 +111,12 @@ impl CreateMessageRequest {
             .as_deref()
             .filter(|servers| !servers.is_empty())
     }
+
+    /// Sampling seed passed as a top-level `seed` extension field. Anthropic's
+    /// API has no seed, so it only arrives through `other`.
+    pub fn seed(&self) -> Option<i64> {
+        self.other.get("seed").and_then(Value::as_i64)
+    }
 }
 
 impl GenerationRequest for CreateMessageRequest {
@@ -2459,4 +2465,19 @@ mod tests {
         assert_eq!(req.messages[0].role, Role::User);
         assert_eq!(req.messages[1].role, Role::System); // preserved in place
     }
+
+    #[test]
+    fn test_seed_extension_is_read_from_other() {
+        let body = json!({
+            "model": "m",
+            "max_tokens": 16,
+            "seed": 1234,
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        let req: CreateMessageRequest = serde_json::from_value(body).unwrap();
+        assert_eq!(req.seed(), Some(1234));
+
+        let reserialized = serde_json::to_value(&req).unwrap();
+        assert_eq!(reserialized["seed"], 1234);
+    }
 }
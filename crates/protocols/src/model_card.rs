@@ -306,6 +306,7 @@ impl ModelCard {
             object: "model".to_owned(),
             created: 0,
             owned_by,
+            availability: None,
         }
     }
 
@@ -36,6 +36,10 @@ bitflags! {
         const AUDIO       = 1 << 10;
         /// Content moderation models
         const MODERATION  = 1 << 11;
+        /// Classification API (/v1/classify)
+        const CLASSIFY    = 1 << 12;
+        /// Scoring API (/v1/score)
+        const SCORE       = 1 << 13;
 
         /// Standard LLM: chat + completions + responses + tools
         const LLM = Self::CHAT.bits() | Self::COMPLETIONS.bits()
@@ -56,6 +60,9 @@ bitflags! {
         /// Reranker model only
         const RERANK_MODEL = Self::RERANK.bits();
 
+        /// Sequence classification model only
+        const CLASSIFY_MODEL = Self::CLASSIFY.bits();
+
         /// Image generation model only (DALL-E, Sora, gpt-image)
         const IMAGE_MODEL = Self::IMAGE_GEN.bits();
 
@@ -81,6 +88,8 @@ const CAPABILITY_NAMES: &[(ModelType, &str)] = &[
     (ModelType::IMAGE_GEN, "image_gen"),
     (ModelType::AUDIO, "audio"),
     (ModelType::MODERATION, "moderation"),
+    (ModelType::CLASSIFY, "classify"),
+    (ModelType::SCORE, "score"),
 ];
 
 impl ModelType {
@@ -156,6 +165,18 @@ impl ModelType {
         self.contains(Self::MODERATION)
     }
 
+    /// Check if this model type supports the classify endpoint
+    #[inline]
+    pub fn supports_classify(self) -> bool {
+        self.contains(Self::CLASSIFY)
+    }
+
+    /// Check if this model type supports the score endpoint
+    #[inline]
+    pub fn supports_score(self) -> bool {
+        self.contains(Self::SCORE)
+    }
+
     /// Check if this model type supports a given endpoint
     pub fn supports_endpoint(self, endpoint: Endpoint) -> bool {
         match endpoint {
@@ -164,6 +185,9 @@ impl ModelType {
             Endpoint::Responses => self.supports_responses(),
             Endpoint::Embeddings => self.supports_embeddings(),
             Endpoint::Rerank => self.supports_rerank(),
+            Endpoint::Classify => self.supports_classify(),
+            Endpoint::Score => self.supports_score(),
+            Endpoint::ImageGenerations => self.supports_image_gen(),
             Endpoint::Generate => self.supports_generate(),
             Endpoint::Models => true,
         }
@@ -198,6 +222,12 @@ impl ModelType {
         self.supports_rerank() && !self.supports_chat()
     }
 
+    /// Check if this is a sequence classification model
+    #[inline]
+    pub fn is_classifier(self) -> bool {
+        self.supports_classify() && !self.supports_chat()
+    }
+
     /// Check if this is an image generation model
     #[inline]
     pub fn is_image_model(self) -> bool {
@@ -312,7 +342,9 @@ impl JsonSchema for ModelType {
                     "reasoning",
                     "image_gen",
                     "audio",
-                    "moderation"
+                    "moderation",
+                    "classify",
+                    "score"
                 ]
             }
         })
@@ -333,6 +365,13 @@ pub enum Endpoint {
     Embeddings,
     /// Rerank endpoint (/v1/rerank)
     Rerank,
+    /// Classify endpoint (/v1/classify)
+    Classify,
+    /// Score endpoint (/v1/score)
+    Score,
+    /// Image generation endpoint (/v1/images/generations)
+    #[serde(rename = "image_generations")]
+    ImageGenerations,
     /// SGLang generate endpoint (/generate)
     Generate,
     /// Models listing endpoint (/v1/models)
@@ -348,6 +387,9 @@ impl Endpoint {
             Endpoint::Responses => "/v1/responses",
             Endpoint::Embeddings => "/v1/embeddings",
             Endpoint::Rerank => "/v1/rerank",
+            Endpoint::Classify => "/v1/classify",
+            Endpoint::Score => "/v1/score",
+            Endpoint::ImageGenerations => "/v1/images/generations",
             Endpoint::Generate => "/generate",
             Endpoint::Models => "/v1/models",
         }
@@ -362,6 +404,9 @@ impl Endpoint {
             "/v1/responses" => Some(Endpoint::Responses),
             "/v1/embeddings" => Some(Endpoint::Embeddings),
             "/v1/rerank" => Some(Endpoint::Rerank),
+            "/v1/classify" => Some(Endpoint::Classify),
+            "/v1/score" => Some(Endpoint::Score),
+            "/v1/images/generations" => Some(Endpoint::ImageGenerations),
             "/generate" => Some(Endpoint::Generate),
             "/v1/models" => Some(Endpoint::Models),
             _ => None,
@@ -376,6 +421,9 @@ impl Endpoint {
             Endpoint::Responses => Some(ModelType::RESPONSES),
             Endpoint::Embeddings => Some(ModelType::EMBEDDINGS),
             Endpoint::Rerank => Some(ModelType::RERANK),
+            Endpoint::Classify => Some(ModelType::CLASSIFY),
+            Endpoint::Score => Some(ModelType::SCORE),
+            Endpoint::ImageGenerations => Some(ModelType::IMAGE_GEN),
             Endpoint::Generate => Some(ModelType::GENERATE),
             Endpoint::Models => None,
         }
@@ -390,6 +438,9 @@ impl std::fmt::Display for Endpoint {
             Endpoint::Responses => write!(f, "responses"),
             Endpoint::Embeddings => write!(f, "embeddings"),
             Endpoint::Rerank => write!(f, "rerank"),
+            Endpoint::Classify => write!(f, "classify"),
+            Endpoint::Score => write!(f, "score"),
+            Endpoint::ImageGenerations => write!(f, "image_generations"),
             Endpoint::Generate => write!(f, "generate"),
             Endpoint::Models => write!(f, "models"),
         }
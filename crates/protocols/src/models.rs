@@ -21,6 +21,20 @@ pub struct ModelObject {
     pub created: i64,
     /// Who owns/hosts the model.
     pub owned_by: String,
+    /// Gateway-side availability; omitted while the model is fully available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<ModelAvailability>,
+}
+
+/// Reduced availability of a model, e.g. during a maintenance window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ModelAvailability {
+    /// `"maintenance"` when no worker can currently serve the model,
+    /// `"degraded"` when only some of its workers are unavailable.
+    pub status: String,
+    /// Unix timestamp (seconds) when the reduced availability is expected to end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
 }
 
 /// Response body for `GET /v1/models`.
@@ -35,8 +35,9 @@ pub struct RerankRequest {
     /// Model to use for reranking
     pub model: String,
 
-    /// Maximum number of documents to return (optional)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Maximum number of documents to return (optional). Also accepted as
+    /// `top_n` (Cohere/Jina naming).
+    #[serde(skip_serializing_if = "Option::is_none", alias = "top_n")]
     #[validate(range(min = 1))]
     pub top_k: Option<usize>,
 
@@ -50,6 +51,62 @@ pub struct RerankRequest {
 
     /// User identifier
     pub user: Option<String>,
+
+    /// Normalization applied to scores before ranking (default: raw scores)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_normalization: Option<ScoreNormalization>,
+}
+
+/// Gateway-side normalization of raw relevance scores.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreNormalization {
+    /// Scores as produced by the model
+    #[default]
+    None,
+    /// Softmax across all documents; scores sum to 1
+    Softmax,
+    /// Linear rescale to [0, 1]; equal scores all map to 1
+    MinMax,
+}
+
+impl ScoreNormalization {
+    /// Normalize `scores` in place.
+    pub fn apply(self, scores: &mut [f32]) {
+        if scores.is_empty() {
+            return;
+        }
+        match self {
+            Self::None => {}
+            Self::Softmax => {
+                // Subtract the max for numerical stability
+                let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                for score in scores.iter_mut() {
+                    *score = (*score - max).exp();
+                }
+                let sum: f32 = scores.iter().sum();
+                if sum > 0.0 {
+                    for score in scores.iter_mut() {
+                        *score /= sum;
+                    }
+                }
+            }
+            Self::MinMax => {
+                let min = scores.iter().copied().fold(f32::INFINITY, f32::min);
+                let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                let range = max - min;
+                for score in scores.iter_mut() {
+                    *score = if range > 0.0 {
+                        (*score - min) / range
+                    } else {
+                        1.0
+                    };
+                }
+            }
+        }
+    }
 }
 
 impl GenerationRequest for RerankRequest {
@@ -175,6 +232,25 @@ impl RerankResponse {
         }
     }
 
+    /// Normalize scores across all results. Must run before `apply_top_k`,
+    /// since softmax and min-max depend on every document's score.
+    pub fn normalize_scores(&mut self, normalization: ScoreNormalization) {
+        let mut scores: Vec<f32> = self.results.iter().map(|r| r.score).collect();
+        normalization.apply(&mut scores);
+        for (result, score) in self.results.iter_mut().zip(scores) {
+            result.score = score;
+        }
+    }
+
+    /// Sort results by score, highest first (ties keep document order)
+    pub fn sort_by_score(&mut self) {
+        self.results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
     /// Apply top_k limit to results
     pub fn apply_top_k(&mut self, k: usize) {
         self.results.truncate(k);
@@ -207,6 +283,7 @@ impl From<V1RerankReqInput> for RerankRequest {
             return_documents: true,
             rid: None,
             user: None,
+            score_normalization: None,
         }
     }
 }
@@ -16,7 +16,10 @@ use super::{
     },
     sampling_params::{validate_top_k_value, validate_top_p_value},
 };
-use crate::{builders::ResponsesResponseBuilder, validated::Normalizable};
+use crate::{
+    builders::{ResponsesRequestBuilder, ResponsesResponseBuilder},
+    validated::Normalizable,
+};
 
 // ============================================================================
 // Responses API Tool Choice
@@ -1571,6 +1574,15 @@ pub enum MessagePhase {
     FinalAnswer,
 }
 
+/// User's answer to an MCP elicitation (mirrors the MCP `ElicitResult` action).
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, schemars::JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpElicitationAction {
+    Accept,
+    Decline,
+    Cancel,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -1641,6 +1653,30 @@ pub enum ResponseInputOutputItem {
         #[serde(skip_serializing_if = "Option::is_none")]
         reason: Option<String>,
     },
+    /// SMG extension: an MCP server's request for user input, replayed from
+    /// a paused response. Same shape as the output item.
+    #[serde(rename = "mcp_elicitation_request")]
+    McpElicitationRequest {
+        id: String,
+        server_label: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        requested_schema: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    /// SMG extension: the user's answer to an `mcp_elicitation_request`.
+    /// Resumes the paused tool call.
+    #[serde(rename = "mcp_elicitation_response")]
+    McpElicitationResponse {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        elicitation_request_id: String,
+        action: McpElicitationAction,
+        /// Form values matching `requested_schema`; only sent with `accept`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<Value>,
+    },
     /// `type: "image_generation_call"` — round-trip form for an image generated
     /// in a prior turn. Spec (OpenAI Responses API, multi-turn image-edit
     /// flow): clients may resubmit only `{ type, id }` to reference a prior
@@ -2206,6 +2242,21 @@ pub enum ResponseOutputItem {
         name: String,
         arguments: String,
     },
+    /// SMG extension: an MCP server asked for user input mid tool call. The
+    /// response pauses until the client sends an `mcp_elicitation_response`
+    /// input item with the same id.
+    #[serde(rename = "mcp_elicitation_request")]
+    McpElicitationRequest {
+        id: String,
+        server_label: String,
+        message: String,
+        /// JSON schema of the requested form (form elicitation).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        requested_schema: Option<Value>,
+        /// Page the user should visit (URL elicitation).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
     #[serde(rename = "web_search_call")]
     WebSearchCall {
         id: String,
@@ -3002,6 +3053,10 @@ pub struct ResponsesRequest {
     #[serde(default = "default_repetition_penalty")]
     #[validate(range(min = 0.0, max = 2.0))]
     pub repetition_penalty: f32,
+
+    /// Sampling seed for reproducible generations (SGLang extension)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
@@ -3050,10 +3105,25 @@ impl Default for ResponsesRequest {
             top_k: default_top_k(),
             min_p: 0.0,
             repetition_penalty: default_repetition_penalty(),
+            seed: None,
         }
     }
 }
 
+impl ResponsesRequest {
+    /// Create a new builder for ResponsesRequest
+    pub fn builder(model: impl Into<String>, input: ResponseInput) -> ResponsesRequestBuilder {
+        ResponsesRequestBuilder::new(model, input)
+    }
+
+    /// Whether the request asked for `field` via `include[]`.
+    pub fn includes(&self, field: &IncludeField) -> bool {
+        self.include
+            .as_ref()
+            .is_some_and(|include| include.contains(field))
+    }
+}
+
 impl Normalizable for ResponsesRequest {
     /// Normalize the request by applying defaults:
     /// 1. Apply tool_choice defaults based on tools presence
@@ -3167,6 +3237,8 @@ impl GenerationRequest for ResponsesRequest {
                         | ResponseInputOutputItem::FunctionCallOutput { .. }
                         | ResponseInputOutputItem::McpApprovalRequest { .. }
                         | ResponseInputOutputItem::McpApprovalResponse { .. }
+                        | ResponseInputOutputItem::McpElicitationRequest { .. }
+                        | ResponseInputOutputItem::McpElicitationResponse { .. }
                         | ResponseInputOutputItem::ImageGenerationCall { .. }
                         | ResponseInputOutputItem::Compaction { .. }
                         | ResponseInputOutputItem::ComputerCall { .. }
@@ -3334,19 +3406,12 @@ fn validate_responses_cross_parameters(request: &ResponsesRequest) -> Result<(),
     validate_tool_choice_with_tools(request)?;
 
     // 2. Validate top_logprobs requires include field
-    if request.top_logprobs.is_some() {
-        let has_logprobs_include = request
-            .include
-            .as_ref()
-            .is_some_and(|inc| inc.contains(&IncludeField::MessageOutputTextLogprobs));
-
-        if !has_logprobs_include {
-            let mut e = ValidationError::new("top_logprobs_requires_include");
-            e.message = Some(
-                "top_logprobs requires include field with 'message.output_text.logprobs'".into(),
-            );
-            return Err(e);
-        }
+    if request.top_logprobs.is_some() && !request.includes(&IncludeField::MessageOutputTextLogprobs)
+    {
+        let mut e = ValidationError::new("top_logprobs_requires_include");
+        e.message =
+            Some("top_logprobs requires include field with 'message.output_text.logprobs'".into());
+        return Err(e);
     }
 
     // 3. Validate conversation and previous_response_id are mutually exclusive
@@ -3446,6 +3511,8 @@ fn validate_input_item(item: &ResponseInputOutputItem) -> Result<(), ValidationE
         ResponseInputOutputItem::FunctionToolCall { .. } => {}
         ResponseInputOutputItem::McpApprovalRequest { .. } => {}
         ResponseInputOutputItem::McpApprovalResponse { .. } => {}
+        ResponseInputOutputItem::McpElicitationRequest { .. } => {}
+        ResponseInputOutputItem::McpElicitationResponse { .. } => {}
         ResponseInputOutputItem::ImageGenerationCall { .. } => {}
         ResponseInputOutputItem::Compaction { .. } => {}
         ResponseInputOutputItem::ComputerCall { .. } => {}
@@ -3712,6 +3779,15 @@ pub struct ResponsesResponse {
     /// Top-p setting used
     pub top_p: Option<f32>,
 
+    /// Sampling seed the response was generated with (SGLang extension)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+
+    /// Fingerprint of the backend configuration that produced the response
+    /// (SGLang extension); pair with `seed` to replay a generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+
     /// Truncation strategy used
     pub truncation: Option<String>,
 
@@ -0,0 +1,142 @@
+//! Score API protocol definitions.
+//!
+//! This module defines the request and response types for the `/v1/score` API,
+//! which is compatible with SGLang's scoring endpoint: for each item, the
+//! backend returns the probability of each label token following the
+//! query/item prompt.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::common::{GenerationRequest, UsageInfo};
+
+fn default_score_object() -> String {
+    "scoring".to_string()
+}
+
+// ============================================================================
+// Score API
+// ============================================================================
+
+/// Query for a score request: text or pre-tokenized ids
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum ScoreQuery {
+    Text(String),
+    Tokens(Vec<u32>),
+}
+
+/// Items to score against the query
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum ScoreItems {
+    Text(String),
+    Texts(Vec<String>),
+    Tokens(Vec<Vec<u32>>),
+}
+
+impl ScoreItems {
+    /// Number of items in the batch
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Text(_) => 1,
+            Self::Texts(texts) => texts.len(),
+            Self::Tokens(tokens) => tokens.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_tokens(&self) -> bool {
+        matches!(self, Self::Tokens(_))
+    }
+}
+
+/// Score request - compatible with SGLang's /v1/score API
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, Validate, schemars::JsonSchema)]
+#[validate(schema(function = "validate_score_request"))]
+pub struct ScoreRequest {
+    /// Model to use for scoring
+    pub model: String,
+
+    /// Query prompt shared by every item
+    pub query: ScoreQuery,
+
+    /// Items to score (one score list per item)
+    pub items: ScoreItems,
+
+    /// Token ids whose probabilities are returned for each item
+    #[validate(length(min = 1))]
+    pub label_token_ids: Vec<u32>,
+
+    /// Normalize the label probabilities of each item to sum to 1
+    #[serde(default)]
+    pub apply_softmax: bool,
+
+    /// Place the item before the query in the prompt
+    #[serde(default)]
+    pub item_first: bool,
+
+    /// Optional user identifier
+    pub user: Option<String>,
+}
+
+/// Schema-level validation for cross-field dependencies
+fn validate_score_request(req: &ScoreRequest) -> Result<(), validator::ValidationError> {
+    if req.items.is_empty() {
+        return Err(validator::ValidationError::new("items cannot be empty"));
+    }
+    let query_is_tokens = matches!(req.query, ScoreQuery::Tokens(_));
+    if query_is_tokens != req.items.is_tokens() {
+        return Err(validator::ValidationError::new(
+            "query and items must both be text or both be token ids",
+        ));
+    }
+    Ok(())
+}
+
+impl GenerationRequest for ScoreRequest {
+    fn is_stream(&self) -> bool {
+        false // Scoring is always non-streaming
+    }
+
+    fn get_model(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn extract_text_for_routing(&self) -> String {
+        match &self.query {
+            ScoreQuery::Text(text) => text.clone(),
+            ScoreQuery::Tokens(_) => String::new(),
+        }
+    }
+}
+
+impl super::validated::Normalizable for ScoreRequest {
+    // Use default no-op normalization
+}
+
+// ============================================================================
+// Score Response
+// ============================================================================
+
+/// Score response
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ScoreResponse {
+    /// One list per item, with one probability per label token
+    pub scores: Vec<Vec<f32>>,
+
+    /// Model used for scoring
+    pub model: String,
+
+    /// Usage information
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageInfo>,
+
+    /// Always "scoring"
+    #[serde(default = "default_score_object")]
+    pub object: String,
+}
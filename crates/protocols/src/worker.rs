@@ -212,8 +212,15 @@ pub enum RuntimeType {
     Mlx,
     /// TokenSpeed runtime.
     TokenSpeed,
+    /// llama.cpp `llama-server` (OpenAI-compatible HTTP).
+    LlamaCpp,
+    /// Ollama (OpenAI-compatible HTTP).
+    Ollama,
     /// External OpenAI-compatible API (not local inference).
     External,
+    /// Another smg deployment (e.g. a cluster in another region) federated
+    /// in as a single worker.
+    Smg,
 }
 
 impl RuntimeType {
@@ -232,7 +239,10 @@ impl RuntimeType {
             RuntimeType::Trtllm => "trtllm",
             RuntimeType::Mlx => "mlx",
             RuntimeType::TokenSpeed => "tokenspeed",
+            RuntimeType::LlamaCpp => "llamacpp",
+            RuntimeType::Ollama => "ollama",
             RuntimeType::External => "external",
+            RuntimeType::Smg => "smg",
         }
     }
 }
@@ -259,8 +269,17 @@ impl std::str::FromStr for RuntimeType {
             Ok(RuntimeType::Mlx)
         } else if s.eq_ignore_ascii_case("tokenspeed") {
             Ok(RuntimeType::TokenSpeed)
+        } else if s.eq_ignore_ascii_case("llamacpp")
+            || s.eq_ignore_ascii_case("llama.cpp")
+            || s.eq_ignore_ascii_case("llama-cpp")
+        {
+            Ok(RuntimeType::LlamaCpp)
+        } else if s.eq_ignore_ascii_case("ollama") {
+            Ok(RuntimeType::Ollama)
         } else if s.eq_ignore_ascii_case("external") {
             Ok(RuntimeType::External)
+        } else if s.eq_ignore_ascii_case("smg") {
+            Ok(RuntimeType::Smg)
         } else {
             Err(format!("Unknown runtime type: {s}"))
         }
@@ -291,6 +310,9 @@ pub enum ProviderType {
     /// Google Gemini — special logprobs handling.
     #[serde(alias = "gemini", alias = "google")]
     Gemini,
+    /// Stability AI — image generation via the v1 text-to-image API.
+    #[serde(alias = "stability", alias = "stabilityai")]
+    Stability,
     /// Custom provider with string identifier.
     #[serde(untagged)]
     Custom(String),
@@ -304,6 +326,7 @@ impl ProviderType {
             Self::XAI => "xai",
             Self::Anthropic => "anthropic",
             Self::Gemini => "gemini",
+            Self::Stability => "stability",
             Self::Custom(s) => s.as_str(),
         }
     }
@@ -321,20 +344,23 @@ impl ProviderType {
             Some(Self::Anthropic)
         } else if host.ends_with("googleapis.com") {
             Some(Self::Gemini)
+        } else if host.ends_with("stability.ai") {
+            Some(Self::Stability)
         } else {
             None
         }
     }
 
     /// Environment variable name for per-provider admin API key (model discovery).
-    /// Returns `None` for `Custom` providers since there's no known env var.
+    /// Returns `None` for `Custom` providers since there's no known env var, and
+    /// for Stability, which has no OpenAI-style `/v1/models` listing.
     pub fn admin_key_env_var(&self) -> Option<&'static str> {
         match self {
             Self::OpenAI => Some("OPENAI_ADMIN_KEY"),
             Self::XAI => Some("XAI_ADMIN_KEY"),
             Self::Anthropic => Some("ANTHROPIC_ADMIN_KEY"),
             Self::Gemini => Some("GEMINI_ADMIN_KEY"),
-            Self::Custom(_) => None,
+            Self::Stability | Self::Custom(_) => None,
         }
     }
 
@@ -353,6 +379,8 @@ impl ProviderType {
             Some(Self::Gemini)
         } else if model_lower.starts_with("claude") {
             Some(Self::Anthropic)
+        } else if model_lower.starts_with("stable-diffusion") {
+            Some(Self::Stability)
         } else if model_lower.starts_with("gpt")
             || model_lower.starts_with("o1")
             || model_lower.starts_with("o3")
@@ -606,6 +634,11 @@ pub struct WorkerSpec {
     #[serde(default = "default_cost")]
     pub cost: f32,
 
+    /// Warm spare: health-checked but kept out of rotation until too few
+    /// regular workers for its model are healthy.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub standby: bool,
+
     /// Worker API key. Accepted on input, never included in responses.
     #[serde(default, skip_serializing)]
     pub api_key: Option<String>,
@@ -657,6 +690,11 @@ pub struct WorkerSpec {
     #[serde(default, skip_serializing_if = "HttpPoolConfig::is_empty")]
     pub http_pool: HttpPoolConfig,
 
+    /// TLS for gRPC workers. When set the channel uses TLS even for a
+    /// `grpc://` URL; without it only `grpcs://` URLs do, with system roots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_tls: Option<GrpcTlsConfig>,
+
     /// Per-worker resilience overrides (retry + circuit breaker).
     #[serde(default, skip_serializing_if = "ResilienceUpdate::is_empty")]
     pub resilience: ResilienceUpdate,
@@ -683,6 +721,44 @@ pub struct WorkerSpec {
     pub multimodal_shm_min_bytes: Option<usize>,
 }
 
+/// Per-worker gRPC channel TLS configuration.
+///
+/// Certificates and keys are PEM files, re-read every
+/// `reload_interval_secs` so rotated material is picked up without
+/// re-registering the worker.
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct GrpcTlsConfig {
+    /// CA bundle used to verify the worker (default: system roots).
+    pub ca_cert_path: Option<String>,
+    /// Client certificate chain for mTLS; requires `client_key_path`.
+    pub client_cert_path: Option<String>,
+    /// Client private key for mTLS; requires `client_cert_path`.
+    pub client_key_path: Option<String>,
+    /// Name sent as SNI and verified against the worker certificate
+    /// (default: the URL host).
+    pub server_name: Option<String>,
+    /// Use HTTP/2 even when the worker does not negotiate `h2` over ALPN.
+    pub assume_http2: bool,
+    /// Seconds between checks for rotated certificate files; 0 disables
+    /// reloading (default: 300).
+    pub reload_interval_secs: u64,
+}
+
+impl Default for GrpcTlsConfig {
+    fn default() -> Self {
+        Self {
+            ca_cert_path: None,
+            client_cert_path: None,
+            client_key_path: None,
+            server_name: None,
+            assume_http2: false,
+            reload_interval_secs: 300,
+        }
+    }
+}
+
 impl WorkerSpec {
     /// Create a new `WorkerSpec` with the given URL and sensible defaults.
     pub fn new(url: impl Into<String>) -> Self {
@@ -696,6 +772,7 @@ impl WorkerSpec {
             labels: HashMap::new(),
             priority: DEFAULT_WORKER_PRIORITY,
             cost: DEFAULT_WORKER_COST,
+            standby: false,
             api_key: None,
             bootstrap_port: None,
             bootstrap_host: String::new(),
@@ -708,6 +785,7 @@ impl WorkerSpec {
             kv_block_size: None,
             health: HealthCheckUpdate::default(),
             http_pool: HttpPoolConfig::default(),
+            grpc_tls: None,
             resilience: ResilienceUpdate::default(),
             max_connection_attempts: default_max_connection_attempts(),
             load_monitor_interval_secs: None,
@@ -808,6 +886,10 @@ pub struct WorkerInfo {
     /// Current load on the worker.
     pub load: usize,
 
+    /// For a standby worker, whether it is currently promoted into rotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted: Option<bool>,
+
     /// Job status for async operations (if available).
     pub job_status: Option<JobStatus>,
 }
@@ -822,6 +904,7 @@ impl WorkerInfo {
             is_healthy: false,
             status: Some(WorkerStatus::Pending),
             load: 0,
+            promoted: None,
             job_status,
         }
     }
@@ -1099,6 +1182,21 @@ pub struct FlushCacheResult {
     pub message: String,
 }
 
+/// Result from a cache operation fanned out to the workers of one model
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CacheAdminResult {
+    pub model: String,
+    pub operation: String,
+    pub successful: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    pub total_workers: usize,
+    /// Whether the gateway's cache-aware routing trees for the model were
+    /// reset along with the operation
+    #[serde(default)]
+    pub routing_reset: bool,
+    pub message: String,
+}
+
 /// Options for starting a profiling run on workers.
 ///
 /// Mirrors the engines' native profile parameters: serialized verbatim as
@@ -1337,6 +1435,43 @@ impl IntoResponse for ProfileResult {
     }
 }
 
+#[cfg(feature = "axum")]
+impl IntoResponse for CacheAdminResult {
+    fn into_response(self) -> Response {
+        let status = if self.total_workers == 0 {
+            StatusCode::NOT_FOUND
+        } else if self.successful.is_empty() {
+            StatusCode::BAD_GATEWAY
+        } else if self.failed.is_empty() {
+            StatusCode::OK
+        } else {
+            StatusCode::PARTIAL_CONTENT
+        };
+
+        let status_str = match status {
+            StatusCode::OK => "success",
+            StatusCode::PARTIAL_CONTENT => "partial_success",
+            _ => "error",
+        };
+        let body = json!({
+            "status": status_str,
+            "model": self.model,
+            "operation": self.operation,
+            "message": self.message,
+            "successful": self.successful,
+            "failed": self
+                .failed
+                .into_iter()
+                .map(|(url, err)| json!({"worker": url, "error": err}))
+                .collect::<Vec<_>>(),
+            "total_workers": self.total_workers,
+            "routing_reset": self.routing_reset,
+        });
+
+        (status, Json(body)).into_response()
+    }
+}
+
 #[cfg(feature = "axum")]
 impl IntoResponse for WorkerLoadsResult {
     fn into_response(self) -> Response {
@@ -4222,3 +4222,49 @@ fn test_mcp_list_tools_input_item_with_error_round_trip() {
 
     assert_eq!(serde_json::to_value(&item).expect("serialize"), payload);
 }
+
+/// `mcp_elicitation_request` output items and `mcp_elicitation_response`
+/// input items round-trip.
+#[test]
+fn test_mcp_elicitation_items_round_trip() {
+    let request_payload = json!({
+        "type": "mcp_elicitation_request",
+        "id": "elicit_1",
+        "server_label": "forms",
+        "message": "What is your email?",
+        "requested_schema": {
+            "type": "object",
+            "properties": { "email": { "type": "string" } }
+        },
+    });
+    let request: ResponseOutputItem = serde_json::from_value(request_payload.clone())
+        .expect("mcp_elicitation_request should deserialize");
+    assert!(matches!(
+        &request,
+        ResponseOutputItem::McpElicitationRequest { url: None, .. }
+    ));
+    assert_eq!(
+        serde_json::to_value(&request).expect("serialize"),
+        request_payload
+    );
+
+    let response_payload = json!({
+        "type": "mcp_elicitation_response",
+        "elicitation_request_id": "elicit_1",
+        "action": "accept",
+        "content": { "email": "user@example.com" },
+    });
+    let response: ResponseInputOutputItem = serde_json::from_value(response_payload.clone())
+        .expect("mcp_elicitation_response should deserialize");
+    match &response {
+        ResponseInputOutputItem::McpElicitationResponse { id, action, .. } => {
+            assert!(id.is_none());
+            assert_eq!(*action, McpElicitationAction::Accept);
+        }
+        other => panic!("expected McpElicitationResponse, got {other:?}"),
+    }
+    assert_eq!(
+        serde_json::to_value(&response).expect("serialize"),
+        response_payload
+    );
+}
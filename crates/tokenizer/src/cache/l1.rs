@@ -29,6 +29,7 @@ use std::{
 
 use blake3;
 use dashmap::DashMap;
+use rayon::prelude::*;
 
 use crate::traits::{Encoder, Encoding, TokenIdType};
 
@@ -70,7 +71,7 @@ const NUM_SHARDS: usize = 16;
 /// - Custom: `<|reserved_special_token_N|>`
 ///
 /// Returns positions immediately after each special token (where prefixes can be cached).
-fn find_special_token_boundaries(text: &str, special_tokens: &[&str]) -> Vec<usize> {
+pub(crate) fn find_special_token_boundaries(text: &str, special_tokens: &[&str]) -> Vec<usize> {
     if special_tokens.is_empty() {
         return Vec::new();
     }
@@ -240,7 +241,7 @@ impl L1Cache {
             return Ok(());
         }
 
-        self.populate_boundaries(input, &seeds, tokenizer, add_special_tokens)?;
+        self.populate_boundaries(input, &seeds, tokenizer, add_special_tokens, false)?;
         Ok(())
     }
 
@@ -256,18 +257,20 @@ impl L1Cache {
         add_special_tokens: bool,
     ) -> anyhow::Result<Encoding> {
         let seeds = Self::boundary_seeds(input, special_tokens, add_special_tokens);
-        self.populate_with_seeds(input, &seeds, tokenizer, add_special_tokens)
+        self.populate_with_seeds(input, &seeds, tokenizer, add_special_tokens, false)
     }
 
     /// Like [`Self::populate_and_encode`], but reuses the `(boundary, digest)` seed
     /// list a failed [`Self::lookup_with_seeds`] already computed, so the miss path
     /// neither re-scans for boundaries nor re-hashes the prefixes it just hashed.
+    /// With `parallel` the segments are encoded concurrently on the rayon pool.
     pub(super) fn populate_with_seeds<E: Encoder + ?Sized>(
         &self,
         input: &str,
         seeds: &[(usize, Blake3Hash)],
         tokenizer: &E,
         add_special_tokens: bool,
+        parallel: bool,
     ) -> anyhow::Result<Encoding> {
         let Some(&(tail_start, _)) = seeds.last() else {
             // No special token boundaries — nothing cacheable; a single plain encode
@@ -276,7 +279,8 @@ impl L1Cache {
         };
 
         // Tokenize + cache every boundary prefix; `running` covers input[0..last boundary].
-        let mut running = self.populate_boundaries(input, seeds, tokenizer, add_special_tokens)?;
+        let mut running =
+            self.populate_boundaries(input, seeds, tokenizer, add_special_tokens, parallel)?;
 
         // The trailing segment after the last boundary is not a cache key (boundaries
         // exclude input.len()); encoding it completes the full tokenization. It is
@@ -296,16 +300,34 @@ impl L1Cache {
         seeds: &[(usize, Blake3Hash)],
         tokenizer: &E,
         add_special_tokens: bool,
+        parallel: bool,
     ) -> anyhow::Result<Vec<TokenIdType>> {
+        // 1. Incremental Tokenization
+        // Only add special tokens (like BOS) for the very first segment to avoid duplicates
+        let encode_segment = |(i, &(boundary_pos, _)): (usize, &(usize, Blake3Hash))| {
+            let segment_start = if i == 0 { 0 } else { seeds[i - 1].0 };
+            tokenizer.encode(
+                &input[segment_start..boundary_pos],
+                (i == 0) && add_special_tokens,
+            )
+        };
+        let segments: Vec<Encoding> = if parallel {
+            seeds
+                .par_iter()
+                .enumerate()
+                .map(encode_segment)
+                .collect::<anyhow::Result<_>>()?
+        } else {
+            seeds
+                .iter()
+                .enumerate()
+                .map(encode_segment)
+                .collect::<anyhow::Result<_>>()?
+        };
+
         let mut running_tokens = Vec::new();
-        let mut last_pos = 0;
         let mut entries_to_insert = Vec::with_capacity(seeds.len());
-        for (i, &(boundary_pos, hash_bytes)) in seeds.iter().enumerate() {
-            let delta_text = &input[last_pos..boundary_pos];
-
-            // 1. Incremental Tokenization
-            // Only add special tokens (like BOS) for the very first segment to avoid duplicates
-            let segment_encoding = tokenizer.encode(delta_text, (i == 0) && add_special_tokens)?;
+        for (&(boundary_pos, hash_bytes), segment_encoding) in seeds.iter().zip(&segments) {
             running_tokens.extend_from_slice(segment_encoding.token_ids());
 
             // 2. Prepare entry
@@ -316,8 +338,6 @@ impl L1Cache {
             let size_bytes = boundary_pos + prefix_tokens.len() * size_of::<TokenIdType>();
 
             entries_to_insert.push((hash_bytes, prefix_tokens, size_bytes));
-
-            last_pos = boundary_pos;
         }
 
         if entries_to_insert.is_empty() {
@@ -23,6 +23,7 @@ use std::sync::Arc;
 use anyhow::Result;
 pub use fingerprint::TokenizerFingerprint;
 pub use l0::{CacheStats, L0Cache};
+pub(crate) use l1::find_special_token_boundaries;
 use l1::PrefixLookup;
 pub use l1::{L1Cache, L1CacheStats};
 use rayon::prelude::*;
@@ -103,33 +104,12 @@ impl CachedTokenizer {
 
     /// Extract all special token strings from the tokenizer (called once at construction)
     fn extract_special_token_strings(tokenizer: &Arc<dyn Tokenizer>) -> Vec<String> {
-        let special_tokens = tokenizer.get_special_tokens();
-        let mut tokens = Vec::new();
-
-        if let Some(ref token) = special_tokens.bos_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.eos_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.unk_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.sep_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.pad_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.cls_token {
-            tokens.push(token.clone());
-        }
-        if let Some(ref token) = special_tokens.mask_token {
-            tokens.push(token.clone());
-        }
-
-        tokens.extend(special_tokens.additional_special_tokens.iter().cloned());
-        tokens
+        tokenizer
+            .get_special_tokens()
+            .strings()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
     }
 
     /// Get L0 cache statistics
@@ -161,10 +141,15 @@ impl CachedTokenizer {
     pub fn inner(&self) -> &Arc<dyn Tokenizer> {
         &self.inner
     }
-}
 
-impl Encoder for CachedTokenizer {
-    fn encode(&self, input: &str, add_special_tokens: bool) -> Result<Encoding> {
+    /// Shared by [`Encoder::encode`] and [`Encoder::encode_parallel`]: the
+    /// cache lookups are the same, `parallel` selects how misses are encoded.
+    fn encode_cached(
+        &self,
+        input: &str,
+        add_special_tokens: bool,
+        parallel: bool,
+    ) -> Result<Encoding> {
         // L0 cache lookup (exact match, keyed on input + add_special_tokens)
         if let Some(l0) = &self.l0 {
             if let Some(cached) = l0.get(input, add_special_tokens) {
@@ -187,7 +172,7 @@ impl Encoder for CachedTokenizer {
                     let suffix = &input[prefix_len..];
                     // The cached prefix already carries any leading special tokens,
                     // so the suffix must never re-add them (it is never segment 0).
-                    let suffix_encoding = self.inner.encode(suffix, false)?;
+                    let suffix_encoding = self.encode_inner(suffix, false, parallel)?;
                     let suffix_tokens = suffix_encoding.token_ids();
 
                     // Splice with exact capacity: one allocation and a single copy
@@ -205,7 +190,7 @@ impl Encoder for CachedTokenizer {
                 // No special token boundaries — nothing cacheable; single plain encode
                 // (preserves the inner tokenizer's native encoding variant).
                 PrefixLookup::Miss(seeds) if seeds.is_empty() => {
-                    self.inner.encode(input, add_special_tokens)?
+                    self.encode_inner(input, add_special_tokens, parallel)?
                 }
                 PrefixLookup::Miss(seeds) => {
                     match l1.populate_with_seeds(
@@ -213,12 +198,13 @@ impl Encoder for CachedTokenizer {
                         &seeds,
                         self.inner.as_ref(),
                         add_special_tokens,
+                        parallel,
                     ) {
                         Ok(encoding) => encoding,
                         // Seeding failed mid-segment (nothing was inserted) — fall
                         // back to the plain uncached encode, matching the previous
                         // behavior where boundary-insert errors were swallowed.
-                        Err(_) => self.inner.encode(input, add_special_tokens)?,
+                        Err(_) => self.encode_inner(input, add_special_tokens, parallel)?,
                     }
                 }
             };
@@ -231,7 +217,7 @@ impl Encoder for CachedTokenizer {
         }
 
         // Full tokenization (no L1 configured), cached in L0 only
-        let encoding = self.inner.encode(input, add_special_tokens)?;
+        let encoding = self.encode_inner(input, add_special_tokens, parallel)?;
 
         if let Some(l0) = &self.l0 {
             l0.insert(input.to_string(), add_special_tokens, encoding.clone());
@@ -240,6 +226,25 @@ impl Encoder for CachedTokenizer {
         Ok(encoding)
     }
 
+    fn encode_inner(
+        &self,
+        input: &str,
+        add_special_tokens: bool,
+        parallel: bool,
+    ) -> Result<Encoding> {
+        if parallel {
+            self.inner.encode_parallel(input, add_special_tokens)
+        } else {
+            self.inner.encode(input, add_special_tokens)
+        }
+    }
+}
+
+impl Encoder for CachedTokenizer {
+    fn encode(&self, input: &str, add_special_tokens: bool) -> Result<Encoding> {
+        self.encode_cached(input, add_special_tokens, false)
+    }
+
     fn encode_batch(&self, inputs: &[&str], add_special_tokens: bool) -> Result<Vec<Encoding>> {
         // Process each input in parallel, leveraging thread-safe caches
         // This maintains the parallelism from the underlying HuggingFaceTokenizer
@@ -248,6 +253,16 @@ impl Encoder for CachedTokenizer {
             .map(|&input| self.encode(input, add_special_tokens))
             .collect()
     }
+
+    fn encode_pair(&self, first: &str, second: &str, add_special_tokens: bool) -> Result<Encoding> {
+        // Pair encodings are not cached; forward so the inner tokenizer's pair
+        // post-processor applies.
+        self.inner.encode_pair(first, second, add_special_tokens)
+    }
+
+    fn encode_parallel(&self, input: &str, add_special_tokens: bool) -> Result<Encoding> {
+        self.encode_cached(input, add_special_tokens, true)
+    }
 }
 
 impl Decoder for CachedTokenizer {
@@ -23,6 +23,7 @@ use crate::{
         ChatTemplateState, ThinkingKeyName, ThinkingToggle,
     },
     encoders::{deepseek_v32, deepseek_v4},
+    parallel::encode_chunked,
     traits::{Decoder, Encoder, Encoding, SpecialTokens, TokenIdType, Tokenizer as TokenizerTrait},
 };
 
@@ -493,6 +494,22 @@ impl Encoder for HuggingFaceTokenizer {
                     .collect()
             })
     }
+
+    fn encode_pair(&self, first: &str, second: &str, add_special_tokens: bool) -> Result<Encoding> {
+        self.tokenizer
+            .encode((first, second), add_special_tokens)
+            .map_err(|e| Error::msg(format!("Pair encoding failed: {e}")))
+            .map(|encoding| Encoding::Hf(Box::new(encoding)))
+    }
+
+    fn encode_parallel(&self, input: &str, add_special_tokens: bool) -> Result<Encoding> {
+        // The post-processor wraps the whole sequence (BOS/EOS), so only input
+        // encoded without it can be split.
+        if add_special_tokens {
+            return self.encode(input, true);
+        }
+        encode_chunked(self, input, &self.special_tokens.strings())
+    }
 }
 
 impl Decoder for HuggingFaceTokenizer {
@@ -9,6 +9,7 @@ pub mod factory;
 pub mod hub;
 pub(crate) mod json_dumps;
 pub mod mock;
+pub mod parallel;
 pub mod registry;
 pub mod sequence;
 pub mod stop;
@@ -92,6 +93,16 @@ impl Tokenizer {
         self.0.encode_batch(inputs, add_special_tokens)
     }
 
+    /// Direct pair encode method (query/document for cross-encoders)
+    pub fn encode_pair(
+        &self,
+        first: &str,
+        second: &str,
+        add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        self.0.encode_pair(first, second, add_special_tokens)
+    }
+
     /// Direct decode method
     pub fn decode(&self, token_ids: &[u32], skip_special_tokens: bool) -> Result<String> {
         self.0.decode(token_ids, skip_special_tokens)
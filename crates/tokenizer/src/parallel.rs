@@ -0,0 +1,96 @@
+//! Parallel encoding of long inputs.
+//!
+//! Special tokens are atomic in BPE tokenizers, so an input split right after
+//! a special token encodes piece by piece to the same IDs as it does whole,
+//! the property the L1 cache relies on. [`encode_chunked`] cuts a rendered
+//! prompt at those boundaries into chunks of at least
+//! [`PARALLEL_ENCODE_CHUNK_BYTES`] and encodes them concurrently on the
+//! current rayon pool. A prompt that is one long message without special
+//! tokens has nowhere safe to cut and is encoded in one piece.
+
+use anyhow::Result;
+use rayon::prelude::*;
+
+use crate::{
+    cache::find_special_token_boundaries,
+    traits::{Encoder, Encoding, TokenIdType},
+};
+
+/// Smallest chunk worth handing to another thread; below this the split and
+/// the concatenation cost more than the encode saves.
+pub const PARALLEL_ENCODE_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Encode `input` (without added special tokens) in chunks cut after the
+/// `special_tokens` it contains, concatenating the chunk encodings.
+pub fn encode_chunked<E: Encoder + ?Sized>(
+    tokenizer: &E,
+    input: &str,
+    special_tokens: &[&str],
+) -> Result<Encoding> {
+    let chunks = split_chunks(input, special_tokens, PARALLEL_ENCODE_CHUNK_BYTES);
+    if chunks.len() < 2 {
+        return tokenizer.encode(input, false);
+    }
+
+    let encodings = chunks
+        .par_iter()
+        .map(|chunk| tokenizer.encode(chunk, false))
+        .collect::<Result<Vec<_>>>()?;
+    let total = encodings.iter().map(|e| e.token_ids().len()).sum();
+    let mut token_ids: Vec<TokenIdType> = Vec::with_capacity(total);
+    for encoding in &encodings {
+        token_ids.extend_from_slice(encoding.token_ids());
+    }
+    Ok(Encoding::Plain(token_ids))
+}
+
+/// Cut `input` after special tokens into chunks of at least `min_bytes`; the
+/// last chunk takes whatever remains.
+fn split_chunks<'a>(input: &'a str, special_tokens: &[&str], min_bytes: usize) -> Vec<&'a str> {
+    if input.len() < 2 * min_bytes {
+        return vec![input];
+    }
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for boundary in find_special_token_boundaries(input, special_tokens) {
+        if boundary - start >= min_bytes && input.len() - boundary >= min_bytes {
+            chunks.push(&input[start..boundary]);
+            start = boundary;
+        }
+    }
+    chunks.push(&input[start..]);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTokenizer;
+
+    #[test]
+    fn test_split_chunks_cuts_after_special_tokens() {
+        let turn = "<|im_start|>user\nHello world<|im_end|>";
+        let input = turn.repeat(8);
+        let chunks = split_chunks(&input, &["<|im_start|>", "<|im_end|>"], 2 * turn.len());
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks.iter().all(|chunk| chunk.ends_with("<|im_end|>")));
+        assert_eq!(chunks.concat(), input);
+
+        // Too short to split, or nowhere to cut.
+        assert_eq!(split_chunks(turn, &["<|im_end|>"], turn.len()).len(), 1);
+        assert_eq!(split_chunks(&input, &[], 8).len(), 1);
+    }
+
+    #[test]
+    fn test_encode_chunked_matches_encode() {
+        let tokenizer = MockTokenizer::new();
+        let turn = "<|im_start|> user Hello world . <|im_end|> ";
+        let input = turn.repeat(PARALLEL_ENCODE_CHUNK_BYTES / turn.len() * 4);
+        let special_tokens = ["<|im_start|>", "<|im_end|>"];
+        assert!(split_chunks(&input, &special_tokens, PARALLEL_ENCODE_CHUNK_BYTES).len() > 1);
+
+        let chunked = encode_chunked(&tokenizer, &input, &special_tokens).unwrap();
+        let whole = tokenizer.encode(&input, false).unwrap();
+        assert_eq!(chunked.token_ids(), whole.token_ids());
+    }
+}
@@ -15,8 +15,17 @@
 //! - `get_by_name(name)`: Exact name match only
 //! - `remove(name)`: Removes by name
 //! - `remove_by_id(id)`: Removes by ID
+//!
+//! ## Fingerprint Sharing
+//!
+//! Tokenizers registered on behalf of workers via [`TokenizerRegistry::load_shared`]
+//! are also keyed by a caller-supplied fingerprint of their source. Names
+//! with the same fingerprint share one loaded instance, and each fingerprint
+//! tracks the holders (workers) using it. [`TokenizerRegistry::release`]
+//! drops a holder and evicts the fingerprint's tokenizers once no holder is
+//! left.
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use dashmap::DashMap;
 use thiserror::Error;
@@ -33,6 +42,9 @@ pub enum LoadOutcome {
     Loaded { id: String },
     /// Tokenizer already existed, returning existing ID
     AlreadyExists { id: String },
+    /// Registered under a new name, reusing the tokenizer already loaded
+    /// for the same fingerprint
+    Shared { id: String },
 }
 
 impl LoadOutcome {
@@ -41,6 +53,7 @@ impl LoadOutcome {
         match self {
             LoadOutcome::Loaded { id } => id,
             LoadOutcome::AlreadyExists { id } => id,
+            LoadOutcome::Shared { id } => id,
         }
     }
 
@@ -73,10 +86,19 @@ pub struct TokenizerEntry {
     pub name: String,
     /// Source path or HuggingFace model ID
     pub source: String,
+    /// Source fingerprint, for tokenizers registered via `load_shared`
+    pub fingerprint: Option<String>,
     /// The tokenizer instance
     pub tokenizer: Arc<dyn Tokenizer>,
 }
 
+/// A loaded tokenizer shared by every name with the same fingerprint
+struct SharedTokenizer {
+    tokenizer: Arc<dyn Tokenizer>,
+    /// Holders (e.g. worker URLs) using this fingerprint
+    holders: HashSet<String>,
+}
+
 /// Registry for managing tokenizers keyed by UUID
 ///
 /// Features:
@@ -90,6 +112,10 @@ pub struct TokenizerRegistry {
     name_to_id: DashMap<String, String>,
     /// Per-key locks to prevent duplicate loading
     loading_locks: DashMap<String, Arc<Mutex<()>>>,
+    /// Fingerprint -> shared tokenizer and its holders
+    shared: DashMap<String, SharedTokenizer>,
+    /// Per-fingerprint locks so different names with one fingerprint load once
+    fingerprint_locks: DashMap<String, Arc<Mutex<()>>>,
 }
 
 /// RAII guard that removes the loading lock entry on drop.
@@ -112,6 +138,8 @@ impl TokenizerRegistry {
             tokenizers: DashMap::new(),
             name_to_id: DashMap::new(),
             loading_locks: DashMap::new(),
+            shared: DashMap::new(),
+            fingerprint_locks: DashMap::new(),
         }
     }
 
@@ -196,6 +224,7 @@ impl TokenizerRegistry {
             id: id.to_string(),
             name: name.to_string(),
             source: source.to_string(),
+            fingerprint: None,
             tokenizer,
         };
 
@@ -211,6 +240,181 @@ impl TokenizerRegistry {
         Ok(LoadOutcome::Loaded { id: id.to_string() })
     }
 
+    /// Load and register a tokenizer shared by fingerprint on behalf of `holder`
+    ///
+    /// Like [`Self::load`], but names with the same `fingerprint` reuse one
+    /// loaded tokenizer, so `loader` runs at most once per fingerprint.
+    /// `holder` (e.g. a worker URL) is recorded against the fingerprint that
+    /// serves `name` until [`Self::release`] is called for it.
+    ///
+    /// # Returns
+    /// * `Ok(LoadOutcome::Loaded { id })` - Tokenizer was newly loaded
+    /// * `Ok(LoadOutcome::Shared { id })` - New name, existing tokenizer reused
+    /// * `Ok(LoadOutcome::AlreadyExists { id })` - Name was already registered
+    /// * `Err(LoadError)` - Validation failed or loading failed
+    pub async fn load_shared<F, Fut>(
+        &self,
+        id: &str,
+        name: &str,
+        source: &str,
+        fingerprint: &str,
+        holder: &str,
+        loader: F,
+    ) -> Result<LoadOutcome, LoadError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Arc<dyn Tokenizer>, String>>,
+    {
+        if name.is_empty() {
+            return Err(LoadError::EmptyName);
+        }
+        if source.is_empty() {
+            return Err(LoadError::EmptySource);
+        }
+
+        if let Some(existing_id) = self.hold_existing(name, holder) {
+            debug!("Tokenizer already registered for name: {}", name);
+            return Ok(LoadOutcome::AlreadyExists { id: existing_id });
+        }
+
+        // Lock the name, then the fingerprint
+        let name_lock = self
+            .loading_locks
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        let _name_guard = name_lock.lock().await;
+        let _name_cleanup = LoadingLockGuard {
+            locks: &self.loading_locks,
+            key: name.to_string(),
+        };
+        let fingerprint_lock = self
+            .fingerprint_locks
+            .entry(fingerprint.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        let _fingerprint_guard = fingerprint_lock.lock().await;
+        let _fingerprint_cleanup = LoadingLockGuard {
+            locks: &self.fingerprint_locks,
+            key: fingerprint.to_string(),
+        };
+
+        if let Some(existing_id) = self.hold_existing(name, holder) {
+            debug!("Tokenizer loaded by another thread for name: {}", name);
+            return Ok(LoadOutcome::AlreadyExists { id: existing_id });
+        }
+
+        let existing = self
+            .shared
+            .get(fingerprint)
+            .map(|shared| Arc::clone(&shared.tokenizer));
+        let (tokenizer, outcome) = match existing {
+            Some(tokenizer) => {
+                info!(
+                    "Sharing tokenizer with fingerprint {} for '{}'",
+                    fingerprint, name
+                );
+                (tokenizer, LoadOutcome::Shared { id: id.to_string() })
+            }
+            None => {
+                info!("Loading tokenizer '{}' from source: {}", name, source);
+                let tokenizer = loader().await.map_err(LoadError::LoadFailed)?;
+                (tokenizer, LoadOutcome::Loaded { id: id.to_string() })
+            }
+        };
+
+        self.attach(fingerprint, holder, &tokenizer);
+        self.tokenizers.insert(
+            id.to_string(),
+            TokenizerEntry {
+                id: id.to_string(),
+                name: name.to_string(),
+                source: source.to_string(),
+                fingerprint: Some(fingerprint.to_string()),
+                tokenizer,
+            },
+        );
+        self.name_to_id.insert(name.to_string(), id.to_string());
+
+        info!(
+            "Successfully registered tokenizer '{}' with id: {}",
+            name, id
+        );
+
+        Ok(outcome)
+    }
+
+    /// If `name` is registered, record `holder` against its fingerprint (if
+    /// any) and return its ID.
+    fn hold_existing(&self, name: &str, holder: &str) -> Option<String> {
+        let id = self.name_to_id.get(name)?.clone();
+        if let Some(entry) = self.tokenizers.get(&id) {
+            if let Some(fingerprint) = &entry.fingerprint {
+                self.attach(fingerprint, holder, &entry.tokenizer);
+            }
+        }
+        Some(id)
+    }
+
+    fn attach(&self, fingerprint: &str, holder: &str, tokenizer: &Arc<dyn Tokenizer>) {
+        self.shared
+            .entry(fingerprint.to_string())
+            .or_insert_with(|| SharedTokenizer {
+                tokenizer: Arc::clone(tokenizer),
+                holders: HashSet::new(),
+            })
+            .holders
+            .insert(holder.to_string());
+    }
+
+    /// Release `holder` from every fingerprint it uses
+    ///
+    /// Fingerprints left without holders are evicted along with every
+    /// tokenizer registered under them. Returns the evicted entries.
+    pub fn release(&self, holder: &str) -> Vec<TokenizerEntry> {
+        let mut evicted = HashSet::new();
+        self.shared.retain(|fingerprint, shared| {
+            shared.holders.remove(holder);
+            if shared.holders.is_empty() {
+                evicted.insert(fingerprint.clone());
+                false
+            } else {
+                true
+            }
+        });
+        if evicted.is_empty() {
+            return Vec::new();
+        }
+
+        let ids: Vec<String> = self
+            .tokenizers
+            .iter()
+            .filter(|entry| {
+                entry
+                    .fingerprint
+                    .as_ref()
+                    .is_some_and(|fp| evicted.contains(fp))
+            })
+            .map(|entry| entry.id.clone())
+            .collect();
+        let removed: Vec<TokenizerEntry> =
+            ids.iter().filter_map(|id| self.remove_by_id(id)).collect();
+        for entry in &removed {
+            info!(
+                "Evicted tokenizer '{}' (id: {}): no workers left using it",
+                entry.name, entry.id
+            );
+        }
+        removed
+    }
+
+    /// Number of holders using `fingerprint`
+    pub fn holder_count(&self, fingerprint: &str) -> usize {
+        self.shared
+            .get(fingerprint)
+            .map_or(0, |shared| shared.holders.len())
+    }
+
     /// Register a preloaded tokenizer with a pre-generated ID
     ///
     /// Atomically inserts a tokenizer into the registry only if no tokenizer
@@ -245,6 +449,7 @@ impl TokenizerRegistry {
                     id: id.to_string(),
                     name: name.to_string(),
                     source: source.to_string(),
+                    fingerprint: None,
                     tokenizer,
                 };
 
@@ -331,6 +536,8 @@ impl TokenizerRegistry {
         self.tokenizers.clear();
         self.name_to_id.clear();
         self.loading_locks.clear();
+        self.shared.clear();
+        self.fingerprint_locks.clear();
     }
 }
 
@@ -356,7 +563,9 @@ mod tests {
 
     use tokio::time::sleep;
 
-    use crate::{mock::MockTokenizer, traits::Tokenizer, LoadError, TokenizerRegistry};
+    use crate::{
+        mock::MockTokenizer, registry::LoadOutcome, traits::Tokenizer, LoadError, TokenizerRegistry,
+    };
 
     #[tokio::test]
     async fn test_basic_operations() {
@@ -683,4 +892,73 @@ mod tests {
         assert!(!outcome.is_newly_loaded());
         assert_eq!(outcome.id(), id1); // Returns original ID
     }
+
+    #[tokio::test]
+    async fn test_load_shared_loads_each_fingerprint_once() {
+        let registry = TokenizerRegistry::new();
+        let load_count = AtomicUsize::new(0);
+        let counter = &load_count;
+        let loader = move || async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new(MockTokenizer::default()) as Arc<dyn Tokenizer>)
+        };
+
+        let first = registry
+            .load_shared("id-a", "model-a", "hf/model", "fp1", "http://w1", loader)
+            .await
+            .unwrap();
+        assert!(first.is_newly_loaded());
+
+        // Same name from another worker: no load, holder recorded.
+        let again = registry
+            .load_shared("id-x", "model-a", "hf/model", "fp1", "http://w2", loader)
+            .await
+            .unwrap();
+        assert_eq!(again.id(), "id-a");
+
+        // Different name, same fingerprint: reuses the loaded tokenizer.
+        let shared = registry
+            .load_shared("id-b", "model-b", "hf/model", "fp1", "http://w3", loader)
+            .await
+            .unwrap();
+        assert!(matches!(shared, LoadOutcome::Shared { .. }));
+
+        assert_eq!(load_count.load(Ordering::SeqCst), 1);
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.holder_count("fp1"), 3);
+        assert!(Arc::ptr_eq(
+            &registry.get("model-a").unwrap(),
+            &registry.get("model-b").unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_release_evicts_after_last_holder() {
+        let registry = TokenizerRegistry::new();
+        for (name, holder) in [("model-a", "http://w1"), ("model-b", "http://w2")] {
+            registry
+                .load_shared(name, name, "hf/model", "fp1", holder, || async {
+                    Ok(Arc::new(MockTokenizer::default()) as Arc<dyn Tokenizer>)
+                })
+                .await
+                .unwrap();
+        }
+        let manual = TokenizerRegistry::generate_id();
+        registry
+            .load(&manual, "manual", "hf/model", || async {
+                Ok(Arc::new(MockTokenizer::default()) as Arc<dyn Tokenizer>)
+            })
+            .await
+            .unwrap();
+
+        assert!(registry.release("http://w1").is_empty());
+        assert_eq!(registry.len(), 3);
+
+        let evicted = registry.release("http://w2");
+        assert_eq!(evicted.len(), 2);
+        assert_eq!(registry.holder_count("fp1"), 0);
+        // Tokenizers registered without a fingerprint are never evicted.
+        assert_eq!(registry.len(), 1);
+        assert!(registry.contains("manual"));
+    }
 }
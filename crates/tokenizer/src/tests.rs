@@ -14,6 +14,14 @@ fn test_mock_tokenizer_encode() {
     assert_eq!(token_ids, &[1, 2]); // "Hello" -> 1, "world" -> 2
 }
 
+#[test]
+fn test_default_encode_pair_concatenates_segments() {
+    let tokenizer = mock::MockTokenizer::new();
+    let encoding = tokenizer.encode_pair("Hello", "world", true).unwrap();
+    assert_eq!(encoding.token_ids(), &[1, 2]);
+    assert!(encoding.type_ids().is_empty());
+}
+
 #[test]
 fn test_mock_tokenizer_decode() {
     let tokenizer = mock::MockTokenizer::new();
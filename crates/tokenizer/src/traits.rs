@@ -16,6 +16,29 @@ pub type TokenIdType = u32;
 pub trait Encoder: Send + Sync {
     fn encode(&self, input: &str, add_special_tokens: bool) -> Result<Encoding>;
     fn encode_batch(&self, inputs: &[&str], add_special_tokens: bool) -> Result<Vec<Encoding>>;
+
+    /// Encode a sequence pair (e.g. query/document for cross-encoders).
+    ///
+    /// The default concatenates the two encodings with no separator; tokenizers
+    /// with a pair post-processor (BERT-style `[CLS] a [SEP] b [SEP]`) override
+    /// this to apply it.
+    fn encode_pair(&self, first: &str, second: &str, add_special_tokens: bool) -> Result<Encoding> {
+        let first = self.encode(first, add_special_tokens)?;
+        let second = self.encode(second, false)?;
+        Ok(Encoding::Plain(
+            [first.token_ids(), second.token_ids()].concat(),
+        ))
+    }
+
+    /// Encode a long input, spreading the work over the current rayon pool
+    /// where the tokenizer can split it without changing the result.
+    ///
+    /// Returns the same token IDs as [`Encoder::encode`]. The default encodes
+    /// in one piece; [`crate::parallel::encode_chunked`] implements the split
+    /// for tokenizers whose special tokens are atomic.
+    fn encode_parallel(&self, input: &str, add_special_tokens: bool) -> Result<Encoding> {
+        self.encode(input, add_special_tokens)
+    }
 }
 
 /// Core decoding trait - can be implemented independently
@@ -155,6 +178,15 @@ impl Encoding {
         }
     }
 
+    /// Segment (token type) IDs, empty when the tokenizer doesn't produce them.
+    /// Only meaningful for pair encodings.
+    pub fn type_ids(&self) -> &[TokenIdType] {
+        match self {
+            Encoding::Hf(inner) => inner.get_type_ids(),
+            Encoding::Plain(_) | Encoding::Tiktoken(_) => &[],
+        }
+    }
+
     /// Get a hash of the token IDs for caching purposes
     pub fn get_hash(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
@@ -185,3 +217,23 @@ pub struct SpecialTokens {
     pub mask_token: Option<String>,
     pub additional_special_tokens: Vec<String>,
 }
+
+impl SpecialTokens {
+    /// Every special token string: the named tokens, then the additional ones.
+    pub fn strings(&self) -> Vec<&str> {
+        [
+            &self.bos_token,
+            &self.eos_token,
+            &self.unk_token,
+            &self.sep_token,
+            &self.pad_token,
+            &self.cls_token,
+            &self.mask_token,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(&self.additional_special_tokens)
+        .map(String::as_str)
+        .collect()
+    }
+}
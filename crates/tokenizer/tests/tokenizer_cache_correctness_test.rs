@@ -462,3 +462,40 @@ async fn test_cache_correctness_with_edge_cases() {
 
     println!("\n✓ All {test_count} edge cases passed!");
 }
+
+#[tokio::test]
+#[expect(clippy::print_stdout, reason = "test diagnostic output")]
+async fn test_parallel_encode_produces_identical_tokens() {
+    let tokenizer_path = match get_tokenizer_path().await {
+        Some(path) => path,
+        None => {
+            println!("Skipping test - tokenizer not available");
+            return;
+        }
+    };
+
+    let base_tokenizer = Arc::new(
+        HuggingFaceTokenizer::from_file(tokenizer_path.to_str().unwrap())
+            .expect("Failed to load base tokenizer"),
+    );
+    let cached_tokenizer = CachedTokenizer::new(
+        base_tokenizer.clone(),
+        CacheConfig {
+            enable_l1: true,
+            ..CacheConfig::default()
+        },
+    );
+
+    // A long multi-turn prompt, large enough to be split into several chunks.
+    let prompt = CHAT_TURNS.concat().repeat(8);
+    let expected = base_tokenizer.encode(&prompt, false).unwrap();
+
+    let parallel = base_tokenizer.encode_parallel(&prompt, false).unwrap();
+    assert_eq!(parallel.token_ids(), expected.token_ids());
+
+    // Cold (L1 miss, segments encoded in parallel) and warm.
+    for _ in 0..2 {
+        let cached = cached_tokenizer.encode_parallel(&prompt, false).unwrap();
+        assert_eq!(cached.token_ids(), expected.token_ids());
+    }
+}
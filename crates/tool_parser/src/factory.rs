@@ -50,6 +50,28 @@ impl ToolConstraint {
     pub fn is_json_schema(&self) -> bool {
         matches!(self, ToolConstraint::JsonSchema(_))
     }
+
+    /// Restrict the constraint to a single tool call, for requests with
+    /// `parallel_tool_calls: false`. Only the generic tool-call array schema
+    /// can express this (`maxItems: 1`); a single-function schema already
+    /// yields one call and structural tags are returned unchanged.
+    pub fn single_call(self) -> Self {
+        let ToolConstraint::JsonSchema(schema) = &self else {
+            return self;
+        };
+        match serde_json::from_str::<serde_json::Value>(schema) {
+            Ok(serde_json::Value::Object(mut obj))
+                if obj.get("type").and_then(|t| t.as_str()) == Some("array") =>
+            {
+                obj.insert("maxItems".to_string(), json!(1));
+                match serde_json::to_string(&obj) {
+                    Ok(schema) => ToolConstraint::JsonSchema(schema),
+                    Err(_) => self,
+                }
+            }
+            _ => self,
+        }
+    }
 }
 
 /// Registration entry for a parser: creator + optional structural tag builder.
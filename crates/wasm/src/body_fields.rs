@@ -0,0 +1,370 @@
+//! Request Body Field Extraction
+//!
+//! Modules that declare `request_body_fields` (JSON pointers such as
+//! `/model`) run headers-only: instead of the request body they receive
+//! just those values. The extractor scans the JSON body incrementally so
+//! the host can stop reading once every declared field has been seen.
+
+use std::collections::HashMap;
+
+use crate::{module::WasmModuleMeta, spec::smg::gateway::middleware_types::BodyField};
+
+/// Most fields a module may declare.
+pub const MAX_BODY_FIELDS: usize = 32;
+
+/// Check declared fields are non-root JSON pointers with valid escapes.
+pub fn validate_body_fields(pointers: &[String]) -> Result<(), String> {
+    if pointers.len() > MAX_BODY_FIELDS {
+        return Err(format!(
+            "request_body_fields cannot declare more than {MAX_BODY_FIELDS} fields"
+        ));
+    }
+    for pointer in pointers {
+        if parse_pointer(pointer).is_none() {
+            return Err(format!(
+                "request_body_fields entry '{pointer}' is not a JSON pointer below the root (e.g. /model)"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Split a pointer into unescaped reference tokens; `None` for the root
+/// pointer or malformed ones.
+fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    let rest = pointer.strip_prefix('/')?;
+    rest.split('/')
+        .map(|token| {
+            let mut out = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some('0') => out.push('~'),
+                        Some('1') => out.push('/'),
+                        _ => return None,
+                    },
+                    c => out.push(c),
+                }
+            }
+            Some(out)
+        })
+        .collect()
+}
+
+/// Body and fields passed to a module: headers-only modules get an empty
+/// body and their declared fields, others the whole body.
+pub fn request_body_for_module(meta: &WasmModuleMeta, body: &[u8]) -> (Vec<u8>, Vec<BodyField>) {
+    match &meta.request_body_fields {
+        Some(pointers) => {
+            let mut extractor = BodyFieldExtractor::new(pointers);
+            extractor.feed(body);
+            (Vec::new(), extractor.fields(pointers))
+        }
+        None => (body.to_vec(), Vec::new()),
+    }
+}
+
+enum Frame {
+    Object {
+        key: Option<String>,
+        expect_key: bool,
+    },
+    Array {
+        index: usize,
+    },
+}
+
+struct Capture {
+    target: usize,
+    depth: usize,
+    buf: Vec<u8>,
+}
+
+/// Incremental JSON scanner that captures the values at a set of pointers.
+///
+/// Feed body chunks in order until [`Self::is_done`]; values are recorded
+/// as compact JSON text. A body that is not JSON yields no values.
+pub struct BodyFieldExtractor {
+    targets: Vec<(String, Vec<String>)>,
+    found: HashMap<String, String>,
+    stack: Vec<Frame>,
+    in_string: bool,
+    escaped: bool,
+    /// Raw bytes of the object key being read, quotes included
+    key: Option<Vec<u8>>,
+    in_scalar: bool,
+    capture: Option<Capture>,
+    done: bool,
+}
+
+impl BodyFieldExtractor {
+    pub fn new<'a>(pointers: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut targets: Vec<(String, Vec<String>)> = Vec::new();
+        for pointer in pointers {
+            if targets.iter().any(|(p, _)| p == pointer) {
+                continue;
+            }
+            if let Some(segments) = parse_pointer(pointer) {
+                targets.push((pointer.clone(), segments));
+            }
+        }
+        let done = targets.is_empty();
+        Self {
+            targets,
+            found: HashMap::new(),
+            stack: Vec::new(),
+            in_string: false,
+            escaped: false,
+            key: None,
+            in_scalar: false,
+            capture: None,
+            done,
+        }
+    }
+
+    /// Every field was found, or the body's top-level value ended.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Scan the next chunk of the body. Returns [`Self::is_done`].
+    pub fn feed(&mut self, chunk: &[u8]) -> bool {
+        for &b in chunk {
+            if self.done {
+                break;
+            }
+            self.step(b);
+        }
+        self.done
+    }
+
+    /// Values of `pointers`, in order; `None` where the body has no value.
+    pub fn fields(&self, pointers: &[String]) -> Vec<BodyField> {
+        pointers
+            .iter()
+            .map(|pointer| BodyField {
+                pointer: pointer.clone(),
+                value: self.found.get(pointer).cloned(),
+            })
+            .collect()
+    }
+
+    fn step(&mut self, b: u8) {
+        if self.in_string {
+            if let Some(key) = &mut self.key {
+                key.push(b);
+            }
+            self.push_capture(b);
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+                match self.key.take() {
+                    Some(raw) => {
+                        if let Some(Frame::Object { key, expect_key }) = self.stack.last_mut() {
+                            *key = serde_json::from_slice(&raw).ok();
+                            *expect_key = false;
+                        }
+                    }
+                    None => self.value_end(),
+                }
+            }
+            return;
+        }
+
+        if self.in_scalar {
+            if b.is_ascii_whitespace() || matches!(b, b',' | b'}' | b']') {
+                self.in_scalar = false;
+                self.value_end();
+                if self.done {
+                    return;
+                }
+            } else {
+                self.push_capture(b);
+                return;
+            }
+        }
+
+        match b {
+            b'"' => {
+                self.in_string = true;
+                if matches!(
+                    self.stack.last(),
+                    Some(Frame::Object {
+                        expect_key: true,
+                        ..
+                    })
+                ) {
+                    self.key = Some(vec![b]);
+                } else {
+                    self.value_start();
+                    self.push_capture(b);
+                }
+            }
+            b'{' | b'[' => {
+                self.value_start();
+                self.push_capture(b);
+                self.stack.push(if b == b'{' {
+                    Frame::Object {
+                        key: None,
+                        expect_key: true,
+                    }
+                } else {
+                    Frame::Array { index: 0 }
+                });
+            }
+            b'}' | b']' => {
+                self.push_capture(b);
+                self.stack.pop();
+                self.value_end();
+            }
+            b',' => {
+                self.push_capture(b);
+                match self.stack.last_mut() {
+                    Some(Frame::Object { expect_key, .. }) => *expect_key = true,
+                    Some(Frame::Array { index }) => *index += 1,
+                    None => {}
+                }
+            }
+            _ if b == b':' || b.is_ascii_whitespace() => self.push_capture(b),
+            _ => {
+                self.value_start();
+                self.in_scalar = true;
+                self.push_capture(b);
+            }
+        }
+    }
+
+    fn push_capture(&mut self, b: u8) {
+        if let Some(capture) = &mut self.capture {
+            capture.buf.push(b);
+        }
+    }
+
+    fn value_start(&mut self) {
+        if self.capture.is_some() {
+            return;
+        }
+        let depth = self.stack.len();
+        let target = self.targets.iter().position(|(pointer, segments)| {
+            segments.len() == depth
+                && !self.found.contains_key(pointer)
+                && self
+                    .stack
+                    .iter()
+                    .zip(segments)
+                    .all(|(frame, segment)| match frame {
+                        Frame::Object { key, .. } => key.as_deref() == Some(segment.as_str()),
+                        Frame::Array { index } => *segment == index.to_string(),
+                    })
+        });
+        if let Some(target) = target {
+            self.capture = Some(Capture {
+                target,
+                depth,
+                buf: Vec::new(),
+            });
+        }
+    }
+
+    fn value_end(&mut self) {
+        if self
+            .capture
+            .as_ref()
+            .is_some_and(|c| c.depth == self.stack.len())
+        {
+            if let Some(capture) = self.capture.take() {
+                if let Ok(value) = serde_json::from_slice::<serde_json::Value>(&capture.buf) {
+                    let pointer = self.targets[capture.target].0.clone();
+                    self.found.insert(pointer, value.to_string());
+                }
+            }
+        }
+        if self.stack.is_empty() || self.found.len() == self.targets.len() {
+            self.done = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pointers(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn values(extractor: &BodyFieldExtractor, list: &[String]) -> Vec<Option<String>> {
+        extractor
+            .fields(list)
+            .into_iter()
+            .map(|field| field.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_extracts_fields_across_chunk_boundaries() {
+        let body = br#"{"model": "gpt-4", "user": {"id": 42, "tags": ["a", "b\"c"]}, "messages": [{"role": "user"}]}"#;
+        let list = pointers(&["/model", "/user/tags/1", "/messages/0", "/missing"]);
+        for chunk_size in [1, 3, 7, body.len()] {
+            let mut extractor = BodyFieldExtractor::new(&list);
+            for chunk in body.chunks(chunk_size) {
+                extractor.feed(chunk);
+            }
+            assert!(extractor.is_done());
+            assert_eq!(
+                values(&extractor, &list),
+                vec![
+                    Some(r#""gpt-4""#.to_string()),
+                    Some(r#""b\"c""#.to_string()),
+                    Some(r#"{"role":"user"}"#.to_string()),
+                    None,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_stops_once_all_fields_found() {
+        let list = pointers(&["/model", "/stream"]);
+        let mut extractor = BodyFieldExtractor::new(&list);
+        assert!(!extractor.feed(br#"{"model": "m", "stream": true"#));
+        // A scalar ends at the next delimiter
+        assert!(extractor.feed(b", \"messages\": ["));
+        assert_eq!(
+            values(&extractor, &list),
+            vec![Some(r#""m""#.to_string()), Some("true".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_nested_keys_do_not_match_top_level_pointer() {
+        let list = pointers(&["/model"]);
+        let mut extractor = BodyFieldExtractor::new(&list);
+        extractor.feed(br#"{"meta": {"model": "inner"}, "model": "outer"}"#);
+        assert_eq!(
+            values(&extractor, &list),
+            vec![Some(r#""outer""#.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_non_json_body_yields_nothing() {
+        let list = pointers(&["/model"]);
+        let mut extractor = BodyFieldExtractor::new(&list);
+        extractor.feed(b"plain text body");
+        assert_eq!(values(&extractor, &list), vec![None]);
+    }
+
+    #[test]
+    fn test_validate_body_fields() {
+        assert!(validate_body_fields(&pointers(&["/model", "/a~1b/0"])).is_ok());
+        assert!(validate_body_fields(&pointers(&[""])).is_err());
+        assert!(validate_body_fields(&pointers(&["model"])).is_err());
+        assert!(validate_body_fields(&pointers(&["/bad~2escape"])).is_err());
+        assert_eq!(parse_pointer("/a~1b/~0"), Some(pointers(&["a/b", "~"])));
+    }
+}
@@ -19,6 +19,38 @@ pub struct WasmRuntimeConfig {
     pub module_cache_size: usize,
     /// Maximum HTTP body size in bytes for middleware request/response processing
     pub max_body_size: usize,
+    /// Per-event time budget in milliseconds for OnResponseChunk modules.
+    /// Events whose module overruns the budget are forwarded unchanged.
+    #[serde(default = "default_max_chunk_execution_time_ms")]
+    pub max_chunk_execution_time_ms: u64,
+    /// Guest log messages each module may emit per second through the
+    /// `host-logging` import; excess messages are dropped and counted.
+    /// 0 drops all guest logs.
+    #[serde(default = "default_max_guest_logs_per_second")]
+    pub max_guest_logs_per_second: u32,
+    /// Byte quota (keys plus values) of each module's `host-kv` namespace.
+    #[serde(default = "default_max_guest_kv_bytes_per_namespace")]
+    pub max_guest_kv_bytes_per_namespace: usize,
+    /// TTL ceiling in seconds for `host-kv` entries; longer or unset TTLs
+    /// are capped to it.
+    #[serde(default = "default_max_guest_kv_ttl_secs")]
+    pub max_guest_kv_ttl_secs: u64,
+}
+
+fn default_max_chunk_execution_time_ms() -> u64 {
+    50
+}
+
+fn default_max_guest_logs_per_second() -> u32 {
+    100
+}
+
+fn default_max_guest_kv_bytes_per_namespace() -> usize {
+    1024 * 1024
+}
+
+fn default_max_guest_kv_ttl_secs() -> u64 {
+    3600
 }
 
 impl Default for WasmRuntimeConfig {
@@ -29,12 +61,16 @@ impl Default for WasmRuntimeConfig {
             .clamp(1, 4);
 
         Self {
-            max_memory_pages: 1024,                     // 64MB
-            max_execution_time_ms: 1000,                // 1 seconds
-            max_stack_size: 1024 * 1024,                // 1MB
+            max_memory_pages: 1024,                                             // 64MB
+            max_execution_time_ms: 1000,                                        // 1 seconds
+            max_stack_size: 1024 * 1024,                                        // 1MB
             thread_pool_size: default_thread_pool_size, // based on cpu count and capped
             module_cache_size: 10,                      // Cache up to 10 modules per worker
             max_body_size: 10 * 1024 * 1024,            // 10MB
+            max_chunk_execution_time_ms: default_max_chunk_execution_time_ms(), // 50ms
+            max_guest_logs_per_second: default_max_guest_logs_per_second(), // 100/s
+            max_guest_kv_bytes_per_namespace: default_max_guest_kv_bytes_per_namespace(), // 1MB
+            max_guest_kv_ttl_secs: default_max_guest_kv_ttl_secs(), // 1 hour
         }
     }
 }
@@ -93,10 +129,39 @@ impl WasmRuntimeConfig {
             return Err("max_body_size cannot exceed 100MB".to_string());
         }
 
+        // Validate max_chunk_execution_time_ms
+        if self.max_chunk_execution_time_ms == 0 {
+            return Err("max_chunk_execution_time_ms cannot be 0".to_string());
+        }
+        if self.max_chunk_execution_time_ms > self.max_execution_time_ms {
+            return Err(
+                "max_chunk_execution_time_ms cannot exceed max_execution_time_ms".to_string(),
+            );
+        }
+
+        // Validate max_guest_logs_per_second
+        if self.max_guest_logs_per_second > 10000 {
+            return Err("max_guest_logs_per_second cannot exceed 10000".to_string());
+        }
+
+        // Validate max_guest_kv_bytes_per_namespace
+        if self.max_guest_kv_bytes_per_namespace > 256 * 1024 * 1024 {
+            return Err("max_guest_kv_bytes_per_namespace cannot exceed 256MB".to_string());
+        }
+
+        // Validate max_guest_kv_ttl_secs
+        if self.max_guest_kv_ttl_secs == 0 {
+            return Err("max_guest_kv_ttl_secs cannot be 0".to_string());
+        }
+        if self.max_guest_kv_ttl_secs > 7 * 24 * 3600 {
+            return Err("max_guest_kv_ttl_secs cannot exceed 604800 (7 days)".to_string());
+        }
+
         Ok(())
     }
 
     /// Create a new config with validation
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         max_memory_pages: u32,
         max_execution_time_ms: u64,
@@ -104,6 +169,10 @@ impl WasmRuntimeConfig {
         thread_pool_size: usize,
         module_cache_size: usize,
         max_body_size: usize,
+        max_chunk_execution_time_ms: u64,
+        max_guest_logs_per_second: u32,
+        max_guest_kv_bytes_per_namespace: usize,
+        max_guest_kv_ttl_secs: u64,
     ) -> Result<Self, String> {
         let config = Self {
             max_memory_pages,
@@ -112,6 +181,10 @@ impl WasmRuntimeConfig {
             thread_pool_size,
             module_cache_size,
             max_body_size,
+            max_chunk_execution_time_ms,
+            max_guest_logs_per_second,
+            max_guest_kv_bytes_per_namespace,
+            max_guest_kv_ttl_secs,
         };
         config.validate()?;
         Ok(config)
@@ -135,7 +208,18 @@ mod tests {
 
     #[test]
     fn test_config_new_with_validation() {
-        let config = WasmRuntimeConfig::new(1024, 1000, 1024 * 1024, 2, 10, 10 * 1024 * 1024);
+        let config = WasmRuntimeConfig::new(
+            1024,
+            1000,
+            1024 * 1024,
+            2,
+            10,
+            10 * 1024 * 1024,
+            50,
+            100,
+            1024 * 1024,
+            3600,
+        );
         assert!(config.is_ok());
     }
 
@@ -148,6 +232,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -163,6 +251,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -180,6 +272,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -197,6 +293,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -214,6 +314,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -231,6 +335,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -248,6 +356,10 @@ mod tests {
             thread_pool_size: 0,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -263,6 +375,10 @@ mod tests {
             thread_pool_size: 129, // Exceeds 128
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -280,6 +396,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 0,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -297,6 +417,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 1001, // Exceeds 1000
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -314,6 +438,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 10 * 1024 * 1024,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         // 1024 pages * 64KB = 64MB
         assert_eq!(config.get_total_memory_bytes(), 64 * 1024 * 1024);
@@ -328,6 +456,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 0,
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -343,6 +475,10 @@ mod tests {
             thread_pool_size: 2,
             module_cache_size: 10,
             max_body_size: 101 * 1024 * 1024, // Exceeds 100MB
+            max_chunk_execution_time_ms: 50,
+            max_guest_logs_per_second: 100,
+            max_guest_kv_bytes_per_namespace: 1024 * 1024,
+            max_guest_kv_ttl_secs: 3600,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -350,4 +486,49 @@ mod tests {
             .unwrap_err()
             .contains("max_body_size cannot exceed 100MB"));
     }
+
+    #[test]
+    fn test_validation_chunk_budget_exceeds_execution_time() {
+        let config = WasmRuntimeConfig {
+            max_execution_time_ms: 100,
+            max_chunk_execution_time_ms: 200,
+            ..WasmRuntimeConfig::default()
+        };
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("max_chunk_execution_time_ms cannot exceed max_execution_time_ms"));
+    }
+
+    #[test]
+    fn test_validation_max_guest_logs_per_second_too_large() {
+        let config = WasmRuntimeConfig {
+            max_guest_logs_per_second: 10001,
+            ..WasmRuntimeConfig::default()
+        };
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("max_guest_logs_per_second cannot exceed 10000"));
+    }
+
+    #[test]
+    fn test_validation_max_guest_kv_ttl_secs() {
+        let config = WasmRuntimeConfig {
+            max_guest_kv_ttl_secs: 0,
+            ..WasmRuntimeConfig::default()
+        };
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("max_guest_kv_ttl_secs cannot be 0"));
+
+        let config = WasmRuntimeConfig {
+            max_guest_kv_ttl_secs: 7 * 24 * 3600 + 1,
+            ..WasmRuntimeConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
 }
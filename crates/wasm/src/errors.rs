@@ -106,6 +106,13 @@ pub enum WasmRuntimeError {
     #[error("execution timeout after {0}ms")]
     Timeout(u64),
 
+    #[error("wasm trap: {message}")]
+    Trap {
+        message: String,
+        /// WASM backtrace captured at the trap, when available
+        backtrace: Option<String>,
+    },
+
     #[error("execution failed: {0}")]
     CallFailed(String),
 }
@@ -0,0 +1,308 @@
+//! Guest Key-Value Store
+//!
+//! Host side of the `host-kv` import. The host, not the guest, decides which
+//! namespace a call operates on: every module gets `<tenant>/<module name>`
+//! (tenant `default` when the module declares none), so a module can never
+//! read or overwrite another module's keys, whatever key names it tries.
+//!
+//! Each namespace has a byte quota (keys plus values) and every entry expires
+//! after at most the configured TTL ceiling. Expired entries are purged
+//! lazily on access and before a write is rejected for quota.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    spec::smg::gateway::host_kv::{self, KvError},
+    types::WasiState,
+};
+
+/// Longest key accepted from a guest.
+pub const MAX_GUEST_KV_KEY_BYTES: usize = 256;
+
+/// Tenant used for modules registered without one.
+pub const DEFAULT_KV_TENANT: &str = "default";
+
+/// Validate a module's declared tenant: 1-64 characters of
+/// `[A-Za-z0-9_.-]`, so it cannot contain the namespace separator.
+pub fn validate_tenant(tenant: &str) -> Result<(), String> {
+    let valid = !tenant.is_empty()
+        && tenant.len() <= 64
+        && tenant
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid tenant '{tenant}': must be 1-64 characters of [A-Za-z0-9_.-]"
+        ))
+    }
+}
+
+/// Namespace the host scopes a module's KV calls to.
+pub fn kv_namespace(tenant: Option<&str>, module_name: &str) -> String {
+    format!("{}/{module_name}", tenant.unwrap_or(DEFAULT_KV_TENANT))
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Instant,
+}
+
+impl Entry {
+    fn size(&self, key: &str) -> usize {
+        key.len() + self.value.len()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Namespace {
+    entries: HashMap<String, Entry>,
+    used_bytes: usize,
+}
+
+impl Namespace {
+    fn purge_expired(&mut self, now: Instant) {
+        let mut freed = 0;
+        self.entries.retain(|key, entry| {
+            let live = entry.expires_at > now;
+            if !live {
+                freed += entry.size(key);
+            }
+            live
+        });
+        self.used_bytes -= freed;
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.used_bytes -= entry.size(key);
+        Some(entry)
+    }
+}
+
+/// KV state of every namespace, shared by all worker threads.
+#[derive(Debug)]
+pub struct GuestKvStore {
+    max_bytes_per_namespace: usize,
+    max_ttl: Duration,
+    namespaces: Mutex<HashMap<Arc<str>, Namespace>>,
+}
+
+impl GuestKvStore {
+    pub fn new(max_bytes_per_namespace: usize, max_ttl: Duration) -> Self {
+        Self {
+            max_bytes_per_namespace,
+            max_ttl,
+            namespaces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, namespace: &str, key: &str) -> Option<Vec<u8>> {
+        self.get_at(namespace, key, Instant::now())
+    }
+
+    /// Store `value` under `key`. `ttl` is capped at the ceiling; `None`
+    /// uses the ceiling.
+    pub fn set(
+        &self,
+        namespace: &Arc<str>,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<(), KvError> {
+        self.set_at(namespace, key, value, ttl, Instant::now())
+    }
+
+    /// Returns whether a live entry was removed.
+    pub fn delete(&self, namespace: &str, key: &str) -> bool {
+        self.delete_at(namespace, key, Instant::now())
+    }
+
+    /// Bytes currently held by `namespace`, including not yet purged
+    /// expired entries.
+    pub fn used_bytes(&self, namespace: &str) -> usize {
+        self.lock().get(namespace).map_or(0, |ns| ns.used_bytes)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Arc<str>, Namespace>> {
+        self.namespaces.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get_at(&self, namespace: &str, key: &str, now: Instant) -> Option<Vec<u8>> {
+        let mut namespaces = self.lock();
+        let ns = namespaces.get_mut(namespace)?;
+        match ns.entries.get(key) {
+            Some(entry) if entry.expires_at > now => Some(entry.value.clone()),
+            Some(_) => {
+                ns.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn set_at(
+        &self,
+        namespace: &Arc<str>,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Option<Duration>,
+        now: Instant,
+    ) -> Result<(), KvError> {
+        if key.is_empty() || key.len() > MAX_GUEST_KV_KEY_BYTES {
+            return Err(KvError::InvalidKey);
+        }
+        let size = key.len() + value.len();
+        if size > self.max_bytes_per_namespace {
+            return Err(KvError::ValueTooLarge);
+        }
+        let ttl = ttl.map_or(self.max_ttl, |ttl| ttl.min(self.max_ttl));
+
+        let mut namespaces = self.lock();
+        let ns = namespaces.entry(namespace.clone()).or_default();
+        let replaced = ns.entries.get(key).map_or(0, |entry| entry.size(key));
+        if ns.used_bytes - replaced + size > self.max_bytes_per_namespace {
+            ns.purge_expired(now);
+            let replaced = ns.entries.get(key).map_or(0, |entry| entry.size(key));
+            if ns.used_bytes - replaced + size > self.max_bytes_per_namespace {
+                return Err(KvError::QuotaExceeded);
+            }
+        }
+        ns.remove(key);
+        ns.used_bytes += size;
+        ns.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at: now + ttl,
+            },
+        );
+        Ok(())
+    }
+
+    fn delete_at(&self, namespace: &str, key: &str, now: Instant) -> bool {
+        let mut namespaces = self.lock();
+        let Some(ns) = namespaces.get_mut(namespace) else {
+            return false;
+        };
+        ns.remove(key).is_some_and(|entry| entry.expires_at > now)
+    }
+}
+
+/// Binds a store's KV calls to the executing module's namespace.
+pub struct GuestKvContext {
+    pub namespace: Arc<str>,
+    pub store: Arc<GuestKvStore>,
+}
+
+impl host_kv::Host for WasiState {
+    async fn get(&mut self, key: String) -> wasmtime::Result<Option<Vec<u8>>> {
+        Ok(self.guest_kv.store.get(&self.guest_kv.namespace, &key))
+    }
+
+    async fn set(
+        &mut self,
+        key: String,
+        value: Vec<u8>,
+        ttl_ms: Option<u64>,
+    ) -> wasmtime::Result<Result<(), KvError>> {
+        Ok(self.guest_kv.store.set(
+            &self.guest_kv.namespace,
+            &key,
+            value,
+            ttl_ms.map(Duration::from_millis),
+        ))
+    }
+
+    async fn delete(&mut self, key: String) -> wasmtime::Result<bool> {
+        Ok(self.guest_kv.store.delete(&self.guest_kv.namespace, &key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ns(name: &str) -> Arc<str> {
+        Arc::from(name)
+    }
+
+    #[test]
+    fn test_namespaces_are_isolated() {
+        let store = GuestKvStore::new(1024, Duration::from_secs(60));
+        let team_a = ns(&kv_namespace(Some("team-a"), "limiter"));
+        let team_b = ns(&kv_namespace(Some("team-b"), "limiter"));
+        assert_eq!(&*team_a, "team-a/limiter");
+        assert_eq!(kv_namespace(None, "limiter"), "default/limiter");
+
+        store.set(&team_a, "count", b"1".to_vec(), None).unwrap();
+        assert_eq!(store.get(&team_a, "count"), Some(b"1".to_vec()));
+        assert_eq!(store.get(&team_b, "count"), None);
+        assert!(!store.delete(&team_b, "count"));
+        assert!(store.delete(&team_a, "count"));
+        assert_eq!(store.used_bytes(&team_a), 0);
+    }
+
+    #[test]
+    fn test_quota_counts_keys_and_values() {
+        let store = GuestKvStore::new(16, Duration::from_secs(60));
+        let a = ns("t/a");
+        store.set(&a, "k1", vec![0; 6], None).unwrap();
+        assert_eq!(store.used_bytes(&a), 8);
+        assert_eq!(
+            store.set(&a, "k2", vec![0; 7], None),
+            Err(KvError::QuotaExceeded)
+        );
+        // Overwriting an entry reuses its bytes
+        store.set(&a, "k1", vec![0; 14], None).unwrap();
+        assert_eq!(store.used_bytes(&a), 16);
+        assert_eq!(
+            store.set(&a, "k3", vec![0; 20], None),
+            Err(KvError::ValueTooLarge)
+        );
+        assert_eq!(store.set(&a, "", vec![], None), Err(KvError::InvalidKey));
+        let long_key = "k".repeat(MAX_GUEST_KV_KEY_BYTES + 1);
+        assert_eq!(
+            GuestKvStore::new(4096, Duration::from_secs(1)).set(&a, &long_key, vec![], None),
+            Err(KvError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn test_ttl_is_capped_and_expired_entries_free_quota() {
+        let store = GuestKvStore::new(16, Duration::from_secs(10));
+        let a = ns("t/a");
+        let start = Instant::now();
+        store
+            .set_at(&a, "k1", vec![0; 6], Some(Duration::from_secs(3600)), start)
+            .unwrap();
+        let before_ceiling = start + Duration::from_secs(9);
+        assert!(store.get_at(&a, "k1", before_ceiling).is_some());
+
+        let after_ceiling = start + Duration::from_secs(11);
+        assert_eq!(store.get_at(&a, "k1", after_ceiling), None);
+        assert_eq!(store.used_bytes(&a), 0);
+
+        store
+            .set_at(&a, "k2", vec![0; 14], Some(Duration::from_secs(1)), start)
+            .unwrap();
+        store
+            .set_at(&a, "k3", vec![0; 14], None, start + Duration::from_secs(2))
+            .unwrap();
+        assert!(!store.delete_at(&a, "k2", start + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_validate_tenant() {
+        assert!(validate_tenant("team-a.prod_1").is_ok());
+        assert!(validate_tenant("").is_err());
+        assert!(validate_tenant("team/a").is_err());
+        assert!(validate_tenant(&"t".repeat(65)).is_err());
+    }
+}
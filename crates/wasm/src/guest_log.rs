@@ -0,0 +1,174 @@
+//! Guest Logging
+//!
+//! Host side of the `host-logging` import. Guest messages are emitted
+//! through tracing under the `smg::wasm::guest` target with the module name
+//! and request id as fields, and rate limited per module with a token bucket.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use tracing::{debug, error, info, trace, warn};
+
+use crate::{spec::smg::gateway::host_logging, types::WasiState};
+
+/// Longest message emitted; longer messages are cut at a char boundary.
+const MAX_GUEST_LOG_MESSAGE_BYTES: usize = 4096;
+
+/// Per-module token buckets shared by all worker threads. Each module may
+/// burst up to one second's worth of messages.
+#[derive(Debug)]
+pub struct GuestLogLimiter {
+    per_second: u32,
+    buckets: Mutex<HashMap<String, LogBucket>>,
+}
+
+#[derive(Debug)]
+struct LogBucket {
+    tokens: f64,
+    refilled_at: Instant,
+    /// Messages dropped since the last one let through
+    suppressed: u64,
+}
+
+impl GuestLogLimiter {
+    /// A limit of 0 drops every guest message.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `module`. Returns the number of messages dropped
+    /// since the last allowed one, or `None` when this one is dropped too.
+    pub fn acquire(&self, module: &str) -> Option<u64> {
+        self.acquire_at(module, Instant::now())
+    }
+
+    fn acquire_at(&self, module: &str, now: Instant) -> Option<u64> {
+        if self.per_second == 0 {
+            return None;
+        }
+        let capacity = f64::from(self.per_second);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets
+            .entry(module.to_string())
+            .or_insert_with(|| LogBucket {
+                tokens: capacity,
+                refilled_at: now,
+                suppressed: 0,
+            });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * capacity).min(capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens < 1.0 {
+            bucket.suppressed += 1;
+            return None;
+        }
+        bucket.tokens -= 1.0;
+        Some(std::mem::take(&mut bucket.suppressed))
+    }
+}
+
+/// Identifies the module and request a store's guest logs belong to.
+pub struct GuestLogContext {
+    pub module_name: Arc<str>,
+    pub request_id: String,
+    pub limiter: Arc<GuestLogLimiter>,
+}
+
+impl GuestLogContext {
+    fn emit(&self, level: host_logging::Level, message: &str) {
+        let module = &*self.module_name;
+        let request_id = self.request_id.as_str();
+        let Some(suppressed) = self.limiter.acquire(module) else {
+            return;
+        };
+        if suppressed > 0 {
+            warn!(
+                target: "smg::wasm::guest",
+                module,
+                request_id,
+                suppressed,
+                "Guest log messages dropped by rate limit"
+            );
+        }
+        let message = truncate_message(message);
+        match level {
+            host_logging::Level::Trace => {
+                trace!(target: "smg::wasm::guest", module, request_id, "{message}")
+            }
+            host_logging::Level::Debug => {
+                debug!(target: "smg::wasm::guest", module, request_id, "{message}")
+            }
+            host_logging::Level::Info => {
+                info!(target: "smg::wasm::guest", module, request_id, "{message}")
+            }
+            host_logging::Level::Warn => {
+                warn!(target: "smg::wasm::guest", module, request_id, "{message}")
+            }
+            host_logging::Level::Error => {
+                error!(target: "smg::wasm::guest", module, request_id, "{message}")
+            }
+        }
+    }
+}
+
+fn truncate_message(message: &str) -> &str {
+    if message.len() <= MAX_GUEST_LOG_MESSAGE_BYTES {
+        return message;
+    }
+    let mut end = MAX_GUEST_LOG_MESSAGE_BYTES;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    &message[..end]
+}
+
+impl host_logging::Host for WasiState {
+    async fn log(&mut self, level: host_logging::Level, message: String) -> wasmtime::Result<()> {
+        self.guest_log.emit(level, &message);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_limiter_counts_suppressed_messages() {
+        let limiter = GuestLogLimiter::new(2);
+        let start = Instant::now();
+        assert_eq!(limiter.acquire_at("a", start), Some(0));
+        assert_eq!(limiter.acquire_at("a", start), Some(0));
+        assert_eq!(limiter.acquire_at("a", start), None);
+        assert_eq!(limiter.acquire_at("a", start), None);
+        // Other modules have their own bucket
+        assert_eq!(limiter.acquire_at("b", start), Some(0));
+        // Half a second refills one token
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.acquire_at("a", later), Some(2));
+        assert_eq!(limiter.acquire_at("a", later), None);
+    }
+
+    #[test]
+    fn test_limiter_zero_drops_everything() {
+        let limiter = GuestLogLimiter::new(0);
+        assert_eq!(limiter.acquire("a"), None);
+    }
+
+    #[test]
+    fn test_truncate_message_at_char_boundary() {
+        let message = "é".repeat(MAX_GUEST_LOG_MESSAGE_BYTES);
+        let truncated = truncate_message(&message);
+        assert!(truncated.len() <= MAX_GUEST_LOG_MESSAGE_BYTES);
+        assert!(truncated.chars().all(|c| c == 'é'));
+        assert_eq!(truncate_message("short"), "short");
+    }
+}
@@ -1,12 +1,17 @@
 //! WebAssembly (WASM) module support for Shepherd Model Gateway
 //!
 //! This crate provides WASM component execution capabilities using the WebAssembly Component Model.
-//! It supports middleware execution at various attach points (OnRequest, OnResponse) with async support.
+//! It supports middleware execution at various attach points (OnRequest, OnResponse,
+//! OnResponseChunk) with async support.
 
+pub mod body_fields;
 pub mod config;
 pub mod errors;
+pub mod guest_kv;
+pub mod guest_log;
 pub mod module;
 pub mod module_manager;
+pub mod response_stream_spec;
 pub mod runtime;
 pub mod spec;
 #[cfg(feature = "storage-hooks")]
@@ -16,16 +21,20 @@ mod storage_spec;
 pub mod types;
 
 // Re-export commonly used types
+pub use body_fields::{BodyFieldExtractor, MAX_BODY_FIELDS};
 pub use config::WasmRuntimeConfig;
 pub use errors::{Result, WasmError, WasmManagerError, WasmModuleError, WasmRuntimeError};
+pub use guest_kv::{GuestKvContext, GuestKvStore};
+pub use guest_log::{GuestLogContext, GuestLogLimiter};
 pub use module::{
     MiddlewareAttachPoint, WasmMetrics, WasmModule, WasmModuleAddRequest, WasmModuleAddResponse,
-    WasmModuleAddResult, WasmModuleAttachPoint, WasmModuleDescriptor, WasmModuleListResponse,
-    WasmModuleMeta, WasmModuleType,
+    WasmModuleAddResult, WasmModuleAttachPoint, WasmModuleDescriptor, WasmModuleFailure,
+    WasmModuleListResponse, WasmModuleMeta, WasmModuleStats, WasmModuleType,
 };
 pub use module_manager::WasmModuleManager;
-pub use runtime::WasmRuntime;
+pub use response_stream_spec::ResponseStream;
+pub use runtime::{WasmExecution, WasmRuntime};
 pub use spec::{apply_modify_action_to_headers, build_wasm_headers_from_axum_headers, smg, Smg};
 #[cfg(feature = "storage-hooks")]
 pub use storage_hook::WasmStorageHook;
-pub use types::{WasiState, WasmComponentInput, WasmComponentOutput};
+pub use types::{TrackedLimits, WasiState, WasmComponentInput, WasmComponentOutput};
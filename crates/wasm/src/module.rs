@@ -2,15 +2,16 @@
 //!
 //! This module defines the core data structures for managing WebAssembly components:
 //! - Module metadata (UUID, name, file path, hash, timestamps, metrics)
-//! - Module types and attachment points (Middleware hooks: OnRequest, OnResponse, OnError)
+//! - Module types and attachment points (Middleware hooks: OnRequest, OnResponse,
+//!   OnResponseChunk, OnError)
 //! - API request/response types for module management
-//! - Execution metrics and statistics
+//! - Execution metrics and statistics, aggregate and per module
 //!
 //! The module provides custom serialization for:
 //! - SHA256 hashes (hex string representation)
 //! - Timestamps (ISO 8601 format for JSON output)
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize, Serializer};
 use uuid::Uuid;
@@ -128,6 +129,14 @@ pub struct WasmModuleDescriptor {
     pub file_path: String,
     pub module_type: WasmModuleType,
     pub attach_points: Vec<WasmModuleAttachPoint>,
+    /// JSON pointers into the request body. When set the module runs
+    /// headers-only on OnRequest and receives just these values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body_fields: Option<Vec<String>>,
+    /// Tenant the module belongs to. Scopes its `host-kv` namespace to
+    /// `<tenant>/<name>`; unset modules share the `default` tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
     pub add_result: Option<WasmModuleAddResult>,
 }
 
@@ -155,6 +164,10 @@ pub struct WasmModuleMeta {
     pub last_accessed_at: u64,
     pub access_count: u64,
     pub attach_points: Vec<WasmModuleAttachPoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body_fields: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
     // Wrapped in Arc to avoid cloning full bytes on every execution request.
     #[serde(skip)]
     pub wasm_bytes: Arc<Vec<u8>>,
@@ -173,6 +186,8 @@ pub enum WasmModuleType {
 pub enum MiddlewareAttachPoint {
     OnRequest,
     OnResponse,
+    /// Called per decoded SSE event of a streaming response
+    OnResponseChunk,
     OnError,
 }
 
@@ -207,3 +222,42 @@ pub struct WasmMetrics {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub average_execution_time_ms: Option<f64>,
 }
+
+/// Runtime statistics of one module, as served by the control plane.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmModuleStats {
+    pub module_uuid: Uuid,
+    pub name: String,
+    /// Executions per attach point, keyed like `OnRequest`
+    pub invocations: HashMap<String, u64>,
+    /// Median execution time over the most recent executions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p50_execution_time_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p99_execution_time_ms: Option<f64>,
+    /// `reject` actions returned by the module
+    pub rejections: u64,
+    /// Executions that trapped, including timeouts
+    pub traps: u64,
+    /// Executions that failed without trapping
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<WasmModuleFailure>,
+    /// Largest linear memory any execution grew to
+    pub memory_high_water_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmModuleFailure {
+    pub message: String,
+    /// WASM backtrace of a trap, truncated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+    pub attach_point: WasmModuleAttachPoint,
+    // nanoseconds since epoch
+    #[serde(
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub occurred_at: u64,
+}
@@ -1,26 +1,149 @@
 //! WASM Module Manager
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{
         atomic::{AtomicU64, Ordering},
-        Arc, RwLock,
+        Arc, Mutex, RwLock,
     },
+    time::Duration,
 };
 
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::{
     config::WasmRuntimeConfig,
-    errors::{Result, WasmError, WasmManagerError, WasmModuleError},
-    module::{WasmModule, WasmModuleAttachPoint},
+    errors::{Result, WasmError, WasmManagerError, WasmModuleError, WasmRuntimeError},
+    guest_kv::kv_namespace,
+    module::{
+        MiddlewareAttachPoint, WasmModule, WasmModuleAttachPoint, WasmModuleFailure,
+        WasmModuleStats,
+    },
+    response_stream_spec::smg::response_stream::response_stream_types::{
+        ChunkAction, ChunkContext, SseEvent,
+    },
     runtime::WasmRuntime,
     spec::smg::gateway::middleware_types::Action as MiddlewareAction,
     types::{WasmComponentInput, WasmComponentOutput},
 };
 
+/// Executions per module kept for latency percentiles.
+const LATENCY_WINDOW: usize = 1024;
+
+/// Backtrace lines kept in a module's last error.
+const MAX_BACKTRACE_LINES: usize = 32;
+
+fn now_nanos() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_nanos() as u64
+}
+
+fn attach_point_name(attach_point: &WasmModuleAttachPoint) -> String {
+    let WasmModuleAttachPoint::Middleware(point) = attach_point;
+    format!("{point:?}")
+}
+
+fn truncate_backtrace(backtrace: &str) -> String {
+    let lines: Vec<&str> = backtrace.lines().collect();
+    if lines.len() <= MAX_BACKTRACE_LINES {
+        return backtrace.to_string();
+    }
+    format!(
+        "{}\n... {} more frames",
+        lines[..MAX_BACKTRACE_LINES].join("\n"),
+        lines.len() - MAX_BACKTRACE_LINES
+    )
+}
+
+/// Per-module execution statistics.
+#[derive(Default)]
+struct ModuleStats {
+    invocations: HashMap<String, u64>,
+    latencies_us: VecDeque<u64>,
+    rejections: u64,
+    traps: u64,
+    errors: u64,
+    last_error: Option<WasmModuleFailure>,
+    memory_high_water_bytes: u64,
+}
+
+impl ModuleStats {
+    fn record(
+        &mut self,
+        attach_point: &WasmModuleAttachPoint,
+        elapsed: Duration,
+        output: &Result<WasmComponentOutput>,
+        peak_memory_bytes: usize,
+    ) {
+        *self
+            .invocations
+            .entry(attach_point_name(attach_point))
+            .or_default() += 1;
+        if self.latencies_us.len() == LATENCY_WINDOW {
+            self.latencies_us.pop_front();
+        }
+        self.latencies_us.push_back(elapsed.as_micros() as u64);
+        self.memory_high_water_bytes = self.memory_high_water_bytes.max(peak_memory_bytes as u64);
+
+        let error = match output {
+            Ok(WasmComponentOutput::MiddlewareAction(MiddlewareAction::Reject(_))) => {
+                self.rejections += 1;
+                return;
+            }
+            Ok(_) => return,
+            Err(error) => error,
+        };
+        let backtrace = match error {
+            WasmError::Runtime(WasmRuntimeError::Trap { backtrace, .. }) => {
+                self.traps += 1;
+                backtrace.as_deref().map(truncate_backtrace)
+            }
+            WasmError::Runtime(WasmRuntimeError::Timeout(_)) => {
+                self.traps += 1;
+                None
+            }
+            _ => {
+                self.errors += 1;
+                None
+            }
+        };
+        self.last_error = Some(WasmModuleFailure {
+            message: error.to_string(),
+            backtrace,
+            attach_point: attach_point.clone(),
+            occurred_at: now_nanos(),
+        });
+    }
+
+    fn snapshot(&self, module_uuid: Uuid, name: String) -> WasmModuleStats {
+        let mut latencies: Vec<u64> = self.latencies_us.iter().copied().collect();
+        latencies.sort_unstable();
+        let percentile = |p: f64| {
+            let last = latencies.len().checked_sub(1)?;
+            let index = (p * last as f64).round() as usize;
+            Some(latencies[index] as f64 / 1000.0)
+        };
+        WasmModuleStats {
+            module_uuid,
+            name,
+            invocations: self.invocations.clone(),
+            p50_execution_time_ms: percentile(0.50),
+            p99_execution_time_ms: percentile(0.99),
+            rejections: self.rejections,
+            traps: self.traps,
+            errors: self.errors,
+            last_error: self.last_error.clone(),
+            memory_high_water_bytes: self.memory_high_water_bytes,
+        }
+    }
+}
+
 pub struct WasmModuleManager {
     modules: Arc<RwLock<HashMap<Uuid, WasmModule>>>,
+    module_stats: Mutex<HashMap<Uuid, ModuleStats>>,
     runtime: Arc<WasmRuntime>,
     // Metrics
     total_executions: AtomicU64,
@@ -35,6 +158,7 @@ impl WasmModuleManager {
         let runtime = Arc::new(WasmRuntime::new(config));
         Self {
             modules: Arc::new(RwLock::new(HashMap::new())),
+            module_stats: Mutex::new(HashMap::new()),
             runtime,
             total_executions: AtomicU64::new(0),
             successful_executions: AtomicU64::new(0),
@@ -68,6 +192,9 @@ impl WasmModuleManager {
             return Err(WasmManagerError::ModuleNotFound(module_uuid).into());
         }
         modules.remove(&module_uuid);
+        if let Ok(mut stats) = self.module_stats.lock() {
+            stats.remove(&module_uuid);
+        }
         Ok(())
     }
 
@@ -131,8 +258,9 @@ impl WasmModuleManager {
     ) -> Result<WasmComponentOutput> {
         let start_time = std::time::Instant::now();
 
-        // Get the SHA256 hash and Arc-wrapped WASM bytes under a read lock.
-        let (sha256_hash, wasm_bytes) = {
+        // Get the SHA256 hash, Arc-wrapped WASM bytes, module name and KV
+        // namespace under a read lock.
+        let (sha256_hash, wasm_bytes, module_name, kv_namespace) = {
             let modules = self
                 .modules
                 .read()
@@ -144,6 +272,11 @@ impl WasmModuleManager {
             (
                 module.module_meta.sha256_hash,
                 module.module_meta.wasm_bytes.clone(),
+                Arc::<str>::from(module.module_meta.name.as_str()),
+                Arc::<str>::from(kv_namespace(
+                    module.module_meta.tenant.as_deref(),
+                    &module.module_meta.name,
+                )),
             )
         };
 
@@ -153,22 +286,34 @@ impl WasmModuleManager {
                 .write()
                 .map_err(|e| WasmManagerError::LockFailed(e.to_string()))?;
             if let Some(module) = modules.get_mut(&module_uuid) {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or(std::time::Duration::ZERO)
-                    .as_nanos() as u64;
-                module.module_meta.last_accessed_at = now;
+                module.module_meta.last_accessed_at = now_nanos();
                 module.module_meta.access_count += 1;
             }
         }
 
-        let result = self
+        let execution = self
             .runtime
-            .execute_component_async(sha256_hash, wasm_bytes, attach_point, input)
+            .execute_component_with_stats(
+                sha256_hash,
+                wasm_bytes,
+                module_name,
+                kv_namespace,
+                attach_point.clone(),
+                input,
+            )
             .await;
+        let result = execution.output;
 
         // Record metrics
-        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+        let elapsed = start_time.elapsed();
+        self.record_module_stats(
+            module_uuid,
+            &attach_point,
+            elapsed,
+            &result,
+            execution.peak_memory_bytes,
+        );
+        let execution_time_ms = elapsed.as_millis() as u64;
         self.total_executions.fetch_add(1, Ordering::Relaxed);
         self.total_execution_time_ms
             .fetch_add(execution_time_ms, Ordering::Relaxed);
@@ -196,6 +341,60 @@ impl WasmModuleManager {
         )
     }
 
+    fn record_module_stats(
+        &self,
+        module_uuid: Uuid,
+        attach_point: &WasmModuleAttachPoint,
+        elapsed: Duration,
+        output: &Result<WasmComponentOutput>,
+        peak_memory_bytes: usize,
+    ) {
+        if let Ok(mut stats) = self.module_stats.lock() {
+            stats.entry(module_uuid).or_default().record(
+                attach_point,
+                elapsed,
+                output,
+                peak_memory_bytes,
+            );
+        }
+    }
+
+    /// Runtime statistics of a registered module; `None` if it is not
+    /// registered.
+    pub fn get_module_stats(&self, module_uuid: Uuid) -> Result<Option<WasmModuleStats>> {
+        let Some(module) = self.get_module(module_uuid)? else {
+            return Ok(None);
+        };
+        let stats = self
+            .module_stats
+            .lock()
+            .map_err(|e| WasmManagerError::LockFailed(e.to_string()))?;
+        let snapshot = match stats.get(&module_uuid) {
+            Some(module_stats) => module_stats.snapshot(module_uuid, module.module_meta.name),
+            None => ModuleStats::default().snapshot(module_uuid, module.module_meta.name),
+        };
+        Ok(Some(snapshot))
+    }
+
+    /// Runtime statistics of every registered module.
+    pub fn get_all_module_stats(&self) -> Result<Vec<WasmModuleStats>> {
+        let modules = self.get_modules()?;
+        let stats = self
+            .module_stats
+            .lock()
+            .map_err(|e| WasmManagerError::LockFailed(e.to_string()))?;
+        let empty = ModuleStats::default();
+        Ok(modules
+            .into_iter()
+            .map(|module| {
+                stats
+                    .get(&module.module_uuid)
+                    .unwrap_or(&empty)
+                    .snapshot(module.module_uuid, module.module_meta.name)
+            })
+            .collect())
+    }
+
     /// Execute a WASM module for a given attach point
     /// Returns the Action if successful, or None if execution failed
     pub async fn execute_module_for_attach_point(
@@ -204,16 +403,19 @@ impl WasmModuleManager {
         attach_point: WasmModuleAttachPoint,
         input: WasmComponentInput,
     ) -> Option<MiddlewareAction> {
-        use tracing::error;
-
         let action_result = self
             .execute_module_interface(module.module_uuid, attach_point, input)
             .await;
 
         match action_result {
-            Ok(output) => match output {
-                WasmComponentOutput::MiddlewareAction(action) => Some(action),
-            },
+            Ok(WasmComponentOutput::MiddlewareAction(action)) => Some(action),
+            Ok(other) => {
+                error!(
+                    "WASM module {} returned unexpected output: {:?}",
+                    module.module_meta.name, other
+                );
+                None
+            }
             Err(e) => {
                 error!(
                     "Failed to execute WASM module {}: {}",
@@ -223,6 +425,56 @@ impl WasmModuleManager {
             }
         }
     }
+
+    /// Run an OnResponseChunk module on one SSE event under the per-chunk time
+    /// budget. Returns None if the module failed or overran the budget, in which
+    /// case callers forward the event unchanged.
+    pub async fn execute_response_chunk(
+        &self,
+        module: &WasmModule,
+        context: ChunkContext,
+        event: SseEvent,
+    ) -> Option<ChunkAction> {
+        let budget_ms = self.runtime.get_config().max_chunk_execution_time_ms;
+        let execution = self.execute_module_interface(
+            module.module_uuid,
+            WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnResponseChunk),
+            WasmComponentInput::ResponseChunk { context, event },
+        );
+
+        match tokio::time::timeout(Duration::from_millis(budget_ms), execution).await {
+            Ok(Ok(WasmComponentOutput::ChunkAction(action))) => Some(action),
+            Ok(Ok(other)) => {
+                error!(
+                    "WASM module {} returned unexpected output: {:?}",
+                    module.module_meta.name, other
+                );
+                None
+            }
+            Ok(Err(e)) => {
+                error!(
+                    "Failed to execute WASM module {}: {}",
+                    module.module_meta.name, e
+                );
+                None
+            }
+            Err(_) => {
+                warn!(
+                    "WASM module {} exceeded the {}ms response chunk budget",
+                    module.module_meta.name, budget_ms
+                );
+                // The abandoned execution is never recorded; count the overrun.
+                self.record_module_stats(
+                    module.module_uuid,
+                    &WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnResponseChunk),
+                    Duration::from_millis(budget_ms),
+                    &Err(WasmRuntimeError::Timeout(budget_ms).into()),
+                    0,
+                );
+                None
+            }
+        }
+    }
 }
 
 impl Default for WasmModuleManager {
@@ -230,3 +482,71 @@ impl Default for WasmModuleManager {
         Self::with_default_config()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ON_REQUEST: WasmModuleAttachPoint =
+        WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnRequest);
+
+    #[test]
+    fn module_stats_classify_outcomes() {
+        let mut stats = ModuleStats::default();
+        for ms in 1..=100 {
+            stats.record(
+                &ON_REQUEST,
+                Duration::from_millis(ms),
+                &Ok(WasmComponentOutput::MiddlewareAction(
+                    MiddlewareAction::Continue,
+                )),
+                65536,
+            );
+        }
+        stats.record(
+            &ON_REQUEST,
+            Duration::from_millis(1),
+            &Ok(WasmComponentOutput::MiddlewareAction(
+                MiddlewareAction::Reject(403),
+            )),
+            131072,
+        );
+        stats.record(
+            &ON_REQUEST,
+            Duration::from_millis(1),
+            &Err(WasmRuntimeError::Trap {
+                message: "unreachable".to_string(),
+                backtrace: Some("frame\n".repeat(40)),
+            }
+            .into()),
+            0,
+        );
+        stats.record(
+            &ON_REQUEST,
+            Duration::from_millis(1),
+            &Err(WasmRuntimeError::CallFailed("bad input".to_string()).into()),
+            0,
+        );
+
+        let snapshot = stats.snapshot(Uuid::nil(), "m".to_string());
+        assert_eq!(snapshot.invocations["OnRequest"], 103);
+        assert_eq!(snapshot.rejections, 1);
+        assert_eq!(snapshot.traps, 1);
+        assert_eq!(snapshot.errors, 1);
+        assert_eq!(snapshot.memory_high_water_bytes, 131072);
+        assert_eq!(snapshot.p50_execution_time_ms, Some(49.0));
+        assert_eq!(snapshot.p99_execution_time_ms, Some(99.0));
+        assert_eq!(
+            snapshot.last_error.unwrap().message,
+            "execution failed: bad input"
+        );
+    }
+
+    #[test]
+    fn trap_backtraces_are_truncated() {
+        let backtrace = truncate_backtrace(&"frame\n".repeat(40));
+        assert_eq!(backtrace.lines().count(), MAX_BACKTRACE_LINES + 1);
+        assert!(backtrace.ends_with("... 8 more frames"));
+        assert_eq!(truncate_backtrace("a\nb"), "a\nb");
+    }
+}
@@ -0,0 +1,13 @@
+//! WebAssembly Interface Bindings for streaming response transformation.
+//!
+//! Invokes `wasmtime::component::bindgen!` at compile time to generate
+//! host-side bindings from `interface/response_stream/response-stream.wit`.
+//! Kept in its own world so existing `smg` middleware components do not
+//! have to export `on-response-chunk`.
+
+wasmtime::component::bindgen!({
+    path: "src/interface/response_stream",
+    world: "response-stream",
+    imports: { default: async | trappable },
+    exports: { default: async },
+});
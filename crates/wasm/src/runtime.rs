@@ -16,8 +16,9 @@ use lru::LruCache;
 use tokio::sync::oneshot;
 use tracing::{debug, error};
 use wasmtime::{
-    component::{Component, Linker, ResourceTable},
+    component::{Component, HasSelf, Linker, ResourceTable},
     Config, Engine, InstanceAllocationStrategy, PoolingAllocationConfig, Store, StoreLimitsBuilder,
+    WasmBacktrace,
 };
 use wasmtime_wasi::WasiCtx;
 
@@ -29,9 +30,15 @@ const EPOCH_INTERVAL_MS: u64 = 100;
 use crate::{
     config::WasmRuntimeConfig,
     errors::{Result, WasmError, WasmRuntimeError},
+    guest_kv::{GuestKvContext, GuestKvStore},
+    guest_log::{GuestLogContext, GuestLogLimiter},
     module::{MiddlewareAttachPoint, WasmModuleAttachPoint},
-    spec::Smg,
-    types::{WasiState, WasmComponentInput, WasmComponentOutput},
+    response_stream_spec::ResponseStream,
+    spec::{
+        smg::gateway::{host_kv, host_logging},
+        Smg,
+    },
+    types::{TrackedLimits, WasiState, WasmComponentInput, WasmComponentOutput},
 };
 
 pub struct WasmRuntime {
@@ -63,12 +70,23 @@ pub enum WasmTask {
         /// WASM component bytes wrapped in Arc to avoid cloning the full bytes
         /// on every request. Only read on cache miss (first compilation).
         wasm_bytes: Arc<Vec<u8>>,
+        /// Module name attached to the guest's log messages
+        module_name: Arc<str>,
+        /// `tenant/module` namespace the guest's `host-kv` calls are scoped to
+        kv_namespace: Arc<str>,
         attach_point: WasmModuleAttachPoint,
         input: WasmComponentInput,
-        response: oneshot::Sender<Result<WasmComponentOutput>>,
+        response: oneshot::Sender<WasmExecution>,
     },
 }
 
+/// Outcome of one component execution.
+pub struct WasmExecution {
+    pub output: Result<WasmComponentOutput>,
+    /// Largest linear memory the instance grew to, in bytes
+    pub peak_memory_bytes: usize,
+}
+
 impl WasmRuntime {
     pub fn new(config: WasmRuntimeConfig) -> Self {
         let thread_pool = Arc::new(WasmThreadPool::new(config.clone()));
@@ -106,29 +124,63 @@ impl WasmRuntime {
         &self,
         sha256_hash: [u8; 32],
         wasm_bytes: Arc<Vec<u8>>,
+        module_name: Arc<str>,
+        kv_namespace: Arc<str>,
         attach_point: WasmModuleAttachPoint,
         input: WasmComponentInput,
     ) -> Result<WasmComponentOutput> {
+        self.execute_component_with_stats(
+            sha256_hash,
+            wasm_bytes,
+            module_name,
+            kv_namespace,
+            attach_point,
+            input,
+        )
+        .await
+        .output
+    }
+
+    /// Like [`Self::execute_component_async`], also reporting the instance's
+    /// memory high-water mark.
+    pub async fn execute_component_with_stats(
+        &self,
+        sha256_hash: [u8; 32],
+        wasm_bytes: Arc<Vec<u8>>,
+        module_name: Arc<str>,
+        kv_namespace: Arc<str>,
+        attach_point: WasmModuleAttachPoint,
+        input: WasmComponentInput,
+    ) -> WasmExecution {
         let start_time = std::time::Instant::now();
         let (response_tx, response_rx) = oneshot::channel();
 
         let task = WasmTask::ExecuteComponent {
             sha256_hash,
             wasm_bytes,
+            module_name,
+            kv_namespace,
             attach_point,
             input,
             response: response_tx,
         };
 
-        self.thread_pool.sender.send(task).await.map_err(|e| {
-            WasmRuntimeError::CallFailed(format!("Failed to send task to thread pool: {e}"))
-        })?;
-
-        let result = response_rx.await.map_err(|e| {
-            WasmRuntimeError::CallFailed(format!(
-                "Failed to receive response from thread pool: {e}"
-            ))
-        })?;
+        let execution = match self.thread_pool.sender.send(task).await {
+            Ok(()) => response_rx.await.unwrap_or_else(|e| WasmExecution {
+                output: Err(WasmRuntimeError::CallFailed(format!(
+                    "Failed to receive response from thread pool: {e}"
+                ))
+                .into()),
+                peak_memory_bytes: 0,
+            }),
+            Err(e) => WasmExecution {
+                output: Err(WasmRuntimeError::CallFailed(format!(
+                    "Failed to send task to thread pool: {e}"
+                ))
+                .into()),
+                peak_memory_bytes: 0,
+            },
+        };
 
         let execution_time_ms = start_time.elapsed().as_millis() as u64;
         self.total_executions.fetch_add(1, Ordering::Relaxed);
@@ -138,13 +190,13 @@ impl WasmRuntime {
         self.max_execution_time_ms
             .fetch_max(execution_time_ms, Ordering::Relaxed);
 
-        if result.is_ok() {
+        if execution.output.is_ok() {
             self.successful_executions.fetch_add(1, Ordering::Relaxed);
         } else {
             self.failed_executions.fetch_add(1, Ordering::Relaxed);
         }
 
-        result
+        execution
     }
 
     /// Get current metrics
@@ -163,10 +215,13 @@ impl WasmRuntime {
 fn map_wasm_error(e: wasmtime::Error, timeout_ms: u64) -> WasmError {
     // Use proper trap code detection instead of brittle string matching.
     // Wasmtime uses Trap::Interrupt for epoch-based interruptions.
-    if e.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::Interrupt) {
-        WasmError::from(WasmRuntimeError::Timeout(timeout_ms))
-    } else {
-        WasmError::from(WasmRuntimeError::CallFailed(e.to_string()))
+    match e.downcast_ref::<wasmtime::Trap>() {
+        Some(wasmtime::Trap::Interrupt) => WasmError::from(WasmRuntimeError::Timeout(timeout_ms)),
+        Some(trap) => WasmError::from(WasmRuntimeError::Trap {
+            message: trap.to_string(),
+            backtrace: e.downcast_ref::<WasmBacktrace>().map(ToString::to_string),
+        }),
+        None => WasmError::from(WasmRuntimeError::CallFailed(e.to_string())),
     }
 }
 
@@ -181,6 +236,13 @@ impl WasmThreadPool {
             .unwrap_or(4)
             .max(1);
         let num_workers = config.thread_pool_size.clamp(1, max_workers);
+        // Shared so a module's log budget holds across workers
+        let log_limiter = Arc::new(GuestLogLimiter::new(config.max_guest_logs_per_second));
+        // Shared so every worker sees the same KV state and quotas
+        let kv_store = Arc::new(GuestKvStore::new(
+            config.max_guest_kv_bytes_per_namespace,
+            Duration::from_secs(config.max_guest_kv_ttl_secs),
+        ));
 
         debug!(
             target: "smg::wasm::runtime",
@@ -191,6 +253,8 @@ impl WasmThreadPool {
         for worker_id in 0..num_workers {
             let receiver = receiver.clone();
             let config = config.clone();
+            let log_limiter = log_limiter.clone();
+            let kv_store = kv_store.clone();
 
             let worker = std::thread::spawn(move || {
                 // create independent tokio runtime for this thread
@@ -208,7 +272,7 @@ impl WasmThreadPool {
                 };
 
                 rt.block_on(async {
-                    Self::worker_loop(worker_id, receiver, config).await;
+                    Self::worker_loop(worker_id, receiver, config, log_limiter, kv_store).await;
                 });
             });
 
@@ -238,6 +302,8 @@ impl WasmThreadPool {
         worker_id: usize,
         receiver: async_channel::Receiver<WasmTask>,
         config: WasmRuntimeConfig,
+        log_limiter: Arc<GuestLogLimiter>,
+        kv_store: Arc<GuestKvStore>,
     ) {
         debug!(
             target: "smg::wasm::runtime",
@@ -287,6 +353,27 @@ impl WasmThreadPool {
             );
             return;
         }
+        if let Err(e) =
+            host_logging::add_to_linker::<_, HasSelf<WasiState>>(&mut linker, |state| state)
+        {
+            error!(
+                target: "smg::wasm::runtime",
+                worker_id = worker_id,
+                "Failed to add host logging to linker: {}",
+                e
+            );
+            return;
+        }
+        if let Err(e) = host_kv::add_to_linker::<_, HasSelf<WasiState>>(&mut linker, |state| state)
+        {
+            error!(
+                target: "smg::wasm::runtime",
+                worker_id = worker_id,
+                "Failed to add host KV to linker: {}",
+                e
+            );
+            return;
+        }
 
         let default_capacity = NonZeroUsize::new(10).unwrap_or(NonZeroUsize::MIN);
         let cache_capacity =
@@ -335,11 +422,22 @@ impl WasmThreadPool {
                 WasmTask::ExecuteComponent {
                     sha256_hash,
                     wasm_bytes,
+                    module_name,
+                    kv_namespace,
                     attach_point,
                     input,
                     response,
                 } => {
-                    let result = Self::execute_component_in_worker(
+                    let guest_log = GuestLogContext {
+                        module_name,
+                        request_id: input.request_id().to_string(),
+                        limiter: log_limiter.clone(),
+                    };
+                    let guest_kv = GuestKvContext {
+                        namespace: kv_namespace,
+                        store: kv_store.clone(),
+                    };
+                    let execution = Self::execute_component_in_worker(
                         &engine,
                         &linker,
                         &mut component_cache,
@@ -347,11 +445,13 @@ impl WasmThreadPool {
                         &wasm_bytes,
                         attach_point,
                         input,
+                        guest_log,
+                        guest_kv,
                         &config,
                     )
                     .await;
 
-                    let _ = response.send(result);
+                    let _ = response.send(execution);
                 }
             }
         }
@@ -366,8 +466,57 @@ impl WasmThreadPool {
         wasm_bytes: &[u8],
         attach_point: WasmModuleAttachPoint,
         input: WasmComponentInput,
+        guest_log: GuestLogContext,
+        guest_kv: GuestKvContext,
         config: &WasmRuntimeConfig,
-    ) -> Result<WasmComponentOutput> {
+    ) -> WasmExecution {
+        let prepared = Self::prepare_component(
+            engine,
+            cache,
+            sha256_hash,
+            wasm_bytes,
+            &attach_point,
+            guest_log,
+            guest_kv,
+            config,
+        );
+        let (component, mut store, budget_ms) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return WasmExecution {
+                    output: Err(e),
+                    peak_memory_bytes: 0,
+                }
+            }
+        };
+        let output = Self::call_component(
+            &mut store,
+            &component,
+            linker,
+            attach_point,
+            input,
+            budget_ms,
+        )
+        .await;
+        WasmExecution {
+            output,
+            peak_memory_bytes: store.data().limits.peak_memory_bytes(),
+        }
+    }
+
+    /// Compile (or fetch from cache) the component and create a store with
+    /// the configured memory limit and the attach point's time budget.
+    #[expect(clippy::too_many_arguments)]
+    fn prepare_component(
+        engine: &Engine,
+        cache: &mut LruCache<[u8; 32], Component>,
+        sha256_hash: [u8; 32],
+        wasm_bytes: &[u8],
+        attach_point: &WasmModuleAttachPoint,
+        guest_log: GuestLogContext,
+        guest_kv: GuestKvContext,
+        config: &WasmRuntimeConfig,
+    ) -> Result<(Component, Store<WasiState>, u64)> {
         // Compile component from bytes, or retrieve from cache (keyed by SHA256
         // hash to avoid hashing the full module bytes per lookup).
         let component = if let Some(comp) = cache.get(&sha256_hash) {
@@ -407,7 +556,9 @@ impl WasmThreadPool {
             WasiState {
                 ctx: builder.build(),
                 table: ResourceTable::new(),
-                limits,
+                limits: TrackedLimits::new(limits),
+                guest_log,
+                guest_kv,
             },
         );
 
@@ -418,7 +569,15 @@ impl WasmThreadPool {
         // Set epoch deadline for timeout enforcement.
         // The deadline is the number of epoch ticks before execution is interrupted.
         // With EPOCH_INTERVAL_MS=100ms and max_execution_time_ms=1000ms, deadline=10 epochs.
-        let deadline_epochs = (config.max_execution_time_ms / EPOCH_INTERVAL_MS).max(1);
+        // Response chunks run under their own, much smaller budget; the host also
+        // enforces it precisely, so the epoch deadline only reclaims the worker.
+        let budget_ms = match attach_point {
+            WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnResponseChunk) => {
+                config.max_chunk_execution_time_ms
+            }
+            _ => config.max_execution_time_ms,
+        };
+        let deadline_epochs = (budget_ms / EPOCH_INTERVAL_MS).max(1);
         store.set_epoch_deadline(deadline_epochs);
 
         // When the epoch deadline is reached, trap to enforce the execution timeout.
@@ -426,11 +585,22 @@ impl WasmThreadPool {
             Err(wasmtime::Error::msg("execution time limit exceeded"))
         });
 
+        Ok((component, store, budget_ms))
+    }
+
+    async fn call_component(
+        store: &mut Store<WasiState>,
+        component: &Component,
+        linker: &Linker<WasiState>,
+        attach_point: WasmModuleAttachPoint,
+        input: WasmComponentInput,
+        budget_ms: u64,
+    ) -> Result<WasmComponentOutput> {
         let output = match attach_point {
             WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnRequest) => {
                 let request = match input {
                     WasmComponentInput::MiddlewareRequest(req) => req,
-                    WasmComponentInput::MiddlewareResponse(_) => {
+                    _ => {
                         return Err(WasmError::from(WasmRuntimeError::CallFailed(
                             "Expected MiddlewareRequest input for OnRequest attach point"
                                 .to_string(),
@@ -439,7 +609,7 @@ impl WasmThreadPool {
                 };
 
                 // Instantiate component (must use async instantiation when async support is enabled)
-                let bindings = Smg::instantiate_async(&mut store, &component, linker)
+                let bindings = Smg::instantiate_async(&mut *store, component, linker)
                     .await
                     .map_err(|e| {
                         WasmError::from(WasmRuntimeError::InstanceCreateFailed(e.to_string()))
@@ -448,9 +618,9 @@ impl WasmThreadPool {
                 // Call on-request (async call when async support is enabled)
                 let action_result = bindings
                     .smg_gateway_middleware_on_request()
-                    .call_on_request(&mut store, &request)
+                    .call_on_request(&mut *store, &request)
                     .await
-                    .map_err(|e| map_wasm_error(e, config.max_execution_time_ms))?;
+                    .map_err(|e| map_wasm_error(e, budget_ms))?;
 
                 WasmComponentOutput::MiddlewareAction(action_result)
             }
@@ -458,7 +628,7 @@ impl WasmThreadPool {
                 // Extract Response input
                 let response = match input {
                     WasmComponentInput::MiddlewareResponse(resp) => resp,
-                    WasmComponentInput::MiddlewareRequest(_) => {
+                    _ => {
                         return Err(WasmError::from(WasmRuntimeError::CallFailed(
                             "Expected MiddlewareResponse input for OnResponse attach point"
                                 .to_string(),
@@ -467,7 +637,7 @@ impl WasmThreadPool {
                 };
 
                 // Instantiate component (must use async instantiation when async support is enabled)
-                let bindings = Smg::instantiate_async(&mut store, &component, linker)
+                let bindings = Smg::instantiate_async(&mut *store, component, linker)
                     .await
                     .map_err(|e| {
                         WasmError::from(WasmRuntimeError::InstanceCreateFailed(e.to_string()))
@@ -476,12 +646,33 @@ impl WasmThreadPool {
                 // Call on-response (async call when async support is enabled)
                 let action_result = bindings
                     .smg_gateway_middleware_on_response()
-                    .call_on_response(&mut store, &response)
+                    .call_on_response(&mut *store, &response)
                     .await
-                    .map_err(|e| map_wasm_error(e, config.max_execution_time_ms))?;
+                    .map_err(|e| map_wasm_error(e, budget_ms))?;
 
                 WasmComponentOutput::MiddlewareAction(action_result)
             }
+            WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnResponseChunk) => {
+                let WasmComponentInput::ResponseChunk { context, event } = input else {
+                    return Err(WasmError::from(WasmRuntimeError::CallFailed(
+                        "Expected ResponseChunk input for OnResponseChunk attach point".to_string(),
+                    )));
+                };
+
+                let bindings = ResponseStream::instantiate_async(&mut *store, component, linker)
+                    .await
+                    .map_err(|e| {
+                        WasmError::from(WasmRuntimeError::InstanceCreateFailed(e.to_string()))
+                    })?;
+
+                let chunk_action = bindings
+                    .smg_response_stream_middleware_on_response_chunk()
+                    .call_on_response_chunk(&mut *store, &context, &event)
+                    .await
+                    .map_err(|e| map_wasm_error(e, budget_ms))?;
+
+                WasmComponentOutput::ChunkAction(chunk_action)
+            }
             WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnError) => {
                 return Err(WasmError::from(WasmRuntimeError::CallFailed(
                     "OnError attach point not yet implemented".to_string(),
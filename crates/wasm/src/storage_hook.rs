@@ -116,6 +116,7 @@ fn to_wit_operation(op: StorageOperation) -> WitOperation {
         StorageOperation::DeleteItem => WitOperation::DeleteItem,
         StorageOperation::StoreResponse => WitOperation::StoreResponse,
         StorageOperation::GetResponse => WitOperation::GetResponse,
+        StorageOperation::UpdateResponse => WitOperation::UpdateResponse,
         StorageOperation::DeleteResponse => WitOperation::DeleteResponse,
         StorageOperation::GetResponseChain => WitOperation::GetResponseChain,
         StorageOperation::ListIdentifierResponses => WitOperation::ListIdentifierResponses,
@@ -289,6 +290,7 @@ mod tests {
             StorageOperation::DeleteItem,
             StorageOperation::StoreResponse,
             StorageOperation::GetResponse,
+            StorageOperation::UpdateResponse,
             StorageOperation::DeleteResponse,
             StorageOperation::GetResponseChain,
             StorageOperation::ListIdentifierResponses,
@@ -3,10 +3,14 @@
 //! Provides generic input/output types for WASM component execution
 //! based on attach points.
 
-use wasmtime::{component::ResourceTable, StoreLimits};
+use wasmtime::{component::ResourceTable, ResourceLimiter, StoreLimits};
 use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
 
-use crate::spec::smg::gateway::middleware_types;
+use crate::{
+    guest_kv::GuestKvContext, guest_log::GuestLogContext,
+    response_stream_spec::smg::response_stream::response_stream_types,
+    spec::smg::gateway::middleware_types,
+};
 
 /// Generic input type for WASM component execution
 ///
@@ -18,6 +22,27 @@ pub enum WasmComponentInput {
     MiddlewareRequest(middleware_types::Request),
     /// Middleware OnResponse input
     MiddlewareResponse(middleware_types::Response),
+    /// Middleware OnResponseChunk input: one decoded SSE event
+    ResponseChunk {
+        context: response_stream_types::ChunkContext,
+        event: response_stream_types::SseEvent,
+    },
+}
+
+impl WasmComponentInput {
+    /// Request id carried by the input; responses fall back to the
+    /// `x-request-id` header, or empty when absent.
+    pub fn request_id(&self) -> &str {
+        match self {
+            Self::MiddlewareRequest(req) => &req.request_id,
+            Self::MiddlewareResponse(resp) => resp
+                .headers
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case("x-request-id"))
+                .map_or("", |h| h.value.as_str()),
+            Self::ResponseChunk { context, .. } => &context.request_id,
+        }
+    }
 }
 
 /// Generic output type from WASM component execution
@@ -28,12 +53,80 @@ pub enum WasmComponentInput {
 pub enum WasmComponentOutput {
     /// Middleware Action output
     MiddlewareAction(middleware_types::Action),
+    /// Middleware OnResponseChunk output
+    ChunkAction(response_stream_types::ChunkAction),
 }
 
 pub struct WasiState {
     pub ctx: WasiCtx,
     pub table: ResourceTable,
-    pub limits: StoreLimits,
+    pub limits: TrackedLimits,
+    pub guest_log: GuestLogContext,
+    pub guest_kv: GuestKvContext,
+}
+
+/// [`StoreLimits`] that also record the largest linear memory granted,
+/// reported as a module's memory high-water mark.
+pub struct TrackedLimits {
+    limits: StoreLimits,
+    peak_memory_bytes: usize,
+}
+
+impl TrackedLimits {
+    pub fn new(limits: StoreLimits) -> Self {
+        Self {
+            limits,
+            peak_memory_bytes: 0,
+        }
+    }
+
+    pub fn peak_memory_bytes(&self) -> usize {
+        self.peak_memory_bytes
+    }
+}
+
+impl ResourceLimiter for TrackedLimits {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        let allowed = self.limits.memory_growing(current, desired, maximum)?;
+        if allowed {
+            self.peak_memory_bytes = self.peak_memory_bytes.max(desired);
+        }
+        Ok(allowed)
+    }
+
+    fn memory_grow_failed(&mut self, error: wasmtime::Error) -> wasmtime::Result<()> {
+        self.limits.memory_grow_failed(error)
+    }
+
+    fn table_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> wasmtime::Result<bool> {
+        self.limits.table_growing(current, desired, maximum)
+    }
+
+    fn table_grow_failed(&mut self, error: wasmtime::Error) -> wasmtime::Result<()> {
+        self.limits.table_grow_failed(error)
+    }
+
+    fn instances(&self) -> usize {
+        self.limits.instances()
+    }
+
+    fn tables(&self) -> usize {
+        self.limits.tables()
+    }
+
+    fn memories(&self) -> usize {
+        self.limits.memories()
+    }
 }
 
 impl WasiView for WasiState {
@@ -24,6 +24,7 @@ impl BeforeGuest for PassthroughHook {
         let extra = match op {
             // Add marker extra column on write operations
             Operation::StoreResponse
+            | Operation::UpdateResponse
             | Operation::CreateConversation
             | Operation::UpdateConversation
             | Operation::CreateItem
@@ -1 +1,6 @@
 """SMG gRPC servicer implementations."""
+
+# Revision of the SMG proto contract these servicers implement, advertised in
+# GetServerInfoResponse.proto_revision. Keep in sync with PROTO_REVISION in
+# crates/grpc_client/src/compat.rs.
+PROTO_REVISION = 2
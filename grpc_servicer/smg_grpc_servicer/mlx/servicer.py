@@ -21,6 +21,8 @@ from mlx_lm.sample_utils import make_logits_processors, make_sampler
 from smg_grpc_proto import mlx_engine_pb2, mlx_engine_pb2_grpc
 from smg_grpc_proto.generated import common_pb2
 
+from smg_grpc_servicer import PROTO_REVISION
+
 logger = logging.getLogger(__name__)
 
 
@@ -548,6 +550,7 @@ class MlxEngineServicer(mlx_engine_pb2_grpc.MlxEngineServicer):
             server_type="mlx-grpc",
             active_requests=self._active_requests,
             uptime_seconds=time.time() - self.start_time,
+            proto_revision=PROTO_REVISION,
         )
 
     def start_generation_loop(self):
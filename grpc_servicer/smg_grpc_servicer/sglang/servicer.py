@@ -52,6 +52,7 @@ from sglang.utils import get_exception_traceback
 from smg_grpc_proto import sglang_scheduler_pb2, sglang_scheduler_pb2_grpc
 from smg_grpc_proto.generated import common_pb2
 
+from smg_grpc_servicer import PROTO_REVISION
 from smg_grpc_servicer.sglang.health_servicer import SGLangHealthServicer
 from smg_grpc_servicer.sglang.request_manager import GrpcRequestManager
 from smg_grpc_servicer.sglang.utils import abort_code_from_output, to_token_id_array
@@ -576,6 +577,7 @@ class SGLangSchedulerServicer(sglang_scheduler_pb2_grpc.SglangSchedulerServicer)
             server_type="grpc",
             start_time=start_timestamp,
             max_total_num_tokens=self.scheduler_info.get("max_total_num_tokens", 0),
+            proto_revision=PROTO_REVISION,
         )
 
     async def GetLoads(
@@ -1097,6 +1099,9 @@ class SGLangSchedulerServicer(sglang_scheduler_pb2_grpc.SglangSchedulerServicer)
         stream_interval = (
             grpc_params.stream_interval if grpc_params.HasField("stream_interval") else None
         )
+        sampling_seed = (
+            grpc_params.sampling_seed if grpc_params.HasField("sampling_seed") else None
+        )
         logit_bias = dict(grpc_params.logit_bias) if grpc_params.logit_bias else None
         stop = list(grpc_params.stop) if grpc_params.stop else None
         stop_token_ids = list(grpc_params.stop_token_ids) if grpc_params.stop_token_ids else None
@@ -1125,6 +1130,7 @@ class SGLangSchedulerServicer(sglang_scheduler_pb2_grpc.SglangSchedulerServicer)
             stream_interval=stream_interval,
             logit_bias=logit_bias,
             custom_params=custom_params,
+            sampling_seed=sampling_seed,
         )
 
     def _convert_output_logprobs_to_proto(
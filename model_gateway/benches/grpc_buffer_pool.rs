@@ -0,0 +1,63 @@
+//! Benchmarks for reusing multimodal tensor buffers across gRPC requests.
+//!
+//! Each iteration is one request's encoder input on the SHM path: the f32
+//! pixel values are serialized into a byte buffer, copied into the shared
+//! memory mapping (a preallocated slice stands in for it here) and the buffer
+//! is released. `fresh` allocates and frees the buffer every time, as the
+//! pipeline did before; `pooled` takes it from and returns it to a
+//! `BufferPool`, so after the first iteration no allocation happens.
+//!
+//! Run with: cargo bench --bench grpc_buffer_pool
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use smg::routers::grpc::buffer_pool::BufferPool;
+
+/// Encoder input sizes in f32 elements: a small image, a typical
+/// Qwen2-VL image (~1k patches of 1176 values) and a multi-image request.
+const SIZES: &[usize] = &[256 * 1024, 1176 * 1024, 4 * 1176 * 1024];
+
+fn serialize_into(buf: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn bench_tensor_buffers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grpc_tensor_buffers");
+    group.sample_size(50);
+
+    for &elements in SIZES {
+        let values = vec![0.5_f32; elements];
+        let nbytes = elements * 4;
+        let mut mapping = vec![0_u8; nbytes];
+        group.throughput(Throughput::Bytes(nbytes as u64));
+
+        group.bench_with_input(BenchmarkId::new("fresh", nbytes), &values, |b, values| {
+            b.iter(|| {
+                let mut buf = Vec::with_capacity(nbytes);
+                serialize_into(&mut buf, values);
+                mapping.copy_from_slice(&buf);
+                black_box(&mapping);
+                drop(buf);
+            });
+        });
+
+        let pool = BufferPool::new(64 << 20);
+        group.bench_with_input(BenchmarkId::new("pooled", nbytes), &values, |b, values| {
+            b.iter(|| {
+                let mut buf = pool.take(nbytes);
+                serialize_into(&mut buf, values);
+                mapping.copy_from_slice(&buf);
+                black_box(&mapping);
+                pool.give(buf);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_tensor_buffers);
+criterion_main!(benches);
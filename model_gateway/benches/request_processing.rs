@@ -16,7 +16,10 @@ use openai_protocol::{
 };
 use serde_json::{from_str, to_string, to_value, to_vec};
 use smg::{
-    routers::http::pd_types::{generate_room_id, RequestWithBootstrap},
+    routers::{
+        common::bootstrap_rooms::{bootstrap_rooms, RoomWidth},
+        http::pd_types::RequestWithBootstrap,
+    },
     worker::{BasicWorker, BasicWorkerBuilder, Worker, WorkerType},
 };
 
@@ -27,6 +30,13 @@ fn create_test_worker() -> BasicWorker {
         .build()
 }
 
+// Allocate (and immediately release) a bootstrap room
+fn generate_room_id() -> u64 {
+    bootstrap_rooms()
+        .allocate(RoomWidth::Bits63, "http://test-server:8000")
+        .room()
+}
+
 // Helper function to get bootstrap info from worker
 fn get_bootstrap_info(worker: &BasicWorker) -> (String, Option<u16>) {
     let hostname = worker.bootstrap_host().to_string();
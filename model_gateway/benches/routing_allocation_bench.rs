@@ -68,6 +68,8 @@ fn extract_text_for_routing_old(req: &ResponsesRequest) -> String {
                 ResponseInputOutputItem::FunctionCallOutput { output, .. } => Some(output.clone()),
                 ResponseInputOutputItem::McpApprovalRequest { .. } => None,
                 ResponseInputOutputItem::McpApprovalResponse { .. } => None,
+                ResponseInputOutputItem::McpElicitationRequest { .. } => None,
+                ResponseInputOutputItem::McpElicitationResponse { .. } => None,
                 ResponseInputOutputItem::ImageGenerationCall { .. } => None,
                 ResponseInputOutputItem::Compaction { .. } => None,
                 ResponseInputOutputItem::ComputerCall { .. } => None,
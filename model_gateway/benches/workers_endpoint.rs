@@ -96,6 +96,7 @@ fn deep_clone_info(w: &Arc<dyn Worker>) -> WorkerInfo {
         is_healthy: status == WorkerStatus::Ready,
         status: Some(status),
         load: w.load(),
+        promoted: None,
         job_status: None,
     }
 }
@@ -3,29 +3,40 @@ use std::{
     time::Duration,
 };
 
+use llm_multimodal::ModelRegistry;
 use llm_tokenizer::registry::TokenizerRegistry;
 use reasoning_parser::ParserFactory as ReasoningParserFactory;
 use reqwest::Client;
 use smg_data_connector::{
-    create_storage, ConversationItemStorage, ConversationStorage, ResponseStorage,
-    StorageFactoryConfig,
+    create_storage, ChatCompletionStorage, ConversationItemStorage, ConversationStorage,
+    DebugCaptureStorage, FileStorage, GenerationJobStorage, HistoryBackend,
+    MemoryChatCompletionStorage, MemoryDebugCaptureStorage, MemoryFileStorage,
+    MemoryGenerationJobStorage, MemoryPromptStorage, MemoryTenantMcpServerStorage,
+    MemoryVectorStoreStorage, PromptStorage, ResponseStorage, StorageFactoryConfig,
+    TenantMcpServerStorage, VectorStoreStorage,
 };
 use smg_mcp::McpOrchestrator;
 use tool_parser::ParserFactory as ToolParserFactory;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
     config::RouterConfig,
-    middleware::TokenBucket,
-    observability::inflight_tracker::InFlightRequestTracker,
+    middleware::{RoutingRules, TokenBucket},
+    observability::{inflight_tracker::InFlightRequestTracker, otel_trace::OtelTraceInjector},
     policies::PolicyRegistry,
     routers::{
-        common::{openai_bridge::FormatRegistry, realtime::RealtimeRegistry},
+        common::{
+            openai_bridge::FormatRegistry, prompt_guard::PromptGuard, realtime::RealtimeRegistry,
+        },
         grpc::multimodal::MultimodalConfigRegistry,
+        http::pd_pairs::PdPairTracker,
         router_manager::RouterManager,
     },
     wasm::{config::WasmRuntimeConfig, module_manager::WasmModuleManager},
-    worker::{KvEventMonitor, WorkerMonitor, WorkerRegistry, WorkerService},
+    worker::{
+        FederationCatalog, KvEventMonitor, MaintenanceController, StandbyController,
+        WorkerDebugTracer, WorkerMonitor, WorkerRegistry, WorkerService,
+    },
     workflow::{JobQueue, WorkflowEngines},
 };
 
@@ -54,6 +65,9 @@ pub struct AppContext {
     pub rate_limiter: Option<Arc<TokenBucket>>,
     pub tokenizer_registry: Arc<TokenizerRegistry>,
     pub multimodal_config_registry: Arc<MultimodalConfigRegistry>,
+    /// Multimodal processor specs, shared so runtime-registered specs apply
+    /// to every gRPC router.
+    pub multimodal_model_registry: Arc<ModelRegistry>,
     pub reasoning_parser_factory: Option<ReasoningParserFactory>,
     pub tool_parser_factory: Option<ToolParserFactory>,
     pub worker_registry: Arc<WorkerRegistry>,
@@ -62,6 +76,20 @@ pub struct AppContext {
     pub response_storage: Arc<dyn ResponseStorage>,
     pub conversation_storage: Arc<dyn ConversationStorage>,
     pub conversation_item_storage: Arc<dyn ConversationItemStorage>,
+    /// Sampled request/response store; `None` unless `debug_capture.enabled`.
+    pub debug_capture_storage: Option<Arc<dyn DebugCaptureStorage>>,
+    /// Generated file store; `None` unless `file_store.enabled`.
+    pub file_storage: Option<Arc<dyn FileStorage>>,
+    /// Ingested file chunks for `file_search`; `None` unless `vector_store.enabled`.
+    pub vector_store_storage: Option<Arc<dyn VectorStoreStorage>>,
+    /// Stored chat completions; `None` unless `chat_completion_store.enabled`.
+    pub chat_completion_storage: Option<Arc<dyn ChatCompletionStorage>>,
+    /// Tenant-registered MCP servers; `None` unless `tenant_mcp_servers.enabled`.
+    pub tenant_mcp_server_storage: Option<Arc<dyn TenantMcpServerStorage>>,
+    /// Tenant prompt template libraries; `None` unless `prompts.enabled`.
+    pub prompt_storage: Option<Arc<dyn PromptStorage>>,
+    /// Asynchronous generation jobs; `None` unless `async_generation.enabled`.
+    pub generation_job_storage: Option<Arc<dyn GenerationJobStorage>>,
     pub worker_monitor: Option<Arc<WorkerMonitor>>,
     pub configured_reasoning_parser: Option<String>,
     pub configured_tool_parser: Option<String>,
@@ -71,6 +99,20 @@ pub struct AppContext {
     pub mcp_format_registry: FormatRegistry,
     pub wasm_manager: Option<Arc<WasmModuleManager>>,
     pub worker_service: Arc<WorkerService>,
+    /// Imports the model catalogs of federated smg workers.
+    pub federation_catalog: Arc<FederationCatalog>,
+    /// Promotes standby workers when their regular pool runs short.
+    pub standby: Arc<StandbyController>,
+    /// Applies scheduled and admin-opened maintenance windows.
+    pub maintenance: Arc<MaintenanceController>,
+    /// Admin-opened per-worker payload trace sessions.
+    pub worker_debug_tracer: Arc<WorkerDebugTracer>,
+    /// Prompt-injection screening of chat and completion requests, when enabled.
+    pub prompt_guard: Option<Arc<PromptGuard>>,
+    /// Routing rule table, replaceable through `/admin/routing-rules`.
+    pub routing_rules: Arc<RoutingRules>,
+    /// Per-pair PD latency accounting and slow-pair detection.
+    pub pd_pairs: Arc<PdPairTracker>,
     pub inflight_tracker: Arc<InFlightRequestTracker>,
     pub kv_event_monitor: Option<Arc<KvEventMonitor>>,
     pub realtime_registry: Arc<RealtimeRegistry>,
@@ -101,6 +143,13 @@ pub struct AppContextBuilder {
     response_storage: Option<Arc<dyn ResponseStorage>>,
     conversation_storage: Option<Arc<dyn ConversationStorage>>,
     conversation_item_storage: Option<Arc<dyn ConversationItemStorage>>,
+    debug_capture_storage: Option<Arc<dyn DebugCaptureStorage>>,
+    file_storage: Option<Arc<dyn FileStorage>>,
+    vector_store_storage: Option<Arc<dyn VectorStoreStorage>>,
+    chat_completion_storage: Option<Arc<dyn ChatCompletionStorage>>,
+    tenant_mcp_server_storage: Option<Arc<dyn TenantMcpServerStorage>>,
+    prompt_storage: Option<Arc<dyn PromptStorage>>,
+    generation_job_storage: Option<Arc<dyn GenerationJobStorage>>,
     worker_monitor: Option<Arc<WorkerMonitor>>,
     worker_job_queue: Option<Arc<OnceLock<Arc<JobQueue>>>>,
     workflow_engines: Option<Arc<OnceLock<WorkflowEngines>>>,
@@ -154,6 +203,13 @@ impl AppContextBuilder {
             response_storage: None,
             conversation_storage: None,
             conversation_item_storage: None,
+            debug_capture_storage: None,
+            file_storage: None,
+            vector_store_storage: None,
+            chat_completion_storage: None,
+            tenant_mcp_server_storage: None,
+            prompt_storage: None,
+            generation_job_storage: None,
             worker_monitor: None,
             worker_job_queue: None,
             workflow_engines: None,
@@ -235,6 +291,56 @@ impl AppContextBuilder {
         self
     }
 
+    pub fn debug_capture_storage(
+        mut self,
+        debug_capture_storage: Option<Arc<dyn DebugCaptureStorage>>,
+    ) -> Self {
+        self.debug_capture_storage = debug_capture_storage;
+        self
+    }
+
+    pub fn file_storage(mut self, file_storage: Option<Arc<dyn FileStorage>>) -> Self {
+        self.file_storage = file_storage;
+        self
+    }
+
+    pub fn vector_store_storage(
+        mut self,
+        vector_store_storage: Option<Arc<dyn VectorStoreStorage>>,
+    ) -> Self {
+        self.vector_store_storage = vector_store_storage;
+        self
+    }
+
+    pub fn tenant_mcp_server_storage(
+        mut self,
+        tenant_mcp_server_storage: Option<Arc<dyn TenantMcpServerStorage>>,
+    ) -> Self {
+        self.tenant_mcp_server_storage = tenant_mcp_server_storage;
+        self
+    }
+
+    pub fn prompt_storage(mut self, prompt_storage: Option<Arc<dyn PromptStorage>>) -> Self {
+        self.prompt_storage = prompt_storage;
+        self
+    }
+
+    pub fn generation_job_storage(
+        mut self,
+        generation_job_storage: Option<Arc<dyn GenerationJobStorage>>,
+    ) -> Self {
+        self.generation_job_storage = generation_job_storage;
+        self
+    }
+
+    pub fn chat_completion_storage(
+        mut self,
+        chat_completion_storage: Option<Arc<dyn ChatCompletionStorage>>,
+    ) -> Self {
+        self.chat_completion_storage = chat_completion_storage;
+        self
+    }
+
     pub fn worker_monitor(mut self, worker_monitor: Option<Arc<WorkerMonitor>>) -> Self {
         self.worker_monitor = worker_monitor;
         self
@@ -331,17 +437,31 @@ impl AppContextBuilder {
             worker_job_queue.clone(),
             router_config.clone(),
         ));
+        let maintenance =
+            MaintenanceController::new(&router_config.maintenance, worker_registry.clone());
+        let standby = StandbyController::new(&router_config.standby, worker_registry.clone());
+        let client = self
+            .client
+            .ok_or(AppContextBuildError::MissingField("client"))?;
+        let federation_catalog = FederationCatalog::new(
+            &router_config.federation,
+            worker_registry.clone(),
+            client.clone(),
+        );
+        let prompt_guard = PromptGuard::from_config(&router_config.prompt_guard, client.clone())
+            .map_err(AppContextBuildError::InvalidConfig)?;
+        let routing_rules = Arc::new(RoutingRules::new(&router_config.routing_rules));
+        let pd_pairs = PdPairTracker::new(&router_config.pd_pairs);
 
         Ok(AppContext {
-            client: self
-                .client
-                .ok_or(AppContextBuildError::MissingField("client"))?,
+            client,
             router_config,
             rate_limiter: self.rate_limiter,
             tokenizer_registry: self
                 .tokenizer_registry
                 .ok_or(AppContextBuildError::MissingField("tokenizer_registry"))?,
             multimodal_config_registry: Arc::new(MultimodalConfigRegistry::new()),
+            multimodal_model_registry: Arc::new(ModelRegistry::default()),
             reasoning_parser_factory: self.reasoning_parser_factory,
             tool_parser_factory: self.tool_parser_factory,
             worker_registry,
@@ -358,6 +478,13 @@ impl AppContextBuilder {
             conversation_item_storage: self.conversation_item_storage.ok_or(
                 AppContextBuildError::MissingField("conversation_item_storage"),
             )?,
+            debug_capture_storage: self.debug_capture_storage,
+            file_storage: self.file_storage,
+            vector_store_storage: self.vector_store_storage,
+            chat_completion_storage: self.chat_completion_storage,
+            tenant_mcp_server_storage: self.tenant_mcp_server_storage,
+            prompt_storage: self.prompt_storage,
+            generation_job_storage: self.generation_job_storage,
             worker_monitor: self.worker_monitor,
             configured_reasoning_parser,
             configured_tool_parser,
@@ -371,6 +498,13 @@ impl AppContextBuilder {
             mcp_format_registry: self.mcp_format_registry.unwrap_or_default(),
             wasm_manager: self.wasm_manager,
             worker_service,
+            federation_catalog,
+            standby,
+            maintenance,
+            worker_debug_tracer: WorkerDebugTracer::new(),
+            prompt_guard,
+            routing_rules,
+            pd_pairs,
             inflight_tracker: InFlightRequestTracker::new(),
             kv_event_monitor: self.kv_event_monitor,
             realtime_registry: Arc::new(RealtimeRegistry::new()),
@@ -397,6 +531,13 @@ impl AppContextBuilder {
             .with_policy_registry(&router_config)
             .with_storage(&router_config)
             .await?
+            .maybe_debug_capture_storage(&router_config)
+            .maybe_file_storage(&router_config)
+            .maybe_vector_store_storage(&router_config)
+            .maybe_chat_completion_storage(&router_config)
+            .maybe_tenant_mcp_server_storage(&router_config)
+            .maybe_prompt_storage(&router_config)
+            .maybe_generation_job_storage(&router_config)
             .with_worker_monitor(&router_config)?
             .with_worker_job_queue()
             .with_workflow_engines()
@@ -527,10 +668,13 @@ impl AppContextBuilder {
 
     /// Create policy registry
     fn with_policy_registry(mut self, config: &RouterConfig) -> Self {
-        self.policy_registry = Some(Arc::new(PolicyRegistry::with_override(
-            config.policy.clone(),
-            config.routing_key_override.clone(),
-        )));
+        self.policy_registry = Some(Arc::new(
+            PolicyRegistry::with_override(
+                config.policy.clone(),
+                config.routing_key_override.clone(),
+            )
+            .with_sessions(&config.sessions),
+        ));
         self
     }
 
@@ -567,29 +711,153 @@ impl AppContextBuilder {
         self.response_storage = Some(bundle.response_storage);
         self.conversation_storage = Some(bundle.conversation_storage);
         self.conversation_item_storage = Some(bundle.conversation_item_storage);
+        self.generation_job_storage = bundle.generation_job_storage;
 
         Ok(self)
     }
 
+    /// Create the bounded debug capture store when capture is enabled
+    fn maybe_debug_capture_storage(mut self, config: &RouterConfig) -> Self {
+        self.debug_capture_storage = config.debug_capture.enabled.then(|| {
+            debug!(
+                sample_rate = config.debug_capture.sample_rate,
+                max_entries = config.debug_capture.max_entries,
+                "Debug capture enabled"
+            );
+            Arc::new(MemoryDebugCaptureStorage::new(
+                config.debug_capture.max_entries,
+            )) as Arc<dyn DebugCaptureStorage>
+        });
+        self
+    }
+
+    /// Create the bounded file store when it is enabled
+    fn maybe_file_storage(mut self, config: &RouterConfig) -> Self {
+        self.file_storage = config.file_store.enabled.then(|| {
+            debug!(
+                max_files = config.file_store.max_files,
+                "File store enabled"
+            );
+            Arc::new(MemoryFileStorage::new(config.file_store.max_files)) as Arc<dyn FileStorage>
+        });
+        self
+    }
+
+    /// Create the in-memory vector store when it is enabled
+    fn maybe_vector_store_storage(mut self, config: &RouterConfig) -> Self {
+        let store = &config.vector_store;
+        self.vector_store_storage = store.enabled.then(|| {
+            debug!(
+                max_chunks_per_store = store.max_chunks_per_store,
+                "Vector store enabled"
+            );
+            Arc::new(MemoryVectorStoreStorage::new(store.max_chunks_per_store))
+                as Arc<dyn VectorStoreStorage>
+        });
+        self
+    }
+
+    /// Create the tenant MCP server registry when it is enabled
+    fn maybe_tenant_mcp_server_storage(mut self, config: &RouterConfig) -> Self {
+        let registry = &config.tenant_mcp_servers;
+        self.tenant_mcp_server_storage = registry.enabled.then(|| {
+            debug!(
+                max_servers_per_tenant = registry.max_servers_per_tenant,
+                "Tenant MCP servers enabled"
+            );
+            Arc::new(MemoryTenantMcpServerStorage::new(
+                registry.max_servers_per_tenant,
+            )) as Arc<dyn TenantMcpServerStorage>
+        });
+        self
+    }
+
+    /// Create the prompt template library when it is enabled. Prompts are
+    /// always held in memory, whatever the history backend
+    fn maybe_prompt_storage(mut self, config: &RouterConfig) -> Self {
+        let prompts = &config.prompts;
+        if prompts.enabled
+            && !matches!(
+                config.history_backend,
+                HistoryBackend::Memory | HistoryBackend::None
+            )
+        {
+            warn!(
+                history_backend = ?config.history_backend,
+                "Prompt templates are held in memory, not in the history backend; \
+                 they are lost on restart and not shared between gateway replicas"
+            );
+        }
+        self.prompt_storage = prompts.enabled.then(|| {
+            debug!(
+                max_prompts_per_tenant = prompts.max_prompts_per_tenant,
+                max_versions_per_prompt = prompts.max_versions_per_prompt,
+                "Prompt templates enabled"
+            );
+            Arc::new(MemoryPromptStorage::new(
+                prompts.max_prompts_per_tenant,
+                prompts.max_versions_per_prompt,
+            )) as Arc<dyn PromptStorage>
+        });
+        self
+    }
+
+    /// Keep the history backend's generation job store when async generation
+    /// is enabled, falling back to a bounded in-memory one for the memory and
+    /// none backends, whose jobs do not survive a restart
+    fn maybe_generation_job_storage(mut self, config: &RouterConfig) -> Self {
+        let async_generation = &config.async_generation;
+        let durable = self.generation_job_storage.take();
+        self.generation_job_storage = async_generation.enabled.then(|| {
+            durable.unwrap_or_else(|| {
+                debug!(
+                    max_retained_jobs = async_generation.max_retained_jobs,
+                    "Generation jobs kept in memory"
+                );
+                Arc::new(MemoryGenerationJobStorage::new(
+                    async_generation.max_retained_jobs,
+                )) as Arc<dyn GenerationJobStorage>
+            })
+        });
+        self
+    }
+
+    /// Create the bounded chat completion store when it is enabled
+    fn maybe_chat_completion_storage(mut self, config: &RouterConfig) -> Self {
+        let store = &config.chat_completion_store;
+        self.chat_completion_storage = store.enabled.then(|| {
+            debug!(
+                max_entries = store.max_entries,
+                "Chat completion store enabled"
+            );
+            Arc::new(MemoryChatCompletionStorage::new(store.max_entries))
+                as Arc<dyn ChatCompletionStorage>
+        });
+        self
+    }
+
     /// Create load monitor
     fn with_worker_monitor(mut self, config: &RouterConfig) -> Result<Self, String> {
         let client = self
             .client
             .as_ref()
             .ok_or_else(|| "client must be set before load monitor".to_string())?;
-        self.worker_monitor = Some(Arc::new(WorkerMonitor::new(
-            self.worker_registry
-                .as_ref()
-                .ok_or_else(|| "worker_registry must be set before load monitor".to_string())?
-                .clone(),
-            self.policy_registry
-                .as_ref()
-                .ok_or_else(|| "policy_registry must be set before load monitor".to_string())?
-                .clone(),
-            client.clone(),
-            config.load_monitor_interval_secs,
-            config.engine_metrics,
-        )));
+        self.worker_monitor = Some(Arc::new(
+            WorkerMonitor::new(
+                self.worker_registry
+                    .as_ref()
+                    .ok_or_else(|| "worker_registry must be set before load monitor".to_string())?
+                    .clone(),
+                self.policy_registry
+                    .as_ref()
+                    .ok_or_else(|| "policy_registry must be set before load monitor".to_string())?
+                    .clone(),
+                client.clone(),
+                config.load_monitor_interval_secs,
+                config.engine_metrics,
+            )
+            .with_load_polling(config.latency_budget.enabled),
+        ));
         Ok(self)
     }
 
@@ -609,16 +877,16 @@ impl AppContextBuilder {
     ///
     /// This initializes the MCP orchestrator with an empty config and default settings.
     /// MCP servers will be registered later via the InitializeMcpServers job.
-    async fn with_mcp_orchestrator(
-        mut self,
-        _router_config: &RouterConfig,
-    ) -> Result<Self, String> {
+    async fn with_mcp_orchestrator(mut self, router_config: &RouterConfig) -> Result<Self, String> {
         // Create OnceLock container
         let mcp_orchestrator_lock = Arc::new(OnceLock::new());
 
         // Always create with empty config and defaults
         debug!("Initializing MCP orchestrator with empty config and default settings (5 min TTL, 100 max connections)");
 
+        // Limits and sampling policies apply to servers registered later, so
+        // take them from the file up front.
+        let file_config = router_config.mcp_config.as_ref();
         let empty_config = smg_mcp::McpConfig {
             servers: Vec::new(),
             pool: Default::default(),
@@ -626,11 +894,14 @@ impl AppContextBuilder {
             warmup: Vec::new(),
             inventory: Default::default(),
             policy: Default::default(),
+            limits: file_config.map(|c| c.limits.clone()).unwrap_or_default(),
+            sampling: file_config.map(|c| c.sampling.clone()).unwrap_or_default(),
         };
 
         let orchestrator = McpOrchestrator::new(empty_config)
             .await
-            .map_err(|e| format!("Failed to initialize MCP orchestrator: {e}"))?;
+            .map_err(|e| format!("Failed to initialize MCP orchestrator: {e}"))?
+            .with_trace_injector(Arc::new(OtelTraceInjector));
 
         // Store the initialized orchestrator in the OnceLock
         mcp_orchestrator_lock
@@ -735,6 +1006,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         }));
     }
 }
@@ -4,10 +4,17 @@ use openai_protocol::worker::TransportMode;
 use smg_mcp::McpConfig;
 
 use super::{
-    CircuitBreakerConfig, ConfigError, ConfigResult, DiscoveryConfig, HealthCheckConfig,
-    HistoryBackend, MetricsConfig, OracleConfig, PolicyConfig, PostgresConfig, RedisConfig,
-    RetryConfig, RouterConfig, RoutingKeyOverrideConfig, RoutingMode, TenantApiKeyEntry,
-    TokenizerCacheConfig, TraceConfig,
+    AsyncGenerationConfig, ChatCompletionStoreConfig, CircuitBreakerConfig, ConfigError,
+    ConfigResult, ConversationCompactionConfig, DebugCaptureConfig, DiscoveryConfig,
+    ExperimentsConfig, FaultInjectionConfig, FederationConfig, FileStoreConfig, GrpcPipelineConfig,
+    HealthCheckConfig, HistoryBackend, LatencyBudgetConfig, MaintenanceConfig, MapReduceConfig,
+    MetadataCacheConfig, MetricsConfig, OracleConfig, PartialTranscriptsConfig, PdBootstrapConfig,
+    PdPairsConfig, PolicyConfig, PostgresConfig, PromptGuardConfig, PromptsConfig,
+    ProvenanceConfig, RedisConfig, RequestCoalescingConfig, RequestFeaturesConfig,
+    RequestTagsConfig, RetryConfig, RouterConfig, RoutingKeyOverrideConfig, RoutingMode,
+    RoutingRulesConfig, SamplingLimitsConfig, SessionsConfig, StandbyConfig, StreamFanoutConfig,
+    StreamIntegrityConfig, StreamRecoveryConfig, TenantApiKeyEntry, TenantMcpServersConfig,
+    TokenizerCacheConfig, TraceConfig, VectorStoreConfig, WebhookConfig,
 };
 use crate::worker::ConnectionMode;
 
@@ -134,6 +141,8 @@ impl RouterConfigBuilder {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         };
         self
     }
@@ -444,6 +453,231 @@ impl RouterConfigBuilder {
         self
     }
 
+    // ==================== Debug Capture ====================
+
+    pub fn debug_capture(mut self, debug_capture: DebugCaptureConfig) -> Self {
+        self.config.debug_capture = debug_capture;
+        self
+    }
+
+    // ==================== File Store ====================
+
+    pub fn file_store(mut self, file_store: FileStoreConfig) -> Self {
+        self.config.file_store = file_store;
+        self
+    }
+
+    // ==================== Vector Store ====================
+
+    pub fn vector_store(mut self, vector_store: VectorStoreConfig) -> Self {
+        self.config.vector_store = vector_store;
+        self
+    }
+
+    // ==================== Chat Completion Store ====================
+
+    pub fn chat_completion_store(mut self, store: ChatCompletionStoreConfig) -> Self {
+        self.config.chat_completion_store = store;
+        self
+    }
+
+    // ==================== Fault Injection ====================
+
+    pub fn fault_injection(mut self, fault_injection: FaultInjectionConfig) -> Self {
+        self.config.fault_injection = fault_injection;
+        self
+    }
+
+    // ==================== Metadata Cache ====================
+
+    pub fn metadata_cache(mut self, metadata_cache: MetadataCacheConfig) -> Self {
+        self.config.metadata_cache = metadata_cache;
+        self
+    }
+
+    // ==================== Stream Recovery ====================
+
+    pub fn stream_recovery(mut self, stream_recovery: StreamRecoveryConfig) -> Self {
+        self.config.stream_recovery = stream_recovery;
+        self
+    }
+
+    // ==================== Stream Fan-out ====================
+
+    pub fn stream_fanout(mut self, stream_fanout: StreamFanoutConfig) -> Self {
+        self.config.stream_fanout = stream_fanout;
+        self
+    }
+
+    // ==================== Request Coalescing ====================
+
+    pub fn request_coalescing(mut self, request_coalescing: RequestCoalescingConfig) -> Self {
+        self.config.request_coalescing = request_coalescing;
+        self
+    }
+
+    // ==================== Conversation Compaction ====================
+
+    pub fn conversation_compaction(
+        mut self,
+        conversation_compaction: ConversationCompactionConfig,
+    ) -> Self {
+        self.config.conversation_compaction = conversation_compaction;
+        self
+    }
+
+    // ==================== Webhooks ====================
+
+    pub fn webhooks(mut self, webhooks: WebhookConfig) -> Self {
+        self.config.webhooks = webhooks;
+        self
+    }
+
+    // ==================== Async Generation ====================
+
+    pub fn async_generation(mut self, async_generation: AsyncGenerationConfig) -> Self {
+        self.config.async_generation = async_generation;
+        self
+    }
+
+    // ==================== Map-Reduce ====================
+
+    pub fn map_reduce(mut self, map_reduce: MapReduceConfig) -> Self {
+        self.config.map_reduce = map_reduce;
+        self
+    }
+
+    // ==================== gRPC Pipeline ====================
+
+    pub fn grpc_pipeline(mut self, grpc_pipeline: GrpcPipelineConfig) -> Self {
+        self.config.grpc_pipeline = grpc_pipeline;
+        self
+    }
+
+    // ==================== Sampling Limits ====================
+
+    pub fn sampling_limits(mut self, sampling_limits: SamplingLimitsConfig) -> Self {
+        self.config.sampling_limits = sampling_limits;
+        self
+    }
+
+    // ==================== Request Tags ====================
+
+    pub fn request_tags(mut self, request_tags: RequestTagsConfig) -> Self {
+        self.config.request_tags = request_tags;
+        self
+    }
+
+    // ==================== Request Features ====================
+
+    pub fn request_features(mut self, request_features: RequestFeaturesConfig) -> Self {
+        self.config.request_features = request_features;
+        self
+    }
+
+    // ==================== Maintenance ====================
+
+    pub fn maintenance(mut self, maintenance: MaintenanceConfig) -> Self {
+        self.config.maintenance = maintenance;
+        self
+    }
+
+    // ==================== Experiments ====================
+
+    pub fn experiments(mut self, experiments: ExperimentsConfig) -> Self {
+        self.config.experiments = experiments;
+        self
+    }
+
+    // ==================== Prompt Guard ====================
+
+    pub fn prompt_guard(mut self, prompt_guard: PromptGuardConfig) -> Self {
+        self.config.prompt_guard = prompt_guard;
+        self
+    }
+
+    // ==================== Provenance ====================
+
+    pub fn provenance(mut self, provenance: ProvenanceConfig) -> Self {
+        self.config.provenance = provenance;
+        self
+    }
+
+    // ==================== Routing Rules ====================
+
+    pub fn routing_rules(mut self, routing_rules: RoutingRulesConfig) -> Self {
+        self.config.routing_rules = routing_rules;
+        self
+    }
+
+    // ==================== Federation ====================
+
+    pub fn federation(mut self, federation: FederationConfig) -> Self {
+        self.config.federation = federation;
+        self
+    }
+
+    // ==================== Standby ====================
+
+    pub fn standby(mut self, standby: StandbyConfig) -> Self {
+        self.config.standby = standby;
+        self
+    }
+
+    // ==================== PD Bootstrap ====================
+
+    pub fn pd_bootstrap(mut self, pd_bootstrap: PdBootstrapConfig) -> Self {
+        self.config.pd_bootstrap = pd_bootstrap;
+        self
+    }
+
+    pub fn pd_pairs(mut self, pd_pairs: PdPairsConfig) -> Self {
+        self.config.pd_pairs = pd_pairs;
+        self
+    }
+
+    // ==================== Sessions ====================
+
+    pub fn sessions(mut self, sessions: SessionsConfig) -> Self {
+        self.config.sessions = sessions;
+        self
+    }
+
+    // ==================== Partial Transcripts ====================
+
+    pub fn partial_transcripts(mut self, partial_transcripts: PartialTranscriptsConfig) -> Self {
+        self.config.partial_transcripts = partial_transcripts;
+        self
+    }
+
+    // ==================== Tenant MCP Servers ====================
+
+    pub fn tenant_mcp_servers(mut self, tenant_mcp_servers: TenantMcpServersConfig) -> Self {
+        self.config.tenant_mcp_servers = tenant_mcp_servers;
+        self
+    }
+
+    // ==================== Latency Budget ====================
+
+    pub fn latency_budget(mut self, latency_budget: LatencyBudgetConfig) -> Self {
+        self.config.latency_budget = latency_budget;
+        self
+    }
+
+    // ==================== Stream Integrity ====================
+
+    pub fn stream_integrity(mut self, stream_integrity: StreamIntegrityConfig) -> Self {
+        self.config.stream_integrity = stream_integrity;
+        self
+    }
+
+    // ==================== Prompts ====================
+
+    pub fn prompts(mut self, prompts: PromptsConfig) -> Self {
+        self.config.prompts = prompts;
+        self
+    }
+
     pub fn model_path<S: Into<String>>(mut self, path: S) -> Self {
         self.config.model_path = Some(path.into());
         self
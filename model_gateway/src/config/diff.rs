@@ -0,0 +1,615 @@
+//! Config file loading, semantic checks, and effective-behavior diffs.
+//!
+//! Backs `smg validate-config` and is shared with any path that swaps a
+//! running config for a new one: [`validate_transition`] runs the full
+//! deserializer + [`ConfigValidator`] on the candidate, layers advisory
+//! checks on top (missing files, settings that have no effect, PD policy
+//! conflicts), and reports what would change relative to the current config.
+
+use std::{fmt, path::Path};
+
+use serde_json::{Map, Value};
+
+use super::{
+    schema::{config_schema, unknown_fields},
+    validation::ConfigValidator,
+    ConfigError, ConfigResult, PolicyConfig, RouterConfig, RoutingMode,
+};
+
+/// Top-level fields excluded from diffs: raw certificate bytes are loaded
+/// from paths and are noise in a behavioral diff.
+const DIFF_IGNORED_FIELDS: &[&str] = &["ca_certificates"];
+
+/// Path suffixes whose values are masked in diff output.
+const SECRET_SUFFIXES: &[&str] = &[
+    "api_key",
+    "tenant_api_keys",
+    "key",
+    "password",
+    "db_url",
+    "url",
+];
+
+/// Load a YAML (or JSON — a YAML subset) config file, filling every field the
+/// file omits from `RouterConfig::default()`. With `strict`, fields no config
+/// type declares are an error instead of being ignored.
+pub fn load_config_file(path: impl AsRef<Path>, strict: bool) -> ConfigResult<RouterConfig> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::ValidationFailed {
+        reason: format!("Failed to read config from {}: {e}", path.display()),
+    })?;
+    let parsed = if strict {
+        parse_config_str_strict(&contents)
+    } else {
+        parse_config_str(&contents)
+    };
+    parsed.map_err(|e| ConfigError::ValidationFailed {
+        reason: format!("Failed to parse config from {}: {e}", path.display()),
+    })
+}
+
+/// Parse a YAML/JSON config document layered over `RouterConfig::default()`.
+pub fn parse_config_str(contents: &str) -> Result<RouterConfig, String> {
+    apply_overlay(parse_overlay(contents)?)
+}
+
+/// Like [`parse_config_str`], but rejects a document that sets fields no
+/// config type declares, naming each by its path (`policy.body_polcy`).
+pub fn parse_config_str_strict(contents: &str) -> Result<RouterConfig, String> {
+    let overlay = parse_overlay(contents)?;
+    let unknown = unknown_fields(&config_schema(), &overlay);
+    if !unknown.is_empty() {
+        return Err(format!("unknown config fields: {}", unknown.join(", ")));
+    }
+    apply_overlay(overlay)
+}
+
+fn parse_overlay(contents: &str) -> Result<Value, String> {
+    let overlay: Value = serde_yaml::from_str(contents).map_err(|e| e.to_string())?;
+    match overlay {
+        Value::Null => Ok(Value::Object(Map::new())),
+        Value::Object(_) => Ok(overlay),
+        other => Err(format!("expected a mapping at top level, found {other}")),
+    }
+}
+
+fn apply_overlay(overlay: Value) -> Result<RouterConfig, String> {
+    let mut merged = serde_json::to_value(RouterConfig::default()).map_err(|e| e.to_string())?;
+    merge_json(&mut merged, overlay);
+    serde_json::from_value(merged).map_err(|e| e.to_string())
+}
+
+/// Deep-merge `overlay` into `base`. Objects merge key-by-key unless they are
+/// differently-tagged enum variants (`type` differs), which replace wholesale
+/// so fields of the old variant don't leak into the new one.
+fn merge_json(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map))
+            if base_map.get("type") == overlay_map.get("type")
+                || !overlay_map.contains_key("type") =>
+        {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Severity of a semantic finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The config will be rejected at startup.
+    Error,
+    /// The config loads, but likely does not do what the operator intended.
+    Warning,
+}
+
+/// A single semantic finding about a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{label}: {}: {}", self.field, self.message)
+    }
+}
+
+fn warning(field: &str, message: impl Into<String>) -> ConfigIssue {
+    ConfigIssue {
+        severity: Severity::Warning,
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Run the startup validator plus advisory checks. An empty result means the
+/// config is clean.
+pub fn check_config(config: &RouterConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    if let Err(e) = ConfigValidator::validate(config) {
+        issues.push(ConfigIssue {
+            severity: Severity::Error,
+            field: error_field(&e),
+            message: e.to_string(),
+        });
+    }
+
+    check_missing_files(config, &mut issues);
+    check_orphaned_settings(config, &mut issues);
+    check_pd_policy_conflicts(config, &mut issues);
+    issues
+}
+
+fn error_field(error: &ConfigError) -> String {
+    match error {
+        ConfigError::InvalidValue { field, .. } | ConfigError::MissingRequired { field } => {
+            field.clone()
+        }
+        ConfigError::ValidationFailed { .. } | ConfigError::IncompatibleConfig { .. } => {
+            "config".to_string()
+        }
+    }
+}
+
+fn check_missing_files(config: &RouterConfig, issues: &mut Vec<ConfigIssue>) {
+    let files = [
+        (
+            "storage_hook_wasm_path",
+            config.storage_hook_wasm_path.as_deref(),
+        ),
+        (
+            "priority_scheduler_config",
+            config.priority_scheduler_config.as_deref(),
+        ),
+    ];
+    for (field, path) in files {
+        if let Some(path) = path {
+            if !Path::new(path).is_file() {
+                issues.push(ConfigIssue {
+                    severity: Severity::Error,
+                    field: field.to_string(),
+                    message: format!("file not found: {path}"),
+                });
+            }
+        }
+    }
+    if let Some(path) = config.storage_hook_wasm_path.as_deref() {
+        if !path.ends_with(".wasm") {
+            issues.push(warning(
+                "storage_hook_wasm_path",
+                format!("{path} does not have a .wasm extension"),
+            ));
+        }
+    }
+}
+
+/// Settings that are set but have no effect given the rest of the config.
+fn check_orphaned_settings(config: &RouterConfig, issues: &mut Vec<ConfigIssue>) {
+    if config.priority_scheduler_config.is_some() && !config.priority_scheduler_enabled {
+        issues.push(warning(
+            "priority_scheduler_config",
+            "set but priority_scheduler_enabled is false; the file is ignored",
+        ));
+    }
+    if config.rate_limit_tokens_per_second.is_some() && config.max_concurrent_requests <= 0 {
+        issues.push(warning(
+            "rate_limit_tokens_per_second",
+            "set but max_concurrent_requests <= 0 disables rate limiting",
+        ));
+    }
+    if config.dp_minimum_tokens_scheduler && !config.dp_aware {
+        issues.push(warning(
+            "dp_minimum_tokens_scheduler",
+            "enabled but dp_aware is false; the scheduler never runs",
+        ));
+    }
+    if matches!(
+        config.mode,
+        RoutingMode::Regular { .. }
+            | RoutingMode::OpenAI { .. }
+            | RoutingMode::Anthropic { .. }
+            | RoutingMode::Gemini { .. }
+    ) && config.discovery.as_ref().is_some_and(|d| {
+        d.enabled && (!d.prefill_selector.is_empty() || !d.decode_selector.is_empty())
+    }) {
+        issues.push(warning(
+            "discovery.prefill_selector",
+            "prefill/decode selectors are ignored outside PD mode",
+        ));
+    }
+    if config.disable_retries && config.retry.max_retries > 1 {
+        issues.push(warning(
+            "retry.max_retries",
+            "overridden to 1 because disable_retries is true",
+        ));
+    }
+}
+
+/// Prefill and decode share KV state; cache-aware trees built at different
+/// block sizes will never agree on what is cached.
+fn check_pd_policy_conflicts(config: &RouterConfig, issues: &mut Vec<ConfigIssue>) {
+    if !matches!(
+        config.mode,
+        RoutingMode::PrefillDecode { .. } | RoutingMode::EncodePrefillDecode { .. }
+    ) {
+        return;
+    }
+    let prefill = config.mode.get_prefill_policy(&config.policy);
+    let decode = config.mode.get_decode_policy(&config.policy);
+    if let (
+        PolicyConfig::CacheAware {
+            block_size: prefill_block,
+            ..
+        },
+        PolicyConfig::CacheAware {
+            block_size: decode_block,
+            ..
+        },
+    ) = (prefill, decode)
+    {
+        if prefill_block != decode_block {
+            issues.push(warning(
+                "mode.decode_policy.block_size",
+                format!(
+                    "prefill cache_aware block_size {prefill_block} differs from decode block_size {decode_block}"
+                ),
+            ));
+        }
+    }
+    for (role, policy) in [("prefill", prefill), ("decode", decode)] {
+        if let PolicyConfig::CacheAware {
+            balance_token_usage_threshold,
+            overload_token_usage_threshold,
+            ..
+        } = policy
+        {
+            if *overload_token_usage_threshold < 1.0
+                && *balance_token_usage_threshold >= *overload_token_usage_threshold
+            {
+                issues.push(warning(
+                    &format!("mode.{role}_policy.balance_token_usage_threshold"),
+                    "is >= overload_token_usage_threshold; the spread trigger can never fire first",
+                ));
+            }
+        }
+    }
+}
+
+/// Kind of change between two configs at one field path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added(Value),
+    Removed(Value),
+    Changed { old: Value, new: Value },
+}
+
+/// One changed leaf field, addressed by a dotted path (`retry.max_retries`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+impl ConfigChange {
+    /// Short explanation of the runtime effect for high-impact fields.
+    pub fn effect(&self) -> Option<&'static str> {
+        let top = self.path.split('.').next().unwrap_or_default();
+        Some(match top {
+            "mode" => "routing topology changes; requires restart",
+            "policy" => "load-balancing policy changes for all models without an override",
+            "host" | "port" | "health_check_port" => "listener address changes; requires restart",
+            "max_concurrent_requests" | "queue_size" | "rate_limit_tokens_per_second" => {
+                "admission/rate limiting behavior changes"
+            }
+            "retry" | "disable_retries" => "retry behavior changes",
+            "circuit_breaker" | "disable_circuit_breaker" => "circuit breaker behavior changes",
+            "health_check" => "worker health checking changes",
+            "history_backend" | "oracle" | "postgres" | "redis" => {
+                "storage backend changes; stored responses/conversations are not migrated"
+            }
+            "api_key" | "tenant_api_keys" => "authentication credentials change",
+            "debug_capture" => "request/response capture changes",
+            "file_store" => "generated file storage changes; stored files are not migrated",
+            "vector_store" => "vector store ingestion changes; ingested chunks are lost",
+            "chat_completion_store" => {
+                "stored chat completion changes; stored completions are not migrated"
+            }
+            "fault_injection" => "injected upstream faults change",
+            "metadata_cache" => "model listing and worker metadata caching changes",
+            "stream_recovery" => "mid-stream recovery of dropped backend streams changes",
+            "stream_fanout" => "attaching subscribers to in-flight streams changes",
+            "webhooks" => "completion webhook delivery changes; dead letters are not kept",
+            "async_generation" => "asynchronous generation queue changes; in-memory jobs are lost",
+            "map_reduce" => "map-reduce orchestration of long-document chat requests changes",
+            "grpc_pipeline" => "custom gRPC pipeline stages change",
+            "sampling_limits" => "per-model sampling defaults and clamps change",
+            "request_features" => "per-request feature flags callers may enable change",
+            "request_coalescing" => "coalescing of identical concurrent requests changes",
+            "conversation_compaction" => "summarization of long conversation histories changes",
+            "tenant_mcp_servers" => "tenant MCP server registry changes; registrations are lost",
+            "latency_budget" => "latency-budget caps on max_tokens change",
+            "stream_integrity" => "integrity trailers on generation streams change",
+            "prompts" => "prompt template library changes; published prompts are lost",
+            _ => return None,
+        })
+    }
+}
+
+fn is_secret_path(path: &str) -> bool {
+    let last = path.rsplit('.').next().unwrap_or(path);
+    SECRET_SUFFIXES.contains(&last)
+}
+
+fn render_value(path: &str, value: &Value) -> String {
+    if is_secret_path(path) && !value.is_null() {
+        "<redacted>".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Added(v) => write!(f, "+ {}: {}", self.path, render_value(&self.path, v))?,
+            ChangeKind::Removed(v) => {
+                write!(f, "- {}: {}", self.path, render_value(&self.path, v))?;
+            }
+            ChangeKind::Changed { old, new } => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                render_value(&self.path, old),
+                render_value(&self.path, new)
+            )?,
+        }
+        if let Some(effect) = self.effect() {
+            write!(f, "  ({effect})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute leaf-level differences between two effective configs.
+pub fn diff_configs(old: &RouterConfig, new: &RouterConfig) -> ConfigResult<Vec<ConfigChange>> {
+    let to_value = |c: &RouterConfig| {
+        serde_json::to_value(c).map_err(|e| ConfigError::ValidationFailed {
+            reason: format!("Failed to serialize config: {e}"),
+        })
+    };
+    let mut old_value = to_value(old)?;
+    let mut new_value = to_value(new)?;
+    for field in DIFF_IGNORED_FIELDS {
+        if let Some(map) = old_value.as_object_mut() {
+            map.remove(*field);
+        }
+        if let Some(map) = new_value.as_object_mut() {
+            map.remove(*field);
+        }
+    }
+
+    let mut changes = Vec::new();
+    diff_values("", &old_value, &new_value, &mut changes);
+    Ok(changes)
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, out: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_v) in old_map {
+                let child = join_path(path, key);
+                match new_map.get(key) {
+                    Some(new_v) => diff_values(&child, old_v, new_v, out),
+                    None => out.push(ConfigChange {
+                        path: child,
+                        kind: ChangeKind::Removed(old_v.clone()),
+                    }),
+                }
+            }
+            for (key, new_v) in new_map {
+                if !old_map.contains_key(key) {
+                    out.push(ConfigChange {
+                        path: join_path(path, key),
+                        kind: ChangeKind::Added(new_v.clone()),
+                    });
+                }
+            }
+        }
+        _ if old == new => {}
+        _ => out.push(ConfigChange {
+            path: path.to_string(),
+            kind: ChangeKind::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+        }),
+    }
+}
+
+/// Result of validating a candidate config against the current one.
+#[derive(Debug, Clone)]
+pub struct ConfigTransitionReport {
+    pub issues: Vec<ConfigIssue>,
+    pub changes: Vec<ConfigChange>,
+}
+
+impl ConfigTransitionReport {
+    /// True when the candidate config has no errors (warnings are allowed).
+    pub fn is_valid(&self) -> bool {
+        !self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+}
+
+impl fmt::Display for ConfigTransitionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.issues.is_empty() {
+            writeln!(f, "Validation: OK")?;
+        } else {
+            writeln!(f, "Validation:")?;
+            for issue in &self.issues {
+                writeln!(f, "  {issue}")?;
+            }
+        }
+        if self.changes.is_empty() {
+            writeln!(f, "Changes: none")?;
+        } else {
+            writeln!(f, "Changes ({}):", self.changes.len())?;
+            for change in &self.changes {
+                writeln!(f, "  {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check `new` and diff it against `old`. Shared by `smg validate-config`
+/// and any runtime config swap.
+pub fn validate_transition(
+    old: &RouterConfig,
+    new: &RouterConfig,
+) -> ConfigResult<ConfigTransitionReport> {
+    Ok(ConfigTransitionReport {
+        issues: check_config(new),
+        changes: diff_configs(old, new)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+mode:
+  type: regular
+  worker_urls: ["http://worker:8000"]
+policy:
+  type: round_robin
+"#;
+
+    #[test]
+    fn test_parse_partial_config_fills_defaults() {
+        let config = parse_config_str(BASE).unwrap();
+        assert!(matches!(config.policy, PolicyConfig::RoundRobin));
+        assert_eq!(config.port, RouterConfig::default().port);
+        assert!(check_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_policy() {
+        let err = parse_config_str("policy:\n  type: fastest\n").unwrap_err();
+        assert!(err.contains("fastest"), "{err}");
+    }
+
+    #[test]
+    fn test_strict_parse_rejects_unknown_fields() {
+        let typo = format!("{BASE}  balance_thresh: 4\nprot: 8080\n");
+        assert!(parse_config_str(&typo).is_ok());
+        let err = parse_config_str_strict(&typo).unwrap_err();
+        assert!(err.contains("prot"), "{err}");
+        // round_robin declares no thresholds.
+        assert!(err.contains("policy.balance_thresh"), "{err}");
+        assert!(parse_config_str_strict(BASE).is_ok());
+    }
+
+    #[test]
+    fn test_tagged_enum_replaced_not_merged() {
+        let config = parse_config_str(
+            "mode:\n  type: prefill_decode\n  prefill_urls: [[\"http://p:8000\", null]]\n  decode_urls: [\"http://d:8000\"]\n",
+        )
+        .unwrap();
+        assert!(matches!(config.mode, RoutingMode::PrefillDecode { .. }));
+    }
+
+    #[test]
+    fn test_check_reports_missing_wasm_and_orphans() {
+        let mut config = parse_config_str(BASE).unwrap();
+        config.storage_hook_wasm_path = Some("/nonexistent/hook.wasm".to_string());
+        config.dp_minimum_tokens_scheduler = true;
+        let issues = check_config(&config);
+        assert!(issues
+            .iter()
+            .any(|i| i.severity == Severity::Error && i.field == "storage_hook_wasm_path"));
+        assert!(issues
+            .iter()
+            .any(|i| i.severity == Severity::Warning && i.field == "dp_minimum_tokens_scheduler"));
+    }
+
+    #[test]
+    fn test_check_flags_pd_block_size_conflict() {
+        let cache_aware = |block_size| PolicyConfig::CacheAware {
+            cache_threshold: 0.3,
+            balance_abs_threshold: 64,
+            balance_rel_threshold: 1.5,
+            eviction_interval_secs: 120,
+            max_tree_size: 1000,
+            block_size,
+            balance_token_usage_threshold: 1.0,
+            overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
+        };
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![("http://p:8000".to_string(), None)],
+                decode_urls: vec!["http://d:8000".to_string()],
+                prefill_policy: Some(cache_aware(16)),
+                decode_policy: Some(cache_aware(32)),
+            },
+            PolicyConfig::Random,
+        );
+        assert!(check_config(&config)
+            .iter()
+            .any(|i| i.field == "mode.decode_policy.block_size"));
+    }
+
+    #[test]
+    fn test_transition_diff_and_redaction() {
+        let old = parse_config_str(BASE).unwrap();
+        let new = parse_config_str(&format!(
+            "{BASE}\nretry:\n  max_retries: 2\napi_key: hunter2\n"
+        ))
+        .unwrap();
+
+        let report = validate_transition(&old, &new).unwrap();
+        assert!(report.is_valid());
+        let rendered = report.to_string();
+        assert!(
+            rendered.contains("~ retry.max_retries: 5 -> 2"),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("api_key: null -> <redacted>"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_identical_configs_have_no_changes() {
+        let config = parse_config_str(BASE).unwrap();
+        assert!(diff_configs(&config, &config).unwrap().is_empty());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod builder;
+pub mod diff;
+pub mod schema;
 pub mod types;
 pub(crate) mod validation;
 
@@ -0,0 +1,194 @@
+//! JSON Schema of the gateway configuration and unknown-field detection.
+//!
+//! [`config_schema`] is generated from the `RouterConfig` type tree and backs
+//! `smg --print-config-schema`. [`unknown_fields`] walks a config document
+//! against that schema and reports every key no config type declares, which
+//! serde would otherwise drop silently: a misspelled `body_polcy` leaves
+//! the setting at its default without any error.
+
+use serde_json::{Map, Value};
+
+use super::RouterConfig;
+
+/// JSON Schema (draft 2020-12) of a full config document.
+pub fn config_schema() -> Value {
+    schemars::schema_for!(RouterConfig).to_value()
+}
+
+/// Keys of `document` that `schema` does not declare, as paths such as
+/// `policy.body_polcy` or `routing_rules.rules[0].mach`.
+pub fn unknown_fields(schema: &Value, document: &Value) -> Vec<String> {
+    check(schema, schema, document, "")
+}
+
+fn resolve<'a>(root: &'a Value, mut schema: &'a Value) -> &'a Value {
+    while let Some(name) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix("#/$defs/"))
+    {
+        match root.get("$defs").and_then(|defs| defs.get(name)) {
+            Some(target) => schema = target,
+            None => break,
+        }
+    }
+    schema
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn check(root: &Value, schema: &Value, value: &Value, path: &str) -> Vec<String> {
+    let schema = resolve(root, schema);
+    match value {
+        Value::Object(map) => check_object(root, schema, map, path),
+        Value::Array(items) => match schema.get("items") {
+            Some(item_schema) => items
+                .iter()
+                .enumerate()
+                .flat_map(|(i, item)| check(root, item_schema, item, &format!("{path}[{i}]")))
+                .collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Properties declared by `schema` itself and by its `allOf` members, or
+/// `None` when it does not describe a struct.
+fn declared_properties(root: &Value, schema: &Value) -> Option<Map<String, Value>> {
+    let mut declared: Option<Map<String, Value>> = None;
+    let members = schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|member| resolve(root, member));
+    for part in std::iter::once(schema).chain(members) {
+        if let Some(properties) = part.get("properties").and_then(Value::as_object) {
+            declared
+                .get_or_insert_with(Map::new)
+                .extend(properties.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+    declared
+}
+
+/// Whether every `const` property of `branch` (an enum tag) matches `map`.
+fn tag_matches(branch: &Value, map: &Map<String, Value>) -> bool {
+    branch
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .all(|(key, property)| match property.get("const") {
+            Some(tag) => map.get(key) == Some(tag),
+            None => true,
+        })
+}
+
+fn check_object(root: &Value, schema: &Value, map: &Map<String, Value>, path: &str) -> Vec<String> {
+    let own = declared_properties(root, schema);
+    let branches: Vec<&Value> = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|branch| resolve(root, branch))
+        .filter(|branch| declared_properties(root, branch).is_some())
+        .collect();
+    if branches.is_empty() {
+        return check_fields(root, own, schema.get("additionalProperties"), map, path);
+    }
+
+    // An enum or an `Option`: the document is held against the variant its
+    // tag selects, or failing that the variant it fits best.
+    let mut best: Option<Vec<String>> = None;
+    for branch in branches.iter().filter(|branch| tag_matches(branch, map)) {
+        let mut declared = own.clone().unwrap_or_default();
+        declared.extend(declared_properties(root, branch).unwrap_or_default());
+        let unknown = check_fields(
+            root,
+            Some(declared),
+            branch.get("additionalProperties"),
+            map,
+            path,
+        );
+        if best.as_ref().is_none_or(|b| unknown.len() < b.len()) {
+            best = Some(unknown);
+        }
+    }
+    // No variant carries the document's tag; deserialization reports that.
+    best.unwrap_or_default()
+}
+
+fn check_fields(
+    root: &Value,
+    declared: Option<Map<String, Value>>,
+    additional: Option<&Value>,
+    map: &Map<String, Value>,
+    path: &str,
+) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in map {
+        let field = join(path, key);
+        match (declared.as_ref().and_then(|d| d.get(key)), additional) {
+            (Some(field_schema), _) => unknown.extend(check(root, field_schema, value, &field)),
+            (None, Some(map_schema)) if map_schema.is_object() => {
+                unknown.extend(check(root, map_schema, value, &field));
+            }
+            (None, _) if declared.is_some() => unknown.push(field),
+            (None, _) => {}
+        }
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_schema_declares_top_level_fields() {
+        let schema = config_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for field in ["mode", "policy", "routing_rules", "partial_transcripts"] {
+            assert!(properties.contains_key(field), "missing {field}");
+        }
+        assert!(unknown_fields(
+            &schema,
+            &serde_json::to_value(RouterConfig::default()).unwrap()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_reported_by_path() {
+        let schema = config_schema();
+        let document = json!({
+            "prot": 8080,
+            "policy": {"type": "cache_aware", "cache_threshold": 0.5, "balance_thresh": 4},
+            "retry": {"max_retries": 3},
+            "tenant_api_keys": [{"tenant_id": "a", "key": "k", "scopes": []}],
+            "sessions": {"idle_ttl_secs": 60},
+        });
+        let mut unknown = unknown_fields(&schema, &document);
+        unknown.sort();
+        assert_eq!(
+            unknown,
+            vec![
+                "policy.balance_thresh".to_string(),
+                "prot".to_string(),
+                "tenant_api_keys[0].scopes".to_string(),
+            ]
+        );
+    }
+}
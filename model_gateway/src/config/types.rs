@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use openai_protocol::worker::HealthCheckConfig as ProtocolHealthCheckConfig;
 pub use openai_protocol::worker::TransportMode;
@@ -12,7 +12,7 @@ use super::{validation::ConfigValidator, ConfigResult};
 use crate::{tenant::DEFAULT_TENANT_HEADER_NAME, worker::ConnectionMode};
 
 /// Main router configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RouterConfig {
     pub mode: RoutingMode,
     #[serde(default)]
@@ -154,9 +154,105 @@ pub struct RouterConfig {
     /// When set, wraps all storage backends with hook-based interceptors.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub storage_hook_wasm_path: Option<String>,
+    /// Opt-in sampled capture of request/response pairs for debugging.
+    #[serde(default)]
+    pub debug_capture: DebugCaptureConfig,
+    /// Opt-in store for gateway-produced files such as generated images.
+    #[serde(default)]
+    pub file_store: FileStoreConfig,
+    /// Opt-in store for chat completions created with `store: true`.
+    #[serde(default)]
+    pub chat_completion_store: ChatCompletionStoreConfig,
+    /// Fault injection for resilience testing. Disabled unless explicitly configured.
+    #[serde(default)]
+    pub fault_injection: FaultInjectionConfig,
+    /// Short-TTL response cache for model listings and worker metadata.
+    #[serde(default)]
+    pub metadata_cache: MetadataCacheConfig,
+    /// Opt-in resumption of streams whose backend drops mid-generation.
+    #[serde(default)]
+    pub stream_recovery: StreamRecoveryConfig,
+    /// Opt-in read-only attachment of extra clients to in-flight streams.
+    #[serde(default)]
+    pub stream_fanout: StreamFanoutConfig,
+    /// Opt-in webhook delivery of completed inference results.
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+    /// Opt-in queue-backed asynchronous generation API.
+    #[serde(default)]
+    pub async_generation: AsyncGenerationConfig,
+    /// Opt-in map-reduce orchestration of chat requests over long documents.
+    #[serde(default)]
+    pub map_reduce: MapReduceConfig,
+    /// Extra stages spliced into the gRPC router's request pipelines.
+    #[serde(default)]
+    pub grpc_pipeline: GrpcPipelineConfig,
+    /// Per-model sampling parameter defaults and clamps.
+    #[serde(default)]
+    pub sampling_limits: SamplingLimitsConfig,
+    /// Single-flight coalescing of identical concurrent deterministic requests.
+    #[serde(default)]
+    pub request_coalescing: RequestCoalescingConfig,
+    /// Background summarization of long stored conversation histories.
+    #[serde(default)]
+    pub conversation_compaction: ConversationCompactionConfig,
+    /// Vector stores filled by ingesting stored files.
+    #[serde(default)]
+    pub vector_store: VectorStoreConfig,
+    /// Allowlisted `x-smg-tags` request tags used for traffic attribution.
+    #[serde(default)]
+    pub request_tags: RequestTagsConfig,
+    /// Allowlisted `x-smg-features` per-request feature flags.
+    #[serde(default)]
+    pub request_features: RequestFeaturesConfig,
+    /// Scheduled per-worker and per-model maintenance windows.
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// Sticky A/B experiments over model and system prompt variants.
+    #[serde(default)]
+    pub experiments: ExperimentsConfig,
+    /// Heuristic prompt-injection screening of incoming messages.
+    #[serde(default)]
+    pub prompt_guard: PromptGuardConfig,
+    /// Signed provenance metadata on inference responses.
+    #[serde(default)]
+    pub provenance: ProvenanceConfig,
+    /// Declarative routing rules evaluated before policy selection.
+    #[serde(default)]
+    pub routing_rules: RoutingRulesConfig,
+    /// Request signing, deadlines and catalog import for federated gateways.
+    #[serde(default)]
+    pub federation: FederationConfig,
+    /// Promotion of standby workers when too few regular workers are healthy.
+    #[serde(default)]
+    pub standby: StandbyConfig,
+    /// Tracking of prefill-decode bootstrap rooms.
+    #[serde(default)]
+    pub pd_bootstrap: PdBootstrapConfig,
+    /// Per-pair latency accounting and slow-pair detection for PD routing.
+    #[serde(default)]
+    pub pd_pairs: PdPairsConfig,
+    /// Client sessions pinned to a worker through `/v1/sessions`.
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+    /// Persistence of transcripts of cancelled and failed streams.
+    #[serde(default)]
+    pub partial_transcripts: PartialTranscriptsConfig,
+    /// MCP servers registered by tenants through `/v1/mcp/servers`.
+    #[serde(default)]
+    pub tenant_mcp_servers: TenantMcpServersConfig,
+    /// `max_tokens` caps derived from a caller's `x-smg-latency-budget-ms`.
+    #[serde(default)]
+    pub latency_budget: LatencyBudgetConfig,
+    /// Checksum and event-count trailers on generation streams.
+    #[serde(default)]
+    pub stream_integrity: StreamIntegrityConfig,
+    /// Versioned prompt templates managed through `/v1/prompts`.
+    #[serde(default)]
+    pub prompts: PromptsConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
 #[serde(default)]
 pub struct TenantResolutionConfig {
     pub trust_tenant_header: bool,
@@ -164,7 +260,7 @@ pub struct TenantResolutionConfig {
 }
 
 /// A single tenant-scoped API key for serving-path authentication.
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
 pub struct TenantApiKeyEntry {
     /// Resolves to tenant key `auth:<tenant_id>`, e.g. `team-red`.
     pub tenant_id: String,
@@ -189,8 +285,1172 @@ impl Default for TenantResolutionConfig {
     }
 }
 
+/// Sampled request/response capture for triaging model output reports.
+///
+/// Captured bodies are passed through the PII redactor before they reach
+/// storage and are browsable via `GET /debug/captures`. Captures are kept in
+/// memory, so enabling capture requires the `memory` history backend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct DebugCaptureConfig {
+    pub enabled: bool,
+    /// Fraction of requests to capture (0.0–1.0)
+    pub sample_rate: f64,
+    /// Maximum number of captures retained; oldest are evicted first
+    pub max_entries: usize,
+    /// Per-body byte cap; larger bodies are redacted, then truncated
+    pub max_body_bytes: usize,
+    /// Mask emails, phone numbers, card numbers, and credentials in bodies
+    pub redact_pii: bool,
+}
+
+impl Default for DebugCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 0.01,
+            max_entries: 1000,
+            max_body_bytes: 64 * 1024,
+            redact_pii: true,
+        }
+    }
+}
+
+/// In-memory store for files the gateway produces, e.g. generated images
+/// requested with `response_format: "url"`.
+///
+/// Stored files are served from `GET /v1/files/{file_id}/content`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct FileStoreConfig {
+    pub enabled: bool,
+    /// Maximum number of files retained; oldest are evicted first
+    pub max_files: usize,
+    /// Externally reachable base URL used when building file URLs
+    /// (e.g. `https://gateway.example.com`); relative URLs when unset
+    pub public_base_url: Option<String>,
+}
+
+impl Default for FileStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_files: 1000,
+            public_base_url: None,
+        }
+    }
+}
+
+/// In-memory vector stores for `file_search`, filled by ingesting files
+/// from the file store.
+///
+/// Ingestion extracts text from PDF, HTML, Markdown and plain-text files,
+/// splits it into chunks, embeds the chunks through the gateway's own
+/// embeddings routing and upserts them into the named vector store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct VectorStoreConfig {
+    pub enabled: bool,
+    /// Embedding model used when an ingestion request names none
+    pub embedding_model: Option<String>,
+    /// Chunks sent per embeddings request
+    pub embedding_batch_size: usize,
+    /// Maximum chunks held per vector store
+    pub max_chunks_per_store: usize,
+    /// Reranker for the second retrieval stage; the stage is skipped while
+    /// no worker serves this model
+    pub rerank_model: Option<String>,
+    /// Candidates taken by similarity search before reranking
+    pub retrieval_top_k: usize,
+    /// Results returned after reranking when a search does not set
+    /// `max_num_results`
+    pub rerank_top_k: usize,
+}
+
+impl Default for VectorStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            embedding_model: None,
+            embedding_batch_size: 64,
+            max_chunks_per_store: 100_000,
+            rerank_model: None,
+            retrieval_top_k: 50,
+            rerank_top_k: 10,
+        }
+    }
+}
+
+/// In-memory store for chat completions created with `store: true`.
+///
+/// Stored completions are served from `GET /v1/chat/completions` and
+/// `GET /v1/chat/completions/{completion_id}`. The store is node-local and
+/// does not use the history backend, so completions do not survive a
+/// restart and are only visible on the replica that stored them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ChatCompletionStoreConfig {
+    pub enabled: bool,
+    /// Maximum number of completions retained; oldest are evicted first
+    pub max_entries: usize,
+}
+
+impl Default for ChatCompletionStoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Response cache for `/v1/models`, `/get_model_info`, `/get_server_info`,
+/// and the worker listing endpoints.
+///
+/// Entries are keyed by path and caller credential, served with an `ETag`
+/// (answering `If-None-Match` with 304), and dropped on any worker registry
+/// change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct MetadataCacheConfig {
+    pub enabled: bool,
+    /// Seconds a cached response is served before it is rebuilt
+    pub ttl_secs: u64,
+    /// Maximum number of cached responses; least recently used are evicted first
+    pub max_entries: usize,
+}
+
+impl Default for MetadataCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 5,
+            max_entries: 1024,
+        }
+    }
+}
+
+/// Mid-stream recovery for streaming chat and completion requests.
+///
+/// When a backend drops a stream after tokens have reached the client, the
+/// request is re-issued to another worker with the generated prefix appended
+/// and streaming resumes where it stopped. Off unless a model is listed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct StreamRecoveryConfig {
+    /// Models that opt in to recovery
+    pub models: Vec<String>,
+    /// Maximum resumptions per request
+    pub max_resumes: u32,
+}
+
+impl Default for StreamRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            models: Vec::new(),
+            max_resumes: 1,
+        }
+    }
+}
+
+impl StreamRecoveryConfig {
+    pub fn enabled_for(&self, model_id: &str) -> bool {
+        self.max_resumes > 0 && self.models.iter().any(|m| m == model_id)
+    }
+}
+
+/// Fan-out of in-flight streaming responses to additional subscribers.
+///
+/// Streamed responses are teed into a per-request broadcast buffer; clients
+/// of the same tenant attach with `GET /v1/streams/{request_id}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct StreamFanoutConfig {
+    pub enabled: bool,
+    /// Chunks buffered per stream; subscribers further behind are dropped
+    pub buffer_chunks: usize,
+    /// Maximum concurrent subscribers per stream
+    pub max_subscribers: usize,
+}
+
+impl Default for StreamFanoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            buffer_chunks: 1024,
+            max_subscribers: 4,
+        }
+    }
+}
+
+/// Single-flight coalescing of identical concurrent generation requests.
+///
+/// Chat and completion requests with `temperature: 0` and the same tenant,
+/// route and body share one execution while it is in flight; every client
+/// receives the full response, including streams.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RequestCoalescingConfig {
+    pub enabled: bool,
+    /// Larger request bodies are never coalesced
+    pub max_request_bytes: usize,
+}
+
+impl Default for RequestCoalescingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_request_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Maintenance windows during which matching workers are drained.
+///
+/// Each window starts whenever its cron `schedule` fires (UTC) and lasts
+/// `duration_secs`. While it is open, covered workers are moved to
+/// `Draining` so routing fails over to the remaining workers for the model;
+/// they return to their previous status when the window closes. Windows
+/// can also be opened ad hoc through `/admin/maintenance`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    pub windows: Vec<MaintenanceWindowConfig>,
+    /// How often windows are re-evaluated.
+    pub check_interval_secs: u64,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            windows: Vec::new(),
+            check_interval_secs: 30,
+        }
+    }
+}
+
+/// One scheduled maintenance window. Exactly one of `worker_url` or
+/// `model` selects the workers it covers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+pub struct MaintenanceWindowConfig {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_url: Option<String>,
+    /// Model ID or alias; covers every worker that lists it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Five-field cron expression (`minute hour day-of-month month
+    /// day-of-week`, UTC) or `@hourly`/`@daily`/`@weekly`.
+    pub schedule: String,
+    pub duration_secs: u64,
+}
+
+/// Inference-time A/B experiments on chat and completion requests.
+///
+/// A request for an experiment's `model` is hashed on its sticky key into
+/// one of the experiment's arms, so the same user (or tenant, or header
+/// value) keeps landing on the same arm. Arms may swap the model and, for
+/// chat, prepend a system prompt. Traffic not covered by an arm's `percent`
+/// is left untouched and not enrolled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ExperimentsConfig {
+    pub experiments: Vec<ExperimentConfig>,
+}
+
+/// One experiment. At most one experiment may target a given model.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct ExperimentConfig {
+    pub name: String,
+    /// Requested model that enrolls a request in the experiment.
+    pub model: String,
+    #[serde(default)]
+    pub sticky_key: ExperimentStickyKey,
+    pub arms: Vec<ExperimentArmConfig>,
+}
+
+/// What a request is bucketed on. Requests without the key are assigned
+/// at random.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentStickyKey {
+    /// `user` on completions, `safety_identifier` on chat.
+    User,
+    /// Resolved tenant key.
+    #[default]
+    Tenant,
+    /// Value of the named request header.
+    Header(String),
+}
+
+/// One variant of an experiment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct ExperimentArmConfig {
+    pub name: String,
+    /// Share of the experiment model's traffic, 0-100.
+    pub percent: f64,
+    /// Model to route to instead of the requested one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// System message prepended to chat requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+}
+
+/// Prompt-injection and jailbreak screening of chat and completion requests.
+///
+/// User messages, completion prompts and (with `scan_tool_outputs`) tool
+/// results are matched against the built-in pattern library and any custom
+/// `rules`, and optionally scored by an external `classifier`. A request
+/// whose highest message score reaches `threshold` gets `action`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct PromptGuardConfig {
+    pub enabled: bool,
+    pub action: PromptGuardAction,
+    /// Score in (0, 1] at which `action` applies.
+    pub threshold: f64,
+    /// Also screen `tool` and `function` messages, where injected
+    /// instructions from fetched content usually arrive.
+    pub scan_tool_outputs: bool,
+    /// Include the built-in pattern library.
+    pub builtin_rules: bool,
+    pub rules: Vec<PromptGuardRuleConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classifier: Option<PromptGuardClassifierConfig>,
+}
+
+impl Default for PromptGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: PromptGuardAction::default(),
+            threshold: 0.7,
+            scan_tool_outputs: true,
+            builtin_rules: true,
+            rules: Vec::new(),
+            classifier: None,
+        }
+    }
+}
+
+/// What happens to a request scoring at or above the threshold.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptGuardAction {
+    /// Forward it and report the score in the `x-smg-prompt-guard` header.
+    #[default]
+    Annotate,
+    /// Reject it with 400.
+    Block,
+    /// Reject it with 403 until it is resent with the returned approval
+    /// token in `x-smg-prompt-guard-approval`.
+    RequireApproval,
+}
+
+/// A custom detection rule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct PromptGuardRuleConfig {
+    /// Label of the rule's hit counter and in the response annotation.
+    pub name: String,
+    /// Regular expression, matched case-insensitively.
+    pub pattern: String,
+    /// Score in (0, 1] contributed by a match.
+    pub score: f64,
+}
+
+/// External classifier scoring screened messages.
+///
+/// Receives `{"texts": [..]}` and must answer `{"scores": [..]}` with one
+/// score in [0, 1] per text. Requests are screened on rules alone when the
+/// classifier fails.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+pub struct PromptGuardClassifierConfig {
+    pub endpoint: String,
+    #[serde(default = "default_prompt_guard_classifier_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_prompt_guard_classifier_timeout_ms() -> u64 {
+    500
+}
+
+/// Provenance metadata on successful inference responses.
+///
+/// Responses carry the serving model's identity and the gateway version in
+/// `x-smg-provenance-*` headers, plus a SHA-256 digest of the body signed
+/// with HMAC-SHA256; streams end with an `smg.provenance` SSE event carrying
+/// the same record for the streamed bytes.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ProvenanceConfig {
+    pub enabled: bool,
+    /// HMAC-SHA256 key signing the provenance record
+    pub signing_key: Option<String>,
+    /// Reported with each signature so verifiers can pick the key
+    pub key_id: Option<String>,
+}
+
+impl std::fmt::Debug for ProvenanceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProvenanceConfig")
+            .field("enabled", &self.enabled)
+            .field(
+                "signing_key",
+                &self.signing_key.as_ref().map(|_| "<redacted>"),
+            )
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+/// Federation with other smg deployments.
+///
+/// A worker registered with `runtime_type: smg` is a whole remote gateway
+/// (e.g. another region). Requests forwarded to it carry the caller's
+/// deadline and, with a `signing_secret`, an HMAC-SHA256 signature; its
+/// `/v1/models` catalog is imported into the worker's model cards on an
+/// interval. The same secret verifies signed requests arriving from a
+/// parent gateway.
+#[derive(Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct FederationConfig {
+    /// Shared HMAC-SHA256 secret for signing and verifying federated requests
+    pub signing_secret: Option<String>,
+    /// Reject inbound requests without a valid federation signature
+    pub require_signed: bool,
+    /// Accepted clock skew between federated gateways
+    pub max_clock_skew_secs: u64,
+    /// Interval between catalog imports from federated gateways
+    pub catalog_refresh_interval_secs: u64,
+}
+
+impl Default for FederationConfig {
+    fn default() -> Self {
+        Self {
+            signing_secret: None,
+            require_signed: false,
+            max_clock_skew_secs: 300,
+            catalog_refresh_interval_secs: 60,
+        }
+    }
+}
+
+impl std::fmt::Debug for FederationConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FederationConfig")
+            .field(
+                "signing_secret",
+                &self.signing_secret.as_ref().map(|_| "<redacted>"),
+            )
+            .field("require_signed", &self.require_signed)
+            .field("max_clock_skew_secs", &self.max_clock_skew_secs)
+            .field(
+                "catalog_refresh_interval_secs",
+                &self.catalog_refresh_interval_secs,
+            )
+            .finish()
+    }
+}
+
+/// Promotion thresholds for standby workers.
+///
+/// A worker registered with `standby: true` is health-checked like any
+/// other but receives no traffic. When fewer than `min_active_workers`
+/// regular workers for one of its models are healthy, healthy standbys are
+/// promoted (highest priority first) to make up the difference, and they
+/// are demoted again once the regular pool recovers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct StandbyConfig {
+    /// Healthy regular workers a model needs before standbys stand down.
+    pub min_active_workers: usize,
+    /// Per-model overrides of `min_active_workers`, keyed by model ID.
+    pub model_min_active_workers: HashMap<String, usize>,
+    /// How often the pools are re-evaluated, in addition to every health
+    /// transition.
+    pub check_interval_secs: u64,
+}
+
+impl Default for StandbyConfig {
+    fn default() -> Self {
+        Self {
+            min_active_workers: 1,
+            model_min_active_workers: HashMap::new(),
+            check_interval_secs: 10,
+        }
+    }
+}
+
+impl StandbyConfig {
+    /// The promotion threshold for `model_id`.
+    pub fn min_active_for(&self, model_id: &str) -> usize {
+        self.model_min_active_workers
+            .get(model_id)
+            .copied()
+            .unwrap_or(self.min_active_workers)
+    }
+}
+
+/// Tracking of prefill-decode bootstrap rooms.
+///
+/// Every PD request holds its bootstrap room until the prefill leg is done.
+/// A room still open after `stuck_threshold_secs` points at a bootstrap that
+/// never completed; such rooms are counted by `smg_pd_bootstrap_rooms_stuck`
+/// and listed by `GET /admin/pd/bootstrap-rooms`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct PdBootstrapConfig {
+    /// Age after which an open room counts as stuck.
+    pub stuck_threshold_secs: u64,
+}
+
+impl Default for PdBootstrapConfig {
+    fn default() -> Self {
+        Self {
+            stuck_threshold_secs: 60,
+        }
+    }
+}
+
+/// Slow-pair detection for prefill-decode routing.
+///
+/// Each (prefill, decode) pair is compared against the fleet over a sliding
+/// window. A pair whose median latency exceeds `slow_factor` times the
+/// median across all pairs, or whose error rate exceeds `max_error_rate`, is
+/// flagged slow and, with `avoid_slow_pairs`, avoided while another decode
+/// worker is available.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct PdPairsConfig {
+    /// Sliding window of samples, in seconds.
+    pub window_secs: u64,
+    /// Samples a pair needs before it can be flagged.
+    pub min_samples: usize,
+    /// Multiple of the fleet median latency above which a pair is slow.
+    pub slow_factor: f64,
+    /// Error rate above which a pair is slow.
+    pub max_error_rate: f64,
+    /// Steer requests away from slow pairs.
+    pub avoid_slow_pairs: bool,
+}
+
+impl Default for PdPairsConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 300,
+            min_samples: 20,
+            slow_factor: 2.0,
+            max_error_rate: 0.2,
+            avoid_slow_pairs: true,
+        }
+    }
+}
+
+/// Sessions opened through `POST /v1/sessions`.
+///
+/// A session pins a client's requests to the worker holding its pre-filled
+/// system prompt. Sessions that carry no request for `idle_ttl_secs` are
+/// closed automatically.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct SessionsConfig {
+    /// Idle time after which a session is closed, in seconds.
+    pub idle_ttl_secs: u64,
+    /// Open sessions allowed at once; further opens are rejected.
+    pub max_sessions: usize,
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            idle_ttl_secs: 1800,
+            max_sessions: 10_000,
+        }
+    }
+}
+
+/// Partial transcripts of streams that do not finish.
+///
+/// With `enabled`, the text and token counts of a streamed response that the
+/// client cancels or that ends without completing are stored in the response
+/// storage under the request ID.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct PartialTranscriptsConfig {
+    pub enabled: bool,
+    /// Generated text kept per transcript, in bytes; the rest is dropped.
+    pub max_output_bytes: usize,
+}
+
+impl Default for PartialTranscriptsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_output_bytes: 1_048_576,
+        }
+    }
+}
+
+/// MCP servers registered per tenant.
+///
+/// With `enabled`, tenants register servers through `/v1/mcp/servers` and
+/// admins on their behalf through `/admin/tenants/{tenant}/mcp/servers`.
+/// A Responses request naming a registered label in an `mcp` tool is
+/// connected to the tenant's server on first use.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct TenantMcpServersConfig {
+    pub enabled: bool,
+    /// Servers one tenant may register.
+    pub max_servers_per_tenant: usize,
+}
+
+impl Default for TenantMcpServersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_servers_per_tenant: 32,
+        }
+    }
+}
+
+/// Tenant libraries of versioned prompt templates.
+///
+/// With `enabled`, tenants publish templates through `/v1/prompts` and
+/// admins on their behalf through `/admin/tenants/{tenant}/prompts`. A
+/// Responses request referencing one in `prompt` is rendered with its
+/// variables before routing. Prompts are held in memory on each replica and
+/// do not use the history backend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct PromptsConfig {
+    pub enabled: bool,
+    /// Prompts one tenant may keep.
+    pub max_prompts_per_tenant: usize,
+    /// Versions one prompt may have.
+    pub max_versions_per_prompt: usize,
+}
+
+impl Default for PromptsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_prompts_per_tenant: 128,
+            max_versions_per_prompt: 100,
+        }
+    }
+}
+
+/// Latency budgets converted into `max_tokens` caps.
+///
+/// With `enabled`, a chat or completion request carrying
+/// `x-smg-latency-budget-ms` has its `max_tokens` lowered to what the
+/// model's slowest worker decodes within the budget, at the per-request rate
+/// its engine last reported. The applied cap is returned in the
+/// `x-smg-latency-budget-max-tokens` response header.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct LatencyBudgetConfig {
+    pub enabled: bool,
+    /// Per-request decode rate, in tokens/s, assumed when no worker of the
+    /// model reports one.
+    pub default_tokens_per_second: f64,
+    /// Part of the budget set aside for queueing and prefill, in milliseconds.
+    pub ttft_allowance_ms: u64,
+    /// Lowest cap applied, however tight the budget.
+    pub min_max_tokens: u32,
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_tokens_per_second: 20.0,
+            ttft_allowance_ms: 500,
+            min_max_tokens: 16,
+        }
+    }
+}
+
+/// Integrity trailers on generation streams.
+///
+/// With `enabled`, successful SSE streams end with an `smg.integrity` event
+/// carrying the number of events and bytes streamed before it and their
+/// SHA-256, so clients behind lossy proxies can tell a truncated stream from
+/// a complete one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct StreamIntegrityConfig {
+    pub enabled: bool,
+}
+
+/// Routing rules evaluated, first match wins, before policy selection.
+///
+/// A rule matches a request on its path, headers, tenant and JSON body
+/// fields, and redirects it to another model and/or to the workers carrying
+/// a set of labels. Rules can also be managed at runtime through
+/// `/admin/routing-rules`, which replaces this list cluster-wide when mesh
+/// is enabled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RoutingRulesConfig {
+    pub rules: Vec<RoutingRuleConfig>,
+}
+
+/// One routing rule. All of its conditions must hold.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct RoutingRuleConfig {
+    pub name: String,
+    #[serde(default, rename = "match")]
+    pub conditions: RoutingRuleMatch,
+    pub action: RoutingRuleAction,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RoutingRuleMatch {
+    /// Request path; a trailing `*` matches any path with that prefix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Header name to required value; `*` requires only presence.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Resolved tenant key, e.g. `auth:team-a` or `header:team-a`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub body: Vec<BodyPredicateConfig>,
+}
+
+/// Condition on a JSON body field.
+///
+/// `field` is a dot-separated path; a `*` segment visits every element of
+/// an array (or value of an object) and a numeric segment indexes an array.
+/// The predicate holds when any visited value satisfies it, except `ne`,
+/// which holds when none equals `value`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct BodyPredicateConfig {
+    pub field: String,
+    pub op: BodyPredicateOp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyPredicateOp {
+    /// The field is present and not `null`.
+    Exists,
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Where a matched request is sent. At least one field must be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RoutingRuleAction {
+    /// Model to route to instead of the requested one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Only workers carrying all of these labels are candidates.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub worker_labels: BTreeMap<String, String>,
+}
+
+/// Client-supplied request tags (`x-smg-tags: key=value,...`) that label
+/// webhook records and the tagged request metrics.
+///
+/// Only keys listed in `tags` are kept. A key with listed values maps any
+/// other value to `other`; a key with an empty list admits the first
+/// `max_values_per_tag` distinct values seen and maps later ones to `other`.
+/// Disabled while `tags` is empty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RequestTagsConfig {
+    pub tags: HashMap<String, Vec<String>>,
+    pub max_values_per_tag: usize,
+}
+
+impl Default for RequestTagsConfig {
+    fn default() -> Self {
+        Self {
+            tags: HashMap::new(),
+            max_values_per_tag: 32,
+        }
+    }
+}
+
+impl RequestTagsConfig {
+    /// Upper bound on configured tag keys; each one is a metric label.
+    pub const MAX_TAGS: usize = 8;
+
+    pub fn enabled(&self) -> bool {
+        !self.tags.is_empty()
+    }
+
+    /// Tag keys become metric label names (`tag_<key>`): lowercase
+    /// alphanumerics and `_`, not starting with a digit.
+    pub fn is_valid_key(key: &str) -> bool {
+        key.len() <= 32
+            && key.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    /// Tag values: 1-64 ASCII alphanumerics, `_`, `-`, `.` or `:`.
+    pub fn is_valid_value(value: &str) -> bool {
+        (1..=64).contains(&value.len())
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+    }
+}
+
+/// Experimental gateway behaviors a caller opts into per request with
+/// `x-smg-features: recovery,coalesce-sse`.
+///
+/// `default` lists the features every caller may enable; `tenants` grants
+/// additional features to callers of a tenant API key, keyed by its
+/// `tenant_id`. Requested features outside the caller's allowlist are
+/// dropped, so with both lists empty the header has no effect.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct RequestFeaturesConfig {
+    pub default: Vec<String>,
+    pub tenants: HashMap<String, Vec<String>>,
+}
+
+impl RequestFeaturesConfig {
+    /// Upper bound on distinct configured features; each one is a metric
+    /// label value.
+    pub const MAX_FEATURES: usize = 32;
+
+    /// Feature names: 1-32 lowercase alphanumerics or `-`.
+    pub fn is_valid_name(name: &str) -> bool {
+        (1..=32).contains(&name.len())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    }
+
+    /// Every feature named in `default` or `tenants`, deduplicated.
+    pub fn feature_names(&self) -> BTreeSet<&str> {
+        self.default
+            .iter()
+            .chain(self.tenants.values().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Summarization compaction of long conversation histories.
+///
+/// After a Responses API turn on a stored conversation whose estimated size
+/// exceeds `token_threshold`, all but the `keep_recent_items` most recent
+/// items are replaced by a model-written summary. Replaced items are
+/// archived, not deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ConversationCompactionConfig {
+    pub enabled: bool,
+    /// Estimated conversation size, in tokens, that triggers compaction
+    pub token_threshold: usize,
+    /// Most recent items kept verbatim after the summary
+    pub keep_recent_items: usize,
+    /// Model that writes summaries; defaults to the conversation's model
+    pub model: Option<String>,
+}
+
+impl Default for ConversationCompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token_threshold: 32_000,
+            keep_recent_items: 10,
+            model: None,
+        }
+    }
+}
+
+/// Webhook delivery of successful non-streaming inference responses.
+///
+/// The URL comes from the `x-smg-webhook-url` request header (when
+/// `allow_request_urls` is set) or the caller's tenant default.
+#[derive(Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    /// Default webhook URL per tenant key (e.g. `auth:team-red`)
+    pub tenant_urls: HashMap<String, String>,
+    /// Accept per-request URLs from the `x-smg-webhook-url` header
+    pub allow_request_urls: bool,
+    /// HMAC-SHA256 signing secret; deliveries are unsigned when unset
+    pub secret: Option<String>,
+    /// Delivery attempts before a webhook is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub initial_backoff_ms: u64,
+    /// Per-attempt timeout
+    pub timeout_secs: u64,
+    /// Larger responses are delivered as an `id` reference only
+    pub max_payload_bytes: usize,
+    /// Failed deliveries retained for inspection; oldest are evicted first
+    pub dead_letter_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tenant_urls: HashMap::new(),
+            allow_request_urls: false,
+            secret: None,
+            max_attempts: 5,
+            initial_backoff_ms: 1000,
+            timeout_secs: 10,
+            max_payload_bytes: 1024 * 1024,
+            dead_letter_capacity: 1000,
+        }
+    }
+}
+
+impl std::fmt::Debug for WebhookConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookConfig")
+            .field("enabled", &self.enabled)
+            .field("tenant_urls", &self.tenant_urls)
+            .field("allow_request_urls", &self.allow_request_urls)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff_ms", &self.initial_backoff_ms)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("max_payload_bytes", &self.max_payload_bytes)
+            .field("dead_letter_capacity", &self.dead_letter_capacity)
+            .finish()
+    }
+}
+
+/// Queue-backed asynchronous generation at `/v1/generations/async`.
+///
+/// Submitted requests are queued and executed by a fixed-size worker pool;
+/// clients poll, stream status events, or cancel by job id.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct AsyncGenerationConfig {
+    pub enabled: bool,
+    /// Jobs waiting for a worker; submissions beyond this are rejected
+    pub max_queued: usize,
+    /// Jobs executed concurrently
+    pub concurrency: usize,
+    /// Queued plus running jobs allowed per tenant
+    pub max_jobs_per_tenant: usize,
+    /// Job records retained; the oldest finished jobs are evicted first
+    pub max_retained_jobs: usize,
+}
+
+impl Default for AsyncGenerationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_queued: 1000,
+            concurrency: 8,
+            max_jobs_per_tenant: 100,
+            max_retained_jobs: 10_000,
+        }
+    }
+}
+
+/// Map-reduce orchestration for chat requests carrying a `map_reduce` field.
+///
+/// The document in the final user message is split into chunks, each chunk is
+/// generated on its own (spread across workers by the routing policy), and a
+/// reduce pass combines the outputs. Off unless a model is listed; requests
+/// for other models that set the field are rejected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct MapReduceConfig {
+    /// Models that opt in to map-reduce
+    pub models: Vec<String>,
+    /// Chunk size in characters when the request does not set one
+    pub chunk_chars: usize,
+    /// Maximum chunks per request; larger documents are rejected
+    pub max_chunks: usize,
+    /// Maximum chunk generations in flight per request
+    pub max_concurrency: usize,
+}
+
+impl Default for MapReduceConfig {
+    fn default() -> Self {
+        Self {
+            models: Vec::new(),
+            chunk_chars: 32_000,
+            max_chunks: 32,
+            max_concurrency: 8,
+        }
+    }
+}
+
+impl MapReduceConfig {
+    pub fn enabled_for(&self, model_id: &str) -> bool {
+        self.models.iter().any(|m| m == model_id)
+    }
+}
+
+/// Fault injection for exercising retry, fallback, and circuit breaker
+/// behavior in staging.
+///
+/// Rules are evaluated in order against the selected worker and route; the
+/// first matching rule whose probability roll succeeds injects its fault.
+/// Only the HTTP regular router injects faults. Never enable this in
+/// production.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct FaultInjectionConfig {
+    pub enabled: bool,
+    pub rules: Vec<FaultRule>,
+}
+
+/// A single fault injection rule.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct FaultRule {
+    /// Worker URL to match; `None` matches every worker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker_url: Option<String>,
+    /// Route to match (e.g. `/v1/chat/completions`); `None` matches every route
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<String>,
+    /// Probability (0.0–1.0) that a matching request is faulted
+    pub probability: f64,
+    #[serde(flatten)]
+    pub fault: FaultKind,
+}
+
+/// The fault a rule injects.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(tag = "fault", rename_all = "snake_case")]
+pub enum FaultKind {
+    /// Delay the upstream call, then proceed normally
+    Latency { delay_ms: u64 },
+    /// Respond with this status without calling the worker
+    Status { status: u16 },
+    /// Fail as if the worker reset the connection
+    ConnectionReset,
+    /// Cut the response body off after `after_bytes` bytes
+    TruncateStream { after_bytes: usize },
+}
+
+/// Custom stages for the gRPC router pipelines.
+///
+/// Each stage runs right after a named built-in stage, in configuration order
+/// when several share an anchor, e.g. a validation stage between
+/// tokenization (`preparation`) and dispatch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct GrpcPipelineConfig {
+    pub stages: Vec<CustomStageConfig>,
+}
+
+/// A single custom pipeline stage.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+pub struct CustomStageConfig {
+    /// Name used in logs and error messages
+    pub name: String,
+    /// Built-in stage this stage runs after
+    pub after: PipelineStageAnchor,
+    /// Pipelines to add the stage to; empty adds it to every pipeline
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<PipelineEndpoint>,
+    #[serde(flatten)]
+    pub kind: CustomStageKind,
+}
+
+/// Built-in stages a custom stage can be placed after. Every pipeline has
+/// each of these.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStageAnchor {
+    /// Request validation and tokenization
+    Preparation,
+    WorkerSelection,
+    ClientAcquisition,
+    /// Building the backend request
+    RequestBuilding,
+    DispatchMetadata,
+}
+
+/// gRPC router pipelines, by the endpoint they serve.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineEndpoint {
+    /// Chat completions, generate and the responses API
+    Chat,
+    Messages,
+    Completion,
+    Harmony,
+    Embeddings,
+    Classify,
+    Rerank,
+}
+
+/// What a custom stage does.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum CustomStageKind {
+    /// Reject requests whose tokenized prompt exceeds `max_tokens`
+    MaxPromptTokens { max_tokens: usize },
+    /// Run the `on-request` export of the loaded WASM module named `module`.
+    /// It sees the typed request as JSON and may reject it, set headers or
+    /// replace the body. Register the module without attach points so the
+    /// WASM middleware does not also run it
+    Wasm { module: String },
+}
+
+/// Per-model defaults and clamps for sampling parameters of chat and
+/// completion requests, keyed by model id.
+///
+/// Unset parameters take the default; out-of-range values are clamped rather
+/// than rejected and reported in the `x-smg-sampling-clamped` response header.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct SamplingLimitsConfig {
+    pub models: HashMap<String, ModelSamplingLimits>,
+}
+
+/// Sampling limits for one model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ModelSamplingLimits {
+    pub temperature: ParamLimits<f32>,
+    pub top_p: ParamLimits<f32>,
+    /// Applies to `max_tokens` and `max_completion_tokens`
+    pub max_tokens: ParamLimits<u32>,
+    pub presence_penalty: ParamLimits<f32>,
+    pub frequency_penalty: ParamLimits<f32>,
+}
+
+/// Default and inclusive bounds of one sampling parameter.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(default)]
+pub struct ParamLimits<T> {
+    /// Used when the request does not set the parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<T>,
+}
+
 /// Tokenizer cache configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
 pub struct TokenizerCacheConfig {
     /// Whole-string exact match cache
     #[serde(default = "default_enable_l0")]
@@ -260,7 +1520,7 @@ fn default_history_backend() -> HistoryBackend {
 }
 
 /// Routing mode configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(tag = "type")]
 pub enum RoutingMode {
     #[serde(rename = "regular")]
@@ -364,7 +1624,9 @@ impl RoutingMode {
 }
 
 /// Assignment mode for manual policy when encountering a new routing key
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, schemars::JsonSchema,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum ManualAssignmentMode {
     /// Random selection (default)
@@ -380,7 +1642,7 @@ pub enum ManualAssignmentMode {
 /// eligible policy routes via manual sticky-map semantics. Reuses the manual
 /// policy knobs for the sticky map; eviction defaults match the manual policy so
 /// config-file users with only `enabled: true` still get TTL eviction (no leak).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RoutingKeyOverrideConfig {
     /// When false, policies are used unchanged.
     #[serde(default)]
@@ -405,7 +1667,7 @@ impl Default for RoutingKeyOverrideConfig {
 }
 
 /// Policy configuration for routing
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(tag = "type")]
 pub enum PolicyConfig {
     #[serde(rename = "random")]
@@ -437,6 +1699,13 @@ pub enum PolicyConfig {
         /// triggers shedding regardless of spread. `>= 1.0` disables (default).
         #[serde(default = "default_balance_token_usage_threshold")]
         overload_token_usage_threshold: f32,
+        /// History window (seconds) for the approximate trees. `0` disables.
+        #[serde(default)]
+        tree_window_secs: u64,
+        /// Half-life (seconds) for matches in older tree generations. `0`
+        /// disables decay.
+        #[serde(default)]
+        cache_decay_half_life_secs: u64,
     },
 
     #[serde(rename = "power_of_two")]
@@ -445,7 +1714,10 @@ pub enum PolicyConfig {
     /// Least-(token-)work policy: routes to the worker minimizing the expected
     /// wait `(queued_tokens + inflight_tokens) / throughput + kv_pressure_weight * k/(1-k)`
     /// — token-work drain time plus a convex KV-cache pressure barrier, computed
-    /// from the load monitor with in-flight correction. See `policies/least_load.rs`.
+    /// from the load monitor with in-flight correction. Where workers report KV
+    /// capacity, `k` is projected after admitting the request's prompt, so
+    /// long-context requests go to workers with room for them. See
+    /// `policies/least_load.rs`.
     #[serde(rename = "least_load")]
     LeastLoad {
         #[serde(default = "default_least_load_interval")]
@@ -497,8 +1769,14 @@ pub enum PolicyConfig {
     /// - X-SMG-Target-Worker: Direct routing to a specific worker by URL
     /// - X-SMG-Routing-Key: Consistent hash routing for session affinity
     /// - Provides O(log n) lookup with minimal redistribution (~1/N keys) on topology change
+    /// - Optional bounded loads: spills past workers above (1 + ε) × average load
     #[serde(rename = "consistent_hashing")]
-    ConsistentHashing,
+    ConsistentHashing {
+        /// Bounded-load slack ε (e.g. 0.25). Unset routes every key to its
+        /// ring owner regardless of load (default).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        load_epsilon: Option<f64>,
+    },
 
     /// Prefix hash policy for KV cache-aware load balancing.
     /// A lightweight alternative to cache_aware radix tree.
@@ -514,6 +1792,12 @@ pub enum PolicyConfig {
         /// Load factor threshold - walk ring if load > avg * factor (default: 1.25)
         #[serde(default = "default_load_factor")]
         load_factor: f64,
+        /// Tokens per hash block; match the backend's KV page size (default: 1)
+        #[serde(default = "default_prefix_block_size")]
+        block_size: usize,
+        /// Number of blocks hashed; overrides prefix_token_count when set
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        block_count: Option<usize>,
     },
 }
 
@@ -533,6 +1817,10 @@ fn default_load_factor() -> f64 {
     1.25
 }
 
+fn default_prefix_block_size() -> usize {
+    1
+}
+
 fn default_manual_eviction_interval_secs() -> u64 {
     60
 }
@@ -568,14 +1856,14 @@ impl PolicyConfig {
             PolicyConfig::LeastLoad { .. } => "least_load",
             PolicyConfig::Bucket { .. } => "bucket",
             PolicyConfig::Manual { .. } => "manual",
-            PolicyConfig::ConsistentHashing => "consistent_hashing",
+            PolicyConfig::ConsistentHashing { .. } => "consistent_hashing",
             PolicyConfig::PrefixHash { .. } => "prefix_hash",
         }
     }
 }
 
 /// Service discovery configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct DiscoveryConfig {
     pub enabled: bool,
     /// None = all namespaces
@@ -627,7 +1915,7 @@ impl Default for DiscoveryConfig {
 }
 
 /// Retry configuration for request handling
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RetryConfig {
     pub max_retries: u32,
     pub initial_backoff_ms: u64,
@@ -655,7 +1943,7 @@ fn default_retry_jitter_factor() -> f32 {
 }
 
 /// Health check configuration for worker monitoring
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct HealthCheckConfig {
     pub failure_threshold: u32,
     pub success_threshold: u32,
@@ -707,7 +1995,7 @@ impl HealthCheckConfig {
 }
 
 /// Circuit breaker configuration for worker reliability
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct CircuitBreakerConfig {
     pub failure_threshold: u32,
     pub success_threshold: u32,
@@ -727,7 +2015,7 @@ impl Default for CircuitBreakerConfig {
 }
 
 /// Metrics configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct MetricsConfig {
     pub port: u16,
     pub host: String,
@@ -742,7 +2030,7 @@ impl Default for MetricsConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct TraceConfig {
     pub enable_trace: bool,
     pub otlp_traces_endpoint: String,
@@ -822,6 +2110,38 @@ impl Default for RouterConfig {
             mcp_config: None,
             enable_wasm: false,
             storage_hook_wasm_path: None,
+            debug_capture: DebugCaptureConfig::default(),
+            file_store: FileStoreConfig::default(),
+            chat_completion_store: ChatCompletionStoreConfig::default(),
+            fault_injection: FaultInjectionConfig::default(),
+            metadata_cache: MetadataCacheConfig::default(),
+            stream_recovery: StreamRecoveryConfig::default(),
+            stream_fanout: StreamFanoutConfig::default(),
+            webhooks: WebhookConfig::default(),
+            async_generation: AsyncGenerationConfig::default(),
+            map_reduce: MapReduceConfig::default(),
+            grpc_pipeline: GrpcPipelineConfig::default(),
+            sampling_limits: SamplingLimitsConfig::default(),
+            request_coalescing: RequestCoalescingConfig::default(),
+            conversation_compaction: ConversationCompactionConfig::default(),
+            vector_store: VectorStoreConfig::default(),
+            request_tags: RequestTagsConfig::default(),
+            request_features: RequestFeaturesConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            experiments: ExperimentsConfig::default(),
+            prompt_guard: PromptGuardConfig::default(),
+            provenance: ProvenanceConfig::default(),
+            routing_rules: RoutingRulesConfig::default(),
+            federation: FederationConfig::default(),
+            standby: StandbyConfig::default(),
+            pd_bootstrap: PdBootstrapConfig::default(),
+            pd_pairs: PdPairsConfig::default(),
+            sessions: SessionsConfig::default(),
+            partial_transcripts: PartialTranscriptsConfig::default(),
+            tenant_mcp_servers: TenantMcpServersConfig::default(),
+            latency_budget: LatencyBudgetConfig::default(),
+            stream_integrity: StreamIntegrityConfig::default(),
+            prompts: PromptsConfig::default(),
             server_cert: None,
             server_key: None,
         }
@@ -1087,6 +2407,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         };
         assert_eq!(cache_aware.name(), "cache_aware");
 
@@ -1111,6 +2433,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         };
         let json = serde_json::to_string(&cache_aware).unwrap();
         assert!(json.contains("\"type\":\"cache_aware\""));
@@ -1136,6 +2460,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         };
 
         match cache_aware {
@@ -1547,6 +2873,8 @@ mod tests {
                 block_size: 16,
                 balance_token_usage_threshold: 1.0,
                 overload_token_usage_threshold: 1.0,
+                tree_window_secs: 0,
+                cache_decay_half_life_secs: 0,
             }),
             decode_policy: Some(PolicyConfig::PowerOfTwo {
                 load_check_interval_secs: 60,
@@ -1580,6 +2908,8 @@ mod tests {
                 block_size: 16,
                 balance_token_usage_threshold: 1.0,
                 overload_token_usage_threshold: 1.0,
+                tree_window_secs: 0,
+                cache_decay_half_life_secs: 0,
             }),
             decode_policy: None,
         };
@@ -1639,6 +2969,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         };
 
         match pd.get_prefill_policy(&main_policy) {
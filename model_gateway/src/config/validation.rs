@@ -1,7 +1,8 @@
-use axum::http::HeaderName;
+use axum::http::{HeaderName, HeaderValue};
 use sha2::{Digest, Sha256};
 
 use super::*;
+use crate::worker::ConnectionMode;
 
 /// Validate a user-supplied mesh server name. The name keys rate-limit
 /// shards as `rl:{counter}:{name}`, so an empty name or one containing the
@@ -60,6 +61,36 @@ impl ConfigValidator {
         }
 
         Self::validate_tokenizer_cache(&config.tokenizer_cache)?;
+        Self::validate_debug_capture(config)?;
+        Self::validate_file_store(&config.file_store)?;
+        Self::validate_vector_store(config)?;
+        Self::validate_chat_completion_store(&config.chat_completion_store)?;
+        Self::validate_metadata_cache(&config.metadata_cache)?;
+        Self::validate_fault_injection(config)?;
+        Self::validate_map_reduce(&config.map_reduce)?;
+        Self::validate_stream_fanout(&config.stream_fanout)?;
+        Self::validate_request_coalescing(&config.request_coalescing)?;
+        Self::validate_conversation_compaction(&config.conversation_compaction)?;
+        Self::validate_webhooks(&config.webhooks)?;
+        Self::validate_async_generation(&config.async_generation)?;
+        Self::validate_grpc_pipeline(config)?;
+        Self::validate_sampling_limits(&config.sampling_limits)?;
+        Self::validate_request_tags(&config.request_tags)?;
+        Self::validate_request_features(&config.request_features)?;
+        Self::validate_maintenance(&config.maintenance)?;
+        Self::validate_experiments(&config.experiments)?;
+        Self::validate_prompt_guard(&config.prompt_guard)?;
+        Self::validate_provenance(&config.provenance)?;
+        Self::validate_routing_rules(&config.routing_rules)?;
+        Self::validate_federation(&config.federation)?;
+        Self::validate_standby(&config.standby)?;
+        Self::validate_pd_bootstrap(&config.pd_bootstrap)?;
+        Self::validate_pd_pairs(&config.pd_pairs)?;
+        Self::validate_sessions(&config.sessions)?;
+        Self::validate_partial_transcripts(&config.partial_transcripts)?;
+        Self::validate_tenant_mcp_servers(&config.tenant_mcp_servers)?;
+        Self::validate_prompts(&config.prompts)?;
+        Self::validate_latency_budget(&config.latency_budget)?;
 
         Ok(())
     }
@@ -356,8 +387,7 @@ impl ConfigValidator {
             PolicyConfig::Random
             | PolicyConfig::RoundRobin
             | PolicyConfig::Passthrough
-            | PolicyConfig::Manual { .. }
-            | PolicyConfig::ConsistentHashing => {}
+            | PolicyConfig::Manual { .. } => {}
             PolicyConfig::CacheAware {
                 cache_threshold,
                 balance_abs_threshold: _,
@@ -367,6 +397,8 @@ impl ConfigValidator {
                 block_size,
                 balance_token_usage_threshold,
                 overload_token_usage_threshold,
+                tree_window_secs: _,
+                cache_decay_half_life_secs: _,
             } => {
                 if *block_size == 0 {
                     return Err(ConfigError::InvalidValue {
@@ -504,6 +536,8 @@ impl ConfigValidator {
             PolicyConfig::PrefixHash {
                 prefix_token_count,
                 load_factor,
+                block_size,
+                block_count,
             } => {
                 if *prefix_token_count == 0 {
                     return Err(ConfigError::InvalidValue {
@@ -520,6 +554,33 @@ impl ConfigValidator {
                         reason: "Must be >= 1.0".to_string(),
                     });
                 }
+
+                if *block_size == 0 {
+                    return Err(ConfigError::InvalidValue {
+                        field: "block_size".to_string(),
+                        value: block_size.to_string(),
+                        reason: "Must be > 0".to_string(),
+                    });
+                }
+
+                if *block_count == Some(0) {
+                    return Err(ConfigError::InvalidValue {
+                        field: "block_count".to_string(),
+                        value: "0".to_string(),
+                        reason: "Must be > 0".to_string(),
+                    });
+                }
+            }
+            PolicyConfig::ConsistentHashing { load_epsilon } => {
+                if let Some(epsilon) = load_epsilon {
+                    if !epsilon.is_finite() || *epsilon <= 0.0 {
+                        return Err(ConfigError::InvalidValue {
+                            field: "load_epsilon".to_string(),
+                            value: epsilon.to_string(),
+                            reason: "Must be a finite number > 0.0".to_string(),
+                        });
+                    }
+                }
             }
         }
         Ok(())
@@ -527,9 +588,9 @@ impl ConfigValidator {
 
     fn validate_encode_policy(policy: &PolicyConfig) -> ConfigResult<()> {
         match policy {
-            PolicyConfig::Random | PolicyConfig::RoundRobin | PolicyConfig::ConsistentHashing => {
-                Ok(())
-            }
+            PolicyConfig::Random
+            | PolicyConfig::RoundRobin
+            | PolicyConfig::ConsistentHashing { .. } => Ok(()),
             _ => Err(ConfigError::IncompatibleConfig {
                 reason: "Encode policy supports random, round_robin, or consistent_hashing"
                     .to_string(),
@@ -840,873 +901,2478 @@ impl ConfigValidator {
         Ok(())
     }
 
-    fn validate_mtls(config: &RouterConfig) -> ConfigResult<()> {
-        if let Some(identity) = &config.client_identity {
-            if identity.is_empty() {
-                return Err(ConfigError::ValidationFailed {
-                    reason: "Client identity cannot be empty".to_string(),
-                });
-            }
+    fn validate_debug_capture(config: &RouterConfig) -> ConfigResult<()> {
+        let capture = &config.debug_capture;
+        if !capture.enabled {
+            return Ok(());
         }
 
-        for (idx, ca_cert) in config.ca_certificates.iter().enumerate() {
-            if ca_cert.is_empty() {
-                return Err(ConfigError::ValidationFailed {
-                    reason: format!("CA certificate at index {idx} cannot be empty"),
-                });
-            }
+        // Captures are only kept in memory; don't let an operator believe
+        // they land in the persistent history backend.
+        if config.history_backend != HistoryBackend::Memory {
+            return Err(ConfigError::IncompatibleConfig {
+                reason: "debug_capture stores captures in memory; set history_backend to memory"
+                    .to_string(),
+            });
+        }
+
+        if !(0.0..=1.0).contains(&capture.sample_rate) {
+            return Err(ConfigError::InvalidValue {
+                field: "debug_capture.sample_rate".to_string(),
+                value: capture.sample_rate.to_string(),
+                reason: "Must be between 0.0 and 1.0".to_string(),
+            });
+        }
+
+        if capture.max_entries == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "debug_capture.max_entries".to_string(),
+                value: capture.max_entries.to_string(),
+                reason: "Must be > 0 when debug capture is enabled".to_string(),
+            });
         }
 
         Ok(())
     }
 
-    fn validate_compatibility(config: &RouterConfig) -> ConfigResult<()> {
-        if config.enable_igw {
-            return Ok(());
+    fn validate_metadata_cache(cache: &MetadataCacheConfig) -> ConfigResult<()> {
+        if cache.enabled && cache.max_entries == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "metadata_cache.max_entries".to_string(),
+                value: cache.max_entries.to_string(),
+                reason: "Must be > 0 when the metadata cache is enabled".to_string(),
+            });
         }
 
-        Self::validate_mtls(config)?;
+        Ok(())
+    }
 
-        let has_service_discovery = config.discovery.as_ref().is_some_and(|d| d.enabled);
+    fn validate_request_coalescing(coalescing: &RequestCoalescingConfig) -> ConfigResult<()> {
+        if coalescing.enabled && coalescing.max_request_bytes == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "request_coalescing.max_request_bytes".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when request coalescing is enabled".to_string(),
+            });
+        }
+        Ok(())
+    }
 
-        if let RoutingMode::EncodePrefillDecode { decode_policy, .. } = &config.mode {
-            let effective_decode_policy = decode_policy.as_ref().unwrap_or(&config.policy);
-            if matches!(effective_decode_policy, PolicyConfig::Bucket { .. }) {
-                return Err(ConfigError::IncompatibleConfig {
-                    reason: "Decode policy should not be allowed to be bucket".to_string(),
+    fn validate_conversation_compaction(
+        compaction: &ConversationCompactionConfig,
+    ) -> ConfigResult<()> {
+        if !compaction.enabled {
+            return Ok(());
+        }
+        if compaction.token_threshold == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "conversation_compaction.token_threshold".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when conversation compaction is enabled".to_string(),
+            });
+        }
+        if compaction
+            .model
+            .as_deref()
+            .is_some_and(|m| m.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidValue {
+                field: "conversation_compaction.model".to_string(),
+                value: String::new(),
+                reason: "Must not be empty when set".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_stream_fanout(fanout: &StreamFanoutConfig) -> ConfigResult<()> {
+        if !fanout.enabled {
+            return Ok(());
+        }
+        for (field, value) in [
+            ("stream_fanout.buffer_chunks", fanout.buffer_chunks),
+            ("stream_fanout.max_subscribers", fanout.max_subscribers),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Must be > 0 when stream fan-out is enabled".to_string(),
                 });
             }
         }
+        Ok(())
+    }
 
-        if !has_service_discovery {
-            if let PolicyConfig::PowerOfTwo { .. } = &config.policy {
-                let worker_count = config.mode.worker_count();
-                if worker_count < 2 {
-                    return Err(ConfigError::IncompatibleConfig {
-                        reason: "Power-of-two policy requires at least 2 workers".to_string(),
-                    });
-                }
+    fn validate_webhooks(webhooks: &WebhookConfig) -> ConfigResult<()> {
+        if !webhooks.enabled {
+            return Ok(());
+        }
+        for (tenant, url) in &webhooks.tenant_urls {
+            if !::url::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("webhooks.tenant_urls.{tenant}"),
+                    value: url.clone(),
+                    reason: "Must be an absolute http(s) URL".to_string(),
+                });
+            }
+        }
+        if webhooks.secret.as_deref() == Some("") {
+            return Err(ConfigError::InvalidValue {
+                field: "webhooks.secret".to_string(),
+                value: String::new(),
+                reason: "Must not be empty when set".to_string(),
+            });
+        }
+        for (field, value) in [
+            ("webhooks.max_attempts", webhooks.max_attempts as u64),
+            ("webhooks.timeout_secs", webhooks.timeout_secs),
+            (
+                "webhooks.dead_letter_capacity",
+                webhooks.dead_letter_capacity as u64,
+            ),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Must be > 0 when webhooks are enabled".to_string(),
+                });
             }
+        }
+        Ok(())
+    }
 
-            if let RoutingMode::PrefillDecode {
-                prefill_urls,
-                decode_urls,
-                prefill_policy,
-                decode_policy,
-            } = &config.mode
-            {
-                if let Some(PolicyConfig::PowerOfTwo { .. }) = prefill_policy {
-                    if prefill_urls.len() < 2 {
-                        return Err(ConfigError::IncompatibleConfig {
-                            reason: "Power-of-two policy for prefill requires at least 2 prefill workers".to_string(),
-                        });
-                    }
+    fn validate_grpc_pipeline(config: &RouterConfig) -> ConfigResult<()> {
+        let mut names = std::collections::HashSet::new();
+        for (i, stage) in config.grpc_pipeline.stages.iter().enumerate() {
+            if stage.name.trim().is_empty() || !names.insert(stage.name.as_str()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("grpc_pipeline.stages[{i}].name"),
+                    value: stage.name.clone(),
+                    reason: "Must be non-empty and unique".to_string(),
+                });
+            }
+            match &stage.kind {
+                CustomStageKind::MaxPromptTokens { max_tokens: 0 } => {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("grpc_pipeline.stages[{i}].max_tokens"),
+                        value: "0".to_string(),
+                        reason: "Must be > 0".to_string(),
+                    });
                 }
-
-                if let Some(PolicyConfig::PowerOfTwo { .. }) = decode_policy {
-                    if decode_urls.len() < 2 {
-                        return Err(ConfigError::IncompatibleConfig {
-                            reason:
-                                "Power-of-two policy for decode requires at least 2 decode workers"
-                                    .to_string(),
-                        });
-                    }
+                CustomStageKind::Wasm { module } if module.trim().is_empty() => {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("grpc_pipeline.stages[{i}].module"),
+                        value: module.clone(),
+                        reason: "Must name a WASM module".to_string(),
+                    });
                 }
-
-                // Check bucket for decode
-                if let Some(PolicyConfig::Bucket { .. }) = decode_policy {
+                CustomStageKind::Wasm { .. } if !config.enable_wasm => {
                     return Err(ConfigError::IncompatibleConfig {
-                        reason: "Decode policy should not be allowed to be bucket".to_string(),
+                        reason: format!(
+                            "gRPC pipeline stage '{}' runs a WASM module but WASM is not enabled",
+                            stage.name
+                        ),
                     });
                 }
+                CustomStageKind::MaxPromptTokens { .. } | CustomStageKind::Wasm { .. } => {}
             }
+        }
+        Ok(())
+    }
 
-            if let RoutingMode::EncodePrefillDecode {
-                prefill_urls,
-                decode_urls,
-                prefill_policy,
-                decode_policy,
-                ..
-            } = &config.mode
-            {
-                let effective_prefill_policy = prefill_policy.as_ref().unwrap_or(&config.policy);
-                let effective_decode_policy = decode_policy.as_ref().unwrap_or(&config.policy);
-
-                if matches!(effective_prefill_policy, PolicyConfig::PowerOfTwo { .. })
-                    && prefill_urls.len() < 2
-                {
-                    return Err(ConfigError::IncompatibleConfig {
-                        reason:
-                            "Power-of-two policy for prefill requires at least 2 prefill workers"
-                                .to_string(),
+    fn validate_sampling_limits(config: &SamplingLimitsConfig) -> ConfigResult<()> {
+        fn check<T: PartialOrd + std::fmt::Display + Copy>(
+            model: &str,
+            param: &str,
+            limits: &ParamLimits<T>,
+        ) -> ConfigResult<()> {
+            let field = |part: &str| format!("sampling_limits.models.{model}.{param}.{part}");
+            if let (Some(min), Some(max)) = (limits.min, limits.max) {
+                if min > max {
+                    return Err(ConfigError::InvalidValue {
+                        field: field("min"),
+                        value: min.to_string(),
+                        reason: format!("Must be <= max ({max})"),
                     });
                 }
-
-                if matches!(effective_decode_policy, PolicyConfig::PowerOfTwo { .. })
-                    && decode_urls.len() < 2
-                {
-                    return Err(ConfigError::IncompatibleConfig {
-                        reason: "Power-of-two policy for decode requires at least 2 decode workers"
-                            .to_string(),
+            }
+            if let Some(default) = limits.default {
+                let below = limits.min.is_some_and(|min| default < min);
+                let above = limits.max.is_some_and(|max| default > max);
+                if below || above {
+                    return Err(ConfigError::InvalidValue {
+                        field: field("default"),
+                        value: default.to_string(),
+                        reason: "Must be within [min, max]".to_string(),
                     });
                 }
             }
+            Ok(())
         }
 
+        for (model, limits) in &config.models {
+            check(model, "temperature", &limits.temperature)?;
+            check(model, "top_p", &limits.top_p)?;
+            check(model, "max_tokens", &limits.max_tokens)?;
+            check(model, "presence_penalty", &limits.presence_penalty)?;
+            check(model, "frequency_penalty", &limits.frequency_penalty)?;
+        }
         Ok(())
     }
 
-    fn validate_urls(urls: &[String]) -> ConfigResult<()> {
-        for url in urls {
-            if url.is_empty() {
+    fn validate_request_tags(config: &RequestTagsConfig) -> ConfigResult<()> {
+        if !config.enabled() {
+            return Ok(());
+        }
+        if config.tags.len() > RequestTagsConfig::MAX_TAGS {
+            return Err(ConfigError::InvalidValue {
+                field: "request_tags.tags".to_string(),
+                value: config.tags.len().to_string(),
+                reason: format!("At most {} tag keys", RequestTagsConfig::MAX_TAGS),
+            });
+        }
+        if config.max_values_per_tag == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "request_tags.max_values_per_tag".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when request tags are configured".to_string(),
+            });
+        }
+        for (key, values) in &config.tags {
+            if !RequestTagsConfig::is_valid_key(key) {
                 return Err(ConfigError::InvalidValue {
-                    field: "worker_url".to_string(),
-                    value: url.clone(),
-                    reason: "URL cannot be empty".to_string(),
+                    field: "request_tags.tags".to_string(),
+                    value: key.clone(),
+                    reason: "Tag keys must match [a-z_][a-z0-9_]* (max 32 chars)".to_string(),
                 });
             }
-
-            // Case-insensitive scheme allow-list. Compare just the scheme
-            // segment so we don't allocate a lowercased copy of the full URL.
-            const ALLOWED_SCHEMES: &[&str] = &["http", "https", "grpc", "grpcs"];
-            let scheme = url.split_once("://").map_or("", |(s, _)| s);
-            if !ALLOWED_SCHEMES
+            if let Some(value) = values
                 .iter()
-                .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+                .find(|value| !RequestTagsConfig::is_valid_value(value))
             {
                 return Err(ConfigError::InvalidValue {
-                    field: "worker_url".to_string(),
-                    value: url.clone(),
-                    reason: "URL must start with http://, https://, grpc://, or grpcs://"
-                        .to_string(),
+                    field: format!("request_tags.tags.{key}"),
+                    value: value.clone(),
+                    reason: "Tag values must be 1-64 chars of [A-Za-z0-9_.:-]".to_string(),
                 });
             }
-
-            match ::url::Url::parse(url) {
-                Ok(parsed) => {
-                    if parsed.host_str().is_none() {
-                        return Err(ConfigError::InvalidValue {
-                            field: "worker_url".to_string(),
-                            value: url.clone(),
-                            reason: "URL must have a valid host".to_string(),
-                        });
-                    }
-                }
-                Err(e) => {
-                    return Err(ConfigError::InvalidValue {
-                        field: "worker_url".to_string(),
-                        value: url.clone(),
-                        reason: format!("Invalid URL format: {e}"),
-                    });
-                }
-            }
         }
         Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::worker::ConnectionMode;
-
-    #[test]
-    fn mesh_server_name_with_colon_is_rejected() {
-        assert!(matches!(
-            validate_mesh_server_name("node:a"),
-            Err(ConfigError::InvalidValue { ref field, .. }) if field == "mesh_server_name"
-        ));
-    }
 
-    #[test]
-    fn empty_mesh_server_name_is_rejected() {
-        assert!(matches!(
-            validate_mesh_server_name(""),
-            Err(ConfigError::InvalidValue { ref field, .. }) if field == "mesh_server_name"
-        ));
+    fn validate_request_features(config: &RequestFeaturesConfig) -> ConfigResult<()> {
+        let names = config.feature_names();
+        if names.len() > RequestFeaturesConfig::MAX_FEATURES {
+            return Err(ConfigError::InvalidValue {
+                field: "request_features".to_string(),
+                value: names.len().to_string(),
+                reason: format!(
+                    "At most {} distinct features",
+                    RequestFeaturesConfig::MAX_FEATURES
+                ),
+            });
+        }
+        if let Some(name) = names
+            .iter()
+            .find(|name| !RequestFeaturesConfig::is_valid_name(name))
+        {
+            return Err(ConfigError::InvalidValue {
+                field: "request_features".to_string(),
+                value: (*name).to_string(),
+                reason: "Feature names must be 1-32 chars of [a-z0-9-]".to_string(),
+            });
+        }
+        if let Some(tenant) = config
+            .tenants
+            .keys()
+            .find(|tenant| tenant.trim().is_empty())
+        {
+            return Err(ConfigError::InvalidValue {
+                field: "request_features.tenants".to_string(),
+                value: tenant.clone(),
+                reason: "Tenant ids must be non-empty".to_string(),
+            });
+        }
+        Ok(())
     }
 
-    #[test]
-    fn valid_mesh_server_name_is_accepted() {
-        assert!(validate_mesh_server_name("node-a").is_ok());
-    }
+    fn validate_maintenance(config: &MaintenanceConfig) -> ConfigResult<()> {
+        use crate::worker::maintenance::{CronSchedule, MAX_WINDOW_SECS};
 
-    #[test]
-    fn test_validate_regular_mode() {
-        let config = RouterConfig::new(
-            RoutingMode::Regular {
+        if config.check_interval_secs == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "maintenance.check_interval_secs".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0".to_string(),
+            });
+        }
+        let mut names = std::collections::HashSet::new();
+        for (i, window) in config.windows.iter().enumerate() {
+            if window.name.is_empty() || !names.insert(window.name.as_str()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("maintenance.windows[{i}].name"),
+                    value: window.name.clone(),
+                    reason: "Window names must be non-empty and unique".to_string(),
+                });
+            }
+            if window.worker_url.is_some() == window.model.is_some() {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("maintenance.windows[{i}]"),
+                    value: window.name.clone(),
+                    reason: "Exactly one of worker_url or model must be set".to_string(),
+                });
+            }
+            if let Err(e) = window.schedule.parse::<CronSchedule>() {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("maintenance.windows[{i}].schedule"),
+                    value: window.schedule.clone(),
+                    reason: e,
+                });
+            }
+            if !(1..=MAX_WINDOW_SECS).contains(&window.duration_secs) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("maintenance.windows[{i}].duration_secs"),
+                    value: window.duration_secs.to_string(),
+                    reason: format!("Must be between 1 and {MAX_WINDOW_SECS}"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_experiments(config: &ExperimentsConfig) -> ConfigResult<()> {
+        // Experiment and arm names are echoed in response headers.
+        let valid_name =
+            |name: &str| !name.is_empty() && name.bytes().all(|b| b.is_ascii_graphic());
+
+        let mut names = std::collections::HashSet::new();
+        let mut models = std::collections::HashSet::new();
+        for (i, experiment) in config.experiments.iter().enumerate() {
+            if !valid_name(&experiment.name) || !names.insert(experiment.name.as_str()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("experiments.experiments[{i}].name"),
+                    value: experiment.name.clone(),
+                    reason: "Experiment names must be unique, non-empty and printable ASCII"
+                        .to_string(),
+                });
+            }
+            if !models.insert(experiment.model.as_str()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("experiments.experiments[{i}].model"),
+                    value: experiment.model.clone(),
+                    reason: "At most one experiment may target a model".to_string(),
+                });
+            }
+            if let ExperimentStickyKey::Header(header) = &experiment.sticky_key {
+                if HeaderName::from_bytes(header.as_bytes()).is_err() {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("experiments.experiments[{i}].sticky_key"),
+                        value: header.clone(),
+                        reason: "Not a valid HTTP header name".to_string(),
+                    });
+                }
+            }
+            if experiment.arms.is_empty() {
+                return Err(ConfigError::MissingRequired {
+                    field: format!("experiments.experiments[{i}].arms"),
+                });
+            }
+            let mut arm_names = std::collections::HashSet::new();
+            let mut total = 0.0;
+            for (j, arm) in experiment.arms.iter().enumerate() {
+                if !valid_name(&arm.name) || !arm_names.insert(arm.name.as_str()) {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("experiments.experiments[{i}].arms[{j}].name"),
+                        value: arm.name.clone(),
+                        reason: "Arm names must be unique, non-empty and printable ASCII"
+                            .to_string(),
+                    });
+                }
+                if !(arm.percent > 0.0 && arm.percent <= 100.0) {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("experiments.experiments[{i}].arms[{j}].percent"),
+                        value: arm.percent.to_string(),
+                        reason: "Must be in (0, 100]".to_string(),
+                    });
+                }
+                total += arm.percent;
+            }
+            if total > 100.0 {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("experiments.experiments[{i}].arms"),
+                    value: total.to_string(),
+                    reason: "Arm percentages must sum to at most 100".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_prompt_guard(config: &PromptGuardConfig) -> ConfigResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        if !(config.threshold > 0.0 && config.threshold <= 1.0) {
+            return Err(ConfigError::InvalidValue {
+                field: "prompt_guard.threshold".to_string(),
+                value: config.threshold.to_string(),
+                reason: "Must be in (0, 1]".to_string(),
+            });
+        }
+        // Rule names label metrics and the response annotation header.
+        let mut names = std::collections::HashSet::new();
+        for (i, rule) in config.rules.iter().enumerate() {
+            let valid_name = !rule.name.is_empty()
+                && rule
+                    .name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.'));
+            if !valid_name || !names.insert(rule.name.as_str()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("prompt_guard.rules[{i}].name"),
+                    value: rule.name.clone(),
+                    reason: "Rule names must be unique and consist of [A-Za-z0-9_.-]".to_string(),
+                });
+            }
+            if let Err(e) = regex::RegexBuilder::new(&rule.pattern)
+                .case_insensitive(true)
+                .build()
+            {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("prompt_guard.rules[{i}].pattern"),
+                    value: rule.pattern.clone(),
+                    reason: e.to_string(),
+                });
+            }
+            if !(rule.score > 0.0 && rule.score <= 1.0) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("prompt_guard.rules[{i}].score"),
+                    value: rule.score.to_string(),
+                    reason: "Must be in (0, 1]".to_string(),
+                });
+            }
+        }
+        if !config.builtin_rules && config.rules.is_empty() && config.classifier.is_none() {
+            return Err(ConfigError::MissingRequired {
+                field: "prompt_guard.rules".to_string(),
+            });
+        }
+        if let Some(classifier) = &config.classifier {
+            if !::url::Url::parse(&classifier.endpoint)
+                .is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+            {
+                return Err(ConfigError::InvalidValue {
+                    field: "prompt_guard.classifier.endpoint".to_string(),
+                    value: classifier.endpoint.clone(),
+                    reason: "Must be an absolute http(s) URL".to_string(),
+                });
+            }
+            if classifier.timeout_ms == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: "prompt_guard.classifier.timeout_ms".to_string(),
+                    value: "0".to_string(),
+                    reason: "Must be positive".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_provenance(config: &ProvenanceConfig) -> ConfigResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        if config.signing_key.as_deref().is_none_or(str::is_empty) {
+            return Err(ConfigError::MissingRequired {
+                field: "provenance.signing_key".to_string(),
+            });
+        }
+        if let Some(key_id) = &config.key_id {
+            if key_id.is_empty() || HeaderValue::from_str(key_id).is_err() {
+                return Err(ConfigError::InvalidValue {
+                    field: "provenance.key_id".to_string(),
+                    value: key_id.clone(),
+                    reason: "Must be a non-empty, header-safe string".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_routing_rules(config: &RoutingRulesConfig) -> ConfigResult<()> {
+        use crate::middleware::{routing_rules::validate_rules, RoutingRulesError};
+
+        match validate_rules(&config.rules) {
+            Err(RoutingRulesError::Invalid { index, reason }) => Err(ConfigError::InvalidValue {
+                field: format!("routing_rules.rules[{index}]"),
+                value: config.rules[index].name.clone(),
+                reason,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_federation(config: &FederationConfig) -> ConfigResult<()> {
+        if config.signing_secret.as_deref() == Some("") {
+            return Err(ConfigError::InvalidValue {
+                field: "federation.signing_secret".to_string(),
+                value: String::new(),
+                reason: "Must not be empty".to_string(),
+            });
+        }
+        if config.require_signed && config.signing_secret.is_none() {
+            return Err(ConfigError::MissingRequired {
+                field: "federation.signing_secret".to_string(),
+            });
+        }
+        for (field, value) in [
+            ("federation.max_clock_skew_secs", config.max_clock_skew_secs),
+            (
+                "federation.catalog_refresh_interval_secs",
+                config.catalog_refresh_interval_secs,
+            ),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Must be > 0".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_standby(config: &StandbyConfig) -> ConfigResult<()> {
+        let thresholds = config
+            .model_min_active_workers
+            .iter()
+            .map(|(model, &min)| (format!("standby.model_min_active_workers.{model}"), min))
+            .chain([(
+                "standby.min_active_workers".to_string(),
+                config.min_active_workers,
+            )]);
+        for (field, value) in thresholds {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field,
+                    value: value.to_string(),
+                    reason: "Must be > 0; use standby: false to keep a worker in rotation"
+                        .to_string(),
+                });
+            }
+        }
+        if config.check_interval_secs == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "standby.check_interval_secs".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_pd_bootstrap(config: &PdBootstrapConfig) -> ConfigResult<()> {
+        if config.stuck_threshold_secs == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "pd_bootstrap.stuck_threshold_secs".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_pd_pairs(config: &PdPairsConfig) -> ConfigResult<()> {
+        for (field, value) in [
+            ("pd_pairs.window_secs", config.window_secs as usize),
+            ("pd_pairs.min_samples", config.min_samples),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: "0".to_string(),
+                    reason: "Must be > 0".to_string(),
+                });
+            }
+        }
+        if !(config.slow_factor > 1.0 && config.slow_factor.is_finite()) {
+            return Err(ConfigError::InvalidValue {
+                field: "pd_pairs.slow_factor".to_string(),
+                value: config.slow_factor.to_string(),
+                reason: "Must be > 1.0".to_string(),
+            });
+        }
+        if !(config.max_error_rate > 0.0 && config.max_error_rate <= 1.0) {
+            return Err(ConfigError::InvalidValue {
+                field: "pd_pairs.max_error_rate".to_string(),
+                value: config.max_error_rate.to_string(),
+                reason: "Must be in (0.0, 1.0]".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_sessions(config: &SessionsConfig) -> ConfigResult<()> {
+        for (field, value) in [
+            ("sessions.idle_ttl_secs", config.idle_ttl_secs as usize),
+            ("sessions.max_sessions", config.max_sessions),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: "0".to_string(),
+                    reason: "Must be > 0".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_partial_transcripts(config: &PartialTranscriptsConfig) -> ConfigResult<()> {
+        if config.enabled && config.max_output_bytes == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "partial_transcripts.max_output_bytes".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when partial transcripts are enabled".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_tenant_mcp_servers(config: &TenantMcpServersConfig) -> ConfigResult<()> {
+        if config.enabled && config.max_servers_per_tenant == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "tenant_mcp_servers.max_servers_per_tenant".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when tenant MCP servers are enabled".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_prompts(config: &PromptsConfig) -> ConfigResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        for (field, value) in [
+            (
+                "prompts.max_prompts_per_tenant",
+                config.max_prompts_per_tenant,
+            ),
+            (
+                "prompts.max_versions_per_prompt",
+                config.max_versions_per_prompt,
+            ),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: "0".to_string(),
+                    reason: "Must be > 0 when prompts are enabled".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_latency_budget(config: &LatencyBudgetConfig) -> ConfigResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        let tps = config.default_tokens_per_second;
+        if !tps.is_finite() || tps <= 0.0 {
+            return Err(ConfigError::InvalidValue {
+                field: "latency_budget.default_tokens_per_second".to_string(),
+                value: tps.to_string(),
+                reason: "Must be a finite number > 0".to_string(),
+            });
+        }
+        if config.min_max_tokens == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "latency_budget.min_max_tokens".to_string(),
+                value: "0".to_string(),
+                reason: "Must be > 0 when latency budgets are enabled".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_async_generation(config: &AsyncGenerationConfig) -> ConfigResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        for (field, value) in [
+            ("async_generation.max_queued", config.max_queued),
+            ("async_generation.concurrency", config.concurrency),
+            (
+                "async_generation.max_jobs_per_tenant",
+                config.max_jobs_per_tenant,
+            ),
+            (
+                "async_generation.max_retained_jobs",
+                config.max_retained_jobs,
+            ),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Must be > 0 when async generation is enabled".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_map_reduce(map_reduce: &MapReduceConfig) -> ConfigResult<()> {
+        if map_reduce.models.is_empty() {
+            return Ok(());
+        }
+
+        for (field, value) in [
+            ("map_reduce.chunk_chars", map_reduce.chunk_chars),
+            ("map_reduce.max_chunks", map_reduce.max_chunks),
+            ("map_reduce.max_concurrency", map_reduce.max_concurrency),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Must be > 0 when map-reduce is enabled".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_vector_store(config: &RouterConfig) -> ConfigResult<()> {
+        let store = &config.vector_store;
+        if !store.enabled {
+            return Ok(());
+        }
+        if !config.file_store.enabled {
+            return Err(ConfigError::IncompatibleConfig {
+                reason: "vector_store requires file_store to be enabled".to_string(),
+            });
+        }
+        for (field, value) in [
+            ("embedding_batch_size", store.embedding_batch_size),
+            ("max_chunks_per_store", store.max_chunks_per_store),
+            ("retrieval_top_k", store.retrieval_top_k),
+            ("rerank_top_k", store.rerank_top_k),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("vector_store.{field}"),
+                    value: "0".to_string(),
+                    reason: "Must be > 0 when the vector store is enabled".to_string(),
+                });
+            }
+        }
+        for (field, model) in [
+            ("embedding_model", &store.embedding_model),
+            ("rerank_model", &store.rerank_model),
+        ] {
+            if model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("vector_store.{field}"),
+                    value: String::new(),
+                    reason: "Must not be empty when set".to_string(),
+                });
+            }
+        }
+        if store.rerank_top_k > store.retrieval_top_k {
+            return Err(ConfigError::InvalidValue {
+                field: "vector_store.rerank_top_k".to_string(),
+                value: store.rerank_top_k.to_string(),
+                reason: format!(
+                    "Must not exceed vector_store.retrieval_top_k ({})",
+                    store.retrieval_top_k
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_file_store(store: &FileStoreConfig) -> ConfigResult<()> {
+        if !store.enabled {
+            return Ok(());
+        }
+
+        if store.max_files == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "file_store.max_files".to_string(),
+                value: store.max_files.to_string(),
+                reason: "Must be > 0 when the file store is enabled".to_string(),
+            });
+        }
+
+        if let Some(base) = &store.public_base_url {
+            if ::url::Url::parse(base).is_err() {
+                return Err(ConfigError::InvalidValue {
+                    field: "file_store.public_base_url".to_string(),
+                    value: base.clone(),
+                    reason: "Must be an absolute URL".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_chat_completion_store(store: &ChatCompletionStoreConfig) -> ConfigResult<()> {
+        if store.enabled && store.max_entries == 0 {
+            return Err(ConfigError::InvalidValue {
+                field: "chat_completion_store.max_entries".to_string(),
+                value: store.max_entries.to_string(),
+                reason: "Must be > 0 when the chat completion store is enabled".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn validate_fault_injection(config: &RouterConfig) -> ConfigResult<()> {
+        let faults = &config.fault_injection;
+        if !faults.enabled {
+            return Ok(());
+        }
+
+        // Only the HTTP regular router injects faults; anywhere else the rules
+        // would silently never fire.
+        let http_regular = config.connection_mode == ConnectionMode::Http
+            && matches!(config.mode, RoutingMode::Regular { .. })
+            && !config.enable_igw;
+        if !http_regular {
+            return Err(ConfigError::IncompatibleConfig {
+                reason: "fault_injection is only supported by the HTTP regular router".to_string(),
+            });
+        }
+
+        for (i, rule) in faults.rules.iter().enumerate() {
+            if !(0.0..=1.0).contains(&rule.probability) {
+                return Err(ConfigError::InvalidValue {
+                    field: format!("fault_injection.rules[{i}].probability"),
+                    value: rule.probability.to_string(),
+                    reason: "Must be between 0.0 and 1.0".to_string(),
+                });
+            }
+
+            if let FaultKind::Status { status } = rule.fault {
+                if !(400..=599).contains(&status) {
+                    return Err(ConfigError::InvalidValue {
+                        field: format!("fault_injection.rules[{i}].status"),
+                        value: status.to_string(),
+                        reason: "Must be an HTTP error status (400-599)".to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_mtls(config: &RouterConfig) -> ConfigResult<()> {
+        if let Some(identity) = &config.client_identity {
+            if identity.is_empty() {
+                return Err(ConfigError::ValidationFailed {
+                    reason: "Client identity cannot be empty".to_string(),
+                });
+            }
+        }
+
+        for (idx, ca_cert) in config.ca_certificates.iter().enumerate() {
+            if ca_cert.is_empty() {
+                return Err(ConfigError::ValidationFailed {
+                    reason: format!("CA certificate at index {idx} cannot be empty"),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_compatibility(config: &RouterConfig) -> ConfigResult<()> {
+        if config.enable_igw {
+            return Ok(());
+        }
+
+        Self::validate_mtls(config)?;
+
+        let has_service_discovery = config.discovery.as_ref().is_some_and(|d| d.enabled);
+
+        if let RoutingMode::EncodePrefillDecode { decode_policy, .. } = &config.mode {
+            let effective_decode_policy = decode_policy.as_ref().unwrap_or(&config.policy);
+            if matches!(effective_decode_policy, PolicyConfig::Bucket { .. }) {
+                return Err(ConfigError::IncompatibleConfig {
+                    reason: "Decode policy should not be allowed to be bucket".to_string(),
+                });
+            }
+        }
+
+        if !has_service_discovery {
+            if let PolicyConfig::PowerOfTwo { .. } = &config.policy {
+                let worker_count = config.mode.worker_count();
+                if worker_count < 2 {
+                    return Err(ConfigError::IncompatibleConfig {
+                        reason: "Power-of-two policy requires at least 2 workers".to_string(),
+                    });
+                }
+            }
+
+            if let RoutingMode::PrefillDecode {
+                prefill_urls,
+                decode_urls,
+                prefill_policy,
+                decode_policy,
+            } = &config.mode
+            {
+                if let Some(PolicyConfig::PowerOfTwo { .. }) = prefill_policy {
+                    if prefill_urls.len() < 2 {
+                        return Err(ConfigError::IncompatibleConfig {
+                            reason: "Power-of-two policy for prefill requires at least 2 prefill workers".to_string(),
+                        });
+                    }
+                }
+
+                if let Some(PolicyConfig::PowerOfTwo { .. }) = decode_policy {
+                    if decode_urls.len() < 2 {
+                        return Err(ConfigError::IncompatibleConfig {
+                            reason:
+                                "Power-of-two policy for decode requires at least 2 decode workers"
+                                    .to_string(),
+                        });
+                    }
+                }
+
+                // Check bucket for decode
+                if let Some(PolicyConfig::Bucket { .. }) = decode_policy {
+                    return Err(ConfigError::IncompatibleConfig {
+                        reason: "Decode policy should not be allowed to be bucket".to_string(),
+                    });
+                }
+            }
+
+            if let RoutingMode::EncodePrefillDecode {
+                prefill_urls,
+                decode_urls,
+                prefill_policy,
+                decode_policy,
+                ..
+            } = &config.mode
+            {
+                let effective_prefill_policy = prefill_policy.as_ref().unwrap_or(&config.policy);
+                let effective_decode_policy = decode_policy.as_ref().unwrap_or(&config.policy);
+
+                if matches!(effective_prefill_policy, PolicyConfig::PowerOfTwo { .. })
+                    && prefill_urls.len() < 2
+                {
+                    return Err(ConfigError::IncompatibleConfig {
+                        reason:
+                            "Power-of-two policy for prefill requires at least 2 prefill workers"
+                                .to_string(),
+                    });
+                }
+
+                if matches!(effective_decode_policy, PolicyConfig::PowerOfTwo { .. })
+                    && decode_urls.len() < 2
+                {
+                    return Err(ConfigError::IncompatibleConfig {
+                        reason: "Power-of-two policy for decode requires at least 2 decode workers"
+                            .to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_urls(urls: &[String]) -> ConfigResult<()> {
+        for url in urls {
+            if url.is_empty() {
+                return Err(ConfigError::InvalidValue {
+                    field: "worker_url".to_string(),
+                    value: url.clone(),
+                    reason: "URL cannot be empty".to_string(),
+                });
+            }
+
+            // Case-insensitive scheme allow-list. Compare just the scheme
+            // segment so we don't allocate a lowercased copy of the full URL.
+            const ALLOWED_SCHEMES: &[&str] = &["http", "https", "grpc", "grpcs"];
+            let scheme = url.split_once("://").map_or("", |(s, _)| s);
+            if !ALLOWED_SCHEMES
+                .iter()
+                .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+            {
+                return Err(ConfigError::InvalidValue {
+                    field: "worker_url".to_string(),
+                    value: url.clone(),
+                    reason: "URL must start with http://, https://, grpc://, or grpcs://"
+                        .to_string(),
+                });
+            }
+
+            match ::url::Url::parse(url) {
+                Ok(parsed) => {
+                    if parsed.host_str().is_none() {
+                        return Err(ConfigError::InvalidValue {
+                            field: "worker_url".to_string(),
+                            value: url.clone(),
+                            reason: "URL must have a valid host".to_string(),
+                        });
+                    }
+                }
+                Err(e) => {
+                    return Err(ConfigError::InvalidValue {
+                        field: "worker_url".to_string(),
+                        value: url.clone(),
+                        reason: format!("Invalid URL format: {e}"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mesh_server_name_with_colon_is_rejected() {
+        assert!(matches!(
+            validate_mesh_server_name("node:a"),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "mesh_server_name"
+        ));
+    }
+
+    #[test]
+    fn empty_mesh_server_name_is_rejected() {
+        assert!(matches!(
+            validate_mesh_server_name(""),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "mesh_server_name"
+        ));
+    }
+
+    #[test]
+    fn valid_mesh_server_name_is_accepted() {
+        assert!(validate_mesh_server_name("node-a").is_ok());
+    }
+
+    #[test]
+    fn test_validate_regular_mode() {
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec!["http://worker:8000".to_string()],
+            },
+            PolicyConfig::Random,
+        );
+
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    fn regular_mode_config() -> RouterConfig {
+        RouterConfig::new(
+            RoutingMode::Regular {
                 worker_urls: vec!["http://worker:8000".to_string()],
             },
             PolicyConfig::Random,
+        )
+    }
+
+    #[test]
+    fn test_validate_distinct_tenant_api_keys_accepted() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![
+            TenantApiKeyEntry {
+                tenant_id: "team-a".to_string(),
+                key: "secret-a".to_string(),
+            },
+            TenantApiKeyEntry {
+                tenant_id: "team-b".to_string(),
+                key: "secret-b".to_string(),
+            },
+        ];
+
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_duplicate_tenant_api_keys_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![
+            TenantApiKeyEntry {
+                tenant_id: "team-a".to_string(),
+                key: "shared-secret".to_string(),
+            },
+            TenantApiKeyEntry {
+                tenant_id: "team-b".to_string(),
+                key: "shared-secret".to_string(),
+            },
+        ];
+
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        let message = err.to_string();
+        assert!(message.contains("team-a"));
+        assert!(message.contains("team-b"));
+        assert!(
+            !message.contains("shared-secret"),
+            "error must not leak the credential value: {message}"
+        );
+    }
+
+    #[test]
+    fn test_validate_tenant_api_key_matching_shared_api_key_rejected() {
+        let mut config = regular_mode_config();
+        config.api_key = Some("shared-secret".to_string());
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: "team-a".to_string(),
+            key: "shared-secret".to_string(),
+        }];
+
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        let message = err.to_string();
+        assert!(message.contains("team-a"));
+        assert!(message.contains("shared api_key"));
+        assert!(
+            !message.contains("shared-secret"),
+            "error must not leak the credential value: {message}"
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_tenant_id_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: String::new(),
+            key: "some-secret".to_string(),
+        }];
+
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        assert!(err.to_string().contains("tenant_id"));
+    }
+
+    #[test]
+    fn test_validate_whitespace_only_tenant_id_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: "   ".to_string(),
+            key: "some-secret".to_string(),
+        }];
+
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    /// A padded-but-otherwise-valid tenant_id (e.g. supplied via a config
+    /// file or binding, bypassing the CLI parser's own trim) must be
+    /// rejected rather than silently resolving to a different `auth:` key
+    /// than the canonical, unpadded tenant_id.
+    #[test]
+    fn test_validate_padded_tenant_id_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: " team-a ".to_string(),
+            key: "some-secret".to_string(),
+        }];
+
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        assert!(err.to_string().contains("whitespace"));
+    }
+
+    /// Same asymmetry as the padded-tenant_id case, but for `key`: the CLI
+    /// trims it, config-file/binding entries don't, so an untrimmed key
+    /// would hash differently than intended and silently evade the
+    /// duplicate-value check.
+    #[test]
+    fn test_validate_padded_key_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: "team-a".to_string(),
+            key: " some-secret ".to_string(),
+        }];
+
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        let message = err.to_string();
+        assert!(message.contains("team-a"));
+        assert!(message.contains("whitespace"));
+        assert!(
+            !message.contains("some-secret"),
+            "error must not leak the credential value: {message}"
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_key_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: "team-a".to_string(),
+            key: String::new(),
+        }];
+
+        // An empty key would make `Authorization: Bearer ` (empty token) a
+        // valid credential for this tenant.
+        let err = ConfigValidator::validate(&config).unwrap_err();
+        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
+        let message = err.to_string();
+        assert!(message.contains("team-a"));
+        assert!(message.contains("key"));
+    }
+
+    #[test]
+    fn test_validate_whitespace_only_key_rejected() {
+        let mut config = regular_mode_config();
+        config.tenant_api_keys = vec![TenantApiKeyEntry {
+            tenant_id: "team-a".to_string(),
+            key: "   ".to_string(),
+        }];
+
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_empty_worker_urls() {
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec![],
+            },
+            PolicyConfig::Random,
+        );
+
+        // Empty worker URLs are now allowed to match legacy behavior
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_empty_worker_urls_with_service_discovery() {
+        let mut config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec![],
+            },
+            PolicyConfig::Random,
+        );
+
+        // Enable service discovery
+        config.discovery = Some(DiscoveryConfig {
+            enabled: true,
+            selector: vec![("app".to_string(), "test".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        });
+
+        // Should pass validation since service discovery is enabled
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_invalid_urls() {
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec!["invalid-url".to_string()],
+            },
+            PolicyConfig::Random,
+        );
+
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_cache_aware_thresholds() {
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec![
+                    "http://worker1:8000".to_string(),
+                    "http://worker2:8000".to_string(),
+                ],
+            },
+            PolicyConfig::CacheAware {
+                cache_threshold: 1.5, // Invalid: > 1.0
+                balance_abs_threshold: 32,
+                balance_rel_threshold: 1.1,
+                eviction_interval_secs: 60,
+                max_tree_size: 1000,
+                block_size: 16,
+                balance_token_usage_threshold: 1.0,
+                overload_token_usage_threshold: 1.0,
+                tree_window_secs: 0,
+                cache_decay_half_life_secs: 0,
+            },
+        );
+
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_cache_aware_single_worker() {
+        // Cache-aware with single worker should be allowed (even if not optimal)
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec!["http://worker1:8000".to_string()],
+            },
+            PolicyConfig::CacheAware {
+                cache_threshold: 0.5,
+                balance_abs_threshold: 32,
+                balance_rel_threshold: 1.1,
+                eviction_interval_secs: 60,
+                max_tree_size: 1000,
+                block_size: 16,
+                balance_token_usage_threshold: 1.0,
+                overload_token_usage_threshold: 1.0,
+                tree_window_secs: 0,
+                cache_decay_half_life_secs: 0,
+            },
+        );
+
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_pd_mode() {
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![("http://prefill:8000".to_string(), Some(8081))],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::Random,
+        );
+
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_roundrobin_with_pd_mode() {
+        // RoundRobin with PD mode is now supported
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::RoundRobin,
+        );
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_cache_aware_with_pd_mode() {
+        // CacheAware with PD mode is now supported
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::CacheAware {
+                cache_threshold: 0.5,
+                balance_abs_threshold: 32,
+                balance_rel_threshold: 1.1,
+                eviction_interval_secs: 60,
+                max_tree_size: 1000,
+                block_size: 16,
+                balance_token_usage_threshold: 1.0,
+                overload_token_usage_threshold: 1.0,
+                tree_window_secs: 0,
+                cache_decay_half_life_secs: 0,
+            },
+        );
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_power_of_two_with_regular_mode() {
+        // PowerOfTwo with Regular mode is now supported
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec![
+                    "http://worker1:8000".to_string(),
+                    "http://worker2:8000".to_string(),
+                ],
+            },
+            PolicyConfig::PowerOfTwo {
+                load_check_interval_secs: 60,
+            },
+        );
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_pd_mode_with_separate_policies() {
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![
+                    ("http://prefill1:8000".to_string(), None),
+                    ("http://prefill2:8000".to_string(), None),
+                ],
+                decode_urls: vec![
+                    "http://decode1:8000".to_string(),
+                    "http://decode2:8000".to_string(),
+                ],
+                prefill_policy: Some(PolicyConfig::CacheAware {
+                    cache_threshold: 0.5,
+                    balance_abs_threshold: 32,
+                    balance_rel_threshold: 1.1,
+                    eviction_interval_secs: 60,
+                    max_tree_size: 1000,
+                    block_size: 16,
+                    balance_token_usage_threshold: 1.0,
+                    overload_token_usage_threshold: 1.0,
+                    tree_window_secs: 0,
+                    cache_decay_half_life_secs: 0,
+                }),
+                decode_policy: Some(PolicyConfig::PowerOfTwo {
+                    load_check_interval_secs: 60,
+                }),
+            },
+            PolicyConfig::Random, // Main policy as fallback
         );
 
-        assert!(ConfigValidator::validate(&config).is_ok());
-    }
-
-    fn regular_mode_config() -> RouterConfig {
-        RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["http://worker:8000".to_string()],
-            },
-            PolicyConfig::Random,
-        )
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
     }
 
     #[test]
-    fn test_validate_distinct_tenant_api_keys_accepted() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![
-            TenantApiKeyEntry {
-                tenant_id: "team-a".to_string(),
-                key: "secret-a".to_string(),
-            },
-            TenantApiKeyEntry {
-                tenant_id: "team-b".to_string(),
-                key: "secret-b".to_string(),
+    fn test_validate_pd_mode_power_of_two_insufficient_workers() {
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![("http://prefill1:8000".to_string(), None)], // Only 1 prefill
+                decode_urls: vec![
+                    "http://decode1:8000".to_string(),
+                    "http://decode2:8000".to_string(),
+                ],
+                prefill_policy: Some(PolicyConfig::PowerOfTwo {
+                    load_check_interval_secs: 60,
+                }), // Requires 2+ workers
+                decode_policy: None,
             },
-        ];
+            PolicyConfig::Random,
+        );
 
-        assert!(ConfigValidator::validate(&config).is_ok());
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(e.to_string().contains("prefill requires at least 2"));
+        }
     }
 
     #[test]
-    fn test_validate_duplicate_tenant_api_keys_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![
-            TenantApiKeyEntry {
-                tenant_id: "team-a".to_string(),
-                key: "shared-secret".to_string(),
-            },
-            TenantApiKeyEntry {
-                tenant_id: "team-b".to_string(),
-                key: "shared-secret".to_string(),
+    fn test_validate_pd_mode_bucket_policy_restrictions() {
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![
+                    ("http://prefill1:8000".to_string(), None),
+                    ("http://prefill2:8000".to_string(), None),
+                ],
+                decode_urls: vec![
+                    "http://decode1:8000".to_string(),
+                    "http://decode2:8000".to_string(),
+                ],
+                prefill_policy: Some(PolicyConfig::Bucket {
+                    balance_abs_threshold: 32,
+                    balance_rel_threshold: 1.1,
+                    bucket_adjust_interval_secs: 5,
+                }),
+                decode_policy: Some(PolicyConfig::PowerOfTwo {
+                    load_check_interval_secs: 60,
+                }),
             },
-        ];
+            PolicyConfig::Random, // Main policy as fallback
+        );
 
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        let message = err.to_string();
-        assert!(message.contains("team-a"));
-        assert!(message.contains("team-b"));
+        let result = ConfigValidator::validate(&config);
         assert!(
-            !message.contains("shared-secret"),
-            "error must not leak the credential value: {message}"
+            result.is_ok(),
+            "Prefill policy should be allowed to be bucket"
         );
-    }
 
-    #[test]
-    fn test_validate_tenant_api_key_matching_shared_api_key_rejected() {
-        let mut config = regular_mode_config();
-        config.api_key = Some("shared-secret".to_string());
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: "team-a".to_string(),
-            key: "shared-secret".to_string(),
-        }];
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![
+                    ("http://prefill1:8000".to_string(), None),
+                    ("http://prefill2:8000".to_string(), None),
+                ],
+                decode_urls: vec![
+                    "http://decode1:8000".to_string(),
+                    "http://decode2:8000".to_string(),
+                ],
+                prefill_policy: Some(PolicyConfig::Bucket {
+                    balance_abs_threshold: 32,
+                    balance_rel_threshold: 1.1,
+                    bucket_adjust_interval_secs: 5,
+                }),
+                decode_policy: Some(PolicyConfig::Bucket {
+                    balance_abs_threshold: 32,
+                    balance_rel_threshold: 1.1,
+                    bucket_adjust_interval_secs: 5,
+                }),
+            },
+            PolicyConfig::Random, // Main policy as fallback
+        );
 
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        let message = err.to_string();
-        assert!(message.contains("team-a"));
-        assert!(message.contains("shared api_key"));
+        let result = ConfigValidator::validate(&config);
         assert!(
-            !message.contains("shared-secret"),
-            "error must not leak the credential value: {message}"
+            result.is_err(),
+            "Decode policy should not be allowed to be bucket"
         );
     }
 
     #[test]
-    fn test_validate_empty_tenant_id_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: String::new(),
-            key: "some-secret".to_string(),
-        }];
-
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        assert!(err.to_string().contains("tenant_id"));
-    }
+    fn test_validate_epd_mode_encode_policy_restrictions() {
+        let valid = RouterConfig::new(
+            RoutingMode::EncodePrefillDecode {
+                encode_urls: vec![("http://encode:8000".to_string(), None)],
+                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                encode_policy: Some(PolicyConfig::ConsistentHashing { load_epsilon: None }),
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::Random,
+        );
+        assert!(ConfigValidator::validate(&valid).is_ok());
 
-    #[test]
-    fn test_validate_whitespace_only_tenant_id_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: "   ".to_string(),
-            key: "some-secret".to_string(),
-        }];
+        let invalid_cache_aware = RouterConfig::new(
+            RoutingMode::EncodePrefillDecode {
+                encode_urls: vec![("http://encode:8000".to_string(), None)],
+                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                encode_policy: Some(PolicyConfig::CacheAware {
+                    cache_threshold: 0.5,
+                    balance_abs_threshold: 32,
+                    balance_rel_threshold: 1.1,
+                    eviction_interval_secs: 60,
+                    max_tree_size: 1000,
+                    block_size: 16,
+                    balance_token_usage_threshold: 1.0,
+                    overload_token_usage_threshold: 1.0,
+                    tree_window_secs: 0,
+                    cache_decay_half_life_secs: 0,
+                }),
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::Random,
+        );
+        assert!(ConfigValidator::validate(&invalid_cache_aware).is_err());
 
-        assert!(ConfigValidator::validate(&config).is_err());
+        let invalid_least_load = RouterConfig::new(
+            RoutingMode::EncodePrefillDecode {
+                encode_urls: vec![("http://encode:8000".to_string(), None)],
+                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+                decode_urls: vec!["http://decode:8000".to_string()],
+                encode_policy: Some(PolicyConfig::LeastLoad {
+                    load_check_interval_secs: 5,
+                    kv_pressure_weight: 0.15,
+                    mean_prefill_tokens: 1024,
+                    default_throughput: 2000.0,
+                }),
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::Random,
+        );
+        assert!(ConfigValidator::validate(&invalid_least_load).is_err());
     }
 
-    /// A padded-but-otherwise-valid tenant_id (e.g. supplied via a config
-    /// file or binding, bypassing the CLI parser's own trim) must be
-    /// rejected rather than silently resolving to a different `auth:` key
-    /// than the canonical, unpadded tenant_id.
     #[test]
-    fn test_validate_padded_tenant_id_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: " team-a ".to_string(),
-            key: "some-secret".to_string(),
-        }];
+    fn test_validate_empty_urls_allowed_without_service_discovery() {
+        // Test that empty URLs are now allowed in PD mode
+        let config = RouterConfig::new(
+            RoutingMode::PrefillDecode {
+                prefill_urls: vec![],
+                decode_urls: vec![],
+                prefill_policy: None,
+                decode_policy: None,
+            },
+            PolicyConfig::Random,
+        );
 
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        assert!(err.to_string().contains("whitespace"));
-    }
+        // Should pass validation even with empty URLs
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-    /// Same asymmetry as the padded-tenant_id case, but for `key`: the CLI
-    /// trims it, config-file/binding entries don't, so an untrimmed key
-    /// would hash differently than intended and silently evade the
-    /// duplicate-value check.
-    #[test]
-    fn test_validate_padded_key_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: "team-a".to_string(),
-            key: " some-secret ".to_string(),
-        }];
+        // Test that empty URLs are allowed in Regular mode
+        let config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec![],
+            },
+            PolicyConfig::Random,
+        );
 
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        let message = err.to_string();
-        assert!(message.contains("team-a"));
-        assert!(message.contains("whitespace"));
-        assert!(
-            !message.contains("some-secret"),
-            "error must not leak the credential value: {message}"
+        // Should pass validation even with empty URLs
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        // Test that empty URLs are allowed in OpenAI mode
+        let config = RouterConfig::new(
+            RoutingMode::OpenAI {
+                worker_urls: vec![],
+            },
+            PolicyConfig::Random,
         );
+
+        // Should pass validation even with empty URLs
+        assert!(ConfigValidator::validate(&config).is_ok());
     }
 
     #[test]
-    fn test_validate_empty_key_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: "team-a".to_string(),
-            key: String::new(),
-        }];
+    fn test_validate_grpc_with_model_path() {
+        let mut config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec!["grpc://worker:50051".to_string()],
+            },
+            PolicyConfig::Random,
+        );
 
-        // An empty key would make `Authorization: Bearer ` (empty token) a
-        // valid credential for this tenant.
-        let err = ConfigValidator::validate(&config).unwrap_err();
-        assert!(matches!(err, ConfigError::ValidationFailed { .. }));
-        let message = err.to_string();
-        assert!(message.contains("team-a"));
-        assert!(message.contains("key"));
+        config.connection_mode = ConnectionMode::Grpc;
+        config.model_path = Some("meta-llama/Llama-3-8B".to_string());
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
     }
 
     #[test]
-    fn test_validate_whitespace_only_key_rejected() {
-        let mut config = regular_mode_config();
-        config.tenant_api_keys = vec![TenantApiKeyEntry {
-            tenant_id: "team-a".to_string(),
-            key: "   ".to_string(),
-        }];
+    fn test_validate_grpcs_worker_url() {
+        let mut config = RouterConfig::new(
+            RoutingMode::Regular {
+                worker_urls: vec!["grpcs://worker:50051".to_string()],
+            },
+            PolicyConfig::Random,
+        );
 
-        assert!(ConfigValidator::validate(&config).is_err());
+        config.connection_mode = ConnectionMode::Grpc;
+        config.model_path = Some("meta-llama/Llama-3-8B".to_string());
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
     }
 
     #[test]
-    fn test_validate_empty_worker_urls() {
-        let config = RouterConfig::new(
+    fn test_validate_grpc_with_tokenizer_path() {
+        let mut config = RouterConfig::new(
             RoutingMode::Regular {
-                worker_urls: vec![],
+                worker_urls: vec!["grpc://worker:50051".to_string()],
             },
             PolicyConfig::Random,
         );
 
-        // Empty worker URLs are now allowed to match legacy behavior
-        assert!(ConfigValidator::validate(&config).is_ok());
+        config.connection_mode = ConnectionMode::Grpc;
+        config.tokenizer_path = Some("/path/to/tokenizer.json".to_string());
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_ok());
     }
 
     #[test]
-    fn test_validate_empty_worker_urls_with_service_discovery() {
+    fn test_reject_duplicate_storage_context_keys() {
         let mut config = RouterConfig::new(
             RoutingMode::Regular {
-                worker_urls: vec![],
+                worker_urls: vec!["http://worker1:8000".to_string()],
             },
             PolicyConfig::Random,
         );
 
-        // Enable service discovery
-        config.discovery = Some(DiscoveryConfig {
-            enabled: true,
-            selector: vec![("app".to_string(), "test".to_string())]
-                .into_iter()
-                .collect(),
-            ..Default::default()
-        });
+        config.storage_context_headers = std::collections::HashMap::from([
+            ("x-tenant-id".to_string(), "tenant_id".to_string()),
+            ("x-org-id".to_string(), "tenant_id".to_string()),
+        ]);
 
-        // Should pass validation since service discovery is enabled
-        assert!(ConfigValidator::validate(&config).is_ok());
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_validate_invalid_urls() {
-        let config = RouterConfig::new(
+    fn test_reject_empty_storage_context_key() {
+        let mut config = RouterConfig::new(
             RoutingMode::Regular {
-                worker_urls: vec!["invalid-url".to_string()],
+                worker_urls: vec!["http://worker1:8000".to_string()],
             },
             PolicyConfig::Random,
         );
 
-        assert!(ConfigValidator::validate(&config).is_err());
+        config.storage_context_headers =
+            std::collections::HashMap::from([("x-tenant-id".to_string(), " ".to_string())]);
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_validate_cache_aware_thresholds() {
-        let config = RouterConfig::new(
+    fn test_reject_empty_storage_context_header_name() {
+        let mut config = RouterConfig::new(
             RoutingMode::Regular {
-                worker_urls: vec![
-                    "http://worker1:8000".to_string(),
-                    "http://worker2:8000".to_string(),
-                ],
-            },
-            PolicyConfig::CacheAware {
-                cache_threshold: 1.5, // Invalid: > 1.0
-                balance_abs_threshold: 32,
-                balance_rel_threshold: 1.1,
-                eviction_interval_secs: 60,
-                max_tree_size: 1000,
-                block_size: 16,
-                balance_token_usage_threshold: 1.0,
-                overload_token_usage_threshold: 1.0,
+                worker_urls: vec!["http://worker1:8000".to_string()],
             },
+            PolicyConfig::Random,
         );
 
-        assert!(ConfigValidator::validate(&config).is_err());
+        config.storage_context_headers =
+            std::collections::HashMap::from([(" ".to_string(), "tenant_id".to_string())]);
+
+        let result = ConfigValidator::validate(&config);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_validate_cache_aware_single_worker() {
-        // Cache-aware with single worker should be allowed (even if not optimal)
-        let config = RouterConfig::new(
+    fn test_reject_health_check_port_zero() {
+        let mut config = RouterConfig::new(
             RoutingMode::Regular {
                 worker_urls: vec!["http://worker1:8000".to_string()],
             },
-            PolicyConfig::CacheAware {
-                cache_threshold: 0.5,
-                balance_abs_threshold: 32,
-                balance_rel_threshold: 1.1,
-                eviction_interval_secs: 60,
-                max_tree_size: 1000,
-                block_size: 16,
-                balance_token_usage_threshold: 1.0,
-                overload_token_usage_threshold: 1.0,
-            },
+            PolicyConfig::Random,
         );
 
+        // 0 = OS-ephemeral; breaks the stable-probe-port contract.
+        config.health_check_port = Some(0);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "health_check_port"
+        ));
+
+        // A real port and the unset (None) default both validate.
+        config.health_check_port = Some(8081);
+        assert!(ConfigValidator::validate(&config).is_ok());
+        config.health_check_port = None;
         assert!(ConfigValidator::validate(&config).is_ok());
     }
 
     #[test]
-    fn test_validate_pd_mode() {
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![("http://prefill:8000".to_string(), Some(8081))],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_fault_injection_rules() {
+        let mut config = regular_mode_config();
+        config.fault_injection.enabled = true;
+        config.fault_injection.rules = vec![FaultRule {
+            worker_url: None,
+            route: Some("/v1/chat/completions".to_string()),
+            probability: 0.5,
+            fault: FaultKind::Status { status: 200 },
+        }];
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "fault_injection.rules[0].status"
+        ));
+
+        config.fault_injection.rules[0].fault = FaultKind::Status { status: 503 };
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.fault_injection.rules[0].probability = 2.0;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_fault_injection_requires_http_regular_router() {
+        let mut config = regular_mode_config();
+        config.fault_injection.enabled = true;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.connection_mode = ConnectionMode::Grpc;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
+
+        config.connection_mode = ConnectionMode::Http;
+        config.enable_igw = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
+
+        config.enable_igw = false;
+        config.mode = RoutingMode::PrefillDecode {
+            prefill_urls: vec![("http://prefill:8000".to_string(), None)],
+            decode_urls: vec!["http://decode:8000".to_string()],
+            prefill_policy: None,
+            decode_policy: None,
+        };
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_debug_capture_sample_rate() {
+        let mut config = regular_mode_config();
+        config.debug_capture.enabled = true;
+        config.debug_capture.sample_rate = 1.5;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "debug_capture.sample_rate"
+        ));
+
+        config.debug_capture.sample_rate = 0.25;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        // Disabled capture is not validated.
+        config.debug_capture.enabled = false;
+        config.debug_capture.max_entries = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_debug_capture_requires_memory_backend() {
+        let mut config = regular_mode_config();
+        config.debug_capture.enabled = true;
+        config.history_backend = HistoryBackend::Redis;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
+
+        config.history_backend = HistoryBackend::Memory;
+        assert!(ConfigValidator::validate(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_file_store() {
+        let mut config = regular_mode_config();
+        config.file_store.enabled = true;
+        config.file_store.public_base_url = Some("gateway.local".to_string());
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "file_store.public_base_url"
+        ));
+
+        config.file_store.public_base_url = Some("https://gateway.example.com".to_string());
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.file_store.max_files = 0;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_vector_store() {
+        let mut config = regular_mode_config();
+        config.vector_store.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
 
+        config.file_store.enabled = true;
         assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.vector_store.embedding_batch_size = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "vector_store.embedding_batch_size"
+        ));
+
+        config.vector_store.embedding_batch_size = 64;
+        config.vector_store.rerank_top_k = config.vector_store.retrieval_top_k + 1;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "vector_store.rerank_top_k"
+        ));
+    }
+
+    #[test]
+    fn test_validate_async_generation() {
+        let mut config = regular_mode_config();
+        config.async_generation.enabled = true;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.async_generation.concurrency = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "async_generation.concurrency"
+        ));
+    }
+
+    #[test]
+    fn test_validate_grpc_pipeline() {
+        let stage = |name: &str, kind| CustomStageConfig {
+            name: name.to_string(),
+            after: PipelineStageAnchor::Preparation,
+            endpoints: vec![],
+            kind,
+        };
+        let mut config = regular_mode_config();
+        config.grpc_pipeline.stages = vec![
+            stage(
+                "limit",
+                CustomStageKind::MaxPromptTokens { max_tokens: 8192 },
+            ),
+            stage(
+                "enrich",
+                CustomStageKind::Wasm {
+                    module: "enricher".to_string(),
+                },
+            ),
+        ];
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::IncompatibleConfig { .. })
+        ));
+
+        config.enable_wasm = true;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.grpc_pipeline.stages[1].name = "limit".to_string();
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "grpc_pipeline.stages[1].name"
+        ));
     }
 
     #[test]
-    fn test_validate_roundrobin_with_pd_mode() {
-        // RoundRobin with PD mode is now supported
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                prefill_policy: None,
-                decode_policy: None,
+    fn test_validate_sampling_limits() {
+        let mut config = regular_mode_config();
+        config.sampling_limits.models.insert(
+            "small".to_string(),
+            ModelSamplingLimits {
+                temperature: ParamLimits {
+                    default: Some(0.7),
+                    min: Some(0.0),
+                    max: Some(1.0),
+                },
+                ..Default::default()
             },
-            PolicyConfig::RoundRobin,
         );
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+        let limits = config.sampling_limits.models.get_mut("small").unwrap();
+        limits.temperature.default = Some(1.5);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "sampling_limits.models.small.temperature.default"
+        ));
+
+        let limits = config.sampling_limits.models.get_mut("small").unwrap();
+        limits.temperature.default = None;
+        limits.max_tokens = ParamLimits {
+            default: None,
+            min: Some(512),
+            max: Some(128),
+        };
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "sampling_limits.models.small.max_tokens.min"
+        ));
     }
 
     #[test]
-    fn test_validate_cache_aware_with_pd_mode() {
-        // CacheAware with PD mode is now supported
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::CacheAware {
-                cache_threshold: 0.5,
-                balance_abs_threshold: 32,
-                balance_rel_threshold: 1.1,
-                eviction_interval_secs: 60,
-                max_tree_size: 1000,
-                block_size: 16,
-                balance_token_usage_threshold: 1.0,
-                overload_token_usage_threshold: 1.0,
-            },
-        );
+    fn test_validate_request_tags() {
+        let mut config = regular_mode_config();
+        config
+            .request_tags
+            .tags
+            .insert("team".to_string(), vec!["search".to_string()]);
+        config
+            .request_tags
+            .tags
+            .insert("feature".to_string(), vec![]);
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
-    }
+        config
+            .request_tags
+            .tags
+            .insert("Team-Name".to_string(), vec![]);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, ref value, .. })
+                if field == "request_tags.tags" && value == "Team-Name"
+        ));
 
-    #[test]
-    fn test_validate_power_of_two_with_regular_mode() {
-        // PowerOfTwo with Regular mode is now supported
-        let config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec![
-                    "http://worker1:8000".to_string(),
-                    "http://worker2:8000".to_string(),
-                ],
-            },
-            PolicyConfig::PowerOfTwo {
-                load_check_interval_secs: 60,
-            },
-        );
+        config.request_tags.tags.remove("Team-Name");
+        config
+            .request_tags
+            .tags
+            .insert("env".to_string(), vec!["prod west".to_string()]);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "request_tags.tags.env"
+        ));
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+        config.request_tags.tags.remove("env");
+        config.request_tags.max_values_per_tag = 0;
+        assert!(ConfigValidator::validate(&config).is_err());
     }
 
     #[test]
-    fn test_validate_pd_mode_with_separate_policies() {
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![
-                    ("http://prefill1:8000".to_string(), None),
-                    ("http://prefill2:8000".to_string(), None),
-                ],
-                decode_urls: vec![
-                    "http://decode1:8000".to_string(),
-                    "http://decode2:8000".to_string(),
-                ],
-                prefill_policy: Some(PolicyConfig::CacheAware {
-                    cache_threshold: 0.5,
-                    balance_abs_threshold: 32,
-                    balance_rel_threshold: 1.1,
-                    eviction_interval_secs: 60,
-                    max_tree_size: 1000,
-                    block_size: 16,
-                    balance_token_usage_threshold: 1.0,
-                    overload_token_usage_threshold: 1.0,
-                }),
-                decode_policy: Some(PolicyConfig::PowerOfTwo {
-                    load_check_interval_secs: 60,
-                }),
-            },
-            PolicyConfig::Random, // Main policy as fallback
+    fn test_validate_request_features() {
+        let mut config = regular_mode_config();
+        config.request_features.default = vec!["recovery".to_string()];
+        config.request_features.tenants.insert(
+            "search".to_string(),
+            vec!["coalesce-sse".to_string(), "recovery".to_string()],
         );
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+        config
+            .request_features
+            .default
+            .push("Coalesce_SSE".to_string());
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, ref value, .. })
+                if field == "request_features" && value == "Coalesce_SSE"
+        ));
+
+        config.request_features.default = (0..=RequestFeaturesConfig::MAX_FEATURES)
+            .map(|i| format!("f{i}"))
+            .collect();
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        config.request_features.default.clear();
+        config
+            .request_features
+            .tenants
+            .insert(" ".to_string(), vec!["recovery".to_string()]);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "request_features.tenants"
+        ));
     }
 
     #[test]
-    fn test_validate_pd_mode_power_of_two_insufficient_workers() {
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![("http://prefill1:8000".to_string(), None)], // Only 1 prefill
-                decode_urls: vec![
-                    "http://decode1:8000".to_string(),
-                    "http://decode2:8000".to_string(),
-                ],
-                prefill_policy: Some(PolicyConfig::PowerOfTwo {
-                    load_check_interval_secs: 60,
-                }), // Requires 2+ workers
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_maintenance() {
+        let mut config = regular_mode_config();
+        config.maintenance.windows.push(MaintenanceWindowConfig {
+            name: "nightly".to_string(),
+            worker_url: None,
+            model: Some("llama".to_string()),
+            schedule: "0 2 * * *".to_string(),
+            duration_secs: 1800,
+        });
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(e.to_string().contains("prefill requires at least 2"));
-        }
+        config.maintenance.windows[0].schedule = "0 25 * * *".to_string();
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "maintenance.windows[0].schedule"
+        ));
+
+        config.maintenance.windows[0].schedule = "@daily".to_string();
+        config.maintenance.windows[0].worker_url = Some("http://w:8000".to_string());
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "maintenance.windows[0]"
+        ));
+
+        config.maintenance.windows[0].worker_url = None;
+        config.maintenance.windows[0].duration_secs = 0;
+        assert!(ConfigValidator::validate(&config).is_err());
     }
 
     #[test]
-    fn test_validate_pd_mode_bucket_policy_restrictions() {
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![
-                    ("http://prefill1:8000".to_string(), None),
-                    ("http://prefill2:8000".to_string(), None),
-                ],
-                decode_urls: vec![
-                    "http://decode1:8000".to_string(),
-                    "http://decode2:8000".to_string(),
-                ],
-                prefill_policy: Some(PolicyConfig::Bucket {
-                    balance_abs_threshold: 32,
-                    balance_rel_threshold: 1.1,
-                    bucket_adjust_interval_secs: 5,
-                }),
-                decode_policy: Some(PolicyConfig::PowerOfTwo {
-                    load_check_interval_secs: 60,
-                }),
-            },
-            PolicyConfig::Random, // Main policy as fallback
-        );
+    fn test_validate_experiments() {
+        let mut config = regular_mode_config();
+        config.experiments.experiments.push(ExperimentConfig {
+            name: "prompt-v2".to_string(),
+            model: "llama".to_string(),
+            sticky_key: ExperimentStickyKey::Header("x-session-id".to_string()),
+            arms: vec![
+                ExperimentArmConfig {
+                    name: "control".to_string(),
+                    percent: 50.0,
+                    model: None,
+                    system_prompt: None,
+                },
+                ExperimentArmConfig {
+                    name: "treatment".to_string(),
+                    percent: 50.0,
+                    model: Some("llama-ft".to_string()),
+                    system_prompt: Some("Be concise.".to_string()),
+                },
+            ],
+        });
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(
-            result.is_ok(),
-            "Prefill policy should be allowed to be bucket"
-        );
+        config.experiments.experiments[0].arms[1].percent = 60.0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "experiments.experiments[0].arms"
+        ));
 
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![
-                    ("http://prefill1:8000".to_string(), None),
-                    ("http://prefill2:8000".to_string(), None),
-                ],
-                decode_urls: vec![
-                    "http://decode1:8000".to_string(),
-                    "http://decode2:8000".to_string(),
-                ],
-                prefill_policy: Some(PolicyConfig::Bucket {
-                    balance_abs_threshold: 32,
-                    balance_rel_threshold: 1.1,
-                    bucket_adjust_interval_secs: 5,
-                }),
-                decode_policy: Some(PolicyConfig::Bucket {
-                    balance_abs_threshold: 32,
-                    balance_rel_threshold: 1.1,
-                    bucket_adjust_interval_secs: 5,
-                }),
-            },
-            PolicyConfig::Random, // Main policy as fallback
-        );
+        config.experiments.experiments[0].arms[1].percent = 50.0;
+        config.experiments.experiments[0].arms[1].name = "control".to_string();
+        assert!(ConfigValidator::validate(&config).is_err());
 
-        let result = ConfigValidator::validate(&config);
-        assert!(
-            result.is_err(),
-            "Decode policy should not be allowed to be bucket"
-        );
+        config.experiments.experiments[0].arms[1].name = "treatment".to_string();
+        config.experiments.experiments[0].sticky_key =
+            ExperimentStickyKey::Header("bad header".to_string());
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "experiments.experiments[0].sticky_key"
+        ));
+
+        config.experiments.experiments[0].sticky_key = ExperimentStickyKey::Tenant;
+        let mut duplicate = config.experiments.experiments[0].clone();
+        duplicate.name = "other".to_string();
+        config.experiments.experiments.push(duplicate);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "experiments.experiments[1].model"
+        ));
     }
 
     #[test]
-    fn test_validate_epd_mode_encode_policy_restrictions() {
-        let valid = RouterConfig::new(
-            RoutingMode::EncodePrefillDecode {
-                encode_urls: vec![("http://encode:8000".to_string(), None)],
-                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                encode_policy: Some(PolicyConfig::ConsistentHashing),
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
-        assert!(ConfigValidator::validate(&valid).is_ok());
+    fn test_validate_prompt_guard() {
+        let mut config = regular_mode_config();
+        config.prompt_guard.enabled = true;
+        config.prompt_guard.rules.push(PromptGuardRuleConfig {
+            name: "internal-codename".to_string(),
+            pattern: r"project\s+nightjar".to_string(),
+            score: 0.9,
+        });
+        config.prompt_guard.classifier = Some(PromptGuardClassifierConfig {
+            endpoint: "http://guard:8080/score".to_string(),
+            timeout_ms: 200,
+        });
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        let invalid_cache_aware = RouterConfig::new(
-            RoutingMode::EncodePrefillDecode {
-                encode_urls: vec![("http://encode:8000".to_string(), None)],
-                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                encode_policy: Some(PolicyConfig::CacheAware {
-                    cache_threshold: 0.5,
-                    balance_abs_threshold: 32,
-                    balance_rel_threshold: 1.1,
-                    eviction_interval_secs: 60,
-                    max_tree_size: 1000,
-                    block_size: 16,
-                    balance_token_usage_threshold: 1.0,
-                    overload_token_usage_threshold: 1.0,
-                }),
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
-        assert!(ConfigValidator::validate(&invalid_cache_aware).is_err());
+        config.prompt_guard.rules[0].pattern = "(unclosed".to_string();
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "prompt_guard.rules[0].pattern"
+        ));
+
+        config.prompt_guard.rules[0].pattern = "nightjar".to_string();
+        config.prompt_guard.rules[0].score = 0.0;
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        config.prompt_guard.rules[0].score = 0.9;
+        config.prompt_guard.threshold = 1.5;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "prompt_guard.threshold"
+        ));
 
-        let invalid_least_load = RouterConfig::new(
-            RoutingMode::EncodePrefillDecode {
-                encode_urls: vec![("http://encode:8000".to_string(), None)],
-                prefill_urls: vec![("http://prefill:8000".to_string(), None)],
-                decode_urls: vec!["http://decode:8000".to_string()],
-                encode_policy: Some(PolicyConfig::LeastLoad {
-                    load_check_interval_secs: 5,
-                    kv_pressure_weight: 0.15,
-                    mean_prefill_tokens: 1024,
-                    default_throughput: 2000.0,
-                }),
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
-        assert!(ConfigValidator::validate(&invalid_least_load).is_err());
+        config.prompt_guard.threshold = 0.7;
+        config.prompt_guard.classifier = Some(PromptGuardClassifierConfig {
+            endpoint: "guard:8080".to_string(),
+            timeout_ms: 200,
+        });
+        assert!(ConfigValidator::validate(&config).is_err());
     }
 
     #[test]
-    fn test_validate_empty_urls_allowed_without_service_discovery() {
-        // Test that empty URLs are now allowed in PD mode
-        let config = RouterConfig::new(
-            RoutingMode::PrefillDecode {
-                prefill_urls: vec![],
-                decode_urls: vec![],
-                prefill_policy: None,
-                decode_policy: None,
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_provenance() {
+        let mut config = regular_mode_config();
+        config.provenance.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::MissingRequired { ref field }) if field == "provenance.signing_key"
+        ));
 
-        // Should pass validation even with empty URLs
+        config.provenance.signing_key = Some("provenance-secret".to_string());
+        config.provenance.key_id = Some("2026-10".to_string());
         assert!(ConfigValidator::validate(&config).is_ok());
 
-        // Test that empty URLs are allowed in Regular mode
-        let config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec![],
+        config.provenance.key_id = Some("bad\nid".to_string());
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_routing_rules() {
+        let mut config = regular_mode_config();
+        config.routing_rules.rules.push(RoutingRuleConfig {
+            name: "long".to_string(),
+            conditions: RoutingRuleMatch {
+                body: vec![BodyPredicateConfig {
+                    field: "max_tokens".to_string(),
+                    op: BodyPredicateOp::Gt,
+                    value: Some(serde_json::json!(4096)),
+                }],
+                ..Default::default()
             },
-            PolicyConfig::Random,
-        );
+            action: RoutingRuleAction {
+                model: Some("big-model".to_string()),
+                ..Default::default()
+            },
+        });
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        // Should pass validation even with empty URLs
+        config.routing_rules.rules[0].action.model = None;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "routing_rules.rules[0]"
+        ));
+    }
+
+    #[test]
+    fn test_validate_federation() {
+        let mut config = regular_mode_config();
+        config.federation.require_signed = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::MissingRequired { ref field }) if field == "federation.signing_secret"
+        ));
+
+        config.federation.signing_secret = Some("federation-secret".to_string());
         assert!(ConfigValidator::validate(&config).is_ok());
 
-        // Test that empty URLs are allowed in OpenAI mode
-        let config = RouterConfig::new(
-            RoutingMode::OpenAI {
-                worker_urls: vec![],
-            },
-            PolicyConfig::Random,
-        );
+        config.federation.catalog_refresh_interval_secs = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "federation.catalog_refresh_interval_secs"
+        ));
+    }
 
-        // Should pass validation even with empty URLs
+    #[test]
+    fn test_validate_standby() {
+        let mut config = regular_mode_config();
+        config
+            .standby
+            .model_min_active_workers
+            .insert("llama-70b".to_string(), 2);
         assert!(ConfigValidator::validate(&config).is_ok());
+
+        config
+            .standby
+            .model_min_active_workers
+            .insert("llama-70b".to_string(), 0);
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "standby.model_min_active_workers.llama-70b"
+        ));
+
+        config.standby = StandbyConfig {
+            check_interval_secs: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "standby.check_interval_secs"
+        ));
     }
 
     #[test]
-    fn test_validate_grpc_with_model_path() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["grpc://worker:50051".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_pd_bootstrap() {
+        let mut config = regular_mode_config();
+        config.pd_bootstrap.stuck_threshold_secs = 300;
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.connection_mode = ConnectionMode::Grpc;
-        config.model_path = Some("meta-llama/Llama-3-8B".to_string());
+        config.pd_bootstrap.stuck_threshold_secs = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "pd_bootstrap.stuck_threshold_secs"
+        ));
+    }
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+    #[test]
+    fn test_validate_pd_pairs() {
+        let mut config = regular_mode_config();
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.pd_pairs.slow_factor = 1.0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "pd_pairs.slow_factor"
+        ));
+
+        config.pd_pairs = PdPairsConfig {
+            max_error_rate: 1.5,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "pd_pairs.max_error_rate"
+        ));
+
+        config.pd_pairs = PdPairsConfig {
+            min_samples: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "pd_pairs.min_samples"
+        ));
     }
 
     #[test]
-    fn test_validate_grpcs_worker_url() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["grpcs://worker:50051".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_sessions() {
+        let mut config = regular_mode_config();
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.connection_mode = ConnectionMode::Grpc;
-        config.model_path = Some("meta-llama/Llama-3-8B".to_string());
+        config.sessions.max_sessions = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. }) if field == "sessions.max_sessions"
+        ));
+    }
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+    #[test]
+    fn test_validate_partial_transcripts() {
+        let mut config = regular_mode_config();
+        config.partial_transcripts.max_output_bytes = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.partial_transcripts.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "partial_transcripts.max_output_bytes"
+        ));
     }
 
     #[test]
-    fn test_validate_grpc_with_tokenizer_path() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["grpc://worker:50051".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_tenant_mcp_servers() {
+        let mut config = regular_mode_config();
+        config.tenant_mcp_servers.max_servers_per_tenant = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.connection_mode = ConnectionMode::Grpc;
-        config.tokenizer_path = Some("/path/to/tokenizer.json".to_string());
+        config.tenant_mcp_servers.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "tenant_mcp_servers.max_servers_per_tenant"
+        ));
+    }
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_ok());
+    #[test]
+    fn test_validate_prompts() {
+        let mut config = regular_mode_config();
+        config.prompts.max_versions_per_prompt = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.prompts.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "prompts.max_versions_per_prompt"
+        ));
     }
 
     #[test]
-    fn test_reject_duplicate_storage_context_keys() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["http://worker1:8000".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_latency_budget() {
+        let mut config = regular_mode_config();
+        config.latency_budget.default_tokens_per_second = 0.0;
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.storage_context_headers = std::collections::HashMap::from([
-            ("x-tenant-id".to_string(), "tenant_id".to_string()),
-            ("x-org-id".to_string(), "tenant_id".to_string()),
-        ]);
+        config.latency_budget.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "latency_budget.default_tokens_per_second"
+        ));
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_err());
+        config.latency_budget.default_tokens_per_second = 25.0;
+        config.latency_budget.min_max_tokens = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "latency_budget.min_max_tokens"
+        ));
     }
 
     #[test]
-    fn test_reject_empty_storage_context_key() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["http://worker1:8000".to_string()],
-            },
-            PolicyConfig::Random,
+    fn test_validate_webhooks() {
+        let mut config = regular_mode_config();
+        config.webhooks.enabled = true;
+        config
+            .webhooks
+            .tenant_urls
+            .insert("auth:team-red".to_string(), "ftp://hooks".to_string());
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "webhooks.tenant_urls.auth:team-red"
+        ));
+
+        config.webhooks.tenant_urls.insert(
+            "auth:team-red".to_string(),
+            "https://hooks.example.com".to_string(),
         );
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.storage_context_headers =
-            std::collections::HashMap::from([("x-tenant-id".to_string(), " ".to_string())]);
+        config.webhooks.max_attempts = 0;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_err());
+    #[test]
+    fn test_validate_request_coalescing() {
+        let mut config = regular_mode_config();
+        config.request_coalescing.max_request_bytes = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
+
+        config.request_coalescing.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "request_coalescing.max_request_bytes"
+        ));
     }
 
     #[test]
-    fn test_reject_empty_storage_context_header_name() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["http://worker1:8000".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_conversation_compaction() {
+        let mut config = regular_mode_config();
+        config.conversation_compaction.token_threshold = 0;
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        config.storage_context_headers =
-            std::collections::HashMap::from([(" ".to_string(), "tenant_id".to_string())]);
+        config.conversation_compaction.enabled = true;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "conversation_compaction.token_threshold"
+        ));
 
-        let result = ConfigValidator::validate(&config);
-        assert!(result.is_err());
+        config.conversation_compaction.token_threshold = 1000;
+        config.conversation_compaction.model = Some(" ".to_string());
+        assert!(ConfigValidator::validate(&config).is_err());
     }
 
     #[test]
-    fn test_reject_health_check_port_zero() {
-        let mut config = RouterConfig::new(
-            RoutingMode::Regular {
-                worker_urls: vec!["http://worker1:8000".to_string()],
-            },
-            PolicyConfig::Random,
-        );
+    fn test_validate_stream_fanout() {
+        let mut config = regular_mode_config();
+        config.stream_fanout.enabled = true;
+        assert!(ConfigValidator::validate(&config).is_ok());
 
-        // 0 = OS-ephemeral; breaks the stable-probe-port contract.
-        config.health_check_port = Some(0);
+        config.stream_fanout.max_subscribers = 0;
         assert!(matches!(
             ConfigValidator::validate(&config),
-            Err(ConfigError::InvalidValue { ref field, .. }) if field == "health_check_port"
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "stream_fanout.max_subscribers"
         ));
+    }
 
-        // A real port and the unset (None) default both validate.
-        config.health_check_port = Some(8081);
+    #[test]
+    fn test_validate_chat_completion_store() {
+        let mut config = regular_mode_config();
+        config.chat_completion_store.enabled = true;
         assert!(ConfigValidator::validate(&config).is_ok());
-        config.health_check_port = None;
+
+        config.chat_completion_store.max_entries = 0;
+        assert!(matches!(
+            ConfigValidator::validate(&config),
+            Err(ConfigError::InvalidValue { ref field, .. })
+                if field == "chat_completion_store.max_entries"
+        ));
+
+        config.chat_completion_store.enabled = false;
         assert!(ConfigValidator::validate(&config).is_ok());
     }
 }
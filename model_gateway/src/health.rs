@@ -129,6 +129,12 @@ impl ProbeState {
         self.readiness.load_full()
     }
 
+    /// Whether `/readiness` would currently report ready.
+    pub fn is_ready(&self) -> bool {
+        let snapshot = self.readiness.load();
+        !self.inflight_tracker.is_draining() && snapshot.workers_ready && snapshot.tokenizers_ready
+    }
+
     /// Re-derive the readiness snapshot from live state.
     ///
     /// This is the exact computation the `/readiness` handler used to run
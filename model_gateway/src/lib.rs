@@ -10,6 +10,8 @@ pub mod routers;
 pub mod server;
 pub mod service_discovery;
 pub mod tenant;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod version;
 pub mod wasm;
 pub mod worker;
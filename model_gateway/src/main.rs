@@ -3,13 +3,23 @@ use std::collections::HashMap;
 use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use openai_protocol::worker::TransportMode;
 use rand::{distr::Alphanumeric, RngExt};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use smg::{
     config::{
-        validate_mesh_server_name, CircuitBreakerConfig, ConfigError, ConfigResult,
-        DiscoveryConfig, HealthCheckConfig, HistoryBackend, ManualAssignmentMode, MetricsConfig,
-        OracleConfig, PolicyConfig, PostgresConfig, RedisConfig, RetryConfig, RouterConfig,
-        RoutingKeyOverrideConfig, RoutingMode, SchemaConfig, TenantApiKeyEntry,
-        TokenizerCacheConfig, TraceConfig,
+        self, validate_mesh_server_name, AsyncGenerationConfig, ChatCompletionStoreConfig,
+        CircuitBreakerConfig, ConfigError, ConfigResult, ConversationCompactionConfig,
+        DebugCaptureConfig, DiscoveryConfig, ExperimentsConfig, FaultInjectionConfig,
+        FederationConfig, FileStoreConfig, GrpcPipelineConfig, HealthCheckConfig, HistoryBackend,
+        LatencyBudgetConfig, MaintenanceConfig, ManualAssignmentMode, MapReduceConfig,
+        MetadataCacheConfig, MetricsConfig, OracleConfig, PartialTranscriptsConfig,
+        PdBootstrapConfig, PdPairsConfig, PolicyConfig, PostgresConfig, PromptGuardConfig,
+        PromptsConfig, ProvenanceConfig, RedisConfig, RequestCoalescingConfig,
+        RequestFeaturesConfig, RequestTagsConfig, RetryConfig, RouterConfig,
+        RoutingKeyOverrideConfig, RoutingMode, RoutingRulesConfig, SamplingLimitsConfig,
+        SchemaConfig, SessionsConfig, StandbyConfig, StreamFanoutConfig, StreamIntegrityConfig,
+        StreamRecoveryConfig, TenantApiKeyEntry, TenantMcpServersConfig, TokenizerCacheConfig,
+        TraceConfig, VectorStoreConfig, WebhookConfig,
     },
     observability::{
         metrics::PrometheusConfig,
@@ -108,6 +118,9 @@ Usage:
   smg launch [OPTIONS]             Launch gateway (short command)
   amg launch [OPTIONS]             Launch gateway (alternative)
   shepherd-model-gateway launch [OPTIONS] Launch gateway (full name)
+  smg validate-config --new b.yaml [--old a.yaml] [--strict]
+                                   Validate a config and diff effective behavior
+  smg --print-config-schema        Print the JSON Schema of config files
 
 Examples:
   # Regular mode
@@ -146,6 +159,44 @@ enum Commands {
         #[command(flatten)]
         args: CliArgs,
     },
+    /// Validate a config file and print how its effective behavior differs
+    /// from `--old` (or from the built-in defaults)
+    ValidateConfig {
+        /// Currently deployed config (YAML/JSON); defaults are used when omitted
+        #[arg(long)]
+        old: Option<String>,
+        /// Candidate config to validate (YAML/JSON)
+        #[arg(long)]
+        new: String,
+        /// Reject fields that no config option declares, such as misspelled
+        /// keys, instead of ignoring them
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+    },
+}
+
+/// `smg validate-config`: print the validation + diff report and fail the
+/// process when the candidate config has errors.
+#[expect(clippy::print_stdout, reason = "CLI report output")]
+fn run_validate_config(
+    old: Option<&str>,
+    new: &str,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let old_config = match old {
+        Some(path) => config::diff::load_config_file(path, strict)?,
+        None => RouterConfig::default(),
+    };
+    let new_config = config::diff::load_config_file(new, strict)?;
+    let report = config::diff::validate_transition(&old_config, &new_config)?;
+
+    println!("Comparing {} -> {new}", old.unwrap_or("<defaults>"));
+    print!("{report}");
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(format!("{new} failed validation").into())
+    }
 }
 
 /// Parse the `--multimodal-tensor-transport` value into a `TransportMode`.
@@ -213,6 +264,23 @@ struct CliArgs {
     #[arg(long, default_value_t = 1.0, help_heading = "Routing Policy")]
     overload_token_usage_threshold: f32,
 
+    /// History window in seconds for cache-aware approximate trees. Older
+    /// routing history is dropped so stale prefixes stop attracting traffic.
+    /// 0 keeps history until size-based eviction.
+    #[arg(long, default_value_t = 0, help_heading = "Routing Policy")]
+    tree_window_secs: u64,
+
+    /// Half-life in seconds for cache-aware matches found in older tree
+    /// generations. 0 disables decay.
+    #[arg(long, default_value_t = 0, help_heading = "Routing Policy")]
+    cache_decay_half_life_secs: u64,
+
+    /// Bounded-load slack for consistent_hashing: a worker above
+    /// (1 + epsilon) x average load spills keys to the next worker on the
+    /// ring. Unset routes every key to its ring owner.
+    #[arg(long, help_heading = "Routing Policy")]
+    consistent_hashing_load_epsilon: Option<f64>,
+
     /// Interval in seconds between cache eviction operations
     #[arg(long, default_value_t = 120, help_heading = "Routing Policy")]
     eviction_interval: u64,
@@ -241,6 +309,16 @@ struct CliArgs {
     #[arg(long, default_value_t = 1.25, help_heading = "Routing Policy")]
     prefix_hash_load_factor: f64,
 
+    /// Tokens per hash block for prefix_hash policy; set to the backend's KV
+    /// page size so requests sharing cached pages hash alike
+    #[arg(long, default_value_t = 1, help_heading = "Routing Policy")]
+    prefix_hash_block_size: usize,
+
+    /// Number of blocks hashed by prefix_hash policy; overrides
+    /// --prefix-token-count when set
+    #[arg(long, help_heading = "Routing Policy")]
+    prefix_hash_block_count: Option<usize>,
+
     /// KV-pressure weight (seconds) for the least_load policy
     #[arg(long, default_value_t = 0.15, help_heading = "Routing Policy")]
     least_load_kv_pressure_weight: f64,
@@ -813,6 +891,417 @@ struct CliArgs {
     /// quota on Rust 1.95+ and is therefore container-aware.
     #[arg(long, help_heading = "Runtime")]
     runtime_worker_threads: Option<usize>,
+
+    // ==================== Debug Capture ====================
+    /// Capture a sample of redacted request/response pairs in memory,
+    /// browsable via the `/debug/captures` admin endpoint (requires
+    /// `--history-backend memory`)
+    #[arg(long, default_value_t = false, help_heading = "Debug Capture")]
+    enable_debug_capture: bool,
+
+    /// Fraction of requests to capture (0.0-1.0)
+    #[arg(long, default_value_t = 0.01, help_heading = "Debug Capture")]
+    debug_capture_sample_rate: f64,
+
+    /// Maximum number of captures retained in memory
+    #[arg(long, default_value_t = 1000, help_heading = "Debug Capture")]
+    debug_capture_max_entries: usize,
+
+    /// Per-body byte cap for captured payloads
+    #[arg(long, default_value_t = 65536, help_heading = "Debug Capture")]
+    debug_capture_max_body_bytes: usize,
+
+    /// Store captured payloads without PII redaction
+    #[arg(long, default_value_t = false, help_heading = "Debug Capture")]
+    debug_capture_disable_redaction: bool,
+
+    // ==================== File Store ====================
+    /// Keep gateway-produced files (e.g. generated images) in memory and
+    /// serve them from `/v1/files/{file_id}/content`
+    #[arg(long, default_value_t = false, help_heading = "File Store")]
+    enable_file_store: bool,
+
+    /// Maximum number of files retained in memory
+    #[arg(long, default_value_t = 1000, help_heading = "File Store")]
+    file_store_max_files: usize,
+
+    /// Externally reachable gateway URL used to build file URLs
+    #[arg(long, help_heading = "File Store")]
+    file_store_public_url: Option<String>,
+
+    // ==================== Vector Store ====================
+    /// Ingest stored files into in-memory vector stores for `file_search`
+    /// (requires --enable-file-store)
+    #[arg(long, default_value_t = false, help_heading = "Vector Store")]
+    enable_vector_store: bool,
+
+    /// Embedding model used when an ingestion request names none
+    #[arg(long, help_heading = "Vector Store")]
+    vector_store_embedding_model: Option<String>,
+
+    /// Chunks sent per embeddings request during ingestion
+    #[arg(long, default_value_t = 64, help_heading = "Vector Store")]
+    vector_store_embedding_batch_size: usize,
+
+    /// Maximum chunks held per vector store
+    #[arg(long, default_value_t = 100_000, help_heading = "Vector Store")]
+    vector_store_max_chunks: usize,
+
+    /// Reranker model for the second stage of vector store search; skipped
+    /// while no worker serves it
+    #[arg(long, help_heading = "Vector Store")]
+    vector_store_rerank_model: Option<String>,
+
+    /// Candidates taken by similarity search before reranking
+    #[arg(long, default_value_t = 50, help_heading = "Vector Store")]
+    vector_store_retrieval_top_k: usize,
+
+    /// Results kept after reranking when a search sets no max_num_results
+    #[arg(long, default_value_t = 10, help_heading = "Vector Store")]
+    vector_store_rerank_top_k: usize,
+
+    // ==================== Chat Completion Store ====================
+    /// Keep chat completions created with `store: true` in memory and serve
+    /// them from `/v1/chat/completions`
+    #[arg(long, default_value_t = false, help_heading = "Chat Completion Store")]
+    enable_chat_completion_store: bool,
+
+    /// Maximum number of stored chat completions retained in memory
+    #[arg(long, default_value_t = 10_000, help_heading = "Chat Completion Store")]
+    chat_completion_store_max_entries: usize,
+
+    // ==================== Fault Injection ====================
+    /// Path to a YAML file of fault injection rules (latency, error status,
+    /// connection reset, truncated body). Passing this flag enables fault
+    /// injection in the HTTP regular router; intended for staging resilience
+    /// tests only
+    #[arg(long, help_heading = "Fault Injection")]
+    fault_injection_config: Option<String>,
+
+    // ==================== Metadata Cache ====================
+    /// Serve `/v1/models`, `/get_model_info`, `/get_server_info`, and worker
+    /// listings fresh on every call instead of from the short-TTL cache
+    #[arg(long, default_value_t = false, help_heading = "Metadata Cache")]
+    disable_metadata_cache: bool,
+
+    /// Seconds a cached metadata response is served before it is rebuilt
+    #[arg(long, default_value_t = 5, help_heading = "Metadata Cache")]
+    metadata_cache_ttl_secs: u64,
+
+    // ==================== Stream Recovery ====================
+    /// Models whose streaming chat/completion requests are resumed on another
+    /// worker when the backend drops mid-stream. Off for unlisted models
+    #[arg(long, num_args = 0.., help_heading = "Stream Recovery")]
+    stream_recovery_models: Vec<String>,
+
+    /// Maximum number of times one stream may be resumed
+    #[arg(long, default_value_t = 1, help_heading = "Stream Recovery")]
+    stream_recovery_max_resumes: u32,
+
+    // ==================== Stream Fan-out ====================
+    /// Let other clients of the same tenant attach to in-flight streams
+    /// read-only via `GET /v1/streams/{request_id}`
+    #[arg(long, default_value_t = false, help_heading = "Stream Fan-out")]
+    enable_stream_fanout: bool,
+
+    /// Chunks buffered per stream; subscribers that fall further behind are
+    /// disconnected
+    #[arg(long, default_value_t = 1024, help_heading = "Stream Fan-out")]
+    stream_fanout_buffer_chunks: usize,
+
+    /// Maximum concurrent subscribers per stream
+    #[arg(long, default_value_t = 4, help_heading = "Stream Fan-out")]
+    stream_fanout_max_subscribers: usize,
+
+    // ==================== Request Coalescing ====================
+    /// Execute identical concurrent `temperature: 0` chat and completion
+    /// requests once and send every client the same response
+    #[arg(long, default_value_t = false, help_heading = "Request Coalescing")]
+    enable_request_coalescing: bool,
+
+    /// Requests with larger bodies are never coalesced
+    #[arg(long, default_value_t = 1024 * 1024, help_heading = "Request Coalescing")]
+    request_coalescing_max_request_bytes: usize,
+
+    // ==================== Conversation Compaction ====================
+    /// Summarize the older part of stored conversations once they grow past
+    /// the token threshold
+    #[arg(
+        long,
+        default_value_t = false,
+        help_heading = "Conversation Compaction"
+    )]
+    enable_conversation_compaction: bool,
+
+    /// Estimated conversation size, in tokens, that triggers compaction
+    #[arg(
+        long,
+        default_value_t = 32_000,
+        help_heading = "Conversation Compaction"
+    )]
+    conversation_compaction_token_threshold: usize,
+
+    /// Most recent items kept verbatim after the summary
+    #[arg(long, default_value_t = 10, help_heading = "Conversation Compaction")]
+    conversation_compaction_keep_recent_items: usize,
+
+    /// Model that writes summaries (defaults to the conversation's model)
+    #[arg(long, help_heading = "Conversation Compaction")]
+    conversation_compaction_model: Option<String>,
+
+    // ==================== Webhooks ====================
+    /// POST successful non-streaming responses to a webhook URL
+    #[arg(long, default_value_t = false, help_heading = "Webhooks")]
+    enable_webhooks: bool,
+
+    /// Default webhook URL per tenant key (format: tenant_key=url)
+    #[arg(long, num_args = 0.., help_heading = "Webhooks")]
+    webhook_tenant_urls: Vec<String>,
+
+    /// Accept per-request webhook URLs from the `x-smg-webhook-url` header
+    #[arg(long, default_value_t = false, help_heading = "Webhooks")]
+    webhook_allow_request_urls: bool,
+
+    /// HMAC-SHA256 secret used to sign webhook deliveries
+    #[arg(long, env = "SMG_WEBHOOK_SECRET", help_heading = "Webhooks")]
+    webhook_secret: Option<String>,
+
+    /// Delivery attempts before a webhook is dead-lettered
+    #[arg(long, default_value_t = 5, help_heading = "Webhooks")]
+    webhook_max_attempts: u32,
+
+    // ==================== Async Generation ====================
+    /// Serve the queue-backed `/v1/generations/async` API
+    #[arg(long, default_value_t = false, help_heading = "Async Generation")]
+    enable_async_generation: bool,
+
+    /// Jobs waiting for a worker before submissions are rejected
+    #[arg(long, default_value_t = 1000, help_heading = "Async Generation")]
+    async_generation_max_queued: usize,
+
+    /// Jobs executed concurrently
+    #[arg(long, default_value_t = 8, help_heading = "Async Generation")]
+    async_generation_concurrency: usize,
+
+    /// Queued plus running jobs allowed per tenant
+    #[arg(long, default_value_t = 100, help_heading = "Async Generation")]
+    async_generation_max_jobs_per_tenant: usize,
+
+    // ==================== Map-Reduce ====================
+    /// Models whose chat requests may set `map_reduce` to split a long
+    /// document across workers and combine the results. Off for unlisted models
+    #[arg(long, num_args = 0.., help_heading = "Map-Reduce")]
+    map_reduce_models: Vec<String>,
+
+    /// Chunk size in characters when a request does not set one
+    #[arg(long, default_value_t = 32_000, help_heading = "Map-Reduce")]
+    map_reduce_chunk_chars: usize,
+
+    /// Maximum chunks per request; larger documents are rejected
+    #[arg(long, default_value_t = 32, help_heading = "Map-Reduce")]
+    map_reduce_max_chunks: usize,
+
+    /// Maximum chunk generations in flight per request
+    #[arg(long, default_value_t = 8, help_heading = "Map-Reduce")]
+    map_reduce_max_concurrency: usize,
+
+    // ==================== gRPC Pipeline ====================
+    /// Path to a YAML file of custom gRPC pipeline stages, each placed after
+    /// a named built-in stage (e.g. a WASM validation stage after preparation)
+    #[arg(long, help_heading = "gRPC Pipeline")]
+    grpc_pipeline_config: Option<String>,
+
+    // ==================== Sampling Limits ====================
+    /// Path to a YAML file of per-model sampling parameter defaults and
+    /// min/max clamps (temperature, top_p, max_tokens, penalties)
+    #[arg(long, help_heading = "Sampling Limits")]
+    sampling_limits_config: Option<String>,
+
+    // ==================== Request Tags ====================
+    /// Path to a YAML file declaring the `x-smg-tags` keys (and optional
+    /// value allowlists) recorded as metric labels
+    #[arg(long, help_heading = "Request Tags")]
+    request_tags_config: Option<String>,
+
+    // ==================== Request Features ====================
+    /// Path to a YAML file listing the `x-smg-features` flags callers may
+    /// enable, by default and per tenant API key
+    #[arg(long, help_heading = "Request Features")]
+    request_features_config: Option<String>,
+
+    // ==================== Maintenance ====================
+    /// Path to a YAML file of cron-scheduled per-worker and per-model
+    /// maintenance windows
+    #[arg(long, help_heading = "Maintenance")]
+    maintenance_config: Option<String>,
+
+    // ==================== Experiments ====================
+    /// Path to a YAML file of sticky A/B experiments (model and system
+    /// prompt variant arms with traffic percentages)
+    #[arg(long, help_heading = "Experiments")]
+    experiments_config: Option<String>,
+
+    // ==================== Prompt Guard ====================
+    /// Path to a YAML file enabling prompt-injection screening (threshold,
+    /// action, custom rules and optional classifier endpoint)
+    #[arg(long, help_heading = "Prompt Guard")]
+    prompt_guard_config: Option<String>,
+
+    // ==================== Provenance ====================
+    /// Attach signed provenance metadata (model identity, gateway version,
+    /// content digest) to inference responses
+    #[arg(long, default_value_t = false, help_heading = "Provenance")]
+    enable_provenance: bool,
+
+    /// HMAC-SHA256 key signing provenance records
+    #[arg(long, env = "SMG_PROVENANCE_SIGNING_KEY", help_heading = "Provenance")]
+    provenance_signing_key: Option<String>,
+
+    /// Key identifier reported alongside provenance signatures
+    #[arg(long, help_heading = "Provenance")]
+    provenance_key_id: Option<String>,
+
+    // ==================== Routing Rules ====================
+    /// Path to a YAML file of routing rules (path, header, tenant and body
+    /// predicates redirecting requests to a model or worker pool)
+    #[arg(long, help_heading = "Routing Rules")]
+    routing_rules_config: Option<String>,
+
+    // ==================== Config Files ====================
+    /// Reject fields that no option declares, such as misspelled keys, in the
+    /// YAML files given to `--schema-config` and the per-feature `--*-config`
+    /// flags, instead of ignoring them
+    #[arg(long, default_value_t = false, help_heading = "Config Files")]
+    strict_config: bool,
+
+    // ==================== Federation ====================
+    /// Shared HMAC-SHA256 secret signing requests to federated smg workers
+    /// and verifying requests from a parent gateway
+    #[arg(
+        long,
+        env = "SMG_FEDERATION_SIGNING_SECRET",
+        help_heading = "Federation"
+    )]
+    federation_signing_secret: Option<String>,
+
+    /// Reject inbound requests that lack a valid federation signature
+    #[arg(long, default_value_t = false, help_heading = "Federation")]
+    federation_require_signed: bool,
+
+    /// Accepted clock skew between federated gateways, in seconds
+    #[arg(long, default_value_t = 300, help_heading = "Federation")]
+    federation_max_clock_skew_secs: u64,
+
+    /// Interval between model catalog imports from federated smg workers
+    #[arg(long, default_value_t = 60, help_heading = "Federation")]
+    federation_catalog_refresh_interval_secs: u64,
+
+    // ==================== Standby ====================
+    /// Healthy regular workers a model needs before its standby workers
+    /// are demoted; below this, healthy standbys are promoted
+    #[arg(long, default_value_t = 1, help_heading = "Standby")]
+    standby_min_active_workers: usize,
+
+    /// Interval between standby promotion checks, in seconds
+    #[arg(long, default_value_t = 10, help_heading = "Standby")]
+    standby_check_interval_secs: u64,
+
+    // ==================== PD Bootstrap ====================
+    /// Age in seconds after which an open PD bootstrap room counts as stuck
+    #[arg(long, default_value_t = 60, help_heading = "PD Bootstrap")]
+    pd_bootstrap_stuck_threshold_secs: u64,
+
+    // ==================== PD Pairs ====================
+    /// Sliding window of per-pair PD latency samples, in seconds
+    #[arg(long, default_value_t = 300, help_heading = "PD Pairs")]
+    pd_pairs_window_secs: u64,
+
+    /// Samples a prefill-decode pair needs before it can be flagged slow
+    #[arg(long, default_value_t = 20, help_heading = "PD Pairs")]
+    pd_pairs_min_samples: usize,
+
+    /// Flag a pair slow when its median latency exceeds this multiple of
+    /// the median across all pairs
+    #[arg(long, default_value_t = 2.0, help_heading = "PD Pairs")]
+    pd_pairs_slow_factor: f64,
+
+    /// Flag a pair slow when its error rate exceeds this fraction
+    #[arg(long, default_value_t = 0.2, help_heading = "PD Pairs")]
+    pd_pairs_max_error_rate: f64,
+
+    /// Keep routing to slow pairs instead of preferring other decode workers
+    #[arg(long, default_value_t = false, help_heading = "PD Pairs")]
+    disable_slow_pair_avoidance: bool,
+
+    // ==================== Sessions ====================
+    /// Close sessions that carry no request for this many seconds
+    #[arg(long, default_value_t = 1800, help_heading = "Sessions")]
+    session_idle_ttl_secs: u64,
+
+    /// Maximum number of sessions open at once
+    #[arg(long, default_value_t = 10_000, help_heading = "Sessions")]
+    max_sessions: usize,
+
+    // ==================== Partial Transcripts ====================
+    /// Store the transcripts of streams that are cancelled or fail, readable
+    /// at /v1/transcripts/{request_id}
+    #[arg(long, default_value_t = false, help_heading = "Partial Transcripts")]
+    enable_partial_transcripts: bool,
+
+    /// Generated text kept per partial transcript, in bytes
+    #[arg(
+        long,
+        default_value_t = 1_048_576,
+        help_heading = "Partial Transcripts"
+    )]
+    partial_transcript_max_bytes: usize,
+
+    // ==================== Tenant MCP Servers ====================
+    /// Let tenants register their own MCP servers at /v1/mcp/servers
+    #[arg(long, default_value_t = false, help_heading = "Tenant MCP Servers")]
+    enable_tenant_mcp_servers: bool,
+
+    /// MCP servers one tenant may register
+    #[arg(long, default_value_t = 32, help_heading = "Tenant MCP Servers")]
+    tenant_mcp_max_servers: usize,
+
+    // ==================== Latency Budget ====================
+    /// Cap max_tokens of chat and completion requests sending
+    /// x-smg-latency-budget-ms to what the model's workers decode in time
+    #[arg(long, default_value_t = false, help_heading = "Latency Budget")]
+    enable_latency_budget: bool,
+
+    /// Per-request decode rate (tokens/s) assumed when workers report none
+    #[arg(long, default_value_t = 20.0, help_heading = "Latency Budget")]
+    latency_budget_default_tps: f64,
+
+    /// Milliseconds of each budget set aside for queueing and prefill
+    #[arg(long, default_value_t = 500, help_heading = "Latency Budget")]
+    latency_budget_ttft_allowance_ms: u64,
+
+    /// Lowest max_tokens cap applied, however tight the budget
+    #[arg(long, default_value_t = 16, help_heading = "Latency Budget")]
+    latency_budget_min_max_tokens: u32,
+
+    // ==================== Stream Integrity ====================
+    /// End generation streams with an smg.integrity event carrying the event
+    /// count, byte count and SHA-256 of everything streamed before it
+    #[arg(long, default_value_t = false, help_heading = "Stream Integrity")]
+    enable_stream_integrity: bool,
+
+    // ==================== Prompts ====================
+    /// Let tenants publish versioned prompt templates at /v1/prompts and
+    /// reference them from Responses requests
+    #[arg(long, default_value_t = false, help_heading = "Prompts")]
+    enable_prompts: bool,
+
+    /// Prompts one tenant may keep
+    #[arg(long, default_value_t = 128, help_heading = "Prompts")]
+    prompts_max_per_tenant: usize,
+
+    /// Versions one prompt may have
+    #[arg(long, default_value_t = 100, help_heading = "Prompts")]
+    prompts_max_versions: usize,
 }
 
 enum OracleConnectSource {
@@ -1056,6 +1545,8 @@ impl CliArgs {
                 block_size: self.block_size,
                 balance_token_usage_threshold: self.balance_token_usage_threshold,
                 overload_token_usage_threshold: self.overload_token_usage_threshold,
+                tree_window_secs: self.tree_window_secs,
+                cache_decay_half_life_secs: self.cache_decay_half_life_secs,
             },
             "power_of_two" => PolicyConfig::PowerOfTwo {
                 load_check_interval_secs: 5,
@@ -1074,8 +1565,12 @@ impl CliArgs {
             "prefix_hash" => PolicyConfig::PrefixHash {
                 prefix_token_count: self.prefix_token_count,
                 load_factor: self.prefix_hash_load_factor,
+                block_size: self.prefix_hash_block_size,
+                block_count: self.prefix_hash_block_count,
+            },
+            "consistent_hashing" => PolicyConfig::ConsistentHashing {
+                load_epsilon: self.consistent_hashing_load_epsilon,
             },
-            "consistent_hashing" => PolicyConfig::ConsistentHashing,
             "manual" => PolicyConfig::Manual {
                 eviction_interval_secs: self.eviction_interval,
                 max_idle_secs: self.max_idle_secs,
@@ -1098,21 +1593,106 @@ impl CliArgs {
         }
     }
 
-    fn load_schema_config(&self) -> ConfigResult<Option<SchemaConfig>> {
-        match &self.schema_config {
-            Some(path) => {
-                let content =
-                    std::fs::read_to_string(path).map_err(|e| ConfigError::ValidationFailed {
-                        reason: format!("Failed to read schema config file '{path}': {e}"),
-                    })?;
-                let schema: SchemaConfig =
-                    serde_yaml::from_str(&content).map_err(|e| ConfigError::ValidationFailed {
-                        reason: format!("Failed to parse schema config file '{path}': {e}"),
-                    })?;
-                Ok(Some(schema))
+    /// Read a YAML config file given to a `--*-config` flag. With
+    /// `--strict-config`, fields that `T` does not declare are rejected.
+    fn read_yaml_config<T: DeserializeOwned + JsonSchema>(
+        &self,
+        path: &str,
+        what: &str,
+    ) -> ConfigResult<T> {
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::ValidationFailed {
+            reason: format!("Failed to read {what} config file '{path}': {e}"),
+        })?;
+        let parse_error = |e: serde_yaml::Error| ConfigError::ValidationFailed {
+            reason: format!("Failed to parse {what} config file '{path}': {e}"),
+        };
+        if self.strict_config {
+            let document: serde_json::Value =
+                serde_yaml::from_str(&content).map_err(parse_error)?;
+            let schema = schemars::schema_for!(T).to_value();
+            let unknown = config::schema::unknown_fields(&schema, &document);
+            if !unknown.is_empty() {
+                return Err(ConfigError::ValidationFailed {
+                    reason: format!(
+                        "Unknown fields in {what} config file '{path}': {}",
+                        unknown.join(", ")
+                    ),
+                });
             }
-            None => Ok(None),
         }
+        serde_yaml::from_str(&content).map_err(parse_error)
+    }
+
+    fn load_schema_config(&self) -> ConfigResult<Option<SchemaConfig>> {
+        self.schema_config
+            .as_deref()
+            .map(|path| self.read_yaml_config(path, "schema"))
+            .transpose()
+    }
+
+    fn load_fault_injection_config(&self) -> ConfigResult<FaultInjectionConfig> {
+        let Some(path) = &self.fault_injection_config else {
+            return Ok(FaultInjectionConfig::default());
+        };
+        let mut faults: FaultInjectionConfig = self.read_yaml_config(path, "fault injection")?;
+        faults.enabled = true;
+        Ok(faults)
+    }
+
+    fn load_grpc_pipeline_config(&self) -> ConfigResult<GrpcPipelineConfig> {
+        let Some(path) = &self.grpc_pipeline_config else {
+            return Ok(GrpcPipelineConfig::default());
+        };
+        self.read_yaml_config(path, "gRPC pipeline")
+    }
+
+    fn load_sampling_limits_config(&self) -> ConfigResult<SamplingLimitsConfig> {
+        let Some(path) = &self.sampling_limits_config else {
+            return Ok(SamplingLimitsConfig::default());
+        };
+        self.read_yaml_config(path, "sampling limits")
+    }
+
+    fn load_request_tags_config(&self) -> ConfigResult<RequestTagsConfig> {
+        let Some(path) = &self.request_tags_config else {
+            return Ok(RequestTagsConfig::default());
+        };
+        self.read_yaml_config(path, "request tags")
+    }
+
+    fn load_request_features_config(&self) -> ConfigResult<RequestFeaturesConfig> {
+        let Some(path) = &self.request_features_config else {
+            return Ok(RequestFeaturesConfig::default());
+        };
+        self.read_yaml_config(path, "request features")
+    }
+
+    fn load_maintenance_config(&self) -> ConfigResult<MaintenanceConfig> {
+        let Some(path) = &self.maintenance_config else {
+            return Ok(MaintenanceConfig::default());
+        };
+        self.read_yaml_config(path, "maintenance")
+    }
+
+    fn load_experiments_config(&self) -> ConfigResult<ExperimentsConfig> {
+        let Some(path) = &self.experiments_config else {
+            return Ok(ExperimentsConfig::default());
+        };
+        self.read_yaml_config(path, "experiments")
+    }
+
+    fn load_prompt_guard_config(&self) -> ConfigResult<PromptGuardConfig> {
+        let Some(path) = &self.prompt_guard_config else {
+            return Ok(PromptGuardConfig::default());
+        };
+        self.read_yaml_config(path, "prompt guard")
+    }
+
+    fn load_routing_rules_config(&self) -> ConfigResult<RoutingRulesConfig> {
+        let Some(path) = &self.routing_rules_config else {
+            return Ok(RoutingRulesConfig::default());
+        };
+        self.read_yaml_config(path, "routing rules")
     }
 
     fn resolve_oracle_connect_details(&self) -> ConfigResult<OracleConnectSource> {
@@ -1362,6 +1942,15 @@ impl CliArgs {
         };
 
         let schema = self.load_schema_config()?;
+        let fault_injection = self.load_fault_injection_config()?;
+        let grpc_pipeline = self.load_grpc_pipeline_config()?;
+        let sampling_limits = self.load_sampling_limits_config()?;
+        let request_tags = self.load_request_tags_config()?;
+        let request_features = self.load_request_features_config()?;
+        let maintenance = self.load_maintenance_config()?;
+        let experiments = self.load_experiments_config()?;
+        let prompt_guard = self.load_prompt_guard_config()?;
+        let routing_rules = self.load_routing_rules_config()?;
 
         let tenant_api_keys = self
             .tenant_api_keys
@@ -1468,6 +2057,137 @@ impl CliArgs {
             .circuit_breaker(!self.disable_circuit_breaker)
             .enable_wasm(self.enable_wasm)
             .maybe_storage_hook_wasm_path(self.storage_hook_wasm_path.as_deref())
+            .debug_capture(DebugCaptureConfig {
+                enabled: self.enable_debug_capture,
+                sample_rate: self.debug_capture_sample_rate,
+                max_entries: self.debug_capture_max_entries,
+                max_body_bytes: self.debug_capture_max_body_bytes,
+                redact_pii: !self.debug_capture_disable_redaction,
+            })
+            .file_store(FileStoreConfig {
+                enabled: self.enable_file_store,
+                max_files: self.file_store_max_files,
+                public_base_url: self.file_store_public_url.clone(),
+            })
+            .vector_store(VectorStoreConfig {
+                enabled: self.enable_vector_store,
+                embedding_model: self.vector_store_embedding_model.clone(),
+                embedding_batch_size: self.vector_store_embedding_batch_size,
+                max_chunks_per_store: self.vector_store_max_chunks,
+                rerank_model: self.vector_store_rerank_model.clone(),
+                retrieval_top_k: self.vector_store_retrieval_top_k,
+                rerank_top_k: self.vector_store_rerank_top_k,
+            })
+            .chat_completion_store(ChatCompletionStoreConfig {
+                enabled: self.enable_chat_completion_store,
+                max_entries: self.chat_completion_store_max_entries,
+            })
+            .fault_injection(fault_injection)
+            .metadata_cache(MetadataCacheConfig {
+                enabled: !self.disable_metadata_cache,
+                ttl_secs: self.metadata_cache_ttl_secs,
+                ..Default::default()
+            })
+            .stream_recovery(StreamRecoveryConfig {
+                models: self.stream_recovery_models.clone(),
+                max_resumes: self.stream_recovery_max_resumes,
+            })
+            .stream_fanout(StreamFanoutConfig {
+                enabled: self.enable_stream_fanout,
+                buffer_chunks: self.stream_fanout_buffer_chunks,
+                max_subscribers: self.stream_fanout_max_subscribers,
+            })
+            .request_coalescing(RequestCoalescingConfig {
+                enabled: self.enable_request_coalescing,
+                max_request_bytes: self.request_coalescing_max_request_bytes,
+            })
+            .conversation_compaction(ConversationCompactionConfig {
+                enabled: self.enable_conversation_compaction,
+                token_threshold: self.conversation_compaction_token_threshold,
+                keep_recent_items: self.conversation_compaction_keep_recent_items,
+                model: self.conversation_compaction_model.clone(),
+            })
+            .async_generation(AsyncGenerationConfig {
+                enabled: self.enable_async_generation,
+                max_queued: self.async_generation_max_queued,
+                concurrency: self.async_generation_concurrency,
+                max_jobs_per_tenant: self.async_generation_max_jobs_per_tenant,
+                ..Default::default()
+            })
+            .webhooks(WebhookConfig {
+                enabled: self.enable_webhooks,
+                tenant_urls: Self::parse_selector(&self.webhook_tenant_urls),
+                allow_request_urls: self.webhook_allow_request_urls,
+                secret: self.webhook_secret.clone(),
+                max_attempts: self.webhook_max_attempts,
+                ..Default::default()
+            })
+            .map_reduce(MapReduceConfig {
+                models: self.map_reduce_models.clone(),
+                chunk_chars: self.map_reduce_chunk_chars,
+                max_chunks: self.map_reduce_max_chunks,
+                max_concurrency: self.map_reduce_max_concurrency,
+            })
+            .grpc_pipeline(grpc_pipeline)
+            .sampling_limits(sampling_limits)
+            .request_tags(request_tags)
+            .request_features(request_features)
+            .maintenance(maintenance)
+            .experiments(experiments)
+            .prompt_guard(prompt_guard)
+            .provenance(ProvenanceConfig {
+                enabled: self.enable_provenance,
+                signing_key: self.provenance_signing_key.clone(),
+                key_id: self.provenance_key_id.clone(),
+            })
+            .routing_rules(routing_rules)
+            .federation(FederationConfig {
+                signing_secret: self.federation_signing_secret.clone(),
+                require_signed: self.federation_require_signed,
+                max_clock_skew_secs: self.federation_max_clock_skew_secs,
+                catalog_refresh_interval_secs: self.federation_catalog_refresh_interval_secs,
+            })
+            .standby(StandbyConfig {
+                min_active_workers: self.standby_min_active_workers,
+                check_interval_secs: self.standby_check_interval_secs,
+                ..Default::default()
+            })
+            .pd_bootstrap(PdBootstrapConfig {
+                stuck_threshold_secs: self.pd_bootstrap_stuck_threshold_secs,
+            })
+            .pd_pairs(PdPairsConfig {
+                window_secs: self.pd_pairs_window_secs,
+                min_samples: self.pd_pairs_min_samples,
+                slow_factor: self.pd_pairs_slow_factor,
+                max_error_rate: self.pd_pairs_max_error_rate,
+                avoid_slow_pairs: !self.disable_slow_pair_avoidance,
+            })
+            .sessions(SessionsConfig {
+                idle_ttl_secs: self.session_idle_ttl_secs,
+                max_sessions: self.max_sessions,
+            })
+            .partial_transcripts(PartialTranscriptsConfig {
+                enabled: self.enable_partial_transcripts,
+                max_output_bytes: self.partial_transcript_max_bytes,
+            })
+            .tenant_mcp_servers(TenantMcpServersConfig {
+                enabled: self.enable_tenant_mcp_servers,
+                max_servers_per_tenant: self.tenant_mcp_max_servers,
+            })
+            .latency_budget(LatencyBudgetConfig {
+                enabled: self.enable_latency_budget,
+                default_tokens_per_second: self.latency_budget_default_tps,
+                ttft_allowance_ms: self.latency_budget_ttft_allowance_ms,
+                min_max_tokens: self.latency_budget_min_max_tokens,
+            })
+            .stream_integrity(StreamIntegrityConfig {
+                enabled: self.enable_stream_integrity,
+            })
+            .prompts(PromptsConfig {
+                enabled: self.enable_prompts,
+                max_prompts_per_tenant: self.prompts_max_per_tenant,
+                max_versions_per_prompt: self.prompts_max_versions,
+            })
             .igw(self.enable_igw)
             .dp_minimum_tokens_scheduler(self.dp_minimum_tokens_scheduler)
             .maybe_server_cert_and_key(self.tls_cert_path.as_ref(), self.tls_key_path.as_ref());
@@ -1592,6 +2312,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("{}", version::get_verbose_version_string());
             return Ok(());
         }
+        if arg == "--print-config-schema" {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&config::schema::config_schema())?
+            );
+            return Ok(());
+        }
     }
 
     let prefill_urls = parse_prefill_args();
@@ -1621,6 +2348,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Handle subcommands or use direct args
     let mut cli_args = match cli.command {
         Some(Commands::Launch { args }) => args,
+        Some(Commands::ValidateConfig { old, new, strict }) => {
+            return run_validate_config(old.as_deref(), &new, strict);
+        }
         None => cli.router_args,
     };
 
@@ -1848,4 +2578,434 @@ mod tests {
         let server_config = cli.to_server_config(router_config).unwrap();
         assert_eq!(server_config.runtime_worker_threads, None);
     }
+
+    /// Debug capture flags must reach `RouterConfig.debug_capture`, with
+    /// redaction on unless explicitly disabled.
+    #[test]
+    fn debug_capture_flags_flow_into_router_config() {
+        let cli = cli_args_from(&[
+            "--enable-debug-capture",
+            "--debug-capture-sample-rate",
+            "0.5",
+            "--debug-capture-max-entries",
+            "42",
+        ]);
+
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        let capture = &router_config.debug_capture;
+        assert!(capture.enabled);
+        assert_eq!(capture.sample_rate, 0.5);
+        assert_eq!(capture.max_entries, 42);
+        assert!(capture.redact_pii);
+
+        let cli = cli_args_from(&[]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.debug_capture.enabled);
+    }
+
+    #[test]
+    fn chat_completion_store_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[
+            "--enable-chat-completion-store",
+            "--chat-completion-store-max-entries",
+            "7",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        assert!(router_config.chat_completion_store.enabled);
+        assert_eq!(router_config.chat_completion_store.max_entries, 7);
+
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.chat_completion_store.enabled);
+    }
+
+    /// The metadata cache is on by default; flags disable it or change the TTL.
+    #[test]
+    fn metadata_cache_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(router_config.metadata_cache.enabled);
+        assert_eq!(router_config.metadata_cache.ttl_secs, 5);
+
+        let cli = cli_args_from(&[
+            "--disable-metadata-cache",
+            "--metadata-cache-ttl-secs",
+            "30",
+        ]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.metadata_cache.enabled);
+        assert_eq!(router_config.metadata_cache.ttl_secs, 30);
+    }
+
+    /// Stream recovery is off unless models opt in.
+    #[test]
+    fn stream_recovery_is_per_model_opt_in() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.stream_recovery.enabled_for("llama"));
+
+        let cli = cli_args_from(&[
+            "--stream-recovery-models",
+            "llama",
+            "qwen",
+            "--stream-recovery-max-resumes",
+            "2",
+        ]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        assert!(router_config.stream_recovery.enabled_for("qwen"));
+        assert!(!router_config.stream_recovery.enabled_for("mistral"));
+        assert_eq!(router_config.stream_recovery.max_resumes, 2);
+    }
+
+    #[test]
+    fn stream_fanout_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.stream_fanout.enabled);
+
+        let router_config = cli_args_from(&[
+            "--enable-stream-fanout",
+            "--stream-fanout-max-subscribers",
+            "2",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        assert!(router_config.stream_fanout.enabled);
+        assert_eq!(router_config.stream_fanout.max_subscribers, 2);
+        assert_eq!(router_config.stream_fanout.buffer_chunks, 1024);
+    }
+
+    #[test]
+    fn request_coalescing_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.request_coalescing.enabled);
+
+        let router_config = cli_args_from(&["--enable-request-coalescing"])
+            .to_router_config(vec![], vec![])
+            .unwrap();
+        assert!(router_config.request_coalescing.enabled);
+        assert_eq!(
+            router_config.request_coalescing.max_request_bytes,
+            1024 * 1024
+        );
+    }
+
+    #[test]
+    fn vector_store_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert_eq!(router_config.vector_store, VectorStoreConfig::default());
+
+        let router_config = cli_args_from(&[
+            "--enable-file-store",
+            "--enable-vector-store",
+            "--vector-store-embedding-model",
+            "bge-m3",
+            "--vector-store-embedding-batch-size",
+            "16",
+            "--vector-store-rerank-model",
+            "bge-reranker",
+            "--vector-store-retrieval-top-k",
+            "100",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        let store = &router_config.vector_store;
+        assert!(store.enabled);
+        assert_eq!(store.embedding_model.as_deref(), Some("bge-m3"));
+        assert_eq!(store.embedding_batch_size, 16);
+        assert_eq!(store.max_chunks_per_store, 100_000);
+        assert_eq!(store.rerank_model.as_deref(), Some("bge-reranker"));
+        assert_eq!(store.retrieval_top_k, 100);
+        assert_eq!(store.rerank_top_k, 10);
+    }
+
+    #[test]
+    fn conversation_compaction_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert_eq!(
+            router_config.conversation_compaction,
+            ConversationCompactionConfig::default()
+        );
+
+        let router_config = cli_args_from(&[
+            "--enable-conversation-compaction",
+            "--conversation-compaction-keep-recent-items",
+            "4",
+            "--conversation-compaction-model",
+            "summarizer",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        let compaction = &router_config.conversation_compaction;
+        assert!(compaction.enabled);
+        assert_eq!(compaction.token_threshold, 32_000);
+        assert_eq!(compaction.keep_recent_items, 4);
+        assert_eq!(compaction.model.as_deref(), Some("summarizer"));
+    }
+
+    #[test]
+    fn async_generation_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.async_generation.enabled);
+
+        let router_config = cli_args_from(&[
+            "--enable-async-generation",
+            "--async-generation-concurrency",
+            "2",
+            "--async-generation-max-jobs-per-tenant",
+            "5",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        let config = &router_config.async_generation;
+        assert!(config.enabled);
+        assert_eq!(config.concurrency, 2);
+        assert_eq!(config.max_jobs_per_tenant, 5);
+        assert_eq!(config.max_queued, 1000);
+    }
+
+    #[test]
+    fn webhook_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[
+            "--enable-webhooks",
+            "--webhook-tenant-urls",
+            "auth:team-red=https://hooks.example.com/red?x=1",
+            "--webhook-max-attempts",
+            "3",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        let webhooks = &router_config.webhooks;
+        assert!(webhooks.enabled);
+        assert!(!webhooks.allow_request_urls);
+        assert_eq!(webhooks.max_attempts, 3);
+        assert_eq!(
+            webhooks
+                .tenant_urls
+                .get("auth:team-red")
+                .map(String::as_str),
+            Some("https://hooks.example.com/red?x=1")
+        );
+    }
+
+    /// Map-reduce is off unless models opt in.
+    #[test]
+    fn map_reduce_is_per_model_opt_in() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.map_reduce.enabled_for("llama"));
+
+        let cli = cli_args_from(&[
+            "--map-reduce-models",
+            "llama",
+            "--map-reduce-max-chunks",
+            "4",
+        ]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        assert!(router_config.map_reduce.enabled_for("llama"));
+        assert!(!router_config.map_reduce.enabled_for("qwen"));
+        assert_eq!(router_config.map_reduce.max_chunks, 4);
+    }
+
+    #[test]
+    fn fault_injection_config_file_enables_injection() {
+        let path = std::env::temp_dir().join(format!("smg-faults-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "rules:\n  - route: /v1/chat/completions\n    probability: 0.1\n    fault: status\n    status: 503\n",
+        )
+        .unwrap();
+
+        let cli = cli_args_from(&["--fault-injection-config", path.to_str().unwrap()]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let faults = &router_config.fault_injection;
+        assert!(faults.enabled);
+        assert_eq!(faults.rules.len(), 1);
+        assert_eq!(
+            faults.rules[0].fault,
+            config::FaultKind::Status { status: 503 }
+        );
+
+        let cli = cli_args_from(&[]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.fault_injection.enabled);
+    }
+
+    #[test]
+    fn strict_config_rejects_unknown_fields_in_config_files() {
+        let path = std::env::temp_dir().join(format!("smg-strict-{}.yaml", std::process::id()));
+        std::fs::write(&path, "tags:\n  team: [red]\nmax_values_per_tg: 4\n").unwrap();
+        let path_arg = path.to_str().unwrap();
+
+        let lenient =
+            cli_args_from(&["--request-tags-config", path_arg]).to_router_config(vec![], vec![]);
+        let strict = cli_args_from(&["--request-tags-config", path_arg, "--strict-config"])
+            .to_router_config(vec![], vec![]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(lenient.is_ok());
+        let err = strict.unwrap_err().to_string();
+        assert!(err.contains("max_values_per_tg"), "{err}");
+    }
+
+    #[test]
+    fn grpc_pipeline_config_file_flows_into_router_config() {
+        let path = std::env::temp_dir().join(format!("smg-pipeline-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "stages:\n  - name: prompt-limit\n    after: preparation\n    endpoints: [chat]\n    stage: max_prompt_tokens\n    max_tokens: 8192\n",
+        )
+        .unwrap();
+
+        let cli = cli_args_from(&["--grpc-pipeline-config", path.to_str().unwrap()]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stages = &router_config.grpc_pipeline.stages;
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].after, config::PipelineStageAnchor::Preparation);
+        assert_eq!(
+            stages[0].kind,
+            config::CustomStageKind::MaxPromptTokens { max_tokens: 8192 }
+        );
+    }
+
+    #[test]
+    fn sampling_limits_config_file_flows_into_router_config() {
+        let path = std::env::temp_dir().join(format!("smg-sampling-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "models:\n  small:\n    temperature: {default: 0.7, max: 1.0}\n    max_tokens: {max: 1024}\n",
+        )
+        .unwrap();
+
+        let cli = cli_args_from(&["--sampling-limits-config", path.to_str().unwrap()]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let limits = &router_config.sampling_limits.models["small"];
+        assert_eq!(limits.temperature.default, Some(0.7));
+        assert_eq!(limits.temperature.max, Some(1.0));
+        assert_eq!(limits.max_tokens.max, Some(1024));
+        assert_eq!(limits.top_p, config::ParamLimits::default());
+    }
+
+    #[test]
+    fn request_tags_config_file_flows_into_router_config() {
+        let path = std::env::temp_dir().join(format!("smg-tags-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "tags:\n  team: [search, ads]\n  feature: []\nmax_values_per_tag: 16\n",
+        )
+        .unwrap();
+
+        let cli = cli_args_from(&["--request-tags-config", path.to_str().unwrap()]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let tags = &router_config.request_tags;
+        assert_eq!(tags.tags["team"], vec!["search", "ads"]);
+        assert!(tags.tags["feature"].is_empty());
+        assert_eq!(tags.max_values_per_tag, 16);
+    }
+
+    #[test]
+    fn request_features_config_file_flows_into_router_config() {
+        let path = std::env::temp_dir().join(format!("smg-features-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "default: [recovery]\ntenants:\n  search: [coalesce-sse]\n",
+        )
+        .unwrap();
+
+        let cli = cli_args_from(&["--request-features-config", path.to_str().unwrap()]);
+        let router_config = cli.to_router_config(vec![], vec![]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let features = &router_config.request_features;
+        assert_eq!(features.default, vec!["recovery"]);
+        assert_eq!(features.tenants["search"], vec!["coalesce-sse"]);
+    }
+
+    #[test]
+    fn validate_config_subcommand_parses() {
+        let cli = Cli::parse_from([
+            "smg",
+            "validate-config",
+            "--old",
+            "a.yaml",
+            "--new",
+            "b.yaml",
+            "--strict",
+        ]);
+        match cli.command {
+            Some(Commands::ValidateConfig { old, new, strict }) => {
+                assert_eq!(old.as_deref(), Some("a.yaml"));
+                assert_eq!(new, "b.yaml");
+                assert!(strict);
+            }
+            other => panic!("expected ValidateConfig, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tenant_mcp_server_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert_eq!(
+            router_config.tenant_mcp_servers,
+            TenantMcpServersConfig::default()
+        );
+
+        let router_config = cli_args_from(&[
+            "--enable-tenant-mcp-servers",
+            "--tenant-mcp-max-servers",
+            "4",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        assert!(router_config.tenant_mcp_servers.enabled);
+        assert_eq!(router_config.tenant_mcp_servers.max_servers_per_tenant, 4);
+    }
+
+    #[test]
+    fn latency_budget_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert_eq!(router_config.latency_budget, LatencyBudgetConfig::default());
+
+        let router_config = cli_args_from(&[
+            "--enable-latency-budget",
+            "--latency-budget-default-tps",
+            "35.5",
+            "--latency-budget-ttft-allowance-ms",
+            "250",
+        ])
+        .to_router_config(vec![], vec![])
+        .unwrap();
+        assert!(router_config.latency_budget.enabled);
+        assert_eq!(router_config.latency_budget.default_tokens_per_second, 35.5);
+        assert_eq!(router_config.latency_budget.ttft_allowance_ms, 250);
+        assert_eq!(router_config.latency_budget.min_max_tokens, 16);
+    }
+
+    #[test]
+    fn stream_integrity_flag_flows_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert!(!router_config.stream_integrity.enabled);
+
+        let router_config = cli_args_from(&["--enable-stream-integrity"])
+            .to_router_config(vec![], vec![])
+            .unwrap();
+        assert!(router_config.stream_integrity.enabled);
+    }
+
+    #[test]
+    fn prompts_flags_flow_into_router_config() {
+        let router_config = cli_args_from(&[]).to_router_config(vec![], vec![]).unwrap();
+        assert_eq!(router_config.prompts, PromptsConfig::default());
+
+        let router_config = cli_args_from(&["--enable-prompts", "--prompts-max-versions", "5"])
+            .to_router_config(vec![], vec![])
+            .unwrap();
+        assert!(router_config.prompts.enabled);
+        assert_eq!(router_config.prompts.max_prompts_per_tenant, 128);
+        assert_eq!(router_config.prompts.max_versions_per_prompt, 5);
+    }
 }
@@ -3,10 +3,14 @@
 //! domain types into the shared merge format, and routes remote
 //! updates into the corresponding registry or cache.
 
+pub mod policy_overrides_sync;
 pub mod rate_limit_sync;
+pub mod routing_rules_sync;
 pub mod tree_sync;
 pub mod worker_sync;
 
+pub use policy_overrides_sync::PolicyOverridesSyncAdapter;
 pub use rate_limit_sync::RateLimitSyncAdapter;
+pub use routing_rules_sync::RoutingRulesSyncAdapter;
 pub use tree_sync::{PeerList, RepairReason, TreeDelta, TreeRepairRequest, TreeSyncAdapter};
 pub use worker_sync::WorkerSyncAdapter;
@@ -0,0 +1,187 @@
+//! `config:policy_overrides` adapter: cluster-wide per-model policies.
+//!
+//! The runtime model -> policy assignments are one last-writer-wins value
+//! in the auto-registered `config:` namespace, JSON-encoded as a map of
+//! model id to policy name. An admin change on any node applies locally and
+//! then publishes the full map via
+//! [`publish`](PolicyOverridesSyncAdapter::publish); every node's inbound
+//! loop applies the winning value. As with routing rules, nothing is
+//! published at startup.
+//!
+//! Policy instances are not shared: each node rebuilds the assigned
+//! policy for a model it serves, seeding cache-aware trees from its own
+//! view of the model's workers. A map naming a policy this node does not
+//! know (e.g. from a newer version) is logged and skipped whole.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use smg_mesh::CrdtNamespace;
+use tracing::{debug, info, warn};
+
+use crate::{policies::PolicyRegistry, worker::WorkerRegistry};
+
+const KEY: &str = "config:policy_overrides";
+
+/// Bridge between the `config:policy_overrides` key and the
+/// [`PolicyRegistry`]'s runtime assignments.
+pub struct PolicyOverridesSyncAdapter {
+    configs: Arc<CrdtNamespace>,
+    policies: Arc<PolicyRegistry>,
+    workers: Arc<WorkerRegistry>,
+}
+
+impl std::fmt::Debug for PolicyOverridesSyncAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyOverridesSyncAdapter")
+            .field("key", &KEY)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PolicyOverridesSyncAdapter {
+    /// Build an adapter over the `config:` namespace. Panics if the
+    /// namespace is scoped to another prefix.
+    pub fn new(
+        configs: Arc<CrdtNamespace>,
+        policies: Arc<PolicyRegistry>,
+        workers: Arc<WorkerRegistry>,
+    ) -> Arc<Self> {
+        assert_eq!(
+            configs.prefix(),
+            "config:",
+            "PolicyOverridesSyncAdapter requires the `config:` namespace",
+        );
+        Arc::new(Self {
+            configs,
+            policies,
+            workers,
+        })
+    }
+
+    /// Subscribe to the key, spawn the inbound loop, then adopt any
+    /// assignments already in the store.
+    pub fn start(self: &Arc<Self>) {
+        let this = Arc::clone(self);
+        let mut sub = self.configs.subscribe("policy_overrides");
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "subscription task ends automatically when the mesh KV drops and closes the channel; no handle needed"
+        )]
+        tokio::spawn(async move {
+            while let Some((key, _snapshot)) = sub.receiver.recv().await {
+                if key == KEY {
+                    // Re-read store truth; a queued snapshot may be stale.
+                    this.sync_from_store();
+                }
+            }
+            debug!("PolicyOverridesSyncAdapter subscription closed");
+        });
+        self.sync_from_store();
+    }
+
+    /// Publish the current local assignments to every node.
+    pub fn publish(&self) {
+        match serde_json::to_vec(&self.policies.model_policy_overrides()) {
+            Ok(bytes) => self.configs.put(KEY, bytes),
+            Err(e) => warn!("Failed to encode policy overrides for mesh sync: {e}"),
+        }
+    }
+
+    fn sync_from_store(&self) {
+        let Some(bytes) = self.configs.get(KEY) else {
+            return;
+        };
+        let overrides: BTreeMap<String, String> = match serde_json::from_slice(&bytes) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                warn!("Ignoring undecodable policy overrides from mesh: {e}");
+                return;
+            }
+        };
+        if overrides == self.policies.model_policy_overrides() {
+            return;
+        }
+        match self
+            .policies
+            .replace_model_policies(&overrides, |model_id| self.workers.get_by_model(model_id))
+        {
+            Ok(()) => info!(
+                models = overrides.len(),
+                "Applied policy overrides from mesh"
+            ),
+            Err(e) => warn!("Ignoring invalid policy overrides from mesh: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use smg_mesh::MeshKV;
+    use tokio::time::sleep;
+
+    use super::*;
+    use crate::config::PolicyConfig;
+
+    fn registry() -> Arc<PolicyRegistry> {
+        Arc::new(PolicyRegistry::new(PolicyConfig::RoundRobin))
+    }
+
+    fn adapter(mesh: &MeshKV, policies: Arc<PolicyRegistry>) -> Arc<PolicyOverridesSyncAdapter> {
+        PolicyOverridesSyncAdapter::new(mesh.configs(), policies, Arc::new(WorkerRegistry::new()))
+    }
+
+    #[tokio::test]
+    async fn test_published_overrides_are_applied_from_store() {
+        let mesh = MeshKV::new("node-a".into());
+        let local = registry();
+        local
+            .set_model_policy("llama-70b", "cache_aware", &[])
+            .unwrap();
+        adapter(&mesh, local).publish();
+
+        // A node starting after the publish adopts the stored assignments,
+        // dropping its own.
+        let starting = registry();
+        starting.set_model_policy("stale", "random", &[]).unwrap();
+        starting.on_worker_added("llama-70b", None);
+        adapter(&mesh, starting.clone()).start();
+        assert_eq!(
+            starting.model_policy_overrides(),
+            BTreeMap::from([("llama-70b".to_string(), "cache_aware".to_string())])
+        );
+        assert_eq!(
+            starting.get_policy("llama-70b").unwrap().name(),
+            "cache_aware"
+        );
+
+        // A live update reaches it through the subscription.
+        let writer = registry();
+        writer
+            .set_model_policy("embeddings", "round_robin", &[])
+            .unwrap();
+        adapter(&mesh, writer).publish();
+        for _ in 0..100 {
+            if starting.model_policy_overrides().contains_key("embeddings") {
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        panic!("policy overrides update was not applied");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_remote_policy_is_skipped() {
+        let mesh = MeshKV::new("node-a".into());
+        mesh.configs()
+            .put(KEY, br#"{"llama-70b": "fastest"}"#.to_vec());
+        let policies = registry();
+        policies.set_model_policy("kept", "random", &[]).unwrap();
+        adapter(&mesh, policies.clone()).start();
+        assert_eq!(
+            policies.model_policy_overrides(),
+            BTreeMap::from([("kept".to_string(), "random".to_string())])
+        );
+    }
+}
@@ -36,6 +36,8 @@ use bytes::Bytes;
 use smg_mesh::{decode_epoch_count, encode_epoch_count, CrdtNamespace, EpochCount};
 use tracing::{debug, warn};
 
+use crate::rate_limit::{MemoryRateLimitStore, RateLimitStore};
+
 const PREFIX: &str = "rl:";
 
 /// Bridge between the `rl:` CRDT namespace and the gateway's
@@ -44,6 +46,9 @@ const PREFIX: &str = "rl:";
 pub struct RateLimitSyncAdapter {
     rate_limits: Arc<CrdtNamespace>,
     node_name: String,
+    /// This node's shard of each counter driven through
+    /// [`RateLimitStore`].
+    local: MemoryRateLimitStore,
 }
 
 impl std::fmt::Debug for RateLimitSyncAdapter {
@@ -79,6 +84,7 @@ impl RateLimitSyncAdapter {
         Arc::new(Self {
             rate_limits,
             node_name,
+            local: MemoryRateLimitStore::new(),
         })
     }
 
@@ -169,6 +175,21 @@ impl RateLimitSyncAdapter {
     /// safer than an over-count that triggers rate limiting
     /// prematurely.
     pub fn get_aggregate(&self, counter_name: &str) -> i64 {
+        let shards = self.shards(counter_name);
+        let Some(max_epoch) = shards.iter().map(|s| s.epoch).max() else {
+            return 0;
+        };
+        sum_at_epoch(&shards, max_epoch)
+    }
+
+    /// Cluster-wide aggregate for a counter in one window, summing only
+    /// shards at `epoch`. Unlike [`Self::get_aggregate`], a window nobody
+    /// has written to yet reads 0 rather than the previous window's total.
+    pub fn get_aggregate_at(&self, counter_name: &str, epoch: u64) -> i64 {
+        sum_at_epoch(&self.shards(counter_name), epoch)
+    }
+
+    fn shards(&self, counter_name: &str) -> Vec<EpochCount> {
         debug_assert!(
             !counter_name.contains(':'),
             "counter_name must not contain ':' (got {counter_name:?})",
@@ -182,19 +203,37 @@ impl RateLimitSyncAdapter {
                 }
             }
         }
-        let Some(max_epoch) = shards.iter().map(|s| s.epoch).max() else {
-            return 0;
-        };
-        // Saturate at i64::MAX instead of wrapping: a signed wrap
-        // would flip the aggregate negative and effectively disable
-        // rate limiting. Realistic cluster totals are nowhere near
-        // overflow, but under-limiting is the failure we must never
-        // accept — capping at i64::MAX keeps the gate closed.
         shards
-            .iter()
-            .filter(|s| s.epoch == max_epoch)
-            .try_fold(0i64, |acc, s| acc.checked_add(s.count))
-            .unwrap_or(i64::MAX)
+    }
+}
+
+/// Saturate at i64::MAX instead of wrapping: a signed wrap would flip the
+/// aggregate negative and effectively disable rate limiting. Realistic
+/// cluster totals are nowhere near overflow, but under-limiting is the
+/// failure we must never accept — capping at i64::MAX keeps the gate
+/// closed.
+fn sum_at_epoch(shards: &[EpochCount], epoch: u64) -> i64 {
+    shards
+        .iter()
+        .filter(|s| s.epoch == epoch)
+        .try_fold(0i64, |acc, s| acc.checked_add(s.count))
+        .unwrap_or(i64::MAX)
+}
+
+/// Cluster-wide windows for hierarchical rate limiting: reads sum every
+/// node's shard, writes publish this node's.
+impl RateLimitStore for RateLimitSyncAdapter {
+    fn count(&self, counter: &str, epoch: u64) -> i64 {
+        self.get_aggregate_at(counter, epoch)
+    }
+
+    fn add(&self, counter: &str, epoch: u64, amount: i64) {
+        self.local.add(counter, epoch, amount);
+        // Zero for a late charge to a window this node already left.
+        let count = self.local.count(counter, epoch);
+        if count != 0 {
+            self.sync_counter(counter, epoch, count);
+        }
     }
 }
 
@@ -379,4 +418,35 @@ mod tests {
 
         assert_eq!(adapter.get_aggregate("global"), i64::MAX);
     }
+
+    #[tokio::test]
+    async fn get_aggregate_at_reads_one_window() {
+        let mesh = MeshKV::new("node-a".into());
+        let ns = rl_namespace(&mesh);
+        let adapter = RateLimitSyncAdapter::new(ns.clone(), "node-a".into());
+
+        ns.put("rl:global:node-a", encode_epoch_count(1, 5).to_vec());
+        ns.put("rl:global:node-b", encode_epoch_count(2, 3).to_vec());
+
+        assert_eq!(adapter.get_aggregate_at("global", 1), 5);
+        assert_eq!(adapter.get_aggregate_at("global", 2), 3);
+        assert_eq!(adapter.get_aggregate_at("global", 3), 0);
+    }
+
+    #[tokio::test]
+    async fn store_adds_to_local_shard_and_reads_cluster_total() {
+        let mesh = MeshKV::new("node-a".into());
+        let ns = rl_namespace(&mesh);
+        let adapter = RateLimitSyncAdapter::new(ns.clone(), "node-a".into());
+        ns.put("rl:tenant:node-b", encode_epoch_count(7, 4).to_vec());
+
+        adapter.add("tenant", 7, 2);
+        adapter.add("tenant", 7, 1);
+        assert_eq!(adapter.count("tenant", 7), 7);
+
+        adapter.add("tenant", 8, 1);
+        adapter.add("tenant", 7, 5);
+        assert_eq!(adapter.count("tenant", 8), 1);
+        assert_eq!(adapter.count("tenant", 7), 4);
+    }
 }
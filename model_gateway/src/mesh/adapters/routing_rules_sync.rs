@@ -0,0 +1,165 @@
+//! `config:routing_rules` adapter: cluster-wide routing rule table.
+//!
+//! The whole table is one last-writer-wins value in the auto-registered
+//! `config:` namespace, JSON-encoded as the list of rule configs. An admin
+//! change on any node installs the table locally and then publishes it via
+//! [`publish`](RoutingRulesSyncAdapter::publish); every node's inbound loop
+//! installs the winning value. Nothing is published at startup, so a node
+//! restarting with its file-configured rules adopts the cluster's table
+//! instead of overwriting it.
+//!
+//! The local write echoes back through the subscription and is inert: the
+//! table it carries is already installed. A remote table this node cannot
+//! parse or compile (e.g. from a newer version) is logged and skipped,
+//! leaving the current table in place.
+
+use std::sync::Arc;
+
+use smg_mesh::CrdtNamespace;
+use tracing::{debug, info, warn};
+
+use crate::{config::RoutingRuleConfig, middleware::RoutingRules};
+
+const KEY: &str = "config:routing_rules";
+
+/// Bridge between the `config:routing_rules` key and the gateway's
+/// [`RoutingRules`] table.
+pub struct RoutingRulesSyncAdapter {
+    configs: Arc<CrdtNamespace>,
+    rules: Arc<RoutingRules>,
+}
+
+impl std::fmt::Debug for RoutingRulesSyncAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutingRulesSyncAdapter")
+            .field("key", &KEY)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RoutingRulesSyncAdapter {
+    /// Build an adapter over the `config:` namespace. Panics if the
+    /// namespace is scoped to another prefix.
+    pub fn new(configs: Arc<CrdtNamespace>, rules: Arc<RoutingRules>) -> Arc<Self> {
+        assert_eq!(
+            configs.prefix(),
+            "config:",
+            "RoutingRulesSyncAdapter requires the `config:` namespace",
+        );
+        Arc::new(Self { configs, rules })
+    }
+
+    /// Subscribe to the key, spawn the inbound loop, then adopt any table
+    /// already in the store.
+    pub fn start(self: &Arc<Self>) {
+        let this = Arc::clone(self);
+        let mut sub = self.configs.subscribe("routing_rules");
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "subscription task ends automatically when the mesh KV drops and closes the channel; no handle needed"
+        )]
+        tokio::spawn(async move {
+            while let Some((key, _snapshot)) = sub.receiver.recv().await {
+                if key == KEY {
+                    // Re-read store truth; a queued snapshot may be stale.
+                    this.sync_from_store();
+                }
+            }
+            debug!("RoutingRulesSyncAdapter subscription closed");
+        });
+        self.sync_from_store();
+    }
+
+    /// Publish the current local table to every node.
+    pub fn publish(&self) {
+        match serde_json::to_vec(&self.rules.list()) {
+            Ok(bytes) => self.configs.put(KEY, bytes),
+            Err(e) => warn!("Failed to encode routing rules for mesh sync: {e}"),
+        }
+    }
+
+    fn sync_from_store(&self) {
+        let Some(bytes) = self.configs.get(KEY) else {
+            return;
+        };
+        let rules: Vec<RoutingRuleConfig> = match serde_json::from_slice(&bytes) {
+            Ok(rules) => rules,
+            Err(e) => {
+                warn!("Ignoring undecodable routing rules from mesh: {e}");
+                return;
+            }
+        };
+        if rules == self.rules.list() {
+            return;
+        }
+        match self.rules.replace(rules) {
+            Ok(()) => info!(
+                rules = self.rules.list().len(),
+                "Applied routing rules from mesh"
+            ),
+            Err(e) => warn!("Ignoring invalid routing rules from mesh: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use smg_mesh::MeshKV;
+    use tokio::time::sleep;
+
+    use super::*;
+    use crate::config::{RoutingRuleAction, RoutingRulesConfig};
+
+    fn rule(name: &str) -> RoutingRuleConfig {
+        RoutingRuleConfig {
+            name: name.to_string(),
+            conditions: Default::default(),
+            action: RoutingRuleAction {
+                model: Some("big-model".to_string()),
+                worker_labels: BTreeMap::new(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_published_table_is_applied_from_store() {
+        let mesh = MeshKV::new("node-a".into());
+        let local = Arc::new(RoutingRules::new(&RoutingRulesConfig::default()));
+        local.create(rule("long"), None).unwrap();
+        RoutingRulesSyncAdapter::new(mesh.configs(), local).publish();
+
+        // A node starting after the publish adopts the stored table over
+        // its own configured one.
+        let starting = Arc::new(RoutingRules::new(&RoutingRulesConfig {
+            rules: vec![rule("stale")],
+        }));
+        RoutingRulesSyncAdapter::new(mesh.configs(), starting.clone()).start();
+        assert_eq!(starting.list(), vec![rule("long")]);
+
+        // A live update reaches it through the subscription.
+        let writer = Arc::new(RoutingRules::new(&RoutingRulesConfig::default()));
+        writer.create(rule("vision"), None).unwrap();
+        RoutingRulesSyncAdapter::new(mesh.configs(), writer).publish();
+        for _ in 0..100 {
+            if starting.get("vision").is_some() {
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        panic!("routing rules update was not applied");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_remote_table_is_skipped() {
+        let mesh = MeshKV::new("node-a".into());
+        mesh.configs()
+            .put(KEY, br#"[{"name": "bad", "action": {}}]"#.to_vec());
+        let rules = Arc::new(RoutingRules::new(&RoutingRulesConfig {
+            rules: vec![rule("kept")],
+        }));
+        RoutingRulesSyncAdapter::new(mesh.configs(), rules.clone()).start();
+        assert_eq!(rules.list(), vec![rule("kept")]);
+    }
+}
@@ -31,6 +31,7 @@
 
 use std::{collections::HashSet, sync::Arc, time::Duration};
 
+use openai_protocol::worker::WorkerStatus;
 use smg_mesh::{CrdtNamespace, WorkerState};
 use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
@@ -267,6 +268,8 @@ impl WorkerSyncAdapter {
                         published.insert(worker_id);
                     }
                 }
+                // Promotion is this node's routing state; nothing to publish.
+                Ok(WorkerEvent::StandbyChanged { .. }) => {}
                 Ok(WorkerEvent::Removed { worker_id, .. }) => {
                     if published.remove(&worker_id) {
                         self.on_worker_removed(worker_id.as_str());
@@ -379,7 +382,9 @@ fn worker_state_of(worker_id: &WorkerId, worker: &Arc<dyn Worker>) -> WorkerStat
         worker_id: worker_id.as_str().to_string(),
         model_id: worker.model_id().to_string(),
         url: worker.url().to_string(),
-        health: worker.is_healthy(),
+        // Probe result only: standby promotion is each node's own routing
+        // decision, so an unpromoted standby is still published healthy.
+        health: worker.status() == WorkerStatus::Ready,
         load: worker.load() as f64,
         version: worker.revision(),
         spec,
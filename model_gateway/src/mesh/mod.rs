@@ -1,9 +1,13 @@
 //! Gateway-side glue for the v2 mesh: adapters that bridge the
-//! typed `MeshKV` namespaces to local registries, plus bootstrap
-//! and shutdown wiring added in later steps.
+//! typed `MeshKV` namespaces to local registries, the rolling restart
+//! coordinator, plus bootstrap and shutdown wiring added in later steps.
 
 pub mod adapters;
+pub mod rolling_restart;
 pub mod wiring;
 
-pub use adapters::{RateLimitSyncAdapter, TreeDelta, TreeSyncAdapter, WorkerSyncAdapter};
+pub use adapters::{
+    RateLimitSyncAdapter, RoutingRulesSyncAdapter, TreeDelta, TreeSyncAdapter, WorkerSyncAdapter,
+};
+pub use rolling_restart::RollingRestartCoordinator;
 pub use wiring::MeshAdapters;
@@ -0,0 +1,440 @@
+//! `restart:` CRDT namespace: drain-aware rolling restarts across the mesh.
+//!
+//! An admin call on any node writes a [`RestartPlan`] under `restart:plan`
+//! listing every alive member, with the calling node last so the node the
+//! operator is talking to stays up to report progress. Each node runs
+//! [`RollingRestartCoordinator`]'s loop, which walks the plan in order:
+//!
+//! 1. The first node not yet `ready` owns the turn. Once every other plan
+//!    member is `ALIVE` in the gossip view it marks itself `draining` under
+//!    `restart:node:{plan_id}:{node}` and fires the drain trigger, which
+//!    runs the same readiness-drain → in-flight-drain → exit path as
+//!    SIGTERM. The process supervisor (Kubernetes, systemd) starts it again.
+//! 2. The restarted process finds itself `draining` in the plan without
+//!    having drained in this lifetime, waits for local readiness, and marks
+//!    itself `ready`, handing the turn to the next node.
+//!
+//! Node names must be stable across restarts for step 2 to recognise the
+//! new process. A node that never comes back stalls the rollout rather than
+//! letting it continue with reduced capacity; [`RollingRestartCoordinator::abort`]
+//! tombstones the plan.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use smg_mesh::{gossip::NodeStatus, ClusterState, CrdtNamespace, MergeStrategy, MeshKV};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::health::ProbeState;
+
+const PREFIX: &str = "restart:";
+const PLAN_KEY: &str = "restart:plan";
+
+/// How often each node re-evaluates the active plan.
+const TICK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A cluster-wide rolling restart, in the order nodes take their turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartPlan {
+    pub id: String,
+    pub nodes: Vec<String>,
+    /// Node that accepted the admin call
+    pub initiated_by: String,
+    /// Unix timestamp (seconds)
+    pub created_at: i64,
+}
+
+/// Per-node progress through a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartPhase {
+    Pending,
+    Draining,
+    Ready,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeRestartStatus {
+    pub name: String,
+    pub phase: RestartPhase,
+    pub alive: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RollingRestartStatus {
+    pub plan: RestartPlan,
+    pub nodes: Vec<NodeRestartStatus>,
+    /// Node whose turn it is; `None` once every node is ready
+    pub current: Option<String>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RollingRestartError {
+    #[error("rolling restart {plan_id} is already in progress")]
+    AlreadyInProgress { plan_id: String },
+}
+
+/// Drives this node's part in mesh-wide rolling restarts.
+pub struct RollingRestartCoordinator {
+    plans: Arc<CrdtNamespace>,
+    node_name: String,
+    cluster_state: ClusterState,
+    probe_state: Arc<ProbeState>,
+    drain: CancellationToken,
+    /// Set once this process has started draining, so the old process never
+    /// mistakes itself for the restarted one.
+    draining: AtomicBool,
+}
+
+impl std::fmt::Debug for RollingRestartCoordinator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RollingRestartCoordinator")
+            .field("node_name", &self.node_name)
+            .field("draining", &self.draining.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl RollingRestartCoordinator {
+    /// Register the `restart:` namespace and spawn the coordination loop.
+    ///
+    /// Like [`super::MeshAdapters::start`], must run before gossip starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `restart:` prefix is already configured.
+    pub fn start(
+        mesh_kv: &MeshKV,
+        node_name: String,
+        cluster_state: ClusterState,
+        probe_state: Arc<ProbeState>,
+    ) -> Arc<Self> {
+        let plans = mesh_kv.configure_crdt_prefix(PREFIX, MergeStrategy::LastWriterWins);
+        let coordinator = Arc::new(Self {
+            plans,
+            node_name,
+            cluster_state,
+            probe_state,
+            drain: CancellationToken::new(),
+            draining: AtomicBool::new(false),
+        });
+
+        let weak = Arc::downgrade(&coordinator);
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the coordinator is dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(TICK_INTERVAL);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(coordinator) = weak.upgrade() else {
+                    break;
+                };
+                coordinator.step();
+            }
+        });
+        coordinator
+    }
+
+    /// Resolves when this node's turn to drain has come.
+    pub async fn drain_requested(&self) {
+        self.drain.cancelled().await;
+    }
+
+    /// Start a rolling restart over every alive mesh member.
+    pub fn begin(&self) -> Result<RestartPlan, RollingRestartError> {
+        if let Some(status) = self.status() {
+            if !status.completed {
+                return Err(RollingRestartError::AlreadyInProgress {
+                    plan_id: status.plan.id,
+                });
+            }
+        }
+
+        let mut nodes: Vec<String> = self
+            .cluster_state
+            .read()
+            .values()
+            .filter(|node| node.status == NodeStatus::Alive as i32)
+            .map(|node| node.name.clone())
+            .filter(|name| *name != self.node_name)
+            .collect();
+        nodes.sort();
+        nodes.push(self.node_name.clone());
+
+        let plan = RestartPlan {
+            id: uuid::Uuid::now_v7().to_string(),
+            nodes,
+            initiated_by: self.node_name.clone(),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        self.put_json(PLAN_KEY, &plan);
+        info!(plan_id = %plan.id, nodes = ?plan.nodes, "Rolling restart started");
+        Ok(plan)
+    }
+
+    /// Tombstone the active plan. Nodes already draining still restart;
+    /// no further node takes a turn. Returns the aborted plan, if any.
+    pub fn abort(&self) -> Option<RestartPlan> {
+        let plan = self.plan()?;
+        self.plans.delete(PLAN_KEY);
+        info!(plan_id = %plan.id, "Rolling restart aborted");
+        Some(plan)
+    }
+
+    /// Progress of the current (or most recently completed) plan.
+    pub fn status(&self) -> Option<RollingRestartStatus> {
+        let plan = self.plan()?;
+        let alive: Vec<String> = {
+            let state = self.cluster_state.read();
+            plan.nodes
+                .iter()
+                .filter(|name| {
+                    state
+                        .get(name.as_str())
+                        .is_some_and(|node| node.status == NodeStatus::Alive as i32)
+                })
+                .cloned()
+                .collect()
+        };
+        let nodes: Vec<NodeRestartStatus> = plan
+            .nodes
+            .iter()
+            .map(|name| NodeRestartStatus {
+                name: name.clone(),
+                phase: self.phase(&plan.id, name),
+                alive: alive.contains(name),
+            })
+            .collect();
+        let current = nodes
+            .iter()
+            .find(|node| node.phase != RestartPhase::Ready)
+            .map(|node| node.name.clone());
+        Some(RollingRestartStatus {
+            completed: current.is_none(),
+            plan,
+            nodes,
+            current,
+        })
+    }
+
+    /// One evaluation of the active plan from this node's point of view.
+    fn step(&self) {
+        let Some(status) = self.status() else {
+            return;
+        };
+        if status.current.as_deref() != Some(self.node_name.as_str()) {
+            return;
+        }
+
+        match self.phase(&status.plan.id, &self.node_name) {
+            RestartPhase::Pending => {
+                if let Some(waiting_on) = status
+                    .nodes
+                    .iter()
+                    .find(|node| node.name != self.node_name && !node.alive)
+                {
+                    debug!(
+                        plan_id = %status.plan.id,
+                        peer = %waiting_on.name,
+                        "Rolling restart waiting for peer to become alive"
+                    );
+                    return;
+                }
+                info!(plan_id = %status.plan.id, "Rolling restart: draining this node");
+                self.draining.store(true, Ordering::Release);
+                self.set_phase(&status.plan.id, RestartPhase::Draining);
+                self.drain.cancel();
+            }
+            RestartPhase::Draining => {
+                if self.draining.load(Ordering::Acquire) || !self.probe_state.is_ready() {
+                    return;
+                }
+                info!(plan_id = %status.plan.id, "Rolling restart: node back and ready");
+                self.set_phase(&status.plan.id, RestartPhase::Ready);
+            }
+            RestartPhase::Ready => {}
+        }
+    }
+
+    fn plan(&self) -> Option<RestartPlan> {
+        let bytes = self.plans.get(PLAN_KEY)?;
+        serde_json::from_slice(&bytes)
+            .inspect_err(|e| warn!(error = %e, "Ignoring malformed rolling restart plan"))
+            .ok()
+    }
+
+    fn phase(&self, plan_id: &str, node: &str) -> RestartPhase {
+        self.plans
+            .get(&Self::node_key(plan_id, node))
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or(RestartPhase::Pending)
+    }
+
+    fn set_phase(&self, plan_id: &str, phase: RestartPhase) {
+        self.put_json(&Self::node_key(plan_id, &self.node_name), &phase);
+    }
+
+    fn put_json<T: Serialize>(&self, key: &str, value: &T) {
+        match serde_json::to_vec(value) {
+            Ok(bytes) => self.plans.put(key, bytes),
+            Err(e) => warn!(key, error = %e, "Failed to serialize rolling restart state"),
+        }
+    }
+
+    fn node_key(plan_id: &str, node: &str) -> String {
+        format!("{PREFIX}node:{plan_id}:{node}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use llm_tokenizer::TokenizerRegistry;
+    use openai_protocol::worker::WorkerStatus;
+    use parking_lot::RwLock;
+    use smg_mesh::gossip::NodeState;
+
+    use super::*;
+    use crate::{
+        config::RouterConfig,
+        observability::inflight_tracker::InFlightRequestTracker,
+        worker::{BasicWorkerBuilder, Worker, WorkerRegistry},
+    };
+
+    fn cluster(names: &[&str]) -> ClusterState {
+        let nodes = names
+            .iter()
+            .map(|name| {
+                let state = NodeState {
+                    name: (*name).to_string(),
+                    address: String::new(),
+                    status: NodeStatus::Alive as i32,
+                    version: 1,
+                    metadata: HashMap::new(),
+                };
+                ((*name).to_string(), state)
+            })
+            .collect::<BTreeMap<_, _>>();
+        Arc::new(RwLock::new(nodes))
+    }
+
+    fn ready_probe_state() -> Arc<ProbeState> {
+        let probe_state = ProbeState::new(Arc::new(InFlightRequestTracker::new()));
+        let registry = WorkerRegistry::new();
+        let worker = BasicWorkerBuilder::new("http://w1:8000").build();
+        worker.set_status(WorkerStatus::Ready);
+        registry.register(Arc::new(worker));
+        probe_state.recompute(
+            &registry,
+            &TokenizerRegistry::new(),
+            &RouterConfig::default(),
+        );
+        probe_state
+    }
+
+    #[tokio::test]
+    async fn nodes_restart_one_at_a_time_in_plan_order() {
+        let mesh = MeshKV::new("node-a".into());
+        let state = cluster(&["node-a", "node-b"]);
+        let a = RollingRestartCoordinator::start(
+            &mesh,
+            "node-a".into(),
+            state.clone(),
+            ready_probe_state(),
+        );
+        // node-b shares the store to stand in for gossip convergence.
+        let b = RollingRestartCoordinator {
+            plans: Arc::clone(&a.plans),
+            node_name: "node-b".into(),
+            cluster_state: state.clone(),
+            probe_state: ready_probe_state(),
+            drain: CancellationToken::new(),
+            draining: AtomicBool::new(false),
+        };
+
+        let plan = b.begin().unwrap();
+        assert_eq!(plan.nodes, vec!["node-a", "node-b"]);
+        assert!(matches!(
+            a.begin(),
+            Err(RollingRestartError::AlreadyInProgress { .. })
+        ));
+
+        // node-a's turn: it drains; node-b holds.
+        b.step();
+        a.step();
+        assert!(a.drain.is_cancelled());
+        assert!(!b.drain.is_cancelled());
+        assert_eq!(a.phase(&plan.id, "node-a"), RestartPhase::Draining);
+
+        // The draining process never reports itself ready.
+        a.step();
+        assert_eq!(a.phase(&plan.id, "node-a"), RestartPhase::Draining);
+
+        // node-a's replacement process comes back ready; node-b's turn.
+        let restarted = RollingRestartCoordinator {
+            plans: Arc::clone(&a.plans),
+            node_name: "node-a".into(),
+            cluster_state: state,
+            probe_state: ready_probe_state(),
+            drain: CancellationToken::new(),
+            draining: AtomicBool::new(false),
+        };
+        restarted.step();
+        assert_eq!(restarted.phase(&plan.id, "node-a"), RestartPhase::Ready);
+        assert!(!restarted.drain.is_cancelled());
+        b.step();
+        assert!(b.drain.is_cancelled());
+
+        let status = b.status().unwrap();
+        assert_eq!(status.current.as_deref(), Some("node-b"));
+        assert!(!status.completed);
+    }
+
+    #[tokio::test]
+    async fn turn_waits_for_peers_and_abort_clears_plan() {
+        let mesh = MeshKV::new("node-a".into());
+        let state = cluster(&["node-a", "node-b"]);
+        let a = RollingRestartCoordinator::start(
+            &mesh,
+            "node-a".into(),
+            state.clone(),
+            ready_probe_state(),
+        );
+        let b = RollingRestartCoordinator {
+            plans: Arc::clone(&a.plans),
+            node_name: "node-b".into(),
+            cluster_state: state.clone(),
+            probe_state: ready_probe_state(),
+            drain: CancellationToken::new(),
+            draining: AtomicBool::new(false),
+        };
+        b.begin().unwrap();
+
+        // node-a holds the turn but must not drain while node-b is unhealthy.
+        if let Some(node) = state.write().get_mut("node-b") {
+            node.status = NodeStatus::Suspected as i32;
+        }
+        a.step();
+        assert!(!a.drain.is_cancelled());
+        let status = a.status().unwrap();
+        assert_eq!(status.current.as_deref(), Some("node-a"));
+        assert!(!status.nodes[1].alive);
+
+        assert!(a.abort().is_some());
+        assert!(a.status().is_none());
+        assert!(a.abort().is_none());
+    }
+}
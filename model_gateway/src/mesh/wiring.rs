@@ -11,8 +11,10 @@ use std::sync::Arc;
 
 use smg_mesh::{MergeStrategy, MeshKV};
 
-use super::adapters::{RateLimitSyncAdapter, WorkerSyncAdapter};
-use crate::worker::WorkerRegistry;
+use super::adapters::{
+    PolicyOverridesSyncAdapter, RateLimitSyncAdapter, RoutingRulesSyncAdapter, WorkerSyncAdapter,
+};
+use crate::{middleware::RoutingRules, policies::PolicyRegistry, worker::WorkerRegistry};
 
 /// Owns the started mesh sync adapters. Mesh on means every adapter here is
 /// constructed, its namespace registered, and its inbound loop running —
@@ -21,11 +23,14 @@ use crate::worker::WorkerRegistry;
 pub struct MeshAdapters {
     worker: Arc<WorkerSyncAdapter>,
     rate_limit: Arc<RateLimitSyncAdapter>,
+    routing_rules: Arc<RoutingRulesSyncAdapter>,
+    policy_overrides: Arc<PolicyOverridesSyncAdapter>,
 }
 
 impl MeshAdapters {
     /// Register the `worker:` (last-writer-wins) and `rl:` (epoch-max-wins)
-    /// CRDT namespaces, construct the adapters, and start their inbound sync
+    /// CRDT namespaces, construct the adapters (routing rules and policy
+    /// overrides use the auto-registered `config:` namespace), and start their inbound sync
     /// loops. One call because the adapters' `start` methods are not
     /// idempotent (each call spawns another subscription task).
     ///
@@ -42,14 +47,26 @@ impl MeshAdapters {
         mesh_kv: &MeshKV,
         node_name: String,
         worker_registry: Arc<WorkerRegistry>,
+        routing_rules: Arc<RoutingRules>,
+        policy_registry: Arc<PolicyRegistry>,
     ) -> Arc<Self> {
         let worker_ns = mesh_kv.configure_crdt_prefix("worker:", MergeStrategy::LastWriterWins);
         let rl_ns = mesh_kv.configure_crdt_prefix("rl:", MergeStrategy::EpochMaxWins);
-        let worker = WorkerSyncAdapter::new(worker_ns, worker_registry);
+        let worker = WorkerSyncAdapter::new(worker_ns, Arc::clone(&worker_registry));
         let rate_limit = RateLimitSyncAdapter::new(rl_ns, node_name);
+        let routing_rules = RoutingRulesSyncAdapter::new(mesh_kv.configs(), routing_rules);
+        let policy_overrides =
+            PolicyOverridesSyncAdapter::new(mesh_kv.configs(), policy_registry, worker_registry);
         worker.start();
         rate_limit.start();
-        Arc::new(Self { worker, rate_limit })
+        routing_rules.start();
+        policy_overrides.start();
+        Arc::new(Self {
+            worker,
+            rate_limit,
+            routing_rules,
+            policy_overrides,
+        })
     }
 
     /// Worker sync adapter.
@@ -61,6 +78,16 @@ impl MeshAdapters {
     pub fn rate_limit(&self) -> &Arc<RateLimitSyncAdapter> {
         &self.rate_limit
     }
+
+    /// Routing rules sync adapter.
+    pub fn routing_rules(&self) -> &Arc<RoutingRulesSyncAdapter> {
+        &self.routing_rules
+    }
+
+    /// Per-model policy overrides sync adapter.
+    pub fn policy_overrides(&self) -> &Arc<PolicyOverridesSyncAdapter> {
+        &self.policy_overrides
+    }
 }
 
 #[cfg(test)]
@@ -71,16 +98,37 @@ mod tests {
     use tokio::time::sleep;
 
     use super::*;
+    use crate::config::{PolicyConfig, RoutingRulesConfig};
+
+    fn rules() -> Arc<RoutingRules> {
+        Arc::new(RoutingRules::new(&RoutingRulesConfig::default()))
+    }
+
+    fn policies() -> Arc<PolicyRegistry> {
+        Arc::new(PolicyRegistry::new(PolicyConfig::RoundRobin))
+    }
 
     fn started(mesh: &MeshKV) -> Arc<MeshAdapters> {
-        MeshAdapters::start(mesh, "node-a".into(), Arc::new(WorkerRegistry::new()))
+        MeshAdapters::start(
+            mesh,
+            "node-a".into(),
+            Arc::new(WorkerRegistry::new()),
+            rules(),
+            policies(),
+        )
     }
 
     #[tokio::test]
     async fn start_wires_worker_inbound_end_to_end() {
         let mesh = MeshKV::new("node-a".into());
         let registry = Arc::new(WorkerRegistry::new());
-        let adapters = MeshAdapters::start(&mesh, "node-a".into(), registry.clone());
+        let adapters = MeshAdapters::start(
+            &mesh,
+            "node-a".into(),
+            registry.clone(),
+            rules(),
+            policies(),
+        );
 
         // A put through the adapter echoes back through the namespace
         // subscription, exercising the registered prefix and the live
@@ -130,6 +178,12 @@ mod tests {
     #[should_panic(expected = "must not contain ':'")]
     async fn start_panics_on_colon_node_name() {
         let mesh = MeshKV::new("node-a".into());
-        let _ = MeshAdapters::start(&mesh, "node:a".into(), Arc::new(WorkerRegistry::new()));
+        let _ = MeshAdapters::start(
+            &mesh,
+            "node:a".into(),
+            Arc::new(WorkerRegistry::new()),
+            rules(),
+            policies(),
+        );
     }
 }
@@ -0,0 +1,358 @@
+//! Sampled request/response debug capture.
+//!
+//! When `debug_capture.enabled` is set, a `sample_rate` fraction of
+//! authenticated serving requests is recorded — redacted request body,
+//! status, latency, and (for non-streaming responses) the redacted response
+//! body — into the debug capture store. Admin handlers at the bottom of this
+//! module back the `/debug/captures` endpoints.
+
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use futures::{stream, StreamExt};
+use rand::RngExt;
+use serde_json::{json, Value};
+use smg_data_connector::{DebugCapture, DebugCaptureFilter, DebugCaptureId};
+use tracing::{error, warn};
+
+use super::{is_streaming_response, request_id::RequestId};
+use crate::{config::DebugCaptureConfig, observability::redaction, server::AppState};
+
+/// Bytes read past `max_body_bytes`, so that a value straddling the cut is
+/// still seen whole by the redactor before the body is truncated.
+const REDACTION_SLACK_BYTES: usize = 4 * 1024;
+
+/// Read at most about `limit` bytes of `body` for capture. Returns the bytes
+/// read, whether they are the whole body, and a body that replays everything
+/// for the handler or client.
+async fn read_capture_prefix(body: Body, limit: usize) -> Result<(Bytes, bool, Body), axum::Error> {
+    let mut chunks = body.into_data_stream();
+    let mut prefix = Vec::new();
+    while prefix.len() <= limit {
+        match chunks.next().await {
+            Some(chunk) => prefix.extend_from_slice(&chunk?),
+            None => {
+                let prefix = Bytes::from(prefix);
+                return Ok((prefix.clone(), true, Body::from(prefix)));
+            }
+        }
+    }
+    let prefix = Bytes::from(prefix);
+    let replay = stream::once(std::future::ready(Ok(prefix.clone()))).chain(chunks);
+    Ok((prefix, false, Body::from_stream(replay)))
+}
+
+/// Cut `text` to at most `max_bytes` on a char boundary. Returns whether
+/// anything was cut.
+fn truncate_text(text: &mut String, max_bytes: usize) -> bool {
+    if text.len() <= max_bytes {
+        return false;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    true
+}
+
+/// Convert a captured body into a redacted JSON value, then truncate it to
+/// `max_body_bytes`. `complete` is false when `bytes` is only a prefix of
+/// the body. Returns the value and whether it was truncated.
+fn capture_body(bytes: &[u8], complete: bool, config: &DebugCaptureConfig) -> (Value, bool) {
+    if bytes.is_empty() {
+        return (Value::Null, false);
+    }
+
+    if complete {
+        if let Ok(mut value) = serde_json::from_slice::<Value>(bytes) {
+            if config.redact_pii {
+                redaction::redact_json(&mut value);
+            }
+            if bytes.len() <= config.max_body_bytes {
+                return (value, false);
+            }
+            let mut text = value.to_string();
+            let truncated = truncate_text(&mut text, config.max_body_bytes);
+            return if truncated {
+                (Value::String(text), true)
+            } else {
+                (value, false)
+            };
+        }
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let mut text = if config.redact_pii {
+        redaction::redact_text(&text).into_owned()
+    } else {
+        text.into_owned()
+    };
+    let truncated = truncate_text(&mut text, config.max_body_bytes) || !complete;
+    (Value::String(text), truncated)
+}
+
+fn extract_model(body: &[u8]) -> Option<String> {
+    serde_json::from_slice::<Value>(body)
+        .ok()?
+        .get("model")?
+        .as_str()
+        .map(ToOwned::to_owned)
+}
+
+pub async fn debug_capture_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = &app_state.context.router_config.debug_capture;
+    let Some(storage) = app_state.context.debug_capture_storage.clone() else {
+        return next.run(request).await;
+    };
+    if !rand::rng().random_bool(config.sample_rate.clamp(0.0, 1.0)) {
+        return next.run(request).await;
+    }
+
+    let read_limit = config.max_body_bytes.saturating_add(REDACTION_SLACK_BYTES);
+    let (parts, body) = request.into_parts();
+    let (request_bytes, request_complete, body) = match read_capture_prefix(body, read_limit).await
+    {
+        Ok(read) => read,
+        Err(e) => {
+            error!("Failed to read request body for debug capture: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("Failed to read request body: {e}")})),
+            )
+                .into_response();
+        }
+    };
+
+    let request_id = parts.extensions.get::<RequestId>().map(|r| r.0.clone());
+    let method = parts.method.to_string();
+    let path = parts.uri.path().to_string();
+    let model = extract_model(&request_bytes);
+    let (request_body, request_truncated) = capture_body(&request_bytes, request_complete, config);
+
+    let start = Instant::now();
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let status = response.status();
+    let streaming = is_streaming_response(response.headers());
+    let (response, response_body, response_truncated) = if streaming {
+        (response, Value::Null, false)
+    } else {
+        let (parts, body) = response.into_parts();
+        let (response_bytes, response_complete, body) =
+            match read_capture_prefix(body, read_limit).await {
+                Ok(read) => read,
+                Err(e) => {
+                    error!("Failed to read response body for debug capture: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({"error": "Failed to read response body"})),
+                    )
+                        .into_response();
+                }
+            };
+        let (captured, truncated) = capture_body(&response_bytes, response_complete, config);
+        (Response::from_parts(parts, body), captured, truncated)
+    };
+
+    let capture = DebugCapture {
+        id: DebugCaptureId::new(),
+        created_at: Utc::now(),
+        request_id,
+        method,
+        path,
+        model,
+        status: status.as_u16(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        request_body,
+        response_body,
+        truncated: request_truncated || response_truncated,
+        streaming,
+    };
+
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "fire-and-forget store: capture persistence must not add latency to the response"
+    )]
+    tokio::spawn(async move {
+        if let Err(e) = storage.store_capture(capture).await {
+            warn!("Failed to store debug capture: {}", e);
+        }
+    });
+
+    response
+}
+
+fn capture_store_unavailable() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Debug capture is not enabled"})),
+    )
+        .into_response()
+}
+
+/// `GET /debug/captures?model=&status=&min_latency_ms=&max_latency_ms=&limit=`
+pub async fn list_captures(
+    State(app_state): State<Arc<AppState>>,
+    Query(filter): Query<DebugCaptureFilter>,
+) -> Response {
+    let Some(storage) = &app_state.context.debug_capture_storage else {
+        return capture_store_unavailable();
+    };
+    match storage.list_captures(&filter).await {
+        Ok(captures) => Json(json!({
+            "object": "list",
+            "data": captures,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// `GET /debug/captures/{capture_id}`
+pub async fn get_capture(
+    State(app_state): State<Arc<AppState>>,
+    Path(capture_id): Path<String>,
+) -> Response {
+    let Some(storage) = &app_state.context.debug_capture_storage else {
+        return capture_store_unavailable();
+    };
+    match storage.get_capture(&DebugCaptureId::from(capture_id)).await {
+        Ok(Some(capture)) => Json(capture).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Capture not found"})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+/// `DELETE /debug/captures`
+pub async fn clear_captures(State(app_state): State<Arc<AppState>>) -> Response {
+    let Some(storage) = &app_state.context.debug_capture_storage else {
+        return capture_store_unavailable();
+    };
+    match storage.clear_captures().await {
+        Ok(deleted) => Json(json!({"deleted": deleted})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderMap, HeaderValue};
+
+    use super::*;
+
+    fn config(max_body_bytes: usize) -> DebugCaptureConfig {
+        DebugCaptureConfig {
+            enabled: true,
+            max_body_bytes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_capture_body_redacts_json() {
+        let body = br#"{"model":"m","messages":[{"role":"user","content":"ping me at a@b.io"}]}"#;
+        let (value, truncated) = capture_body(body, true, &config(1024));
+        assert!(!truncated);
+        assert_eq!(value["model"], "m");
+        assert_eq!(
+            value["messages"][0]["content"],
+            format!("ping me at {}", redaction::REDACTED)
+        );
+    }
+
+    #[test]
+    fn test_capture_body_truncates_to_string() {
+        let body = br#"{"model":"m","prompt":"0123456789"}"#;
+        let (value, truncated) = capture_body(body, true, &config(8));
+        assert!(truncated);
+        assert_eq!(value, Value::String(r#"{"model""#.to_string()));
+    }
+
+    #[test]
+    fn test_capture_body_redacts_before_truncating() {
+        // The cut falls inside the email; no fragment of it may survive.
+        let body = br#"{"content":"write to someone@example.com today"}"#;
+        let (value, truncated) = capture_body(body, true, &config(30));
+        assert!(truncated);
+        let text = value.as_str().unwrap();
+        assert!(!text.contains("someone"), "{text}");
+
+        let (value, truncated) = capture_body(b"mail someone@example.com", false, &config(12));
+        assert!(truncated);
+        assert!(!value.as_str().unwrap().contains("some"));
+    }
+
+    #[tokio::test]
+    async fn test_read_capture_prefix_replays_whole_body() {
+        let chunks: Vec<Result<Bytes, std::io::Error>> =
+            (0..10).map(|_| Ok(Bytes::from(vec![b'x'; 100]))).collect();
+        let body = Body::from_stream(stream::iter(chunks));
+
+        let (prefix, complete, replay) = read_capture_prefix(body, 250).await.unwrap();
+        assert!(!complete);
+        assert_eq!(prefix.len(), 300);
+        let replayed = axum::body::to_bytes(replay, usize::MAX).await.unwrap();
+        assert_eq!(replayed.len(), 1000);
+
+        let (prefix, complete, _) = read_capture_prefix(Body::from("small"), 250).await.unwrap();
+        assert!(complete);
+        assert_eq!(prefix, "small");
+    }
+
+    #[test]
+    fn test_capture_body_respects_redaction_toggle() {
+        let mut cfg = config(1024);
+        cfg.redact_pii = false;
+        let (value, _) = capture_body(br#"{"content":"a@b.io"}"#, true, &cfg);
+        assert_eq!(value["content"], "a@b.io");
+    }
+
+    #[test]
+    fn test_extract_model() {
+        assert_eq!(
+            extract_model(br#"{"model":"llama"}"#).as_deref(),
+            Some("llama")
+        );
+        assert_eq!(extract_model(b"not json"), None);
+    }
+
+    #[test]
+    fn test_is_streaming_response() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_streaming_response(&headers));
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        assert!(is_streaming_response(&headers));
+    }
+}
@@ -0,0 +1,178 @@
+//! Reject inference requests for endpoints no registered worker can serve.
+//!
+//! Without this gate a `/v1/rerank` call against a fleet of chat models picks
+//! a chat worker and relays whatever confusing error the backend produces.
+//! The check is fleet-wide and uses the capabilities on each worker's model
+//! cards; per-model mismatches are still reported by the routers. It stays
+//! out of the way when the answer is unknown: an empty registry (the routers
+//! report missing workers), a wildcard worker whose models are not yet
+//! discovered, or an endpoint no model card advertises (`/generate`).
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use openai_protocol::model_type::Endpoint;
+
+use crate::{
+    routers::error,
+    worker::{Worker, WorkerRegistry},
+};
+
+/// Endpoints gated on model-card capabilities, in the order they are listed
+/// back to the caller.
+const GATED_ENDPOINTS: [Endpoint; 8] = [
+    Endpoint::Chat,
+    Endpoint::Completions,
+    Endpoint::Responses,
+    Endpoint::Embeddings,
+    Endpoint::Rerank,
+    Endpoint::Classify,
+    Endpoint::Score,
+    Endpoint::ImageGenerations,
+];
+
+fn gated_endpoint(path: &str) -> Option<Endpoint> {
+    // `/rerank` is the SGLang-native alias of `/v1/rerank`.
+    let endpoint = match path.trim_end_matches('/') {
+        "/rerank" => Endpoint::Rerank,
+        other => Endpoint::from_path(other)?,
+    };
+    GATED_ENDPOINTS.contains(&endpoint).then_some(endpoint)
+}
+
+/// `None` when the worker's capabilities are unknown (wildcard).
+fn worker_serves(worker: &dyn Worker, endpoint: Endpoint) -> Option<bool> {
+    let models = worker.models();
+    if models.is_empty() {
+        return None;
+    }
+    Some(models.iter().any(|m| m.supports_endpoint(endpoint)))
+}
+
+/// Return 404 `endpoint_not_supported` when every registered worker has
+/// known capabilities and none of them covers the requested endpoint.
+pub async fn endpoint_capability_middleware(
+    State(worker_registry): State<Arc<WorkerRegistry>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(endpoint) = gated_endpoint(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let workers = worker_registry.get_all();
+    if workers.is_empty() {
+        return next.run(request).await;
+    }
+    for worker in &workers {
+        if worker_serves(worker.as_ref(), endpoint) != Some(false) {
+            return next.run(request).await;
+        }
+    }
+
+    let served: Vec<&str> = GATED_ENDPOINTS
+        .iter()
+        .filter(|e| {
+            workers
+                .iter()
+                .any(|w| worker_serves(w.as_ref(), **e) == Some(true))
+        })
+        .map(|e| e.path())
+        .collect();
+    let path = endpoint.path();
+    let message = if served.is_empty() {
+        format!("No registered worker supports {path}")
+    } else {
+        format!(
+            "No registered worker supports {path}; supported endpoints: {}",
+            served.join(", ")
+        )
+    };
+    error::create_error(StatusCode::NOT_FOUND, "endpoint_not_supported", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware::from_fn_with_state, routing::post, Router};
+    use openai_protocol::{
+        model_card::ModelCard, model_type::ModelType, worker::HealthCheckConfig,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::worker::BasicWorkerBuilder;
+
+    fn app(cards: Vec<Option<ModelCard>>) -> Router {
+        let registry = Arc::new(WorkerRegistry::new());
+        for (i, card) in cards.into_iter().enumerate() {
+            let mut builder = BasicWorkerBuilder::new(format!("http://w{i}:8000")).health_config(
+                HealthCheckConfig {
+                    disable_health_check: true,
+                    ..Default::default()
+                },
+            );
+            if let Some(card) = card {
+                builder = builder.model(card);
+            }
+            registry.register(Arc::new(builder.build()));
+        }
+        Router::new()
+            .route("/v1/rerank", post(|| async { "ok" }))
+            .route("/rerank", post(|| async { "ok" }))
+            .route("/v1/chat/completions", post(|| async { "ok" }))
+            .route("/generate", post(|| async { "ok" }))
+            .layer(from_fn_with_state(registry, endpoint_capability_middleware))
+    }
+
+    async fn status(app: &Router, path: &str) -> StatusCode {
+        app.clone()
+            .oneshot(Request::post(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn rerank_rejected_when_only_chat_models_registered() {
+        let app = app(vec![Some(ModelCard::new("llama"))]);
+
+        assert_eq!(status(&app, "/v1/rerank").await, StatusCode::NOT_FOUND);
+        assert_eq!(status(&app, "/rerank").await, StatusCode::NOT_FOUND);
+        assert_eq!(status(&app, "/v1/chat/completions").await, StatusCode::OK);
+        // No model card advertises /generate, so it is never gated.
+        assert_eq!(status(&app, "/generate").await, StatusCode::OK);
+
+        let response = app
+            .oneshot(Request::post("/v1/rerank").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("endpoint_not_supported"));
+        assert!(body.contains("/v1/chat/completions"));
+    }
+
+    #[tokio::test]
+    async fn unknown_capabilities_pass_through() {
+        // Empty registry: the routers report missing workers.
+        assert_eq!(status(&app(vec![]), "/v1/rerank").await, StatusCode::OK);
+
+        // A wildcard worker may serve anything once its models are discovered.
+        let app = app(vec![Some(ModelCard::new("llama")), None]);
+        assert_eq!(status(&app, "/v1/rerank").await, StatusCode::OK);
+
+        let app = self::app(vec![
+            Some(ModelCard::new("llama")),
+            Some(ModelCard::new("bge").with_model_type(ModelType::RERANK_MODEL)),
+        ]);
+        assert_eq!(status(&app, "/v1/rerank").await, StatusCode::OK);
+    }
+}
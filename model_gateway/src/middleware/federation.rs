@@ -0,0 +1,370 @@
+//! Request signing and deadline propagation between federated gateways.
+//!
+//! A worker registered with `runtime_type: smg` is another smg deployment.
+//! The HTTP router forwards requests to it with an `x-smg-deadline` header
+//! (Unix milliseconds; the earlier of the caller's own deadline and this
+//! gateway's request timeout) and, when `federation.signing_secret` is set,
+//! `x-smg-federation-timestamp` and `x-smg-federation-signature` headers.
+//! The signature is
+//! `v1=<hex HMAC-SHA256 of "{timestamp}\n{method}\n{path}\n{query}\n{deadline}\n{hex SHA-256 of body}">`,
+//! where `query` is the raw query string (empty when there is none).
+//!
+//! On the receiving gateway [`federation_middleware`] verifies signed
+//! requests and enforces their deadline: an expired request is rejected
+//! with 504 before admission, and a live one gets a 504 if no response has
+//! started by the deadline. Unsigned requests pass through untouched unless
+//! `federation.require_signed` is set; only a signed deadline is honored.
+
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+
+use super::signing::hmac_sha256;
+use crate::{config::FederationConfig, observability::metrics::Metrics, routers::error};
+
+pub(crate) static HEADER_DEADLINE: HeaderName = HeaderName::from_static("x-smg-deadline");
+static HEADER_TIMESTAMP: HeaderName = HeaderName::from_static("x-smg-federation-timestamp");
+static HEADER_SIGNATURE: HeaderName = HeaderName::from_static("x-smg-federation-signature");
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn deadline_from(headers: &HeaderMap) -> Option<i64> {
+    headers.get(&HEADER_DEADLINE)?.to_str().ok()?.parse().ok()
+}
+
+/// Signs requests to federated gateways and verifies requests from them.
+#[derive(Clone)]
+pub struct FederationSigner {
+    secret: Option<Arc<[u8]>>,
+    require_signed: bool,
+    max_clock_skew_secs: i64,
+    request_timeout_ms: i64,
+}
+
+impl std::fmt::Debug for FederationSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FederationSigner")
+            .field("signing", &self.secret.is_some())
+            .field("require_signed", &self.require_signed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FederationSigner {
+    pub fn new(config: &FederationConfig, request_timeout_secs: u64) -> Self {
+        Self {
+            secret: config
+                .signing_secret
+                .as_deref()
+                .map(|secret| Arc::from(secret.as_bytes())),
+            require_signed: config.require_signed,
+            max_clock_skew_secs: config.max_clock_skew_secs as i64,
+            request_timeout_ms: request_timeout_secs.saturating_mul(1000) as i64,
+        }
+    }
+
+    /// Whether inbound requests need checking, i.e. a secret is configured.
+    pub fn verifies_inbound(&self) -> bool {
+        self.secret.is_some()
+    }
+
+    /// Headers for a request forwarded to a federated gateway: the
+    /// propagated deadline and, with a secret, the signature over
+    /// `path_and_query` and `body`. `inbound` is the caller's request, whose
+    /// deadline (if any) is kept when it is earlier than this gateway's own
+    /// timeout.
+    pub fn outbound_headers(
+        &self,
+        inbound: Option<&HeaderMap>,
+        method: &Method,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> HeaderMap {
+        let own_deadline = now_ms().saturating_add(self.request_timeout_ms);
+        let deadline = inbound
+            .and_then(deadline_from)
+            .map_or(own_deadline, |d| d.min(own_deadline))
+            .to_string();
+
+        let mut headers = HeaderMap::with_capacity(3);
+        if let Ok(value) = HeaderValue::from_str(&deadline) {
+            headers.insert(HEADER_DEADLINE.clone(), value);
+        }
+        if let Some(secret) = &self.secret {
+            let timestamp = chrono::Utc::now().timestamp().to_string();
+            let (path, query) = path_and_query
+                .split_once('?')
+                .unwrap_or((path_and_query, ""));
+            let signature = sign(
+                secret,
+                &timestamp,
+                method.as_str(),
+                path,
+                query,
+                &deadline,
+                body,
+            );
+            if let (Ok(timestamp), Ok(signature)) = (
+                HeaderValue::from_str(&timestamp),
+                HeaderValue::from_str(&signature),
+            ) {
+                headers.insert(HEADER_TIMESTAMP.clone(), timestamp);
+                headers.insert(HEADER_SIGNATURE.clone(), signature);
+            }
+        }
+        headers
+    }
+
+    /// Check a signed request; returns its deadline, or the rejection reason.
+    fn verify(
+        &self,
+        headers: &HeaderMap,
+        method: &Method,
+        uri: &Uri,
+        body: &[u8],
+    ) -> Result<Option<i64>, &'static str> {
+        let secret = self.secret.as_ref().ok_or("no_secret")?;
+        let timestamp = headers
+            .get(&HEADER_TIMESTAMP)
+            .and_then(|v| v.to_str().ok())
+            .ok_or("missing_timestamp")?;
+        let issued: i64 = timestamp.parse().map_err(|_| "invalid_timestamp")?;
+        if (chrono::Utc::now().timestamp() - issued).abs() > self.max_clock_skew_secs {
+            return Err("stale_timestamp");
+        }
+        let deadline = headers
+            .get(&HEADER_DEADLINE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let expected = sign(
+            secret,
+            timestamp,
+            method.as_str(),
+            uri.path(),
+            uri.query().unwrap_or_default(),
+            deadline,
+            body,
+        );
+        let provided = headers
+            .get(&HEADER_SIGNATURE)
+            .map(HeaderValue::as_bytes)
+            .unwrap_or_default();
+        if !constant_time_eq(expected.as_bytes(), provided) {
+            return Err("invalid_signature");
+        }
+        Ok(deadline_from(headers))
+    }
+}
+
+fn sign(
+    secret: &[u8],
+    timestamp: &str,
+    method: &str,
+    path: &str,
+    query: &str,
+    deadline: &str,
+    body: &[u8],
+) -> String {
+    let mut message = format!("{timestamp}\n{method}\n{path}\n{query}\n{deadline}\n");
+    for byte in Sha256::digest(body) {
+        let _ = write!(message, "{byte:02x}");
+    }
+    let mut signature = String::with_capacity(67);
+    signature.push_str("v1=");
+    for byte in hmac_sha256(secret, message.as_bytes()) {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn unauthorized(reason: &'static str) -> Response {
+    Metrics::record_federation_rejection(reason);
+    error::create_error(
+        StatusCode::UNAUTHORIZED,
+        "invalid_federation_signature",
+        format!("Federation signature rejected: {reason}"),
+    )
+}
+
+fn deadline_exceeded() -> Response {
+    Metrics::record_federation_rejection("deadline_exceeded");
+    error::gateway_timeout(
+        "deadline_exceeded",
+        "The federated request deadline passed before a response started",
+    )
+}
+
+/// Verify federation signatures and enforce signed deadlines.
+pub async fn federation_middleware(
+    State(signer): State<FederationSigner>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !request.headers().contains_key(&HEADER_SIGNATURE) {
+        if signer.require_signed {
+            return unauthorized("unsigned");
+        }
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_request("invalid_request_body", format!("Failed to read body: {e}"))
+        }
+    };
+    let deadline = match signer.verify(&parts.headers, &parts.method, &parts.uri, &bytes) {
+        Ok(deadline) => deadline,
+        Err(reason) => return unauthorized(reason),
+    };
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    let Some(deadline) = deadline else {
+        return next.run(request).await;
+    };
+    let remaining = deadline - now_ms();
+    if remaining <= 0 {
+        return deadline_exceeded();
+    }
+    match tokio::time::timeout(Duration::from_millis(remaining as u64), next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => deadline_exceeded(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware::from_fn_with_state, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn signer(require_signed: bool) -> FederationSigner {
+        FederationSigner::new(
+            &FederationConfig {
+                signing_secret: Some("federation-secret".to_string()),
+                require_signed,
+                ..Default::default()
+            },
+            60,
+        )
+    }
+
+    fn app(signer: FederationSigner) -> Router {
+        Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "ok"
+                }),
+            )
+            .layer(from_fn_with_state(signer, federation_middleware))
+    }
+
+    fn request(headers: HeaderMap, body: &'static str) -> Request<Body> {
+        request_to("/v1/chat/completions", headers, body)
+    }
+
+    fn request_to(uri: &str, headers: HeaderMap, body: &'static str) -> Request<Body> {
+        let mut request = Request::post(uri).body(Body::from(body)).unwrap();
+        *request.headers_mut() = headers;
+        request
+    }
+
+    #[test]
+    fn test_outbound_deadline_keeps_earlier_caller_deadline() {
+        let signer = signer(false);
+        let mut inbound = HeaderMap::new();
+        let caller = now_ms() + 5_000;
+        inbound.insert(&HEADER_DEADLINE, caller.to_string().parse().unwrap());
+        let headers = signer.outbound_headers(Some(&inbound), &Method::POST, "/generate", b"{}");
+        assert_eq!(deadline_from(&headers), Some(caller));
+
+        inbound.insert(&HEADER_DEADLINE, i64::MAX.to_string().parse().unwrap());
+        let headers = signer.outbound_headers(Some(&inbound), &Method::POST, "/generate", b"{}");
+        assert!(deadline_from(&headers).unwrap() <= now_ms() + 60_000);
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_is_verified() {
+        let signer = signer(true);
+        let body = r#"{"model":"m"}"#;
+        let headers =
+            signer.outbound_headers(None, &Method::POST, "/v1/chat/completions", body.as_bytes());
+        let response = app(signer.clone())
+            .oneshot(request(headers.clone(), body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A tampered body no longer matches the signature.
+        let response = app(signer.clone())
+            .oneshot(request(headers, r#"{"model":"other"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app(signer)
+            .oneshot(request(HeaderMap::new(), body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_signature_covers_query() {
+        let signer = signer(true);
+        let headers = signer.outbound_headers(
+            None,
+            &Method::POST,
+            "/v1/chat/completions?api-version=1",
+            b"{}",
+        );
+        let response = app(signer.clone())
+            .oneshot(request_to(
+                "/v1/chat/completions?api-version=1",
+                headers.clone(),
+                "{}",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for uri in ["/v1/chat/completions?api-version=2", "/v1/chat/completions"] {
+            let response = app(signer.clone())
+                .oneshot(request_to(uri, headers.clone(), "{}"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signed_deadline_is_enforced() {
+        let signer = signer(false);
+        let mut inbound = HeaderMap::new();
+        inbound.insert(
+            &HEADER_DEADLINE,
+            (now_ms() + 50).to_string().parse().unwrap(),
+        );
+        let headers =
+            signer.outbound_headers(Some(&inbound), &Method::POST, "/v1/chat/completions", b"{}");
+        let response = app(signer).oneshot(request(headers, "{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+}
@@ -39,6 +39,9 @@ impl<B> MakeSpan<B> for RequestSpan {
             status_code = Empty,
             latency = Empty,
             error = Empty,
+            tags = Empty,
+            features = Empty,
+            experiment = Empty,
             module = "smg"
         );
 
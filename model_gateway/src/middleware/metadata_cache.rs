@@ -0,0 +1,308 @@
+//! Short-TTL response cache with ETag revalidation for metadata endpoints.
+//!
+//! `/v1/models` rebuilds the model list (and may fan out to upstream
+//! providers for BYOK callers) on every call, and SDKs poll it aggressively.
+//! Successful `GET` responses on the wrapped routes are cached per path and
+//! caller credential, tagged with a content-hash `ETag`, and answered with
+//! `304 Not Modified` when the client's `If-None-Match` still matches. Any
+//! worker registry event (register, replace, remove, status change) drops
+//! every entry, so listings never outlive the registry state they describe.
+
+use std::{
+    fmt::Write as _,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use lru::LruCache;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+use crate::{
+    config::MetadataCacheConfig,
+    routers::error,
+    worker::{event::WorkerEvent, WorkerRegistry},
+};
+
+/// Metadata responses are small; anything larger is not worth caching.
+const MAX_CACHED_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    etag: HeaderValue,
+    expires_at: Instant,
+}
+
+impl CachedResponse {
+    fn new(status: StatusCode, mut headers: HeaderMap, body: Bytes, ttl: Duration) -> Self {
+        headers.remove(header::CONTENT_LENGTH);
+        let digest = Sha256::digest(&body);
+        let mut tag = String::with_capacity(34);
+        tag.push('"');
+        for byte in &digest[..16] {
+            let _ = write!(tag, "{byte:02x}");
+        }
+        tag.push('"');
+        Self {
+            status,
+            headers,
+            body,
+            etag: HeaderValue::from_str(&tag).unwrap_or(HeaderValue::from_static("\"\"")),
+            expires_at: Instant::now() + ttl,
+        }
+    }
+
+    fn into_response(self, if_none_match: Option<&HeaderValue>) -> Response {
+        if if_none_match.is_some_and(|value| etag_matches(value, &self.etag)) {
+            return (StatusCode::NOT_MODIFIED, [(header::ETAG, self.etag)]).into_response();
+        }
+        let mut response = (self.status, self.headers, self.body).into_response();
+        response.headers_mut().insert(header::ETAG, self.etag);
+        response
+    }
+}
+
+/// `If-None-Match` is a comma-separated list of (possibly weak) tags or `*`.
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(candidates) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.as_bytes();
+    candidates
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/").as_bytes() == etag)
+}
+
+struct CacheState {
+    entries: LruCache<[u8; 32], CachedResponse>,
+    registry_events: broadcast::Receiver<WorkerEvent>,
+    /// Bumped on every invalidation so a response built before a registry
+    /// change is not inserted after it.
+    generation: u64,
+}
+
+impl CacheState {
+    /// Drain pending registry events; any event (or lag) clears the cache.
+    fn sync_with_registry(&mut self) {
+        let mut changed = false;
+        loop {
+            match self.registry_events.try_recv() {
+                Ok(_) | Err(TryRecvError::Lagged(_)) => changed = true,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        if changed {
+            self.entries.clear();
+            self.generation += 1;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MetadataCache {
+    ttl: Duration,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl MetadataCache {
+    /// `None` when the cache is disabled.
+    pub fn new(config: &MetadataCacheConfig, worker_registry: &WorkerRegistry) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let capacity = NonZeroUsize::new(config.max_entries)?;
+        Some(Self {
+            ttl: Duration::from_secs(config.ttl_secs),
+            state: Arc::new(Mutex::new(CacheState {
+                entries: LruCache::new(capacity),
+                registry_events: worker_registry.subscribe_events(),
+                generation: 0,
+            })),
+        })
+    }
+
+    /// Fresh entry for `key`, plus the generation to pass to [`Self::insert`]
+    /// on a miss.
+    fn lookup(&self, key: &[u8; 32]) -> (Option<CachedResponse>, u64) {
+        let mut state = self.state.lock();
+        state.sync_with_registry();
+        let hit = match state.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.clone()),
+            Some(_) => {
+                state.entries.pop(key);
+                None
+            }
+            None => None,
+        };
+        (hit, state.generation)
+    }
+
+    fn insert(&self, key: [u8; 32], entry: CachedResponse, generation: u64) {
+        let mut state = self.state.lock();
+        state.sync_with_registry();
+        if state.generation == generation {
+            state.entries.put(key, entry);
+        }
+    }
+}
+
+/// Path, query, and caller credential: BYOK model listings differ per key,
+/// and one tenant's cached response must never be served to another.
+fn cache_key(request: &Request<Body>) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or_else(|| request.uri().path());
+    hasher.update(path.as_bytes());
+    for name in [header::AUTHORIZATION.as_str(), "x-api-key"] {
+        hasher.update([0]);
+        if let Some(value) = request.headers().get(name) {
+            hasher.update(value.as_bytes());
+        }
+    }
+    hasher.finalize().into()
+}
+
+/// Serve `GET` requests from the cache, filling it from successful responses.
+pub async fn metadata_cache_middleware(
+    State(cache): State<MetadataCache>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let key = cache_key(&request);
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let (hit, generation) = cache.lookup(&key);
+    if let Some(entry) = hit {
+        return entry.into_response(if_none_match.as_ref());
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_CACHED_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return error::internal_error(
+                "metadata_read_failed",
+                format!("Failed to read metadata response: {e}"),
+            )
+        }
+    };
+
+    let entry = CachedResponse::new(parts.status, parts.headers, body, cache.ttl);
+    cache.insert(key, entry.clone(), generation);
+    entry.into_response(if_none_match.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{middleware::from_fn_with_state, routing::get, Router};
+    use openai_protocol::worker::HealthCheckConfig;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::worker::BasicWorkerBuilder;
+
+    fn app(registry: &WorkerRegistry, ttl_secs: u64) -> (Router, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let cache = MetadataCache::new(
+            &MetadataCacheConfig {
+                ttl_secs,
+                ..Default::default()
+            },
+            registry,
+        )
+        .unwrap();
+        let app = Router::new()
+            .route(
+                "/v1/models",
+                get(move || {
+                    let n = counter.fetch_add(1, Ordering::SeqCst);
+                    async move { format!("models-{n}") }
+                }),
+            )
+            .route_layer(from_fn_with_state(cache, metadata_cache_middleware));
+        (app, calls)
+    }
+
+    async fn get_models(app: &Router, if_none_match: Option<&HeaderValue>) -> Response {
+        let mut request = Request::get("/v1/models");
+        if let Some(tag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, tag);
+        }
+        app.clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_responses_revalidate_with_etag() {
+        let registry = WorkerRegistry::new();
+        let (app, calls) = app(&registry, 60);
+
+        let first = get_models(&app, None).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(header::ETAG).unwrap().clone();
+
+        let second = get_models(&app, None).await;
+        assert_eq!(second.headers().get(header::ETAG), Some(&etag));
+
+        let revalidated = get_models(&app, Some(&etag)).await;
+        assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let stale = HeaderValue::from_static("W/\"other\"");
+        assert_eq!(
+            get_models(&app, Some(&stale)).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn registry_changes_invalidate_entries() {
+        let registry = WorkerRegistry::new();
+        let (app, calls) = app(&registry, 60);
+
+        let etag = get_models(&app, None).await.headers()[header::ETAG].clone();
+        registry.register(Arc::new(
+            BasicWorkerBuilder::new("http://w1:8000")
+                .health_config(HealthCheckConfig {
+                    disable_health_check: true,
+                    ..Default::default()
+                })
+                .build(),
+        ));
+
+        let response = get_models(&app, Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -6,28 +6,62 @@
 
 pub mod auth;
 pub mod concurrency;
+pub mod debug_capture;
+pub mod endpoint_capability;
+pub mod federation;
 pub mod logging;
+pub mod metadata_cache;
 pub mod metrics;
+pub mod partial_transcripts;
+pub mod provenance;
+pub mod request_cancellation;
+pub mod request_coalescing;
+pub mod request_features;
 pub mod request_id;
+pub mod request_tags;
+pub mod routing_rules;
 pub mod scheduler;
+mod signing;
 pub mod storage_context;
+pub mod stream_fanout;
+pub mod stream_integrity;
+mod stream_trailer;
+pub mod target_worker;
 pub mod tenant_resolution;
 pub mod token_bucket;
 pub mod wasm;
+pub mod webhook;
 
 pub use auth::{auth_middleware, deny_all_middleware, AuthConfig};
 pub use concurrency::{
     concurrency_limit_middleware, ConcurrencyLimiter, QueueProcessor, QueuedRequest, TokenGuardBody,
 };
+pub use debug_capture::debug_capture_middleware;
+pub use endpoint_capability::endpoint_capability_middleware;
+pub use federation::{federation_middleware, FederationSigner};
 pub use logging::{create_logging_layer, RequestLogger, RequestSpan, ResponseLogger};
+pub use metadata_cache::{metadata_cache_middleware, MetadataCache};
 pub use metrics::{HttpMetricsLayer, HttpMetricsMiddleware};
+pub use partial_transcripts::{partial_transcripts_middleware, PartialTranscripts};
+pub use provenance::{provenance_middleware, ProvenanceSigner, ServingWorker};
+pub use request_cancellation::{
+    cancelled_response, request_cancellation_middleware, CancelledRequest, RequestCancellation,
+};
+pub use request_coalescing::{request_coalescing_middleware, RequestCoalescer};
+pub use request_features::{request_features_middleware, RequestFeatureGate, RequestFeatures};
 pub use request_id::{RequestId, RequestIdLayer, RequestIdMiddleware};
+pub use request_tags::{request_tags_middleware, RequestTagger, RequestTags};
+pub use routing_rules::{routing_rules_middleware, RoutingRules, RoutingRulesError};
 pub use storage_context::storage_context_middleware;
+pub use stream_fanout::{stream_fanout_middleware, StreamFanout};
+pub use stream_integrity::stream_integrity_middleware;
+pub use target_worker::{target_worker_middleware, TargetWorkerState};
 pub use tenant_resolution::{
     ordinary_tenant_resolution_middleware, route_request_meta_middleware, TenantResolutionState,
 };
 pub use token_bucket::TokenBucket;
 pub use wasm::wasm_middleware;
+pub use webhook::{webhook_middleware, WebhookDispatcher};
 
 pub use crate::tenant::{
     resolve_admin_target_tenant_id, resolve_admin_target_tenant_key, DataPlaneCaller,
@@ -37,3 +71,27 @@ pub use crate::tenant::{
 /// Backward-compatible alias for the older tenant metadata name used in a few
 /// router-path tests and plumbing call sites.
 pub type TenantRequestMeta = RouteRequestMeta;
+
+/// Whether a response is a server-sent event stream.
+pub(crate) fn is_event_stream(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"))
+}
+
+/// Whether a response is streamed (SSE / NDJSON / chunked) and must not be
+/// buffered.
+pub(crate) fn is_streaming_response(headers: &axum::http::HeaderMap) -> bool {
+    use axum::http::header;
+
+    is_event_stream(headers)
+        || headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("application/x-ndjson"))
+        || headers
+            .get(header::TRANSFER_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|te| te.contains("chunked"))
+}
@@ -0,0 +1,521 @@
+//! Persistence of partial transcripts for streams that do not finish.
+//!
+//! When `partial_transcripts.enabled` is set, every SSE response on the
+//! serving routes is decoded as it is relayed to collect the generated text
+//! and token counts. A stream that runs to its completion marker leaves
+//! nothing behind. A stream the client abandons, so that its body is dropped
+//! early, is stored with status `cancelled`. One that ends without a
+//! completion marker or carries an error event is stored as `failed`.
+//!
+//! Transcripts go to the response storage under the request id and are read
+//! back by the tenant that sent the request with
+//! `GET /v1/transcripts/{request_id}`. Token counts come from the stream's
+//! own usage reports; without one, the completion count is the number of
+//! chunks that carried text and is flagged as estimated.
+
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, BodyDataStream, Bytes},
+    extract::{Extension, Path, Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use futures::Stream;
+use serde_json::{json, Value};
+use smg_data_connector::{ResponseId, ResponseStorage, StoredResponse};
+use tracing::{debug, warn};
+
+use super::{is_event_stream, request_id::RequestId, RouteRequestMeta, TenantKey};
+use crate::{
+    config::PartialTranscriptsConfig,
+    observability::metrics::Metrics,
+    routers::{common::sse::SseDecoder, error},
+};
+
+/// `object` of a stored transcript, telling it apart from stored responses.
+const TRANSCRIPT_OBJECT: &str = "transcript";
+
+#[derive(Clone)]
+pub struct PartialTranscripts {
+    storage: Arc<dyn ResponseStorage>,
+    max_output_bytes: usize,
+}
+
+impl PartialTranscripts {
+    /// `None` when partial transcripts are disabled.
+    pub fn new(
+        config: &PartialTranscriptsConfig,
+        storage: Arc<dyn ResponseStorage>,
+    ) -> Option<Self> {
+        config.enabled.then(|| Self {
+            storage,
+            max_output_bytes: config.max_output_bytes,
+        })
+    }
+}
+
+/// Text and token counts collected from one stream.
+#[derive(Debug, Default)]
+pub(super) struct Transcript {
+    model: Option<String>,
+    response_id: Option<String>,
+    output: String,
+    truncated: bool,
+    text_chunks: u64,
+    prompt_tokens: Option<u64>,
+    completion_tokens: Option<u64>,
+    completed: bool,
+    error: Option<String>,
+}
+
+impl Transcript {
+    fn append(&mut self, text: &str, max_bytes: usize) {
+        if text.is_empty() {
+            return;
+        }
+        self.text_chunks += 1;
+        let room = max_bytes.saturating_sub(self.output.len());
+        if text.len() <= room {
+            self.output.push_str(text);
+            return;
+        }
+        let mut end = room;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.output.push_str(&text[..end]);
+        self.truncated = true;
+    }
+
+    /// Fold one SSE frame of any of the streaming formats the gateway serves
+    /// (chat, completions, SGLang generate, Responses, Anthropic Messages).
+    pub(super) fn observe(&mut self, event: Option<&str>, data: &str, max_bytes: usize) {
+        if data == "[DONE]" {
+            self.completed = true;
+            return;
+        }
+        let Ok(frame) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        let kind = event.or_else(|| frame.get("type").and_then(Value::as_str));
+
+        if let Some(error) = frame.get("error").filter(|e| !e.is_null()) {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            self.error = Some(message);
+        }
+        if self.model.is_none() {
+            self.model = frame
+                .get("model")
+                .or_else(|| frame.pointer("/message/model"))
+                .or_else(|| frame.pointer("/response/model"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        if self.response_id.is_none() {
+            self.response_id = frame
+                .pointer("/response/id")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        match kind {
+            Some("response.output_text.delta") => {
+                if let Some(delta) = frame.get("delta").and_then(Value::as_str) {
+                    self.append(delta, max_bytes);
+                }
+            }
+            Some("response.completed") => {
+                self.completed = true;
+                self.read_usage(frame.pointer("/response/usage"));
+            }
+            Some("response.failed") => {
+                self.error
+                    .get_or_insert_with(|| "response failed".to_string());
+            }
+            Some("content_block_delta") => {
+                if let Some(text) = frame.pointer("/delta/text").and_then(Value::as_str) {
+                    self.append(text, max_bytes);
+                }
+            }
+            Some("message_start") => self.read_usage(frame.pointer("/message/usage")),
+            Some("message_delta") => self.read_usage(frame.get("usage")),
+            Some("message_stop") => self.completed = true,
+            _ => {
+                for choice in frame
+                    .get("choices")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    let text = choice
+                        .pointer("/delta/content")
+                        .or_else(|| choice.get("text"))
+                        .and_then(Value::as_str);
+                    if let Some(text) = text {
+                        self.append(text, max_bytes);
+                    }
+                }
+                self.read_usage(frame.get("usage"));
+                // SGLang `/generate` streams the cumulative text.
+                if let Some(meta) = frame.get("meta_info") {
+                    if let Some(text) = frame.get("text").and_then(Value::as_str) {
+                        self.output.clear();
+                        self.text_chunks = 0;
+                        self.truncated = false;
+                        self.append(text, max_bytes);
+                    }
+                    self.read_usage(Some(meta));
+                    if meta
+                        .get("finish_reason")
+                        .is_some_and(|reason| !reason.is_null())
+                    {
+                        self.completed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read OpenAI (`prompt_tokens`/`completion_tokens`) or Responses and
+    /// Anthropic (`input_tokens`/`output_tokens`) usage.
+    fn read_usage(&mut self, usage: Option<&Value>) {
+        let Some(usage) = usage.filter(|u| u.is_object()) else {
+            return;
+        };
+        if let Some(prompt) = usage
+            .get("prompt_tokens")
+            .or_else(|| usage.get("input_tokens"))
+            .and_then(Value::as_u64)
+        {
+            self.prompt_tokens = Some(prompt);
+        }
+        if let Some(completion) = usage
+            .get("completion_tokens")
+            .or_else(|| usage.get("output_tokens"))
+            .and_then(Value::as_u64)
+        {
+            self.completion_tokens = Some(completion);
+        }
+    }
+
+    /// Token counts so far. Without a usage report from the stream the
+    /// completion count is the number of text chunks, flagged as estimated.
+    pub(super) fn usage(&self) -> Value {
+        json!({
+            "prompt_tokens": self.prompt_tokens,
+            "completion_tokens": self.completion_tokens.unwrap_or(self.text_chunks),
+            "estimated": self.completion_tokens.is_none(),
+        })
+    }
+
+    /// Id of the Responses API response the stream belongs to, once seen.
+    pub(super) fn response_id(&self) -> Option<&str> {
+        self.response_id.as_deref()
+    }
+
+    /// `None` when the stream completed and there is nothing to keep.
+    fn status(&self, body_finished: bool) -> Option<&'static str> {
+        if self.error.is_some() {
+            Some("failed")
+        } else if !body_finished {
+            Some("cancelled")
+        } else if !self.completed {
+            Some("failed")
+        } else {
+            None
+        }
+    }
+}
+
+/// Decodes a relayed stream and stores its transcript if it did not finish.
+struct Recorder {
+    transcripts: PartialTranscripts,
+    request_id: String,
+    tenant_key: TenantKey,
+    endpoint: String,
+    decoder: SseDecoder,
+    transcript: Transcript,
+}
+
+impl Recorder {
+    fn push(&mut self, bytes: &[u8]) {
+        if self.decoder.push(bytes).is_err() {
+            return;
+        }
+        while let Some(frame) = self.decoder.next_frame() {
+            if let Ok(frame) = frame {
+                self.transcript.observe(
+                    frame.event_type.as_deref(),
+                    &frame.data,
+                    self.transcripts.max_output_bytes,
+                );
+            }
+        }
+        self.decoder.compact();
+    }
+
+    fn finish(self, body_finished: bool) {
+        let Some(status) = self.transcript.status(body_finished) else {
+            return;
+        };
+        let transcript = self.transcript;
+        let usage = transcript.usage();
+        let raw_response = json!({
+            "id": self.request_id,
+            "object": TRANSCRIPT_OBJECT,
+            "status": status,
+            "endpoint": self.endpoint,
+            "model": transcript.model,
+            "created_at": Utc::now().timestamp(),
+            "output_text": transcript.output,
+            "truncated": transcript.truncated,
+            "usage": usage,
+            "error": transcript.error,
+        });
+        let stored = StoredResponse {
+            id: ResponseId::from(self.request_id.as_str()),
+            model: transcript.model,
+            raw_response,
+            tenant_key: Some(self.tenant_key.to_string()),
+            ..StoredResponse::new(None)
+        };
+        Metrics::record_partial_transcript(status);
+        let storage = self.transcripts.storage;
+        let request_id = self.request_id;
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "a single storage write; losing it on shutdown only loses a partial transcript"
+        )]
+        tokio::spawn(async move {
+            match storage.store_response(stored).await {
+                Ok(_) => debug!(request_id, status, "Stored partial transcript"),
+                Err(e) => warn!(request_id, "Failed to store partial transcript: {e}"),
+            }
+        });
+    }
+}
+
+/// Relayed body that feeds the recorder and settles it when the stream ends
+/// or the body is dropped.
+struct TranscriptBody {
+    inner: BodyDataStream,
+    recorder: Option<Recorder>,
+}
+
+impl Stream for TranscriptBody {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let polled = Pin::new(&mut this.inner).poll_next(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(bytes))) => {
+                if let Some(recorder) = this.recorder.as_mut() {
+                    recorder.push(bytes);
+                }
+            }
+            Poll::Ready(Some(Err(e))) => {
+                if let Some(recorder) = this.recorder.as_mut() {
+                    recorder.transcript.error = Some(e.to_string());
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(recorder) = this.recorder.take() {
+                    recorder.finish(true);
+                }
+            }
+            Poll::Pending => {}
+        }
+        polled
+    }
+}
+
+impl Drop for TranscriptBody {
+    fn drop(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish(false);
+        }
+    }
+}
+
+/// Record SSE responses of serving requests and keep the transcripts of
+/// those that do not finish.
+pub async fn partial_transcripts_middleware(
+    State(transcripts): State<PartialTranscripts>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let tenant_key = request
+        .extensions()
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().clone());
+    let endpoint = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    let (Some(request_id), Some(tenant_key)) = (request_id, tenant_key) else {
+        return response;
+    };
+    if !response.status().is_success() || !is_event_stream(response.headers()) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = TranscriptBody {
+        inner: body.into_data_stream(),
+        recorder: Some(Recorder {
+            transcripts,
+            request_id,
+            tenant_key,
+            endpoint,
+            decoder: SseDecoder::new(),
+            transcript: Transcript::default(),
+        }),
+    };
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+/// `GET /v1/transcripts/{request_id}`: the stored partial transcript of one
+/// of the caller's requests.
+pub async fn get_transcript(
+    State(transcripts): State<PartialTranscripts>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(request_id): Path<String>,
+) -> Response {
+    let not_found = || {
+        error::not_found(
+            "transcript_not_found",
+            format!("No partial transcript for request '{request_id}'"),
+        )
+    };
+    let stored = match transcripts
+        .storage
+        .get_response(&ResponseId::from(request_id.as_str()))
+        .await
+    {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return error::internal_error("transcript_storage_error", e.to_string());
+        }
+    };
+    let owned = stored.tenant_key.as_deref() == Some(meta.tenant_key().as_str());
+    let is_transcript = stored.raw_response.get("object") == Some(&json!(TRANSCRIPT_OBJECT));
+    if !(owned && is_transcript) {
+        return not_found();
+    }
+    Json(stored.raw_response).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observe_all(transcript: &mut Transcript, frames: &[(Option<&str>, &str)]) {
+        for (event, data) in frames {
+            transcript.observe(*event, data, 1024);
+        }
+    }
+
+    #[test]
+    fn chat_stream_without_done_is_cancelled_or_failed() {
+        let mut transcript = Transcript::default();
+        observe_all(
+            &mut transcript,
+            &[
+                (
+                    None,
+                    r#"{"model":"llama","choices":[{"delta":{"content":"Hel"}}]}"#,
+                ),
+                (None, r#"{"choices":[{"delta":{"content":"lo"}}]}"#),
+            ],
+        );
+        assert_eq!(transcript.output, "Hello");
+        assert_eq!(transcript.model.as_deref(), Some("llama"));
+        assert_eq!(transcript.text_chunks, 2);
+        assert_eq!(transcript.status(false), Some("cancelled"));
+        assert_eq!(transcript.status(true), Some("failed"));
+
+        observe_all(
+            &mut transcript,
+            &[
+                (
+                    None,
+                    r#"{"choices":[],"usage":{"prompt_tokens":9,"completion_tokens":2}}"#,
+                ),
+                (None, "[DONE]"),
+            ],
+        );
+        assert_eq!(transcript.completion_tokens, Some(2));
+        assert_eq!(transcript.status(true), None);
+    }
+
+    #[test]
+    fn responses_and_messages_events_are_understood() {
+        let mut responses = Transcript::default();
+        observe_all(
+            &mut responses,
+            &[
+                (
+                    Some("response.created"),
+                    r#"{"type":"response.created","response":{"id":"resp_1"}}"#,
+                ),
+                (
+                    Some("response.output_text.delta"),
+                    r#"{"type":"response.output_text.delta","delta":"Hi"}"#,
+                ),
+                (
+                    Some("response.failed"),
+                    r#"{"type":"response.failed","response":{}}"#,
+                ),
+            ],
+        );
+        assert_eq!(responses.output, "Hi");
+        assert_eq!(responses.response_id(), Some("resp_1"));
+        assert_eq!(responses.status(true), Some("failed"));
+
+        let mut messages = Transcript::default();
+        observe_all(
+            &mut messages,
+            &[
+                (
+                    Some("message_start"),
+                    r#"{"message":{"model":"claude","usage":{"input_tokens":5}}}"#,
+                ),
+                (
+                    Some("content_block_delta"),
+                    r#"{"delta":{"type":"text_delta","text":"Hey"}}"#,
+                ),
+            ],
+        );
+        assert_eq!(messages.output, "Hey");
+        assert_eq!(messages.prompt_tokens, Some(5));
+        assert_eq!(messages.status(false), Some("cancelled"));
+    }
+
+    #[test]
+    fn output_is_truncated_on_a_char_boundary() {
+        let mut transcript = Transcript::default();
+        transcript.append("aé", 2);
+        assert_eq!(transcript.output, "a");
+        assert!(transcript.truncated);
+    }
+}
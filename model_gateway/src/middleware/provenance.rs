@@ -0,0 +1,375 @@
+//! Signed provenance metadata on inference responses.
+//!
+//! When `provenance.enabled` is set, successful responses from the
+//! generation routes identify the model that produced them and the gateway
+//! that served them, together with a SHA-256 digest of the response body
+//! signed with HMAC-SHA256. The router reports the serving worker through a
+//! [`ServingWorker`] response extension; its model version and hash come
+//! from the worker's `model_version` (or discovered `weight_version`) and
+//! `model_hash` labels.
+//!
+//! Non-streaming responses carry the record in `x-smg-provenance-*`
+//! headers. Streams carry the model and gateway headers up front and end
+//! with an `smg.provenance` SSE event whose digest covers every byte
+//! streamed before it. The signature is
+//! `v1=<hex HMAC-SHA256 of "{timestamp}\n{digest}\n{model}\n{model_version}\n{model_hash}\n{gateway}">`,
+//! with empty strings for unknown fields.
+
+use std::{fmt::Write as _, sync::Arc};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::{
+    is_event_stream,
+    signing::hmac_sha256,
+    stream_trailer::{self, StreamDigest},
+};
+use crate::{config::ProvenanceConfig, routers::error, version, worker::Worker};
+
+static HEADER_MODEL: HeaderName = HeaderName::from_static("x-smg-provenance-model");
+static HEADER_MODEL_VERSION: HeaderName = HeaderName::from_static("x-smg-provenance-model-version");
+static HEADER_MODEL_HASH: HeaderName = HeaderName::from_static("x-smg-provenance-model-hash");
+static HEADER_GATEWAY: HeaderName = HeaderName::from_static("x-smg-provenance-gateway");
+static HEADER_TIMESTAMP: HeaderName = HeaderName::from_static("x-smg-provenance-timestamp");
+static HEADER_DIGEST: HeaderName = HeaderName::from_static("x-smg-provenance-digest");
+static HEADER_SIGNATURE: HeaderName = HeaderName::from_static("x-smg-provenance-signature");
+static HEADER_KEY_ID: HeaderName = HeaderName::from_static("x-smg-provenance-key-id");
+
+/// The worker that produced a response, attached by the router as a
+/// response extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServingWorker {
+    pub model: String,
+    pub model_version: Option<String>,
+    pub model_hash: Option<String>,
+}
+
+impl ServingWorker {
+    pub fn new(worker: &dyn Worker, model_id: &str) -> Self {
+        let labels = &worker.metadata().spec.labels;
+        Self {
+            model: model_id.to_string(),
+            model_version: labels
+                .get("model_version")
+                .or_else(|| labels.get("weight_version"))
+                .cloned(),
+            model_hash: labels.get("model_hash").cloned(),
+        }
+    }
+}
+
+/// One signed provenance record.
+#[derive(Debug, Clone, Serialize)]
+struct ProvenanceRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model_hash: Option<String>,
+    gateway: &'static str,
+    timestamp: i64,
+    digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_id: Option<String>,
+    signature: String,
+}
+
+fn gateway() -> &'static str {
+    static GATEWAY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    GATEWAY.get_or_init(|| format!("smg/{}", version::VERSION))
+}
+
+#[derive(Clone)]
+pub struct ProvenanceSigner {
+    key: Arc<[u8]>,
+    key_id: Option<String>,
+}
+
+impl ProvenanceSigner {
+    /// `None` when provenance is disabled.
+    pub fn new(config: &ProvenanceConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let key = config.signing_key.as_deref()?;
+        Some(Self {
+            key: Arc::from(key.as_bytes()),
+            key_id: config.key_id.clone(),
+        })
+    }
+
+    fn record(&self, worker: Option<&ServingWorker>, digest: [u8; 32]) -> ProvenanceRecord {
+        let hex = stream_trailer::format_sha256(digest);
+        let timestamp = chrono::Utc::now().timestamp();
+        let model = worker.map(|w| w.model.clone());
+        let model_version = worker.and_then(|w| w.model_version.clone());
+        let model_hash = worker.and_then(|w| w.model_hash.clone());
+        let message = format!(
+            "{timestamp}\n{hex}\n{}\n{}\n{}\n{}",
+            model.as_deref().unwrap_or_default(),
+            model_version.as_deref().unwrap_or_default(),
+            model_hash.as_deref().unwrap_or_default(),
+            gateway()
+        );
+        let mut signature = String::with_capacity(67);
+        signature.push_str("v1=");
+        for byte in hmac_sha256(&self.key, message.as_bytes()) {
+            let _ = write!(signature, "{byte:02x}");
+        }
+        ProvenanceRecord {
+            model,
+            model_version,
+            model_hash,
+            gateway: gateway(),
+            timestamp,
+            digest: hex,
+            key_id: self.key_id.clone(),
+            signature,
+        }
+    }
+}
+
+fn insert(headers: &mut HeaderMap, name: &HeaderName, value: Option<&str>) {
+    if let Some(value) = value.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers.insert(name.clone(), value);
+    }
+}
+
+/// Model and gateway headers, known before the body is read.
+fn insert_identity(headers: &mut HeaderMap, worker: Option<&ServingWorker>) {
+    insert(headers, &HEADER_MODEL, worker.map(|w| w.model.as_str()));
+    insert(
+        headers,
+        &HEADER_MODEL_VERSION,
+        worker.and_then(|w| w.model_version.as_deref()),
+    );
+    insert(
+        headers,
+        &HEADER_MODEL_HASH,
+        worker.and_then(|w| w.model_hash.as_deref()),
+    );
+    insert(headers, &HEADER_GATEWAY, Some(gateway()));
+}
+
+/// Hashes a stream as it passes and signs it at the end.
+struct StreamProvenance {
+    hasher: Sha256,
+    signer: ProvenanceSigner,
+    worker: Option<ServingWorker>,
+}
+
+impl StreamDigest for StreamProvenance {
+    fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    fn trailer(self) -> Bytes {
+        let record = self
+            .signer
+            .record(self.worker.as_ref(), self.hasher.finalize().into());
+        stream_trailer::trailer_event("smg.provenance", &record)
+    }
+}
+
+/// Attach provenance metadata to successful generation responses.
+pub async fn provenance_middleware(
+    State(signer): State<ProvenanceSigner>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !stream_trailer::is_generation_request(&request) {
+        return next.run(request).await;
+    }
+    let response = next.run(request).await;
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let worker = parts.extensions.get::<ServingWorker>().cloned();
+    insert_identity(&mut parts.headers, worker.as_ref());
+
+    if is_event_stream(&parts.headers) {
+        let digest = StreamProvenance {
+            hasher: Sha256::new(),
+            signer,
+            worker,
+        };
+        return Response::from_parts(parts, stream_trailer::append_trailer(body, digest));
+    }
+
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_gateway(
+                "upstream_body_error",
+                format!("Failed to read response body: {e}"),
+            )
+        }
+    };
+    let record = signer.record(worker.as_ref(), Sha256::digest(&bytes).into());
+    let timestamp = record.timestamp.to_string();
+    insert(&mut parts.headers, &HEADER_TIMESTAMP, Some(&timestamp));
+    insert(&mut parts.headers, &HEADER_DIGEST, Some(&record.digest));
+    insert(
+        &mut parts.headers,
+        &HEADER_SIGNATURE,
+        Some(&record.signature),
+    );
+    insert(&mut parts.headers, &HEADER_KEY_ID, record.key_id.as_deref());
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        http::{header, Method, StatusCode},
+        routing::post,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn signer() -> ProvenanceSigner {
+        ProvenanceSigner::new(&ProvenanceConfig {
+            enabled: true,
+            signing_key: Some("provenance-secret".to_string()),
+            key_id: Some("k1".to_string()),
+        })
+        .unwrap()
+    }
+
+    fn worker() -> ServingWorker {
+        ServingWorker {
+            model: "llama".to_string(),
+            model_version: Some("2026-09-01".to_string()),
+            model_hash: None,
+        }
+    }
+
+    fn app(stream: bool) -> Router {
+        Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(move || async move {
+                    let mut response = if stream {
+                        Response::builder()
+                            .header(header::CONTENT_TYPE, "text/event-stream")
+                            .body(Body::from("data: {\"x\":1}\n\ndata: [DONE]\n\n"))
+                            .unwrap()
+                    } else {
+                        Response::new(Body::from("{\"x\":1}"))
+                    };
+                    response.extensions_mut().insert(worker());
+                    response
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                signer(),
+                provenance_middleware,
+            ))
+    }
+
+    fn request() -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/v1/chat/completions")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn expected_signature(timestamp: &str, body: &[u8]) -> (String, String) {
+        let digest: String = Sha256::digest(body)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let digest = format!("sha256:{digest}");
+        let message = format!("{timestamp}\n{digest}\nllama\n2026-09-01\n\n{}", gateway());
+        let mac: String = hmac_sha256(b"provenance-secret", message.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        (digest, format!("v1={mac}"))
+    }
+
+    #[tokio::test]
+    async fn test_non_streaming_response_is_signed() {
+        let response = app(false).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers().clone();
+        assert_eq!(headers[&HEADER_MODEL], "llama");
+        assert_eq!(headers[&HEADER_MODEL_VERSION], "2026-09-01");
+        assert!(!headers.contains_key(&HEADER_MODEL_HASH));
+        assert_eq!(headers[&HEADER_GATEWAY], gateway());
+        assert_eq!(headers[&HEADER_KEY_ID], "k1");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"{\"x\":1}");
+        let (digest, signature) =
+            expected_signature(headers[&HEADER_TIMESTAMP].to_str().unwrap(), &body);
+        assert_eq!(headers[&HEADER_DIGEST], digest.as_str());
+        assert_eq!(headers[&HEADER_SIGNATURE], signature.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_with_provenance_event() {
+        let response = app(true).oneshot(request()).await.unwrap();
+        assert_eq!(response.headers()[&HEADER_MODEL], "llama");
+        assert!(!response.headers().contains_key(&HEADER_SIGNATURE));
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = std::str::from_utf8(&body).unwrap();
+        let (streamed, trailer) = body.split_once("event: smg.provenance\n").unwrap();
+        assert_eq!(streamed, "data: {\"x\":1}\n\ndata: [DONE]\n\n");
+        let record: serde_json::Value = serde_json::from_str(
+            trailer
+                .strip_prefix("data: ")
+                .unwrap()
+                .trim_end_matches('\n'),
+        )
+        .unwrap();
+        let (digest, signature) =
+            expected_signature(&record["timestamp"].to_string(), streamed.as_bytes());
+        assert_eq!(record["digest"], digest.as_str());
+        assert_eq!(record["signature"], signature.as_str());
+        assert_eq!(record["model"], "llama");
+        assert_eq!(record["key_id"], "k1");
+    }
+
+    #[tokio::test]
+    async fn test_other_routes_and_errors_are_untouched() {
+        let app = Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(|| async { error::bad_request("bad", "nope") }),
+            )
+            .route("/v1/conversations", post(|| async { "{}" }))
+            .layer(axum::middleware::from_fn_with_state(
+                signer(),
+                provenance_middleware,
+            ));
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert!(!response.headers().contains_key(&HEADER_GATEWAY));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/v1/conversations")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(&HEADER_GATEWAY));
+    }
+}
@@ -0,0 +1,324 @@
+//! Explicit cancellation of in-flight requests.
+//!
+//! Every serving request is registered under its request id (the
+//! `x-request-id` response header) for as long as it runs, including while
+//! its SSE body is being relayed. `POST /v1/responses/{id}/cancel`,
+//! `DELETE /v1/responses/{id}` and `POST /v1/chat/completions/{id}/cancel`
+//! look the id up for the caller's tenant and cancel it:
+//!
+//! - Before the response exists the handler future is dropped, which drops
+//!   the upstream HTTP request or gRPC stream (the gRPC clients send the
+//!   abort RPC when their stream is dropped). The original caller receives
+//!   a `499` `request_cancelled` error.
+//! - A relayed stream ends at the next chunk boundary and its upstream is
+//!   dropped the same way. Layered outside partial transcripts, so a
+//!   cancelled stream's transcript is stored with status `cancelled`.
+//!
+//! The canceller gets the usage observed so far: the stream's own usage
+//! report when it sent one, otherwise the number of text chunks relayed,
+//! flagged as estimated.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dashmap::{mapref::entry::Entry, DashMap};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::{
+    is_event_stream, partial_transcripts::Transcript, request_id::RequestId, RouteRequestMeta,
+    TenantKey,
+};
+use crate::routers::{common::sse::SseDecoder, error};
+
+/// nginx's "client closed request", as the scheduler uses for callers that
+/// go away while queued.
+const STATUS_CLIENT_CLOSED_REQUEST: u16 = 499;
+
+struct InflightRequest {
+    tenant_key: TenantKey,
+    token: CancellationToken,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+/// Registry of cancellable in-flight requests, keyed by request id.
+/// Responses API streams can also be found by the response id they report.
+#[derive(Clone, Default)]
+pub struct RequestCancellation {
+    requests: Arc<DashMap<String, InflightRequest>>,
+    response_ids: Arc<DashMap<String, String>>,
+}
+
+/// What a cancelled request had produced.
+pub struct CancelledRequest {
+    /// Token counts so far; estimated when the stream reported none.
+    pub usage: Value,
+    /// Id of the stored Responses API response, if the stream reported one.
+    pub response_id: Option<String>,
+}
+
+/// Unregisters the request when the request (or its relayed body) is done.
+struct Registration {
+    requests: Arc<DashMap<String, InflightRequest>>,
+    response_ids: Arc<DashMap<String, String>>,
+    request_id: String,
+    response_id: Option<String>,
+    token: CancellationToken,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl Registration {
+    /// Make the request cancellable by the response id its stream reports.
+    fn observe_response_id(&mut self) {
+        if self.response_id.is_some() {
+            return;
+        }
+        let Some(response_id) = self.transcript.lock().response_id().map(str::to_string) else {
+            return;
+        };
+        if let Entry::Vacant(slot) = self.response_ids.entry(response_id.clone()) {
+            slot.insert(self.request_id.clone());
+            self.response_id = Some(response_id);
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(response_id) = &self.response_id {
+            self.response_ids.remove(response_id);
+        }
+        self.requests.remove(&self.request_id);
+    }
+}
+
+impl RequestCancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an in-flight request. A request id that is already in
+    /// flight (e.g. a client-supplied id reused concurrently) is not
+    /// registered again, so one caller can never cancel another's request.
+    fn register(&self, request_id: String, tenant_key: TenantKey) -> Option<Registration> {
+        let Entry::Vacant(slot) = self.requests.entry(request_id.clone()) else {
+            return None;
+        };
+        let token = CancellationToken::new();
+        let transcript = Arc::new(Mutex::new(Transcript::default()));
+        slot.insert(InflightRequest {
+            tenant_key,
+            token: token.clone(),
+            transcript: transcript.clone(),
+        });
+        Some(Registration {
+            requests: self.requests.clone(),
+            response_ids: self.response_ids.clone(),
+            request_id,
+            response_id: None,
+            token,
+            transcript,
+        })
+    }
+
+    /// Cancel the in-flight request `id` of `tenant_key`, by request id or
+    /// by response id. Requests of other tenants are reported as not in
+    /// flight.
+    pub fn cancel(&self, id: &str, tenant_key: &TenantKey) -> Option<CancelledRequest> {
+        let request_id = self
+            .response_ids
+            .get(id)
+            .map_or_else(|| id.to_string(), |request_id| request_id.clone());
+        let request = self.requests.get(&request_id)?;
+        if &request.tenant_key != tenant_key {
+            return None;
+        }
+        request.token.cancel();
+        info!(request_id = %request_id, "Cancelled in-flight request");
+        let transcript = request.transcript.lock();
+        Some(CancelledRequest {
+            usage: transcript.usage(),
+            response_id: transcript.response_id().map(str::to_string),
+        })
+    }
+}
+
+/// Body of a successful cancellation: the request's id, an `object` in the
+/// endpoint's vocabulary, status `cancelled` and the partial usage.
+pub fn cancelled_response(request_id: &str, object: &str, usage: Value) -> Response {
+    Json(json!({
+        "id": request_id,
+        "object": object,
+        "status": "cancelled",
+        "usage": usage,
+    }))
+    .into_response()
+}
+
+/// Register serving requests for cancellation and unwind the ones that get
+/// cancelled.
+pub async fn request_cancellation_middleware(
+    State(cancellation): State<RequestCancellation>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let tenant_key = request
+        .extensions()
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().clone());
+    let (Some(request_id), Some(tenant_key)) = (request_id, tenant_key) else {
+        return next.run(request).await;
+    };
+    let Some(mut registration) = cancellation.register(request_id, tenant_key) else {
+        return next.run(request).await;
+    };
+
+    let token = registration.token.clone();
+    let response = tokio::select! {
+        response = next.run(request) => response,
+        () = token.cancelled() => {
+            debug!(request_id = %registration.request_id, "Request cancelled before response");
+            return error::create_error(
+                StatusCode::from_u16(STATUS_CLIENT_CLOSED_REQUEST)
+                    .unwrap_or(StatusCode::REQUEST_TIMEOUT),
+                "request_cancelled",
+                format!("Request '{}' was cancelled", registration.request_id),
+            );
+        }
+    };
+    if !response.status().is_success() || !is_event_stream(response.headers()) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let mut decoder = SseDecoder::new();
+    let body = body
+        .into_data_stream()
+        .take_until(token.cancelled_owned())
+        .inspect(move |chunk| {
+            let Ok(bytes) = chunk else {
+                return;
+            };
+            if decoder.push(bytes).is_err() {
+                return;
+            }
+            {
+                let mut transcript = registration.transcript.lock();
+                while let Some(frame) = decoder.next_frame() {
+                    if let Ok(frame) = frame {
+                        transcript.observe(frame.event_type.as_deref(), &frame.data, 0);
+                    }
+                }
+            }
+            decoder.compact();
+            registration.observe_response_id();
+        });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_is_scoped_to_the_tenant() {
+        let cancellation = RequestCancellation::new();
+        let registration = cancellation
+            .register("chatcmpl-1".to_string(), TenantKey::from("auth:a"))
+            .unwrap();
+
+        assert!(cancellation
+            .cancel("chatcmpl-1", &TenantKey::from("auth:b"))
+            .is_none());
+        assert!(!registration.token.is_cancelled());
+        assert!(cancellation
+            .cancel("chatcmpl-2", &TenantKey::from("auth:a"))
+            .is_none());
+
+        let usage = cancellation
+            .cancel("chatcmpl-1", &TenantKey::from("auth:a"))
+            .unwrap()
+            .usage;
+        assert!(registration.token.is_cancelled());
+        assert_eq!(usage["completion_tokens"], 0);
+        assert_eq!(usage["estimated"], true);
+    }
+
+    #[test]
+    fn registration_is_unique_and_removed_on_drop() {
+        let cancellation = RequestCancellation::new();
+        let registration = cancellation
+            .register("resp-1".to_string(), TenantKey::from("auth:a"))
+            .unwrap();
+        assert!(cancellation
+            .register("resp-1".to_string(), TenantKey::from("auth:b"))
+            .is_none());
+
+        drop(registration);
+        assert!(cancellation
+            .cancel("resp-1", &TenantKey::from("auth:a"))
+            .is_none());
+        assert!(cancellation
+            .register("resp-1".to_string(), TenantKey::from("auth:b"))
+            .is_some());
+    }
+
+    #[test]
+    fn cancel_reports_stream_usage() {
+        let cancellation = RequestCancellation::new();
+        let registration = cancellation
+            .register("chatcmpl-1".to_string(), TenantKey::from("auth:a"))
+            .unwrap();
+        {
+            let mut transcript = registration.transcript.lock();
+            transcript.observe(None, r#"{"choices":[{"delta":{"content":"Hel"}}]}"#, 0);
+            transcript.observe(None, r#"{"choices":[{"delta":{"content":"lo"}}]}"#, 0);
+        }
+        let usage = cancellation
+            .cancel("chatcmpl-1", &TenantKey::from("auth:a"))
+            .unwrap()
+            .usage;
+        assert_eq!(usage["completion_tokens"], 2);
+        assert_eq!(usage["estimated"], true);
+    }
+
+    #[test]
+    fn responses_are_cancellable_by_response_id() {
+        let cancellation = RequestCancellation::new();
+        let mut registration = cancellation
+            .register("resp-1".to_string(), TenantKey::from("auth:a"))
+            .unwrap();
+        registration.transcript.lock().observe(
+            Some("response.created"),
+            r#"{"type":"response.created","response":{"id":"resp_abc"}}"#,
+            0,
+        );
+        registration.observe_response_id();
+
+        let cancelled = cancellation
+            .cancel("resp_abc", &TenantKey::from("auth:a"))
+            .unwrap();
+        assert!(registration.token.is_cancelled());
+        assert_eq!(cancelled.response_id.as_deref(), Some("resp_abc"));
+
+        drop(registration);
+        assert!(cancellation.response_ids.is_empty());
+    }
+}
@@ -0,0 +1,335 @@
+//! Single-flight coalescing of identical concurrent generation requests.
+//!
+//! When `request_coalescing.enabled` is set, deterministic (`temperature: 0`)
+//! chat and completion requests are keyed by a hash of their tenant, route
+//! and JSON body. The first request of a key is executed; identical requests
+//! arriving while it is in flight attach to it instead of generating again
+//! and receive the same status, headers and body, streamed or not, from the
+//! first byte. This suits many clients refreshing the same generated page.
+//!
+//! The shared execution runs detached from any one client and stops once
+//! every attached client has gone. A request opts out with the
+//! `x-smg-coalesce: off` header.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use dashmap::{mapref::entry::Entry, DashMap};
+use futures::{stream, StreamExt};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::watch;
+use tracing::debug;
+
+use super::RouteRequestMeta;
+use crate::{config::RequestCoalescingConfig, routers::error};
+
+static HEADER_COALESCE: HeaderName = HeaderName::from_static("x-smg-coalesce");
+static HEADER_COALESCED: HeaderName = HeaderName::from_static("x-smg-coalesced");
+
+const COALESCED_ROUTES: [&str; 2] = ["/v1/chat/completions", "/v1/completions"];
+
+type FlightKey = [u8; 32];
+
+/// Everything the shared execution has produced so far.
+#[derive(Default)]
+struct Flight {
+    head: Option<(StatusCode, HeaderMap)>,
+    chunks: Vec<Bytes>,
+    done: bool,
+}
+
+#[derive(Clone)]
+pub struct RequestCoalescer {
+    max_request_bytes: usize,
+    flights: Arc<DashMap<FlightKey, Arc<watch::Sender<Flight>>>>,
+}
+
+impl RequestCoalescer {
+    /// `None` when coalescing is disabled.
+    pub fn new(config: &RequestCoalescingConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            max_request_bytes: config.max_request_bytes,
+            flights: Arc::new(DashMap::new()),
+        })
+    }
+
+    /// Whether the request can be coalesced, judged from its head.
+    fn eligible(&self, request: &Request<Body>) -> bool {
+        let opted_out = request
+            .headers()
+            .get(&HEADER_COALESCE)
+            .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"off"));
+        let small_enough = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok())
+            .is_some_and(|len| len <= self.max_request_bytes);
+        request.method() == Method::POST
+            && COALESCED_ROUTES.contains(&request.uri().path())
+            && !opted_out
+            && small_enough
+    }
+
+    /// Execute `request` as a new flight under `key`, or attach to the one
+    /// already in flight.
+    fn join(
+        &self,
+        key: FlightKey,
+        request: Request<Body>,
+        next: Next,
+    ) -> (watch::Receiver<Flight>, bool) {
+        let sender = match self.flights.entry(key) {
+            Entry::Occupied(flight) => return (flight.get().subscribe(), true),
+            Entry::Vacant(slot) => {
+                let sender = Arc::new(watch::Sender::new(Flight::default()));
+                slot.insert(sender.clone());
+                sender
+            }
+        };
+        let receiver = sender.subscribe();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "the shared execution must outlive the client that started it; it stops once no client is attached"
+        )]
+        tokio::spawn(Self::relay(
+            self.flights.clone(),
+            key,
+            sender,
+            next.run(request),
+        ));
+        (receiver, false)
+    }
+
+    async fn relay(
+        flights: Arc<DashMap<FlightKey, Arc<watch::Sender<Flight>>>>,
+        key: FlightKey,
+        sender: Arc<watch::Sender<Flight>>,
+        response: impl std::future::Future<Output = Response>,
+    ) {
+        let unregister = || {
+            flights.remove_if(&key, |_, flight| Arc::ptr_eq(flight, &sender));
+        };
+        let (parts, body) = response.await.into_parts();
+        sender.send_modify(|flight| flight.head = Some((parts.status, parts.headers)));
+
+        let mut body = body.into_data_stream();
+        while let Some(Ok(chunk)) = body.next().await {
+            sender.send_modify(|flight| flight.chunks.push(chunk));
+            if sender.receiver_count() == 0 {
+                // Stop admitting clients, then make sure none slipped in.
+                unregister();
+                if sender.receiver_count() == 0 {
+                    debug!("Coalesced request abandoned by every client");
+                    return;
+                }
+            }
+        }
+        unregister();
+        sender.send_modify(|flight| flight.done = true);
+    }
+}
+
+/// Hash of the tenant, route and JSON body, or `None` if the request is not
+/// deterministic.
+fn flight_key(tenant: &str, path: &str, body: &Bytes) -> Option<FlightKey> {
+    let json: Value = serde_json::from_slice(body).ok()?;
+    if json.get("temperature").and_then(Value::as_f64) != Some(0.0) {
+        return None;
+    }
+    let canonical = json.to_string();
+    let mut hasher = Sha256::new();
+    for part in [tenant.as_bytes(), path.as_bytes(), canonical.as_bytes()] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    Some(hasher.finalize().into())
+}
+
+/// Build a client's response from the shared flight, replaying everything
+/// produced so far and following it to the end.
+async fn respond(mut receiver: watch::Receiver<Flight>, coalesced: bool) -> Response {
+    let head = match receiver.wait_for(|flight| flight.head.is_some()).await {
+        Ok(flight) => flight.head.clone(),
+        Err(_) => None,
+    };
+    let Some((status, headers)) = head else {
+        return error::service_unavailable(
+            "coalesced_request_failed",
+            "The shared execution of this request ended before responding",
+        );
+    };
+
+    let body = stream::unfold((receiver, 0), |(mut receiver, sent)| async move {
+        loop {
+            let (chunks, done) = {
+                let flight = receiver.borrow_and_update();
+                (flight.chunks[sent..].to_vec(), flight.done)
+            };
+            if !chunks.is_empty() {
+                let sent = sent + chunks.len();
+                return Some((stream::iter(chunks), (receiver, sent)));
+            }
+            if done || receiver.changed().await.is_err() {
+                return None;
+            }
+        }
+    })
+    .flatten()
+    .map(Ok::<_, Infallible>);
+
+    let mut response = Response::new(Body::from_stream(body));
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    if coalesced {
+        response
+            .headers_mut()
+            .insert(HEADER_COALESCED.clone(), HeaderValue::from_static("true"));
+    }
+    response
+}
+
+/// Coalesce identical deterministic generation requests.
+pub async fn request_coalescing_middleware(
+    State(coalescer): State<RequestCoalescer>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !coalescer.eligible(&request) {
+        return next.run(request).await;
+    }
+    let Some(tenant) = request
+        .extensions()
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().as_str().to_string())
+    else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, coalescer.max_request_bytes).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_request("invalid_request_body", format!("Failed to read body: {e}"))
+        }
+    };
+    let key = flight_key(&tenant, parts.uri.path(), &bytes);
+    let request = Request::from_parts(parts, Body::from(bytes));
+    let Some(key) = key else {
+        return next.run(request).await;
+    };
+
+    let (receiver, coalesced) = coalescer.join(key, request, next);
+    if coalesced {
+        debug!("Request coalesced onto an identical in-flight request");
+    }
+    respond(receiver, coalesced).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{middleware::from_fn_with_state, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::middleware::TenantKey;
+
+    #[test]
+    fn only_deterministic_requests_have_a_key() {
+        let body = |json: &str| Bytes::from(json.to_string());
+        let key = flight_key(
+            "t",
+            "/v1/completions",
+            &body(r#"{"temperature": 0, "prompt": "hi"}"#),
+        );
+        assert!(key.is_some());
+        assert_eq!(
+            key,
+            flight_key(
+                "t",
+                "/v1/completions",
+                &body(r#"{"temperature":0,"prompt":"hi"}"#)
+            )
+        );
+        assert_ne!(
+            key,
+            flight_key(
+                "u",
+                "/v1/completions",
+                &body(r#"{"temperature": 0, "prompt": "hi"}"#)
+            )
+        );
+        assert!(flight_key("t", "/v1/completions", &body(r#"{"temperature": 0.7}"#)).is_none());
+        assert!(flight_key("t", "/v1/completions", &body(r#"{"prompt": "hi"}"#)).is_none());
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_requests_execute_once() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = calls.clone();
+        let coalescer = RequestCoalescer::new(&RequestCoalescingConfig {
+            enabled: true,
+            max_request_bytes: 1024,
+        })
+        .unwrap();
+        let app = Router::new()
+            .route(
+                "/v1/completions",
+                post(move || {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        "generated"
+                    }
+                }),
+            )
+            .layer(from_fn_with_state(coalescer, request_coalescing_middleware));
+
+        let request = |opt_out: bool| {
+            let body = r#"{"model": "m", "prompt": "hi", "temperature": 0}"#;
+            let mut request = Request::post("/v1/completions")
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Body::from(body))
+                .unwrap();
+            if opt_out {
+                request
+                    .headers_mut()
+                    .insert(HEADER_COALESCE.clone(), HeaderValue::from_static("off"));
+            }
+            request
+                .extensions_mut()
+                .insert(RouteRequestMeta::new(TenantKey::from("tenant-a")));
+            request
+        };
+        let (first, second) = tokio::join!(
+            app.clone().oneshot(request(false)),
+            app.clone().oneshot(request(false))
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            [&first, &second]
+                .iter()
+                .filter(|r| r.headers().contains_key(&HEADER_COALESCED))
+                .count(),
+            1
+        );
+        for response in [first, second] {
+            let body = to_bytes(response.into_body(), 1024).await.unwrap();
+            assert_eq!(body, "generated");
+        }
+
+        app.clone().oneshot(request(true)).await.unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+}
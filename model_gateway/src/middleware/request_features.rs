@@ -0,0 +1,286 @@
+//! `x-smg-features` per-request feature flags.
+//!
+//! Callers opt into experimental gateway behaviors for a single request with
+//! `x-smg-features: recovery,coalesce-sse`. Every requested feature is
+//! checked against `request_features.default` and, for callers of a tenant
+//! API key, `request_features.tenants.<tenant_id>`; anything else is dropped
+//! and logged at debug. The header is then rewritten to the accepted
+//! features, or removed, so handlers and routers reading it downstream only
+//! ever see flags the caller was allowed to set.
+//!
+//! Accepted features are recorded on the request span (and therefore in the
+//! request log), counted per feature and status code in
+//! `smg_feature_requests_total`, and echoed in the response header, which
+//! lets a new behavior be rolled out one caller at a time and watched.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{debug, Span};
+
+use crate::{
+    config::RequestFeaturesConfig, observability::metrics::Metrics, tenant::RouteRequestMeta,
+};
+
+/// Per-request feature flag header.
+pub const REQUEST_FEATURES_HEADER: &str = "x-smg-features";
+
+/// Mid-stream recovery for a streaming request whose model is not listed in
+/// `stream_recovery.models`.
+pub const FEATURE_RECOVERY: &str = "recovery";
+
+/// Per-section prompt token counts under `usage["x-smg"]` on gRPC chat
+/// completions.
+pub const FEATURE_USAGE_BREAKDOWN: &str = "usage-breakdown";
+
+/// Metric label for a rejected feature that no allowlist names.
+const OTHER_FEATURE: &str = "other";
+
+/// Tenant key prefix of callers authenticated with a tenant API key.
+const TENANT_KEY_PREFIX: &str = "auth:";
+
+/// Accepted features of a request, sorted and deduplicated. Inserted as a
+/// request extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestFeatures(pub Vec<Arc<str>>);
+
+impl RequestFeatures {
+    /// Features named in `headers`. Only trustworthy downstream of
+    /// [`request_features_middleware`], which rewrites the header to the
+    /// accepted set.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut features: Vec<Arc<str>> = requested(headers).map(Arc::from).collect();
+        features.sort();
+        features.dedup();
+        Self(features)
+    }
+
+    pub fn contains(&self, feature: &str) -> bool {
+        self.0.iter().any(|f| &**f == feature)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Display for RequestFeatures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, feature) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(feature)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the request behind `headers` opted into `feature`.
+pub fn feature_enabled(headers: Option<&HeaderMap>, feature: &str) -> bool {
+    headers.is_some_and(|headers| requested(headers).any(|f| f == feature))
+}
+
+fn requested(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(REQUEST_FEATURES_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+}
+
+/// Per-caller feature allowlists.
+#[derive(Clone)]
+pub struct RequestFeatureGate {
+    default: Arc<HashSet<Arc<str>>>,
+    tenants: Arc<HashMap<String, HashSet<Arc<str>>>>,
+}
+
+impl RequestFeatureGate {
+    /// Always built: with nothing configured every requested feature is
+    /// rejected, which still keeps the header from reaching routers.
+    pub fn new(config: &RequestFeaturesConfig) -> Self {
+        let set = |features: &[String]| features.iter().map(|f| Arc::from(f.as_str())).collect();
+        Self {
+            default: Arc::new(set(&config.default)),
+            tenants: Arc::new(
+                config
+                    .tenants
+                    .iter()
+                    .map(|(tenant, features)| (tenant.clone(), set(features)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The allowlisted spelling of `feature` for `tenant_key`, if allowed.
+    fn allowed(&self, tenant_key: Option<&str>, feature: &str) -> Option<Arc<str>> {
+        if let Some(feature) = self.default.get(feature) {
+            return Some(feature.clone());
+        }
+        let tenant_id = tenant_key?.strip_prefix(TENANT_KEY_PREFIX)?;
+        self.tenants.get(tenant_id)?.get(feature).cloned()
+    }
+
+    fn is_known(&self, feature: &str) -> bool {
+        self.default.contains(feature) || self.tenants.values().any(|set| set.contains(feature))
+    }
+
+    /// Split the requested features into the accepted set and the metric
+    /// labels of rejected ones; names no allowlist knows are labelled `other`.
+    fn resolve(
+        &self,
+        headers: &HeaderMap,
+        tenant_key: Option<&str>,
+    ) -> (RequestFeatures, Vec<Arc<str>>) {
+        let mut accepted = Vec::new();
+        let mut rejected = Vec::new();
+        for feature in requested(headers) {
+            if let Some(feature) = self.allowed(tenant_key, feature) {
+                accepted.push(feature);
+                continue;
+            }
+            debug!(
+                feature,
+                "Ignoring {REQUEST_FEATURES_HEADER} feature not allowed for caller"
+            );
+            rejected.push(if self.is_known(feature) {
+                Arc::from(feature)
+            } else {
+                Arc::from(OTHER_FEATURE)
+            });
+        }
+        accepted.sort();
+        accepted.dedup();
+        (RequestFeatures(accepted), rejected)
+    }
+}
+
+/// Validate `x-smg-features` against the caller's allowlist and attach the
+/// accepted [`RequestFeatures`].
+///
+/// Requests without the header pass through untouched.
+pub async fn request_features_middleware(
+    State(gate): State<RequestFeatureGate>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !request.headers().contains_key(REQUEST_FEATURES_HEADER) {
+        return next.run(request).await;
+    }
+
+    let tenant_key = request
+        .extensions()
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().as_str().to_string());
+    let (features, rejected) = gate.resolve(request.headers(), tenant_key.as_deref());
+    for feature in &rejected {
+        Metrics::record_feature_rejection(feature);
+    }
+
+    request.headers_mut().remove(REQUEST_FEATURES_HEADER);
+    if features.is_empty() {
+        return next.run(request).await;
+    }
+    let header = HeaderValue::from_str(&features.to_string()).ok();
+    if let Some(header) = &header {
+        request
+            .headers_mut()
+            .insert(REQUEST_FEATURES_HEADER, header.clone());
+    }
+    Span::current().record("features", tracing::field::display(&features));
+    request.extensions_mut().insert(features.clone());
+
+    let mut response = next.run(request).await;
+    let status_code = response.status().as_u16();
+    for feature in &features.0 {
+        Metrics::record_feature_request(feature, status_code);
+    }
+    if let Some(header) = header {
+        response
+            .headers_mut()
+            .insert(REQUEST_FEATURES_HEADER, header);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate() -> RequestFeatureGate {
+        RequestFeatureGate::new(&RequestFeaturesConfig {
+            default: vec!["recovery".to_string()],
+            tenants: HashMap::from([("search".to_string(), vec!["coalesce-sse".to_string()])]),
+        })
+    }
+
+    fn headers(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_FEATURES_HEADER, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_default_features_allowed_for_everyone() {
+        let (features, rejected) =
+            gate().resolve(&headers(" recovery, recovery ,"), Some("ip:10.0.0.1"));
+        assert_eq!(features.to_string(), "recovery");
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn test_tenant_features_require_tenant_api_key() {
+        let gate = gate();
+        let headers = headers("coalesce-sse,recovery,teleport");
+
+        let (features, rejected) = gate.resolve(&headers, Some("auth:search"));
+        assert_eq!(features.to_string(), "coalesce-sse,recovery");
+        assert_eq!(rejected, vec![Arc::from(OTHER_FEATURE)]);
+
+        for tenant_key in [Some("auth:ads"), Some("header:search"), None] {
+            let (features, rejected) = gate.resolve(&headers, tenant_key);
+            assert_eq!(features.to_string(), "recovery");
+            assert_eq!(
+                rejected,
+                vec![Arc::from("coalesce-sse"), Arc::from(OTHER_FEATURE)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_unconfigured_gate_rejects_everything() {
+        let gate = RequestFeatureGate::new(&RequestFeaturesConfig::default());
+        let (features, rejected) = gate.resolve(&headers("recovery"), Some("auth:search"));
+        assert!(features.is_empty());
+        assert_eq!(rejected, vec![Arc::from(OTHER_FEATURE)]);
+    }
+
+    #[test]
+    fn test_feature_enabled_reads_header() {
+        assert!(feature_enabled(
+            Some(&headers("coalesce-sse, recovery")),
+            FEATURE_RECOVERY
+        ));
+        assert!(!feature_enabled(
+            Some(&headers("recovery-v2")),
+            FEATURE_RECOVERY
+        ));
+        assert!(!feature_enabled(None, FEATURE_RECOVERY));
+        assert_eq!(
+            RequestFeatures::from_headers(&headers("b,a,b")).to_string(),
+            "a,b"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! `x-smg-tags` request tagging.
+//!
+//! Clients attach free-form attribution to a request with
+//! `x-smg-tags: team=search,feature=autocomplete`. Only keys declared in
+//! `request_tags.tags` are kept; each becomes a `tag_<key>` label on the
+//! `smg_tagged_*` metrics, is recorded on the request span (and therefore in
+//! the request log), and is carried in webhook payloads. Keys with a value
+//! allowlist map anything else to `other`; open-ended keys accept the first
+//! `max_values_per_tag` distinct values and fold the rest into `other`, so a
+//! misbehaving client cannot blow up metric cardinality.
+//!
+//! Tags are attribution, not input: malformed entries are dropped (and
+//! logged at debug) rather than failing the request.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use metrics::Label;
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use tracing::{debug, Span};
+
+use crate::{config::RequestTagsConfig, observability::metrics::Metrics};
+
+/// Per-request tag header.
+pub const REQUEST_TAGS_HEADER: &str = "x-smg-tags";
+
+/// Value recorded for a tag outside its allowlist or over the value cap.
+const OTHER_VALUE: &str = "other";
+
+/// Value recorded for a configured tag the request did not set.
+const UNSET_VALUE: &str = "none";
+
+/// Accepted tags of a request, sorted by key. Inserted as a request extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestTags(pub Vec<(Arc<str>, Arc<str>)>);
+
+impl RequestTags {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.iter().find(|(k, _)| &**k == key).map(|(_, v)| &**v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `{"team": "search", ...}` for webhook payloads.
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+                .collect::<Map<_, _>>(),
+        )
+    }
+}
+
+impl std::fmt::Display for RequestTags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+enum TagValues {
+    Allowlist(HashSet<Arc<str>>),
+    /// Distinct values seen so far, capped at `max_values_per_tag`.
+    Open(Mutex<HashSet<Arc<str>>>),
+}
+
+struct TagSpec {
+    key: Arc<str>,
+    label: String,
+    values: TagValues,
+}
+
+#[derive(Clone)]
+pub struct RequestTagger {
+    /// Sorted by key.
+    specs: Arc<Vec<TagSpec>>,
+    max_values_per_tag: usize,
+}
+
+impl RequestTagger {
+    /// `None` when no tag keys are configured.
+    pub fn new(config: &RequestTagsConfig) -> Option<Self> {
+        if !config.enabled() {
+            return None;
+        }
+        let mut specs: Vec<TagSpec> = config
+            .tags
+            .iter()
+            .map(|(key, allowlist)| TagSpec {
+                key: Arc::from(key.as_str()),
+                label: format!("tag_{key}"),
+                values: if allowlist.is_empty() {
+                    TagValues::Open(Mutex::new(HashSet::new()))
+                } else {
+                    TagValues::Allowlist(allowlist.iter().map(|v| Arc::from(v.as_str())).collect())
+                },
+            })
+            .collect();
+        specs.sort_by(|a, b| a.key.cmp(&b.key));
+        Some(Self {
+            specs: Arc::new(specs),
+            max_values_per_tag: config.max_values_per_tag,
+        })
+    }
+
+    /// Parse `k=v,k2=v2` and bound each value. Unknown keys and malformed
+    /// entries are dropped; for a repeated key the first entry wins.
+    fn parse(&self, headers: &HeaderMap) -> RequestTags {
+        let Some(raw) = headers
+            .get(REQUEST_TAGS_HEADER)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return RequestTags::default();
+        };
+
+        let mut tags: HashMap<usize, Arc<str>> = HashMap::new();
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((key, value)) = entry.split_once('=') else {
+                debug!(entry, "Ignoring {REQUEST_TAGS_HEADER} entry without '='");
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let Some(index) = self.specs.iter().position(|spec| &*spec.key == key) else {
+                debug!(key, "Ignoring unconfigured {REQUEST_TAGS_HEADER} key");
+                continue;
+            };
+            if !RequestTagsConfig::is_valid_value(value) {
+                debug!(key, "Ignoring invalid {REQUEST_TAGS_HEADER} value");
+                continue;
+            }
+            tags.entry(index)
+                .or_insert_with(|| self.bound_value(&self.specs[index], value));
+        }
+
+        let mut tags: Vec<_> = tags.into_iter().collect();
+        tags.sort_by_key(|(index, _)| *index);
+        RequestTags(
+            tags.into_iter()
+                .map(|(index, value)| (self.specs[index].key.clone(), value))
+                .collect(),
+        )
+    }
+
+    fn bound_value(&self, spec: &TagSpec, value: &str) -> Arc<str> {
+        match &spec.values {
+            TagValues::Allowlist(allowed) => match allowed.get(value) {
+                Some(value) => value.clone(),
+                None => Arc::from(OTHER_VALUE),
+            },
+            TagValues::Open(seen) => {
+                let mut seen = seen.lock();
+                if let Some(value) = seen.get(value) {
+                    return value.clone();
+                }
+                if seen.len() >= self.max_values_per_tag {
+                    return Arc::from(OTHER_VALUE);
+                }
+                let value: Arc<str> = Arc::from(value);
+                seen.insert(value.clone());
+                value
+            }
+        }
+    }
+
+    /// One `tag_<key>` label per configured key, `none` when unset.
+    fn labels(&self, tags: &RequestTags) -> Vec<Label> {
+        self.specs
+            .iter()
+            .map(|spec| {
+                let value = tags.get(&spec.key).unwrap_or(UNSET_VALUE);
+                Label::new(spec.label.clone(), value.to_string())
+            })
+            .collect()
+    }
+}
+
+/// Attach [`RequestTags`] to the request and record tagged metrics.
+///
+/// Requests without an accepted tag pass through unrecorded.
+pub async fn request_tags_middleware(
+    State(tagger): State<RequestTagger>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let tags = tagger.parse(request.headers());
+    if tags.is_empty() {
+        return next.run(request).await;
+    }
+
+    Span::current().record("tags", tracing::field::display(&tags));
+    let labels = tagger.labels(&tags);
+    request.extensions_mut().insert(tags);
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    Metrics::record_tagged_request(labels, response.status().as_u16(), start.elapsed());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn tagger(max_values_per_tag: usize) -> RequestTagger {
+        RequestTagger::new(&RequestTagsConfig {
+            tags: HashMap::from([
+                (
+                    "team".to_string(),
+                    vec!["search".to_string(), "ads".to_string()],
+                ),
+                ("feature".to_string(), vec![]),
+            ]),
+            max_values_per_tag,
+        })
+        .unwrap()
+    }
+
+    fn headers(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_TAGS_HEADER, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_disabled_without_configured_tags() {
+        assert!(RequestTagger::new(&RequestTagsConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_parse_keeps_configured_keys_sorted() {
+        let tags = tagger(8).parse(&headers(
+            " team = search, unknown=x, feature=autocomplete, team=ads, broken, env=",
+        ));
+        assert_eq!(tags.to_string(), "feature=autocomplete,team=search");
+        assert_eq!(
+            tags.to_json(),
+            serde_json::json!({"feature": "autocomplete", "team": "search"})
+        );
+        assert!(tagger(8).parse(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_values_are_bounded() {
+        let tagger = tagger(2);
+        assert_eq!(
+            tagger.parse(&headers("team=billing")).get("team"),
+            Some(OTHER_VALUE)
+        );
+        assert!(tagger.parse(&headers("feature=a b")).is_empty());
+
+        for (value, expected) in [
+            ("feature=a", "a"),
+            ("feature=b", "b"),
+            ("feature=c", OTHER_VALUE),
+            ("feature=a", "a"),
+        ] {
+            assert_eq!(tagger.parse(&headers(value)).get("feature"), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_labels_cover_every_configured_key() {
+        let tagger = tagger(8);
+        let labels = tagger.labels(&tagger.parse(&headers("team=ads")));
+        let labels: Vec<(&str, &str)> = labels.iter().map(|l| (l.key(), l.value())).collect();
+        assert_eq!(labels, vec![("tag_feature", "none"), ("tag_team", "ads")]);
+    }
+}
@@ -0,0 +1,674 @@
+//! Declarative routing rules evaluated before policy selection.
+//!
+//! Each POST request is checked against the rule table in order; the first
+//! rule whose path, header, tenant and body conditions all hold decides
+//! where the request goes. Its action may rewrite the body's `model` and
+//! may restrict worker selection to the workers carrying a set of labels,
+//! which the routers read from the internal `x-smg-worker-labels` header.
+//! Client-supplied values of that header are always dropped. Matched
+//! responses carry `x-smg-routing-rule: <name>`.
+//!
+//! Rules come from `routing_rules.rules` and can be replaced at runtime
+//! through `/admin/routing-rules`. With mesh enabled, admin changes are
+//! published to the `config:` namespace and applied on every node (see
+//! [`crate::mesh::adapters::routing_rules_sync`]).
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use parking_lot::Mutex;
+use serde_json::Value;
+use tracing::warn;
+
+use super::RouteRequestMeta;
+use crate::{
+    config::{BodyPredicateConfig, BodyPredicateOp, RoutingRuleConfig, RoutingRulesConfig},
+    observability::metrics::Metrics,
+    routers::{common::header_utils::HEADER_WORKER_LABELS, error},
+};
+
+static HEADER_ROUTING_RULE: HeaderName = HeaderName::from_static("x-smg-routing-rule");
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RoutingRulesError {
+    #[error("rules[{index}]: {reason}")]
+    Invalid { index: usize, reason: String },
+    #[error("routing rule '{0}' already exists")]
+    Duplicate(String),
+    #[error("no routing rule named '{0}'")]
+    NotFound(String),
+}
+
+#[derive(Debug)]
+enum PathMatch {
+    Exact(String),
+    Prefix(String),
+}
+
+#[derive(Debug)]
+enum Segment {
+    Key(String),
+    Any,
+}
+
+#[derive(Debug)]
+struct BodyPredicate {
+    path: Vec<Segment>,
+    op: BodyPredicateOp,
+    value: Value,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    path: Option<PathMatch>,
+    /// `None` value: the header only has to be present.
+    headers: Vec<(HeaderName, Option<String>)>,
+    tenant: Option<String>,
+    body: Vec<BodyPredicate>,
+    model: Option<String>,
+    worker_labels: Option<HeaderValue>,
+}
+
+/// What the first matching rule decided.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingDecision {
+    pub rule: String,
+    pub model: Option<String>,
+    pub worker_labels: Option<HeaderValue>,
+}
+
+fn compile_rule(rule: &RoutingRuleConfig) -> Result<CompiledRule, String> {
+    // Names are echoed in a response header and a metric label.
+    if rule.name.is_empty() || !rule.name.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(format!(
+            "name '{}' must be non-empty printable ASCII",
+            rule.name
+        ));
+    }
+    let conditions = &rule.conditions;
+    let path = match conditions.path.as_deref() {
+        None => None,
+        Some(path) if !path.starts_with('/') => {
+            return Err(format!("path '{path}' must start with '/'"));
+        }
+        Some(path) => Some(match path.strip_suffix('*') {
+            Some(prefix) => PathMatch::Prefix(prefix.to_string()),
+            None => PathMatch::Exact(path.to_string()),
+        }),
+    };
+    let mut headers = Vec::with_capacity(conditions.headers.len());
+    for (name, value) in &conditions.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("'{name}' is not a valid header name"))?;
+        headers.push((name, (value != "*").then(|| value.clone())));
+    }
+    if conditions.tenant.as_deref() == Some("") {
+        return Err("tenant must not be empty".to_string());
+    }
+    let body = conditions
+        .body
+        .iter()
+        .map(compile_predicate)
+        .collect::<Result<_, _>>()?;
+
+    let action = &rule.action;
+    if action.model.is_none() && action.worker_labels.is_empty() {
+        return Err("action must set model or worker_labels".to_string());
+    }
+    if action.model.as_deref() == Some("") {
+        return Err("action.model must not be empty".to_string());
+    }
+    let worker_labels = if action.worker_labels.is_empty() {
+        None
+    } else {
+        let valid = |s: &str| !s.is_empty() && !s.contains([',', '=']);
+        if let Some((key, value)) = action
+            .worker_labels
+            .iter()
+            .find(|(key, value)| !valid(key) || !valid(value))
+        {
+            return Err(format!(
+                "worker label '{key}={value}' must be non-empty and contain no ',' or '='"
+            ));
+        }
+        let selector = action
+            .worker_labels
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        Some(
+            HeaderValue::from_str(&selector)
+                .map_err(|_| format!("worker labels '{selector}' are not header-safe"))?,
+        )
+    };
+
+    Ok(CompiledRule {
+        name: rule.name.clone(),
+        path,
+        headers,
+        tenant: conditions.tenant.clone(),
+        body,
+        model: action.model.clone(),
+        worker_labels,
+    })
+}
+
+fn compile_predicate(predicate: &BodyPredicateConfig) -> Result<BodyPredicate, String> {
+    let field = &predicate.field;
+    if field.split('.').any(str::is_empty) {
+        return Err(format!("body field '{field}' has an empty segment"));
+    }
+    let path = field
+        .split('.')
+        .map(|segment| match segment {
+            "*" => Segment::Any,
+            key => Segment::Key(key.to_string()),
+        })
+        .collect();
+    let value = match (predicate.op, &predicate.value) {
+        (BodyPredicateOp::Exists, _) => Value::Null,
+        (_, None) => return Err(format!("body field '{field}' needs a value")),
+        (
+            BodyPredicateOp::Gt | BodyPredicateOp::Gte | BodyPredicateOp::Lt | BodyPredicateOp::Lte,
+            Some(value),
+        ) if !value.is_number() => {
+            return Err(format!(
+                "body field '{field}' compares against a non-number"
+            ));
+        }
+        (_, Some(value)) => value.clone(),
+    };
+    Ok(BodyPredicate {
+        path,
+        op: predicate.op,
+        value,
+    })
+}
+
+/// Compile a rule table, rejecting invalid rules and duplicate names.
+fn compile_rules(rules: &[RoutingRuleConfig]) -> Result<Vec<CompiledRule>, RoutingRulesError> {
+    let mut names = std::collections::HashSet::new();
+    rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            if !names.insert(rule.name.as_str()) {
+                return Err(RoutingRulesError::Invalid {
+                    index,
+                    reason: format!("duplicate name '{}'", rule.name),
+                });
+            }
+            compile_rule(rule).map_err(|reason| RoutingRulesError::Invalid { index, reason })
+        })
+        .collect()
+}
+
+/// Check a rule table without installing it.
+pub fn validate_rules(rules: &[RoutingRuleConfig]) -> Result<(), RoutingRulesError> {
+    compile_rules(rules).map(|_| ())
+}
+
+/// Whether `found` holds for any value `path` resolves to under `value`.
+fn visit<'a>(
+    value: &'a Value,
+    path: &[Segment],
+    found: &mut impl FnMut(&'a Value) -> bool,
+) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        return found(value);
+    };
+    match (segment, value) {
+        (Segment::Any, Value::Array(items)) => items.iter().any(|v| visit(v, rest, found)),
+        (Segment::Any, Value::Object(map)) => map.values().any(|v| visit(v, rest, found)),
+        (Segment::Key(key), Value::Object(map)) => {
+            map.get(key).is_some_and(|v| visit(v, rest, found))
+        }
+        (Segment::Key(key), Value::Array(items)) => key
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get(i))
+            .is_some_and(|v| visit(v, rest, found)),
+        _ => false,
+    }
+}
+
+impl BodyPredicate {
+    fn matches(&self, body: &Value) -> bool {
+        let compare = |actual: &Value| match (actual.as_f64(), self.value.as_f64()) {
+            (Some(actual), Some(expected)) => actual.partial_cmp(&expected),
+            _ => None,
+        };
+        match self.op {
+            BodyPredicateOp::Exists => visit(body, &self.path, &mut |v| !v.is_null()),
+            BodyPredicateOp::Eq => visit(body, &self.path, &mut |v| *v == self.value),
+            BodyPredicateOp::Ne => !visit(body, &self.path, &mut |v| *v == self.value),
+            BodyPredicateOp::Gt => visit(body, &self.path, &mut |v| {
+                compare(v).is_some_and(|o| o.is_gt())
+            }),
+            BodyPredicateOp::Gte => visit(body, &self.path, &mut |v| {
+                compare(v).is_some_and(|o| o.is_ge())
+            }),
+            BodyPredicateOp::Lt => visit(body, &self.path, &mut |v| {
+                compare(v).is_some_and(|o| o.is_lt())
+            }),
+            BodyPredicateOp::Lte => visit(body, &self.path, &mut |v| {
+                compare(v).is_some_and(|o| o.is_le())
+            }),
+        }
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, path: &str, headers: &HeaderMap, tenant: Option<&str>, body: &Value) -> bool {
+        let path_matches = match &self.path {
+            None => true,
+            Some(PathMatch::Exact(exact)) => path == exact,
+            Some(PathMatch::Prefix(prefix)) => path.starts_with(prefix.as_str()),
+        };
+        path_matches
+            && self.headers.iter().all(|(name, expected)| {
+                headers.get(name).is_some_and(|actual| {
+                    expected
+                        .as_deref()
+                        .is_none_or(|expected| actual.as_bytes() == expected.as_bytes())
+                })
+            })
+            && self.tenant.as_deref().is_none_or(|t| tenant == Some(t))
+            && self.body.iter().all(|predicate| predicate.matches(body))
+    }
+}
+
+struct RuleSet {
+    configs: Vec<RoutingRuleConfig>,
+    compiled: Vec<CompiledRule>,
+}
+
+/// The live rule table. Reads are lock-free; writers are serialized.
+pub struct RoutingRules {
+    rules: ArcSwap<RuleSet>,
+    write: Mutex<()>,
+}
+
+impl std::fmt::Debug for RoutingRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutingRules")
+            .field("rules", &self.rules.load().configs.len())
+            .finish()
+    }
+}
+
+impl RoutingRules {
+    pub fn new(config: &RoutingRulesConfig) -> Self {
+        // The config was validated at startup; this only guards direct use.
+        let (configs, compiled) = match compile_rules(&config.rules) {
+            Ok(compiled) => (config.rules.clone(), compiled),
+            Err(e) => {
+                warn!("Ignoring invalid routing rules: {e}");
+                (Vec::new(), Vec::new())
+            }
+        };
+        Self {
+            rules: ArcSwap::from_pointee(RuleSet { configs, compiled }),
+            write: Mutex::new(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.load().compiled.is_empty()
+    }
+
+    /// Rules in evaluation order.
+    pub fn list(&self) -> Vec<RoutingRuleConfig> {
+        self.rules.load().configs.clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<RoutingRuleConfig> {
+        self.rules
+            .load()
+            .configs
+            .iter()
+            .find(|rule| rule.name == name)
+            .cloned()
+    }
+
+    /// Replace the whole table.
+    pub fn replace(&self, rules: Vec<RoutingRuleConfig>) -> Result<(), RoutingRulesError> {
+        let _write = self.write.lock();
+        self.install(rules)
+    }
+
+    /// Insert `rule` at `position` (default: last).
+    pub fn create(
+        &self,
+        rule: RoutingRuleConfig,
+        position: Option<usize>,
+    ) -> Result<(), RoutingRulesError> {
+        let _write = self.write.lock();
+        let mut rules = self.list();
+        if rules.iter().any(|r| r.name == rule.name) {
+            return Err(RoutingRulesError::Duplicate(rule.name));
+        }
+        let position = position.map_or(rules.len(), |p| p.min(rules.len()));
+        rules.insert(position, rule);
+        self.install(rules)
+    }
+
+    /// Replace the rule named `name` in place; `rule` may rename it.
+    pub fn update(&self, name: &str, rule: RoutingRuleConfig) -> Result<(), RoutingRulesError> {
+        let _write = self.write.lock();
+        let mut rules = self.list();
+        let Some(slot) = rules.iter_mut().find(|r| r.name == name) else {
+            return Err(RoutingRulesError::NotFound(name.to_string()));
+        };
+        *slot = rule;
+        self.install(rules)
+    }
+
+    pub fn remove(&self, name: &str) -> Option<RoutingRuleConfig> {
+        let _write = self.write.lock();
+        let mut rules = self.list();
+        let index = rules.iter().position(|r| r.name == name)?;
+        let removed = rules.remove(index);
+        // Dropping a rule cannot make the rest invalid.
+        self.install(rules).ok()?;
+        Some(removed)
+    }
+
+    fn install(&self, rules: Vec<RoutingRuleConfig>) -> Result<(), RoutingRulesError> {
+        let compiled = compile_rules(&rules)?;
+        self.rules.store(Arc::new(RuleSet {
+            configs: rules,
+            compiled,
+        }));
+        Ok(())
+    }
+
+    /// The first rule matching the request, if any.
+    pub fn evaluate(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+        tenant: Option<&str>,
+        body: &Value,
+    ) -> Option<RoutingDecision> {
+        let rules = self.rules.load();
+        let rule = rules
+            .compiled
+            .iter()
+            .find(|rule| rule.matches(path, headers, tenant, body))?;
+        Some(RoutingDecision {
+            rule: rule.name.clone(),
+            model: rule.model.clone(),
+            worker_labels: rule.worker_labels.clone(),
+        })
+    }
+}
+
+/// Apply the first matching routing rule to a JSON request.
+pub async fn routing_rules_middleware(
+    State(rules): State<Arc<RoutingRules>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    // Only a routing rule may narrow the worker pool.
+    request.headers_mut().remove(&HEADER_WORKER_LABELS);
+    if request.method() != Method::POST || rules.is_empty() {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_request("invalid_request_body", format!("Failed to read body: {e}"))
+        }
+    };
+    let Ok(mut json) = serde_json::from_slice::<Value>(&bytes) else {
+        return next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await;
+    };
+    let tenant = parts
+        .extensions
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().as_str());
+    let Some(decision) = rules.evaluate(parts.uri.path(), &parts.headers, tenant, &json) else {
+        return next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await;
+    };
+
+    Metrics::record_routing_rule_match(&decision.rule);
+    let body = match (&decision.model, json.as_object_mut()) {
+        (Some(model), Some(object)) => {
+            object.insert("model".to_string(), Value::String(model.clone()));
+            let rewritten = serde_json::to_vec(&json).unwrap_or_default();
+            parts
+                .headers
+                .insert(header::CONTENT_LENGTH, rewritten.len().into());
+            Body::from(rewritten)
+        }
+        _ => Body::from(bytes),
+    };
+    if let Some(labels) = decision.worker_labels {
+        parts.headers.insert(HEADER_WORKER_LABELS.clone(), labels);
+    }
+
+    let mut response = next.run(Request::from_parts(parts, body)).await;
+    if let Ok(value) = HeaderValue::from_str(&decision.rule) {
+        response
+            .headers_mut()
+            .insert(HEADER_ROUTING_RULE.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::{RoutingRuleAction, RoutingRuleMatch};
+
+    fn rule(name: &str, conditions: RoutingRuleMatch, model: &str) -> RoutingRuleConfig {
+        RoutingRuleConfig {
+            name: name.to_string(),
+            conditions,
+            action: RoutingRuleAction {
+                model: Some(model.to_string()),
+                worker_labels: BTreeMap::new(),
+            },
+        }
+    }
+
+    fn predicate(field: &str, op: BodyPredicateOp, value: Option<Value>) -> BodyPredicateConfig {
+        BodyPredicateConfig {
+            field: field.to_string(),
+            op,
+            value,
+        }
+    }
+
+    fn table() -> RoutingRules {
+        let mut pool = rule(
+            "vision",
+            RoutingRuleMatch {
+                body: vec![predicate(
+                    "messages.*.content.*.type",
+                    BodyPredicateOp::Eq,
+                    Some(json!("image_url")),
+                )],
+                ..Default::default()
+            },
+            "unused",
+        );
+        pool.action = RoutingRuleAction {
+            model: None,
+            worker_labels: BTreeMap::from([
+                ("pool".to_string(), "vlm".to_string()),
+                ("gpu".to_string(), "h100".to_string()),
+            ]),
+        };
+        RoutingRules::new(&RoutingRulesConfig {
+            rules: vec![
+                rule(
+                    "long",
+                    RoutingRuleMatch {
+                        path: Some("/v1/*".to_string()),
+                        body: vec![predicate(
+                            "max_tokens",
+                            BodyPredicateOp::Gt,
+                            Some(json!(4096)),
+                        )],
+                        ..Default::default()
+                    },
+                    "big-model",
+                ),
+                pool,
+                rule(
+                    "premium",
+                    RoutingRuleMatch {
+                        headers: BTreeMap::from([("X-Tier".to_string(), "*".to_string())]),
+                        tenant: Some("auth:team-a".to_string()),
+                        ..Default::default()
+                    },
+                    "premium-model",
+                ),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = table();
+        let headers = HeaderMap::new();
+        let long = json!({"max_tokens": 8192, "messages": [
+            {"role": "user", "content": [{"type": "image_url", "image_url": {"url": "x"}}]}
+        ]});
+        let decision = rules
+            .evaluate("/v1/chat/completions", &headers, None, &long)
+            .unwrap();
+        assert_eq!(decision.rule, "long");
+        assert_eq!(decision.model.as_deref(), Some("big-model"));
+
+        // Same body on a path outside the first rule falls through to the
+        // image predicate.
+        let decision = rules.evaluate("/generate", &headers, None, &long).unwrap();
+        assert_eq!(decision.rule, "vision");
+        assert_eq!(decision.model, None);
+        assert_eq!(
+            decision.worker_labels,
+            Some(HeaderValue::from_static("gpu=h100,pool=vlm"))
+        );
+
+        let short = json!({"max_tokens": 100, "messages": [{"role": "user", "content": "hi"}]});
+        assert_eq!(
+            rules.evaluate("/v1/chat/completions", &headers, None, &short),
+            None
+        );
+    }
+
+    #[test]
+    fn test_header_and_tenant_conditions() {
+        let rules = table();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tier", HeaderValue::from_static("gold"));
+        let body = json!({"model": "m"});
+        assert_eq!(
+            rules
+                .evaluate("/v1/completions", &headers, Some("auth:team-a"), &body)
+                .map(|d| d.rule),
+            Some("premium".to_string())
+        );
+        assert!(rules
+            .evaluate("/v1/completions", &headers, Some("auth:team-b"), &body)
+            .is_none());
+        assert!(rules
+            .evaluate(
+                "/v1/completions",
+                &HeaderMap::new(),
+                Some("auth:team-a"),
+                &body
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_admin_changes_are_validated() {
+        let rules = table();
+        let premium = rules.get("premium").unwrap();
+        assert_eq!(
+            rules.create(premium.clone(), None),
+            Err(RoutingRulesError::Duplicate("premium".to_string()))
+        );
+
+        let mut invalid = premium.clone();
+        invalid.name = "invalid".to_string();
+        invalid.action = RoutingRuleAction::default();
+        assert!(matches!(
+            rules.create(invalid, Some(0)),
+            Err(RoutingRulesError::Invalid { index: 0, .. })
+        ));
+        assert_eq!(rules.list().len(), 3);
+
+        let mut first = premium;
+        first.name = "first".to_string();
+        first.conditions = RoutingRuleMatch::default();
+        rules.create(first, Some(0)).unwrap();
+        let body = json!({"max_tokens": 8192});
+        assert_eq!(
+            rules
+                .evaluate("/v1/completions", &HeaderMap::new(), None, &body)
+                .map(|d| d.rule),
+            Some("first".to_string())
+        );
+
+        assert!(rules.remove("first").is_some());
+        assert!(rules.remove("first").is_none());
+        assert_eq!(
+            rules
+                .evaluate("/v1/completions", &HeaderMap::new(), None, &body)
+                .map(|d| d.rule),
+            Some("long".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let mut bad = rule("bad", RoutingRuleMatch::default(), "m");
+        bad.conditions.body = vec![predicate(
+            "max_tokens",
+            BodyPredicateOp::Gt,
+            Some(json!("x")),
+        )];
+        assert!(validate_rules(&[bad.clone()]).is_err());
+        bad.conditions.body = vec![predicate("a..b", BodyPredicateOp::Exists, None)];
+        assert!(validate_rules(&[bad.clone()]).is_err());
+        bad.conditions.body.clear();
+        bad.conditions.path = Some("v1/chat".to_string());
+        assert!(validate_rules(&[bad.clone()]).is_err());
+        bad.conditions.path = None;
+        bad.action.worker_labels = BTreeMap::from([("pool".to_string(), "a,b".to_string())]);
+        assert!(validate_rules(&[bad.clone()]).is_err());
+        bad.action.worker_labels.clear();
+        assert!(validate_rules(&[bad.clone()]).is_ok());
+        assert_eq!(
+            validate_rules(&[bad.clone(), bad]),
+            Err(RoutingRulesError::Invalid {
+                index: 1,
+                reason: "duplicate name 'bad'".to_string()
+            })
+        );
+    }
+}
@@ -562,6 +562,11 @@ impl PriorityScheduler {
         });
     }
 
+    /// Number of requests currently waiting in `class`'s queue.
+    pub fn queue_depth(&self, class: Class) -> usize {
+        self.class_queues[class as usize].depth()
+    }
+
     /// Refresh the capacity / autoscaling gauges. Reads the slot pool and
     /// queues under their own locks; never touches the inflight registry.
     fn sample_metrics(&self) {
@@ -0,0 +1,236 @@
+//! Read-only fan-out of in-flight streaming responses.
+//!
+//! When `stream_fanout.enabled` is set, every SSE response on the serving
+//! routes is teed into a broadcast buffer keyed by its request id (the
+//! `x-request-id` response header). Another client of the same tenant, such
+//! as a monitoring UI, can attach with `GET /v1/streams/{request_id}` and
+//! receive a live copy of the stream from the point it attaches. The original
+//! client is never slowed down: a subscriber that falls more than
+//! `buffer_chunks` chunks behind is disconnected.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Extension, Path, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use dashmap::{mapref::entry::Entry, DashMap};
+use futures::{future, StreamExt};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::debug;
+
+use super::{is_event_stream, request_id::RequestId, RouteRequestMeta, TenantKey};
+use crate::{config::StreamFanoutConfig, routers::error};
+
+struct FanoutStream {
+    tenant_key: TenantKey,
+    sender: broadcast::Sender<Bytes>,
+}
+
+#[derive(Clone)]
+pub struct StreamFanout {
+    buffer_chunks: usize,
+    max_subscribers: usize,
+    streams: Arc<DashMap<String, FanoutStream>>,
+}
+
+/// Unregisters the stream when the relayed body is dropped, which also drops
+/// the sender and ends every subscriber's copy.
+struct Registration {
+    streams: Arc<DashMap<String, FanoutStream>>,
+    request_id: String,
+    sender: broadcast::Sender<Bytes>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.streams.remove(&self.request_id);
+    }
+}
+
+impl StreamFanout {
+    /// `None` when fan-out is disabled.
+    pub fn new(config: &StreamFanoutConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            buffer_chunks: config.buffer_chunks.max(1),
+            max_subscribers: config.max_subscribers,
+            streams: Arc::new(DashMap::new()),
+        })
+    }
+
+    /// Register an in-flight stream. A request id that is already streaming
+    /// (e.g. a client-supplied id reused concurrently) is not registered
+    /// again, so one tenant can never attach to another's stream.
+    fn register(&self, request_id: String, tenant_key: TenantKey) -> Option<Registration> {
+        let Entry::Vacant(slot) = self.streams.entry(request_id.clone()) else {
+            return None;
+        };
+        let (sender, _) = broadcast::channel(self.buffer_chunks);
+        slot.insert(FanoutStream {
+            tenant_key,
+            sender: sender.clone(),
+        });
+        Some(Registration {
+            streams: self.streams.clone(),
+            request_id,
+            sender,
+        })
+    }
+
+    /// Attach to an in-flight stream. Streams of other tenants are reported
+    /// as not found.
+    fn subscribe(
+        &self,
+        request_id: &str,
+        tenant_key: &TenantKey,
+    ) -> Result<broadcast::Receiver<Bytes>, Response> {
+        let not_found = || {
+            error::not_found(
+                "stream_not_found",
+                format!("No in-flight stream with request id '{request_id}'"),
+            )
+        };
+        let stream = self.streams.get(request_id).ok_or_else(not_found)?;
+        if &stream.tenant_key != tenant_key {
+            return Err(not_found());
+        }
+        if stream.sender.receiver_count() >= self.max_subscribers {
+            return Err(error::create_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                "too_many_stream_subscribers",
+                format!(
+                    "Stream '{request_id}' already has {} subscribers",
+                    self.max_subscribers
+                ),
+            ));
+        }
+        Ok(stream.sender.subscribe())
+    }
+}
+
+/// Tee SSE responses of serving requests into the fan-out registry.
+pub async fn stream_fanout_middleware(
+    State(fanout): State<StreamFanout>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let tenant_key = request
+        .extensions()
+        .get::<RouteRequestMeta>()
+        .map(|meta| meta.tenant_key().clone());
+
+    let response = next.run(request).await;
+    let (Some(request_id), Some(tenant_key)) = (request_id, tenant_key) else {
+        return response;
+    };
+    if !response.status().is_success() || !is_event_stream(response.headers()) {
+        return response;
+    }
+    let Some(registration) = fanout.register(request_id, tenant_key) else {
+        return response;
+    };
+    debug!(request_id = %registration.request_id, "Stream registered for fan-out");
+
+    let (parts, body) = response.into_parts();
+    let body = body.into_data_stream().inspect(move |chunk| {
+        if let Ok(bytes) = chunk {
+            // No subscribers is the common case, not an error.
+            let _ = registration.sender.send(bytes.clone());
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+/// `GET /v1/streams/{request_id}`: live, read-only copy of an in-flight
+/// stream, starting from the next chunk.
+pub async fn subscribe_stream(
+    State(fanout): State<StreamFanout>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(request_id): Path<String>,
+) -> Response {
+    let receiver = match fanout.subscribe(&request_id, meta.tenant_key()) {
+        Ok(receiver) => receiver,
+        Err(response) => return response,
+    };
+    // A lagged subscriber has missed chunks; end its copy rather than send
+    // a corrupted stream.
+    let body = BroadcastStream::new(receiver)
+        .take_while(|chunk| future::ready(chunk.is_ok()))
+        .filter_map(|chunk| future::ready(chunk.ok().map(Ok::<_, Infallible>)));
+
+    let mut response = Response::new(Body::from_stream(body));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fanout(max_subscribers: usize) -> StreamFanout {
+        StreamFanout::new(&StreamFanoutConfig {
+            enabled: true,
+            buffer_chunks: 4,
+            max_subscribers,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_chunks_until_the_stream_ends() {
+        let fanout = fanout(2);
+        let owner = TenantKey::from("tenant-a");
+        let registration = fanout.register("req-1".to_string(), owner.clone()).unwrap();
+        assert!(fanout
+            .register("req-1".to_string(), owner.clone())
+            .is_none());
+
+        let mut receiver = fanout.subscribe("req-1", &owner).unwrap();
+        registration
+            .sender
+            .send(Bytes::from("data: hi\n\n"))
+            .unwrap();
+        assert_eq!(receiver.recv().await.unwrap(), Bytes::from("data: hi\n\n"));
+
+        drop(registration);
+        assert!(receiver.recv().await.is_err());
+        assert!(fanout.subscribe("req-1", &owner).is_err());
+    }
+
+    #[test]
+    fn other_tenants_and_excess_subscribers_are_rejected() {
+        let fanout = fanout(1);
+        let owner = TenantKey::from("tenant-a");
+        let _registration = fanout.register("req-1".to_string(), owner.clone()).unwrap();
+
+        let err = fanout
+            .subscribe("req-1", &TenantKey::from("tenant-b"))
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+
+        let _first = fanout.subscribe("req-1", &owner).unwrap();
+        let err = fanout.subscribe("req-1", &owner).unwrap_err();
+        assert_eq!(err.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn disabled_config_builds_nothing() {
+        assert!(StreamFanout::new(&StreamFanoutConfig::default()).is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Integrity trailers on SSE streams.
+//!
+//! Proxies that drop a connection mid-stream often close the client side
+//! cleanly, so a truncated stream looks complete. When
+//! `stream_integrity.enabled` is set, successful event streams from the
+//! generation routes announce `x-smg-stream-integrity: sha256` up front and
+//! end with an `smg.integrity` SSE event:
+//!
+//! ```text
+//! event: smg.integrity
+//! data: {"events":12,"bytes":3456,"checksum":"sha256:<hex>"}
+//! ```
+//!
+//! `events` counts the complete SSE events and `bytes` the bytes streamed
+//! before the trailer; `checksum` is the SHA-256 of those bytes, hashed as
+//! they pass. A stream that announced the header but ends without the
+//! trailer, or whose counts disagree with what the client received, was cut
+//! short and should be re-requested. Upstream errors end the stream without
+//! a trailer.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::{
+    is_event_stream,
+    stream_trailer::{self, StreamDigest},
+};
+
+static HEADER_STREAM_INTEGRITY: HeaderName = HeaderName::from_static("x-smg-stream-integrity");
+
+/// Counts complete SSE events across arbitrary chunk boundaries. An event
+/// ends at the first blank line after a field line; comment lines (`:`)
+/// are not fields, so keep-alive comments count as nothing. `\r` is ignored
+/// so CRLF streams count the same.
+#[derive(Debug, Default)]
+struct EventCounter {
+    line: Line,
+    in_event: bool,
+    events: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Line {
+    #[default]
+    Empty,
+    Comment,
+    Field,
+}
+
+impl EventCounter {
+    fn push(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            match (byte, self.line) {
+                (b'\n', Line::Empty) => {
+                    if self.in_event {
+                        self.events += 1;
+                        self.in_event = false;
+                    }
+                }
+                (b'\n', line) => {
+                    self.in_event |= line == Line::Field;
+                    self.line = Line::Empty;
+                }
+                (b'\r', _) => {}
+                (b':', Line::Empty) => self.line = Line::Comment,
+                (_, Line::Empty) => self.line = Line::Field,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Running totals for one stream.
+#[derive(Default)]
+struct Tally {
+    hasher: Sha256,
+    counter: EventCounter,
+    bytes: u64,
+}
+
+impl StreamDigest for Tally {
+    fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+        self.counter.push(chunk);
+        self.bytes += chunk.len() as u64;
+    }
+
+    fn trailer(self) -> Bytes {
+        let record = IntegrityRecord {
+            events: self.counter.events,
+            bytes: self.bytes,
+            checksum: stream_trailer::sha256_hex(self.hasher),
+        };
+        stream_trailer::trailer_event("smg.integrity", &record)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IntegrityRecord {
+    events: u64,
+    bytes: u64,
+    checksum: String,
+}
+
+/// Append an integrity trailer to successful generation streams.
+pub async fn stream_integrity_middleware(request: Request<Body>, next: Next) -> Response {
+    if !stream_trailer::is_generation_request(&request) {
+        return next.run(request).await;
+    }
+    let response = next.run(request).await;
+    if !response.status().is_success() || !is_event_stream(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(
+        HEADER_STREAM_INTEGRITY.clone(),
+        HeaderValue::from_static("sha256"),
+    );
+    let body = stream_trailer::append_trailer(body, Tally::default());
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::to_bytes,
+        http::{header, Method},
+        routing::post,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn app(content_type: &'static str, body: &'static str) -> Router {
+        Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(move || async move {
+                    Response::builder()
+                        .header(header::CONTENT_TYPE, content_type)
+                        .body(Body::from(body))
+                        .unwrap()
+                }),
+            )
+            .layer(axum::middleware::from_fn(stream_integrity_middleware))
+    }
+
+    fn request() -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/v1/chat/completions")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn counter_spans_chunk_boundaries() {
+        let mut counter = EventCounter::default();
+        for chunk in [
+            "data: {\"a\"",
+            ":1}\n",
+            "\nevent: x\r\ndata: y\r\n",
+            "\r\n\n\n",
+            ": ping\n\n",
+        ] {
+            counter.push(chunk.as_bytes());
+        }
+        assert_eq!(counter.events, 2);
+        // An event without its closing blank line is incomplete.
+        counter.push(b"data: z\n");
+        assert_eq!(counter.events, 2);
+        counter.push(b"\n");
+        assert_eq!(counter.events, 3);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_with_integrity_event() {
+        let streamed = "data: {\"x\":1}\n\ndata: [DONE]\n\n";
+        let response = app("text/event-stream", streamed)
+            .oneshot(request())
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[&HEADER_STREAM_INTEGRITY], "sha256");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = std::str::from_utf8(&body).unwrap();
+        let (prefix, trailer) = body.split_once("event: smg.integrity\n").unwrap();
+        assert_eq!(prefix, streamed);
+        let record: serde_json::Value = serde_json::from_str(
+            trailer
+                .strip_prefix("data: ")
+                .unwrap()
+                .trim_end_matches('\n'),
+        )
+        .unwrap();
+        let checksum: String = Sha256::digest(streamed.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(record["events"], 2);
+        assert_eq!(record["bytes"], streamed.len());
+        assert_eq!(record["checksum"], format!("sha256:{checksum}"));
+    }
+
+    #[tokio::test]
+    async fn non_streaming_responses_are_untouched() {
+        let response = app("application/json", "{\"x\":1}")
+            .oneshot(request())
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(&HEADER_STREAM_INTEGRITY));
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"{\"x\":1}");
+    }
+}
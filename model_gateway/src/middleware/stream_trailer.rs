@@ -0,0 +1,141 @@
+//! Trailer events appended to generation streams.
+//!
+//! The stream integrity and provenance middlewares both hash a successful
+//! generation stream as it passes and, once the upstream ends cleanly,
+//! append one named SSE event summarizing it. Upstream errors end the
+//! stream without a trailer.
+
+use std::fmt::Write as _;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::Method,
+};
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Routes whose responses are model output.
+const GENERATION_ROUTES: &[&str] = &[
+    "/generate",
+    "/v1/chat/completions",
+    "/v1/completions",
+    "/v1/responses",
+    "/v1/messages",
+    "/v1/interactions",
+];
+
+/// Whether `request` is a POST to one of the generation routes.
+pub(crate) fn is_generation_request(request: &Request<Body>) -> bool {
+    request.method() == Method::POST && GENERATION_ROUTES.contains(&request.uri().path())
+}
+
+/// Observes every streamed chunk and produces the trailer event.
+pub(crate) trait StreamDigest: Send + 'static {
+    fn update(&mut self, chunk: &[u8]);
+
+    /// The event appended after the last chunk.
+    fn trailer(self) -> Bytes;
+}
+
+/// Pass `body` through `digest` and end it with the digest's trailer.
+pub(crate) fn append_trailer<D: StreamDigest>(body: Body, digest: D) -> Body {
+    let state = Some((body.into_data_stream(), digest));
+    let stream = stream::unfold(state, |state| async move {
+        let (mut inner, mut digest) = state?;
+        match inner.next().await {
+            Some(Ok(chunk)) => {
+                digest.update(&chunk);
+                Some((Ok(chunk), Some((inner, digest))))
+            }
+            Some(Err(e)) => Some((Err(e), None)),
+            None => Some((Ok(digest.trailer()), None)),
+        }
+    });
+    Body::from_stream(stream)
+}
+
+/// A named SSE event carrying `data` as JSON.
+pub(crate) fn trailer_event(event: &str, data: &impl Serialize) -> Bytes {
+    let data = serde_json::to_string(data).unwrap_or_default();
+    Bytes::from(format!("event: {event}\ndata: {data}\n\n"))
+}
+
+/// `sha256:<hex>` form of a finished SHA-256 hash.
+pub(crate) fn sha256_hex(hasher: Sha256) -> String {
+    format_sha256(hasher.finalize().into())
+}
+
+/// `sha256:<hex>` form of a SHA-256 digest.
+pub(crate) fn format_sha256(digest: [u8; 32]) -> String {
+    let mut hex = String::with_capacity(71);
+    hex.push_str("sha256:");
+    for byte in digest {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+
+    use super::*;
+
+    struct ChunkCount(usize);
+
+    impl StreamDigest for ChunkCount {
+        fn update(&mut self, _chunk: &[u8]) {
+            self.0 += 1;
+        }
+
+        fn trailer(self) -> Bytes {
+            trailer_event("test.count", &self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn trailer_follows_the_streamed_chunks() {
+        let chunks = ["data: a\n\n", "data: b\n\n"].map(Ok::<_, std::io::Error>);
+        let body = append_trailer(Body::from_stream(stream::iter(chunks)), ChunkCount(0));
+        let body = to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(
+            &body[..],
+            b"data: a\n\ndata: b\n\nevent: test.count\ndata: 2\n\n"
+        );
+    }
+
+    #[test]
+    fn generation_routes_require_post() {
+        let request = |method: Method, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        assert!(is_generation_request(&request(
+            Method::POST,
+            "/v1/chat/completions"
+        )));
+        assert!(!is_generation_request(&request(
+            Method::GET,
+            "/v1/chat/completions"
+        )));
+        assert!(!is_generation_request(&request(
+            Method::POST,
+            "/v1/conversations"
+        )));
+    }
+
+    #[test]
+    fn sha256_hex_matches_digest() {
+        let mut hasher = Sha256::new();
+        hasher.update(b"abc");
+        assert_eq!(
+            sha256_hex(hasher),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
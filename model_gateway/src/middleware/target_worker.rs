@@ -0,0 +1,297 @@
+//! Admin-scoped `X-SMG-Target-Worker` pinning.
+//!
+//! The header bypasses policy selection and sends the request to one named
+//! worker, which is how operators isolate a single misbehaving replica. Only
+//! admin credentials may set it: the control-plane admin role when
+//! control-plane auth is configured, otherwise the shared `--api-key`. The
+//! value may be a worker ID or URL and is rewritten to the worker's URL for
+//! the routers; purely numeric values are left alone for the legacy
+//! `consistent_hashing` index form. Every pin, allowed or denied, is written
+//! to the audit log.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use smg_auth::{AuditContext, AuditLogger, ControlPlaneAuthState, Role};
+
+use super::{auth::AuthConfig, request_id::RequestId};
+use crate::{
+    routers::{common::header_utils::extract_target_worker, error},
+    worker::{registry::WorkerId, WorkerRegistry},
+};
+
+static HEADER_TARGET_WORKER: HeaderName = HeaderName::from_static("x-smg-target-worker");
+
+/// Who may pin, mirroring the gate on the admin routes.
+#[derive(Clone)]
+enum PinAuthority {
+    /// Control-plane auth: JWT or API key with the admin role.
+    ControlPlane(ControlPlaneAuthState),
+    /// Shared gateway-wide `--api-key`.
+    SharedKey(AuthConfig),
+    /// Only tenant keys are configured, so no caller is an admin.
+    Deny,
+    /// No keys at all (dev/test deployment).
+    Open,
+}
+
+/// An admin caller that passed the pin check.
+struct Admin {
+    principal: String,
+    auth_method: &'static str,
+}
+
+#[derive(Clone)]
+pub struct TargetWorkerState {
+    authority: PinAuthority,
+    worker_registry: Arc<WorkerRegistry>,
+    audit_logger: AuditLogger,
+}
+
+impl TargetWorkerState {
+    /// Takes the same auth inputs as the admin routes in `build_app`.
+    pub fn new(
+        serving_auth_config: &AuthConfig,
+        admin_auth_config: &AuthConfig,
+        control_plane_auth_state: Option<&ControlPlaneAuthState>,
+        worker_registry: Arc<WorkerRegistry>,
+    ) -> Self {
+        let authority = match control_plane_auth_state {
+            Some(cp) if cp.is_auth_required() => PinAuthority::ControlPlane(cp.clone()),
+            _ if admin_auth_config.is_enabled() => {
+                PinAuthority::SharedKey(admin_auth_config.clone())
+            }
+            _ if serving_auth_config.is_enabled() => PinAuthority::Deny,
+            _ => PinAuthority::Open,
+        };
+        Self {
+            authority,
+            worker_registry,
+            // Pin overrides are always audited, independent of control-plane
+            // `audit_enabled`.
+            audit_logger: AuditLogger::new(true),
+        }
+    }
+
+    async fn authorize(&self, token: Option<&str>) -> Result<Admin, &'static str> {
+        match &self.authority {
+            PinAuthority::Open => Ok(Admin {
+                principal: "anonymous".to_string(),
+                auth_method: "none",
+            }),
+            PinAuthority::Deny => Err("No admin credential is configured"),
+            PinAuthority::SharedKey(config) => match token {
+                Some(token) if config.contains_token(token) => Ok(Admin {
+                    principal: "shared_api_key".to_string(),
+                    auth_method: "api_key",
+                }),
+                _ => Err("Admin API key required"),
+            },
+            PinAuthority::ControlPlane(cp) => {
+                let token = token.ok_or("Admin role required")?;
+                if let Some(validator) = &cp.jwt_validator {
+                    if let Ok(validated) = validator.validate(token).await {
+                        return if validated.role.is_admin() {
+                            Ok(Admin {
+                                principal: validated.subject,
+                                auth_method: "jwt",
+                            })
+                        } else {
+                            Err("Admin role required")
+                        };
+                    }
+                }
+                match cp.config.find_api_key(token) {
+                    Some(entry) if entry.role.is_admin() => Ok(Admin {
+                        principal: entry.id.clone(),
+                        auth_method: "api_key",
+                    }),
+                    _ => Err("Admin role required"),
+                }
+            }
+        }
+    }
+
+    /// Canonical URL for a worker ID or URL, if registered.
+    fn resolve(&self, target: &str) -> Option<String> {
+        if self.worker_registry.get_by_url(target).is_some() {
+            return Some(target.to_string());
+        }
+        self.worker_registry
+            .get_url_by_id(&WorkerId::from_string(target.to_string()))
+    }
+}
+
+/// Authorize and resolve `X-SMG-Target-Worker`. Requests without the header
+/// pass through untouched. Must run after serving auth.
+pub async fn target_worker_middleware(
+    State(state): State<TargetWorkerState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(target) = extract_target_worker(Some(request.headers())).map(str::to_string) else {
+        return next.run(request).await;
+    };
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let request_id = request.extensions().get::<RequestId>().map(|r| r.0.clone());
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    let admin = match state.authorize(token).await {
+        Ok(admin) => admin,
+        Err(reason) => {
+            let ctx = AuditContext::new(
+                "unknown",
+                "none",
+                Role::User,
+                &method,
+                &path,
+                request_id.as_deref(),
+            );
+            state
+                .audit_logger
+                .log_denied(&ctx, &format!("Worker pin to {target} rejected: {reason}"));
+            return error::create_error(
+                StatusCode::FORBIDDEN,
+                "target_worker_forbidden",
+                "X-SMG-Target-Worker requires an admin credential",
+            );
+        }
+    };
+
+    let resolved = if target.bytes().all(|b| b.is_ascii_digit()) {
+        target.clone()
+    } else {
+        let Some(url) = state.resolve(&target) else {
+            return error::not_found(
+                "target_worker_not_found",
+                format!("Target worker '{target}' is not registered"),
+            );
+        };
+        url
+    };
+
+    if resolved != target {
+        let Ok(value) = HeaderValue::from_str(&resolved) else {
+            return error::bad_request(
+                "invalid_target_worker",
+                "Target worker URL is not a valid header value",
+            );
+        };
+        request
+            .headers_mut()
+            .insert(HEADER_TARGET_WORKER.clone(), value);
+    }
+
+    let ctx = AuditContext::new(
+        &admin.principal,
+        admin.auth_method,
+        Role::Admin,
+        &method,
+        &path,
+        request_id.as_deref(),
+    );
+    state.audit_logger.log_success(&ctx, Some(&resolved));
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware::from_fn_with_state, routing::post, Router};
+    use openai_protocol::worker::HealthCheckConfig;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::worker::BasicWorkerBuilder;
+
+    async fn echo_target(request: Request<Body>) -> String {
+        extract_target_worker(Some(request.headers()))
+            .unwrap_or("none")
+            .to_string()
+    }
+
+    fn app(admin_key: Option<&str>, serving_key: Option<&str>) -> (Router, WorkerId) {
+        let registry = Arc::new(WorkerRegistry::new());
+        let worker_id = registry
+            .register(Arc::new(
+                BasicWorkerBuilder::new("http://w1:8000")
+                    .health_config(HealthCheckConfig {
+                        disable_health_check: true,
+                        ..Default::default()
+                    })
+                    .build(),
+            ))
+            .unwrap();
+        let state = TargetWorkerState::new(
+            &AuthConfig::new(serving_key.map(str::to_string)),
+            &AuthConfig::new(admin_key.map(str::to_string)),
+            None,
+            registry,
+        );
+        let app = Router::new()
+            .route("/v1/chat/completions", post(echo_target))
+            .layer(from_fn_with_state(state, target_worker_middleware));
+        (app, worker_id)
+    }
+
+    async fn send(app: Router, target: &str, token: Option<&str>) -> (StatusCode, String) {
+        let mut builder =
+            Request::post("/v1/chat/completions").header("x-smg-target-worker", target);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = app
+            .oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn pin_requires_admin_key_and_resolves_worker_id() {
+        let (app, worker_id) = app(Some("admin-key"), None);
+
+        let (status, _) = send(app.clone(), "http://w1:8000", Some("other")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = send(app.clone(), worker_id.as_str(), Some("admin-key")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "http://w1:8000");
+
+        let (status, _) = send(app.clone(), "http://missing:8000", Some("admin-key")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Legacy consistent_hashing index form passes through unchanged.
+        let (status, body) = send(app, "1", Some("admin-key")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "1");
+    }
+
+    #[tokio::test]
+    async fn tenant_only_deployments_reject_pins() {
+        let (app, _) = app(None, Some("tenant-key"));
+        let (status, _) = send(app, "http://w1:8000", Some("tenant-key")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (open, _) = self::app(None, None);
+        let (status, body) = send(open, "http://w1:8000", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "http://w1:8000");
+    }
+}
@@ -3,30 +3,54 @@
 //! Dispatches request and response through every WASM module attached at
 //! the corresponding `Middleware::OnRequest` / `Middleware::OnResponse`
 //! point. Streaming responses skip the OnResponse phase to avoid buffering
-//! arbitrary bodies into memory.
+//! arbitrary bodies into memory; SSE streams instead pass event by event
+//! through modules attached at `Middleware::OnResponseChunk`.
+//!
+//! Modules that declare `request_body_fields` run headers-only and receive
+//! those values instead of the body; when every OnRequest module does, the
+//! request body is read only until the fields are found and is otherwise
+//! streamed upstream unbuffered.
+//!
+//! Each OnRequest/OnResponse invocation runs in its own `wasm_middleware`
+//! span, and OnRequest guests see the gateway's current W3C trace context
+//! and baggage in their request headers so they can join the trace.
 
 use std::{sync::Arc, time::Duration};
 
 use axum::{
     body::Body,
     extract::{Request, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use bytes::Bytes;
+use futures_util::StreamExt;
 use serde_json::json;
-use tracing::{error, warn};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{error, info_span, warn, Instrument, Span};
 
-use super::request_id::{generate_request_id, RequestId};
+use super::{
+    is_event_stream, is_streaming_response,
+    request_id::{generate_request_id, RequestId},
+};
 use crate::{
+    observability::otel_trace::{inject_trace_context_http, is_otel_enabled},
+    routers::common::sse::SseDecoder,
     server::AppState,
     wasm::{
-        module::{MiddlewareAttachPoint, WasmModuleAttachPoint},
+        body_fields::{request_body_for_module, BodyFieldExtractor},
+        module::{MiddlewareAttachPoint, WasmModule, WasmModuleAttachPoint},
+        module_manager::WasmModuleManager,
+        response_stream_spec::smg::response_stream::response_stream_types::{
+            ChunkAction, ChunkContext, SseEvent,
+        },
         spec::{
             apply_modify_action_to_headers, build_wasm_headers_from_axum_headers,
             smg::gateway::middleware_types::{
-                Action, Request as WasmRequest, Response as WasmResponse,
+                Action, Header as WasmHeader, Request as WasmRequest, Response as WasmResponse,
             },
         },
         types::WasmComponentInput,
@@ -57,6 +81,7 @@ pub async fn wasm_middleware(
         .get::<RequestId>()
         .map(|r| r.0.clone())
         .unwrap_or_else(|| generate_request_id(request.uri().path()));
+    let path = request.uri().path().to_string();
 
     // ===== OnRequest Phase =====
     let on_request_attach_point =
@@ -81,40 +106,76 @@ pub async fn wasm_middleware(
         let mut headers = parts.headers;
         let extensions = parts.extensions;
 
+        // Headers-only modules need just their declared fields, so when every
+        // module is headers-only the body is read only until those are found.
         let max_body_size = wasm_manager.get_max_body_size();
-        let body_bytes = match axum::body::to_bytes(body, max_body_size).await {
-            Ok(bytes) => bytes.to_vec(),
-            Err(e) => {
-                error!("Failed to read request body for WASM processing: {}", e);
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": format!("Failed to read request body: {e}")})),
-                )
-                    .into_response();
+        let headers_only = modules_on_request
+            .iter()
+            .all(|m| m.module_meta.request_body_fields.is_some());
+        let mut request_body = if headers_only {
+            let pointers: Vec<&String> = modules_on_request
+                .iter()
+                .flat_map(|m| m.module_meta.request_body_fields.iter().flatten())
+                .collect();
+            match extract_body_fields_streaming(body, pointers, max_body_size).await {
+                Ok((fields, body)) => RequestBody::Streamed { fields, body },
+                Err(e) => {
+                    error!("Failed to read request body for WASM processing: {}", e);
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": format!("Failed to read request body: {e}")})),
+                    )
+                        .into_response();
+                }
+            }
+        } else {
+            match axum::body::to_bytes(body, max_body_size).await {
+                Ok(bytes) => RequestBody::Buffered(bytes.to_vec()),
+                Err(e) => {
+                    error!("Failed to read request body for WASM processing: {}", e);
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": format!("Failed to read request body: {e}")})),
+                    )
+                        .into_response();
+                }
             }
         };
 
-        // Process each OnRequest module
-        let mut modified_body = body_bytes;
-
         // Pre-compute strings once before the loop to avoid repeated allocations
         let method_str = method.to_string();
         let path_str = uri.path().to_string();
         let query_str = uri.query().unwrap_or("").to_string();
 
+        // Process each OnRequest module
         for module in modules_on_request {
-            let wasm_headers = build_wasm_headers_from_axum_headers(&headers);
+            let span = module_span(&module, "on_request");
+            let wasm_headers = guest_request_headers(&headers, &span);
+            let (body, body_fields) = match &request_body {
+                RequestBody::Buffered(bytes) => request_body_for_module(&module.module_meta, bytes),
+                RequestBody::Streamed { fields, .. } => (
+                    Vec::new(),
+                    fields.fields(
+                        module
+                            .module_meta
+                            .request_body_fields
+                            .as_deref()
+                            .unwrap_or_default(),
+                    ),
+                ),
+            };
             let wasm_request = WasmRequest {
                 method: method_str.clone(),
                 path: path_str.clone(),
                 query: query_str.clone(),
                 headers: wasm_headers,
-                body: modified_body.clone(),
+                body,
                 request_id: request_id.clone(),
                 now_epoch_ms: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_else(|_| Duration::from_millis(0))
                     .as_millis() as u64,
+                body_fields,
             };
 
             let action = match wasm_manager
@@ -123,6 +184,7 @@ pub async fn wasm_middleware(
                     on_request_attach_point.clone(),
                     WasmComponentInput::MiddlewareRequest(wasm_request),
                 )
+                .instrument(span)
                 .await
             {
                 Some(action) => action,
@@ -139,17 +201,22 @@ pub async fn wasm_middleware(
                 Action::Modify(modify) => {
                     apply_modify_action_to_headers(&mut headers, &modify);
                     if let Some(body_bytes) = modify.body_replace {
-                        modified_body = body_bytes;
+                        request_body = RequestBody::Buffered(body_bytes);
                     }
                 }
             }
         }
 
+        let body = match request_body {
+            RequestBody::Buffered(bytes) => Body::from(bytes),
+            RequestBody::Streamed { body, .. } => body,
+        };
+
         // Reconstruct request with modifications, preserving original extensions
         let mut final_request = Request::builder()
             .method(method)
             .uri(uri)
-            .body(Body::from(modified_body))
+            .body(body)
             .unwrap_or_else(|_| Request::new(Body::empty()));
         *final_request.headers_mut() = headers;
         *final_request.extensions_mut() = extensions;
@@ -169,24 +236,16 @@ pub async fn wasm_middleware(
                 return response;
             }
         };
-    if modules_on_response.is_empty() {
-        return response;
-    }
 
     // Skip WASM OnResponse processing for streaming responses to avoid
     // buffering the entire stream into memory (breaks SSE, causes OOM on large streams).
-    let is_streaming = response
-        .headers()
-        .get(header::CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .is_some_and(|ct| ct.contains("text/event-stream") || ct.contains("application/x-ndjson"))
-        || response
-            .headers()
-            .get(header::TRANSFER_ENCODING)
-            .and_then(|v| v.to_str().ok())
-            .is_some_and(|te| te.contains("chunked"));
-    if is_streaming {
-        warn!("Skipping WASM OnResponse for streaming response; OnResponse modules do not apply to streaming");
+    if is_streaming_response(response.headers()) {
+        if !modules_on_response.is_empty() {
+            warn!("Skipping WASM OnResponse for streaming response; OnResponse modules do not apply to streaming");
+        }
+        return apply_response_chunk_modules(wasm_manager, response, request_id, path);
+    }
+    if modules_on_response.is_empty() {
         return response;
     }
 
@@ -208,6 +267,7 @@ pub async fn wasm_middleware(
 
     // Process each OnResponse module
     for module in modules_on_response {
+        let span = module_span(&module, "on_response");
         let wasm_headers = build_wasm_headers_from_axum_headers(&headers);
         let wasm_response = WasmResponse {
             status: status.as_u16(),
@@ -221,6 +281,7 @@ pub async fn wasm_middleware(
                 on_response_attach_point.clone(),
                 WasmComponentInput::MiddlewareResponse(wasm_response),
             )
+            .instrument(span)
             .await
         {
             Some(action) => action,
@@ -258,3 +319,376 @@ pub async fn wasm_middleware(
     *final_response.headers_mut() = headers;
     final_response
 }
+
+/// Request body during the OnRequest phase.
+enum RequestBody {
+    /// Read in full, or replaced by a module
+    Buffered(Vec<u8>),
+    /// Read only as far as the declared fields; `body` replays the read
+    /// prefix followed by the unread remainder
+    Streamed {
+        fields: BodyFieldExtractor,
+        body: Body,
+    },
+}
+
+/// Read `body` until every field in `pointers` was found, the body ended, or
+/// `limit` bytes were read, without buffering the rest.
+async fn extract_body_fields_streaming(
+    body: Body,
+    pointers: Vec<&String>,
+    limit: usize,
+) -> Result<(BodyFieldExtractor, Body), axum::Error> {
+    let mut fields = BodyFieldExtractor::new(pointers);
+    let mut stream = body.into_data_stream();
+    let mut prefix = Vec::new();
+    let mut read = 0;
+    while !fields.is_done() && read < limit {
+        match stream.next().await {
+            Some(Ok(chunk)) => {
+                read += chunk.len();
+                fields.feed(&chunk);
+                prefix.push(Ok(chunk));
+            }
+            Some(Err(e)) => return Err(e),
+            None => break,
+        }
+    }
+    let body = Body::from_stream(futures_util::stream::iter(prefix).chain(stream));
+    Ok((fields, body))
+}
+
+/// Span covering a single module invocation, so middleware time shows up in
+/// the request's trace.
+fn module_span(module: &WasmModule, attach_point: &'static str) -> Span {
+    info_span!(
+        target: "smg::otel-trace",
+        "wasm_middleware",
+        module = %module.module_meta.name,
+        attach_point,
+    )
+}
+
+/// Request headers as seen by the guest, with the trace context and baggage
+/// of `span` injected. The injected entries are not forwarded upstream; the
+/// router injects its own context on the outgoing request.
+fn guest_request_headers(headers: &HeaderMap, span: &Span) -> Vec<WasmHeader> {
+    if !is_otel_enabled() {
+        return build_wasm_headers_from_axum_headers(headers);
+    }
+    let mut headers = headers.clone();
+    span.in_scope(|| inject_trace_context_http(&mut headers));
+    build_wasm_headers_from_axum_headers(&headers)
+}
+
+// ===== OnResponseChunk Phase =====
+
+/// Channel buffer between the chunk relay task and the client body. Bounded
+/// so a slow client applies backpressure to the upstream read instead of
+/// piling transformed events in memory.
+const CHUNK_RELAY_BUFFER: usize = 32;
+
+/// Relay an SSE response through every OnResponseChunk module, one decoded
+/// event at a time. Modules run in attach order, each seeing the events the
+/// previous one emitted; a module that fails or overruns the per-chunk budget
+/// leaves the event untouched. Blocks without `data:` (e.g. a bare `retry:`)
+/// are forwarded verbatim; comment and keep-alive blocks are not forwarded
+/// once any module is attached.
+fn apply_response_chunk_modules(
+    wasm_manager: &Arc<WasmModuleManager>,
+    response: Response,
+    request_id: String,
+    path: String,
+) -> Response {
+    if !is_event_stream(response.headers()) {
+        return response;
+    }
+
+    let modules = match wasm_manager.get_modules_by_attach_point(WasmModuleAttachPoint::Middleware(
+        MiddlewareAttachPoint::OnResponseChunk,
+    )) {
+        Ok(modules) => modules,
+        Err(e) => {
+            error!("Failed to get WASM modules for OnResponseChunk: {}", e);
+            return response;
+        }
+    };
+    if modules.is_empty() {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(CHUNK_RELAY_BUFFER);
+    let wasm_manager = Arc::clone(wasm_manager);
+
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "relay task ends with the upstream body or when the client disconnects"
+    )]
+    tokio::spawn(async move {
+        let mut stream = body.into_data_stream();
+        let mut decoder = SseDecoder::new();
+        let mut relay = ChunkRelay {
+            wasm_manager: &wasm_manager,
+            modules: &modules,
+            request_id,
+            path,
+            index: 0,
+            tx: &tx,
+        };
+
+        while let Some(chunk) = stream.next().await {
+            let pushed = chunk
+                .map_err(|e| e.to_string())
+                .and_then(|chunk| decoder.push(&chunk).map_err(|e| e.to_string()));
+            if let Err(e) = pushed {
+                warn!("WASM OnResponseChunk relay aborted: {}", e);
+                let _ = tx.send(Err(std::io::Error::other(e))).await;
+                return;
+            }
+
+            while let Some(block) = decoder.next_block() {
+                let block = match block {
+                    Ok(block) => block,
+                    Err(e) => {
+                        warn!("Dropping undecodable SSE block: {}", e);
+                        continue;
+                    }
+                };
+                if !relay.forward(&block).await {
+                    return; // client disconnected
+                }
+            }
+            decoder.compact();
+        }
+
+        if let Some(Ok(block)) = decoder.flush_block() {
+            relay.forward(&block).await;
+        }
+    });
+
+    Response::from_parts(parts, Body::from_stream(ReceiverStream::new(rx)))
+}
+
+/// Per-stream state of the OnResponseChunk relay task.
+struct ChunkRelay<'a> {
+    wasm_manager: &'a WasmModuleManager,
+    modules: &'a [WasmModule],
+    request_id: String,
+    path: String,
+    index: u32,
+    tx: &'a mpsc::Sender<Result<Bytes, std::io::Error>>,
+}
+
+impl ChunkRelay<'_> {
+    /// Run one SSE block through the modules and send the result to the
+    /// client. Returns `false` once the client has disconnected.
+    async fn forward(&mut self, block: &str) -> bool {
+        let Some(event) = decode_sse_event(block) else {
+            if is_comment_block(block) {
+                return true;
+            }
+            return self.send(Bytes::from(format!("{block}\n\n"))).await;
+        };
+
+        let context = ChunkContext {
+            request_id: self.request_id.clone(),
+            path: self.path.clone(),
+            index: self.index,
+        };
+        self.index = self.index.saturating_add(1);
+        for event in transform_event(self.wasm_manager, self.modules, context, event).await {
+            if !self.send(encode_sse_event(&event)).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn send(&self, bytes: Bytes) -> bool {
+        self.tx.send(Ok(bytes)).await.is_ok()
+    }
+}
+
+async fn transform_event(
+    wasm_manager: &WasmModuleManager,
+    modules: &[WasmModule],
+    context: ChunkContext,
+    event: SseEvent,
+) -> Vec<SseEvent> {
+    let mut events = vec![event];
+
+    for module in modules {
+        let mut next = Vec::with_capacity(events.len());
+        for event in events {
+            match wasm_manager
+                .execute_response_chunk(module, context.clone(), event.clone())
+                .await
+            {
+                Some(ChunkAction::Continue) | None => next.push(event),
+                Some(ChunkAction::Discard) => {}
+                Some(ChunkAction::Replace(replacement)) => next.extend(replacement),
+            }
+        }
+        events = next;
+    }
+    events
+}
+
+/// Decode an SSE block (EOLs already normalized to `\n`) into an event,
+/// keeping its `event`, `id` and `retry` fields alongside the data. Returns
+/// `None` for blocks without a `data:` line, which dispatch no event. Per the
+/// SSE spec an `id` containing NUL and a non-numeric `retry` are ignored.
+fn decode_sse_event(block: &str) -> Option<SseEvent> {
+    let mut event = SseEvent {
+        event: None,
+        data: String::new(),
+        id: None,
+        retry: None,
+    };
+    let mut has_data = false;
+
+    for line in block.split('\n') {
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((f, v)) => (f, v.strip_prefix(' ').unwrap_or(v)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => {
+                if has_data {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+                has_data = true;
+            }
+            "event" => event.event = Some(value.to_string()),
+            "id" if !value.contains('\0') => event.id = Some(value.to_string()),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                event.retry = value.parse().ok();
+            }
+            _ => {}
+        }
+    }
+
+    has_data.then_some(event)
+}
+
+/// Whether a block holds only comments (e.g. `: keep-alive`).
+fn is_comment_block(block: &str) -> bool {
+    block
+        .split('\n')
+        .all(|line| line.is_empty() || line.starts_with(':'))
+}
+
+/// Re-frame an event as `event:`/`id:`/`retry:`/`data:` lines, splitting
+/// multi-line data into one `data:` line each. Line breaks in the event name
+/// or id would break framing, so only their first line is kept.
+fn encode_sse_event(event: &SseEvent) -> Bytes {
+    let mut out = String::with_capacity(event.data.len() + 16);
+    if let Some(name) = &event.event {
+        out.push_str("event: ");
+        out.push_str(name.split(['\r', '\n']).next().unwrap_or_default());
+        out.push('\n');
+    }
+    if let Some(id) = &event.id {
+        out.push_str("id: ");
+        out.push_str(id.split(['\r', '\n']).next().unwrap_or_default());
+        out.push('\n');
+    }
+    if let Some(retry) = event.retry {
+        out.push_str("retry: ");
+        out.push_str(&retry.to_string());
+        out.push('\n');
+    }
+    for line in event.data.split('\n') {
+        out.push_str("data: ");
+        out.push_str(line.strip_suffix('\r').unwrap_or(line));
+        out.push('\n');
+    }
+    out.push('\n');
+    Bytes::from(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routers::common::sse::parse_block;
+
+    #[test]
+    fn test_encode_sse_event_round_trips() {
+        let event = SseEvent {
+            event: Some("message_delta".to_string()),
+            data: "{\"a\":1}\nsecond".to_string(),
+            id: None,
+            retry: None,
+        };
+        let encoded = encode_sse_event(&event);
+        assert_eq!(
+            encoded.as_ref(),
+            b"event: message_delta\ndata: {\"a\":1}\ndata: second\n\n"
+        );
+
+        let text = std::str::from_utf8(&encoded).unwrap();
+        let frame = parse_block(text).unwrap();
+        assert_eq!(frame.event_type.as_deref(), Some("message_delta"));
+        assert_eq!(frame.data, event.data);
+    }
+
+    #[test]
+    fn test_encode_sse_event_strips_newlines_from_event_name() {
+        let event = SseEvent {
+            event: Some("bad\ndata: injected".to_string()),
+            data: "x".to_string(),
+            id: None,
+            retry: None,
+        };
+        assert_eq!(
+            encode_sse_event(&event).as_ref(),
+            b"event: bad\ndata: x\n\n"
+        );
+    }
+
+    #[test]
+    fn test_id_and_retry_survive_decode_and_encode() {
+        let block = "event: delta\nid: 42\nretry: 3000\n: note\ndata: a\ndata: b";
+        let event = decode_sse_event(block).unwrap();
+        assert_eq!(event.event.as_deref(), Some("delta"));
+        assert_eq!(event.id.as_deref(), Some("42"));
+        assert_eq!(event.retry, Some(3000));
+        assert_eq!(event.data, "a\nb");
+        assert_eq!(
+            encode_sse_event(&event).as_ref(),
+            b"event: delta\nid: 42\nretry: 3000\ndata: a\ndata: b\n\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_sse_event_ignores_invalid_id_and_retry() {
+        let event = decode_sse_event("id: a\0b\nretry: 1s\ndata: x").unwrap();
+        assert_eq!(event.id, None);
+        assert_eq!(event.retry, None);
+    }
+
+    #[test]
+    fn test_blocks_without_data_are_not_events() {
+        assert!(decode_sse_event("retry: 500").is_none());
+        assert!(!is_comment_block("retry: 500"));
+        assert!(decode_sse_event(": keep-alive").is_none());
+        assert!(is_comment_block(": keep-alive"));
+    }
+
+    #[test]
+    fn test_is_streaming_response() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_streaming_response(&headers));
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/event-stream"),
+        );
+        assert!(is_streaming_response(&headers));
+    }
+}
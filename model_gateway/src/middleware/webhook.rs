@@ -0,0 +1,544 @@
+//! Webhook delivery of completed inference results.
+//!
+//! When `webhooks.enabled` is set, a successful non-streaming serving
+//! response is POSTed to a webhook URL taken from the request's
+//! `x-smg-webhook-url` header (if `allow_request_urls`) or from the tenant's
+//! default in `tenant_urls`. Request-supplied URLs must resolve to public
+//! addresses, checked on arrival and again before every delivery attempt,
+//! and their deliveries refuse redirects to non-public addresses. The payload
+//! carries the response body, or only its `id` as a storage reference when
+//! the body exceeds `max_payload_bytes`. With a `secret`, each delivery is
+//! signed with HMAC-SHA256 over `"{timestamp}.{body}"`. Requests tagged with
+//! `x-smg-tags` carry their accepted tags in a `tags` object. Deliveries run
+//! in the background and are retried with exponential backoff; deliveries
+//! that still fail are recorded as dead letters, listed by the
+//! `/debug/webhooks/dead_letters` admin endpoints.
+
+use std::{collections::VecDeque, fmt::Write as _, sync::Arc, time::Duration};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use llm_multimodal::fetch::{ensure_public_host, public_redirect_policy};
+use parking_lot::Mutex;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use smg_data_connector::GenerationJob;
+use tracing::{debug, warn};
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    is_streaming_response, request_id::RequestId, request_tags::RequestTags, signing::hmac_sha256,
+    RouteRequestMeta,
+};
+use crate::{config::WebhookConfig, routers::error};
+
+/// Per-request webhook URL header.
+pub const WEBHOOK_URL_HEADER: &str = "x-smg-webhook-url";
+const WEBHOOK_ID_HEADER: &str = "x-smg-webhook-id";
+const WEBHOOK_TIMESTAMP_HEADER: &str = "x-smg-webhook-timestamp";
+const WEBHOOK_SIGNATURE_HEADER: &str = "x-smg-webhook-signature";
+
+/// A delivery that exhausted its attempts.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub webhook_id: String,
+    pub url: String,
+    pub request_id: Option<String>,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+    pub payload: Value,
+}
+
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    client: Client,
+    /// Client for request-supplied URLs, refusing redirects to non-public
+    /// addresses; the build error if it could not be created.
+    untrusted_client: Result<Client, String>,
+    config: Arc<WebhookConfig>,
+    dead_letters: Arc<Mutex<VecDeque<DeadLetter>>>,
+}
+
+/// Where a delivery goes. `untrusted` URLs came from the request rather than
+/// the gateway config.
+struct WebhookTarget {
+    url: String,
+    untrusted: bool,
+}
+
+struct Delivery {
+    webhook_id: String,
+    target: WebhookTarget,
+    request_id: Option<String>,
+    payload: Value,
+}
+
+impl WebhookDispatcher {
+    /// `None` when webhooks are disabled.
+    pub fn new(config: &WebhookConfig, client: Client) -> Option<Self> {
+        config.enabled.then(|| Self {
+            client,
+            untrusted_client: Client::builder()
+                .redirect(public_redirect_policy())
+                .build()
+                .map_err(|e| e.to_string()),
+            config: Arc::new(config.clone()),
+            dead_letters: Arc::new(Mutex::new(VecDeque::new())),
+        })
+    }
+
+    /// The request's webhook URL, falling back to the tenant default.
+    async fn resolve_target(
+        &self,
+        headers: &HeaderMap,
+        meta: Option<&RouteRequestMeta>,
+    ) -> Result<Option<WebhookTarget>, Response> {
+        if let Some(value) = headers.get(WEBHOOK_URL_HEADER) {
+            if !self.config.allow_request_urls {
+                return Err(error::bad_request(
+                    "webhook_url_not_allowed",
+                    format!("{WEBHOOK_URL_HEADER} is not enabled on this gateway"),
+                ));
+            }
+            let Some(url) = value
+                .to_str()
+                .ok()
+                .and_then(|url| Url::parse(url).ok())
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+            else {
+                return Err(error::bad_request(
+                    "invalid_webhook_url",
+                    format!("{WEBHOOK_URL_HEADER} must be an absolute http(s) URL"),
+                ));
+            };
+            if let Err(e) = &self.untrusted_client {
+                return Err(error::service_unavailable(
+                    "webhook_unavailable",
+                    format!("{WEBHOOK_URL_HEADER} cannot be served: {e}"),
+                ));
+            }
+            if let Err(e) = ensure_public_host(&url).await {
+                return Err(error::bad_request(
+                    "invalid_webhook_url",
+                    format!("{WEBHOOK_URL_HEADER} must resolve to a public address: {e}"),
+                ));
+            }
+            return Ok(Some(WebhookTarget {
+                url: url.into(),
+                untrusted: true,
+            }));
+        }
+        Ok(meta.and_then(|meta| {
+            let url = self.config.tenant_urls.get(meta.tenant_key().as_str())?;
+            Some(WebhookTarget {
+                url: url.clone(),
+                untrusted: false,
+            })
+        }))
+    }
+
+    /// Validate the request's own webhook URL for an async job. The tenant
+    /// default is looked up when the job finishes instead.
+    pub(crate) async fn job_request_url(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<Option<String>, Response> {
+        Ok(self
+            .resolve_target(headers, None)
+            .await?
+            .map(|target| target.url))
+    }
+
+    /// Deliver a finished async generation job to its request URL, or else
+    /// to its tenant's default.
+    pub(crate) fn deliver_job(&self, job: &GenerationJob) {
+        let target = match &job.webhook_url {
+            Some(url) => WebhookTarget {
+                url: url.clone(),
+                untrusted: true,
+            },
+            None => match self.config.tenant_urls.get(&job.tenant_key) {
+                Some(url) => WebhookTarget {
+                    url: url.clone(),
+                    untrusted: false,
+                },
+                None => return,
+            },
+        };
+        let webhook_id = format!("whk_{}", Uuid::now_v7().simple());
+        let mut payload = json!({
+            "id": webhook_id,
+            "type": "generation.job.completed",
+            "created_at": Utc::now().timestamp(),
+            "job_id": job.id,
+            "status": job.status,
+            "path": job.endpoint,
+        });
+        if let Some(result) = &job.result {
+            if result.to_string().len() <= self.config.max_payload_bytes {
+                payload["response"] = result.clone();
+            } else {
+                payload["response_ref"] = json!({ "id": job.id });
+            }
+        }
+        if let Some(error) = &job.error {
+            payload["error"] = error.clone();
+        }
+        self.spawn(Delivery {
+            webhook_id,
+            target,
+            request_id: None,
+            payload,
+        });
+    }
+
+    fn payload(
+        &self,
+        webhook_id: &str,
+        request_id: Option<&str>,
+        path: &str,
+        body: &Bytes,
+    ) -> Value {
+        let mut payload = json!({
+            "id": webhook_id,
+            "type": "inference.completed",
+            "created_at": Utc::now().timestamp(),
+            "request_id": request_id,
+            "path": path,
+        });
+        match serde_json::from_slice::<Value>(body) {
+            Ok(value) if body.len() <= self.config.max_payload_bytes => {
+                payload["response"] = value;
+            }
+            Ok(value) => payload["response_ref"] = json!({ "id": value.get("id") }),
+            Err(_) => payload["response_ref"] = json!({ "id": null }),
+        }
+        payload
+    }
+
+    fn spawn(&self, delivery: Delivery) {
+        let dispatcher = self.clone();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "fire-and-forget delivery: webhook retries must not hold the response"
+        )]
+        tokio::spawn(async move { dispatcher.deliver(delivery).await });
+    }
+
+    async fn deliver(&self, delivery: Delivery) {
+        let body = delivery.payload.to_string();
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut last_error = String::new();
+        let mut attempts = 0;
+
+        for attempt in 1..=max_attempts {
+            attempts = attempt;
+            match self.send(&delivery, &body).await {
+                Ok(()) => {
+                    debug!(webhook_id = %delivery.webhook_id, attempt, "Webhook delivered");
+                    return;
+                }
+                Err((error, retryable)) => {
+                    warn!(
+                        webhook_id = %delivery.webhook_id,
+                        attempt,
+                        error = %error,
+                        "Webhook delivery failed"
+                    );
+                    last_error = error;
+                    if !retryable {
+                        break;
+                    }
+                }
+            }
+            if attempt < max_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2);
+            }
+        }
+
+        self.record_dead_letter(DeadLetter {
+            webhook_id: delivery.webhook_id,
+            url: delivery.target.url,
+            request_id: delivery.request_id,
+            attempts,
+            last_error,
+            failed_at: Utc::now(),
+            payload: delivery.payload,
+        });
+    }
+
+    /// One attempt. Errors carry whether the failure is worth retrying.
+    async fn send(&self, delivery: &Delivery, body: &str) -> Result<(), (String, bool)> {
+        let client = if delivery.target.untrusted {
+            // The host may resolve differently than when the request arrived.
+            let url = Url::parse(&delivery.target.url).map_err(|e| (e.to_string(), false))?;
+            ensure_public_host(&url)
+                .await
+                .map_err(|e| (e.to_string(), false))?;
+            self.untrusted_client
+                .as_ref()
+                .map_err(|e| (e.clone(), false))?
+        } else {
+            &self.client
+        };
+        let timestamp = Utc::now().timestamp().to_string();
+        let mut request = client
+            .post(&delivery.target.url)
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_ID_HEADER, &delivery.webhook_id)
+            .header(WEBHOOK_TIMESTAMP_HEADER, &timestamp);
+        if let Some(secret) = &self.config.secret {
+            request = request.header(WEBHOOK_SIGNATURE_HEADER, sign(secret, &timestamp, body));
+        }
+
+        let response = request
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| (e.to_string(), true))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+        Err((format!("endpoint returned {status}"), retryable))
+    }
+
+    fn record_dead_letter(&self, dead_letter: DeadLetter) {
+        let mut dead_letters = self.dead_letters.lock();
+        while dead_letters.len() >= self.config.dead_letter_capacity.max(1) {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(dead_letter);
+    }
+}
+
+/// `v1=<hex HMAC-SHA256 of "{timestamp}.{body}">`
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mac = hmac_sha256(secret.as_bytes(), format!("{timestamp}.{body}").as_bytes());
+    let mut signature = String::with_capacity(67);
+    signature.push_str("v1=");
+    for byte in mac {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+/// Deliver successful non-streaming responses to the resolved webhook URL.
+pub async fn webhook_middleware(
+    State(dispatcher): State<WebhookDispatcher>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let target = match dispatcher
+        .resolve_target(
+            request.headers(),
+            request.extensions().get::<RouteRequestMeta>(),
+        )
+        .await
+    {
+        Ok(Some(target)) => target,
+        Ok(None) => return next.run(request).await,
+        Err(response) => return response,
+    };
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone());
+    let tags = request.extensions().get::<RequestTags>().cloned();
+    let path = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    if !response.status().is_success() || is_streaming_response(response.headers()) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_gateway(
+                "upstream_body_error",
+                format!("Failed to read response body: {e}"),
+            )
+        }
+    };
+    let webhook_id = format!("whk_{}", Uuid::now_v7().simple());
+    let mut payload = dispatcher.payload(&webhook_id, request_id.as_deref(), &path, &bytes);
+    if let Some(tags) = tags {
+        payload["tags"] = tags.to_json();
+    }
+    dispatcher.spawn(Delivery {
+        webhook_id,
+        target,
+        request_id,
+        payload,
+    });
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+fn webhooks_unavailable() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Webhooks are not enabled"})),
+    )
+        .into_response()
+}
+
+/// `GET /debug/webhooks/dead_letters`
+pub async fn list_dead_letters(State(dispatcher): State<Option<WebhookDispatcher>>) -> Response {
+    let Some(dispatcher) = dispatcher else {
+        return webhooks_unavailable();
+    };
+    let dead_letters: Vec<DeadLetter> = dispatcher.dead_letters.lock().iter().cloned().collect();
+    Json(json!({ "object": "list", "data": dead_letters })).into_response()
+}
+
+/// `DELETE /debug/webhooks/dead_letters`
+pub async fn clear_dead_letters(State(dispatcher): State<Option<WebhookDispatcher>>) -> Response {
+    let Some(dispatcher) = dispatcher else {
+        return webhooks_unavailable();
+    };
+    let cleared = std::mem::take(&mut *dispatcher.dead_letters.lock()).len();
+    Json(json!({ "cleared": cleared })).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+    use crate::tenant::TenantKey;
+
+    fn dispatcher(config: WebhookConfig) -> WebhookDispatcher {
+        WebhookDispatcher::new(
+            &WebhookConfig {
+                enabled: true,
+                ..config
+            },
+            Client::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn signature_is_versioned_hex_hmac() {
+        let signature = sign("secret", "1700000000", "{}");
+        let mac: String = hmac_sha256(b"secret", b"1700000000.{}")
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(signature, format!("v1={mac}"));
+    }
+
+    #[tokio::test]
+    async fn request_url_requires_opt_in_and_tenant_default_applies() {
+        let mut config = WebhookConfig::default();
+        config.tenant_urls.insert(
+            "team-red".to_string(),
+            "https://hooks.example.com/red".to_string(),
+        );
+        let meta = RouteRequestMeta::new(TenantKey::from("team-red"));
+
+        let mut headers = HeaderMap::new();
+        let closed = dispatcher(config.clone());
+        let target = closed
+            .resolve_target(&headers, Some(&meta))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.url, "https://hooks.example.com/red");
+        assert!(!target.untrusted);
+
+        headers.insert(
+            WEBHOOK_URL_HEADER,
+            HeaderValue::from_static("https://93.184.216.34/job"),
+        );
+        assert!(closed.resolve_target(&headers, Some(&meta)).await.is_err());
+
+        config.allow_request_urls = true;
+        let open = dispatcher(config);
+        let target = open
+            .resolve_target(&headers, Some(&meta))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.url, "https://93.184.216.34/job");
+        assert!(target.untrusted);
+        headers.insert(WEBHOOK_URL_HEADER, HeaderValue::from_static("file:///etc"));
+        assert!(open.resolve_target(&headers, Some(&meta)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn request_urls_must_resolve_to_public_addresses() {
+        let open = dispatcher(WebhookConfig {
+            allow_request_urls: true,
+            ..Default::default()
+        });
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5/hook",
+            "http://[::1]/hook",
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(WEBHOOK_URL_HEADER, HeaderValue::from_static(url));
+            assert!(
+                open.resolve_target(&headers, None).await.is_err(),
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn large_responses_are_sent_by_reference() {
+        let dispatcher = dispatcher(WebhookConfig {
+            max_payload_bytes: 16,
+            ..Default::default()
+        });
+        let body = Bytes::from(r#"{"id":"chatcmpl-1","choices":[]}"#);
+        let payload = dispatcher.payload("whk_1", Some("req-1"), "/v1/chat/completions", &body);
+        assert_eq!(payload["response_ref"]["id"], "chatcmpl-1");
+        assert!(payload.get("response").is_none());
+        assert_eq!(payload["request_id"], "req-1");
+    }
+
+    #[test]
+    fn dead_letters_are_bounded() {
+        let dispatcher = dispatcher(WebhookConfig {
+            dead_letter_capacity: 2,
+            ..Default::default()
+        });
+        for i in 0..3 {
+            dispatcher.record_dead_letter(DeadLetter {
+                webhook_id: format!("whk_{i}"),
+                url: "https://hooks.example.com".to_string(),
+                request_id: None,
+                attempts: 1,
+                last_error: "endpoint returned 500".to_string(),
+                failed_at: Utc::now(),
+                payload: Value::Null,
+            });
+        }
+        let dead_letters = dispatcher.dead_letters.lock();
+        assert_eq!(dead_letters.len(), 2);
+        assert_eq!(dead_letters[0].webhook_id, "whk_1");
+    }
+}
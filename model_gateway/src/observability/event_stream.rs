@@ -0,0 +1,414 @@
+//! Live gateway event stream for dashboards (`GET /admin/events/stream`).
+//!
+//! Worker lifecycle and health transitions are pushed from the
+//! [`WorkerRegistry`] broadcast channel as they happen. Circuit breaker
+//! state and admission queue depth have no channel of their own, so each
+//! connection samples them every [`SAMPLE_INTERVAL`] and emits only changes.
+//!
+//! Every connection starts with a `snapshot` of the fleet so a client can
+//! render without a separate fetch. A `lagged` event means the client fell
+//! behind the registry channel and should treat its view as stale until the
+//! next snapshot (reconnect).
+//!
+//! Clients that send a WebSocket upgrade get one JSON text frame per event;
+//! everything else gets Server-Sent Events with the same JSON as `data`.
+
+use std::{collections::HashMap, convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        FromRequestParts, Request,
+    },
+    http::header::UPGRADE,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use openai_protocol::worker::WorkerStatus;
+use serde::Serialize;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tracing::debug;
+
+use crate::{
+    middleware::{
+        scheduler::{Class, PriorityScheduler},
+        QueuedRequest,
+    },
+    worker::{event::WorkerEvent, registry::WorkerId, Worker, WorkerRegistry},
+};
+
+/// How often circuit breaker state and queue depth are re-sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Per-connection buffer between the event pump and the transport.
+const STREAM_BUFFER: usize = 256;
+
+/// Queue label for the legacy concurrency-limit queue.
+const CONCURRENCY_QUEUE: &str = "concurrency";
+
+/// Point-in-time view of one worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerState {
+    pub worker_id: String,
+    pub url: String,
+    pub model_id: String,
+    pub status: WorkerStatus,
+    pub circuit_state: &'static str,
+    pub load: usize,
+}
+
+impl WorkerState {
+    fn new(worker_id: &WorkerId, worker: &dyn Worker) -> Self {
+        Self {
+            worker_id: worker_id.as_str().to_string(),
+            url: worker.url().to_string(),
+            model_id: worker.model_id().to_string(),
+            status: worker.status(),
+            circuit_state: worker.circuit_breaker_state().as_str(),
+            load: worker.load(),
+        }
+    }
+}
+
+/// An event delivered to dashboard clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatewayEvent {
+    /// Full fleet state; always the first event on a connection.
+    Snapshot {
+        workers: Vec<WorkerState>,
+    },
+    WorkerRegistered(WorkerState),
+    WorkerUpdated(WorkerState),
+    WorkerRemoved {
+        worker_id: String,
+        url: String,
+    },
+    /// Health-driven lifecycle transition (e.g. ready -> not_ready).
+    WorkerStatusChanged {
+        worker_id: String,
+        url: String,
+        old_status: WorkerStatus,
+        new_status: WorkerStatus,
+    },
+    /// A standby was promoted into or demoted out of rotation.
+    WorkerStandbyChanged {
+        worker_id: String,
+        url: String,
+        promoted: bool,
+    },
+    CircuitStateChanged {
+        worker_id: String,
+        url: String,
+        old_state: &'static str,
+        new_state: &'static str,
+    },
+    /// Admission queue depth: a scheduler class, or `concurrency` for the
+    /// legacy concurrency-limit queue.
+    QueueDepth {
+        queue: &'static str,
+        depth: usize,
+    },
+    /// The client fell behind and `missed` registry events were dropped.
+    Lagged {
+        missed: u64,
+    },
+}
+
+impl GatewayEvent {
+    fn from_worker_event(event: WorkerEvent) -> Self {
+        match event {
+            WorkerEvent::Registered { worker_id, worker } => {
+                Self::WorkerRegistered(WorkerState::new(&worker_id, worker.as_ref()))
+            }
+            WorkerEvent::Replaced { worker_id, new, .. } => {
+                Self::WorkerUpdated(WorkerState::new(&worker_id, new.as_ref()))
+            }
+            WorkerEvent::Removed { worker_id, worker } => Self::WorkerRemoved {
+                worker_id: worker_id.as_str().to_string(),
+                url: worker.url().to_string(),
+            },
+            WorkerEvent::StatusChanged {
+                worker_id,
+                worker,
+                old_status,
+                new_status,
+            } => Self::WorkerStatusChanged {
+                worker_id: worker_id.as_str().to_string(),
+                url: worker.url().to_string(),
+                old_status,
+                new_status,
+            },
+            WorkerEvent::StandbyChanged {
+                worker_id,
+                worker,
+                promoted,
+            } => Self::WorkerStandbyChanged {
+                worker_id: worker_id.as_str().to_string(),
+                url: worker.url().to_string(),
+                promoted,
+            },
+        }
+    }
+}
+
+/// Wire envelope: the event plus its emission time.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamMessage {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: GatewayEvent,
+}
+
+impl From<GatewayEvent> for StreamMessage {
+    fn from(event: GatewayEvent) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            event,
+        }
+    }
+}
+
+/// Everything the stream reads from. Cheap to clone (all `Arc`s / senders).
+#[derive(Clone)]
+pub struct EventSources {
+    pub worker_registry: Arc<WorkerRegistry>,
+    /// Set when the priority scheduler owns admission.
+    pub scheduler: Option<Arc<PriorityScheduler>>,
+    /// Set when the legacy concurrency-limit queue owns admission.
+    pub concurrency_queue: Option<mpsc::Sender<QueuedRequest>>,
+}
+
+impl EventSources {
+    fn queue_depths(&self) -> Vec<(&'static str, usize)> {
+        let mut depths = Vec::new();
+        if let Some(scheduler) = &self.scheduler {
+            depths.extend(
+                Class::ALL
+                    .iter()
+                    .map(|&class| (class.as_str(), scheduler.queue_depth(class))),
+            );
+        }
+        if let Some(tx) = &self.concurrency_queue {
+            depths.push((CONCURRENCY_QUEUE, tx.max_capacity() - tx.capacity()));
+        }
+        depths
+    }
+}
+
+/// Diffs sampled state against what the client has already been sent.
+#[derive(Default)]
+struct Sampler {
+    circuit_states: HashMap<WorkerId, &'static str>,
+    queue_depths: HashMap<&'static str, usize>,
+}
+
+impl Sampler {
+    fn seed(&mut self, workers: &[(WorkerId, Arc<dyn Worker>)]) {
+        for (worker_id, worker) in workers {
+            self.circuit_states
+                .insert(worker_id.clone(), worker.circuit_breaker_state().as_str());
+        }
+    }
+
+    fn sample(&mut self, sources: &EventSources) -> Vec<GatewayEvent> {
+        let mut events = Vec::new();
+        let workers = sources.worker_registry.get_all_with_ids();
+
+        let mut circuit_states = HashMap::with_capacity(workers.len());
+        for (worker_id, worker) in workers {
+            let state = worker.circuit_breaker_state().as_str();
+            // Workers registered since the last sample were announced with
+            // their circuit state; only report transitions after that.
+            if let Some(&old_state) = self.circuit_states.get(&worker_id) {
+                if old_state != state {
+                    events.push(GatewayEvent::CircuitStateChanged {
+                        worker_id: worker_id.as_str().to_string(),
+                        url: worker.url().to_string(),
+                        old_state,
+                        new_state: state,
+                    });
+                }
+            }
+            circuit_states.insert(worker_id, state);
+        }
+        self.circuit_states = circuit_states;
+
+        for (queue, depth) in sources.queue_depths() {
+            if self.queue_depths.insert(queue, depth) != Some(depth) {
+                events.push(GatewayEvent::QueueDepth { queue, depth });
+            }
+        }
+        events
+    }
+}
+
+/// Produce events into `tx` until the receiver is dropped.
+async fn pump(sources: EventSources, tx: mpsc::Sender<StreamMessage>) {
+    // Subscribe before snapshotting so no mutation falls between the two.
+    let mut worker_events = sources.worker_registry.subscribe_events();
+    let workers = sources.worker_registry.get_all_with_ids();
+    let snapshot = GatewayEvent::Snapshot {
+        workers: workers
+            .iter()
+            .map(|(id, worker)| WorkerState::new(id, worker.as_ref()))
+            .collect(),
+    };
+    if tx.send(snapshot.into()).await.is_err() {
+        return;
+    }
+
+    let mut sampler = Sampler::default();
+    sampler.seed(&workers);
+    let mut tick = tokio::time::interval(SAMPLE_INTERVAL);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        let events = tokio::select! {
+            () = tx.closed() => break,
+            recv = worker_events.recv() => match recv {
+                Ok(event) => vec![GatewayEvent::from_worker_event(event)],
+                Err(RecvError::Lagged(missed)) => vec![GatewayEvent::Lagged { missed }],
+                Err(RecvError::Closed) => break,
+            },
+            _ = tick.tick() => sampler.sample(&sources),
+        };
+        for event in events {
+            if tx.send(event.into()).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn spawn_pump(sources: EventSources) -> mpsc::Receiver<StreamMessage> {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    #[expect(
+        clippy::disallowed_methods,
+        reason = "pump exits as soon as the connection drops its receiver"
+    )]
+    tokio::spawn(pump(sources, tx));
+    rx
+}
+
+/// `GET /admin/events/stream` — WebSocket when upgraded, SSE otherwise.
+pub async fn events_stream(sources: EventSources, request: Request) -> Response {
+    let (mut parts, _body) = request.into_parts();
+    if !parts.headers.contains_key(UPGRADE) {
+        let stream = ReceiverStream::new(spawn_pump(sources)).map(|message| {
+            Ok::<_, Infallible>(
+                Event::default()
+                    .json_data(&message)
+                    .unwrap_or_else(|_| Event::default().comment("unserializable event")),
+            )
+        });
+        return Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response();
+    }
+
+    match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+        Ok(ws) => ws
+            .on_upgrade(move |socket| forward_to_socket(socket, spawn_pump(sources)))
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn forward_to_socket(mut socket: WebSocket, mut rx: mpsc::Receiver<StreamMessage>) {
+    loop {
+        tokio::select! {
+            message = rx.recv() => {
+                let Some(message) = message else { break };
+                let Ok(text) = serde_json::to_string(&message) else { continue };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            // Inbound frames are ignored; we only watch for the client leaving.
+            incoming = socket.recv() => match incoming {
+                None | Some(Err(_) | Ok(Message::Close(_))) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("Event stream WebSocket closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::{BasicWorkerBuilder, CircuitBreakerConfig};
+
+    fn sources(registry: &Arc<WorkerRegistry>) -> EventSources {
+        EventSources {
+            worker_registry: Arc::clone(registry),
+            scheduler: None,
+            concurrency_queue: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn pump_sends_snapshot_then_registry_events() {
+        let registry = Arc::new(WorkerRegistry::new());
+        registry.register(Arc::new(BasicWorkerBuilder::new("http://w1:8000").build()));
+
+        let mut rx = spawn_pump(sources(&registry));
+        let snapshot = serde_json::to_value(rx.recv().await.unwrap()).unwrap();
+        assert_eq!(snapshot["type"], "snapshot");
+        assert_eq!(snapshot["workers"][0]["url"], "http://w1:8000");
+
+        let worker_id = registry
+            .register(Arc::new(BasicWorkerBuilder::new("http://w2:8000").build()))
+            .unwrap();
+        let registered = serde_json::to_value(rx.recv().await.unwrap()).unwrap();
+        assert_eq!(registered["type"], "worker_registered");
+        assert_eq!(registered["url"], "http://w2:8000");
+
+        registry.remove(&worker_id);
+        let removed = serde_json::to_value(rx.recv().await.unwrap()).unwrap();
+        assert_eq!(removed["type"], "worker_removed");
+        assert_eq!(removed["worker_id"], worker_id.as_str());
+    }
+
+    #[test]
+    fn sampler_reports_only_changes() {
+        let registry = Arc::new(WorkerRegistry::new());
+        registry.register(Arc::new(BasicWorkerBuilder::new("http://w1:8000").build()));
+        let (tx, _rx) = mpsc::channel(4);
+        let sources = EventSources {
+            concurrency_queue: Some(tx),
+            ..sources(&registry)
+        };
+
+        let mut sampler = Sampler::default();
+        sampler.seed(&registry.get_all_with_ids());
+        let first = sampler.sample(&sources);
+        assert!(matches!(
+            first.as_slice(),
+            [GatewayEvent::QueueDepth {
+                queue: CONCURRENCY_QUEUE,
+                depth: 0
+            }]
+        ));
+        assert!(sampler.sample(&sources).is_empty());
+
+        let worker = registry.get_by_url("http://w1:8000").unwrap();
+        for _ in 0..CircuitBreakerConfig::default().failure_threshold {
+            worker.record_circuit_breaker_outcome(false);
+        }
+        let events = sampler.sample(&sources);
+        assert!(matches!(
+            events.as_slice(),
+            [GatewayEvent::CircuitStateChanged {
+                old_state: "closed",
+                new_state: "open",
+                ..
+            }]
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+//! Trace-ID exemplars for latency histograms.
+//!
+//! `metrics-exporter-prometheus` has no exemplar support, so the [`Metrics`]
+//! facade records the trace ID of the current sampled span next to selected
+//! histogram observations (router duration, TTFT). When a scraper negotiates
+//! OpenMetrics, [`render_openmetrics`] converts the exporter's text output
+//! and attaches the most recent exemplar to each matching `_bucket` line, so
+//! Grafana can jump from a latency spike straight to an example trace.
+//!
+//! Only one exemplar is kept per series and bucket, so memory is bounded by
+//! the histogram's own cardinality.
+//!
+//! [`Metrics`]: super::metrics::Metrics
+
+use std::{
+    fmt::Write,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+use super::otel_trace::current_trace_id;
+
+/// Content type served when the scraper accepts OpenMetrics.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Debug, Clone)]
+struct Exemplar {
+    trace_id: String,
+    value: f64,
+    timestamp: f64,
+}
+
+/// Bucket upper bounds shared by the exemplar-carrying histograms.
+static BUCKET_BOUNDS: OnceLock<Vec<f64>> = OnceLock::new();
+
+/// `metric{canonical labels}` -> exemplar per bucket (last is +Inf).
+static EXEMPLARS: Lazy<DashMap<String, Vec<Option<Exemplar>>>> = Lazy::new(DashMap::new);
+
+/// Register the bucket bounds configured on the Prometheus recorder.
+pub(crate) fn set_bucket_bounds(bounds: &[f64]) {
+    let _ = BUCKET_BOUNDS.set(bounds.to_vec());
+}
+
+/// Record `value` as an exemplar for the histogram series `metric{labels}`,
+/// tagged with the current trace ID. No-op outside a sampled trace.
+pub(crate) fn record(metric: &str, labels: &[(&str, &str)], value: f64) {
+    let Some(trace_id) = current_trace_id() else {
+        return;
+    };
+    let Some(bounds) = BUCKET_BOUNDS.get() else {
+        return;
+    };
+    let bucket = bounds.partition_point(|&bound| bound < value);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default();
+
+    let pairs = labels
+        .iter()
+        .map(|(key, value)| ((*key).to_string(), escape_label_value(value)))
+        .collect();
+    let mut series = EXEMPLARS
+        .entry(series_key(metric, pairs))
+        .or_insert_with(|| vec![None; bounds.len() + 1]);
+    if let Some(slot) = series.get_mut(bucket) {
+        *slot = Some(Exemplar {
+            trace_id,
+            value,
+            timestamp,
+        });
+    }
+}
+
+/// Whether an `Accept` header value asks for OpenMetrics.
+pub fn accepts_openmetrics(accept: &str) -> bool {
+    accept.contains("application/openmetrics-text")
+}
+
+/// Convert Prometheus text exposition output to OpenMetrics, attaching
+/// exemplars to histogram bucket lines.
+///
+/// Counters are renamed to their family name (`_total` stripped from
+/// `# HELP`/`# TYPE`); counters without the suffix are typed `unknown`
+/// because OpenMetrics requires it on counter samples.
+pub fn render_openmetrics(prometheus_text: &str) -> String {
+    let counters: Vec<&str> = prometheus_text
+        .lines()
+        .filter_map(|line| line.strip_prefix("# TYPE "))
+        .filter_map(|rest| rest.strip_suffix(" counter"))
+        .collect();
+
+    let mut out = String::with_capacity(prometheus_text.len() + 64);
+    for line in prometheus_text.lines() {
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            if let Some(name) = rest.strip_suffix(" counter") {
+                match name.strip_suffix("_total") {
+                    Some(family) => {
+                        let _ = writeln!(out, "# TYPE {family} counter");
+                    }
+                    None => {
+                        let _ = writeln!(out, "# TYPE {name} unknown");
+                    }
+                }
+                continue;
+            }
+        } else if let Some(rest) = line.strip_prefix("# HELP ") {
+            let (name, help) = rest.split_once(' ').unwrap_or((rest, ""));
+            if counters.contains(&name) {
+                let family = name.strip_suffix("_total").unwrap_or(name);
+                let _ = writeln!(out, "# HELP {family} {help}");
+                continue;
+            }
+        } else if let Some(exemplar) = bucket_exemplar(line) {
+            let _ = writeln!(
+                out,
+                "{line} # {{trace_id=\"{}\"}} {} {:.3}",
+                exemplar.trace_id, exemplar.value, exemplar.timestamp
+            );
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Look up the exemplar for a `<name>_bucket{...,le="..."} <count>` line.
+fn bucket_exemplar(line: &str) -> Option<Exemplar> {
+    if EXEMPLARS.is_empty() {
+        return None;
+    }
+    let (name, rest) = line.split_once("_bucket{")?;
+    let mut pairs = parse_labels(rest)?;
+    let le_pos = pairs.iter().position(|(key, _)| key == "le")?;
+    let (_, le) = pairs.remove(le_pos);
+    let bounds = BUCKET_BOUNDS.get()?;
+    let bucket = if le == "+Inf" {
+        bounds.len()
+    } else {
+        let le: f64 = le.parse().ok()?;
+        bounds
+            .iter()
+            .position(|&bound| (bound - le).abs() <= f64::EPSILON * bound.abs().max(1.0))?
+    };
+
+    EXEMPLARS
+        .get(&series_key(name, pairs))?
+        .get(bucket)?
+        .clone()
+}
+
+/// Parse `key="value",...}` (the text after `{`) into raw, still-escaped
+/// label pairs.
+fn parse_labels(s: &str) -> Option<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(',');
+        if rest.starts_with('}') {
+            return Some(pairs);
+        }
+        let (key, after) = rest.split_once("=\"")?;
+        let mut value_end = None;
+        let mut escaped = false;
+        for (i, ch) in after.char_indices() {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    value_end = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let value_end = value_end?;
+        pairs.push((key.to_string(), after[..value_end].to_string()));
+        rest = &after[value_end + 1..];
+    }
+}
+
+/// `metric{k1="v1",k2="v2"}` with labels sorted by key, so the series
+/// matches regardless of the exporter's label order.
+fn series_key(metric: &str, mut pairs: Vec<(String, String)>) -> String {
+    pairs.sort();
+    let mut out = format!("{metric}{{");
+    for (i, (key, value)) in pairs.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "{key}=\"{value}\"");
+    }
+    out.push('}');
+    out
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels_handles_escapes() {
+        let pairs = parse_labels(r#"model="a\"b,c",le="0.5"} 3"#).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("model".to_string(), r#"a\"b,c"#.to_string()),
+                ("le".to_string(), "0.5".to_string()),
+            ]
+        );
+        assert_eq!(parse_labels("} 1").unwrap(), vec![]);
+        assert!(parse_labels(r#"model="unterminated"#).is_none());
+    }
+
+    #[test]
+    fn test_render_openmetrics_renames_counters_and_attaches_exemplars() {
+        set_bucket_bounds(&[0.1, 1.0]);
+        EXEMPLARS.insert(
+            series_key(
+                "test_exemplar_latency_seconds",
+                vec![("model".to_string(), "m".to_string())],
+            ),
+            vec![
+                None,
+                Some(Exemplar {
+                    trace_id: "abc".to_string(),
+                    value: 0.5,
+                    timestamp: 1.0,
+                }),
+                None,
+            ],
+        );
+
+        let text = "# HELP test_requests_total Requests\n\
+                    # TYPE test_requests_total counter\n\
+                    test_requests_total 4\n\
+                    # TYPE test_items counter\n\
+                    test_items 2\n\
+                    # TYPE test_exemplar_latency_seconds histogram\n\
+                    test_exemplar_latency_seconds_bucket{model=\"m\",le=\"0.1\"} 0\n\
+                    test_exemplar_latency_seconds_bucket{model=\"m\",le=\"1\"} 1\n\
+                    test_exemplar_latency_seconds_bucket{model=\"m\",le=\"+Inf\"} 1\n";
+        let rendered = render_openmetrics(text);
+        assert_eq!(
+            rendered,
+            "# HELP test_requests Requests\n\
+             # TYPE test_requests counter\n\
+             test_requests_total 4\n\
+             # TYPE test_items unknown\n\
+             test_items 2\n\
+             # TYPE test_exemplar_latency_seconds histogram\n\
+             test_exemplar_latency_seconds_bucket{model=\"m\",le=\"0.1\"} 0\n\
+             test_exemplar_latency_seconds_bucket{model=\"m\",le=\"1\"} 1 # {trace_id=\"abc\"} 0.5 1.000\n\
+             test_exemplar_latency_seconds_bucket{model=\"m\",le=\"+Inf\"} 1\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn test_accepts_openmetrics() {
+        assert!(accepts_openmetrics(
+            "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5"
+        ));
+        assert!(!accepts_openmetrics("text/plain"));
+    }
+}
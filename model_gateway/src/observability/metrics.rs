@@ -3,10 +3,14 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use dashmap::DashMap;
-use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
+use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Label,
+};
 use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use once_cell::sync::Lazy;
 
+use super::exemplars;
+
 // Interned strings are never freed; only intern low-cardinality, server-controlled
 // labels (model IDs, worker URLs, normalized paths), never user-controlled input.
 
@@ -160,6 +164,70 @@ pub(crate) fn init_metrics() {
         "smg_http_rate_limit_total",
         "Rate limiting decisions by result (allowed/rejected)"
     );
+    describe_counter!(
+        "smg_rate_limit_admitted_total",
+        "Requests admitted by every level of their hierarchical rate limit chain"
+    );
+    describe_counter!(
+        "smg_rate_limit_rejections_total",
+        "Requests rejected by hierarchical rate limits by level (global/tenant/api_key/model) and limit (requests/tokens)"
+    );
+    describe_counter!(
+        "smg_tagged_requests_total",
+        "Total tagged requests by status_code and configured x-smg-tags keys (tag_<key>)"
+    );
+    describe_histogram!(
+        "smg_tagged_request_duration_seconds",
+        "Tagged request duration by configured x-smg-tags keys (tag_<key>)"
+    );
+    describe_counter!(
+        "smg_feature_requests_total",
+        "Total requests with an accepted x-smg-features flag by feature and status_code"
+    );
+    describe_counter!(
+        "smg_feature_rejections_total",
+        "Requested x-smg-features flags outside the caller's allowlist by feature"
+    );
+    describe_counter!(
+        "smg_experiment_requests_total",
+        "Total experiment-enrolled requests by experiment, arm and status_code"
+    );
+    describe_histogram!(
+        "smg_experiment_request_duration_seconds",
+        "Experiment-enrolled request duration by experiment and arm"
+    );
+    describe_counter!(
+        "smg_experiment_tokens_total",
+        "Tokens used by experiment-enrolled non-streaming requests by experiment, arm and token_type"
+    );
+    describe_counter!(
+        "smg_prompt_guard_requests_total",
+        "Requests screened by the prompt guard by outcome (clean/annotated/blocked/approval_required/approved)"
+    );
+    describe_counter!(
+        "smg_prompt_guard_rule_hits_total",
+        "Screened requests matching each prompt guard rule"
+    );
+    describe_counter!(
+        "smg_prompt_guard_classifier_errors_total",
+        "Prompt guard classifier calls that failed and fell back to rule scores"
+    );
+    describe_counter!(
+        "smg_routing_rule_matches_total",
+        "Requests redirected by each routing rule"
+    );
+    describe_counter!(
+        "smg_federation_rejections_total",
+        "Inbound federated requests rejected by reason"
+    );
+    describe_counter!(
+        "smg_federation_catalog_imports_total",
+        "Model catalog imports from federated gateways by result"
+    );
+    describe_counter!(
+        "smg_standby_transitions_total",
+        "Standby worker promotions and demotions by model and direction"
+    );
 
     // Layer 2: Router metrics
     describe_counter!(
@@ -200,6 +268,10 @@ pub(crate) fn init_metrics() {
         "smg_router_generation_duration_seconds",
         "Total generation time by router_type, backend_type, model, endpoint (gRPC only)"
     );
+    describe_counter!(
+        "smg_router_images_total",
+        "Total images generated by router_type, backend_type, model"
+    );
 
     // Layer 2: PD disaggregation metrics (signals only SMG can measure — it is the
     // only component that observes both the prefill and decode legs of a request).
@@ -227,6 +299,47 @@ pub(crate) fn init_metrics() {
         "smg_pd_kv_transfer_failures_total",
         "PD KV-transfer failures (missing connector params at decode handoff)"
     );
+    describe_counter!(
+        "smg_pd_bootstrap_room_collisions_total",
+        "PD bootstrap room candidates skipped because the room was still open"
+    );
+    describe_gauge!(
+        "smg_pd_bootstrap_rooms_open",
+        "PD bootstrap rooms currently open"
+    );
+    describe_gauge!(
+        "smg_pd_bootstrap_rooms_stuck",
+        "PD bootstrap rooms open longer than pd_bootstrap.stuck_threshold_secs"
+    );
+    describe_histogram!(
+        "smg_pd_pair_latency_seconds",
+        "Time from dispatch to the decode response head by prefill and decode worker"
+    );
+    describe_counter!(
+        "smg_pd_pair_requests_total",
+        "PD requests by prefill and decode worker and status (success/error)"
+    );
+    describe_gauge!(
+        "smg_pd_slow_pairs",
+        "Prefill-decode pairs currently flagged slow"
+    );
+    describe_counter!(
+        "smg_pd_slow_pair_avoidances_total",
+        "PD worker selections that skipped decode workers paired slowly with the chosen prefill"
+    );
+    describe_gauge!("smg_sessions_active", "Sessions open through /v1/sessions");
+    describe_counter!(
+        "smg_session_routing_total",
+        "Requests carrying X-SMG-Session-Id by result (pinned/repinned/unknown)"
+    );
+    describe_counter!(
+        "smg_partial_transcripts_total",
+        "Transcripts stored for streams that did not finish by status (cancelled/failed)"
+    );
+    describe_counter!(
+        "smg_model_card_revisions_total",
+        "Model card revisions recorded by model"
+    );
 
     // Layer 3: Worker metrics
     describe_gauge!(
@@ -266,6 +379,10 @@ pub(crate) fn init_metrics() {
         "smg_manual_policy_cache_entries",
         "Number of routing entries in manual policy cache"
     );
+    describe_histogram!(
+        "smg_prefix_hash_blocks",
+        "Prefix blocks hashed per prefix_hash routing decision by branch"
+    );
 
     // Layer 3: Worker resilience metrics (circuit breaker)
     describe_gauge!(
@@ -402,6 +519,10 @@ pub(crate) fn init_metrics() {
         "smg_mm_shm_write_failures_total",
         "SHM tensor write attempts that failed and fell back to inline, by runtime"
     );
+    describe_counter!(
+        "smg_mm_image_safety_total",
+        "Images screened by the safety classifier, by outcome (allowed/flagged/blocked/error)"
+    );
 
     // Layer 0: Tokio runtime self-observability (event-loop canary + sampler).
     super::runtime_metrics::describe();
@@ -409,6 +530,9 @@ pub(crate) fn init_metrics() {
     // Initialize mesh metrics
     smg_mesh::init_mesh_metrics();
 
+    // Initialize multimodal media fetch metrics
+    llm_multimodal::init_media_metrics();
+
     // Priority scheduler metrics (no-op at scrape time unless the scheduler
     // is enabled and recording).
     use crate::middleware::scheduler::metrics as scheduler_metrics;
@@ -445,6 +569,16 @@ pub fn start_prometheus(config: PrometheusConfig) -> PrometheusHandle {
     let ttft_matcher = Matcher::Suffix(String::from("ttft_seconds"));
     let tpot_matcher = Matcher::Suffix(String::from("tpot_seconds"));
 
+    // Block counts are small integers; power-of-two buckets up to the
+    // largest practical prefix keep the distribution readable.
+    let prefix_blocks_matcher = Matcher::Full(String::from("smg_prefix_hash_blocks"));
+    let prefix_blocks_bucket: Vec<f64> = vec![
+        1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0, 1024.0,
+    ];
+
+    // Router duration and TTFT carry trace exemplars; both use these buckets.
+    super::exemplars::set_bucket_bounds(&duration_bucket);
+
     PrometheusBuilder::new()
         .upkeep_timeout(Duration::from_secs(UPKEEP_INTERVAL_SECS))
         .set_buckets_for_metric(duration_matcher, &duration_bucket)
@@ -453,6 +587,8 @@ pub fn start_prometheus(config: PrometheusConfig) -> PrometheusHandle {
         .expect("failed to set ttft bucket")
         .set_buckets_for_metric(tpot_matcher, &duration_bucket)
         .expect("failed to set tpot bucket")
+        .set_buckets_for_metric(prefix_blocks_matcher, &prefix_blocks_bucket)
+        .expect("failed to set prefix hash blocks bucket")
         .set_buckets_for_metric(
             canary_matcher,
             super::runtime_metrics::EVENT_LOOP_DELAY_BUCKETS,
@@ -487,6 +623,8 @@ pub mod metrics_labels {
     pub const ENDPOINT_RERANK: &str = "rerank";
     pub const ENDPOINT_EMBEDDINGS: &str = "embeddings";
     pub const ENDPOINT_CLASSIFY: &str = "classify";
+    pub const ENDPOINT_SCORE: &str = "score";
+    pub const ENDPOINT_IMAGE_GENERATIONS: &str = "image_generations";
     pub const ENDPOINT_MESSAGES: &str = "messages";
     pub const ENDPOINT_REALTIME: &str = "realtime";
     pub const ENDPOINT_REALTIME_SESSIONS: &str = "realtime_sessions";
@@ -638,6 +776,106 @@ impl Metrics {
         .increment(1);
     }
 
+    /// Record a request carrying `x-smg-tags`. `tags` are the already
+    /// bounded `tag_<key>` labels, one per configured key.
+    pub fn record_tagged_request(tags: Vec<Label>, status_code: u16, duration: Duration) {
+        histogram!("smg_tagged_request_duration_seconds", tags.clone())
+            .record(duration.as_secs_f64());
+        let mut labels = tags;
+        labels.push(Label::new("status_code", status_code_to_cow(status_code)));
+        counter!("smg_tagged_requests_total", labels).increment(1);
+    }
+
+    /// Record a request that enabled `feature` through `x-smg-features`.
+    pub fn record_feature_request(feature: &str, status_code: u16) {
+        counter!(
+            "smg_feature_requests_total",
+            "feature" => feature.to_string(),
+            "status_code" => status_code_to_cow(status_code)
+        )
+        .increment(1);
+    }
+
+    /// Record a requested feature outside the caller's allowlist. `feature`
+    /// is already bounded to configured names or `other`.
+    pub fn record_feature_rejection(feature: &str) {
+        counter!("smg_feature_rejections_total", "feature" => feature.to_string()).increment(1);
+    }
+
+    /// Record a request enrolled in an A/B experiment arm.
+    pub fn record_experiment_request(
+        experiment: &str,
+        arm: &str,
+        status_code: u16,
+        duration: Duration,
+    ) {
+        histogram!(
+            "smg_experiment_request_duration_seconds",
+            "experiment" => experiment.to_string(),
+            "arm" => arm.to_string()
+        )
+        .record(duration.as_secs_f64());
+        counter!(
+            "smg_experiment_requests_total",
+            "experiment" => experiment.to_string(),
+            "arm" => arm.to_string(),
+            "status_code" => status_code_to_cow(status_code)
+        )
+        .increment(1);
+    }
+
+    /// Record token usage of an experiment arm's response.
+    pub fn record_experiment_tokens(experiment: &str, arm: &str, prompt: u64, completion: u64) {
+        for (token_type, count) in [("prompt", prompt), ("completion", completion)] {
+            counter!(
+                "smg_experiment_tokens_total",
+                "experiment" => experiment.to_string(),
+                "arm" => arm.to_string(),
+                "token_type" => token_type
+            )
+            .increment(count);
+        }
+    }
+
+    /// Record one prompt guard screening `outcome`.
+    pub fn record_prompt_guard_request(outcome: &'static str) {
+        counter!("smg_prompt_guard_requests_total", "outcome" => outcome).increment(1);
+    }
+
+    /// Record a screened request matching prompt guard `rule`.
+    pub fn record_prompt_guard_rule_hit(rule: &str) {
+        counter!("smg_prompt_guard_rule_hits_total", "rule" => rule.to_string()).increment(1);
+    }
+
+    pub fn record_prompt_guard_classifier_error() {
+        counter!("smg_prompt_guard_classifier_errors_total").increment(1);
+    }
+
+    /// Record a request matched by routing rule `rule`.
+    pub fn record_routing_rule_match(rule: &str) {
+        counter!("smg_routing_rule_matches_total", "rule" => rule.to_string()).increment(1);
+    }
+
+    /// Record an inbound federated request rejected for `reason`.
+    pub fn record_federation_rejection(reason: &'static str) {
+        counter!("smg_federation_rejections_total", "reason" => reason).increment(1);
+    }
+
+    /// Record a catalog import from a federated gateway.
+    pub fn record_federation_catalog_import(result: &'static str) {
+        counter!("smg_federation_catalog_imports_total", "result" => result).increment(1);
+    }
+
+    /// Record a standby worker promoted into or demoted out of rotation.
+    pub fn record_standby_transition(model: &str, direction: &'static str) {
+        counter!(
+            "smg_standby_transitions_total",
+            "model" => model.to_string(),
+            "direction" => direction
+        )
+        .increment(1);
+    }
+
     /// Record rate limit decision.
     pub fn record_http_rate_limit(result: &'static str) {
         counter!(
@@ -647,6 +885,22 @@ impl Metrics {
         .increment(1);
     }
 
+    /// Record a request admitted by its whole rate limit chain.
+    pub fn record_rate_limit_admission() {
+        counter!("smg_rate_limit_admitted_total").increment(1);
+    }
+
+    /// Record a request rejected by the outermost exhausted rate limit
+    /// `level`, where `limit` is the budget that ran out.
+    pub fn record_rate_limit_rejection(level: &'static str, limit: &'static str) {
+        counter!(
+            "smg_rate_limit_rejections_total",
+            "level" => level,
+            "limit" => limit
+        )
+        .increment(1);
+    }
+
     /// Record one multimodal tensor sent over `path` ("inline"|"shm"|"remote") for `runtime`.
     pub fn record_mm_tensor(runtime: &'static str, path: &'static str, nbytes: usize) {
         counter!("smg_mm_tensors_total", "runtime" => runtime, "path" => path).increment(1);
@@ -659,6 +913,11 @@ impl Metrics {
         counter!("smg_mm_shm_write_failures_total", "runtime" => runtime).increment(1);
     }
 
+    /// Record one image safety screening `outcome` ("allowed"|"flagged"|"blocked"|"error").
+    pub fn record_mm_image_safety(outcome: &'static str) {
+        counter!("smg_mm_image_safety_total", "outcome" => outcome).increment(1);
+    }
+
     // ========================================================================
     // Layer 2: Router metrics
     // ========================================================================
@@ -701,6 +960,17 @@ impl Metrics {
         duration: Duration,
     ) {
         let model = intern_string(model_id);
+        exemplars::record(
+            "smg_router_request_duration_seconds",
+            &[
+                ("router_type", router_type),
+                ("backend_type", backend_type),
+                ("connection_mode", connection_mode),
+                ("model", &*model),
+                ("endpoint", endpoint),
+            ],
+            duration.as_secs_f64(),
+        );
         histogram!(
             "smg_router_request_duration_seconds",
             "router_type" => router_type,
@@ -782,6 +1052,16 @@ impl Metrics {
         duration: Duration,
     ) {
         let model = intern_string(model_id);
+        exemplars::record(
+            "smg_router_ttft_seconds",
+            &[
+                ("router_type", router_type),
+                ("backend_type", backend_type),
+                ("model", &*model),
+                ("endpoint", endpoint),
+            ],
+            duration.as_secs_f64(),
+        );
         histogram!(
             "smg_router_ttft_seconds",
             "router_type" => router_type,
@@ -832,6 +1112,23 @@ impl Metrics {
         .increment(count);
     }
 
+    /// Record images returned by an image generation request
+    pub fn record_router_images(
+        router_type: &'static str,
+        backend_type: &'static str,
+        model_id: &str,
+        count: u64,
+    ) {
+        let model = intern_string(model_id);
+        counter!(
+            "smg_router_images_total",
+            "router_type" => router_type,
+            "backend_type" => backend_type,
+            "model" => model
+        )
+        .increment(count);
+    }
+
     /// Record total generation duration.
     /// Uses string interning for model_id.
     pub fn record_router_generation_duration(
@@ -873,6 +1170,16 @@ impl Metrics {
 
         // TTFT and TPOT (only if we have a first token time)
         if let Some(ttft_duration) = ttft {
+            exemplars::record(
+                "smg_router_ttft_seconds",
+                &[
+                    ("router_type", router_type),
+                    ("backend_type", backend_type),
+                    ("model", &*model),
+                    ("endpoint", endpoint),
+                ],
+                ttft_duration.as_secs_f64(),
+            );
             histogram!(
                 "smg_router_ttft_seconds",
                 "router_type" => router_type,
@@ -1014,6 +1321,69 @@ impl Metrics {
         counter!("smg_pd_bootstrap_failures_total").increment(1);
     }
 
+    /// Record a PD bootstrap room candidate skipped because it was still open.
+    pub fn record_pd_bootstrap_room_collision() {
+        counter!("smg_pd_bootstrap_room_collisions_total").increment(1);
+    }
+
+    /// Set the open and stuck PD bootstrap room gauges.
+    pub fn set_pd_bootstrap_rooms(open: usize, stuck: usize) {
+        gauge!("smg_pd_bootstrap_rooms_open").set(open as f64);
+        gauge!("smg_pd_bootstrap_rooms_stuck").set(stuck as f64);
+    }
+
+    /// Record the dispatch-to-decode-head latency of a PD pair.
+    pub fn record_pd_pair_latency(prefill_url: &str, decode_url: &str, latency: Duration) {
+        histogram!(
+            "smg_pd_pair_latency_seconds",
+            "prefill" => intern_string(prefill_url),
+            "decode" => intern_string(decode_url)
+        )
+        .record(latency.as_secs_f64());
+    }
+
+    /// Record the outcome of a PD request through a pair.
+    pub fn record_pd_pair_request(prefill_url: &str, decode_url: &str, success: bool) {
+        counter!(
+            "smg_pd_pair_requests_total",
+            "prefill" => intern_string(prefill_url),
+            "decode" => intern_string(decode_url),
+            "status" => if success { "success" } else { "error" }
+        )
+        .increment(1);
+    }
+
+    /// Set the number of PD pairs flagged slow.
+    pub fn set_pd_slow_pairs(count: usize) {
+        gauge!("smg_pd_slow_pairs").set(count as f64);
+    }
+
+    /// Record a PD selection that steered around a slow pair.
+    pub fn record_pd_slow_pair_avoided() {
+        counter!("smg_pd_slow_pair_avoidances_total").increment(1);
+    }
+
+    /// Set the number of open sessions.
+    pub fn set_sessions_active(count: usize) {
+        gauge!("smg_sessions_active").set(count as f64);
+    }
+
+    /// Record how a request carrying a session ID was routed.
+    pub fn record_session_routing(result: &'static str) {
+        counter!("smg_session_routing_total", "result" => result).increment(1);
+    }
+
+    /// Record a partial transcript stored for a stream that did not finish.
+    pub fn record_partial_transcript(status: &'static str) {
+        counter!("smg_partial_transcripts_total", "status" => status).increment(1);
+    }
+
+    /// Record a new revision of a model's card.
+    pub fn record_model_card_revision(model_id: &str) {
+        let model = intern_string(model_id);
+        counter!("smg_model_card_revisions_total", "model" => model).increment(1);
+    }
+
     /// Record a PD KV-transfer failure (missing connector params at handoff).
     pub fn record_pd_kv_transfer_failure() {
         counter!("smg_pd_kv_transfer_failures_total").increment(1);
@@ -1129,6 +1499,16 @@ impl Metrics {
         .increment(1);
     }
 
+    /// Record the number of prefix blocks hashed for a routed prefix hash
+    /// request, by branch (ring hit vs. load-balance walk)
+    pub fn record_prefix_hash_blocks(branch: &'static str, blocks: usize) {
+        histogram!(
+            "smg_prefix_hash_blocks",
+            "branch" => branch
+        )
+        .record(blocks as f64);
+    }
+
     /// Set running requests per worker
     pub fn set_worker_requests_active(worker: &str, count: usize) {
         let worker_interned = intern_string(worker);
@@ -1,5 +1,6 @@
 //! HTTP server for the Prometheus metrics endpoint (port 29000).
-//! Serves `GET /metrics` (Prometheus).
+//! Serves `GET /metrics` (Prometheus, or OpenMetrics with exemplars when
+//! negotiated via `Accept`).
 
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -11,21 +12,40 @@ use metrics_exporter_prometheus::PrometheusHandle;
 use tokio::task::JoinHandle;
 use tracing::{error, info};
 
-use super::metrics::UPKEEP_INTERVAL_SECS;
+use super::{exemplars, metrics::UPKEEP_INTERVAL_SECS};
 
 #[derive(Clone)]
 struct MetricsState {
     handle: PrometheusHandle,
 }
 
-async fn prometheus_handler(State(state): State<MetricsState>) -> impl IntoResponse {
-    (
-        [(
-            http::header::CONTENT_TYPE,
-            "text/plain; version=0.0.4; charset=utf-8",
-        )],
-        state.handle.render(),
-    )
+/// Serves OpenMetrics (with trace exemplars) when the scraper asks for it,
+/// Prometheus text format otherwise.
+async fn prometheus_handler(
+    State(state): State<MetricsState>,
+    headers: http::HeaderMap,
+) -> impl IntoResponse {
+    let openmetrics = headers
+        .get(http::header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(exemplars::accepts_openmetrics);
+    if openmetrics {
+        (
+            [(
+                http::header::CONTENT_TYPE,
+                exemplars::OPENMETRICS_CONTENT_TYPE,
+            )],
+            exemplars::render_openmetrics(&state.handle.render()),
+        )
+    } else {
+        (
+            [(
+                http::header::CONTENT_TYPE,
+                "text/plain; version=0.0.4; charset=utf-8",
+            )],
+            state.handle.render(),
+        )
+    }
 }
 
 async fn bind_metrics_listener(addr: SocketAddr) -> Result<tokio::net::TcpListener, String> {
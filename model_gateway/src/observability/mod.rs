@@ -1,10 +1,13 @@
 //! Observability utilities for logging, metrics, and tracing.
 
+pub mod event_stream;
 pub mod events;
+pub mod exemplars;
 pub mod gauge_histogram;
 pub mod inflight_tracker;
 pub mod logging;
 pub mod metrics;
 pub mod metrics_server;
 pub mod otel_trace;
+pub mod redaction;
 pub mod runtime_metrics;
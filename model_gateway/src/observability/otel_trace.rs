@@ -1,6 +1,7 @@
 //! OpenTelemetry tracing integration.
 
 use std::{
+    collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         OnceLock,
@@ -10,10 +11,15 @@ use std::{
 
 use anyhow::Result;
 use axum::http::{HeaderMap, HeaderName, HeaderValue};
-use opentelemetry::{global, trace::TracerProvider as _, KeyValue};
+use opentelemetry::{
+    global,
+    propagation::TextMapCompositePropagator,
+    trace::{TraceContextExt, TracerProvider as _},
+    KeyValue,
+};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{
-    propagation::TraceContextPropagator,
+    propagation::{BaggagePropagator, TraceContextPropagator},
     trace::{BatchConfigBuilder, BatchSpanProcessor, SdkTracer, SdkTracerProvider},
     Resource,
 };
@@ -100,7 +106,12 @@ pub fn otel_tracing_init(enable: bool, otlp_endpoint: Option<&str>) -> Result<()
         endpoint.to_string()
     };
 
-    global::set_text_map_propagator(TraceContextPropagator::new());
+    // Baggage rides alongside trace context so caller-supplied entries reach
+    // workers, MCP servers, and WASM guests.
+    global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(BaggagePropagator::new()),
+    ]));
 
     let exporter = opentelemetry_otlp::SpanExporter::builder()
         .with_tonic()
@@ -260,6 +271,23 @@ pub fn extract_trace_context_http(headers: &HeaderMap) -> opentelemetry::Context
     global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
 }
 
+/// Trace ID of the current span, if OTel is enabled and the span is sampled.
+///
+/// Used to attach exemplars to latency histograms; unsampled traces are
+/// skipped since they would link to nothing in the tracing backend.
+#[inline]
+pub fn current_trace_id() -> Option<String> {
+    if !is_otel_enabled() {
+        return None;
+    }
+
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    (span_context.is_valid() && span_context.is_sampled())
+        .then(|| span_context.trace_id().to_string())
+}
+
 /// Inject W3C trace context headers into an HTTP request.
 #[inline]
 pub fn inject_trace_context_http(headers: &mut HeaderMap) {
@@ -314,10 +342,24 @@ pub fn inject_trace_context_grpc(metadata: &mut MetadataMap) {
     });
 }
 
-/// OpenTelemetry-based trace injector for gRPC clients.
+/// Inject W3C trace context and baggage into a string map, e.g. an MCP
+/// request's `_meta`.
+#[inline]
+pub fn inject_trace_context_map(carrier: &mut HashMap<String, String>) {
+    if !is_otel_enabled() {
+        return;
+    }
+
+    let context = tracing::Span::current().context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, carrier);
+    });
+}
+
+/// OpenTelemetry-based trace injector for gRPC clients and MCP tool calls.
 ///
-/// This implements the `TraceInjector` trait from `smg_grpc_client`,
-/// enabling distributed tracing across gRPC calls.
+/// This implements the `TraceInjector` traits from `smg_grpc_client` and
+/// `smg_mcp`, enabling distributed tracing across gRPC and tool calls.
 #[derive(Clone, Default)]
 pub struct OtelTraceInjector;
 
@@ -330,3 +372,13 @@ impl smg_grpc_client::TraceInjector for OtelTraceInjector {
         Ok(())
     }
 }
+
+impl smg_mcp::TraceInjector for OtelTraceInjector {
+    fn inject(
+        &self,
+        carrier: &mut HashMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        inject_trace_context_map(carrier);
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! PII redaction for payloads that leave the request path (debug captures,
+//! payload logs).
+//!
+//! Masks email addresses, phone numbers, payment card numbers, and bearer
+//! tokens / API keys in free text, and replaces the values of
+//! credential-like JSON keys wholesale. Redaction is best-effort pattern
+//! matching, not a guarantee — captured data should still be treated as
+//! sensitive.
+
+use std::borrow::Cow;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+/// Replacement marker for masked spans and credential values.
+pub const REDACTED: &str = "[REDACTED]";
+
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}")
+        .expect("static regex pattern is valid")
+});
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static CARD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").expect("static regex pattern is valid"));
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static PHONE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[ .-]?)?\(?\b\d{3}\)?[ .-]?\d{3}[ .-]?\d{4}\b")
+        .expect("static regex pattern is valid")
+});
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static CREDENTIAL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+|\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}")
+        .expect("static regex pattern is valid")
+});
+
+/// JSON object keys whose values are always replaced, regardless of content.
+const SENSITIVE_KEYS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "authorization",
+    "password",
+    "secret",
+    "client_secret",
+    "access_token",
+    "refresh_token",
+];
+
+fn is_sensitive_key(key: &str) -> bool {
+    SENSITIVE_KEYS.iter().any(|k| key.eq_ignore_ascii_case(k))
+}
+
+/// Mask PII and credentials in free text. Returns the input unchanged
+/// (borrowed) when nothing matched.
+pub fn redact_text(text: &str) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(text);
+    // Credentials first so their digits aren't partially eaten by the card
+    // and phone patterns.
+    for re in [&*CREDENTIAL_RE, &*EMAIL_RE, &*CARD_RE, &*PHONE_RE] {
+        if re.is_match(&out) {
+            out = Cow::Owned(re.replace_all(&out, REDACTED).into_owned());
+        }
+    }
+    out
+}
+
+/// Redact every string in a JSON document in place, replacing the values of
+/// credential-like keys wholesale.
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            if let Cow::Owned(redacted) = redact_text(s) {
+                *s = redacted;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_sensitive_key(key) && !v.is_null() {
+                    *v = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redact_text_masks_pii() {
+        let text = "mail bob@example.com or call 415-555-0134, card 4111 1111 1111 1111";
+        let redacted = redact_text(text);
+        assert!(!redacted.contains("bob@example.com"));
+        assert!(!redacted.contains("555-0134"));
+        assert!(!redacted.contains("4111 1111"));
+        assert_eq!(redacted.matches(REDACTED).count(), 3);
+    }
+
+    #[test]
+    fn test_redact_text_masks_credentials() {
+        let redacted =
+            redact_text("Authorization: Bearer abc.def-123 and sk-abcdefghijklmnopqrstuv");
+        assert!(!redacted.contains("abc.def-123"));
+        assert!(!redacted.contains("sk-abcdefghijklmnopqrstuv"));
+    }
+
+    #[test]
+    fn test_redact_text_borrows_when_clean() {
+        assert!(matches!(
+            redact_text("the answer is 42"),
+            Cow::Borrowed("the answer is 42")
+        ));
+    }
+
+    #[test]
+    fn test_redact_json_walks_nested_values() {
+        let mut value = json!({
+            "model": "llama",
+            "api_key": "plain-secret",
+            "messages": [{"role": "user", "content": "I am alice@corp.io"}],
+            "max_tokens": 16
+        });
+        redact_json(&mut value);
+        assert_eq!(value["model"], "llama");
+        assert_eq!(value["api_key"], REDACTED);
+        assert_eq!(value["messages"][0]["content"], format!("I am {REDACTED}"));
+        assert_eq!(value["max_tokens"], 16);
+    }
+}
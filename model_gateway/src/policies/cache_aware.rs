@@ -39,6 +39,18 @@
     eviction_interval_secs:  Interval between LRU eviction cycles
     max_tree_size:           Max nodes per approximate tree before eviction
     block_size:              Backend KV cache block size for event-driven routing
+    tree_window_secs:        History window for approximate trees (0 = unbounded)
+    cache_decay_half_life_secs: Half-life for matches in older generations (0 = none)
+
+    Time-Windowed Trees
+    -------------------------------------------
+    With a window configured, each model's approximate trees are rotated into
+    generations every window / TREE_WINDOW_GENERATIONS seconds. New routing
+    history always goes into the current generation; sealed generations are
+    only matched, and dropped once they fall out of the window. A match found
+    in a sealed generation is weighted by 0.5^(age / half_life) before being
+    compared against cache_threshold, so stale prefixes fade out instead of
+    pinning traffic to a worker whose KV cache no longer holds them.
 */
 
 use std::{
@@ -47,10 +59,11 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::{Duration, Instant},
 };
 
 use dashmap::DashMap;
-use kv_index::{compute_request_content_hashes, PositionalIndexer, TokenTree, Tree};
+use kv_index::{compute_request_content_hashes, PositionalIndexer, TenantId, TokenTree, Tree};
 use openai_protocol::worker::WorkerLoadResponse;
 use parking_lot::RwLock;
 use rand::RngExt;
@@ -67,6 +80,9 @@ use crate::{
     worker::{KvEventMonitor, Worker},
 };
 
+/// Number of generations (current plus sealed) a tree window is split into.
+const TREE_WINDOW_GENERATIONS: usize = 4;
+
 /// Latest per-worker backend load snapshot stream, keyed by worker URL.
 pub(crate) type LoadReceiver = watch::Receiver<HashMap<String, WorkerLoadResponse>>;
 
@@ -89,6 +105,10 @@ pub struct CacheAwarePolicy {
     /// Token-based trees for gRPC connections (pre-tokenized input)
     token_trees: Arc<DashMap<String, Arc<TokenTree>>>,
     _eviction_task: Option<PeriodicTask>,
+    /// Sealed tree generations per model, newest first. Empty unless a tree
+    /// window is configured.
+    sealed_trees: Arc<DashMap<String, SealedGenerations>>,
+    _window_task: Option<PeriodicTask>,
     /// Event-driven KV cache monitor for overlap scoring (gRPC workers only).
     kv_monitor: RwLock<Option<Arc<KvEventMonitor>>>,
     /// Latest per-worker backend load snapshot (keyed by worker URL) from the
@@ -123,6 +143,45 @@ pub struct CacheAwarePolicy {
     populate_hash_index: AtomicBool,
 }
 
+/// Read-only tree generations rotated out of the current trees, newest first.
+#[derive(Debug, Default)]
+struct SealedGenerations {
+    string: Vec<(Instant, Arc<Tree>)>,
+    token: Vec<(Instant, Arc<TokenTree>)>,
+}
+
+/// Seal the current trees of every model and start fresh ones, keeping at
+/// most `max_sealed` sealed generations per model. Tenants of the sealed tree
+/// are re-registered at the root of the new one so they stay routable.
+fn rotate_tree_generations(
+    string_trees: &DashMap<String, Arc<Tree>>,
+    token_trees: &DashMap<String, Arc<TokenTree>>,
+    sealed_trees: &DashMap<String, SealedGenerations>,
+    max_sealed: usize,
+    now: Instant,
+) {
+    for mut entry in string_trees.iter_mut() {
+        let fresh = Arc::new(Tree::new());
+        for tenant in entry.value().get_tenant_char_count().keys() {
+            fresh.insert_text("", tenant);
+        }
+        let sealed = std::mem::replace(entry.value_mut(), fresh);
+        let mut generations = sealed_trees.entry(entry.key().clone()).or_default();
+        generations.string.insert(0, (now, sealed));
+        generations.string.truncate(max_sealed);
+    }
+    for mut entry in token_trees.iter_mut() {
+        let fresh = Arc::new(TokenTree::new());
+        for tenant in entry.value().get_tenant_token_counts().keys() {
+            fresh.insert_tokens(&[], tenant);
+        }
+        let sealed = std::mem::replace(entry.value_mut(), fresh);
+        let mut generations = sealed_trees.entry(entry.key().clone()).or_default();
+        generations.token.insert(0, (now, sealed));
+        generations.token.truncate(max_sealed);
+    }
+}
+
 /// Per-model inner container for [`CacheAwarePolicy::hash_index`].
 /// Keeping both kinds in one struct per model makes the
 /// "separate model-scoped hash indexes for string and token
@@ -221,11 +280,37 @@ impl CacheAwarePolicy {
             None
         };
 
+        // Start background generation rotation if a tree window is configured
+        let sealed_trees = Arc::new(DashMap::<String, SealedGenerations>::new());
+        let window_task = config.effective_tree_window_secs().map(|window_secs| {
+            let string_trees_clone = Arc::clone(&string_trees);
+            let token_trees_clone = Arc::clone(&token_trees);
+            let sealed_trees_clone = Arc::clone(&sealed_trees);
+            let rotate_secs = (window_secs / TREE_WINDOW_GENERATIONS as u64).max(1);
+
+            PeriodicTask::spawn(rotate_secs, "TreeWindow", move || {
+                rotate_tree_generations(
+                    &string_trees_clone,
+                    &token_trees_clone,
+                    &sealed_trees_clone,
+                    TREE_WINDOW_GENERATIONS - 1,
+                    Instant::now(),
+                );
+                debug!(
+                    "Tree generations rotated for {} models, window: {}s",
+                    sealed_trees_clone.len(),
+                    window_secs
+                );
+            })
+        });
+
         Self {
             config,
             string_trees,
             token_trees,
             _eviction_task: eviction_task,
+            sealed_trees,
+            _window_task: window_task,
             kv_monitor: RwLock::new(None),
             load_rx: RwLock::new(None),
             hash_index,
@@ -467,6 +552,98 @@ impl CacheAwarePolicy {
         }
     }
 
+    /// Record `text` as cached on `worker` in its model's string tree, as a
+    /// routed HTTP request would.
+    pub fn record_prefix(&self, worker: &dyn Worker, text: &str) {
+        let tree_key = normalize_model_key(worker.model_id()).to_string();
+        self.string_trees
+            .entry(tree_key)
+            .or_insert_with(|| Arc::new(Tree::new()))
+            .insert_text(text, worker.url());
+    }
+
+    /// Forget the cached prefixes of one model, e.g. after its workers
+    /// flushed their KV caches. Workers stay in the trees; sealed
+    /// generations and the model's hash index are dropped.
+    pub fn reset_model(&self, model_id: &str) {
+        let tree_key = normalize_model_key(model_id);
+        if let Some(mut tree) = self.string_trees.get_mut(tree_key) {
+            let fresh = Arc::new(Tree::new());
+            for tenant in tree.get_tenant_char_count().keys() {
+                fresh.insert_text("", tenant);
+            }
+            *tree = fresh;
+        }
+        if let Some(mut tree) = self.token_trees.get_mut(tree_key) {
+            let fresh = Arc::new(TokenTree::new());
+            for tenant in tree.get_tenant_token_counts().keys() {
+                fresh.insert_tokens(&[], tenant);
+            }
+            *tree = fresh;
+        }
+        self.sealed_trees.remove(tree_key);
+        self.hash_index.remove(tree_key);
+        debug!(model_id = tree_key, "Cache-aware trees reset");
+    }
+
+    /// Seal the current approximate trees into a new generation now, as the
+    /// window task does on its interval.
+    pub fn rotate_tree_generations(&self) {
+        rotate_tree_generations(
+            &self.string_trees,
+            &self.token_trees,
+            &self.sealed_trees,
+            TREE_WINDOW_GENERATIONS - 1,
+            Instant::now(),
+        );
+    }
+
+    /// Weight of a match found in a generation sealed `age` ago.
+    fn decay_weight(&self, age: Duration) -> f32 {
+        match self.config.cache_decay_half_life_secs {
+            0 => 1.0,
+            half_life => 0.5f32.powf(age.as_secs_f32() / half_life as f32),
+        }
+    }
+
+    /// Best decayed match rate across the sealed token generations of a model.
+    fn best_sealed_token_match(&self, model_id: &str, tokens: &[u32]) -> Option<(f32, TenantId)> {
+        let generations = self.sealed_trees.get(model_id)?.token.clone();
+        let now = Instant::now();
+        let mut best: Option<(f32, TenantId)> = None;
+        for (sealed_at, tree) in generations {
+            let result = tree.match_prefix_with_counts(tokens);
+            if result.matched_token_count == 0 {
+                continue;
+            }
+            let rate = result.matched_token_count as f32 / result.input_token_count as f32
+                * self.decay_weight(now.saturating_duration_since(sealed_at));
+            if best.as_ref().is_none_or(|(best_rate, _)| rate > *best_rate) {
+                best = Some((rate, result.tenant));
+            }
+        }
+        best
+    }
+
+    /// Best decayed match rate across the sealed string generations of a model.
+    fn best_sealed_text_match(&self, model_id: &str, text: &str) -> Option<(f32, TenantId)> {
+        let generations = self.sealed_trees.get(model_id)?.string.clone();
+        let now = Instant::now();
+        let mut best: Option<(f32, TenantId)> = None;
+        for (sealed_at, tree) in generations {
+            let result = tree.match_prefix_with_counts(text);
+            if result.matched_char_count == 0 {
+                continue;
+            }
+            let rate = result.matched_char_count as f32 / result.input_char_count as f32
+                * self.decay_weight(now.saturating_duration_since(sealed_at));
+            if best.as_ref().is_none_or(|(best_rate, _)| rate > *best_rate) {
+                best = Some((rate, result.tenant));
+            }
+        }
+        best
+    }
+
     /// Select worker with minimum load (used when load is imbalanced)
     /// Handles both HTTP (text-based) and gRPC (token-based) requests.
     fn select_worker_min_load(
@@ -1008,6 +1185,10 @@ impl CacheAwarePolicy {
             //     worker — insert for it;
             //   * matched worker gone/unhealthy: select nothing and DON'T insert
             //     (closure returns None), falling back to first-healthy below.
+            //
+            // With a tree window, sealed generations are matched first; a
+            // decayed match there wins only if it beats the current tree.
+            let sealed_best = self.best_sealed_token_match(model_id, tokens);
             let mut selected_idx: Option<usize> = None;
             let result = tree.match_and_insert_with(tokens, |result| {
                 let match_rate = if result.input_token_count == 0 {
@@ -1015,12 +1196,15 @@ impl CacheAwarePolicy {
                 } else {
                     result.matched_token_count as f32 / result.input_token_count as f32
                 };
+                let (match_rate, tenant_url): (f32, &str) = match &sealed_best {
+                    Some((rate, tenant)) if *rate > match_rate => (*rate, &**tenant),
+                    _ => (match_rate, &*result.tenant),
+                };
 
                 selected_idx = if match_rate > self.config.cache_threshold {
                     // Cache hit: scan healthy_indices for the tenant (hash-free;
                     // url() is cheap). "Healthy" excludes circuit-broken workers, so
                     // a CB-tripped tenant falls through to min-load (intended).
-                    let tenant_url: &str = &result.tenant;
                     healthy_indices
                         .iter()
                         .copied()
@@ -1091,6 +1275,7 @@ impl CacheAwarePolicy {
             // then insert for it — replacing the former match_prefix_with_counts
             // + insert_text pair. Selection logic is unchanged (see the token
             // path for the per-branch rationale).
+            let sealed_best = self.best_sealed_text_match(model_id, text);
             let mut selected_idx: Option<usize> = None;
             let result = tree.match_and_insert_with(text, |result| {
                 let match_rate = if result.input_char_count == 0 {
@@ -1098,12 +1283,15 @@ impl CacheAwarePolicy {
                 } else {
                     result.matched_char_count as f32 / result.input_char_count as f32
                 };
+                let (match_rate, tenant_url): (f32, &str) = match &sealed_best {
+                    Some((rate, tenant)) if *rate > match_rate => (*rate, &**tenant),
+                    _ => (match_rate, &*result.tenant),
+                };
 
                 selected_idx = if match_rate > self.config.cache_threshold {
                     // Cache hit: scan healthy_indices for the tenant (hash-free;
                     // url() is cheap). "Healthy" excludes circuit-broken workers, so
                     // a CB-tripped tenant falls through to min-load (intended).
-                    let tenant_url: &str = &result.tenant;
                     healthy_indices
                         .iter()
                         .copied()
@@ -2363,4 +2551,126 @@ mod tests {
             .unwrap();
         assert_eq!(idx, idx2); // token tree cache affinity preserved
     }
+
+    #[test]
+    fn test_tree_window_drops_old_generations() {
+        let policy = CacheAwarePolicy::with_config(CacheAwareConfig {
+            eviction_interval_secs: 0,
+            ..Default::default()
+        });
+        let workers = make_workers(&["http://w1:8000", "http://w2:8000"]);
+        policy.init_workers(&workers);
+        let model_id = normalize_model_key(workers[0].model_id());
+        let info = SelectWorkerInfo {
+            request_text: Some("hello world"),
+            ..Default::default()
+        };
+
+        let idx1 = policy.select_worker(&workers, &info).unwrap();
+        policy.rotate_tree_generations();
+
+        // The current tree is empty, but the sealed generation still routes
+        // to the same worker (min-load alone would pick the other one).
+        assert_eq!(policy.select_worker(&workers, &info), Some(idx1));
+
+        for _ in 0..TREE_WINDOW_GENERATIONS {
+            policy.rotate_tree_generations();
+        }
+        let sealed = policy.sealed_trees.get(model_id).unwrap();
+        assert_eq!(sealed.string.len(), TREE_WINDOW_GENERATIONS - 1);
+        drop(sealed);
+        assert!(policy
+            .best_sealed_text_match(model_id, "hello world")
+            .is_none());
+    }
+
+    #[test]
+    fn test_record_prefix_steers_matching_requests() {
+        let policy = CacheAwarePolicy::with_config(CacheAwareConfig {
+            eviction_interval_secs: 0,
+            ..Default::default()
+        });
+        let workers = make_workers(&["http://w1:8000", "http://w2:8000"]);
+        policy.init_workers(&workers);
+
+        let prompt = "You are a meticulous code reviewer for the payments service.";
+        policy.record_prefix(workers[1].as_ref(), prompt);
+        let info = SelectWorkerInfo {
+            request_text: Some(prompt),
+            ..Default::default()
+        };
+        assert_eq!(policy.select_worker(&workers, &info), Some(1));
+    }
+
+    #[test]
+    fn test_reset_model_forgets_prefixes_but_keeps_workers() {
+        let policy = CacheAwarePolicy::with_config(CacheAwareConfig {
+            eviction_interval_secs: 0,
+            ..Default::default()
+        });
+        let workers = make_workers(&["http://w1:8000", "http://w2:8000"]);
+        policy.init_workers(&workers);
+        let model_id = normalize_model_key(workers[0].model_id());
+        let info = SelectWorkerInfo {
+            request_text: Some("hello world"),
+            ..Default::default()
+        };
+        policy.select_worker(&workers, &info).unwrap();
+        policy.rotate_tree_generations();
+        policy.select_worker(&workers, &info).unwrap();
+
+        policy.reset_model(workers[0].model_id());
+
+        let counts = policy
+            .string_trees
+            .get(model_id)
+            .unwrap()
+            .get_tenant_char_count();
+        assert_eq!(counts.len(), 2);
+        assert!(counts.values().all(|&count| count == 0));
+        assert!(policy.sealed_trees.get(model_id).is_none());
+        assert!(policy
+            .best_sealed_text_match(model_id, "hello world")
+            .is_none());
+    }
+
+    #[test]
+    fn test_sealed_match_decays_with_half_life() {
+        let policy = CacheAwarePolicy::with_config(CacheAwareConfig {
+            eviction_interval_secs: 0,
+            cache_decay_half_life_secs: 60,
+            ..Default::default()
+        });
+        let workers = make_workers(&["http://w1:8000", "http://w2:8000"]);
+        policy.init_workers(&workers);
+        let model_id = normalize_model_key(workers[0].model_id());
+
+        let stale = Arc::new(Tree::new());
+        stale.insert_text("hello world", workers[1].url());
+        let sealed_at = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap();
+        policy
+            .sealed_trees
+            .entry(model_id.to_string())
+            .or_default()
+            .string
+            .push((sealed_at, stale));
+
+        let (rate, tenant) = policy
+            .best_sealed_text_match(model_id, "hello world")
+            .unwrap();
+        assert!((rate - 0.25).abs() < 0.01, "rate {rate}");
+        assert_eq!(&*tenant, workers[1].url());
+
+        // Two half-lives old: below cache_threshold, so min-load wins.
+        let idx = policy.select_worker(
+            &workers,
+            &SelectWorkerInfo {
+                request_text: Some("hello world"),
+                ..Default::default()
+            },
+        );
+        assert_eq!(idx, Some(0));
+    }
 }
@@ -15,6 +15,15 @@
 //! This ensures O(log n) lookup performance.
 //!
 //! Complexity: O(log n) binary search + O(k) walk where k = consecutive unhealthy workers.
+//!
+//! ## Bounded Loads
+//!
+//! With a load bound `ε` configured, this is consistent hashing with bounded
+//! loads (CH-BL): each worker's capacity is `ceil((1 + ε) * (total_load + 1) / n)`
+//! over the healthy workers. A key whose ring owner is at capacity spills to the
+//! next worker clockwise with room, so hot keys cannot pile onto one worker while
+//! every other key keeps its affinity. Smaller `ε` balances tighter at the cost
+//! of more spills.
 
 use std::sync::Arc;
 
@@ -34,6 +43,7 @@ enum Branch {
     TargetWorkerHit,
     TargetWorkerMiss,
     RoutingKeyHit,
+    RoutingKeySpill,
     RandomFallback,
 }
 
@@ -45,17 +55,29 @@ impl Branch {
             Self::TargetWorkerHit => "target_worker_hit",
             Self::TargetWorkerMiss => "target_worker_miss",
             Self::RoutingKeyHit => "routing_key_hit",
+            Self::RoutingKeySpill => "routing_key_spill",
             Self::RandomFallback => "random_fallback",
         }
     }
 }
 
 #[derive(Debug, Default)]
-pub struct ConsistentHashingPolicy;
+pub struct ConsistentHashingPolicy {
+    /// Bounded-load slack `ε`; `None` routes every key to its ring owner.
+    load_epsilon: Option<f64>,
+}
 
 impl ConsistentHashingPolicy {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Consistent hashing with bounded loads: a worker accepts new keys only
+    /// while its load is below `(1 + epsilon)` times the average.
+    pub fn with_load_bound(epsilon: f64) -> Self {
+        Self {
+            load_epsilon: Some(epsilon),
+        }
     }
 
     /// Use consistent hashing to find a worker for the given key.
@@ -64,12 +86,16 @@ impl ConsistentHashingPolicy {
     /// The ring returns a worker URL, which we then map to an index in the workers array.
     /// This correctly handles filtered worker arrays since we match by URL, not by index.
     ///
+    /// With a load bound, a ring owner at capacity spills clockwise to the next
+    /// worker with room; the returned flag is true when that happened.
+    ///
     /// Complexity: O(n) to build healthy URL map + O(log n) ring lookup + O(k) walk
     fn find_by_consistent_hash(
+        &self,
         workers: &[Arc<dyn Worker>],
         info: &SelectWorkerInfo,
         key: &str,
-    ) -> Option<usize> {
+    ) -> Option<(usize, bool)> {
         // Build URL→index map for healthy workers: O(n) once, O(1) lookups
         let healthy_url_to_idx: std::collections::HashMap<&str, usize> = workers
             .iter()
@@ -86,7 +112,24 @@ impl ConsistentHashingPolicy {
         if let Some(ref ring) = info.hash_ring {
             // O(1) lookup per URL checked instead of O(n)
             let url = ring.find_healthy_url(key, |url| healthy_url_to_idx.contains_key(url))?;
-            return healthy_url_to_idx.get(url).copied();
+            let owner = healthy_url_to_idx.get(url).copied()?;
+
+            let Some(capacity) = self.load_capacity(workers, &healthy_url_to_idx) else {
+                return Some((owner, false));
+            };
+            if workers[owner].load() < capacity {
+                return Some((owner, false));
+            }
+            // Owner is at capacity: walk on to the first worker with room. Loads
+            // move concurrently, so keep the owner if none is found.
+            let spill = ring
+                .find_healthy_url(key, |url| {
+                    healthy_url_to_idx
+                        .get(url)
+                        .is_some_and(|&idx| workers[idx].load() < capacity)
+                })
+                .and_then(|url| healthy_url_to_idx.get(url).copied());
+            return Some(spill.map_or((owner, false), |idx| (idx, true)));
         }
 
         // Fallback: no ring provided, use simple modulo (less optimal but functional)
@@ -106,13 +149,24 @@ impl ConsistentHashingPolicy {
                 .expect("blake3 hash is always 32 bytes, slicing first 8 is infallible"),
         );
         let idx = (hash_val as usize) % healthy_indices.len();
-        Some(healthy_indices[idx])
+        Some((healthy_indices[idx], false))
+    }
+
+    /// Per-worker load capacity under the bound, or `None` when unbounded.
+    fn load_capacity(
+        &self,
+        workers: &[Arc<dyn Worker>],
+        healthy_url_to_idx: &std::collections::HashMap<&str, usize>,
+    ) -> Option<usize> {
+        let epsilon = self.load_epsilon?;
+        let total_load: usize = healthy_url_to_idx
+            .values()
+            .map(|&idx| workers[idx].load())
+            .sum();
+        let average = (total_load + 1) as f64 / healthy_url_to_idx.len() as f64;
+        Some(((1.0 + epsilon) * average).ceil() as usize)
     }
 
-    #[expect(
-        clippy::unused_self,
-        reason = "consistent with LoadBalancingPolicy trait pattern; may use self in future"
-    )]
     fn select_worker_impl(
         &self,
         workers: &[Arc<dyn Worker>],
@@ -138,8 +192,9 @@ impl ConsistentHashingPolicy {
 
         // Priority 2: X-SMG-Routing-Key - consistent hash routing (O(log n))
         if let Some(key) = routing_key {
-            return match Self::find_by_consistent_hash(workers, info, key) {
-                Some(idx) => (Some(idx), Branch::RoutingKeyHit),
+            return match self.find_by_consistent_hash(workers, info, key) {
+                Some((idx, false)) => (Some(idx), Branch::RoutingKeyHit),
+                Some((idx, true)) => (Some(idx), Branch::RoutingKeySpill),
                 None => (None, Branch::NoHealthyWorkers),
             };
         }
@@ -154,8 +209,9 @@ impl ConsistentHashingPolicy {
         });
 
         if let Some(key) = implicit_key {
-            return match Self::find_by_consistent_hash(workers, info, key) {
-                Some(idx) => (Some(idx), Branch::RoutingKeyHit),
+            return match self.find_by_consistent_hash(workers, info, key) {
+                Some((idx, false)) => (Some(idx), Branch::RoutingKeyHit),
+                Some((idx, true)) => (Some(idx), Branch::RoutingKeySpill),
                 None => (None, Branch::NoHealthyWorkers),
             };
         }
@@ -530,4 +586,35 @@ mod tests {
         let policy = ConsistentHashingPolicy::new();
         assert_eq!(policy.name(), "consistent_hashing");
     }
+
+    #[test]
+    fn test_bounded_load_spills_from_hot_owner() {
+        let bounded = ConsistentHashingPolicy::with_load_bound(0.25);
+        let unbounded = ConsistentHashingPolicy::new();
+        let workers = create_workers(&["http://w1:8000", "http://w2:8000", "http://w3:8000"]);
+        let ring = Arc::new(HashRing::new(workers.iter().map(|w| w.url())));
+        let headers = headers_with_routing_key("session-hot");
+        let info = SelectWorkerInfo {
+            headers: Some(&headers),
+            hash_ring: Some(ring),
+            ..Default::default()
+        };
+
+        let (result, branch) = bounded.select_worker_impl(&workers, &info);
+        let owner = result.unwrap();
+        assert_eq!(branch, Branch::RoutingKeyHit);
+
+        // Total load 10 over 3 workers: capacity ceil(1.25 * 11 / 3) = 5.
+        for _ in 0..10 {
+            workers[owner].increment_load();
+        }
+
+        let (result, branch) = bounded.select_worker_impl(&workers, &info);
+        assert_eq!(branch, Branch::RoutingKeySpill);
+        assert_ne!(result.unwrap(), owner);
+
+        let (result, branch) = unbounded.select_worker_impl(&workers, &info);
+        assert_eq!(result, Some(owner));
+        assert_eq!(branch, Branch::RoutingKeyHit);
+    }
 }
@@ -39,6 +39,8 @@ impl PolicyFactory {
                 block_size,
                 balance_token_usage_threshold,
                 overload_token_usage_threshold,
+                tree_window_secs,
+                cache_decay_half_life_secs,
             } => {
                 let config = CacheAwareConfig {
                     cache_threshold: *cache_threshold,
@@ -49,6 +51,8 @@ impl PolicyFactory {
                     block_size: *block_size,
                     balance_token_usage_threshold: *balance_token_usage_threshold,
                     overload_token_usage_threshold: *overload_token_usage_threshold,
+                    tree_window_secs: *tree_window_secs,
+                    cache_decay_half_life_secs: *cache_decay_half_life_secs,
                 };
                 Arc::new(CacheAwarePolicy::with_config(config))
             }
@@ -76,14 +80,21 @@ impl PolicyFactory {
                 };
                 Arc::new(ManualPolicy::with_config(config))
             }
-            PolicyConfig::ConsistentHashing => Arc::new(ConsistentHashingPolicy::new()),
+            PolicyConfig::ConsistentHashing { load_epsilon } => match load_epsilon {
+                Some(epsilon) => Arc::new(ConsistentHashingPolicy::with_load_bound(*epsilon)),
+                None => Arc::new(ConsistentHashingPolicy::new()),
+            },
             PolicyConfig::PrefixHash {
                 prefix_token_count,
                 load_factor,
+                block_size,
+                block_count,
             } => {
                 let config = PrefixHashConfig {
                     prefix_token_count: *prefix_token_count,
                     load_factor: *load_factor,
+                    block_size: *block_size,
+                    block_count: *block_count,
                 };
                 Arc::new(PrefixHashPolicy::new(config))
             }
@@ -139,6 +150,8 @@ mod tests {
             block_size: 16,
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         });
         assert_eq!(policy.name(), "cache_aware");
 
@@ -156,12 +169,16 @@ mod tests {
         });
         assert_eq!(policy.name(), "manual");
 
-        let policy = PolicyFactory::create_from_config(&PolicyConfig::ConsistentHashing);
+        let policy = PolicyFactory::create_from_config(&PolicyConfig::ConsistentHashing {
+            load_epsilon: None,
+        });
         assert_eq!(policy.name(), "consistent_hashing");
 
         let policy = PolicyFactory::create_from_config(&PolicyConfig::PrefixHash {
             prefix_token_count: 100,
             load_factor: 0.8,
+            block_size: 1,
+            block_count: None,
         });
         assert_eq!(policy.name(), "prefix_hash");
     }
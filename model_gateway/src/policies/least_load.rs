@@ -23,6 +23,10 @@ pub const DEFAULT_MEAN_PREFILL_TOKENS: u32 = 1024;
 /// co-tunes with `kv_pressure_weight`.
 pub const DEFAULT_THROUGHPUT: f64 = 2000.0;
 
+/// Characters per token when estimating a prompt's size from its text (the
+/// HTTP path, where the request is not tokenized before routing).
+const CHARS_PER_TOKEN_ESTIMATE: usize = 4;
+
 /// Least-(token-)work routing — route to the worker with the lowest estimated
 /// time-to-drain plus a convex KV-pressure barrier (argmin, lower is better):
 ///
@@ -45,6 +49,16 @@ pub const DEFAULT_THROUGHPUT: f64 = 2000.0;
 ///   `k`; convex and divergent at the KV cliff, so routing avoids the
 ///   preemption/recompute that begins as KV fills.
 ///
+/// When a worker reports absolute KV capacity (`max_total_num_tokens`), `k` is
+/// the *projected* utilization after admitting the request's prompt on the
+/// rank with the most free KV: `(used_tokens + prompt_tokens) / capacity`.
+/// A short prompt barely moves it, but a long-context prompt is steered to a
+/// worker with room for it: one that does not fit hits the barrier's ceiling,
+/// avoiding the KV-exhaustion preemptions it would otherwise trigger. Prompt
+/// size is the token count on gRPC and estimated from the request text on
+/// HTTP; ratio-only snapshots (Prometheus `/metrics`) keep the reported
+/// `token_usage`.
+///
 /// Both terms are in seconds, so they add directly. Missing signals degrade
 /// gracefully and stay in time units:
 /// - no queued-token report (backend doesn't expose waiting-queue tokens):
@@ -148,6 +162,7 @@ impl LeastLoadPolicy {
         inflight: &HashMap<String, u64>,
         nominal_throughput: f64,
         fleet_has_loads: bool,
+        prompt_tokens: Option<u64>,
     ) -> f64 {
         let url = worker.url();
         match loads.and_then(|m| m.get(url)) {
@@ -160,7 +175,10 @@ impl LeastLoadPolicy {
                 } else {
                     self.default_throughput
                 };
-                let k = load.effective_token_usage().clamp(0.0, 0.999);
+                let k = prompt_tokens
+                    .and_then(|tokens| projected_kv_usage(load, tokens))
+                    .unwrap_or_else(|| load.effective_token_usage())
+                    .clamp(0.0, 0.999);
                 (queued_tokens + inflight_tokens) / throughput
                     + self.kv_pressure_weight * k / (1.0 - k)
             }
@@ -185,6 +203,30 @@ impl LeastLoadPolicy {
     }
 }
 
+/// Prompt size of the request being routed: its token count if tokenized,
+/// else estimated from its text.
+fn prompt_tokens(info: &SelectWorkerInfo) -> Option<u64> {
+    match (info.tokens, info.request_text) {
+        (Some(tokens), _) => Some(tokens.len() as u64),
+        (None, Some(text)) => Some(text.len().div_ceil(CHARS_PER_TOKEN_ESTIMATE) as u64),
+        (None, None) => None,
+    }
+}
+
+/// KV utilization of the rank with the most free KV after admitting
+/// `prompt_tokens`, or `None` without absolute capacity data. Exceeds `1.0`
+/// when the prompt fits on no rank.
+fn projected_kv_usage(load: &WorkerLoadResponse, prompt_tokens: u64) -> Option<f64> {
+    load.loads
+        .iter()
+        .filter(|rank| rank.max_total_num_tokens > 0)
+        .max_by_key(|rank| rank.max_total_num_tokens - rank.num_used_tokens)
+        .map(|rank| {
+            (rank.num_used_tokens.max(0) as f64 + prompt_tokens as f64)
+                / rank.max_total_num_tokens as f64
+        })
+}
+
 impl LoadBalancingPolicy for LeastLoadPolicy {
     fn select_worker(&self, workers: &[Arc<dyn Worker>], info: &SelectWorkerInfo) -> Option<usize> {
         let healthy = get_healthy_worker_indices(workers);
@@ -216,6 +258,7 @@ impl LoadBalancingPolicy for LeastLoadPolicy {
         let fleet_has_loads = loads
             .map(|m| healthy.iter().any(|&i| m.contains_key(workers[i].url())))
             .unwrap_or(false);
+        let prompt_tokens = prompt_tokens(info);
 
         // Held across selection so the in-flight estimate stays consistent and
         // the chosen worker can be credited before the guard is released.
@@ -231,6 +274,7 @@ impl LoadBalancingPolicy for LeastLoadPolicy {
             &inflight,
             nominal_throughput,
             fleet_has_loads,
+            prompt_tokens,
         );
         for &idx in &healthy[1..] {
             let s = self.score(
@@ -239,6 +283,7 @@ impl LoadBalancingPolicy for LeastLoadPolicy {
                 &inflight,
                 nominal_throughput,
                 fleet_has_loads,
+                prompt_tokens,
             );
             if s < best_score {
                 best = idx;
@@ -452,6 +497,67 @@ mod tests {
         );
     }
 
+    /// One DP rank reporting absolute KV capacity.
+    fn make_capacity_load(num_used_tokens: i32, max_total_num_tokens: i32) -> WorkerLoadResponse {
+        let mut load = make_load(0, 0.0, 100.0);
+        load.loads[0].num_used_tokens = num_used_tokens;
+        load.loads[0].max_total_num_tokens = max_total_num_tokens;
+        load.loads[0].token_usage = num_used_tokens as f64 / max_total_num_tokens as f64;
+        load
+    }
+
+    #[test]
+    fn long_prompt_prefers_worker_with_free_kv() {
+        // a: 45k of 100k used (55k free); b: 20k of 50k used (30k free).
+        // a is fuller by ratio, so a short prompt goes to b, but a 40k-token
+        // prompt only fits on a.
+        let policy = LeastLoadPolicy::new();
+        let workers = vec![mk("http://a:8000"), mk("http://b:8000")];
+        let mut loads = HashMap::new();
+        loads.insert(
+            "http://a:8000".to_string(),
+            make_capacity_load(45_000, 100_000),
+        );
+        loads.insert(
+            "http://b:8000".to_string(),
+            make_capacity_load(20_000, 50_000),
+        );
+
+        let short = vec![0u32; 100];
+        policy.update_loads(&loads);
+        let info = SelectWorkerInfo {
+            tokens: Some(&short),
+            ..Default::default()
+        };
+        assert_eq!(policy.select_worker(&workers, &info), Some(1));
+
+        let long = vec![0u32; 40_000];
+        policy.update_loads(&loads);
+        let info = SelectWorkerInfo {
+            tokens: Some(&long),
+            ..Default::default()
+        };
+        assert_eq!(policy.select_worker(&workers, &info), Some(0));
+    }
+
+    #[test]
+    fn prompt_size_is_estimated_from_text_without_tokens() {
+        let text = "x".repeat(4001);
+        let info = SelectWorkerInfo {
+            request_text: Some(&text),
+            ..Default::default()
+        };
+        assert_eq!(prompt_tokens(&info), Some(1001));
+        assert_eq!(prompt_tokens(&SelectWorkerInfo::default()), None);
+
+        // Ratio-only snapshots have no capacity to project against.
+        assert_eq!(projected_kv_usage(&make_load(0, 0.5, 100.0), 1000), None);
+        assert_eq!(
+            projected_kv_usage(&make_capacity_load(500, 1000), 250),
+            Some(0.75)
+        );
+    }
+
     #[test]
     fn inflight_correction_spreads_within_poll_interval() {
         // Two identical workers, no fresh poll between dispatches: the in-flight
@@ -22,6 +22,7 @@ mod prefix_hash;
 mod random;
 mod registry;
 mod round_robin;
+mod sessions;
 pub(crate) mod utils;
 
 pub use bucket::BucketPolicy;
@@ -37,8 +38,9 @@ pub use passthrough::PassthroughPolicy;
 pub use power_of_two::PowerOfTwoPolicy;
 pub use prefix_hash::{PrefixHashConfig, PrefixHashPolicy};
 pub use random::RandomPolicy;
-pub use registry::PolicyRegistry;
+pub use registry::{PolicyOverrideError, PolicyRegistry};
 pub use round_robin::RoundRobinPolicy;
+pub use sessions::{SessionInfo, SessionPins, SessionTarget};
 
 /// Core trait for load balancing policies
 ///
@@ -128,6 +130,28 @@ pub struct CacheAwareConfig {
     /// shedding load off a critically-saturated engine. A safety valve, best set
     /// high (e.g. 0.9). Requires `token_usage`; `>= 1.0` disables it (default).
     pub overload_token_usage_threshold: f32,
+    /// Sliding window (seconds) of routing history used for approximate-tree
+    /// matching. The trees are rotated into generations and generations older
+    /// than the window are dropped, so prefixes the backends have long since
+    /// evicted stop attracting traffic. `0` keeps history until size-based
+    /// eviction (default).
+    pub tree_window_secs: u64,
+    /// Half-life (seconds) applied to matches found in older generations: a
+    /// match one half-life old counts half as much against `cache_threshold`.
+    /// `0` disables decay (default). Without an explicit window, enabling
+    /// decay uses a window of four half-lives.
+    pub cache_decay_half_life_secs: u64,
+}
+
+impl CacheAwareConfig {
+    /// Effective history window in seconds, or `None` when windowing is off.
+    pub fn effective_tree_window_secs(&self) -> Option<u64> {
+        match (self.tree_window_secs, self.cache_decay_half_life_secs) {
+            (0, 0) => None,
+            (0, half_life) => Some(half_life.saturating_mul(4)),
+            (window, _) => Some(window),
+        }
+    }
 }
 
 impl Default for CacheAwareConfig {
@@ -143,6 +167,8 @@ impl Default for CacheAwareConfig {
             // balance e.g. 0.5 (spread) and/or overload e.g. 0.9 (ceiling).
             balance_token_usage_threshold: 1.0,
             overload_token_usage_threshold: 1.0,
+            tree_window_secs: 0,
+            cache_decay_half_life_secs: 0,
         }
     }
 }
@@ -219,8 +245,10 @@ pub struct SelectWorkerInfo<'a> {
     pub tokens: Option<&'a [u32]>,
     /// HTTP headers for header-based routing policies
     /// Policies can extract routing information from headers like:
-    /// - X-SMG-Target-Worker: Direct routing to a specific worker by index
+    /// - X-SMG-Target-Worker: Direct routing to a specific worker by URL
+    ///   (admin-only, see `middleware::target_worker`) or by index
     /// - X-SMG-Routing-Key: Consistent hash routing for session affinity
+    /// - X-SMG-Session-Id: Pinning to the worker of a `/v1/sessions` session
     pub headers: Option<&'a http::HeaderMap>,
     /// Pre-computed hash ring for O(log n) consistent hashing
     /// Built and cached by WorkerRegistry, passed through to avoid per-request rebuilds
@@ -6,7 +6,10 @@
 //!
 //! ## Algorithm
 //!
-//! 1. Extract first N tokens from the request (configurable prefix length)
+//! 1. Extract the prefix in whole blocks of `block_size` tokens, up to
+//!    `block_count` blocks (or `prefix_token_count` tokens when unset). A
+//!    trailing partial block is dropped, matching backends that only cache
+//!    full pages; requests shorter than one block hash all their tokens.
 //! 2. Hash the token sequence using xxhash for fast, stable hashing
 //! 3. Use consistent hash ring to find the target worker
 //! 4. If worker is overloaded (load > avg * load_factor), find least loaded
@@ -48,6 +51,15 @@ pub struct PrefixHashConfig {
     /// walk clockwise to the next worker.
     /// Default: 1.25 (125% of average load)
     pub load_factor: f64,
+
+    /// Tokens per hash block. Set to the backend's KV page/block size so
+    /// requests sharing cached pages hash alike.
+    /// Default: 1 (token granularity)
+    pub block_size: usize,
+
+    /// Number of blocks hashed. Overrides `prefix_token_count` when set.
+    /// Default: None
+    pub block_count: Option<usize>,
 }
 
 impl Default for PrefixHashConfig {
@@ -55,6 +67,8 @@ impl Default for PrefixHashConfig {
         Self {
             prefix_token_count: 256,
             load_factor: 1.25,
+            block_size: 1,
+            block_count: None,
         }
     }
 }
@@ -102,14 +116,32 @@ impl PrefixHashPolicy {
         Self::new(PrefixHashConfig::default())
     }
 
-    /// Compute hash of prefix tokens using xxhash
+    /// Number of prefix tokens hashed for a request of `token_count` tokens
+    #[inline]
+    fn prefix_len(&self, token_count: usize) -> usize {
+        let block_size = self.config.block_size.max(1);
+        let max_tokens = match self.config.block_count {
+            Some(blocks) => blocks.saturating_mul(block_size),
+            None => self.config.prefix_token_count,
+        };
+        let prefix_len = token_count.min(max_tokens);
+        if prefix_len < block_size {
+            prefix_len
+        } else {
+            prefix_len - prefix_len % block_size
+        }
+    }
+
+    /// Compute hash of prefix tokens using xxhash, along with the number of
+    /// blocks hashed
     #[inline]
-    fn compute_prefix_hash(&self, tokens: &[u32]) -> u64 {
-        let prefix_len = tokens.len().min(self.config.prefix_token_count);
+    fn compute_prefix_hash(&self, tokens: &[u32]) -> (u64, usize) {
+        let prefix_len = self.prefix_len(tokens.len());
         let prefix = &tokens[..prefix_len];
 
         let bytes: &[u8] = bytemuck::cast_slice(prefix);
-        xxhash_rust::xxh3::xxh3_64(bytes)
+        let blocks = prefix_len.div_ceil(self.config.block_size.max(1));
+        (xxhash_rust::xxh3::xxh3_64(bytes), blocks)
     }
 
     /// Check if a worker's load is acceptable
@@ -214,10 +246,14 @@ impl PrefixHashPolicy {
         };
 
         // Compute prefix hash
-        let prefix_hash = self.compute_prefix_hash(tokens);
+        let (prefix_hash, blocks) = self.compute_prefix_hash(tokens);
 
         // Find worker using ring with load balancing
-        self.find_worker_with_load_balance(workers, info, prefix_hash)
+        let (result, branch) = self.find_worker_with_load_balance(workers, info, prefix_hash);
+        if result.is_some() {
+            Metrics::record_prefix_hash_blocks(branch.as_str(), blocks);
+        }
+        (result, branch)
     }
 }
 
@@ -411,4 +447,44 @@ mod tests {
         let policy = PrefixHashPolicy::with_defaults();
         assert_eq!(policy.name(), "prefix_hash");
     }
+
+    #[test]
+    fn test_prefix_len_aligns_to_blocks() {
+        let policy = PrefixHashPolicy::new(PrefixHashConfig {
+            block_size: 4,
+            block_count: Some(3),
+            ..Default::default()
+        });
+
+        assert_eq!(policy.prefix_len(2), 2); // Shorter than a block: hash it all
+        assert_eq!(policy.prefix_len(7), 4); // Partial trailing block dropped
+        assert_eq!(policy.prefix_len(8), 8);
+        assert_eq!(policy.prefix_len(100), 12); // Capped at block_count blocks
+
+        // Without block_count, prefix_token_count caps the prefix
+        let policy = PrefixHashPolicy::new(PrefixHashConfig {
+            prefix_token_count: 10,
+            block_size: 4,
+            ..Default::default()
+        });
+        assert_eq!(policy.prefix_len(100), 8);
+    }
+
+    #[test]
+    fn test_shared_blocks_route_same() {
+        let policy = PrefixHashPolicy::new(PrefixHashConfig {
+            block_size: 4,
+            block_count: Some(2),
+            ..Default::default()
+        });
+
+        // Same two full blocks; differ only in the partial third block
+        let (hash1, blocks1) = policy.compute_prefix_hash(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let (hash2, blocks2) = policy.compute_prefix_hash(&[1, 2, 3, 4, 5, 6, 7, 8, 42, 43]);
+        assert_eq!(hash1, hash2);
+        assert_eq!((blocks1, blocks2), (2, 2));
+
+        let (hash3, _) = policy.compute_prefix_hash(&[1, 2, 3, 4, 5, 6, 7, 9]);
+        assert_ne!(hash1, hash3);
+    }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::{Arc, OnceLock},
 };
 
@@ -13,14 +13,19 @@ use tracing::{debug, info, warn};
 /// When the first worker of a new model is added, it determines the policy for that model.
 /// All subsequent workers of the same model use the established policy.
 /// When the last worker of a model is removed, the policy mapping is cleaned up.
+/// A policy assigned to a model at runtime (see [`PolicyRegistry::set_model_policy`])
+/// takes precedence over worker hints and the default.
 use super::{
     BucketPolicy, CacheAwarePolicy, DPRankLoadPolicy, LoadBalancingPolicy, ManualConfig,
-    ManualPolicy, PolicyFactory, SelectWorkerInfo,
+    ManualPolicy, PolicyFactory, SelectWorkerInfo, SessionPins, WorkerLeg,
 };
 use crate::{
-    config::types::{PolicyConfig, RoutingKeyOverrideConfig},
+    config::types::{PolicyConfig, RoutingKeyOverrideConfig, SessionsConfig},
+    observability::metrics::Metrics,
     policies::cache_aware::LoadReceiver,
-    routers::common::header_utils::extract_routing_key,
+    routers::common::header_utils::{
+        extract_routing_key, extract_session_id, extract_target_worker,
+    },
     worker::{KvEventMonitor, Worker},
 };
 
@@ -33,6 +38,15 @@ pub struct PolicyRegistry {
     /// Model ID -> Worker count for cleanup tracking (lock-free reads via DashMap)
     model_worker_counts: Arc<DashMap<String, usize>>,
 
+    /// Model ID -> policy name assigned at runtime through the admin API or
+    /// mesh sync. Outlives the model's workers, so a model that comes back
+    /// gets its assigned policy again.
+    model_overrides: Arc<DashMap<String, &'static str>>,
+
+    /// Model ID -> policy hint of the worker that created the model's policy,
+    /// restored when the model's override is cleared.
+    model_hints: Arc<DashMap<String, String>>,
+
     /// Default policy instance (cached, immutable after creation)
     default_policy: Arc<dyn LoadBalancingPolicy>,
 
@@ -61,6 +75,10 @@ pub struct PolicyRegistry {
     /// override is enabled; consulted (instead of the configured policy) for keyed
     /// requests via [`PolicyRegistry::select_worker`].
     routing_key_sticky: Option<Arc<ManualPolicy>>,
+
+    /// Sessions pinned through `/v1/sessions`, consulted for requests carrying
+    /// `X-SMG-Session-Id`.
+    sessions: Arc<SessionPins>,
 }
 
 impl PolicyRegistry {
@@ -87,6 +105,8 @@ impl PolicyRegistry {
         Self {
             model_policies: Arc::new(DashMap::new()),
             model_worker_counts: Arc::new(DashMap::new()),
+            model_overrides: Arc::new(DashMap::new()),
+            model_hints: Arc::new(DashMap::new()),
             default_policy,
             prefill_policy: Arc::new(OnceLock::new()),
             decode_policy: Arc::new(OnceLock::new()),
@@ -95,18 +115,106 @@ impl PolicyRegistry {
             load_rx: Arc::new(RwLock::new(None)),
             dp_rank_policy: Arc::new(OnceLock::new()),
             routing_key_sticky,
+            sessions: SessionPins::new(&SessionsConfig::default()),
         }
     }
 
+    /// Replace the session store with one using `config`.
+    #[must_use]
+    pub fn with_sessions(mut self, config: &SessionsConfig) -> Self {
+        self.sessions = SessionPins::new(config);
+        self
+    }
+
+    /// Sessions pinned through `/v1/sessions`.
+    pub fn sessions(&self) -> &Arc<SessionPins> {
+        &self.sessions
+    }
+
     /// Select a worker, applying the `X-SMG-Routing-Key` sticky override when it is
     /// enabled, the request carries the header, and the configured policy does not
     /// already honor the key (`manual` / `consistent_hashing`). Otherwise delegates
     /// to `policy`. `policy.name()` stays the real policy (for metrics).
+    ///
+    /// An `X-SMG-Target-Worker` URL (already authorized and resolved by
+    /// `target_worker_middleware`) takes precedence over both. A pinned worker
+    /// that is not among `workers` yields `None` rather than a silent reroute,
+    /// except on PD legs where the pin names only one side of the pair. Next
+    /// comes an `X-SMG-Session-Id` session pin, for non-PD selections only.
     pub fn select_worker(
         &self,
         policy: &Arc<dyn LoadBalancingPolicy>,
         workers: &[Arc<dyn Worker>],
         info: &SelectWorkerInfo,
+    ) -> Option<usize> {
+        if let Some(target) = extract_target_worker(info.headers) {
+            if let Some(idx) = workers.iter().position(|w| w.url() == target) {
+                return Some(idx);
+            }
+            // Numeric values are the `consistent_hashing` index form.
+            if info.leg == WorkerLeg::Single && !target.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+        }
+        if info.leg == WorkerLeg::Single {
+            if let Some(session_id) = extract_session_id(info.headers) {
+                return self.select_session_worker(session_id, policy, workers, info);
+            }
+        }
+        self.select_unpinned(policy, workers, info)
+    }
+
+    /// Route to the session's worker while it is available; otherwise route
+    /// normally and move the session to the selected worker.
+    fn select_session_worker(
+        &self,
+        session_id: &str,
+        policy: &Arc<dyn LoadBalancingPolicy>,
+        workers: &[Arc<dyn Worker>],
+        info: &SelectWorkerInfo,
+    ) -> Option<usize> {
+        let Some(target) = self.sessions.resolve(session_id) else {
+            Metrics::record_session_routing("unknown");
+            return self.select_unpinned(policy, workers, info);
+        };
+        if let Some(idx) = workers
+            .iter()
+            .position(|w| w.url() == target.worker_url && w.is_available())
+        {
+            if let Some(text) = info.request_text {
+                Self::record_session_prefix(policy, workers[idx].as_ref(), text);
+            }
+            Metrics::record_session_routing("pinned");
+            return Some(idx);
+        }
+        let idx = self.select_unpinned(policy, workers, info)?;
+        // A request for another model must not take the session with it.
+        if workers[idx].supports_model(&target.model_id) {
+            self.sessions.repin(session_id, workers[idx].url());
+            Metrics::record_session_routing("repinned");
+        }
+        Some(idx)
+    }
+
+    /// Record `text` for `worker` in its model's cache-aware tree, so requests
+    /// sharing a session's prompt keep landing on its worker. No-op unless
+    /// `policy` is cache-aware.
+    pub fn record_session_prefix(
+        policy: &Arc<dyn LoadBalancingPolicy>,
+        worker: &dyn Worker,
+        text: &str,
+    ) {
+        if let Some(cache_aware) = policy.as_any().downcast_ref::<CacheAwarePolicy>() {
+            cache_aware.record_prefix(worker, text);
+        }
+    }
+
+    /// Policy selection with the `X-SMG-Routing-Key` override, after pins.
+    fn select_unpinned(
+        &self,
+        policy: &Arc<dyn LoadBalancingPolicy>,
+        workers: &[Arc<dyn Worker>],
+        info: &SelectWorkerInfo,
     ) -> Option<usize> {
         if let Some(sticky) = self.routing_key_sticky.as_ref() {
             if Self::routing_key_override_applies(policy.name())
@@ -227,6 +335,10 @@ impl PolicyRegistry {
         // Store policy for this model (DashMap handles concurrent inserts)
         self.model_policies
             .insert(model_id.to_string(), Arc::clone(&policy));
+        if let Some(hint) = policy_hint {
+            self.model_hints
+                .insert(model_id.to_string(), hint.to_string());
+        }
 
         policy
     }
@@ -258,6 +370,7 @@ impl PolicyRegistry {
 
         // Clean up policy if this was the last worker
         if should_cleanup {
+            self.model_hints.remove(model_id);
             if let Some((_, policy)) = self.model_policies.remove(model_id) {
                 info!(
                     "Removed policy {} for model {} (last worker removed)",
@@ -290,13 +403,22 @@ impl PolicyRegistry {
         model_id: &str,
         policy_hint: Option<&str>,
     ) -> Arc<dyn LoadBalancingPolicy> {
-        // 1. Check policy hint from worker
+        // 1. Check policy assigned at runtime
+        if let Some(policy_type) = self.model_overrides.get(model_id).map(|p| *p) {
+            debug!(
+                "Using assigned policy '{}' for model {}",
+                policy_type, model_id
+            );
+            return self.create_policy_from_type(policy_type);
+        }
+
+        // 2. Check policy hint from worker
         if let Some(policy_type) = policy_hint {
             debug!("Using policy hint '{}' for model {}", policy_type, model_id);
             return self.create_policy_from_type(policy_type);
         }
 
-        // 2. Use default policy
+        // 3. Use default policy
         debug!("Using default policy for model {}", model_id);
         Arc::clone(&self.default_policy)
     }
@@ -326,6 +448,106 @@ impl PolicyRegistry {
         }
     }
 
+    /// Assign `policy` to `model_id`, taking precedence over worker hints and
+    /// the default. Returns the canonical policy name.
+    ///
+    /// If the model has workers its policy is rebuilt right away, with a
+    /// cache-aware policy's trees seeded from `workers` (the model's
+    /// workers); otherwise the policy is created when its first worker
+    /// registers. Re-assigning the current policy keeps its state.
+    pub fn set_model_policy(
+        &self,
+        model_id: &str,
+        policy: &str,
+        workers: &[Arc<dyn Worker>],
+    ) -> Result<&'static str, PolicyOverrideError> {
+        let name = Self::canonical_policy_name(policy)?;
+        self.model_overrides.insert(model_id.to_string(), name);
+        self.rebuild_model_policy(model_id, workers);
+        Ok(name)
+    }
+
+    /// Drop the policy assigned to `model_id`, returning it. The model goes
+    /// back to its worker hint or the default policy.
+    pub fn clear_model_policy(
+        &self,
+        model_id: &str,
+        workers: &[Arc<dyn Worker>],
+    ) -> Option<&'static str> {
+        let (_, name) = self.model_overrides.remove(model_id)?;
+        self.rebuild_model_policy(model_id, workers);
+        Some(name)
+    }
+
+    /// Policies assigned at runtime, by model.
+    pub fn model_policy_overrides(&self) -> BTreeMap<String, String> {
+        self.model_overrides
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().to_string()))
+            .collect()
+    }
+
+    /// Replace every runtime assignment with `overrides` (used by mesh
+    /// sync). Nothing changes if any policy name is unknown. `workers_of`
+    /// yields a model's workers for rebuilt cache-aware policies.
+    pub fn replace_model_policies<W>(
+        &self,
+        overrides: &BTreeMap<String, String>,
+        workers_of: impl Fn(&str) -> W,
+    ) -> Result<(), PolicyOverrideError>
+    where
+        W: AsRef<[Arc<dyn Worker>]>,
+    {
+        let overrides = overrides
+            .iter()
+            .map(|(model_id, policy)| Ok((model_id, Self::canonical_policy_name(policy)?)))
+            .collect::<Result<Vec<_>, PolicyOverrideError>>()?;
+
+        let stale: Vec<String> = self
+            .model_overrides
+            .iter()
+            .map(|entry| entry.key().clone())
+            .filter(|model_id| !overrides.iter().any(|(m, _)| *m == model_id))
+            .collect();
+        for model_id in stale {
+            self.clear_model_policy(&model_id, workers_of(&model_id).as_ref());
+        }
+        for (model_id, name) in overrides {
+            if self.model_overrides.get(model_id).map(|p| *p) != Some(name) {
+                self.model_overrides.insert(model_id.clone(), name);
+                self.rebuild_model_policy(model_id, workers_of(model_id).as_ref());
+            }
+        }
+        Ok(())
+    }
+
+    fn canonical_policy_name(policy: &str) -> Result<&'static str, PolicyOverrideError> {
+        PolicyFactory::create_by_name(policy)
+            .map(|p| p.name())
+            .ok_or_else(|| PolicyOverrideError::UnknownPolicy(policy.to_string()))
+    }
+
+    /// Swap the policy of a model that has workers for the one
+    /// [`Self::determine_policy_for_model`] now picks, unless it is the same
+    /// kind. The replaced instance, and any per-model state it held, is dropped.
+    fn rebuild_model_policy(&self, model_id: &str, workers: &[Arc<dyn Worker>]) {
+        if !self.model_worker_counts.contains_key(model_id) {
+            return;
+        }
+        let hint = self.model_hints.get(model_id).map(|h| h.clone());
+        let policy = self.determine_policy_for_model(model_id, hint.as_deref());
+        if let Some(current) = self.model_policies.get(model_id) {
+            if current.name() == policy.name() {
+                return;
+            }
+        }
+        if let Some(cache_aware) = policy.as_any().downcast_ref::<CacheAwarePolicy>() {
+            cache_aware.init_workers(workers);
+        }
+        info!("Switching model {} to policy {}", model_id, policy.name());
+        self.model_policies.insert(model_id.to_string(), policy);
+    }
+
     /// Create a policy from a PolicyConfig (delegates to PolicyFactory)
     fn create_policy_from_config(config: &PolicyConfig) -> Arc<dyn LoadBalancingPolicy> {
         PolicyFactory::create_from_config(config)
@@ -351,6 +573,8 @@ impl PolicyRegistry {
     pub fn clear(&self) {
         self.model_policies.clear();
         self.model_worker_counts.clear();
+        self.model_overrides.clear();
+        self.model_hints.clear();
     }
 
     /// Set the prefill policy for PD mode (lock-free, set once at startup)
@@ -405,10 +629,11 @@ impl PolicyRegistry {
     /// repeated multimodal items keep stable affinity even when the main policy is
     /// load-oriented or random.
     pub fn get_encode_policy(&self) -> Arc<dyn LoadBalancingPolicy> {
-        self.encode_policy
-            .get()
-            .map(Arc::clone)
-            .unwrap_or_else(|| PolicyFactory::create_from_config(&PolicyConfig::ConsistentHashing))
+        self.encode_policy.get().map(Arc::clone).unwrap_or_else(|| {
+            PolicyFactory::create_from_config(&PolicyConfig::ConsistentHashing {
+                load_epsilon: None,
+            })
+        })
     }
 
     /// Get all load-aware policies that need periodic load updates (lock-free).
@@ -487,6 +712,24 @@ impl PolicyRegistry {
         }
     }
 
+    /// Reset the cache-aware trees of `model_id` in its own policy and the
+    /// PD policies. Returns whether any cache-aware policy was reset.
+    pub fn reset_cache_aware_model(&self, model_id: &str) -> bool {
+        let policies = [
+            self.get_policy(model_id),
+            self.prefill_policy.get().cloned(),
+            self.decode_policy.get().cloned(),
+        ];
+        let mut reset = false;
+        for policy in policies.into_iter().flatten() {
+            if let Some(cache_aware) = policy.as_any().downcast_ref::<CacheAwarePolicy>() {
+                cache_aware.reset_model(model_id);
+                reset = true;
+            }
+        }
+        reset
+    }
+
     /// Remove a worker from cache-aware policy if applicable
     /// This should be called when a worker is being removed
     pub fn remove_worker_from_cache_aware(&self, model_id: &str, worker_url: &str) {
@@ -597,11 +840,19 @@ impl PolicyRegistry {
     }
 }
 
+/// Rejected runtime policy assignment.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PolicyOverrideError {
+    #[error("unknown policy '{0}'")]
+    UnknownPolicy(String),
+}
+
 impl std::fmt::Debug for PolicyRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PolicyRegistry")
             .field("model_policies", &self.model_policies)
             .field("model_worker_counts", &self.model_worker_counts)
+            .field("model_overrides", &self.model_overrides)
             .field("default_policy", &self.default_policy.name())
             .finish()
     }
@@ -725,6 +976,76 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn target_worker_pin_bypasses_policy() {
+        let reg = PolicyRegistry::new(PolicyConfig::RoundRobin);
+        let policy = reg.get_default_policy();
+        let workers = vec![
+            worker("http://w1", WorkerType::Regular),
+            worker("http://w2", WorkerType::Regular),
+        ];
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-smg-target-worker", "http://w2".parse().unwrap());
+        let info = SelectWorkerInfo {
+            headers: Some(&headers),
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            assert_eq!(reg.select_worker(&policy, &workers, &info), Some(1));
+        }
+
+        // A pin to a worker outside the candidate set is not rerouted...
+        headers.insert("x-smg-target-worker", "http://w9".parse().unwrap());
+        let info = SelectWorkerInfo {
+            headers: Some(&headers),
+            ..Default::default()
+        };
+        assert_eq!(reg.select_worker(&policy, &workers, &info), None);
+
+        // ...except on a PD leg, where it names only the other side.
+        let info = SelectWorkerInfo {
+            headers: Some(&headers),
+            leg: WorkerLeg::Prefill,
+            ..Default::default()
+        };
+        assert!(reg.select_worker(&policy, &workers, &info).is_some());
+    }
+
+    #[test]
+    fn session_pin_follows_available_worker() {
+        let reg = PolicyRegistry::new(PolicyConfig::RoundRobin);
+        let policy = reg.get_default_policy();
+        let workers: Vec<Arc<dyn Worker>> = ["http://w1", "http://w2"]
+            .into_iter()
+            .map(|url| {
+                Arc::new(
+                    BasicWorkerBuilder::new(url)
+                        .model(crate::worker::ModelCard::new("llama"))
+                        .health_config(no_health_check())
+                        .build(),
+                ) as Arc<dyn Worker>
+            })
+            .collect();
+        let session = reg.sessions().open("tenant", "llama", "http://w2").unwrap();
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-smg-session-id", session.id.parse().unwrap());
+        let info = SelectWorkerInfo {
+            headers: Some(&headers),
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            assert_eq!(reg.select_worker(&policy, &workers, &info), Some(1));
+        }
+
+        // The pinned worker goes away: route normally and move the session.
+        workers[1].set_status(openai_protocol::worker::WorkerStatus::NotReady);
+        assert_eq!(reg.select_worker(&policy, &workers, &info), Some(0));
+        assert_eq!(
+            reg.sessions().resolve(&session.id).unwrap().worker_url,
+            "http://w1"
+        );
+    }
+
     #[test]
     fn test_policy_registry_basic() {
         let registry = PolicyRegistry::new(PolicyConfig::RoundRobin);
@@ -772,6 +1093,104 @@ mod tests {
         assert_eq!(registry.get_worker_counts().get("llama-3"), None);
     }
 
+    #[test]
+    fn test_model_policy_override_rebuilds_policy() {
+        let registry = PolicyRegistry::new(PolicyConfig::RoundRobin);
+        let workers: Vec<Arc<dyn Worker>> = ["http://w1", "http://w2"]
+            .into_iter()
+            .map(|url| {
+                Arc::new(
+                    BasicWorkerBuilder::new(url)
+                        .model(crate::worker::ModelCard::new("llama-70b"))
+                        .health_config(no_health_check())
+                        .build(),
+                ) as Arc<dyn Worker>
+            })
+            .collect();
+        registry.on_worker_added("llama-70b", Some("random"));
+        registry.on_worker_added("llama-70b", None);
+
+        // Assigning switches the live policy, with its tree seeded from the
+        // model's workers so routing is prefix-sticky straight away.
+        assert_eq!(
+            registry.set_model_policy("llama-70b", "CacheAware", &workers),
+            Ok("cache_aware")
+        );
+        let policy = registry.get_policy("llama-70b").unwrap();
+        assert_eq!(policy.name(), "cache_aware");
+        let info = SelectWorkerInfo {
+            request_text: Some("shared prefix request"),
+            ..Default::default()
+        };
+        let first = policy.select_worker(&workers, &info);
+        assert!(first.is_some());
+        assert_eq!(policy.select_worker(&workers, &info), first);
+
+        // Re-assigning the same policy keeps the instance and its state.
+        registry
+            .set_model_policy("llama-70b", "cache_aware", &workers)
+            .unwrap();
+        assert!(Arc::ptr_eq(
+            &policy,
+            &registry.get_policy("llama-70b").unwrap()
+        ));
+
+        // Clearing restores the worker hint.
+        assert_eq!(
+            registry.clear_model_policy("llama-70b", &workers),
+            Some("cache_aware")
+        );
+        assert_eq!(registry.get_policy("llama-70b").unwrap().name(), "random");
+        assert_eq!(registry.clear_model_policy("llama-70b", &workers), None);
+
+        assert_eq!(
+            registry.set_model_policy("llama-70b", "fastest", &workers),
+            Err(PolicyOverrideError::UnknownPolicy("fastest".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_model_policy_override_outlives_workers() {
+        let registry = PolicyRegistry::new(PolicyConfig::RoundRobin);
+
+        // Assigned before the model has workers: nothing is created yet.
+        registry
+            .set_model_policy("embeddings", "power_of_two", &[])
+            .unwrap();
+        assert!(registry.get_policy("embeddings").is_none());
+
+        // The assignment wins over the worker hint, and survives the model
+        // going away and coming back.
+        let policy = registry.on_worker_added("embeddings", Some("random"));
+        assert_eq!(policy.name(), "power_of_two");
+        registry.on_worker_removed("embeddings");
+        assert!(registry.get_policy("embeddings").is_none());
+        let policy = registry.on_worker_added("embeddings", None);
+        assert_eq!(policy.name(), "power_of_two");
+    }
+
+    #[test]
+    fn test_replace_model_policies_is_all_or_nothing() {
+        let registry = PolicyRegistry::new(PolicyConfig::RoundRobin);
+        registry.on_worker_added("a", None);
+        registry.on_worker_added("b", None);
+        registry.set_model_policy("a", "random", &[]).unwrap();
+        let no_workers = |_: &str| Vec::<Arc<dyn Worker>>::new();
+
+        let bad = BTreeMap::from([
+            ("a".to_string(), "least_load".to_string()),
+            ("b".to_string(), "fastest".to_string()),
+        ]);
+        assert!(registry.replace_model_policies(&bad, no_workers).is_err());
+        assert_eq!(registry.get_policy("a").unwrap().name(), "random");
+
+        let good = BTreeMap::from([("b".to_string(), "least_load".to_string())]);
+        registry.replace_model_policies(&good, no_workers).unwrap();
+        assert_eq!(registry.model_policy_overrides(), good);
+        assert_eq!(registry.get_policy("a").unwrap().name(), "round_robin");
+        assert_eq!(registry.get_policy("b").unwrap().name(), "least_load");
+    }
+
     #[test]
     fn test_passthrough_is_not_load_aware() {
         // Passthrough must not be polled by the WorkerMonitor: with it as the
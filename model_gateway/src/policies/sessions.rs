@@ -0,0 +1,253 @@
+//! Client sessions pinned to one worker.
+//!
+//! `POST /v1/sessions` opens a session for a model and a system prompt: a
+//! worker is selected for the prompt, the prompt is recorded for that worker
+//! in the model's cache-aware tree and pre-filled there. Requests carrying
+//! `X-SMG-Session-Id` then go to the pinned worker while it is available.
+//! When it is not, the request is routed normally and the session moves to
+//! the worker it lands on, whose cache now holds the prompt.
+//!
+//! A session is closed by `DELETE /v1/sessions/{id}` or once it has carried
+//! no request for `sessions.idle_ttl_secs`. Sessions live on the gateway
+//! that opened them and are not shared over the mesh.
+
+use std::{
+    sync::{Arc, Weak},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{config::SessionsConfig, observability::metrics::Metrics};
+
+/// Upper bound on the interval between idle sweeps.
+const MAX_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct SessionPin {
+    tenant: String,
+    model_id: String,
+    worker_url: String,
+    created_at: u64,
+    last_used: Instant,
+}
+
+impl SessionPin {
+    fn info(&self, id: &str, idle_ttl_secs: u64) -> SessionInfo {
+        SessionInfo {
+            id: id.to_string(),
+            object: "session",
+            model: self.model_id.clone(),
+            created_at: self.created_at,
+            idle_ttl_secs,
+        }
+    }
+}
+
+/// A session as returned to the client that owns it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub object: &'static str,
+    pub model: String,
+    pub created_at: u64,
+    pub idle_ttl_secs: u64,
+}
+
+/// Where a session's requests are pinned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTarget {
+    pub model_id: String,
+    pub worker_url: String,
+}
+
+/// Open sessions, keyed by session ID.
+pub struct SessionPins {
+    config: SessionsConfig,
+    sessions: DashMap<String, SessionPin>,
+}
+
+impl std::fmt::Debug for SessionPins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionPins")
+            .field("config", &self.config)
+            .field("open", &self.sessions.len())
+            .finish()
+    }
+}
+
+impl SessionPins {
+    pub fn new(config: &SessionsConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config: config.clone(),
+            sessions: DashMap::new(),
+        })
+    }
+
+    /// Spawn the loop closing idle sessions. It holds only a `Weak<Self>`
+    /// and exits when the sessions are dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        let interval = self.idle_ttl().min(MAX_SWEEP_INTERVAL);
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the sessions are dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(interval);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(sessions) = weak.upgrade() else {
+                    break;
+                };
+                sessions.expire_idle(Instant::now());
+            }
+            debug!("SessionPins sweep loop exited");
+        });
+    }
+
+    fn idle_ttl(&self) -> Duration {
+        Duration::from_secs(self.config.idle_ttl_secs.max(1))
+    }
+
+    /// Open a session for `tenant` pinned to `worker_url`. `None` when
+    /// `sessions.max_sessions` are already open.
+    pub fn open(&self, tenant: &str, model_id: &str, worker_url: &str) -> Option<SessionInfo> {
+        if self.sessions.len() >= self.config.max_sessions {
+            return None;
+        }
+        let id = format!("sess_{:032x}", rand::random::<u128>());
+        let pin = SessionPin {
+            tenant: tenant.to_string(),
+            model_id: model_id.to_string(),
+            worker_url: worker_url.to_string(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            last_used: Instant::now(),
+        };
+        let info = pin.info(&id, self.config.idle_ttl_secs);
+        self.sessions.insert(id, pin);
+        Metrics::set_sessions_active(self.sessions.len());
+        Some(info)
+    }
+
+    /// The session `id` if `tenant` owns it.
+    pub fn get(&self, tenant: &str, id: &str) -> Option<SessionInfo> {
+        self.sessions
+            .get(id)
+            .filter(|pin| pin.tenant == tenant)
+            .map(|pin| pin.info(id, self.config.idle_ttl_secs))
+    }
+
+    /// Close the session `id` if `tenant` owns it.
+    pub fn close(&self, tenant: &str, id: &str) -> bool {
+        let closed = self
+            .sessions
+            .remove_if(id, |_, pin| pin.tenant == tenant)
+            .is_some();
+        if closed {
+            Metrics::set_sessions_active(self.sessions.len());
+        }
+        closed
+    }
+
+    /// Where the session `id` is pinned; counts as activity on the session.
+    pub fn resolve(&self, id: &str) -> Option<SessionTarget> {
+        let mut pin = self.sessions.get_mut(id)?;
+        pin.last_used = Instant::now();
+        Some(SessionTarget {
+            model_id: pin.model_id.clone(),
+            worker_url: pin.worker_url.clone(),
+        })
+    }
+
+    /// Move the session `id` to another worker.
+    pub fn repin(&self, id: &str, worker_url: &str) {
+        if let Some(mut pin) = self.sessions.get_mut(id) {
+            debug!(
+                session_id = id,
+                from = %pin.worker_url,
+                to = worker_url,
+                "Session moved to another worker"
+            );
+            pin.worker_url = worker_url.to_string();
+        }
+    }
+
+    /// Number of open sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Close every session idle for longer than the TTL as of `now`.
+    fn expire_idle(&self, now: Instant) -> usize {
+        let idle_ttl = self.idle_ttl();
+        let before = self.sessions.len();
+        self.sessions
+            .retain(|_, pin| now.saturating_duration_since(pin.last_used) <= idle_ttl);
+        let expired = before.saturating_sub(self.sessions.len());
+        if expired > 0 {
+            debug!(expired, "Closed idle sessions");
+        }
+        Metrics::set_sessions_active(self.sessions.len());
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_are_scoped_to_their_tenant() {
+        let sessions = SessionPins::new(&SessionsConfig::default());
+        let info = sessions
+            .open("tenant-a", "llama", "http://w1:8000")
+            .unwrap();
+        assert!(info.id.starts_with("sess_"));
+
+        assert!(sessions.get("tenant-b", &info.id).is_none());
+        assert!(!sessions.close("tenant-b", &info.id));
+        assert_eq!(
+            sessions.resolve(&info.id),
+            Some(SessionTarget {
+                model_id: "llama".to_string(),
+                worker_url: "http://w1:8000".to_string(),
+            })
+        );
+
+        sessions.repin(&info.id, "http://w2:8000");
+        assert_eq!(
+            sessions.resolve(&info.id).unwrap().worker_url,
+            "http://w2:8000"
+        );
+
+        assert!(sessions.close("tenant-a", &info.id));
+        assert!(sessions.resolve(&info.id).is_none());
+    }
+
+    #[test]
+    fn test_session_limit_and_idle_expiry() {
+        let sessions = SessionPins::new(&SessionsConfig {
+            idle_ttl_secs: 60,
+            max_sessions: 2,
+        });
+        let first = sessions.open("t", "llama", "http://w1:8000").unwrap();
+        sessions.open("t", "llama", "http://w1:8000").unwrap();
+        assert!(sessions.open("t", "llama", "http://w1:8000").is_none());
+
+        assert_eq!(sessions.expire_idle(Instant::now()), 0);
+        let later = Instant::now() + Duration::from_secs(61);
+        assert_eq!(sessions.expire_idle(later), 2);
+        assert!(sessions.get("t", &first.id).is_none());
+        assert!(sessions.is_empty());
+    }
+}
@@ -26,6 +26,19 @@ pub struct TenantPolicySpec {
     pub requests_per_minute: u32,
     #[serde(default)]
     pub model_rules: Vec<ModelRuleSpec>,
+    /// Limits applied to each API key resolving to this tenant, inside the
+    /// tenant-wide budget. Unset leaves keys bounded by the tenant limits
+    /// alone.
+    #[serde(default)]
+    pub api_key_limits: Option<LimitSpec>,
+}
+
+/// Token/request-per-minute limits for a scope without model rules: the
+/// cluster-wide `global` ceiling and the per-API-key share of a tenant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitSpec {
+    pub tokens_per_minute: u32,
+    pub requests_per_minute: u32,
 }
 
 /// A per-model rate-limit rule layered on top of the tenant-global limits.
@@ -49,6 +62,9 @@ pub enum ModelMatcherSpec {
 /// `--tenant-rate-limit-config <path>` CLI flag added in a follow-up PR.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitYaml {
+    /// Ceiling across all tenants, checked before any tenant policy.
+    #[serde(default)]
+    pub global: Option<LimitSpec>,
     pub default_policy: TenantPolicySpec,
     #[serde(default)]
     pub tenants: Vec<TenantPolicySpec>,
@@ -98,17 +114,33 @@ fn is_valid_rule_id(s: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
 }
 
-fn validate_policy(label: &str, spec: &TenantPolicySpec) -> Result<(), RateLimitConfigError> {
-    if spec.tokens_per_minute == 0 {
+fn validate_limits(
+    label: &str,
+    tokens_per_minute: u32,
+    requests_per_minute: u32,
+) -> Result<(), RateLimitConfigError> {
+    if tokens_per_minute == 0 {
         return Err(RateLimitConfigError::ZeroTokensPerMinute {
             label: label.to_string(),
         });
     }
-    if spec.requests_per_minute == 0 {
+    if requests_per_minute == 0 {
         return Err(RateLimitConfigError::ZeroRequestsPerMinute {
             label: label.to_string(),
         });
     }
+    Ok(())
+}
+
+fn validate_policy(label: &str, spec: &TenantPolicySpec) -> Result<(), RateLimitConfigError> {
+    validate_limits(label, spec.tokens_per_minute, spec.requests_per_minute)?;
+    if let Some(limits) = &spec.api_key_limits {
+        validate_limits(
+            &format!("{label}.api_key_limits"),
+            limits.tokens_per_minute,
+            limits.requests_per_minute,
+        )?;
+    }
 
     let mut seen_rule_ids = HashSet::new();
     // Owned rather than borrowed: this only runs once at startup, so the
@@ -183,6 +215,13 @@ impl RateLimitYaml {
     /// tenant, no duplicate exact/prefix matcher within a tenant, no empty
     /// matcher value. Rejects at load time rather than at first use.
     pub fn validate(&self) -> Result<(), RateLimitConfigError> {
+        if let Some(global) = &self.global {
+            validate_limits(
+                "global",
+                global.tokens_per_minute,
+                global.requests_per_minute,
+            )?;
+        }
         if self.default_policy.tenant_key.is_some() {
             return Err(RateLimitConfigError::DefaultPolicyHasTenantKey);
         }
@@ -234,6 +273,7 @@ mod tests {
             tokens_per_minute: tpm,
             requests_per_minute: rpm,
             model_rules: Vec::new(),
+            api_key_limits: None,
         }
     }
 
@@ -249,6 +289,7 @@ mod tests {
     #[test]
     fn valid_yaml_passes() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(None, 1000, 60),
             tenants: vec![policy(Some("auth:team-red"), 5000, 300)],
         };
@@ -258,6 +299,7 @@ mod tests {
     #[test]
     fn default_policy_with_tenant_key_rejected() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(Some("auth:oops"), 1000, 60),
             tenants: vec![],
         };
@@ -270,6 +312,7 @@ mod tests {
     #[test]
     fn tenant_missing_key_rejected() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(None, 1000, 60),
             tenants: vec![policy(None, 5000, 300)],
         };
@@ -283,6 +326,7 @@ mod tests {
     fn tenant_empty_or_whitespace_key_rejected() {
         for key in ["", "   "] {
             let yaml = RateLimitYaml {
+                global: None,
                 default_policy: policy(None, 1000, 60),
                 tenants: vec![policy(Some(key), 5000, 300)],
             };
@@ -297,6 +341,7 @@ mod tests {
     fn tenant_padded_key_rejected() {
         for tenant_key in [" auth:team-red", "auth:team-red "] {
             let yaml = RateLimitYaml {
+                global: None,
                 default_policy: policy(None, 1000, 60),
                 tenants: vec![policy(Some(tenant_key), 5000, 300)],
             };
@@ -312,6 +357,7 @@ mod tests {
     #[test]
     fn duplicate_tenant_key_rejected() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(None, 1000, 60),
             tenants: vec![
                 policy(Some("auth:team-red"), 5000, 300),
@@ -329,6 +375,7 @@ mod tests {
     #[test]
     fn zero_tokens_per_minute_rejected() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(None, 0, 60),
             tenants: vec![],
         };
@@ -343,6 +390,7 @@ mod tests {
     #[test]
     fn zero_requests_per_minute_rejected() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: policy(None, 1000, 0),
             tenants: vec![],
         };
@@ -354,6 +402,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn zero_global_and_api_key_limits_rejected() {
+        let yaml = RateLimitYaml {
+            global: Some(LimitSpec {
+                tokens_per_minute: 0,
+                requests_per_minute: 100,
+            }),
+            default_policy: policy(None, 1000, 60),
+            tenants: vec![],
+        };
+        assert_eq!(
+            yaml.validate(),
+            Err(RateLimitConfigError::ZeroTokensPerMinute {
+                label: "global".to_string()
+            })
+        );
+
+        let mut tenant = policy(Some("auth:team-red"), 1000, 60);
+        tenant.api_key_limits = Some(LimitSpec {
+            tokens_per_minute: 100,
+            requests_per_minute: 0,
+        });
+        let yaml = RateLimitYaml {
+            global: None,
+            default_policy: policy(None, 1000, 60),
+            tenants: vec![tenant],
+        };
+        assert_eq!(
+            yaml.validate(),
+            Err(RateLimitConfigError::ZeroRequestsPerMinute {
+                label: "auth:team-red.api_key_limits".to_string()
+            })
+        );
+    }
+
     #[test]
     fn invalid_rule_id_rejected() {
         let mut default_policy = policy(None, 1000, 60);
@@ -366,6 +449,7 @@ mod tests {
             10,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
@@ -398,6 +482,7 @@ mod tests {
             10,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
@@ -422,6 +507,7 @@ mod tests {
             10,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
@@ -446,6 +532,7 @@ mod tests {
             10,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
@@ -471,6 +558,7 @@ mod tests {
                 10,
             ));
             let yaml = RateLimitYaml {
+                global: None,
                 default_policy,
                 tenants: vec![],
             };
@@ -505,6 +593,7 @@ mod tests {
             20,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
@@ -538,6 +627,7 @@ mod tests {
             20,
         ));
         let yaml = RateLimitYaml {
+            global: None,
             default_policy,
             tenants: vec![],
         };
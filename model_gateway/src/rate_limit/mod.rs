@@ -1,16 +1,23 @@
 //! Per-tenant LLM token/request rate limiting.
 //!
-//! This is the policy layer only: config schema, validation, and
-//! compilation into an immutable, cheap-to-look-up `CompiledPolicySet`.
-//! Nothing here does any rate-limit accounting or enforcement yet — the
-//! reserve/settle engine, in-memory backend, and CLI/startup wiring land in
-//! a follow-up change. This module is not yet reachable from any request
-//! path.
+//! The policy layer (config schema, validation, and compilation into an
+//! immutable, cheap-to-look-up `CompiledPolicySet`) plus the fixed-window
+//! evaluator that checks a request against its global → tenant → API key →
+//! model limit chain in one pass. Counters live in a `RateLimitStore`: the
+//! in-memory store for one node, or the mesh `RateLimitSyncAdapter` for
+//! cluster-wide windows. Token settlement and CLI/startup wiring land in a
+//! follow-up change; this module is not yet reachable from any request path.
 
 mod config;
 mod policy;
+mod window;
 
 pub use config::{
-    ModelMatcherSpec, ModelRuleSpec, RateLimitConfigError, RateLimitYaml, TenantPolicySpec,
+    LimitSpec, ModelMatcherSpec, ModelRuleSpec, RateLimitConfigError, RateLimitYaml,
+    TenantPolicySpec,
 };
 pub use policy::{CompiledPolicySet, ScopeLimits};
+pub use window::{
+    LevelUsage, LimitKind, MemoryRateLimitStore, RateLimitDecision, RateLimitLevel, RateLimitStore,
+    RateLimitWindow, ScopedLimit, WINDOW,
+};
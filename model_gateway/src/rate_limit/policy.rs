@@ -3,7 +3,10 @@
 
 use std::collections::HashMap;
 
-use super::config::{ModelMatcherSpec, RateLimitConfigError, RateLimitYaml, TenantPolicySpec};
+use super::{
+    config::{LimitSpec, ModelMatcherSpec, RateLimitConfigError, RateLimitYaml, TenantPolicySpec},
+    window::{RateLimitLevel, ScopedLimit},
+};
 use crate::tenant::TenantKey;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,20 +15,18 @@ pub struct ScopeLimits {
     pub requests_per_minute: u32,
 }
 
+impl From<&LimitSpec> for ScopeLimits {
+    fn from(spec: &LimitSpec) -> Self {
+        Self {
+            tokens_per_minute: spec.tokens_per_minute,
+            requests_per_minute: spec.requests_per_minute,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
-#[cfg_attr(
-    not(test),
-    expect(
-        dead_code,
-        reason = "consumed by the reserve/settle engine landing in a follow-up change; exercised by this module's own tests today"
-    )
-)]
 pub(crate) struct CompiledModelRule {
     pub rule_id: String,
-    #[expect(
-        dead_code,
-        reason = "consumed by the reserve/settle engine landing in a follow-up change; not read by this module's own tests"
-    )]
     pub limits: ScopeLimits,
 }
 
@@ -39,15 +40,9 @@ pub(crate) struct CompiledPrefixRule {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(
-    not(test),
-    expect(
-        dead_code,
-        reason = "consumed by the reserve/settle engine landing in a follow-up change; exercised by this module's own tests today"
-    )
-)]
 pub(crate) struct CompiledTenantPolicy {
     pub limits: ScopeLimits,
+    pub api_key_limits: Option<ScopeLimits>,
     /// O(1) lookup — exact matches never need to scan.
     exact_model_rules: HashMap<String, CompiledModelRule>,
     /// Sorted longest-prefix-first once at compile time, so matching is a
@@ -61,13 +56,6 @@ impl CompiledTenantPolicy {
     /// over prefix; among prefixes, the longest wins (guaranteed by
     /// `prefix_model_rules`'s compile-time sort). Rules never stack — at
     /// most one is returned.
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "consumed by the reserve/settle engine landing in a follow-up change; exercised by this module's own tests today"
-        )
-    )]
     pub(crate) fn matching_rule(&self, model_id: &str) -> Option<&CompiledModelRule> {
         if let Some(exact) = self.exact_model_rules.get(model_id) {
             return Some(exact);
@@ -111,16 +99,19 @@ impl CompiledTenantPolicy {
                 tokens_per_minute: spec.tokens_per_minute,
                 requests_per_minute: spec.requests_per_minute,
             },
+            api_key_limits: spec.api_key_limits.as_ref().map(ScopeLimits::from),
             exact_model_rules,
             prefix_model_rules,
         }
     }
 }
 
-/// Immutable, compiled rate-limit policy: a default plus per-tenant
-/// overrides, resolved in O(1) by [`Self::policy_for`].
+/// Immutable, compiled rate-limit policy: an optional global ceiling, a
+/// default plus per-tenant overrides, resolved in O(1) by
+/// [`Self::policy_for`].
 #[derive(Debug, Clone)]
 pub struct CompiledPolicySet {
+    global: Option<ScopeLimits>,
     default: CompiledTenantPolicy,
     tenants: HashMap<TenantKey, CompiledTenantPolicy>,
 }
@@ -142,19 +133,57 @@ impl CompiledPolicySet {
                     .map(|k| (TenantKey::from(k), CompiledTenantPolicy::compile(spec)))
             })
             .collect();
-        Ok(Self { default, tenants })
+        Ok(Self {
+            global: yaml.global.as_ref().map(ScopeLimits::from),
+            default,
+            tenants,
+        })
     }
 
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "consumed by the reserve/settle engine landing in a follow-up change; exercised by this module's own tests today"
-        )
-    )]
     pub(crate) fn policy_for(&self, tenant: &TenantKey) -> &CompiledTenantPolicy {
         self.tenants.get(tenant).unwrap_or(&self.default)
     }
+
+    /// Every limit a request is held to, outermost first: global, tenant,
+    /// API key, then the tenant's matching model rule. Levels without a
+    /// configured limit are left out.
+    ///
+    /// `api_key_id` must be a stable, non-secret identifier of the
+    /// caller's key (e.g. a hash of it); it only keys the counter. Each
+    /// scope is nested under its tenant, so two tenants matching the same
+    /// model rule id never share a budget.
+    pub fn limit_chain(
+        &self,
+        tenant: &TenantKey,
+        api_key_id: Option<&str>,
+        model_id: Option<&str>,
+    ) -> Vec<ScopedLimit> {
+        let policy = self.policy_for(tenant);
+        let mut chain = Vec::with_capacity(4);
+        if let Some(limits) = self.global {
+            chain.push(ScopedLimit::new(RateLimitLevel::Global, "global", limits));
+        }
+        chain.push(ScopedLimit::new(
+            RateLimitLevel::Tenant,
+            tenant.as_str(),
+            policy.limits,
+        ));
+        if let (Some(limits), Some(key)) = (policy.api_key_limits, api_key_id) {
+            chain.push(ScopedLimit::new(
+                RateLimitLevel::ApiKey,
+                format!("{tenant}/{key}"),
+                limits,
+            ));
+        }
+        if let Some(rule) = model_id.and_then(|model| policy.matching_rule(model)) {
+            chain.push(ScopedLimit::new(
+                RateLimitLevel::Model,
+                format!("{tenant}/{}", rule.rule_id),
+                rule.limits,
+            ));
+        }
+        chain
+    }
 }
 
 #[cfg(test)]
@@ -168,6 +197,7 @@ mod tests {
             tokens_per_minute: 1000,
             requests_per_minute: 60,
             model_rules: rules,
+            api_key_limits: None,
         }
     }
 
@@ -183,6 +213,7 @@ mod tests {
     #[test]
     fn unknown_tenant_falls_back_to_default() {
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: spec_with_rules(vec![]),
             tenants: vec![],
         };
@@ -197,6 +228,7 @@ mod tests {
         tenant_spec.tenant_key = Some("auth:team-red".to_string());
         tenant_spec.tokens_per_minute = 5000;
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: spec_with_rules(vec![]),
             tenants: vec![tenant_spec],
         };
@@ -222,6 +254,7 @@ mod tests {
             ),
         ]);
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: spec,
             tenants: vec![],
         };
@@ -248,6 +281,7 @@ mod tests {
             ),
         ]);
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: spec,
             tenants: vec![],
         };
@@ -266,6 +300,7 @@ mod tests {
             },
         )]);
         let yaml = RateLimitYaml {
+            global: None,
             default_policy: spec,
             tenants: vec![],
         };
@@ -0,0 +1,430 @@
+//! Fixed one-minute windows over a hierarchy of rate limits.
+//!
+//! A request is checked against every level of its limit chain (global →
+//! tenant → API key → model, see [`CompiledPolicySet::limit_chain`]) in one
+//! pass. It is admitted only if every level has room for one more request
+//! and its estimated tokens; an admitted request is then charged to every
+//! level, a rejected one to none, so a request turned away by the model
+//! rule does not eat into its tenant's budget.
+//!
+//! Counts live in a [`RateLimitStore`]: in-process for a single node, or the
+//! `rl:` mesh namespace for a cluster-wide view. The check and the charge
+//! are separate store calls, so concurrent requests can overshoot a limit
+//! by the number of requests in flight; windows are an admission bound, not
+//! an exact quota.
+//!
+//! [`CompiledPolicySet::limit_chain`]: super::CompiledPolicySet::limit_chain
+
+use std::{
+    cmp::Ordering,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+
+use super::policy::ScopeLimits;
+use crate::observability::metrics::Metrics;
+
+/// Window length; every configured limit is per minute.
+pub const WINDOW: Duration = Duration::from_secs(60);
+
+/// A level of the limit hierarchy, outermost first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RateLimitLevel {
+    Global,
+    Tenant,
+    ApiKey,
+    Model,
+}
+
+impl RateLimitLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Tenant => "tenant",
+            Self::ApiKey => "api_key",
+            Self::Model => "model",
+        }
+    }
+}
+
+/// Which budget of a level ran out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Requests,
+    Tokens,
+}
+
+impl LimitKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Requests => "requests",
+            Self::Tokens => "tokens",
+        }
+    }
+}
+
+/// One level of a request's limit chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedLimit {
+    pub level: RateLimitLevel,
+    /// Identity of the counted scope, e.g. the tenant key.
+    pub scope: String,
+    pub limits: ScopeLimits,
+}
+
+impl ScopedLimit {
+    pub fn new(level: RateLimitLevel, scope: impl Into<String>, limits: ScopeLimits) -> Self {
+        Self {
+            level,
+            scope: scope.into(),
+            limits,
+        }
+    }
+
+    /// Store counter name for `kind`. Scopes embed tenant keys and model
+    /// ids, which may contain `:` (the mesh shard separator), so they are
+    /// hashed into a fixed-width name.
+    fn counter(&self, kind: LimitKind) -> String {
+        let digest = Sha256::digest(self.scope.as_bytes());
+        let mut name = format!("{}-{}-", self.level.as_str(), kind.as_str());
+        for byte in &digest[..8] {
+            name.push_str(&format!("{byte:02x}"));
+        }
+        name
+    }
+}
+
+/// Windowed counters shared by all levels.
+pub trait RateLimitStore: Send + Sync {
+    /// Total count of `counter` in window `epoch`; 0 once the window has
+    /// moved on.
+    fn count(&self, counter: &str, epoch: u64) -> i64;
+
+    /// Add `amount` to this node's count of `counter` in window `epoch`.
+    fn add(&self, counter: &str, epoch: u64, amount: i64);
+}
+
+impl<T: RateLimitStore + ?Sized> RateLimitStore for Arc<T> {
+    fn count(&self, counter: &str, epoch: u64) -> i64 {
+        (**self).count(counter, epoch)
+    }
+
+    fn add(&self, counter: &str, epoch: u64, amount: i64) {
+        (**self).add(counter, epoch, amount);
+    }
+}
+
+/// Counters for a single node.
+#[derive(Debug, Default)]
+pub struct MemoryRateLimitStore {
+    /// Counter name → (epoch, count) of its latest window.
+    counters: DashMap<String, (u64, i64)>,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop counters from windows before `epoch`.
+    pub fn evict_before(&self, epoch: u64) {
+        self.counters.retain(|_, (e, _)| *e >= epoch);
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    fn count(&self, counter: &str, epoch: u64) -> i64 {
+        self.counters
+            .get(counter)
+            .filter(|entry| entry.0 == epoch)
+            .map_or(0, |entry| entry.1)
+    }
+
+    fn add(&self, counter: &str, epoch: u64, amount: i64) {
+        let mut entry = self
+            .counters
+            .entry(counter.to_string())
+            .or_insert((epoch, 0));
+        match entry.0.cmp(&epoch) {
+            Ordering::Less => *entry = (epoch, amount),
+            Ordering::Equal => entry.1 = entry.1.saturating_add(amount),
+            // A late charge for a window that already rolled over.
+            Ordering::Greater => {}
+        }
+    }
+}
+
+/// Usage of one level as seen by a decision, including the request being
+/// decided when it was admitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelUsage {
+    pub level: RateLimitLevel,
+    pub limits: ScopeLimits,
+    pub requests: i64,
+    pub tokens: i64,
+}
+
+impl LevelUsage {
+    pub fn remaining_requests(&self) -> i64 {
+        (i64::from(self.limits.requests_per_minute) - self.requests).max(0)
+    }
+
+    pub fn remaining_tokens(&self) -> i64 {
+        (i64::from(self.limits.tokens_per_minute) - self.tokens).max(0)
+    }
+}
+
+/// Outcome of checking a request against its whole limit chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// Every level of the chain, outermost first.
+    pub levels: Vec<LevelUsage>,
+    /// The outermost level that turned the request away, and which of its
+    /// budgets ran out.
+    pub rejected: Option<(RateLimitLevel, LimitKind)>,
+    /// Time until the current window ends.
+    pub reset_after: Duration,
+}
+
+impl RateLimitDecision {
+    pub fn allowed(&self) -> bool {
+        self.rejected.is_none()
+    }
+
+    /// Requests left before the tightest level rejects; `None` for an empty
+    /// chain.
+    pub fn remaining_requests(&self) -> Option<i64> {
+        self.levels.iter().map(LevelUsage::remaining_requests).min()
+    }
+
+    /// Tokens left before the tightest level rejects; `None` for an empty
+    /// chain.
+    pub fn remaining_tokens(&self) -> Option<i64> {
+        self.levels.iter().map(LevelUsage::remaining_tokens).min()
+    }
+}
+
+/// Evaluates limit chains against windowed counters in a [`RateLimitStore`].
+#[derive(Debug)]
+pub struct RateLimitWindow<S> {
+    store: S,
+}
+
+impl<S: RateLimitStore> RateLimitWindow<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Check one request carrying an estimated `tokens` against `chain`
+    /// and, if every level admits it, charge it to all of them.
+    pub fn evaluate(&self, chain: &[ScopedLimit], tokens: u32) -> RateLimitDecision {
+        self.evaluate_at(chain, tokens, SystemTime::now())
+    }
+
+    pub fn evaluate_at(
+        &self,
+        chain: &[ScopedLimit],
+        tokens: u32,
+        now: SystemTime,
+    ) -> RateLimitDecision {
+        let elapsed = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let epoch = elapsed.as_secs() / WINDOW.as_secs();
+        let reset_after = WINDOW - Duration::from_secs(elapsed.as_secs() % WINDOW.as_secs());
+        let tokens = i64::from(tokens);
+
+        let mut rejected = None;
+        let mut levels = Vec::with_capacity(chain.len());
+        for limit in chain {
+            let requests = self.store.count(&limit.counter(LimitKind::Requests), epoch);
+            let used_tokens = self.store.count(&limit.counter(LimitKind::Tokens), epoch);
+            if rejected.is_none() {
+                if requests + 1 > i64::from(limit.limits.requests_per_minute) {
+                    rejected = Some((limit.level, LimitKind::Requests));
+                } else if used_tokens + tokens > i64::from(limit.limits.tokens_per_minute) {
+                    rejected = Some((limit.level, LimitKind::Tokens));
+                }
+            }
+            levels.push(LevelUsage {
+                level: limit.level,
+                limits: limit.limits,
+                requests,
+                tokens: used_tokens,
+            });
+        }
+
+        match rejected {
+            Some((level, kind)) => {
+                Metrics::record_rate_limit_rejection(level.as_str(), kind.as_str())
+            }
+            None => {
+                for (limit, usage) in chain.iter().zip(&mut levels) {
+                    self.store
+                        .add(&limit.counter(LimitKind::Requests), epoch, 1);
+                    self.store
+                        .add(&limit.counter(LimitKind::Tokens), epoch, tokens);
+                    usage.requests += 1;
+                    usage.tokens += tokens;
+                }
+                Metrics::record_rate_limit_admission();
+            }
+        }
+
+        RateLimitDecision {
+            levels,
+            rejected,
+            reset_after,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rate_limit::{
+            config::{LimitSpec, RateLimitYaml, TenantPolicySpec},
+            CompiledPolicySet, ModelMatcherSpec, ModelRuleSpec,
+        },
+        tenant::TenantKey,
+    };
+
+    fn limits(tokens_per_minute: u32, requests_per_minute: u32) -> ScopeLimits {
+        ScopeLimits {
+            tokens_per_minute,
+            requests_per_minute,
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn policies() -> CompiledPolicySet {
+        CompiledPolicySet::compile(&RateLimitYaml {
+            global: Some(LimitSpec {
+                tokens_per_minute: 10_000,
+                requests_per_minute: 100,
+            }),
+            default_policy: TenantPolicySpec {
+                tenant_key: None,
+                tokens_per_minute: 1000,
+                requests_per_minute: 10,
+                model_rules: vec![ModelRuleSpec {
+                    rule_id: "gpt".to_string(),
+                    matcher: ModelMatcherSpec::Prefix {
+                        value: "gpt-".to_string(),
+                    },
+                    tokens_per_minute: 1000,
+                    requests_per_minute: 2,
+                }],
+                api_key_limits: Some(LimitSpec {
+                    tokens_per_minute: 1000,
+                    requests_per_minute: 5,
+                }),
+            },
+            tenants: vec![],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_limit_chain_is_outermost_first() {
+        let tenant = TenantKey::new("auth:team-red");
+        let chain = policies().limit_chain(&tenant, Some("k1"), Some("gpt-4"));
+        let levels: Vec<_> = chain.iter().map(|l| l.level).collect();
+        assert_eq!(
+            levels,
+            vec![
+                RateLimitLevel::Global,
+                RateLimitLevel::Tenant,
+                RateLimitLevel::ApiKey,
+                RateLimitLevel::Model,
+            ]
+        );
+
+        let chain = policies().limit_chain(&tenant, None, Some("claude-3"));
+        let levels: Vec<_> = chain.iter().map(|l| l.level).collect();
+        assert_eq!(levels, vec![RateLimitLevel::Global, RateLimitLevel::Tenant]);
+    }
+
+    #[test]
+    fn test_rejection_names_level_and_charges_nothing() {
+        let window = RateLimitWindow::new(MemoryRateLimitStore::new());
+        let tenant = TenantKey::new("auth:team-red");
+        let chain = policies().limit_chain(&tenant, Some("k1"), Some("gpt-4"));
+
+        for _ in 0..2 {
+            assert!(window.evaluate_at(&chain, 10, at(60)).allowed());
+        }
+        let decision = window.evaluate_at(&chain, 10, at(61));
+        assert_eq!(
+            decision.rejected,
+            Some((RateLimitLevel::Model, LimitKind::Requests))
+        );
+        assert_eq!(decision.remaining_requests(), Some(0));
+        assert_eq!(decision.reset_after, Duration::from_secs(59));
+        assert_eq!(decision.levels[1].requests, 2);
+
+        // Other models of the tenant still have room: the rejected request
+        // was not charged to the tenant or key.
+        let other = policies().limit_chain(&tenant, Some("k1"), Some("claude-3"));
+        let decision = window.evaluate_at(&other, 10, at(62));
+        assert!(decision.allowed());
+        assert_eq!(decision.levels[1].requests, 3);
+        assert_eq!(decision.remaining_requests(), Some(2));
+    }
+
+    #[test]
+    fn test_outermost_exhausted_level_wins() {
+        let window = RateLimitWindow::new(MemoryRateLimitStore::new());
+        let chain = vec![
+            ScopedLimit::new(RateLimitLevel::Global, "global", limits(1000, 1)),
+            ScopedLimit::new(RateLimitLevel::Tenant, "auth:a", limits(5, 1)),
+        ];
+        assert!(window.evaluate_at(&chain, 1, at(0)).allowed());
+        assert_eq!(
+            window.evaluate_at(&chain, 1, at(1)).rejected,
+            Some((RateLimitLevel::Global, LimitKind::Requests))
+        );
+
+        let chain = &chain[1..];
+        assert_eq!(
+            window.evaluate_at(chain, 10, at(60)).rejected,
+            Some((RateLimitLevel::Tenant, LimitKind::Tokens))
+        );
+    }
+
+    #[test]
+    fn test_counts_reset_each_window() {
+        let window = RateLimitWindow::new(MemoryRateLimitStore::new());
+        let chain = vec![ScopedLimit::new(
+            RateLimitLevel::Tenant,
+            "auth:a",
+            limits(1000, 1),
+        )];
+        assert!(window.evaluate_at(&chain, 1, at(119)).allowed());
+        assert!(!window.evaluate_at(&chain, 1, at(119)).allowed());
+        assert!(window.evaluate_at(&chain, 1, at(120)).allowed());
+
+        window.store().evict_before(2);
+        assert_eq!(window.store().counters.len(), 2);
+        window.store().evict_before(3);
+        assert!(window.store().counters.is_empty());
+    }
+
+    #[test]
+    fn test_counter_names_avoid_shard_separator() {
+        let limit = ScopedLimit::new(RateLimitLevel::Model, "auth:a/llama3:8b", limits(1, 1));
+        let name = limit.counter(LimitKind::Tokens);
+        assert!(name.starts_with("model-tokens-"));
+        assert!(!name.contains(':'));
+        assert_ne!(name, limit.counter(LimitKind::Requests));
+    }
+}
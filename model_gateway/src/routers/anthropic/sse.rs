@@ -11,7 +11,6 @@ use axum::{
     response::Response,
 };
 use bytes::Bytes;
-use futures::StreamExt;
 use openai_protocol::messages::{ContentBlock, MessageDeltaUsage, StopReason, ToolUseBlock};
 use serde_json::Value;
 use tokio::sync::mpsc;
@@ -19,7 +18,10 @@ use tracing::{debug, error, warn};
 
 use super::mcp::{IterationResult, McpToolCall};
 use crate::routers::{
-    common::sse::{SseDecodeError, SseDecoder, SseEncodeError, SseEncoder, SseFrame},
+    common::{
+        sse::{SseDecodeError, SseEncodeError, SseEncoder, SseFrame},
+        sse_client::{SseClient, SseClientConfig, SseClientError},
+    },
     error::internal_error,
 };
 
@@ -229,8 +231,13 @@ pub(crate) async fn consume_and_forward<F>(
 where
     F: Fn(&str) -> String,
 {
-    let mut stream = response.bytes_stream();
-    let mut decoder = SseDecoder::with_max_size(MAX_SSE_BUFFER_SIZE);
+    let mut client = SseClient::new(
+        response,
+        SseClientConfig {
+            max_buffer_size: MAX_SSE_BUFFER_SIZE,
+            ..SseClientConfig::default()
+        },
+    );
     let mut processor = EventProcessor::new(
         tx,
         enc,
@@ -239,30 +246,16 @@ where
         resolve_server_name,
     );
 
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Stream read error: {e}"))?;
-
-        decoder.push(&chunk).map_err(|e| match e {
-            SseDecodeError::BufferOverflow => format!(
+    while let Some(frame) = client.next_frame().await {
+        let frame = frame.map_err(|e| match e {
+            SseClientError::Transport(e) => format!("Stream read error: {e}"),
+            SseClientError::Decode(SseDecodeError::BufferOverflow) => format!(
                 "SSE buffer exceeded maximum size ({MAX_SSE_BUFFER_SIZE} bytes) — possible malformed upstream stream"
             ),
-            other => format!("SSE decode error: {other}"),
-        })?;
-
-        while let Some(frame) = decoder.next_frame() {
-            let frame = frame.map_err(|e| format!("Invalid UTF-8 in SSE frame: {e}"))?;
-            if let Some((event_type, data)) = resolve_event(frame) {
-                processor.process(&event_type, &data).await?;
+            SseClientError::Decode(SseDecodeError::InvalidUtf8(u)) => {
+                format!("Invalid UTF-8 in SSE frame: {u}")
             }
-        }
-        decoder.compact();
-    }
-
-    // Process any trailing data not terminated by a blank line.
-    if let Some(frame) = decoder.flush() {
-        let frame = frame.map_err(|e| match e {
-            SseDecodeError::InvalidUtf8(u) => format!("Invalid UTF-8 in final SSE data: {u}"),
-            other => format!("SSE decode error on flush: {other}"),
+            other => other.to_string(),
         })?;
         if let Some((event_type, data)) = resolve_event(frame) {
             processor.process(&event_type, &data).await?;
@@ -692,6 +685,7 @@ fn resolve_event(frame: SseFrame<'_>) -> Option<(Cow<'_, str>, Cow<'_, str>)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::routers::common::sse::SseDecoder;
 
     /// Decode `bytes` through the shared `SseDecoder` and resolve each frame
     /// to `(event_type, data)` the way `consume_and_forward` does.
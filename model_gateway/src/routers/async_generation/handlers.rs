@@ -0,0 +1,497 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Extension, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::{stream, StreamExt};
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, validated::Normalizable,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use smg_data_connector::GenerationJob;
+use validator::Validate;
+
+use super::queue::{AsyncGenerationQueue, SubmitError, CHAT_COMPLETIONS, COMPLETIONS};
+use crate::{
+    middleware::{RouteRequestMeta, TenantKey},
+    routers::error,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitGenerationRequest {
+    /// Serving endpoint the request is for; defaults to chat completions.
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    pub request: Value,
+}
+
+fn default_endpoint() -> String {
+    CHAT_COMPLETIONS.to_string()
+}
+
+/// Validate as the synchronous endpoint would, returning the normalized
+/// request and its model.
+fn parse_request<T>(request: Value) -> Result<(Value, String), Response>
+where
+    T: DeserializeOwned + Serialize + Validate + Normalizable,
+{
+    let mut parsed: T = serde_json::from_value(request)
+        .map_err(|e| error::bad_request("invalid_request", e.to_string()))?;
+    parsed.normalize();
+    parsed
+        .validate()
+        .map_err(|e| error::bad_request("invalid_request", e.to_string()))?;
+    let value = serde_json::to_value(&parsed)
+        .map_err(|e| error::internal_error("serialization_error", e.to_string()))?;
+    let model = value
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Ok((value, model))
+}
+
+fn job_to_json(job: &GenerationJob) -> Value {
+    json!({
+        "id": job.id,
+        "object": "generation.job",
+        "status": job.status,
+        "endpoint": job.endpoint,
+        "model": job.model,
+        "created_at": job.created_at.timestamp(),
+        "started_at": job.started_at.map(|t| t.timestamp()),
+        "completed_at": job.completed_at.map(|t| t.timestamp()),
+        "result": job.result,
+        "error": job.error,
+    })
+}
+
+/// Jobs of other tenants are reported as not found.
+async fn load_job(
+    queue: &AsyncGenerationQueue,
+    tenant_key: &TenantKey,
+    job_id: &str,
+) -> Result<GenerationJob, Response> {
+    match queue.get(job_id).await {
+        Ok(Some(job)) if job.tenant_key == tenant_key.as_str() => Ok(job),
+        Ok(_) => Err(error::not_found(
+            "job_not_found",
+            format!("No generation job found with id '{job_id}'"),
+        )),
+        Err(e) => Err(error::internal_error(
+            "storage_error",
+            format!("Failed to get generation job: {e}"),
+        )),
+    }
+}
+
+/// `POST /v1/generations/async`: validate and enqueue, returning `202` with
+/// the queued job. An `x-smg-webhook-url` header is notified when the job
+/// finishes, as for synchronous requests.
+pub async fn submit_generation(
+    State(queue): State<Arc<AsyncGenerationQueue>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    headers: HeaderMap,
+    Json(body): Json<SubmitGenerationRequest>,
+) -> Response {
+    let parsed = match body.endpoint.as_str() {
+        CHAT_COMPLETIONS => parse_request::<ChatCompletionRequest>(body.request),
+        COMPLETIONS => parse_request::<CompletionRequest>(body.request),
+        other => Err(error::bad_request(
+            "unsupported_endpoint",
+            format!(
+                "Async generation supports '{CHAT_COMPLETIONS}' and '{COMPLETIONS}', got '{other}'"
+            ),
+        )),
+    };
+    let (request, model) = match parsed {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    if request.get("stream").and_then(Value::as_bool) == Some(true) {
+        return error::bad_request(
+            "stream_not_supported",
+            "Async generation jobs cannot stream; poll the job or subscribe to its events",
+        );
+    }
+
+    let webhook_url = match queue.webhook_url(&headers).await {
+        Ok(url) => url,
+        Err(response) => return response,
+    };
+
+    match queue
+        .submit(
+            meta.tenant_key(),
+            &body.endpoint,
+            model,
+            request,
+            webhook_url,
+        )
+        .await
+    {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job_to_json(&job))).into_response(),
+        Err(e @ SubmitError::TenantQuotaExceeded(_)) => error::create_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "tenant_quota_exceeded",
+            e.to_string(),
+        ),
+        Err(e @ SubmitError::QueueFull) => error::service_unavailable("queue_full", e.to_string()),
+        Err(e @ SubmitError::Storage(_)) => error::internal_error("storage_error", e.to_string()),
+    }
+}
+
+/// `GET /v1/generations/async/{job_id}`
+pub async fn get_generation(
+    State(queue): State<Arc<AsyncGenerationQueue>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(job_id): Path<String>,
+) -> Response {
+    match load_job(&queue, meta.tenant_key(), &job_id).await {
+        Ok(job) => Json(job_to_json(&job)).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// `POST /v1/generations/async/{job_id}/cancel`: cancel a queued or running
+/// job. Cancelling a finished job returns it unchanged.
+pub async fn cancel_generation(
+    State(queue): State<Arc<AsyncGenerationQueue>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(job_id): Path<String>,
+) -> Response {
+    let job = match load_job(&queue, meta.tenant_key(), &job_id).await {
+        Ok(job) => job,
+        Err(response) => return response,
+    };
+    if !job.status.is_terminal() {
+        queue.cancel(&job).await;
+    }
+    get_generation(State(queue), Extension(meta), Path(job_id)).await
+}
+
+fn sse_event(event: &str, data: &Value) -> Result<Bytes, Infallible> {
+    Ok(Bytes::from(format!("event: {event}\ndata: {data}\n\n")))
+}
+
+/// `GET /v1/generations/async/{job_id}/events`: a `status` event on every
+/// status change, then a `done` event carrying the finished job.
+pub async fn generation_events(
+    State(queue): State<Arc<AsyncGenerationQueue>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(job_id): Path<String>,
+) -> Response {
+    let job = match load_job(&queue, meta.tenant_key(), &job_id).await {
+        Ok(job) => job,
+        Err(response) => return response,
+    };
+    // Replay the live status once in case it moved on since the job was read.
+    let receiver = queue.watch(&job_id).map(|mut rx| {
+        rx.mark_changed();
+        rx
+    });
+
+    // Each step yields one event: the current status first, then every
+    // change until the job finishes (or its watcher goes away), then `done`.
+    let first = sse_event("status", &json!({"id": job_id, "status": job.status}));
+    let events = stream::once(async move { first }).chain(stream::unfold(
+        Some((queue, job_id, receiver)),
+        |state| async move {
+            let (queue, job_id, receiver) = state?;
+            if let Some(mut rx) = receiver {
+                if rx.changed().await.is_ok() {
+                    let status = *rx.borrow_and_update();
+                    let rx = (!status.is_terminal()).then_some(rx);
+                    let event = sse_event("status", &json!({"id": job_id, "status": status}));
+                    return Some((event, Some((queue, job_id, rx))));
+                }
+            }
+            let done = match queue.get(&job_id).await {
+                Ok(Some(job)) => job_to_json(&job),
+                _ => json!({"id": job_id}),
+            };
+            Some((sse_event("done", &done), None))
+        },
+    ));
+
+    let mut response = Response::new(Body::from_stream(events));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use axum::{body::to_bytes, http::HeaderMap, routing::post, Router};
+    use smg_data_connector::{
+        GenerationJobStatus, GenerationJobStorage, MemoryGenerationJobStorage,
+    };
+    use tokio::sync::Notify;
+
+    use super::*;
+    use crate::{
+        config::{AsyncGenerationConfig, WebhookConfig},
+        middleware::{TenantRequestMeta, WebhookDispatcher},
+        routers::RouterTrait,
+    };
+
+    /// Answers chat completions once released, echoing the model.
+    #[derive(Debug, Default)]
+    struct GatedRouter {
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl RouterTrait for GatedRouter {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        async fn route_chat(
+            &self,
+            _headers: Option<&HeaderMap>,
+            _tenant_meta: &TenantRequestMeta,
+            body: &ChatCompletionRequest,
+            _model_id: &str,
+        ) -> Response {
+            self.release.notified().await;
+            Json(json!({"object": "chat.completion", "model": body.model})).into_response()
+        }
+
+        fn router_type(&self) -> &'static str {
+            "gated"
+        }
+    }
+
+    fn queue(router: Arc<GatedRouter>, max_jobs_per_tenant: usize) -> Arc<AsyncGenerationQueue> {
+        let storage = Arc::new(MemoryGenerationJobStorage::new(
+            AsyncGenerationConfig::default().max_retained_jobs,
+        ));
+        queue_on(router, max_jobs_per_tenant, storage)
+    }
+
+    fn queue_on(
+        router: Arc<GatedRouter>,
+        max_jobs_per_tenant: usize,
+        storage: Arc<dyn GenerationJobStorage>,
+    ) -> Arc<AsyncGenerationQueue> {
+        let config = AsyncGenerationConfig {
+            enabled: true,
+            max_jobs_per_tenant,
+            ..Default::default()
+        };
+        AsyncGenerationQueue::start(&config, router, storage, None)
+    }
+
+    fn meta(tenant: &str) -> RouteRequestMeta {
+        RouteRequestMeta::new(TenantKey::from(tenant))
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn chat_submission() -> SubmitGenerationRequest {
+        serde_json::from_value(json!({
+            "request": {
+                "model": "test-model",
+                "messages": [{"role": "user", "content": "hi"}]
+            }
+        }))
+        .unwrap()
+    }
+
+    async fn submit(queue: &Arc<AsyncGenerationQueue>, tenant: &str) -> Response {
+        submit_generation(
+            State(queue.clone()),
+            Extension(meta(tenant)),
+            HeaderMap::new(),
+            Json(chat_submission()),
+        )
+        .await
+    }
+
+    async fn wait_for(queue: &AsyncGenerationQueue, job_id: &str, status: GenerationJobStatus) {
+        for _ in 0..100 {
+            if queue.get(job_id).await.unwrap().unwrap().status == status {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {job_id} never reached {status:?}");
+    }
+
+    #[tokio::test]
+    async fn submitted_job_runs_to_completion() {
+        let router = Arc::new(GatedRouter::default());
+        let queue = queue(router.clone(), 10);
+
+        let response = submit(&queue, "tenant-a").await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let job = body_json(response).await;
+        assert_eq!(job["status"], "queued");
+        let job_id = job["id"].as_str().unwrap().to_string();
+
+        wait_for(&queue, &job_id, GenerationJobStatus::Running).await;
+        router.release.notify_one();
+        wait_for(&queue, &job_id, GenerationJobStatus::Succeeded).await;
+
+        let job = body_json(
+            get_generation(
+                State(queue.clone()),
+                Extension(meta("tenant-a")),
+                Path(job_id.clone()),
+            )
+            .await,
+        )
+        .await;
+        assert_eq!(job["result"]["model"], "test-model");
+
+        let other = get_generation(State(queue), Extension(meta("tenant-b")), Path(job_id)).await;
+        assert_eq!(other.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unfinished_job_resumes_after_restart() {
+        let storage: Arc<dyn GenerationJobStorage> = Arc::new(MemoryGenerationJobStorage::new(
+            AsyncGenerationConfig::default().max_retained_jobs,
+        ));
+
+        // The first process picks the job up and dies before it finishes:
+        // its router is never released.
+        let stalled = queue_on(Arc::new(GatedRouter::default()), 10, storage.clone());
+        let job = body_json(submit(&stalled, "tenant-a").await).await;
+        let job_id = job["id"].as_str().unwrap().to_string();
+        wait_for(&stalled, &job_id, GenerationJobStatus::Running).await;
+
+        let router = Arc::new(GatedRouter::default());
+        let restarted = queue_on(router.clone(), 10, storage);
+        router.release.notify_one();
+        wait_for(&restarted, &job_id, GenerationJobStatus::Succeeded).await;
+
+        let job = restarted.get(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.result.unwrap()["model"], "test-model");
+    }
+
+    #[tokio::test]
+    async fn cancel_and_tenant_quota() {
+        let router = Arc::new(GatedRouter::default());
+        let queue = queue(router, 1);
+
+        let job = body_json(submit(&queue, "tenant-a").await).await;
+        let job_id = job["id"].as_str().unwrap().to_string();
+        assert_eq!(
+            submit(&queue, "tenant-a").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        let cancelled = body_json(
+            cancel_generation(
+                State(queue.clone()),
+                Extension(meta("tenant-a")),
+                Path(job_id),
+            )
+            .await,
+        )
+        .await;
+        assert_eq!(cancelled["status"], "cancelled");
+        assert_eq!(
+            submit(&queue, "tenant-a").await.status(),
+            StatusCode::ACCEPTED
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_streaming_and_unknown_endpoints() {
+        let queue = queue(Arc::new(GatedRouter::default()), 10);
+        let mut streaming = chat_submission();
+        streaming.request["stream"] = json!(true);
+        let response = submit_generation(
+            State(queue.clone()),
+            Extension(meta("t")),
+            HeaderMap::new(),
+            Json(streaming),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let mut unknown = chat_submission();
+        unknown.endpoint = "/v1/embeddings".to_string();
+        let response = submit_generation(
+            State(queue),
+            Extension(meta("t")),
+            HeaderMap::new(),
+            Json(unknown),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn finished_job_is_delivered_to_tenant_webhook() {
+        let (sender, mut received) = tokio::sync::mpsc::channel::<Value>(1);
+        let hook = Router::new().route(
+            "/hook",
+            post(move |Json(payload): Json<Value>| async move {
+                let _ = sender.send(payload).await;
+                StatusCode::OK
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "test webhook receiver lives for the duration of the test"
+        )]
+        tokio::spawn(async move { axum::serve(listener, hook).await });
+
+        let mut config = WebhookConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        config
+            .tenant_urls
+            .insert("tenant-a".to_string(), format!("http://{addr}/hook"));
+        let webhooks = WebhookDispatcher::new(&config, reqwest::Client::new());
+
+        let router = Arc::new(GatedRouter::default());
+        let queue = AsyncGenerationQueue::start(
+            &AsyncGenerationConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            router.clone(),
+            Arc::new(MemoryGenerationJobStorage::new(
+                AsyncGenerationConfig::default().max_retained_jobs,
+            )),
+            webhooks,
+        );
+
+        let job = body_json(submit(&queue, "tenant-a").await).await;
+        let job_id = job["id"].as_str().unwrap().to_string();
+        wait_for(&queue, &job_id, GenerationJobStatus::Running).await;
+        router.release.notify_one();
+
+        let payload = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payload["type"], "generation.job.completed");
+        assert_eq!(payload["job_id"], job_id);
+        assert_eq!(payload["status"], "succeeded");
+        assert_eq!(payload["response"]["model"], "test-model");
+    }
+}
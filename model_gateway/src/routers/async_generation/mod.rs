@@ -0,0 +1,14 @@
+//! Queue-backed asynchronous generation API.
+//!
+//! `POST /v1/generations/async` validates a chat or text completion request,
+//! records it as a job and returns the job id immediately. A fixed-size
+//! worker pool drains the bounded queue through the router; clients poll the
+//! job, follow its status events over SSE, or cancel it. Job records live in
+//! a [`GenerationJobStorage`](smg_data_connector::GenerationJobStorage), and
+//! unfinished jobs found there at startup are queued again.
+
+mod handlers;
+mod queue;
+
+pub use handlers::*;
+pub use queue::{AsyncGenerationQueue, SubmitError};
@@ -0,0 +1,348 @@
+use std::sync::Arc;
+
+use axum::{body::to_bytes, http::HeaderMap, response::Response};
+use chrono::Utc;
+use dashmap::DashMap;
+use openai_protocol::{chat::ChatCompletionRequest, completion::CompletionRequest};
+use serde_json::{json, Value};
+use smg_data_connector::{GenerationJob, GenerationJobStatus, GenerationJobStorage};
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    watch, Semaphore,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::AsyncGenerationConfig,
+    middleware::{RouteRequestMeta, TenantKey, WebhookDispatcher},
+    routers::RouterTrait,
+};
+
+pub(super) const CHAT_COMPLETIONS: &str = "/v1/chat/completions";
+pub(super) const COMPLETIONS: &str = "/v1/completions";
+
+/// A single non-streaming response; larger results fail the job.
+const RESULT_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubmitError {
+    #[error("tenant already has {0} queued or running jobs")]
+    TenantQuotaExceeded(usize),
+    #[error("the job queue is full")]
+    QueueFull,
+    #[error("failed to record job: {0}")]
+    Storage(String),
+}
+
+/// In-process state of a queued or running job. Whoever removes it from
+/// [`AsyncGenerationQueue::live`] first (the worker on completion, or a
+/// cancel) writes the job's final state.
+struct LiveJob {
+    tenant_key: TenantKey,
+    cancel: CancellationToken,
+    status: watch::Sender<GenerationJobStatus>,
+}
+
+pub struct AsyncGenerationQueue {
+    storage: Arc<dyn GenerationJobStorage>,
+    router: Arc<dyn RouterTrait>,
+    webhooks: Option<WebhookDispatcher>,
+    max_jobs_per_tenant: usize,
+    sender: mpsc::Sender<String>,
+    live: DashMap<String, LiveJob>,
+    tenant_jobs: DashMap<TenantKey, usize>,
+}
+
+impl AsyncGenerationQueue {
+    /// Start the dispatcher and re-queue unfinished jobs found in `storage`.
+    /// Finished jobs are announced through `webhooks` when it is set.
+    pub fn start(
+        config: &AsyncGenerationConfig,
+        router: Arc<dyn RouterTrait>,
+        storage: Arc<dyn GenerationJobStorage>,
+        webhooks: Option<WebhookDispatcher>,
+    ) -> Arc<Self> {
+        let (sender, receiver) = mpsc::channel(config.max_queued.max(1));
+        let queue = Arc::new(Self {
+            storage,
+            router,
+            webhooks,
+            max_jobs_per_tenant: config.max_jobs_per_tenant,
+            sender,
+            live: DashMap::new(),
+            tenant_jobs: DashMap::new(),
+        });
+
+        let permits = Arc::new(Semaphore::new(config.concurrency.max(1)));
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "dispatcher runs for the lifetime of the server and exits when the queue is dropped"
+        )]
+        tokio::spawn(Self::dispatch(Arc::downgrade(&queue), receiver, permits));
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "resuming may wait on a full queue; it must not block startup"
+        )]
+        tokio::spawn(Self::resume(Arc::downgrade(&queue)));
+        queue
+    }
+
+    async fn dispatch(
+        queue: std::sync::Weak<Self>,
+        mut receiver: mpsc::Receiver<String>,
+        permits: Arc<Semaphore>,
+    ) {
+        while let Some(job_id) = receiver.recv().await {
+            let Ok(permit) = permits.clone().acquire_owned().await else {
+                return;
+            };
+            let Some(queue) = queue.upgrade() else {
+                return;
+            };
+            #[expect(
+                clippy::disallowed_methods,
+                reason = "job execution is bounded by the worker-pool semaphore permit it holds"
+            )]
+            tokio::spawn(async move {
+                queue.run(&job_id).await;
+                drop(permit);
+            });
+        }
+    }
+
+    async fn resume(queue: std::sync::Weak<Self>) {
+        let Some(storage) = queue.upgrade().map(|q| q.storage.clone()) else {
+            return;
+        };
+        let jobs = match storage.list_unfinished_jobs().await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                warn!(error = %e, "Failed to load unfinished generation jobs");
+                return;
+            }
+        };
+        for mut job in jobs {
+            let Some(queue) = queue.upgrade() else {
+                return;
+            };
+            job.status = GenerationJobStatus::Queued;
+            job.started_at = None;
+            let tenant_key = TenantKey::from(job.tenant_key.as_str());
+            *queue.tenant_jobs.entry(tenant_key.clone()).or_default() += 1;
+            queue.register(&job.id, tenant_key);
+            if let Err(e) = queue.storage.put_job(job.clone()).await {
+                warn!(job_id = %job.id, error = %e, "Failed to re-queue generation job");
+            }
+            let sender = queue.sender.clone();
+            drop(queue);
+            if sender.send(job.id).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn register(&self, job_id: &str, tenant_key: TenantKey) {
+        let (status, _) = watch::channel(GenerationJobStatus::Queued);
+        self.live.insert(
+            job_id.to_string(),
+            LiveJob {
+                tenant_key,
+                cancel: CancellationToken::new(),
+                status,
+            },
+        );
+    }
+
+    /// Take ownership of a job's final state; `None` if already finalized.
+    fn finalize(&self, job_id: &str) -> Option<LiveJob> {
+        let (_, live) = self.live.remove(job_id)?;
+        if let Some(mut count) = self.tenant_jobs.get_mut(&live.tenant_key) {
+            *count = count.saturating_sub(1);
+        }
+        Some(live)
+    }
+
+    /// The request's own webhook URL, validated; `None` when it has none or
+    /// webhooks are disabled.
+    pub async fn webhook_url(&self, headers: &HeaderMap) -> Result<Option<String>, Response> {
+        match &self.webhooks {
+            Some(webhooks) => webhooks.job_request_url(headers).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Record and enqueue a validated request.
+    pub async fn submit(
+        &self,
+        tenant_key: &TenantKey,
+        endpoint: &str,
+        model: String,
+        request: Value,
+        webhook_url: Option<String>,
+    ) -> Result<GenerationJob, SubmitError> {
+        {
+            let mut count = self.tenant_jobs.entry(tenant_key.clone()).or_default();
+            if *count >= self.max_jobs_per_tenant {
+                return Err(SubmitError::TenantQuotaExceeded(self.max_jobs_per_tenant));
+            }
+            *count += 1;
+        }
+
+        let job = GenerationJob {
+            id: format!("gen_{}", Uuid::now_v7().simple()),
+            tenant_key: tenant_key.as_str().to_string(),
+            endpoint: endpoint.to_string(),
+            model,
+            request,
+            status: GenerationJobStatus::Queued,
+            created_at: Utc::now(),
+            started_at: None,
+            completed_at: None,
+            result: None,
+            error: None,
+            webhook_url,
+        };
+        self.register(&job.id, tenant_key.clone());
+        if let Err(e) = self.storage.put_job(job.clone()).await {
+            self.finalize(&job.id);
+            return Err(SubmitError::Storage(e.to_string()));
+        }
+
+        if let Err(TrySendError::Full(_) | TrySendError::Closed(_)) =
+            self.sender.try_send(job.id.clone())
+        {
+            self.finalize(&job.id);
+            let mut rejected = job;
+            rejected.status = GenerationJobStatus::Failed;
+            rejected.completed_at = Some(Utc::now());
+            rejected.error = Some(json!({"message": "queue full"}));
+            if let Err(e) = self.storage.put_job(rejected).await {
+                warn!(error = %e, "Failed to record rejected generation job");
+            }
+            return Err(SubmitError::QueueFull);
+        }
+        debug!(job_id = %job.id, endpoint, "Generation job queued");
+        Ok(job)
+    }
+
+    /// Cancel a queued or running job. Returns `false` if it had already
+    /// finished.
+    pub async fn cancel(&self, job: &GenerationJob) -> bool {
+        let Some(live) = self.finalize(&job.id) else {
+            return false;
+        };
+        live.cancel.cancel();
+        let mut cancelled = job.clone();
+        cancelled.status = GenerationJobStatus::Cancelled;
+        cancelled.completed_at = Some(Utc::now());
+        if let Err(e) = self.storage.put_job(cancelled).await {
+            warn!(job_id = %job.id, error = %e, "Failed to record cancelled generation job");
+        }
+        let _ = live.status.send(GenerationJobStatus::Cancelled);
+        true
+    }
+
+    pub async fn get(&self, job_id: &str) -> Result<Option<GenerationJob>, String> {
+        self.storage
+            .get_job(job_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Status updates of a queued or running job; `None` once it finished.
+    pub fn watch(&self, job_id: &str) -> Option<watch::Receiver<GenerationJobStatus>> {
+        self.live.get(job_id).map(|live| live.status.subscribe())
+    }
+
+    async fn run(&self, job_id: &str) {
+        let Some(cancel) = self.live.get(job_id).map(|live| live.cancel.clone()) else {
+            return;
+        };
+        let mut job = match self.storage.get_job(job_id).await {
+            Ok(Some(job)) => job,
+            Ok(None) | Err(_) => {
+                warn!(job_id, "Generation job record missing; dropping job");
+                self.finalize(job_id);
+                return;
+            }
+        };
+        if cancel.is_cancelled() {
+            return;
+        }
+
+        job.status = GenerationJobStatus::Running;
+        job.started_at = Some(Utc::now());
+        if let Err(e) = self.storage.put_job(job.clone()).await {
+            warn!(job_id, error = %e, "Failed to record running generation job");
+        }
+        if let Some(live) = self.live.get(job_id) {
+            let _ = live.status.send(GenerationJobStatus::Running);
+        }
+
+        let outcome = tokio::select! {
+            outcome = self.execute(&job) => outcome,
+            () = cancel.cancelled() => return,
+        };
+        let Some(live) = self.finalize(job_id) else {
+            return;
+        };
+        job.completed_at = Some(Utc::now());
+        match outcome {
+            Ok(result) => {
+                job.status = GenerationJobStatus::Succeeded;
+                job.result = Some(result);
+            }
+            Err(error) => {
+                job.status = GenerationJobStatus::Failed;
+                job.error = Some(error);
+            }
+        }
+        let status = job.status;
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.deliver_job(&job);
+        }
+        if let Err(e) = self.storage.put_job(job).await {
+            warn!(job_id, error = %e, "Failed to record finished generation job");
+        }
+        let _ = live.status.send(status);
+        debug!(job_id, ?status, "Generation job finished");
+    }
+
+    async fn execute(&self, job: &GenerationJob) -> Result<Value, Value> {
+        let meta = RouteRequestMeta::new(TenantKey::from(job.tenant_key.as_str()));
+        let response = match job.endpoint.as_str() {
+            CHAT_COMPLETIONS => {
+                let request: ChatCompletionRequest = serde_json::from_value(job.request.clone())
+                    .map_err(|e| json!({"message": format!("invalid stored request: {e}")}))?;
+                self.router
+                    .route_chat(None, &meta, &request, &request.model)
+                    .await
+            }
+            COMPLETIONS => {
+                let request: CompletionRequest = serde_json::from_value(job.request.clone())
+                    .map_err(|e| json!({"message": format!("invalid stored request: {e}")}))?;
+                self.router
+                    .route_completion(None, &meta, &request, &request.model)
+                    .await
+            }
+            other => return Err(json!({"message": format!("unsupported endpoint '{other}'")})),
+        };
+        response_outcome(response).await
+    }
+}
+
+async fn response_outcome(response: Response) -> Result<Value, Value> {
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), RESULT_BODY_LIMIT)
+        .await
+        .map_err(|e| json!({"message": format!("failed to read response: {e}")}))?;
+    let body = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(json!({"status": status.as_u16(), "body": body}))
+    }
+}
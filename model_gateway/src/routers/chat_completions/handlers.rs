@@ -0,0 +1,443 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use openai_protocol::chat::ChatCompletionRequest;
+use serde_json::{json, Value};
+use smg_data_connector::{
+    ChatCompletionFilter, ChatCompletionStorage, SortOrder, StoredChatCompletion,
+};
+use tracing::{debug, info, warn};
+
+use crate::{routers::error, tenant::TenantKey};
+
+/// A single non-streaming chat completion; anything larger is not stored.
+const RESPONSE_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+const DEFAULT_LIST_LIMIT: usize = 20;
+const MAX_LIST_LIMIT: usize = 100;
+
+/// Check a `store: true` request before it is routed.
+///
+/// Streamed completions are not assembled for storage, so `store: true` with
+/// `stream: true` is rejected rather than silently dropped. Without a
+/// configured store the flag is ignored with a warning.
+pub fn check_store_request(
+    storage: Option<&Arc<dyn ChatCompletionStorage>>,
+    request: &ChatCompletionRequest,
+) -> Result<(), Response> {
+    if request.store != Some(true) {
+        return Ok(());
+    }
+    match storage {
+        Some(_) if request.stream => Err(error::bad_request(
+            "store_not_supported_for_stream",
+            "store: true is not supported for streaming chat completions",
+        )),
+        Some(_) => Ok(()),
+        None => {
+            warn!(
+                model = %request.model,
+                "store: true ignored; the chat completion store is not enabled"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Store the completion when the request set `store: true`.
+///
+/// Only successful non-streaming responses are stored. The response body is
+/// buffered and returned unchanged; a storage failure is logged and does not
+/// fail the request.
+pub async fn store_completion(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    request: &ChatCompletionRequest,
+    tenant_key: &TenantKey,
+    response: Response,
+) -> Response {
+    if request.store != Some(true) || request.stream || !response.status().is_success() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, RESPONSE_BODY_LIMIT).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_gateway(
+                "upstream_body_error",
+                format!("Failed to read chat completion response: {e}"),
+            )
+        }
+    };
+
+    match completion_record(request, tenant_key, &bytes) {
+        Some(completion) => {
+            let id = completion.id.clone();
+            match storage.store_completion(completion).await {
+                Ok(()) => debug!(completion_id = %id, "Stored chat completion"),
+                Err(e) => warn!(completion_id = %id, error = %e, "Failed to store chat completion"),
+            }
+        }
+        None => warn!("Chat completion response has no id; not stored"),
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+fn completion_record(
+    request: &ChatCompletionRequest,
+    tenant_key: &TenantKey,
+    body: &[u8],
+) -> Option<StoredChatCompletion> {
+    let response: Value = serde_json::from_slice(body).ok()?;
+    let id = response.get("id")?.as_str()?.to_string();
+    let model = response
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or(&request.model)
+        .to_string();
+    let created_at = response
+        .get("created")
+        .and_then(Value::as_i64)
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .unwrap_or_else(Utc::now);
+
+    Some(StoredChatCompletion {
+        id,
+        created_at,
+        model,
+        metadata: request.metadata.clone().unwrap_or_default(),
+        request: serde_json::to_value(request).unwrap_or(Value::Null),
+        response,
+        tenant_key: Some(tenant_key.as_str().to_string()),
+    })
+}
+
+/// Completions stored by another tenant are reported as not found.
+fn visible_to(completion: &StoredChatCompletion, tenant_key: &TenantKey) -> bool {
+    completion
+        .tenant_key
+        .as_deref()
+        .is_none_or(|owner| owner == tenant_key.as_str())
+}
+
+async fn load_completion(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    tenant_key: &TenantKey,
+    completion_id: &str,
+) -> Result<StoredChatCompletion, Response> {
+    match storage.get_completion(completion_id).await {
+        Ok(Some(completion)) if visible_to(&completion, tenant_key) => Ok(completion),
+        Ok(_) => Err(error::not_found(
+            "not_found",
+            format!("No chat completion found with id '{completion_id}'"),
+        )),
+        Err(e) => Err(error::internal_error(
+            "storage_error",
+            format!("Failed to get chat completion: {e}"),
+        )),
+    }
+}
+
+/// The stored response, with the request metadata attached as OpenAI does.
+fn completion_to_json(completion: StoredChatCompletion) -> Value {
+    let mut response = completion.response;
+    if let Some(obj) = response.as_object_mut() {
+        obj.insert("metadata".to_string(), json!(completion.metadata));
+    }
+    response
+}
+
+pub async fn get_completion(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    tenant_key: &TenantKey,
+    completion_id: &str,
+) -> Response {
+    match load_completion(storage, tenant_key, completion_id).await {
+        Ok(completion) => (StatusCode::OK, Json(completion_to_json(completion))).into_response(),
+        Err(response) => response,
+    }
+}
+
+pub async fn delete_completion(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    tenant_key: &TenantKey,
+    completion_id: &str,
+) -> Response {
+    if let Err(response) = load_completion(storage, tenant_key, completion_id).await {
+        return response;
+    }
+    match storage.delete_completion(completion_id).await {
+        Ok(_) => {
+            info!(completion_id = %completion_id, "Deleted chat completion");
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "id": completion_id,
+                    "object": "chat.completion.deleted",
+                    "deleted": true,
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => error::internal_error(
+            "storage_error",
+            format!("Failed to delete chat completion: {e}"),
+        ),
+    }
+}
+
+struct ListQuery {
+    limit: usize,
+    order: SortOrder,
+    after: Option<String>,
+}
+
+/// Parse `limit`, `order` (default `asc`) and `after` from the query string.
+fn parse_list_query(query: &HashMap<String, String>) -> Result<ListQuery, Response> {
+    let limit = match query.get("limit") {
+        Some(raw) => match raw.parse::<usize>() {
+            Ok(limit) if (1..=MAX_LIST_LIMIT).contains(&limit) => limit,
+            _ => {
+                return Err(error::bad_request(
+                    "invalid_limit",
+                    format!("limit must be an integer between 1 and {MAX_LIST_LIMIT}"),
+                ))
+            }
+        },
+        None => DEFAULT_LIST_LIMIT,
+    };
+    let order = match query.get("order").map(String::as_str) {
+        None | Some("asc") => SortOrder::Asc,
+        Some("desc") => SortOrder::Desc,
+        Some(other) => {
+            return Err(error::bad_request(
+                "invalid_order",
+                format!("order must be 'asc' or 'desc', got '{other}'"),
+            ))
+        }
+    };
+    Ok(ListQuery {
+        limit,
+        order,
+        after: query.get("after").cloned(),
+    })
+}
+
+fn list_page(data: Vec<Value>, has_more: bool) -> Response {
+    let first_id = data.first().and_then(|v| v.get("id")).cloned();
+    let last_id = data.last().and_then(|v| v.get("id")).cloned();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "object": "list",
+            "data": data,
+            "has_more": has_more,
+            "first_id": first_id,
+            "last_id": last_id,
+        })),
+    )
+        .into_response()
+}
+
+/// List stored completions. Supports `model`, `metadata[key]=value`
+/// filters and `after`/`limit`/`order` pagination.
+pub async fn list_completions(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    tenant_key: &TenantKey,
+    query: &HashMap<String, String>,
+) -> Response {
+    let list = match parse_list_query(query) {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+    let metadata = query
+        .iter()
+        .filter_map(|(key, value)| {
+            let key = key.strip_prefix("metadata[")?.strip_suffix(']')?;
+            Some((key.to_string(), value.clone()))
+        })
+        .collect();
+
+    // Fetch one extra row to learn whether another page exists.
+    let filter = ChatCompletionFilter {
+        model: query.get("model").cloned(),
+        metadata,
+        tenant_key: Some(tenant_key.as_str().to_string()),
+        after: list.after,
+        limit: list.limit + 1,
+        order: list.order,
+    };
+    match storage.list_completions(&filter).await {
+        Ok(mut completions) => {
+            let has_more = completions.len() > list.limit;
+            completions.truncate(list.limit);
+            list_page(
+                completions.into_iter().map(completion_to_json).collect(),
+                has_more,
+            )
+        }
+        Err(e) => error::internal_error(
+            "storage_error",
+            format!("Failed to list chat completions: {e}"),
+        ),
+    }
+}
+
+/// List the request messages of a stored completion. Messages get stable
+/// ids of the form `{completion_id}-{index}` for `after` pagination.
+pub async fn list_completion_messages(
+    storage: &Arc<dyn ChatCompletionStorage>,
+    tenant_key: &TenantKey,
+    completion_id: &str,
+    query: &HashMap<String, String>,
+) -> Response {
+    let list = match parse_list_query(query) {
+        Ok(list) => list,
+        Err(response) => return response,
+    };
+    let completion = match load_completion(storage, tenant_key, completion_id).await {
+        Ok(completion) => completion,
+        Err(response) => return response,
+    };
+
+    let mut messages: Vec<Value> = completion
+        .request
+        .get("messages")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut message)| {
+            if let Some(obj) = message.as_object_mut() {
+                obj.insert("id".to_string(), json!(format!("{completion_id}-{index}")));
+            }
+            message
+        })
+        .collect();
+    if list.order == SortOrder::Desc {
+        messages.reverse();
+    }
+    if let Some(after) = &list.after {
+        let position = messages
+            .iter()
+            .position(|m| m.get("id").and_then(Value::as_str) == Some(after.as_str()));
+        messages.drain(..position.map_or(messages.len(), |p| p + 1));
+    }
+    let has_more = messages.len() > list.limit;
+    messages.truncate(list.limit);
+    list_page(messages, has_more)
+}
+
+#[cfg(test)]
+mod tests {
+    use smg_data_connector::MemoryChatCompletionStorage;
+
+    use super::*;
+
+    fn chat_request(store: bool) -> ChatCompletionRequest {
+        serde_json::from_value(json!({
+            "model": "test-model",
+            "messages": [{"role": "user", "content": "hi"}],
+            "store": store,
+            "metadata": {"suite": "eval"}
+        }))
+        .unwrap()
+    }
+
+    fn completion_response(id: &str) -> Response {
+        Json(json!({
+            "id": id,
+            "object": "chat.completion",
+            "created": 1_700_000_000,
+            "model": "test-model",
+            "choices": []
+        }))
+        .into_response()
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn stores_only_when_requested_and_scopes_by_tenant() {
+        let storage: Arc<dyn ChatCompletionStorage> = Arc::new(MemoryChatCompletionStorage::new(8));
+        let owner = TenantKey::from("tenant-a");
+        let other = TenantKey::from("tenant-b");
+
+        let response = store_completion(
+            &storage,
+            &chat_request(false),
+            &owner,
+            completion_response("chatcmpl-skip"),
+        )
+        .await;
+        assert_eq!(body_json(response).await["id"], "chatcmpl-skip");
+        assert!(storage
+            .get_completion("chatcmpl-skip")
+            .await
+            .unwrap()
+            .is_none());
+
+        store_completion(
+            &storage,
+            &chat_request(true),
+            &owner,
+            completion_response("chatcmpl-1"),
+        )
+        .await;
+
+        let found = body_json(get_completion(&storage, &owner, "chatcmpl-1").await).await;
+        assert_eq!(found["metadata"]["suite"], "eval");
+        assert_eq!(
+            get_completion(&storage, &other, "chatcmpl-1")
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+
+        let query = HashMap::from([("metadata[suite]".to_string(), "eval".to_string())]);
+        let page = body_json(list_completions(&storage, &owner, &query).await).await;
+        assert_eq!(page["data"].as_array().unwrap().len(), 1);
+        let page = body_json(list_completions(&storage, &other, &query).await).await;
+        assert!(page["data"].as_array().unwrap().is_empty());
+
+        let messages = body_json(
+            list_completion_messages(&storage, &owner, "chatcmpl-1", &HashMap::new()).await,
+        )
+        .await;
+        assert_eq!(messages["data"][0]["id"], "chatcmpl-1-0");
+    }
+
+    #[test]
+    fn store_with_stream_is_rejected_only_when_store_is_enabled() {
+        let storage: Arc<dyn ChatCompletionStorage> = Arc::new(MemoryChatCompletionStorage::new(8));
+        let mut request = chat_request(true);
+        assert!(check_store_request(Some(&storage), &request).is_ok());
+
+        request.stream = true;
+        let response = check_store_request(Some(&storage), &request).unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(check_store_request(None, &request).is_ok());
+
+        request.store = Some(false);
+        assert!(check_store_request(Some(&storage), &request).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_list_parameters() {
+        let query = HashMap::from([("limit".to_string(), "0".to_string())]);
+        assert!(parse_list_query(&query).is_err());
+        let query = HashMap::from([("order".to_string(), "sideways".to_string())]);
+        assert!(parse_list_query(&query).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Stored chat completions.
+//!
+//! Chat completion requests that set `store: true` are persisted after a
+//! successful non-streaming response and served from the
+//! `/v1/chat/completions` retrieval endpoints. The store is an in-memory,
+//! per-replica cache; it is not backed by the history backend.
+
+mod handlers;
+
+pub use handlers::*;
@@ -0,0 +1,248 @@
+//! Bootstrap room allocation for prefill-decode disaggregation.
+//!
+//! The prefill and decode legs of a PD request rendezvous at the KV transfer
+//! layer on a shared `bootstrap_room`. Two in-flight requests with the same
+//! room on one prefill worker cross-talk, so rooms are not drawn at random.
+//! Each is composed of a per-gateway prefix, a monotonic counter and a
+//! random suffix, and a candidate that is still open is skipped:
+//!
+//! | Width | Prefix | Counter | Suffix |
+//! |-------|--------|---------|--------|
+//! | 63-bit (HTTP, TokenSpeed) | 16 | 32 | 15 |
+//! | 31-bit (SGLang gRPC) | 8 | 16 | 7 |
+//!
+//! The prefix separates gateways that share prefill workers; the suffix keeps
+//! a restarted gateway, whose counter starts over, off its previous rooms.
+//!
+//! Every room stays registered until its [`RoomLease`] drops, once the
+//! request no longer needs the rendezvous. Rooms open longer than
+//! `pd_bootstrap.stuck_threshold_secs` are listed by
+//! `GET /admin/pd/bootstrap-rooms` and counted by the
+//! `smg_pd_bootstrap_rooms_stuck` gauge.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, LazyLock,
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::{mapref::entry::Entry, DashMap};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::observability::metrics::Metrics;
+
+/// Candidates tried before giving up on finding an unused room.
+const MAX_ATTEMPTS: usize = 16;
+
+/// How often the open and stuck room gauges are refreshed.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+static ROOMS: LazyLock<Arc<BootstrapRooms>> =
+    LazyLock::new(|| Arc::new(BootstrapRooms::new(rand::random())));
+
+/// The gateway-wide room allocator.
+pub fn bootstrap_rooms() -> &'static Arc<BootstrapRooms> {
+    &ROOMS
+}
+
+/// Bit width of the room field a backend accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomWidth {
+    /// `u64`/`i64` rooms restricted to `[0, 2^63)`.
+    Bits63,
+    /// `i32` rooms restricted to `[0, 2^31)`.
+    Bits31,
+}
+
+impl RoomWidth {
+    /// `(prefix, counter, suffix)` bits.
+    const fn layout(self) -> (u32, u32, u32) {
+        match self {
+            Self::Bits63 => (16, 32, 15),
+            Self::Bits31 => (8, 16, 7),
+        }
+    }
+
+    fn compose(self, prefix: u64, counter: u64, suffix: u64) -> u64 {
+        let (prefix_bits, counter_bits, suffix_bits) = self.layout();
+        let mask = |bits: u32| (1u64 << bits) - 1;
+        ((prefix & mask(prefix_bits)) << (counter_bits + suffix_bits))
+            | ((counter & mask(counter_bits)) << suffix_bits)
+            | (suffix & mask(suffix_bits))
+    }
+}
+
+struct OpenRoom {
+    opened: Instant,
+    prefill_url: String,
+}
+
+/// A room open longer than the queried age.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenRoomInfo {
+    pub room: u64,
+    pub prefill_url: String,
+    pub age_secs: u64,
+}
+
+/// Allocates bootstrap rooms and tracks the ones still open.
+pub struct BootstrapRooms {
+    prefix: u64,
+    counter: AtomicU64,
+    open: DashMap<u64, OpenRoom>,
+}
+
+impl std::fmt::Debug for BootstrapRooms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BootstrapRooms")
+            .field("open", &self.open.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl BootstrapRooms {
+    fn new(prefix: u64) -> Self {
+        Self {
+            prefix,
+            counter: AtomicU64::new(0),
+            open: DashMap::new(),
+        }
+    }
+
+    /// Allocate a room for a request bootstrapping against `prefill_url`.
+    pub fn allocate(self: &Arc<Self>, width: RoomWidth, prefill_url: &str) -> RoomLease {
+        let mut room = 0;
+        for _ in 0..MAX_ATTEMPTS {
+            let counter = self.counter.fetch_add(1, Ordering::Relaxed);
+            room = width.compose(self.prefix, counter, rand::random());
+            match self.open.entry(room) {
+                Entry::Vacant(entry) => {
+                    entry.insert(OpenRoom {
+                        opened: Instant::now(),
+                        prefill_url: prefill_url.to_string(),
+                    });
+                    return RoomLease {
+                        rooms: Some(Arc::clone(self)),
+                        room,
+                    };
+                }
+                Entry::Occupied(_) => Metrics::record_pd_bootstrap_room_collision(),
+            }
+        }
+        warn!(
+            open = self.open.len(),
+            "No free PD bootstrap room found; reusing an open room untracked"
+        );
+        RoomLease { rooms: None, room }
+    }
+
+    /// Number of rooms currently open.
+    pub fn open_count(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Rooms open for at least `min_age`, oldest first.
+    pub fn open_longer_than(&self, min_age: Duration) -> Vec<OpenRoomInfo> {
+        let now = Instant::now();
+        let mut rooms: Vec<OpenRoomInfo> = self
+            .open
+            .iter()
+            .filter_map(|entry| {
+                let age = now.duration_since(entry.opened);
+                (age >= min_age).then(|| OpenRoomInfo {
+                    room: *entry.key(),
+                    prefill_url: entry.prefill_url.clone(),
+                    age_secs: age.as_secs(),
+                })
+            })
+            .collect();
+        rooms.sort_by(|a, b| b.age_secs.cmp(&a.age_secs));
+        rooms
+    }
+
+    /// Spawn the loop refreshing the open and stuck room gauges.
+    pub fn start_sampler(self: &Arc<Self>, stuck_threshold: Duration) {
+        let rooms = Arc::clone(self);
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "the allocator is process-wide, so its sampler runs for the life of the process"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(SAMPLE_INTERVAL);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let stuck = rooms.open_longer_than(stuck_threshold).len();
+                Metrics::set_pd_bootstrap_rooms(rooms.open_count(), stuck);
+            }
+        });
+    }
+}
+
+/// An allocated room; releases it on drop.
+pub struct RoomLease {
+    rooms: Option<Arc<BootstrapRooms>>,
+    room: u64,
+}
+
+impl std::fmt::Debug for RoomLease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RoomLease").field(&self.room).finish()
+    }
+}
+
+impl RoomLease {
+    pub fn room(&self) -> u64 {
+        self.room
+    }
+}
+
+impl Drop for RoomLease {
+    fn drop(&mut self) {
+        if let Some(rooms) = &self.rooms {
+            rooms.open.remove(&self.room);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_rooms_fit_width_and_carry_prefix() {
+        let rooms = Arc::new(BootstrapRooms::new(0xabcd));
+        for _ in 0..1_000 {
+            let wide = rooms.allocate(RoomWidth::Bits63, "http://p:8000");
+            assert!(wide.room() <= i64::MAX as u64);
+            assert_eq!(wide.room() >> 47, 0xabcd);
+
+            let narrow = rooms.allocate(RoomWidth::Bits31, "http://p:8000");
+            assert!(narrow.room() <= i32::MAX as u64);
+            assert_eq!(narrow.room() >> 23, 0xcd);
+        }
+    }
+
+    #[test]
+    fn test_open_rooms_are_unique_and_released_on_drop() {
+        let rooms = Arc::new(BootstrapRooms::new(7));
+        // Far more leases than the 31-bit counter's 16 bits can number.
+        let leases: Vec<RoomLease> = (0..70_000)
+            .map(|_| rooms.allocate(RoomWidth::Bits31, "http://p:8000"))
+            .collect();
+        let unique: HashSet<u64> = leases.iter().map(RoomLease::room).collect();
+        assert_eq!(unique.len(), leases.len());
+        assert_eq!(rooms.open_count(), leases.len());
+
+        assert_eq!(rooms.open_longer_than(Duration::ZERO).len(), leases.len());
+        assert!(rooms.open_longer_than(Duration::from_secs(60)).is_empty());
+
+        drop(leases);
+        assert_eq!(rooms.open_count(), 0);
+    }
+}
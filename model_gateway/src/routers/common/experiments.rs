@@ -0,0 +1,354 @@
+//! Inference-time A/B experiments with sticky arm assignment.
+//!
+//! A chat or completion request for an experiment's model is bucketed by
+//! hashing the experiment name with the request's sticky key (user, tenant
+//! or a header), so a given key always lands on the same arm. The chosen
+//! arm may swap the model and prepend a chat system prompt before the
+//! request is routed. Requests outside every arm's share are not enrolled.
+//!
+//! Enrolled responses carry the `x-smg-experiment` and
+//! `x-smg-experiment-arm` headers for downstream quality evaluation, the
+//! request span records `experiment=<name>/<arm>`, and per-arm request,
+//! latency and (for non-streaming responses) token usage metrics are
+//! recorded.
+
+use std::time::Instant;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue},
+    response::Response,
+};
+use openai_protocol::{
+    chat::{ChatCompletionRequest, ChatMessage, MessageContent},
+    completion::CompletionRequest,
+};
+use rand::RngExt;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::Span;
+
+use crate::{
+    config::{ExperimentArmConfig, ExperimentConfig, ExperimentStickyKey, ExperimentsConfig},
+    observability::metrics::Metrics,
+    routers::error,
+    tenant::TenantKey,
+};
+
+static HEADER_EXPERIMENT: HeaderName = HeaderName::from_static("x-smg-experiment");
+static HEADER_EXPERIMENT_ARM: HeaderName = HeaderName::from_static("x-smg-experiment-arm");
+
+/// Buckets per experiment; arm percentages resolve to 0.01%.
+const BUCKETS: u64 = 10_000;
+
+const RESPONSE_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+/// The arm a request was assigned to.
+#[derive(Debug, Clone)]
+pub struct ExperimentAssignment {
+    pub experiment: String,
+    pub arm: String,
+    started: Instant,
+}
+
+/// Assign a chat request to an arm of the experiment targeting its model
+/// and apply the arm's variant.
+pub fn assign_chat(
+    config: &ExperimentsConfig,
+    headers: &HeaderMap,
+    tenant_key: &TenantKey,
+    request: &mut ChatCompletionRequest,
+) -> Option<ExperimentAssignment> {
+    let experiment = experiment_for(config, &request.model)?;
+    let key = sticky_key(
+        experiment,
+        headers,
+        tenant_key,
+        request.safety_identifier.as_deref(),
+    );
+    let arm = select_arm(experiment, key)?;
+    if let Some(model) = &arm.model {
+        request.model.clone_from(model);
+    }
+    if let Some(prompt) = &arm.system_prompt {
+        request.messages.insert(
+            0,
+            ChatMessage::System {
+                content: MessageContent::Text(prompt.clone()),
+                name: None,
+            },
+        );
+    }
+    Some(ExperimentAssignment::new(experiment, arm))
+}
+
+/// Assign a completion request to an arm of the experiment targeting its
+/// model. Only the arm's model applies; system prompts are chat-only.
+pub fn assign_completion(
+    config: &ExperimentsConfig,
+    headers: &HeaderMap,
+    tenant_key: &TenantKey,
+    request: &mut CompletionRequest,
+) -> Option<ExperimentAssignment> {
+    let experiment = experiment_for(config, &request.model)?;
+    let key = sticky_key(experiment, headers, tenant_key, request.user.as_deref());
+    let arm = select_arm(experiment, key)?;
+    if let Some(model) = &arm.model {
+        request.model.clone_from(model);
+    }
+    Some(ExperimentAssignment::new(experiment, arm))
+}
+
+impl ExperimentAssignment {
+    fn new(experiment: &ExperimentConfig, arm: &ExperimentArmConfig) -> Self {
+        Span::current().record(
+            "experiment",
+            tracing::field::display(format_args!("{}/{}", experiment.name, arm.name)),
+        );
+        Self {
+            experiment: experiment.name.clone(),
+            arm: arm.name.clone(),
+            started: Instant::now(),
+        }
+    }
+}
+
+fn experiment_for<'a>(config: &'a ExperimentsConfig, model: &str) -> Option<&'a ExperimentConfig> {
+    config.experiments.iter().find(|e| e.model == model)
+}
+
+fn sticky_key<'a>(
+    experiment: &ExperimentConfig,
+    headers: &'a HeaderMap,
+    tenant_key: &'a TenantKey,
+    user: Option<&'a str>,
+) -> Option<&'a str> {
+    let key = match &experiment.sticky_key {
+        ExperimentStickyKey::User => user,
+        ExperimentStickyKey::Tenant => Some(tenant_key.as_str()),
+        ExperimentStickyKey::Header(name) => headers.get(name).and_then(|v| v.to_str().ok()),
+    };
+    key.filter(|k| !k.is_empty())
+}
+
+/// Pick the arm whose cumulative share covers the request's bucket; keyless
+/// requests get a random bucket.
+fn select_arm<'a>(
+    experiment: &'a ExperimentConfig,
+    key: Option<&str>,
+) -> Option<&'a ExperimentArmConfig> {
+    let bucket = match key {
+        Some(key) => bucket(&experiment.name, key),
+        None => rand::rng().random_range(0..BUCKETS),
+    };
+    let mut upper = 0;
+    experiment.arms.iter().find(|arm| {
+        upper += (arm.percent * (BUCKETS as f64 / 100.0)).round() as u64;
+        bucket < upper
+    })
+}
+
+/// Hashing the experiment name in keeps assignments independent across
+/// experiments.
+fn bucket(experiment: &str, key: &str) -> u64 {
+    let digest = Sha256::new()
+        .chain_update(experiment.as_bytes())
+        .chain_update(b":")
+        .chain_update(key.as_bytes())
+        .finalize();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(prefix) % BUCKETS
+}
+
+/// Annotate the response with the assigned arm and record per-arm metrics.
+///
+/// Non-streaming responses are buffered to read token usage; for streams
+/// the latency is the time to the response head.
+pub async fn finish(response: Response, assignment: Option<ExperimentAssignment>) -> Response {
+    let Some(assignment) = assignment else {
+        return response;
+    };
+    let (mut parts, body) = response.into_parts();
+    for (name, value) in [
+        (&HEADER_EXPERIMENT, &assignment.experiment),
+        (&HEADER_EXPERIMENT_ARM, &assignment.arm),
+    ] {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            parts.headers.insert(name.clone(), value);
+        }
+    }
+
+    let is_stream = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    let response = if parts.status.is_success() && !is_stream {
+        let bytes = match to_bytes(body, RESPONSE_BODY_LIMIT).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return error::bad_gateway(
+                    "upstream_body_error",
+                    format!("Failed to read completion response: {e}"),
+                )
+            }
+        };
+        if let Some((prompt, completion)) = usage(&bytes) {
+            Metrics::record_experiment_tokens(
+                &assignment.experiment,
+                &assignment.arm,
+                prompt,
+                completion,
+            );
+        }
+        Response::from_parts(parts, Body::from(bytes))
+    } else {
+        Response::from_parts(parts, body)
+    };
+
+    Metrics::record_experiment_request(
+        &assignment.experiment,
+        &assignment.arm,
+        response.status().as_u16(),
+        assignment.started.elapsed(),
+    );
+    response
+}
+
+/// `(prompt_tokens, completion_tokens)` from an OpenAI-style response body.
+fn usage(body: &[u8]) -> Option<(u64, u64)> {
+    let response: Value = serde_json::from_slice(body).ok()?;
+    let usage = response.get("usage")?;
+    Some((
+        usage.get("prompt_tokens")?.as_u64()?,
+        usage.get("completion_tokens")?.as_u64()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use super::*;
+
+    fn arm(name: &str, percent: f64, model: Option<&str>) -> ExperimentArmConfig {
+        ExperimentArmConfig {
+            name: name.to_string(),
+            percent,
+            model: model.map(str::to_string),
+            system_prompt: None,
+        }
+    }
+
+    fn config(
+        sticky_key: ExperimentStickyKey,
+        arms: Vec<ExperimentArmConfig>,
+    ) -> ExperimentsConfig {
+        ExperimentsConfig {
+            experiments: vec![ExperimentConfig {
+                name: "exp".to_string(),
+                model: "llama".to_string(),
+                sticky_key,
+                arms,
+            }],
+        }
+    }
+
+    fn chat(model: &str) -> ChatCompletionRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_assignment_is_sticky_per_key() {
+        let config = config(
+            ExperimentStickyKey::Tenant,
+            vec![arm("a", 50.0, None), arm("b", 50.0, Some("llama-ft"))],
+        );
+        let mut seen = std::collections::HashSet::new();
+        for i in 0..64 {
+            let tenant = TenantKey::from(format!("auth:tenant-{i}"));
+            let first = assign_chat(&config, &HeaderMap::new(), &tenant, &mut chat("llama"))
+                .unwrap()
+                .arm;
+            for _ in 0..4 {
+                let mut request = chat("llama");
+                let again = assign_chat(&config, &HeaderMap::new(), &tenant, &mut request).unwrap();
+                assert_eq!(again.arm, first);
+                let expected = if first == "b" { "llama-ft" } else { "llama" };
+                assert_eq!(request.model, expected);
+            }
+            seen.insert(first);
+        }
+        assert_eq!(seen.len(), 2, "both arms should receive traffic");
+    }
+
+    #[test]
+    fn test_unenrolled_traffic_is_untouched() {
+        let config = config(ExperimentStickyKey::User, vec![arm("a", 0.01, None)]);
+        let tenant = TenantKey::from("anonymous");
+        let mut request = chat("mistral");
+        assert!(assign_chat(&config, &HeaderMap::new(), &tenant, &mut request).is_none());
+        assert_eq!(request.messages.len(), 1);
+
+        let enrolled = (0..200)
+            .filter(|i| {
+                let mut request = chat("llama");
+                request.safety_identifier = Some(format!("user-{i}"));
+                assign_chat(&config, &HeaderMap::new(), &tenant, &mut request).is_some()
+            })
+            .count();
+        assert!(enrolled < 10);
+    }
+
+    #[test]
+    fn test_arm_applies_system_prompt_and_header_key() {
+        let mut treatment = arm("treatment", 100.0, Some("llama-ft"));
+        treatment.system_prompt = Some("Be concise.".to_string());
+        let config = config(
+            ExperimentStickyKey::Header("x-session-id".to_string()),
+            vec![treatment],
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert("x-session-id", HeaderValue::from_static("s-1"));
+        let mut request = chat("llama");
+        let assignment = assign_chat(
+            &config,
+            &headers,
+            &TenantKey::from("anonymous"),
+            &mut request,
+        )
+        .unwrap();
+        assert_eq!(assignment.arm, "treatment");
+        assert_eq!(request.model, "llama-ft");
+        assert!(matches!(
+            &request.messages[0],
+            ChatMessage::System { content: MessageContent::Text(text), .. } if text == "Be concise."
+        ));
+        assert_eq!(request.messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_finish_annotates_response() {
+        let assignment = ExperimentAssignment {
+            experiment: "exp".to_string(),
+            arm: "b".to_string(),
+            started: Instant::now(),
+        };
+        let body = serde_json::json!({"usage": {"prompt_tokens": 3, "completion_tokens": 5}});
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = finish(response, Some(assignment)).await;
+        assert_eq!(response.headers()[&HEADER_EXPERIMENT], "exp");
+        assert_eq!(response.headers()[&HEADER_EXPERIMENT_ARM], "b");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(usage(&bytes), Some((3, 5)));
+    }
+}
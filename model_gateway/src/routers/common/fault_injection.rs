@@ -0,0 +1,169 @@
+//! Fault injection for resilience testing.
+//!
+//! When `fault_injection.enabled` is set, the HTTP router consults a
+//! [`FaultInjector`] right before each upstream call. Injected faults flow
+//! through the real retry, circuit breaker, and metrics paths, so operators
+//! can verify failover behavior in staging without touching the workers.
+
+use std::time::Duration;
+
+use axum::{http::StatusCode, response::Response};
+use rand::RngExt;
+use tracing::warn;
+
+use crate::{
+    config::{FaultInjectionConfig, FaultKind, FaultRule},
+    routers::error,
+};
+
+/// Evaluates configured fault rules against upstream calls.
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    rules: Vec<FaultRule>,
+}
+
+impl FaultInjector {
+    /// Build an injector, or `None` when fault injection is disabled or has
+    /// no rules.
+    pub fn from_config(config: &FaultInjectionConfig) -> Option<Self> {
+        if !config.enabled || config.rules.is_empty() {
+            return None;
+        }
+        warn!(
+            rules = config.rules.len(),
+            "Fault injection is enabled; upstream calls may be deliberately failed"
+        );
+        Some(Self {
+            rules: config.rules.clone(),
+        })
+    }
+
+    /// Pick the fault to inject for a call to `worker_url` on `route`, if any.
+    pub fn pick(&self, worker_url: &str, route: &str) -> Option<&FaultKind> {
+        let mut rng = rand::rng();
+        self.rules
+            .iter()
+            .filter(|rule| rule_matches(rule, worker_url, route))
+            .find(|rule| rng.random_bool(rule.probability.clamp(0.0, 1.0)))
+            .map(|rule| &rule.fault)
+    }
+}
+
+fn rule_matches(rule: &FaultRule, worker_url: &str, route: &str) -> bool {
+    rule.worker_url
+        .as_deref()
+        .is_none_or(|url| url.trim_end_matches('/') == worker_url.trim_end_matches('/'))
+        && rule.route.as_deref().is_none_or(|r| r == route)
+}
+
+/// What the caller should do after applying the pre-send part of a fault.
+#[derive(Debug)]
+pub enum FaultAction {
+    /// Call the worker normally
+    Proceed,
+    /// Call the worker, then cut the response body off after this many bytes
+    Truncate(usize),
+    /// Skip the worker and return this response
+    Respond(Response),
+}
+
+/// Apply `fault` ahead of an upstream call to `worker_url`. Latency faults
+/// sleep here and then proceed.
+pub async fn apply(fault: Option<&FaultKind>, worker_url: &str) -> FaultAction {
+    let Some(fault) = fault else {
+        return FaultAction::Proceed;
+    };
+    match fault {
+        FaultKind::Latency { delay_ms } => {
+            tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
+            FaultAction::Proceed
+        }
+        FaultKind::Status { status } => FaultAction::Respond(error::create_error(
+            StatusCode::from_u16(*status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            "fault_injected",
+            format!("Injected {status} response for worker {worker_url}"),
+        )),
+        // Mirrors `convert_reqwest_error` for a failed connection.
+        FaultKind::ConnectionReset => FaultAction::Respond(error::internal_error(
+            "call_upstream_connection_failed",
+            format!("Injected connection reset. URL: {worker_url}"),
+        )),
+        FaultKind::TruncateStream { after_bytes } => FaultAction::Truncate(*after_bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(worker_url: Option<&str>, route: Option<&str>, probability: f64) -> FaultRule {
+        FaultRule {
+            worker_url: worker_url.map(str::to_string),
+            route: route.map(str::to_string),
+            probability,
+            fault: FaultKind::ConnectionReset,
+        }
+    }
+
+    fn injector(rules: Vec<FaultRule>) -> FaultInjector {
+        FaultInjector::from_config(&FaultInjectionConfig {
+            enabled: true,
+            rules,
+        })
+        .expect("enabled config with rules builds an injector")
+    }
+
+    #[test]
+    fn test_from_config_requires_enabled_and_rules() {
+        let mut config = FaultInjectionConfig {
+            enabled: false,
+            rules: vec![rule(None, None, 1.0)],
+        };
+        assert!(FaultInjector::from_config(&config).is_none());
+        config.enabled = true;
+        config.rules.clear();
+        assert!(FaultInjector::from_config(&config).is_none());
+    }
+
+    #[test]
+    fn test_pick_matches_worker_and_route() {
+        let faults = injector(vec![rule(
+            Some("http://w1:8000/"),
+            Some("/v1/chat/completions"),
+            1.0,
+        )]);
+        assert!(faults
+            .pick("http://w1:8000", "/v1/chat/completions")
+            .is_some());
+        assert!(faults
+            .pick("http://w2:8000", "/v1/chat/completions")
+            .is_none());
+        assert!(faults.pick("http://w1:8000", "/generate").is_none());
+    }
+
+    #[test]
+    fn test_pick_respects_probability_and_order() {
+        let mut never = rule(None, None, 0.0);
+        never.fault = FaultKind::Status { status: 503 };
+        let faults = injector(vec![never, rule(None, None, 1.0)]);
+        assert_eq!(
+            faults.pick("http://w1:8000", "/generate"),
+            Some(&FaultKind::ConnectionReset)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_status_fault_skips_worker() {
+        let fault = FaultKind::Status { status: 503 };
+        match apply(Some(&fault), "http://w1:8000").await {
+            FaultAction::Respond(resp) => {
+                assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+            }
+            other => panic!("expected injected response, got {other:?}"),
+        }
+        assert!(matches!(
+            apply(None, "http://w1:8000").await,
+            FaultAction::Proceed
+        ));
+    }
+}
@@ -9,7 +9,13 @@ use http::header::HeaderName;
 
 static HEADER_TARGET_WORKER: HeaderName = HeaderName::from_static("x-smg-target-worker");
 static HEADER_ROUTING_KEY: HeaderName = HeaderName::from_static("x-smg-routing-key");
+pub(crate) static HEADER_SESSION_ID: HeaderName = HeaderName::from_static("x-smg-session-id");
+static HEADER_MODEL_CARD_REVISION: HeaderName =
+    HeaderName::from_static("x-smg-model-card-revision");
 static HEADER_MCP: HeaderName = HeaderName::from_static("x-smg-mcp");
+/// Set only by the routing rules middleware: `key=value,...` labels every
+/// selected worker must carry.
+pub(crate) static HEADER_WORKER_LABELS: HeaderName = HeaderName::from_static("x-smg-worker-labels");
 
 fn extract_header_value<'a>(headers: Option<&'a HeaderMap>, name: &HeaderName) -> Option<&'a str> {
     headers
@@ -26,6 +32,23 @@ pub fn extract_routing_key(headers: Option<&HeaderMap>) -> Option<&str> {
     extract_header_value(headers, &HEADER_ROUTING_KEY)
 }
 
+pub fn extract_session_id(headers: Option<&HeaderMap>) -> Option<&str> {
+    extract_header_value(headers, &HEADER_SESSION_ID)
+}
+
+/// Model card revision the request is pinned to; malformed values are ignored.
+pub fn extract_model_card_revision(headers: Option<&HeaderMap>) -> Option<u64> {
+    extract_header_value(headers, &HEADER_MODEL_CARD_REVISION).and_then(|v| v.parse().ok())
+}
+
+/// `(key, value)` worker labels a routing rule restricted the request to.
+pub fn extract_worker_labels(headers: Option<&HeaderMap>) -> impl Iterator<Item = (&str, &str)> {
+    extract_header_value(headers, &HEADER_WORKER_LABELS)
+        .into_iter()
+        .flat_map(|labels| labels.split(','))
+        .filter_map(|label| label.split_once('='))
+}
+
 /// Check if SMG MCP orchestration is enabled via `X-SMG-MCP: enabled` header.
 pub fn is_smg_mcp_enabled(headers: Option<&HeaderMap>) -> bool {
     headers
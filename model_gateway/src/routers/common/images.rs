@@ -0,0 +1,248 @@
+//! Image generation response handling shared by the HTTP and OpenAI routers.
+//!
+//! Upstreams disagree on how images come back: OpenAI honours
+//! `response_format`, gpt-image models and Stability always return base64,
+//! and self-hosted diffusion workers have no public URL to hand out. When the
+//! gateway file store is enabled, routers request base64 from upstream and
+//! [`finish_image_response`] persists the images and returns gateway URLs to
+//! clients that asked for `url`. Without a store the upstream payload is
+//! passed through unchanged.
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::CONTENT_TYPE, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use openai_protocol::images::{ImageGenerationResponse, ImageResponseFormat};
+use smg_data_connector::{FileId, FileStorage, StoredFile};
+use tracing::warn;
+
+use crate::{app_context::AppContext, observability::metrics::Metrics, routers::error};
+
+/// Upper bound on a buffered upstream image response (n=10 large base64 images).
+const IMAGE_RESPONSE_BODY_LIMIT: usize = 256 * 1024 * 1024;
+
+const IMAGE_FILE_PURPOSE: &str = "image_generation";
+
+/// Gateway file store handle used to host generated images.
+#[derive(Clone)]
+pub(crate) struct ImageStore {
+    storage: Arc<dyn FileStorage>,
+    public_base_url: Option<String>,
+}
+
+impl ImageStore {
+    /// Build from the app context; `None` unless the file store is enabled.
+    pub(crate) fn from_context(ctx: &AppContext) -> Option<Self> {
+        ctx.file_storage.as_ref().map(|storage| Self {
+            storage: Arc::clone(storage),
+            public_base_url: ctx.router_config.file_store.public_base_url.clone(),
+        })
+    }
+
+    fn file_url(&self, id: &FileId) -> String {
+        let base = self
+            .public_base_url
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        format!("{base}/v1/files/{id}/content")
+    }
+
+    /// Decode a base64 image, store it, and return its gateway URL.
+    async fn persist(&self, b64: &str) -> Result<String, String> {
+        let bytes = BASE64_STANDARD
+            .decode(b64)
+            .map_err(|e| format!("Upstream returned invalid base64 image: {e}"))?;
+        let (content_type, extension) = sniff_image_type(&bytes);
+        let file = StoredFile::new(
+            format!("image.{extension}"),
+            IMAGE_FILE_PURPOSE,
+            content_type,
+            bytes,
+        );
+        let id = self
+            .storage
+            .store_file(file)
+            .await
+            .map_err(|e| format!("Failed to store generated image: {e}"))?;
+        Ok(self.file_url(&id))
+    }
+}
+
+/// Format to request from upstream for a client asking for `requested`.
+///
+/// With a store the gateway always takes base64 so it can host the bytes
+/// itself; without one the client's choice is forwarded.
+pub(crate) fn upstream_response_format(
+    requested: ImageResponseFormat,
+    store: Option<&ImageStore>,
+) -> ImageResponseFormat {
+    if store.is_some() {
+        ImageResponseFormat::B64Json
+    } else {
+        requested
+    }
+}
+
+/// Rewrite base64 images into stored-file URLs when the client asked for `url`.
+async fn apply_response_format(
+    response: &mut ImageGenerationResponse,
+    requested: ImageResponseFormat,
+    store: Option<&ImageStore>,
+) -> Result<(), String> {
+    let (ImageResponseFormat::Url, Some(store)) = (requested, store) else {
+        return Ok(());
+    };
+    for image in &mut response.data {
+        if image.url.is_some() {
+            continue;
+        }
+        if let Some(b64) = image.b64_json.take() {
+            image.url = Some(store.persist(&b64).await?);
+        }
+    }
+    Ok(())
+}
+
+/// Post-process an upstream image generation response.
+///
+/// Non-success responses are returned as-is. Successful bodies are parsed,
+/// their images hosted on the file store if needed, and counted per image.
+pub(crate) async fn finish_image_response(
+    response: Response,
+    requested: ImageResponseFormat,
+    store: Option<&ImageStore>,
+    router_type: &'static str,
+    backend_type: &'static str,
+    model_id: &str,
+) -> Response {
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, IMAGE_RESPONSE_BODY_LIMIT).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error::bad_gateway(
+                "upstream_error",
+                format!("Failed to read image response: {e}"),
+            )
+        }
+    };
+
+    let mut parsed: ImageGenerationResponse = match serde_json::from_slice(&bytes) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            warn!(model = model_id, error = %e, "Unrecognized image generation response; passing through");
+            return Response::from_parts(parts, Body::from(bytes));
+        }
+    };
+
+    if let Err(message) = apply_response_format(&mut parsed, requested, store).await {
+        return error::internal_error("image_storage_failed", message);
+    }
+
+    Metrics::record_router_images(
+        router_type,
+        backend_type,
+        model_id,
+        parsed.data.len() as u64,
+    );
+
+    let mut response = (parts.status, axum::Json(parsed)).into_response();
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+/// Identify the image encoding from its magic bytes, defaulting to PNG.
+fn sniff_image_type(bytes: &[u8]) -> (&'static str, &'static str) {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        ("image/jpeg", "jpeg")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        ("image/webp", "webp")
+    } else {
+        ("image/png", "png")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::images::ImageData;
+    use smg_data_connector::MemoryFileStorage;
+
+    use super::*;
+
+    fn store() -> ImageStore {
+        ImageStore {
+            storage: Arc::new(MemoryFileStorage::new(4)),
+            public_base_url: Some("https://gw.example.com/".to_string()),
+        }
+    }
+
+    fn response_with(image: ImageData) -> ImageGenerationResponse {
+        ImageGenerationResponse {
+            created: 0,
+            data: vec![image],
+            usage: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn url_requests_are_served_from_the_file_store() {
+        let store = store();
+        let jpeg = BASE64_STANDARD.encode([0xFF, 0xD8, 0xFF, 0xE0]);
+        let mut response = response_with(ImageData {
+            b64_json: Some(jpeg),
+            ..Default::default()
+        });
+
+        apply_response_format(&mut response, ImageResponseFormat::Url, Some(&store))
+            .await
+            .unwrap();
+
+        let image = &response.data[0];
+        assert!(image.b64_json.is_none());
+        let url = image.url.as_deref().unwrap();
+        let id = url
+            .strip_prefix("https://gw.example.com/v1/files/")
+            .and_then(|rest| rest.strip_suffix("/content"))
+            .unwrap();
+        let stored = store.storage.get_file(&id.into()).await.unwrap().unwrap();
+        assert_eq!(stored.content_type, "image/jpeg");
+        assert_eq!(stored.bytes, vec![0xFF, 0xD8, 0xFF, 0xE0]);
+    }
+
+    #[tokio::test]
+    async fn base64_requests_and_storeless_routing_pass_through() {
+        let b64 = BASE64_STANDARD.encode([0x89, b'P', b'N', b'G']);
+        let mut response = response_with(ImageData {
+            b64_json: Some(b64.clone()),
+            ..Default::default()
+        });
+
+        apply_response_format(&mut response, ImageResponseFormat::B64Json, Some(&store()))
+            .await
+            .unwrap();
+        apply_response_format(&mut response, ImageResponseFormat::Url, None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.data[0].b64_json.as_deref(), Some(b64.as_str()));
+        assert!(response.data[0].url.is_none());
+        assert_eq!(
+            upstream_response_format(ImageResponseFormat::Url, None),
+            ImageResponseFormat::Url
+        );
+        assert_eq!(
+            upstream_response_format(ImageResponseFormat::Url, Some(&store())),
+            ImageResponseFormat::B64Json
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Latency budgets converted into `max_tokens` caps.
+//!
+//! Interactive callers that must answer within a deadline send
+//! `x-smg-latency-budget-ms`. With `latency_budget.enabled`, the budget less
+//! the configured time-to-first-token allowance is multiplied by the decode
+//! rate one request currently gets on the model's slowest worker, and
+//! `max_tokens` is lowered to the result. The rate is each DP rank's
+//! reported generation throughput divided by its running requests; without
+//! load reports the configured default rate is used. The worker is not yet
+//! chosen when the cap is computed, hence the slowest one.
+//!
+//! Caps only ever lower the request's own limit. The cap is reported in the
+//! `x-smg-latency-budget-max-tokens` response header.
+
+use std::collections::HashMap;
+
+use axum::{
+    http::{HeaderMap, HeaderName, HeaderValue},
+    response::Response,
+};
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, worker::WorkerLoadResponse,
+};
+
+use crate::{app_context::AppContext, config::LatencyBudgetConfig};
+
+/// Request header carrying the caller's latency budget in milliseconds.
+pub const LATENCY_BUDGET_HEADER: &str = "x-smg-latency-budget-ms";
+
+static HEADER_BUDGET_MAX_TOKENS: HeaderName =
+    HeaderName::from_static("x-smg-latency-budget-max-tokens");
+
+/// The caller's budget in milliseconds; absent, malformed and zero budgets
+/// are ignored.
+fn budget_ms(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(LATENCY_BUDGET_HEADER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|ms| *ms > 0)
+}
+
+/// Per-request decode rate, in tokens/s, of the slowest of `worker_urls`
+/// that reports one. Ranks without running requests say nothing about the
+/// per-request rate and are skipped.
+pub fn decode_rate<'a>(
+    loads: &HashMap<String, WorkerLoadResponse>,
+    worker_urls: impl IntoIterator<Item = &'a str>,
+) -> Option<f64> {
+    worker_urls
+        .into_iter()
+        .filter_map(|url| loads.get(url))
+        .flat_map(|load| &load.loads)
+        .filter(|rank| rank.num_running_reqs > 0 && rank.gen_throughput > 0.0)
+        .map(|rank| rank.gen_throughput / f64::from(rank.num_running_reqs))
+        .min_by(f64::total_cmp)
+}
+
+/// Tokens decodable within `budget_ms` at `tokens_per_second`, never below
+/// `min_max_tokens`.
+pub fn max_tokens_cap(config: &LatencyBudgetConfig, budget_ms: u64, tokens_per_second: f64) -> u32 {
+    let decode_ms = budget_ms.saturating_sub(config.ttft_allowance_ms);
+    let tokens = (tokens_per_second * decode_ms as f64 / 1000.0).floor();
+    // Float-to-int `as` saturates, so huge budgets cap at `u32::MAX`.
+    (tokens as u32).max(config.min_max_tokens)
+}
+
+/// The cap for a request to `model` carrying `headers`, if it has a budget.
+fn cap_for(context: &AppContext, headers: &HeaderMap, model: &str) -> Option<u32> {
+    let config = &context.router_config.latency_budget;
+    if !config.enabled {
+        return None;
+    }
+    let budget_ms = budget_ms(headers)?;
+    let observed = context.worker_monitor.as_ref().and_then(|monitor| {
+        let loads = monitor.subscribe();
+        let loads = loads.borrow();
+        let workers = context.worker_registry.get_by_model(model);
+        decode_rate(&loads, workers.iter().map(|worker| worker.url()))
+    });
+    let rate = observed.unwrap_or(config.default_tokens_per_second);
+    Some(max_tokens_cap(config, budget_ms, rate))
+}
+
+/// Lower `value` to `cap`, filling it in when unset.
+fn lower(value: &mut Option<u32>, cap: u32) {
+    *value = Some(value.map_or(cap, |current| current.min(cap)));
+}
+
+/// Lower a chat request's token limit to `cap`.
+fn cap_chat(request: &mut ChatCompletionRequest, cap: u32) {
+    // `max_tokens` is the deprecated spelling of `max_completion_tokens`;
+    // lower whichever the client sent and fill in the current one otherwise.
+    if request.max_tokens.is_some() {
+        lower(&mut request.max_tokens, cap);
+    }
+    if request.max_tokens.is_none() || request.max_completion_tokens.is_some() {
+        lower(&mut request.max_completion_tokens, cap);
+    }
+}
+
+/// Cap the request's token limit to its latency budget. Returns the cap
+/// applied, if any.
+pub fn apply_to_chat(
+    context: &AppContext,
+    headers: &HeaderMap,
+    request: &mut ChatCompletionRequest,
+) -> Option<u32> {
+    let cap = cap_for(context, headers, &request.model)?;
+    cap_chat(request, cap);
+    Some(cap)
+}
+
+/// Cap the request's token limit to its latency budget. Returns the cap
+/// applied, if any.
+pub fn apply_to_completion(
+    context: &AppContext,
+    headers: &HeaderMap,
+    request: &mut CompletionRequest,
+) -> Option<u32> {
+    let cap = cap_for(context, headers, &request.model)?;
+    lower(&mut request.max_tokens, cap);
+    Some(cap)
+}
+
+/// Report the applied cap on the response, if any.
+pub fn annotate_response(mut response: Response, cap: Option<u32>) -> Response {
+    if let Some(cap) = cap {
+        response
+            .headers_mut()
+            .insert(HEADER_BUDGET_MAX_TOKENS.clone(), HeaderValue::from(cap));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::worker::SchedulerLoadSnapshot;
+
+    use super::*;
+
+    fn rank(num_running_reqs: i32, gen_throughput: f64) -> SchedulerLoadSnapshot {
+        SchedulerLoadSnapshot {
+            num_running_reqs,
+            gen_throughput,
+            ..Default::default()
+        }
+    }
+
+    fn enabled() -> LatencyBudgetConfig {
+        LatencyBudgetConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn budget_header_must_be_positive_integer() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(budget_ms(&headers), None);
+        for (value, expected) in [(" 1500 ", Some(1500)), ("0", None), ("1.5s", None)] {
+            headers.insert(LATENCY_BUDGET_HEADER, HeaderValue::from_static(value));
+            assert_eq!(budget_ms(&headers), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn decode_rate_is_slowest_busy_rank() {
+        let loads = HashMap::from([
+            (
+                "http://a".to_string(),
+                WorkerLoadResponse {
+                    loads: vec![rank(4, 200.0), rank(0, 0.0)],
+                    ..Default::default()
+                },
+            ),
+            (
+                "http://b".to_string(),
+                WorkerLoadResponse {
+                    loads: vec![rank(2, 60.0)],
+                    ..Default::default()
+                },
+            ),
+            (
+                "http://other-model".to_string(),
+                WorkerLoadResponse {
+                    loads: vec![rank(10, 10.0)],
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(decode_rate(&loads, ["http://a", "http://b"]), Some(30.0));
+        assert_eq!(decode_rate(&loads, ["http://a", "http://c"]), Some(50.0));
+        assert_eq!(decode_rate(&loads, ["http://c"]), None);
+
+        let idle = HashMap::from([(
+            "http://a".to_string(),
+            WorkerLoadResponse {
+                loads: vec![rank(0, 0.0)],
+                ..Default::default()
+            },
+        )]);
+        assert_eq!(decode_rate(&idle, ["http://a"]), None);
+    }
+
+    #[test]
+    fn cap_subtracts_ttft_allowance_and_respects_floor() {
+        let config = enabled();
+        assert_eq!(max_tokens_cap(&config, 2_500, 40.0), 80);
+        assert_eq!(max_tokens_cap(&config, 2_510, 40.0), 80);
+        assert_eq!(max_tokens_cap(&config, 400, 40.0), 16);
+        assert_eq!(max_tokens_cap(&config, u64::MAX, 1e9), u32::MAX);
+    }
+
+    #[test]
+    fn cap_only_lowers_chat_limits() {
+        let chat = |json: serde_json::Value| -> ChatCompletionRequest {
+            serde_json::from_value(json).unwrap()
+        };
+        let mut request = chat(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+        }));
+        cap_chat(&mut request, 100);
+        assert_eq!(request.max_completion_tokens, Some(100));
+        assert_eq!(request.max_tokens, None);
+
+        let mut request = chat(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+            "max_tokens": 40,
+        }));
+        cap_chat(&mut request, 100);
+        assert_eq!(request.max_tokens, Some(40));
+        assert_eq!(request.max_completion_tokens, None);
+
+        let mut request = chat(serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hi"}],
+            "max_tokens": 400,
+            "max_completion_tokens": 300,
+        }));
+        cap_chat(&mut request, 100);
+        assert_eq!(request.max_tokens, Some(100));
+        assert_eq!(request.max_completion_tokens, Some(100));
+    }
+}
@@ -0,0 +1,284 @@
+//! Map-reduce orchestration of chat requests over documents longer than the
+//! model's context window.
+//!
+//! The final user message is the document. It is split into chunks, each
+//! chunk is generated as an independent non-streaming request through the
+//! router (so the routing policy spreads chunks across workers), and a reduce
+//! request combines the chunk outputs. The reduce pass keeps the caller's
+//! `stream` setting and its response is returned unchanged.
+
+use axum::{body::to_bytes, http::HeaderMap, response::Response};
+use futures::{stream, StreamExt};
+use openai_protocol::chat::{ChatCompletionRequest, ChatMessage, MessageContent};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    config::MapReduceConfig,
+    middleware::TenantRequestMeta,
+    routers::{error, RouterTrait},
+};
+
+/// Chunk outputs are single non-streaming completions; anything larger than
+/// this is not a chat response.
+const CHUNK_RESPONSE_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+const DEFAULT_MAP_PROMPT: &str = "Extract the information in this part of a longer document \
+     that is relevant to the task. Be concise and do not add anything that is not in the text.";
+
+const DEFAULT_REDUCE_PROMPT: &str = "The following notes were taken from consecutive parts of \
+     one document. Combine them into a single coherent response.";
+
+/// Route a chat request, running map-reduce when it sets `map_reduce`.
+pub async fn route_chat(
+    router: &dyn RouterTrait,
+    config: &MapReduceConfig,
+    headers: Option<&HeaderMap>,
+    tenant_meta: &TenantRequestMeta,
+    body: &ChatCompletionRequest,
+) -> Response {
+    let Some(options) = &body.map_reduce else {
+        return router
+            .route_chat(headers, tenant_meta, body, &body.model)
+            .await;
+    };
+    if !config.enabled_for(&body.model) {
+        return error::bad_request(
+            "map_reduce_not_enabled",
+            format!("map_reduce is not enabled for model '{}'", body.model),
+        );
+    }
+
+    let chunk_chars = options.chunk_chars.unwrap_or(config.chunk_chars);
+    let overlap_chars = options.overlap_chars.unwrap_or(0);
+    if chunk_chars == 0 || overlap_chars >= chunk_chars {
+        return error::bad_request(
+            "invalid_map_reduce",
+            "map_reduce.chunk_chars must be > 0 and greater than overlap_chars",
+        );
+    }
+    let (context, document) = match split_request(&body.messages) {
+        Ok(split) => split,
+        Err(message) => return error::bad_request("invalid_map_reduce", message),
+    };
+
+    let chunks = split_document(&document, chunk_chars, overlap_chars);
+    if chunks.len() > config.max_chunks {
+        return error::bad_request(
+            "map_reduce_too_many_chunks",
+            format!(
+                "document splits into {} chunks; the limit is {}",
+                chunks.len(),
+                config.max_chunks
+            ),
+        );
+    }
+
+    // Clone the request once without its messages; every pass starts from it.
+    let mut template = body.clone();
+    template.messages = Vec::new();
+    template.map_reduce = None;
+
+    if chunks.len() <= 1 {
+        template.messages = body.messages.clone();
+        return router
+            .route_chat(headers, tenant_meta, &template, &body.model)
+            .await;
+    }
+
+    debug!(
+        model = %body.model,
+        chunks = chunks.len(),
+        "Running map-reduce over long document"
+    );
+    let map_prompt = options.map_prompt.as_deref().unwrap_or(DEFAULT_MAP_PROMPT);
+    let total = chunks.len();
+    let requests = chunks.iter().enumerate().map(|(index, chunk)| {
+        let text = format!("{map_prompt}\n\n[Part {} of {total}]\n{chunk}", index + 1);
+        map_request(&template, context, text)
+    });
+    let outputs: Vec<Result<String, Response>> =
+        stream::iter(requests.map(|request| generate_chunk(router, headers, tenant_meta, request)))
+            .buffered(config.max_concurrency.max(1))
+            .collect()
+            .await;
+
+    let reduce_prompt = options
+        .reduce_prompt
+        .as_deref()
+        .unwrap_or(DEFAULT_REDUCE_PROMPT);
+    let mut text = reduce_prompt.to_string();
+    for (index, output) in outputs.into_iter().enumerate() {
+        match output {
+            Ok(notes) => {
+                text.push_str(&format!("\n\n[Part {} of {total}]\n{notes}", index + 1));
+            }
+            Err(response) => return response,
+        }
+    }
+
+    let mut reduce = template;
+    reduce.messages = context.to_vec();
+    reduce.messages.push(user_message(text));
+    router
+        .route_chat(headers, tenant_meta, &reduce, &body.model)
+        .await
+}
+
+/// Separate the conversation context from the document in the final user
+/// message.
+fn split_request(messages: &[ChatMessage]) -> Result<(&[ChatMessage], String), String> {
+    let Some((ChatMessage::User { content, .. }, context)) = messages.split_last() else {
+        return Err("map_reduce requires the document in the final user message".to_string());
+    };
+    let document = match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(_) => {
+            let mut text = String::new();
+            content.append_text_to(&mut text);
+            text
+        }
+    };
+    if document.is_empty() {
+        return Err("map_reduce requires a non-empty text document".to_string());
+    }
+    Ok((context, document))
+}
+
+/// Split `text` into chunks of at most `chunk_chars` characters, each starting
+/// `overlap_chars` before the previous one ended. Chunks end after a newline,
+/// or failing that after whitespace, when one falls in the second half of the
+/// window.
+fn split_document(text: &str, chunk_chars: usize, overlap_chars: usize) -> Vec<&str> {
+    // Byte offset of every character boundary, including the end.
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let total = bounds.len() - 1;
+    let ends_with =
+        |end: usize, pred: fn(char) -> bool| text[bounds[end - 1]..bounds[end]].chars().all(pred);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < total {
+        let mut end = (start + chunk_chars).min(total);
+        if end < total {
+            let earliest = start + chunk_chars / 2 + 1;
+            end = (earliest..end)
+                .rev()
+                .find(|&i| ends_with(i, |c| c == '\n'))
+                .or_else(|| {
+                    (earliest..end)
+                        .rev()
+                        .find(|&i| ends_with(i, char::is_whitespace))
+                })
+                .unwrap_or(end);
+        }
+        chunks.push(&text[bounds[start]..bounds[end]]);
+        if end == total {
+            break;
+        }
+        start = end.saturating_sub(overlap_chars).max(start + 1);
+    }
+    chunks
+}
+
+fn user_message(text: String) -> ChatMessage {
+    ChatMessage::User {
+        content: MessageContent::Text(text),
+        name: None,
+    }
+}
+
+/// Build a non-streaming chunk request from the template.
+fn map_request(
+    template: &ChatCompletionRequest,
+    context: &[ChatMessage],
+    text: String,
+) -> ChatCompletionRequest {
+    let mut request = template.clone();
+    request.messages = context.to_vec();
+    request.messages.push(user_message(text));
+    request.stream = false;
+    request.stream_options = None;
+    request.n = None;
+    request.tools = None;
+    request.tool_choice = None;
+    request
+}
+
+async fn generate_chunk(
+    router: &dyn RouterTrait,
+    headers: Option<&HeaderMap>,
+    tenant_meta: &TenantRequestMeta,
+    request: ChatCompletionRequest,
+) -> Result<String, Response> {
+    let response = router
+        .route_chat(headers, tenant_meta, &request, &request.model)
+        .await;
+    if !response.status().is_success() {
+        return Err(response);
+    }
+    let bytes = to_bytes(response.into_body(), CHUNK_RESPONSE_BODY_LIMIT)
+        .await
+        .map_err(|e| {
+            error::bad_gateway(
+                "map_reduce_chunk_failed",
+                format!("failed to read chunk response: {e}"),
+            )
+        })?;
+    let completion: Value = serde_json::from_slice(&bytes).map_err(|e| {
+        error::bad_gateway(
+            "map_reduce_chunk_failed",
+            format!("invalid chunk response: {e}"),
+        )
+    })?;
+    completion
+        .pointer("/choices/0/message/content")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            error::bad_gateway(
+                "map_reduce_chunk_failed",
+                "chunk response has no message content",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_document_prefers_line_breaks() {
+        let text = "alpha beta\ngamma delta epsilon";
+        let chunks = split_document(text, 16, 0);
+        assert_eq!(chunks, vec!["alpha beta\n", "gamma delta ", "epsilon"]);
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn test_split_document_overlap_and_multibyte() {
+        let text = "ééééééééé";
+        let chunks = split_document(text, 4, 1);
+        assert_eq!(chunks, vec!["éééé", "éééé", "ééé"]);
+
+        assert_eq!(split_document("short", 100, 10), vec!["short"]);
+    }
+
+    #[test]
+    fn test_split_request_requires_final_user_message() {
+        let messages: Vec<ChatMessage> = serde_json::from_value(serde_json::json!([
+            {"role": "system", "content": "Be brief"},
+            {"role": "user", "content": "long document"}
+        ]))
+        .unwrap();
+        let (context, document) = split_request(&messages).unwrap();
+        assert_eq!(context.len(), 1);
+        assert_eq!(document, "long document");
+
+        assert!(split_request(&messages[..1]).is_err());
+    }
+}
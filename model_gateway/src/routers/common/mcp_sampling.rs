@@ -0,0 +1,154 @@
+//! MCP sampling backend that runs server-initiated completions through the
+//! gateway's own chat routing.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::to_bytes;
+use openai_protocol::chat::ChatCompletionRequest;
+use serde_json::{json, Value};
+use smg_mcp::{SamplingBackend, SamplingRequest, SamplingResponse};
+
+use crate::{middleware::RouteRequestMeta, routers::RouterTrait, tenant::TenantKey};
+
+/// Sampling responses are single completions capped by the server's
+/// `max_tokens`; anything larger than this is not a chat response.
+const SAMPLING_RESPONSE_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+pub struct RouterSamplingBackend {
+    router: Arc<dyn RouterTrait>,
+}
+
+impl RouterSamplingBackend {
+    pub fn new(router: Arc<dyn RouterTrait>) -> Self {
+        Self { router }
+    }
+}
+
+#[async_trait]
+impl SamplingBackend for RouterSamplingBackend {
+    async fn create_message(&self, request: SamplingRequest) -> Result<SamplingResponse, String> {
+        let body = chat_request(&request)?;
+        // Attribute the call to the originating tenant when there is one.
+        let tenant_key = match &request.tenant_id {
+            Some(tenant) => TenantKey::new(tenant.as_str()),
+            None => TenantKey::new(format!("mcp:{}", request.server_key)),
+        };
+        let meta = RouteRequestMeta::new(tenant_key);
+
+        let response = self
+            .router
+            .route_chat(None, &meta, &body, &request.model)
+            .await;
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), SAMPLING_RESPONSE_BODY_LIMIT)
+            .await
+            .map_err(|e| format!("failed to read completion: {e}"))?;
+        if !status.is_success() {
+            return Err(format!(
+                "completion failed with {status}: {}",
+                String::from_utf8_lossy(&bytes)
+            ));
+        }
+        let completion: Value = serde_json::from_slice(&bytes)
+            .map_err(|e| format!("invalid completion response: {e}"))?;
+        Ok(sampling_response(&completion, &request.model))
+    }
+}
+
+fn chat_request(request: &SamplingRequest) -> Result<ChatCompletionRequest, String> {
+    let mut messages = Vec::with_capacity(request.messages.len() + 1);
+    if let Some(system) = &request.system_prompt {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    messages.extend(
+        request
+            .messages
+            .iter()
+            .map(|m| json!({ "role": m.role, "content": m.text })),
+    );
+
+    let mut body = json!({
+        "model": request.model,
+        "messages": messages,
+        "max_completion_tokens": request.max_tokens,
+        "stream": false,
+    });
+    if let Some(temperature) = request.temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if !request.stop_sequences.is_empty() {
+        body["stop"] = json!(request.stop_sequences);
+    }
+    serde_json::from_value(body).map_err(|e| format!("invalid sampling request: {e}"))
+}
+
+fn sampling_response(completion: &Value, model: &str) -> SamplingResponse {
+    let choice = completion.pointer("/choices/0");
+    let text = choice
+        .and_then(|c| c.pointer("/message/content"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let stop_reason = choice
+        .and_then(|c| c.get("finish_reason"))
+        .and_then(Value::as_str)
+        .map(|reason| match reason {
+            "stop" => "endTurn".to_string(),
+            "length" => "maxTokens".to_string(),
+            other => other.to_string(),
+        });
+    SamplingResponse {
+        model: completion
+            .get("model")
+            .and_then(Value::as_str)
+            .unwrap_or(model)
+            .to_string(),
+        text,
+        stop_reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smg_mcp::SamplingMessage;
+
+    use super::*;
+
+    #[test]
+    fn test_chat_request_from_sampling_request() {
+        let request = SamplingRequest {
+            server_key: "researcher".to_string(),
+            tenant_id: None,
+            model: "llama".to_string(),
+            system_prompt: Some("Be brief".to_string()),
+            messages: vec![SamplingMessage {
+                role: "user".to_string(),
+                text: "Summarize".to_string(),
+            }],
+            max_tokens: 128,
+            temperature: Some(0.2),
+            stop_sequences: vec![],
+        };
+
+        let body = chat_request(&request).unwrap();
+        assert_eq!(body.model, "llama");
+        assert_eq!(body.messages.len(), 2);
+        assert_eq!(body.max_completion_tokens, Some(128));
+    }
+
+    #[test]
+    fn test_sampling_response_maps_finish_reason() {
+        let completion = json!({
+            "model": "llama",
+            "choices": [{
+                "message": { "role": "assistant", "content": "done" },
+                "finish_reason": "length"
+            }]
+        });
+        let response = sampling_response(&completion, "fallback");
+        assert_eq!(response.model, "llama");
+        assert_eq!(response.text, "done");
+        assert_eq!(response.stop_reason.as_deref(), Some("maxTokens"));
+    }
+}
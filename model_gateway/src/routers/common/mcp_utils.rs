@@ -475,6 +475,8 @@ mod tests {
             warmup: Vec::new(),
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
 
         let registry = FormatRegistry::new();
@@ -496,6 +498,8 @@ mod tests {
             warmup: Vec::new(),
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
 
         (
@@ -642,6 +646,8 @@ mod tests {
             warmup: Vec::new(),
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
 
         let format_registry = FormatRegistry::new();
@@ -716,6 +722,8 @@ mod tests {
             warmup: Vec::new(),
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
 
         let format_registry = FormatRegistry::new();
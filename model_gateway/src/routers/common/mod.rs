@@ -6,11 +6,37 @@
 //! `RouterTrait` definition and the per-protocol submodules.
 //!
 //! Submodules:
+//! - [`experiments`] — sticky A/B experiment arm assignment and per-arm
+//!   metrics for chat and completion requests
+//! - [`bootstrap_rooms`] — collision-free PD bootstrap room allocation
+//!   and tracking of rooms whose bootstrap is still open
+//! - [`fault_injection`] — opt-in fault injection (latency, error status,
+//!   connection reset, truncated body) for resilience testing
+//! - [`images`] — image generation response handling (file store
+//!   hosting of generated images, per-image metering)
 //! - [`header_utils`] — request header parsing helpers
 //!   (`extract_routing_key`, `extract_target_worker`, etc.)
+//! - [`latency_budget`] — `max_tokens` caps derived from a caller's
+//!   latency budget and the model's observed decode rate
+//! - [`map_reduce`] — opt-in map-reduce orchestration that splits a long
+//!   document across workers and combines the chunk outputs
+//! - [`mcp_sampling`] — serves MCP sampling (server-initiated LLM
+//!   calls) through the gateway's chat routing
 //! - [`mcp_utils`] — Model Context Protocol tool-call orchestration
+//! - [`prompts`] — tenant libraries of versioned prompt templates and
+//!   their rendering into Responses requests
+//! - [`prompt_guard`] — heuristic prompt-injection screening of chat and
+//!   completion requests with per-rule hit counters
 //! - [`persistence_utils`] — response/conversation persistence
 //!   helpers shared across the chat / responses / messages routes
+//! - [`sampling_limits`] — per-model sampling parameter defaults and
+//!   clamps applied to chat and completion requests
+//! - [`sessions`] — `/v1/sessions` handlers that open worker-pinned
+//!   sessions and pre-fill their system prompt
+//! - [`tenant_mcp`] — tenant-scoped MCP server registry handlers and
+//!   the resolution of registered servers into Responses requests
+//! - [`tool_history`] — normalization of Anthropic and chat shaped tool
+//!   calls and results in replayed conversation history
 //! - [`realtime`] — Realtime API transport (WS/WebRTC/REST relay +
 //!   session registry) shared by the OpenAI and HTTP routers
 //! - [`worker_selection`] — per-request worker-selection helpers used
@@ -21,12 +47,28 @@
 //!   historical reasons before this extraction.
 //! - [`sse`] — shared SSE codec (encoder + decoder) for streaming
 //!   responses to clients and parsing upstream SSE byte streams
+//! - [`sse_client`] — upstream SSE reader built on the decoder, with idle
+//!   timeouts and optional `Last-Event-ID` reconnect
 
+pub mod bootstrap_rooms;
+pub mod experiments;
+pub mod fault_injection;
 pub mod header_utils;
+pub(crate) mod images;
+pub mod latency_budget;
+pub mod map_reduce;
+pub mod mcp_sampling;
 pub mod mcp_utils;
 pub mod openai_bridge;
 pub mod persistence_utils;
+pub mod prompt_guard;
+pub mod prompts;
 pub mod realtime;
 pub mod retry;
+pub mod sampling_limits;
+pub mod sessions;
 pub mod sse;
+pub mod sse_client;
+pub mod tenant_mcp;
+pub mod tool_history;
 pub mod worker_selection;
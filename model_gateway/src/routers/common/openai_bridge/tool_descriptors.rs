@@ -273,7 +273,7 @@ pub fn should_hide_output_item_json(
                 session.is_builtin_server_label(label)
                     || session.is_internal_non_builtin_server_label(label)
             }),
-        Some("mcp_call") | Some("mcp_approval_request") => {
+        Some("mcp_call") | Some("mcp_approval_request") | Some("mcp_elicitation_request") => {
             let matches_internal = item
                 .get("server_label")
                 .and_then(|v| v.as_str())
@@ -320,6 +320,9 @@ fn is_client_visible_output_item(
         | ResponseOutputItem::McpApprovalRequest {
             server_label, name, ..
         } => !session.should_hide_mcp_call_like_by_label(name, server_label),
+        ResponseOutputItem::McpElicitationRequest { server_label, .. } => {
+            !session.is_internal_non_builtin_server_label(server_label)
+        }
         ResponseOutputItem::FunctionToolCall { name, .. } => {
             !session.should_hide_function_call_like(name, user_function_names)
         }
@@ -16,6 +16,7 @@ use smg_data_connector::{
 use tracing::{debug, info, warn};
 
 use super::openai_bridge;
+use crate::tenant::TenantKey;
 
 // ============================================================================
 // Constants
@@ -194,7 +195,7 @@ fn get_string(json: &Value, key: &str) -> Option<String> {
 /// without an extra clone — the persistence path passes a freshly-compacted
 /// JSON object that has no other live references.
 pub fn build_stored_response(
-    response_json: Value,
+    mut response_json: Value,
     original_body: &ResponsesRequest,
 ) -> StoredResponse {
     let mut stored = StoredResponse::new(None);
@@ -226,10 +227,27 @@ pub fn build_stored_response(
         stored.id = ResponseId::from(id_str.as_str());
     }
 
+    // Keep the requested seed next to `system_fingerprint` so the generation
+    // can be replayed, even when the upstream response doesn't echo it.
+    if let (Some(seed), Some(obj)) = (original_body.seed, response_json.as_object_mut()) {
+        obj.entry("seed").or_insert_with(|| json!(seed));
+    }
+
     stored.raw_response = response_json;
     stored
 }
 
+/// Whether `tenant_key` may chain onto or read `stored`.
+///
+/// Responses persisted before ownership was recorded have no owner and stay
+/// visible to every tenant.
+pub fn tenant_owns_response(stored: &StoredResponse, tenant_key: &TenantKey) -> bool {
+    stored
+        .tenant_key
+        .as_deref()
+        .is_none_or(|owner| owner == tenant_key.as_str())
+}
+
 /// Extract and normalize input items from ResponseInput
 fn extract_input_items(input: &ResponseInput) -> Result<Vec<Value>, String> {
     let items = match input {
@@ -416,6 +434,7 @@ pub async fn persist_conversation_items(
     response_storage: Arc<dyn ResponseStorage>,
     response_json: &Value,
     original_body: &ResponsesRequest,
+    tenant_key: Option<&TenantKey>,
     request_context: Option<StorageRequestContext>,
 ) -> Result<(), String> {
     let inner = persist_conversation_items_inner(
@@ -424,6 +443,7 @@ pub async fn persist_conversation_items(
         response_storage,
         response_json,
         original_body,
+        tenant_key,
     );
     match request_context {
         Some(ctx) => with_request_context(ctx, inner).await,
@@ -437,6 +457,7 @@ async fn persist_conversation_items_inner(
     response_storage: Arc<dyn ResponseStorage>,
     response_json: &Value,
     original_body: &ResponsesRequest,
+    tenant_key: Option<&TenantKey>,
 ) -> Result<(), String> {
     // Respect store=false: skip persistence entirely (matches official API behavior)
     if !original_body.store.unwrap_or(true) {
@@ -480,6 +501,7 @@ async fn persist_conversation_items_inner(
     let mut stored_response = build_stored_response(response_json, original_body);
     stored_response.id = response_id.clone();
     stored_response.input = Value::Array(input_items.clone());
+    stored_response.tenant_key = tenant_key.map(|key| key.as_str().to_string());
 
     response_storage
         .store_response(stored_response)
@@ -620,4 +642,32 @@ mod tests {
             json!([{"type": "input_text", "text": "hi"}])
         );
     }
+
+    #[test]
+    fn stored_response_records_request_seed_for_replay() {
+        let request = ResponsesRequest {
+            model: "m".to_string(),
+            seed: Some(42),
+            ..Default::default()
+        };
+        let stored = build_stored_response(
+            json!({"id": "resp_1", "model": "m", "system_fingerprint": "v1"}),
+            &request,
+        );
+        assert_eq!(stored.raw_response["seed"], json!(42));
+        assert_eq!(stored.raw_response["system_fingerprint"], json!("v1"));
+    }
+
+    #[test]
+    fn tenant_owns_response_rejects_other_tenants_but_not_legacy_rows() {
+        let alice = TenantKey::from("auth:alice");
+        let bob = TenantKey::from("auth:bob");
+
+        let mut stored = StoredResponse::new(None);
+        assert!(tenant_owns_response(&stored, &bob));
+
+        stored.tenant_key = Some(alice.as_str().to_string());
+        assert!(tenant_owns_response(&stored, &alice));
+        assert!(!tenant_owns_response(&stored, &bob));
+    }
 }
@@ -0,0 +1,511 @@
+//! Tenant-scoped library of versioned prompt templates.
+//!
+//! Tenants publish templates under a name at
+//! `POST /v1/prompts/{prompt_id}/versions`; each publish stores a new,
+//! immutable version numbered from 1. Admins manage any tenant's library at
+//! `/admin/tenants/{tenant}/prompts`. A template has optional instructions
+//! and messages (typically few-shot examples) whose `{{name}}` placeholders
+//! are filled from the request's prompt variables.
+//!
+//! A Responses request carrying `prompt: {id, version, variables}` is
+//! rendered by [`apply_prompt`] before routing: the template's instructions
+//! precede the request's own, its messages precede the request's input, and
+//! `prompt` is removed so upstreams never see the reference. Without a
+//! `version` the latest one is used. Prompts are only visible to the tenant
+//! that published them.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use openai_protocol::{
+    common::{PromptVariable, PromptVariableTyped},
+    responses::{ResponseInput, ResponseInputOutputItem, ResponsesRequest, StringOrContentParts},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use smg_data_connector::{PromptMessage, PromptStorage, PromptStorageError, StoredPrompt};
+use tracing::{debug, info};
+
+use crate::{routers::error, tenant::TenantKey};
+
+const MAX_ID_LEN: usize = 64;
+
+/// Combined size of one version's templates.
+const MAX_TEMPLATE_BYTES: usize = 64 * 1024;
+
+const MESSAGE_ROLES: &[&str] = &["system", "developer", "user", "assistant"];
+
+/// Body of `POST /v1/prompts/{prompt_id}/versions`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishPromptRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub instructions: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<PromptMessage>,
+}
+
+/// Query of `GET /v1/prompts/{prompt_id}`; the latest version when unset.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PromptVersionQuery {
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+/// Fill the `{{name}}` placeholders of `template` through `lookup`, which
+/// gets the trimmed name. An unclosed `{{` is copied verbatim.
+fn substitute(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(len) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..open]);
+        rendered.push_str(&lookup(rest[open + 2..open + 2 + len].trim())?);
+        rest = &rest[open + 2 + len + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn templates(prompt: &PublishPromptRequest) -> impl Iterator<Item = &str> {
+    prompt
+        .instructions
+        .as_deref()
+        .into_iter()
+        .chain(prompt.messages.iter().map(|m| m.content.as_str()))
+}
+
+fn valid_name(name: &str) -> bool {
+    name.len() <= MAX_ID_LEN
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn validate_publish(id: &str, prompt: &PublishPromptRequest) -> Result<(), String> {
+    if !valid_name(id) {
+        return Err(format!(
+            "prompt id must start with a letter and be at most {MAX_ID_LEN} letters, digits, '_' or '-'"
+        ));
+    }
+    if prompt.instructions.is_none() && prompt.messages.is_empty() {
+        return Err("prompt needs instructions or messages".to_string());
+    }
+    if let Some(message) = prompt
+        .messages
+        .iter()
+        .find(|m| !MESSAGE_ROLES.contains(&m.role.as_str()))
+    {
+        return Err(format!(
+            "message role '{}' must be one of {}",
+            message.role,
+            MESSAGE_ROLES.join(", ")
+        ));
+    }
+    if templates(prompt).map(str::len).sum::<usize>() > MAX_TEMPLATE_BYTES {
+        return Err(format!(
+            "prompt templates must total at most {MAX_TEMPLATE_BYTES} bytes"
+        ));
+    }
+    for template in templates(prompt) {
+        substitute(template, |name| {
+            if valid_name(name) {
+                Ok(String::new())
+            } else {
+                Err(format!("invalid placeholder '{{{{{name}}}}}'"))
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Names of the placeholders in a stored prompt's templates, sorted.
+fn variable_names(prompt: &StoredPrompt) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    let templates = prompt
+        .instructions
+        .as_deref()
+        .into_iter()
+        .chain(prompt.messages.iter().map(|m| m.content.as_str()));
+    for template in templates {
+        let _ = substitute(template, |name| {
+            names.insert(name.to_string());
+            Ok(String::new())
+        });
+    }
+    names.into_iter().collect()
+}
+
+fn prompt_json(prompt: &StoredPrompt) -> Value {
+    json!({
+        "object": "prompt",
+        "id": prompt.id,
+        "version": prompt.version.to_string(),
+        "description": prompt.description,
+        "instructions": prompt.instructions,
+        "messages": prompt.messages,
+        "variables": variable_names(prompt),
+        "created_at": prompt.created_at.timestamp(),
+    })
+}
+
+fn disabled() -> Response {
+    error::not_found("prompts_disabled", "Prompt templates are not enabled")
+}
+
+fn prompt_not_found(id: &str, version: Option<u32>) -> Response {
+    let message = match version {
+        Some(version) => format!("Prompt '{id}' version {version} not found"),
+        None => format!("Prompt '{id}' not found"),
+    };
+    error::not_found("prompt_not_found", message)
+}
+
+fn storage_error(e: PromptStorageError) -> Response {
+    match e {
+        PromptStorageError::TooManyPrompts(_) | PromptStorageError::TooManyVersions(_) => {
+            error::create_error(StatusCode::CONFLICT, "prompt_limit_exceeded", e.to_string())
+        }
+        PromptStorageError::StorageError(_) => {
+            error::internal_error("prompt_storage_error", e.to_string())
+        }
+    }
+}
+
+/// List the latest version of each of a tenant's prompts.
+pub async fn list_prompts(
+    storage: Option<&Arc<dyn PromptStorage>>,
+    tenant_key: &TenantKey,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.list_prompts(tenant_key.as_str()).await {
+        Ok(prompts) => Json(json!({
+            "object": "list",
+            "data": prompts.iter().map(prompt_json).collect::<Vec<_>>(),
+        }))
+        .into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Get a version of a tenant's prompt, the latest when `version` is unset.
+pub async fn get_prompt(
+    storage: Option<&Arc<dyn PromptStorage>>,
+    tenant_key: &TenantKey,
+    id: &str,
+    version: Option<u32>,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.get_prompt(tenant_key.as_str(), id, version).await {
+        Ok(Some(prompt)) => Json(prompt_json(&prompt)).into_response(),
+        Ok(None) => prompt_not_found(id, version),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// List every version of a tenant's prompt, oldest first.
+pub async fn list_versions(
+    storage: Option<&Arc<dyn PromptStorage>>,
+    tenant_key: &TenantKey,
+    id: &str,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.list_versions(tenant_key.as_str(), id).await {
+        Ok(versions) if versions.is_empty() => prompt_not_found(id, None),
+        Ok(versions) => Json(json!({
+            "object": "list",
+            "data": versions.iter().map(prompt_json).collect::<Vec<_>>(),
+        }))
+        .into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Publish a new version of a tenant's prompt, creating it if needed.
+pub async fn publish_version(
+    storage: Option<&Arc<dyn PromptStorage>>,
+    tenant_key: &TenantKey,
+    id: &str,
+    request: PublishPromptRequest,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    if let Err(reason) = validate_publish(id, &request) {
+        return error::bad_request("invalid_prompt", reason);
+    }
+
+    let prompt = StoredPrompt {
+        tenant_key: tenant_key.to_string(),
+        id: id.to_string(),
+        version: 0,
+        description: request.description,
+        instructions: request.instructions,
+        messages: request.messages,
+        created_at: Utc::now(),
+    };
+    match storage.publish_version(prompt).await {
+        Ok(stored) => {
+            info!(tenant = %tenant_key, id, version = stored.version, "Published prompt version");
+            (StatusCode::CREATED, Json(prompt_json(&stored))).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Remove every version of a tenant's prompt.
+pub async fn delete_prompt(
+    storage: Option<&Arc<dyn PromptStorage>>,
+    tenant_key: &TenantKey,
+    id: &str,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.delete_prompt(tenant_key.as_str(), id).await {
+        Ok(0) => prompt_not_found(id, None),
+        Ok(versions) => {
+            info!(tenant = %tenant_key, id, versions, "Removed prompt");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+fn variable_text(
+    variables: Option<&HashMap<String, PromptVariable>>,
+    name: &str,
+) -> Result<String, String> {
+    match variables.and_then(|variables| variables.get(name)) {
+        Some(PromptVariable::String(text))
+        | Some(PromptVariable::Typed(PromptVariableTyped::ResponseInputText { text })) => {
+            Ok(text.clone())
+        }
+        Some(PromptVariable::Typed(_)) => Err(format!("prompt variable '{name}' must be text")),
+        None => Err(format!("missing prompt variable '{name}'")),
+    }
+}
+
+/// Render `prompt` into `request`. Leaves the request untouched when a
+/// placeholder cannot be filled.
+pub fn render_prompt(
+    prompt: &StoredPrompt,
+    variables: Option<&HashMap<String, PromptVariable>>,
+    request: &mut ResponsesRequest,
+) -> Result<(), String> {
+    let lookup = |name: &str| variable_text(variables, name);
+    let instructions = prompt
+        .instructions
+        .as_deref()
+        .map(|template| substitute(template, lookup))
+        .transpose()?;
+    let messages = prompt
+        .messages
+        .iter()
+        .map(|message| {
+            Ok(ResponseInputOutputItem::SimpleInputMessage {
+                content: StringOrContentParts::String(substitute(&message.content, lookup)?),
+                role: message.role.clone(),
+                r#type: None,
+                phase: None,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if let Some(instructions) = instructions {
+        request.instructions = Some(match request.instructions.take() {
+            Some(own) if !own.is_empty() => format!("{instructions}\n\n{own}"),
+            _ => instructions,
+        });
+    }
+    if !messages.is_empty() {
+        let mut items = messages;
+        match std::mem::replace(&mut request.input, ResponseInput::Items(Vec::new())) {
+            ResponseInput::Text(text) => items.push(ResponseInputOutputItem::SimpleInputMessage {
+                content: StringOrContentParts::String(text),
+                role: "user".to_string(),
+                r#type: None,
+                phase: None,
+            }),
+            ResponseInput::Items(own) => items.extend(own),
+        }
+        request.input = ResponseInput::Items(items);
+    }
+    Ok(())
+}
+
+/// Resolve and render the request's `prompt` reference against the
+/// tenant's library, removing the reference.
+pub async fn apply_prompt(
+    storage: &dyn PromptStorage,
+    tenant_key: &TenantKey,
+    request: &mut ResponsesRequest,
+) -> Result<(), Response> {
+    let Some(reference) = request.prompt.take() else {
+        return Ok(());
+    };
+    let version = match reference.version.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(version) => Some(version.parse::<u32>().map_err(|_| {
+            error::bad_request(
+                "invalid_prompt_version",
+                format!("prompt version '{version}' must be a positive integer"),
+            )
+        })?),
+    };
+    let prompt = match storage
+        .get_prompt(tenant_key.as_str(), &reference.id, version)
+        .await
+    {
+        Ok(Some(prompt)) => prompt,
+        Ok(None) => return Err(prompt_not_found(&reference.id, version)),
+        Err(e) => return Err(storage_error(e)),
+    };
+    render_prompt(&prompt, reference.variables.as_ref(), request)
+        .map_err(|reason| error::bad_request("invalid_prompt_variables", reason))?;
+    debug!(id = %prompt.id, version = prompt.version, "Rendered prompt template");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(instructions: Option<&str>, messages: &[(&str, &str)]) -> StoredPrompt {
+        StoredPrompt {
+            tenant_key: "auth:team".to_string(),
+            id: "support".to_string(),
+            version: 1,
+            description: None,
+            instructions: instructions.map(str::to_string),
+            messages: messages
+                .iter()
+                .map(|(role, content)| PromptMessage {
+                    role: role.to_string(),
+                    content: content.to_string(),
+                })
+                .collect(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn request(value: Value) -> ResponsesRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn variables(value: Value) -> HashMap<String, PromptVariable> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_substitute_fills_placeholders() {
+        let lookup = |name: &str| Ok(name.to_uppercase());
+        assert_eq!(
+            substitute("Hi {{ name }}, {{x}}!", lookup).unwrap(),
+            "Hi NAME, X!"
+        );
+        assert_eq!(substitute("open {{ end", lookup).unwrap(), "open {{ end");
+        assert!(substitute("{{a}}", |_| Err("missing".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_render_prepends_instructions_and_examples() {
+        let prompt = stored(
+            Some("You support {{product}}."),
+            &[
+                ("user", "Reset?"),
+                ("assistant", "Open {{product}} settings."),
+            ],
+        );
+        let mut req = request(json!({
+            "model": "m",
+            "input": "How do I log in?",
+            "instructions": "Be brief.",
+        }));
+        let vars = variables(json!({
+            "product": {"type": "input_text", "text": "Acme"},
+        }));
+        render_prompt(&prompt, Some(&vars), &mut req).unwrap();
+
+        assert_eq!(
+            req.instructions.as_deref(),
+            Some("You support Acme.\n\nBe brief.")
+        );
+        let input = serde_json::to_value(&req.input).unwrap();
+        assert_eq!(
+            input,
+            json!([
+                {"role": "user", "content": "Reset?"},
+                {"role": "assistant", "content": "Open Acme settings."},
+                {"role": "user", "content": "How do I log in?"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_render_failure_leaves_request_untouched() {
+        let prompt = stored(Some("For {{product}}"), &[("user", "{{question}}")]);
+        let vars = variables(json!({
+            "product": "Acme",
+            "question": {"type": "input_image", "image_url": "https://x/y.png"},
+        }));
+        let mut req = request(json!({"model": "m", "input": "hi"}));
+        let err = render_prompt(&prompt, Some(&vars), &mut req).unwrap_err();
+        assert!(err.contains("must be text"), "{err}");
+        assert!(req.instructions.is_none());
+        assert!(matches!(req.input, ResponseInput::Text(_)));
+
+        let err = render_prompt(&prompt, None, &mut req).unwrap_err();
+        assert!(err.contains("missing prompt variable 'product'"), "{err}");
+    }
+
+    #[test]
+    fn test_publish_validation() {
+        let body =
+            |instructions: Option<&str>, messages: Vec<PromptMessage>| PublishPromptRequest {
+                description: None,
+                instructions: instructions.map(str::to_string),
+                messages,
+            };
+        let message = |role: &str| PromptMessage {
+            role: role.to_string(),
+            content: "{{q}}".to_string(),
+        };
+        assert!(validate_publish("support-v2", &body(Some("Hi {{name}}"), vec![])).is_ok());
+        assert!(validate_publish("support", &body(None, vec![message("user")])).is_ok());
+        assert!(validate_publish("1support", &body(Some("Hi"), vec![])).is_err());
+        assert!(validate_publish("support", &body(None, vec![])).is_err());
+        assert!(validate_publish("support", &body(None, vec![message("tool")])).is_err());
+        assert!(validate_publish("support", &body(Some("Hi {{ }}"), vec![])).is_err());
+        assert!(validate_publish("support", &body(Some("Hi {{a b}}"), vec![])).is_err());
+    }
+
+    #[test]
+    fn test_prompt_json_lists_variables() {
+        let prompt = stored(Some("{{b}} and {{a}}"), &[("user", "{{ b }}")]);
+        let body = prompt_json(&prompt);
+        assert_eq!(body["variables"], json!(["a", "b"]));
+        assert_eq!(body["version"], "1");
+    }
+}
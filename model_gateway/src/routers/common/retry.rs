@@ -1,6 +1,8 @@
-use std::time::Duration;
+use std::{fmt::Display, time::Duration};
 
 use axum::{http::StatusCode, response::Response};
+use bytes::Bytes;
+use futures_util::{stream, Stream, StreamExt};
 use rand::RngExt;
 use tracing::debug;
 
@@ -19,6 +21,49 @@ pub fn is_retryable_status(status: StatusCode) -> bool {
     )
 }
 
+/// Retry classification attached to an error response by the layer that
+/// understood the failure, e.g. a classified backend gRPC error. Takes
+/// precedence over the status-code heuristic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryHint {
+    pub retryable: bool,
+    /// Minimum delay the backend asked for before the next attempt
+    pub retry_after: Option<Duration>,
+}
+
+/// Check if a response should be retried: its [`RetryHint`] when present,
+/// otherwise [`is_retryable_status`].
+pub fn is_retryable_response(response: &Response) -> bool {
+    response.extensions().get::<RetryHint>().map_or_else(
+        || is_retryable_status(response.status()),
+        |hint| hint.retryable,
+    )
+}
+
+/// Hold an upstream stream until its first non-empty chunk.
+///
+/// Until a byte is forwarded the client has seen nothing but (unsent) headers,
+/// so a stream that errors or ends here is replay-safe: callers turn the
+/// `Err` into a retryable status and let [`RetryExecutor`] re-dispatch. On
+/// success the returned stream yields the held chunk followed by the rest.
+pub async fn await_first_chunk<S, E>(
+    upstream: S,
+) -> Result<impl Stream<Item = Result<Bytes, E>> + Send + 'static, String>
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: Display + Send + 'static,
+{
+    let mut upstream = Box::pin(upstream);
+    loop {
+        match upstream.next().await {
+            Some(Ok(chunk)) if chunk.is_empty() => continue,
+            Some(Ok(chunk)) => return Ok(stream::iter([Ok(chunk)]).chain(upstream)),
+            Some(Err(e)) => return Err(e.to_string()),
+            None => return Err("stream ended before the first byte".to_string()),
+        }
+    }
+}
+
 /// Computes exponential backoff with optional jitter.
 #[derive(Debug, Clone)]
 pub struct BackoffCalculator;
@@ -92,7 +137,8 @@ impl RetryExecutor {
     /// - `should_retry(&response, attempt)`: decide if the given response should be retried
     ///   (e.g., based on HTTP status). Returning false short-circuits and returns the response.
     /// - `on_backoff(delay, next_attempt)`: called before sleeping between attempts.
-    ///   Use this to record metrics.
+    ///   Use this to record metrics. The delay is raised to the response's
+    ///   [`RetryHint::retry_after`], capped at `max_backoff_ms`.
     /// - `on_exhausted()`: called when the executor has exhausted all retry attempts.
     ///
     /// Example:
@@ -141,7 +187,14 @@ impl RetryExecutor {
             }
 
             let next_attempt = attempt + 1;
-            let delay = BackoffCalculator::calculate_delay(config, attempt);
+            let mut delay = BackoffCalculator::calculate_delay(config, attempt);
+            if let Some(retry_after) = response
+                .extensions()
+                .get::<RetryHint>()
+                .and_then(|hint| hint.retry_after)
+            {
+                delay = delay.max(retry_after.min(Duration::from_millis(config.max_backoff_ms)));
+            }
             debug!(
                 attempt = attempt,
                 next_attempt = next_attempt,
@@ -177,6 +230,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_await_first_chunk_replays_held_chunk() {
+        let upstream = stream::iter([
+            Ok::<_, String>(Bytes::new()),
+            Ok(Bytes::from_static(b"data: a\n\n")),
+            Ok(Bytes::from_static(b"data: b\n\n")),
+        ]);
+        let chunks: Vec<_> = await_first_chunk(upstream)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(chunks, ["data: a\n\n", "data: b\n\n"]);
+
+        let failed = stream::iter([Ok(Bytes::new()), Err("connection reset".to_string())]);
+        assert_eq!(
+            await_first_chunk(failed).await.err().as_deref(),
+            Some("connection reset")
+        );
+        let empty = stream::iter(Vec::<Result<Bytes, String>>::new());
+        assert!(await_first_chunk(empty).await.is_err());
+    }
+
     #[test]
     fn test_backoff_no_jitter_progression_and_cap() {
         let cfg = RetryConfig {
@@ -393,4 +470,67 @@ mod tests {
         assert_eq!(backoffs.load(Ordering::Relaxed), cfg.max_retries - 1);
         assert_eq!(exhausted.load(Ordering::Relaxed), 1);
     }
+
+    fn hinted(status: StatusCode, hint: RetryHint) -> Response {
+        let mut response = (status, "fail").into_response();
+        response.extensions_mut().insert(hint);
+        response
+    }
+
+    #[test]
+    fn test_retry_hint_overrides_status() {
+        let permanent = hinted(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            RetryHint {
+                retryable: false,
+                retry_after: None,
+            },
+        );
+        assert!(!is_retryable_response(&permanent));
+
+        let transient = hinted(
+            StatusCode::CONFLICT,
+            RetryHint {
+                retryable: true,
+                retry_after: None,
+            },
+        );
+        assert!(is_retryable_response(&transient));
+
+        let plain = (StatusCode::BAD_GATEWAY, "fail").into_response();
+        assert!(is_retryable_response(&plain));
+    }
+
+    #[tokio::test]
+    async fn test_execute_response_with_retry_honors_retry_after() {
+        let cfg = RetryConfig {
+            max_retries: 2,
+            max_backoff_ms: 50,
+            ..base_retry_config()
+        };
+        let delays = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        RetryExecutor::execute_response_with_retry(
+            &cfg,
+            |_attempt| async move {
+                hinted(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    RetryHint {
+                        retryable: true,
+                        retry_after: Some(Duration::from_secs(60)),
+                    },
+                )
+            },
+            |res, _attempt| is_retryable_response(res),
+            {
+                let delays = delays.clone();
+                move |delay, _next_attempt| delays.lock().push(delay)
+            },
+            || {},
+        )
+        .await;
+
+        // Pushback raises the backoff but stays within max_backoff_ms
+        assert_eq!(*delays.lock(), vec![Duration::from_millis(50)]);
+    }
 }
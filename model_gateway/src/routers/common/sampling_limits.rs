@@ -0,0 +1,236 @@
+//! Per-model sampling parameter defaults and clamps.
+//!
+//! Operators can protect low-capacity models from pathological sampling
+//! settings (huge `max_tokens`, extreme temperatures or penalties). Unset
+//! parameters take the model's configured default; set parameters are clamped
+//! into `[min, max]`. Requests are never rejected for out-of-range values.
+//! Instead the names of the adjusted parameters are reported to the caller in
+//! the `x-smg-sampling-clamped` response header.
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::Response,
+};
+use openai_protocol::{chat::ChatCompletionRequest, completion::CompletionRequest};
+
+use crate::config::{ModelSamplingLimits, ParamLimits, SamplingLimitsConfig};
+
+static HEADER_SAMPLING_CLAMPED: HeaderName = HeaderName::from_static("x-smg-sampling-clamped");
+
+/// Apply defaults and record a clamp under `name` when the value changed.
+fn apply<T: PartialOrd + Copy>(
+    limits: &ParamLimits<T>,
+    value: &mut Option<T>,
+    name: &'static str,
+    clamped: &mut Vec<&'static str>,
+) {
+    if value.is_none() {
+        *value = limits.default;
+    }
+    let Some(current) = *value else {
+        return;
+    };
+    let mut bounded = current;
+    if let Some(min) = limits.min {
+        if bounded < min {
+            bounded = min;
+        }
+    }
+    if let Some(max) = limits.max {
+        if bounded > max {
+            bounded = max;
+        }
+    }
+    if bounded != current {
+        *value = Some(bounded);
+        if !clamped.contains(&name) {
+            clamped.push(name);
+        }
+    }
+}
+
+/// Apply the limits configured for the request's model. Returns the names of
+/// the parameters that were clamped.
+pub fn apply_to_chat(
+    config: &SamplingLimitsConfig,
+    request: &mut ChatCompletionRequest,
+) -> Vec<&'static str> {
+    let Some(limits) = config.models.get(&request.model) else {
+        return Vec::new();
+    };
+    let mut clamped = Vec::new();
+    apply_common(
+        limits,
+        &mut request.temperature,
+        &mut request.top_p,
+        &mut request.presence_penalty,
+        &mut request.frequency_penalty,
+        &mut clamped,
+    );
+    // `max_tokens` is the deprecated spelling of `max_completion_tokens`;
+    // clamp whichever the client sent and default the current one.
+    if request.max_tokens.is_some() {
+        let no_default = ParamLimits {
+            default: None,
+            ..limits.max_tokens
+        };
+        apply(
+            &no_default,
+            &mut request.max_tokens,
+            "max_tokens",
+            &mut clamped,
+        );
+    }
+    if request.max_tokens.is_none() || request.max_completion_tokens.is_some() {
+        apply(
+            &limits.max_tokens,
+            &mut request.max_completion_tokens,
+            "max_tokens",
+            &mut clamped,
+        );
+    }
+    clamped
+}
+
+/// Apply the limits configured for the request's model. Returns the names of
+/// the parameters that were clamped.
+pub fn apply_to_completion(
+    config: &SamplingLimitsConfig,
+    request: &mut CompletionRequest,
+) -> Vec<&'static str> {
+    let Some(limits) = config.models.get(&request.model) else {
+        return Vec::new();
+    };
+    let mut clamped = Vec::new();
+    apply_common(
+        limits,
+        &mut request.temperature,
+        &mut request.top_p,
+        &mut request.presence_penalty,
+        &mut request.frequency_penalty,
+        &mut clamped,
+    );
+    apply(
+        &limits.max_tokens,
+        &mut request.max_tokens,
+        "max_tokens",
+        &mut clamped,
+    );
+    clamped
+}
+
+fn apply_common(
+    limits: &ModelSamplingLimits,
+    temperature: &mut Option<f32>,
+    top_p: &mut Option<f32>,
+    presence_penalty: &mut Option<f32>,
+    frequency_penalty: &mut Option<f32>,
+    clamped: &mut Vec<&'static str>,
+) {
+    apply(&limits.temperature, temperature, "temperature", clamped);
+    apply(&limits.top_p, top_p, "top_p", clamped);
+    apply(
+        &limits.presence_penalty,
+        presence_penalty,
+        "presence_penalty",
+        clamped,
+    );
+    apply(
+        &limits.frequency_penalty,
+        frequency_penalty,
+        "frequency_penalty",
+        clamped,
+    );
+}
+
+/// Note the clamped parameters on the response, if any.
+pub fn annotate_response(mut response: Response, clamped: &[&'static str]) -> Response {
+    if clamped.is_empty() {
+        return response;
+    }
+    if let Ok(value) = HeaderValue::from_str(&clamped.join(",")) {
+        response
+            .headers_mut()
+            .insert(HEADER_SAMPLING_CLAMPED.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn config() -> SamplingLimitsConfig {
+        SamplingLimitsConfig {
+            models: HashMap::from([(
+                "small".to_string(),
+                ModelSamplingLimits {
+                    temperature: ParamLimits {
+                        default: Some(0.7),
+                        min: Some(0.0),
+                        max: Some(1.0),
+                    },
+                    max_tokens: ParamLimits {
+                        default: Some(256),
+                        min: None,
+                        max: Some(1024),
+                    },
+                    ..Default::default()
+                },
+            )]),
+        }
+    }
+
+    fn chat(json: serde_json::Value) -> ChatCompletionRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn unset_parameters_take_defaults_without_clamping() {
+        let mut request = chat(serde_json::json!({
+            "model": "small",
+            "messages": [{"role": "user", "content": "hi"}],
+        }));
+        let clamped = apply_to_chat(&config(), &mut request);
+        assert!(clamped.is_empty());
+        assert_eq!(request.temperature, Some(0.7));
+        assert_eq!(request.max_completion_tokens, Some(256));
+        assert_eq!(request.max_tokens, None);
+    }
+
+    #[test]
+    fn out_of_range_parameters_are_clamped_and_reported() {
+        let mut request = chat(serde_json::json!({
+            "model": "small",
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 1.8,
+            "max_tokens": 100_000,
+        }));
+        let clamped = apply_to_chat(&config(), &mut request);
+        assert_eq!(clamped, vec!["temperature", "max_tokens"]);
+        assert_eq!(request.temperature, Some(1.0));
+        assert_eq!(request.max_tokens, Some(1024));
+        assert_eq!(request.max_completion_tokens, None);
+
+        let response = annotate_response(Response::new(axum::body::Body::empty()), &clamped);
+        assert_eq!(
+            response.headers()["x-smg-sampling-clamped"],
+            "temperature,max_tokens"
+        );
+    }
+
+    #[test]
+    fn other_models_are_untouched() {
+        let mut request: CompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "large",
+            "prompt": "hi",
+            "temperature": 1.8,
+        }))
+        .unwrap();
+        assert!(apply_to_completion(&config(), &mut request).is_empty());
+        assert_eq!(request.temperature, Some(1.8));
+        assert_eq!(request.max_tokens, None);
+    }
+}
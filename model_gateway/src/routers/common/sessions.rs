@@ -0,0 +1,170 @@
+//! `/v1/sessions`: open, inspect and close sessions pinned to a worker.
+//!
+//! Opening a session selects a worker for the system prompt through the
+//! model's policy, records the prompt for that worker in the cache-aware
+//! tree, opens the pin and then pre-fills the prompt with a one-token chat
+//! completion routed through the pin. A failed pre-fill closes the session
+//! again and is returned to the client as is. See
+//! [`crate::policies::SessionPins`] for how pinned requests are routed.
+
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use openai_protocol::{chat::ChatCompletionRequest, common::GenerationRequest};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::{
+    app_context::AppContext,
+    middleware::TenantRequestMeta,
+    policies::{PolicyRegistry, SelectWorkerInfo},
+    routers::{common::header_utils::HEADER_SESSION_ID, error, RouterTrait},
+    worker::{Worker, WorkerType},
+};
+
+/// Body of `POST /v1/sessions`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateSessionRequest {
+    pub model: String,
+    /// System prompt shared by every request of the session.
+    pub system_prompt: String,
+}
+
+fn prefill_request(request: &CreateSessionRequest) -> Result<ChatCompletionRequest, String> {
+    serde_json::from_value(json!({
+        "model": request.model,
+        "messages": [{"role": "system", "content": request.system_prompt}],
+        "max_tokens": 1,
+        "stream": false,
+    }))
+    .map_err(|e| e.to_string())
+}
+
+/// Open a session and pre-fill its system prompt on the selected worker.
+pub async fn create_session(
+    router: &dyn RouterTrait,
+    context: &AppContext,
+    headers: &HeaderMap,
+    tenant_meta: &TenantRequestMeta,
+    request: CreateSessionRequest,
+) -> Response {
+    if request.model.trim().is_empty() || request.system_prompt.is_empty() {
+        return error::bad_request(
+            "invalid_session_request",
+            "'model' and 'system_prompt' are required",
+        );
+    }
+    let prefill = match prefill_request(&request) {
+        Ok(prefill) => prefill,
+        Err(e) => return error::bad_request("invalid_session_request", e),
+    };
+    let text = prefill.extract_text_for_routing();
+
+    let available: Vec<_> = context
+        .worker_registry
+        .get_workers_filtered(
+            Some(&request.model),
+            Some(WorkerType::Regular),
+            None,
+            None,
+            false,
+        )
+        .into_iter()
+        .filter(|w| w.is_available())
+        .collect();
+    let policy = context
+        .policy_registry
+        .get_policy_or_default(&request.model);
+    let selected = context.policy_registry.select_worker(
+        &policy,
+        &available,
+        &SelectWorkerInfo {
+            request_text: Some(&text),
+            headers: Some(headers),
+            hash_ring: context.worker_registry.get_hash_ring(&request.model),
+            ..Default::default()
+        },
+    );
+    let Some(worker) = selected.map(|idx| &available[idx]) else {
+        return error::service_unavailable(
+            "no_available_workers",
+            format!("No available workers for model '{}'", request.model),
+        );
+    };
+    PolicyRegistry::record_session_prefix(&policy, worker.as_ref(), &text);
+
+    let tenant = tenant_meta.tenant_key().as_str();
+    let sessions = context.policy_registry.sessions();
+    let Some(session) = sessions.open(tenant, &request.model, worker.url()) else {
+        return error::create_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "too_many_sessions",
+            "The gateway has reached its open session limit",
+        );
+    };
+
+    let mut prefill_headers = headers.clone();
+    if let Ok(value) = HeaderValue::from_str(&session.id) {
+        prefill_headers.insert(HEADER_SESSION_ID.clone(), value);
+    }
+    let response = router
+        .route_chat(
+            Some(&prefill_headers),
+            tenant_meta,
+            &prefill,
+            &request.model,
+        )
+        .await;
+    if !response.status().is_success() {
+        warn!(
+            session_id = %session.id,
+            status = %response.status(),
+            "Session pre-fill failed; closing session"
+        );
+        sessions.close(tenant, &session.id);
+        return response;
+    }
+
+    info!(
+        session_id = %session.id,
+        model = %request.model,
+        worker_url = %worker.url(),
+        "Opened session"
+    );
+    (StatusCode::OK, Json(session)).into_response()
+}
+
+pub fn get_session(context: &AppContext, tenant_meta: &TenantRequestMeta, id: &str) -> Response {
+    match context
+        .policy_registry
+        .sessions()
+        .get(tenant_meta.tenant_key().as_str(), id)
+    {
+        Some(session) => Json(session).into_response(),
+        None => session_not_found(id),
+    }
+}
+
+pub fn close_session(context: &AppContext, tenant_meta: &TenantRequestMeta, id: &str) -> Response {
+    if !context
+        .policy_registry
+        .sessions()
+        .close(tenant_meta.tenant_key().as_str(), id)
+    {
+        return session_not_found(id);
+    }
+    info!(session_id = id, "Closed session");
+    Json(json!({
+        "id": id,
+        "object": "session.deleted",
+        "deleted": true,
+    }))
+    .into_response()
+}
+
+fn session_not_found(id: &str) -> Response {
+    error::not_found("session_not_found", format!("Session '{id}' not found"))
+}
@@ -221,6 +221,25 @@ impl SseDecoder {
         }
     }
 
+    /// Yield the next complete SSE block as raw text, without parsing fields.
+    ///
+    /// For passthrough paths that rewrite or forward blocks verbatim. Line
+    /// endings are normalized to `\n`. Blank blocks are skipped, but comment
+    /// and other control-only blocks are returned.
+    pub fn next_block(&mut self) -> Option<Result<String, SseDecodeError>> {
+        loop {
+            let remaining = &self.buf[self.consumed..];
+            let (pos, delim_len) = find_frame_boundary(remaining)?;
+            let block = std::str::from_utf8(&remaining[..pos]).map(normalize_eol);
+            self.consumed += pos + delim_len;
+            match block {
+                Ok(block) if block.trim().is_empty() => continue,
+                Ok(block) => return Some(Ok(block)),
+                Err(e) => return Some(Err(SseDecodeError::InvalidUtf8(e))),
+            }
+        }
+    }
+
     /// Compact: shift unconsumed bytes to front. Call after draining frames.
     /// Single O(n) memmove per batch instead of per-frame.
     pub fn compact(&mut self) {
@@ -261,6 +280,23 @@ impl SseDecoder {
         owned.map(Ok)
     }
 
+    /// Raw-block counterpart of [`flush`](Self::flush): return any trailing
+    /// block not terminated by a blank line at end of stream.
+    pub fn flush_block(&mut self) -> Option<Result<String, SseDecodeError>> {
+        if find_frame_boundary(&self.buf[self.consumed..]).is_some() {
+            return Some(Err(SseDecodeError::IncompleteFlush));
+        }
+
+        let block = std::str::from_utf8(&self.buf[self.consumed..])
+            .map(|s| normalize_eol(s.trim_end_matches(['\r', '\n'])));
+        self.consumed = self.buf.len();
+        match block {
+            Ok(block) if block.trim().is_empty() => None,
+            Ok(block) => Some(Ok(block)),
+            Err(e) => Some(Err(SseDecodeError::InvalidUtf8(e))),
+        }
+    }
+
     /// Unconsumed byte count (for DoS monitoring).
     pub fn buffered_len(&self) -> usize {
         self.buf.len() - self.consumed
@@ -345,6 +381,15 @@ fn eol_len_at(buf: &[u8], pos: usize) -> usize {
     }
 }
 
+/// Normalize all SSE-spec line endings (`\r\n`, `\r`) to `\n`.
+fn normalize_eol(s: &str) -> String {
+    if s.contains('\r') {
+        s.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        s.to_owned()
+    }
+}
+
 /// Split a string into lines using all SSE-spec line endings: `\r\n`, `\r`, `\n`.
 ///
 /// `str::lines()` handles `\n` and `\r\n` but not bare `\r`. The SSE spec
@@ -1038,4 +1083,41 @@ mod tests {
     fn test_find_frame_boundary_empty() {
         assert_eq!(find_frame_boundary(b""), None);
     }
+
+    // --- Raw block tests ---
+
+    #[test]
+    fn test_next_block_reassembles_split_frames() {
+        let mut dec = SseDecoder::new();
+        dec.push(b"event: a\r\ndata: {\"x\"").unwrap();
+        assert!(dec.next_block().is_none());
+        dec.push(b":1}\r\n\r\n\n\n: keep-alive\n\n").unwrap();
+
+        assert_eq!(
+            dec.next_block().unwrap().unwrap(),
+            "event: a\ndata: {\"x\":1}"
+        );
+        assert_eq!(dec.next_block().unwrap().unwrap(), ": keep-alive");
+        assert!(dec.next_block().is_none());
+    }
+
+    #[test]
+    fn test_next_block_handles_split_multibyte_utf8() {
+        let text = "data: héllo\n\n".as_bytes();
+        let split = text.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        let mut dec = SseDecoder::new();
+        dec.push(&text[..split]).unwrap();
+        assert!(dec.next_block().is_none());
+        dec.push(&text[split..]).unwrap();
+        assert_eq!(dec.next_block().unwrap().unwrap(), "data: héllo");
+    }
+
+    #[test]
+    fn test_flush_block_returns_unterminated_tail() {
+        let mut dec = SseDecoder::new();
+        dec.push(b"data: tail\r\n").unwrap();
+        assert!(dec.next_block().is_none());
+        assert_eq!(dec.flush_block().unwrap().unwrap(), "data: tail");
+        assert!(dec.flush_block().is_none());
+    }
 }
@@ -0,0 +1,325 @@
+//! Resilient client for consuming upstream SSE streams.
+//!
+//! Wraps an upstream `reqwest::Response` body in the shared [`SseDecoder`]
+//! so every provider path (OpenAI, Anthropic, Gemini) gets the same
+//! edge-case handling:
+//!
+//! - partial frames and multi-byte UTF-8 split across chunks are reassembled
+//! - a stream that goes silent for longer than the idle timeout fails
+//!   instead of hanging the downstream client
+//! - when the caller supplies a reconnect function and the upstream has
+//!   sent event ids, transport failures reconnect with `Last-Event-ID`
+//!   and resume after the last delivered event
+
+use std::{future::Future, time::Duration};
+
+use futures_util::{future::BoxFuture, stream::BoxStream, StreamExt};
+use tracing::{debug, warn};
+
+use super::{
+    retry::BackoffCalculator,
+    sse::{parse_block, SseDecodeError, SseDecoder, SseFrame},
+};
+use crate::config::types::RetryConfig;
+
+/// Default maximum buffered bytes for a single upstream frame (1 MB).
+const DEFAULT_MAX_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Default time to wait for the next upstream chunk before giving up.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+type UpstreamBody = BoxStream<'static, Result<bytes::Bytes, reqwest::Error>>;
+type ReconnectFn =
+    Box<dyn FnMut(String) -> BoxFuture<'static, Result<reqwest::Response, String>> + Send>;
+
+/// Configuration for [`SseClient`].
+#[derive(Debug, Clone)]
+pub struct SseClientConfig {
+    /// Maximum bytes buffered while waiting for a frame delimiter.
+    pub max_buffer_size: usize,
+    /// Fail the stream if no bytes arrive for this long. `None` disables it.
+    pub idle_timeout: Option<Duration>,
+    /// Reconnect attempts and backoff. Only used with
+    /// [`SseClient::with_reconnect`].
+    pub reconnect: RetryConfig,
+}
+
+impl Default for SseClientConfig {
+    fn default() -> Self {
+        Self {
+            max_buffer_size: DEFAULT_MAX_BUFFER_SIZE,
+            idle_timeout: Some(DEFAULT_IDLE_TIMEOUT),
+            reconnect: RetryConfig {
+                max_retries: 3,
+                initial_backoff_ms: 100,
+                max_backoff_ms: 2000,
+                ..RetryConfig::default()
+            },
+        }
+    }
+}
+
+/// Errors surfaced while consuming an upstream SSE stream.
+#[derive(Debug, thiserror::Error)]
+pub enum SseClientError {
+    /// Reading the upstream body failed and the stream could not be resumed.
+    #[error("stream read error: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// No bytes arrived within the idle timeout.
+    #[error("upstream stream idle for more than {}s", .0.as_secs())]
+    IdleTimeout(Duration),
+    /// The byte stream was not valid SSE.
+    #[error("SSE decode error: {0}")]
+    Decode(#[from] SseDecodeError),
+}
+
+/// Pull-based reader over an upstream SSE response.
+pub struct SseClient {
+    body: UpstreamBody,
+    decoder: SseDecoder,
+    config: SseClientConfig,
+    last_event_id: Option<String>,
+    reconnect: Option<ReconnectFn>,
+    reconnect_attempts: u32,
+    ended: bool,
+}
+
+impl SseClient {
+    pub fn new(response: reqwest::Response, config: SseClientConfig) -> Self {
+        Self::from_body(response.bytes_stream().boxed(), config)
+    }
+
+    fn from_body(body: UpstreamBody, config: SseClientConfig) -> Self {
+        Self {
+            body,
+            decoder: SseDecoder::with_max_size(config.max_buffer_size),
+            config,
+            last_event_id: None,
+            reconnect: None,
+            reconnect_attempts: 0,
+            ended: false,
+        }
+    }
+
+    /// Enable resumption for upstreams that support `Last-Event-ID`.
+    ///
+    /// `reconnect` receives the last seen event id and must re-issue the
+    /// request with it (typically as the `Last-Event-ID` header). Streams
+    /// that never sent an `id:` field are not resumed.
+    pub fn with_reconnect<F, Fut>(mut self, mut reconnect: F) -> Self
+    where
+        F: FnMut(String) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<reqwest::Response, String>> + Send + 'static,
+    {
+        self.reconnect = Some(Box::new(move |last_event_id| {
+            Box::pin(reconnect(last_event_id))
+        }));
+        self
+    }
+
+    /// The `id:` of the most recent event delivered, if the upstream sends ids.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Next raw SSE block (see [`SseDecoder::next_block`]), or `None` once the
+    /// upstream has ended and any trailing unterminated block was returned.
+    pub async fn next_block(&mut self) -> Option<Result<String, SseClientError>> {
+        loop {
+            if let Some(block) = self.decoder.next_block() {
+                return Some(self.deliver(block));
+            }
+            if self.ended {
+                return None;
+            }
+            self.decoder.compact();
+
+            let next = match self.config.idle_timeout {
+                Some(idle) => match tokio::time::timeout(idle, self.body.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        if self.try_reconnect("idle timeout").await {
+                            continue;
+                        }
+                        return Some(Err(SseClientError::IdleTimeout(idle)));
+                    }
+                },
+                None => self.body.next().await,
+            };
+
+            match next {
+                Some(Ok(chunk)) => {
+                    if let Err(e) = self.decoder.push(&chunk) {
+                        return Some(Err(e.into()));
+                    }
+                }
+                Some(Err(e)) => {
+                    if self.try_reconnect(&e.to_string()).await {
+                        continue;
+                    }
+                    return Some(Err(e.into()));
+                }
+                None => {
+                    self.ended = true;
+                    if let Some(block) = self.decoder.flush_block() {
+                        return Some(self.deliver(block));
+                    }
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Next parsed frame, skipping control-only blocks (comments, id/retry).
+    pub async fn next_frame(&mut self) -> Option<Result<SseFrame<'static>, SseClientError>> {
+        loop {
+            match self.next_block().await? {
+                Ok(block) => {
+                    if let Some(frame) = parse_block(&block) {
+                        return Some(Ok(frame.into_owned()));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    fn deliver(&mut self, block: Result<String, SseDecodeError>) -> Result<String, SseClientError> {
+        let block = block?;
+        if let Some(id) = event_id(&block) {
+            self.last_event_id = Some(id.to_string());
+        }
+        self.reconnect_attempts = 0;
+        Ok(block)
+    }
+
+    /// Re-open the stream from the last event id. Returns false if resumption
+    /// is unsupported or the attempts are exhausted.
+    async fn try_reconnect(&mut self, reason: &str) -> bool {
+        let (Some(reconnect), Some(last_event_id)) =
+            (self.reconnect.as_mut(), self.last_event_id.clone())
+        else {
+            return false;
+        };
+        while self.reconnect_attempts < self.config.reconnect.max_retries {
+            let delay =
+                BackoffCalculator::calculate_delay(&self.config.reconnect, self.reconnect_attempts);
+            self.reconnect_attempts += 1;
+            debug!(
+                attempt = self.reconnect_attempts,
+                last_event_id = %last_event_id,
+                delay_ms = delay.as_millis() as u64,
+                "Upstream SSE stream interrupted ({reason}), reconnecting"
+            );
+            tokio::time::sleep(delay).await;
+
+            match reconnect(last_event_id.clone()).await {
+                Ok(response) if response.status().is_success() => {
+                    // Any partial frame belongs to the interrupted connection;
+                    // the upstream replays from after `last_event_id`.
+                    self.body = response.bytes_stream().boxed();
+                    self.decoder = SseDecoder::with_max_size(self.config.max_buffer_size);
+                    return true;
+                }
+                Ok(response) => {
+                    warn!(
+                        status = %response.status(),
+                        "Upstream SSE reconnect rejected"
+                    );
+                }
+                Err(e) => warn!(error = %e, "Upstream SSE reconnect failed"),
+            }
+        }
+        false
+    }
+}
+
+/// The `id:` field of a raw SSE block, if any. Per spec, ids containing NUL
+/// are ignored.
+fn event_id(block: &str) -> Option<&str> {
+    block
+        .lines()
+        .filter_map(|line| line.strip_prefix("id:"))
+        .map(|value| value.strip_prefix(' ').unwrap_or(value))
+        .filter(|id| !id.contains('\0'))
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+
+    use super::*;
+
+    fn body(chunks: Vec<&'static str>) -> UpstreamBody {
+        stream::iter(
+            chunks
+                .into_iter()
+                .map(|c| Ok(Bytes::from_static(c.as_bytes()))),
+        )
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn test_reassembles_blocks_and_tracks_event_id() {
+        let mut client = SseClient::from_body(
+            body(vec![
+                "id: 1\ndata: {\"a\"",
+                ":1}\n\n: ping\n\nid: 2\nevent: e\ndata: x\n\ndata: tail",
+            ]),
+            SseClientConfig::default(),
+        );
+
+        let frame = client.next_frame().await.unwrap().unwrap();
+        assert_eq!(frame.data, "{\"a\":1}");
+        assert_eq!(client.last_event_id(), Some("1"));
+
+        let frame = client.next_frame().await.unwrap().unwrap();
+        assert_eq!(frame.event_type.as_deref(), Some("e"));
+        assert_eq!(client.last_event_id(), Some("2"));
+
+        let frame = client.next_frame().await.unwrap().unwrap();
+        assert_eq!(frame.data, "tail");
+        assert!(client.next_frame().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_raw_blocks_include_comments() {
+        let mut client = SseClient::from_body(
+            body(vec![": keep-alive\r\n\r\ndata: 1\r\n\r\n"]),
+            SseClientConfig::default(),
+        );
+        assert_eq!(client.next_block().await.unwrap().unwrap(), ": keep-alive");
+        assert_eq!(client.next_block().await.unwrap().unwrap(), "data: 1");
+        assert!(client.next_block().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_timeout() {
+        let stalled = stream::iter([Ok(Bytes::from_static(b"data: 1\n\n"))])
+            .chain(stream::pending())
+            .boxed();
+        let mut client = SseClient::from_body(
+            stalled,
+            SseClientConfig {
+                idle_timeout: Some(Duration::from_secs(5)),
+                ..SseClientConfig::default()
+            },
+        );
+
+        assert!(client.next_block().await.unwrap().is_ok());
+        assert!(matches!(
+            client.next_block().await,
+            Some(Err(SseClientError::IdleTimeout(_)))
+        ));
+    }
+
+    #[test]
+    fn test_event_id_parsing() {
+        assert_eq!(event_id("id: 7\ndata: x"), Some("7"));
+        assert_eq!(event_id("id:8"), Some("8"));
+        assert_eq!(event_id("data: x"), None);
+        assert_eq!(event_id("id: a\0b\ndata: x"), None);
+    }
+}
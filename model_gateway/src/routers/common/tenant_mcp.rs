@@ -0,0 +1,378 @@
+//! Tenant-scoped MCP server registry.
+//!
+//! Tenants register MCP servers under a label at `/v1/mcp/servers/{label}`;
+//! admins manage any tenant's servers at
+//! `/admin/tenants/{tenant}/mcp/servers/{label}`. Registrations are kept in
+//! the data connector and are not connected when registered. A Responses
+//! request of the tenant that names the label in an `mcp` tool without a
+//! `server_url` has the registered URL, credentials and headers filled in by
+//! [`apply_tenant_mcp_servers`], and the per-request MCP setup connects the
+//! server on first use.
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use openai_protocol::responses::{McpAllowedTools, ResponseTool, ResponsesRequest};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use smg_data_connector::{TenantMcpServer, TenantMcpServerStorage, TenantMcpServerStorageError};
+use tracing::{debug, info, warn};
+
+use crate::{routers::error, tenant::TenantKey};
+
+const MAX_LABEL_LEN: usize = 64;
+
+/// Body of `PUT /v1/mcp/servers/{label}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterMcpServerRequest {
+    pub server_url: String,
+    #[serde(default)]
+    pub authorization: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Tools exposed to the model; all tools when unset.
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+fn validate_registration(label: &str, request: &RegisterMcpServerRequest) -> Result<(), String> {
+    // Same shape as a Responses `server_label`, so every label is usable.
+    let valid_label = label.len() <= MAX_LABEL_LEN
+        && label.starts_with(|c: char| c.is_ascii_alphabetic())
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_label {
+        return Err(format!(
+            "label must start with a letter and be at most {MAX_LABEL_LEN} letters, digits, '_' or '-'"
+        ));
+    }
+    let url = request.server_url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err("server_url must be an http:// or https:// URL".to_string());
+    }
+    Ok(())
+}
+
+/// Registration as returned to clients. Credentials and header values are
+/// never echoed back.
+fn server_json(server: &TenantMcpServer) -> Value {
+    let mut header_names: Vec<&str> = server.headers.keys().map(String::as_str).collect();
+    header_names.sort_unstable();
+    json!({
+        "object": "mcp_server",
+        "label": server.label,
+        "server_url": server.server_url,
+        "has_authorization": server.authorization.is_some(),
+        "header_names": header_names,
+        "allowed_tools": server.allowed_tools,
+        "created_at": server.created_at.timestamp(),
+        "updated_at": server.updated_at.timestamp(),
+    })
+}
+
+fn disabled() -> Response {
+    error::not_found(
+        "tenant_mcp_servers_disabled",
+        "Tenant MCP servers are not enabled",
+    )
+}
+
+fn server_not_found(label: &str) -> Response {
+    error::not_found(
+        "mcp_server_not_found",
+        format!("MCP server '{label}' not found"),
+    )
+}
+
+fn storage_error(e: TenantMcpServerStorageError) -> Response {
+    match e {
+        TenantMcpServerStorageError::LimitExceeded(_) => error::create_error(
+            StatusCode::CONFLICT,
+            "mcp_server_limit_exceeded",
+            e.to_string(),
+        ),
+        TenantMcpServerStorageError::StorageError(_) => {
+            error::internal_error("mcp_server_storage_error", e.to_string())
+        }
+    }
+}
+
+/// List a tenant's registered servers.
+pub async fn list_servers(
+    storage: Option<&Arc<dyn TenantMcpServerStorage>>,
+    tenant_key: &TenantKey,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.list_servers(tenant_key.as_str()).await {
+        Ok(servers) => Json(json!({
+            "object": "list",
+            "data": servers.iter().map(server_json).collect::<Vec<_>>(),
+        }))
+        .into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Get one of a tenant's registered servers.
+pub async fn get_server(
+    storage: Option<&Arc<dyn TenantMcpServerStorage>>,
+    tenant_key: &TenantKey,
+    label: &str,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.get_server(tenant_key.as_str(), label).await {
+        Ok(Some(server)) => Json(server_json(&server)).into_response(),
+        Ok(None) => server_not_found(label),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Register or replace a tenant's server under `label`.
+pub async fn put_server(
+    storage: Option<&Arc<dyn TenantMcpServerStorage>>,
+    tenant_key: &TenantKey,
+    label: &str,
+    request: RegisterMcpServerRequest,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    if let Err(reason) = validate_registration(label, &request) {
+        return error::bad_request("invalid_mcp_server", reason);
+    }
+
+    let created_at = match storage.get_server(tenant_key.as_str(), label).await {
+        Ok(existing) => existing.map_or_else(Utc::now, |s| s.created_at),
+        Err(e) => return storage_error(e),
+    };
+    let server = TenantMcpServer {
+        tenant_key: tenant_key.to_string(),
+        label: label.to_string(),
+        server_url: request.server_url.trim().to_string(),
+        authorization: request.authorization,
+        headers: request.headers,
+        allowed_tools: request.allowed_tools,
+        created_at,
+        updated_at: Utc::now(),
+    };
+    let body = server_json(&server);
+    match storage.put_server(server).await {
+        Ok(previous) => {
+            info!(tenant = %tenant_key, label, "Registered tenant MCP server");
+            let status = if previous.is_some() {
+                StatusCode::OK
+            } else {
+                StatusCode::CREATED
+            };
+            (status, Json(body)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Remove a tenant's server.
+pub async fn delete_server(
+    storage: Option<&Arc<dyn TenantMcpServerStorage>>,
+    tenant_key: &TenantKey,
+    label: &str,
+) -> Response {
+    let Some(storage) = storage else {
+        return disabled();
+    };
+    match storage.delete_server(tenant_key.as_str(), label).await {
+        Ok(Some(_)) => {
+            info!(tenant = %tenant_key, label, "Removed tenant MCP server");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(None) => server_not_found(label),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// Narrow the request's allowed tools to those the registration exposes.
+fn restrict_allowed_tools(
+    requested: Option<McpAllowedTools>,
+    registered: &[String],
+) -> McpAllowedTools {
+    let keep = |names: Vec<String>| -> Vec<String> {
+        names
+            .into_iter()
+            .filter(|name| registered.contains(name))
+            .collect()
+    };
+    match requested {
+        None => McpAllowedTools::List(registered.to_vec()),
+        Some(McpAllowedTools::List(names)) => McpAllowedTools::List(keep(names)),
+        Some(McpAllowedTools::Filter(mut filter)) => {
+            filter.tool_names = Some(match filter.tool_names.take() {
+                Some(names) => keep(names),
+                None => registered.to_vec(),
+            });
+            McpAllowedTools::Filter(filter)
+        }
+    }
+}
+
+/// Fill in `mcp` tools that name one of the tenant's registered servers.
+///
+/// Only tools without a `server_url` or `connector_id` are resolved, so a
+/// registration also takes precedence over a static server of the same
+/// label. Request headers win over registered ones, and registered
+/// `allowed_tools` bound what the request may expose.
+pub fn resolve_tenant_mcp_tools(request: &mut ResponsesRequest, servers: &[TenantMcpServer]) {
+    let Some(tools) = request.tools.as_mut() else {
+        return;
+    };
+    for tool in tools {
+        let ResponseTool::Mcp(mcp) = tool else {
+            continue;
+        };
+        if mcp
+            .server_url
+            .as_deref()
+            .is_some_and(|u| !u.trim().is_empty())
+            || mcp.connector_id.is_some()
+        {
+            continue;
+        }
+        let Some(server) = servers.iter().find(|s| s.label == mcp.server_label) else {
+            continue;
+        };
+        mcp.server_url = Some(server.server_url.clone());
+        if mcp.authorization.is_none() {
+            mcp.authorization.clone_from(&server.authorization);
+        }
+        if !server.headers.is_empty() {
+            let mut headers = server.headers.clone();
+            headers.extend(mcp.headers.take().unwrap_or_default());
+            mcp.headers = Some(headers);
+        }
+        if let Some(registered) = &server.allowed_tools {
+            mcp.allowed_tools = Some(restrict_allowed_tools(mcp.allowed_tools.take(), registered));
+        }
+        debug!(label = %server.label, "Resolved MCP tool to tenant-registered server");
+    }
+}
+
+/// Resolve `request`'s MCP tools against the servers `tenant_key` registered.
+///
+/// A storage failure leaves the request unchanged; its label-only tools then
+/// fall back to static servers.
+pub async fn apply_tenant_mcp_servers(
+    storage: &dyn TenantMcpServerStorage,
+    tenant_key: &TenantKey,
+    request: &mut ResponsesRequest,
+) {
+    let names_unresolved_label = request.tools.as_deref().is_some_and(|tools| {
+        tools.iter().any(|tool| {
+            matches!(tool, ResponseTool::Mcp(mcp)
+                if mcp.server_url.as_deref().is_none_or(|u| u.trim().is_empty())
+                    && mcp.connector_id.is_none())
+        })
+    });
+    if !names_unresolved_label {
+        return;
+    }
+    match storage.list_servers(tenant_key.as_str()).await {
+        Ok(servers) => resolve_tenant_mcp_tools(request, &servers),
+        Err(e) => warn!(tenant = %tenant_key, error = %e, "Failed to load tenant MCP servers"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered(allowed_tools: Option<Vec<String>>) -> TenantMcpServer {
+        TenantMcpServer {
+            tenant_key: "auth:team".to_string(),
+            label: "crm".to_string(),
+            server_url: "https://crm.internal/mcp".to_string(),
+            authorization: Some("secret".to_string()),
+            headers: HashMap::from([("x-env".to_string(), "prod".to_string())]),
+            allowed_tools,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn request(tools: Value) -> ResponsesRequest {
+        serde_json::from_value(json!({"model": "m", "input": "hi", "tools": tools})).unwrap()
+    }
+
+    fn mcp_tool(request: &ResponsesRequest, i: usize) -> &openai_protocol::responses::McpTool {
+        match &request.tools.as_ref().unwrap()[i] {
+            ResponseTool::Mcp(mcp) => mcp,
+            other => panic!("expected mcp tool, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_label_only_tools_resolve_to_registration() {
+        let mut req = request(json!([
+            {"type": "mcp", "server_label": "crm", "headers": {"x-env": "staging"}},
+            {"type": "mcp", "server_label": "other"},
+            {"type": "mcp", "server_label": "crm", "server_url": "https://mine/mcp"},
+        ]));
+        resolve_tenant_mcp_tools(&mut req, &[registered(None)]);
+
+        let crm = mcp_tool(&req, 0);
+        assert_eq!(crm.server_url.as_deref(), Some("https://crm.internal/mcp"));
+        assert_eq!(crm.authorization.as_deref(), Some("secret"));
+        assert_eq!(crm.headers.as_ref().unwrap()["x-env"], "staging");
+        assert!(mcp_tool(&req, 1).server_url.is_none());
+        assert_eq!(
+            mcp_tool(&req, 2).server_url.as_deref(),
+            Some("https://mine/mcp")
+        );
+    }
+
+    #[test]
+    fn test_registered_allowed_tools_bound_the_request() {
+        let server = registered(Some(vec!["lookup".to_string(), "search".to_string()]));
+
+        let mut req = request(json!([{"type": "mcp", "server_label": "crm"}]));
+        resolve_tenant_mcp_tools(&mut req, std::slice::from_ref(&server));
+        assert_eq!(
+            mcp_tool(&req, 0).allowed_tools,
+            Some(McpAllowedTools::List(vec![
+                "lookup".to_string(),
+                "search".to_string()
+            ]))
+        );
+
+        let mut req = request(json!([
+            {"type": "mcp", "server_label": "crm", "allowed_tools": ["search", "delete"]}
+        ]));
+        resolve_tenant_mcp_tools(&mut req, &[server]);
+        assert_eq!(
+            mcp_tool(&req, 0).allowed_tools,
+            Some(McpAllowedTools::List(vec!["search".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_registration_validation() {
+        let body = |url: &str| RegisterMcpServerRequest {
+            server_url: url.to_string(),
+            authorization: None,
+            headers: HashMap::new(),
+            allowed_tools: None,
+        };
+        assert!(validate_registration("crm-prod_1", &body("https://crm/mcp")).is_ok());
+        assert!(validate_registration("crm", &body("ftp://crm")).is_err());
+        assert!(validate_registration("a/b", &body("https://crm/mcp")).is_err());
+        assert!(validate_registration("", &body("https://crm/mcp")).is_err());
+        assert!(validate_registration("1crm", &body("https://crm/mcp")).is_err());
+    }
+}
@@ -0,0 +1,540 @@
+//! Normalization of tool calls and results in loaded history.
+//!
+//! Conversation items can be written through `/v1/conversations/{id}/items`
+//! in whatever shape the client's API used, so replayed history mixes the
+//! Responses shapes with Anthropic Messages and chat completion ones:
+//!
+//! - Anthropic `tool_use` / `tool_result` blocks inside message content, or
+//!   as items of their own, and `text` blocks instead of `input_text` /
+//!   `output_text`.
+//! - Chat assistant messages with `tool_calls` and `role: "tool"` messages
+//!   answering them by `tool_call_id`.
+//! - Message content as a plain string, tool results as content arrays, and
+//!   tool arguments as JSON objects rather than strings.
+//!
+//! [`normalize_history`] rewrites all of these into Responses `message`,
+//! `function_call` and `function_call_output` items, in order, before the
+//! history is deserialized. Ids are reconciled deterministically so every
+//! output names its call: a call without `call_id` uses its `id`, or else
+//! `call_smg_{hash}` of its name and arguments, which does not depend on
+//! how much history was loaded, and an output without one answers the
+//! oldest call still unanswered. No API accepts an output that answers no
+//! call in the history, so such outputs are kept as user text instead. A
+//! call without a name cannot be replayed and fails the whole history.
+//! Items already in the Responses shape pass through unchanged, so
+//! normalizing twice is the same as normalizing once.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write as _,
+};
+
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+const FUNCTION_CALL: &str = "function_call";
+const FUNCTION_CALL_OUTPUT: &str = "function_call_output";
+
+/// Rewrite foreign tool call and result shapes in `items` into Responses
+/// items, pairing every output with its call. Fails on a tool call without
+/// a name.
+pub fn normalize_history(items: Vec<Value>) -> Result<Vec<Value>, String> {
+    let mut normalizer = Normalizer::default();
+    for item in items {
+        normalizer.push(item)?;
+    }
+    Ok(normalizer.items)
+}
+
+#[derive(Default)]
+struct Normalizer {
+    items: Vec<Value>,
+    /// Call ids not yet answered, oldest first.
+    pending: VecDeque<String>,
+    /// Every call id seen so far.
+    calls: HashSet<String>,
+    /// How often each synthesized call id was handed out.
+    synthesized: HashMap<String, usize>,
+}
+
+fn str_field<'a>(obj: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    obj.get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+/// Text of a tool result: strings as they are, content blocks' text joined
+/// by newlines, anything else as JSON.
+fn output_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                Value::String(text) => Some(text.as_str()),
+                block => block.get("text").and_then(Value::as_str),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// `{prefix}{hash}` of `fields`, stable across loads of the same history.
+fn hashed_id(prefix: &str, fields: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for field in fields {
+        hasher.update(field.as_bytes());
+        hasher.update([0]);
+    }
+    let hash = hasher.finalize();
+    let mut id = String::from(prefix);
+    for byte in &hash[..8] {
+        let _ = write!(id, "{byte:02x}");
+    }
+    id
+}
+
+/// `call_smg_{hash}` of a call's name and arguments; repeats of the same
+/// call get a `_{n}` suffix.
+fn synthesized_call_id(
+    name: &str,
+    arguments: &str,
+    synthesized: &mut HashMap<String, usize>,
+) -> String {
+    let mut call_id = hashed_id("call_smg_", &[name, arguments]);
+    let seen = synthesized.entry(call_id.clone()).or_default();
+    *seen += 1;
+    if *seen > 1 {
+        call_id = format!("{call_id}_{seen}");
+    }
+    call_id
+}
+
+fn text_part(role: &str, text: &str) -> Value {
+    if role == "assistant" {
+        json!({"type": "output_text", "text": text, "annotations": []})
+    } else {
+        json!({"type": "input_text", "text": text})
+    }
+}
+
+/// Whether a message carries anything [`Normalizer::push_message`] rewrites.
+fn is_foreign_message(obj: &Map<String, Value>) -> bool {
+    if str_field(obj, "role") == Some("tool") || obj.contains_key("tool_calls") {
+        return true;
+    }
+    match obj.get("content") {
+        Some(Value::String(_)) => true,
+        Some(Value::Array(blocks)) => blocks.iter().any(|block| {
+            matches!(
+                block.get("type").and_then(Value::as_str),
+                Some("text" | "tool_use" | "tool_result" | "thinking" | "redacted_thinking")
+            )
+        }),
+        _ => false,
+    }
+}
+
+impl Normalizer {
+    fn push(&mut self, item: Value) -> Result<(), String> {
+        let Value::Object(obj) = item else {
+            self.items.push(item);
+            return Ok(());
+        };
+        match obj.get("type").and_then(Value::as_str).unwrap_or("message") {
+            "message" => return self.push_message(obj),
+            FUNCTION_CALL | "tool_use" => return self.push_call(&obj),
+            FUNCTION_CALL_OUTPUT | "tool_result" => self.push_output(&obj),
+            _ => self.items.push(Value::Object(obj)),
+        }
+        Ok(())
+    }
+
+    /// Push a call: a Responses `function_call`, an Anthropic `tool_use` or a
+    /// chat `tool_calls` entry.
+    fn push_call(&mut self, obj: &Map<String, Value>) -> Result<(), String> {
+        let own_call_id = str_field(obj, "call_id");
+        let function = obj.get("function").and_then(Value::as_object);
+        let name = str_field(obj, "name")
+            .or_else(|| function.and_then(|f| str_field(f, "name")))
+            .ok_or_else(|| match own_call_id.or_else(|| str_field(obj, "id")) {
+                Some(id) => format!("Tool call '{id}' in history has no name"),
+                None => "A tool call in history has no name".to_string(),
+            })?;
+        let arguments = obj
+            .get("arguments")
+            .or_else(|| obj.get("input"))
+            .or_else(|| function.and_then(|f| f.get("arguments")));
+        let arguments = match arguments {
+            Some(Value::String(arguments)) => arguments.clone(),
+            None | Some(Value::Null) => "{}".to_string(),
+            Some(other) => other.to_string(),
+        };
+        let call_id = own_call_id.or_else(|| str_field(obj, "id")).map_or_else(
+            || synthesized_call_id(name, &arguments, &mut self.synthesized),
+            str::to_string,
+        );
+
+        let mut call = Map::new();
+        call.insert("type".to_string(), json!(FUNCTION_CALL));
+        // A Responses item id only exists alongside its own `call_id`.
+        if let (Some(_), Some(id)) = (own_call_id, str_field(obj, "id")) {
+            call.insert("id".to_string(), json!(id));
+        }
+        call.insert("call_id".to_string(), json!(call_id));
+        call.insert("name".to_string(), json!(name));
+        call.insert("arguments".to_string(), json!(arguments));
+        for key in ["output", "status"] {
+            if let Some(value) = obj.get(key).filter(|v| !v.is_null()) {
+                call.insert(key.to_string(), value.clone());
+            }
+        }
+        self.calls.insert(call_id.clone());
+        self.pending.push_back(call_id);
+        self.items.push(Value::Object(call));
+        Ok(())
+    }
+
+    /// Push a result: a Responses `function_call_output`, an Anthropic
+    /// `tool_result` or a chat `tool` message.
+    fn push_output(&mut self, obj: &Map<String, Value>) {
+        let call_id = ["call_id", "tool_call_id", "tool_use_id"]
+            .into_iter()
+            .find_map(|key| str_field(obj, key))
+            .map(str::to_string);
+        let output = match obj.get("output") {
+            Some(output) => output_text(Some(output)),
+            None => output_text(obj.get("content")),
+        };
+        let answered = match &call_id {
+            Some(call_id) if self.calls.contains(call_id) => {
+                self.pending.retain(|pending| pending != call_id);
+                Some(call_id.clone())
+            }
+            Some(_) => None,
+            None => self.pending.pop_front(),
+        };
+        let Some(call_id) = answered else {
+            warn!(
+                call_id = call_id.as_deref(),
+                "Tool result answers no call in history, keeping it as text"
+            );
+            self.push_orphaned_output(obj, call_id.as_deref(), &output);
+            return;
+        };
+
+        let mut result = Map::new();
+        result.insert("type".to_string(), json!(FUNCTION_CALL_OUTPUT));
+        if str_field(obj, "call_id").is_some() {
+            if let Some(id) = str_field(obj, "id") {
+                result.insert("id".to_string(), json!(id));
+            }
+        }
+        result.insert("call_id".to_string(), json!(call_id));
+        result.insert("output".to_string(), json!(output));
+        if let Some(status) = obj.get("status").filter(|v| !v.is_null()) {
+            result.insert("status".to_string(), status.clone());
+        }
+        self.items.push(Value::Object(result));
+    }
+
+    /// Push a result that answers no call as a user message.
+    fn push_orphaned_output(
+        &mut self,
+        obj: &Map<String, Value>,
+        call_id: Option<&str>,
+        output: &str,
+    ) {
+        let text = match call_id {
+            Some(call_id) => format!("Tool result for {call_id}:\n{output}"),
+            None => format!("Tool result:\n{output}"),
+        };
+        let id =
+            str_field(obj, "id").map_or_else(|| hashed_id("msg_smg_", &[&text]), str::to_string);
+        let mut message = Map::new();
+        message.insert("id".to_string(), json!(id));
+        message.insert("type".to_string(), json!("message"));
+        message.insert("role".to_string(), json!("user"));
+        message.insert("content".to_string(), json!([text_part("user", &text)]));
+        self.items.push(Value::Object(message));
+    }
+
+    fn push_message(&mut self, mut obj: Map<String, Value>) -> Result<(), String> {
+        if !obj.contains_key("role") {
+            obj.insert("role".to_string(), json!("user"));
+        }
+        if !is_foreign_message(&obj) {
+            self.items.push(Value::Object(obj));
+            return Ok(());
+        }
+        let role = str_field(&obj, "role").unwrap_or("user").to_string();
+        if role == "tool" {
+            self.push_output(&obj);
+            return Ok(());
+        }
+
+        let blocks = match obj.remove("content") {
+            Some(Value::String(text)) if !text.is_empty() => vec![text_part(&role, &text)],
+            Some(Value::Array(blocks)) => blocks,
+            _ => Vec::new(),
+        };
+        let tool_calls = obj.remove("tool_calls");
+
+        // Tool blocks split the message; each run of other blocks between
+        // them becomes a message of its own.
+        let mut segments = 0;
+        let mut parts = Vec::new();
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => {
+                    let text = block
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    parts.push(text_part(&role, text));
+                }
+                Some("tool_use") => {
+                    self.flush_message(&obj, &mut parts, &mut segments);
+                    if let Value::Object(block) = &block {
+                        self.push_call(block)?;
+                    }
+                }
+                Some("tool_result") => {
+                    self.flush_message(&obj, &mut parts, &mut segments);
+                    if let Value::Object(block) = &block {
+                        self.push_output(block);
+                    }
+                }
+                // Anthropic thinking is signed for Anthropic alone.
+                Some("thinking" | "redacted_thinking") => {}
+                _ => parts.push(block),
+            }
+        }
+        self.flush_message(&obj, &mut parts, &mut segments);
+
+        for call in tool_calls
+            .as_ref()
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Value::Object(call) = call {
+                self.push_call(call)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Push the parts collected so far as a message with `obj`'s fields.
+    /// Segments after the first get their id suffixed with their index.
+    fn flush_message(
+        &mut self,
+        obj: &Map<String, Value>,
+        parts: &mut Vec<Value>,
+        segments: &mut usize,
+    ) {
+        if parts.is_empty() {
+            return;
+        }
+        let mut message = obj.clone();
+        message.insert("type".to_string(), json!("message"));
+        if *segments > 0 {
+            if let Some(id) = str_field(obj, "id") {
+                let id = format!("{id}_{segments}");
+                message.insert("id".to_string(), json!(id));
+            }
+        }
+        message.insert("content".to_string(), Value::Array(std::mem::take(parts)));
+        *segments += 1;
+        self.items.push(Value::Object(message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::responses::ResponseInputOutputItem;
+
+    use super::*;
+    use crate::routers::translation::{translate, Direction};
+
+    fn deserialize(items: &[Value]) -> Vec<ResponseInputOutputItem> {
+        items
+            .iter()
+            .map(|item| serde_json::from_value(item.clone()).unwrap())
+            .collect()
+    }
+
+    fn anthropic_history() -> Vec<Value> {
+        vec![
+            json!({"id": "msg_1", "type": "message", "role": "user", "content": "Weather in Paris?"}),
+            json!({"id": "msg_2", "type": "message", "role": "assistant", "content": [
+                {"type": "thinking", "thinking": "...", "signature": "sig"},
+                {"type": "text", "text": "Checking."},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Paris"}},
+            ]}),
+            json!({"id": "msg_3", "type": "message", "role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "toolu_1", "content": [
+                    {"type": "text", "text": "18C"},
+                    {"type": "text", "text": "sunny"},
+                ]},
+                {"type": "text", "text": "And tomorrow?"},
+            ]}),
+        ]
+    }
+
+    #[test]
+    fn anthropic_blocks_become_responses_items() {
+        let items = normalize_history(anthropic_history()).unwrap();
+        assert_eq!(
+            items,
+            vec![
+                json!({"id": "msg_1", "type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "Weather in Paris?"},
+                ]}),
+                json!({"id": "msg_2", "type": "message", "role": "assistant", "content": [
+                    {"type": "output_text", "text": "Checking.", "annotations": []},
+                ]}),
+                json!({"type": "function_call", "call_id": "toolu_1", "name": "get_weather",
+                       "arguments": "{\"city\":\"Paris\"}"}),
+                json!({"type": "function_call_output", "call_id": "toolu_1", "output": "18C\nsunny"}),
+                json!({"id": "msg_3_1", "type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "And tomorrow?"},
+                ]}),
+            ]
+        );
+        assert_eq!(deserialize(&items).len(), 5);
+    }
+
+    #[test]
+    fn chat_tool_messages_become_responses_items() {
+        let items = normalize_history(vec![
+            json!({"type": "message", "role": "assistant", "content": "", "tool_calls": [
+                {"id": "call_a", "type": "function", "function": {"name": "f", "arguments": "{}"}},
+                {"id": "call_b", "type": "function", "function": {"name": "g", "arguments": "{\"x\":1}"}},
+            ]}),
+            json!({"type": "message", "role": "tool", "tool_call_id": "call_b", "content": "B"}),
+            json!({"type": "message", "role": "tool", "tool_call_id": "call_a", "content": "A"}),
+        ])
+        .unwrap();
+        let pairs: Vec<_> = items
+            .iter()
+            .map(|item| (item["type"].as_str().unwrap(), item["call_id"].as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("function_call", Some("call_a")),
+                ("function_call", Some("call_b")),
+                ("function_call_output", Some("call_b")),
+                ("function_call_output", Some("call_a")),
+            ]
+        );
+        deserialize(&items);
+    }
+
+    #[test]
+    fn missing_ids_are_mapped_deterministically() {
+        let history = vec![
+            json!({"type": "function_call", "name": "f", "arguments": {"q": 1}}),
+            json!({"type": "function_call", "id": "toolu_9", "name": "g", "arguments": "{}"}),
+            json!({"type": "function_call_output", "output": [{"type": "input_text", "text": "F"}]}),
+            json!({"type": "function_call_output", "output": "G"}),
+        ];
+        let items = normalize_history(history.clone()).unwrap();
+        let call_id = items[0]["call_id"].as_str().unwrap();
+        assert!(call_id.starts_with("call_smg_"));
+        assert_eq!(
+            items,
+            vec![
+                json!({"type": "function_call", "call_id": call_id, "name": "f", "arguments": "{\"q\":1}"}),
+                json!({"type": "function_call", "call_id": "toolu_9", "name": "g", "arguments": "{}"}),
+                json!({"type": "function_call_output", "call_id": call_id, "output": "F"}),
+                json!({"type": "function_call_output", "call_id": "toolu_9", "output": "G"}),
+            ]
+        );
+        assert_eq!(normalize_history(history.clone()).unwrap(), items);
+
+        // The synthesized id does not depend on how much history precedes
+        // the call; a repeat of the same call gets its own id.
+        let mut longer = vec![json!({"type": "function_call", "name": "h", "arguments": "{}"})];
+        longer.extend(history[..1].iter().cloned());
+        longer.extend(history[..1].iter().cloned());
+        let longer = normalize_history(longer).unwrap();
+        assert_eq!(longer[1]["call_id"], call_id);
+        assert_eq!(longer[2]["call_id"], format!("{call_id}_2"));
+    }
+
+    #[test]
+    fn orphaned_results_are_kept_as_text() {
+        let items = normalize_history(vec![
+            json!({"type": "function_call_output", "output": "lost"}),
+            json!({"id": "fco_1", "type": "function_call_output", "call_id": "call_gone", "output": "42"}),
+        ])
+        .unwrap();
+        let id = items[0]["id"].as_str().unwrap();
+        assert!(id.starts_with("msg_smg_"));
+        assert_eq!(
+            items,
+            vec![
+                json!({"id": id, "type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "Tool result:\nlost"},
+                ]}),
+                json!({"id": "fco_1", "type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "Tool result for call_gone:\n42"},
+                ]}),
+            ]
+        );
+        assert_eq!(normalize_history(items.clone()).unwrap(), items);
+        assert_eq!(deserialize(&items).len(), 2);
+    }
+
+    #[test]
+    fn calls_without_a_name_are_rejected() {
+        let err = normalize_history(vec![
+            json!({"id": "msg_1", "type": "message", "role": "assistant",
+            "content": [{"type": "tool_use", "id": "toolu_1", "input": {}}]}),
+        ])
+        .unwrap_err();
+        assert_eq!(err, "Tool call 'toolu_1' in history has no name");
+    }
+
+    #[test]
+    fn responses_history_round_trips_unchanged() {
+        let responses_history = vec![
+            json!({"id": "msg_1", "type": "message", "role": "user",
+                   "content": [{"type": "input_text", "text": "hi"}], "status": "completed"}),
+            json!({"id": "fc_1", "type": "function_call", "call_id": "call_1", "name": "f",
+                   "arguments": "{}", "status": "completed"}),
+            json!({"id": "fco_1", "type": "function_call_output", "call_id": "call_1", "output": "ok"}),
+            json!({"id": "rs_1", "type": "reasoning", "summary": []}),
+        ];
+        assert_eq!(
+            normalize_history(responses_history.clone()).unwrap(),
+            responses_history
+        );
+
+        let once = normalize_history(anthropic_history()).unwrap();
+        assert_eq!(normalize_history(once.clone()).unwrap(), once);
+    }
+
+    #[test]
+    fn normalized_anthropic_history_translates_to_paired_chat_messages() {
+        let request = json!({
+            "model": "m",
+            "input": normalize_history(anthropic_history()).unwrap(),
+        });
+        let chat = translate(Direction::ResponsesRequestToChat, &request).unwrap();
+        let messages = chat["messages"].as_array().unwrap();
+        let call_id = messages
+            .iter()
+            .find_map(|m| m["tool_calls"][0]["id"].as_str())
+            .unwrap();
+        let answer = messages.iter().find(|m| m["role"] == "tool").unwrap();
+        assert_eq!(call_id, "toolu_1");
+        assert_eq!(answer["tool_call_id"], call_id);
+        assert_eq!(answer["content"], "18C\nsunny");
+    }
+}
@@ -9,16 +9,61 @@ use axum::{
     response::Response,
 };
 use futures_util::future::join_all;
-use openai_protocol::models::ListModelsResponse;
+use openai_protocol::{model_type::Endpoint, models::ListModelsResponse};
 
 use crate::{
     routers::{
-        common::header_utils::{apply_provider_headers, extract_auth_header},
+        common::header_utils::{
+            apply_provider_headers, extract_auth_header, extract_model_card_revision,
+            extract_target_worker, extract_worker_labels,
+        },
         error,
     },
     worker::{ConnectionMode, ProviderType, RuntimeType, Worker, WorkerRegistry, WorkerType},
 };
 
+/// Whether `worker` may serve `endpoint` requests for `model_id`.
+///
+/// Classify and score are served only by workers whose model card advertises
+/// the capability: generation workers would accept the request and fail or
+/// return garbage. Other endpoints accept any worker serving the model.
+pub(crate) fn serves_endpoint(worker: &dyn Worker, model_id: &str, endpoint: Endpoint) -> bool {
+    match endpoint {
+        Endpoint::Classify | Endpoint::Score => worker.supports_endpoint(model_id, endpoint),
+        _ => true,
+    }
+}
+
+/// Whether `worker` carries every label a routing rule restricted the
+/// request to. Requests no rule narrowed accept any worker.
+pub(crate) fn matches_worker_labels(worker: &dyn Worker, headers: Option<&HeaderMap>) -> bool {
+    let labels = &worker.metadata().spec.labels;
+    extract_worker_labels(headers).all(|(key, value)| labels.get(key).is_some_and(|l| l == value))
+}
+
+/// Whether `worker` serves the model card revision an
+/// `X-SMG-Model-Card-Revision` header pinned the request to. Unpinned
+/// requests accept any worker.
+pub(crate) fn matches_card_revision(
+    registry: &WorkerRegistry,
+    worker: &dyn Worker,
+    model_id: &str,
+    headers: Option<&HeaderMap>,
+) -> bool {
+    let Some(revision) = extract_model_card_revision(headers) else {
+        return true;
+    };
+    // Revisions are tracked under the card's primary ID, not an alias.
+    let card_id = worker
+        .metadata()
+        .find_model(model_id)
+        .map_or(model_id, |card| card.id.as_str());
+    registry
+        .model_cards()
+        .served_revision(worker.url(), card_id)
+        == Some(revision)
+}
+
 /// Holds references to shared infrastructure needed for worker selection.
 ///
 /// Created once per router (or per-request where lifetimes differ) and
@@ -125,9 +170,16 @@ impl<'a> WorkerSelector<'a> {
     }
 
     fn find_best_worker(&self, req: &SelectWorkerRequest<'_>) -> Option<Arc<dyn Worker>> {
+        // An admin `X-SMG-Target-Worker` pin narrows the candidates to that
+        // worker; the numeric index form only means something to policies.
+        let target =
+            extract_target_worker(req.headers).filter(|t| !t.bytes().all(|b| b.is_ascii_digit()));
         self.get_candidates(req)
             .into_iter()
+            .filter(|w| target.is_none_or(|url| w.url() == url))
+            .filter(|w| matches_worker_labels(w.as_ref(), req.headers))
             .filter(|w| w.supports_model(req.model_id))
+            .filter(|w| matches_card_revision(self.registry, w.as_ref(), req.model_id, req.headers))
             .filter(|w| !req.require_realtime_capable || w.is_realtime_capable())
             .min_by_key(|w| w.load())
     }
@@ -366,4 +418,25 @@ mod tests {
     fn default_request_does_not_require_realtime() {
         assert!(!SelectWorkerRequest::default().require_realtime_capable);
     }
+
+    #[test]
+    fn classify_and_score_require_advertised_capability() {
+        use openai_protocol::{model_card::ModelCard, model_type::ModelType};
+
+        let llm = BasicWorkerBuilder::new("http://127.0.0.1:18080")
+            .model(ModelCard::new("m"))
+            .health_config(no_health_check())
+            .build();
+        let classifier = BasicWorkerBuilder::new("http://127.0.0.1:18081")
+            .model(ModelCard::new("m").with_model_type(ModelType::CLASSIFY_MODEL))
+            .health_config(no_health_check())
+            .build();
+
+        assert!(!serves_endpoint(&llm, "m", Endpoint::Classify));
+        assert!(!serves_endpoint(&llm, "m", Endpoint::Score));
+        assert!(serves_endpoint(&llm, "m", Endpoint::Chat));
+        assert!(serves_endpoint(&classifier, "m", Endpoint::Classify));
+        // Ungated endpoints don't consult the model card.
+        assert!(serves_endpoint(&classifier, "m", Endpoint::Embeddings));
+    }
 }
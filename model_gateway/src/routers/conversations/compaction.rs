@@ -0,0 +1,298 @@
+//! Summarization compaction of long conversation histories.
+//!
+//! After a Responses API turn on a stored conversation, the conversation's
+//! size is estimated. Once it exceeds `token_threshold`, everything but the
+//! `keep_recent_items` most recent items is summarized by the model in the
+//! background. The summary is linked as a `system` message ahead of the
+//! recent items, and the summarized items are archived: unlinked from the
+//! conversation but kept in storage.
+//! Subsequent turns load the summary plus the recent items.
+
+use std::sync::Arc;
+
+use axum::body::to_bytes;
+use chrono::{Duration, Utc};
+use dashmap::DashSet;
+use openai_protocol::chat::ChatCompletionRequest;
+use serde_json::{json, Value};
+use smg_data_connector::{
+    ConversationId, ConversationItem, ConversationItemStorage, ListParams, NewConversationItem,
+    SortOrder,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::ConversationCompactionConfig, middleware::TenantRequestMeta, routers::RouterTrait,
+};
+
+const CHARS_PER_TOKEN_ESTIMATE: usize = 4;
+
+/// Page size when loading a conversation's items.
+const LIST_PAGE_SIZE: usize = 100;
+
+/// A summary is a single chat completion.
+const SUMMARY_RESPONSE_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+const SUMMARY_PROMPT: &str = "Summarize the conversation below so that it can replace the \
+     original messages as context for continuing it. Keep every fact, decision, open question \
+     and user preference; drop pleasantries and repetition.";
+
+const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+pub struct ConversationCompactor {
+    config: ConversationCompactionConfig,
+    router: Arc<dyn RouterTrait>,
+    item_storage: Arc<dyn ConversationItemStorage>,
+    /// Conversations being compacted; later turns do not start another run.
+    running: DashSet<ConversationId>,
+}
+
+impl ConversationCompactor {
+    pub fn new(
+        config: ConversationCompactionConfig,
+        router: Arc<dyn RouterTrait>,
+        item_storage: Arc<dyn ConversationItemStorage>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            router,
+            item_storage,
+            running: DashSet::new(),
+        })
+    }
+
+    /// Check a conversation after a turn and compact it in the background if
+    /// it has grown past the threshold. `model` summarizes unless the config
+    /// names one.
+    pub fn schedule(
+        self: &Arc<Self>,
+        conversation_id: ConversationId,
+        model: &str,
+        tenant_meta: &TenantRequestMeta,
+    ) {
+        if !self.running.insert(conversation_id.clone()) {
+            return;
+        }
+        let compactor = self.clone();
+        let model = self
+            .config
+            .model
+            .clone()
+            .unwrap_or_else(|| model.to_string());
+        let tenant_meta = tenant_meta.clone();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "compaction runs off the request path; at most one task per conversation"
+        )]
+        tokio::spawn(async move {
+            if let Err(e) = compactor
+                .compact(&conversation_id, &model, &tenant_meta)
+                .await
+            {
+                warn!(conversation_id = %conversation_id, error = %e, "Conversation compaction failed");
+            }
+            compactor.running.remove(&conversation_id);
+        });
+    }
+
+    async fn compact(
+        &self,
+        conversation_id: &ConversationId,
+        model: &str,
+        tenant_meta: &TenantRequestMeta,
+    ) -> Result<(), String> {
+        let items = self.load_items(conversation_id).await?;
+        let tokens = estimate_tokens(&items);
+        if tokens <= self.config.token_threshold || items.len() <= self.config.keep_recent_items {
+            return Ok(());
+        }
+        let (older, recent) = items.split_at(items.len() - self.config.keep_recent_items);
+        debug!(
+            conversation_id = %conversation_id,
+            tokens,
+            summarized = older.len(),
+            "Compacting conversation history"
+        );
+
+        let summary = self.summarize(older, model, tenant_meta).await?;
+        let summary_item = self
+            .item_storage
+            .create_item(NewConversationItem {
+                id: None,
+                response_id: None,
+                item_type: "message".to_string(),
+                role: Some("system".to_string()),
+                content: json!([{"type": "input_text", "text": format!("{SUMMARY_PREFIX}{summary}")}]),
+                status: Some("completed".to_string()),
+            })
+            .await
+            .map_err(|e| format!("failed to store summary: {e}"))?;
+        // Order the summary before the items it does not replace. Storage
+        // backends order links with second precision.
+        let added_at = recent
+            .first()
+            .map_or_else(Utc::now, |item| item.created_at - Duration::seconds(1));
+        self.item_storage
+            .link_item(conversation_id, &summary_item.id, added_at)
+            .await
+            .map_err(|e| format!("failed to link summary: {e}"))?;
+
+        for item in older {
+            self.item_storage
+                .delete_item(conversation_id, &item.id)
+                .await
+                .map_err(|e| format!("failed to archive item {}: {e}", item.id))?;
+        }
+        info!(
+            conversation_id = %conversation_id,
+            archived = older.len(),
+            "Compacted conversation history into a summary"
+        );
+        Ok(())
+    }
+
+    async fn load_items(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Vec<ConversationItem>, String> {
+        let mut items: Vec<ConversationItem> = Vec::new();
+        loop {
+            let page = self
+                .item_storage
+                .list_items(
+                    conversation_id,
+                    ListParams {
+                        limit: LIST_PAGE_SIZE,
+                        order: SortOrder::Asc,
+                        after: items.last().map(|item| item.id.0.clone()),
+                    },
+                )
+                .await
+                .map_err(|e| format!("failed to list items: {e}"))?;
+            let done = page.len() < LIST_PAGE_SIZE;
+            items.extend(page);
+            if done {
+                return Ok(items);
+            }
+        }
+    }
+
+    async fn summarize(
+        &self,
+        items: &[ConversationItem],
+        model: &str,
+        tenant_meta: &TenantRequestMeta,
+    ) -> Result<String, String> {
+        let request: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": model,
+            "messages": [
+                {"role": "system", "content": SUMMARY_PROMPT},
+                {"role": "user", "content": transcript(items)},
+            ],
+            "temperature": 0,
+            "stream": false,
+        }))
+        .map_err(|e| format!("failed to build summary request: {e}"))?;
+
+        let response = self
+            .router
+            .route_chat(None, tenant_meta, &request, model)
+            .await;
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), SUMMARY_RESPONSE_BODY_LIMIT)
+            .await
+            .map_err(|e| format!("failed to read summary response: {e}"))?;
+        if !status.is_success() {
+            return Err(format!(
+                "summary request failed with {status}: {}",
+                String::from_utf8_lossy(&bytes)
+            ));
+        }
+        let completion: Value =
+            serde_json::from_slice(&bytes).map_err(|e| format!("invalid summary response: {e}"))?;
+        completion
+            .pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .filter(|summary| !summary.trim().is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| "summary response has no message content".to_string())
+    }
+}
+
+/// Text of an item: the `text` of its content parts, or its JSON content for
+/// non-message items such as tool calls.
+fn item_text(item: &ConversationItem) -> String {
+    let parts = item
+        .content
+        .as_array()
+        .or_else(|| item.content.get("content").and_then(Value::as_array));
+    match parts {
+        Some(parts) => parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => item.content.to_string(),
+    }
+}
+
+fn transcript(items: &[ConversationItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let speaker = item.role.as_deref().unwrap_or(&item.item_type);
+            format!("{speaker}: {}", item_text(item))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn estimate_tokens(items: &[ConversationItem]) -> usize {
+    items
+        .iter()
+        .map(|item| item.content.to_string().len())
+        .sum::<usize>()
+        .div_ceil(CHARS_PER_TOKEN_ESTIMATE)
+}
+
+#[cfg(test)]
+mod tests {
+    use smg_data_connector::ConversationItemId;
+
+    use super::*;
+
+    fn item(role: &str, content: Value) -> ConversationItem {
+        ConversationItem {
+            id: ConversationItemId::from("msg_1"),
+            response_id: None,
+            item_type: "message".to_string(),
+            role: Some(role.to_string()),
+            content,
+            status: Some("completed".to_string()),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn transcript_reads_text_from_both_stored_message_shapes() {
+        let items = [
+            item("user", json!([{"type": "input_text", "text": "hello"}])),
+            item(
+                "assistant",
+                json!({"content": [{"type": "output_text", "text": "hi there"}], "phase": "final"}),
+            ),
+        ];
+        assert_eq!(transcript(&items), "user: hello\n\nassistant: hi there");
+    }
+
+    #[test]
+    fn token_estimate_grows_with_content() {
+        let short = [item("user", json!([{"type": "input_text", "text": "hi"}]))];
+        let long = [item(
+            "user",
+            json!([{"type": "input_text", "text": "x".repeat(4000)}]),
+        )];
+        assert!(estimate_tokens(&short) < 20);
+        assert!(estimate_tokens(&long) >= 1000);
+    }
+}
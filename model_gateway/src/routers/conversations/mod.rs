@@ -3,6 +3,8 @@
 //! This module provides conversation CRUD operations that can be shared
 //! across different router implementations.
 
+mod compaction;
 mod handlers;
 
+pub use compaction::ConversationCompactor;
 pub use handlers::*;
@@ -200,7 +200,7 @@ impl RouterFactory {
         main_policy_config: &PolicyConfig,
         ctx: &Arc<AppContext>,
     ) {
-        let default_encode_policy = PolicyConfig::ConsistentHashing;
+        let default_encode_policy = PolicyConfig::ConsistentHashing { load_epsilon: None };
         let encode_policy = PolicyFactory::create_from_config(
             encode_policy_config.unwrap_or(&default_encode_policy),
         );
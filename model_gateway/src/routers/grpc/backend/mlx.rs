@@ -0,0 +1,145 @@
+//! MLX engine backend.
+//!
+//! MLX takes no multimodal inputs (the preparation stage rejects them) and
+//! samples one choice per request, so `n > 1` is fanned out.
+
+use async_trait::async_trait;
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
+    messages::CreateMessageRequest, responses::ResponsesRequest,
+};
+use smg_grpc_client::{mlx_proto as mlx, MlxEngineClient};
+use tonic::Status;
+
+use super::{mismatched, Backend, BackendCapabilities};
+use crate::{
+    routers::grpc::{
+        client::{GenerateRequestBuildOptions, HealthCheckResponse},
+        proto_wrapper::{
+            ProtoGenerateComplete, ProtoGenerateRequest, ProtoGenerateStreamChunk,
+            ProtoResponseVariant, ProtoStream,
+        },
+    },
+    worker::RuntimeType,
+};
+
+#[async_trait]
+impl Backend for MlxEngineClient {
+    type GenerateResponse = mlx::GenerateResponse;
+
+    fn runtime_type(&self) -> RuntimeType {
+        RuntimeType::Mlx
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
+
+    fn build_chat_request(
+        &self,
+        request_id: String,
+        body: &ChatCompletionRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_chat(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            options.tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
+    }
+
+    fn build_messages_request(
+        &self,
+        request_id: String,
+        body: &CreateMessageRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_messages(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            options.tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
+    }
+
+    fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_responses(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
+    }
+
+    fn build_completion_request(
+        &self,
+        request_id: String,
+        body: &CompletionRequest,
+        original_text: String,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_completion(
+            request_id,
+            body,
+            original_text,
+            token_ids,
+        )?;
+        Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
+    }
+
+    fn build_generate_request(
+        &self,
+        request_id: String,
+        body: &GenerateRequest,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_plain_generate_request(request_id, body, original_text, token_ids)?;
+        Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
+    }
+
+    async fn submit(&self, request: ProtoGenerateRequest) -> Result<ProtoStream, Status> {
+        let ProtoGenerateRequest::Mlx(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        Ok(ProtoStream::Mlx(self.generate(*request).await?))
+    }
+
+    fn decode_chunk(response: mlx::GenerateResponse) -> ProtoResponseVariant {
+        match response.response {
+            Some(mlx::generate_response::Response::Chunk(chunk)) => {
+                ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Mlx(chunk))
+            }
+            Some(mlx::generate_response::Response::Complete(complete)) => {
+                ProtoResponseVariant::Complete(ProtoGenerateComplete::Mlx(complete))
+            }
+            None => ProtoResponseVariant::None,
+        }
+    }
+
+    async fn check_health(&self) -> Result<HealthCheckResponse, Status> {
+        let resp = self.health_check().await?;
+        Ok(HealthCheckResponse {
+            healthy: resp.healthy,
+            message: resp.message,
+        })
+    }
+}
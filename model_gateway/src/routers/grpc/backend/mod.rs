@@ -0,0 +1,156 @@
+//! Per-engine gRPC backends.
+//!
+//! [`Backend`] is everything the pipeline needs from one engine: building its
+//! `GenerateRequest` from each API surface, submitting and aborting
+//! generations, decoding its stream responses, and which optional RPCs it
+//! serves ([`BackendCapabilities`]). Each engine implements it on its client
+//! in its own submodule; [`GrpcClient`](super::client::GrpcClient) only
+//! dispatches to it.
+//!
+//! Adding an engine means a submodule here plus a variant in each of the
+//! wrapper enums (`GrpcClient`, `ProtoGenerateRequest`, `ProtoStream`,
+//! `ProtoGenerateResponse`, ...); no pipeline stage matches on engines.
+
+mod mlx;
+mod sglang;
+mod tokenspeed;
+mod trtllm;
+mod vllm;
+
+use async_trait::async_trait;
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
+    messages::CreateMessageRequest, responses::ResponsesRequest,
+};
+use smg_grpc_client::AbortOnDropClient;
+use tonic::Status;
+
+use crate::{
+    routers::grpc::{
+        client::{GenerateRequestBuildOptions, HealthCheckResponse},
+        proto_wrapper::{
+            ProtoEmbedComplete, ProtoEmbedRequest, ProtoGenerateRequest, ProtoResponseVariant,
+            ProtoStream,
+        },
+    },
+    worker::RuntimeType,
+};
+
+/// Optional features an engine serves. RPCs behind a `false` flag return
+/// `Status::unimplemented`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    /// Samples `n > 1` choices from one request. Requests to engines that
+    /// don't are fanned out into `n` single-choice requests.
+    pub native_n: bool,
+    /// Serves the Embed RPC.
+    pub embeddings: bool,
+    /// Reports scheduler load through GetLoads.
+    pub loads: bool,
+    /// Serves FlushCache.
+    pub flush_cache: bool,
+    /// Serves StartProfile / StopProfile.
+    pub profiling: bool,
+    /// Streams KV cache events.
+    pub kv_events: bool,
+}
+
+/// One inference engine behind a gRPC client.
+#[async_trait]
+pub trait Backend: AbortOnDropClient {
+    /// The engine's streamed `GenerateResponse`.
+    type GenerateResponse: Send;
+
+    fn runtime_type(&self) -> RuntimeType;
+
+    fn capabilities(&self) -> BackendCapabilities;
+
+    fn build_chat_request(
+        &self,
+        request_id: String,
+        body: &ChatCompletionRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String>;
+
+    fn build_messages_request(
+        &self,
+        request_id: String,
+        body: &CreateMessageRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String>;
+
+    fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String>;
+
+    fn build_completion_request(
+        &self,
+        request_id: String,
+        body: &CompletionRequest,
+        original_text: String,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String>;
+
+    fn build_generate_request(
+        &self,
+        request_id: String,
+        body: &GenerateRequest,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String>;
+
+    /// Build an embed request, or `None` when the engine serves no
+    /// embeddings.
+    fn build_embedding_request(
+        &self,
+        _request_id: String,
+        _original_text: Option<String>,
+        _token_ids: Vec<u32>,
+    ) -> Option<ProtoEmbedRequest> {
+        None
+    }
+
+    /// Submit a request built by this backend. The returned stream aborts
+    /// the request when dropped before `mark_completed`.
+    async fn submit(&self, request: ProtoGenerateRequest) -> Result<ProtoStream, Status>;
+
+    /// Split one stream response into a chunk, the completion, or nothing.
+    fn decode_chunk(response: Self::GenerateResponse) -> ProtoResponseVariant;
+
+    /// Abort an in-flight request.
+    async fn abort(&self, request_id: String) -> Result<(), Status> {
+        self.clone().abort_for_drop(request_id).await
+    }
+
+    async fn check_health(&self) -> Result<HealthCheckResponse, Status>;
+
+    async fn submit_embed(
+        &self,
+        _request: ProtoEmbedRequest,
+    ) -> Result<ProtoEmbedComplete, Status> {
+        Err(unsupported("Embed", self.runtime_type()))
+    }
+}
+
+/// Error for an RPC the engine does not serve.
+fn unsupported(rpc: &str, runtime_type: RuntimeType) -> Status {
+    Status::unimplemented(format!(
+        "{rpc} RPC not supported for {runtime_type} backend"
+    ))
+}
+
+/// Error for a request built by a different engine's backend.
+fn mismatched(runtime_type: RuntimeType) -> Status {
+    Status::internal(format!(
+        "request built for another backend submitted to {runtime_type} backend"
+    ))
+}
@@ -0,0 +1,184 @@
+//! SGLang scheduler backend.
+
+use async_trait::async_trait;
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
+    messages::CreateMessageRequest, responses::ResponsesRequest,
+};
+use smg_grpc_client::{
+    sglang_proto as sglang, SglangGenerateRequestOptions, SglangSchedulerClient,
+};
+use tonic::Status;
+
+use super::{mismatched, Backend, BackendCapabilities};
+use crate::{
+    routers::grpc::{
+        client::{GenerateRequestBuildOptions, HealthCheckResponse},
+        proto_wrapper::{
+            ProtoEmbedComplete, ProtoEmbedRequest, ProtoGenerateComplete, ProtoGenerateRequest,
+            ProtoGenerateStreamChunk, ProtoResponseVariant, ProtoStream,
+        },
+        MultimodalData,
+    },
+    worker::RuntimeType,
+};
+
+#[expect(
+    clippy::unreachable,
+    reason = "assembly stage guarantees matching MultimodalData variant for each backend"
+)]
+fn request_options(options: GenerateRequestBuildOptions) -> SglangGenerateRequestOptions {
+    SglangGenerateRequestOptions {
+        multimodal_inputs: options.multimodal_inputs.map(|mm| match mm {
+            MultimodalData::Sglang(data) => data.into_proto(),
+            _ => unreachable!("caller guarantees matching variant"),
+        }),
+        tool_call_constraint: options.tool_constraints,
+        require_reasoning: options.require_reasoning,
+    }
+}
+
+#[async_trait]
+impl Backend for SglangSchedulerClient {
+    type GenerateResponse = sglang::GenerateResponse;
+
+    fn runtime_type(&self) -> RuntimeType {
+        RuntimeType::Sglang
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            native_n: true,
+            embeddings: true,
+            loads: true,
+            flush_cache: true,
+            profiling: true,
+            kv_events: true,
+        }
+    }
+
+    fn build_chat_request(
+        &self,
+        request_id: String,
+        body: &ChatCompletionRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_chat(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            request_options(options),
+        )?;
+        Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
+    }
+
+    fn build_messages_request(
+        &self,
+        request_id: String,
+        body: &CreateMessageRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_messages(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            request_options(options),
+        )?;
+        Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
+    }
+
+    fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_responses(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
+    }
+
+    fn build_completion_request(
+        &self,
+        request_id: String,
+        body: &CompletionRequest,
+        original_text: String,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_completion(
+            request_id,
+            body,
+            original_text,
+            token_ids,
+        )?;
+        Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
+    }
+
+    fn build_generate_request(
+        &self,
+        request_id: String,
+        body: &GenerateRequest,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_plain_generate_request(request_id, body, original_text, token_ids)?;
+        Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
+    }
+
+    fn build_embedding_request(
+        &self,
+        request_id: String,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Option<ProtoEmbedRequest> {
+        let req = self.build_embed_request(request_id, original_text, token_ids);
+        Some(ProtoEmbedRequest::Sglang(Box::new(req)))
+    }
+
+    async fn submit(&self, request: ProtoGenerateRequest) -> Result<ProtoStream, Status> {
+        let ProtoGenerateRequest::Sglang(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        Ok(ProtoStream::Sglang(self.generate(*request).await?))
+    }
+
+    fn decode_chunk(response: sglang::GenerateResponse) -> ProtoResponseVariant {
+        match response.response {
+            Some(sglang::generate_response::Response::Chunk(chunk)) => {
+                ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Sglang(chunk))
+            }
+            Some(sglang::generate_response::Response::Complete(complete)) => {
+                ProtoResponseVariant::Complete(ProtoGenerateComplete::Sglang(complete))
+            }
+            None => ProtoResponseVariant::None,
+        }
+    }
+
+    async fn check_health(&self) -> Result<HealthCheckResponse, Status> {
+        let resp = self.health_check().await?;
+        Ok(HealthCheckResponse {
+            healthy: resp.healthy,
+            message: resp.message,
+        })
+    }
+
+    async fn submit_embed(&self, request: ProtoEmbedRequest) -> Result<ProtoEmbedComplete, Status> {
+        let ProtoEmbedRequest::Sglang(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        Ok(ProtoEmbedComplete::Sglang(self.embed(*request).await?))
+    }
+}
@@ -0,0 +1,184 @@
+//! TensorRT-LLM service backend.
+
+use async_trait::async_trait;
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
+    messages::CreateMessageRequest, responses::ResponsesRequest,
+};
+use smg_grpc_client::{trtllm_proto as trtllm, TrtllmServiceClient};
+use tonic::Status;
+
+use super::{mismatched, Backend, BackendCapabilities};
+use crate::{
+    routers::grpc::{
+        client::{GenerateRequestBuildOptions, HealthCheckResponse},
+        proto_wrapper::{
+            ProtoGenerateComplete, ProtoGenerateRequest, ProtoGenerateStreamChunk,
+            ProtoResponseVariant, ProtoStream,
+        },
+        MultimodalData,
+    },
+    worker::RuntimeType,
+};
+
+/// TRT-LLM reports liveness via a free-form status string whose only healthy
+/// value is `"OK"` (per `trtllm_service.proto`); anything else is an error
+/// description and must be treated as unhealthy.
+fn status_healthy(status: &str) -> bool {
+    status.trim().eq_ignore_ascii_case("ok")
+}
+
+#[expect(
+    clippy::unreachable,
+    reason = "assembly stage guarantees matching MultimodalData variant for each backend"
+)]
+fn multimodal_inputs(options: &mut GenerateRequestBuildOptions) -> Option<trtllm::MultimodalInput> {
+    options.multimodal_inputs.take().map(|mm| match mm {
+        MultimodalData::Trtllm(data) => data.into_proto(),
+        _ => unreachable!("caller guarantees matching variant"),
+    })
+}
+
+#[async_trait]
+impl Backend for TrtllmServiceClient {
+    type GenerateResponse = trtllm::GenerateResponse;
+
+    fn runtime_type(&self) -> RuntimeType {
+        RuntimeType::Trtllm
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            native_n: true,
+            kv_events: true,
+            ..Default::default()
+        }
+    }
+
+    fn build_chat_request(
+        &self,
+        request_id: String,
+        body: &ChatCompletionRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        mut options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_chat(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            multimodal_inputs(&mut options),
+            options.tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
+    }
+
+    fn build_messages_request(
+        &self,
+        request_id: String,
+        body: &CreateMessageRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        mut options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_messages(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            multimodal_inputs(&mut options),
+            options.tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
+    }
+
+    fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_responses(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
+    }
+
+    fn build_completion_request(
+        &self,
+        request_id: String,
+        body: &CompletionRequest,
+        original_text: String,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_completion(
+            request_id,
+            body,
+            original_text,
+            token_ids,
+        )?;
+        Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
+    }
+
+    fn build_generate_request(
+        &self,
+        request_id: String,
+        body: &GenerateRequest,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_plain_generate_request(request_id, body, original_text, token_ids)?;
+        Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
+    }
+
+    async fn submit(&self, request: ProtoGenerateRequest) -> Result<ProtoStream, Status> {
+        let ProtoGenerateRequest::Trtllm(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        Ok(ProtoStream::Trtllm(self.generate(*request).await?))
+    }
+
+    fn decode_chunk(response: trtllm::GenerateResponse) -> ProtoResponseVariant {
+        match response.response {
+            Some(trtllm::generate_response::Response::Chunk(chunk)) => {
+                ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Trtllm(chunk))
+            }
+            Some(trtllm::generate_response::Response::Complete(complete)) => {
+                ProtoResponseVariant::Complete(ProtoGenerateComplete::Trtllm(complete))
+            }
+            None => ProtoResponseVariant::None,
+        }
+    }
+
+    async fn check_health(&self) -> Result<HealthCheckResponse, Status> {
+        let resp = self.health_check().await?;
+        Ok(HealthCheckResponse {
+            healthy: status_healthy(&resp.status),
+            message: resp.status,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::status_healthy;
+
+    #[test]
+    fn status_healthy_matches_ok_exactly() {
+        assert!(status_healthy("ok"));
+        assert!(status_healthy("OK"));
+        assert!(status_healthy("  OK  "));
+
+        assert!(!status_healthy("not ok"));
+        assert!(!status_healthy("checking"));
+        assert!(!status_healthy("degraded"));
+        assert!(!status_healthy(""));
+    }
+}
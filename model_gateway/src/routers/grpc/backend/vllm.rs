@@ -0,0 +1,189 @@
+//! vLLM engine backend.
+
+use async_trait::async_trait;
+use openai_protocol::{
+    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
+    messages::CreateMessageRequest, responses::ResponsesRequest,
+};
+use smg_grpc_client::{vllm_proto as vllm, VllmEngineClient};
+use tonic::Status;
+
+use super::{mismatched, Backend, BackendCapabilities};
+use crate::{
+    routers::grpc::{
+        client::{GenerateRequestBuildOptions, HealthCheckResponse},
+        proto_wrapper::{
+            cleanup_mm_shm_handles, collect_vllm_generate_request_shm_handles, finish_vllm_request,
+            ProtoEmbedComplete, ProtoEmbedRequest, ProtoGenerateComplete, ProtoGenerateRequest,
+            ProtoGenerateStreamChunk, ProtoResponseVariant, ProtoStream,
+        },
+        MultimodalData,
+    },
+    worker::RuntimeType,
+};
+
+#[expect(
+    clippy::unreachable,
+    reason = "assembly stage guarantees matching MultimodalData variant for each backend"
+)]
+fn multimodal_inputs(options: &mut GenerateRequestBuildOptions) -> Option<vllm::MultimodalInputs> {
+    options.multimodal_inputs.take().map(|mm| match mm {
+        MultimodalData::Vllm(data) => data.into_proto(),
+        _ => unreachable!("caller guarantees matching variant"),
+    })
+}
+
+#[async_trait]
+impl Backend for VllmEngineClient {
+    type GenerateResponse = vllm::GenerateResponse;
+
+    fn runtime_type(&self) -> RuntimeType {
+        RuntimeType::Vllm
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            native_n: true,
+            embeddings: true,
+            loads: true,
+            kv_events: true,
+            ..Default::default()
+        }
+    }
+
+    fn build_chat_request(
+        &self,
+        request_id: String,
+        body: &ChatCompletionRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        mut options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        finish_vllm_request(multimodal_inputs(&mut options), |mm| {
+            self.build_generate_request_from_chat(
+                request_id,
+                body,
+                processed_text,
+                token_ids,
+                mm,
+                options.tool_constraints,
+            )
+        })
+    }
+
+    fn build_messages_request(
+        &self,
+        request_id: String,
+        body: &CreateMessageRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        mut options: GenerateRequestBuildOptions,
+    ) -> Result<ProtoGenerateRequest, String> {
+        finish_vllm_request(multimodal_inputs(&mut options), |mm| {
+            self.build_generate_request_from_messages(
+                request_id,
+                body,
+                processed_text,
+                token_ids,
+                mm,
+                options.tool_constraints,
+            )
+        })
+    }
+
+    fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_responses(
+            request_id,
+            body,
+            processed_text,
+            token_ids,
+            tool_constraints,
+        )?;
+        Ok(ProtoGenerateRequest::Vllm(Box::new(req)))
+    }
+
+    fn build_completion_request(
+        &self,
+        request_id: String,
+        body: &CompletionRequest,
+        original_text: String,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_generate_request_from_completion(
+            request_id,
+            body,
+            original_text,
+            token_ids,
+        )?;
+        Ok(ProtoGenerateRequest::Vllm(Box::new(req)))
+    }
+
+    fn build_generate_request(
+        &self,
+        request_id: String,
+        body: &GenerateRequest,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        let req = self.build_plain_generate_request(request_id, body, original_text, token_ids)?;
+        Ok(ProtoGenerateRequest::Vllm(Box::new(req)))
+    }
+
+    fn build_embedding_request(
+        &self,
+        request_id: String,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Option<ProtoEmbedRequest> {
+        let req = self.build_embed_request(request_id, original_text, token_ids);
+        Some(ProtoEmbedRequest::Vllm(Box::new(req)))
+    }
+
+    async fn submit(&self, request: ProtoGenerateRequest) -> Result<ProtoStream, Status> {
+        let ProtoGenerateRequest::Vllm(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        let shm_handles = collect_vllm_generate_request_shm_handles(&request);
+        match self.generate(*request).await {
+            Ok(stream) => Ok(ProtoStream::Vllm(stream)),
+            Err(error) => {
+                cleanup_mm_shm_handles(&shm_handles);
+                Err(error)
+            }
+        }
+    }
+
+    fn decode_chunk(response: vllm::GenerateResponse) -> ProtoResponseVariant {
+        match response.response {
+            Some(vllm::generate_response::Response::Chunk(chunk)) => {
+                ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Vllm(chunk))
+            }
+            Some(vllm::generate_response::Response::Complete(complete)) => {
+                ProtoResponseVariant::Complete(ProtoGenerateComplete::Vllm(complete))
+            }
+            None => ProtoResponseVariant::None,
+        }
+    }
+
+    async fn check_health(&self) -> Result<HealthCheckResponse, Status> {
+        let resp = self.health_check().await?;
+        Ok(HealthCheckResponse {
+            healthy: resp.healthy,
+            message: resp.message,
+        })
+    }
+
+    async fn submit_embed(&self, request: ProtoEmbedRequest) -> Result<ProtoEmbedComplete, Status> {
+        let ProtoEmbedRequest::Vllm(request) = request else {
+            return Err(mismatched(self.runtime_type()));
+        };
+        Ok(ProtoEmbedComplete::Vllm(self.embed(*request).await?))
+    }
+}
@@ -0,0 +1,111 @@
+//! Byte buffers reused across requests by the gRPC pipeline.
+//!
+//! Serializing a multimodal request allocates its encoder input (pixel
+//! values) as one buffer of several megabytes. When the tensor then travels
+//! over SHM the buffer is copied into the mapping and dropped, so every
+//! request paid for a fresh allocation of that size, and at that size the
+//! allocator hands memory back to the OS and faults it in again each time.
+//! [`TENSOR_BUFFERS`] keeps those buffers instead: serialization takes one
+//! sized for the tensor and the SHM path gives it back once written.
+//!
+//! Buffers that end up inline in a proto message are consumed by tonic and
+//! are not returned. The same goes for prompt token IDs, which move into the
+//! outgoing request, so they are not pooled.
+
+use parking_lot::Mutex;
+
+/// Pool for serialized multimodal tensors.
+pub static TENSOR_BUFFERS: BufferPool = BufferPool::new(64 << 20);
+
+/// A free list of byte buffers bounded by their total capacity.
+pub struct BufferPool {
+    free: Mutex<Vec<Vec<u8>>>,
+    /// Capacity kept across all pooled buffers; a returned buffer that does
+    /// not fit is dropped.
+    max_bytes: usize,
+}
+
+impl BufferPool {
+    pub const fn new(max_bytes: usize) -> Self {
+        Self {
+            free: Mutex::new(Vec::new()),
+            max_bytes,
+        }
+    }
+
+    /// An empty buffer with room for at least `capacity` bytes. The smallest
+    /// pooled buffer that fits is reused; otherwise a new one is allocated.
+    pub fn take(&self, capacity: usize) -> Vec<u8> {
+        let mut free = self.free.lock();
+        let fitting = free
+            .iter()
+            .enumerate()
+            .filter(|(_, buf)| buf.capacity() >= capacity)
+            .min_by_key(|(_, buf)| buf.capacity())
+            .map(|(i, _)| i);
+        match fitting {
+            Some(i) => free.swap_remove(i),
+            None => {
+                drop(free);
+                Vec::with_capacity(capacity)
+            }
+        }
+    }
+
+    /// Return a buffer for reuse by a later [`BufferPool::take`].
+    pub fn give(&self, mut buf: Vec<u8>) {
+        if buf.capacity() == 0 {
+            return;
+        }
+        buf.clear();
+        let mut free = self.free.lock();
+        let pooled: usize = free.iter().map(Vec::capacity).sum();
+        if pooled + buf.capacity() <= self.max_bytes {
+            free.push(buf);
+        }
+    }
+
+    /// Number of buffers currently pooled.
+    pub fn len(&self) -> usize {
+        self.free.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_the_smallest_buffer_that_fits() {
+        let pool = BufferPool::new(1 << 20);
+        pool.give(Vec::with_capacity(64));
+        pool.give(vec![7; 4096]);
+        pool.give(Vec::with_capacity(1024));
+
+        let buf = pool.take(1000);
+        assert!(buf.is_empty());
+        assert!(buf.capacity() >= 1000 && buf.capacity() < 4096);
+        assert_eq!(pool.len(), 2);
+
+        // Nothing pooled is large enough: allocate.
+        let big = pool.take(8192);
+        assert!(big.capacity() >= 8192);
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn bounds_the_capacity_it_keeps() {
+        let pool = BufferPool::new(1024);
+        pool.give(Vec::with_capacity(2048));
+        pool.give(Vec::new());
+        assert!(pool.is_empty());
+
+        pool.give(Vec::with_capacity(600));
+        pool.give(Vec::with_capacity(600));
+        assert_eq!(pool.len(), 1);
+    }
+}
@@ -1,23 +1,24 @@
-//! Unified gRPC client wrapper for SGLang, vLLM, and TensorRT-LLM backends
+//! Unified gRPC client wrapper over the per-engine backends
 
 use std::collections::HashMap;
 
 use openai_protocol::{
-    chat::ChatCompletionRequest, completion::CompletionRequest, generate::GenerateRequest,
-    messages::CreateMessageRequest, worker::WorkerLoadResponse,
+    chat::ChatCompletionRequest,
+    completion::CompletionRequest,
+    generate::GenerateRequest,
+    messages::CreateMessageRequest,
+    responses::ResponsesRequest,
+    worker::{GrpcTlsConfig, WorkerLoadResponse},
 };
 use smg_grpc_client::{
-    common_proto, tokenizer_bundle, tokenizer_bundle::StreamBundle, MlxEngineClient,
-    SglangGenerateRequestOptions, SglangSchedulerClient, TokenSpeedSchedulerClient,
-    TrtllmServiceClient, VllmEngineClient,
+    common_proto, tokenizer_bundle, tokenizer_bundle::StreamBundle, MlxEngineClient, ProtoCompat,
+    SglangSchedulerClient, TokenSpeedSchedulerClient, TrtllmServiceClient, VllmEngineClient,
 };
+use tracing::warn;
 
 use crate::routers::grpc::{
-    proto_wrapper::{
-        cleanup_mm_shm_handles, collect_tokenspeed_generate_request_shm_handles,
-        collect_vllm_generate_request_shm_handles, finish_tokenspeed_request, finish_vllm_request,
-        ProtoEmbedComplete, ProtoEmbedRequest, ProtoGenerateRequest, ProtoStream,
-    },
+    backend::{Backend, BackendCapabilities},
+    proto_wrapper::{ProtoEmbedComplete, ProtoEmbedRequest, ProtoGenerateRequest, ProtoStream},
     MultimodalData,
 };
 
@@ -28,15 +29,9 @@ pub struct HealthCheckResponse {
     pub message: String,
 }
 
-/// TRT-LLM reports liveness via a free-form status string whose only healthy
-/// value is `"OK"` (per `trtllm_service.proto`); anything else is an error
-/// description and must be treated as unhealthy.
-fn trtllm_status_healthy(status: &str) -> bool {
-    status.trim().eq_ignore_ascii_case("ok")
-}
-
-/// Wraps the per-backend gRPC clients. RPCs absent on a backend's wire
-/// return `Status::unimplemented`.
+/// Wraps the per-backend gRPC clients. Request building, generation and
+/// health checks go through each client's [`Backend`] implementation; RPCs
+/// absent on a backend's wire return `Status::unimplemented`.
 #[derive(Clone)]
 pub enum GrpcClient {
     Sglang(SglangSchedulerClient),
@@ -46,6 +41,20 @@ pub enum GrpcClient {
     TokenSpeed(TokenSpeedSchedulerClient),
 }
 
+/// Bind the engine client inside a [`GrpcClient`] to `$client` and evaluate
+/// `$body` with it.
+macro_rules! dispatch {
+    ($self:expr, $client:ident => $body:expr) => {
+        match $self {
+            GrpcClient::Sglang($client) => $body,
+            GrpcClient::Vllm($client) => $body,
+            GrpcClient::Trtllm($client) => $body,
+            GrpcClient::Mlx($client) => $body,
+            GrpcClient::TokenSpeed($client) => $body,
+        }
+    };
+}
+
 #[derive(Default)]
 pub struct GenerateRequestBuildOptions {
     pub multimodal_inputs: Option<MultimodalData>,
@@ -184,79 +193,118 @@ impl GrpcClient {
         matches!(self, Self::TokenSpeed(_))
     }
 
+    /// Optional features of the backend behind this client.
+    pub fn capabilities(&self) -> BackendCapabilities {
+        dispatch!(self, client => client.capabilities())
+    }
+
+    /// Whether the backend samples `n > 1` choices from one request. Requests
+    /// to backends that don't are fanned out into `n` single-choice requests.
+    pub fn supports_native_n(&self) -> bool {
+        self.capabilities().native_n
+    }
+
     /// Runtime type backing this client. Lets shared logic (e.g. the multimodal
     /// capability matrix) key on the backend without matching every variant.
     pub fn runtime_type(&self) -> crate::worker::RuntimeType {
-        use crate::worker::RuntimeType;
-        match self {
-            Self::Sglang(_) => RuntimeType::Sglang,
-            Self::Vllm(_) => RuntimeType::Vllm,
-            Self::Trtllm(_) => RuntimeType::Trtllm,
-            Self::Mlx(_) => RuntimeType::Mlx,
-            Self::TokenSpeed(_) => RuntimeType::TokenSpeed,
-        }
+        dispatch!(self, client => client.runtime_type())
     }
 
+    /// Connect to a backend of `runtime_type`, over TLS when the worker has
+    /// a `grpc_tls` configuration.
     pub async fn connect(
         url: &str,
         runtime_type: &str,
+        tls: Option<&GrpcTlsConfig>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         match runtime_type {
-            "sglang" => Ok(Self::Sglang(SglangSchedulerClient::connect(url).await?)),
-            "vllm" => Ok(Self::Vllm(VllmEngineClient::connect(url).await?)),
-            "trtllm" | "tensorrt-llm" => Ok(Self::Trtllm(TrtllmServiceClient::connect(url).await?)),
-            "mlx" => Ok(Self::Mlx(MlxEngineClient::connect(url).await?)),
+            "sglang" => Ok(Self::Sglang(
+                SglangSchedulerClient::connect_with_tls(url, tls).await?,
+            )),
+            "vllm" => Ok(Self::Vllm(
+                VllmEngineClient::connect_with_tls(url, tls).await?,
+            )),
+            "trtllm" | "tensorrt-llm" => Ok(Self::Trtllm(
+                TrtllmServiceClient::connect_with_tls(url, tls).await?,
+            )),
+            "mlx" => Ok(Self::Mlx(
+                MlxEngineClient::connect_with_tls(url, tls).await?,
+            )),
             "tokenspeed" => Ok(Self::TokenSpeed(
-                TokenSpeedSchedulerClient::connect(url).await?,
+                TokenSpeedSchedulerClient::connect_with_tls(url, tls).await?,
             )),
             _ => Err(format!("Unknown runtime type: {runtime_type}").into()),
         }
     }
 
-    pub async fn health_check(&self) -> Result<HealthCheckResponse, tonic::Status> {
+    /// Connect and negotiate the proto revision from the backend's server
+    /// info. A backend that fails to report server info is assumed to speak
+    /// the current revision.
+    pub async fn connect_negotiated(
+        url: &str,
+        runtime_type: &str,
+        tls: Option<&GrpcTlsConfig>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = Self::connect(url, runtime_type, tls).await?;
+        match client.get_server_info().await {
+            Ok(info) => {
+                client.negotiate_proto(&info);
+            }
+            Err(e) => warn!(
+                "Failed to fetch gRPC server info from {} for proto negotiation: {}",
+                url, e
+            ),
+        }
+        Ok(client)
+    }
+
+    /// Adopt the proto revision advertised in `info`, enabling the
+    /// compatibility shims older servicers need.
+    pub fn negotiate_proto(&mut self, info: &ServerInfo) -> ProtoCompat {
+        let negotiated = match (&mut *self, info) {
+            (Self::Sglang(client), ServerInfo::Sglang(info)) => Some(client.negotiate_proto(info)),
+            (Self::Vllm(client), ServerInfo::Vllm(info)) => Some(client.negotiate_proto(info)),
+            (Self::Trtllm(client), ServerInfo::Trtllm(info)) => Some(client.negotiate_proto(info)),
+            (Self::Mlx(client), ServerInfo::Mlx(info)) => Some(client.negotiate_proto(info)),
+            (Self::TokenSpeed(client), ServerInfo::TokenSpeed(info)) => {
+                Some(client.negotiate_proto(info))
+            }
+            _ => None,
+        };
+        let Some(compat) = negotiated else {
+            return self.proto_compat();
+        };
+        if compat.is_legacy() {
+            warn!(
+                "{} backend predates proto revision negotiation; applying compatibility shims",
+                self.runtime_type()
+            );
+        }
+        compat
+    }
+
+    /// Proto revision negotiated with the backend.
+    pub fn proto_compat(&self) -> ProtoCompat {
         match self {
-            Self::Sglang(client) => {
-                let resp = client.health_check().await?;
-                Ok(HealthCheckResponse {
-                    healthy: resp.healthy,
-                    message: resp.message,
-                })
-            }
-            Self::Vllm(client) => {
-                let resp = client.health_check().await?;
-                Ok(HealthCheckResponse {
-                    healthy: resp.healthy,
-                    message: resp.message,
-                })
-            }
-            Self::Trtllm(client) => {
-                let resp = client.health_check().await?;
-                let healthy = trtllm_status_healthy(&resp.status);
-                Ok(HealthCheckResponse {
-                    healthy,
-                    message: resp.status,
-                })
-            }
-            Self::Mlx(client) => {
-                let resp = client.health_check().await?;
-                Ok(HealthCheckResponse {
-                    healthy: resp.healthy,
-                    message: resp.message,
-                })
-            }
-            Self::TokenSpeed(client) => {
-                let resp = client.health_check().await?;
-                Ok(HealthCheckResponse {
-                    healthy: resp.healthy,
-                    message: resp.message,
-                })
-            }
+            Self::Sglang(client) => client.proto_compat(),
+            Self::Vllm(client) => client.proto_compat(),
+            Self::Trtllm(client) => client.proto_compat(),
+            Self::Mlx(client) => client.proto_compat(),
+            Self::TokenSpeed(client) => client.proto_compat(),
         }
     }
 
+    pub async fn health_check(&self) -> Result<HealthCheckResponse, tonic::Status> {
+        dispatch!(self, client => client.check_health().await)
+    }
+
     pub async fn get_model_info(&self) -> Result<ModelInfo, tonic::Status> {
         match self {
-            Self::Sglang(client) => Ok(ModelInfo::Sglang(Box::new(client.get_model_info().await?))),
+            Self::Sglang(client) => {
+                let mut info = client.get_model_info().await?;
+                client.proto_compat().shim_sglang_model_info(&mut info);
+                Ok(ModelInfo::Sglang(Box::new(info)))
+            }
             Self::Vllm(client) => Ok(ModelInfo::Vllm(client.get_model_info().await?)),
             Self::Trtllm(client) => Ok(ModelInfo::Trtllm(client.get_model_info().await?)),
             Self::Mlx(client) => Ok(ModelInfo::Mlx(client.get_model_info().await?)),
@@ -392,86 +440,41 @@ impl GrpcClient {
         Ok(bundle)
     }
 
-    /// Generate streaming response from request
+    /// Submit a request built by this client's backend.
     ///
-    /// Dispatches to the appropriate backend client and wraps the result in ProtoStream.
     /// Returns `tonic::Status` on error so callers can inspect the gRPC status code directly.
     pub async fn generate(
         &mut self,
         req: ProtoGenerateRequest,
     ) -> Result<ProtoStream, tonic::Status> {
-        match (self, req) {
-            (Self::Sglang(client), ProtoGenerateRequest::Sglang(boxed_req)) => {
-                let stream = client.generate(*boxed_req).await?;
-                Ok(ProtoStream::Sglang(stream))
-            }
-            (Self::Vllm(client), ProtoGenerateRequest::Vllm(boxed_req)) => {
-                let shm_handles = collect_vllm_generate_request_shm_handles(&boxed_req);
-                match client.generate(*boxed_req).await {
-                    Ok(stream) => Ok(ProtoStream::Vllm(stream)),
-                    Err(error) => {
-                        cleanup_mm_shm_handles(&shm_handles);
-                        Err(error)
-                    }
-                }
-            }
-            (Self::Trtllm(client), ProtoGenerateRequest::Trtllm(boxed_req)) => {
-                let stream = client.generate(*boxed_req).await?;
-                Ok(ProtoStream::Trtllm(stream))
-            }
-            (Self::Mlx(client), ProtoGenerateRequest::Mlx(boxed_req)) => {
-                let stream = client.generate(*boxed_req).await?;
-                Ok(ProtoStream::Mlx(stream))
-            }
-            (Self::TokenSpeed(client), ProtoGenerateRequest::TokenSpeed(boxed_req)) => {
-                let shm_handles = collect_tokenspeed_generate_request_shm_handles(&boxed_req);
-                match client.generate(*boxed_req).await {
-                    Ok(stream) => Ok(ProtoStream::TokenSpeed(stream)),
-                    Err(error) => {
-                        cleanup_mm_shm_handles(&shm_handles);
-                        Err(error)
-                    }
-                }
-            }
-            #[expect(
-                clippy::panic,
-                reason = "client and request types are always matched by construction in the pipeline"
-            )]
-            _ => panic!("Mismatched client and request types"),
-        }
+        dispatch!(self, client => client.submit(req).await)
     }
 
     pub async fn embed(
         &mut self,
         req: ProtoEmbedRequest,
     ) -> Result<ProtoEmbedComplete, tonic::Status> {
-        match (self, req) {
-            (Self::Sglang(client), ProtoEmbedRequest::Sglang(boxed_req)) => {
-                let resp = client.embed(*boxed_req).await?;
-                Ok(ProtoEmbedComplete::Sglang(resp))
-            }
-            (Self::Vllm(client), ProtoEmbedRequest::Vllm(boxed_req)) => {
-                let resp = client.embed(*boxed_req).await?;
-                Ok(ProtoEmbedComplete::Vllm(resp))
-            }
-            (Self::TokenSpeed(_), _) => Err(tonic::Status::unimplemented(
-                "TokenSpeed backend does not support embedding",
-            )),
-            (Self::Mlx(_), _) => Err(tonic::Status::unimplemented(
-                "MLX backend does not support embedding",
-            )),
-            #[expect(
-                clippy::panic,
-                reason = "client and request types are always matched by construction in the pipeline"
-            )]
-            _ => panic!("Mismatched client and request types or unsupported embedding backend"),
-        }
+        dispatch!(self, client => client.submit_embed(req).await)
+    }
+
+    /// Build an embed request, or `None` when the backend serves no
+    /// embeddings.
+    pub fn build_embed_request(
+        &self,
+        request_id: String,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Option<ProtoEmbedRequest> {
+        dispatch!(self, client => {
+            client.build_embedding_request(request_id, original_text, token_ids)
+        })
+    }
+
+    /// Abort an in-flight request on the backend.
+    pub async fn abort(&self, request_id: String) -> Result<(), tonic::Status> {
+        dispatch!(self, client => client.abort(request_id).await)
     }
 
-    #[expect(
-        clippy::unreachable,
-        reason = "assembly stage guarantees matching MultimodalData variant for each backend"
-    )]
     pub fn build_chat_request(
         &self,
         request_id: String,
@@ -480,90 +483,11 @@ impl GrpcClient {
         token_ids: Vec<u32>,
         options: GenerateRequestBuildOptions,
     ) -> Result<ProtoGenerateRequest, String> {
-        match self {
-            Self::Sglang(client) => {
-                let sglang_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Sglang(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                let req = client.build_generate_request_from_chat(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    SglangGenerateRequestOptions {
-                        multimodal_inputs: sglang_mm,
-                        tool_call_constraint: options.tool_constraints,
-                        require_reasoning: options.require_reasoning,
-                    },
-                )?;
-                Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
-            }
-            Self::Vllm(client) => {
-                let vllm_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Vllm(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                finish_vllm_request(vllm_mm, |mm| {
-                    client.build_generate_request_from_chat(
-                        request_id,
-                        body,
-                        processed_text,
-                        token_ids,
-                        mm,
-                        options.tool_constraints,
-                    )
-                })
-            }
-            Self::Trtllm(client) => {
-                let trtllm_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Trtllm(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                let req = client.build_generate_request_from_chat(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    trtllm_mm,
-                    options.tool_constraints,
-                )?;
-                Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
-            }
-            // MLX: caller stage rejects multimodal before reaching this path.
-            Self::Mlx(client) => {
-                let req = client.build_generate_request_from_chat(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    options.tool_constraints,
-                )?;
-                Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
-            }
-            Self::TokenSpeed(client) => {
-                let tokenspeed_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::TokenSpeed(data) => data.into_proto(true),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                finish_tokenspeed_request(tokenspeed_mm, |mm| {
-                    client.build_generate_request_from_chat(
-                        request_id,
-                        body,
-                        processed_text,
-                        token_ids,
-                        mm,
-                        options.tool_constraints,
-                    )
-                })
-            }
-        }
+        dispatch!(self, client => {
+            client.build_chat_request(request_id, body, processed_text, token_ids, options)
+        })
     }
 
-    #[expect(
-        clippy::unreachable,
-        reason = "assembly stage guarantees matching MultimodalData variant for each backend"
-    )]
     pub fn build_messages_request(
         &self,
         request_id: String,
@@ -572,84 +496,28 @@ impl GrpcClient {
         token_ids: Vec<u32>,
         options: GenerateRequestBuildOptions,
     ) -> Result<ProtoGenerateRequest, String> {
-        match self {
-            Self::Sglang(client) => {
-                let sglang_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Sglang(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                let req = client.build_generate_request_from_messages(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    SglangGenerateRequestOptions {
-                        multimodal_inputs: sglang_mm,
-                        tool_call_constraint: options.tool_constraints,
-                        require_reasoning: options.require_reasoning,
-                    },
-                )?;
-                Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
-            }
-            Self::Vllm(client) => {
-                let vllm_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Vllm(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                finish_vllm_request(vllm_mm, |mm| {
-                    client.build_generate_request_from_messages(
-                        request_id,
-                        body,
-                        processed_text,
-                        token_ids,
-                        mm,
-                        options.tool_constraints,
-                    )
-                })
-            }
-            Self::Trtllm(client) => {
-                let trtllm_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::Trtllm(data) => data.into_proto(),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                let req = client.build_generate_request_from_messages(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    trtllm_mm,
-                    options.tool_constraints,
-                )?;
-                Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
-            }
-            // MLX: caller stage rejects multimodal before reaching this path.
-            Self::Mlx(client) => {
-                let req = client.build_generate_request_from_messages(
-                    request_id,
-                    body,
-                    processed_text,
-                    token_ids,
-                    options.tool_constraints,
-                )?;
-                Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
-            }
-            Self::TokenSpeed(client) => {
-                let tokenspeed_mm = options.multimodal_inputs.map(|mm| match mm {
-                    MultimodalData::TokenSpeed(data) => data.into_proto(true),
-                    _ => unreachable!("caller guarantees matching variant"),
-                });
-                finish_tokenspeed_request(tokenspeed_mm, |mm| {
-                    client.build_generate_request_from_messages(
-                        request_id,
-                        body,
-                        processed_text,
-                        token_ids,
-                        mm,
-                        options.tool_constraints,
-                    )
-                })
-            }
-        }
+        dispatch!(self, client => {
+            client.build_messages_request(request_id, body, processed_text, token_ids, options)
+        })
+    }
+
+    pub fn build_responses_request(
+        &self,
+        request_id: String,
+        body: &ResponsesRequest,
+        processed_text: String,
+        token_ids: Vec<u32>,
+        tool_constraints: Option<(String, String)>,
+    ) -> Result<ProtoGenerateRequest, String> {
+        dispatch!(self, client => {
+            client.build_responses_request(
+                request_id,
+                body,
+                processed_text,
+                token_ids,
+                tool_constraints,
+            )
+        })
     }
 
     pub fn build_completion_request(
@@ -659,53 +527,9 @@ impl GrpcClient {
         original_text: String,
         token_ids: Vec<u32>,
     ) -> Result<ProtoGenerateRequest, String> {
-        match self {
-            Self::Sglang(client) => {
-                let req = client.build_generate_request_from_completion(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
-            }
-            Self::Vllm(client) => {
-                let req = client.build_generate_request_from_completion(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Vllm(Box::new(req)))
-            }
-            Self::Trtllm(client) => {
-                let req = client.build_generate_request_from_completion(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
-            }
-            Self::Mlx(client) => {
-                let req = client.build_generate_request_from_completion(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
-            }
-            Self::TokenSpeed(client) => {
-                let req = client.build_generate_request_from_completion(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::TokenSpeed(Box::new(req)))
-            }
-        }
+        dispatch!(self, client => {
+            client.build_completion_request(request_id, body, original_text, token_ids)
+        })
     }
 
     pub fn build_generate_request(
@@ -715,53 +539,9 @@ impl GrpcClient {
         original_text: Option<String>,
         token_ids: Vec<u32>,
     ) -> Result<ProtoGenerateRequest, String> {
-        match self {
-            Self::Sglang(client) => {
-                let req = client.build_plain_generate_request(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Sglang(Box::new(req)))
-            }
-            Self::Vllm(client) => {
-                let req = client.build_plain_generate_request(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Vllm(Box::new(req)))
-            }
-            Self::Trtllm(client) => {
-                let req = client.build_plain_generate_request(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Trtllm(Box::new(req)))
-            }
-            Self::Mlx(client) => {
-                let req = client.build_plain_generate_request(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::Mlx(Box::new(req)))
-            }
-            Self::TokenSpeed(client) => {
-                let req = client.build_plain_generate_request(
-                    request_id,
-                    body,
-                    original_text,
-                    token_ids,
-                )?;
-                Ok(ProtoGenerateRequest::TokenSpeed(Box::new(req)))
-            }
-        }
+        dispatch!(self, client => {
+            client.build_generate_request(request_id, body, original_text, token_ids)
+        })
     }
 }
 
@@ -810,6 +590,12 @@ impl ServerInfo {
                 if !info.sglang_version.is_empty() {
                     labels.insert("version".to_string(), info.sglang_version.clone());
                 }
+                if info.proto_revision > 0 {
+                    labels.insert(
+                        "proto_revision".to_string(),
+                        info.proto_revision.to_string(),
+                    );
+                }
                 labels
             }
             ServerInfo::Vllm(info) => flat_labels(info),
@@ -823,6 +609,12 @@ impl ServerInfo {
                 if !info.tokenspeed_version.is_empty() {
                     labels.insert("version".to_string(), info.tokenspeed_version.clone());
                 }
+                if info.proto_revision > 0 {
+                    labels.insert(
+                        "proto_revision".to_string(),
+                        info.proto_revision.to_string(),
+                    );
+                }
                 // Carry the worker's /dev/shm namespace identity (advertised in
                 // scheduler_info). The router compares it to its own to decide the
                 // SHM tensor transport by *verifying* a shared /dev/shm rather than
@@ -944,19 +736,7 @@ mod tests {
 
     use smg_grpc_client::{sglang_proto, tokenspeed_proto};
 
-    use super::{trtllm_status_healthy, ModelInfo, ServerInfo};
-
-    #[test]
-    fn trtllm_status_healthy_matches_ok_exactly() {
-        assert!(trtllm_status_healthy("ok"));
-        assert!(trtllm_status_healthy("OK"));
-        assert!(trtllm_status_healthy("  OK  "));
-
-        assert!(!trtllm_status_healthy("not ok"));
-        assert!(!trtllm_status_healthy("checking"));
-        assert!(!trtllm_status_healthy("degraded"));
-        assert!(!trtllm_status_healthy(""));
-    }
+    use super::{ModelInfo, ServerInfo};
 
     fn string_value(s: &str) -> prost_types::Value {
         prost_types::Value {
@@ -1057,6 +837,22 @@ mod tests {
         assert!(!labels.contains_key("api_key"));
     }
 
+    #[test]
+    fn server_info_to_labels_surfaces_advertised_proto_revision() {
+        let info = ServerInfo::Sglang(Box::new(sglang_proto::GetServerInfoResponse {
+            proto_revision: 1,
+            ..Default::default()
+        }));
+        assert_eq!(
+            info.to_labels().get("proto_revision").map(String::as_str),
+            Some("1")
+        );
+
+        // Servicers that predate negotiation leave it unset.
+        let legacy = ServerInfo::Sglang(Box::default());
+        assert!(!legacy.to_labels().contains_key("proto_revision"));
+    }
+
     /// `GetModelInfoResponse` is flat for every backend, so it serializes via
     /// `flat_labels`: empty strings and zero numbers are skipped, booleans are
     /// kept, arrays are JSON-encoded.
@@ -276,6 +276,26 @@ impl ResponseStreamEventEmitter {
     // INVARIANT: this method is terminal — it drains internal state via `take()`
     // and must only be called once per emitter lifetime.
     pub fn emit_completed(&mut self, usage: Option<&serde_json::Value>) -> serde_json::Value {
+        self.emit_terminal(ResponseEvent::COMPLETED, "completed", None, usage)
+    }
+
+    /// Terminal `response.failed` event carrying `error` (`{"code", "message"}`)
+    /// and the output completed so far. Same invariant as [`Self::emit_completed`].
+    pub fn emit_failed(
+        &mut self,
+        error: serde_json::Value,
+        usage: Option<&serde_json::Value>,
+    ) -> serde_json::Value {
+        self.emit_terminal(ResponseEvent::FAILED, "failed", Some(error), usage)
+    }
+
+    fn emit_terminal(
+        &mut self,
+        event_type: &str,
+        status: &str,
+        error: Option<serde_json::Value>,
+        usage: Option<&serde_json::Value>,
+    ) -> serde_json::Value {
         // Build output array from tracked items
         let output: Vec<serde_json::Value> = self
             .output_items
@@ -290,7 +310,7 @@ impl ResponseStreamEventEmitter {
             .collect();
 
         // If no items were tracked (legacy path), fall back to generic message
-        let output = if output.is_empty() {
+        let output = if output.is_empty() && error.is_none() {
             vec![json!({
                 "id": std::mem::take(&mut self.message_id),
                 "type": "message",
@@ -309,11 +329,15 @@ impl ResponseStreamEventEmitter {
             "id": self.response_id,
             "object": "response",
             "created_at": self.created_at,
-            "status": "completed",
+            "status": status,
             "model": self.model,
             "output": output
         });
 
+        if let Some(error) = error {
+            response_obj["error"] = error;
+        }
+
         // Add usage if provided
         if let Some(usage_val) = usage {
             response_obj["usage"] = usage_val.clone();
@@ -359,7 +383,7 @@ impl ResponseStreamEventEmitter {
         }
 
         json!({
-            "type": ResponseEvent::COMPLETED,
+            "type": event_type,
             "sequence_number": self.next_sequence(),
             "response": response_obj
         })
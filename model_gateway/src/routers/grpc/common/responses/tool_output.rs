@@ -0,0 +1,283 @@
+//! Token budget for tool output fed back to the model.
+//!
+//! Caps come from `limits.output_tokens` in the MCP config and are counted
+//! with the request model's tokenizer. Only the continuation prompt is
+//! shortened; the tool call items returned to the client keep the full
+//! output. Without a tokenizer for the model, outputs pass through unchanged.
+
+use std::{collections::HashMap, sync::Arc};
+
+use llm_tokenizer::traits::Tokenizer;
+use serde_json::{Map, Value};
+use smg_mcp::{McpToolSession, TruncationStrategy};
+use tracing::{debug, warn};
+
+/// Strings longer than this are shortened in a summary outline.
+const SUMMARY_STRING_CHARS: usize = 200;
+/// Array items kept in a summary outline.
+const SUMMARY_ARRAY_ITEMS: usize = 3;
+/// Nesting depth below which objects and arrays are reduced to counts.
+const SUMMARY_MAX_DEPTH: usize = 4;
+
+/// Tracks the tool output token budget across one response.
+pub(crate) struct ToolOutputBudget {
+    tokenizer: Option<Arc<dyn Tokenizer>>,
+    strategy: TruncationStrategy,
+    per_call: Option<usize>,
+    /// Per-tool caps keyed by the tool name exposed to the model.
+    per_tool: HashMap<String, usize>,
+    remaining: Option<usize>,
+}
+
+impl ToolOutputBudget {
+    pub fn new(session: &McpToolSession<'_>, tokenizer: Option<Arc<dyn Tokenizer>>) -> Self {
+        let limits = &session.orchestrator().tool_limits().output_tokens;
+        if limits.is_enabled() && tokenizer.is_none() {
+            debug!("No tokenizer for model; tool output token limits not applied");
+        }
+        let per_tool = session
+            .exposed_name_by_qualified()
+            .iter()
+            .filter_map(|(qualified, exposed)| {
+                limits
+                    .limit_for(qualified.server_key(), qualified.tool_name())
+                    .map(|cap| (exposed.clone(), cap))
+            })
+            .collect();
+
+        Self {
+            tokenizer,
+            strategy: limits.strategy,
+            per_call: limits.per_call,
+            per_tool,
+            remaining: limits.total,
+        }
+    }
+
+    /// Shorten `output` of `tool_name` to its cap and charge it to the
+    /// response-wide budget.
+    pub fn fit(&mut self, tool_name: &str, output: String) -> String {
+        let cap = self.per_tool.get(tool_name).copied().or(self.per_call);
+        let cap = match (cap, self.remaining) {
+            (Some(cap), Some(remaining)) => Some(cap.min(remaining)),
+            (cap, remaining) => cap.or(remaining),
+        };
+        let (Some(cap), Some(tokenizer)) = (cap, self.tokenizer.clone()) else {
+            return output;
+        };
+
+        let ids = match tokenizer.encode(&output, false) {
+            Ok(encoding) => encoding.token_ids().to_vec(),
+            Err(e) => {
+                warn!(tool = %tool_name, error = %e, "Failed to tokenize tool output");
+                return output;
+            }
+        };
+        if ids.len() <= cap {
+            self.charge(ids.len());
+            return output;
+        }
+
+        debug!(
+            tool = %tool_name,
+            tokens = ids.len(),
+            cap,
+            "Truncating tool output fed back to the model"
+        );
+        self.charge(cap);
+        match truncate(&*tokenizer, &output, &ids, cap, self.strategy) {
+            Some(truncated) => truncated,
+            None => {
+                warn!(tool = %tool_name, "Failed to decode truncated tool output");
+                omitted(ids.len())
+            }
+        }
+    }
+
+    fn charge(&mut self, tokens: usize) {
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(tokens);
+        }
+    }
+}
+
+fn omitted(total: usize) -> String {
+    format!("[tool output omitted: {total} tokens exceeds the remaining budget]")
+}
+
+fn truncate(
+    tokenizer: &dyn Tokenizer,
+    output: &str,
+    ids: &[u32],
+    cap: usize,
+    strategy: TruncationStrategy,
+) -> Option<String> {
+    let total = ids.len();
+    if cap == 0 {
+        return Some(omitted(total));
+    }
+    match strategy {
+        TruncationStrategy::Head => head(tokenizer, ids, cap),
+        TruncationStrategy::HeadTail => head_tail(tokenizer, ids, cap),
+        TruncationStrategy::Summary => match serde_json::from_str::<Value>(output) {
+            Ok(value) => {
+                let summary = outline(&value, 0).to_string();
+                let summary_ids = tokenizer.encode(&summary, false).ok()?;
+                let summary_ids = summary_ids.token_ids();
+                if summary_ids.len() <= cap {
+                    Some(format!(
+                        "{summary}\n[output summarized: {total} tokens exceeds the {cap} token limit]"
+                    ))
+                } else {
+                    head(tokenizer, summary_ids, cap)
+                }
+            }
+            Err(_) => head_tail(tokenizer, ids, cap),
+        },
+    }
+}
+
+fn head(tokenizer: &dyn Tokenizer, ids: &[u32], cap: usize) -> Option<String> {
+    let text = tokenizer.decode(&ids[..cap], false).ok()?;
+    Some(format!(
+        "{text}\n[output truncated: showing {cap} of {} tokens]",
+        ids.len()
+    ))
+}
+
+fn head_tail(tokenizer: &dyn Tokenizer, ids: &[u32], cap: usize) -> Option<String> {
+    let tail_len = cap / 2;
+    let head_len = cap - tail_len;
+    let head = tokenizer.decode(&ids[..head_len], false).ok()?;
+    let tail = tokenizer.decode(&ids[ids.len() - tail_len..], false).ok()?;
+    Some(format!(
+        "{head}\n[... {} tokens omitted ...]\n{tail}",
+        ids.len() - cap
+    ))
+}
+
+/// Structural outline of a JSON value: long strings shortened, arrays cut to
+/// a few items, and deep nesting reduced to counts.
+fn outline(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::String(s) => {
+            let chars = s.chars().count();
+            if chars <= SUMMARY_STRING_CHARS {
+                return value.clone();
+            }
+            let prefix: String = s.chars().take(SUMMARY_STRING_CHARS).collect();
+            Value::String(format!("{prefix}... ({chars} chars)"))
+        }
+        Value::Array(items) if depth >= SUMMARY_MAX_DEPTH => {
+            Value::String(format!("[{} items]", items.len()))
+        }
+        Value::Array(items) => {
+            let mut kept: Vec<Value> = items
+                .iter()
+                .take(SUMMARY_ARRAY_ITEMS)
+                .map(|item| outline(item, depth + 1))
+                .collect();
+            if items.len() > SUMMARY_ARRAY_ITEMS {
+                kept.push(Value::String(format!(
+                    "... {} more items",
+                    items.len() - SUMMARY_ARRAY_ITEMS
+                )));
+            }
+            Value::Array(kept)
+        }
+        Value::Object(map) if depth >= SUMMARY_MAX_DEPTH => {
+            Value::String(format!("{{{} keys}}", map.len()))
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), outline(value, depth + 1)))
+                .collect::<Map<_, _>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use llm_tokenizer::mock::MockTokenizer;
+    use serde_json::json;
+
+    use super::*;
+
+    fn budget(per_call: Option<usize>, total: Option<usize>) -> ToolOutputBudget {
+        ToolOutputBudget {
+            tokenizer: Some(Arc::new(MockTokenizer::new())),
+            strategy: TruncationStrategy::Head,
+            per_call,
+            per_tool: HashMap::from([("search".to_string(), 2)]),
+            remaining: total,
+        }
+    }
+
+    #[test]
+    fn test_per_call_and_per_tool_caps() {
+        let mut budget = budget(Some(3), None);
+        let output = "Hello world test token Hello".to_string();
+
+        assert_eq!(
+            budget.fit("fetch", output.clone()),
+            "Hello world test\n[output truncated: showing 3 of 5 tokens]"
+        );
+        assert_eq!(
+            budget.fit("search", output),
+            "Hello world\n[output truncated: showing 2 of 5 tokens]"
+        );
+        assert_eq!(budget.fit("fetch", "Hello".to_string()), "Hello");
+    }
+
+    #[test]
+    fn test_total_budget_is_shared() {
+        let mut budget = budget(None, Some(4));
+        assert_eq!(
+            budget.fit("fetch", "Hello world test".to_string()),
+            "Hello world test"
+        );
+        assert_eq!(
+            budget.fit("fetch", "Hello world".to_string()),
+            "Hello\n[output truncated: showing 1 of 2 tokens]"
+        );
+        assert_eq!(
+            budget.fit("fetch", "Hello world".to_string()),
+            "[tool output omitted: 2 tokens exceeds the remaining budget]"
+        );
+    }
+
+    #[test]
+    fn test_head_tail_keeps_both_ends() {
+        let mut budget = budget(Some(3), None);
+        budget.strategy = TruncationStrategy::HeadTail;
+        assert_eq!(
+            budget.fit("fetch", "Hello world test token Hello world".to_string()),
+            "Hello world\n[... 3 tokens omitted ...]\nworld"
+        );
+    }
+
+    #[test]
+    fn test_without_tokenizer_output_is_unchanged() {
+        let mut budget = budget(Some(1), Some(1));
+        budget.tokenizer = None;
+        let output = "Hello world test".to_string();
+        assert_eq!(budget.fit("fetch", output.clone()), output);
+    }
+
+    #[test]
+    fn test_summary_outline() {
+        let value = json!({
+            "rows": [1, 2, 3, 4, 5],
+            "note": "x".repeat(SUMMARY_STRING_CHARS + 10),
+            "nested": { "a": { "b": { "c": { "d": 1 } } } },
+        });
+        let summary = outline(&value, 0);
+        assert_eq!(summary["rows"], json!([1, 2, 3, "... 2 more items"]));
+        assert!(summary["note"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("... ({} chars)", SUMMARY_STRING_CHARS + 10)));
+        assert_eq!(summary["nested"]["a"]["b"]["c"], json!("{1 keys}"));
+    }
+}
@@ -23,6 +23,7 @@ use crate::{
         },
         error,
     },
+    tenant::TenantKey,
     worker::WorkerRegistry,
 };
 
@@ -152,6 +153,7 @@ pub(crate) async fn persist_response_if_needed(
     response_storage: Arc<dyn ResponseStorage>,
     response: &ResponsesResponse,
     original_request: &ResponsesRequest,
+    tenant_key: Option<&TenantKey>,
     request_context: Option<StorageRequestContext>,
 ) {
     if !original_request.store.unwrap_or(true) {
@@ -165,6 +167,7 @@ pub(crate) async fn persist_response_if_needed(
             response_storage,
             &response_json,
             original_request,
+            tenant_key,
             request_context,
         )
         .await
@@ -0,0 +1,375 @@
+//! Custom stages: configured stages spliced in after built-in stages
+//!
+//! Built-in stages are identified by the suffix of their `name()` (e.g.
+//! `ChatGeneratePreparation` is the `preparation` anchor), so every endpoint's
+//! pipeline exposes the same anchors regardless of its concrete stages.
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, response::Response};
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+use super::PipelineStage;
+use crate::{
+    config::{CustomStageConfig, CustomStageKind, PipelineEndpoint, PipelineStageAnchor},
+    routers::{
+        error,
+        grpc::context::{PreparationOutput, RequestContext, RequestType},
+    },
+    wasm::{
+        body_fields::request_body_for_module,
+        module::{MiddlewareAttachPoint, WasmModuleAttachPoint},
+        module_manager::WasmModuleManager,
+        spec::{
+            apply_modify_action_to_headers, build_wasm_headers_from_axum_headers,
+            smg::gateway::middleware_types::{Action, Request as WasmRequest},
+        },
+        types::WasmComponentInput,
+    },
+};
+
+/// The anchor a built-in stage provides, from its name.
+fn anchor_of(stage_name: &str) -> Option<PipelineStageAnchor> {
+    [
+        ("Preparation", PipelineStageAnchor::Preparation),
+        ("WorkerSelection", PipelineStageAnchor::WorkerSelection),
+        ("ClientAcquisition", PipelineStageAnchor::ClientAcquisition),
+        ("RequestBuilding", PipelineStageAnchor::RequestBuilding),
+        ("DispatchMetadata", PipelineStageAnchor::DispatchMetadata),
+    ]
+    .into_iter()
+    .find_map(|(suffix, anchor)| stage_name.ends_with(suffix).then_some(anchor))
+}
+
+/// Insert the configured stages that apply to `endpoint` after their anchors.
+pub(crate) fn splice_custom_stages(
+    stages: Vec<Box<dyn PipelineStage>>,
+    endpoint: PipelineEndpoint,
+    custom: &[CustomStageConfig],
+    wasm_manager: Option<&Arc<WasmModuleManager>>,
+) -> Vec<Box<dyn PipelineStage>> {
+    if custom.is_empty() {
+        return stages;
+    }
+    let mut spliced = Vec::with_capacity(stages.len() + custom.len());
+    for stage in stages {
+        let anchor = anchor_of(stage.name());
+        spliced.push(stage);
+        let Some(anchor) = anchor else {
+            continue;
+        };
+        spliced.extend(
+            custom
+                .iter()
+                .filter(|c| c.after == anchor)
+                .filter(|c| c.endpoints.is_empty() || c.endpoints.contains(&endpoint))
+                .map(|c| build_stage(c, wasm_manager)),
+        );
+    }
+    spliced
+}
+
+fn build_stage(
+    config: &CustomStageConfig,
+    wasm_manager: Option<&Arc<WasmModuleManager>>,
+) -> Box<dyn PipelineStage> {
+    match &config.kind {
+        CustomStageKind::MaxPromptTokens { max_tokens } => Box::new(MaxPromptTokensStage {
+            name: config.name.clone(),
+            max_tokens: *max_tokens,
+        }),
+        CustomStageKind::Wasm { module } => Box::new(WasmStage {
+            name: config.name.clone(),
+            module: module.clone(),
+            wasm_manager: wasm_manager.cloned(),
+        }),
+    }
+}
+
+/// Rejects requests whose longest tokenized prompt exceeds `max_tokens`.
+pub(crate) struct MaxPromptTokensStage {
+    name: String,
+    max_tokens: usize,
+}
+
+fn longest_prompt(preparation: &PreparationOutput) -> usize {
+    match preparation {
+        PreparationOutput::Completion { items, .. }
+        | PreparationOutput::EmbeddingBatch { items, .. } => items
+            .iter()
+            .map(|item| item.token_ids.len())
+            .max()
+            .unwrap_or(0),
+        PreparationOutput::Rerank { pairs, .. } => pairs
+            .iter()
+            .map(|pair| pair.token_ids.len())
+            .max()
+            .unwrap_or(0),
+        other => other.token_ids().len(),
+    }
+}
+
+#[async_trait]
+impl PipelineStage for MaxPromptTokensStage {
+    async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        let Some(preparation) = ctx.state.preparation.as_ref() else {
+            return Ok(None);
+        };
+        let prompt_tokens = longest_prompt(preparation);
+        if prompt_tokens > self.max_tokens {
+            debug!(
+                stage = %self.name,
+                prompt_tokens,
+                max_tokens = self.max_tokens,
+                "Rejecting request over the prompt token limit"
+            );
+            return Err(error::bad_request(
+                "prompt_too_long",
+                format!(
+                    "Prompt has {prompt_tokens} tokens; at most {} are allowed",
+                    self.max_tokens
+                ),
+            ));
+        }
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "MaxPromptTokens"
+    }
+}
+
+/// Runs a WASM module's `on-request` export against the typed request.
+///
+/// The module is looked up by name on every request, so it can be loaded or
+/// replaced after startup; a missing module fails the request rather than
+/// silently skipping a configured check.
+pub(crate) struct WasmStage {
+    name: String,
+    module: String,
+    wasm_manager: Option<Arc<WasmModuleManager>>,
+}
+
+fn request_path(request: &RequestType) -> &'static str {
+    match request {
+        RequestType::Chat(_) => "/v1/chat/completions",
+        RequestType::Generate(_) => "/generate",
+        RequestType::Completion(_) => "/v1/completions",
+        RequestType::Responses(_) => "/v1/responses",
+        RequestType::Embedding(_) => "/v1/embeddings",
+        RequestType::Classify(_) => "/v1/classify",
+        RequestType::Rerank(_) => "/v1/rerank",
+        RequestType::Messages(_) => "/v1/messages",
+    }
+}
+
+fn request_json(request: &RequestType) -> serde_json::Result<Vec<u8>> {
+    match request {
+        RequestType::Chat(r) => serde_json::to_vec(r),
+        RequestType::Generate(r) => serde_json::to_vec(r),
+        RequestType::Completion(r) => serde_json::to_vec(r),
+        RequestType::Responses(r) => serde_json::to_vec(r),
+        RequestType::Embedding(r) => serde_json::to_vec(r),
+        RequestType::Classify(r) => serde_json::to_vec(r),
+        RequestType::Rerank(r) => serde_json::to_vec(r),
+        RequestType::Messages(r) => serde_json::to_vec(r),
+    }
+}
+
+fn reparse<T: DeserializeOwned>(slot: &mut Arc<T>, body: &[u8]) -> serde_json::Result<()> {
+    *slot = Arc::new(serde_json::from_slice(body)?);
+    Ok(())
+}
+
+/// Replace the request with the module's rewritten body, keeping its type.
+fn replace_request(request: &mut RequestType, body: &[u8]) -> serde_json::Result<()> {
+    match request {
+        RequestType::Chat(r) => reparse(r, body),
+        RequestType::Generate(r) => reparse(r, body),
+        RequestType::Completion(r) => reparse(r, body),
+        RequestType::Responses(r) => reparse(r, body),
+        RequestType::Embedding(r) => reparse(r, body),
+        RequestType::Classify(r) => reparse(r, body),
+        RequestType::Rerank(r) => reparse(r, body),
+        RequestType::Messages(r) => reparse(r, body),
+    }
+}
+
+#[async_trait]
+impl PipelineStage for WasmStage {
+    async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        let unavailable = || {
+            error::service_unavailable(
+                "pipeline_stage_unavailable",
+                format!(
+                    "WASM module '{}' for pipeline stage '{}' is not loaded",
+                    self.module, self.name
+                ),
+            )
+        };
+        let manager = self.wasm_manager.as_ref().ok_or_else(unavailable)?;
+        let module = manager
+            .get_modules()
+            .ok()
+            .and_then(|modules| {
+                modules
+                    .into_iter()
+                    .find(|m| m.module_meta.name == self.module)
+            })
+            .ok_or_else(unavailable)?;
+
+        let body = request_json(&ctx.input.request_type).map_err(|e| {
+            error::internal_error(
+                "serialization_error",
+                format!("Failed to serialize request for pipeline stage: {e}"),
+            )
+        })?;
+        let mut headers = ctx.input.headers.clone().unwrap_or_default();
+        let request_id = headers
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| ctx.input.request_type.rid())
+            .unwrap_or_default()
+            .to_string();
+        let (body, body_fields) = request_body_for_module(&module.module_meta, &body);
+        let wasm_request = WasmRequest {
+            method: "POST".to_string(),
+            path: request_path(&ctx.input.request_type).to_string(),
+            query: String::new(),
+            headers: build_wasm_headers_from_axum_headers(&headers),
+            body,
+            request_id,
+            now_epoch_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_millis() as u64,
+            body_fields,
+        };
+
+        // A module that fails to run returns no action and the request
+        // proceeds, matching the WASM middleware.
+        let Some(action) = manager
+            .execute_module_for_attach_point(
+                &module,
+                WasmModuleAttachPoint::Middleware(MiddlewareAttachPoint::OnRequest),
+                WasmComponentInput::MiddlewareRequest(wasm_request),
+            )
+            .await
+        else {
+            return Ok(None);
+        };
+
+        match action {
+            Action::Continue => {}
+            Action::Reject(status) => {
+                let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_REQUEST);
+                return Err(error::create_error(
+                    status,
+                    "rejected_by_pipeline_stage",
+                    format!("Request rejected by pipeline stage '{}'", self.name),
+                ));
+            }
+            Action::Modify(modify) => {
+                apply_modify_action_to_headers(&mut headers, &modify);
+                ctx.input.headers = Some(headers);
+                if let Some(body) = modify.body_replace {
+                    replace_request(&mut ctx.input.request_type, &body).map_err(|e| {
+                        warn!(stage = %self.name, error = %e, "Pipeline stage returned an invalid request body");
+                        error::internal_error(
+                            "invalid_stage_output",
+                            format!("Pipeline stage '{}' returned an invalid request: {e}", self.name),
+                        )
+                    })?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "Wasm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routers::grpc::common::stages::{ClientAcquisitionStage, DispatchMetadataStage};
+
+    fn stage(
+        name: &str,
+        after: PipelineStageAnchor,
+        endpoints: Vec<PipelineEndpoint>,
+    ) -> CustomStageConfig {
+        CustomStageConfig {
+            name: name.to_string(),
+            after,
+            endpoints,
+            kind: CustomStageKind::MaxPromptTokens { max_tokens: 16 },
+        }
+    }
+
+    #[test]
+    fn anchors_are_matched_by_stage_name_suffix() {
+        assert_eq!(
+            anchor_of("ChatGeneratePreparation"),
+            Some(PipelineStageAnchor::Preparation)
+        );
+        assert_eq!(
+            anchor_of("CompletionRequestBuilding"),
+            Some(PipelineStageAnchor::RequestBuilding)
+        );
+        assert_eq!(anchor_of("RequestExecution"), None);
+        assert_eq!(anchor_of("ChatGenerateResponseProcessing"), None);
+    }
+
+    #[test]
+    fn custom_stages_are_spliced_after_their_anchor_for_matching_endpoints() {
+        let builtin = || -> Vec<Box<dyn PipelineStage>> {
+            vec![
+                Box::new(ClientAcquisitionStage),
+                Box::new(DispatchMetadataStage),
+            ]
+        };
+        let custom = [
+            stage("a", PipelineStageAnchor::ClientAcquisition, vec![]),
+            stage(
+                "b",
+                PipelineStageAnchor::ClientAcquisition,
+                vec![PipelineEndpoint::Rerank],
+            ),
+            stage("c", PipelineStageAnchor::DispatchMetadata, vec![]),
+        ];
+
+        let names = |stages: Vec<Box<dyn PipelineStage>>| -> Vec<&'static str> {
+            stages.iter().map(|s| s.name()).collect()
+        };
+        assert_eq!(
+            names(splice_custom_stages(
+                builtin(),
+                PipelineEndpoint::Chat,
+                &custom,
+                None
+            )),
+            [
+                "ClientAcquisition",
+                "MaxPromptTokens",
+                "DispatchMetadata",
+                "MaxPromptTokens"
+            ]
+        );
+        assert_eq!(
+            splice_custom_stages(builtin(), PipelineEndpoint::Rerank, &custom, None).len(),
+            5
+        );
+        assert_eq!(
+            splice_custom_stages(builtin(), PipelineEndpoint::Chat, &[], None).len(),
+            2
+        );
+    }
+}
@@ -38,6 +38,7 @@ impl PipelineStage for DispatchMetadataStage {
             RequestType::Responses(req) => req.model.clone(),
             RequestType::Embedding(req) => req.model.clone(),
             RequestType::Classify(req) => req.model.clone(),
+            RequestType::Rerank(req) => req.model.clone(),
             RequestType::Messages(req) => req.model.clone(),
         };
 
@@ -62,6 +63,7 @@ impl PipelineStage for DispatchMetadataStage {
             model,
             created,
             weight_version: Some(weight_version),
+            prompt_breakdown: ctx.state.response.prompt_breakdown.take(),
         });
 
         Ok(None)
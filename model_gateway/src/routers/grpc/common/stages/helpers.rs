@@ -2,7 +2,6 @@
 
 use std::sync::Arc;
 
-use rand::RngExt;
 use smg_grpc_client::{
     mlx_proto,
     sglang_proto::{self, DisaggregatedParams},
@@ -12,9 +11,13 @@ use tracing::{debug, warn};
 
 use crate::{
     middleware::{RequestId, TenantRequestMeta},
-    routers::grpc::{
-        context::{RequestType, WorkerSelection},
-        proto_wrapper::ProtoGenerateRequest,
+    routers::{
+        common::bootstrap_rooms::{bootstrap_rooms, RoomLease, RoomWidth},
+        grpc::{
+            client::GrpcClient,
+            context::{ExecutionPlanKind, RequestType, WorkerSelection},
+            proto_wrapper::ProtoGenerateRequest,
+        },
     },
     worker::{
         sampling_defaults::SamplingDefaults, RuntimeType, Worker, DEFAULT_BOOTSTRAP_PORT,
@@ -69,9 +72,10 @@ impl SamplingDefaultsMask {
                 min_p: true,
                 repetition_penalty: true,
             }),
-            RequestType::Responses(_) | RequestType::Embedding(_) | RequestType::Classify(_) => {
-                None
-            }
+            RequestType::Responses(_)
+            | RequestType::Embedding(_)
+            | RequestType::Classify(_)
+            | RequestType::Rerank(_) => None,
         }
     }
 
@@ -80,6 +84,17 @@ impl SamplingDefaultsMask {
     }
 }
 
+/// Branch count when an `n > 1` request must be fanned out because the
+/// backend ignores `n`, else `None`. Only single-worker plans fan out.
+pub(crate) fn fan_out_choices(
+    client: &GrpcClient,
+    plan_kind: ExecutionPlanKind,
+    n: Option<u32>,
+) -> Option<u32> {
+    let n = n.filter(|&n| n > 1)?;
+    (plan_kind == ExecutionPlanKind::Single && !client.supports_native_n()).then_some(n)
+}
+
 /// The middleware-assigned request id (client-sent via a configured header,
 /// else generated; see `middleware/request_id.rs`), carried on the tenant
 /// request metadata.
@@ -295,19 +310,19 @@ fn apply_tokenspeed_sampling_defaults(
 ///
 /// SGLang uses DisaggregatedParams with bootstrap host/port/room.
 /// vLLM kv_transfer_params are handled in the request_execution stage.
+///
+/// Returns the lease on the injected room; keep it until the request is done.
 pub(crate) fn maybe_inject_pd_metadata(
     request: &mut ProtoGenerateRequest,
     workers: &WorkerSelection,
-) {
-    if let WorkerSelection::Disaggregated {
-        prefill,
-        runtime_type,
-        ..
-    } = workers
-    {
-        if *runtime_type == RuntimeType::Sglang {
-            inject_sglang_bootstrap_metadata(request, prefill);
-        }
+) -> Option<RoomLease> {
+    match workers {
+        WorkerSelection::Disaggregated {
+            prefill,
+            runtime_type: RuntimeType::Sglang,
+            ..
+        } => Some(inject_sglang_bootstrap_metadata(request, prefill)),
+        _ => None,
     }
 }
 
@@ -315,11 +330,13 @@ pub(crate) fn maybe_inject_pd_metadata(
 fn inject_sglang_bootstrap_metadata(
     request: &mut ProtoGenerateRequest,
     prefill_worker: &Arc<dyn Worker>,
-) {
+) -> RoomLease {
     let metadata = prefill_worker.metadata();
     let hostname = metadata.bootstrap_host();
     let bootstrap_port = metadata.bootstrap_port().unwrap_or(DEFAULT_BOOTSTRAP_PORT);
-    let room_id = rand::rng().random_range(0..i32::MAX);
+    // The SGLang proto field is an `i32`.
+    let lease = bootstrap_rooms().allocate(RoomWidth::Bits31, prefill_worker.url());
+    let room_id = lease.room() as i32;
 
     let disagg_params = DisaggregatedParams {
         bootstrap_host: hostname.to_string(),
@@ -334,6 +351,7 @@ fn inject_sglang_bootstrap_metadata(
         "Injected bootstrap metadata: host={}, port={}, room={}",
         hostname, bootstrap_port, room_id
     );
+    lease
 }
 
 /// Inject prefill->decode rendezvous params for backends that carry them in the
@@ -344,10 +362,12 @@ fn inject_sglang_bootstrap_metadata(
 /// this stage). Host/port name the PREFILL worker's Mooncake bootstrap server
 /// (the KV data source); the decode worker discovers it there by `bootstrap_room`.
 /// This KV leg is independent of any per-item encode->prefill bootstrap info.
+///
+/// Returns the lease on the injected room; keep it until the request is done.
 pub(crate) fn maybe_inject_pd_rendezvous(
     request: &mut ProtoGenerateRequest,
     workers: &WorkerSelection,
-) {
+) -> Option<RoomLease> {
     // The KV bootstrap leg is identical for plain PD and EPD; EPD just layers
     // encode assignments on the disaggregated worker selection.
     let (prefill, runtime_type) = match workers {
@@ -356,23 +376,24 @@ pub(crate) fn maybe_inject_pd_rendezvous(
             runtime_type,
             ..
         } => (prefill, runtime_type),
-        WorkerSelection::Single { .. } => return,
+        WorkerSelection::Single { .. } => return None,
     };
-    if *runtime_type == RuntimeType::TokenSpeed {
-        let metadata = prefill.metadata();
-        let hostname = metadata.bootstrap_host();
-        let bootstrap_port = metadata.bootstrap_port().unwrap_or(DEFAULT_BOOTSTRAP_PORT);
-        // 63-bit room: no dedup, keep the space wide so the birthday collision
-        // rate stays negligible. See the proto field doc.
-        let room_id = rand::rng().random_range(0..i64::MAX);
-
-        request.set_kv_bootstrap_info(hostname.to_string(), bootstrap_port as i32, room_id);
-
-        debug!(
-            "Injected PD rendezvous: host={}, port={}, room={}",
-            hostname, bootstrap_port, room_id
-        );
+    if *runtime_type != RuntimeType::TokenSpeed {
+        return None;
     }
+    let metadata = prefill.metadata();
+    let hostname = metadata.bootstrap_host();
+    let bootstrap_port = metadata.bootstrap_port().unwrap_or(DEFAULT_BOOTSTRAP_PORT);
+    let lease = bootstrap_rooms().allocate(RoomWidth::Bits63, prefill.url());
+    let room_id = lease.room() as i64;
+
+    request.set_kv_bootstrap_info(hostname.to_string(), bootstrap_port as i32, room_id);
+
+    debug!(
+        "Injected PD rendezvous: host={}, port={}, room={}",
+        hostname, bootstrap_port, room_id
+    );
+    Some(lease)
 }
 
 #[cfg(test)]
@@ -40,6 +40,7 @@ pub trait PipelineStage: Send + Sync {
 }
 
 mod client_acquisition;
+mod custom;
 mod dispatch_metadata;
 pub(crate) mod encode;
 pub(crate) mod helpers;
@@ -48,6 +49,7 @@ mod worker_selection;
 
 // Export stage implementations
 pub(crate) use client_acquisition::ClientAcquisitionStage;
+pub(crate) use custom::splice_custom_stages;
 pub(crate) use dispatch_metadata::DispatchMetadataStage;
 pub(crate) use encode::EncodeStage;
 pub(crate) use request_execution::RequestExecutionStage;
@@ -19,8 +19,8 @@ use crate::{
                 PdTiming, RequestContext, WorkerSelection,
             },
             proto_wrapper::{
-                ProtoEmbedRequest, ProtoGenerateRequest, ProtoRequest, ProtoResponseVariant,
-                ProtoStream,
+                FanOutStream, ProtoEmbedRequest, ProtoGenerateRequest, ProtoRequest,
+                ProtoResponseVariant, ProtoStream,
             },
             utils::tonic_ext::{TonicResultExt, TonicStatusExt},
         },
@@ -168,7 +168,10 @@ impl PipelineStage for RequestExecutionStage {
         })?;
 
         let sub_requests = match &execution_plan {
-            ExecutionPlan::Batch { requests, .. } => requests.len(),
+            ExecutionPlan::Batch { requests, .. } | ExecutionPlan::FanOut { requests, .. } => {
+                requests.len()
+            }
+            ExecutionPlan::EmbedBatch { requests, .. } => requests.len(),
             _ => 1,
         };
         ctx.state.load_guards = Some(LoadGuards::scaled(
@@ -216,6 +219,12 @@ impl PipelineStage for RequestExecutionStage {
                     self.execute_batch_dispatch(kind, requests, clients, workers, model)
                         .await
                 }
+                ExecutionPlan::FanOut { requests, .. } => {
+                    self.execute_fan_out(requests, clients, workers).await
+                }
+                ExecutionPlan::EmbedBatch { requests, .. } => {
+                    self.execute_embed_batch(requests, clients, workers).await
+                }
             }
         }
         .instrument(span)
@@ -256,7 +265,10 @@ impl RequestExecutionStage {
             }
             Some(RuntimeType::Trtllm)
             | Some(RuntimeType::Mlx)
+            | Some(RuntimeType::LlamaCpp)
+            | Some(RuntimeType::Ollama)
             | Some(RuntimeType::External)
+            | Some(RuntimeType::Smg)
             | Some(RuntimeType::Unspecified) => {
                 error!(
                     function = "RequestExecutionStage::execute",
@@ -372,6 +384,55 @@ impl RequestExecutionStage {
         Ok(ExecutionResult::Batch { results })
     }
 
+    /// Dispatch one single-choice branch per requested choice concurrently and
+    /// merge the branch streams into one multi-choice stream. Fail-fast like
+    /// batch dispatch.
+    async fn execute_fan_out(
+        &self,
+        requests: Vec<ProtoGenerateRequest>,
+        clients: &ClientSelection,
+        workers: &WorkerSelection,
+    ) -> Result<ExecutionResult, Response> {
+        let dispatches = requests.into_iter().map(|request| {
+            let mut clients = clients.clone();
+            async move { self.execute_single(request, &mut clients, workers).await }
+        });
+
+        // `execute_single` only ever yields `Single` results.
+        let branches = try_join_all(dispatches)
+            .await?
+            .into_iter()
+            .filter_map(|result| match result {
+                ExecutionResult::Single { stream } => Some(stream),
+                _ => None,
+            })
+            .collect();
+
+        Ok(ExecutionResult::Single {
+            stream: ProtoStream::FanOut(FanOutStream::new(branches)),
+        })
+    }
+
+    /// Dispatch one embed request per rerank document concurrently,
+    /// preserving document order. Fail-fast like batch dispatch.
+    async fn execute_embed_batch(
+        &self,
+        requests: Vec<ProtoEmbedRequest>,
+        clients: &ClientSelection,
+        workers: &WorkerSelection,
+    ) -> Result<ExecutionResult, Response> {
+        let dispatches = requests.into_iter().map(|request| {
+            let mut clients = clients.clone();
+            async move {
+                self.execute_single_embed(request, &mut clients, workers)
+                    .await
+            }
+        });
+
+        let results = try_join_all(dispatches).await?;
+        Ok(ExecutionResult::Batch { results })
+    }
+
     async fn execute_single(
         &self,
         mut proto_request: ProtoGenerateRequest,
@@ -7,6 +7,7 @@ use axum::{
     http::{HeaderMap, HeaderValue},
     response::Response,
 };
+use openai_protocol::model_type::Endpoint;
 use tracing::{error, warn};
 
 use super::PipelineStage;
@@ -14,9 +15,10 @@ use crate::{
     observability::metrics::{metrics_labels, Metrics},
     policies::{LoadBalancingPolicy, PolicyRegistry, SelectWorkerInfo, WorkerLeg},
     routers::{
+        common::worker_selection::{matches_card_revision, matches_worker_labels, serves_endpoint},
         error,
         grpc::{
-            context::{EncodeWorkerAssignment, RequestContext, WorkerSelection},
+            context::{EncodeWorkerAssignment, RequestContext, RequestType, WorkerSelection},
             multimodal,
         },
     },
@@ -92,9 +94,14 @@ impl PipelineStage for WorkerSelectionStage {
         let headers = ctx.input.headers.as_ref();
 
         let model_id = ctx.input.model_id.as_str();
+        // Classification needs a worker whose model card advertises it.
+        let endpoint = match &ctx.input.request_type {
+            RequestType::Classify(_) => Some(Endpoint::Classify),
+            _ => None,
+        };
         let workers = match self.mode {
             WorkerSelectionMode::Regular => {
-                match self.select_single_worker(model_id, text, tokens, headers) {
+                match self.select_single_worker(model_id, text, tokens, headers, endpoint) {
                     Some(w) => WorkerSelection::Single { worker: w },
                     None => {
                         error!(
@@ -220,6 +227,7 @@ impl WorkerSelectionStage {
         text: Option<&str>,
         tokens: Option<&[u32]>,
         headers: Option<&HeaderMap>,
+        endpoint: Option<Endpoint>,
     ) -> Option<Arc<dyn Worker>> {
         // Treat "unknown" model as wildcard (match any worker)
         let model_filter = if model_id == UNKNOWN_MODEL_ID {
@@ -238,8 +246,13 @@ impl WorkerSelectionStage {
         );
 
         // Use into_iter() to take ownership of Arcs without cloning (avoids atomic inc/dec)
-        let available: Vec<Arc<dyn Worker>> =
-            workers.into_iter().filter(|w| w.is_available()).collect();
+        let available: Vec<Arc<dyn Worker>> = workers
+            .into_iter()
+            .filter(|w| w.is_available())
+            .filter(|w| endpoint.is_none_or(|e| serves_endpoint(w.as_ref(), model_id, e)))
+            .filter(|w| matches_worker_labels(w.as_ref(), headers))
+            .filter(|w| matches_card_revision(&self.worker_registry, w.as_ref(), model_id, headers))
+            .collect();
 
         if available.is_empty() {
             return None;
@@ -303,7 +316,15 @@ impl WorkerSelectionStage {
             all_workers
                 .into_iter()
                 .fold((Vec::new(), Vec::new()), |mut acc, w| {
-                    if w.is_available() {
+                    if w.is_available()
+                        && matches_worker_labels(w.as_ref(), headers)
+                        && matches_card_revision(
+                            &self.worker_registry,
+                            w.as_ref(),
+                            model_id,
+                            headers,
+                        )
+                    {
                         match w.metadata().spec.worker_type {
                             WorkerType::Prefill => acc.0.push(w),
                             WorkerType::Decode => acc.1.push(w),
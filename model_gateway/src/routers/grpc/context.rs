@@ -11,10 +11,12 @@ use llm_tokenizer::{stop::StopSequenceDecoder, traits::Tokenizer, TokenizerRegis
 use openai_protocol::{
     chat::{ChatCompletionRequest, ChatCompletionResponse},
     classify::{ClassifyRequest, ClassifyResponse},
+    common::{PromptTokenBreakdown, StringOrArray},
     completion::{CompletionRequest, CompletionResponse},
     embedding::{EmbeddingRequest, EmbeddingResponse},
     generate::{GenerateRequest, GenerateResponse},
     messages::{CreateMessageRequest, Message},
+    rerank::{RerankRequest, RerankResponse},
     responses::ResponsesRequest,
 };
 use reasoning_parser::ParserFactory as ReasoningParserFactory;
@@ -32,6 +34,7 @@ use super::{
 };
 use crate::{
     middleware::TenantRequestMeta,
+    routers::common::bootstrap_rooms::RoomLease,
     worker::{RuntimeType, Worker, WorkerLoadGuard},
 };
 
@@ -63,6 +66,7 @@ pub(crate) enum RequestType {
     Responses(Arc<ResponsesRequest>),
     Embedding(Arc<EmbeddingRequest>),
     Classify(Arc<ClassifyRequest>),
+    Rerank(Arc<RerankRequest>),
     Messages(Arc<CreateMessageRequest>),
 }
 
@@ -76,6 +80,10 @@ impl RequestType {
             Self::Completion(r) => r.rid.as_deref(),
             Self::Embedding(r) => r.rid.as_deref(),
             Self::Classify(r) => r.rid.as_deref(),
+            Self::Rerank(r) => match &r.rid {
+                Some(StringOrArray::String(rid)) => Some(rid),
+                _ => None,
+            },
             Self::Messages(r) => r.rid.as_deref(),
             Self::Responses(_) => None,
         }
@@ -91,6 +99,7 @@ impl std::fmt::Display for RequestType {
             Self::Responses(_) => write!(f, "Responses"),
             Self::Embedding(_) => write!(f, "Embedding"),
             Self::Classify(_) => write!(f, "Classify"),
+            Self::Rerank(_) => write!(f, "Rerank"),
             Self::Messages(_) => write!(f, "Messages"),
         }
     }
@@ -104,6 +113,7 @@ impl std::fmt::Display for FinalResponse {
             Self::Completion(_) => write!(f, "Completion"),
             Self::Embedding(_) => write!(f, "Embedding"),
             Self::Classify(_) => write!(f, "Classify"),
+            Self::Rerank(_) => write!(f, "Rerank"),
             Self::Messages(_) => write!(f, "Messages"),
         }
     }
@@ -151,6 +161,9 @@ pub(crate) struct ProcessingState {
     // Stage 4: Request building outputs
     pub execution_plan: Option<ExecutionPlan>,
 
+    /// PD bootstrap rooms injected at request building; released with the state.
+    pub bootstrap_rooms: Vec<RoomLease>,
+
     // Stage 5: Dispatch metadata
     pub dispatch: Option<DispatchMetadata>,
 
@@ -191,6 +204,21 @@ pub(crate) enum ExecutionPlan {
         shared_request_id: String,
         requests: Vec<ProtoGenerateRequest>,
     },
+    /// `n > 1` fan-out for backends that ignore `n`: one n=1 request per
+    /// choice, all dispatched to the selected worker and merged back into a
+    /// single multi-choice stream. Branch ids are `{shared_request_id}-n{i}`.
+    FanOut {
+        shared_request_id: String,
+        requests: Vec<ProtoGenerateRequest>,
+    },
+    /// Rerank scoring and batched classify: one embed request per document
+    /// (or input), all dispatched to the selected worker. Sub-request ids are
+    /// `{shared_request_id}-d{i}` for rerank and `{shared_request_id}-i{i}`
+    /// for classify.
+    EmbedBatch {
+        shared_request_id: String,
+        requests: Vec<ProtoEmbedRequest>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -209,6 +237,24 @@ impl ExecutionPlan {
         }
     }
 
+    /// Split `request` into `n` single-choice branches. Explicit seeds are
+    /// offset per branch so the fan-out stays reproducible.
+    pub(crate) fn fan_out(request: ProtoGenerateRequest, n: u32) -> Self {
+        let shared_request_id = request.request_id().to_string();
+        let requests = (0..n)
+            .map(|i| {
+                let mut branch = request.clone();
+                branch.set_request_id(format!("{shared_request_id}-n{i}"));
+                branch.offset_sampling_seed(i);
+                branch
+            })
+            .collect();
+        Self::FanOut {
+            shared_request_id,
+            requests,
+        }
+    }
+
     pub(crate) fn embed(request: ProtoEmbedRequest) -> Self {
         Self::Single(ProtoRequest::Embed(request))
     }
@@ -221,6 +267,12 @@ impl ExecutionPlan {
             }
             Self::Batch {
                 shared_request_id, ..
+            }
+            | Self::FanOut {
+                shared_request_id, ..
+            }
+            | Self::EmbedBatch {
+                shared_request_id, ..
             } => shared_request_id,
         }
     }
@@ -230,14 +282,15 @@ impl ExecutionPlan {
             Self::Single(ProtoRequest::Generate(_))
             | Self::PrefillDecode(_)
             | Self::EncodePrefillDecode { .. }
-            | Self::Batch { .. } => "generate",
-            Self::Single(ProtoRequest::Embed(_)) => "embed",
+            | Self::Batch { .. }
+            | Self::FanOut { .. } => "generate",
+            Self::Single(ProtoRequest::Embed(_)) | Self::EmbedBatch { .. } => "embed",
         }
     }
 
     pub(crate) fn mode_label(&self) -> &'static str {
         match self {
-            Self::Single(_) => "single",
+            Self::Single(_) | Self::FanOut { .. } | Self::EmbedBatch { .. } => "single",
             Self::PrefillDecode(_) => "prefill_decode",
             Self::EncodePrefillDecode { .. } => "encode_prefill_decode",
             Self::Batch { kind, .. } => match kind {
@@ -279,6 +332,18 @@ pub(crate) enum PreparationOutput {
         original_text: String,
         token_ids: Vec<u32>,
     },
+    /// Batched classify input: one embed request per item.
+    EmbeddingBatch {
+        /// One entry per input, in input order.
+        items: Vec<CompletionItem>,
+        /// Input texts joined for routing.
+        joined_routing_text: String,
+    },
+    Rerank {
+        query: String,
+        /// One pair encoding per document, in document order.
+        pairs: Vec<RerankPair>,
+    },
     Harmony {
         token_ids: Vec<u32>,
         selection_text: String,
@@ -297,6 +362,14 @@ pub(crate) struct CompletionItem {
     pub token_ids: Vec<u32>,
 }
 
+/// One tokenized (query, document) rerank pair.
+pub(crate) struct RerankPair {
+    pub token_ids: Vec<u32>,
+    /// Segment ids from the tokenizer's pair encoding; empty when the
+    /// tokenizer doesn't produce them.
+    pub token_type_ids: Vec<u32>,
+}
+
 impl PreparationOutput {
     /// Token IDs (common to all variants). Batched completions expose the
     /// first prompt's tokens as the routing-affinity proxy.
@@ -307,9 +380,12 @@ impl PreparationOutput {
             | Self::Generate { token_ids, .. }
             | Self::Embedding { token_ids, .. }
             | Self::Harmony { token_ids, .. } => token_ids,
-            Self::Completion { items, .. } => {
+            Self::Completion { items, .. } | Self::EmbeddingBatch { items, .. } => {
                 items.first().map_or(&[], |item| item.token_ids.as_slice())
             }
+            Self::Rerank { pairs, .. } => {
+                pairs.first().map_or(&[], |pair| pair.token_ids.as_slice())
+            }
         }
     }
 
@@ -330,6 +406,11 @@ impl PreparationOutput {
                 .as_deref()
                 .or_else(|| items.first().map(|item| item.text.as_str())),
             Self::Embedding { original_text, .. } => Some(original_text),
+            Self::EmbeddingBatch {
+                joined_routing_text,
+                ..
+            } => Some(joined_routing_text),
+            Self::Rerank { query, .. } => Some(query),
             Self::Generate { original_text, .. } => original_text.as_deref(),
             Self::Harmony { selection_text, .. } => Some(selection_text),
         }
@@ -378,6 +459,8 @@ pub(crate) struct DispatchMetadata {
     pub model: String,
     pub created: u64,
     pub weight_version: Option<String>,
+    /// Attached to the response usage when the caller asked for it.
+    pub prompt_breakdown: Option<PromptTokenBreakdown>,
 }
 
 /// Load guards for worker load tracking
@@ -432,6 +515,10 @@ pub(crate) struct ResponseState {
     /// Stop sequence decoder
     pub stop_decoder: Option<StopSequenceDecoder>,
 
+    /// Prompt token breakdown for `x-smg-features: usage-breakdown` (set in
+    /// chat preparation, moved into [`DispatchMetadata`]).
+    pub prompt_breakdown: Option<PromptTokenBreakdown>,
+
     /// Derived skip_special_tokens for streaming (set in preparation, read in response_processing).
     /// Stored here because PreparationOutput is consumed by request_building before
     /// response_processing runs.
@@ -562,6 +649,25 @@ impl RequestContext {
         }
     }
 
+    /// Create context for rerank request
+    pub fn for_rerank(
+        request: Arc<RerankRequest>,
+        headers: Option<HeaderMap>,
+        model_id: String,
+        components: Arc<SharedComponents>,
+    ) -> Self {
+        Self {
+            input: RequestInput {
+                request_type: RequestType::Rerank(request),
+                headers,
+                model_id,
+                tenant_request_meta: None,
+            },
+            components,
+            state: ProcessingState::default(),
+        }
+    }
+
     /// Create context for messages request
     pub fn for_messages(
         request: Arc<CreateMessageRequest>,
@@ -707,6 +813,7 @@ impl RequestContext {
             RequestType::Messages(req) => req.stream.unwrap_or(false),
             RequestType::Embedding(_) => false, // Embeddings are never streaming
             RequestType::Classify(_) => false,  // Classification is never streaming
+            RequestType::Rerank(_) => false,    // Reranking is never streaming
         }
     }
 
@@ -894,7 +1001,8 @@ pub(crate) enum ExecutionResult {
     Embedding {
         response: ProtoEmbedComplete,
     },
-    /// Batched completion fan-out: one result per prompt, in prompt order.
+    /// Batched completion fan-out or rerank scoring: one result per prompt
+    /// (or document), in request order.
     Batch {
         results: Vec<ExecutionResult>,
     },
@@ -922,12 +1030,16 @@ pub(crate) enum FinalResponse {
     Embedding(EmbeddingResponse),
     /// Classification response
     Classify(ClassifyResponse),
+    /// Rerank response
+    Rerank(RerankResponse),
     /// Messages API response
     Messages(Message),
 }
 
 #[cfg(test)]
 mod tests {
+    use smg_grpc_client::mlx_proto as mlx;
+
     use super::*;
 
     fn completion_prep(texts: &[&str], joined: Option<&str>) -> PreparationOutput {
@@ -966,4 +1078,23 @@ mod tests {
         assert_eq!(plan.request_type(), "generate");
         assert_eq!(plan.mode_label(), "prefill_decode");
     }
+
+    #[test]
+    fn fan_out_plan_splits_request_into_branches() {
+        let request = ProtoGenerateRequest::Mlx(Box::new(mlx::GenerateRequest {
+            request_id: "chatcmpl-abc".to_string(),
+            ..Default::default()
+        }));
+        let plan = ExecutionPlan::fan_out(request, 3);
+        assert_eq!(plan.request_id(), "chatcmpl-abc");
+        assert_eq!(plan.mode_label(), "single");
+        let ExecutionPlan::FanOut { requests, .. } = plan else {
+            panic!("expected fan-out plan");
+        };
+        let ids: Vec<_> = requests.iter().map(|r| r.request_id()).collect();
+        assert_eq!(
+            ids,
+            ["chatcmpl-abc-n0", "chatcmpl-abc-n1", "chatcmpl-abc-n2"]
+        );
+    }
 }
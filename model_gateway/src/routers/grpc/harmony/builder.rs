@@ -867,6 +867,8 @@ impl HarmonyBuilder {
 
             ResponseInputOutputItem::McpApprovalResponse { .. }
             | ResponseInputOutputItem::McpApprovalRequest { .. }
+            | ResponseInputOutputItem::McpElicitationRequest { .. }
+            | ResponseInputOutputItem::McpElicitationResponse { .. }
             | ResponseInputOutputItem::ComputerCall { .. }
             | ResponseInputOutputItem::ComputerCallOutput { .. }
             | ResponseInputOutputItem::McpCall { .. }
@@ -7,6 +7,7 @@ use openai_protocol::common::{FunctionCallResponse, ToolCall};
 use uuid::Uuid;
 
 use super::types::{HarmonyChannelDelta, HarmonyChannelOutput};
+use crate::routers::grpc::utils::finish_reason;
 
 /// Get the global Harmony encoding
 ///
@@ -256,12 +257,9 @@ impl HarmonyParserAdapter {
         // Check for incomplete content in parser state
         Self::handle_incomplete_content(&self.parser, &mut analysis, &mut final_text);
 
-        // Determine finish reason: override to "tool_calls" if commentary has tool calls
-        let final_finish_reason = if commentary.is_some() {
-            "tool_calls".to_string()
-        } else {
-            finish_reason.clone()
-        };
+        // Report "tool_calls" when commentary has tool calls and generation stopped naturally
+        let final_finish_reason =
+            finish_reason::with_tool_calls(&finish_reason, commentary.is_some()).to_string();
 
         Ok(HarmonyChannelOutput {
             analysis,
@@ -483,12 +481,9 @@ impl HarmonyParserAdapter {
         // Check for remaining incomplete content
         Self::handle_incomplete_content(&self.parser, &mut analysis, &mut final_text);
 
-        // Determine finish reason: override to "tool_calls" if commentary has tool calls
-        let final_finish_reason = if commentary.is_some() {
-            "tool_calls".to_string()
-        } else {
-            finish_reason
-        };
+        // Report "tool_calls" when commentary has tool calls and generation stopped naturally
+        let final_finish_reason =
+            finish_reason::with_tool_calls(&finish_reason, commentary.is_some()).to_string();
 
         HarmonyChannelOutput {
             analysis,
@@ -137,6 +137,7 @@ impl HarmonyResponseProcessor {
                 .choices(choices)
                 .usage(usage)
                 .maybe_system_fingerprint(dispatch.weight_version.as_deref())
+                .maybe_seed(chat_request.effective_seed())
                 .build(),
         )
     }
@@ -309,6 +310,7 @@ impl HarmonyResponseProcessor {
             .status(ResponseStatus::Completed)
             .output(output)
             .maybe_text(responses_request.text.clone())
+            .maybe_system_fingerprint(dispatch.weight_version.as_deref())
             .usage(ResponsesUsage::Modern(ResponseUsage {
                 input_tokens: usage.prompt_tokens,
                 output_tokens: usage.completion_tokens,
@@ -18,10 +18,16 @@ use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use super::execution::ToolResult;
-use crate::routers::{
-    common::openai_bridge::{self, FormatRegistry, ResponseFormat},
-    error,
-    grpc::common::responses::ResponsesContext,
+use crate::{
+    middleware::TenantRequestMeta,
+    routers::{
+        common::{
+            openai_bridge::{self, FormatRegistry, ResponseFormat},
+            persistence_utils::tenant_owns_response,
+        },
+        error,
+        grpc::common::responses::{ResponsesContext, ToolOutputBudget},
+    },
 };
 
 /// Record of a single MCP tool call execution
@@ -65,13 +71,15 @@ impl McpCallTracking {
 /// Constructs a new ResponsesRequest with:
 /// 1. Original input items (preserved)
 /// 2. Assistant message with analysis (reasoning) + partial_text + tool_calls
-/// 3. Tool result messages for each tool execution
+/// 3. Tool result messages for each tool execution, shortened to the tool
+///    output token budget
 pub(super) fn build_next_request_with_tools(
     mut request: ResponsesRequest,
     tool_calls: Vec<ToolCall>,
     tool_results: Vec<ToolResult>,
     analysis: Option<String>, // Analysis channel content (becomes reasoning content)
     partial_text: String,     // Final channel content (becomes message content)
+    output_budget: &mut ToolOutputBudget,
 ) -> ResponsesRequest {
     // Get current input items (or empty vec if Text variant)
     let mut items = match request.input {
@@ -142,6 +150,7 @@ pub(super) fn build_next_request_with_tools(
         // Update the corresponding tool call with output and completed status
         // Find and update the matching FunctionToolCall
         if let Some(ResponseInputOutputItem::FunctionToolCall {
+            name,
             output,
             status,
             ..
@@ -149,7 +158,7 @@ pub(super) fn build_next_request_with_tools(
             .iter_mut()
             .find(|item| matches!(item, ResponseInputOutputItem::FunctionToolCall { call_id, .. } if call_id == &tool_result.call_id))
         {
-            *output = Some(output_str);
+            *output = Some(output_budget.fit(name, output_str));
             *status = if tool_result.is_error {
                 Some("failed".to_string())
             } else {
@@ -196,6 +205,7 @@ pub(super) fn inject_mcp_metadata(
 pub(super) async fn load_previous_messages(
     ctx: &ResponsesContext,
     request: ResponsesRequest,
+    tenant_meta: &TenantRequestMeta,
 ) -> Result<ResponsesRequest, Response> {
     let Some(ref prev_id_str) = request.previous_response_id else {
         // No previous_response_id, return request as-is
@@ -231,7 +241,13 @@ pub(super) async fn load_previous_messages(
         }
     };
 
-    if chain.responses.is_empty() {
+    // Another tenant's chain is reported as missing.
+    if chain.responses.is_empty()
+        || !chain
+            .responses
+            .iter()
+            .all(|stored| tenant_owns_response(stored, tenant_meta.tenant_key()))
+    {
         return Err(error::bad_request(
             "previous_response_not_found",
             format!("Previous response with id '{prev_id_str}' not found."),
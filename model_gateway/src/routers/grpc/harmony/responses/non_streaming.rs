@@ -34,7 +34,7 @@ use crate::{
         grpc::{
             common::responses::{
                 collect_user_function_names, ensure_mcp_connection, persist_response_if_needed,
-                ResponsesContext,
+                ResponsesContext, ToolOutputBudget,
             },
             harmony::processor::ResponsesIterationResult,
         },
@@ -60,7 +60,7 @@ pub(crate) async fn serve_harmony_responses(
     let original_request = request.clone();
 
     // Load previous conversation history if previous_response_id is set
-    let current_request = load_previous_messages(ctx, request).await?;
+    let current_request = load_previous_messages(ctx, request, &tenant_request_meta).await?;
 
     // Check MCP connection and get whether MCP tools are present
     let (has_mcp_tools, mcp_servers) = ensure_mcp_connection(
@@ -70,6 +70,7 @@ pub(crate) async fn serve_harmony_responses(
     )
     .await?;
 
+    let tenant_key = tenant_request_meta.tenant_key().clone();
     let response = if has_mcp_tools {
         execute_with_mcp_loop(
             ctx,
@@ -90,6 +91,7 @@ pub(crate) async fn serve_harmony_responses(
         ctx.response_storage.clone(),
         &response,
         &original_request,
+        Some(&tenant_key),
         ctx.request_context.clone(),
     )
     .await;
@@ -122,6 +124,12 @@ async fn execute_with_mcp_loop(
     let session_request_id = format!("resp_{}", uuid::Uuid::now_v7());
 
     let session = McpToolSession::new(&ctx.mcp_orchestrator, mcp_servers, &session_request_id);
+    let mut output_budget = ToolOutputBudget::new(
+        &session,
+        ctx.components
+            .tokenizer_registry
+            .get(&current_request.model),
+    );
 
     // Add filtered MCP tools (static + requested dynamic) to the request
     let mcp_tools = session.mcp_tools();
@@ -342,6 +350,7 @@ async fn execute_with_mcp_loop(
                     mcp_results,
                     analysis,
                     partial_text,
+                    &mut output_budget,
                 );
 
                 // Continue loop - next iteration will select workers and execute
@@ -26,7 +26,7 @@ use crate::{
         grpc::{
             common::responses::{
                 build_sse_response, ensure_mcp_connection, persist_response_if_needed,
-                streaming::ResponseStreamEventEmitter, ResponsesContext,
+                streaming::ResponseStreamEventEmitter, ResponsesContext, ToolOutputBudget,
             },
             harmony::{processor::ResponsesIterationResult, streaming::HarmonyStreamingProcessor},
         },
@@ -47,10 +47,11 @@ pub(crate) async fn serve_harmony_responses_stream(
     tenant_request_meta: TenantRequestMeta,
 ) -> Response {
     // Load previous conversation history if previous_response_id is set
-    let current_request = match load_previous_messages(ctx, request.clone()).await {
-        Ok(req) => req,
-        Err(err_response) => return err_response,
-    };
+    let current_request =
+        match load_previous_messages(ctx, request.clone(), &tenant_request_meta).await {
+            Ok(req) => req,
+            Err(err_response) => return err_response,
+        };
 
     // Check MCP connection BEFORE starting stream and get whether MCP tools are present
     let (has_mcp_tools, mcp_servers) = match ensure_mcp_connection(
@@ -150,6 +151,12 @@ async fn execute_mcp_tool_loop_streaming(
     let session_request_id = format!("resp_{}", Uuid::now_v7());
 
     let session = McpToolSession::new(&ctx.mcp_orchestrator, mcp_servers, &session_request_id);
+    let mut output_budget = ToolOutputBudget::new(
+        &session,
+        ctx.components
+            .tokenizer_registry
+            .get(&current_request.model),
+    );
 
     // Add filtered MCP tools (static + requested dynamic) to the request
     let mcp_tools = session.mcp_tools();
@@ -379,6 +386,7 @@ async fn execute_mcp_tool_loop_streaming(
                     mcp_results,
                     analysis,
                     partial_text,
+                    &mut output_budget,
                 );
 
                 // Continue loop
@@ -401,6 +409,7 @@ async fn execute_mcp_tool_loop_streaming(
                     ctx.response_storage.clone(),
                     &final_response,
                     original_request,
+                    Some(tenant_request_meta.tenant_key()),
                     ctx.request_context.clone(),
                 )
                 .await;
@@ -438,6 +447,7 @@ async fn execute_without_mcp_streaming(
     tx: &mpsc::UnboundedSender<Result<Bytes, std::io::Error>>,
 ) {
     debug!("No MCP tools - executing single iteration");
+    let tenant_key = tenant_request_meta.tenant_key().clone();
 
     // Execute pipeline and get stream + load guards
     let (execution_result, _load_guards) = match ctx
@@ -490,6 +500,7 @@ async fn execute_without_mcp_streaming(
         ctx.response_storage.clone(),
         &final_response,
         original_request,
+        Some(&tenant_key),
         ctx.request_context.clone(),
     )
     .await;
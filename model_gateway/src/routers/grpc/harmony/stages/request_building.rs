@@ -2,13 +2,12 @@
 
 use async_trait::async_trait;
 use axum::response::Response;
-use smg_grpc_client::SglangGenerateRequestOptions;
 use tracing::{debug, error};
 
 use crate::routers::{
     error,
     grpc::{
-        client::GrpcClient,
+        client::GenerateRequestBuildOptions,
         common::stages::{helpers, PipelineStage},
         context::{
             ClientSelection, ExecutionPlan, ExecutionPlanKind, PreparationOutput, RequestContext,
@@ -98,6 +97,7 @@ impl PipelineStage for HarmonyRequestBuildingStage {
             | RequestType::Completion(_)
             | RequestType::Embedding(_)
             | RequestType::Classify(_)
+            | RequestType::Rerank(_)
             | RequestType::Messages(_)) => {
                 error!(
                     function = "HarmonyRequestBuildingStage::execute",
@@ -114,236 +114,55 @@ impl PipelineStage for HarmonyRequestBuildingStage {
         // Build gRPC request using token_ids directly (Harmony encoding already handled message rendering)
         let placeholder_processed_text = "[harmony]".to_string();
 
-        // Build proto request based on backend type and request type
-        let mut proto_request = match builder_client {
-            GrpcClient::Sglang(sglang_client) => {
-                let req = match &ctx.input.request_type {
-                    RequestType::Chat(request) => {
-                        let body = modified_request.as_deref().unwrap_or_else(|| request.as_ref());
-                        sglang_client
-                            .build_generate_request_from_chat(
-                                request_id,
-                                body,
-                                placeholder_processed_text,
-                                token_ids,
-                                SglangGenerateRequestOptions {
-                                    multimodal_inputs: None,
-                                    tool_call_constraint: tool_constraints,
-                                    require_reasoning: false,
-                                },
-                            )
-                            .map_err(|e| {
-                                error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build SGLang generate request");
-                                error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                            })?
-                    }
-                    RequestType::Responses(request) => sglang_client
-                        .build_generate_request_from_responses(
-                            request_id,
-                            request.as_ref(),
-                            placeholder_processed_text,
-                            token_ids,
-                            tool_constraints,
-                        )
-                        .map_err(|e| {
-                            error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build SGLang generate request from responses");
-                            error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                        })?,
-                    RequestType::Embedding(_) => {
-                        return Err(error::bad_request(
-                            "harmony_embedding_not_supported",
-                            "Embedding requests are not supported with Harmony models".to_string(),
-                        ));
-                    }
-                    _ => {
-                        return Err(error::bad_request(
-                            "unsupported_request_type",
-                            "Unsupported request type for Harmony models".to_string(),
-                        ));
-                    }
-                };
-                ProtoGenerateRequest::Sglang(Box::new(req))
-            }
-            GrpcClient::Vllm(vllm_client) => {
-                let req = match &ctx.input.request_type {
-                    RequestType::Chat(request) => {
-                        let body = modified_request.as_deref().unwrap_or_else(|| request.as_ref());
-                        vllm_client
-                            .build_generate_request_from_chat(
-                                request_id,
-                                body,
-                                placeholder_processed_text,
-                                token_ids,
-                                None, // No multimodal in Harmony pipeline
-                                tool_constraints,
-                            )
-                            .map_err(|e| {
-                                error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build vLLM generate request");
-                                error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                            })?
-                    }
-                    RequestType::Responses(request) => vllm_client
-                        .build_generate_request_from_responses(
-                            request_id,
-                            request.as_ref(),
-                            placeholder_processed_text,
-                            token_ids,
-                            tool_constraints,
-                        )
-                        .map_err(|e| {
-                            error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build vLLM generate request from responses");
-                            error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                        })?,
-                    RequestType::Embedding(_) => {
-                        return Err(error::bad_request(
-                            "harmony_embedding_not_supported",
-                            "Embedding requests are not supported with Harmony models".to_string(),
-                        ));
-                    }
-                    _ => {
-                        return Err(error::bad_request(
-                            "unsupported_request_type",
-                            "Unsupported request type for Harmony models".to_string(),
-                        ));
-                    }
-                };
-                ProtoGenerateRequest::Vllm(Box::new(req))
+        // Build proto request based on request type; the backend shapes it
+        let built = match &ctx.input.request_type {
+            RequestType::Chat(request) => {
+                let body = modified_request
+                    .as_deref()
+                    .unwrap_or_else(|| request.as_ref());
+                builder_client.build_chat_request(
+                    request_id,
+                    body,
+                    placeholder_processed_text,
+                    token_ids,
+                    GenerateRequestBuildOptions {
+                        tool_constraints,
+                        ..Default::default()
+                    },
+                )
             }
-            GrpcClient::Trtllm(trtllm_client) => {
-                let req = match &ctx.input.request_type {
-                    RequestType::Chat(request) => {
-                        let body = modified_request.as_deref().unwrap_or_else(|| request.as_ref());
-                        trtllm_client
-                            .build_generate_request_from_chat(
-                                request_id,
-                                body,
-                                placeholder_processed_text,
-                                token_ids,
-                                None, // No multimodal in Harmony pipeline
-                                tool_constraints,
-                            )
-                            .map_err(|e| {
-                                error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build TensorRT-LLM generate request");
-                                error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                            })?
-                    }
-                    RequestType::Responses(request) => trtllm_client
-                        .build_generate_request_from_responses(
-                            request_id,
-                            request.as_ref(),
-                            placeholder_processed_text,
-                            token_ids,
-                            tool_constraints,
-                        )
-                        .map_err(|e| {
-                            error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build TensorRT-LLM generate request from responses");
-                            error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                        })?,
-                    RequestType::Embedding(_) => {
-                        return Err(error::bad_request(
-                            "harmony_embedding_not_supported",
-                            "Embedding requests are not supported with Harmony models".to_string(),
-                        ));
-                    }
-                    _ => {
-                        return Err(error::bad_request(
-                            "unsupported_request_type",
-                            "Unsupported request type for Harmony models".to_string(),
-                        ));
-                    }
-                };
-                ProtoGenerateRequest::Trtllm(Box::new(req))
-            }
-            GrpcClient::Mlx(mlx_client) => {
-                let req = match &ctx.input.request_type {
-                    RequestType::Chat(request) => {
-                        let body = modified_request.as_deref().unwrap_or_else(|| request.as_ref());
-                        mlx_client
-                            .build_generate_request_from_chat(
-                                request_id,
-                                body,
-                                placeholder_processed_text,
-                                token_ids,
-                                tool_constraints,
-                            )
-                            .map_err(|e| {
-                                error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build MLX generate request");
-                                error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                            })?
-                    }
-                    RequestType::Responses(request) => mlx_client
-                        .build_generate_request_from_responses(
-                            request_id,
-                            request.as_ref(),
-                            placeholder_processed_text,
-                            token_ids,
-                            tool_constraints,
-                        )
-                        .map_err(|e| {
-                            error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build MLX generate request from responses");
-                            error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                        })?,
-                    RequestType::Embedding(_) => {
-                        return Err(error::bad_request(
-                            "harmony_embedding_not_supported",
-                            "Embedding requests are not supported with Harmony models".to_string(),
-                        ));
-                    }
-                    _ => {
-                        return Err(error::bad_request(
-                            "unsupported_request_type",
-                            "Unsupported request type for Harmony models".to_string(),
-                        ));
-                    }
-                };
-                ProtoGenerateRequest::Mlx(Box::new(req))
+            RequestType::Responses(request) => builder_client.build_responses_request(
+                request_id,
+                request.as_ref(),
+                placeholder_processed_text,
+                token_ids,
+                tool_constraints,
+            ),
+            RequestType::Embedding(_) => {
+                return Err(error::bad_request(
+                    "harmony_embedding_not_supported",
+                    "Embedding requests are not supported with Harmony models".to_string(),
+                ));
             }
-            GrpcClient::TokenSpeed(tokenspeed_client) => {
-                let req = match &ctx.input.request_type {
-                    RequestType::Chat(request) => {
-                        let body = modified_request.as_deref().unwrap_or_else(|| request.as_ref());
-                        tokenspeed_client
-                            .build_generate_request_from_chat(
-                                request_id,
-                                body,
-                                placeholder_processed_text,
-                                token_ids,
-                                None, // Harmony path: multimodal not yet wired
-                                tool_constraints,
-                            )
-                            .map_err(|e| {
-                                error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build TokenSpeed generate request");
-                                error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                            })?
-                    }
-                    RequestType::Responses(request) => tokenspeed_client
-                        .build_generate_request_from_responses(
-                            request_id,
-                            request.as_ref(),
-                            placeholder_processed_text,
-                            token_ids,
-                            tool_constraints,
-                        )
-                        .map_err(|e| {
-                            error!(function = "HarmonyRequestBuildingStage::execute", error = %e, "Failed to build TokenSpeed generate request from responses");
-                            error::bad_request("invalid_request_parameters", format!("Invalid request parameters: {e}"))
-                        })?,
-                    RequestType::Embedding(_) => {
-                        return Err(error::bad_request(
-                            "harmony_embedding_not_supported",
-                            "Embedding requests are not supported with Harmony models".to_string(),
-                        ));
-                    }
-                    _ => {
-                        return Err(error::bad_request(
-                            "unsupported_request_type",
-                            "Unsupported request type for Harmony models".to_string(),
-                        ));
-                    }
-                };
-                ProtoGenerateRequest::TokenSpeed(Box::new(req))
+            _ => {
+                return Err(error::bad_request(
+                    "unsupported_request_type",
+                    "Unsupported request type for Harmony models".to_string(),
+                ));
             }
         };
+        let mut proto_request = built.map_err(|e| {
+            error!(
+                function = "HarmonyRequestBuildingStage::execute",
+                backend = %builder_client.runtime_type(),
+                error = %e,
+                "Failed to build generate request"
+            );
+            error::bad_request(
+                "invalid_request_parameters",
+                format!("Invalid request parameters: {e}"),
+            )
+        })?;
 
         // Inject Harmony stop token IDs into sampling params for ALL Harmony requests
         // These stop tokens (<|return|> and <|call|>) prevent the model from generating
@@ -402,7 +221,8 @@ impl PipelineStage for HarmonyRequestBuildingStage {
 
         if self.inject_pd_metadata {
             if let Some(workers) = ctx.state.workers.as_ref() {
-                helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                let room = helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                ctx.state.bootstrap_rooms.extend(room);
             }
         }
 
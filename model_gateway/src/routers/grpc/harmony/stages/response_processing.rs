@@ -142,6 +142,7 @@ impl PipelineStage for HarmonyResponseProcessingStage {
             | RequestType::Completion(_)
             | RequestType::Embedding(_)
             | RequestType::Classify(_)
+            | RequestType::Rerank(_)
             | RequestType::Messages(_)) => {
                 error!(
                     function = "HarmonyResponseProcessingStage::execute",
@@ -5,6 +5,8 @@ use openai_protocol::{chat::ChatCompletionRequest, common::StringOrArray};
 
 use crate::routers::error;
 
+pub mod backend;
+pub mod buffer_pool; // Used by benches
 pub mod client; // Used by core/
 pub(crate) mod common;
 pub(crate) mod context;
@@ -1,17 +1,23 @@
 //! Multimodal model configuration: the shared config-file registry and the
 //! per-router component bundle (media connector + processor/model registries).
 
-use std::{path::Path, sync::Arc};
+use std::{
+    path::Path,
+    sync::{Arc, OnceLock},
+};
 
 use anyhow::{Context, Result};
 use dashmap::DashMap;
 use llm_multimodal::{
-    MediaConnector, MediaConnectorConfig, ModelRegistry, PreProcessorConfig,
-    VisionProcessorRegistry,
+    fetch::public_redirect_policy, MediaCache, MediaConnector, MediaConnectorConfig, ModelRegistry,
+    PreProcessorConfig, VisionProcessorRegistry,
 };
 use tracing::{debug, warn};
 
-use super::pixel_cache::{pixel_cache_from_env, PixelCache};
+use super::{
+    pixel_cache::{pixel_cache_from_env, PixelCache},
+    safety::{image_safety_from_env, ImageSafetyScreener},
+};
 
 /// Cached model configuration files loaded from the tokenizer directory.
 #[derive(Debug, Clone)]
@@ -204,31 +210,94 @@ pub(crate) struct MultimodalComponents {
     pub model_registry: Arc<ModelRegistry>,
     /// Shared reference to the app-level multimodal config cache.
     pub config_registry: Arc<MultimodalConfigRegistry>,
+    /// Optional cache of decoded inline images shared across requests.
+    pub media_cache: Option<Arc<MediaCache>>,
     /// Optional host-DRAM cache of preprocessed per-image encoder inputs.
     pub pixel_cache: Option<Arc<PixelCache>>,
+    /// Optional safety classifier screening fetched images before dispatch.
+    pub image_safety: Option<Arc<ImageSafetyScreener>>,
 }
 
 impl MultimodalComponents {
-    /// Create multimodal components with default registries and a reference
-    /// to the shared `MultimodalConfigRegistry` owned by `AppContext`.
-    pub fn new(config_registry: Arc<MultimodalConfigRegistry>) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create reqwest client")?;
-        let media_connector = MediaConnector::new(client, MediaConnectorConfig::default())
-            .context("Failed to create MediaConnector")?;
+    /// Create multimodal components with a default vision processor registry
+    /// and references to the shared `MultimodalConfigRegistry` and
+    /// `ModelRegistry` owned by `AppContext`.
+    pub fn new(
+        config_registry: Arc<MultimodalConfigRegistry>,
+        model_registry: Arc<ModelRegistry>,
+    ) -> Result<Self> {
+        let media_config = media_connector_config_from_env();
+        let mut client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(30));
+        if !media_config.allow_private_addresses {
+            client = client.redirect(public_redirect_policy());
+        }
+        let client = client.build().context("Failed to create reqwest client")?;
+        let media_connector =
+            MediaConnector::new(client, media_config).context("Failed to create MediaConnector")?;
 
         Ok(Self {
             media_connector: Arc::new(media_connector),
             vision_processor_registry: Arc::new(VisionProcessorRegistry::with_defaults()),
-            model_registry: Arc::new(ModelRegistry::default()),
+            model_registry,
             config_registry,
+            media_cache: media_cache_from_env(),
             pixel_cache: pixel_cache_from_env(),
+            image_safety: image_safety_from_env(),
         })
     }
 }
 
+/// Media connector settings, with fetch concurrency, retries and the
+/// private-address guard overridable through `SMG_MM_FETCH_*` variables.
+fn media_connector_config_from_env() -> MediaConnectorConfig {
+    fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+        std::env::var(name)
+            .ok()
+            .and_then(|raw| raw.trim().parse().ok())
+    }
+
+    let mut config = MediaConnectorConfig::default();
+    if let Some(max) = env_parse::<usize>("SMG_MM_FETCH_MAX_CONCURRENCY").filter(|n| *n > 0) {
+        config.max_concurrent_fetches = max;
+    }
+    if let Some(max) = env_parse::<usize>("SMG_MM_FETCH_MAX_PER_HOST").filter(|n| *n > 0) {
+        config.max_concurrent_fetches_per_host = max;
+    }
+    if let Some(retries) = env_parse::<u32>("SMG_MM_FETCH_MAX_RETRIES") {
+        config.retry.max_retries = retries;
+    }
+    if let Some(ms) = env_parse::<u64>("SMG_MM_FETCH_RETRY_BACKOFF_MS") {
+        config.retry.initial_backoff = std::time::Duration::from_millis(ms);
+    }
+    if let Some(allow) = env_parse::<bool>("SMG_MM_FETCH_ALLOW_PRIVATE_ADDRESSES") {
+        config.allow_private_addresses = allow;
+    }
+    config
+}
+
+/// Decoded-image cache sized by `SMG_MM_MEDIA_CACHE_MB`; disabled when unset
+/// or 0.
+fn media_cache_from_env() -> Option<Arc<MediaCache>> {
+    static CACHE: OnceLock<Option<Arc<MediaCache>>> = OnceLock::new();
+    CACHE
+        .get_or_init(|| {
+            let mb = std::env::var("SMG_MM_MEDIA_CACHE_MB")
+                .ok()
+                .and_then(|raw| raw.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            if mb == 0 {
+                return None;
+            }
+            tracing::info!(
+                target: "smg::request",
+                cache_mb = mb,
+                "multimodal decoded image cache enabled"
+            );
+            Some(Arc::new(MediaCache::new(mb.saturating_mul(1024 * 1024))))
+        })
+        .clone()
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
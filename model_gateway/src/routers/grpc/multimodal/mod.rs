@@ -9,6 +9,7 @@
 //!   build the lightweight [`MultimodalIntermediate`].
 //! - [`assemble`]: turn the intermediate into backend-specific `MultimodalData`
 //!   once the target backend is known (after worker selection).
+//! - [`safety`]: optional image safety pre-screening before preprocessing.
 //! - [`serialize`]: tensor byte/dtype serialization used by assembly.
 //! - [`transport`]: SHM-vs-inline transport resolution and `/dev/shm`
 //!   namespace verification.
@@ -30,6 +31,7 @@ mod detect;
 mod pixel_cache;
 mod plan;
 mod process;
+mod safety;
 mod serialize;
 mod transport;
 
@@ -48,6 +50,7 @@ pub(crate) use plan::{
     PlaceholderTokens,
 };
 pub(crate) use process::process_multimodal_plan;
+pub(crate) use safety::ImageSafetyError;
 pub(crate) use transport::{init_mm_transport_defaults, mm_rdma_exporter};
 
 /// Whether verbose multimodal timing logs are enabled via `SMG_LOG_MM_TIMING`.
@@ -6,7 +6,7 @@ use std::{
     sync::{Arc, OnceLock},
 };
 
-use llm_multimodal::{ModelSpecificValue, PreprocessedEncoderInputs};
+use llm_multimodal::{ImageDetail, ModelSpecificValue, PreprocessedEncoderInputs};
 use lru::LruCache;
 use parking_lot::Mutex;
 
@@ -16,6 +16,8 @@ use parking_lot::Mutex;
 pub(crate) struct PixelCacheKey {
     /// blake3 hex digest of the raw encoded image bytes (`ImageFrame.hash`).
     pub image_hash: String,
+    /// Requested image detail; low detail preprocesses a downscaled image.
+    pub detail: ImageDetail,
     /// Stable hash of model identity/config for this deployment.
     pub config_fingerprint: u64,
 }
@@ -187,6 +189,7 @@ mod tests {
     fn pixel_cache_key(hash: &str) -> PixelCacheKey {
         PixelCacheKey {
             image_hash: hash.to_string(),
+            detail: ImageDetail::Auto,
             config_fingerprint: 7,
         }
     }
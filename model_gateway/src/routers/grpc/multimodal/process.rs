@@ -9,9 +9,10 @@ use std::{collections::HashMap, sync::Arc, time::Instant};
 use anyhow::{Context, Result};
 use futures::future::try_join_all;
 use llm_multimodal::{
-    AsyncMultiModalTracker, AudioClip, EncoderFieldLayouts, ImageFrame, Modality, ModelMetadata,
-    ModelProcessorSpec, PlaceholderRange, PreProcessorConfig, PreprocessedEncoderInputs,
-    PromptReplacement, TrackedMedia, TrackerOutput, VideoClip, VisionProcessorRegistry,
+    vision::detail::images_for_preprocessing, AsyncMultiModalTracker, AudioClip,
+    EncoderFieldLayouts, ImageFrame, Modality, ModelMetadata, ModelProcessorSpec, PlaceholderRange,
+    PreProcessorConfig, PreprocessedEncoderInputs, PromptReplacement, TrackedMedia, TrackerOutput,
+    VideoClip, VisionProcessorRegistry,
 };
 use llm_tokenizer::TokenizerTrait;
 use tracing::{debug, info, warn};
@@ -24,6 +25,7 @@ use super::{
     MediaBatch, MultimodalIntermediate, MultimodalOutput, PrecomputedMultimodalIntermediate,
     PromptBinding,
 };
+use crate::tenant::TenantKey;
 
 struct PreparedMultimodalPart {
     preprocessed: PreprocessedEncoderInputs,
@@ -44,11 +46,15 @@ pub(crate) async fn process_multimodal_plan(
     components: &MultimodalComponents,
     tokenizer_id: &str,
     tokenizer_source: &str,
+    tenant: Option<&TenantKey>,
 ) -> Result<MultimodalOutput> {
     let log_timing = log_mm_timing_enabled();
     let total_started = Instant::now();
     let media_started = Instant::now();
     let mut tracker = AsyncMultiModalTracker::new(components.media_connector.clone());
+    if let Some(cache) = &components.media_cache {
+        tracker = tracker.with_media_cache(cache.clone());
+    }
 
     for part in plan.into_parts() {
         tracker
@@ -75,6 +81,11 @@ pub(crate) async fn process_multimodal_plan(
         })
         .unwrap_or_default();
 
+    // Screen fetched images before any preprocessing or dispatch work.
+    if let Some(screener) = &components.image_safety {
+        screener.screen(&images, tenant).await?;
+    }
+
     let videos: Vec<Arc<VideoClip>> = tracker_output
         .data
         .get(&Modality::Video)
@@ -194,7 +205,7 @@ pub(crate) async fn process_multimodal_plan(
             components,
             model_id,
             model_type,
-            spec,
+            spec.as_ref(),
             tokenizer_id,
             &model_config,
         )
@@ -347,11 +358,23 @@ async fn preprocess_modality(
             .video_preprocessor_config
             .clone()
             .unwrap_or_else(|| model_config.preprocessor_config.clone()),
+        Modality::Image => spec
+            .preprocessor_config()
+            .cloned()
+            .unwrap_or_else(|| model_config.preprocessor_config.clone()),
         _ => model_config.preprocessor_config.clone(),
     };
+    // Runtime-registered specs carry their own image processor.
+    let spec_processor = spec.vision_processor();
 
     if let MediaBatch::Images(images) = media {
-        if let (Some(cache), [image]) = (components.pixel_cache.clone(), images.as_slice()) {
+        // The pixel cache fingerprint covers the checkpoint config only, so
+        // specs that can be re-registered with another config bypass it.
+        if let (Some(cache), [image], None) = (
+            components.pixel_cache.clone(),
+            images.as_slice(),
+            &spec_processor,
+        ) {
             return preprocess_image_cached(
                 cache,
                 image,
@@ -385,15 +408,17 @@ async fn preprocess_modality(
 
     tokio::task::spawn_blocking(move || match media_for_preprocess {
         MediaBatch::Images(images) => {
-            let processor = registry
-                .find(&model_id_owned, model_type_owned.as_deref())
-                .ok_or_else(|| {
-                    anyhow::anyhow!("No vision processor found for model: {model_id_owned}")
-                })?;
+            let processor = match &spec_processor {
+                Some(processor) => processor.as_ref(),
+                None => registry
+                    .find(&model_id_owned, model_type_owned.as_deref())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("No vision processor found for model: {model_id_owned}")
+                    })?,
+            };
             // Extract DynamicImages inside the blocking closure so the expensive
-            // clone happens off the tokio async runtime.
-            let raw_images: Vec<image::DynamicImage> =
-                images.iter().map(|frame| frame.image.clone()).collect();
+            // clone (or low-detail downscale) happens off the tokio async runtime.
+            let raw_images = images_for_preprocessing(processor, &images, &pp_config);
             processor
                 .preprocess(&raw_images, &pp_config)
                 .map_err(|e| anyhow::anyhow!("Image preprocessing failed: {e}"))
@@ -483,6 +508,7 @@ async fn preprocess_image_cached(
 ) -> Result<PreprocessedEncoderInputs> {
     let key = PixelCacheKey {
         image_hash: image.hash.clone(),
+        detail: image.detail,
         config_fingerprint: fingerprint,
     };
     if let Some(cached) = cache.get(&key) {
@@ -513,11 +539,12 @@ async fn preprocess_image_batch(
     pp_config: PreProcessorConfig,
     images: &[Arc<ImageFrame>],
 ) -> Result<PreprocessedEncoderInputs> {
-    let raw_images: Vec<image::DynamicImage> = images.iter().map(|f| f.image.clone()).collect();
+    let images = images.to_vec();
     tokio::task::spawn_blocking(move || {
         let processor = registry
             .find(&model_id, model_type.as_deref())
             .ok_or_else(|| anyhow::anyhow!("No vision processor found for model: {model_id}"))?;
+        let raw_images = images_for_preprocessing(processor, &images, &pp_config);
         processor
             .preprocess(&raw_images, &pp_config)
             .map_err(|e| anyhow::anyhow!("Image preprocessing failed: {e}"))
@@ -0,0 +1,430 @@
+//! Optional image safety pre-screening before dispatch.
+//!
+//! When `SMG_MM_IMAGE_SAFETY_CONFIG` points at a YAML file, every fetched
+//! image is run through an [`ImageSafetyClassifier`] before preprocessing.
+//! The per-tenant [`SafetyAction`] decides whether a flagged image blocks
+//! the request or is only logged and counted.
+//!
+//! ```yaml
+//! classifier:
+//!   type: http
+//!   endpoint: http://safety-classifier:8080/v1/classify
+//!   timeout_ms: 2000
+//! default_action: block
+//! fail_open: false
+//! tenants:
+//!   - tenant_key: "header:internal-evals"
+//!     action: flag
+//! ```
+//!
+//! The HTTP classifier receives `{"image": "<base64>", "index": N}` and
+//! must answer `{"flagged": bool, "categories": [..]}`. Local classifiers
+//! (e.g. an ONNX model) plug in by implementing [`ImageSafetyClassifier`].
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use axum::response::Response;
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use futures::future::try_join_all;
+use llm_multimodal::{ImageFrame, ImageSource};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+use crate::{observability::metrics::Metrics, routers::error, tenant::TenantKey};
+
+/// Classification result for one image.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct SafetyVerdict {
+    pub flagged: bool,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+/// A pluggable image safety classifier.
+#[async_trait]
+pub(crate) trait ImageSafetyClassifier: Send + Sync {
+    /// Classify the `index`-th image of a request.
+    async fn classify(&self, index: usize, image: &ImageFrame) -> Result<SafetyVerdict>;
+}
+
+/// Classifier backed by an external HTTP endpoint.
+pub(crate) struct HttpImageSafetyClassifier {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+#[derive(Serialize)]
+struct ClassifyRequest<'a> {
+    image: &'a str,
+    index: usize,
+}
+
+impl HttpImageSafetyClassifier {
+    pub fn new(endpoint: String, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to create image safety client")?;
+        Ok(Self { client, endpoint })
+    }
+}
+
+#[async_trait]
+impl ImageSafetyClassifier for HttpImageSafetyClassifier {
+    async fn classify(&self, index: usize, image: &ImageFrame) -> Result<SafetyVerdict> {
+        let encoded = BASE64_STANDARD.encode(image.raw_bytes());
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(&ClassifyRequest {
+                image: &encoded,
+                index,
+            })
+            .send()
+            .await
+            .context("image safety request failed")?
+            .error_for_status()
+            .context("image safety classifier returned an error")?;
+        response
+            .json()
+            .await
+            .context("invalid image safety classifier response")
+    }
+}
+
+/// What to do with a request containing a flagged image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SafetyAction {
+    /// Reject the request
+    Block,
+    /// Log and count the image, then dispatch as usual
+    Flag,
+    /// Skip classification
+    Allow,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ClassifierSpec {
+    Http {
+        endpoint: String,
+        #[serde(default = "default_timeout_ms")]
+        timeout_ms: u64,
+    },
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TenantSafetySpec {
+    pub tenant_key: String,
+    pub action: SafetyAction,
+}
+
+/// On-disk YAML shape of the image safety config.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ImageSafetyYaml {
+    pub classifier: ClassifierSpec,
+    pub default_action: SafetyAction,
+    /// Dispatch images the classifier failed to classify instead of
+    /// rejecting the request.
+    #[serde(default)]
+    pub fail_open: bool,
+    #[serde(default)]
+    pub tenants: Vec<TenantSafetySpec>,
+}
+
+/// Why image screening stopped a request.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ImageSafetyError {
+    #[error("{image} was rejected by the image safety policy ({})", categories.join(", "))]
+    Rejected {
+        image: String,
+        categories: Vec<String>,
+    },
+    #[error("image safety classifier unavailable for {image}: {reason}")]
+    Unavailable { image: String, reason: String },
+}
+
+impl ImageSafetyError {
+    pub fn to_response(&self) -> Response {
+        match self {
+            Self::Rejected { .. } => error::bad_request("image_safety_rejected", self.to_string()),
+            Self::Unavailable { .. } => {
+                error::service_unavailable("image_safety_unavailable", self.to_string())
+            }
+        }
+    }
+}
+
+/// Human-readable name of the `index`-th image, used in rejection errors.
+fn describe_image(index: usize, image: &ImageFrame) -> String {
+    match &image.source {
+        ImageSource::Url { url } => format!("image {index} ({url})"),
+        _ => format!("image {index}"),
+    }
+}
+
+/// Classifier plus per-tenant policy.
+pub(crate) struct ImageSafetyScreener {
+    classifier: Arc<dyn ImageSafetyClassifier>,
+    default_action: SafetyAction,
+    fail_open: bool,
+    tenant_actions: HashMap<String, SafetyAction>,
+}
+
+impl ImageSafetyScreener {
+    pub fn new(classifier: Arc<dyn ImageSafetyClassifier>, default_action: SafetyAction) -> Self {
+        Self {
+            classifier,
+            default_action,
+            fail_open: false,
+            tenant_actions: HashMap::new(),
+        }
+    }
+
+    pub fn from_yaml(yaml: ImageSafetyYaml) -> Result<Self> {
+        let classifier: Arc<dyn ImageSafetyClassifier> = match yaml.classifier {
+            ClassifierSpec::Http {
+                endpoint,
+                timeout_ms,
+            } => Arc::new(HttpImageSafetyClassifier::new(
+                endpoint,
+                Duration::from_millis(timeout_ms),
+            )?),
+        };
+        let mut screener = Self::new(classifier, yaml.default_action);
+        screener.fail_open = yaml.fail_open;
+        for tenant in yaml.tenants {
+            if screener
+                .tenant_actions
+                .insert(tenant.tenant_key.clone(), tenant.action)
+                .is_some()
+            {
+                anyhow::bail!("duplicate tenant_key '{}'", tenant.tenant_key);
+            }
+        }
+        Ok(screener)
+    }
+
+    fn action_for(&self, tenant: Option<&TenantKey>) -> SafetyAction {
+        tenant
+            .and_then(|tenant| self.tenant_actions.get(tenant.as_str()))
+            .copied()
+            .unwrap_or(self.default_action)
+    }
+
+    /// Screen all images of one request. Images are classified
+    /// concurrently; the first blocked image in request order is reported.
+    pub async fn screen(
+        &self,
+        images: &[Arc<ImageFrame>],
+        tenant: Option<&TenantKey>,
+    ) -> Result<(), ImageSafetyError> {
+        let action = self.action_for(tenant);
+        if action == SafetyAction::Allow || images.is_empty() {
+            return Ok(());
+        }
+
+        let verdicts = try_join_all(images.iter().enumerate().map(|(index, image)| async move {
+            match self.classifier.classify(index, image).await {
+                Ok(verdict) => Ok(Some(verdict)),
+                Err(e) if self.fail_open => {
+                    warn!(index, error = %e, "Image safety classification failed; failing open");
+                    Metrics::record_mm_image_safety("error");
+                    Ok(None)
+                }
+                Err(e) => {
+                    error!(index, error = %e, "Image safety classification failed");
+                    Metrics::record_mm_image_safety("error");
+                    Err(ImageSafetyError::Unavailable {
+                        image: describe_image(index, image),
+                        reason: format!("{e:#}"),
+                    })
+                }
+            }
+        }))
+        .await?;
+
+        let mut rejection = None;
+        for (index, (image, verdict)) in images.iter().zip(verdicts).enumerate() {
+            let Some(verdict) = verdict.filter(|verdict| verdict.flagged) else {
+                Metrics::record_mm_image_safety("allowed");
+                continue;
+            };
+            warn!(
+                index,
+                tenant = tenant.map(TenantKey::as_str),
+                categories = ?verdict.categories,
+                ?action,
+                "Image flagged by safety classifier"
+            );
+            match action {
+                SafetyAction::Block => {
+                    Metrics::record_mm_image_safety("blocked");
+                    rejection.get_or_insert(ImageSafetyError::Rejected {
+                        image: describe_image(index, image),
+                        categories: verdict.categories,
+                    });
+                }
+                _ => Metrics::record_mm_image_safety("flagged"),
+            }
+        }
+        rejection.map_or(Ok(()), Err)
+    }
+}
+
+/// Screener configured by `SMG_MM_IMAGE_SAFETY_CONFIG`, loaded once.
+/// Screening is disabled when the variable is unset or the file is invalid.
+pub(crate) fn image_safety_from_env() -> Option<Arc<ImageSafetyScreener>> {
+    static SCREENER: OnceLock<Option<Arc<ImageSafetyScreener>>> = OnceLock::new();
+    SCREENER
+        .get_or_init(|| {
+            let path = std::env::var("SMG_MM_IMAGE_SAFETY_CONFIG").ok()?;
+            let loaded = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading {path}"))
+                .and_then(|contents| {
+                    serde_yaml::from_str(&contents).with_context(|| format!("parsing {path}"))
+                })
+                .and_then(ImageSafetyScreener::from_yaml);
+            match loaded {
+                Ok(screener) => {
+                    tracing::info!(path = %path, "image safety pre-screening enabled");
+                    Some(Arc::new(screener))
+                }
+                Err(e) => {
+                    error!(path = %path, error = %e, "Invalid image safety config; screening disabled");
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, RgbImage};
+    use llm_multimodal::ImageDetail;
+
+    use super::*;
+
+    /// Flags images whose raw bytes start with `b"bad"`.
+    struct PrefixClassifier;
+
+    #[async_trait]
+    impl ImageSafetyClassifier for PrefixClassifier {
+        async fn classify(&self, _index: usize, image: &ImageFrame) -> Result<SafetyVerdict> {
+            if image.raw_bytes().starts_with(b"fail") {
+                anyhow::bail!("classifier down");
+            }
+            Ok(SafetyVerdict {
+                flagged: image.raw_bytes().starts_with(b"bad"),
+                categories: vec!["violence".to_string()],
+            })
+        }
+    }
+
+    fn frame(bytes: &'static [u8], url: &str) -> Arc<ImageFrame> {
+        Arc::new(ImageFrame::new(
+            DynamicImage::from(RgbImage::new(1, 1)),
+            bytes::Bytes::from_static(bytes),
+            ImageDetail::Auto,
+            ImageSource::Url {
+                url: url.to_string(),
+            },
+            String::new(),
+        ))
+    }
+
+    fn screener(default_action: SafetyAction) -> ImageSafetyScreener {
+        let mut screener = ImageSafetyScreener::new(Arc::new(PrefixClassifier), default_action);
+        screener
+            .tenant_actions
+            .insert("header:evals".to_string(), SafetyAction::Flag);
+        screener
+    }
+
+    #[tokio::test]
+    async fn test_block_names_first_flagged_image() {
+        let images = [
+            frame(b"ok", "https://a/ok.png"),
+            frame(b"bad", "https://a/bad.png"),
+            frame(b"bad", "https://a/worse.png"),
+        ];
+        let err = screener(SafetyAction::Block)
+            .screen(&images, None)
+            .await
+            .unwrap_err();
+        match &err {
+            ImageSafetyError::Rejected { image, categories } => {
+                assert_eq!(image, "image 1 (https://a/bad.png)");
+                assert_eq!(categories, &["violence"]);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "image 1 (https://a/bad.png) was rejected by the image safety policy (violence)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenant_policy_overrides_default() {
+        let images = [frame(b"bad", "https://a/bad.png")];
+        let screener = screener(SafetyAction::Block);
+        let evals = TenantKey::new("header:evals");
+        assert!(screener.screen(&images, Some(&evals)).await.is_ok());
+        let other = TenantKey::new("header:other");
+        assert!(screener.screen(&images, Some(&other)).await.is_err());
+        assert!(
+            ImageSafetyScreener::new(Arc::new(PrefixClassifier), SafetyAction::Allow)
+                .screen(&images, None)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_classifier_failure_respects_fail_open() {
+        let images = [frame(b"fail", "https://a/x.png")];
+        let mut screener = screener(SafetyAction::Block);
+        assert!(matches!(
+            screener.screen(&images, None).await,
+            Err(ImageSafetyError::Unavailable { .. })
+        ));
+        screener.fail_open = true;
+        assert!(screener.screen(&images, None).await.is_ok());
+    }
+
+    #[test]
+    fn test_parse_yaml_config() {
+        let yaml: ImageSafetyYaml = serde_yaml::from_str(
+            r#"
+classifier:
+  type: http
+  endpoint: http://localhost:9000/classify
+default_action: flag
+tenants:
+  - tenant_key: "header:strict"
+    action: block
+"#,
+        )
+        .unwrap();
+        let screener = ImageSafetyScreener::from_yaml(yaml).unwrap();
+        assert_eq!(screener.action_for(None), SafetyAction::Flag);
+        assert_eq!(
+            screener.action_for(Some(&TenantKey::new("header:strict"))),
+            SafetyAction::Block
+        );
+        assert!(!screener.fail_open);
+    }
+}
@@ -10,8 +10,9 @@ use rayon::prelude::*;
 use tracing::{info, warn};
 
 use super::log_mm_timing_enabled;
-use crate::routers::grpc::proto_wrapper::{
-    write_tokenspeed_shm_with, TensorBytes, TokenSpeedTensor,
+use crate::routers::grpc::{
+    buffer_pool::TENSOR_BUFFERS,
+    proto_wrapper::{write_tokenspeed_shm_with, TensorBytes, TokenSpeedTensor},
 };
 
 /// Serialize the primary encoder input ndarray to raw little-endian f32 bytes + shape.
@@ -36,7 +37,9 @@ fn serialize_array(encoder_input: &ArrayViewD<'_, f32>) -> (Vec<u8>, Vec<u32>) {
         #[cfg(target_endian = "little")]
         {
             let byte_slice: &[u8] = bytemuck::cast_slice(encoder_slice);
-            byte_slice.to_vec()
+            let mut bytes = TENSOR_BUFFERS.take(byte_slice.len());
+            bytes.extend_from_slice(byte_slice);
+            bytes
         }
         #[cfg(not(target_endian = "little"))]
         {
@@ -224,7 +227,9 @@ where
     F: Fn(f32) -> u16 + Copy + Send + Sync,
 {
     let element_count = encoder_input.len();
-    let mut bytes = vec![0u8; element_count * size_of::<u16>()];
+    let nbytes = element_count * size_of::<u16>();
+    let mut bytes = TENSOR_BUFFERS.take(nbytes);
+    bytes.resize(nbytes, 0);
     fill_array_as_u16_bytes(&mut bytes, encoder_input, convert);
     bytes
 }
@@ -13,6 +13,7 @@ use openai_protocol::{
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
     messages::CreateMessageRequest,
+    rerank::RerankRequest,
 };
 use reasoning_parser::ParserFactory as ReasoningParserFactory;
 use tool_parser::ParserFactory as ToolParserFactory;
@@ -41,6 +42,9 @@ use super::{
                 MessagePreparationStage, MessageRequestBuildingStage,
                 MessageResponseProcessingStage,
             },
+            rerank::{
+                RerankPreparationStage, RerankRequestBuildingStage, RerankResponseProcessingStage,
+            },
             ChatGeneratePreparationStage, ChatGenerateRequestBuildingStage,
             ChatGenerateResponseProcessingStage,
         },
@@ -49,10 +53,12 @@ use super::{
     utils::error_type_from_status,
 };
 use crate::{
+    config::{CustomStageConfig, PipelineEndpoint},
     middleware::TenantRequestMeta,
     observability::metrics::{bool_to_static_str, metrics_labels, Metrics},
     policies::PolicyRegistry,
     routers::error,
+    wasm::module_manager::WasmModuleManager,
     worker::WorkerRegistry,
 };
 
@@ -67,6 +73,21 @@ pub(crate) enum Endpoint {
     Harmony,
     Embeddings,
     Classify,
+    Rerank,
+}
+
+impl Endpoint {
+    fn config_endpoint(self) -> PipelineEndpoint {
+        match self {
+            Self::Chat => PipelineEndpoint::Chat,
+            Self::Messages => PipelineEndpoint::Messages,
+            Self::Completion => PipelineEndpoint::Completion,
+            Self::Harmony => PipelineEndpoint::Harmony,
+            Self::Embeddings => PipelineEndpoint::Embeddings,
+            Self::Classify => PipelineEndpoint::Classify,
+            Self::Rerank => PipelineEndpoint::Rerank,
+        }
+    }
 }
 
 /// Construction dependencies shared by every endpoint pipeline.
@@ -82,6 +103,9 @@ pub(crate) struct PipelineDeps {
     reasoning_parser_factory: ReasoningParserFactory,
     configured_tool_parser: Option<String>,
     configured_reasoning_parser: Option<String>,
+    /// Configured stages spliced in after built-in stages.
+    custom_stages: Arc<[CustomStageConfig]>,
+    wasm_manager: Option<Arc<WasmModuleManager>>,
 }
 
 impl PipelineDeps {
@@ -102,9 +126,23 @@ impl PipelineDeps {
             reasoning_parser_factory,
             configured_tool_parser,
             configured_reasoning_parser,
+            custom_stages: Arc::from([]),
+            wasm_manager: None,
         }
     }
 
+    /// Add the configured custom stages to every pipeline built from these
+    /// deps. `wasm_manager` runs WASM-backed stages.
+    pub(crate) fn with_custom_stages(
+        mut self,
+        custom_stages: &[CustomStageConfig],
+        wasm_manager: Option<Arc<WasmModuleManager>>,
+    ) -> Self {
+        self.custom_stages = Arc::from(custom_stages);
+        self.wasm_manager = wasm_manager;
+        self
+    }
+
     /// Deps for endpoints (embeddings/classify/completion) with no configured
     /// parsers; the parser fields are placeholders those endpoints never read.
     pub(crate) fn pair(
@@ -118,6 +156,8 @@ impl PipelineDeps {
             reasoning_parser_factory: ReasoningParserFactory::default(),
             configured_tool_parser: None,
             configured_reasoning_parser: None,
+            custom_stages: Arc::from([]),
+            wasm_manager: None,
         }
     }
 
@@ -180,6 +220,8 @@ impl PipelineDeps {
             reasoning_parser_factory: ReasoningParserFactory::default(),
             configured_tool_parser: None,
             configured_reasoning_parser: None,
+            custom_stages: Arc::from([]),
+            wasm_manager: None,
         }
     }
 }
@@ -400,8 +442,34 @@ impl RequestPipeline {
                     Box::new(ClassifyResponseProcessingStage::new()),
                 ]
             }
+            Endpoint::Rerank => {
+                // Rerank is single-worker only.
+                if !matches!(mode, Mode::Regular) {
+                    return None;
+                }
+                vec![
+                    Box::new(RerankPreparationStage),
+                    Box::new(WorkerSelectionStage::new(
+                        deps.worker_registry.clone(),
+                        deps.policy_registry.clone(),
+                        worker_selection,
+                    )),
+                    Box::new(ClientAcquisitionStage),
+                    Box::new(RerankRequestBuildingStage),
+                    Box::new(DispatchMetadataStage),
+                    Box::new(RequestExecutionStage::new()),
+                    Box::new(RerankResponseProcessingStage),
+                ]
+            }
         };
 
+        let stages = splice_custom_stages(
+            stages,
+            endpoint.config_endpoint(),
+            &deps.custom_stages,
+            deps.wasm_manager.as_ref(),
+        );
+
         Some(Self {
             stages: Arc::new(stages),
             backend_type: backend,
@@ -486,6 +554,7 @@ impl RequestPipeline {
                 | FinalResponse::Completion(_)
                 | FinalResponse::Embedding(_)
                 | FinalResponse::Classify(_)
+                | FinalResponse::Rerank(_)
                 | FinalResponse::Messages(_)),
             ) => self.wrong_response_type(
                 "execute_chat",
@@ -577,6 +646,7 @@ impl RequestPipeline {
                 | FinalResponse::Completion(_)
                 | FinalResponse::Embedding(_)
                 | FinalResponse::Classify(_)
+                | FinalResponse::Rerank(_)
                 | FinalResponse::Messages(_)),
             ) => self.wrong_response_type(
                 "execute_generate",
@@ -668,6 +738,7 @@ impl RequestPipeline {
                 | FinalResponse::Generate(_)
                 | FinalResponse::Embedding(_)
                 | FinalResponse::Classify(_)
+                | FinalResponse::Rerank(_)
                 | FinalResponse::Messages(_)),
             ) => self.wrong_response_type(
                 "execute_completion",
@@ -888,6 +959,108 @@ impl RequestPipeline {
         }
     }
 
+    /// Execute the complete pipeline for a rerank request
+    pub async fn execute_rerank(
+        &self,
+        request: Arc<RerankRequest>,
+        headers: Option<http::HeaderMap>,
+        model_id: String,
+        components: Arc<SharedComponents>,
+        tenant_request_meta: Option<TenantRequestMeta>,
+    ) -> Response {
+        debug!(
+            "execute_rerank: Starting execution for model: {}",
+            &model_id
+        );
+        let start = Instant::now();
+
+        // Record request start
+        Metrics::record_router_request(
+            metrics_labels::ROUTER_GRPC,
+            self.backend_type,
+            metrics_labels::CONNECTION_GRPC,
+            &model_id,
+            metrics_labels::ENDPOINT_RERANK,
+            bool_to_static_str(false), // Rerank is never streaming
+        );
+
+        let mut ctx = RequestContext::for_rerank(request, headers, model_id.clone(), components);
+        ctx.input.tenant_request_meta = tenant_request_meta;
+
+        for stage in self.stages.iter() {
+            debug!("execute_rerank: Executing stage: {}", stage.name());
+            match stage.execute(&mut ctx).await {
+                Ok(Some(response)) => {
+                    debug!(
+                        "execute_rerank: Stage {} returned final response.",
+                        stage.name()
+                    );
+                    Metrics::record_router_duration(
+                        metrics_labels::ROUTER_GRPC,
+                        self.backend_type,
+                        metrics_labels::CONNECTION_GRPC,
+                        &model_id,
+                        metrics_labels::ENDPOINT_RERANK,
+                        start.elapsed(),
+                    );
+                    return response;
+                }
+                Ok(None) => {
+                    debug!(
+                        "execute_rerank: Stage {} completed, continuing to next stage.",
+                        stage.name()
+                    );
+                    continue;
+                }
+                Err(response) => {
+                    error!(
+                        "execute_rerank: Stage {} failed with status {:?}, returning error response.",
+                        stage.name(),
+                        response.status()
+                    );
+                    Metrics::record_router_error(
+                        metrics_labels::ROUTER_GRPC,
+                        self.backend_type,
+                        metrics_labels::CONNECTION_GRPC,
+                        &model_id,
+                        metrics_labels::ENDPOINT_RERANK,
+                        error_type_from_status(response.status()),
+                    );
+                    return response;
+                }
+            }
+        }
+
+        debug!(
+            "execute_rerank: Pipeline finished, processing final_response. Current state: {:?}",
+            ctx.state.response.final_response
+        );
+        match ctx.state.response.final_response {
+            Some(FinalResponse::Rerank(response)) => {
+                Metrics::record_router_duration(
+                    metrics_labels::ROUTER_GRPC,
+                    self.backend_type,
+                    metrics_labels::CONNECTION_GRPC,
+                    &model_id,
+                    metrics_labels::ENDPOINT_RERANK,
+                    start.elapsed(),
+                );
+                axum::Json(response).into_response()
+            }
+            Some(_) => {
+                error!(function = "execute_rerank", "Wrong response type");
+                error::internal_error("wrong_response_type", "Internal error: wrong response type")
+            }
+            None => {
+                error!(
+                    function = "execute_rerank",
+                    "No final response produced by pipeline."
+                );
+                error::internal_error("no_response_produced", "No response produced")
+            }
+        }
+    }
+
     /// Execute the complete pipeline for a Messages API request
     pub async fn execute_messages(
         &self,
@@ -964,7 +1137,8 @@ impl RequestPipeline {
                 | FinalResponse::Generate(_)
                 | FinalResponse::Completion(_)
                 | FinalResponse::Embedding(_)
-                | FinalResponse::Classify(_)),
+                | FinalResponse::Classify(_)
+                | FinalResponse::Rerank(_)),
             ) => self.wrong_response_type(
                 "execute_messages",
                 "Messages",
@@ -1032,10 +1206,11 @@ impl RequestPipeline {
             | Some(FinalResponse::Completion(_))
             | Some(FinalResponse::Embedding(_))
             | Some(FinalResponse::Classify(_))
+            | Some(FinalResponse::Rerank(_))
             | Some(FinalResponse::Messages(_)) => {
                 error!(
                     function = "execute_chat_for_responses",
-                    "Wrong response type: expected Chat, got Generate/Embedding/Classify/Messages"
+                    "Wrong response type: expected Chat, got Generate/Embedding/Classify/Rerank/Messages"
                 );
                 Err(error::internal_error(
                     "wrong_response_type",
@@ -1379,6 +1554,18 @@ mod build_parity_tests {
                 ]),
                 REGULAR,
             ),
+            (Endpoint::Rerank, Mode::Regular) => (
+                v(&[
+                    "RerankPreparationStage",
+                    "WorkerSelectionStage(Regular)",
+                    "ClientAcquisitionStage",
+                    "RerankRequestBuildingStage",
+                    "DispatchMetadataStage",
+                    "RequestExecutionStage",
+                    "RerankResponseProcessingStage",
+                ]),
+                REGULAR,
+            ),
             (endpoint, mode) => panic!("no golden for invalid combo {endpoint:?}/{mode:?}"),
         }
     }
@@ -1421,7 +1608,7 @@ mod build_parity_tests {
         assert_parity(Endpoint::Harmony, Mode::Regular, &deps);
         assert_parity(Endpoint::Harmony, Mode::PrefillDecode, &deps);
 
-        for endpoint in [Endpoint::Embeddings, Endpoint::Classify] {
+        for endpoint in [Endpoint::Embeddings, Endpoint::Classify, Endpoint::Rerank] {
             assert!(
                 RequestPipeline::build(endpoint, Mode::PrefillDecode, &deps).is_none(),
                 "{endpoint:?} PD must be invalid"
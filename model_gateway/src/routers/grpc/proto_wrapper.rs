@@ -17,7 +17,10 @@ use std::{
     time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
-use futures_util::StreamExt;
+use futures_util::{
+    future::{select_all, BoxFuture},
+    StreamExt,
+};
 use memmap2::MmapOptions;
 use rand::RngExt;
 #[cfg(target_os = "linux")]
@@ -36,10 +39,15 @@ use smg_grpc_client::{
     trtllm_service::AbortOnDropStream as TrtllmStream,
     vllm_engine::AbortOnDropStream as VllmStream,
     vllm_proto::{self as vllm, generate_complete::MatchedStop as VllmMatchedStop},
+    MlxEngineClient, SglangSchedulerClient, TokenSpeedSchedulerClient, TrtllmServiceClient,
+    VllmEngineClient,
 };
 use smg_mm_rdma::RdmaExporter;
 
-use crate::routers::grpc::multimodal::mm_rdma_exporter;
+use crate::routers::grpc::{
+    backend::Backend, buffer_pool::TENSOR_BUFFERS, multimodal::mm_rdma_exporter,
+    utils::finish_reason,
+};
 
 /// Backend-neutral encode->prefill bootstrap info for one multimodal item.
 ///
@@ -557,6 +565,9 @@ fn resolve_mm_tensor_payload(
                     "smg_mm_timing mm_shm_write"
                 );
             }
+            // The bytes now live in the mapping; keep the buffer for the
+            // next request's tensor.
+            TENSOR_BUFFERS.give(data);
             Metrics::record_mm_tensor(engine, "shm", nbytes);
             MmTensorPayload::Shm(handle)
         }
@@ -812,7 +823,7 @@ pub fn cleanup_mm_shm_handles(handles: &[common::ShmHandle]) {
 /// multimodal proto, unlinking any `/dev/shm` segments it references if `build`
 /// fails — so a build error doesn't leak SHM files before the send-path cleanup
 /// can run. Keeping this engine-specific SHM lifecycle in the protocol layer
-/// lets the per-engine dispatch in `client.rs` stay a thin, neutral wrapper.
+/// lets the per-engine backends stay thin.
 pub(crate) fn finish_tokenspeed_request(
     tokenspeed_mm: Option<tokenspeed::MultimodalInputs>,
     build: impl FnOnce(
@@ -1277,6 +1288,41 @@ impl ProtoGenerateRequest {
         }
     }
 
+    /// Set request ID
+    pub fn set_request_id(&mut self, request_id: String) {
+        match self {
+            Self::Sglang(req) => req.request_id = request_id,
+            Self::Vllm(req) => req.request_id = request_id,
+            Self::Trtllm(req) => req.request_id = request_id,
+            Self::Mlx(req) => req.request_id = request_id,
+            Self::TokenSpeed(req) => req.request_id = request_id,
+        }
+    }
+
+    /// Shift an explicit sampling seed by `offset` so fan-out branches stay
+    /// reproducible without sampling identical sequences. No-op when no seed
+    /// is set or the engine has no seed field.
+    pub fn offset_sampling_seed(&mut self, offset: u32) {
+        match self {
+            Self::Vllm(req) => {
+                if let Some(seed) = req.sampling_params.as_mut().and_then(|p| p.seed.as_mut()) {
+                    *seed = seed.wrapping_add_unsigned(offset);
+                }
+            }
+            Self::Mlx(req) => {
+                if let Some(seed) = req.sampling_params.as_mut().and_then(|p| p.seed.as_mut()) {
+                    *seed = seed.wrapping_add_unsigned(offset);
+                }
+            }
+            Self::Trtllm(req) => {
+                if let Some(seed) = req.sampling_config.as_mut().and_then(|c| c.seed.as_mut()) {
+                    *seed = seed.wrapping_add(u64::from(offset));
+                }
+            }
+            Self::Sglang(_) | Self::TokenSpeed(_) => {}
+        }
+    }
+
     /// Set KV transfer parameters for Mooncake PD disaggregation (vLLM only).
     /// These parameters tell the decode worker where to fetch KV cache from the prefill worker.
     pub fn set_kv_transfer_params(&mut self, remote_host: String, remote_port: u32) {
@@ -1400,51 +1446,59 @@ impl ProtoGenerateResponse {
     /// Consumes self to avoid cloning large proto messages in hot streaming path
     pub fn into_response(self) -> ProtoResponseVariant {
         match self {
-            Self::Sglang(resp) => match resp.response {
-                Some(sglang::generate_response::Response::Chunk(chunk)) => {
-                    ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Sglang(chunk))
-                }
-                Some(sglang::generate_response::Response::Complete(complete)) => {
-                    ProtoResponseVariant::Complete(ProtoGenerateComplete::Sglang(complete))
-                }
-                None => ProtoResponseVariant::None,
-            },
-            Self::Vllm(resp) => match resp.response {
-                Some(vllm::generate_response::Response::Chunk(chunk)) => {
-                    ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Vllm(chunk))
-                }
-                Some(vllm::generate_response::Response::Complete(complete)) => {
-                    ProtoResponseVariant::Complete(ProtoGenerateComplete::Vllm(complete))
-                }
-                None => ProtoResponseVariant::None,
-            },
-            Self::Trtllm(resp) => match resp.response {
-                Some(trtllm::generate_response::Response::Chunk(chunk)) => {
-                    ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Trtllm(chunk))
-                }
-                Some(trtllm::generate_response::Response::Complete(complete)) => {
-                    ProtoResponseVariant::Complete(ProtoGenerateComplete::Trtllm(complete))
-                }
-                None => ProtoResponseVariant::None,
-            },
-            Self::Mlx(resp) => match resp.response {
-                Some(mlx::generate_response::Response::Chunk(chunk)) => {
-                    ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::Mlx(chunk))
-                }
-                Some(mlx::generate_response::Response::Complete(complete)) => {
-                    ProtoResponseVariant::Complete(ProtoGenerateComplete::Mlx(complete))
-                }
-                None => ProtoResponseVariant::None,
-            },
-            Self::TokenSpeed(resp) => match resp.response {
-                Some(tokenspeed::generate_response::Response::Chunk(chunk)) => {
-                    ProtoResponseVariant::Chunk(ProtoGenerateStreamChunk::TokenSpeed(chunk))
-                }
-                Some(tokenspeed::generate_response::Response::Complete(complete)) => {
-                    ProtoResponseVariant::Complete(ProtoGenerateComplete::TokenSpeed(complete))
+            Self::Sglang(resp) => SglangSchedulerClient::decode_chunk(*resp),
+            Self::Vllm(resp) => VllmEngineClient::decode_chunk(*resp),
+            Self::Trtllm(resp) => TrtllmServiceClient::decode_chunk(*resp),
+            Self::Mlx(resp) => MlxEngineClient::decode_chunk(*resp),
+            Self::TokenSpeed(resp) => TokenSpeedSchedulerClient::decode_chunk(*resp),
+        }
+    }
+}
+
+impl ProtoGenerateResponse {
+    /// Overwrite the choice index of a chunk or complete response. Fan-out
+    /// branches each report index 0; the merged stream renumbers them.
+    pub fn set_index(&mut self, index: u32) {
+        macro_rules! set {
+            ($resp:expr, $chunk:path, $complete:path, $field:ident) => {
+                match $resp.response.as_mut() {
+                    Some($chunk(chunk)) => chunk.$field = index,
+                    Some($complete(complete)) => complete.$field = index,
+                    None => {}
                 }
-                None => ProtoResponseVariant::None,
-            },
+            };
+        }
+        match self {
+            Self::Sglang(resp) => set!(
+                resp,
+                sglang::generate_response::Response::Chunk,
+                sglang::generate_response::Response::Complete,
+                index
+            ),
+            Self::Vllm(resp) => set!(
+                resp,
+                vllm::generate_response::Response::Chunk,
+                vllm::generate_response::Response::Complete,
+                index
+            ),
+            Self::Trtllm(resp) => set!(
+                resp,
+                trtllm::generate_response::Response::Chunk,
+                trtllm::generate_response::Response::Complete,
+                sequence_index
+            ),
+            Self::Mlx(resp) => set!(
+                resp,
+                mlx::generate_response::Response::Chunk,
+                mlx::generate_response::Response::Complete,
+                index
+            ),
+            Self::TokenSpeed(resp) => set!(
+                resp,
+                tokenspeed::generate_response::Response::Chunk,
+                tokenspeed::generate_response::Response::Complete,
+                index
+            ),
         }
     }
 }
@@ -1749,8 +1803,22 @@ impl ProtoGenerateComplete {
         }
     }
 
-    /// Get finish reason
-    pub fn finish_reason(&self) -> &str {
+    /// Finish reason normalized to its OpenAI value (`stop`, `length`,
+    /// `tool_calls`, `content_filter` or `abort`), whichever engine sent it
+    pub fn finish_reason(&self) -> &'static str {
+        let quirks = match self {
+            Self::Sglang(_) => &finish_reason::SGLANG_QUIRKS,
+            Self::Vllm(_) => &finish_reason::VLLM_QUIRKS,
+            Self::Trtllm(_) => &finish_reason::TRTLLM_QUIRKS,
+            Self::Mlx(_) => &finish_reason::MLX_QUIRKS,
+            Self::TokenSpeed(_) => &finish_reason::TOKENSPEED_QUIRKS,
+        };
+        finish_reason::normalize_finish_reason(quirks, self.raw_finish_reason())
+    }
+
+    /// Finish reason exactly as the engine reported it, for the native
+    /// `/generate` API
+    pub fn raw_finish_reason(&self) -> &str {
         match self {
             Self::Sglang(c) => &c.finish_reason,
             Self::Vllm(c) => &c.finish_reason,
@@ -1942,6 +2010,8 @@ pub enum ProtoStream {
     Trtllm(TrtllmStream),
     Mlx(MlxStream),
     TokenSpeed(TokenSpeedStream),
+    /// Merged branches of an `n > 1` fan-out
+    FanOut(FanOutStream),
 }
 
 impl ProtoStream {
@@ -1968,6 +2038,7 @@ impl ProtoStream {
                 .next()
                 .await
                 .map(|result| result.map(|r| ProtoGenerateResponse::TokenSpeed(Box::new(r)))),
+            Self::FanOut(stream) => stream.next().await,
         }
     }
 
@@ -1979,6 +2050,69 @@ impl ProtoStream {
             Self::Trtllm(stream) => stream.mark_completed(),
             Self::Mlx(stream) => stream.mark_completed(),
             Self::TokenSpeed(stream) => stream.mark_completed(),
+            Self::FanOut(stream) => stream.mark_completed(),
+        }
+    }
+}
+
+/// Merges the per-choice streams of an `n > 1` fan-out (one n=1 generation
+/// per branch, for backends that ignore `n`) into a single stream, rewriting
+/// each response's choice index to its branch position.
+///
+/// Branches are polled concurrently, so chunks interleave in arrival order
+/// exactly like a native multi-choice stream. Dropping the stream before
+/// `mark_completed` aborts every branch.
+pub struct FanOutStream {
+    branches: Vec<ProtoStream>,
+    exhausted: Vec<bool>,
+}
+
+impl FanOutStream {
+    pub fn new(branches: Vec<ProtoStream>) -> Self {
+        let exhausted = vec![false; branches.len()];
+        Self {
+            branches,
+            exhausted,
+        }
+    }
+
+    /// Next response from whichever branch yields first. Boxed because
+    /// branches are themselves `ProtoStream`s.
+    pub fn next(&mut self) -> BoxFuture<'_, Option<Result<ProtoGenerateResponse, tonic::Status>>> {
+        Box::pin(async move {
+            loop {
+                let pending: Vec<_> = self
+                    .branches
+                    .iter_mut()
+                    .zip(&self.exhausted)
+                    .enumerate()
+                    .filter(|(_, (_, exhausted))| !**exhausted)
+                    .map(|(i, (branch, _))| Box::pin(async move { (i, branch.next().await) }))
+                    .collect();
+                if pending.is_empty() {
+                    return None;
+                }
+
+                // Losing futures are dropped un-polled to completion; stream
+                // `next()` is cancel-safe, so no response is lost.
+                let ((index, item), _, rest) = select_all(pending).await;
+                drop(rest);
+                match item {
+                    Some(Ok(mut response)) => {
+                        response.set_index(index as u32);
+                        return Some(Ok(response));
+                    }
+                    Some(Err(status)) => return Some(Err(status)),
+                    None => self.exhausted[index] = true,
+                }
+            }
+        })
+    }
+
+    /// Mark every branch as completed (no abort needed)
+    pub fn mark_completed(&mut self) {
+        for branch in &mut self.branches {
+            branch.mark_completed();
         }
     }
 }
@@ -2072,8 +2206,168 @@ impl ProtoEmbedComplete {
 #[cfg(test)]
 mod tests {
     use prost::Message;
+    use tonic::{codec::Codec, transport::Channel};
 
     use super::*;
+    use crate::routers::grpc::{client::GrpcClient, common::response_formatting::build_usage};
+
+    #[test]
+    fn set_index_rewrites_chunk_and_complete() {
+        let mut chunk = ProtoGenerateResponse::Mlx(Box::new(mlx::GenerateResponse {
+            response: Some(mlx::generate_response::Response::Chunk(
+                mlx::GenerateStreamChunk::default(),
+            )),
+        }));
+        chunk.set_index(3);
+        let ProtoResponseVariant::Chunk(chunk) = chunk.into_response() else {
+            panic!("expected chunk");
+        };
+        assert_eq!(chunk.index(), 3);
+
+        let mut complete = ProtoGenerateResponse::Trtllm(Box::new(trtllm::GenerateResponse {
+            response: Some(trtllm::generate_response::Response::Complete(
+                trtllm::GenerateComplete::default(),
+            )),
+            ..Default::default()
+        }));
+        complete.set_index(2);
+        let ProtoResponseVariant::Complete(complete) = complete.into_response() else {
+            panic!("expected complete");
+        };
+        assert_eq!(complete.index(), 2);
+    }
+
+    /// An MLX branch replaying `responses` from a gRPC-framed body, so it
+    /// decodes exactly like a worker stream.
+    fn mlx_branch(client: &MlxEngineClient, responses: &[mlx::GenerateResponse]) -> ProtoStream {
+        let mut body = Vec::new();
+        for response in responses {
+            let message = response.encode_to_vec();
+            body.push(0); // uncompressed
+            body.extend_from_slice(&u32::try_from(message.len()).unwrap().to_be_bytes());
+            body.extend_from_slice(&message);
+        }
+        let streaming = tonic::Streaming::new_request(
+            tonic_prost::ProstCodec::<mlx::GenerateResponse, mlx::GenerateResponse>::default()
+                .decoder(),
+            http_body_util::Full::new(bytes::Bytes::from(body)),
+            None,
+            None,
+        );
+        ProtoStream::Mlx(MlxStream::new(
+            streaming,
+            "fan-out-test".to_string(),
+            client.clone(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn fan_out_stream_merges_branches_into_choices() {
+        let channel = || Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let client = MlxEngineClient::from_channel(channel());
+        assert!(!GrpcClient::Mlx(client.clone()).supports_native_n());
+        assert!(
+            GrpcClient::Sglang(SglangSchedulerClient::from_channel(channel())).supports_native_n()
+        );
+
+        // Each branch is an n=1 generation, so the worker reports index 0.
+        let branches = [(2, "stop"), (4, "length"), (1, "stop")]
+            .into_iter()
+            .map(|(completion_tokens, finish_reason)| {
+                mlx_branch(
+                    &client,
+                    &[
+                        mlx::GenerateResponse {
+                            response: Some(mlx::generate_response::Response::Chunk(
+                                mlx::GenerateStreamChunk {
+                                    token_ids: vec![7; completion_tokens as usize],
+                                    ..Default::default()
+                                },
+                            )),
+                        },
+                        mlx::GenerateResponse {
+                            response: Some(mlx::generate_response::Response::Complete(
+                                mlx::GenerateComplete {
+                                    finish_reason: finish_reason.to_string(),
+                                    prompt_tokens: 5,
+                                    completion_tokens,
+                                    ..Default::default()
+                                },
+                            )),
+                        },
+                    ],
+                )
+            })
+            .collect();
+        let mut stream = ProtoStream::FanOut(FanOutStream::new(branches));
+
+        let mut chunk_indices = Vec::new();
+        let mut completes = Vec::new();
+        while let Some(response) = stream.next().await {
+            match response.unwrap().into_response() {
+                ProtoResponseVariant::Chunk(chunk) => chunk_indices.push(chunk.index()),
+                ProtoResponseVariant::Complete(complete) => completes.push(complete),
+                ProtoResponseVariant::None => {}
+            }
+        }
+        stream.mark_completed();
+
+        chunk_indices.sort_unstable();
+        assert_eq!(chunk_indices, [0, 1, 2]);
+        completes.sort_by_key(ProtoGenerateComplete::index);
+        let finish_reasons: Vec<_> = completes
+            .iter()
+            .map(|complete| (complete.index(), complete.finish_reason()))
+            .collect();
+        assert_eq!(finish_reasons, [(0, "stop"), (1, "length"), (2, "stop")]);
+
+        let usage = build_usage(&completes);
+        assert_eq!(usage.prompt_tokens, 15);
+        assert_eq!(usage.completion_tokens, 7);
+        assert_eq!(usage.total_tokens, 22);
+    }
+
+    #[test]
+    fn finish_reasons_are_normalized_per_backend() {
+        let trtllm = ProtoGenerateComplete::Trtllm(trtllm::GenerateComplete {
+            finish_reason: "stop_word".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(trtllm.finish_reason(), "stop");
+        assert_eq!(trtllm.raw_finish_reason(), "stop_word");
+
+        let sglang = ProtoGenerateComplete::Sglang(sglang::GenerateComplete {
+            finish_reason: r#"{"type": "length", "length": 32}"#.to_string(),
+            ..Default::default()
+        });
+        assert_eq!(sglang.finish_reason(), "length");
+
+        let mlx = ProtoGenerateComplete::Mlx(mlx::GenerateComplete::default());
+        assert_eq!(mlx.finish_reason(), "stop");
+    }
+
+    #[test]
+    fn offset_sampling_seed_only_shifts_explicit_seeds() {
+        let mut seeded = ProtoGenerateRequest::Mlx(Box::new(mlx::GenerateRequest {
+            sampling_params: Some(mlx::SamplingParams {
+                seed: Some(41),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+        seeded.offset_sampling_seed(2);
+        let ProtoGenerateRequest::Mlx(req) = &seeded else {
+            panic!("expected MLX request");
+        };
+        assert_eq!(req.sampling_params.as_ref().and_then(|p| p.seed), Some(43));
+
+        let mut unseeded = ProtoGenerateRequest::Mlx(Box::default());
+        unseeded.offset_sampling_seed(2);
+        let ProtoGenerateRequest::Mlx(req) = &unseeded else {
+            panic!("expected MLX request");
+        };
+        assert!(req.sampling_params.is_none());
+    }
 
     #[test]
     fn tokenspeed_image_into_proto_uses_itemized_payload() {
@@ -29,7 +29,7 @@ use crate::routers::{
         common::{response_collection, response_formatting},
         context::{DispatchMetadata, ExecutionResult},
         proto_wrapper::ProtoGenerateComplete,
-        utils,
+        utils::{self, finish_reason},
     },
 };
 
@@ -177,15 +177,9 @@ impl ResponseProcessor {
             }
         }
 
-        // Step 3: Use finish reason directly from proto (already OpenAI-compatible string)
-        let finish_reason_str = complete.finish_reason();
-
-        // Override finish reason if we have tool calls
-        let final_finish_reason_str = if tool_calls.is_some() {
-            "tool_calls"
-        } else {
-            finish_reason_str
-        };
+        // Step 3: Normalized finish reason, overridden when tool calls were parsed
+        let final_finish_reason_str =
+            finish_reason::with_tool_calls(complete.finish_reason(), tool_calls.is_some());
 
         let matched_stop = complete.matched_stop_json();
 
@@ -294,7 +288,8 @@ impl ResponseProcessor {
         }
 
         // Build usage from gRPC response counters.
-        let usage = response_formatting::build_usage(&all_responses);
+        let usage = response_formatting::build_usage(&all_responses)
+            .with_prompt_breakdown(dispatch.prompt_breakdown.clone());
 
         // Build final ChatCompletionResponse
         Ok(
@@ -303,6 +298,7 @@ impl ResponseProcessor {
                 .choices(choices)
                 .usage(usage)
                 .maybe_system_fingerprint(dispatch.weight_version.clone())
+                .maybe_seed(chat_request.effective_seed())
                 .build(),
         )
     }
@@ -419,7 +415,7 @@ impl ResponseProcessor {
             }
 
             let output_ids = complete.output_ids().to_vec();
-            let finish_reason_str = complete.finish_reason();
+            let finish_reason_str = complete.raw_finish_reason();
 
             // Parse finish_reason from string to proper type
             let finish_reason =
@@ -851,22 +847,8 @@ impl ResponseProcessor {
                 prompt_tokens = prompt_tokens.max(complete.prompt_tokens());
                 total_completion += complete.completion_tokens();
 
-                let finish_reason = {
-                    let reason = complete.finish_reason();
-                    if reason.is_empty() {
-                        None
-                    } else if reason == "stop" || reason == "length" {
-                        Some(reason.to_string())
-                    } else if let Ok(json) = serde_json::from_str::<serde_json::Value>(reason) {
-                        json.get("type").and_then(|v| v.as_str()).map(|s| match s {
-                            "length" => "length".to_string(),
-                            "stop" => "stop".to_string(),
-                            other => other.to_string(),
-                        })
-                    } else {
-                        Some(reason.to_string())
-                    }
-                };
+                // Completions have no tool calls to report.
+                let finish_reason = finish_reason::with_tool_calls(complete.finish_reason(), false);
 
                 let matched_stop = complete.matched_stop_json();
 
@@ -889,7 +871,7 @@ impl ResponseProcessor {
                     text,
                     index: index_offset + i as u32,
                     logprobs: None, // TODO: wire legacy LogProbs from backend token_logprobs
-                    finish_reason: finish_reason.or_else(|| Some("stop".to_string())),
+                    finish_reason: Some(finish_reason.to_string()),
                     matched_stop,
                 });
             }
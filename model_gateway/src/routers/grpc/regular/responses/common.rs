@@ -8,13 +8,14 @@
 
 use axum::{http, response::Response};
 use openai_protocol::{
-    chat::ChatCompletionRequest,
+    chat::{ChatCompletionRequest, ChatCompletionResponse},
     common::{Tool, ToolChoice, ToolChoiceValue},
     responses::{
         self, ResponseContentPart, ResponseInput, ResponseInputOutputItem, ResponseOutputItem,
         ResponsesRequest,
     },
 };
+use serde_json::Value;
 use smg_data_connector::{
     self as data_connector, ConversationId, ResponseId, ResponseStorageError,
 };
@@ -24,7 +25,12 @@ use tracing::{debug, warn};
 use crate::{
     middleware::TenantRequestMeta,
     routers::{
-        common::{openai_bridge, persistence_utils::split_stored_message_content},
+        common::{
+            mcp_utils::DEFAULT_MAX_ITERATIONS,
+            openai_bridge,
+            persistence_utils::{item_to_json, tenant_owns_response},
+            tool_history::normalize_history,
+        },
         error,
         grpc::common::responses::ResponsesContext,
     },
@@ -117,6 +123,85 @@ pub(super) fn prepare_chat_tools_and_choice(
     };
 }
 
+/// Gateway-side enforcement of a request's `max_tool_calls`,
+/// `parallel_tool_calls` and `tool_choice` across the tool loop. The same
+/// settings reach the backend through the chat request as constraints where
+/// it supports them; these checks hold when it does not.
+pub(super) struct ToolCallLimits {
+    /// Hosted (MCP) calls the loop may execute in total: `max_tool_calls`
+    /// capped by the safety limit.
+    pub max_calls: usize,
+    /// Whether one model turn may issue several calls.
+    pub parallel: bool,
+    /// Whether the first model turn must call a tool.
+    pub required: bool,
+}
+
+impl ToolCallLimits {
+    pub fn new(request: &ResponsesRequest) -> Self {
+        let required = match request
+            .tool_choice
+            .as_ref()
+            .map(|tc| tc.to_chat_tool_choice())
+        {
+            Some(ToolChoice::Value(ToolChoiceValue::Required) | ToolChoice::Function { .. }) => {
+                true
+            }
+            Some(ToolChoice::AllowedTools { mode, .. }) => mode == "required",
+            _ => false,
+        };
+        Self {
+            max_calls: request.max_tool_calls.map_or(DEFAULT_MAX_ITERATIONS, |n| {
+                (n as usize).min(DEFAULT_MAX_ITERATIONS)
+            }),
+            parallel: request.parallel_tool_calls.unwrap_or(true),
+            required,
+        }
+    }
+
+    /// Drop the calls of one model turn beyond the first when parallel calls
+    /// are off. Returns how many were dropped.
+    pub fn limit_turn(&self, response: &mut ChatCompletionResponse) -> usize {
+        if self.parallel {
+            return 0;
+        }
+        let Some(calls) = response
+            .choices
+            .first_mut()
+            .and_then(|choice| choice.message.tool_calls.as_mut())
+        else {
+            return 0;
+        };
+        let dropped = calls.len().saturating_sub(1);
+        calls.truncate(1);
+        dropped
+    }
+
+    /// How many of `requested` hosted calls may run after `executed` have.
+    pub fn admit(&self, executed: usize, requested: usize) -> usize {
+        requested.min(self.max_calls.saturating_sub(executed))
+    }
+}
+
+/// Error payload of a response stopped by `max_tool_calls`.
+pub(super) fn max_tool_calls_error(limit: usize) -> serde_json::Value {
+    serde_json::json!({
+        "code": "max_tool_calls_exceeded",
+        "message": format!(
+            "Reached the max_tool_calls limit ({limit}) before executing the remaining tool calls."
+        ),
+    })
+}
+
+/// Error payload of a response whose model did not call a tool although
+/// `tool_choice` required one.
+pub(super) fn required_tool_call_error() -> serde_json::Value {
+    serde_json::json!({
+        "code": "tool_call_required",
+        "message": "tool_choice requires a tool call, but the model did not call any tool.",
+    })
+}
+
 /// Tool call extracted from a ChatCompletionResponse
 #[derive(Debug, Clone)]
 pub(super) struct ExtractedToolCall {
@@ -162,10 +247,33 @@ pub(super) fn convert_mcp_tools_to_chat_tools(session: &McpToolSession<'_>) -> V
 // Conversation History Loading
 // ============================================================================
 
+/// Conversation item types replayed as input. `tool_use` and `tool_result`
+/// are Anthropic-shaped items that [`normalize_history`] rewrites.
+const REPLAYED_ITEM_TYPES: &[&str] = &[
+    "message",
+    "function_call",
+    "function_call_output",
+    "tool_use",
+    "tool_result",
+];
+
+/// Deserialize normalized history items, skipping the ones that fail.
+fn deserialize_history(history: Vec<Value>) -> Vec<ResponseInputOutputItem> {
+    history
+        .into_iter()
+        .filter_map(|item| {
+            serde_json::from_value::<ResponseInputOutputItem>(item.clone())
+                .map_err(|e| warn!("Failed to deserialize stored item: {}. Item: {}", e, item))
+                .ok()
+        })
+        .collect()
+}
+
 /// Load conversation history and response chains, returning modified request
 pub(super) async fn load_conversation_history(
     ctx: &ResponsesContext,
     request: &ResponsesRequest,
+    tenant_meta: &TenantRequestMeta,
 ) -> Result<ResponsesRequest, Response> {
     let mut modified_request = request.clone();
     let mut conversation_items: Option<Vec<ResponseInputOutputItem>> = None;
@@ -178,46 +286,32 @@ pub(super) async fn load_conversation_history(
             .get_response_chain(&prev_id, None)
             .await
         {
-            Ok(chain) if !chain.responses.is_empty() => {
-                let mut items = Vec::new();
-                for stored in &chain.responses {
-                    // Convert input items from stored input (which is now a JSON array)
-                    if let Some(input_arr) = stored.input.as_array() {
-                        for item in input_arr {
-                            match serde_json::from_value::<ResponseInputOutputItem>(item.clone()) {
-                                Ok(input_item) => {
-                                    items.push(input_item);
-                                }
-                                Err(e) => {
-                                    warn!(
-                                        "Failed to deserialize stored input item: {}. Item: {}",
-                                        e, item
-                                    );
-                                }
-                            }
-                        }
-                    }
-
-                    // Convert output items from stored raw_response["output"] (which is a JSON array)
-                    if let Some(output_arr) =
-                        stored.raw_response.get("output").and_then(|v| v.as_array())
-                    {
-                        for item in output_arr {
-                            match serde_json::from_value::<ResponseInputOutputItem>(item.clone()) {
-                                Ok(output_item) => {
-                                    items.push(output_item);
-                                }
-                                Err(e) => {
-                                    warn!(
-                                        "Failed to deserialize stored output item: {}. Item: {}",
-                                        e, item
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-                conversation_items = Some(items);
+            // Another tenant's chain is reported as missing.
+            Ok(chain)
+                if !chain.responses.is_empty()
+                    && chain
+                        .responses
+                        .iter()
+                        .all(|stored| tenant_owns_response(stored, tenant_meta.tenant_key())) =>
+            {
+                let history: Vec<Value> = chain
+                    .responses
+                    .iter()
+                    .flat_map(|stored| {
+                        // Stored input and raw_response["output"] are JSON arrays.
+                        let input = stored.input.as_array().into_iter().flatten();
+                        let output = stored
+                            .raw_response
+                            .get("output")
+                            .and_then(Value::as_array)
+                            .into_iter()
+                            .flatten();
+                        input.chain(output).cloned()
+                    })
+                    .collect();
+                let history = normalize_history(history)
+                    .map_err(|e| error::bad_request("invalid_history", e))?;
+                conversation_items = Some(deserialize_history(history));
                 modified_request.previous_response_id = None;
             }
             Ok(_) | Err(ResponseStorageError::ResponseNotFound(_)) => {
@@ -275,27 +369,14 @@ pub(super) async fn load_conversation_history(
             .await
         {
             Ok(stored_items) => {
-                let mut items: Vec<ResponseInputOutputItem> = Vec::new();
-                for item in stored_items {
-                    if item.item_type == "message" {
-                        // Stored content may be either the raw content array
-                        // (legacy) or an object `{content: [...], phase: ...}`
-                        // when the message carried a phase label (P3).
-                        let (content_value, stored_phase) =
-                            split_stored_message_content(item.content.clone());
-                        if let Ok(content_parts) =
-                            serde_json::from_value::<Vec<ResponseContentPart>>(content_value)
-                        {
-                            items.push(ResponseInputOutputItem::Message {
-                                id: item.id.0.clone(),
-                                role: item.role.clone().unwrap_or_else(|| "user".to_string()),
-                                content: content_parts,
-                                status: item.status.clone(),
-                                phase: stored_phase,
-                            });
-                        }
-                    }
-                }
+                let history: Vec<Value> = stored_items
+                    .iter()
+                    .filter(|item| REPLAYED_ITEM_TYPES.contains(&item.item_type.as_str()))
+                    .map(item_to_json)
+                    .collect();
+                let history = normalize_history(history)
+                    .map_err(|e| error::bad_request("invalid_history", e))?;
+                let mut items = deserialize_history(history);
 
                 // Append current request
                 match &modified_request.input {
@@ -424,5 +505,85 @@ pub(super) fn build_next_request(
         top_k: current_request.top_k,
         min_p: current_request.min_p,
         repetition_penalty: current_request.repetition_penalty,
+        seed: current_request.seed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::responses::{ResponsesToolChoice, ToolChoiceOptions};
+
+    use super::*;
+
+    fn response_with_calls(n: usize) -> ChatCompletionResponse {
+        let calls: Vec<serde_json::Value> = (0..n)
+            .map(|i| {
+                serde_json::json!({
+                    "id": format!("call_{i}"),
+                    "type": "function",
+                    "function": {"name": "search", "arguments": "{}"}
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "m",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "tool_calls": calls},
+                "finish_reason": "tool_calls"
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tool_call_limits_from_request() {
+        let limits = ToolCallLimits::new(&ResponsesRequest::default());
+        assert_eq!(limits.max_calls, DEFAULT_MAX_ITERATIONS);
+        assert!(limits.parallel);
+        assert!(!limits.required);
+
+        let limits = ToolCallLimits::new(&ResponsesRequest {
+            max_tool_calls: Some(2),
+            parallel_tool_calls: Some(false),
+            tool_choice: Some(ResponsesToolChoice::Options(ToolChoiceOptions::Required)),
+            ..Default::default()
+        });
+        assert_eq!(limits.max_calls, 2);
+        assert!(!limits.parallel);
+        assert!(limits.required);
+
+        let limits = ToolCallLimits::new(&ResponsesRequest {
+            max_tool_calls: Some(u32::MAX),
+            ..Default::default()
+        });
+        assert_eq!(limits.max_calls, DEFAULT_MAX_ITERATIONS);
+    }
+
+    #[test]
+    fn test_tool_call_limits_truncate() {
+        let limits = ToolCallLimits {
+            max_calls: 3,
+            parallel: false,
+            required: false,
+        };
+        let mut response = response_with_calls(3);
+        assert_eq!(limits.limit_turn(&mut response), 2);
+        assert_eq!(extract_all_tool_calls_from_chat(&response).len(), 1);
+
+        assert_eq!(limits.admit(0, 2), 2);
+        assert_eq!(limits.admit(2, 2), 1);
+        assert_eq!(limits.admit(3, 1), 0);
+
+        let parallel = ToolCallLimits {
+            parallel: true,
+            ..limits
+        };
+        let mut response = response_with_calls(3);
+        assert_eq!(parallel.limit_turn(&mut response), 0);
+        assert_eq!(extract_all_tool_calls_from_chat(&response).len(), 3);
     }
 }
@@ -30,6 +30,7 @@ use crate::routers::grpc::common::responses::utils::extract_tools_from_response_
 /// - `max_output_tokens` → `max_completion_tokens`
 /// - `tools` → function tools extracted from ResponseTools
 /// - `tool_choice` → passed through from request
+/// - `seed` → `seed`
 /// - Response-specific fields (previous_response_id, conversation) are handled by router
 pub(crate) fn responses_to_chat(req: &ResponsesRequest) -> Result<ChatCompletionRequest, String> {
     let mut messages = Vec::new();
@@ -146,6 +147,8 @@ pub(crate) fn responses_to_chat(req: &ResponsesRequest) -> Result<ChatCompletion
                     }
                     ResponseInputOutputItem::McpApprovalResponse { .. }
                     | ResponseInputOutputItem::McpApprovalRequest { .. }
+                    | ResponseInputOutputItem::McpElicitationRequest { .. }
+                    | ResponseInputOutputItem::McpElicitationResponse { .. }
                     | ResponseInputOutputItem::ComputerCall { .. }
                     | ResponseInputOutputItem::ComputerCallOutput { .. }
                     | ResponseInputOutputItem::McpCall { .. }
@@ -215,46 +218,48 @@ pub(crate) fn responses_to_chat(req: &ResponsesRequest) -> Result<ChatCompletion
         Some(function_tools)
     };
 
-    // 4. Build ChatCompletionRequest
+    // 4. Build ChatCompletionRequest. The Responses request was validated at
+    // its own boundary, and the translation deliberately carries fields the
+    // Chat validator would reject on their own (`top_logprobs` without
+    // `logprobs`), so skip re-validation.
     let is_streaming = req.stream.unwrap_or(false);
+    let model = if req.model.is_empty() {
+        UNKNOWN_MODEL_ID
+    } else {
+        req.model.as_str()
+    };
 
-    Ok(ChatCompletionRequest {
-        messages,
-        model: if req.model.is_empty() {
-            UNKNOWN_MODEL_ID.to_string()
-        } else {
-            req.model.clone()
-        },
-        temperature: req.temperature,
-        max_completion_tokens: req.max_output_tokens,
-        stream: is_streaming,
-        // Preserve caller-provided stream_options (e.g. `include_obfuscation: false`
-        // on the Responses API) and only default `include_usage` when the caller
-        // did not set it. Non-streaming requests intentionally drop stream_options.
-        stream_options: if is_streaming {
-            let mut opts = req.stream_options.clone().unwrap_or_default();
-            if opts.include_usage.is_none() {
-                opts.include_usage = Some(true);
-            }
-            Some(opts)
-        } else {
-            None
-        },
-        parallel_tool_calls: req.parallel_tool_calls,
-        top_logprobs: req.top_logprobs,
-        top_p: req.top_p,
-        skip_special_tokens: true,
-        tools,
-        tool_choice: req.tool_choice.as_ref().map(|tc| tc.to_chat_tool_choice()),
-        response_format: map_text_to_response_format(req.text.as_ref()),
-        reasoning_effort: req
-            .reasoning
-            .as_ref()
-            .and_then(|r| r.effort.as_ref())
-            .map(reasoning_effort_to_str)
-            .map(str::to_string),
-        ..Default::default()
-    })
+    let mut builder = ChatCompletionRequest::builder(model)
+        .messages(messages)
+        .maybe_temperature(req.temperature)
+        .maybe_max_completion_tokens(req.max_output_tokens)
+        .stream(is_streaming)
+        .maybe_parallel_tool_calls(req.parallel_tool_calls)
+        .maybe_top_logprobs(req.top_logprobs)
+        .maybe_top_p(req.top_p)
+        .maybe_seed(req.seed)
+        .maybe_tools(tools)
+        .maybe_tool_choice(req.tool_choice.as_ref().map(|tc| tc.to_chat_tool_choice()))
+        .maybe_response_format(map_text_to_response_format(req.text.as_ref()))
+        .maybe_reasoning_effort(
+            req.reasoning
+                .as_ref()
+                .and_then(|r| r.effort.as_ref())
+                .map(reasoning_effort_to_str),
+        );
+
+    // Preserve caller-provided stream_options (e.g. `include_obfuscation: false`
+    // on the Responses API) and only default `include_usage` when the caller
+    // did not set it. Non-streaming requests intentionally drop stream_options.
+    if is_streaming {
+        let mut opts = req.stream_options.clone().unwrap_or_default();
+        if opts.include_usage.is_none() {
+            opts.include_usage = Some(true);
+        }
+        builder = builder.stream_options(opts);
+    }
+
+    Ok(builder.build_unvalidated())
 }
 
 /// Map the Responses `reasoning.effort` enum to the Chat `reasoning_effort`
@@ -348,6 +353,7 @@ fn map_text_to_response_format(text: Option<&TextConfig>) -> Option<ResponseForm
 /// - `choices[0].message` → `output` array (convert to ResponseOutputItem::Message)
 /// - `choices[0].finish_reason` → determines `status` (stop/length → Completed)
 /// - `created` timestamp → `created_at`
+/// - `system_fingerprint` → `system_fingerprint` (with the request `seed`, enough to replay)
 pub(crate) fn chat_to_responses(
     chat_resp: &ChatCompletionResponse,
     original_req: &ResponsesRequest,
@@ -439,6 +445,7 @@ pub(crate) fn chat_to_responses(
         .output(output)
         .maybe_text(original_req.text.clone())
         .maybe_usage(usage)
+        .maybe_system_fingerprint(chat_resp.system_fingerprint.as_deref())
         .build())
 }
 
@@ -464,6 +471,19 @@ mod tests {
         assert_eq!(chat_req.temperature, Some(0.7));
     }
 
+    #[test]
+    fn test_seed_flows_through() {
+        let req = ResponsesRequest {
+            input: ResponseInput::Text("hi".to_string()),
+            model: "m".to_string(),
+            seed: Some(1234),
+            ..Default::default()
+        };
+
+        let chat_req = responses_to_chat(&req).unwrap();
+        assert_eq!(chat_req.effective_seed(), Some(1234));
+    }
+
     #[test]
     fn test_reasoning_effort_flows_through() {
         use openai_protocol::responses::ResponseReasoningParam;
@@ -99,10 +99,11 @@ async fn route_responses_streaming(
     params: ResponsesCallContext,
 ) -> Response {
     // 1. Load conversation history
-    let modified_request = match load_conversation_history(ctx, &request).await {
-        Ok(req) => req,
-        Err(response) => return response, // Already a Response with proper status code
-    };
+    let modified_request =
+        match load_conversation_history(ctx, &request, &params.tenant_request_meta).await {
+            Ok(req) => req,
+            Err(response) => return response, // Already a Response with proper status code
+        };
 
     // 2. Check MCP connection and get whether MCP tools are present
     let (has_mcp_tools, mcp_servers) = match ensure_mcp_connection(
@@ -17,4 +17,6 @@ mod non_streaming;
 mod streaming;
 
 // Public exports
+pub(crate) use conversions::{chat_to_responses, responses_to_chat};
 pub(crate) use handlers::route_responses;
+pub(crate) use streaming::accumulate_chat_stream;
@@ -16,8 +16,9 @@ use tracing::{debug, error, trace, warn};
 use super::{
     common::{
         build_next_request, convert_mcp_tools_to_chat_tools, extract_all_tool_calls_from_chat,
-        load_conversation_history, prepare_chat_tools_and_choice, ExtractedToolCall,
-        ResponsesCallContext, ToolLoopState,
+        load_conversation_history, max_tool_calls_error, prepare_chat_tools_and_choice,
+        required_tool_call_error, ExtractedToolCall, ResponsesCallContext, ToolCallLimits,
+        ToolLoopState,
     },
     conversions,
 };
@@ -31,7 +32,7 @@ use crate::{
         error,
         grpc::common::responses::{
             collect_user_function_names, ensure_mcp_connection, persist_response_if_needed,
-            ResponsesContext,
+            ResponsesContext, ToolOutputBudget,
         },
     },
 };
@@ -49,7 +50,8 @@ pub(super) async fn route_responses_internal(
     params: ResponsesCallContext,
 ) -> Result<ResponsesResponse, Response> {
     // 1. Load conversation history and build modified request
-    let modified_request = load_conversation_history(ctx, &request).await?;
+    let modified_request =
+        load_conversation_history(ctx, &request, &params.tenant_request_meta).await?;
 
     // 2. Check MCP connection and get whether MCP tools are present
     let (has_mcp_tools, mcp_servers) = ensure_mcp_connection(
@@ -59,6 +61,7 @@ pub(super) async fn route_responses_internal(
     )
     .await?;
 
+    let tenant_key = params.tenant_request_meta.tenant_key().clone();
     let responses_response = if has_mcp_tools {
         debug!("MCP tools detected, using tool loop");
 
@@ -76,6 +79,7 @@ pub(super) async fn route_responses_internal(
         ctx.response_storage.clone(),
         &responses_response,
         &request,
+        Some(&tenant_key),
         ctx.request_context.clone(),
     )
     .await;
@@ -104,7 +108,7 @@ pub(super) async fn execute_without_mcp(
     })?;
 
     // Execute chat pipeline (errors already have proper HTTP status codes)
-    let chat_response = ctx
+    let mut chat_response = ctx
         .pipeline
         .execute_chat_for_responses(
             Arc::new(chat_request),
@@ -115,6 +119,10 @@ pub(super) async fn execute_without_mcp(
         )
         .await?; // Preserve the Response error as-is
 
+    // Function calls go back to the caller, so parallel_tool_calls=false only
+    // needs the turn cut to its first call.
+    ToolCallLimits::new(original_request).limit_turn(&mut chat_response);
+
     // Convert ChatCompletionResponse → ResponsesResponse
     conversions::chat_to_responses(&chat_response, original_request, params.response_id).map_err(
         |e| {
@@ -146,13 +154,11 @@ pub(super) async fn execute_tool_loop(
     mcp_servers: Vec<McpServerBinding>,
 ) -> Result<ResponsesResponse, Response> {
     let mut state = ToolLoopState::new(original_request.input.clone());
-
-    // Configuration: max iterations as safety limit
-    let max_tool_calls = original_request.max_tool_calls.map(|n| n as usize);
+    let limits = ToolCallLimits::new(original_request);
 
     trace!(
         "Starting MCP tool loop: max_tool_calls={:?}, max_iterations={}",
-        max_tool_calls,
+        original_request.max_tool_calls,
         DEFAULT_MAX_ITERATIONS
     );
 
@@ -164,6 +170,10 @@ pub(super) async fn execute_tool_loop(
 
     let session = McpToolSession::new(&ctx.mcp_orchestrator, mcp_servers, &session_request_id);
     let user_function_names = collect_user_function_names(original_request);
+    let mut output_budget = ToolOutputBudget::new(
+        &session,
+        ctx.components.tokenizer_registry.get(&params.model_id),
+    );
 
     // Get MCP tools and convert to chat format (do this once before loop)
     let mcp_chat_tools = convert_mcp_tools_to_chat_tools(&session);
@@ -191,7 +201,7 @@ pub(super) async fn execute_tool_loop(
         prepare_chat_tools_and_choice(&mut chat_request, &mcp_chat_tools, state.iteration);
 
         // Execute chat pipeline (errors already have proper HTTP status codes)
-        let chat_response = ctx
+        let mut chat_response = ctx
             .pipeline
             .execute_chat_for_responses(
                 Arc::new(chat_request),
@@ -202,6 +212,11 @@ pub(super) async fn execute_tool_loop(
             )
             .await?;
 
+        let dropped = limits.limit_turn(&mut chat_response);
+        if dropped > 0 {
+            warn!("parallel_tool_calls=false: dropped {dropped} extra tool call(s) of one turn");
+        }
+
         // Check for function calls (extract all for parallel execution)
         let tool_calls = extract_all_tool_calls_from_chat(&chat_response);
 
@@ -249,6 +264,14 @@ pub(super) async fn execute_tool_loop(
                 );
             }
 
+            // The backend may not honor a required tool_choice; a first turn
+            // without a call is a failed run rather than a plain answer.
+            if state.iteration == 0 && limits.required {
+                warn!("tool_choice required a tool call, but the model made none");
+                responses_response.status = ResponseStatus::Failed;
+                responses_response.error = Some(required_tool_call_error());
+            }
+
             return Ok(responses_response);
         } else {
             state.iteration += 1;
@@ -263,7 +286,7 @@ pub(super) async fn execute_tool_loop(
             );
 
             // Separate MCP and function tool calls using session-exposed names.
-            let (mcp_tool_calls, function_tool_calls): (Vec<ExtractedToolCall>, Vec<_>) =
+            let (mut mcp_tool_calls, function_tool_calls): (Vec<ExtractedToolCall>, Vec<_>) =
                 tool_calls
                     .into_iter()
                     .partition(|tc| session.has_exposed_tool(tc.name.as_str()));
@@ -300,23 +323,25 @@ pub(super) async fn execute_tool_loop(
                 return Ok(responses_response);
             }
 
-            // All MCP tools - check combined limit BEFORE executing
-            let effective_limit = match max_tool_calls {
-                Some(user_max) => user_max.min(DEFAULT_MAX_ITERATIONS),
-                None => DEFAULT_MAX_ITERATIONS,
-            };
+            // All MCP tools - check combined limit BEFORE executing. Calls
+            // beyond the remaining budget are dropped; once nothing fits the
+            // run stops.
+            let admitted = limits.admit(state.total_calls, mcp_tool_calls.len());
 
-            if state.total_calls + mcp_tool_calls.len() > effective_limit {
+            if admitted < mcp_tool_calls.len() {
                 warn!(
                     "Reached tool call limit: {} + {} > {} (max_tool_calls={:?}, safety_limit={})",
                     state.total_calls,
                     mcp_tool_calls.len(),
-                    effective_limit,
-                    max_tool_calls,
+                    limits.max_calls,
+                    original_request.max_tool_calls,
                     DEFAULT_MAX_ITERATIONS
                 );
+                mcp_tool_calls.truncate(admitted);
+            }
 
-                // Convert chat response to responses format and mark as incomplete
+            if mcp_tool_calls.is_empty() {
+                // Convert chat response to responses format and mark as failed
                 let mut responses_response = conversions::chat_to_responses(
                     &chat_response,
                     original_request,
@@ -342,12 +367,7 @@ pub(super) async fn execute_tool_loop(
                 // `error` payload (truncation `incomplete_details` is reserved
                 // for `max_output_tokens` / `content_filter`).
                 responses_response.status = ResponseStatus::Failed;
-                responses_response.error = Some(json!({
-                    "code": "max_tool_calls_exceeded",
-                    "message": format!(
-                        "Reached the max_tool_calls limit ({effective_limit}) before executing the remaining tool calls."
-                    ),
-                }));
+                responses_response.error = Some(max_tool_calls_error(limits.max_calls));
 
                 return Ok(responses_response);
             }
@@ -429,7 +449,7 @@ pub(super) async fn execute_tool_loop(
                 );
 
                 let output_item = openai_bridge::transform_tool_output(&result, response_format);
-                let output_str = result.output.to_string();
+                let output_str = output_budget.fit(&result.tool_name, result.output.to_string());
                 state.record_call(
                     result.call_id,
                     result.tool_name,
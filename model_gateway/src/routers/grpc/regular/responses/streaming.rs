@@ -43,7 +43,8 @@ use uuid::Uuid;
 use super::{
     common::{
         build_next_request, convert_mcp_tools_to_chat_tools, extract_all_tool_calls_from_chat,
-        prepare_chat_tools_and_choice, ExtractedToolCall, ResponsesCallContext, ToolLoopState,
+        max_tool_calls_error, prepare_chat_tools_and_choice, required_tool_call_error,
+        ExtractedToolCall, ResponsesCallContext, ToolCallLimits, ToolLoopState,
     },
     conversions,
 };
@@ -58,11 +59,12 @@ use crate::{
             common::responses::{
                 build_sse_response, persist_response_if_needed,
                 streaming::{attach_mcp_server_label, OutputItemKind, ResponseStreamEventEmitter},
-                ResponsesContext,
+                ResponsesContext, ToolOutputBudget,
             },
             utils,
         },
     },
+    tenant::TenantKey,
 };
 
 // ============================================================================
@@ -84,6 +86,7 @@ pub(super) async fn convert_chat_stream_to_responses_stream(
     original_request: &ResponsesRequest,
 ) -> Response {
     debug!("Converting chat SSE stream to responses SSE format");
+    let tenant_key = params.tenant_request_meta.tenant_key().clone();
 
     // Get chat streaming response
     let chat_response = ctx
@@ -121,6 +124,7 @@ pub(super) async fn convert_chat_stream_to_responses_stream(
             response_storage,
             conversation_storage,
             conversation_item_storage,
+            tenant_key,
             request_context,
             tx.clone(),
         )
@@ -139,12 +143,14 @@ pub(super) async fn convert_chat_stream_to_responses_stream(
 }
 
 /// Process chat SSE stream and transform to responses format
+#[expect(clippy::too_many_arguments)]
 async fn process_and_transform_sse_stream(
     body: Body,
     original_request: ResponsesRequest,
     response_storage: Arc<dyn ResponseStorage>,
     conversation_storage: Arc<dyn ConversationStorage>,
     conversation_item_storage: Arc<dyn ConversationItemStorage>,
+    tenant_key: TenantKey,
     request_context: Option<StorageRequestContext>,
     tx: mpsc::UnboundedSender<Result<Bytes, std::io::Error>>,
 ) -> Result<(), String> {
@@ -239,6 +245,7 @@ async fn process_and_transform_sse_stream(
         response_storage,
         &final_response,
         &original_request,
+        Some(&tenant_key),
         request_context,
     )
     .await;
@@ -246,12 +253,26 @@ async fn process_and_transform_sse_stream(
     Ok(())
 }
 
+/// Fold a chat completion stream into the final Responses API response, as
+/// the non-MCP streaming path does before persisting it.
+pub(crate) fn accumulate_chat_stream(
+    original_request: &ResponsesRequest,
+    chunks: &[ChatCompletionStreamResponse],
+) -> ResponsesResponse {
+    let mut accumulator = StreamingResponseAccumulator::new(original_request);
+    for chunk in chunks {
+        accumulator.process_chunk(chunk);
+    }
+    accumulator.finalize()
+}
+
 /// Response accumulator for streaming responses (non-MCP path)
 struct StreamingResponseAccumulator {
     // Response metadata
     response_id: String,
     model: String,
     created_at: i64,
+    system_fingerprint: Option<String>,
 
     // Accumulated content
     content_buffer: String,
@@ -272,6 +293,7 @@ impl StreamingResponseAccumulator {
             response_id: String::new(),
             model: String::new(),
             created_at: 0,
+            system_fingerprint: None,
             content_buffer: String::new(),
             reasoning_buffer: String::new(),
             tool_calls: Vec::new(),
@@ -288,6 +310,10 @@ impl StreamingResponseAccumulator {
             self.model.clone_from(&chunk.model);
             self.created_at = chunk.created as i64;
         }
+        if self.system_fingerprint.is_none() {
+            self.system_fingerprint
+                .clone_from(&chunk.system_fingerprint);
+        }
 
         // Process first choice (responses API doesn't support n>1)
         if let Some(choice) = chunk.choices.first() {
@@ -418,6 +444,7 @@ impl StreamingResponseAccumulator {
             .status(status)
             .output(output)
             .maybe_usage(usage)
+            .maybe_system_fingerprint(self.system_fingerprint)
             .build()
     }
 }
@@ -509,13 +536,17 @@ async fn execute_tool_loop_streaming_internal(
     tx: mpsc::UnboundedSender<Result<Bytes, std::io::Error>>,
 ) -> Result<(), String> {
     let mut state = ToolLoopState::new(original_request.input.clone());
-    let max_tool_calls = original_request.max_tool_calls.map(|n| n as usize);
+    let limits = ToolCallLimits::new(original_request);
 
     // Generate response ID first so we can use it for both emitter and session
     let response_id = format!("resp_{}", Uuid::now_v7());
 
     // Create session once — bundles orchestrator, request_ctx, server_keys, mcp_tools
     let session = McpToolSession::new(&ctx.mcp_orchestrator, mcp_servers, &response_id);
+    let mut output_budget = ToolOutputBudget::new(
+        &session,
+        ctx.components.tokenizer_registry.get(&params.model_id),
+    );
 
     // Create response event emitter
     let created_at = SystemTime::now()
@@ -570,8 +601,9 @@ async fn execute_tool_loop_streaming_internal(
         let mut chat_request = conversions::responses_to_chat(&current_request)
             .map_err(|e| format!("Failed to convert request: {e}"))?;
 
-        // Prepare tools and tool_choice for this iteration (same logic as non-streaming)
-        prepare_chat_tools_and_choice(&mut chat_request, &mcp_chat_tools, state.iteration);
+        // Prepare tools and tool_choice for this iteration (same logic as
+        // non-streaming, which counts iterations from 0)
+        prepare_chat_tools_and_choice(&mut chat_request, &mcp_chat_tools, state.iteration - 1);
 
         // Execute chat streaming
         let response = ctx
@@ -587,8 +619,19 @@ async fn execute_tool_loop_streaming_internal(
 
         // Convert chat stream to Responses API events while accumulating for tool call detection
         // Stream text naturally - it only appears on final iteration (tool iterations have empty content)
-        let accumulated_response =
+        let mut accumulated_response =
             convert_and_accumulate_stream(response.into_body(), &mut emitter, &tx).await?;
+        let dropped = limits.limit_turn(&mut accumulated_response);
+        if dropped > 0 {
+            warn!("parallel_tool_calls=false: dropped {dropped} extra tool call(s) of one turn");
+        }
+        let usage_json = accumulated_response.usage.as_ref().map(|u| {
+            json!({
+                "input_tokens": u.prompt_tokens,
+                "output_tokens": u.completion_tokens,
+                "total_tokens": u.total_tokens
+            })
+        });
 
         // Check for tool calls (extract all of them for parallel execution)
         let tool_calls = extract_all_tool_calls_from_chat(&accumulated_response);
@@ -601,7 +644,7 @@ async fn execute_tool_loop_streaming_internal(
             );
 
             // Separate MCP and function tool calls using session-exposed names.
-            let (mcp_tool_calls, function_tool_calls): (Vec<ExtractedToolCall>, Vec<_>) =
+            let (mut mcp_tool_calls, function_tool_calls): (Vec<ExtractedToolCall>, Vec<_>) =
                 tool_calls
                     .into_iter()
                     .partition(|tc| session.has_exposed_tool(tc.name.as_str()));
@@ -612,22 +655,25 @@ async fn execute_tool_loop_streaming_internal(
                 function_tool_calls.len()
             );
 
-            // Check combined limit (only count MCP tools since function tools will be returned)
-            let effective_limit = match max_tool_calls {
-                Some(user_max) => user_max.min(DEFAULT_MAX_ITERATIONS),
-                None => DEFAULT_MAX_ITERATIONS,
-            };
-
-            if state.total_calls + mcp_tool_calls.len() > effective_limit {
+            // Check combined limit (only count MCP tools since function tools will be returned).
+            // Calls beyond the remaining budget are dropped; once nothing fits the run fails.
+            let admitted = limits.admit(state.total_calls, mcp_tool_calls.len());
+            if admitted < mcp_tool_calls.len() {
                 warn!(
                     "Reached tool call limit: {} + {} > {} (max_tool_calls={:?}, safety_limit={})",
                     state.total_calls,
                     mcp_tool_calls.len(),
-                    effective_limit,
-                    max_tool_calls,
+                    limits.max_calls,
+                    original_request.max_tool_calls,
                     DEFAULT_MAX_ITERATIONS
                 );
-                break;
+                mcp_tool_calls.truncate(admitted);
+                if mcp_tool_calls.is_empty() && function_tool_calls.is_empty() {
+                    let event = emitter
+                        .emit_failed(max_tool_calls_error(limits.max_calls), usage_json.as_ref());
+                    emitter.send_event(&event, &tx)?;
+                    break;
+                }
             }
 
             // Process each MCP tool call
@@ -814,6 +860,7 @@ async fn execute_tool_loop_streaming_internal(
                     },
                 );
 
+                let output_str = output_budget.fit(&tool_output.tool_name, output_str);
                 state.record_call(
                     tool_output.call_id,
                     tool_output.tool_name,
@@ -913,15 +960,15 @@ async fn execute_tool_loop_streaming_internal(
         // Text message events already emitted naturally by process_chunk during stream processing
         // (OpenAI router approach - text only appears on final iteration when no tool calls)
 
-        // Emit final response.completed event
-        let usage_json = accumulated_response.usage.as_ref().map(|u| {
-            json!({
-                "input_tokens": u.prompt_tokens,
-                "output_tokens": u.completion_tokens,
-                "total_tokens": u.total_tokens
-            })
-        });
-        let event = emitter.emit_completed(usage_json.as_ref());
+        // The backend may not honor a required tool_choice; a first turn
+        // without a call fails the run instead of completing it.
+        let event = if state.iteration == 1 && limits.required {
+            warn!("tool_choice required a tool call, but the model made none");
+            emitter.emit_failed(required_tool_call_error(), usage_json.as_ref())
+        } else {
+            // Emit final response.completed event
+            emitter.emit_completed(usage_json.as_ref())
+        };
         emitter.send_event(&event, &tx)?;
 
         break;
@@ -4,6 +4,7 @@
 //! They work with any model type by using injected model adapters.
 
 mod preparation;
+mod prompt_breakdown;
 mod request_building;
 mod response_processing;
 
@@ -6,14 +6,18 @@ use openai_protocol::{
     chat::ChatCompletionRequest,
     common::{ToolChoice, ToolChoiceValue},
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
-use crate::routers::{
-    error,
-    grpc::{
-        common::stages::PipelineStage,
-        context::{PreparationOutput, RequestContext},
-        multimodal, utils,
+use super::prompt_breakdown;
+use crate::{
+    middleware::request_features,
+    routers::{
+        error,
+        grpc::{
+            common::stages::PipelineStage,
+            context::{PreparationOutput, RequestContext},
+            multimodal, utils,
+        },
     },
 };
 
@@ -170,6 +174,7 @@ impl ChatPreparationStage {
         };
 
         let mut token_ids = encoding.token_ids().to_vec();
+        let rendered_tokens = token_ids.len();
 
         if let (Some(placeholders), Some((_, _, _, _, media_plan))) =
             (placeholder_tokens.as_ref(), mm_context.as_ref())
@@ -195,6 +200,10 @@ impl ChatPreparationStage {
         if let Some((mm_components, model_id, tokenizer_id, tokenizer_source, media_plan)) =
             mm_context
         {
+            let tenant_key = ctx
+                .tenant_request_meta
+                .as_ref()
+                .map(|meta| meta.tenant_key());
             match multimodal::process_multimodal_plan(
                 media_plan,
                 model_id,
@@ -203,6 +212,7 @@ impl ChatPreparationStage {
                 mm_components,
                 &tokenizer_id,
                 &tokenizer_source,
+                tenant_key,
             )
             .await
             {
@@ -216,6 +226,14 @@ impl ChatPreparationStage {
                     multimodal_intermediate = Some(output.intermediate);
                 }
                 Err(e) => {
+                    if let Some(safety_error) = e.downcast_ref::<multimodal::ImageSafetyError>() {
+                        warn!(
+                            function = "ChatPreparationStage::execute",
+                            error = %safety_error,
+                            "Multimodal request stopped by image safety screening"
+                        );
+                        return Err(safety_error.to_response());
+                    }
                     error!(
                         function = "ChatPreparationStage::execute",
                         error = %e,
@@ -229,6 +247,22 @@ impl ChatPreparationStage {
             }
         }
 
+        // Opt-in per request: counting the sections tokenizes the prompt again.
+        // A failure only costs the caller the breakdown.
+        if request_features::feature_enabled(
+            ctx.input.headers.as_ref(),
+            request_features::FEATURE_USAGE_BREAKDOWN,
+        ) {
+            match prompt_breakdown::compute(&tokenizer, &body_ref, rendered_tokens, token_ids.len())
+                .await
+            {
+                Ok(breakdown) => ctx.state.response.prompt_breakdown = Some(breakdown),
+                Err(e) => {
+                    warn!(function = "ChatPreparationStage::execute", error = %e, "Prompt token breakdown failed");
+                }
+            }
+        }
+
         // Step 4: Build tool constraints if needed
         // The tool parser registry handles both structural tag (for native format
         // parsers like Mistral, KimiK2) and generic JSON schema fallback.
@@ -250,6 +284,14 @@ impl ChatPreparationStage {
                         format!("Invalid tool configuration: {e}"),
                     )
                 })?
+                // `parallel_tool_calls: false` caps a required-calls schema at one call.
+                .map(|constraint| {
+                    if request.parallel_tool_calls == Some(false) {
+                        constraint.single_call()
+                    } else {
+                        constraint
+                    }
+                })
         } else {
             None
         };
@@ -0,0 +1,228 @@
+//! Prompt token breakdown for the `usage-breakdown` request feature.
+//!
+//! The prompt is split into the sections callers budget for: system and
+//! developer messages, tool definitions, the history up to the last
+//! assistant turn and the current turn after it. Each section is tokenized
+//! on its own; image tokens are what multimodal expansion added to the
+//! rendered prompt, and the template markup gets the remainder.
+
+use std::sync::Arc;
+
+use llm_tokenizer::traits::Tokenizer;
+use openai_protocol::{
+    chat::{ChatCompletionRequest, ChatMessage},
+    common::PromptTokenBreakdown,
+};
+
+use crate::routers::grpc::utils;
+
+/// Text of each budgeted prompt section.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Sections {
+    system: String,
+    tools: String,
+    history: String,
+    current_turn: String,
+}
+
+fn push_line(section: &mut String, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if !section.is_empty() {
+        section.push('\n');
+    }
+    section.push_str(text);
+}
+
+/// Text a conversation message contributes to its section.
+fn message_text(message: &ChatMessage) -> String {
+    match message {
+        ChatMessage::User { content, .. } | ChatMessage::Tool { content, .. } => {
+            content.to_simple_string()
+        }
+        ChatMessage::Assistant {
+            content,
+            tool_calls,
+            reasoning_content,
+            ..
+        } => {
+            let mut text = String::new();
+            if let Some(reasoning) = reasoning_content {
+                push_line(&mut text, reasoning);
+            }
+            if let Some(content) = content {
+                push_line(&mut text, &content.to_simple_string());
+            }
+            for call in tool_calls.iter().flatten() {
+                push_line(&mut text, &call.function.name);
+                push_line(&mut text, call.function.arguments.as_deref().unwrap_or(""));
+            }
+            text
+        }
+        ChatMessage::Function { content, .. } => content.clone(),
+        ChatMessage::System { .. } | ChatMessage::Developer { .. } => String::new(),
+    }
+}
+
+/// Split `request` (already filtered by `tool_choice`) into its sections.
+fn sections(request: &ChatCompletionRequest) -> Sections {
+    let mut sections = Sections::default();
+    if let Some(tools) = &request.tools {
+        push_line(
+            &mut sections.tools,
+            &serde_json::to_string(tools).unwrap_or_default(),
+        );
+    }
+
+    let current_start = request
+        .messages
+        .iter()
+        .rposition(|m| matches!(m, ChatMessage::Assistant { .. }))
+        .map_or(0, |i| i + 1);
+    for (i, message) in request.messages.iter().enumerate() {
+        match message {
+            ChatMessage::System { content, .. } => {
+                push_line(&mut sections.system, &content.to_simple_string());
+            }
+            ChatMessage::Developer { content, tools, .. } => {
+                push_line(&mut sections.system, &content.to_simple_string());
+                if let Some(tools) = tools {
+                    push_line(
+                        &mut sections.tools,
+                        &serde_json::to_string(tools).unwrap_or_default(),
+                    );
+                }
+            }
+            message if i < current_start => {
+                push_line(&mut sections.history, &message_text(message));
+            }
+            message => push_line(&mut sections.current_turn, &message_text(message)),
+        }
+    }
+    sections
+}
+
+fn to_u32(count: usize) -> u32 {
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+/// Fill in `images` and `template` from the prompt's token counts before
+/// (`rendered`) and after (`prompt`) multimodal expansion.
+fn assemble(
+    mut breakdown: PromptTokenBreakdown,
+    rendered: usize,
+    prompt: usize,
+) -> PromptTokenBreakdown {
+    breakdown.images = to_u32(prompt.saturating_sub(rendered));
+    let attributed = [
+        breakdown.system,
+        breakdown.tools,
+        breakdown.history,
+        breakdown.current_turn,
+        breakdown.images,
+    ]
+    .iter()
+    .fold(0u32, |sum, n| sum.saturating_add(*n));
+    breakdown.template = to_u32(prompt).saturating_sub(attributed);
+    breakdown
+}
+
+async fn count(tokenizer: &Arc<dyn Tokenizer>, text: String) -> anyhow::Result<u32> {
+    if text.is_empty() {
+        return Ok(0);
+    }
+    let encoding = utils::encode_blocking(tokenizer.clone(), text, false).await?;
+    Ok(to_u32(encoding.token_ids().len()))
+}
+
+/// Break the prompt of `request` down by section. `rendered_tokens` is the
+/// length of the tokenized chat template output and `prompt_tokens` the
+/// length after multimodal expansion.
+pub(super) async fn compute(
+    tokenizer: &Arc<dyn Tokenizer>,
+    request: &ChatCompletionRequest,
+    rendered_tokens: usize,
+    prompt_tokens: usize,
+) -> anyhow::Result<PromptTokenBreakdown> {
+    let sections = sections(request);
+    let breakdown = PromptTokenBreakdown {
+        system: count(tokenizer, sections.system).await?,
+        tools: count(tokenizer, sections.tools).await?,
+        history: count(tokenizer, sections.history).await?,
+        current_turn: count(tokenizer, sections.current_turn).await?,
+        ..Default::default()
+    };
+    Ok(assemble(breakdown, rendered_tokens, prompt_tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(body: serde_json::Value) -> ChatCompletionRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn sections_split_history_at_last_assistant_turn() {
+        let request = request(json!({
+            "model": "m",
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Weather?"},
+                {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1", "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}
+                }]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "18C"},
+                {"role": "user", "content": [{"type": "text", "text": "And tomorrow?"}]}
+            ],
+            "tools": [{"type": "function", "function": {"name": "get_weather", "parameters": {}}}]
+        }));
+        let sections = sections(&request);
+        assert_eq!(sections.system, "Be brief.");
+        assert!(sections.tools.contains("\"get_weather\""));
+        assert_eq!(
+            sections.history,
+            "Weather?\nget_weather\n{\"city\":\"Paris\"}"
+        );
+        assert_eq!(sections.current_turn, "18C\nAnd tomorrow?");
+    }
+
+    #[test]
+    fn without_assistant_turn_everything_is_current() {
+        let request = request(json!({
+            "model": "m",
+            "messages": [
+                {"role": "developer", "content": "Rules."},
+                {"role": "user", "content": "Hi"}
+            ]
+        }));
+        let sections = sections(&request);
+        assert_eq!(sections.system, "Rules.");
+        assert_eq!(sections.history, "");
+        assert_eq!(sections.current_turn, "Hi");
+    }
+
+    #[test]
+    fn template_takes_the_remainder() {
+        let counted = PromptTokenBreakdown {
+            system: 10,
+            tools: 20,
+            history: 30,
+            current_turn: 5,
+            ..Default::default()
+        };
+        let breakdown = assemble(counted.clone(), 80, 580);
+        assert_eq!(breakdown.images, 500);
+        assert_eq!(breakdown.template, 15);
+
+        // Separately tokenized sections can overshoot the prompt.
+        let breakdown = assemble(counted, 60, 60);
+        assert_eq!(breakdown.images, 0);
+        assert_eq!(breakdown.template, 0);
+    }
+}
@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use axum::response::Response;
+use openai_protocol::chat::ChatCompletionRequest;
 use tracing::error;
 
 use crate::routers::{
@@ -124,10 +125,20 @@ impl PipelineStage for ChatRequestBuildingStage {
             )
         });
 
+        // Backends that ignore `n` get one single-choice request per choice.
+        let fan_out = helpers::fan_out_choices(builder_client, self.plan_kind, chat_request.n);
+        let single_choice_request = fan_out.map(|_| ChatCompletionRequest {
+            n: None,
+            ..(*chat_request).clone()
+        });
+        let body = single_choice_request
+            .as_ref()
+            .unwrap_or_else(|| chat_request.as_ref());
+
         let mut proto_request = builder_client
             .build_chat_request(
                 request_id,
-                &chat_request,
+                body,
                 processed_messages.text,
                 token_ids,
                 GenerateRequestBuildOptions {
@@ -149,7 +160,8 @@ impl PipelineStage for ChatRequestBuildingStage {
 
         if self.inject_pd_metadata {
             if let Some(workers) = ctx.state.workers.as_ref() {
-                helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                let room = helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                ctx.state.bootstrap_rooms.extend(room);
             }
         }
 
@@ -164,10 +176,14 @@ impl PipelineStage for ChatRequestBuildingStage {
         // in the request. Runs before execute_parallel_pd clones the request, so
         // both prefill and decode carry the same room.
         if let Some(workers) = ctx.state.workers.as_ref() {
-            helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            let room = helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            ctx.state.bootstrap_rooms.extend(room);
         }
 
-        ctx.state.execution_plan = Some(ExecutionPlan::generate(self.plan_kind, proto_request));
+        ctx.state.execution_plan = Some(match fan_out {
+            Some(n) => ExecutionPlan::fan_out(proto_request, n),
+            None => ExecutionPlan::generate(self.plan_kind, proto_request),
+        });
         Ok(None)
     }
 
@@ -1,11 +1,12 @@
 //! Response processing stage for classify requests.
 //!
 //! Key responsibilities:
-//! 1. Extract embedding (logits) from EmbedComplete response
+//! 1. Extract embedding (logits) from each EmbedComplete response (one per
+//!    input for batched requests)
 //! 2. Apply softmax to convert logits to probabilities
 //! 3. Find predicted class (argmax)
 //! 4. Map class index to label (from id2label or generic LABEL_N)
-//! 5. Build ClassifyResponse
+//! 5. Build ClassifyResponse with one entry per input
 
 use std::collections::HashMap;
 
@@ -87,6 +88,19 @@ impl ClassifyResponseProcessingStage {
             .unwrap_or_else(|| format!("LABEL_{class_idx}"))
     }
 
+    /// Classify one input: softmax the logits, take the argmax, and map it
+    /// to a label.
+    fn classify(index: u32, logits: &[f32], id2label: &HashMap<u32, String>) -> ClassifyData {
+        let probs = Self::softmax(logits);
+        let predicted_class = Self::argmax(&probs);
+        ClassifyData {
+            index,
+            label: Self::get_label(id2label, predicted_class),
+            num_classes: probs.len() as u32,
+            probs,
+        }
+    }
+
     /// Extract id2label mapping from the selected worker's model card.
     fn get_id2label_from_context(ctx: &RequestContext) -> HashMap<u32, String> {
         // Get the selected worker
@@ -125,53 +139,62 @@ impl PipelineStage for ClassifyResponseProcessingStage {
             error::internal_error("execution_result_missing", "Execution result missing")
         })?;
 
-        // Expect Embedding result variant (classify uses embed backend)
-        let proto_response = if let ExecutionResult::Embedding { response } = execution_result {
-            response
-        } else {
-            error!(
-                function = "ClassifyResponseProcessingStage::execute",
-                "Invalid execution result: expected Embedding"
-            );
-            return Err(error::internal_error(
-                "invalid_execution_result",
-                "Expected Embedding result for classify",
-            ));
+        // Classify uses the embed backend: a single Embedding result, or one
+        // per input for batched requests.
+        let responses = match execution_result {
+            ExecutionResult::Embedding { response } => vec![response],
+            ExecutionResult::Batch { results } => {
+                let mut responses = Vec::with_capacity(results.len());
+                for result in results {
+                    let ExecutionResult::Embedding { response } = result else {
+                        error!(
+                            function = "ClassifyResponseProcessingStage::execute",
+                            "Invalid batch item: expected Embedding"
+                        );
+                        return Err(error::internal_error(
+                            "invalid_execution_result",
+                            "Expected Embedding result for classify",
+                        ));
+                    };
+                    responses.push(response);
+                }
+                responses
+            }
+            _ => {
+                error!(
+                    function = "ClassifyResponseProcessingStage::execute",
+                    "Invalid execution result: expected Embedding"
+                );
+                return Err(error::internal_error(
+                    "invalid_execution_result",
+                    "Expected Embedding result for classify",
+                ));
+            }
         };
 
-        // Get logits from embedding response
-        let logits = proto_response.embedding();
-
-        if logits.is_empty() {
-            error!(
-                function = "ClassifyResponseProcessingStage::execute",
-                "Empty logits received from scheduler"
-            );
-            return Err(error::internal_error(
-                "empty_logits",
-                "Empty logits received from scheduler",
-            ));
-        }
-
         // Get id2label from the worker's model card
         let id2label = Self::get_id2label_from_context(ctx);
 
-        // Apply softmax to get probabilities
-        let probs = Self::softmax(logits);
-
-        // Get predicted class (argmax)
-        let predicted_class = Self::argmax(&probs);
-
-        // Get label for predicted class
-        let label = Self::get_label(&id2label, predicted_class);
-
-        // Build classify data
-        let classify_data = ClassifyData {
-            index: 0,
-            label,
-            probs: probs.clone(),
-            num_classes: probs.len() as u32,
-        };
+        let mut data = Vec::with_capacity(responses.len());
+        let mut prompt_tokens = 0;
+        for (index, proto_response) in responses.iter().enumerate() {
+            // Get logits from embedding response
+            let logits = proto_response.embedding();
+
+            if logits.is_empty() {
+                error!(
+                    function = "ClassifyResponseProcessingStage::execute",
+                    "Empty logits received from scheduler"
+                );
+                return Err(error::internal_error(
+                    "empty_logits",
+                    "Empty logits received from scheduler",
+                ));
+            }
+
+            data.push(Self::classify(index as u32, logits, &id2label));
+            prompt_tokens += proto_response.prompt_tokens();
+        }
 
         // Get dispatch metadata
         let dispatch = ctx.state.dispatch.as_ref().ok_or_else(|| {
@@ -183,7 +206,6 @@ impl PipelineStage for ClassifyResponseProcessingStage {
         })?;
 
         // Build usage info
-        let prompt_tokens = proto_response.prompt_tokens();
         let usage = UsageInfo {
             prompt_tokens,
             total_tokens: prompt_tokens,
@@ -197,7 +219,7 @@ impl PipelineStage for ClassifyResponseProcessingStage {
             dispatch.request_id.clone(),
             dispatch.model.clone(),
             dispatch.created,
-            vec![classify_data],
+            data,
             usage,
         );
 
@@ -284,6 +306,18 @@ mod tests {
         ); // Fallback for unknown
     }
 
+    #[test]
+    fn test_classify_sets_index_and_label() {
+        let mut id2label = HashMap::new();
+        id2label.insert(1, "positive".to_string());
+
+        let data = ClassifyResponseProcessingStage::classify(3, &[0.5, 2.0], &id2label);
+        assert_eq!(data.index, 3);
+        assert_eq!(data.label, "positive");
+        assert_eq!(data.num_classes, 2);
+        assert!(data.probs[1] > data.probs[0]);
+    }
+
     #[test]
     fn test_get_label_without_mapping() {
         let id2label = HashMap::new();
@@ -15,6 +15,7 @@ use tracing::error;
 use uuid::Uuid;
 
 use crate::routers::{
+    common::bootstrap_rooms::RoomLease,
     error,
     grpc::{
         client::GrpcClient,
@@ -42,11 +43,12 @@ impl CompletionRequestBuildingStage {
 
     /// Build one backend request for one prompt. PD bootstrap rooms are minted
     /// per call, so injection runs per sub-request rather than
-    /// build-once-then-clone.
+    /// build-once-then-clone; their leases are pushed onto `rooms`.
     #[expect(
         clippy::result_large_err,
         reason = "Response is the standard error type in the pipeline stage pattern"
     )]
+    #[expect(clippy::too_many_arguments)]
     fn build_proto_request(
         &self,
         builder_client: &GrpcClient,
@@ -55,6 +57,7 @@ impl CompletionRequestBuildingStage {
         completion_request: &CompletionRequest,
         request_type: &RequestType,
         workers: Option<&WorkerSelection>,
+        rooms: &mut Vec<RoomLease>,
     ) -> Result<ProtoGenerateRequest, Response> {
         let mut proto_request = builder_client
             .build_completion_request(
@@ -83,7 +86,10 @@ impl CompletionRequestBuildingStage {
 
         if self.inject_pd_metadata {
             if let Some(workers) = workers {
-                helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                rooms.extend(helpers::maybe_inject_pd_metadata(
+                    &mut proto_request,
+                    workers,
+                ));
             }
         }
 
@@ -91,7 +97,10 @@ impl CompletionRequestBuildingStage {
         // text-only (no encode jobs), so this is the only EPD injection here.
         // No-op unless the backend carries it in the request.
         if let Some(workers) = workers {
-            helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            rooms.extend(helpers::maybe_inject_pd_rendezvous(
+                &mut proto_request,
+                workers,
+            ));
         }
 
         Ok(proto_request)
@@ -141,6 +150,7 @@ impl PipelineStage for CompletionRequestBuildingStage {
         let disaggregated = matches!(clients, ClientSelection::Disaggregated { .. });
         let request_type = &ctx.input.request_type;
         let workers = ctx.state.workers.as_ref();
+        let mut rooms = Vec::new();
 
         let plan = match items.as_slice() {
             [] => {
@@ -149,22 +159,49 @@ impl PipelineStage for CompletionRequestBuildingStage {
                     "No prompts prepared",
                 ))
             }
-            [item] => ExecutionPlan::generate(
-                self.plan_kind,
-                self.build_proto_request(
-                    builder_client,
-                    helpers::resolve_request_id(
-                        request_type,
-                        ctx.input.tenant_request_meta.as_ref(),
-                        "cmpl_",
-                        disaggregated,
-                    ),
-                    item,
-                    &completion_request,
+            [item] => {
+                let request_id = helpers::resolve_request_id(
                     request_type,
-                    workers,
-                )?,
-            ),
+                    ctx.input.tenant_request_meta.as_ref(),
+                    "cmpl_",
+                    disaggregated,
+                );
+                // Backends that ignore `n` get one single-choice request per
+                // choice. Batched prompts keep the backend's own `n` handling.
+                match helpers::fan_out_choices(builder_client, self.plan_kind, completion_request.n)
+                {
+                    Some(n) => {
+                        let single_choice_request = CompletionRequest {
+                            n: None,
+                            ..(*completion_request).clone()
+                        };
+                        ExecutionPlan::fan_out(
+                            self.build_proto_request(
+                                builder_client,
+                                request_id,
+                                item,
+                                &single_choice_request,
+                                request_type,
+                                workers,
+                                &mut rooms,
+                            )?,
+                            n,
+                        )
+                    }
+                    None => ExecutionPlan::generate(
+                        self.plan_kind,
+                        self.build_proto_request(
+                            builder_client,
+                            request_id,
+                            item,
+                            &completion_request,
+                            request_type,
+                            workers,
+                            &mut rooms,
+                        )?,
+                    ),
+                }
+            }
             batch_items => {
                 // The shared id (client rid or middleware request id) stays
                 // clean for the response; per-sub engine ids get a uniqueness
@@ -193,6 +230,7 @@ impl PipelineStage for CompletionRequestBuildingStage {
                         &completion_request,
                         request_type,
                         workers,
+                        &mut rooms,
                     )?);
                 }
                 ExecutionPlan::Batch {
@@ -203,6 +241,7 @@ impl PipelineStage for CompletionRequestBuildingStage {
             }
         };
 
+        ctx.state.bootstrap_rooms = rooms;
         ctx.state.execution_plan = Some(plan);
         Ok(None)
     }
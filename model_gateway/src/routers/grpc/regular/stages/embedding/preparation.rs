@@ -2,14 +2,16 @@
 
 use async_trait::async_trait;
 use axum::response::Response;
+use futures::future::try_join_all;
 use openai_protocol::common::GenerationRequest;
+use serde_json::Value;
 use tracing::error;
 
 use crate::routers::{
     error,
     grpc::{
         common::stages::PipelineStage,
-        context::{PreparationOutput, RequestContext, RequestType},
+        context::{CompletionItem, PreparationOutput, RequestContext, RequestType},
         utils,
     },
 };
@@ -25,6 +27,19 @@ impl EmbeddingPreparationStage {
 #[async_trait]
 impl PipelineStage for EmbeddingPreparationStage {
     async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        // Classify batches are scored one input per embed request.
+        if let RequestType::Classify(req) = &ctx.input.request_type {
+            if let Value::Array(inputs) = &req.input {
+                let texts: Option<Vec<String>> = inputs
+                    .iter()
+                    .map(|v| v.as_str().map(String::from))
+                    .collect();
+                if let Some(texts) = texts.filter(|t| t.len() > 1) {
+                    return Self::prepare_batch(ctx, texts).await;
+                }
+            }
+        }
+
         // Extract text from embedding or classify request (both use same preparation)
         let text = match &ctx.input.request_type {
             RequestType::Embedding(req) => req.extract_text_for_routing(),
@@ -79,3 +94,52 @@ impl PipelineStage for EmbeddingPreparationStage {
         "EmbeddingPreparation"
     }
 }
+
+impl EmbeddingPreparationStage {
+    async fn prepare_batch(
+        ctx: &mut RequestContext,
+        texts: Vec<String>,
+    ) -> Result<Option<Response>, Response> {
+        if texts.iter().any(String::is_empty) {
+            return Err(error::bad_request(
+                "empty_input",
+                "Input text cannot be empty",
+            ));
+        }
+
+        let tokenizer = utils::resolve_tokenizer(ctx, "EmbeddingPreparationStage::prepare_batch")
+            .map_err(|e| *e)?;
+
+        let encodings = try_join_all(
+            texts
+                .iter()
+                .map(|text| utils::encode_blocking(tokenizer.clone(), text.clone(), true)),
+        )
+        .await
+        .map_err(|e| {
+            error!(
+                function = "EmbeddingPreparationStage::prepare_batch",
+                error = %e,
+                "Tokenization failed"
+            );
+            error::bad_request("tokenization_failed", format!("Tokenization failed: {e}"))
+        })?;
+
+        let joined_routing_text = texts.join(" ");
+        let items = texts
+            .into_iter()
+            .zip(encodings)
+            .map(|(text, encoding)| CompletionItem {
+                text,
+                token_ids: encoding.token_ids().to_vec(),
+            })
+            .collect();
+
+        ctx.state.preparation = Some(PreparationOutput::EmbeddingBatch {
+            items,
+            joined_routing_text,
+        });
+
+        Ok(None)
+    }
+}
@@ -9,7 +9,7 @@ use crate::routers::{
     grpc::{
         client::GrpcClient,
         common::stages::{helpers, PipelineStage},
-        context::{ExecutionPlan, RequestContext, RequestType},
+        context::{ExecutionPlan, PreparationOutput, RequestContext, RequestType},
         proto_wrapper::ProtoEmbedRequest,
     },
 };
@@ -67,52 +67,32 @@ impl PipelineStage for EmbeddingRequestBuildingStage {
             false,
         );
 
-        // Build backend-specific embed request
-        let original_text = prep_output.routing_text().map(String::from);
-        let token_ids = prep_output.token_ids().to_vec();
-
-        let proto_req = match client {
-            GrpcClient::Sglang(c) => {
-                let req = c.build_embed_request(request_id.clone(), original_text, token_ids);
-                ProtoEmbedRequest::Sglang(Box::new(req))
-            }
-            GrpcClient::Vllm(c) => {
-                let req = c.build_embed_request(request_id.clone(), original_text, token_ids);
-                ProtoEmbedRequest::Vllm(Box::new(req))
-            }
-            GrpcClient::Trtllm(_) => {
-                error!(
-                    function = "EmbeddingRequestBuildingStage::execute",
-                    "TensorRT-LLM embedding not yet supported"
-                );
-                return Err(error::not_implemented(
-                    "unsupported_backend",
-                    "TensorRT-LLM embedding is not yet supported via gRPC",
-                ));
-            }
-            GrpcClient::Mlx(_) => {
-                error!(
-                    function = "EmbeddingRequestBuildingStage::execute",
-                    "MLX embedding not supported"
-                );
-                return Err(error::not_implemented(
-                    "unsupported_backend",
-                    "MLX embedding is not supported via gRPC",
-                ));
-            }
-            GrpcClient::TokenSpeed(_) => {
-                error!(
-                    function = "EmbeddingRequestBuildingStage::execute",
-                    "TokenSpeed backend does not support embeddings"
-                );
-                return Err(error::not_implemented(
-                    "unsupported_backend",
-                    "TokenSpeed backend does not support embeddings",
-                ));
-            }
+        let plan = match prep_output {
+            // Batched classify: one embed request per input, `{id}-i{i}`.
+            PreparationOutput::EmbeddingBatch { items, .. } => ExecutionPlan::EmbedBatch {
+                requests: items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        Self::build_request(
+                            client,
+                            format!("{request_id}-i{i}"),
+                            Some(item.text.clone()),
+                            item.token_ids.clone(),
+                        )
+                    })
+                    .collect::<Result<_, _>>()?,
+                shared_request_id: request_id,
+            },
+            _ => ExecutionPlan::embed(Self::build_request(
+                client,
+                request_id,
+                prep_output.routing_text().map(String::from),
+                prep_output.token_ids().to_vec(),
+            )?),
         };
 
-        ctx.state.execution_plan = Some(ExecutionPlan::embed(proto_req));
+        ctx.state.execution_plan = Some(plan);
         Ok(None)
     }
 
@@ -120,3 +100,28 @@ impl PipelineStage for EmbeddingRequestBuildingStage {
         "EmbeddingRequestBuilding"
     }
 }
+
+impl EmbeddingRequestBuildingStage {
+    /// Build the backend-specific embed request
+    fn build_request(
+        client: &GrpcClient,
+        request_id: String,
+        original_text: Option<String>,
+        token_ids: Vec<u32>,
+    ) -> Result<ProtoEmbedRequest, Response> {
+        client
+            .build_embed_request(request_id, original_text, token_ids)
+            .ok_or_else(|| {
+                let backend = client.runtime_type();
+                error!(
+                    function = "EmbeddingRequestBuildingStage::execute",
+                    %backend,
+                    "Embedding not supported by backend"
+                );
+                error::not_implemented(
+                    "unsupported_backend",
+                    format!("{backend} embedding is not supported via gRPC"),
+                )
+            })
+    }
+}
@@ -67,11 +67,28 @@ impl PipelineStage for GenerateRequestBuildingStage {
             disaggregated,
         );
 
+        // Backends that ignore `n` get one single-choice request per choice.
+        let fan_out = helpers::fan_out_choices(
+            builder_client,
+            self.plan_kind,
+            generate_request.sampling_params.as_ref().and_then(|p| p.n),
+        );
+        let single_choice_request = fan_out.map(|_| {
+            let mut request = (*generate_request).clone();
+            if let Some(params) = request.sampling_params.as_mut() {
+                params.n = None;
+            }
+            request
+        });
+        let body = single_choice_request
+            .as_ref()
+            .unwrap_or_else(|| generate_request.as_ref());
+
         // Build proto request using centralized dispatch
         let mut proto_request = builder_client
             .build_generate_request(
                 request_id,
-                &generate_request,
+                body,
                 prep.routing_text().map(String::from),
                 prep.token_ids().to_vec(),
             )
@@ -88,17 +105,22 @@ impl PipelineStage for GenerateRequestBuildingStage {
 
         if self.inject_pd_metadata {
             if let Some(workers) = ctx.state.workers.as_ref() {
-                helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                let room = helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                ctx.state.bootstrap_rooms.extend(room);
             }
         }
 
         // EPD: inject the prefill->decode KV rendezvous for backends that carry it
         // in the request. No-op unless the selected workers are TokenSpeed EPD.
         if let Some(workers) = ctx.state.workers.as_ref() {
-            helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            let room = helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            ctx.state.bootstrap_rooms.extend(room);
         }
 
-        ctx.state.execution_plan = Some(ExecutionPlan::generate(self.plan_kind, proto_request));
+        ctx.state.execution_plan = Some(match fan_out {
+            Some(n) => ExecutionPlan::fan_out(proto_request, n),
+            None => ExecutionPlan::generate(self.plan_kind, proto_request),
+        });
         Ok(None)
     }
 
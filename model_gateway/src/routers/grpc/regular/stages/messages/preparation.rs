@@ -8,7 +8,7 @@ use openai_protocol::{
     common::{StringOrArray, ToolChoice, ToolChoiceValue},
     messages::CreateMessageRequest,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 use crate::routers::{
     error,
@@ -225,6 +225,10 @@ impl MessagePreparationStage {
         if let Some((mm_components, model_id, tokenizer_id, tokenizer_source, media_plan)) =
             mm_context
         {
+            let tenant_key = ctx
+                .tenant_request_meta
+                .as_ref()
+                .map(|meta| meta.tenant_key());
             match multimodal::process_multimodal_plan(
                 media_plan,
                 model_id,
@@ -233,6 +237,7 @@ impl MessagePreparationStage {
                 mm_components,
                 &tokenizer_id,
                 &tokenizer_source,
+                tenant_key,
             )
             .await
             {
@@ -246,6 +251,14 @@ impl MessagePreparationStage {
                     multimodal_intermediate = Some(output.intermediate);
                 }
                 Err(e) => {
+                    if let Some(safety_error) = e.downcast_ref::<multimodal::ImageSafetyError>() {
+                        warn!(
+                            function = "MessagePreparationStage::execute",
+                            error = %safety_error,
+                            "Multimodal request stopped by image safety screening"
+                        );
+                        return Err(safety_error.to_response());
+                    }
                     error!(
                         function = "MessagePreparationStage::execute",
                         error = %e,
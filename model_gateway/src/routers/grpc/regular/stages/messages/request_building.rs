@@ -150,7 +150,8 @@ impl PipelineStage for MessageRequestBuildingStage {
 
         if self.inject_pd_metadata {
             if let Some(workers) = ctx.state.workers.as_ref() {
-                helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                let room = helpers::maybe_inject_pd_metadata(&mut proto_request, workers);
+                ctx.state.bootstrap_rooms.extend(room);
             }
         }
 
@@ -164,7 +165,8 @@ impl PipelineStage for MessageRequestBuildingStage {
         // EPD: inject the prefill->decode KV rendezvous (mirrors the chat path).
         // No-op unless the backend carries it in the request.
         if let Some(workers) = ctx.state.workers.as_ref() {
-            helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            let room = helpers::maybe_inject_pd_rendezvous(&mut proto_request, workers);
+            ctx.state.bootstrap_rooms.extend(room);
         }
 
         ctx.state.execution_plan = Some(ExecutionPlan::generate(self.plan_kind, proto_request));
@@ -10,6 +10,7 @@ pub(crate) mod generate;
 pub(crate) mod messages;
 pub(crate) mod preparation;
 pub(crate) mod request_building;
+pub(crate) mod rerank;
 pub(crate) mod response_processing;
 
 // Re-export chat+generate dispatcher stages
@@ -0,0 +1,15 @@
+//! Rerank API endpoint pipeline stages
+//!
+//! `/v1/rerank` over gRPC scores each (query, document) pair with one
+//! cross-encoder embed RPC. Backends return a raw relevance score per
+//! document; normalization, sorting, and top_n truncation happen in the
+//! response processing stage. The shared stages (worker selection, client
+//! acquisition, dispatch, execution) are reused from the existing pipeline.
+
+mod preparation;
+mod request_building;
+mod response_processing;
+
+pub(crate) use preparation::RerankPreparationStage;
+pub(crate) use request_building::RerankRequestBuildingStage;
+pub(crate) use response_processing::RerankResponseProcessingStage;
@@ -0,0 +1,79 @@
+//! Rerank preparation stage: tokenize every (query, document) pair.
+
+use async_trait::async_trait;
+use axum::response::Response;
+use tracing::error;
+
+use crate::routers::{
+    error,
+    grpc::{
+        common::stages::PipelineStage,
+        context::{PreparationOutput, RequestContext, RequestType, RerankPair},
+        utils,
+    },
+};
+
+pub(crate) struct RerankPreparationStage;
+
+#[async_trait]
+impl PipelineStage for RerankPreparationStage {
+    async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        let RequestType::Rerank(request) = &ctx.input.request_type else {
+            error!(
+                function = "RerankPreparationStage::execute",
+                "Invalid request type: expected Rerank"
+            );
+            return Err(error::internal_error(
+                "invalid_request_type",
+                "Expected Rerank request",
+            ));
+        };
+        // Empty queries and document lists are rejected at the boundary
+        // (`RerankRequest` validation).
+        let request = request.clone();
+
+        let tokenizer =
+            utils::resolve_tokenizer(ctx, "RerankPreparationStage::execute").map_err(|e| *e)?;
+
+        // Pair encoding inserts the separator/special tokens and segment ids
+        // the cross-encoder was trained with. One blocking task covers every
+        // document so large candidate lists don't stall the runtime.
+        let query = request.query.clone();
+        let documents = request.documents.clone();
+        let pairs = tokio::task::spawn_blocking(move || {
+            documents
+                .iter()
+                .map(|document| {
+                    tokenizer
+                        .encode_pair(&query, document, true)
+                        .map(|encoding| RerankPair {
+                            token_ids: encoding.token_ids().to_vec(),
+                            token_type_ids: encoding.type_ids().to_vec(),
+                        })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("tokenization task failed: {e}"))
+        .and_then(|result| result)
+        .map_err(|e| {
+            error!(
+                function = "RerankPreparationStage::execute",
+                error = %e,
+                "Tokenization failed"
+            );
+            error::bad_request("tokenization_failed", format!("Tokenization failed: {e}"))
+        })?;
+
+        ctx.state.preparation = Some(PreparationOutput::Rerank {
+            query: request.query.clone(),
+            pairs,
+        });
+
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "RerankPreparation"
+    }
+}
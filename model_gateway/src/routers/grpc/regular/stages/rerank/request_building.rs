@@ -0,0 +1,128 @@
+//! Rerank request building stage: one cross-encoder embed request per document.
+
+use async_trait::async_trait;
+use axum::response::Response;
+use tracing::error;
+
+use crate::routers::{
+    error,
+    grpc::{
+        client::GrpcClient,
+        common::stages::{helpers, PipelineStage},
+        context::{ExecutionPlan, PreparationOutput, RequestContext, RequestType},
+        proto_wrapper::ProtoEmbedRequest,
+    },
+};
+
+pub(crate) struct RerankRequestBuildingStage;
+
+#[async_trait]
+impl PipelineStage for RerankRequestBuildingStage {
+    async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        let RequestType::Rerank(request) = &ctx.input.request_type else {
+            error!(
+                function = "RerankRequestBuildingStage::execute",
+                "Invalid request type: expected Rerank"
+            );
+            return Err(error::internal_error(
+                "invalid_request_type",
+                "Expected Rerank request",
+            ));
+        };
+
+        let Some(PreparationOutput::Rerank { query, pairs }) = ctx.state.preparation.as_ref()
+        else {
+            error!(
+                function = "RerankRequestBuildingStage::execute",
+                "Rerank preparation output missing"
+            );
+            return Err(error::internal_error(
+                "preparation_missing",
+                "Rerank preparation output missing",
+            ));
+        };
+
+        let client = ctx
+            .state
+            .clients
+            .as_ref()
+            .and_then(|c| c.single())
+            .ok_or_else(|| {
+                error!(
+                    function = "RerankRequestBuildingStage::execute",
+                    "Client not selected"
+                );
+                error::internal_error("client_missing", "Client not selected")
+            })?;
+
+        // Rerank is single-worker only (never disaggregated).
+        let request_id = helpers::resolve_request_id(
+            &ctx.input.request_type,
+            ctx.input.tenant_request_meta.as_ref(),
+            "rerank-",
+            false,
+        );
+
+        let requests = match client {
+            GrpcClient::Sglang(c) => pairs
+                .iter()
+                .zip(&request.documents)
+                .enumerate()
+                .map(|(i, (pair, document))| {
+                    let req = c.build_rerank_request(
+                        format!("{request_id}-d{i}"),
+                        query,
+                        document,
+                        pair.token_ids.clone(),
+                        pair.token_type_ids.iter().map(|&t| t as i32).collect(),
+                    );
+                    ProtoEmbedRequest::Sglang(Box::new(req))
+                })
+                .collect(),
+            // vLLM scores the pair encoding through its regular embed path;
+            // cross-encoder models return the relevance logit as a
+            // one-element embedding.
+            GrpcClient::Vllm(c) => pairs
+                .iter()
+                .zip(&request.documents)
+                .enumerate()
+                .map(|(i, (pair, document))| {
+                    let req = c.build_embed_request(
+                        format!("{request_id}-d{i}"),
+                        Some(format!("{query}{document}")),
+                        pair.token_ids.clone(),
+                    );
+                    ProtoEmbedRequest::Vllm(Box::new(req))
+                })
+                .collect(),
+            GrpcClient::Trtllm(_) => {
+                return Err(error::not_implemented(
+                    "unsupported_backend",
+                    "TensorRT-LLM rerank is not yet supported via gRPC",
+                ));
+            }
+            GrpcClient::Mlx(_) => {
+                return Err(error::not_implemented(
+                    "unsupported_backend",
+                    "MLX rerank is not supported via gRPC",
+                ));
+            }
+            GrpcClient::TokenSpeed(_) => {
+                return Err(error::not_implemented(
+                    "unsupported_backend",
+                    "TokenSpeed backend does not support rerank",
+                ));
+            }
+        };
+
+        ctx.state.execution_plan = Some(ExecutionPlan::EmbedBatch {
+            shared_request_id: request_id,
+            requests,
+        });
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "RerankRequestBuilding"
+    }
+}
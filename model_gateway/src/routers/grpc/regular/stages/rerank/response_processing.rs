@@ -0,0 +1,195 @@
+//! Rerank response processing stage.
+//!
+//! Collects the raw per-document scores from the embed batch, then applies
+//! the requested score normalization, sorts by relevance, and truncates to
+//! `top_n` gateway-side, since backends return a score for every document.
+
+use async_trait::async_trait;
+use axum::response::Response;
+use openai_protocol::{
+    common::UsageInfo,
+    rerank::{RerankRequest, RerankResponse, RerankResult},
+};
+use tracing::error;
+
+use crate::routers::{
+    error,
+    grpc::{
+        common::stages::PipelineStage,
+        context::{ExecutionResult, FinalResponse, RequestContext, RequestType},
+    },
+};
+
+pub(crate) struct RerankResponseProcessingStage;
+
+impl RerankResponseProcessingStage {
+    /// Build the client-facing response from raw scores in document order.
+    fn build_response(
+        request: &RerankRequest,
+        scores: Vec<f32>,
+        model: String,
+        prompt_tokens: u32,
+    ) -> RerankResponse {
+        let results = scores
+            .into_iter()
+            .zip(&request.documents)
+            .enumerate()
+            .map(|(index, (score, document))| RerankResult {
+                score,
+                document: Some(document.clone()),
+                index,
+                meta_info: None,
+            })
+            .collect();
+
+        let mut response = RerankResponse::new(results, model, request.rid.clone());
+        response.usage = Some(UsageInfo {
+            prompt_tokens,
+            total_tokens: prompt_tokens,
+            completion_tokens: 0,
+            prompt_tokens_details: None,
+            reasoning_tokens: None,
+        });
+
+        // Normalization needs every document's score, so it runs before
+        // truncation.
+        response.normalize_scores(request.score_normalization.unwrap_or_default());
+        response.sort_by_score();
+        if let Some(k) = request.top_k {
+            response.apply_top_k(k);
+        }
+        if !request.return_documents {
+            response.drop_documents();
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl PipelineStage for RerankResponseProcessingStage {
+    async fn execute(&self, ctx: &mut RequestContext) -> Result<Option<Response>, Response> {
+        let RequestType::Rerank(request) = &ctx.input.request_type else {
+            error!(
+                function = "RerankResponseProcessingStage::execute",
+                "Invalid request type: expected Rerank"
+            );
+            return Err(error::internal_error(
+                "invalid_request_type",
+                "Expected Rerank request",
+            ));
+        };
+
+        let execution_result = ctx.state.response.execution_result.take().ok_or_else(|| {
+            error!(
+                function = "RerankResponseProcessingStage::execute",
+                "Execution result missing"
+            );
+            error::internal_error("execution_result_missing", "Execution result missing")
+        })?;
+
+        let ExecutionResult::Batch { results } = execution_result else {
+            error!(
+                function = "RerankResponseProcessingStage::execute",
+                "Invalid execution result: expected Batch"
+            );
+            return Err(error::internal_error(
+                "invalid_execution_result",
+                "Expected Batch result for rerank",
+            ));
+        };
+
+        let mut scores = Vec::with_capacity(results.len());
+        let mut prompt_tokens = 0;
+        for result in results {
+            let ExecutionResult::Embedding { response } = result else {
+                error!(
+                    function = "RerankResponseProcessingStage::execute",
+                    "Invalid batch item: expected Embedding"
+                );
+                return Err(error::internal_error(
+                    "invalid_execution_result",
+                    "Expected Embedding results for rerank",
+                ));
+            };
+            let Some(&score) = response.embedding().first() else {
+                error!(
+                    function = "RerankResponseProcessingStage::execute",
+                    "Empty score received from scheduler"
+                );
+                return Err(error::internal_error(
+                    "empty_score",
+                    "Empty score received from scheduler",
+                ));
+            };
+            scores.push(score);
+            prompt_tokens += response.prompt_tokens();
+        }
+
+        let dispatch = ctx.state.dispatch.as_ref().ok_or_else(|| {
+            error!(
+                function = "RerankResponseProcessingStage::execute",
+                "Dispatch metadata missing"
+            );
+            error::internal_error("dispatch_missing", "Dispatch metadata missing")
+        })?;
+
+        let response = Self::build_response(request, scores, dispatch.model.clone(), prompt_tokens);
+        ctx.state.response.final_response = Some(FinalResponse::Rerank(response));
+
+        Ok(None)
+    }
+
+    fn name(&self) -> &'static str {
+        "RerankResponseProcessing"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::rerank::ScoreNormalization;
+
+    use super::*;
+
+    fn request(top_k: Option<usize>, return_documents: bool) -> RerankRequest {
+        RerankRequest {
+            query: "q".to_string(),
+            documents: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            model: "m".to_string(),
+            top_k,
+            return_documents,
+            rid: None,
+            user: None,
+            score_normalization: None,
+        }
+    }
+
+    #[test]
+    fn test_build_response_sorts_and_truncates() {
+        let response = RerankResponseProcessingStage::build_response(
+            &request(Some(2), true),
+            vec![0.1, 0.9, 0.5],
+            "m".to_string(),
+            12,
+        );
+        let indices: Vec<usize> = response.results.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(response.results[0].document.as_deref(), Some("b"));
+        assert_eq!(response.usage.map(|u| u.prompt_tokens), Some(12));
+    }
+
+    #[test]
+    fn test_build_response_normalizes_before_top_k() {
+        let mut req = request(Some(1), false);
+        req.score_normalization = Some(ScoreNormalization::MinMax);
+        let response = RerankResponseProcessingStage::build_response(
+            &req,
+            vec![2.0, 4.0, 3.0],
+            "m".to_string(),
+            0,
+        );
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].index, 1);
+        assert!((response.results[0].score - 1.0).abs() < 1e-6);
+        assert!(response.results[0].document.is_none());
+    }
+}
@@ -42,8 +42,7 @@ use crate::{
             common::{response_formatting::CompletionTokenTracker, responses::build_sse_response},
             context,
             proto_wrapper::{ProtoResponseVariant, ProtoStream},
-            utils,
-            utils::message_utils,
+            utils::{self, finish_reason, message_utils},
         },
     },
 };
@@ -614,12 +613,11 @@ impl StreamingProcessor {
 
         // Phase 4: Finish reason chunks
         for (index, finish_reason) in &finish_reasons {
-            let final_finish_reason =
-                if has_tool_calls.get(index).copied().unwrap_or(false) && finish_reason == "stop" {
-                    "tool_calls".to_string()
-                } else {
-                    finish_reason.clone()
-                };
+            let final_finish_reason = finish_reason::with_tool_calls(
+                finish_reason,
+                has_tool_calls.get(index).copied().unwrap_or(false),
+            )
+            .to_string();
 
             let matched_stop_value = matched_stops.get(index).and_then(|v| v.clone());
 
@@ -649,7 +647,8 @@ impl StreamingProcessor {
                     .usage(
                         Usage::from_counts(total_prompt, total_completion)
                             .with_cached_tokens(total_cached)
-                            .with_reasoning_tokens(total_reasoning),
+                            .with_reasoning_tokens(total_reasoning)
+                            .with_prompt_breakdown(dispatch.prompt_breakdown.clone()),
                     )
                     .maybe_system_fingerprint(system_fingerprint)
                     .build();
@@ -911,7 +910,7 @@ impl StreamingProcessor {
                         "output_ids": complete.output_ids()[complete.output_ids().len().saturating_sub(1)..].to_vec(),
                         "meta_info": {
                             "id": index_id,
-                            "finish_reason": complete.finish_reason(),
+                            "finish_reason": complete.raw_finish_reason(),
                             "prompt_tokens": complete.prompt_tokens(),
                             "weight_version": &ctx.weight_version,
                             "completion_tokens": completion_tokens,
@@ -1119,7 +1118,7 @@ impl StreamingProcessor {
 
                     // Parse finish_reason
                     let finish_reason = utils::parse_finish_reason(
-                        complete.finish_reason(),
+                        complete.raw_finish_reason(),
                         complete.completion_tokens(),
                     );
 
@@ -1513,6 +1512,60 @@ impl StreamingProcessor {
             .map_err(|_| "Client disconnected".to_string())
     }
 
+    /// Send a `MessageStreamEvent`, running `tool_use` blocks through the
+    /// `input_json_delta` validator: fragments are repaired or dropped and an
+    /// unterminated input object is closed before its `content_block_stop`.
+    fn send_validated_messages_event(
+        tx: &UnboundedSender<Result<Bytes, io::Error>>,
+        buffer: &mut Vec<u8>,
+        validator: &mut utils::ToolInputJsonValidator,
+        event: &MessageStreamEvent,
+    ) -> Result<(), String> {
+        match event {
+            MessageStreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::ToolUse { .. },
+            } => validator.start_block(*index),
+            MessageStreamEvent::ContentBlockDelta {
+                index,
+                delta: ContentBlockDelta::InputJsonDelta { partial_json },
+            } => {
+                let fragment = validator.push_delta(*index, partial_json);
+                if fragment.is_empty() {
+                    return Ok(());
+                }
+                if fragment != partial_json.as_str() {
+                    return Self::send_messages_event(
+                        tx,
+                        buffer,
+                        &MessageStreamEvent::ContentBlockDelta {
+                            index: *index,
+                            delta: ContentBlockDelta::InputJsonDelta {
+                                partial_json: fragment.into_owned(),
+                            },
+                        },
+                    );
+                }
+            }
+            MessageStreamEvent::ContentBlockStop { index } => {
+                if let Some(suffix) = validator.finish_block(*index) {
+                    Self::send_messages_event(
+                        tx,
+                        buffer,
+                        &MessageStreamEvent::ContentBlockDelta {
+                            index: *index,
+                            delta: ContentBlockDelta::InputJsonDelta {
+                                partial_json: suffix,
+                            },
+                        },
+                    )?;
+                }
+            }
+            _ => {}
+        }
+        Self::send_messages_event(tx, buffer, event)
+    }
+
     /// Process reasoning content in Messages streaming mode (n=1 only).
     ///
     /// Returns `(normal_text, reasoning_text, in_reasoning)`.
@@ -1705,6 +1758,8 @@ impl StreamingProcessor {
 
         // Reusable SSE formatting buffer to avoid allocations per event
         let mut sse_buffer = Vec::with_capacity(512);
+        // Keeps tool_use input_json_delta fragments parseable by client SDKs
+        let mut tool_input_validator = utils::ToolInputJsonValidator::new();
 
         let request_id = &dispatch.request_id;
         let model = &dispatch.model;
@@ -1849,9 +1904,10 @@ impl StreamingProcessor {
                 service_tier: None,
             },
         };
-        Self::send_messages_event(
+        Self::send_validated_messages_event(
             tx,
             &mut sse_buffer,
+            &mut tool_input_validator,
             &MessageStreamEvent::MessageStart {
                 message: start_message,
             },
@@ -1894,9 +1950,10 @@ impl StreamingProcessor {
                     // Emit thinking content block deltas
                     if !reasoning_chunk_text.is_empty() {
                         if !thinking_block_open {
-                            Self::send_messages_event(
+                            Self::send_validated_messages_event(
                                 tx,
                                 &mut sse_buffer,
+                                &mut tool_input_validator,
                                 &MessageStreamEvent::ContentBlockStart {
                                     index: current_block_index,
                                     content_block: ContentBlock::Thinking {
@@ -1907,9 +1964,10 @@ impl StreamingProcessor {
                             )?;
                             thinking_block_open = true;
                         }
-                        Self::send_messages_event(
+                        Self::send_validated_messages_event(
                             tx,
                             &mut sse_buffer,
+                            &mut tool_input_validator,
                             &MessageStreamEvent::ContentBlockDelta {
                                 index: current_block_index,
                                 delta: ContentBlockDelta::ThinkingDelta {
@@ -1921,9 +1979,10 @@ impl StreamingProcessor {
 
                     // Transition: reasoning ended, close thinking block
                     if thinking_block_open && !in_reasoning && !normal_text.is_empty() {
-                        Self::send_messages_event(
+                        Self::send_validated_messages_event(
                             tx,
                             &mut sse_buffer,
+                            &mut tool_input_validator,
                             &MessageStreamEvent::ContentBlockStop {
                                 index: current_block_index,
                             },
@@ -1940,9 +1999,10 @@ impl StreamingProcessor {
                                 has_tool_calls = true;
                                 // Close text block if open before starting tool block
                                 if text_block_open {
-                                    Self::send_messages_event(
+                                    Self::send_validated_messages_event(
                                         tx,
                                         &mut sse_buffer,
+                                        &mut tool_input_validator,
                                         &MessageStreamEvent::ContentBlockStop {
                                             index: current_block_index,
                                         },
@@ -1961,9 +2021,10 @@ impl StreamingProcessor {
                                     0,
                                     history_tool_calls_count,
                                 );
-                                Self::send_messages_event(
+                                Self::send_validated_messages_event(
                                     tx,
                                     &mut sse_buffer,
+                                    &mut tool_input_validator,
                                     &MessageStreamEvent::ContentBlockStart {
                                         index: current_block_index,
                                         content_block: ContentBlock::ToolUse {
@@ -1977,9 +2038,10 @@ impl StreamingProcessor {
                             }
                             // Emit arguments delta
                             if !normal_text.is_empty() {
-                                Self::send_messages_event(
+                                Self::send_validated_messages_event(
                                     tx,
                                     &mut sse_buffer,
+                                    &mut tool_input_validator,
                                     &MessageStreamEvent::ContentBlockDelta {
                                         index: current_block_index,
                                         delta: ContentBlockDelta::InputJsonDelta {
@@ -1998,9 +2060,10 @@ impl StreamingProcessor {
                                     // Emit normal text from parser as text content blocks
                                     if !text.is_empty() {
                                         if !text_block_open {
-                                            Self::send_messages_event(
+                                            Self::send_validated_messages_event(
                                                 tx,
                                                 &mut sse_buffer,
+                                                &mut tool_input_validator,
                                                 &MessageStreamEvent::ContentBlockStart {
                                                     index: current_block_index,
                                                     content_block: ContentBlock::Text {
@@ -2011,9 +2074,10 @@ impl StreamingProcessor {
                                             )?;
                                             text_block_open = true;
                                         }
-                                        Self::send_messages_event(
+                                        Self::send_validated_messages_event(
                                             tx,
                                             &mut sse_buffer,
+                                            &mut tool_input_validator,
                                             &MessageStreamEvent::ContentBlockDelta {
                                                 index: current_block_index,
                                                 delta: ContentBlockDelta::TextDelta { text },
@@ -2028,9 +2092,10 @@ impl StreamingProcessor {
                                         if let Some(ref name) = tool_call_item.name {
                                             // New tool call: close previous blocks, emit start
                                             if text_block_open {
-                                                Self::send_messages_event(
+                                                Self::send_validated_messages_event(
                                                     tx,
                                                     &mut sse_buffer,
+                                                    &mut tool_input_validator,
                                                     &MessageStreamEvent::ContentBlockStop {
                                                         index: current_block_index,
                                                     },
@@ -2039,9 +2104,10 @@ impl StreamingProcessor {
                                                 current_block_index += 1;
                                             }
                                             if tool_block_open {
-                                                Self::send_messages_event(
+                                                Self::send_validated_messages_event(
                                                     tx,
                                                     &mut sse_buffer,
+                                                    &mut tool_input_validator,
                                                     &MessageStreamEvent::ContentBlockStop {
                                                         index: current_block_index,
                                                     },
@@ -2055,9 +2121,10 @@ impl StreamingProcessor {
                                                 tool_call_item.tool_index,
                                                 history_tool_calls_count,
                                             );
-                                            Self::send_messages_event(
+                                            Self::send_validated_messages_event(
                                                 tx,
                                                 &mut sse_buffer,
+                                                &mut tool_input_validator,
                                                 &MessageStreamEvent::ContentBlockStart {
                                                     index: current_block_index,
                                                     content_block: ContentBlock::ToolUse {
@@ -2072,9 +2139,10 @@ impl StreamingProcessor {
 
                                         // Emit incremental arguments
                                         if !tool_call_item.parameters.is_empty() {
-                                            Self::send_messages_event(
+                                            Self::send_validated_messages_event(
                                                 tx,
                                                 &mut sse_buffer,
+                                                &mut tool_input_validator,
                                                 &MessageStreamEvent::ContentBlockDelta {
                                                     index: current_block_index,
                                                     delta: ContentBlockDelta::InputJsonDelta {
@@ -2096,9 +2164,10 @@ impl StreamingProcessor {
                     // Regular text emission (no tools active)
                     if !normal_text.is_empty() {
                         if !text_block_open {
-                            Self::send_messages_event(
+                            Self::send_validated_messages_event(
                                 tx,
                                 &mut sse_buffer,
+                                &mut tool_input_validator,
                                 &MessageStreamEvent::ContentBlockStart {
                                     index: current_block_index,
                                     content_block: ContentBlock::Text {
@@ -2109,9 +2178,10 @@ impl StreamingProcessor {
                             )?;
                             text_block_open = true;
                         }
-                        Self::send_messages_event(
+                        Self::send_validated_messages_event(
                             tx,
                             &mut sse_buffer,
+                            &mut tool_input_validator,
                             &MessageStreamEvent::ContentBlockDelta {
                                 index: current_block_index,
                                 delta: ContentBlockDelta::TextDelta { text: normal_text },
@@ -2124,9 +2194,10 @@ impl StreamingProcessor {
                     if let SequenceDecoderOutput::Text(text) = stop_decoder.flush() {
                         if !text.is_empty() {
                             if !text_block_open {
-                                Self::send_messages_event(
+                                Self::send_validated_messages_event(
                                     tx,
                                     &mut sse_buffer,
+                                    &mut tool_input_validator,
                                     &MessageStreamEvent::ContentBlockStart {
                                         index: current_block_index,
                                         content_block: ContentBlock::Text {
@@ -2137,9 +2208,10 @@ impl StreamingProcessor {
                                 )?;
                                 text_block_open = true;
                             }
-                            Self::send_messages_event(
+                            Self::send_validated_messages_event(
                                 tx,
                                 &mut sse_buffer,
+                                &mut tool_input_validator,
                                 &MessageStreamEvent::ContentBlockDelta {
                                     index: current_block_index,
                                     delta: ContentBlockDelta::TextDelta { text },
@@ -2166,9 +2238,10 @@ impl StreamingProcessor {
                     if let Some(ref name) = tool_call_item.name {
                         // Close text block if open before starting tool block
                         if text_block_open {
-                            Self::send_messages_event(
+                            Self::send_validated_messages_event(
                                 tx,
                                 &mut sse_buffer,
+                                &mut tool_input_validator,
                                 &MessageStreamEvent::ContentBlockStop {
                                     index: current_block_index,
                                 },
@@ -2177,9 +2250,10 @@ impl StreamingProcessor {
                             current_block_index += 1;
                         }
                         if tool_block_open {
-                            Self::send_messages_event(
+                            Self::send_validated_messages_event(
                                 tx,
                                 &mut sse_buffer,
+                                &mut tool_input_validator,
                                 &MessageStreamEvent::ContentBlockStop {
                                     index: current_block_index,
                                 },
@@ -2193,9 +2267,10 @@ impl StreamingProcessor {
                             tool_call_item.tool_index,
                             history_tool_calls_count,
                         );
-                        Self::send_messages_event(
+                        Self::send_validated_messages_event(
                             tx,
                             &mut sse_buffer,
+                            &mut tool_input_validator,
                             &MessageStreamEvent::ContentBlockStart {
                                 index: current_block_index,
                                 content_block: ContentBlock::ToolUse {
@@ -2209,9 +2284,10 @@ impl StreamingProcessor {
                     }
 
                     if !tool_call_item.parameters.is_empty() {
-                        Self::send_messages_event(
+                        Self::send_validated_messages_event(
                             tx,
                             &mut sse_buffer,
+                            &mut tool_input_validator,
                             &MessageStreamEvent::ContentBlockDelta {
                                 index: current_block_index,
                                 delta: ContentBlockDelta::InputJsonDelta {
@@ -2226,9 +2302,10 @@ impl StreamingProcessor {
 
         // Phase 3.5: Close any open content blocks
         if thinking_block_open {
-            Self::send_messages_event(
+            Self::send_validated_messages_event(
                 tx,
                 &mut sse_buffer,
+                &mut tool_input_validator,
                 &MessageStreamEvent::ContentBlockStop {
                     index: current_block_index,
                 },
@@ -2237,9 +2314,10 @@ impl StreamingProcessor {
         }
 
         if text_block_open {
-            Self::send_messages_event(
+            Self::send_validated_messages_event(
                 tx,
                 &mut sse_buffer,
+                &mut tool_input_validator,
                 &MessageStreamEvent::ContentBlockStop {
                     index: current_block_index,
                 },
@@ -2248,9 +2326,10 @@ impl StreamingProcessor {
         }
 
         if tool_block_open {
-            Self::send_messages_event(
+            Self::send_validated_messages_event(
                 tx,
                 &mut sse_buffer,
+                &mut tool_input_validator,
                 &MessageStreamEvent::ContentBlockStop {
                     index: current_block_index,
                 },
@@ -2274,9 +2353,10 @@ impl StreamingProcessor {
             None
         };
 
-        Self::send_messages_event(
+        Self::send_validated_messages_event(
             tx,
             &mut sse_buffer,
+            &mut tool_input_validator,
             &MessageStreamEvent::MessageDelta {
                 delta: MessageDelta {
                     stop_reason,
@@ -2792,31 +2872,10 @@ impl StreamingProcessor {
                             .map_err(|_| "Channel closed".to_string())?;
                     }
 
-                    let finish_reason = {
-                        let reason = complete.finish_reason();
-                        if reason.is_empty() || reason == "stop" {
-                            Some("stop".to_string())
-                        } else if reason == "length" || reason == "content_filter" {
-                            Some(reason.to_string())
-                        } else if let Ok(json) = serde_json::from_str::<Value>(reason) {
-                            json.get("type")
-                                .and_then(|v| v.as_str())
-                                .map(|t| match t {
-                                    "stop" | "length" | "content_filter" => t.to_string(),
-                                    other => {
-                                        warn!(unexpected_finish_reason = other, "Unmapped finish_reason type from backend, defaulting to stop");
-                                        "stop".to_string()
-                                    }
-                                })
-                                .or_else(|| Some("stop".to_string()))
-                        } else {
-                            warn!(
-                                unexpected_finish_reason = reason,
-                                "Unrecognized finish_reason from backend, defaulting to stop"
-                            );
-                            Some("stop".to_string())
-                        }
-                    };
+                    // Completions have no tool calls to report.
+                    let finish_reason = Some(
+                        finish_reason::with_tool_calls(complete.finish_reason(), false).to_string(),
+                    );
 
                     let final_chunk = CompletionStreamResponse {
                         id: request_id.clone(),
@@ -14,6 +14,7 @@ use openai_protocol::{
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
     messages::CreateMessageRequest,
+    rerank::RerankRequest,
     responses::ResponsesRequest,
     transcription::{AudioFile, TranscriptionRequest},
 };
@@ -37,7 +38,7 @@ use crate::{
     middleware::TenantRequestMeta,
     observability::metrics::{metrics_labels, Metrics},
     routers::{
-        common::retry::{is_retryable_status, RetryExecutor},
+        common::retry::{is_retryable_response, RetryExecutor},
         error, RouterTrait,
     },
     worker::{ConnectionMode, WorkerRegistry, WorkerType},
@@ -294,8 +295,8 @@ fn not_implemented(message: &'static str) -> Response {
 /// EncodePrefillDecode. `mode` selects the disaggregation params baked into
 /// every pipeline and drives the per-mode retry-metric labels, `Debug` output,
 /// and `router_type`. Optional members are `None` when the mode doesn't serve
-/// them and the corresponding endpoints 501: `embedding_pipeline` and
-/// `classify_pipeline` are Regular-only; `harmony_pipeline`,
+/// them and the corresponding endpoints 501: `embedding_pipeline`,
+/// `classify_pipeline`, and `rerank_pipeline` are Regular-only; `harmony_pipeline`,
 /// `responses_context`, and `harmony_responses_context` exist in every mode
 /// but EPD.
 #[derive(Clone)]
@@ -306,6 +307,7 @@ pub struct GrpcRouter {
     harmony_pipeline: Option<RequestPipeline>,
     embedding_pipeline: Option<RequestPipeline>,
     classify_pipeline: Option<RequestPipeline>,
+    rerank_pipeline: Option<RequestPipeline>,
     messages_pipeline: RequestPipeline,
     completion_pipeline: RequestPipeline,
     shared_components: Arc<SharedComponents>,
@@ -317,7 +319,7 @@ pub struct GrpcRouter {
 impl GrpcRouter {
     /// Regular and PD build the Harmony pipeline and responses contexts and
     /// require the MCP orchestrator; EPD leaves them `None` and 501s those
-    /// endpoints. Embedding/classify pipelines are Regular-only.
+    /// endpoints. Embedding/classify/rerank pipelines are Regular-only.
     pub fn new(ctx: &Arc<AppContext>, mode: Mode) -> Result<Self, String> {
         // Get tokenizer registry (no longer requires pre-loaded tokenizer)
         let tokenizer_registry = ctx.tokenizer_registry.clone();
@@ -337,7 +339,10 @@ impl GrpcRouter {
         let policy_registry = ctx.policy_registry.clone();
 
         // Create multimodal components (best-effort; non-fatal if initialization fails)
-        let multimodal = match MultimodalComponents::new(ctx.multimodal_config_registry.clone()) {
+        let multimodal = match MultimodalComponents::new(
+            ctx.multimodal_config_registry.clone(),
+            ctx.multimodal_model_registry.clone(),
+        ) {
             Ok(mc) => Some(Arc::new(mc)),
             Err(e) => {
                 tracing::warn!("Multimodal components initialization failed (non-fatal): {e}");
@@ -363,9 +368,17 @@ impl GrpcRouter {
             reasoning_parser_factory.clone(),
             ctx.configured_tool_parser.clone(),
             ctx.configured_reasoning_parser.clone(),
+        )
+        .with_custom_stages(
+            &ctx.router_config.grpc_pipeline.stages,
+            ctx.wasm_manager.clone(),
         );
-        // Deps for the parser-free endpoints (completion/embeddings/classify).
-        let pair_deps = PipelineDeps::pair(worker_registry.clone(), policy_registry.clone());
+        // Deps for the parser-free endpoints (completion/embeddings/classify/rerank).
+        let pair_deps = PipelineDeps::pair(worker_registry.clone(), policy_registry.clone())
+            .with_custom_stages(
+                &ctx.router_config.grpc_pipeline.stages,
+                ctx.wasm_manager.clone(),
+            );
 
         // Present in every mode: chat/generate, messages, completion.
         let pipeline = RequestPipeline::build(Endpoint::Chat, mode, &configured_deps)
@@ -379,6 +392,7 @@ impl GrpcRouter {
         let harmony_pipeline = RequestPipeline::build(Endpoint::Harmony, mode, &configured_deps);
         let embedding_pipeline = RequestPipeline::build(Endpoint::Embeddings, mode, &pair_deps);
         let classify_pipeline = RequestPipeline::build(Endpoint::Classify, mode, &pair_deps);
+        let rerank_pipeline = RequestPipeline::build(Endpoint::Rerank, mode, &pair_deps);
 
         // Responses contexts are the sole consumer of the MCP orchestrator; EPD
         // builds neither (it doesn't serve /v1/responses).
@@ -425,6 +439,7 @@ impl GrpcRouter {
             harmony_pipeline,
             embedding_pipeline,
             classify_pipeline,
+            rerank_pipeline,
             messages_pipeline,
             completion_pipeline,
             shared_components,
@@ -522,8 +537,8 @@ impl GrpcRouter {
                         .await
                 }
             },
-            // Should retry: check if status is retryable
-            |res, _attempt| is_retryable_status(res.status()),
+            // Should retry: backend classification, else status
+            |res, _attempt| is_retryable_response(res),
             // On backoff: record retry metrics
             |delay, attempt| {
                 self.record_retry(metrics_labels::ENDPOINT_CHAT);
@@ -572,8 +587,8 @@ impl GrpcRouter {
                         .await
                 }
             },
-            // Should retry: check if status is retryable
-            |res, _attempt| is_retryable_status(res.status()),
+            // Should retry: backend classification, else status
+            |res, _attempt| is_retryable_response(res),
             // On backoff: record retry metrics
             |delay, attempt| {
                 self.record_retry(metrics_labels::ENDPOINT_GENERATE);
@@ -713,7 +728,7 @@ impl GrpcRouter {
                         .await
                 }
             },
-            |res, _attempt| is_retryable_status(res.status()),
+            |res, _attempt| is_retryable_response(res),
             |delay, attempt| {
                 self.record_retry(metrics_labels::ENDPOINT_MESSAGES);
                 Metrics::record_worker_retry_backoff(attempt, delay);
@@ -764,7 +779,7 @@ impl GrpcRouter {
                         .await
                 }
             },
-            |res, _attempt| is_retryable_status(res.status()),
+            |res, _attempt| is_retryable_response(res),
             |delay, attempt| {
                 self.record_retry(metrics_labels::ENDPOINT_COMPLETIONS);
                 Metrics::record_worker_retry_backoff(attempt, delay);
@@ -799,6 +814,30 @@ impl GrpcRouter {
             )
             .await
     }
+
+    /// Main route_rerank implementation
+    async fn route_rerank_impl(
+        &self,
+        headers: Option<&HeaderMap>,
+        tenant_meta: &TenantRequestMeta,
+        body: &RerankRequest,
+        model_id: &str,
+    ) -> Response {
+        let Some(rerank_pipeline) = self.rerank_pipeline.as_ref() else {
+            return not_implemented("Rerank not implemented");
+        };
+        debug!("Processing rerank request for model: {}", model_id);
+
+        rerank_pipeline
+            .execute_rerank(
+                Arc::new(body.clone()),
+                headers.cloned(),
+                model_id.to_string(),
+                self.shared_components.clone(),
+                Some(tenant_meta.clone()),
+            )
+            .await
+    }
 }
 
 impl std::fmt::Debug for GrpcRouter {
@@ -902,6 +941,17 @@ impl RouterTrait for GrpcRouter {
             .await
     }
 
+    async fn route_rerank(
+        &self,
+        headers: Option<&HeaderMap>,
+        tenant_meta: &TenantRequestMeta,
+        body: &RerankRequest,
+        model_id: &str,
+    ) -> Response {
+        self.route_rerank_impl(headers, tenant_meta, body, model_id)
+            .await
+    }
+
     async fn route_audio_transcriptions(
         &self,
         headers: Option<&HeaderMap>,
@@ -22,7 +22,7 @@ use openai_protocol::{
     generate::GenerateFinishReason,
 };
 use serde_json::{json, Value};
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::{mpsc, oneshot};
 use tracing::error;
 use uuid::Uuid;
 
@@ -86,30 +86,41 @@ pub(crate) fn resolve_tokenizer(
     Ok(tokenizer)
 }
 
-/// Below this input size (in bytes) the `spawn_blocking` + permit round-trip
-/// costs more than the encode itself, so we tokenize inline. Larger prompts —
-/// the ones that actually pin a worker thread — are offloaded.
+/// Below this input size (in bytes) the hand-off to the tokenizer pool costs
+/// more than the encode itself, so we tokenize inline. Larger prompts — the
+/// ones that actually pin a worker thread — are offloaded.
 const ENCODE_OFFLOAD_MIN_BYTES: usize = 512;
 
-/// Bounds how many CPU-bound encodes run concurrently on the blocking pool.
-/// tokio's blocking pool is otherwise unbounded (grows to 512 threads), so under
-/// a burst of large prompts the offloaded encodes would oversubscribe the CPU
-/// and starve the very request runtime this offload is meant to protect. Sized
-/// to the host's available parallelism.
-fn encode_permits() -> &'static Semaphore {
-    static SEM: OnceLock<Semaphore> = OnceLock::new();
-    SEM.get_or_init(|| {
+/// Dedicated rayon pool for offloaded encodes, sized to the host's available
+/// parallelism. Its fixed size bounds how many CPU-bound encodes run at once
+/// (tokio's blocking pool grows to 512 threads), so a burst of large prompts
+/// cannot oversubscribe the CPU and starve the very request runtime this
+/// offload is meant to protect. The chunks of a long prompt encoded in
+/// parallel run on the same threads. `None` if the pool could not be built.
+fn tokenize_pool() -> Option<&'static rayon::ThreadPool> {
+    static POOL: OnceLock<Option<rayon::ThreadPool>> = OnceLock::new();
+    POOL.get_or_init(|| {
         let n = std::thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(8);
-        Semaphore::new(n)
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .thread_name(|i| format!("smg-tokenize-{i}"))
+            // A panicking encode drops its result sender; the caller sees an
+            // error instead of the process aborting.
+            .panic_handler(|_| error!("Tokenization task panicked"))
+            .build()
+            .map_err(|e| error!(error = %e, "Failed to build tokenizer pool"))
+            .ok()
     })
+    .as_ref()
 }
 
 /// Tokenize off the async worker threads so CPU-bound `encode` cannot stall the
-/// runtime, bounded by [`encode_permits`] so concurrent offloaded encodes cannot
-/// oversubscribe the CPU. Small inputs are encoded inline to avoid the offload
-/// round-trip dominating.
+/// runtime. Offloaded inputs run on [`tokenize_pool`] through
+/// `Encoder::encode_parallel`, which splits long prompts into chunks encoded
+/// concurrently where the tokenizer supports it. Small inputs are encoded
+/// inline to avoid the offload round-trip dominating.
 pub(crate) async fn encode_blocking(
     tokenizer: Arc<dyn Tokenizer>,
     text: String,
@@ -118,12 +129,15 @@ pub(crate) async fn encode_blocking(
     if text.len() < ENCODE_OFFLOAD_MIN_BYTES {
         return tokenizer.encode(&text, add_special_tokens);
     }
-    let _permit = encode_permits()
-        .acquire()
-        .await
-        .map_err(|e| anyhow!("encode semaphore closed: {e}"))?;
-    tokio::task::spawn_blocking(move || tokenizer.encode(&text, add_special_tokens))
-        .await
+    let (tx, rx) = oneshot::channel();
+    let job = move || {
+        let _ = tx.send(tokenizer.encode_parallel(&text, add_special_tokens));
+    };
+    match tokenize_pool() {
+        Some(pool) => pool.spawn(job),
+        None => drop(tokio::task::spawn_blocking(job)),
+    }
+    rx.await
         .map_err(|e| anyhow!("tokenization task failed: {e}"))?
 }
 
@@ -1390,4 +1404,17 @@ mod tests {
             "image must precede the question (vstart={vstart}, qpos={qpos}).\n--- rendered ---\n{rendered}"
         );
     }
+
+    #[tokio::test]
+    async fn test_encode_blocking_offloads_large_inputs() {
+        let tokenizer: Arc<dyn Tokenizer> = Arc::new(llm_tokenizer::MockTokenizer::new());
+        let text = "<|im_start|> user Hello world <|im_end|> ".repeat(2048);
+        assert!(text.len() >= ENCODE_OFFLOAD_MIN_BYTES);
+
+        let offloaded = encode_blocking(tokenizer.clone(), text.clone(), false)
+            .await
+            .unwrap();
+        let inline = tokenizer.encode(&text, false).unwrap();
+        assert_eq!(offloaded.token_ids(), inline.token_ids());
+    }
 }
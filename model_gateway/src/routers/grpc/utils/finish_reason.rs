@@ -0,0 +1,145 @@
+//! Normalization of engine finish reasons.
+//!
+//! Engines disagree on how a finished sequence is reported: TensorRT-LLM says
+//! `stop_word` where the others say `stop`, SGLang may send a JSON object
+//! (`{"type": "length", "length": 128}`), MLX leaves the field empty, and some
+//! engines report `tool_calls` on their own. Every OpenAI-compatible response
+//! built from a `GenerateComplete` goes through [`normalize_finish_reason`]
+//! so clients see the same `finish_reason` whichever backend served them.
+
+use serde_json::Value;
+use tracing::warn;
+
+/// Per-backend finish reason spellings and the OpenAI reason they mean.
+pub(crate) struct BackendQuirks {
+    pub backend: &'static str,
+    pub aliases: &'static [(&'static str, &'static str)],
+}
+
+pub(crate) const SGLANG_QUIRKS: BackendQuirks = BackendQuirks {
+    backend: "sglang",
+    aliases: &[],
+};
+
+pub(crate) const VLLM_QUIRKS: BackendQuirks = BackendQuirks {
+    backend: "vllm",
+    aliases: &[],
+};
+
+pub(crate) const TRTLLM_QUIRKS: BackendQuirks = BackendQuirks {
+    backend: "trtllm",
+    aliases: &[
+        ("stop_word", "stop"),
+        ("end_id", "stop"),
+        ("cancelled", "abort"),
+    ],
+};
+
+pub(crate) const MLX_QUIRKS: BackendQuirks = BackendQuirks {
+    backend: "mlx",
+    aliases: &[],
+};
+
+pub(crate) const TOKENSPEED_QUIRKS: BackendQuirks = BackendQuirks {
+    backend: "tokenspeed",
+    aliases: &[],
+};
+
+/// Map an engine's raw finish reason to `stop`, `length`, `tool_calls`,
+/// `content_filter` or `abort`. Unrecognized reasons are reported as `stop`.
+pub(crate) fn normalize_finish_reason(quirks: &BackendQuirks, raw: &str) -> &'static str {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return "stop";
+    }
+    if let Some((_, reason)) = quirks.aliases.iter().find(|(alias, _)| *alias == raw) {
+        return reason;
+    }
+    if let Some(reason) = canonical(raw) {
+        return reason;
+    }
+    if let Ok(json) = serde_json::from_str::<Value>(raw) {
+        if let Some(reason) = json.get("type").and_then(Value::as_str).and_then(canonical) {
+            return reason;
+        }
+    }
+    warn!(
+        backend = quirks.backend,
+        unexpected_finish_reason = raw,
+        "Unrecognized finish_reason from backend, defaulting to stop"
+    );
+    "stop"
+}
+
+fn canonical(reason: &str) -> Option<&'static str> {
+    Some(match reason {
+        "stop" => "stop",
+        "length" => "length",
+        "tool_calls" => "tool_calls",
+        "content_filter" => "content_filter",
+        "abort" => "abort",
+        _ => return None,
+    })
+}
+
+/// Final chat `finish_reason` once tool calls have been parsed from the
+/// output. A sequence that stopped naturally with tool calls reports
+/// `tool_calls`; a truncated one still reports `length`; an engine-reported
+/// `tool_calls` with nothing parsed reports `stop`.
+pub(crate) fn with_tool_calls(reason: &str, has_tool_calls: bool) -> &str {
+    match (reason, has_tool_calls) {
+        ("stop" | "tool_calls", true) => "tool_calls",
+        ("tool_calls", false) => "stop",
+        (other, _) => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [&BackendQuirks; 5] = [
+        &SGLANG_QUIRKS,
+        &VLLM_QUIRKS,
+        &TRTLLM_QUIRKS,
+        &MLX_QUIRKS,
+        &TOKENSPEED_QUIRKS,
+    ];
+
+    #[test]
+    fn canonical_reasons_are_consistent_across_backends() {
+        for quirks in ALL {
+            for reason in ["stop", "length", "tool_calls", "content_filter", "abort"] {
+                assert_eq!(normalize_finish_reason(quirks, reason), reason);
+            }
+            assert_eq!(normalize_finish_reason(quirks, ""), "stop");
+            assert_eq!(
+                normalize_finish_reason(quirks, r#"{"type": "length", "length": 16}"#),
+                "length"
+            );
+            assert_eq!(normalize_finish_reason(quirks, "mystery"), "stop");
+        }
+    }
+
+    #[test]
+    fn backend_specific_spellings_are_mapped() {
+        assert_eq!(normalize_finish_reason(&TRTLLM_QUIRKS, "stop_word"), "stop");
+        assert_eq!(normalize_finish_reason(&TRTLLM_QUIRKS, "end_id"), "stop");
+        assert_eq!(
+            normalize_finish_reason(&TRTLLM_QUIRKS, "cancelled"),
+            "abort"
+        );
+        assert_eq!(
+            normalize_finish_reason(&SGLANG_QUIRKS, r#"{"type": "stop", "matched": 2}"#),
+            "stop"
+        );
+    }
+
+    #[test]
+    fn tool_calls_override_only_natural_stops() {
+        assert_eq!(with_tool_calls("stop", true), "tool_calls");
+        assert_eq!(with_tool_calls("length", true), "length");
+        assert_eq!(with_tool_calls("tool_calls", false), "stop");
+        assert_eq!(with_tool_calls("stop", false), "stop");
+    }
+}
@@ -38,36 +38,15 @@ pub fn process_messages(
     placeholder_tokens: Option<&PlaceholderTokens>,
     media_order: MediaPartOrder,
 ) -> Result<ProcessedMessages, String> {
-    let content_format = tokenizer.chat_template_content_format();
-
-    // Step 1: Convert InputMessages to chat template JSON values
-    let mut transformed_messages = process_message_content_format(
-        &request.messages,
-        content_format,
+    // Step 1: Convert the system prompt and messages to chat template JSON values
+    let transformed_messages = template_messages(
+        request,
+        tokenizer.chat_template_content_format(),
         placeholder_tokens,
         media_order,
     )?;
 
-    // Step 2: Prepend system message if present
-    if let Some(system) = &request.system {
-        let system_text = match system {
-            SystemContent::String(s) => s.clone(),
-            SystemContent::Blocks(blocks) => blocks
-                .iter()
-                .map(|b| {
-                    let messages::SystemContentBlock::Text(tb) = b;
-                    tb.text.as_str()
-                })
-                .collect::<Vec<_>>()
-                .join("\n"),
-        };
-        transformed_messages.insert(0, json!({"role": "system", "content": system_text}));
-    }
-
-    // Step 3: Process tool call arguments in assistant messages (reuse from chat_utils)
-    chat_utils::process_tool_call_arguments(&mut transformed_messages)?;
-
-    // Step 4: Serialize tools to JSON values for template processing
+    // Step 2: Serialize tools to JSON values for template processing
     let tools_json: Option<Vec<Value>> = chat_tools
         .map(|tools| {
             tools
@@ -78,7 +57,7 @@ pub fn process_messages(
         .transpose()
         .map_err(|e| format!("Failed to serialize tools: {e}"))?;
 
-    // Step 5: Project the Anthropic ThinkingConfig onto a thinking on/off
+    // Step 3: Project the Anthropic ThinkingConfig onto a thinking on/off
     // preference. Adaptive is treated as "thinking on"; the model decides
     // whether to actually emit it. The tokenizer applies this under the model's
     // own toggle key (`enable_thinking`/`thinking`) in `apply`.
@@ -88,7 +67,7 @@ pub fn process_messages(
         None => None, // Let template use its default behavior
     };
 
-    // Step 6: Apply chat template
+    // Step 4: Apply chat template
     let params = ChatTemplateParams {
         add_generation_prompt: true,
         tools: tools_json.as_deref(),
@@ -100,7 +79,7 @@ pub fn process_messages(
         .apply_chat_template(&transformed_messages, params)
         .map_err(|e| format!("Failed to apply chat template: {e}"))?;
 
-    // Step 7: Build ProcessedMessages
+    // Step 5: Build ProcessedMessages
     let stop_sequences = request
         .stop_sequences
         .as_ref()
@@ -116,6 +95,44 @@ pub fn process_messages(
 // InputMessage → JSON conversion
 // ============================================================================
 
+/// Convert a CreateMessageRequest's system prompt and messages to the chat
+/// template JSON messages, with tool call arguments parsed to objects.
+pub(crate) fn template_messages(
+    request: &CreateMessageRequest,
+    content_format: ChatTemplateContentFormat,
+    placeholder_tokens: Option<&PlaceholderTokens>,
+    media_order: MediaPartOrder,
+) -> Result<Vec<Value>, String> {
+    // Step 1: Convert InputMessages to chat template JSON values
+    let mut transformed_messages = process_message_content_format(
+        &request.messages,
+        content_format,
+        placeholder_tokens,
+        media_order,
+    )?;
+
+    // Step 2: Prepend system message if present
+    if let Some(system) = &request.system {
+        let system_text = match system {
+            SystemContent::String(s) => s.clone(),
+            SystemContent::Blocks(blocks) => blocks
+                .iter()
+                .map(|b| {
+                    let messages::SystemContentBlock::Text(tb) = b;
+                    tb.text.as_str()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        transformed_messages.insert(0, json!({"role": "system", "content": system_text}));
+    }
+
+    // Step 3: Process tool call arguments in assistant messages (reuse from chat_utils)
+    chat_utils::process_tool_call_arguments(&mut transformed_messages)?;
+
+    Ok(transformed_messages)
+}
+
 /// Convert InputMessage array to JSON Values for the chat template.
 ///
 /// Mirrors `process_content_format()` in chat_utils but works with
@@ -11,6 +11,10 @@ pub(crate) fn route_to_endpoint(route: &str) -> &'static str {
         "/generate" => metrics_labels::ENDPOINT_GENERATE,
         "/v1/completions" => metrics_labels::ENDPOINT_COMPLETIONS,
         "/v1/rerank" => metrics_labels::ENDPOINT_RERANK,
+        "/v1/embeddings" => metrics_labels::ENDPOINT_EMBEDDINGS,
+        "/v1/classify" => metrics_labels::ENDPOINT_CLASSIFY,
+        "/v1/score" => metrics_labels::ENDPOINT_SCORE,
+        "/v1/images/generations" => metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
         "/v1/responses" => metrics_labels::ENDPOINT_RESPONSES,
         "/v1/messages" => metrics_labels::ENDPOINT_MESSAGES,
         "/v1/audio/transcriptions" => metrics_labels::ENDPOINT_AUDIO_TRANSCRIPTIONS,
@@ -1,11 +1,13 @@
 //! Shared utilities for gRPC routers.
 
 mod chat_utils;
+pub(crate) mod finish_reason;
 mod logprobs;
 pub(crate) mod message_utils;
 mod metrics;
 mod parsers;
 pub(crate) mod tonic_ext;
+mod tool_input_json;
 
 // Re-export all public items so consumer imports stay unchanged.
 pub use chat_utils::{create_stop_decoder, process_chat_messages};
@@ -24,6 +26,7 @@ pub(crate) use parsers::{
     check_reasoning_parser_availability, check_tool_parser_availability, create_reasoning_parser,
     create_tool_parser, get_tool_parser, reasoning_parser_requires_special_tokens,
 };
+pub(crate) use tool_input_json::ToolInputJsonValidator;
 // `pub` (not `pub(crate)`) so the Go bindings can reuse the gateway's reasoning
 // detection instead of duplicating it.
 pub use parsers::{resolve_user_thinking, should_mark_reasoning_started};
@@ -2,9 +2,10 @@
 
 use axum::response::Response;
 use http::StatusCode;
+use smg_grpc_client::GrpcClientError;
 use tonic::Code;
 
-use crate::routers::error;
+use crate::routers::{common::retry::RetryHint, error};
 
 /// Extension methods for `tonic::Status`.
 pub(crate) trait TonicStatusExt {
@@ -12,6 +13,8 @@ pub(crate) trait TonicStatusExt {
     fn http_status(&self) -> StatusCode;
 
     /// Convert this gRPC error into an HTTP error response with the appropriate status code.
+    /// The response carries a [`RetryHint`] from the classified backend error, so
+    /// retries skip permanent failures and honor server pushback.
     fn to_http_error(&self, code: &str, msg: String) -> Response;
 }
 
@@ -37,7 +40,13 @@ impl TonicStatusExt for tonic::Status {
     }
 
     fn to_http_error(&self, code: &str, msg: String) -> Response {
-        error::create_error(self.http_status(), code, msg)
+        let classified = GrpcClientError::from(self);
+        let mut response = error::create_error(self.http_status(), code, msg);
+        response.extensions_mut().insert(RetryHint {
+            retryable: classified.is_retryable(),
+            retry_after: classified.retry_after(),
+        });
+        response
     }
 }
 
@@ -0,0 +1,270 @@
+//! Streaming validation of Anthropic `input_json_delta` fragments.
+//!
+//! When a non-Anthropic backend is translated to the Messages API, tool-call
+//! arguments come from a tool parser and are forwarded as `input_json_delta`
+//! fragments. Claude SDK clients feed these into an incremental JSON parser
+//! and fail hard on anything that is not a prefix of a JSON object, so the
+//! translator runs every fragment through [`ToolInputJsonValidator`]:
+//!
+//! - raw control characters inside strings are escaped
+//! - unbalanced closing brackets and data after the closed object are dropped
+//! - fragments that do not open an object are dropped
+//! - at `content_block_stop` an unterminated object is closed off
+//!
+//! Every repair is logged so a misbehaving parser/backend is visible.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use tracing::warn;
+
+/// Partial JSON state for one `tool_use` content block.
+#[derive(Debug, Default)]
+struct PartialToolInput {
+    /// Everything forwarded so far, used to verify the final repair.
+    forwarded: String,
+    /// Closers expected for the currently open containers.
+    closers: Vec<char>,
+    in_string: bool,
+    escaped: bool,
+    /// The top-level object has been opened.
+    started: bool,
+    /// The top-level object has been closed.
+    complete: bool,
+    /// Last non-whitespace character outside a string.
+    last_structural: Option<char>,
+}
+
+impl PartialToolInput {
+    /// Returns the fragment to forward (possibly rewritten) and whether a
+    /// repair was needed.
+    fn push<'a>(&mut self, fragment: &'a str) -> (Cow<'a, str>, Option<&'static str>) {
+        let mut out: Option<String> = None;
+        let mut issue = None;
+
+        for (pos, ch) in fragment.char_indices() {
+            let mut replacement: Option<Cow<'static, str>> = None;
+            let mut keep = true;
+
+            if self.complete {
+                if !ch.is_whitespace() {
+                    keep = false;
+                    issue = Some("data after the end of the input object");
+                }
+            } else if !self.started {
+                if ch == '{' {
+                    self.started = true;
+                    self.closers.push('}');
+                    self.last_structural = Some(ch);
+                } else if !ch.is_whitespace() {
+                    keep = false;
+                    issue = Some("tool input does not start with an object");
+                }
+            } else if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if ch == '\\' {
+                    self.escaped = true;
+                } else if ch == '"' {
+                    self.in_string = false;
+                } else if (ch as u32) < 0x20 {
+                    replacement = Some(escape_control(ch));
+                    issue = Some("unescaped control character in string");
+                }
+            } else {
+                match ch {
+                    '"' => self.in_string = true,
+                    '{' => self.closers.push('}'),
+                    '[' => self.closers.push(']'),
+                    '}' | ']' => {
+                        if self.closers.last() == Some(&ch) {
+                            self.closers.pop();
+                            self.complete = self.closers.is_empty();
+                        } else {
+                            keep = false;
+                            issue = Some("unbalanced closing bracket");
+                        }
+                    }
+                    _ => {}
+                }
+                if keep && !ch.is_whitespace() {
+                    self.last_structural = Some(ch);
+                }
+            }
+
+            if (!keep || replacement.is_some()) && out.is_none() {
+                out = Some(fragment[..pos].to_string());
+            }
+            if let Some(buf) = out.as_mut() {
+                match replacement {
+                    Some(replacement) => buf.push_str(&replacement),
+                    None if keep => buf.push(ch),
+                    None => {}
+                }
+            }
+        }
+
+        let forwarded = out.map_or(Cow::Borrowed(fragment), Cow::Owned);
+        self.forwarded.push_str(&forwarded);
+        (forwarded, issue)
+    }
+
+    /// Suffix that closes an unterminated object, or `None` if nothing needs
+    /// (or can be) appended.
+    fn closing_suffix(&self) -> Option<String> {
+        if !self.started || self.complete {
+            return None;
+        }
+        let mut suffix = String::new();
+        if self.in_string {
+            if self.escaped {
+                suffix.push('\\');
+            }
+            suffix.push('"');
+        } else if self.last_structural == Some(':') {
+            suffix.push_str("null");
+        }
+        suffix.extend(self.closers.iter().rev());
+        Some(suffix)
+    }
+}
+
+fn escape_control(ch: char) -> Cow<'static, str> {
+    match ch {
+        '\n' => Cow::Borrowed("\\n"),
+        '\r' => Cow::Borrowed("\\r"),
+        '\t' => Cow::Borrowed("\\t"),
+        other => Cow::Owned(format!("\\u{:04x}", other as u32)),
+    }
+}
+
+/// Tracks partial tool input JSON per content block index.
+#[derive(Debug, Default)]
+pub(crate) struct ToolInputJsonValidator {
+    blocks: HashMap<u32, PartialToolInput>,
+}
+
+impl ToolInputJsonValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin tracking a `tool_use` block opened by `content_block_start`.
+    pub fn start_block(&mut self, index: u32) {
+        self.blocks.insert(index, PartialToolInput::default());
+    }
+
+    /// Validate an `input_json_delta` fragment for block `index`.
+    ///
+    /// Returns the fragment to forward; an empty result means the delta
+    /// should be dropped.
+    pub fn push_delta<'a>(&mut self, index: u32, partial_json: &'a str) -> Cow<'a, str> {
+        let Some(block) = self.blocks.get_mut(&index) else {
+            warn!(
+                index,
+                "Dropping input_json_delta for a content block that is not an open tool_use block"
+            );
+            return Cow::Borrowed("");
+        };
+        let (forwarded, issue) = block.push(partial_json);
+        if let Some(issue) = issue {
+            warn!(
+                index,
+                issue, "Repaired malformed input_json_delta from backend"
+            );
+        }
+        forwarded
+    }
+
+    /// Stop tracking block `index` at `content_block_stop`.
+    ///
+    /// Returns a final fragment that closes an unterminated input object.
+    pub fn finish_block(&mut self, index: u32) -> Option<String> {
+        let block = self.blocks.remove(&index)?;
+        let suffix = block.closing_suffix();
+        let complete = match &suffix {
+            Some(suffix) => format!("{}{suffix}", block.forwarded),
+            None => block.forwarded,
+        };
+        if !complete.trim().is_empty()
+            && serde_json::from_str::<serde_json::Value>(&complete).is_err()
+        {
+            warn!(
+                index,
+                "Tool input for content block is not valid JSON after repair"
+            );
+        } else if suffix.is_some() {
+            warn!(index, "Closed unterminated tool input JSON from backend");
+        }
+        suffix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(fragments: &[&str]) -> String {
+        let mut validator = ToolInputJsonValidator::new();
+        validator.start_block(1);
+        let mut out = String::new();
+        for fragment in fragments {
+            out.push_str(&validator.push_delta(1, fragment));
+        }
+        if let Some(suffix) = validator.finish_block(1) {
+            out.push_str(&suffix);
+        }
+        out
+    }
+
+    #[test]
+    fn test_well_formed_fragments_pass_through_unchanged() {
+        let mut validator = ToolInputJsonValidator::new();
+        validator.start_block(0);
+        for fragment in [
+            "{\"location\": \"San",
+            " Francisco\", \"units\": [\"c\"",
+            "]}",
+        ] {
+            assert!(matches!(
+                validator.push_delta(0, fragment),
+                Cow::Borrowed(f) if f == fragment
+            ));
+        }
+        assert_eq!(validator.finish_block(0), None);
+    }
+
+    #[test]
+    fn test_escapes_control_characters_in_strings() {
+        assert_eq!(
+            run(&["{\"code\": \"a\n", "\tb\"}"]),
+            "{\"code\": \"a\\n\\tb\"}"
+        );
+    }
+
+    #[test]
+    fn test_drops_unbalanced_and_trailing_data() {
+        assert_eq!(run(&["{\"a\": 1]", "}", " {\"b\": 2}"]), "{\"a\": 1} ");
+        assert_eq!(run(&["hello", "{\"a\": 1}"]), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn test_closes_truncated_input() {
+        for (fragments, expected) in [
+            (&["{\"a\": [1, 2"][..], "{\"a\": [1, 2]}"),
+            (&["{\"a\": \"x"][..], "{\"a\": \"x\"}"),
+            (&["{\"a\": \"x\\"][..], "{\"a\": \"x\\\\\"}"),
+            (&["{\"a\":"][..], "{\"a\":null}"),
+        ] {
+            let repaired = run(fragments);
+            assert_eq!(repaired, expected);
+            assert!(serde_json::from_str::<serde_json::Value>(&repaired).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_drops_delta_for_untracked_block() {
+        let mut validator = ToolInputJsonValidator::new();
+        assert_eq!(validator.push_delta(3, "{}"), "");
+        assert_eq!(validator.finish_block(3), None);
+    }
+}
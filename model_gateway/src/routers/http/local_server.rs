@@ -0,0 +1,407 @@
+//! Request shaping for llama.cpp server and Ollama workers.
+//!
+//! Both speak the OpenAI-compatible API but not SGLang's extensions, and
+//! each has its own gaps:
+//!
+//! - llama.cpp reads `top_k`, `min_p`, `ignore_eos`, `json_schema` and
+//!   `chat_template_kwargs` natively, calls the repetition penalty
+//!   `repeat_penalty`, and samples one choice per request, rejecting `n > 1`
+//!   with an unhelpful error.
+//! - Ollama's OpenAI layer reads only the OpenAI sampling fields and only
+//!   `max_tokens`, not `max_completion_tokens`.
+//!
+//! The SGLang seed extension becomes the OpenAI `seed`; extensions with no
+//! equivalent are dropped rather than forwarded to be ignored.
+//!
+//! Their streams are reshaped to OpenAI's chunk layout as well. llama.cpp
+//! attaches `timings`, and depending on version puts `usage` on the final
+//! content chunk whether or not it was asked for. Ollama only sends usage
+//! when `stream_options.include_usage` is set, and ends tool-call streams
+//! with `finish_reason: "stop"`. Clients get usage in a chunk of its own with
+//! empty `choices`, only when they asked for it, and `tool_calls` as the
+//! finish reason after streamed tool calls.
+//!
+//! Tokenization is not proxied: `/v1/tokenize` and `/v1/detokenize` use the
+//! gateway's own tokenizers, so GGUF models need one registered through
+//! `/v1/tokenizers`. Forwarding to llama.cpp's `/tokenize` (`content` in,
+//! `tokens` out) is a follow-up.
+
+use bytes::Bytes;
+use futures_util::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    routers::{
+        common::sse::{parse_block, SseDecoder},
+        openai::SGLANG_FIELDS,
+    },
+    worker::RuntimeType,
+};
+
+/// Largest single event buffered while reshaping a stream.
+const MAX_EVENT_SIZE: usize = 16 * 1024 * 1024;
+
+/// SGLang extensions llama.cpp reads under the same name.
+const LLAMACPP_NATIVE_FIELDS: &[&str] = &[
+    "top_k",
+    "min_p",
+    "ignore_eos",
+    "json_schema",
+    "chat_template_kwargs",
+];
+
+/// Shape `payload` for a worker of `runtime_type`. Other runtimes are left
+/// untouched. Fails on requests the server cannot serve.
+pub(crate) fn prepare_request(
+    runtime_type: RuntimeType,
+    payload: &mut Value,
+) -> Result<(), String> {
+    let Some(obj) = payload.as_object_mut() else {
+        return Ok(());
+    };
+    match runtime_type {
+        RuntimeType::LlamaCpp => {
+            if obj.get("n").and_then(Value::as_u64).is_some_and(|n| n > 1) {
+                return Err(
+                    "llama.cpp server samples one choice per request; 'n' must be 1".to_string(),
+                );
+            }
+            rename(obj, "repetition_penalty", "repeat_penalty");
+            promote_seed(obj);
+            strip_extensions(obj, LLAMACPP_NATIVE_FIELDS);
+        }
+        RuntimeType::Ollama => {
+            rename(obj, "max_completion_tokens", "max_tokens");
+            promote_seed(obj);
+            strip_extensions(obj, &[]);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Move `from` to `to` unless `to` is already set.
+fn rename(obj: &mut Map<String, Value>, from: &str, to: &str) {
+    let Some(value) = obj.remove(from).filter(|v| !v.is_null()) else {
+        return;
+    };
+    if obj.get(to).is_none_or(Value::is_null) {
+        obj.insert(to.to_string(), value);
+    }
+}
+
+fn promote_seed(obj: &mut Map<String, Value>) {
+    rename(obj, "sampling_seed", "seed");
+}
+
+fn strip_extensions(obj: &mut Map<String, Value>, keep: &[&str]) {
+    for field in SGLANG_FIELDS {
+        if !keep.contains(field) {
+            obj.remove(*field);
+        }
+    }
+}
+
+/// Whether the client asked for a usage chunk at the end of the stream.
+pub(crate) fn include_usage(payload: &Value) -> bool {
+    payload
+        .pointer("/stream_options/include_usage")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Reshape the stream of a worker of `runtime_type` to OpenAI's chunk
+/// layout. Other runtimes are returned untouched.
+pub(crate) fn shape_stream<E: Send + 'static>(
+    runtime_type: RuntimeType,
+    include_usage: bool,
+    upstream: BoxStream<'static, Result<Bytes, E>>,
+) -> BoxStream<'static, Result<Bytes, E>> {
+    if !matches!(runtime_type, RuntimeType::LlamaCpp | RuntimeType::Ollama) {
+        return upstream;
+    }
+    let state = (
+        Some(upstream),
+        SseDecoder::with_max_size(MAX_EVENT_SIZE),
+        ChunkShaper::new(include_usage),
+    );
+    stream::unfold(state, |(upstream, mut decoder, mut shaper)| async move {
+        let mut upstream = upstream?;
+        loop {
+            match upstream.next().await {
+                Some(Ok(chunk)) if shaper.passthrough => {
+                    return Some((Ok(chunk), (Some(upstream), decoder, shaper)));
+                }
+                Some(Ok(chunk)) => {
+                    if decoder.push(&chunk).is_err() {
+                        // An oversized event: forward the rest as it comes.
+                        shaper.passthrough = true;
+                        let mut out = decoder
+                            .flush_block()
+                            .and_then(Result::ok)
+                            .unwrap_or_default()
+                            .into_bytes();
+                        out.extend_from_slice(&chunk);
+                        return Some((Ok(Bytes::from(out)), (Some(upstream), decoder, shaper)));
+                    }
+                    let mut out = String::new();
+                    while let Some(Ok(block)) = decoder.next_block() {
+                        shaper.shape_block(&block, &mut out);
+                    }
+                    decoder.compact();
+                    if !out.is_empty() {
+                        return Some((Ok(Bytes::from(out)), (Some(upstream), decoder, shaper)));
+                    }
+                }
+                Some(Err(e)) => return Some((Err(e), (None, decoder, shaper))),
+                None => {
+                    let mut out = String::new();
+                    if let Some(Ok(block)) = decoder.flush_block() {
+                        shaper.shape_block(&block, &mut out);
+                    }
+                    return (!out.is_empty())
+                        .then(|| (Ok(Bytes::from(out)), (None, decoder, shaper)));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Rewrites llama.cpp and Ollama stream chunks one event at a time.
+struct ChunkShaper {
+    include_usage: bool,
+    saw_tool_calls: bool,
+    /// Set once an event outgrows the decoder; the rest is not reshaped.
+    passthrough: bool,
+}
+
+impl ChunkShaper {
+    fn new(include_usage: bool) -> Self {
+        Self {
+            include_usage,
+            saw_tool_calls: false,
+            passthrough: false,
+        }
+    }
+
+    /// Append the reshaped form of one SSE block to `out`. Comments, named
+    /// events and non-JSON data pass through unchanged.
+    fn shape_block(&mut self, block: &str, out: &mut String) {
+        let chunks = match parse_block(block) {
+            Some(frame) if frame.event_type.is_none() => self.shape_data(&frame.data),
+            _ => None,
+        };
+        match chunks {
+            Some(chunks) => {
+                for chunk in chunks {
+                    out.push_str("data: ");
+                    out.push_str(&chunk);
+                    out.push_str("\n\n");
+                }
+            }
+            None => {
+                out.push_str(block);
+                out.push_str("\n\n");
+            }
+        }
+    }
+
+    /// The chunks to send in place of one `data:` payload, or `None` to
+    /// forward it unchanged.
+    fn shape_data(&mut self, data: &str) -> Option<Vec<String>> {
+        let Ok(Value::Object(mut chunk)) = serde_json::from_str(data) else {
+            return None;
+        };
+        chunk.remove("timings");
+        let usage = chunk.remove("usage").filter(|usage| !usage.is_null());
+
+        let mut has_choices = false;
+        if let Some(choices) = chunk.get_mut("choices").and_then(Value::as_array_mut) {
+            has_choices = !choices.is_empty();
+            for choice in choices {
+                if choice
+                    .pointer("/delta/tool_calls")
+                    .is_some_and(|calls| !calls.is_null())
+                {
+                    self.saw_tool_calls = true;
+                }
+                if self.saw_tool_calls
+                    && choice.get("finish_reason").and_then(Value::as_str) == Some("stop")
+                {
+                    choice["finish_reason"] = json!("tool_calls");
+                }
+            }
+        }
+
+        let Some(usage) = usage else {
+            return Some(vec![Value::Object(chunk).to_string()]);
+        };
+        let mut chunks = Vec::with_capacity(2);
+        if has_choices {
+            chunks.push(Value::Object(chunk.clone()).to_string());
+        }
+        if self.include_usage {
+            chunk.insert("choices".to_string(), json!([]));
+            chunk.insert("usage".to_string(), usage);
+            chunks.push(Value::Object(chunk).to_string());
+        }
+        Some(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn llamacpp_keeps_native_fields_and_renames_penalty() {
+        let mut payload = json!({
+            "model": "m",
+            "top_k": 40,
+            "min_p": 0.05,
+            "repetition_penalty": 1.1,
+            "sampling_seed": 7,
+            "regex": "a+",
+            "separate_reasoning": true,
+        });
+        prepare_request(RuntimeType::LlamaCpp, &mut payload).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "model": "m",
+                "top_k": 40,
+                "min_p": 0.05,
+                "repeat_penalty": 1.1,
+                "seed": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn llamacpp_rejects_multiple_choices() {
+        let mut payload = json!({"model": "m", "n": 2});
+        assert!(prepare_request(RuntimeType::LlamaCpp, &mut payload).is_err());
+        let mut payload = json!({"model": "m", "n": 1});
+        assert!(prepare_request(RuntimeType::LlamaCpp, &mut payload).is_ok());
+    }
+
+    #[test]
+    fn ollama_reads_max_tokens_and_openai_seed() {
+        let mut payload = json!({
+            "model": "m",
+            "max_completion_tokens": 64,
+            "sampling_seed": 7,
+            "seed": 3,
+            "top_k": 40,
+        });
+        prepare_request(RuntimeType::Ollama, &mut payload).unwrap();
+        assert_eq!(payload, json!({"model": "m", "max_tokens": 64, "seed": 3}));
+    }
+
+    async fn shaped(runtime_type: RuntimeType, include_usage: bool, chunks: &[&str]) -> String {
+        let upstream = stream::iter(
+            chunks
+                .iter()
+                .map(|c| Ok::<_, String>(Bytes::copy_from_slice(c.as_bytes())))
+                .collect::<Vec<_>>(),
+        )
+        .boxed();
+        let out: Vec<_> = shape_stream(runtime_type, include_usage, upstream)
+            .collect()
+            .await;
+        out.into_iter()
+            .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+            .collect()
+    }
+
+    const LLAMACPP_FINAL: &str = concat!(
+        r#"data: {"id":"c","choices":[{"index":0,"delta":{},"finish_reason":"stop"}],"#,
+        r#""usage":{"prompt_tokens":3,"completion_tokens":2,"total_tokens":5},"#,
+        r#""timings":{"predicted_ms":12.5}}"#,
+        "\n\n"
+    );
+
+    #[tokio::test]
+    async fn llamacpp_usage_split_into_its_own_chunk_when_requested() {
+        let out = shaped(
+            RuntimeType::LlamaCpp,
+            true,
+            &[LLAMACPP_FINAL, "data: [DONE]\n\n"],
+        )
+        .await;
+        let events: Vec<&str> = out
+            .split_terminator("\n\n")
+            .map(|event| event.strip_prefix("data: ").unwrap())
+            .collect();
+        assert_eq!(events.len(), 3, "{out}");
+        let finish: Value = serde_json::from_str(events[0]).unwrap();
+        assert_eq!(
+            finish,
+            json!({"id": "c", "choices": [{"index": 0, "delta": {}, "finish_reason": "stop"}]})
+        );
+        let usage: Value = serde_json::from_str(events[1]).unwrap();
+        assert_eq!(usage["choices"], json!([]));
+        assert_eq!(usage["usage"]["total_tokens"], 5);
+        assert_eq!(events[2], "[DONE]");
+    }
+
+    #[tokio::test]
+    async fn unrequested_usage_is_dropped() {
+        let usage_only = "data: {\"id\":\"c\",\"choices\":[],\"usage\":{\"total_tokens\":5}}\n\n";
+        let out = shaped(
+            RuntimeType::Ollama,
+            false,
+            &[LLAMACPP_FINAL, usage_only, "data: [DONE]\n\n"],
+        )
+        .await;
+        assert!(!out.contains("usage"), "{out}");
+        assert!(!out.contains("timings"), "{out}");
+        assert!(out.ends_with("data: [DONE]\n\n"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn ollama_tool_call_stream_finishes_with_tool_calls() {
+        let out = shaped(
+            RuntimeType::Ollama,
+            false,
+            &[
+                // Split mid-event, with CRLF line endings.
+                "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,",
+                "\"function\":{\"name\":\"f\",\"arguments\":\"{}\"}}]}}]}\r\n\r\n",
+                "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: [DONE]",
+            ],
+        )
+        .await;
+        assert!(out.contains(r#""finish_reason":"tool_calls""#), "{out}");
+        assert!(out.ends_with("data: [DONE]\n\n"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn other_runtime_streams_untouched() {
+        let out = shaped(RuntimeType::Sglang, false, &[LLAMACPP_FINAL]).await;
+        assert_eq!(out, LLAMACPP_FINAL);
+    }
+
+    #[test]
+    fn reads_include_usage() {
+        assert!(include_usage(
+            &json!({"stream_options": {"include_usage": true}})
+        ));
+        assert!(!include_usage(&json!({"stream": true})));
+    }
+
+    #[test]
+    fn other_runtimes_untouched() {
+        let mut payload = json!({"model": "m", "top_k": 40, "n": 4});
+        let original = payload.clone();
+        prepare_request(RuntimeType::Sglang, &mut payload).unwrap();
+        assert_eq!(payload, original);
+    }
+}
@@ -1,5 +1,8 @@
 //! HTTP router implementations
 
+pub(crate) mod local_server;
+pub mod pd_pairs;
 pub mod pd_router;
 pub mod pd_types;
 pub mod router;
+pub(crate) mod stream_recovery;
@@ -0,0 +1,385 @@
+//! Per-pair latency accounting for prefill-decode disaggregation.
+//!
+//! Every PD request is recorded against its (prefill, decode) worker pair:
+//! the time to the decode response head, which spans the prefill and the KV
+//! transfer between the two workers, and whether the request failed.
+//! Samples older than `pd_pairs.window_secs` are dropped.
+//!
+//! [`PdPairTracker`] periodically compares each pair against the fleet. A
+//! pair is flagged slow when its median latency exceeds
+//! `pd_pairs.slow_factor` times the median across all pairs, or when its
+//! error rate exceeds `pd_pairs.max_error_rate`; either needs at least
+//! `pd_pairs.min_samples` samples. Consistently slow pairings, such as
+//! cross-rack ones, stand out this way even when both workers are fine on
+//! their own. Unless `pd_pairs.avoid_slow_pairs` is off, the PD router then
+//! prefers any other available decode worker for that prefill worker. An
+//! avoided pair stops collecting samples, so it ages out of the window and
+//! is tried again.
+//!
+//! Findings are listed by `GET /admin/pd/pairs`.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::{config::PdPairsConfig, observability::metrics::Metrics, worker::Worker};
+
+/// How often pairs are re-evaluated.
+const EVALUATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// `(prefill_url, decode_url)`
+type PairKey = (String, String);
+
+#[derive(Default)]
+struct PairWindow {
+    latencies: VecDeque<(Instant, f64)>,
+    outcomes: VecDeque<(Instant, bool)>,
+}
+
+impl PairWindow {
+    fn prune(&mut self, cutoff: Instant) {
+        while self.latencies.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.latencies.pop_front();
+        }
+        while self.outcomes.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.outcomes.pop_front();
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.latencies.is_empty() && self.outcomes.is_empty()
+    }
+}
+
+/// Why a pair is flagged slow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowPairReason {
+    Latency,
+    ErrorRate,
+}
+
+/// One pair's window, as reported by `GET /admin/pd/pairs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairReport {
+    pub prefill_url: String,
+    pub decode_url: String,
+    pub requests: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p50_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95_ms: Option<f64>,
+    pub slow: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<SlowPairReason>,
+}
+
+/// The result of one evaluation.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairFindings {
+    /// Median of the per-pair median latencies; `None` until some pair has
+    /// `min_samples` latencies.
+    pub baseline_p50_ms: Option<f64>,
+    /// Slow pairs first, then by median latency, slowest first.
+    pub pairs: Vec<PairReport>,
+}
+
+/// `q`-quantile of an ascending slice.
+fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    sorted.get(((last as f64) * q).round() as usize).copied()
+}
+
+/// Records PD pair latencies and flags consistently slow pairs.
+pub struct PdPairTracker {
+    config: PdPairsConfig,
+    window: Duration,
+    pairs: DashMap<PairKey, PairWindow>,
+    slow: RwLock<HashSet<PairKey>>,
+}
+
+impl std::fmt::Debug for PdPairTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PdPairTracker")
+            .field("config", &self.config)
+            .field("pairs", &self.pairs.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PdPairTracker {
+    pub fn new(config: &PdPairsConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config: config.clone(),
+            window: Duration::from_secs(config.window_secs.max(1)),
+            pairs: DashMap::new(),
+            slow: RwLock::new(HashSet::new()),
+        })
+    }
+
+    /// Spawn the evaluation loop. It holds only a `Weak<Self>` and exits
+    /// when the tracker is dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the tracker is dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(EVALUATE_INTERVAL);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(tracker) = weak.upgrade() else {
+                    break;
+                };
+                tracker.evaluate();
+            }
+            debug!("PdPairTracker loop exited");
+        });
+    }
+
+    /// Record the time from dispatch to the decode response head.
+    pub fn record_latency(&self, prefill_url: &str, decode_url: &str, latency: Duration) {
+        Metrics::record_pd_pair_latency(prefill_url, decode_url, latency);
+        self.pairs
+            .entry((prefill_url.to_string(), decode_url.to_string()))
+            .or_default()
+            .latencies
+            .push_back((Instant::now(), latency.as_secs_f64()));
+    }
+
+    /// Record whether a request through the pair succeeded.
+    pub fn record_outcome(&self, prefill_url: &str, decode_url: &str, success: bool) {
+        Metrics::record_pd_pair_request(prefill_url, decode_url, success);
+        self.pairs
+            .entry((prefill_url.to_string(), decode_url.to_string()))
+            .or_default()
+            .outcomes
+            .push_back((Instant::now(), success));
+    }
+
+    pub fn is_slow(&self, prefill_url: &str, decode_url: &str) -> bool {
+        self.slow
+            .read()
+            .contains(&(prefill_url.to_string(), decode_url.to_string()))
+    }
+
+    /// Drop the decode workers paired slowly with `prefill_url`, unless
+    /// that leaves no available decode worker.
+    pub fn preferred_decodes(
+        &self,
+        prefill_url: &str,
+        decodes: Vec<Arc<dyn Worker>>,
+    ) -> Vec<Arc<dyn Worker>> {
+        if !self.config.avoid_slow_pairs {
+            return decodes;
+        }
+        let slow = self.slow.read();
+        if slow.is_empty() {
+            return decodes;
+        }
+        let is_fast = |decode: &Arc<dyn Worker>| {
+            !slow.contains(&(prefill_url.to_string(), decode.url().to_string()))
+        };
+        if decodes.iter().all(is_fast)
+            || !decodes
+                .iter()
+                .any(|decode| is_fast(decode) && decode.is_available())
+        {
+            return decodes;
+        }
+        Metrics::record_pd_slow_pair_avoided();
+        decodes.into_iter().filter(is_fast).collect()
+    }
+
+    /// Prune the windows, re-flag slow pairs and report every pair.
+    pub fn evaluate(&self) -> PairFindings {
+        let cutoff = Instant::now().checked_sub(self.window);
+        let min_samples = self.config.min_samples.max(1);
+
+        let mut pairs = Vec::with_capacity(self.pairs.len());
+        self.pairs.retain(|(prefill_url, decode_url), window| {
+            if let Some(cutoff) = cutoff {
+                window.prune(cutoff);
+            }
+            if window.is_empty() {
+                return false;
+            }
+            let mut latencies: Vec<f64> = window.latencies.iter().map(|(_, l)| *l).collect();
+            latencies.sort_by(f64::total_cmp);
+            let errors = window.outcomes.iter().filter(|(_, ok)| !ok).count();
+            let requests = window.outcomes.len();
+            pairs.push((
+                PairReport {
+                    prefill_url: prefill_url.clone(),
+                    decode_url: decode_url.clone(),
+                    requests,
+                    errors,
+                    error_rate: if requests == 0 {
+                        0.0
+                    } else {
+                        errors as f64 / requests as f64
+                    },
+                    p50_ms: quantile(&latencies, 0.5).map(|s| s * 1000.0),
+                    p95_ms: quantile(&latencies, 0.95).map(|s| s * 1000.0),
+                    slow: false,
+                    reason: None,
+                },
+                latencies.len(),
+            ));
+            true
+        });
+
+        let mut medians: Vec<f64> = pairs
+            .iter()
+            .filter(|(_, latencies)| *latencies >= min_samples)
+            .filter_map(|(report, _)| report.p50_ms)
+            .collect();
+        medians.sort_by(f64::total_cmp);
+        let baseline_p50_ms = quantile(&medians, 0.5);
+
+        let mut slow = HashSet::new();
+        for (report, latencies) in &mut pairs {
+            let slow_latency = *latencies >= min_samples
+                && matches!(
+                    (report.p50_ms, baseline_p50_ms),
+                    (Some(p50), Some(baseline)) if p50 > baseline * self.config.slow_factor
+                );
+            let high_errors =
+                report.requests >= min_samples && report.error_rate > self.config.max_error_rate;
+            report.reason = if high_errors {
+                Some(SlowPairReason::ErrorRate)
+            } else if slow_latency {
+                Some(SlowPairReason::Latency)
+            } else {
+                None
+            };
+            report.slow = report.reason.is_some();
+            if report.slow {
+                slow.insert((report.prefill_url.clone(), report.decode_url.clone()));
+            }
+        }
+
+        let mut current = self.slow.write();
+        for (prefill_url, decode_url) in slow.difference(&current) {
+            info!(
+                prefill_url = %prefill_url,
+                decode_url = %decode_url,
+                "Flagged slow PD pair"
+            );
+        }
+        Metrics::set_pd_slow_pairs(slow.len());
+        *current = slow;
+        drop(current);
+
+        let mut pairs: Vec<PairReport> = pairs.into_iter().map(|(report, _)| report).collect();
+        pairs.sort_by(|a, b| {
+            b.slow
+                .cmp(&a.slow)
+                .then_with(|| b.p50_ms.unwrap_or(0.0).total_cmp(&a.p50_ms.unwrap_or(0.0)))
+        });
+        PairFindings {
+            baseline_p50_ms,
+            pairs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::worker::WorkerStatus;
+
+    use super::*;
+    use crate::worker::{BasicWorkerBuilder, WorkerType};
+
+    fn config() -> PdPairsConfig {
+        PdPairsConfig {
+            min_samples: 5,
+            ..Default::default()
+        }
+    }
+
+    fn record(tracker: &PdPairTracker, prefill: &str, decode: &str, ms: u64, success: bool) {
+        for _ in 0..5 {
+            tracker.record_latency(prefill, decode, Duration::from_millis(ms));
+            tracker.record_outcome(prefill, decode, success);
+        }
+    }
+
+    #[test]
+    fn test_slow_pairs_are_flagged_against_the_fleet() {
+        let tracker = PdPairTracker::new(&config());
+        record(&tracker, "http://p1", "http://d1", 100, true);
+        record(&tracker, "http://p1", "http://d2", 110, true);
+        record(&tracker, "http://p2", "http://d1", 105, true);
+        record(&tracker, "http://p2", "http://d2", 400, true);
+        record(&tracker, "http://p3", "http://d1", 90, false);
+
+        let findings = tracker.evaluate();
+        assert_eq!(findings.baseline_p50_ms, Some(105.0));
+        assert_eq!(findings.pairs.len(), 5);
+        let slow: Vec<_> = findings
+            .pairs
+            .iter()
+            .filter(|p| p.slow)
+            .map(|p| (p.prefill_url.as_str(), p.decode_url.as_str(), p.reason))
+            .collect();
+        assert_eq!(
+            slow,
+            vec![
+                ("http://p2", "http://d2", Some(SlowPairReason::Latency)),
+                ("http://p3", "http://d1", Some(SlowPairReason::ErrorRate)),
+            ]
+        );
+        assert!(tracker.is_slow("http://p2", "http://d2"));
+        assert!(!tracker.is_slow("http://p1", "http://d2"));
+    }
+
+    #[test]
+    fn test_preferred_decodes_skip_slow_pairs_while_alternatives_exist() {
+        let tracker = PdPairTracker::new(&config());
+        record(&tracker, "http://p1", "http://d1", 100, true);
+        record(&tracker, "http://p2", "http://d1", 100, true);
+        record(&tracker, "http://p1", "http://d2", 500, true);
+        tracker.evaluate();
+
+        let decode = |url: &str, status: WorkerStatus| -> Arc<dyn Worker> {
+            Arc::new(
+                BasicWorkerBuilder::new(url)
+                    .worker_type(WorkerType::Decode)
+                    .status(status)
+                    .build(),
+            )
+        };
+        let d1 = decode("http://d1", WorkerStatus::Ready);
+        let d2 = decode("http://d2", WorkerStatus::Ready);
+
+        let preferred = tracker.preferred_decodes("http://p1", vec![d1.clone(), d2.clone()]);
+        assert_eq!(preferred.len(), 1);
+        assert_eq!(preferred[0].url(), "http://d1");
+        // Other prefill workers keep the pairing.
+        assert_eq!(
+            tracker
+                .preferred_decodes("http://p2", vec![d1.clone(), d2.clone()])
+                .len(),
+            2
+        );
+
+        // The slow pair is still used when it is the only way through.
+        d1.set_status(WorkerStatus::NotReady);
+        assert_eq!(
+            tracker.preferred_decodes("http://p1", vec![d1, d2]).len(),
+            2
+        );
+    }
+}
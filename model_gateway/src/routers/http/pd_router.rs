@@ -25,8 +25,8 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, warn};
 
 use crate::{
-    config::types::RetryConfig,
-    middleware::TenantRequestMeta,
+    config::types::{RetryConfig, StreamRecoveryConfig},
+    middleware::{request_features, TenantRequestMeta},
     observability::{
         events::{self, Event},
         metrics::{bool_to_static_str, metrics_labels, Metrics},
@@ -35,24 +35,31 @@ use crate::{
     policies::{LoadBalancingPolicy, PolicyRegistry, SelectWorkerInfo},
     routers::{
         common::{
+            bootstrap_rooms::{bootstrap_rooms, RoomLease, RoomWidth},
             header_utils,
-            retry::{is_retryable_status, RetryExecutor},
+            retry::{await_first_chunk, is_retryable_status, RetryExecutor},
             sse::SseEncoder,
         },
         error,
         grpc::utils::{error_type_from_status, route_to_endpoint},
+        http::{
+            pd_pairs::PdPairTracker,
+            stream_recovery::{self, ResumeDispatch, StreamResumer, UpstreamStream},
+        },
         RouterTrait,
     },
     worker::{HashRing, Worker, WorkerLoadGuard, WorkerRegistry, WorkerType, UNKNOWN_MODEL_ID},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PDRouter {
     pub worker_registry: Arc<WorkerRegistry>,
     pub policy_registry: Arc<PolicyRegistry>,
     pub client: Client,
     pub retry_config: RetryConfig,
     pub api_key: Option<String>,
+    pub pd_pairs: Arc<PdPairTracker>,
+    pub stream_recovery: StreamRecoveryConfig,
 }
 
 #[derive(Clone)]
@@ -169,6 +176,8 @@ impl PDRouter {
             client: ctx.client.clone(),
             retry_config: ctx.router_config.effective_retry_config(),
             api_key: ctx.router_config.api_key.clone(),
+            pd_pairs: Arc::clone(&ctx.pd_pairs),
+            stream_recovery: ctx.router_config.stream_recovery.clone(),
         })
     }
 
@@ -218,15 +227,19 @@ impl PDRouter {
     const BOOTSTRAP_PORT_KEY: &'static str = "bootstrap_port";
     const BOOTSTRAP_ROOM_KEY: &'static str = "bootstrap_room";
 
+    /// Returns the request with bootstrap fields and the leases on its rooms,
+    /// which stay open until the prefill leg is done.
     fn inject_bootstrap_into_value(
         mut original: Value,
         prefill_worker: &dyn Worker,
         batch_size: Option<usize>,
-    ) -> Result<Value, String> {
+    ) -> Result<(Value, Vec<RoomLease>), String> {
         let obj = original
             .as_object_mut()
             .ok_or_else(|| "Request must be a JSON object".to_string())?;
 
+        let allocate_room = || bootstrap_rooms().allocate(RoomWidth::Bits63, prefill_worker.url());
+        let mut leases = Vec::with_capacity(batch_size.unwrap_or(1));
         if let Some(n) = batch_size {
             let mut hosts = Vec::with_capacity(n);
             let mut ports = Vec::with_capacity(n);
@@ -234,7 +247,9 @@ impl PDRouter {
             for _ in 0..n {
                 hosts.push(prefill_worker.bootstrap_host());
                 ports.push(prefill_worker.bootstrap_port());
-                rooms.push(super::pd_types::generate_room_id());
+                let lease = allocate_room();
+                rooms.push(lease.room());
+                leases.push(lease);
             }
             obj.insert(
                 Self::BOOTSTRAP_HOST_KEY.to_string(),
@@ -268,12 +283,14 @@ impl PDRouter {
                     None => Value::Null,
                 },
             );
+            let lease = allocate_room();
             obj.insert(
                 Self::BOOTSTRAP_ROOM_KEY.to_string(),
-                Value::from(super::pd_types::generate_room_id()),
+                Value::from(lease.room()),
             );
+            leases.push(lease);
         }
-        Ok(original)
+        Ok((original, leases))
     }
 
     fn inject_dp_rank_to_json(json_val: &mut Value, rank: isize, rank_key: &str) {
@@ -282,6 +299,88 @@ impl PDRouter {
         }
     }
 
+    /// Route both legs to their DP ranks: the pair's own ranks, overridden
+    /// by the DP-rank policy when one is configured.
+    fn inject_dp_ranks(
+        &self,
+        prefill: &dyn Worker,
+        decode: &dyn Worker,
+        request_text: Option<&str>,
+        prefill_json_request: &mut Value,
+        decode_json_request: &mut Value,
+    ) {
+        let mut prefill_rank = prefill.dp_rank().map(|rank| rank as isize);
+        let mut decode_rank = decode.dp_rank().map(|rank| rank as isize);
+
+        let dp_rank_policy_opt = self.policy_registry.get_dp_rank_policy();
+        if let Some(dp_rank_policy) = dp_rank_policy_opt.as_ref() {
+            let estimated_cost: isize = match request_text {
+                Some(text) => {
+                    // Calculate token count using a simple heuristic
+                    // In a real implementation, we would use the tokenizer
+                    // For now, use a simple words-to-tokens ratio
+                    let word_count = text.split_whitespace().count();
+                    // Assume average 1.3 tokens per word
+                    let token_count = (word_count as f64 * 1.3).ceil() as isize;
+                    token_count.max(1)
+                }
+                None => 1, // Use at least 1 to avoid no-op
+            };
+            let policy_prefill_rank = dp_rank_policy.select_dp_rank(prefill, estimated_cost);
+            let policy_decode_rank = dp_rank_policy.select_dp_rank(decode, estimated_cost);
+            if let Some(rank) = policy_prefill_rank {
+                prefill_rank = Some(rank);
+            }
+            if let Some(rank) = policy_decode_rank {
+                decode_rank = Some(rank);
+            }
+        }
+
+        if let Some(p_rank) = prefill_rank {
+            Self::inject_dp_rank_to_json(prefill_json_request, p_rank, "routed_dp_rank");
+            Self::inject_dp_rank_to_json(decode_json_request, p_rank, "disagg_prefill_dp_rank");
+        }
+        if let Some(d_rank) = decode_rank {
+            Self::inject_dp_rank_to_json(decode_json_request, d_rank, "routed_dp_rank");
+        }
+        if prefill_rank.is_some() || decode_rank.is_some() {
+            debug!(
+                "PD selected DP ranks prefill={:?} decode={:?}",
+                prefill_rank, decode_rank
+            );
+        }
+    }
+
+    /// Resumer that moves a dropped stream to a fresh prefill/decode pair,
+    /// when stream recovery applies to the request. `request` is the
+    /// client's request before bootstrap injection. Logprob requests are not
+    /// resumed: their prefill logprobs are merged into the first decode leg.
+    fn stream_resumer(
+        &self,
+        context: &PDRequestContext<'_>,
+        request: &Value,
+    ) -> Option<StreamResumer> {
+        let headers = context.headers.as_ref();
+        let recover = self.stream_recovery.enabled_for(context.model_id)
+            || (self.stream_recovery.max_resumes > 0
+                && request_features::feature_enabled(headers, request_features::FEATURE_RECOVERY));
+        if !context.is_stream || context.return_logprob || !recover {
+            return None;
+        }
+        let dispatch = PdResumeDispatch {
+            router: self.clone(),
+            model_id: context.model_id.to_string(),
+            headers: context.headers.clone(),
+        };
+        StreamResumer::new(
+            context.route,
+            request,
+            Box::new(dispatch),
+            self.worker_registry.clone(),
+            self.stream_recovery.max_resumes,
+        )
+    }
+
     async fn execute_dual_dispatch<T: Serialize + Clone>(
         &self,
         headers: Option<&HeaderMap>,
@@ -326,6 +425,7 @@ impl PDRouter {
                                 context.request_text.as_deref(),
                                 context.model_id,
                                 context.headers.as_ref(),
+                                &[],
                             )
                             .await
                         {
@@ -342,17 +442,18 @@ impl PDRouter {
                             decode.url()
                         );
 
-                        let mut json_request = match serde_json::to_value(shared_request.as_ref()) {
+                        let json_request = match serde_json::to_value(shared_request.as_ref()) {
                             Ok(v) => v,
                             Err(e) => return Self::handle_serialization_error(e),
                         };
+                        let resumer = self.stream_resumer(&context, &json_request);
 
-                        json_request = match Self::inject_bootstrap_into_value(
+                        let (json_request, rooms) = match Self::inject_bootstrap_into_value(
                             json_request,
                             prefill.as_ref(),
                             context.batch_size,
                         ) {
-                            Ok(v) => v,
+                            Ok(injected) => injected,
                             Err(e) => {
                                 Metrics::record_pd_bootstrap_failure();
                                 return Self::handle_serialization_error(e);
@@ -361,61 +462,13 @@ impl PDRouter {
 
                         let mut prefill_json_request = json_request.clone();
                         let mut decode_json_request = json_request;
-
-                        let mut prefill_rank = prefill.dp_rank().map(|rank| rank as isize);
-                        let mut decode_rank = decode.dp_rank().map(|rank| rank as isize);
-
-                        let dp_rank_policy_opt = self.policy_registry.get_dp_rank_policy();
-                        if let Some(dp_rank_policy) = dp_rank_policy_opt.as_ref() {
-                            let estimated_cost: isize = match context.request_text.as_ref() {
-                                Some(text) => {
-                                    // Calculate token count using a simple heuristic
-                                    // In a real implementation, we would use the tokenizer
-                                    // For now, use a simple words-to-tokens ratio
-                                    let word_count = text.split_whitespace().count();
-                                    // Assume average 1.3 tokens per word
-                                    let token_count = (word_count as f64 * 1.3).ceil() as isize;
-                                    token_count.max(1)
-                                }
-                                None => 1, // Use at least 1 to avoid no-op
-                            };
-                            let policy_prefill_rank =
-                                dp_rank_policy.select_dp_rank(prefill.as_ref(), estimated_cost);
-                            let policy_decode_rank =
-                                dp_rank_policy.select_dp_rank(decode.as_ref(), estimated_cost);
-                            if let Some(rank) = policy_prefill_rank {
-                                prefill_rank = Some(rank);
-                            }
-                            if let Some(rank) = policy_decode_rank {
-                                decode_rank = Some(rank);
-                            }
-                        }
-
-                        if let Some(p_rank) = prefill_rank {
-                            Self::inject_dp_rank_to_json(
-                                &mut prefill_json_request,
-                                p_rank,
-                                "routed_dp_rank",
-                            );
-                            Self::inject_dp_rank_to_json(
-                                &mut decode_json_request,
-                                p_rank,
-                                "disagg_prefill_dp_rank",
-                            );
-                        }
-                        if let Some(d_rank) = decode_rank {
-                            Self::inject_dp_rank_to_json(
-                                &mut decode_json_request,
-                                d_rank,
-                                "routed_dp_rank",
-                            );
-                        }
-                        if prefill_rank.is_some() || decode_rank.is_some() {
-                            debug!(
-                                "PD selected DP ranks prefill={:?} decode={:?}",
-                                prefill_rank, decode_rank
-                            );
-                        }
+                        self.inject_dp_ranks(
+                            prefill.as_ref(),
+                            decode.as_ref(),
+                            context.request_text.as_deref(),
+                            &mut prefill_json_request,
+                            &mut decode_json_request,
+                        );
 
                         let response = self
                             .execute_dual_dispatch_internal(
@@ -425,12 +478,19 @@ impl PDRouter {
                                 context,
                                 Arc::clone(&prefill),
                                 Arc::clone(&decode),
+                                rooms,
+                                resumer,
                             )
                             .await;
 
                         let status = response.status();
                         prefill.record_outcome(status.as_u16());
                         decode.record_outcome(status.as_u16());
+                        self.pd_pairs.record_outcome(
+                            prefill.url(),
+                            decode.url(),
+                            !status.is_server_error(),
+                        );
 
                         // Record worker errors for server errors (5xx)
                         if status.is_server_error() {
@@ -530,6 +590,7 @@ impl PDRouter {
                 Some(decode_url),
                 Some(response_headers),
                 load_guards,
+                None,
             )
         } else {
             // Handle non-streaming error response
@@ -604,6 +665,7 @@ impl PDRouter {
     }
 
     // Internal method that performs the actual dual dispatch (without retry logic)
+    #[expect(clippy::too_many_arguments)]
     async fn execute_dual_dispatch_internal(
         &self,
         headers: Option<&HeaderMap>,
@@ -612,6 +674,8 @@ impl PDRouter {
         context: PDRequestContext<'_>,
         prefill: Arc<dyn Worker>,
         decode: Arc<dyn Worker>,
+        rooms: Vec<RoomLease>,
+        resumer: Option<StreamResumer>,
     ) -> Response {
         let load_guards = vec![
             WorkerLoadGuard::new(prefill.clone(), headers),
@@ -712,6 +776,8 @@ impl PDRouter {
             runtime,
             decode_head_elapsed,
         );
+        self.pd_pairs
+            .record_latency(prefill.url(), decode.url(), decode_head_elapsed);
 
         // Process prefill response
         let prefill_drain_start = Instant::now();
@@ -722,6 +788,8 @@ impl PDRouter {
             Ok((_, body)) => body,
             Err(error_response) => return error_response,
         };
+        // The prefill leg is done, and with it the bootstrap.
+        drop(rooms);
 
         // Prefill RPC duration: prefill-head elapsed + body drain, independent
         // of decode so a slower decode head never inflates it.
@@ -746,14 +814,27 @@ impl PDRouter {
             let response_headers =
                 header_utils::preserve_response_headers(decode_response.headers());
 
+            // Replay-safe until the first decode byte reaches the client.
+            let decode_stream = match await_first_chunk(decode_response.bytes_stream()).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Decode stream failed before first byte: {e}");
+                    return error::bad_gateway(
+                        "stream_failed_before_first_byte",
+                        format!("Decode stream failed before sending data: {e}"),
+                    );
+                }
+            };
+
             self.create_streaming_response(
-                decode_response.bytes_stream(),
+                decode_stream,
                 status,
                 prefill_logprobs,
                 context.return_logprob,
-                None,
+                Some(decode.url().to_string()),
                 Some(response_headers),
                 load_guards,
+                resumer,
             )
         } else {
             // Non-streaming response
@@ -801,6 +882,7 @@ impl PDRouter {
         request_text: Option<&str>,
         model_id: &str,
         headers: Option<&HeaderMap>,
+        failed_decodes: &[String],
     ) -> Result<(Arc<dyn Worker>, Arc<dyn Worker>), String> {
         debug!("Selecting PD pair: model_id={:?}", model_id);
 
@@ -828,11 +910,17 @@ impl PDRouter {
                 .get_by_model(model_id)
                 .iter()
                 .filter(|w| matches!(w.worker_type(), WorkerType::Decode))
+                .filter(|w| !failed_decodes.iter().any(|url| url == w.url()))
                 .cloned()
                 .collect();
             if by_model.is_empty() && is_unknown_model {
                 // Only fall back to all workers when model is "unknown" (wildcard)
-                self.worker_registry.get_decode_workers().to_vec()
+                self.worker_registry
+                    .get_decode_workers()
+                    .iter()
+                    .filter(|w| !failed_decodes.iter().any(|url| url == w.url()))
+                    .cloned()
+                    .collect()
             } else {
                 by_model
             }
@@ -854,6 +942,11 @@ impl PDRouter {
             crate::policies::WorkerLeg::Prefill,
         )?;
 
+        // Pair the chosen prefill worker with a decode worker it is not
+        // known to be slow with.
+        let decode_workers = self
+            .pd_pairs
+            .preferred_decodes(prefill.url(), decode_workers);
         let decode = self.pick_worker_by_policy_arc(
             &decode_workers,
             &decode_policy,
@@ -952,60 +1045,75 @@ impl PDRouter {
         decode_url: Option<String>,
         headers: Option<HeaderMap>,
         load_guards: Vec<WorkerLoadGuard>,
+        resumer: Option<StreamResumer>,
     ) -> Response {
         use crate::worker::AttachedBody;
 
         let (tx, rx) = mpsc::unbounded_channel();
 
-        #[expect(
-            clippy::disallowed_methods,
-            reason = "fire-and-forget stream relay; gateway shutdown need not wait for decode stream forwarding"
-        )]
-        tokio::spawn(async move {
-            futures_util::pin_mut!(stream);
-            // Reusable SSE encoder for the logprob-merge re-encode path.
-            let mut encoder = SseEncoder::new();
-            // Whether the next chunk begins at an SSE line boundary (i.e. the
-            // previous chunk ended with an EOL); used to anchor the [DONE]
-            // sentinel detection when the match sits at the start of a chunk.
-            let mut at_line_start = true;
-            while let Some(chunk_result) = stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        let is_done = Self::chunk_contains_done_event(&chunk, at_line_start);
-                        if let Some(&last) = chunk.last() {
-                            at_line_start = last == b'\n' || last == b'\r';
-                        }
+        if let Some(resumer) = resumer {
+            #[expect(
+                clippy::disallowed_methods,
+                reason = "fire-and-forget stream relay; gateway shutdown need not wait for decode stream forwarding"
+            )]
+            tokio::spawn(stream_recovery::relay(
+                decode_url.unwrap_or_default(),
+                stream.boxed(),
+                None,
+                resumer,
+                tx,
+            ));
+        } else {
+            #[expect(
+                clippy::disallowed_methods,
+                reason = "fire-and-forget stream relay; gateway shutdown need not wait for decode stream forwarding"
+            )]
+            tokio::spawn(async move {
+                futures_util::pin_mut!(stream);
+                // Reusable SSE encoder for the logprob-merge re-encode path.
+                let mut encoder = SseEncoder::new();
+                // Whether the next chunk begins at an SSE line boundary (i.e. the
+                // previous chunk ended with an EOL); used to anchor the [DONE]
+                // sentinel detection when the match sits at the start of a chunk.
+                let mut at_line_start = true;
+                while let Some(chunk_result) = stream.next().await {
+                    match chunk_result {
+                        Ok(chunk) => {
+                            let is_done = Self::chunk_contains_done_event(&chunk, at_line_start);
+                            if let Some(&last) = chunk.last() {
+                                at_line_start = last == b'\n' || last == b'\r';
+                            }
 
-                        let result = if return_logprob && prefill_logprobs.is_some() {
-                            Self::merge_streaming_logprobs(
-                                prefill_logprobs.as_ref(),
-                                &chunk,
-                                &mut encoder,
-                            )
-                            .unwrap_or(chunk)
-                        } else {
-                            chunk
-                        };
+                            let result = if return_logprob && prefill_logprobs.is_some() {
+                                Self::merge_streaming_logprobs(
+                                    prefill_logprobs.as_ref(),
+                                    &chunk,
+                                    &mut encoder,
+                                )
+                                .unwrap_or(chunk)
+                            } else {
+                                chunk
+                            };
 
-                        if tx.send(Ok(result)).is_err() {
-                            break;
-                        }
+                            if tx.send(Ok(result)).is_err() {
+                                break;
+                            }
 
-                        if is_done {
-                            break;
+                            if is_done {
+                                break;
+                            }
                         }
-                    }
-                    Err(e) => {
-                        if let Some(ref url) = decode_url {
-                            error!("Stream error from decode server {}: {}", url, e);
+                        Err(e) => {
+                            if let Some(ref url) = decode_url {
+                                error!("Stream error from decode server {}: {}", url, e);
+                            }
+                            let _ = tx.send(Err(format!("Stream error: {e}")));
+                            break;
                         }
-                        let _ = tx.send(Err(format!("Stream error: {e}")));
-                        break;
                     }
                 }
-            }
-        });
+            });
+        }
 
         let stream = UnboundedReceiverStream::new(rx);
         let body = Body::from_stream(stream);
@@ -1302,6 +1410,90 @@ impl PDRouter {
     }
 }
 
+/// Re-issues a dropped PD stream as a continuation on a new prefill/decode
+/// pair, avoiding decode workers that already failed.
+struct PdResumeDispatch {
+    router: PDRouter,
+    model_id: String,
+    headers: Option<HeaderMap>,
+}
+
+#[async_trait]
+impl ResumeDispatch for PdResumeDispatch {
+    async fn dispatch(
+        &self,
+        route: &'static str,
+        request: Value,
+        failed: &[String],
+    ) -> Result<(String, UpstreamStream), (Option<String>, String)> {
+        let router = &self.router;
+        let (prefill, decode) = router
+            .select_pd_pair(None, &self.model_id, self.headers.as_ref(), failed)
+            .await
+            .map_err(|e| (None, e))?;
+        let decode_url = decode.url().to_string();
+
+        let (request, rooms) =
+            PDRouter::inject_bootstrap_into_value(request, prefill.as_ref(), None)
+                .map_err(|e| (None, e))?;
+        let mut prefill_request = request.clone();
+        let mut decode_request = request;
+        router.inject_dp_ranks(
+            prefill.as_ref(),
+            decode.as_ref(),
+            None,
+            &mut prefill_request,
+            &mut decode_request,
+        );
+
+        let mut headers = self.headers.clone().unwrap_or_default();
+        inject_trace_context_http(&mut headers);
+        let prefill_send = router
+            .build_post_with_headers(
+                &router.client,
+                prefill.as_ref(),
+                route,
+                &prefill_request,
+                Some(&headers),
+                false,
+            )
+            .send();
+        let decode_send = router
+            .build_post_with_headers(
+                &router.client,
+                decode.as_ref(),
+                route,
+                &decode_request,
+                Some(&headers),
+                false,
+            )
+            .send();
+        let (prefill_response, decode_response) = tokio::try_join!(prefill_send, decode_send)
+            .map_err(|e| (Some(decode_url.clone()), e.to_string()))?;
+        if !decode_response.status().is_success() {
+            let status = decode_response.status();
+            return Err((Some(decode_url), format!("decode worker returned {status}")));
+        }
+
+        if router
+            .process_prefill_response(prefill_response, prefill.url(), false)
+            .await
+            .is_err()
+        {
+            return Err((
+                Some(prefill.url().to_string()),
+                "prefill worker failed".to_string(),
+            ));
+        }
+        drop(rooms);
+
+        let stream = await_first_chunk(decode_response.bytes_stream())
+            .await
+            .map_err(|e| (Some(decode_url.clone()), e))?;
+        Ok((decode_url, stream.boxed()))
+    }
+}
+
 #[async_trait]
 impl RouterTrait for PDRouter {
     fn as_any(&self) -> &dyn std::any::Any {
@@ -1312,7 +1504,7 @@ impl RouterTrait for PDRouter {
         // Note: This endpoint actually causes the model to generate tokens, so we only test one pair
 
         // Select a random worker pair using the policy
-        let (prefill, decode) = match self.select_pd_pair(None, UNKNOWN_MODEL_ID, None).await {
+        let (prefill, decode) = match self.select_pd_pair(None, UNKNOWN_MODEL_ID, None, &[]).await {
             Ok(pair) => pair,
             Err(e) => {
                 return error::service_unavailable(
@@ -1548,6 +1740,8 @@ mod tests {
             client: Client::new(),
             retry_config: RetryConfig::default(),
             api_key: Some("test_api_key".to_string()),
+            pd_pairs: PdPairTracker::new(&Default::default()),
+            stream_recovery: StreamRecoveryConfig::default(),
         }
     }
 
@@ -1687,7 +1881,9 @@ mod tests {
             .worker_registry
             .register_or_replace(Arc::from(decode_worker));
 
-        let result = router.select_pd_pair(None, UNKNOWN_MODEL_ID, None).await;
+        let result = router
+            .select_pd_pair(None, UNKNOWN_MODEL_ID, None, &[])
+            .await;
 
         assert!(result.is_ok());
         let (prefill, _decode) = result.unwrap();
@@ -1696,11 +1892,75 @@ mod tests {
         assert!(prefill.is_healthy());
     }
 
+    #[tokio::test]
+    async fn test_resume_skips_failed_decode_workers() {
+        let router = create_test_pd_router();
+        for (url, worker_type) in [
+            ("http://prefill", WorkerType::Prefill),
+            ("http://decode-a", WorkerType::Decode),
+            ("http://decode-b", WorkerType::Decode),
+        ] {
+            router
+                .worker_registry
+                .register_or_replace(Arc::from(create_test_worker(
+                    url.to_string(),
+                    worker_type,
+                    true,
+                )));
+        }
+
+        let failed = ["http://decode-a".to_string()];
+        for _ in 0..4 {
+            let (_, decode) = router
+                .select_pd_pair(None, UNKNOWN_MODEL_ID, None, &failed)
+                .await
+                .unwrap();
+            assert_eq!(decode.url(), "http://decode-b");
+        }
+
+        let failed = ["http://decode-a".to_string(), "http://decode-b".to_string()];
+        assert!(router
+            .select_pd_pair(None, UNKNOWN_MODEL_ID, None, &failed)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_stream_resumer_only_for_recoverable_streams() {
+        let mut router = create_test_pd_router();
+        router.stream_recovery.models = vec!["m".to_string()];
+        let request = json!({"model": "m", "stream": true,
+                             "messages": [{"role": "user", "content": "hi"}]});
+        let context = PDRequestContext {
+            route: "/v1/chat/completions",
+            batch_size: None,
+            is_stream: true,
+            return_logprob: false,
+            request_text: None,
+            model_id: "m",
+            headers: None,
+        };
+        assert!(router.stream_resumer(&context, &request).is_some());
+
+        let logprobs = PDRequestContext {
+            return_logprob: true,
+            ..context.clone()
+        };
+        assert!(router.stream_resumer(&logprobs, &request).is_none());
+        let other_model = PDRequestContext {
+            model_id: "other",
+            ..context
+        };
+        assert!(router.stream_resumer(&other_model, &request).is_none());
+    }
+
     #[tokio::test]
     async fn test_empty_worker_lists() {
         let router = create_test_pd_router();
 
-        let result = router.select_pd_pair(None, UNKNOWN_MODEL_ID, None).await;
+        let result = router
+            .select_pd_pair(None, UNKNOWN_MODEL_ID, None, &[])
+            .await;
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("No prefill workers available"));
@@ -1837,6 +2097,7 @@ mod tests {
                 None,
                 None,
                 guards,
+                None,
             );
 
             // Guards are now attached to response body, so load should be 1
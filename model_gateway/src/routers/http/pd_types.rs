@@ -12,12 +12,6 @@ pub struct RequestWithBootstrap<'a, T: Serialize> {
     pub bootstrap_room: u64,
 }
 
-/// Generate a random bootstrap room ID.
-pub fn generate_room_id() -> u64 {
-    // Generate a value in the range [0, 2^63 - 1] to match Python's random.randint(0, 2**63 - 1)
-    rand::random::<u64>() & (i64::MAX as u64)
-}
-
 /// PD-specific routing policies.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PDSelectionPolicy {
@@ -3,7 +3,10 @@ use std::{error::Error as _, sync::Arc, time::Instant};
 use axum::{
     body::{to_bytes, Body},
     extract::Request,
-    http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, Method, StatusCode},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
     response::{IntoResponse, Response},
     Json,
 };
@@ -15,13 +18,16 @@ use openai_protocol::{
     completion::CompletionRequest,
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
+    images::ImageGenerationRequest,
     messages::CreateMessageRequest,
+    model_type::Endpoint,
     realtime_session::{
         RealtimeClientSecretCreateRequest, RealtimeSessionCreateRequest,
         RealtimeTranscriptionSessionCreateRequest,
     },
     rerank::{RerankRequest, RerankResponse, RerankResult},
     responses::ResponsesRequest,
+    score::ScoreRequest,
     transcription::{AudioFile, TranscriptionRequest},
 };
 use reqwest::{
@@ -30,12 +36,12 @@ use reqwest::{
 };
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::{
     app_context::AppContext,
-    config::types::RetryConfig,
-    middleware::TenantRequestMeta,
+    config::types::{RetryConfig, StreamRecoveryConfig},
+    middleware::{request_features, FederationSigner, ServingWorker, TenantRequestMeta},
     observability::{
         events::{self, Event},
         metrics::{bool_to_static_str, metrics_labels, Metrics},
@@ -44,20 +50,32 @@ use crate::{
     policies::{PolicyRegistry, SelectWorkerInfo},
     routers::{
         common::{
+            fault_injection::{self, FaultAction, FaultInjector},
             header_utils,
+            images::{self, ImageStore},
             realtime::{
                 rest::forward_realtime_rest, webrtc, webrtc::handle_realtime_webrtc,
                 ws::handle_realtime_ws, RealtimeLabels, RealtimeRegistry,
             },
-            retry::{is_retryable_status, RetryExecutor},
-            worker_selection::{SelectWorkerRequest, WorkerSelector},
+            retry::{await_first_chunk, is_retryable_status, RetryExecutor},
+            worker_selection::{
+                matches_card_revision, matches_worker_labels, serves_endpoint, SelectWorkerRequest,
+                WorkerSelector,
+            },
         },
         error::{self, extract_error_code_from_response},
         grpc::utils::{error_type_from_status, route_to_endpoint},
+        http::{
+            local_server,
+            stream_recovery::{self, StreamResumer, WorkerDispatch},
+        },
         openai::strip_default_sglang_fields,
         RouterTrait,
     },
-    worker::{AttachedBody, ConnectionMode, Worker, WorkerLoadGuard, WorkerRegistry, WorkerType},
+    worker::{
+        AttachedBody, ConnectionMode, RuntimeType, Worker, WorkerDebugTracer, WorkerLoadGuard,
+        WorkerRegistry, WorkerType,
+    },
 };
 
 /// Max body size for a WebRTC `/v1/realtime/calls` SDP offer (10 MiB).
@@ -69,6 +87,11 @@ pub struct Router {
     policy_registry: Arc<PolicyRegistry>,
     client: Client,
     retry_config: RetryConfig,
+    fault_injector: Option<FaultInjector>,
+    debug_tracer: Arc<WorkerDebugTracer>,
+    stream_recovery: StreamRecoveryConfig,
+    federation: FederationSigner,
+    image_store: Option<ImageStore>,
     realtime_registry: Arc<RealtimeRegistry>,
     webrtc_bind_addr: Option<std::net::IpAddr>,
     webrtc_stun_server: Option<String>,
@@ -97,6 +120,14 @@ impl Router {
             policy_registry: ctx.policy_registry.clone(),
             client: ctx.client.clone(),
             retry_config: ctx.router_config.effective_retry_config(),
+            fault_injector: FaultInjector::from_config(&ctx.router_config.fault_injection),
+            debug_tracer: ctx.worker_debug_tracer.clone(),
+            stream_recovery: ctx.router_config.stream_recovery.clone(),
+            federation: FederationSigner::new(
+                &ctx.router_config.federation,
+                ctx.router_config.request_timeout_secs,
+            ),
+            image_store: ImageStore::from_context(ctx),
             realtime_registry: ctx.realtime_registry.clone(),
             webrtc_bind_addr: ctx.webrtc_bind_addr,
             webrtc_stun_server: ctx.webrtc_stun_server.clone(),
@@ -157,11 +188,14 @@ impl Router {
     /// Select worker considering circuit breaker state.
     /// Filters to workers serving the specified model. When model is "unknown"
     /// (generate endpoint without model), considers all HTTP workers.
+    /// `endpoint` further restricts capability-gated endpoints (classify,
+    /// score) to workers advertising them.
     fn select_worker_for_model(
         &self,
         model_id: &str,
         text: Option<&str>,
         headers: Option<&HeaderMap>,
+        endpoint: Option<Endpoint>,
     ) -> Option<Arc<dyn Worker>> {
         // UNKNOWN_MODEL_ID means caller didn't specify a model — find any available worker
         let model_filter = if model_id == crate::worker::UNKNOWN_MODEL_ID {
@@ -180,6 +214,9 @@ impl Router {
         let available: Vec<Arc<dyn Worker>> = workers
             .iter()
             .filter(|w| w.is_available())
+            .filter(|w| endpoint.is_none_or(|e| serves_endpoint(w.as_ref(), model_id, e)))
+            .filter(|w| matches_worker_labels(w.as_ref(), headers))
+            .filter(|w| matches_card_revision(&self.worker_registry, w.as_ref(), model_id, headers))
             .cloned()
             .collect();
         if available.is_empty() {
@@ -331,7 +368,8 @@ impl Router {
         is_stream: bool,
         text: &str,
     ) -> Response {
-        let worker = match self.select_worker_for_model(model_id, Some(text), headers) {
+        let endpoint = Endpoint::from_path(route);
+        let worker = match self.select_worker_for_model(model_id, Some(text), headers, endpoint) {
             Some(w) => w,
             None => {
                 // Distinguish "no workers for this model" from "workers exist but unavailable"
@@ -347,8 +385,20 @@ impl Router {
                     None,
                     false,
                 );
+                let capable = endpoint.is_none_or(|e| {
+                    total
+                        .iter()
+                        .any(|w| serves_endpoint(w.as_ref(), model_id, e))
+                });
                 return if total.is_empty() {
                     error::model_not_found(model_id)
+                } else if !capable {
+                    error::bad_request(
+                        "endpoint_not_supported",
+                        format!(
+                            "Model '{model_id}' is not served by any worker supporting {route}"
+                        ),
+                    )
                 } else {
                     error::service_unavailable(
                         "no_available_workers",
@@ -375,6 +425,7 @@ impl Router {
                 headers,
                 typed_req,
                 route,
+                model_id,
                 worker.as_ref(),
                 is_stream,
                 load_guard,
@@ -831,6 +882,9 @@ impl Router {
                     *response.status_mut() = status;
                     *response.headers_mut() = response_headers;
                     response
+                        .extensions_mut()
+                        .insert(ServingWorker::new(worker, model_id));
+                    response
                 }
                 Err(e) => error::internal_error(
                     "read_response_body_failed",
@@ -878,11 +932,13 @@ impl Router {
     }
 
     // Send typed request directly without conversion
+    #[expect(clippy::too_many_arguments)]
     async fn send_typed_request<T: serde::Serialize>(
         &self,
         headers: Option<&HeaderMap>,
         typed_req: &T,
         route: &'static str,
+        model_id: &str,
         worker: &dyn Worker,
         is_stream: bool,
         load_guard: Option<WorkerLoadGuard>,
@@ -900,6 +956,36 @@ impl Router {
             }
         };
 
+        // Built from the client's request before per-worker preparation so a
+        // resumed stream is prepared for the worker it lands on. Callers may
+        // also opt a single request in with `x-smg-features: recovery`.
+        let recover = self.stream_recovery.enabled_for(model_id)
+            || (self.stream_recovery.max_resumes > 0
+                && request_features::feature_enabled(headers, request_features::FEATURE_RECOVERY));
+        let resumer = if is_stream && recover {
+            let mut forwarded = HeaderMap::new();
+            for (name, value) in headers.into_iter().flatten() {
+                if header_utils::should_forward_request_header(name.as_str()) {
+                    forwarded.append(name.clone(), value.clone());
+                }
+            }
+            let dispatch = WorkerDispatch::new(
+                model_id,
+                forwarded,
+                self.worker_registry.clone(),
+                self.client.clone(),
+            );
+            StreamResumer::new(
+                route,
+                &json_val,
+                Box::new(dispatch),
+                self.worker_registry.clone(),
+                self.stream_recovery.max_resumes,
+            )
+        } else {
+            None
+        };
+
         let mut json_val = match worker.prepare_request(json_val) {
             Ok(prepared) => prepared,
             Err(e) => {
@@ -910,8 +996,35 @@ impl Router {
             }
         };
         strip_default_sglang_fields(&mut json_val);
+        let runtime_type = worker.metadata().spec.runtime_type;
+        if let Err(e) = local_server::prepare_request(runtime_type, &mut json_val) {
+            return error::bad_request("unsupported_parameter", e);
+        }
+        let include_usage = local_server::include_usage(&json_val);
+        let trace = self.debug_tracer.begin(worker.url(), route, &json_val);
 
-        let mut request_builder = self.client.post(&endpoint_url).json(&json_val);
+        let mut request_builder = if runtime_type == RuntimeType::Smg {
+            // Serialize up front so the signature covers the bytes sent.
+            let body = match serde_json::to_vec(&json_val) {
+                Ok(body) => body,
+                Err(e) => {
+                    return error::bad_request(
+                        "serialization_failed",
+                        format!("Failed to serialize request: {e}"),
+                    );
+                }
+            };
+            let federation = self
+                .federation
+                .outbound_headers(headers, &Method::POST, route, &body);
+            self.client
+                .post(&endpoint_url)
+                .header(CONTENT_TYPE, "application/json")
+                .headers(federation)
+                .body(body)
+        } else {
+            self.client.post(&endpoint_url).json(&json_val)
+        };
 
         if let Some(key) = api_key {
             // Pre-allocate string with capacity to avoid reallocation
@@ -929,6 +1042,16 @@ impl Router {
             }
         }
 
+        let fault = self
+            .fault_injector
+            .as_ref()
+            .and_then(|f| f.pick(worker.url(), route));
+        let truncate_after = match fault_injection::apply(fault, worker.url()).await {
+            FaultAction::Proceed => None,
+            FaultAction::Truncate(after_bytes) => Some(after_bytes),
+            FaultAction::Respond(response) => return response,
+        };
+
         let res = match request_builder.send().await {
             Ok(res) => res,
             Err(e) => {
@@ -938,6 +1061,9 @@ impl Router {
                     route,
                     e
                 );
+                if let Some(trace) = trace {
+                    trace.fail(&e);
+                }
 
                 return convert_reqwest_error(e);
             }
@@ -952,30 +1078,82 @@ impl Router {
             // Ensure we set the correct content-type for SSE
             response_headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
 
-            let stream = res.bytes_stream();
+            // Nothing has been forwarded yet, so a backend that fails before
+            // its first byte is reported as a retryable 502.
+            let stream = if status.is_success() {
+                match await_first_chunk(res.bytes_stream()).await {
+                    Ok(stream) => stream.boxed(),
+                    Err(e) => {
+                        warn!(
+                            "Stream failed before first byte worker_url={} route={} error={}",
+                            worker.url(),
+                            route,
+                            e
+                        );
+                        if let Some(trace) = trace {
+                            trace.fail(&e);
+                        }
+                        return error::bad_gateway(
+                            "stream_failed_before_first_byte",
+                            format!("Upstream stream failed before sending data: {e}"),
+                        );
+                    }
+                }
+            } else {
+                res.bytes_stream().boxed()
+            };
+            let stream = match trace {
+                Some(trace) => trace.stream(status.as_u16(), stream).boxed(),
+                None => stream,
+            };
+            let stream = local_server::shape_stream(runtime_type, include_usage, stream);
             let (tx, rx) = mpsc::unbounded_channel();
 
             // Spawn task to forward stream
-            #[expect(
-                clippy::disallowed_methods,
-                reason = "fire-and-forget stream relay; gateway shutdown need not wait for individual stream forwarding"
-            )]
-            tokio::spawn(async move {
-                let mut stream = stream;
-                while let Some(chunk) = stream.next().await {
-                    match chunk {
-                        Ok(bytes) => {
-                            if tx.send(Ok(bytes)).is_err() {
+            if let Some(resumer) = resumer.filter(|_| status.is_success()) {
+                #[expect(
+                    clippy::disallowed_methods,
+                    reason = "fire-and-forget stream relay; gateway shutdown need not wait for individual stream forwarding"
+                )]
+                tokio::spawn(stream_recovery::relay(
+                    worker.url().to_string(),
+                    stream,
+                    truncate_after,
+                    resumer,
+                    tx,
+                ));
+            } else {
+                #[expect(
+                    clippy::disallowed_methods,
+                    reason = "fire-and-forget stream relay; gateway shutdown need not wait for individual stream forwarding"
+                )]
+                tokio::spawn(async move {
+                    let mut stream = stream;
+                    let mut remaining = truncate_after;
+                    while let Some(chunk) = stream.next().await {
+                        match chunk {
+                            Ok(mut bytes) => {
+                                if let Some(left) = remaining.as_mut() {
+                                    bytes.truncate(*left);
+                                    *left -= bytes.len();
+                                }
+                                if !bytes.is_empty() && tx.send(Ok(bytes)).is_err() {
+                                    break;
+                                }
+                                if remaining == Some(0) {
+                                    let _ = tx
+                                        .send(Err("Stream error: injected truncation".to_string()));
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                let _ = tx.send(Err(format!("Stream error: {e}")));
                                 break;
                             }
                         }
-                        Err(e) => {
-                            let _ = tx.send(Err(format!("Stream error: {e}")));
-                            break;
-                        }
                     }
-                }
-            });
+                });
+            }
 
             let stream = UnboundedReceiverStream::new(rx);
             let body = Body::from_stream(stream);
@@ -983,6 +1161,9 @@ impl Router {
             let mut response = Response::new(body);
             *response.status_mut() = status;
             *response.headers_mut() = response_headers;
+            response
+                .extensions_mut()
+                .insert(ServingWorker::new(worker, model_id));
 
             // Attach load guard to response body for proper RAII lifecycle
             // Guard is dropped when response body is consumed or client disconnects
@@ -992,16 +1173,27 @@ impl Router {
             response
         } else {
             // For non-streaming requests, preserve headers
-            let response_headers = header_utils::preserve_response_headers(res.headers());
+            let mut response_headers = header_utils::preserve_response_headers(res.headers());
 
             let response = match res.bytes().await {
-                Ok(body) => {
+                Ok(mut body) => {
+                    if let Some(after_bytes) = truncate_after {
+                        body.truncate(after_bytes);
+                        // The worker's length no longer matches the body.
+                        response_headers.insert(CONTENT_LENGTH, body.len().into());
+                    }
+                    if let Some(trace) = trace {
+                        trace.respond(status.as_u16(), &body);
+                    }
                     let mut response = Response::new(Body::from(body));
                     *response.status_mut() = status;
                     *response.headers_mut() = response_headers;
                     response
                 }
                 Err(e) => {
+                    if let Some(trace) = trace {
+                        trace.fail(&e);
+                    }
                     let error_msg = format!("Failed to get response body: {e}");
                     error::internal_error("read_response_body_failed", error_msg)
                 }
@@ -1021,7 +1213,11 @@ impl Router {
         let rerank_results = serde_json::from_slice::<Vec<RerankResult>>(&body_bytes)?;
         let mut rerank_response =
             RerankResponse::new(rerank_results, req.model.clone(), req.rid.clone());
-        // Sorting is handled by Python worker (serving_rerank.py)
+        // Sorting is handled by Python worker (serving_rerank.py); both
+        // normalizations are monotonic, so the order survives them.
+        if let Some(normalization) = req.score_normalization {
+            rerank_response.normalize_scores(normalization);
+        }
         if let Some(top_k) = req.top_k {
             rerank_response.apply_top_k(top_k);
         }
@@ -1232,6 +1428,44 @@ impl RouterTrait for Router {
             .await
     }
 
+    async fn route_score(
+        &self,
+        headers: Option<&HeaderMap>,
+        _tenant_meta: &TenantRequestMeta,
+        body: &ScoreRequest,
+        model_id: &str,
+    ) -> Response {
+        self.route_typed_request(headers, body, "/v1/score", model_id)
+            .await
+    }
+
+    async fn route_image_generation(
+        &self,
+        headers: Option<&HeaderMap>,
+        _tenant_meta: &TenantRequestMeta,
+        body: &ImageGenerationRequest,
+        model_id: &str,
+    ) -> Response {
+        let requested = body.response_format();
+        let mut upstream = body.clone();
+        upstream.response_format = Some(images::upstream_response_format(
+            requested,
+            self.image_store.as_ref(),
+        ));
+        let response = self
+            .route_typed_request(headers, &upstream, "/v1/images/generations", model_id)
+            .await;
+        images::finish_image_response(
+            response,
+            requested,
+            self.image_store.as_ref(),
+            metrics_labels::ROUTER_HTTP,
+            metrics_labels::BACKEND_REGULAR,
+            model_id,
+        )
+        .await
+    }
+
     async fn route_audio_transcriptions(
         &self,
         headers: Option<&HeaderMap>,
@@ -1457,6 +1691,10 @@ mod tests {
             policy_registry,
             client: Client::new(),
             retry_config: RetryConfig::default(),
+            fault_injector: None,
+            debug_tracer: WorkerDebugTracer::new(),
+            stream_recovery: StreamRecoveryConfig::default(),
+            image_store: None,
             realtime_registry: Arc::new(RealtimeRegistry::new()),
             webrtc_bind_addr: None,
             webrtc_stun_server: None,
@@ -0,0 +1,739 @@
+//! Mid-stream recovery for streaming chat and completion requests.
+//!
+//! Retries stop once the first byte reaches the client. For models listed in
+//! `stream_recovery.models`, the relay instead tracks the text generated so
+//! far and, when the backend drops before `[DONE]`, re-issues the request to
+//! another worker (a new prefill/decode pair in PD mode, see
+//! [`ResumeDispatch`]) as a continuation: chat requests get the partial answer as
+//! a trailing assistant message with `continue_final_message`, completion
+//! requests get it appended to the prompt. The relay forwards only complete
+//! SSE events, so the client never sees a torn event, and writes an
+//! `smg.stream_resumed` event before the resumed events. The new
+//! worker's role preamble and any run of deltas it resends from before the
+//! switch are dropped, so no text is duplicated across it.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::{
+    routers::{
+        common::{
+            retry::await_first_chunk,
+            sse::{parse_block, SseDecoder},
+        },
+        http::local_server,
+        openai::strip_default_sglang_fields,
+    },
+    worker::{ConnectionMode, Worker, WorkerRegistry, WorkerType},
+};
+
+const CHAT_ROUTE: &str = "/v1/chat/completions";
+const COMPLETIONS_ROUTE: &str = "/v1/completions";
+
+/// SSE event type written before a resumed stream's events.
+const RESUMED_EVENT: &str = "smg.stream_resumed";
+
+pub(crate) type UpstreamStream = BoxStream<'static, Result<Bytes, reqwest::Error>>;
+
+/// Sends a continuation to a worker that has not failed yet.
+#[async_trait]
+pub(crate) trait ResumeDispatch: Send + Sync {
+    /// Send `request` to `route` on a worker whose URL is not in `failed`
+    /// and wait for the first byte of its stream. Returns the URL of the
+    /// streaming worker; on error, the URL of the worker that was tried, if
+    /// one was picked.
+    async fn dispatch(
+        &self,
+        route: &'static str,
+        request: Value,
+        failed: &[String],
+    ) -> Result<(String, UpstreamStream), (Option<String>, String)>;
+}
+
+/// Resumes on the least-loaded regular HTTP worker serving the model.
+pub(crate) struct WorkerDispatch {
+    model_id: String,
+    headers: HeaderMap,
+    worker_registry: Arc<WorkerRegistry>,
+    client: Client,
+}
+
+impl WorkerDispatch {
+    /// `headers` must already be filtered to forwardable request headers.
+    pub(crate) fn new(
+        model_id: &str,
+        headers: HeaderMap,
+        worker_registry: Arc<WorkerRegistry>,
+        client: Client,
+    ) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            headers,
+            worker_registry,
+            client,
+        }
+    }
+
+    fn select_worker(&self, failed: &[String]) -> Option<Arc<dyn Worker>> {
+        self.worker_registry
+            .get_workers_filtered(
+                Some(&self.model_id),
+                Some(WorkerType::Regular),
+                Some(ConnectionMode::Http),
+                None,
+                false,
+            )
+            .into_iter()
+            .filter(|w| w.is_available() && !failed.iter().any(|u| u == w.url()))
+            .min_by_key(|w| w.load())
+    }
+}
+
+#[async_trait]
+impl ResumeDispatch for WorkerDispatch {
+    async fn dispatch(
+        &self,
+        route: &'static str,
+        request: Value,
+        failed: &[String],
+    ) -> Result<(String, UpstreamStream), (Option<String>, String)> {
+        let worker = self
+            .select_worker(failed)
+            .ok_or_else(|| (None, "no other worker available".to_string()))?;
+        let url = worker.url().to_string();
+
+        let mut body = worker
+            .prepare_request(request)
+            .map_err(|e| (None, e.to_string()))?;
+        strip_default_sglang_fields(&mut body);
+        let runtime_type = worker.metadata().spec.runtime_type;
+        local_server::prepare_request(runtime_type, &mut body).map_err(|e| (None, e))?;
+        let include_usage = local_server::include_usage(&body);
+        let mut request_builder = self.client.post(worker.endpoint_url(route)).json(&body);
+        if let Some(key) = worker.api_key() {
+            request_builder = request_builder.bearer_auth(key);
+        }
+        for (name, value) in &self.headers {
+            request_builder = request_builder.header(name, value);
+        }
+
+        let stream = match request_builder.send().await {
+            Ok(res) if res.status().is_success() => {
+                await_first_chunk(res.bytes_stream()).await.map(|stream| {
+                    local_server::shape_stream(runtime_type, include_usage, stream.boxed())
+                })
+            }
+            Ok(res) => Err(format!("worker returned {}", res.status())),
+            Err(e) => Err(e.to_string()),
+        };
+        stream
+            .map(|stream| (url.clone(), stream))
+            .map_err(|e| (Some(url), e))
+    }
+}
+
+/// Everything needed to re-issue a dropped stream to another worker.
+pub(crate) struct StreamResumer {
+    route: &'static str,
+    request: Value,
+    dispatch: Box<dyn ResumeDispatch>,
+    worker_registry: Arc<WorkerRegistry>,
+    failed_urls: Vec<String>,
+    resumes_left: u32,
+}
+
+impl StreamResumer {
+    /// `None` unless the request is a single-choice chat or string-prompt
+    /// completion, the only shapes a continuation can be built for.
+    pub(crate) fn new(
+        route: &'static str,
+        request: &Value,
+        dispatch: Box<dyn ResumeDispatch>,
+        worker_registry: Arc<WorkerRegistry>,
+        max_resumes: u32,
+    ) -> Option<Self> {
+        let single_choice = request.get("n").and_then(Value::as_u64).unwrap_or(1) == 1;
+        let resumable = match route {
+            CHAT_ROUTE => request.get("messages").is_some_and(Value::is_array),
+            COMPLETIONS_ROUTE => request.get("prompt").is_some_and(Value::is_string),
+            _ => false,
+        };
+        (single_choice && resumable && max_resumes > 0).then(|| Self {
+            route,
+            request: request.clone(),
+            dispatch,
+            worker_registry,
+            failed_urls: Vec::new(),
+            resumes_left: max_resumes,
+        })
+    }
+
+    /// Request that picks up after `progress`.
+    fn continuation(&self, progress: &Progress) -> Value {
+        let mut request = self.request.clone();
+        if progress.generated.is_empty() {
+            return request;
+        }
+        if self.route == CHAT_ROUTE {
+            if let Some(messages) = request.get_mut("messages").and_then(Value::as_array_mut) {
+                messages.push(json!({"role": "assistant", "content": progress.generated}));
+            }
+            request["continue_final_message"] = json!(true);
+            request["add_generation_prompt"] = json!(false);
+        } else if let Some(prompt) = request
+            .get("prompt")
+            .and_then(Value::as_str)
+            .map(|prompt| format!("{prompt}{}", progress.generated))
+        {
+            request["prompt"] = json!(prompt);
+        }
+        // Content events approximate generated tokens; keep the overall
+        // budget roughly where the client set it.
+        for field in ["max_tokens", "max_completion_tokens"] {
+            if let Some(limit) = request.get(field).and_then(Value::as_u64) {
+                request[field] = json!(limit.saturating_sub(progress.content_events).max(1));
+            }
+        }
+        request
+    }
+
+    fn mark_failed(&mut self, url: &str) {
+        if let Some(worker) = self.worker_registry.get_by_url(url) {
+            worker.record_outcome(502);
+        }
+        self.failed_urls.push(url.to_string());
+    }
+
+    /// Send the continuation and wait for its first byte.
+    async fn resume(&mut self, progress: &Progress) -> Result<(String, UpstreamStream), String> {
+        self.resumes_left -= 1;
+        let result = self
+            .dispatch
+            .dispatch(self.route, self.continuation(progress), &self.failed_urls)
+            .await;
+        match result {
+            Ok((url, stream)) => {
+                if let Some(worker) = self.worker_registry.get_by_url(&url) {
+                    worker.record_outcome(200);
+                }
+                Ok((url, stream))
+            }
+            Err((Some(url), e)) => {
+                self.mark_failed(&url);
+                Err(format!("{url}: {e}"))
+            }
+            Err((None, e)) => Err(e),
+        }
+    }
+}
+
+/// Content deltas remembered for overlap detection after a switch.
+const OVERLAP_WINDOW_TOKENS: usize = 64;
+
+/// Shortest run of resent deltas treated as overlap. Shorter matches are
+/// as likely to be the model repeating itself, so they are forwarded.
+const MIN_OVERLAP_TOKENS: usize = 3;
+
+/// What one SSE event carries, as far as recovery is concerned.
+#[derive(Debug, Default, PartialEq)]
+struct EventContent {
+    /// Non-empty text delta (roughly one token).
+    text: Option<String>,
+    /// A chat delta carrying only the role, which opens every stream.
+    preamble: bool,
+    /// Carries a `finish_reason`.
+    finished: bool,
+    /// The `[DONE]` sentinel.
+    done: bool,
+}
+
+impl EventContent {
+    fn parse(event: &str, chat: bool) -> Self {
+        let Some(frame) = parse_block(event) else {
+            return Self::default();
+        };
+        if frame.data == "[DONE]" {
+            return Self {
+                done: true,
+                ..Self::default()
+            };
+        }
+        let Ok(chunk) = frame.decode_data::<Value>() else {
+            return Self::default();
+        };
+        let Some(choice) = chunk.pointer("/choices/0") else {
+            return Self::default();
+        };
+        let text = if chat {
+            choice.pointer("/delta/content")
+        } else {
+            choice.get("text")
+        };
+        let text = text
+            .and_then(Value::as_str)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        let finished = choice.get("finish_reason").is_some_and(Value::is_string);
+        let preamble = chat
+            && text.is_none()
+            && !finished
+            && choice
+                .get("delta")
+                .and_then(Value::as_object)
+                .is_some_and(|delta| {
+                    delta.iter().all(|(key, value)| {
+                        key == "role" || value.is_null() || value.as_str() == Some("")
+                    })
+                });
+        Self {
+            text,
+            preamble,
+            finished,
+            done: false,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.finished || self.done
+    }
+}
+
+/// What the client has received so far.
+#[derive(Default)]
+struct Progress {
+    generated: String,
+    content_events: u64,
+    finished: bool,
+    done: bool,
+}
+
+impl Progress {
+    fn record(&mut self, content: &EventContent) {
+        if let Some(text) = &content.text {
+            self.generated.push_str(text);
+            self.content_events += 1;
+        }
+        self.finished |= content.finished;
+        self.done |= content.done;
+    }
+}
+
+/// Drops what a replacement worker resends from before the switch.
+///
+/// After [`Self::arm`], the resumed stream's role-only preamble is dropped,
+/// since the client already has one. Its events are then held while their
+/// deltas still line up with a suffix of the deltas already sent. If at least
+/// [`MIN_OVERLAP_TOKENS`] of them align up to the last delta sent, they were
+/// duplicates and are dropped; as soon as no alignment survives (or the
+/// stream finishes) they are released.
+#[derive(Default)]
+struct OverlapFilter {
+    recent: VecDeque<String>,
+    /// The resumed stream has not yet sent anything past its preamble.
+    skip_preamble: bool,
+    matching: Option<OverlapMatch>,
+}
+
+struct OverlapMatch {
+    /// Start offsets into `recent` still consistent with the held deltas.
+    candidates: Vec<usize>,
+    matched: usize,
+    held: Vec<(Bytes, EventContent)>,
+}
+
+impl OverlapFilter {
+    /// Record a delta the client received.
+    fn sent(&mut self, content: &EventContent) {
+        if let Some(text) = &content.text {
+            if self.recent.len() == OVERLAP_WINDOW_TOKENS {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(text.clone());
+        }
+    }
+
+    /// Start checking the next worker's output for overlap.
+    fn arm(&mut self) {
+        self.skip_preamble = true;
+        self.matching = (!self.recent.is_empty()).then(|| OverlapMatch {
+            candidates: (0..self.recent.len()).collect(),
+            matched: 0,
+            held: Vec::new(),
+        });
+    }
+
+    /// Events that may be forwarded now, in order.
+    fn push(&mut self, event: Bytes, content: EventContent) -> Vec<(Bytes, EventContent)> {
+        if self.skip_preamble {
+            if content.preamble {
+                return Vec::new();
+            }
+            self.skip_preamble = false;
+        }
+        let Some(matching) = self.matching.as_mut() else {
+            return vec![(event, content)];
+        };
+        if content.is_terminal() {
+            let mut released = std::mem::take(&mut matching.held);
+            released.push((event, content));
+            self.matching = None;
+            return released;
+        }
+        let Some(text) = content.text.as_deref() else {
+            matching.held.push((event, content));
+            return Vec::new();
+        };
+
+        let offset = matching.matched;
+        let recent = &self.recent;
+        matching
+            .candidates
+            .retain(|start| recent.get(start + offset).is_some_and(|sent| sent == text));
+        matching.matched += 1;
+        matching.held.push((event, content));
+
+        // Every sent delta counts when fewer than the minimum were sent.
+        let min_overlap = MIN_OVERLAP_TOKENS.min(recent.len());
+        let overlap_complete = matching.matched >= min_overlap
+            && matching
+                .candidates
+                .iter()
+                .any(|start| start + matching.matched == recent.len());
+        if overlap_complete {
+            let held = std::mem::take(&mut matching.held);
+            self.matching = None;
+            return held
+                .into_iter()
+                .filter(|(_, content)| content.text.is_none())
+                .collect();
+        }
+        if matching.candidates.is_empty() {
+            let held = std::mem::take(&mut matching.held);
+            self.matching = None;
+            return held;
+        }
+        Vec::new()
+    }
+}
+
+/// Whether an unterminated trailing block is a whole event rather than one
+/// torn by the drop: `[DONE]` or a complete JSON payload.
+fn is_whole_event(block: &str) -> bool {
+    parse_block(block).is_some_and(|frame| frame.is_done() || frame.decode_data::<Value>().is_ok())
+}
+
+/// Client-side state of a relayed stream.
+struct Relay {
+    chat: bool,
+    progress: Progress,
+    overlap: OverlapFilter,
+    tx: mpsc::UnboundedSender<Result<Bytes, String>>,
+}
+
+impl Relay {
+    /// Forward one complete SSE event through the overlap filter. Returns
+    /// `false` once the client has gone away.
+    fn forward(&mut self, block: &str) -> bool {
+        let content = EventContent::parse(block, self.chat);
+        let event = Bytes::from(format!("{block}\n\n"));
+        for (event, content) in self.overlap.push(event, content) {
+            self.progress.record(&content);
+            self.overlap.sent(&content);
+            if self.tx.send(Ok(event)).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Relay `upstream` to `tx`, resuming on another worker when it drops.
+/// `truncate_after` applies an injected truncation fault to the first
+/// upstream only.
+pub(crate) async fn relay(
+    worker_url: String,
+    mut upstream: UpstreamStream,
+    mut truncate_after: Option<usize>,
+    mut resumer: StreamResumer,
+    tx: mpsc::UnboundedSender<Result<Bytes, String>>,
+) {
+    let mut worker_url = worker_url;
+    let mut relay = Relay {
+        chat: resumer.route == CHAT_ROUTE,
+        progress: Progress::default(),
+        overlap: OverlapFilter::default(),
+        tx,
+    };
+
+    loop {
+        let mut decoder = SseDecoder::new();
+        let failure = loop {
+            match upstream.next().await {
+                Some(Ok(mut bytes)) => {
+                    if let Some(left) = truncate_after.as_mut() {
+                        bytes.truncate(*left);
+                        *left -= bytes.len();
+                    }
+                    if let Err(e) = decoder.push(&bytes) {
+                        break e.to_string();
+                    }
+                    while let Some(block) = decoder.next_block() {
+                        match block {
+                            Ok(block) if !relay.forward(&block) => return,
+                            Ok(_) => {}
+                            Err(e) => warn!("Dropping undecodable SSE event: {e}"),
+                        }
+                    }
+                    decoder.compact();
+                    if truncate_after == Some(0) {
+                        break "injected truncation".to_string();
+                    }
+                }
+                Some(Err(e)) => break e.to_string(),
+                None => {
+                    // A clean end of stream may leave the final event
+                    // without its blank line; forward it if it is whole.
+                    if let Some(Ok(block)) = decoder.flush_block() {
+                        if is_whole_event(&block) && !relay.forward(&block) {
+                            return;
+                        }
+                    }
+                    if relay.progress.done {
+                        return;
+                    }
+                    break "stream ended before [DONE]".to_string();
+                }
+            }
+        };
+        if relay.progress.done {
+            return;
+        }
+
+        warn!("Stream dropped mid-generation worker_url={worker_url} error={failure}");
+        resumer.mark_failed(&worker_url);
+
+        let progress = &relay.progress;
+        let mut resumed = None;
+        let mut last_error = failure;
+        while !progress.finished && resumer.resumes_left > 0 {
+            match resumer.resume(progress).await {
+                Ok(next) => {
+                    resumed = Some(next);
+                    break;
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        let Some((next_url, next_stream)) = resumed else {
+            let _ = relay.tx.send(Err(format!("Stream error: {last_error}")));
+            return;
+        };
+
+        let generated_chars = progress.generated.chars().count();
+        info!(
+            "Resuming stream from_worker={worker_url} to_worker={next_url} generated_chars={generated_chars}"
+        );
+        let marker = json!({"worker": next_url, "generated_chars": generated_chars});
+        let marker = format!("event: {RESUMED_EVENT}\ndata: {marker}\n\n");
+        if relay.tx.send(Ok(Bytes::from(marker))).is_err() {
+            return;
+        }
+        worker_url = next_url;
+        upstream = next_stream;
+        truncate_after = None;
+        relay.overlap.arm();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resumer(route: &'static str, request: Value) -> Option<StreamResumer> {
+        let registry = Arc::new(WorkerRegistry::new());
+        let dispatch = WorkerDispatch::new("m", HeaderMap::new(), registry.clone(), Client::new());
+        StreamResumer::new(route, &request, Box::new(dispatch), registry, 1)
+    }
+
+    #[test]
+    fn continuation_appends_generated_prefix() {
+        let mut progress = Progress::default();
+        for event in [
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hello\"}}]}",
+            "data: {\"choices\":[{\"delta\":{\"content\":\", wor\"}}]}",
+        ] {
+            progress.record(&EventContent::parse(event, true));
+        }
+        assert_eq!(progress.generated, "Hello, wor");
+        assert!(!progress.finished && !progress.done);
+
+        let chat = resumer(
+            CHAT_ROUTE,
+            json!({"model": "m", "stream": true, "max_tokens": 10,
+                   "messages": [{"role": "user", "content": "hi"}]}),
+        )
+        .unwrap();
+        let next = chat.continuation(&progress);
+        assert_eq!(next["messages"][1]["content"], "Hello, wor");
+        assert_eq!(next["continue_final_message"], true);
+        assert_eq!(next["max_tokens"], 8);
+
+        let completion =
+            resumer(COMPLETIONS_ROUTE, json!({"model": "m", "prompt": "Say: "})).unwrap();
+        assert_eq!(
+            completion.continuation(&progress)["prompt"],
+            "Say: Hello, wor"
+        );
+
+        // Multi-choice and token-array prompts cannot be continued.
+        assert!(resumer(CHAT_ROUTE, json!({"messages": [], "n": 2})).is_none());
+        assert!(resumer(COMPLETIONS_ROUTE, json!({"prompt": [1, 2]})).is_none());
+    }
+
+    fn delta(text: &str) -> (Bytes, EventContent) {
+        let content = EventContent {
+            text: Some(text.to_string()),
+            ..Default::default()
+        };
+        (Bytes::from(format!("data: {text}\n\n")), content)
+    }
+
+    fn push_all(filter: &mut OverlapFilter, texts: &[&str]) -> Vec<String> {
+        let mut forwarded = Vec::new();
+        for text in texts {
+            let (event, content) = delta(text);
+            for (_, content) in filter.push(event, content) {
+                filter.sent(&content);
+                forwarded.extend(content.text);
+            }
+        }
+        forwarded
+    }
+
+    #[test]
+    fn overlapping_deltas_are_dropped_after_switch() {
+        let mut filter = OverlapFilter::default();
+        assert_eq!(
+            push_all(&mut filter, &["The", " quick", " brown", " fox"]),
+            ["The", " quick", " brown", " fox"]
+        );
+
+        // The new worker resends the last three tokens before continuing.
+        filter.arm();
+        assert_eq!(
+            push_all(&mut filter, &[" quick", " brown", " fox", " jumps"]),
+            [" jumps"]
+        );
+
+        // No overlap: held deltas are released once alignment fails.
+        filter.arm();
+        assert_eq!(
+            push_all(&mut filter, &[" quick", " over"]),
+            [" quick", " over"]
+        );
+    }
+
+    #[test]
+    fn short_repeats_are_not_treated_as_overlap() {
+        let mut filter = OverlapFilter::default();
+        push_all(&mut filter, &["It", " is", " very"]);
+
+        // " very very" is what the model meant to say.
+        filter.arm();
+        assert_eq!(
+            push_all(&mut filter, &[" very", " good"]),
+            [" very", " good"]
+        );
+    }
+
+    #[test]
+    fn resumed_role_preamble_is_dropped() {
+        let mut filter = OverlapFilter::default();
+        push_all(&mut filter, &["Hi"]);
+        filter.arm();
+
+        let role = "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"\"}}]}";
+        let content = EventContent::parse(role, true);
+        assert!(content.preamble);
+        assert!(filter
+            .push(Bytes::from_static(role.as_bytes()), content)
+            .is_empty());
+        assert_eq!(push_all(&mut filter, &[" there"]), [" there"]);
+
+        // Only the resumed stream's opening preamble is dropped.
+        let content = EventContent::parse(role, true);
+        assert_eq!(
+            filter
+                .push(Bytes::from_static(role.as_bytes()), content)
+                .len(),
+            1
+        );
+    }
+
+    /// Relay `chunks` from a single worker; nothing can be resumed.
+    async fn relay_chunks(chunks: &[&'static str]) -> Vec<Result<String, String>> {
+        let upstream = futures_util::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok::<_, reqwest::Error>(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>(),
+        )
+        .boxed();
+        let resumer = resumer(
+            COMPLETIONS_ROUTE,
+            json!({"model": "m", "prompt": "Say: ", "stream": true}),
+        )
+        .unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        relay("http://w1".to_string(), upstream, None, resumer, tx).await;
+
+        let mut received = Vec::new();
+        while let Ok(item) = rx.try_recv() {
+            received.push(item.map(|bytes| String::from_utf8(bytes.to_vec()).unwrap()));
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn crlf_events_are_forwarded_as_they_complete() {
+        let received = relay_chunks(&[
+            "data: {\"choices\":[{\"text\":\"a\"}]}\r\n\r\ndata: {\"choi",
+            "ces\":[{\"text\":\"b\"}]}\r\n\r\n",
+            "data: [DONE]\r\n\r\n",
+        ])
+        .await;
+        assert_eq!(
+            received,
+            [
+                Ok("data: {\"choices\":[{\"text\":\"a\"}]}\n\n".to_string()),
+                Ok("data: {\"choices\":[{\"text\":\"b\"}]}\n\n".to_string()),
+                Ok("data: [DONE]\n\n".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn final_event_without_blank_line_is_not_truncation() {
+        let received =
+            relay_chunks(&["data: {\"choices\":[{\"text\":\"a\"}]}\n\ndata: [DONE]\n"]).await;
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1], Ok("data: [DONE]\n\n".to_string()));
+
+        // A torn event at a real drop is discarded and the drop reported.
+        let received =
+            relay_chunks(&["data: {\"choices\":[{\"text\":\"a\"}]}\n\ndata: {\"cho"]).await;
+        assert_eq!(received.len(), 2);
+        assert!(received[1]
+            .as_ref()
+            .is_err_and(|e| e.contains("no other worker")));
+    }
+}
@@ -15,6 +15,7 @@ use openai_protocol::{
     completion::CompletionRequest,
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
+    images::ImageGenerationRequest,
     interactions::InteractionsRequest,
     messages::CreateMessageRequest,
     realtime_session::{
@@ -23,12 +24,15 @@ use openai_protocol::{
     },
     rerank::RerankRequest,
     responses::ResponsesRequest,
+    score::ScoreRequest,
     transcription::{AudioFile, TranscriptionRequest},
 };
 
 use crate::middleware::TenantRequestMeta;
 
 pub mod anthropic;
+pub mod async_generation;
+pub mod chat_completions;
 pub mod common;
 pub mod conversations;
 pub mod error;
@@ -41,6 +45,8 @@ pub mod parse;
 pub mod responses;
 pub mod router_manager;
 pub mod tokenize;
+pub mod translation;
+pub mod vector_stores;
 
 pub use factory::RouterFactory;
 // Re-export HTTP routers for convenience
@@ -174,6 +180,32 @@ pub trait RouterTrait: Send + Sync + Debug {
         (StatusCode::NOT_IMPLEMENTED, "Classify not implemented").into_response()
     }
 
+    /// Route scoring requests (SGLang-compatible /v1/score)
+    async fn route_score(
+        &self,
+        _headers: Option<&HeaderMap>,
+        _tenant_meta: &TenantRequestMeta,
+        _body: &ScoreRequest,
+        _model_id: &str,
+    ) -> Response {
+        (StatusCode::NOT_IMPLEMENTED, "Score not implemented").into_response()
+    }
+
+    /// Route image generation requests (OpenAI-compatible /v1/images/generations)
+    async fn route_image_generation(
+        &self,
+        _headers: Option<&HeaderMap>,
+        _tenant_meta: &TenantRequestMeta,
+        _body: &ImageGenerationRequest,
+        _model_id: &str,
+    ) -> Response {
+        (
+            StatusCode::NOT_IMPLEMENTED,
+            "Image generation not implemented",
+        )
+            .into_response()
+    }
+
     /// Route audio transcription requests (OpenAI-compatible /v1/audio/transcriptions).
     ///
     /// Unlike the JSON-bodied endpoints, `/v1/audio/transcriptions` uses
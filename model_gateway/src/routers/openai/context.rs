@@ -14,7 +14,7 @@ use smg_mcp::{McpOrchestrator, McpToolSession};
 use super::provider::Provider;
 use crate::{
     config::RouterConfig, middleware::TenantRequestMeta, routers::common::openai_bridge,
-    worker::Worker,
+    tenant::TenantKey, worker::Worker,
 };
 
 pub struct RequestContext {
@@ -230,6 +230,8 @@ pub struct StorageHandles {
     pub conversation: Arc<dyn ConversationStorage>,
     pub conversation_item: Arc<dyn ConversationItemStorage>,
     pub request_context: Option<StorageRequestContext>,
+    /// Owner recorded on the persisted response.
+    pub tenant_key: Option<TenantKey>,
 }
 
 pub struct OwnedStreamingContext {
@@ -276,6 +278,9 @@ impl RequestContext {
                 conversation,
                 conversation_item,
                 request_context: self.storage_request_context,
+                tenant_key: self
+                    .tenant_request_meta
+                    .map(|meta| meta.tenant_key().clone()),
             },
         })
     }
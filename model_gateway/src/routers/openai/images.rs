@@ -0,0 +1,209 @@
+//! Image generation routing for the OpenAI router.
+//!
+//! Forwards `/v1/images/generations` to an upstream image provider (OpenAI,
+//! Stability), translating the request and response through the provider
+//! adapter, then hands the OpenAI-shaped result to the shared image handling
+//! for file store hosting and per-image metering.
+
+use std::time::Instant;
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use openai_protocol::images::ImageGenerationRequest;
+use serde_json::{to_value, Value};
+
+use super::{provider::ProviderRegistry, router::resolve_provider};
+use crate::{
+    config::types::RetryConfig,
+    observability::metrics::{metrics_labels, Metrics},
+    routers::{
+        common::{
+            header_utils::{apply_provider_headers, extract_auth_header},
+            images::{self, ImageStore},
+            retry::{is_retryable_status, RetryExecutor},
+            worker_selection::{SelectWorkerRequest, WorkerSelector},
+        },
+        error,
+    },
+    worker::{Endpoint, ProviderType, WorkerRegistry},
+};
+
+/// Shared context passed to image routing functions.
+pub(super) struct ImagesRouterContext<'a> {
+    pub worker_registry: &'a WorkerRegistry,
+    pub provider_registry: &'a ProviderRegistry,
+    pub client: &'a reqwest::Client,
+    pub retry_config: &'a RetryConfig,
+    pub image_store: Option<&'a ImageStore>,
+}
+
+fn record_error(model: &str, error_type: &'static str) {
+    Metrics::record_router_error(
+        metrics_labels::ROUTER_OPENAI,
+        metrics_labels::BACKEND_EXTERNAL,
+        metrics_labels::CONNECTION_HTTP,
+        model,
+        metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
+        error_type,
+    );
+}
+
+/// Route an image generation request to the appropriate upstream provider.
+pub(super) async fn route_image_generation(
+    deps: &ImagesRouterContext<'_>,
+    headers: Option<&HeaderMap>,
+    body: &ImageGenerationRequest,
+    model_id: &str,
+) -> Response {
+    let start = Instant::now();
+    let model = model_id;
+
+    Metrics::record_router_request(
+        metrics_labels::ROUTER_OPENAI,
+        metrics_labels::BACKEND_EXTERNAL,
+        metrics_labels::CONNECTION_HTTP,
+        model,
+        metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
+        "false",
+    );
+
+    let selector = WorkerSelector::new(deps.worker_registry, deps.client);
+    let worker = match selector
+        .select_worker(&SelectWorkerRequest {
+            model_id: model,
+            headers,
+            provider: Some(ProviderType::from_model_name(model).unwrap_or(ProviderType::OpenAI)),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(w) => w,
+        Err(response) => {
+            record_error(model, metrics_labels::ERROR_NO_WORKERS);
+            return response;
+        }
+    };
+
+    let requested = body.response_format();
+    let mut upstream = body.clone();
+    upstream.model = model.to_owned();
+    upstream.response_format = Some(images::upstream_response_format(
+        requested,
+        deps.image_store,
+    ));
+    let mut payload = match to_value(&upstream) {
+        Ok(v) => v,
+        Err(e) => {
+            record_error(model, metrics_labels::ERROR_VALIDATION);
+            return error::bad_request(
+                "invalid_request",
+                format!("Failed to serialize request: {e}"),
+            );
+        }
+    };
+
+    let provider = resolve_provider(deps.provider_registry, worker.as_ref(), model);
+    if let Err(e) = provider.transform_request(&mut payload, Endpoint::ImageGenerations) {
+        record_error(model, metrics_labels::ERROR_VALIDATION);
+        return error::bad_request("invalid_request", format!("Provider transform error: {e}"));
+    }
+
+    let url = format!(
+        "{}{}",
+        worker.url(),
+        provider.request_path(Endpoint::ImageGenerations, model)
+    );
+    let auth_header = extract_auth_header(headers, worker.api_key());
+
+    let response = RetryExecutor::execute_response_with_retry(
+        deps.retry_config,
+        |_attempt| {
+            let (url, payload, worker, provider) = (&url, &payload, &worker, &provider);
+            let auth_header = auth_header.as_ref();
+
+            async move {
+                let req = apply_provider_headers(deps.client.post(url), url, auth_header);
+                let req = provider.apply_headers(req.json(payload));
+
+                let resp = match req.send().await {
+                    Ok(r) => r,
+                    Err(e) => {
+                        worker.record_outcome(503);
+                        return error::service_unavailable(
+                            "upstream_error",
+                            format!("Failed to contact upstream: {e}"),
+                        );
+                    }
+                };
+
+                let status = StatusCode::from_u16(resp.status().as_u16())
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                worker.record_outcome(status.as_u16());
+
+                let mut json = match resp.json::<Value>().await {
+                    Ok(json) => json,
+                    Err(e) => {
+                        return error::bad_gateway(
+                            "upstream_error",
+                            format!("Failed to read response: {e}"),
+                        )
+                    }
+                };
+                if status.is_success() {
+                    if let Err(e) =
+                        provider.transform_response(&mut json, Endpoint::ImageGenerations)
+                    {
+                        return error::bad_gateway(
+                            "upstream_error",
+                            format!("Provider transform error: {e}"),
+                        );
+                    }
+                }
+                (status, Json(json)).into_response()
+            }
+        },
+        |res, _attempt| is_retryable_status(res.status()),
+        |delay, attempt| {
+            Metrics::record_worker_retry(
+                metrics_labels::BACKEND_EXTERNAL,
+                metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
+            );
+            Metrics::record_worker_retry_backoff(attempt, delay);
+        },
+        || {
+            Metrics::record_worker_retries_exhausted(
+                metrics_labels::BACKEND_EXTERNAL,
+                metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
+            );
+        },
+    )
+    .await;
+
+    let response = images::finish_image_response(
+        response,
+        requested,
+        deps.image_store,
+        metrics_labels::ROUTER_OPENAI,
+        metrics_labels::BACKEND_EXTERNAL,
+        model,
+    )
+    .await;
+
+    if response.status().is_success() {
+        Metrics::record_router_duration(
+            metrics_labels::ROUTER_OPENAI,
+            metrics_labels::BACKEND_EXTERNAL,
+            metrics_labels::CONNECTION_HTTP,
+            model,
+            metrics_labels::ENDPOINT_IMAGE_GENERATIONS,
+            start.elapsed(),
+        );
+    } else {
+        record_error(model, metrics_labels::ERROR_BACKEND);
+    }
+
+    response
+}
@@ -14,10 +14,16 @@ use axum::http::HeaderMap;
 use bytes::Bytes;
 use openai_protocol::{
     event_types::{is_function_call_type, ItemType, McpEvent, OutputItemEvent},
-    responses::{generate_id, ResponseInput, ResponseTool, ResponsesRequest},
+    responses::{
+        generate_id, McpElicitationAction, ResponseInput, ResponseInputOutputItem, ResponseTool,
+        ResponsesRequest,
+    },
 };
 use serde_json::{json, to_value, Value};
-use smg_mcp::{McpServerBinding, McpToolSession, ToolExecutionInput, ToolExecutionResult};
+use smg_mcp::{
+    ElicitationAction, ElicitationResponse, McpServerBinding, McpToolSession,
+    PendingElicitationExecution, ToolExecutionInput, ToolExecutionResult,
+};
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
@@ -856,10 +862,70 @@ pub(crate) async fn execute_tool_loop(
         existing_mcp_list_tools_labels.to_vec(),
     );
     let max_tool_calls = original_body.max_tool_calls.map(|n| n as usize);
+    let mut initial_payload = initial_payload;
+    let elicitation_responses = take_elicitation_items(&mut initial_payload, &mut state);
     let base_payload = initial_payload.clone();
     let tools_json = base_payload.get("tools").cloned().unwrap_or(json!([]));
     let mut current_payload = initial_payload;
 
+    // Finish tool calls that paused on elicitations answered in this input.
+    if !elicitation_responses.is_empty() {
+        for (elicitation_id, response) in elicitation_responses {
+            let result = session
+                .resume_elicitation(&elicitation_id, response)
+                .await
+                .map_err(|e| format!("failed to resume elicitation '{elicitation_id}': {e}"))?;
+            let tool_output = match result {
+                ToolExecutionResult::Executed(tool_output) => tool_output,
+                ToolExecutionResult::PendingElicitation(pending) => {
+                    let elicitation_item = build_mcp_elicitation_request_item(
+                        &pending,
+                        &session.resolve_tool_server_label(&pending.tool_name),
+                    );
+                    return build_approval_response(
+                        json!({ "id": generate_id("resp"), "object": "response" }),
+                        state,
+                        session,
+                        original_body,
+                        elicitation_item,
+                    );
+                }
+                ToolExecutionResult::PendingApproval(_) => {
+                    return Err(format!(
+                        "elicitation '{elicitation_id}' resumed into an approval request"
+                    ));
+                }
+            };
+
+            state.total_calls += 1;
+            let response_format =
+                openai_bridge::lookup_tool_format(session, format_registry, &tool_output.tool_name);
+            let transformed_item = build_transformed_mcp_call_item(
+                &tool_output.output,
+                response_format,
+                &non_streaming_tool_item_id_source(&tool_output.call_id, response_format),
+                &session.resolve_tool_server_label(&tool_output.tool_name),
+                &tool_output.tool_name,
+                &tool_output.arguments_str,
+            );
+            state.record_call(
+                session.is_builtin_tool(&tool_output.tool_name),
+                tool_output.call_id,
+                tool_output.tool_name,
+                tool_output.arguments_str,
+                tool_output.output.to_string(),
+                transformed_item,
+            );
+        }
+        current_payload = build_resume_payload(
+            &base_payload,
+            &state.conversation_history,
+            &state.original_input,
+            &tools_json,
+            false,
+        )?;
+    }
+
     info!(
         "Starting tool loop: max_tool_calls={:?}, max_iterations={}",
         max_tool_calls, DEFAULT_MAX_ITERATIONS
@@ -1026,6 +1092,17 @@ pub(crate) async fn execute_tool_loop(
                         approval_item,
                     );
                 }
+                ToolExecutionResult::PendingElicitation(pending) => {
+                    let elicitation_item =
+                        build_mcp_elicitation_request_item(&pending, &server_label);
+                    return build_approval_response(
+                        response_json,
+                        state,
+                        session,
+                        original_body,
+                        elicitation_item,
+                    );
+                }
             };
 
             Metrics::record_mcp_tool_duration(
@@ -1247,6 +1324,73 @@ fn build_mcp_approval_request_item(
     })
 }
 
+fn build_mcp_elicitation_request_item(
+    pending: &PendingElicitationExecution,
+    server_label: &str,
+) -> Value {
+    let mut item = json!({
+        "id": pending.elicitation.elicitation_id,
+        "type": ItemType::MCP_ELICITATION_REQUEST,
+        "server_label": server_label,
+        "message": pending.elicitation.message,
+    });
+    if let Some(schema) = &pending.elicitation.requested_schema {
+        item["requested_schema"] = schema.clone();
+    }
+    if let Some(url) = &pending.elicitation.url {
+        item["url"] = json!(url);
+    }
+    item
+}
+
+/// Remove elicitation items from the input and return the answers they carry.
+///
+/// The items are SMG extensions the upstream does not accept, so they are
+/// dropped from both the outgoing payload and the input kept for resumes.
+fn take_elicitation_items(
+    payload: &mut Value,
+    state: &mut ToolLoopState,
+) -> Vec<(String, ElicitationResponse)> {
+    let ResponseInput::Items(items) = &mut state.original_input else {
+        return Vec::new();
+    };
+
+    let mut responses = Vec::new();
+    items.retain(|item| match item {
+        ResponseInputOutputItem::McpElicitationResponse {
+            elicitation_request_id,
+            action,
+            content,
+            ..
+        } => {
+            responses.push((
+                elicitation_request_id.clone(),
+                ElicitationResponse {
+                    action: match action {
+                        McpElicitationAction::Accept => ElicitationAction::Accept,
+                        McpElicitationAction::Decline => ElicitationAction::Decline,
+                        McpElicitationAction::Cancel => ElicitationAction::Cancel,
+                    },
+                    content: content.clone(),
+                },
+            ));
+            false
+        }
+        ResponseInputOutputItem::McpElicitationRequest { .. } => false,
+        _ => true,
+    });
+
+    if let Some(input) = payload.get_mut("input").and_then(Value::as_array_mut) {
+        input.retain(|item| {
+            !matches!(
+                item.get("type").and_then(Value::as_str),
+                Some(ItemType::MCP_ELICITATION_REQUEST | ItemType::MCP_ELICITATION_RESPONSE)
+            )
+        });
+    }
+    responses
+}
+
 /// Build a transformed output item using ResponseTransformer
 ///
 /// Converts the output using the tool's response_format to the correctly-typed
@@ -2064,4 +2208,36 @@ mod tests {
 
         assert_eq!(resumed["tool_choice"], json!("auto"));
     }
+
+    #[test]
+    fn take_elicitation_items_strips_extension_items() {
+        let input = json!([
+            { "type": "message", "role": "user", "content": "sign me up" },
+            {
+                "type": "mcp_elicitation_request",
+                "id": "elicit_1",
+                "server_label": "forms",
+                "message": "Your email?"
+            },
+            {
+                "type": "mcp_elicitation_response",
+                "elicitation_request_id": "elicit_1",
+                "action": "accept",
+                "content": { "email": "user@example.com" }
+            }
+        ]);
+        let mut payload = json!({ "model": "m", "input": input.clone() });
+        let mut state = ToolLoopState::new(
+            ResponseInput::Items(serde_json::from_value(input).expect("input items")),
+            vec![],
+        );
+
+        let responses = super::take_elicitation_items(&mut payload, &mut state);
+
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].0, "elicit_1");
+        assert_eq!(responses[0].1.action, smg_mcp::ElicitationAction::Accept);
+        assert_eq!(payload["input"].as_array().map(Vec::len), Some(1));
+        assert!(matches!(&state.original_input, ResponseInput::Items(items) if items.len() == 1));
+    }
 }
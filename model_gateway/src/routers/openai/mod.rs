@@ -6,14 +6,16 @@
 //! - Response storage and conversation management
 //! - Multi-turn tool execution loops
 //! - SSE (Server-Sent Events) streaming
+//! - Image generation through provider adapters (OpenAI, Stability)
 
 mod chat;
 mod context;
 mod health;
+mod images;
 pub(crate) mod mcp;
 mod provider;
 pub mod responses;
 mod router;
 
-pub(crate) use provider::strip_default_sglang_fields;
+pub(crate) use provider::{strip_default_sglang_fields, SGLANG_FIELDS};
 pub use router::OpenAIRouter;
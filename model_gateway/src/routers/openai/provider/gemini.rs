@@ -15,7 +15,7 @@ impl Provider for GeminiProvider {
         payload: &mut Value,
         endpoint: Endpoint,
     ) -> Result<(), ProviderError> {
-        super::types::strip_sglang_fields(payload);
+        super::types::strip_sglang_fields(payload, endpoint);
 
         if endpoint == Endpoint::Chat {
             if let Some(obj) = payload.as_object_mut() {
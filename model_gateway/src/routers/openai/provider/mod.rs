@@ -6,6 +6,7 @@ mod openai;
 mod provider_trait;
 mod registry;
 mod sglang;
+mod stability;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -17,6 +18,7 @@ pub use openai::OpenAIProvider;
 pub use provider_trait::Provider;
 pub use registry::ProviderRegistry;
 pub use sglang::SGLangProvider;
-pub(crate) use types::strip_default_sglang_fields;
+pub use stability::StabilityProvider;
 pub use types::ProviderError;
+pub(crate) use types::{strip_default_sglang_fields, SGLANG_FIELDS};
 pub use xai::XAIProvider;
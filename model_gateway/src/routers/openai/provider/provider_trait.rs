@@ -11,9 +11,9 @@ pub trait Provider: Send + Sync {
     fn transform_request(
         &self,
         payload: &mut Value,
-        _endpoint: Endpoint,
+        endpoint: Endpoint,
     ) -> Result<(), ProviderError> {
-        strip_sglang_fields(payload);
+        strip_sglang_fields(payload, endpoint);
         Ok(())
     }
 
@@ -28,4 +28,9 @@ pub trait Provider: Send + Sync {
     fn apply_headers(&self, builder: RequestBuilder) -> RequestBuilder {
         builder
     }
+
+    /// Upstream path for `endpoint`; providers with non-OpenAI routes override it.
+    fn request_path(&self, endpoint: Endpoint, _model: &str) -> String {
+        endpoint.path().to_string()
+    }
 }
@@ -1,7 +1,8 @@
 use std::{collections::HashMap, sync::Arc};
 
 use super::{
-    AnthropicProvider, GeminiProvider, OpenAIProvider, Provider, SGLangProvider, XAIProvider,
+    AnthropicProvider, GeminiProvider, OpenAIProvider, Provider, SGLangProvider, StabilityProvider,
+    XAIProvider,
 };
 use crate::worker::ProviderType;
 
@@ -36,6 +37,10 @@ impl ProviderRegistry {
             ProviderType::Anthropic,
             Arc::new(AnthropicProvider) as Arc<dyn Provider>,
         );
+        providers.insert(
+            ProviderType::Stability,
+            Arc::new(StabilityProvider) as Arc<dyn Provider>,
+        );
 
         Self {
             providers,
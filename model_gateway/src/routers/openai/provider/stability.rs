@@ -0,0 +1,118 @@
+use reqwest::RequestBuilder;
+use serde_json::{json, Map, Value};
+
+use super::{Provider, ProviderError};
+use crate::worker::{Endpoint, ProviderType};
+
+/// OpenAI image styles with no Stability `style_preset` equivalent.
+const OPENAI_IMAGE_STYLES: &[&str] = &["vivid", "natural"];
+
+pub struct StabilityProvider;
+
+impl Provider for StabilityProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Stability
+    }
+
+    fn transform_request(
+        &self,
+        payload: &mut Value,
+        endpoint: Endpoint,
+    ) -> Result<(), ProviderError> {
+        if endpoint != Endpoint::ImageGenerations {
+            return Err(ProviderError::UnsupportedEndpoint(endpoint));
+        }
+        let obj = payload
+            .as_object()
+            .ok_or_else(|| ProviderError::TransformError("expected a JSON object".to_string()))?;
+        *payload = Value::Object(Self::text_to_image_body(obj)?);
+        Ok(())
+    }
+
+    fn transform_response(
+        &self,
+        response: &mut Value,
+        endpoint: Endpoint,
+    ) -> Result<(), ProviderError> {
+        if endpoint != Endpoint::ImageGenerations {
+            return Err(ProviderError::UnsupportedEndpoint(endpoint));
+        }
+        let artifacts = response
+            .get("artifacts")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                ProviderError::TransformError("missing `artifacts` in response".to_string())
+            })?;
+        let data: Vec<Value> = artifacts
+            .iter()
+            .filter_map(|artifact| artifact.get("base64").cloned())
+            .map(|b64| json!({ "b64_json": b64 }))
+            .collect();
+        *response = json!({
+            "created": chrono::Utc::now().timestamp(),
+            "data": data,
+        });
+        Ok(())
+    }
+
+    fn apply_headers(&self, builder: RequestBuilder) -> RequestBuilder {
+        builder.header("Accept", "application/json")
+    }
+
+    fn request_path(&self, endpoint: Endpoint, model: &str) -> String {
+        match endpoint {
+            Endpoint::ImageGenerations => format!("/v1/generation/{model}/text-to-image"),
+            _ => endpoint.path().to_string(),
+        }
+    }
+}
+
+impl StabilityProvider {
+    /// Map an OpenAI image request onto Stability's v1 text-to-image body.
+    ///
+    /// `response_format`, `quality`, and `user` have no Stability equivalent;
+    /// Stability always answers with base64 artifacts.
+    fn text_to_image_body(obj: &Map<String, Value>) -> Result<Map<String, Value>, ProviderError> {
+        let prompt = obj
+            .get("prompt")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ProviderError::TransformError("missing `prompt`".to_string()))?;
+
+        let mut text_prompts = vec![json!({ "text": prompt, "weight": 1.0 })];
+        if let Some(negative) = obj.get("negative_prompt").and_then(Value::as_str) {
+            text_prompts.push(json!({ "text": negative, "weight": -1.0 }));
+        }
+
+        let mut body = Map::new();
+        body.insert("text_prompts".to_string(), Value::Array(text_prompts));
+        if let Some(n) = obj.get("n") {
+            body.insert("samples".to_string(), n.clone());
+        }
+        if let Some((width, height)) = obj
+            .get("size")
+            .and_then(Value::as_str)
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(w, h)| Some((w.parse::<u32>().ok()?, h.parse::<u32>().ok()?)))
+        {
+            body.insert("width".to_string(), width.into());
+            body.insert("height".to_string(), height.into());
+        }
+        for (from, to) in [
+            ("guidance_scale", "cfg_scale"),
+            ("num_inference_steps", "steps"),
+            ("seed", "seed"),
+        ] {
+            if let Some(value) = obj.get(from).filter(|v| !v.is_null()) {
+                body.insert(to.to_string(), value.clone());
+            }
+        }
+        if let Some(style) = obj
+            .get("style")
+            .and_then(Value::as_str)
+            .filter(|style| !OPENAI_IMAGE_STYLES.contains(style))
+        {
+            body.insert("style_preset".to_string(), style.into());
+        }
+        Ok(body)
+    }
+}
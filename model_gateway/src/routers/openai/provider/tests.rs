@@ -13,7 +13,9 @@ use openai_protocol::{
 };
 use serde_json::{json, to_value, Value};
 
-use super::{types::strip_default_sglang_fields, OpenAIProvider, Provider, XAIProvider};
+use super::{
+    types::strip_default_sglang_fields, OpenAIProvider, Provider, StabilityProvider, XAIProvider,
+};
 use crate::worker::Endpoint;
 
 /// Build a `ResponsesRequest` whose single input message carries every
@@ -269,3 +271,98 @@ fn xai_provider_is_noop_for_chat_endpoint() {
     let content = first_content_array(&payload);
     assert_eq!(content[4]["type"], json!("output_text"));
 }
+
+#[test]
+fn sampling_seed_is_promoted_to_seed_for_chat() {
+    let mut payload = json!({"model": "m", "messages": [], "sampling_seed": 7});
+    OpenAIProvider
+        .transform_request(&mut payload, Endpoint::Chat)
+        .expect("default OpenAI transform is infallible");
+    assert_eq!(payload.get("seed"), Some(&json!(7)));
+    assert_eq!(payload.get("sampling_seed"), None);
+
+    // An explicit OpenAI seed wins over the extension.
+    let mut payload = json!({"model": "m", "messages": [], "seed": 1, "sampling_seed": 7});
+    OpenAIProvider
+        .transform_request(&mut payload, Endpoint::Chat)
+        .expect("default OpenAI transform is infallible");
+    assert_eq!(payload.get("seed"), Some(&json!(1)));
+}
+
+#[test]
+fn seed_extension_is_dropped_for_responses() {
+    let req = ResponsesRequest {
+        seed: Some(42),
+        ..request_with_all_content_parts()
+    };
+    let mut payload = to_value(&req).expect("serialize request");
+    OpenAIProvider
+        .transform_request(&mut payload, Endpoint::Responses)
+        .expect("default OpenAI transform is infallible");
+    assert_eq!(payload.get("seed"), None);
+}
+
+#[test]
+fn image_extensions_are_dropped_for_openai() {
+    let mut payload = json!({
+        "model": "dall-e-3",
+        "prompt": "a lighthouse",
+        "negative_prompt": "fog",
+        "num_inference_steps": 30,
+        "guidance_scale": 7.5,
+        "seed": 3,
+    });
+    OpenAIProvider
+        .transform_request(&mut payload, Endpoint::ImageGenerations)
+        .expect("default OpenAI transform is infallible");
+    assert_eq!(
+        payload,
+        json!({"model": "dall-e-3", "prompt": "a lighthouse"})
+    );
+}
+
+#[test]
+fn stability_maps_image_requests_to_text_to_image() {
+    let provider = StabilityProvider;
+    let mut payload = json!({
+        "model": "stable-diffusion-xl-1024-v1-0",
+        "prompt": "a lighthouse",
+        "negative_prompt": "fog",
+        "n": 2,
+        "size": "1024x768",
+        "guidance_scale": 7.0,
+        "style": "vivid",
+        "response_format": "b64_json",
+    });
+    provider
+        .transform_request(&mut payload, Endpoint::ImageGenerations)
+        .expect("image request maps onto Stability");
+    assert_eq!(
+        payload,
+        json!({
+            "text_prompts": [
+                {"text": "a lighthouse", "weight": 1.0},
+                {"text": "fog", "weight": -1.0},
+            ],
+            "samples": 2,
+            "width": 1024,
+            "height": 768,
+            "cfg_scale": 7.0,
+        })
+    );
+    assert_eq!(
+        provider.request_path(Endpoint::ImageGenerations, "stable-diffusion-xl-1024-v1-0"),
+        "/v1/generation/stable-diffusion-xl-1024-v1-0/text-to-image"
+    );
+
+    let mut response =
+        json!({"artifacts": [{"base64": "aGk=", "seed": 1, "finishReason": "SUCCESS"}]});
+    provider
+        .transform_response(&mut response, Endpoint::ImageGenerations)
+        .expect("artifacts map onto OpenAI data");
+    assert_eq!(response["data"], json!([{"b64_json": "aGk="}]));
+
+    assert!(provider
+        .transform_request(&mut json!({}), Endpoint::Chat)
+        .is_err());
+}
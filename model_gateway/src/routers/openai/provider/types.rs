@@ -29,8 +29,33 @@ pub(crate) const SGLANG_FIELDS: &[&str] = &[
     "backend_url",
 ];
 
-pub(crate) fn strip_sglang_fields(payload: &mut Value) {
+/// Diffusion extensions on image generation requests that OpenAI rejects.
+pub(crate) const IMAGE_EXTENSION_FIELDS: &[&str] = &[
+    "negative_prompt",
+    "num_inference_steps",
+    "guidance_scale",
+    "seed",
+];
+
+/// Remove SGLang extension fields before forwarding to an external provider.
+///
+/// `sampling_seed` is promoted to the OpenAI `seed` when the caller did not set
+/// one, so determinism requests survive the translation. The Responses API has
+/// no `seed` upstream, so the extension is dropped there, and image requests
+/// lose their diffusion knobs.
+pub(crate) fn strip_sglang_fields(payload: &mut Value, endpoint: Endpoint) {
     if let Some(obj) = payload.as_object_mut() {
+        if endpoint == Endpoint::Responses {
+            obj.remove("seed");
+        } else if endpoint == Endpoint::ImageGenerations {
+            for field in IMAGE_EXTENSION_FIELDS {
+                obj.remove(*field);
+            }
+        } else if obj.get("seed").is_none_or(Value::is_null) {
+            if let Some(seed) = obj.get("sampling_seed").filter(|v| !v.is_null()).cloned() {
+                obj.insert("seed".to_string(), seed);
+            }
+        }
         for field in SGLANG_FIELDS {
             obj.remove(*field);
         }
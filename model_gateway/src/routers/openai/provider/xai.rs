@@ -15,7 +15,7 @@ impl Provider for XAIProvider {
         payload: &mut Value,
         endpoint: Endpoint,
     ) -> Result<(), ProviderError> {
-        super::types::strip_sglang_fields(payload);
+        super::types::strip_sglang_fields(payload, endpoint);
 
         if endpoint == Endpoint::Responses {
             if let Some(obj) = payload.as_object_mut() {
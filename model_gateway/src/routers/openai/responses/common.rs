@@ -22,56 +22,6 @@ pub(crate) fn get_event_type<'a>(event_name: Option<&'a str>, parsed: &'a Value)
         .unwrap_or("")
 }
 
-/// Processes incoming byte chunks into complete SSE blocks.
-/// Handles buffering of partial chunks and CRLF normalization.
-pub(super) struct ChunkProcessor {
-    pending: String,
-}
-
-impl ChunkProcessor {
-    pub fn new() -> Self {
-        Self {
-            pending: String::new(),
-        }
-    }
-
-    /// Append a chunk to the buffer, normalizing line endings
-    pub fn push_chunk(&mut self, chunk: &[u8]) {
-        let chunk_str = match std::str::from_utf8(chunk) {
-            Ok(s) => Cow::Borrowed(s),
-            Err(_) => Cow::Owned(String::from_utf8_lossy(chunk).into_owned()),
-        };
-        if chunk_str.contains('\r') {
-            self.pending.push_str(&chunk_str.replace("\r\n", "\n"));
-        } else {
-            self.pending.push_str(&chunk_str);
-        }
-    }
-
-    /// Extract the next complete SSE block from the buffer, if available
-    pub fn next_block(&mut self) -> Option<String> {
-        loop {
-            let pos = self.pending.find("\n\n")?;
-            let block = self.pending[..pos].to_string();
-            self.pending.drain(..pos + 2);
-
-            if !block.trim().is_empty() {
-                return Some(block);
-            }
-        }
-    }
-
-    /// Check if there's remaining content in the buffer
-    pub fn has_remaining(&self) -> bool {
-        !self.pending.trim().is_empty()
-    }
-
-    /// Take any remaining content from the buffer
-    pub fn take_remaining(&mut self) -> String {
-        std::mem::take(&mut self.pending)
-    }
-}
-
 /// Parse an SSE block into event name and data.
 ///
 /// Delegates field parsing to the shared [`parse_block`] codec. Returns
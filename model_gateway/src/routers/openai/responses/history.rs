@@ -17,11 +17,43 @@ use tracing::warn;
 use super::super::context::ResponsesComponents;
 use crate::{
     observability::metrics::{metrics_labels, Metrics},
-    routers::{common::persistence_utils::split_stored_message_content, error},
+    routers::{
+        common::{
+            persistence_utils::{item_to_json, tenant_owns_response},
+            tool_history::normalize_history,
+        },
+        error,
+    },
+    tenant::TenantKey,
 };
 
 const MAX_CONVERSATION_HISTORY_ITEMS: usize = 100;
 
+/// Conversation item types replayed as input. `tool_use` and `tool_result`
+/// are Anthropic-shaped items that [`normalize_history`] rewrites; reasoning
+/// without `encrypted_content` is dropped later, before the upstream call.
+const REPLAYED_ITEM_TYPES: &[&str] = &[
+    "message",
+    ItemType::FUNCTION_CALL,
+    ItemType::FUNCTION_CALL_OUTPUT,
+    "reasoning",
+    "tool_use",
+    "tool_result",
+];
+
+/// Reject history that cannot be replayed, see [`normalize_history`].
+fn invalid_history_error(model: &str, message: String) -> Response {
+    Metrics::record_router_error(
+        metrics_labels::ROUTER_OPENAI,
+        metrics_labels::BACKEND_EXTERNAL,
+        metrics_labels::CONNECTION_HTTP,
+        model,
+        metrics_labels::ENDPOINT_RESPONSES,
+        metrics_labels::ERROR_VALIDATION,
+    );
+    error::bad_request("invalid_history", message)
+}
+
 pub(crate) struct LoadedInputHistory {
     pub previous_response_id: Option<String>,
     pub existing_mcp_list_tools_labels: Vec<String>,
@@ -36,6 +68,7 @@ pub(crate) async fn load_input_history(
     conversation: Option<&str>,
     request_body: &mut ResponsesRequest,
     model: &str,
+    tenant_key: &TenantKey,
 ) -> Result<LoadedInputHistory, Response> {
     let previous_response_id = request_body
         .previous_response_id
@@ -52,28 +85,38 @@ pub(crate) async fn load_input_history(
             .get_response_chain(&prev_id, None)
             .await
         {
-            Ok(chain) if !chain.responses.is_empty() => {
+            // A chain that reaches another tenant's response is reported as
+            // missing so response ids never leak across tenants.
+            Ok(chain)
+                if !chain.responses.is_empty()
+                    && chain
+                        .responses
+                        .iter()
+                        .all(|stored| tenant_owns_response(stored, tenant_key)) =>
+            {
                 existing_mcp_list_tools_labels.extend(chain.responses.iter().flat_map(|stored| {
                     extract_mcp_list_tools_labels(
                         stored.raw_response.get("output").unwrap_or(&Value::Null),
                     )
                 }));
 
-                let items: Vec<ResponseInputOutputItem> = chain
+                let history: Vec<Value> = chain
                     .responses
                     .iter()
                     .flat_map(|stored| {
-                        deserialize_items_from_array(&stored.input)
+                        let input = stored.input.as_array().into_iter().flatten();
+                        let output = stored
+                            .raw_response
+                            .get("output")
+                            .and_then(Value::as_array)
                             .into_iter()
-                            .chain(deserialize_items_from_array(
-                                stored
-                                    .raw_response
-                                    .get("output")
-                                    .unwrap_or(&Value::Array(vec![])),
-                            ))
+                            .flatten();
+                        input.chain(output).cloned()
                     })
                     .collect();
-                chain_items = Some(items);
+                let history =
+                    normalize_history(history).map_err(|e| invalid_history_error(model, e))?;
+                chain_items = Some(deserialize_items_from_array(&Value::Array(history)));
             }
             Ok(_) | Err(ResponseStorageError::ResponseNotFound(_)) => {
                 Metrics::record_router_error(
@@ -145,68 +188,20 @@ pub(crate) async fn load_input_history(
             .await
         {
             Ok(stored_items) => {
-                let mut items: Vec<ResponseInputOutputItem> = Vec::new();
-                for item in stored_items {
-                    match item.item_type.as_str() {
-                        "message" => {
-                            // Stored content may be either the raw content array
-                            // (legacy shape) or an object `{content: [...], phase: ...}`
-                            // when the message carried a phase label (P3).
-                            let (content_value, stored_phase) =
-                                split_stored_message_content(item.content);
-                            match serde_json::from_value::<Vec<ResponseContentPart>>(content_value)
-                            {
-                                Ok(content_parts) => {
-                                    items.push(ResponseInputOutputItem::Message {
-                                        id: item.id.0.clone(),
-                                        role: item
-                                            .role
-                                            .clone()
-                                            .unwrap_or_else(|| "user".to_string()),
-                                        content: content_parts,
-                                        status: item.status.clone(),
-                                        phase: stored_phase,
-                                    });
-                                }
-                                Err(e) => {
-                                    tracing::error!("Failed to deserialize message content: {}", e);
-                                }
-                            }
-                        }
-                        ItemType::FUNCTION_CALL => {
-                            match serde_json::from_value::<ResponseInputOutputItem>(item.content) {
-                                Ok(func_call) => items.push(func_call),
-                                Err(e) => {
-                                    tracing::error!("Failed to deserialize function_call: {}", e);
-                                }
-                            }
-                        }
-                        ItemType::FUNCTION_CALL_OUTPUT => {
-                            tracing::debug!(
-                                item_id = %item.id.0,
-                                "Loading function_call_output from DB"
-                            );
-                            match serde_json::from_value::<ResponseInputOutputItem>(item.content) {
-                                Ok(func_output) => {
-                                    tracing::debug!(
-                                        "Successfully deserialized function_call_output"
-                                    );
-                                    items.push(func_output);
-                                }
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Failed to deserialize function_call_output: {}",
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                        "reasoning" => {}
-                        _ => {
+                let history: Vec<Value> = stored_items
+                    .iter()
+                    .filter(|item| {
+                        let replayed = REPLAYED_ITEM_TYPES.contains(&item.item_type.as_str());
+                        if !replayed {
                             warn!("Unknown item type in conversation: {}", item.item_type);
                         }
-                    }
-                }
+                        replayed
+                    })
+                    .map(item_to_json)
+                    .collect();
+                let history =
+                    normalize_history(history).map_err(|e| invalid_history_error(model, e))?;
+                let mut items = deserialize_items_from_array(&Value::Array(history));
 
                 append_current_input(&mut items, &request_body.input, conv_id_str);
                 request_body.input = ResponseInput::Items(items);
@@ -99,6 +99,9 @@ pub async fn handle_non_streaming_response(mut ctx: RequestContext) -> Response
         if let Some(tools) = original_body.tools.as_deref() {
             openai_bridge::configure_response_tools_approval(&mut session, tools);
         }
+        // The tool loop can return server elicitations to the client and
+        // resume on the answer.
+        session.set_elicitation_forwarding(true);
         prepare_mcp_tools_as_functions(&mut payload, &session);
 
         match execute_tool_loop(
@@ -189,6 +192,9 @@ pub async fn handle_non_streaming_response(mut ctx: RequestContext) -> Response
             resp_storage.clone(),
             &response_json,
             original_body,
+            ctx.tenant_request_meta
+                .as_ref()
+                .map(|meta| meta.tenant_key()),
             ctx.storage_request_context.clone(),
         )
         .await
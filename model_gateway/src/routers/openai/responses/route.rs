@@ -118,6 +118,7 @@ pub(in crate::routers::openai) async fn route_responses(
         conversation.map(|c| c.as_id()),
         &mut request_body,
         model,
+        tenant_meta.tenant_key(),
     )
     .await
     {
@@ -127,7 +128,7 @@ pub(in crate::routers::openai) async fn route_responses(
 
     request_body.store = Some(false);
     if let ResponseInput::Items(ref mut items) = request_body.input {
-        items.retain(|item| !matches!(item, ResponseInputOutputItem::Reasoning { .. }));
+        items.retain(is_replayable_upstream);
     }
 
     let mut payload = match to_value(&request_body) {
@@ -204,6 +205,19 @@ pub(in crate::routers::openai) async fn route_responses(
     response
 }
 
+/// The upstream never stores (`store: false` above), so it cannot resolve
+/// reasoning items by id. Reasoning that carries its `encrypted_content`
+/// (returned when the client asked for `include: ["reasoning.encrypted_content"]`)
+/// is self-contained and must be forwarded for stateless multi-turn reasoning.
+fn is_replayable_upstream(item: &ResponseInputOutputItem) -> bool {
+    match item {
+        ResponseInputOutputItem::Reasoning {
+            encrypted_content, ..
+        } => encrypted_content.is_some(),
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     //! R1 wire-contract tests: the OpenAI-compat Responses router forwards
@@ -412,4 +426,24 @@ mod tests {
         assert_eq!(output_text["type"], json!("output_text"));
         assert_eq!(output_text["annotations"], json!([]));
     }
+
+    #[test]
+    fn only_encrypted_reasoning_is_replayed_upstream() {
+        let plain =
+            ResponseInputOutputItem::new_reasoning("rs_1".to_string(), vec![], vec![], None);
+        let encrypted = ResponseInputOutputItem::new_reasoning_encrypted(
+            "rs_2".to_string(),
+            vec![],
+            vec![],
+            "gAAAA-opaque".to_string(),
+            None,
+        );
+        let mut items = vec![plain, encrypted];
+        items.retain(super::is_replayable_upstream);
+
+        assert_eq!(items.len(), 1);
+        let value = to_value(&items[0]).unwrap();
+        assert_eq!(value["id"], json!("rs_2"));
+        assert_eq!(value["encrypted_content"], json!("gAAAA-opaque"));
+    }
 }
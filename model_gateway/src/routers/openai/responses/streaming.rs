@@ -15,7 +15,6 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use bytes::Bytes;
-use futures_util::StreamExt;
 use openai_protocol::{
     event_types::{
         is_function_call_type, is_response_event, FunctionCallEvent, ItemType, McpEvent,
@@ -31,7 +30,7 @@ use tracing::warn;
 
 use super::{
     accumulator::StreamingResponseAccumulator,
-    common::{extract_output_index, get_event_type, parse_sse_block, ChunkProcessor},
+    common::{extract_output_index, get_event_type, parse_sse_block},
     utils::{
         patch_response_with_request_metadata, response_tool_to_value, restore_original_tools,
         rewrite_streaming_block,
@@ -50,6 +49,7 @@ use crate::{
             mcp_utils::DEFAULT_MAX_ITERATIONS,
             persistence_utils::persist_conversation_items,
             sse::SseEncoder,
+            sse_client::{SseClient, SseClientConfig},
         },
         error,
         openai::{
@@ -568,7 +568,7 @@ pub(super) async fn handle_simple_streaming_passthrough(
     }
 
     let preserved_headers = preserve_response_headers(response.headers());
-    let mut upstream_stream = response.bytes_stream();
+    let mut upstream_stream = SseClient::new(response, SseClientConfig::default());
 
     let (tx, rx) = mpsc::unbounded_channel::<Result<Bytes, io::Error>>();
 
@@ -585,56 +585,44 @@ pub(super) async fn handle_simple_streaming_passthrough(
         let mut accumulator = StreamingResponseAccumulator::new();
         let mut upstream_failed = false;
         let mut receiver_connected = true;
-        let mut chunk_processor = ChunkProcessor::new();
-
-        while let Some(chunk_result) = upstream_stream.next().await {
-            match chunk_result {
-                Ok(chunk) => {
-                    chunk_processor.push_chunk(&chunk);
-
-                    while let Some(raw_block) = chunk_processor.next_block() {
-                        let block_cow = match rewrite_streaming_block(
-                            &raw_block,
-                            &original_request,
-                            previous_response_id.as_deref(),
-                        ) {
-                            Some(modified) => Cow::Owned(modified),
-                            None => Cow::Borrowed(raw_block.as_str()),
-                        };
-
-                        if should_store {
-                            accumulator.ingest_block(&block_cow);
-                        }
-
-                        if receiver_connected {
-                            let chunk_to_send = format!("{block_cow}\n\n");
-                            if tx.send(Ok(Bytes::from(chunk_to_send))).is_err() {
-                                receiver_connected = false;
-                            }
-                        }
 
-                        if !receiver_connected && !should_store {
-                            break;
-                        }
-                    }
-
-                    if !receiver_connected && !should_store {
-                        break;
-                    }
-                }
+        while let Some(block_result) = upstream_stream.next_block().await {
+            let raw_block = match block_result {
+                Ok(block) => block,
                 Err(err) => {
                     upstream_failed = true;
                     let io_err = io::Error::other(err);
                     let _ = tx.send(Err(io_err));
                     break;
                 }
+            };
+
+            let block_cow = match rewrite_streaming_block(
+                &raw_block,
+                &original_request,
+                previous_response_id.as_deref(),
+            ) {
+                Some(modified) => Cow::Owned(modified),
+                None => Cow::Borrowed(raw_block.as_str()),
+            };
+
+            if should_store {
+                accumulator.ingest_block(&block_cow);
+            }
+
+            if receiver_connected {
+                let chunk_to_send = format!("{block_cow}\n\n");
+                if tx.send(Ok(Bytes::from(chunk_to_send))).is_err() {
+                    receiver_connected = false;
+                }
+            }
+
+            if !receiver_connected && !should_store {
+                break;
             }
         }
 
         if should_store && !upstream_failed {
-            if chunk_processor.has_remaining() {
-                accumulator.ingest_block(&chunk_processor.take_remaining());
-            }
             let encountered_error = accumulator.encountered_error().cloned();
             if let Some(mut response_json) = accumulator.into_final_response() {
                 patch_response_with_request_metadata(
@@ -650,6 +638,7 @@ pub(super) async fn handle_simple_streaming_passthrough(
                     storage.response.clone(),
                     &response_json,
                     &original_request,
+                    storage.tenant_key.as_ref(),
                     storage.request_context.clone(),
                 )
                 .await
@@ -783,163 +772,153 @@ pub(super) fn handle_streaming_with_tool_interception(
                 return;
             }
 
-            let mut upstream_stream = response.bytes_stream();
+            let mut upstream_stream = SseClient::new(response, SseClientConfig::default());
             let mut handler = StreamingToolHandler::with_starting_index(next_output_index);
             if let Some(ref id) = preserved_response_id {
                 handler.original_response_id = Some(id.clone());
             }
-            let mut chunk_processor = ChunkProcessor::new();
             let mut tool_calls_detected = false;
             let mut seen_in_progress = false;
 
-            while let Some(chunk_result) = upstream_stream.next().await {
-                match chunk_result {
-                    Ok(chunk) => {
-                        chunk_processor.push_chunk(&chunk);
+            while let Some(block_result) = upstream_stream.next_block().await {
+                let raw_block = match block_result {
+                    Ok(block) => block,
+                    Err(e) => {
+                        let _ = send_sse_event(
+                            &tx,
+                            &mut sse_encoder,
+                            "error",
+                            &json!({"error": {"message": format!("Stream error: {}", e)}}),
+                        );
+                        return;
+                    }
+                };
 
-                        while let Some(raw_block) = chunk_processor.next_block() {
-                            // Parse event
-                            let (event_name, data) = parse_sse_block(&raw_block);
+                // Parse event
+                let (event_name, data) = parse_sse_block(&raw_block);
 
-                            if data.is_empty() {
-                                continue;
-                            }
+                if data.is_empty() {
+                    continue;
+                }
 
-                            // Process through handler
-                            let action = handler.process_event(event_name, data.as_ref());
-
-                            match action {
-                                StreamAction::Forward => {
-                                    // Parse data once and reuse for skip check, forwarding, and in_progress check
-                                    let parsed = serde_json::from_str::<Value>(data.as_ref()).ok();
-
-                                    // Skip response.created and response.in_progress on subsequent iterations
-                                    let should_skip = if is_first_iteration {
-                                        false
-                                    } else {
-                                        parsed.as_ref().is_some_and(|v| {
-                                            matches!(
-                                                v.get("type").and_then(|t| t.as_str()),
-                                                Some(ResponseEvent::CREATED)
-                                                    | Some(ResponseEvent::IN_PROGRESS)
-                                            )
-                                        })
-                                    };
-
-                                    // Check in_progress before moving parsed into SseEventData
-                                    let is_in_progress = !seen_in_progress
-                                        && parsed.as_ref().is_some_and(|v| {
-                                            v.get("type").and_then(|t| t.as_str())
-                                                == Some(ResponseEvent::IN_PROGRESS)
-                                        });
-
-                                    if !should_skip {
-                                        // Forward the event with pre-parsed value (moved, not cloned)
-                                        if !forward_streaming_event(
-                                            SseEventData {
-                                                raw_block: &raw_block,
-                                                event_name,
-                                                data: data.as_ref(),
-                                                pre_parsed: parsed,
-                                            },
-                                            &mut handler,
-                                            &tx,
-                                            &mut sse_encoder,
-                                            &streaming_ctx,
-                                            &mut sequence_number,
-                                        ) {
-                                            return;
-                                        }
-                                    }
+                // Process through handler
+                let action = handler.process_event(event_name, data.as_ref());
+
+                match action {
+                    StreamAction::Forward => {
+                        // Parse data once and reuse for skip check, forwarding, and in_progress check
+                        let parsed = serde_json::from_str::<Value>(data.as_ref()).ok();
+
+                        // Skip response.created and response.in_progress on subsequent iterations
+                        let should_skip = if is_first_iteration {
+                            false
+                        } else {
+                            parsed.as_ref().is_some_and(|v| {
+                                matches!(
+                                    v.get("type").and_then(|t| t.as_str()),
+                                    Some(ResponseEvent::CREATED) | Some(ResponseEvent::IN_PROGRESS)
+                                )
+                            })
+                        };
 
-                                    if is_in_progress {
-                                        seen_in_progress = true;
-                                        if !mcp_list_tools_sent {
-                                            for (server_label, server_key) in &list_tools_bindings {
-                                                let list_tools_index =
-                                                    handler.allocate_synthetic_output_index();
-                                                if !send_mcp_list_tools_events(
-                                                    &tx,
-                                                    &session,
-                                                    server_label,
-                                                    list_tools_index,
-                                                    &mut sequence_number,
-                                                    server_key,
-                                                ) {
-                                                    // Client disconnected
-                                                    return;
-                                                }
-                                            }
-                                            mcp_list_tools_sent = true;
-                                        }
-                                    }
-                                }
-                                StreamAction::Buffer => {
-                                    // Don't forward, just buffer
-                                }
-                                StreamAction::Drop => {
-                                    // R6.7c native passthrough: upstream
-                                    // emitted an `output_item.done` for a
-                                    // hosted tool-call item that we did
-                                    // NOT wrap as a function_call (and so
-                                    // did not track in `pending_calls`).
-                                    // The upstream envelope arrives
-                                    // mis-ordered BEFORE
-                                    // `response.<type>.completed`, so we
-                                    // drop it here to preserve the wire
-                                    // invariant that `output_item.done`
-                                    // is the LAST event for a given item.
-                                    // Unlike `ExecuteTools`, this does NOT
-                                    // kick the tool loop — there is no
-                                    // dispatched call to finish.
-                                }
-                                StreamAction::ExecuteTools {
-                                    forward_triggering_event,
-                                } => {
-                                    // When the upstream signals tool completion via
-                                    // `output_item.done` (instead of a preceding
-                                    // `function_call_arguments.done`), forwarding the
-                                    // event here would emit an umbrella
-                                    // `response.output_item.done` BEFORE
-                                    // `response.<tool>.completed`, violating spec
-                                    // sub-event ordering. The tool loop emits its own
-                                    // `output_item.done` at the correct position after
-                                    // the `.completed` sub-event; suppress here.
-                                    if forward_triggering_event
-                                        && !forward_streaming_event(
-                                            SseEventData {
-                                                raw_block: &raw_block,
-                                                event_name,
-                                                data: data.as_ref(),
-                                                pre_parsed: None,
-                                            },
-                                            &mut handler,
-                                            &tx,
-                                            &mut sse_encoder,
-                                            &streaming_ctx,
-                                            &mut sequence_number,
-                                        )
-                                    {
+                        // Check in_progress before moving parsed into SseEventData
+                        let is_in_progress = !seen_in_progress
+                            && parsed.as_ref().is_some_and(|v| {
+                                v.get("type").and_then(|t| t.as_str())
+                                    == Some(ResponseEvent::IN_PROGRESS)
+                            });
+
+                        if !should_skip {
+                            // Forward the event with pre-parsed value (moved, not cloned)
+                            if !forward_streaming_event(
+                                SseEventData {
+                                    raw_block: &raw_block,
+                                    event_name,
+                                    data: data.as_ref(),
+                                    pre_parsed: parsed,
+                                },
+                                &mut handler,
+                                &tx,
+                                &mut sse_encoder,
+                                &streaming_ctx,
+                                &mut sequence_number,
+                            ) {
+                                return;
+                            }
+                        }
+
+                        if is_in_progress {
+                            seen_in_progress = true;
+                            if !mcp_list_tools_sent {
+                                for (server_label, server_key) in &list_tools_bindings {
+                                    let list_tools_index =
+                                        handler.allocate_synthetic_output_index();
+                                    if !send_mcp_list_tools_events(
+                                        &tx,
+                                        &session,
+                                        server_label,
+                                        list_tools_index,
+                                        &mut sequence_number,
+                                        server_key,
+                                    ) {
+                                        // Client disconnected
                                         return;
                                     }
-                                    tool_calls_detected = true;
-                                    break; // Exit stream processing to execute tools
                                 }
+                                mcp_list_tools_sent = true;
                             }
                         }
-
-                        if tool_calls_detected {
-                            break;
-                        }
                     }
-                    Err(e) => {
-                        let _ = send_sse_event(
-                            &tx,
-                            &mut sse_encoder,
-                            "error",
-                            &json!({"error": {"message": format!("Stream error: {}", e)}}),
-                        );
-                        return;
+                    StreamAction::Buffer => {
+                        // Don't forward, just buffer
+                    }
+                    StreamAction::Drop => {
+                        // R6.7c native passthrough: upstream
+                        // emitted an `output_item.done` for a
+                        // hosted tool-call item that we did
+                        // NOT wrap as a function_call (and so
+                        // did not track in `pending_calls`).
+                        // The upstream envelope arrives
+                        // mis-ordered BEFORE
+                        // `response.<type>.completed`, so we
+                        // drop it here to preserve the wire
+                        // invariant that `output_item.done`
+                        // is the LAST event for a given item.
+                        // Unlike `ExecuteTools`, this does NOT
+                        // kick the tool loop — there is no
+                        // dispatched call to finish.
+                    }
+                    StreamAction::ExecuteTools {
+                        forward_triggering_event,
+                    } => {
+                        // When the upstream signals tool completion via
+                        // `output_item.done` (instead of a preceding
+                        // `function_call_arguments.done`), forwarding the
+                        // event here would emit an umbrella
+                        // `response.output_item.done` BEFORE
+                        // `response.<tool>.completed`, violating spec
+                        // sub-event ordering. The tool loop emits its own
+                        // `output_item.done` at the correct position after
+                        // the `.completed` sub-event; suppress here.
+                        if forward_triggering_event
+                            && !forward_streaming_event(
+                                SseEventData {
+                                    raw_block: &raw_block,
+                                    event_name,
+                                    data: data.as_ref(),
+                                    pre_parsed: None,
+                                },
+                                &mut handler,
+                                &tx,
+                                &mut sse_encoder,
+                                &streaming_ctx,
+                                &mut sequence_number,
+                            )
+                        {
+                            return;
+                        }
+                        tool_calls_detected = true;
+                        break; // Exit stream processing to execute tools
                     }
                 }
             }
@@ -990,6 +969,7 @@ pub(super) fn handle_streaming_with_tool_interception(
                         storage.response.clone(),
                         &response_json,
                         &original_request,
+                        storage.tenant_key.as_ref(),
                         storage.request_context.clone(),
                     )
                     .await
@@ -12,6 +12,7 @@ use axum::{
 };
 use openai_protocol::{
     chat::ChatCompletionRequest,
+    images::ImageGenerationRequest,
     realtime_session::{
         RealtimeClientSecretCreateRequest, RealtimeSessionCreateRequest,
         RealtimeTranscriptionSessionCreateRequest,
@@ -23,6 +24,7 @@ use super::{
     chat::{self, ChatRouterContext},
     context::{ResponsesComponents, SharedComponents},
     health,
+    images::{self, ImagesRouterContext},
     provider::ProviderRegistry,
     responses::route::{self as responses_route, ResponsesRouterContext},
 };
@@ -33,6 +35,7 @@ use crate::{
     observability::metrics::{metrics_labels, Metrics},
     routers::common::{
         header_utils::extract_auth_header,
+        images::ImageStore,
         realtime::{
             rest::forward_realtime_rest, webrtc, webrtc::handle_realtime_webrtc,
             ws::handle_realtime_ws, RealtimeLabels, RealtimeRegistry,
@@ -69,6 +72,7 @@ pub struct OpenAIRouter {
     shared_components: Arc<SharedComponents>,
     responses_components: Arc<ResponsesComponents>,
     retry_config: RetryConfig,
+    image_store: Option<ImageStore>,
     realtime_registry: Arc<RealtimeRegistry>,
     context: Arc<AppContext>,
 }
@@ -119,6 +123,7 @@ impl OpenAIRouter {
             shared_components,
             responses_components,
             retry_config: ctx.router_config.effective_retry_config(),
+            image_store: ImageStore::from_context(ctx),
             realtime_registry: ctx.realtime_registry.clone(),
             context: Arc::clone(ctx),
         })
@@ -191,6 +196,26 @@ impl crate::routers::RouterTrait for OpenAIRouter {
         responses_route::route_responses(&deps, headers, tenant_meta, body, model_id).await
     }
 
+    async fn route_image_generation(
+        &self,
+        headers: Option<&HeaderMap>,
+        _tenant_meta: &TenantRequestMeta,
+        body: &ImageGenerationRequest,
+        model_id: &str,
+    ) -> Response {
+        let per_model_retry_config = self.worker_registry.get_retry_config(model_id);
+        let deps = ImagesRouterContext {
+            worker_registry: &self.worker_registry,
+            provider_registry: &self.provider_registry,
+            client: &self.shared_components.client,
+            retry_config: per_model_retry_config
+                .as_ref()
+                .unwrap_or(&self.retry_config),
+            image_store: self.image_store.as_ref(),
+        };
+        images::route_image_generation(&deps, headers, body, model_id).await
+    }
+
     async fn route_realtime_session(
         &self,
         headers: Option<&HeaderMap>,
@@ -59,6 +59,41 @@ pub async fn delete_response(
     }
 }
 
+/// Set the status of a stored, unfinished response to `cancelled` after its
+/// in-flight request was cancelled.
+pub async fn mark_response_cancelled(
+    response_storage: &Arc<dyn ResponseStorage>,
+    response_id: &str,
+) {
+    let id = ResponseId::from(response_id);
+    let mut raw_response = match response_storage.get_response(&id).await {
+        Ok(Some(stored)) => stored.raw_response,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(response_id, "Failed to read cancelled response: {e}");
+            return;
+        }
+    };
+    if matches!(
+        raw_response.get("status").and_then(Value::as_str),
+        Some("completed" | "failed" | "cancelled")
+    ) {
+        return;
+    }
+    let Some(raw) = raw_response.as_object_mut() else {
+        return;
+    };
+    raw.insert("status".to_string(), json!("cancelled"));
+    match response_storage
+        .update_raw_response(&id, raw_response)
+        .await
+    {
+        Ok(true) => info!(response_id, "Marked response cancelled"),
+        Ok(false) => {}
+        Err(e) => warn!(response_id, "Failed to mark response cancelled: {e}"),
+    }
+}
+
 pub async fn list_response_input_items(
     response_storage: &Arc<dyn ResponseStorage>,
     response_id: &str,
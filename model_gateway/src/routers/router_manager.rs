@@ -22,6 +22,7 @@ use openai_protocol::{
     completion::CompletionRequest,
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
+    images::ImageGenerationRequest,
     interactions::InteractionsRequest,
     messages::CreateMessageRequest,
     model_card::ModelCard,
@@ -32,6 +33,7 @@ use openai_protocol::{
     },
     rerank::RerankRequest,
     responses::ResponsesRequest,
+    score::ScoreRequest,
     transcription::{AudioFile, TranscriptionRequest},
     UNKNOWN_MODEL_ID,
 };
@@ -49,7 +51,10 @@ use crate::{
         RouterFactory, RouterTrait,
     },
     server::ServerConfig,
-    worker::{ConnectionMode, ProviderType, RuntimeType, Worker, WorkerRegistry, WorkerType},
+    worker::{
+        ConnectionMode, MaintenanceController, ProviderType, RuntimeType, Worker, WorkerRegistry,
+        WorkerType,
+    },
 };
 
 pub struct RouterManager {
@@ -64,6 +69,8 @@ pub struct RouterManager {
     routers: Arc<DashMap<RouterId, Arc<dyn RouterTrait>>>,
     default_router: Arc<std::sync::RwLock<Option<RouterId>>>,
     enable_igw: bool,
+    /// Source of the `availability` annotation on registry model listings.
+    maintenance: Option<Arc<MaintenanceController>>,
 }
 
 impl RouterManager {
@@ -75,6 +82,7 @@ impl RouterManager {
             routers: Arc::new(DashMap::new()),
             default_router: Arc::new(std::sync::RwLock::new(None)),
             enable_igw: false,
+            maintenance: None,
         }
     }
 
@@ -109,6 +117,7 @@ impl RouterManager {
             config.router_config.api_key.clone(),
             &config.router_config.tenant_api_keys,
         );
+        manager.maintenance = Some(app_context.maintenance.clone());
         let manager = Arc::new(manager);
 
         if config.router_config.enable_igw {
@@ -358,7 +367,13 @@ impl RouterManager {
         if cards.is_empty() {
             (StatusCode::SERVICE_UNAVAILABLE, "No models available").into_response()
         } else {
-            let resp = ListModelsResponse::from_model_cards(cards);
+            let mut resp = ListModelsResponse::from_model_cards(cards);
+            if let Some(maintenance) = &self.maintenance {
+                let mut availability = maintenance.model_availability();
+                for model in &mut resp.data {
+                    model.availability = availability.remove(&model.id);
+                }
+            }
             (StatusCode::OK, Json(resp)).into_response()
         }
     }
@@ -740,6 +755,50 @@ impl RouterTrait for RouterManager {
         }
     }
 
+    async fn route_score(
+        &self,
+        headers: Option<&HeaderMap>,
+        tenant_meta: &TenantRequestMeta,
+        body: &ScoreRequest,
+        model_id: &str,
+    ) -> Response {
+        let router = self.select_router_for_request(Some(model_id));
+
+        if let Some(router) = router {
+            router
+                .route_score(headers, tenant_meta, body, model_id)
+                .await
+        } else {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Model '{}' not found or no router available", body.model),
+            )
+                .into_response()
+        }
+    }
+
+    async fn route_image_generation(
+        &self,
+        headers: Option<&HeaderMap>,
+        tenant_meta: &TenantRequestMeta,
+        body: &ImageGenerationRequest,
+        model_id: &str,
+    ) -> Response {
+        let router = self.select_router_for_request(Some(model_id));
+
+        if let Some(router) = router {
+            router
+                .route_image_generation(headers, tenant_meta, body, model_id)
+                .await
+        } else {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Model '{}' not found or no router available", body.model),
+            )
+                .into_response()
+        }
+    }
+
     async fn route_audio_transcriptions(
         &self,
         headers: Option<&HeaderMap>,
@@ -238,6 +238,7 @@ pub async fn add_tokenizer(context: &Arc<AppContext>, request: AddTokenizerReque
         chat_template_path: request.chat_template_path.clone(),
         cache_config: None,
         fail_on_duplicate: true,
+        worker_url: None,
     };
 
     let job = Job::AddTokenizer {
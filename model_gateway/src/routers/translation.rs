@@ -0,0 +1,199 @@
+//! Protocol translations runnable outside the router.
+//!
+//! The gRPC pipeline serves the Responses and Messages APIs by translating
+//! them to and from the chat completion pipeline, and maps engine finish
+//! reasons onto OpenAI's. Each of those conversions is a [`Direction`] here
+//! that [`translate`] runs on JSON in and JSON out, without workers, a
+//! tokenizer or a running gateway. The golden corpus under
+//! `model_gateway/tests/fixtures/translation/` pins every direction, so a
+//! change to one converter that shifts another direction's output fails the
+//! `translation_golden_test` suite.
+//!
+//! Gemini Interactions requests are forwarded to the upstream as they are
+//! (only `model` and `store` are rewritten), so no Gemini direction exists.
+
+use llm_multimodal::MediaPartOrder;
+use llm_tokenizer::chat_template::ChatTemplateContentFormat;
+use openai_protocol::{
+    chat::{ChatCompletionResponse, ChatCompletionStreamResponse},
+    messages::CreateMessageRequest,
+    responses::ResponsesRequest,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::routers::grpc::{
+    regular::responses,
+    utils::{finish_reason, message_utils},
+};
+
+/// Version of the fixture format. Fixtures record the version they were
+/// written for, and the golden suite rejects fixtures of another version.
+pub const FIXTURE_VERSION: u32 = 1;
+
+/// A translation the gateway performs between two protocols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Responses request -> chat completion request.
+    ///
+    /// Input: a `ResponsesRequest`. Output: a `ChatCompletionRequest`.
+    ResponsesRequestToChat,
+    /// Chat completion -> Responses response.
+    ///
+    /// Input: `{"request": ResponsesRequest, "response": ChatCompletionResponse}`.
+    /// Output: a `ResponsesResponse`.
+    ChatResponseToResponses,
+    /// Streamed chat completion chunks -> final Responses response.
+    ///
+    /// Input: `{"request": ResponsesRequest, "chunks": [ChatCompletionStreamResponse]}`.
+    /// Output: a `ResponsesResponse`.
+    ChatStreamToResponses,
+    /// Anthropic Messages request -> chat template inputs.
+    ///
+    /// Input: `{"request": CreateMessageRequest, "content_format": "string" | "openai"}`,
+    /// the format defaulting to `string`. Output:
+    /// `{"messages": [...], "tools": [...], "tool_choice": ...}`.
+    MessagesRequestToChatTemplate,
+    /// Engine finish reason from a gRPC `GenerateComplete` -> OpenAI finish reason.
+    ///
+    /// Input: `{"backend": "sglang" | "vllm" | "trtllm" | "mlx" | "tokenspeed",
+    /// "finish_reason": "...", "has_tool_calls": bool}`. Output:
+    /// `{"finish_reason": "..."}`.
+    EngineFinishReason,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 5] = [
+        Direction::ResponsesRequestToChat,
+        Direction::ChatResponseToResponses,
+        Direction::ChatStreamToResponses,
+        Direction::MessagesRequestToChatTemplate,
+        Direction::EngineFinishReason,
+    ];
+
+    /// Name of the direction, also the directory holding its fixtures.
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::ResponsesRequestToChat => "responses_request_to_chat",
+            Direction::ChatResponseToResponses => "chat_response_to_responses",
+            Direction::ChatStreamToResponses => "chat_stream_to_responses",
+            Direction::MessagesRequestToChatTemplate => "messages_request_to_chat_template",
+            Direction::EngineFinishReason => "engine_finish_reason",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|direction| direction.name() == name)
+    }
+
+    /// Whether the direction translates a streamed response.
+    pub fn is_streaming(self) -> bool {
+        matches!(self, Direction::ChatStreamToResponses)
+    }
+}
+
+/// Run `direction` on `input` and return the translated document.
+pub fn translate(direction: Direction, input: &Value) -> Result<Value, String> {
+    match direction {
+        Direction::ResponsesRequestToChat => {
+            let request: ResponsesRequest = parse(input, "input")?;
+            to_value(&responses::responses_to_chat(&request)?)
+        }
+        Direction::ChatResponseToResponses => {
+            let request: ResponsesRequest = field(input, "request")?;
+            let response: ChatCompletionResponse = field(input, "response")?;
+            to_value(&responses::chat_to_responses(&response, &request, None)?)
+        }
+        Direction::ChatStreamToResponses => {
+            let request: ResponsesRequest = field(input, "request")?;
+            let chunks: Vec<ChatCompletionStreamResponse> = field(input, "chunks")?;
+            to_value(&responses::accumulate_chat_stream(&request, &chunks))
+        }
+        Direction::MessagesRequestToChatTemplate => {
+            let request: CreateMessageRequest = field(input, "request")?;
+            let content_format = match input.get("content_format").and_then(Value::as_str) {
+                None | Some("string") => ChatTemplateContentFormat::String,
+                Some("openai") => ChatTemplateContentFormat::OpenAI,
+                Some(other) => return Err(format!("unknown content_format '{other}'")),
+            };
+            let messages = message_utils::template_messages(
+                &request,
+                content_format,
+                None,
+                MediaPartOrder::Authored,
+            )?;
+            let tools = request
+                .tools
+                .as_deref()
+                .map(message_utils::extract_chat_tools)
+                .unwrap_or_default();
+            let tool_choice = request
+                .tool_choice
+                .as_ref()
+                .map(message_utils::convert_message_tool_choice);
+            Ok(json!({
+                "messages": messages,
+                "tools": to_value(&tools)?,
+                "tool_choice": to_value(&tool_choice)?,
+            }))
+        }
+        Direction::EngineFinishReason => {
+            let quirks = match input.get("backend").and_then(Value::as_str) {
+                Some("sglang") => &finish_reason::SGLANG_QUIRKS,
+                Some("vllm") => &finish_reason::VLLM_QUIRKS,
+                Some("trtllm") => &finish_reason::TRTLLM_QUIRKS,
+                Some("mlx") => &finish_reason::MLX_QUIRKS,
+                Some("tokenspeed") => &finish_reason::TOKENSPEED_QUIRKS,
+                other => return Err(format!("unknown backend {other:?}")),
+            };
+            let raw: String = field(input, "finish_reason")?;
+            let has_tool_calls = input
+                .get("has_tool_calls")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let reason = finish_reason::normalize_finish_reason(quirks, &raw);
+            Ok(json!({
+                "finish_reason": finish_reason::with_tool_calls(reason, has_tool_calls),
+            }))
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: &Value, what: &str) -> Result<T, String> {
+    serde_json::from_value(value.clone()).map_err(|e| format!("invalid {what}: {e}"))
+}
+
+fn field<T: DeserializeOwned>(input: &Value, name: &str) -> Result<T, String> {
+    parse(input.get(name).unwrap_or(&Value::Null), name)
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("failed to serialize output: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_names_round_trip() {
+        for direction in Direction::ALL {
+            assert_eq!(Direction::from_name(direction.name()), Some(direction));
+        }
+        assert_eq!(Direction::from_name("gemini_to_chat"), None);
+    }
+
+    #[test]
+    fn test_invalid_input_is_reported() {
+        let err = translate(Direction::ChatResponseToResponses, &json!({})).unwrap_err();
+        assert!(err.starts_with("invalid request"), "{err}");
+        let err = translate(
+            Direction::EngineFinishReason,
+            &json!({"backend": "tgi", "finish_reason": "stop"}),
+        )
+        .unwrap_err();
+        assert!(err.contains("unknown backend"), "{err}");
+    }
+}
@@ -0,0 +1,328 @@
+//! Chunking strategies for extracted text.
+//!
+//! Sizes are given in tokens and estimated at four characters per token, so
+//! chunking does not depend on the embedding model's tokenizer.
+
+use serde::{Deserialize, Serialize};
+
+pub(super) const CHARS_PER_TOKEN_ESTIMATE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Fixed-size windows that overlap, cut at whitespace where possible
+    Fixed {
+        #[serde(default = "default_max_chunk_tokens")]
+        max_chunk_tokens: usize,
+        #[serde(default = "default_chunk_overlap_tokens")]
+        chunk_overlap_tokens: usize,
+    },
+    /// Whole sentences packed up to the size limit; each chunk repeats the
+    /// last `overlap_sentences` sentences of the previous one
+    Sentence {
+        #[serde(default = "default_max_chunk_tokens")]
+        max_chunk_tokens: usize,
+        #[serde(default = "default_overlap_sentences")]
+        overlap_sentences: usize,
+    },
+    /// Sentences grouped while the cosine similarity of consecutive
+    /// sentence embeddings stays at or above `similarity_threshold`
+    Semantic {
+        #[serde(default = "default_max_chunk_tokens")]
+        max_chunk_tokens: usize,
+        #[serde(default = "default_similarity_threshold")]
+        similarity_threshold: f32,
+    },
+}
+
+fn default_max_chunk_tokens() -> usize {
+    800
+}
+
+fn default_chunk_overlap_tokens() -> usize {
+    400
+}
+
+fn default_overlap_sentences() -> usize {
+    1
+}
+
+fn default_similarity_threshold() -> f32 {
+    0.75
+}
+
+impl Default for ChunkingStrategy {
+    fn default() -> Self {
+        Self::Fixed {
+            max_chunk_tokens: default_max_chunk_tokens(),
+            chunk_overlap_tokens: default_chunk_overlap_tokens(),
+        }
+    }
+}
+
+impl ChunkingStrategy {
+    pub fn validate(&self) -> Result<(), String> {
+        let max_chunk_tokens = match *self {
+            Self::Fixed {
+                max_chunk_tokens,
+                chunk_overlap_tokens,
+            } => {
+                if chunk_overlap_tokens * 2 > max_chunk_tokens {
+                    return Err(
+                        "chunk_overlap_tokens must not exceed half of max_chunk_tokens".to_string(),
+                    );
+                }
+                max_chunk_tokens
+            }
+            Self::Sentence {
+                max_chunk_tokens, ..
+            } => max_chunk_tokens,
+            Self::Semantic {
+                max_chunk_tokens,
+                similarity_threshold,
+            } => {
+                if !(-1.0..=1.0).contains(&similarity_threshold) {
+                    return Err("similarity_threshold must be between -1 and 1".to_string());
+                }
+                max_chunk_tokens
+            }
+        };
+        if !(100..=4096).contains(&max_chunk_tokens) {
+            return Err("max_chunk_tokens must be between 100 and 4096".to_string());
+        }
+        Ok(())
+    }
+
+    pub(super) fn max_chunk_chars(&self) -> usize {
+        let (Self::Fixed {
+            max_chunk_tokens, ..
+        }
+        | Self::Sentence {
+            max_chunk_tokens, ..
+        }
+        | Self::Semantic {
+            max_chunk_tokens, ..
+        }) = *self;
+        max_chunk_tokens * CHARS_PER_TOKEN_ESTIMATE
+    }
+}
+
+/// Split into windows of at most `max_chars` characters, each starting
+/// `overlap_chars` before the end of the previous one. A window is cut at
+/// its last whitespace when that keeps it over half full.
+pub(super) fn fixed_chunks(text: &str, max_chars: usize, overlap_chars: usize) -> Vec<String> {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let mut end = (start + max_chars).min(chars.len());
+        if end < chars.len() {
+            if let Some(cut) = (start + max_chars / 2 + 1..=end)
+                .rev()
+                .find(|&i| chars[i - 1].is_whitespace())
+            {
+                end = cut;
+            }
+        }
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        if end == chars.len() {
+            break;
+        }
+        start = end.saturating_sub(overlap_chars).max(start + 1);
+    }
+    chunks
+}
+
+/// Split at sentence-ending punctuation followed by whitespace, and at
+/// paragraph breaks.
+pub(super) fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let boundary = match c {
+            '.' | '!' | '?' | '。' | '！' | '？' => {
+                !matches!(chars.peek(), Some(next) if !next.is_whitespace())
+            }
+            '\n' => chars.peek() == Some(&'\n'),
+            _ => false,
+        };
+        if boundary {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    let sentence = current.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+    sentences
+}
+
+fn joined_len(parts: &[&str]) -> usize {
+    parts.iter().map(|p| p.chars().count()).sum::<usize>() + parts.len().saturating_sub(1)
+}
+
+/// Pack whole sentences into chunks of at most `max_chars` characters.
+/// Each chunk starts with up to `overlap_sentences` sentences of the
+/// previous one; sentences longer than a chunk are split like fixed chunks.
+pub(super) fn pack_sentences(
+    sentences: &[String],
+    max_chars: usize,
+    overlap_sentences: usize,
+) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for sentence in sentences {
+        let len = sentence.chars().count();
+        if len > max_chars {
+            if !current.is_empty() {
+                chunks.push(current.join(" "));
+            }
+            current.clear();
+            chunks.extend(fixed_chunks(sentence, max_chars, 0));
+            continue;
+        }
+        if !current.is_empty() && joined_len(&current) + 1 + len > max_chars {
+            chunks.push(current.join(" "));
+            current.drain(..current.len().saturating_sub(overlap_sentences));
+            while !current.is_empty() && joined_len(&current) + 1 + len > max_chars {
+                current.remove(0);
+            }
+        }
+        current.push(sentence);
+    }
+    if !current.is_empty() {
+        chunks.push(current.join(" "));
+    }
+    chunks
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Group consecutive sentences, starting a new chunk where a sentence's
+/// embedding is less similar than `threshold` to the previous sentence's,
+/// or where the chunk would exceed `max_chars`.
+pub(super) fn semantic_chunks(
+    sentences: &[String],
+    embeddings: &[Vec<f32>],
+    threshold: f32,
+    max_chars: usize,
+) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for (i, sentence) in sentences.iter().enumerate() {
+        let len = sentence.chars().count();
+        let topic_shift = i > 0
+            && match (embeddings.get(i - 1), embeddings.get(i)) {
+                (Some(prev), Some(this)) => cosine_similarity(prev, this) < threshold,
+                _ => false,
+            };
+        if !current.is_empty() && (topic_shift || joined_len(&current) + 1 + len > max_chars) {
+            chunks.push(current.join(" "));
+            current.clear();
+        }
+        if len > max_chars {
+            chunks.extend(fixed_chunks(sentence, max_chars, 0));
+            continue;
+        }
+        current.push(sentence);
+    }
+    if !current.is_empty() {
+        chunks.push(current.join(" "));
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_deserialize_with_defaults_and_validate() {
+        let strategy: ChunkingStrategy = serde_json::from_str(r#"{"type": "sentence"}"#).unwrap();
+        assert_eq!(
+            strategy,
+            ChunkingStrategy::Sentence {
+                max_chunk_tokens: 800,
+                overlap_sentences: 1
+            }
+        );
+        assert!(strategy.validate().is_ok());
+        assert_eq!(strategy.max_chunk_chars(), 3200);
+
+        let strategy: ChunkingStrategy = serde_json::from_str(
+            r#"{"type": "fixed", "max_chunk_tokens": 200, "chunk_overlap_tokens": 150}"#,
+        )
+        .unwrap();
+        assert!(strategy.validate().is_err());
+        assert!(ChunkingStrategy::default().validate().is_ok());
+    }
+
+    #[test]
+    fn fixed_chunks_overlap_and_cut_at_whitespace() {
+        let chunks = fixed_chunks("aaaa bbbb cccc dddd", 10, 5);
+        assert_eq!(chunks, vec!["aaaa bbbb", "bbbb cccc", "cccc dddd"]);
+        assert!(fixed_chunks("   ", 10, 0).is_empty());
+    }
+
+    #[test]
+    fn sentences_split_on_punctuation_and_paragraphs() {
+        assert_eq!(
+            split_sentences("Version 2.5 is out! Is it stable? Yes.\n\nNext topic"),
+            vec!["Version 2.5 is out!", "Is it stable?", "Yes.", "Next topic"]
+        );
+    }
+
+    #[test]
+    fn sentences_pack_with_overlap() {
+        let sentences: Vec<String> = ["One two.", "Three four.", "Five six.", "Seven."]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            pack_sentences(&sentences, 21, 1),
+            vec![
+                "One two. Three four.",
+                "Three four. Five six.",
+                "Five six. Seven."
+            ]
+        );
+        assert_eq!(
+            pack_sentences(&sentences, 21, 0),
+            vec!["One two. Three four.", "Five six. Seven."]
+        );
+    }
+
+    #[test]
+    fn semantic_chunks_break_on_topic_shift() {
+        let sentences: Vec<String> = ["Cats purr.", "Cats nap.", "Taxes are due."]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let embeddings = vec![vec![1.0, 0.1], vec![0.9, 0.2], vec![0.0, 1.0]];
+        assert_eq!(
+            semantic_chunks(&sentences, &embeddings, 0.75, 1000),
+            vec!["Cats purr. Cats nap.", "Taxes are due."]
+        );
+    }
+}
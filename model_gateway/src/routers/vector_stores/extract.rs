@@ -0,0 +1,234 @@
+//! Plain-text extraction from stored documents.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Pdf,
+    Html,
+    Markdown,
+    Text,
+}
+
+impl DocumentFormat {
+    /// Detect the format from the content type, falling back to the file
+    /// extension for generic types such as `application/octet-stream`.
+    pub fn detect(content_type: &str, filename: &str) -> Option<Self> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "application/pdf" => return Some(Self::Pdf),
+            "text/html" | "application/xhtml+xml" => return Some(Self::Html),
+            "text/markdown" | "text/x-markdown" => return Some(Self::Markdown),
+            _ => {}
+        }
+        let extension = filename
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("pdf") => Some(Self::Pdf),
+            Some("html" | "htm" | "xhtml") => Some(Self::Html),
+            Some("md" | "markdown") => Some(Self::Markdown),
+            Some("txt" | "text") => Some(Self::Text),
+            _ if mime.starts_with("text/") => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Extract the readable text of a document. PDF parsing is CPU-bound; call
+/// this from a blocking task.
+pub fn extract_text(format: DocumentFormat, bytes: &[u8]) -> Result<String, String> {
+    let text = match format {
+        DocumentFormat::Pdf => pdf_extract::extract_text_from_mem(bytes)
+            .map_err(|e| format!("failed to extract PDF text: {e}"))?,
+        DocumentFormat::Html => html_to_text(&String::from_utf8_lossy(bytes)),
+        DocumentFormat::Markdown => markdown_to_text(&String::from_utf8_lossy(bytes)),
+        DocumentFormat::Text => String::from_utf8_lossy(bytes).into_owned(),
+    };
+    Ok(normalize_whitespace(&text))
+}
+
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static HTML_HIDDEN_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)<!--.*?-->|<script\b.*?</script\s*>|<style\b.*?</style\s*>|<head\b.*?</head\s*>|<noscript\b.*?</noscript\s*>",
+    )
+    .expect("static regex pattern is valid")
+});
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static HTML_BLOCK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)<\s*(?:br|hr|/p|/div|/li|/h[1-6]|/tr|/section|/article|/blockquote|/pre|/table)\b[^>]*>",
+    )
+    .expect("static regex pattern is valid")
+});
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static HTML_TAG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<[^>]*>").expect("static regex pattern is valid"));
+
+fn html_to_text(html: &str) -> String {
+    let visible = HTML_HIDDEN_RE.replace_all(html, " ");
+    let broken = HTML_BLOCK_RE.replace_all(&visible, "\n\n");
+    decode_entities(&HTML_TAG_RE.replace_all(&broken, ""))
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest
+            .find(';')
+            .filter(|&semi| semi <= 10)
+            .and_then(|semi| decode_entity(&rest[1..semi]).map(|c| (c, semi)));
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(code) = name.strip_prefix('#') {
+        let value = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        return char::from_u32(value);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => return None,
+    })
+}
+
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static MD_LINK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").expect("static regex pattern is valid"));
+#[expect(
+    clippy::expect_used,
+    reason = "static regex patterns are compile-time constants; invalid pattern is a developer bug"
+)]
+static MD_LINE_PREFIX_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:#{1,6}[ \t]+|>[ \t]?)").expect("static regex pattern is valid")
+});
+
+/// Drop Markdown syntax that carries no text: fences, heading and quote
+/// markers, link targets and emphasis markers. Code is kept as text.
+fn markdown_to_text(markdown: &str) -> String {
+    let body: String = markdown
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !(line.starts_with("```") || line.starts_with("~~~"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let body = MD_LINE_PREFIX_RE.replace_all(&body, "");
+    let body = MD_LINK_RE.replace_all(&body, "$1");
+    body.replace("**", "")
+        .replace("__", "")
+        .replace("~~", "")
+        .replace('`', "")
+}
+
+/// Collapse runs of blanks within lines and of empty lines between
+/// paragraphs, keeping paragraph breaks for sentence splitting.
+fn normalize_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        blank_lines = 0;
+        out.push_str(&line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_detection_prefers_specific_content_types() {
+        let detect = DocumentFormat::detect;
+        assert_eq!(
+            detect("application/pdf", "x.bin"),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(
+            detect("text/html; charset=utf-8", "page"),
+            Some(DocumentFormat::Html)
+        );
+        assert_eq!(
+            detect("application/octet-stream", "README.md"),
+            Some(DocumentFormat::Markdown)
+        );
+        assert_eq!(
+            detect("text/plain", "notes.md"),
+            Some(DocumentFormat::Markdown)
+        );
+        assert_eq!(detect("text/csv", "data.csv"), Some(DocumentFormat::Text));
+        assert_eq!(detect("image/png", "cat.png"), None);
+    }
+
+    #[test]
+    fn html_keeps_visible_text_and_paragraphs() {
+        let html = "<html><head><title>T</title><style>p{}</style></head><body>\
+                    <h1>Refunds</h1><p>Within&nbsp;30 days &amp; unused.</p>\
+                    <script>track()</script><p>Contact &lt;support&gt; &#x41;</p></body></html>";
+        assert_eq!(
+            extract_text(DocumentFormat::Html, html.as_bytes()).unwrap(),
+            "Refunds\n\nWithin 30 days & unused.\n\nContact <support> A"
+        );
+    }
+
+    #[test]
+    fn markdown_syntax_is_dropped() {
+        let markdown = "# Setup\n\n> Run **this** first: `make`.\n\n```sh\nmake install\n```\n\
+                        See [the guide](https://example.com) and ![diagram](d.png).";
+        assert_eq!(
+            extract_text(DocumentFormat::Markdown, markdown.as_bytes()).unwrap(),
+            "Setup\n\nRun this first: make.\n\nmake install\nSee the guide and diagram."
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use smg_data_connector::FileId;
+
+use super::{
+    chunking::ChunkingStrategy,
+    ingestion::{VectorStoreError, VectorStoreIngestor},
+};
+use crate::{middleware::RouteRequestMeta, routers::error};
+
+#[derive(Debug, Deserialize)]
+pub struct AddVectorStoreFileRequest {
+    pub file_id: String,
+    #[serde(default)]
+    pub chunking_strategy: ChunkingStrategy,
+    /// Embedding model for a new store; defaults to the configured model.
+    /// Must match the store's model once it has files.
+    pub embedding_model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchVectorStoreRequest {
+    pub query: String,
+    /// Results returned; defaults to the configured `rerank_top_k`
+    pub max_num_results: Option<usize>,
+}
+
+fn error_response(e: VectorStoreError) -> Response {
+    match e {
+        VectorStoreError::NotFound(msg) => error::not_found("not_found", msg),
+        VectorStoreError::InvalidRequest(msg) => error::bad_request("invalid_request", msg),
+        VectorStoreError::Conflict(msg) => {
+            error::create_error(StatusCode::CONFLICT, "ingestion_in_progress", msg)
+        }
+        VectorStoreError::Upstream(msg) => error::bad_gateway("embedding_failed", msg),
+        VectorStoreError::Storage(msg) => error::internal_error("storage_error", msg),
+    }
+}
+
+/// `POST /v1/vector_stores/{vector_store_id}/files`: start ingesting an
+/// uploaded file. Poll the returned file for `progress` and `status`.
+pub async fn add_vector_store_file(
+    State(ingestor): State<Arc<VectorStoreIngestor>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(vector_store_id): Path<String>,
+    Json(body): Json<AddVectorStoreFileRequest>,
+) -> Response {
+    match ingestor
+        .add_file(
+            &meta,
+            &vector_store_id,
+            FileId::from(body.file_id),
+            body.chunking_strategy,
+            body.embedding_model,
+        )
+        .await
+    {
+        Ok(file) => Json(file).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// `GET /v1/vector_stores/{vector_store_id}/files/{file_id}`
+pub async fn get_vector_store_file(
+    State(ingestor): State<Arc<VectorStoreIngestor>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path((vector_store_id, file_id)): Path<(String, String)>,
+) -> Response {
+    match ingestor.get_file(&meta, &vector_store_id, &FileId::from(file_id)) {
+        Ok(file) => Json(file).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// `DELETE /v1/vector_stores/{vector_store_id}/files/{file_id}`: remove the
+/// file's chunks from the store. The uploaded file is kept.
+pub async fn delete_vector_store_file(
+    State(ingestor): State<Arc<VectorStoreIngestor>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path((vector_store_id, file_id)): Path<(String, String)>,
+) -> Response {
+    let file_id = FileId::from(file_id);
+    match ingestor
+        .remove_file(&meta, &vector_store_id, &file_id)
+        .await
+    {
+        Ok(()) => Json(json!({
+            "id": file_id,
+            "object": "vector_store.file.deleted",
+            "deleted": true,
+        }))
+        .into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// `POST /v1/vector_stores/{vector_store_id}/search`: similarity search,
+/// reranked when a reranker is served. `metadata.retrieval_stages` reports
+/// each stage's `top_k`, result count and latency.
+pub async fn search_vector_store(
+    State(ingestor): State<Arc<VectorStoreIngestor>>,
+    Extension(meta): Extension<RouteRequestMeta>,
+    Path(vector_store_id): Path<String>,
+    Json(body): Json<SearchVectorStoreRequest>,
+) -> Response {
+    if body.max_num_results.is_some_and(|n| !(1..=50).contains(&n)) {
+        return error::bad_request(
+            "invalid_request",
+            "max_num_results must be between 1 and 50",
+        );
+    }
+    let results = match ingestor
+        .search(&meta, &vector_store_id, &body.query, body.max_num_results)
+        .await
+    {
+        Ok(results) => results,
+        Err(e) => return error_response(e),
+    };
+    let data: Vec<_> = results
+        .hits
+        .into_iter()
+        .map(|hit| {
+            json!({
+                "file_id": hit.chunk.file_id,
+                "filename": hit.chunk.filename,
+                "score": hit.score,
+                "content": [{"type": "text", "text": hit.chunk.text}],
+            })
+        })
+        .collect();
+    Json(json!({
+        "object": "vector_store.search_results.page",
+        "search_query": body.query,
+        "data": data,
+        "has_more": false,
+        "next_page": null,
+        "metadata": {"retrieval_stages": results.stages},
+    }))
+    .into_response()
+}
@@ -0,0 +1,688 @@
+use std::{sync::Arc, time::Instant};
+
+use axum::body::to_bytes;
+use chrono::Utc;
+use dashmap::{mapref::entry::Entry, DashMap};
+use openai_protocol::embedding::EmbeddingRequest;
+use serde::Serialize;
+use serde_json::{json, Value};
+use smg_data_connector::{FileId, FileStorage, VectorStoreChunk, VectorStoreStorage};
+use tracing::{debug, info, warn};
+
+use super::{
+    chunking::{self, ChunkingStrategy},
+    extract::{self, DocumentFormat},
+    retrieval::{self, RetrievalStage, SearchResults, RERANK_STAGE, SIMILARITY_STAGE},
+};
+use crate::{
+    config::VectorStoreConfig,
+    middleware::{RouteRequestMeta, TenantKey},
+    routers::RouterTrait,
+    worker::WorkerRegistry,
+};
+
+/// One batch of embeddings; larger responses fail the ingestion.
+const EMBEDDING_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum VectorStoreError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Upstream(String),
+    #[error("{0}")]
+    Storage(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestionStatus {
+    InProgress,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IngestionProgress {
+    /// Chunks the file was split into; zero until extraction finishes
+    pub chunks_total: usize,
+    pub chunks_embedded: usize,
+}
+
+/// A file's ingestion into a vector store, shaped like OpenAI's
+/// `vector_store.file` object plus `embedding_model` and `progress`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorStoreFile {
+    pub id: String,
+    pub object: &'static str,
+    pub vector_store_id: String,
+    pub status: IngestionStatus,
+    pub created_at: i64,
+    pub usage_bytes: usize,
+    pub chunking_strategy: ChunkingStrategy,
+    pub embedding_model: String,
+    pub progress: IngestionProgress,
+    pub last_error: Option<Value>,
+}
+
+/// Owner and embedding model of a vector store, fixed by its first file.
+struct StoreInfo {
+    tenant_key: TenantKey,
+    embedding_model: String,
+}
+
+type FileKey = (String, FileId);
+
+pub struct VectorStoreIngestor {
+    config: VectorStoreConfig,
+    router: Arc<dyn RouterTrait>,
+    /// Consulted for whether the reranker model is served
+    worker_registry: Arc<WorkerRegistry>,
+    file_storage: Arc<dyn FileStorage>,
+    storage: Arc<dyn VectorStoreStorage>,
+    stores: DashMap<String, StoreInfo>,
+    files: DashMap<FileKey, VectorStoreFile>,
+}
+
+impl VectorStoreIngestor {
+    pub fn new(
+        config: VectorStoreConfig,
+        router: Arc<dyn RouterTrait>,
+        worker_registry: Arc<WorkerRegistry>,
+        file_storage: Arc<dyn FileStorage>,
+        storage: Arc<dyn VectorStoreStorage>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            router,
+            worker_registry,
+            file_storage,
+            storage,
+            stores: DashMap::new(),
+            files: DashMap::new(),
+        })
+    }
+
+    /// Start ingesting a stored file into a vector store, creating the store
+    /// on first use. Returns the file's `in_progress` record; ingestion
+    /// continues in the background.
+    pub async fn add_file(
+        self: &Arc<Self>,
+        meta: &RouteRequestMeta,
+        vector_store_id: &str,
+        file_id: FileId,
+        strategy: ChunkingStrategy,
+        embedding_model: Option<String>,
+    ) -> Result<VectorStoreFile, VectorStoreError> {
+        strategy
+            .validate()
+            .map_err(VectorStoreError::InvalidRequest)?;
+        let file = self
+            .file_storage
+            .get_file(&file_id)
+            .await
+            .map_err(|e| VectorStoreError::Storage(e.to_string()))?
+            .ok_or_else(|| {
+                VectorStoreError::NotFound(format!("No file found with id '{file_id}'"))
+            })?;
+        let format =
+            DocumentFormat::detect(&file.content_type, &file.filename).ok_or_else(|| {
+                VectorStoreError::InvalidRequest(format!(
+                "Unsupported file type '{}' for '{}'; expected PDF, HTML, Markdown or plain text",
+                file.content_type, file.filename
+            ))
+            })?;
+        let embedding_model =
+            self.claim_store(meta.tenant_key(), vector_store_id, embedding_model)?;
+
+        let record = VectorStoreFile {
+            id: file_id.0.clone(),
+            object: "vector_store.file",
+            vector_store_id: vector_store_id.to_string(),
+            status: IngestionStatus::InProgress,
+            created_at: Utc::now().timestamp(),
+            usage_bytes: file.bytes.len(),
+            chunking_strategy: strategy,
+            embedding_model,
+            progress: IngestionProgress::default(),
+            last_error: None,
+        };
+        let key = (vector_store_id.to_string(), file_id);
+        match self.files.entry(key.clone()) {
+            Entry::Occupied(entry) if entry.get().status == IngestionStatus::InProgress => {
+                return Err(VectorStoreError::Conflict(format!(
+                    "File '{}' is already being ingested into vector store '{vector_store_id}'",
+                    key.1
+                )));
+            }
+            Entry::Occupied(mut entry) => {
+                entry.insert(record.clone());
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(record.clone());
+            }
+        }
+
+        let ingestor = self.clone();
+        let meta = meta.clone();
+        let job = record.clone();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "ingestion outlives the request; an interrupted ingestion leaves the file failed or missing"
+        )]
+        tokio::spawn(async move {
+            ingestor
+                .run(key, &meta, format, file.filename, file.bytes, &job)
+                .await;
+        });
+        Ok(record)
+    }
+
+    /// The store's embedding model, registering the store for the tenant if
+    /// it is new. Stores of other tenants are reported as not found.
+    fn claim_store(
+        &self,
+        tenant_key: &TenantKey,
+        vector_store_id: &str,
+        requested_model: Option<String>,
+    ) -> Result<String, VectorStoreError> {
+        match self.stores.entry(vector_store_id.to_string()) {
+            Entry::Occupied(entry) => {
+                let info = entry.get();
+                if &info.tenant_key != tenant_key {
+                    return Err(store_not_found(vector_store_id));
+                }
+                match requested_model {
+                    Some(model) if model != info.embedding_model => {
+                        Err(VectorStoreError::InvalidRequest(format!(
+                            "Vector store '{vector_store_id}' is embedded with '{}', not '{model}'",
+                            info.embedding_model
+                        )))
+                    }
+                    _ => Ok(info.embedding_model.clone()),
+                }
+            }
+            Entry::Vacant(slot) => {
+                let model = requested_model
+                    .or_else(|| self.config.embedding_model.clone())
+                    .ok_or_else(|| {
+                        VectorStoreError::InvalidRequest(
+                            "embedding_model is required; no default embedding model is configured"
+                                .to_string(),
+                        )
+                    })?;
+                slot.insert(StoreInfo {
+                    tenant_key: tenant_key.clone(),
+                    embedding_model: model.clone(),
+                });
+                Ok(model)
+            }
+        }
+    }
+
+    fn store_model(
+        &self,
+        tenant_key: &TenantKey,
+        vector_store_id: &str,
+    ) -> Result<String, VectorStoreError> {
+        match self.stores.get(vector_store_id) {
+            Some(info) if &info.tenant_key == tenant_key => Ok(info.embedding_model.clone()),
+            _ => Err(store_not_found(vector_store_id)),
+        }
+    }
+
+    pub fn get_file(
+        &self,
+        meta: &RouteRequestMeta,
+        vector_store_id: &str,
+        file_id: &FileId,
+    ) -> Result<VectorStoreFile, VectorStoreError> {
+        self.store_model(meta.tenant_key(), vector_store_id)?;
+        self.files
+            .get(&(vector_store_id.to_string(), file_id.clone()))
+            .map(|file| file.clone())
+            .ok_or_else(|| {
+                VectorStoreError::NotFound(format!(
+                    "File '{file_id}' is not in vector store '{vector_store_id}'"
+                ))
+            })
+    }
+
+    /// Remove a file and its chunks from a vector store. The stored file
+    /// itself is kept.
+    pub async fn remove_file(
+        &self,
+        meta: &RouteRequestMeta,
+        vector_store_id: &str,
+        file_id: &FileId,
+    ) -> Result<(), VectorStoreError> {
+        let file = self.get_file(meta, vector_store_id, file_id)?;
+        if file.status == IngestionStatus::InProgress {
+            return Err(VectorStoreError::Conflict(format!(
+                "File '{file_id}' is still being ingested into vector store '{vector_store_id}'"
+            )));
+        }
+        self.files
+            .remove(&(vector_store_id.to_string(), file_id.clone()));
+        self.storage
+            .delete_file_chunks(vector_store_id, file_id)
+            .await
+            .map_err(|e| VectorStoreError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    /// The `top_k` chunks of a vector store most relevant to `query`
+    /// (`rerank_top_k` by default). With a served reranker, the
+    /// `retrieval_top_k` most similar chunks are reranked; if reranking
+    /// fails, similarity order is kept and the error is reported in the
+    /// rerank stage.
+    pub async fn search(
+        &self,
+        meta: &RouteRequestMeta,
+        vector_store_id: &str,
+        query: &str,
+        top_k: Option<usize>,
+    ) -> Result<SearchResults, VectorStoreError> {
+        let model = self.store_model(meta.tenant_key(), vector_store_id)?;
+        let top_k = top_k.unwrap_or(self.config.rerank_top_k);
+        let reranker = self
+            .config
+            .rerank_model
+            .as_deref()
+            .filter(|model| !self.worker_registry.get_by_model(model).is_empty());
+        let candidates = match reranker {
+            Some(_) => self.config.retrieval_top_k.max(top_k),
+            None => top_k,
+        };
+
+        // The similarity stage includes embedding the query.
+        let started = Instant::now();
+        let embedding = self
+            .embed(meta, &model, &[query.to_string()])
+            .await
+            .map_err(VectorStoreError::Upstream)?
+            .pop()
+            .unwrap_or_default();
+        let mut hits = self
+            .storage
+            .search(vector_store_id, &embedding, candidates)
+            .await
+            .map_err(|e| VectorStoreError::Storage(e.to_string()))?;
+        let mut stages = vec![RetrievalStage::finished(
+            SIMILARITY_STAGE,
+            Some(&model),
+            candidates,
+            hits.len(),
+            started,
+        )];
+
+        if let Some(reranker) = reranker {
+            let started = Instant::now();
+            let reranked =
+                retrieval::rerank(self.router.as_ref(), meta, reranker, query, &hits, top_k).await;
+            let mut stage =
+                RetrievalStage::finished(RERANK_STAGE, Some(reranker), top_k, 0, started);
+            match reranked {
+                Ok(reranked) => hits = reranked,
+                Err(error) => {
+                    warn!(%vector_store_id, %error, "Reranking failed; keeping similarity order");
+                    hits.truncate(top_k);
+                    stage.error = Some(error);
+                }
+            }
+            stage.results = hits.len();
+            stages.push(stage);
+        }
+        Ok(SearchResults { hits, stages })
+    }
+
+    async fn run(
+        &self,
+        key: FileKey,
+        meta: &RouteRequestMeta,
+        format: DocumentFormat,
+        filename: String,
+        bytes: Vec<u8>,
+        job: &VectorStoreFile,
+    ) {
+        let result = self.ingest(&key, meta, format, filename, bytes, job).await;
+        let (vector_store_id, file_id) = &key;
+        if let Err(error) = &result {
+            warn!(%vector_store_id, %file_id, %error, "Vector store ingestion failed");
+            // Do not leave a partially searchable file behind.
+            if let Err(e) = self
+                .storage
+                .delete_file_chunks(vector_store_id, file_id)
+                .await
+            {
+                warn!(%vector_store_id, %file_id, error = %e, "Failed to remove partial chunks");
+            }
+        }
+        self.update(&key, |file| match result {
+            Ok(()) => file.status = IngestionStatus::Completed,
+            Err(message) => {
+                file.status = IngestionStatus::Failed;
+                file.last_error = Some(json!({"code": "ingestion_failed", "message": message}));
+            }
+        });
+    }
+
+    async fn ingest(
+        &self,
+        key: &FileKey,
+        meta: &RouteRequestMeta,
+        format: DocumentFormat,
+        filename: String,
+        bytes: Vec<u8>,
+        job: &VectorStoreFile,
+    ) -> Result<(), String> {
+        let (vector_store_id, file_id) = key;
+        let model = &job.embedding_model;
+        let text = tokio::task::spawn_blocking(move || extract::extract_text(format, &bytes))
+            .await
+            .map_err(|e| format!("text extraction aborted: {e}"))??;
+
+        let max_chars = job.chunking_strategy.max_chunk_chars();
+        let chunks = match job.chunking_strategy {
+            ChunkingStrategy::Fixed {
+                chunk_overlap_tokens,
+                ..
+            } => chunking::fixed_chunks(
+                &text,
+                max_chars,
+                chunk_overlap_tokens * chunking::CHARS_PER_TOKEN_ESTIMATE,
+            ),
+            ChunkingStrategy::Sentence {
+                overlap_sentences, ..
+            } => chunking::pack_sentences(
+                &chunking::split_sentences(&text),
+                max_chars,
+                overlap_sentences,
+            ),
+            ChunkingStrategy::Semantic {
+                similarity_threshold,
+                ..
+            } => {
+                let sentences = chunking::split_sentences(&text);
+                let mut embeddings = Vec::with_capacity(sentences.len());
+                for batch in sentences.chunks(self.config.embedding_batch_size) {
+                    embeddings.extend(self.embed(meta, model, batch).await?);
+                }
+                chunking::semantic_chunks(&sentences, &embeddings, similarity_threshold, max_chars)
+            }
+        };
+        if chunks.is_empty() {
+            return Err("no text could be extracted from the file".to_string());
+        }
+        self.update(key, |file| file.progress.chunks_total = chunks.len());
+
+        // Re-ingesting a file replaces its earlier chunks.
+        self.storage
+            .delete_file_chunks(vector_store_id, file_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut embedded = 0;
+        for batch in chunks.chunks(self.config.embedding_batch_size) {
+            let embeddings = self.embed(meta, model, batch).await?;
+            let records = batch
+                .iter()
+                .zip(embeddings)
+                .enumerate()
+                .map(|(i, (text, embedding))| VectorStoreChunk {
+                    vector_store_id: vector_store_id.clone(),
+                    file_id: file_id.clone(),
+                    filename: filename.clone(),
+                    index: embedded + i,
+                    text: text.clone(),
+                    embedding,
+                })
+                .collect();
+            self.storage
+                .upsert_chunks(records)
+                .await
+                .map_err(|e| e.to_string())?;
+            embedded += batch.len();
+            self.update(key, |file| file.progress.chunks_embedded = embedded);
+            debug!(%vector_store_id, %file_id, embedded, total = chunks.len(), "Embedded chunks");
+        }
+        info!(%vector_store_id, %file_id, chunks = chunks.len(), "Ingested file into vector store");
+        Ok(())
+    }
+
+    fn update(&self, key: &FileKey, f: impl FnOnce(&mut VectorStoreFile)) {
+        if let Some(mut file) = self.files.get_mut(key) {
+            f(&mut file);
+        }
+    }
+
+    /// Embed `texts` through the gateway's embeddings routing.
+    async fn embed(
+        &self,
+        meta: &RouteRequestMeta,
+        model: &str,
+        texts: &[String],
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let request = EmbeddingRequest {
+            model: model.to_string(),
+            input: json!(texts),
+            encoding_format: Some("float".to_string()),
+            user: None,
+            dimensions: None,
+            rid: None,
+        };
+        let response = self
+            .router
+            .route_embeddings(None, meta, &request, model)
+            .await;
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), EMBEDDING_BODY_LIMIT)
+            .await
+            .map_err(|e| format!("failed to read embedding response: {e}"))?;
+        if !status.is_success() {
+            return Err(format!(
+                "embedding request failed with {status}: {}",
+                String::from_utf8_lossy(&bytes)
+            ));
+        }
+        parse_embeddings(&bytes, texts.len())
+    }
+}
+
+fn store_not_found(vector_store_id: &str) -> VectorStoreError {
+    VectorStoreError::NotFound(format!("No vector store found with id '{vector_store_id}'"))
+}
+
+/// Embeddings of an OpenAI-style embeddings response, in input order.
+fn parse_embeddings(body: &[u8], expected: usize) -> Result<Vec<Vec<f32>>, String> {
+    let response: Value =
+        serde_json::from_slice(body).map_err(|e| format!("invalid embedding response: {e}"))?;
+    let data = response
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or("embedding response has no data")?;
+    let mut embeddings: Vec<Option<Vec<f32>>> = vec![None; expected];
+    for (position, item) in data.iter().enumerate() {
+        let index = item
+            .get("index")
+            .and_then(Value::as_u64)
+            .map_or(position, |i| i as usize);
+        let vector = item
+            .get("embedding")
+            .and_then(Value::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(Value::as_f64)
+                    .map(|v| v as f32)
+                    .collect()
+            });
+        match (embeddings.get_mut(index), vector) {
+            (Some(slot), Some(vector)) => *slot = Some(vector),
+            _ => return Err(format!("unexpected embedding at index {index}")),
+        }
+    }
+    embeddings
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| format!("expected {expected} embeddings"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use axum::{
+        http::HeaderMap,
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use smg_data_connector::{MemoryFileStorage, MemoryVectorStoreStorage, StoredFile};
+
+    use super::*;
+    use crate::middleware::TenantRequestMeta;
+
+    const TOPICS: [&str; 3] = ["refund", "shipping", "warranty"];
+
+    /// Embeds text as a count of each topic keyword.
+    #[derive(Debug)]
+    struct KeywordEmbeddingRouter;
+
+    #[async_trait]
+    impl RouterTrait for KeywordEmbeddingRouter {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        async fn route_embeddings(
+            &self,
+            _headers: Option<&HeaderMap>,
+            _tenant_meta: &TenantRequestMeta,
+            body: &EmbeddingRequest,
+            _model_id: &str,
+        ) -> Response {
+            let inputs: Vec<String> = serde_json::from_value(body.input.clone()).unwrap();
+            let data: Vec<Value> = inputs
+                .iter()
+                .enumerate()
+                .map(|(index, text)| {
+                    let text = text.to_lowercase();
+                    let embedding: Vec<f32> = TOPICS
+                        .iter()
+                        .map(|topic| text.matches(topic).count() as f32)
+                        .collect();
+                    json!({"object": "embedding", "index": index, "embedding": embedding})
+                })
+                .collect();
+            Json(json!({"object": "list", "data": data})).into_response()
+        }
+
+        fn router_type(&self) -> &'static str {
+            "keyword"
+        }
+    }
+
+    fn meta(tenant: &str) -> RouteRequestMeta {
+        RouteRequestMeta::new(TenantKey::from(tenant))
+    }
+
+    #[tokio::test]
+    async fn ingested_markdown_is_searchable() {
+        let files = Arc::new(MemoryFileStorage::new(4));
+        let document =
+            "# Policies\n\nA refund is issued within 30 days. Refund requests need a receipt.\n\n\
+                        Shipping takes five days. Express shipping is available.";
+        let file_id = files
+            .store_file(StoredFile::new(
+                "policies.md",
+                "assistants",
+                "text/markdown",
+                document.as_bytes().to_vec(),
+            ))
+            .await
+            .unwrap();
+        let ingestor = VectorStoreIngestor::new(
+            VectorStoreConfig {
+                enabled: true,
+                embedding_model: Some("embedder".to_string()),
+                embedding_batch_size: 1,
+                ..Default::default()
+            },
+            Arc::new(KeywordEmbeddingRouter),
+            Arc::new(WorkerRegistry::new()),
+            files,
+            Arc::new(MemoryVectorStoreStorage::default()),
+        );
+        let strategy = ChunkingStrategy::Semantic {
+            max_chunk_tokens: 100,
+            similarity_threshold: 0.5,
+        };
+
+        let record = ingestor
+            .add_file(&meta("a"), "vs_1", file_id.clone(), strategy, None)
+            .await
+            .unwrap();
+        assert_eq!(record.status, IngestionStatus::InProgress);
+        assert_eq!(record.embedding_model, "embedder");
+
+        let mut file = record;
+        for _ in 0..100 {
+            file = ingestor.get_file(&meta("a"), "vs_1", &file_id).unwrap();
+            if file.status != IngestionStatus::InProgress {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            file.status,
+            IngestionStatus::Completed,
+            "{:?}",
+            file.last_error
+        );
+        assert_eq!(file.progress.chunks_total, 3);
+        assert_eq!(file.progress.chunks_embedded, 3);
+
+        let results = ingestor
+            .search(&meta("a"), "vs_1", "shipping options", Some(1))
+            .await
+            .unwrap();
+        let hits = results.hits;
+        assert_eq!(results.stages.len(), 1);
+        assert_eq!(results.stages[0].stage, SIMILARITY_STAGE);
+        assert_eq!(results.stages[0].top_k, 1);
+        assert_eq!(
+            hits[0].chunk.text,
+            "Shipping takes five days. Express shipping is available."
+        );
+        assert!(matches!(
+            ingestor.search(&meta("b"), "vs_1", "refund", None).await,
+            Err(VectorStoreError::NotFound(_))
+        ));
+
+        ingestor
+            .remove_file(&meta("a"), "vs_1", &file_id)
+            .await
+            .unwrap();
+        assert!(ingestor
+            .search(&meta("a"), "vs_1", "refund", None)
+            .await
+            .unwrap()
+            .hits
+            .is_empty());
+    }
+
+    #[test]
+    fn embeddings_are_returned_in_input_order() {
+        let body =
+            br#"{"data": [{"index": 1, "embedding": [0.5]}, {"index": 0, "embedding": [1.0]}]}"#;
+        assert_eq!(
+            parse_embeddings(body, 2).unwrap(),
+            vec![vec![1.0], vec![0.5]]
+        );
+        assert!(parse_embeddings(body, 3).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+//! Vector store ingestion and search.
+//!
+//! `POST /v1/vector_stores/{id}/files` ingests an uploaded file in the
+//! background: its text is extracted (PDF, HTML, Markdown or plain text),
+//! split by the requested [`ChunkingStrategy`] and embedded in batches
+//! through the gateway's own embeddings routing. The chunks are stored in a
+//! [`VectorStoreStorage`](smg_data_connector::VectorStoreStorage) and can be
+//! searched by similarity, optionally reranked by a second stage through
+//! the gateway's rerank routing. A store belongs to the tenant that added its first
+//! file and keeps that file's embedding model.
+
+mod chunking;
+mod extract;
+mod handlers;
+mod ingestion;
+mod retrieval;
+
+pub use chunking::ChunkingStrategy;
+pub use handlers::*;
+pub use ingestion::{
+    IngestionProgress, IngestionStatus, VectorStoreError, VectorStoreFile, VectorStoreIngestor,
+};
+pub use retrieval::{RetrievalStage, SearchResults};
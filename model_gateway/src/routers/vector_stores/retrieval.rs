@@ -0,0 +1,222 @@
+//! Second-stage reranking of similarity search candidates.
+//!
+//! Search takes `retrieval_top_k` candidates by embedding similarity and,
+//! when the configured reranker model is served, reorders them through the
+//! gateway's `/v1/rerank` routing, keeping the best `top_k`. Each stage's
+//! size and latency is reported with the results.
+
+use std::time::Instant;
+
+use axum::body::to_bytes;
+use openai_protocol::rerank::RerankRequest;
+use serde::Serialize;
+use serde_json::Value;
+use smg_data_connector::VectorSearchHit;
+
+use crate::{middleware::RouteRequestMeta, routers::RouterTrait};
+
+/// Scores for at most `retrieval_top_k` documents.
+const RERANK_BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+pub const SIMILARITY_STAGE: &str = "similarity_search";
+pub const RERANK_STAGE: &str = "rerank";
+
+/// One retrieval stage, as reported in search result metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetrievalStage {
+    pub stage: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub top_k: usize,
+    pub results: usize,
+    pub latency_ms: f64,
+    /// Set when a rerank failed and similarity order was kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RetrievalStage {
+    pub(super) fn finished(
+        stage: &'static str,
+        model: Option<&str>,
+        top_k: usize,
+        results: usize,
+        started: Instant,
+    ) -> Self {
+        Self {
+            stage,
+            model: model.map(str::to_string),
+            top_k,
+            results,
+            latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    pub hits: Vec<VectorSearchHit>,
+    pub stages: Vec<RetrievalStage>,
+}
+
+/// Reorder `hits` by the reranker's relevance to `query` and keep the best
+/// `top_k`. Scores are replaced by the reranker's.
+pub(super) async fn rerank(
+    router: &dyn RouterTrait,
+    meta: &RouteRequestMeta,
+    model: &str,
+    query: &str,
+    hits: &[VectorSearchHit],
+    top_k: usize,
+) -> Result<Vec<VectorSearchHit>, String> {
+    if hits.is_empty() {
+        return Ok(Vec::new());
+    }
+    let request = RerankRequest {
+        query: query.to_string(),
+        documents: hits.iter().map(|hit| hit.chunk.text.clone()).collect(),
+        model: model.to_string(),
+        top_k: Some(top_k),
+        return_documents: false,
+        rid: None,
+        user: None,
+        score_normalization: None,
+    };
+    let response = router.route_rerank(None, meta, &request, model).await;
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), RERANK_BODY_LIMIT)
+        .await
+        .map_err(|e| format!("failed to read rerank response: {e}"))?;
+    if !status.is_success() {
+        return Err(format!(
+            "rerank request failed with {status}: {}",
+            String::from_utf8_lossy(&bytes)
+        ));
+    }
+    let scores = parse_rerank_scores(&bytes)?;
+
+    let mut seen = vec![false; hits.len()];
+    let mut reranked: Vec<VectorSearchHit> = scores
+        .into_iter()
+        .filter_map(|(index, score)| {
+            let unseen = seen.get_mut(index)?;
+            if std::mem::replace(unseen, true) {
+                return None;
+            }
+            Some(VectorSearchHit {
+                chunk: hits[index].chunk.clone(),
+                score,
+            })
+        })
+        .collect();
+    reranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    reranked.truncate(top_k);
+    Ok(reranked)
+}
+
+/// `(index, score)` pairs of a rerank response: the gateway's `results`
+/// list, or a bare list of results.
+fn parse_rerank_scores(body: &[u8]) -> Result<Vec<(usize, f32)>, String> {
+    let response: Value =
+        serde_json::from_slice(body).map_err(|e| format!("invalid rerank response: {e}"))?;
+    let results = response
+        .get("results")
+        .unwrap_or(&response)
+        .as_array()
+        .ok_or("rerank response has no results")?;
+    results
+        .iter()
+        .map(|result| {
+            let index = result.get("index").and_then(Value::as_u64);
+            let score = result
+                .get("score")
+                .or_else(|| result.get("relevance_score"))
+                .and_then(Value::as_f64);
+            match (index, score) {
+                (Some(index), Some(score)) => Ok((index as usize, score as f32)),
+                _ => Err(format!("rerank result without index and score: {result}")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use axum::{
+        http::HeaderMap,
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use serde_json::json;
+    use smg_data_connector::{FileId, VectorStoreChunk};
+
+    use super::*;
+    use crate::middleware::{TenantKey, TenantRequestMeta};
+
+    /// Scores documents by their length, longest first.
+    #[derive(Debug)]
+    struct LengthReranker;
+
+    #[async_trait]
+    impl RouterTrait for LengthReranker {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        async fn route_rerank(
+            &self,
+            _headers: Option<&HeaderMap>,
+            _tenant_meta: &TenantRequestMeta,
+            body: &RerankRequest,
+            _model_id: &str,
+        ) -> Response {
+            let results: Vec<Value> = body
+                .documents
+                .iter()
+                .enumerate()
+                .map(|(index, doc)| json!({"index": index, "score": doc.len() as f32}))
+                .collect();
+            Json(json!({"object": "rerank", "model": body.model, "results": results}))
+                .into_response()
+        }
+
+        fn router_type(&self) -> &'static str {
+            "length"
+        }
+    }
+
+    fn hit(text: &str, score: f32) -> VectorSearchHit {
+        VectorSearchHit {
+            chunk: VectorStoreChunk {
+                vector_store_id: "vs_1".to_string(),
+                file_id: FileId::from("file_1"),
+                filename: "doc.txt".to_string(),
+                index: 0,
+                text: text.to_string(),
+                embedding: vec![],
+            },
+            score,
+        }
+    }
+
+    #[tokio::test]
+    async fn rerank_reorders_and_truncates_candidates() {
+        let hits = vec![hit("a", 0.9), hit("ccc", 0.8), hit("bb", 0.7)];
+        let meta = RouteRequestMeta::new(TenantKey::from("t"));
+        let reranked = rerank(&LengthReranker, &meta, "reranker", "q", &hits, 2)
+            .await
+            .unwrap();
+        let texts: Vec<_> = reranked.iter().map(|h| h.chunk.text.as_str()).collect();
+        assert_eq!(texts, vec!["ccc", "bb"]);
+        assert_eq!(reranked[0].score, 3.0);
+    }
+
+    #[test]
+    fn rerank_scores_accept_relevance_score() {
+        let body = br#"[{"index": 1, "relevance_score": 0.25}]"#;
+        assert_eq!(parse_rerank_scores(body).unwrap(), vec![(1, 0.25)]);
+        assert!(parse_rerank_scores(br#"{"results": [{"index": 0}]}"#).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -7,12 +8,16 @@ use std::{
 };
 
 use axum::{
-    extract::{Extension, Path, Query, Request, State},
-    http::{header::InvalidHeaderName, HeaderMap, StatusCode},
+    extract::{Extension, Multipart, Path, Query, Request, State},
+    http::{
+        header::{InvalidHeaderName, CONTENT_TYPE},
+        HeaderMap, StatusCode,
+    },
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Json, Router,
 };
+use llm_multimodal::CustomSpecDefinition;
 use llm_tokenizer::TokenizerRegistry;
 use openai_protocol::{
     chat::ChatCompletionRequest,
@@ -20,6 +25,7 @@ use openai_protocol::{
     completion::CompletionRequest,
     embedding::EmbeddingRequest,
     generate::GenerateRequest,
+    images::ImageGenerationRequest,
     interactions::InteractionsRequest,
     messages::CreateMessageRequest,
     multipart::AudioTranscriptionMultipart,
@@ -30,6 +36,7 @@ use openai_protocol::{
     },
     rerank::{RerankRequest, V1RerankReqInput},
     responses::ResponsesRequest,
+    score::ScoreRequest,
     tokenize::{AddTokenizerRequest, DetokenizeRequest, TokenizeRequest},
     validated::ValidatedJson,
     worker::{
@@ -38,7 +45,8 @@ use openai_protocol::{
 };
 use rustls::crypto::ring;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{json, Value};
+use smg_data_connector::{FileId, MemoryGenerationJobStorage, StoredFile};
 use smg_mesh::{MeshServerBuilder, MeshServerConfig, MeshServerHandler};
 use tokio::{signal, spawn, sync::mpsc};
 use tracing::{debug, error, info, warn, Level};
@@ -46,21 +54,42 @@ use wfaas::LoggingSubscriber;
 
 use crate::{
     app_context::AppContext,
-    config::RouterConfig,
-    mesh::MeshAdapters,
-    middleware::{self, AuthConfig, QueuedRequest},
+    config::{RouterConfig, RoutingRuleConfig, RoutingRulesConfig},
+    mesh::{MeshAdapters, RollingRestartCoordinator},
+    middleware::{self, debug_capture, AuthConfig, QueuedRequest},
     observability::{
+        event_stream,
         logging::{self, LoggingConfig},
         metrics::{self, PrometheusConfig},
         metrics_server, otel_trace, runtime_metrics,
     },
     routers::{
-        common::realtime::ws::RealtimeQueryParams, conversations, parse,
-        responses as response_handlers, router_manager::RouterManager, tokenize, RouterTrait,
+        async_generation, chat_completions,
+        common::{
+            bootstrap_rooms::bootstrap_rooms, experiments, latency_budget, map_reduce,
+            mcp_sampling::RouterSamplingBackend, prompt_guard, prompts,
+            realtime::ws::RealtimeQueryParams, sampling_limits, sessions, tenant_mcp,
+        },
+        conversations, error, parse, responses as response_handlers,
+        router_manager::RouterManager,
+        tokenize, vector_stores, RouterTrait,
     },
     service_discovery::{start_service_discovery, ServiceDiscoveryConfig},
-    wasm::route::{add_wasm_module, list_wasm_modules, remove_wasm_module},
-    worker::manager::{WorkerManager, WorkerManagerConfig},
+    tenant::TenantKey,
+    wasm::route::{
+        add_wasm_module, get_wasm_module_stats, list_wasm_module_stats, list_wasm_modules,
+        remove_wasm_module,
+    },
+    worker::{
+        debug_trace::{
+            parse_trace_duration, DEFAULT_TRACE_CAPACITY, DEFAULT_TRACE_DURATION,
+            MAX_TRACE_CAPACITY,
+        },
+        maintenance::MaintenanceWindowRequest,
+        manager::{WorkerManager, WorkerManagerConfig},
+        service::{WorkerService, WorkerServiceError},
+        Worker,
+    },
     workflow::{
         job_queue::{JobQueue, JobQueueConfig},
         Job, TokenizerConfigRequest, WorkflowEngines,
@@ -74,6 +103,8 @@ pub struct AppState {
     pub router_manager: Option<Arc<RouterManager>>,
     pub mesh_handler: Option<Arc<MeshServerHandler>>,
     pub mesh_adapters: Option<Arc<MeshAdapters>>,
+    /// Mesh-wide rolling restart coordinator; `None` when mesh is disabled.
+    pub rolling_restart: Option<Arc<RollingRestartCoordinator>>,
     /// Cached O(1) readiness state shared with the optional dedicated
     /// probe listener. Maintained event-driven by
     /// [`crate::health::spawn_readiness_maintainer`].
@@ -158,15 +189,284 @@ async fn v1_chat_completions(
     headers: HeaderMap,
     Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
     cancel: middleware::scheduler::PreemptionGuard,
-    ValidatedJson(body): ValidatedJson<ChatCompletionRequest>,
+    ValidatedJson(mut body): ValidatedJson<ChatCompletionRequest>,
 ) -> Response {
-    cancel
-        .guard(
-            state
-                .router
-                .route_chat(Some(&headers), &tenant_meta, &body, &body.model),
-        )
-        .await
+    if let Err(response) =
+        chat_completions::check_store_request(state.context.chat_completion_storage.as_ref(), &body)
+    {
+        return response;
+    }
+    let guard_verdict =
+        match prompt_guard::screen_chat(state.context.prompt_guard.as_deref(), &headers, &body)
+            .await
+        {
+            Ok(verdict) => verdict,
+            Err(response) => return response,
+        };
+    let assignment = experiments::assign_chat(
+        &state.context.router_config.experiments,
+        &headers,
+        tenant_meta.tenant_key(),
+        &mut body,
+    );
+    let clamped =
+        sampling_limits::apply_to_chat(&state.context.router_config.sampling_limits, &mut body);
+    let budget_cap = latency_budget::apply_to_chat(&state.context, &headers, &mut body);
+    let response = if body.map_reduce.is_some() {
+        cancel
+            .guard(map_reduce::route_chat(
+                state.router.as_ref(),
+                &state.context.router_config.map_reduce,
+                Some(&headers),
+                &tenant_meta,
+                &body,
+            ))
+            .await
+    } else {
+        cancel
+            .guard(
+                state
+                    .router
+                    .route_chat(Some(&headers), &tenant_meta, &body, &body.model),
+            )
+            .await
+    };
+    let response = match &state.context.chat_completion_storage {
+        Some(storage) if body.store == Some(true) => {
+            chat_completions::store_completion(storage, &body, tenant_meta.tenant_key(), response)
+                .await
+        }
+        _ => response,
+    };
+    let response = sampling_limits::annotate_response(response, &clamped);
+    let response = latency_budget::annotate_response(response, budget_cap);
+    let response = prompt_guard::annotate_response(response, guard_verdict.as_ref());
+    experiments::finish(response, assignment).await
+}
+
+fn chat_completion_store_disabled() -> Response {
+    error::not_found(
+        "chat_completion_store_disabled",
+        "Chat completion store is not enabled",
+    )
+}
+
+async fn v1_sessions_create(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Json(body): Json<sessions::CreateSessionRequest>,
+) -> Response {
+    sessions::create_session(
+        state.router.as_ref(),
+        &state.context,
+        &headers,
+        &tenant_meta,
+        body,
+    )
+    .await
+}
+
+async fn v1_sessions_get(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(session_id): Path<String>,
+) -> Response {
+    sessions::get_session(&state.context, &tenant_meta, &session_id)
+}
+
+async fn v1_sessions_delete(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(session_id): Path<String>,
+) -> Response {
+    sessions::close_session(&state.context, &tenant_meta, &session_id)
+}
+
+async fn v1_mcp_servers_list(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+) -> Response {
+    tenant_mcp::list_servers(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        tenant_meta.tenant_key(),
+    )
+    .await
+}
+
+async fn v1_mcp_servers_get(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(label): Path<String>,
+) -> Response {
+    tenant_mcp::get_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &label,
+    )
+    .await
+}
+
+async fn v1_mcp_servers_put(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(label): Path<String>,
+    Json(body): Json<tenant_mcp::RegisterMcpServerRequest>,
+) -> Response {
+    tenant_mcp::put_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &label,
+        body,
+    )
+    .await
+}
+
+async fn v1_mcp_servers_delete(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(label): Path<String>,
+) -> Response {
+    tenant_mcp::delete_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &label,
+    )
+    .await
+}
+
+async fn v1_prompts_list(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+) -> Response {
+    prompts::list_prompts(
+        state.context.prompt_storage.as_ref(),
+        tenant_meta.tenant_key(),
+    )
+    .await
+}
+
+async fn v1_prompts_get(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(prompt_id): Path<String>,
+    Query(query): Query<prompts::PromptVersionQuery>,
+) -> Response {
+    prompts::get_prompt(
+        state.context.prompt_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &prompt_id,
+        query.version,
+    )
+    .await
+}
+
+async fn v1_prompts_delete(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(prompt_id): Path<String>,
+) -> Response {
+    prompts::delete_prompt(
+        state.context.prompt_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &prompt_id,
+    )
+    .await
+}
+
+async fn v1_prompt_versions_list(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(prompt_id): Path<String>,
+) -> Response {
+    prompts::list_versions(
+        state.context.prompt_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &prompt_id,
+    )
+    .await
+}
+
+async fn v1_prompt_versions_publish(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(prompt_id): Path<String>,
+    Json(body): Json<prompts::PublishPromptRequest>,
+) -> Response {
+    prompts::publish_version(
+        state.context.prompt_storage.as_ref(),
+        tenant_meta.tenant_key(),
+        &prompt_id,
+        body,
+    )
+    .await
+}
+
+async fn v1_chat_completions_list(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(storage) = &state.context.chat_completion_storage else {
+        return chat_completion_store_disabled();
+    };
+    chat_completions::list_completions(storage, tenant_meta.tenant_key(), &query).await
+}
+
+async fn v1_chat_completions_get(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(completion_id): Path<String>,
+) -> Response {
+    let Some(storage) = &state.context.chat_completion_storage else {
+        return chat_completion_store_disabled();
+    };
+    chat_completions::get_completion(storage, tenant_meta.tenant_key(), &completion_id).await
+}
+
+async fn v1_chat_completions_delete(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(completion_id): Path<String>,
+) -> Response {
+    let Some(storage) = &state.context.chat_completion_storage else {
+        return chat_completion_store_disabled();
+    };
+    chat_completions::delete_completion(storage, tenant_meta.tenant_key(), &completion_id).await
+}
+
+async fn v1_chat_completions_cancel(
+    Extension(cancellation): Extension<middleware::RequestCancellation>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(completion_id): Path<String>,
+) -> Response {
+    match cancellation.cancel(&completion_id, tenant_meta.tenant_key()) {
+        Some(cancelled) => {
+            middleware::cancelled_response(&completion_id, "chat.completion", cancelled.usage)
+        }
+        None => error::not_found(
+            "request_not_found",
+            format!("No in-flight request with id '{completion_id}'"),
+        ),
+    }
+}
+
+async fn v1_chat_completions_messages(
+    State(state): State<Arc<AppState>>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    Path(completion_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let Some(storage) = &state.context.chat_completion_storage else {
+        return chat_completion_store_disabled();
+    };
+    chat_completions::list_completion_messages(
+        storage,
+        tenant_meta.tenant_key(),
+        &completion_id,
+        &query,
+    )
+    .await
 }
 
 async fn v1_completions(
@@ -174,15 +474,40 @@ async fn v1_completions(
     headers: HeaderMap,
     Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
     cancel: middleware::scheduler::PreemptionGuard,
-    ValidatedJson(body): ValidatedJson<CompletionRequest>,
+    ValidatedJson(mut body): ValidatedJson<CompletionRequest>,
 ) -> Response {
-    cancel
+    let guard_verdict = match prompt_guard::screen_completion(
+        state.context.prompt_guard.as_deref(),
+        &headers,
+        &body,
+    )
+    .await
+    {
+        Ok(verdict) => verdict,
+        Err(response) => return response,
+    };
+    let assignment = experiments::assign_completion(
+        &state.context.router_config.experiments,
+        &headers,
+        tenant_meta.tenant_key(),
+        &mut body,
+    );
+    let clamped = sampling_limits::apply_to_completion(
+        &state.context.router_config.sampling_limits,
+        &mut body,
+    );
+    let budget_cap = latency_budget::apply_to_completion(&state.context, &headers, &mut body);
+    let response = cancel
         .guard(
             state
                 .router
                 .route_completion(Some(&headers), &tenant_meta, &body, &body.model),
         )
-        .await
+        .await;
+    let response = sampling_limits::annotate_response(response, &clamped);
+    let response = latency_budget::annotate_response(response, budget_cap);
+    let response = prompt_guard::annotate_response(response, guard_verdict.as_ref());
+    experiments::finish(response, assignment).await
 }
 
 async fn rerank(
@@ -223,16 +548,34 @@ async fn v1_responses(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    compactor: Option<Extension<Arc<conversations::ConversationCompactor>>>,
     cancel: middleware::scheduler::PreemptionGuard,
-    ValidatedJson(body): ValidatedJson<ResponsesRequest>,
+    ValidatedJson(mut body): ValidatedJson<ResponsesRequest>,
 ) -> Response {
-    cancel
+    if let Some(storage) = &state.context.prompt_storage {
+        if let Err(response) =
+            prompts::apply_prompt(storage.as_ref(), tenant_meta.tenant_key(), &mut body).await
+        {
+            return response;
+        }
+    }
+    if let Some(storage) = &state.context.tenant_mcp_server_storage {
+        tenant_mcp::apply_tenant_mcp_servers(storage.as_ref(), tenant_meta.tenant_key(), &mut body)
+            .await;
+    }
+    let response = cancel
         .guard(
             state
                 .router
                 .route_responses(Some(&headers), &tenant_meta, &body, &body.model),
         )
-        .await
+        .await;
+    if let (Some(Extension(compactor)), Some(conversation)) = (compactor, &body.conversation) {
+        if response.status().is_success() && !conversation.is_empty() {
+            compactor.schedule(conversation.as_id().into(), &body.model, &tenant_meta);
+        }
+    }
+    response
 }
 
 async fn v1_interactions(
@@ -300,6 +643,110 @@ async fn v1_classify(
         .await
 }
 
+async fn v1_score(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    cancel: middleware::scheduler::PreemptionGuard,
+    ValidatedJson(body): ValidatedJson<ScoreRequest>,
+) -> Response {
+    cancel
+        .guard(
+            state
+                .router
+                .route_score(Some(&headers), &tenant_meta, &body, &body.model),
+        )
+        .await
+}
+
+async fn v1_images_generations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
+    cancel: middleware::scheduler::PreemptionGuard,
+    ValidatedJson(body): ValidatedJson<ImageGenerationRequest>,
+) -> Response {
+    cancel
+        .guard(state.router.route_image_generation(
+            Some(&headers),
+            &tenant_meta,
+            &body,
+            &body.model,
+        ))
+        .await
+}
+
+async fn v1_files_content(
+    State(state): State<Arc<AppState>>,
+    Path(file_id): Path<String>,
+) -> Response {
+    let Some(storage) = &state.context.file_storage else {
+        return error::not_found("file_store_disabled", "File store is not enabled");
+    };
+    match storage.get_file(&FileId::from(file_id.as_str())).await {
+        Ok(Some(file)) => ([(CONTENT_TYPE, file.content_type)], file.bytes).into_response(),
+        Ok(None) => error::not_found(
+            "file_not_found",
+            format!("No file found with id '{file_id}'"),
+        ),
+        Err(e) => error::internal_error("file_store_error", e.to_string()),
+    }
+}
+
+/// `POST /v1/files`: store a multipart `file` part under the form's
+/// `purpose`.
+async fn v1_files_upload(State(state): State<Arc<AppState>>, mut multipart: Multipart) -> Response {
+    let Some(storage) = &state.context.file_storage else {
+        return error::not_found("file_store_disabled", "File store is not enabled");
+    };
+    let mut purpose = None;
+    let mut upload = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return error::bad_request("invalid_multipart", e.to_string()),
+        };
+        match field.name() {
+            Some("purpose") => match field.text().await {
+                Ok(text) => purpose = Some(text),
+                Err(e) => return error::bad_request("invalid_multipart", e.to_string()),
+            },
+            Some("file") => {
+                let filename = field.file_name().unwrap_or("upload").to_string();
+                let content_type = field
+                    .content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                match field.bytes().await {
+                    Ok(bytes) => upload = Some((filename, content_type, bytes.to_vec())),
+                    Err(e) => return error::bad_request("invalid_multipart", e.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+    let (Some(purpose), Some((filename, content_type, bytes))) = (purpose, upload) else {
+        return error::bad_request(
+            "invalid_request",
+            "Both 'file' and 'purpose' form fields are required",
+        );
+    };
+    let file = StoredFile::new(filename, purpose, content_type, bytes);
+    let response = json!({
+        "id": file.id,
+        "object": "file",
+        "bytes": file.bytes.len(),
+        "created_at": file.created_at.timestamp(),
+        "filename": file.filename,
+        "purpose": file.purpose,
+    });
+    match storage.store_file(file).await {
+        Ok(_) => Json(response).into_response(),
+        Err(e) => error::internal_error("file_store_error", e.to_string()),
+    }
+}
+
 async fn v1_audio_transcriptions(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -325,11 +772,38 @@ async fn v1_responses_get(
     response_handlers::get_response(&state.context.response_storage, &response_id).await
 }
 
+/// Cancel the caller's in-flight request `response_id`, if there is one,
+/// and mark its stored response cancelled.
+async fn cancel_inflight_response(
+    state: &AppState,
+    cancellation: &middleware::RequestCancellation,
+    tenant_meta: &middleware::TenantRequestMeta,
+    response_id: &str,
+) -> Option<Response> {
+    let cancelled = cancellation.cancel(response_id, tenant_meta.tenant_key())?;
+    if let Some(stored_id) = &cancelled.response_id {
+        response_handlers::mark_response_cancelled(&state.context.response_storage, stored_id)
+            .await;
+    }
+    Some(middleware::cancelled_response(
+        response_id,
+        "response",
+        cancelled.usage,
+    ))
+}
+
 async fn v1_responses_cancel(
     State(state): State<Arc<AppState>>,
+    Extension(cancellation): Extension<middleware::RequestCancellation>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
     Path(response_id): Path<String>,
     headers: HeaderMap,
 ) -> Response {
+    if let Some(response) =
+        cancel_inflight_response(&state, &cancellation, &tenant_meta, &response_id).await
+    {
+        return response;
+    }
     state
         .router
         .cancel_response(Some(&headers), &response_id)
@@ -338,8 +812,15 @@ async fn v1_responses_cancel(
 
 async fn v1_responses_delete(
     State(state): State<Arc<AppState>>,
+    Extension(cancellation): Extension<middleware::RequestCancellation>,
+    Extension(tenant_meta): Extension<middleware::TenantRequestMeta>,
     Path(response_id): Path<String>,
 ) -> Response {
+    if let Some(response) =
+        cancel_inflight_response(&state, &cancellation, &tenant_meta, &response_id).await
+    {
+        return response;
+    }
     response_handlers::delete_response(&state.context.response_storage, &response_id).await
 }
 
@@ -408,120 +889,733 @@ async fn v1_conversations_list_items(
     .await
 }
 
-#[derive(Deserialize, Default)]
-struct GetItemQuery {
-    /// Additional fields to include in response (not yet implemented)
-    include: Option<Vec<String>>,
+#[derive(Deserialize, Default)]
+struct GetItemQuery {
+    /// Additional fields to include in response (not yet implemented)
+    include: Option<Vec<String>>,
+}
+
+async fn v1_conversations_create_items(
+    State(state): State<Arc<AppState>>,
+    Path(conversation_id): Path<String>,
+    Json(body): Json<Value>,
+) -> Response {
+    conversations::create_conversation_items(
+        &state.context.conversation_storage,
+        &state.context.conversation_item_storage,
+        &conversation_id,
+        body,
+    )
+    .await
+}
+
+async fn v1_conversations_get_item(
+    State(state): State<Arc<AppState>>,
+    Path((conversation_id, item_id)): Path<(String, String)>,
+    Query(query): Query<GetItemQuery>,
+) -> Response {
+    conversations::get_conversation_item(
+        &state.context.conversation_storage,
+        &state.context.conversation_item_storage,
+        &conversation_id,
+        &item_id,
+        query.include,
+    )
+    .await
+}
+
+async fn v1_conversations_delete_item(
+    State(state): State<Arc<AppState>>,
+    Path((conversation_id, item_id)): Path<(String, String)>,
+) -> Response {
+    conversations::delete_conversation_item(
+        &state.context.conversation_storage,
+        &state.context.conversation_item_storage,
+        &conversation_id,
+        &item_id,
+    )
+    .await
+}
+
+async fn v1_realtime_webrtc(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RealtimeQueryParams>,
+    req: Request,
+) -> Response {
+    // Model may come from query param (application/sdp) or session body
+    // (multipart/form-data). Let the handler validate per content type.
+    let model = params.model.unwrap_or_default();
+    state.router.route_realtime_webrtc(req, &model).await
+}
+
+async fn v1_realtime_ws(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RealtimeQueryParams>,
+    req: Request,
+) -> Response {
+    let model = match params.model {
+        Some(m) if !m.trim().is_empty() => m,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Missing required 'model' query parameter",
+            )
+                .into_response();
+        }
+    };
+    state.router.route_realtime_ws(req, &model).await
+}
+
+async fn v1_realtime_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<RealtimeSessionCreateRequest>,
+) -> Response {
+    state
+        .router
+        .route_realtime_session(Some(&headers), &body)
+        .await
+}
+
+async fn v1_realtime_client_secret(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<RealtimeClientSecretCreateRequest>,
+) -> Response {
+    state
+        .router
+        .route_realtime_client_secret(Some(&headers), &body)
+        .await
+}
+
+async fn v1_realtime_transcription_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<RealtimeTranscriptionSessionCreateRequest>,
+) -> Response {
+    state
+        .router
+        .route_realtime_transcription_session(Some(&headers), &body)
+        .await
+}
+
+async fn flush_cache(State(state): State<Arc<AppState>>, _req: Request) -> Response {
+    WorkerManager::flush_cache_all(&state.context.worker_registry)
+        .await
+        .into_response()
+}
+
+async fn flush_model_cache(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+) -> Response {
+    let mut result =
+        WorkerManager::flush_cache_for_model(&state.context.worker_registry, &model_id).await;
+    // Flushed prefixes are gone from the workers; stop routing as if they were not.
+    if !result.successful.is_empty() {
+        result.routing_reset = state
+            .context
+            .policy_registry
+            .reset_cache_aware_model(&model_id);
+    }
+    result.into_response()
+}
+
+async fn get_model_card_revisions(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+) -> Response {
+    match state
+        .context
+        .worker_registry
+        .model_cards()
+        .history(&model_id)
+    {
+        Some(history) => Json(history).into_response(),
+        None => error::not_found(
+            "model_card_not_found",
+            format!("No model card recorded for model '{model_id}'"),
+        ),
+    }
+}
+
+async fn set_model_cache_params(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+    Json(params): Json<Value>,
+) -> Response {
+    if !params.is_object() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Cache parameters must be a JSON object",
+        )
+            .into_response();
+    }
+    WorkerManager::set_cache_params_for_model(&state.context.worker_registry, &model_id, &params)
+        .await
+        .into_response()
+}
+
+fn rolling_restart_coordinator(
+    state: &AppState,
+) -> Result<&Arc<RollingRestartCoordinator>, Response> {
+    state.rolling_restart.as_ref().ok_or_else(|| {
+        error::service_unavailable(
+            "mesh_disabled",
+            "Rolling restarts require mesh to be enabled",
+        )
+    })
+}
+
+async fn begin_rolling_restart(State(state): State<Arc<AppState>>) -> Response {
+    let coordinator = match rolling_restart_coordinator(&state) {
+        Ok(coordinator) => coordinator,
+        Err(response) => return response,
+    };
+    match coordinator.begin() {
+        Ok(plan) => (StatusCode::ACCEPTED, Json(plan)).into_response(),
+        Err(e) => error::create_error(StatusCode::CONFLICT, "restart_in_progress", e.to_string()),
+    }
+}
+
+async fn get_rolling_restart(State(state): State<Arc<AppState>>) -> Response {
+    let coordinator = match rolling_restart_coordinator(&state) {
+        Ok(coordinator) => coordinator,
+        Err(response) => return response,
+    };
+    match coordinator.status() {
+        Some(status) => Json(status).into_response(),
+        None => error::not_found("restart_not_found", "No rolling restart is active"),
+    }
+}
+
+async fn abort_rolling_restart(State(state): State<Arc<AppState>>) -> Response {
+    let coordinator = match rolling_restart_coordinator(&state) {
+        Ok(coordinator) => coordinator,
+        Err(response) => return response,
+    };
+    match coordinator.abort() {
+        Some(plan) => Json(plan).into_response(),
+        None => error::not_found("restart_not_found", "No rolling restart is active"),
+    }
+}
+
+async fn list_maintenance_windows(State(state): State<Arc<AppState>>) -> Response {
+    Json(json!({ "object": "list", "data": state.context.maintenance.windows() })).into_response()
+}
+
+async fn create_maintenance_window(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<MaintenanceWindowRequest>,
+) -> Response {
+    match state.context.maintenance.open_window(request) {
+        Ok(window) => (StatusCode::CREATED, Json(window)).into_response(),
+        Err(e) => error::bad_request("invalid_maintenance_window", e.to_string()),
+    }
+}
+
+async fn cancel_maintenance_window(
+    State(state): State<Arc<AppState>>,
+    Path(window_id): Path<String>,
+) -> Response {
+    match state.context.maintenance.cancel_window(&window_id) {
+        Some(window) => Json(window).into_response(),
+        None => error::not_found(
+            "maintenance_window_not_found",
+            format!("No manual maintenance window '{window_id}'"),
+        ),
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct BootstrapRoomsQuery {
+    /// Minimum room age; defaults to `pd_bootstrap.stuck_threshold_secs`
+    min_age_secs: Option<u64>,
+}
+
+async fn list_bootstrap_rooms(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BootstrapRoomsQuery>,
+) -> Response {
+    let rooms = bootstrap_rooms();
+    let config = &state.context.router_config.pd_bootstrap;
+    let min_age_secs = query.min_age_secs.unwrap_or(config.stuck_threshold_secs);
+    Json(json!({
+        "object": "list",
+        "open": rooms.open_count(),
+        "min_age_secs": min_age_secs,
+        "data": rooms.open_longer_than(Duration::from_secs(min_age_secs)),
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize, Default)]
+struct PdPairsQuery {
+    /// Only list pairs flagged slow
+    #[serde(default)]
+    slow: bool,
+}
+
+async fn list_pd_pairs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PdPairsQuery>,
+) -> Response {
+    let findings = state.context.pd_pairs.evaluate();
+    let pairs: Vec<_> = findings
+        .pairs
+        .into_iter()
+        .filter(|pair| pair.slow || !query.slow)
+        .collect();
+    Json(json!({
+        "object": "list",
+        "baseline_p50_ms": findings.baseline_p50_ms,
+        "data": pairs,
+    }))
+    .into_response()
+}
+
+async fn list_routing_rules(State(state): State<Arc<AppState>>) -> Response {
+    Json(json!({ "object": "list", "data": state.context.routing_rules.list() })).into_response()
+}
+
+#[derive(Deserialize, Default)]
+struct RoutingRulePositionQuery {
+    /// Index to insert at; defaults to the end of the table
+    position: Option<usize>,
+}
+
+/// Share an admin change to the rule table with the other mesh nodes.
+fn publish_routing_rules(state: &AppState) {
+    if let Some(adapters) = &state.mesh_adapters {
+        adapters.routing_rules().publish();
+    }
+}
+
+fn routing_rules_error(e: middleware::RoutingRulesError) -> Response {
+    match e {
+        middleware::RoutingRulesError::Invalid { .. } => {
+            error::bad_request("invalid_routing_rule", e.to_string())
+        }
+        middleware::RoutingRulesError::Duplicate(_) => {
+            error::create_error(StatusCode::CONFLICT, "routing_rule_exists", e.to_string())
+        }
+        middleware::RoutingRulesError::NotFound(_) => {
+            error::not_found("routing_rule_not_found", e.to_string())
+        }
+    }
+}
+
+async fn create_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RoutingRulePositionQuery>,
+    Json(rule): Json<RoutingRuleConfig>,
+) -> Response {
+    match state
+        .context
+        .routing_rules
+        .create(rule.clone(), query.position)
+    {
+        Ok(()) => {
+            publish_routing_rules(&state);
+            (StatusCode::CREATED, Json(rule)).into_response()
+        }
+        Err(e) => routing_rules_error(e),
+    }
+}
+
+async fn replace_routing_rules(
+    State(state): State<Arc<AppState>>,
+    Json(config): Json<RoutingRulesConfig>,
+) -> Response {
+    match state.context.routing_rules.replace(config.rules) {
+        Ok(()) => {
+            publish_routing_rules(&state);
+            list_routing_rules(State(state)).await
+        }
+        Err(e) => routing_rules_error(e),
+    }
+}
+
+async fn get_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    match state.context.routing_rules.get(&name) {
+        Some(rule) => Json(rule).into_response(),
+        None => routing_rules_error(middleware::RoutingRulesError::NotFound(name)),
+    }
+}
+
+async fn update_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(rule): Json<RoutingRuleConfig>,
+) -> Response {
+    match state.context.routing_rules.update(&name, rule.clone()) {
+        Ok(()) => {
+            publish_routing_rules(&state);
+            Json(rule).into_response()
+        }
+        Err(e) => routing_rules_error(e),
+    }
+}
+
+async fn delete_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    match state.context.routing_rules.remove(&name) {
+        Some(rule) => {
+            publish_routing_rules(&state);
+            Json(rule).into_response()
+        }
+        None => routing_rules_error(middleware::RoutingRulesError::NotFound(name)),
+    }
+}
+
+async fn list_model_policies(State(state): State<Arc<AppState>>) -> Response {
+    let registry = &state.context.policy_registry;
+    Json(json!({
+        "default": registry.get_default_policy().name(),
+        "overrides": registry.model_policy_overrides(),
+        "models": registry.get_all_mappings(),
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct ModelPolicyRequest {
+    policy: String,
+}
+
+/// Share an admin change to the per-model policies with the other mesh nodes.
+fn publish_policy_overrides(state: &AppState) {
+    if let Some(adapters) = &state.mesh_adapters {
+        adapters.policy_overrides().publish();
+    }
+}
+
+async fn set_model_policy(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+    Json(request): Json<ModelPolicyRequest>,
+) -> Response {
+    let workers = state.context.worker_registry.get_by_model(&model_id);
+    match state
+        .context
+        .policy_registry
+        .set_model_policy(&model_id, &request.policy, &workers)
+    {
+        Ok(policy) => {
+            publish_policy_overrides(&state);
+            Json(json!({ "model_id": model_id, "policy": policy })).into_response()
+        }
+        Err(e) => error::bad_request("invalid_policy", e.to_string()),
+    }
+}
+
+async fn clear_model_policy(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+) -> Response {
+    let workers = state.context.worker_registry.get_by_model(&model_id);
+    match state
+        .context
+        .policy_registry
+        .clear_model_policy(&model_id, &workers)
+    {
+        Some(policy) => {
+            publish_policy_overrides(&state);
+            Json(json!({ "model_id": model_id, "policy": policy })).into_response()
+        }
+        None => error::not_found(
+            "model_policy_not_found",
+            format!("No policy assigned to model '{model_id}'"),
+        ),
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct WorkerDebugQuery {
+    /// `300`, `90s`, `5m` or `1h`
+    duration: Option<String>,
+    capacity: Option<usize>,
+}
+
+fn debug_trace_worker(state: &AppState, worker_id_raw: &str) -> Result<Arc<dyn Worker>, Response> {
+    let worker_id = WorkerService::parse_worker_id(worker_id_raw).map_err(|e| e.into_response())?;
+    state
+        .context
+        .worker_registry
+        .get(&worker_id)
+        .ok_or_else(|| {
+            WorkerServiceError::NotFound {
+                worker_id: worker_id_raw.to_string(),
+            }
+            .into_response()
+        })
+}
+
+async fn start_worker_debug(
+    State(state): State<Arc<AppState>>,
+    Path(worker_id): Path<String>,
+    Query(query): Query<WorkerDebugQuery>,
+) -> Response {
+    let worker = match debug_trace_worker(&state, &worker_id) {
+        Ok(worker) => worker,
+        Err(response) => return response,
+    };
+    let duration = match query.duration.as_deref().map(parse_trace_duration) {
+        None => DEFAULT_TRACE_DURATION,
+        Some(Ok(duration)) => duration,
+        Some(Err(e)) => return error::bad_request("invalid_duration", e),
+    };
+    let capacity = query.capacity.unwrap_or(DEFAULT_TRACE_CAPACITY);
+    if !(1..=MAX_TRACE_CAPACITY).contains(&capacity) {
+        return error::bad_request(
+            "invalid_capacity",
+            format!("capacity must be between 1 and {MAX_TRACE_CAPACITY}"),
+        );
+    }
+    let session =
+        state
+            .context
+            .worker_debug_tracer
+            .start(&worker_id, worker.url(), duration, capacity);
+    info!(
+        worker_url = %worker.url(),
+        duration_secs = duration.as_secs(),
+        "Started worker payload trace"
+    );
+    Json(session).into_response()
+}
+
+async fn get_worker_debug(
+    State(state): State<Arc<AppState>>,
+    Path(worker_id): Path<String>,
+) -> Response {
+    let worker = match debug_trace_worker(&state, &worker_id) {
+        Ok(worker) => worker,
+        Err(response) => return response,
+    };
+    match state.context.worker_debug_tracer.exchanges(worker.url()) {
+        Some((session, exchanges)) => {
+            Json(json!({ "session": session, "exchanges": exchanges })).into_response()
+        }
+        None => error::not_found(
+            "worker_debug_not_found",
+            format!("No payload trace for worker '{worker_id}'"),
+        ),
+    }
+}
+
+async fn stop_worker_debug(
+    State(state): State<Arc<AppState>>,
+    Path(worker_id): Path<String>,
+) -> Response {
+    let worker = match debug_trace_worker(&state, &worker_id) {
+        Ok(worker) => worker,
+        Err(response) => return response,
+    };
+    if state.context.worker_debug_tracer.stop(worker.url()) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        error::not_found(
+            "worker_debug_not_found",
+            format!("No payload trace for worker '{worker_id}'"),
+        )
+    }
+}
+
+fn mesh_handler(state: &AppState) -> Result<&Arc<MeshServerHandler>, Response> {
+    state.mesh_handler.as_ref().ok_or_else(|| {
+        error::service_unavailable(
+            "mesh_disabled",
+            "Mesh node weights require mesh to be enabled",
+        )
+    })
+}
+
+async fn list_mesh_nodes(State(state): State<Arc<AppState>>) -> Response {
+    match mesh_handler(&state) {
+        Ok(handler) => Json(json!({ "nodes": handler.node_capacities() })).into_response(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Deserialize)]
+struct SetNodeWeightRequest {
+    weight: u32,
+}
+
+async fn set_mesh_node_weight(
+    State(state): State<Arc<AppState>>,
+    Path(node_name): Path<String>,
+    Json(body): Json<SetNodeWeightRequest>,
+) -> Response {
+    let handler = match mesh_handler(&state) {
+        Ok(handler) => handler,
+        Err(response) => return response,
+    };
+    match handler.set_node_weight(&node_name, body.weight) {
+        Ok(node) => Json(node).into_response(),
+        Err(e) => error::not_found("node_not_found", e.to_string()),
+    }
+}
+
+async fn admin_mcp_servers_list(
+    State(state): State<Arc<AppState>>,
+    Path(tenant): Path<String>,
+) -> Response {
+    tenant_mcp::list_servers(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        &TenantKey::from(tenant),
+    )
+    .await
+}
+
+async fn admin_mcp_servers_get(
+    State(state): State<Arc<AppState>>,
+    Path((tenant, label)): Path<(String, String)>,
+) -> Response {
+    tenant_mcp::get_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &label,
+    )
+    .await
 }
 
-async fn v1_conversations_create_items(
+async fn admin_mcp_servers_put(
     State(state): State<Arc<AppState>>,
-    Path(conversation_id): Path<String>,
-    Json(body): Json<Value>,
+    Path((tenant, label)): Path<(String, String)>,
+    Json(body): Json<tenant_mcp::RegisterMcpServerRequest>,
 ) -> Response {
-    conversations::create_conversation_items(
-        &state.context.conversation_storage,
-        &state.context.conversation_item_storage,
-        &conversation_id,
+    tenant_mcp::put_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &label,
         body,
     )
     .await
 }
 
-async fn v1_conversations_get_item(
+async fn admin_mcp_servers_delete(
     State(state): State<Arc<AppState>>,
-    Path((conversation_id, item_id)): Path<(String, String)>,
-    Query(query): Query<GetItemQuery>,
+    Path((tenant, label)): Path<(String, String)>,
 ) -> Response {
-    conversations::get_conversation_item(
-        &state.context.conversation_storage,
-        &state.context.conversation_item_storage,
-        &conversation_id,
-        &item_id,
-        query.include,
+    tenant_mcp::delete_server(
+        state.context.tenant_mcp_server_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &label,
     )
     .await
 }
 
-async fn v1_conversations_delete_item(
+async fn admin_prompts_list(
     State(state): State<Arc<AppState>>,
-    Path((conversation_id, item_id)): Path<(String, String)>,
+    Path(tenant): Path<String>,
 ) -> Response {
-    conversations::delete_conversation_item(
-        &state.context.conversation_storage,
-        &state.context.conversation_item_storage,
-        &conversation_id,
-        &item_id,
+    prompts::list_prompts(
+        state.context.prompt_storage.as_ref(),
+        &TenantKey::from(tenant),
     )
     .await
 }
 
-async fn v1_realtime_webrtc(
+async fn admin_prompts_get(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<RealtimeQueryParams>,
-    req: Request,
+    Path((tenant, prompt_id)): Path<(String, String)>,
+    Query(query): Query<prompts::PromptVersionQuery>,
 ) -> Response {
-    // Model may come from query param (application/sdp) or session body
-    // (multipart/form-data). Let the handler validate per content type.
-    let model = params.model.unwrap_or_default();
-    state.router.route_realtime_webrtc(req, &model).await
+    prompts::get_prompt(
+        state.context.prompt_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &prompt_id,
+        query.version,
+    )
+    .await
 }
 
-async fn v1_realtime_ws(
+async fn admin_prompts_delete(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<RealtimeQueryParams>,
-    req: Request,
+    Path((tenant, prompt_id)): Path<(String, String)>,
 ) -> Response {
-    let model = match params.model {
-        Some(m) if !m.trim().is_empty() => m,
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                "Missing required 'model' query parameter",
-            )
-                .into_response();
-        }
-    };
-    state.router.route_realtime_ws(req, &model).await
+    prompts::delete_prompt(
+        state.context.prompt_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &prompt_id,
+    )
+    .await
 }
 
-async fn v1_realtime_session(
+async fn admin_prompt_versions_list(
     State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    ValidatedJson(body): ValidatedJson<RealtimeSessionCreateRequest>,
+    Path((tenant, prompt_id)): Path<(String, String)>,
 ) -> Response {
-    state
-        .router
-        .route_realtime_session(Some(&headers), &body)
-        .await
+    prompts::list_versions(
+        state.context.prompt_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &prompt_id,
+    )
+    .await
 }
 
-async fn v1_realtime_client_secret(
+async fn admin_prompt_versions_publish(
     State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    ValidatedJson(body): ValidatedJson<RealtimeClientSecretCreateRequest>,
+    Path((tenant, prompt_id)): Path<(String, String)>,
+    Json(body): Json<prompts::PublishPromptRequest>,
 ) -> Response {
-    state
-        .router
-        .route_realtime_client_secret(Some(&headers), &body)
-        .await
+    prompts::publish_version(
+        state.context.prompt_storage.as_ref(),
+        &TenantKey::from(tenant),
+        &prompt_id,
+        body,
+    )
+    .await
 }
 
-async fn v1_realtime_transcription_session(
+async fn list_multimodal_specs(State(state): State<Arc<AppState>>) -> Response {
+    Json(json!({
+        "specs": state.context.multimodal_model_registry.custom_definitions(),
+    }))
+    .into_response()
+}
+
+async fn register_multimodal_spec(
     State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    ValidatedJson(body): ValidatedJson<RealtimeTranscriptionSessionCreateRequest>,
+    Json(definition): Json<CustomSpecDefinition>,
 ) -> Response {
-    state
-        .router
-        .route_realtime_transcription_session(Some(&headers), &body)
-        .await
+    let name = definition.name.clone();
+    match state
+        .context
+        .multimodal_model_registry
+        .register_custom(definition)
+    {
+        Ok(()) => {
+            info!(spec = %name, "Registered custom multimodal processor spec");
+            (StatusCode::CREATED, Json(json!({ "name": name }))).into_response()
+        }
+        Err(e) => error::bad_request("invalid_processor_spec", e.to_string()),
+    }
 }
 
-async fn flush_cache(State(state): State<Arc<AppState>>, _req: Request) -> Response {
-    WorkerManager::flush_cache_all(&state.context.worker_registry)
-        .await
-        .into_response()
+async fn remove_multimodal_spec(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    if state
+        .context
+        .multimodal_model_registry
+        .unregister_custom(&name)
+    {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        error::not_found(
+            "processor_spec_not_found",
+            format!("Custom processor spec '{name}' not found"),
+        )
+    }
 }
 
 async fn start_profile(
@@ -773,67 +1867,381 @@ pub fn build_app(
         app_state.context.rate_limiter.clone(),
     );
 
-    let protected_routes = with_admission_layer(
-        Router::new()
-            .route("/v1/responses", post(v1_responses))
-            .route("/v1/responses/{response_id}", get(v1_responses_get))
+    let target_worker_state = middleware::TargetWorkerState::new(
+        &serving_auth_config,
+        &admin_auth_config,
+        control_plane_auth_state.as_ref(),
+        app_state.context.worker_registry.clone(),
+    );
+
+    // Inside tenant resolution so each stream is recorded with its tenant.
+    let stream_fanout =
+        middleware::StreamFanout::new(&app_state.context.router_config.stream_fanout);
+    let with_stream_fanout = |routes: Router<Arc<AppState>>| match &stream_fanout {
+        Some(fanout) => routes
+            .route_layer(axum::middleware::from_fn_with_state(
+                fanout.clone(),
+                middleware::stream_fanout_middleware,
+            ))
+            .route(
+                "/v1/streams/{request_id}",
+                get(middleware::stream_fanout::subscribe_stream).with_state(fanout.clone()),
+            ),
+        None => routes,
+    };
+
+    // Outside stream fan-out so a transcript covers the stream the client
+    // itself was reading.
+    let partial_transcripts = middleware::PartialTranscripts::new(
+        &app_state.context.router_config.partial_transcripts,
+        app_state.context.response_storage.clone(),
+    );
+    let with_partial_transcripts = |routes: Router<Arc<AppState>>| match &partial_transcripts {
+        Some(transcripts) => routes
+            .route_layer(axum::middleware::from_fn_with_state(
+                transcripts.clone(),
+                middleware::partial_transcripts_middleware,
+            ))
             .route(
-                "/v1/responses/{response_id}/cancel",
-                post(v1_responses_cancel),
+                "/v1/transcripts/{request_id}",
+                get(middleware::partial_transcripts::get_transcript)
+                    .with_state(transcripts.clone()),
+            ),
+        None => routes,
+    };
+
+    // Outside partial transcripts so a cancelled stream's transcript is
+    // stored as cancelled; the registry is also handed to the cancel routes.
+    let cancellation = middleware::RequestCancellation::new();
+    let with_request_cancellation = |routes: Router<Arc<AppState>>| {
+        routes
+            .route_layer(axum::middleware::from_fn_with_state(
+                cancellation.clone(),
+                middleware::request_cancellation_middleware,
+            ))
+            .route_layer(Extension(cancellation.clone()))
+    };
+
+    // Inside tenant resolution (flights are keyed per tenant) but outside
+    // admission, so coalesced clients do not hold admission slots.
+    let coalescer =
+        middleware::RequestCoalescer::new(&app_state.context.router_config.request_coalescing);
+    let with_coalescing = |routes: Router<Arc<AppState>>| match &coalescer {
+        Some(coalescer) => routes.route_layer(axum::middleware::from_fn_with_state(
+            coalescer.clone(),
+            middleware::request_coalescing_middleware,
+        )),
+        None => routes,
+    };
+
+    // Also inside tenant resolution, for per-tenant default URLs.
+    let webhooks = middleware::WebhookDispatcher::new(
+        &app_state.context.router_config.webhooks,
+        app_state.context.client.clone(),
+    );
+    let with_webhooks = |routes: Router<Arc<AppState>>| match &webhooks {
+        Some(dispatcher) => routes.route_layer(axum::middleware::from_fn_with_state(
+            dispatcher.clone(),
+            middleware::webhook_middleware,
+        )),
+        None => routes,
+    };
+
+    // Innermost, so the body and worker labels a rule rewrites are what the
+    // handlers see; always layered since rules can be added at runtime.
+    let routing_rules = app_state.context.routing_rules.clone();
+    let with_routing_rules = |routes: Router<Arc<AppState>>| {
+        routes.route_layer(axum::middleware::from_fn_with_state(
+            routing_rules.clone(),
+            middleware::routing_rules_middleware,
+        ))
+    };
+
+    // Outside admission and coalescing, so an expired federated request
+    // never takes a queue slot and its deadline also bounds queueing.
+    let federation = middleware::FederationSigner::new(
+        &app_state.context.router_config.federation,
+        app_state.context.router_config.request_timeout_secs,
+    );
+    let with_federation = |routes: Router<Arc<AppState>>| {
+        if federation.verifies_inbound() {
+            routes.route_layer(axum::middleware::from_fn_with_state(
+                federation.clone(),
+                middleware::federation_middleware,
+            ))
+        } else {
+            routes
+        }
+    };
+
+    // Inside coalescing, so coalesced clients share the record of the body
+    // they all receive.
+    let provenance = middleware::ProvenanceSigner::new(&app_state.context.router_config.provenance);
+    let with_provenance = |routes: Router<Arc<AppState>>| match &provenance {
+        Some(signer) => routes.route_layer(axum::middleware::from_fn_with_state(
+            signer.clone(),
+            middleware::provenance_middleware,
+        )),
+        None => routes,
+    };
+
+    // Outside provenance, so the checksum covers its trailer too.
+    let with_stream_integrity = |routes: Router<Arc<AppState>>| {
+        if app_state.context.router_config.stream_integrity.enabled {
+            routes.route_layer(axum::middleware::from_fn(
+                middleware::stream_integrity_middleware,
+            ))
+        } else {
+            routes
+        }
+    };
+
+    // Outside admission, so tagged latency includes queueing, and outside
+    // webhooks, so deliveries carry the parsed tags.
+    let request_tagger =
+        middleware::RequestTagger::new(&app_state.context.router_config.request_tags);
+    let with_request_tags = |routes: Router<Arc<AppState>>| match &request_tagger {
+        Some(tagger) => routes.route_layer(axum::middleware::from_fn_with_state(
+            tagger.clone(),
+            middleware::request_tags_middleware,
+        )),
+        None => routes,
+    };
+
+    // Always installed, so an unvalidated header never reaches a router.
+    let feature_gate =
+        middleware::RequestFeatureGate::new(&app_state.context.router_config.request_features);
+    let with_request_features = |routes: Router<Arc<AppState>>| {
+        routes.route_layer(axum::middleware::from_fn_with_state(
+            feature_gate.clone(),
+            middleware::request_features_middleware,
+        ))
+    };
+
+    // Outside admission: submitting only enqueues, and the queue's own
+    // worker pool bounds how many jobs run at once.
+    let async_generation = app_state
+        .context
+        .router_config
+        .async_generation
+        .enabled
+        .then(|| {
+            let config = &app_state.context.router_config.async_generation;
+            let storage = app_state
+                .context
+                .generation_job_storage
+                .clone()
+                .unwrap_or_else(|| {
+                    Arc::new(MemoryGenerationJobStorage::new(config.max_retained_jobs))
+                });
+            async_generation::AsyncGenerationQueue::start(
+                config,
+                app_state.router.clone(),
+                storage,
+                webhooks.clone(),
             )
-            .route("/v1/responses/{response_id}", delete(v1_responses_delete))
+        });
+    let with_async_generation = |routes: Router<Arc<AppState>>| match &async_generation {
+        Some(queue) => routes
             .route(
-                "/v1/responses/{response_id}/input_items",
-                get(v1_responses_list_input_items),
+                "/v1/generations/async",
+                post(async_generation::submit_generation).with_state(queue.clone()),
             )
-            .route("/v1/conversations", post(v1_conversations_create))
             .route(
-                "/v1/conversations/{conversation_id}",
-                get(v1_conversations_get)
-                    .post(v1_conversations_update)
-                    .delete(v1_conversations_delete),
+                "/v1/generations/async/{job_id}",
+                get(async_generation::get_generation).with_state(queue.clone()),
             )
             .route(
-                "/v1/conversations/{conversation_id}/items",
-                get(v1_conversations_list_items).post(v1_conversations_create_items),
+                "/v1/generations/async/{job_id}/cancel",
+                post(async_generation::cancel_generation).with_state(queue.clone()),
             )
             .route(
-                "/v1/conversations/{conversation_id}/items/{item_id}",
-                get(v1_conversations_get_item).delete(v1_conversations_delete_item),
+                "/v1/generations/async/{job_id}/events",
+                get(async_generation::generation_events).with_state(queue.clone()),
+            ),
+        None => routes,
+    };
+
+    let vector_store_ingestor = match (
+        app_state.context.router_config.vector_store.enabled,
+        &app_state.context.file_storage,
+        &app_state.context.vector_store_storage,
+    ) {
+        (true, Some(file_storage), Some(storage)) => Some(vector_stores::VectorStoreIngestor::new(
+            app_state.context.router_config.vector_store.clone(),
+            app_state.router.clone(),
+            app_state.context.worker_registry.clone(),
+            file_storage.clone(),
+            storage.clone(),
+        )),
+        _ => None,
+    };
+    let with_vector_stores = |routes: Router<Arc<AppState>>| match &vector_store_ingestor {
+        Some(ingestor) => routes
+            .route(
+                "/v1/vector_stores/{vector_store_id}/files",
+                post(vector_stores::add_vector_store_file).with_state(ingestor.clone()),
             )
-            .route_layer(axum::middleware::from_fn_with_state(
-                app_state.clone(),
-                middleware::storage_context_middleware,
-            ))
-            .route("/generate", post(generate))
-            .route("/v1/chat/completions", post(v1_chat_completions))
-            .route("/v1/completions", post(v1_completions))
-            .route("/rerank", post(rerank))
-            .route("/v1/rerank", post(v1_rerank))
-            .route("/v1/embeddings", post(v1_embeddings))
-            .route("/v1/messages", post(v1_messages))
-            .route("/v1/interactions", post(v1_interactions))
-            .route("/v1/classify", post(v1_classify))
-            // Tokenize / Detokenize endpoints
-            .route("/v1/tokenize", post(v1_tokenize))
-            .route("/v1/detokenize", post(v1_detokenize))
-            // Realtime REST endpoints (same middleware as other protected routes)
-            .route("/v1/realtime/sessions", post(v1_realtime_session))
             .route(
-                "/v1/realtime/client_secrets",
-                post(v1_realtime_client_secret),
+                "/v1/vector_stores/{vector_store_id}/files/{file_id}",
+                get(vector_stores::get_vector_store_file)
+                    .delete(vector_stores::delete_vector_store_file)
+                    .with_state(ingestor.clone()),
             )
             .route(
-                "/v1/realtime/transcription_sessions",
-                post(v1_realtime_transcription_session),
+                "/v1/vector_stores/{vector_store_id}/search",
+                post(vector_stores::search_vector_store).with_state(ingestor.clone()),
             ),
-        &admission_mode,
-        app_state.clone(),
-    )
+        None => routes,
+    };
+
+    let compactor = app_state
+        .context
+        .router_config
+        .conversation_compaction
+        .enabled
+        .then(|| {
+            conversations::ConversationCompactor::new(
+                app_state
+                    .context
+                    .router_config
+                    .conversation_compaction
+                    .clone(),
+                app_state.router.clone(),
+                app_state.context.conversation_item_storage.clone(),
+            )
+        });
+    let with_compaction = |routes: Router<Arc<AppState>>| match &compactor {
+        Some(compactor) => routes.route_layer(Extension(compactor.clone())),
+        None => routes,
+    };
+
+    let protected_routes = with_vector_stores(with_async_generation(with_request_cancellation(
+        with_partial_transcripts(with_stream_fanout(with_request_features(
+            with_request_tags(with_federation(with_webhooks(with_coalescing(
+                with_stream_integrity(with_provenance(with_admission_layer(
+                    with_routing_rules(with_compaction(
+                        Router::new()
+                            .route("/v1/responses", post(v1_responses))
+                            .route("/v1/responses/{response_id}", get(v1_responses_get))
+                            .route(
+                                "/v1/responses/{response_id}/cancel",
+                                post(v1_responses_cancel),
+                            )
+                            .route("/v1/responses/{response_id}", delete(v1_responses_delete))
+                            .route(
+                                "/v1/responses/{response_id}/input_items",
+                                get(v1_responses_list_input_items),
+                            )
+                            .route("/v1/conversations", post(v1_conversations_create))
+                            .route(
+                                "/v1/conversations/{conversation_id}",
+                                get(v1_conversations_get)
+                                    .post(v1_conversations_update)
+                                    .delete(v1_conversations_delete),
+                            )
+                            .route(
+                                "/v1/conversations/{conversation_id}/items",
+                                get(v1_conversations_list_items)
+                                    .post(v1_conversations_create_items),
+                            )
+                            .route(
+                                "/v1/conversations/{conversation_id}/items/{item_id}",
+                                get(v1_conversations_get_item).delete(v1_conversations_delete_item),
+                            )
+                            .route_layer(axum::middleware::from_fn_with_state(
+                                app_state.clone(),
+                                middleware::storage_context_middleware,
+                            ))
+                            .route("/generate", post(generate))
+                            .route(
+                                "/v1/chat/completions",
+                                post(v1_chat_completions).get(v1_chat_completions_list),
+                            )
+                            .route(
+                                "/v1/chat/completions/{completion_id}",
+                                get(v1_chat_completions_get).delete(v1_chat_completions_delete),
+                            )
+                            .route(
+                                "/v1/chat/completions/{completion_id}/messages",
+                                get(v1_chat_completions_messages),
+                            )
+                            .route(
+                                "/v1/chat/completions/{completion_id}/cancel",
+                                post(v1_chat_completions_cancel),
+                            )
+                            .route("/v1/completions", post(v1_completions))
+                            .route("/rerank", post(rerank))
+                            .route("/v1/rerank", post(v1_rerank))
+                            .route("/v1/embeddings", post(v1_embeddings))
+                            .route("/v1/messages", post(v1_messages))
+                            .route("/v1/interactions", post(v1_interactions))
+                            .route("/v1/classify", post(v1_classify))
+                            .route("/v1/score", post(v1_score))
+                            .route("/v1/images/generations", post(v1_images_generations))
+                            .route("/v1/files/{file_id}/content", get(v1_files_content))
+                            // Tokenize / Detokenize endpoints
+                            .route("/v1/tokenize", post(v1_tokenize))
+                            .route("/v1/detokenize", post(v1_detokenize))
+                            .route("/v1/sessions", post(v1_sessions_create))
+                            .route("/v1/prompts", get(v1_prompts_list))
+                            .route(
+                                "/v1/prompts/{prompt_id}",
+                                get(v1_prompts_get).delete(v1_prompts_delete),
+                            )
+                            .route(
+                                "/v1/prompts/{prompt_id}/versions",
+                                get(v1_prompt_versions_list).post(v1_prompt_versions_publish),
+                            )
+                            .route("/v1/mcp/servers", get(v1_mcp_servers_list))
+                            .route(
+                                "/v1/mcp/servers/{label}",
+                                get(v1_mcp_servers_get)
+                                    .put(v1_mcp_servers_put)
+                                    .delete(v1_mcp_servers_delete),
+                            )
+                            .route(
+                                "/v1/sessions/{session_id}",
+                                get(v1_sessions_get).delete(v1_sessions_delete),
+                            )
+                            // Realtime REST endpoints (same middleware as other protected routes)
+                            .route("/v1/realtime/sessions", post(v1_realtime_session))
+                            .route(
+                                "/v1/realtime/client_secrets",
+                                post(v1_realtime_client_secret),
+                            )
+                            .route(
+                                "/v1/realtime/transcription_sessions",
+                                post(v1_realtime_transcription_session),
+                            ),
+                    )),
+                    &admission_mode,
+                    app_state.clone(),
+                ))),
+            )))),
+        ))),
+    )))
+    // Outside admission so unservable requests never take a queue slot.
+    .route_layer(axum::middleware::from_fn_with_state(
+        app_state.context.worker_registry.clone(),
+        middleware::endpoint_capability_middleware,
+    ))
     .route_layer(axum::middleware::from_fn_with_state(
         tenant_resolution_state.clone(),
         middleware::route_request_meta_middleware,
     ))
+    // Inside auth so unauthenticated traffic is never captured; inside WASM
+    // so captures reflect what the backend actually received.
+    .route_layer(axum::middleware::from_fn_with_state(
+        app_state.clone(),
+        middleware::debug_capture_middleware,
+    ))
+    // Admin-only worker pinning; checked after serving auth so the caller is
+    // already known to be a gateway client.
+    .route_layer(axum::middleware::from_fn_with_state(
+        target_worker_state.clone(),
+        middleware::target_worker_middleware,
+    ))
     .route_layer(axum::middleware::from_fn_with_state(
         serving_auth_config.clone(),
         middleware::auth_middleware,
@@ -857,6 +2265,10 @@ pub fn build_app(
         tenant_resolution_state.clone(),
         middleware::route_request_meta_middleware,
     ))
+    .route_layer(axum::middleware::from_fn_with_state(
+        target_worker_state.clone(),
+        middleware::target_worker_middleware,
+    ))
     .route_layer(axum::middleware::from_fn_with_state(
         serving_auth_config.clone(),
         middleware::auth_middleware,
@@ -868,7 +2280,9 @@ pub fn build_app(
     // routinely exceed that, so WASM middleware would reject them with 400
     // before reaching the handler.
     let multipart_upload_routes = with_admission_layer(
-        Router::new().route("/v1/audio/transcriptions", post(v1_audio_transcriptions)),
+        Router::new()
+            .route("/v1/audio/transcriptions", post(v1_audio_transcriptions))
+            .route("/v1/files", post(v1_files_upload)),
         &admission_mode,
         app_state.clone(),
     )
@@ -876,20 +2290,48 @@ pub fn build_app(
         tenant_resolution_state,
         middleware::route_request_meta_middleware,
     ))
+    .route_layer(axum::middleware::from_fn_with_state(
+        target_worker_state,
+        middleware::target_worker_middleware,
+    ))
     .route_layer(axum::middleware::from_fn_with_state(
         serving_auth_config.clone(),
         middleware::auth_middleware,
     ));
 
+    let metadata_cache = middleware::MetadataCache::new(
+        &app_state.context.router_config.metadata_cache,
+        &app_state.context.worker_registry,
+    );
+    let with_metadata_cache = |routes: Router<Arc<AppState>>| match &metadata_cache {
+        Some(cache) => routes.route_layer(axum::middleware::from_fn_with_state(
+            cache.clone(),
+            middleware::metadata_cache_middleware,
+        )),
+        None => routes,
+    };
+
     let public_routes = Router::new()
         .route("/liveness", get(liveness))
         .route("/readiness", get(readiness))
         .route("/health", get(health))
         .route("/health_generate", get(health_generate))
         .route("/engine_metrics", get(engine_metrics))
-        .route("/v1/models", get(v1_models))
-        .route("/get_model_info", get(get_model_info))
-        .route("/get_server_info", get(get_server_info));
+        .merge(with_metadata_cache(
+            Router::new()
+                .route("/v1/models", get(v1_models))
+                .route("/get_model_info", get(get_model_info))
+                .route("/get_server_info", get(get_server_info)),
+        ));
+
+    let event_sources = event_stream::EventSources {
+        worker_registry: app_state.context.worker_registry.clone(),
+        scheduler: match &admission_mode {
+            middleware::scheduler::AdmissionMode::Priority(state) => Some(state.scheduler.clone()),
+            middleware::scheduler::AdmissionMode::Legacy => None,
+        },
+        concurrency_queue: app_state.concurrency_queue_tx.clone(),
+    };
 
     // Build admin routes with control plane auth if configured, otherwise use simple API key auth
     let admin_routes = Router::new()
@@ -902,6 +2344,111 @@ pub fn build_app(
         .route("/wasm", post(add_wasm_module))
         .route("/wasm/{module_uuid}", delete(remove_wasm_module))
         .route("/wasm", get(list_wasm_modules))
+        .route("/wasm/stats", get(list_wasm_module_stats))
+        .route("/wasm/{module_uuid}/stats", get(get_wasm_module_stats))
+        .route(
+            "/debug/captures",
+            get(debug_capture::list_captures).delete(debug_capture::clear_captures),
+        )
+        .route(
+            "/debug/captures/{capture_id}",
+            get(debug_capture::get_capture),
+        )
+        .route(
+            "/debug/webhooks/dead_letters",
+            get(middleware::webhook::list_dead_letters)
+                .delete(middleware::webhook::clear_dead_letters)
+                .with_state(webhooks.clone()),
+        )
+        .route(
+            "/admin/mesh/rolling-restart",
+            post(begin_rolling_restart)
+                .get(get_rolling_restart)
+                .delete(abort_rolling_restart),
+        )
+        .route(
+            "/admin/maintenance",
+            get(list_maintenance_windows).post(create_maintenance_window),
+        )
+        .route(
+            "/admin/maintenance/{window_id}",
+            delete(cancel_maintenance_window),
+        )
+        .route(
+            "/admin/models/{model_id}/cache/flush",
+            post(flush_model_cache),
+        )
+        .route(
+            "/admin/models/{model_id}/cache/params",
+            post(set_model_cache_params),
+        )
+        .route(
+            "/admin/models/{model_id}/card/revisions",
+            get(get_model_card_revisions),
+        )
+        .route(
+            "/admin/models/{model_id}/policy",
+            put(set_model_policy).delete(clear_model_policy),
+        )
+        .route("/admin/policies", get(list_model_policies))
+        .route("/admin/pd/bootstrap-rooms", get(list_bootstrap_rooms))
+        .route("/admin/pd/pairs", get(list_pd_pairs))
+        .route(
+            "/admin/routing-rules",
+            get(list_routing_rules)
+                .post(create_routing_rule)
+                .put(replace_routing_rules),
+        )
+        .route(
+            "/admin/routing-rules/{name}",
+            get(get_routing_rule)
+                .put(update_routing_rule)
+                .delete(delete_routing_rule),
+        )
+        .route(
+            "/admin/workers/{worker_id}/debug",
+            post(start_worker_debug)
+                .get(get_worker_debug)
+                .delete(stop_worker_debug),
+        )
+        .route("/admin/mesh/nodes", get(list_mesh_nodes))
+        .route(
+            "/admin/mesh/nodes/{node_name}/weight",
+            put(set_mesh_node_weight),
+        )
+        .route(
+            "/admin/multimodal/specs",
+            get(list_multimodal_specs).post(register_multimodal_spec),
+        )
+        .route(
+            "/admin/multimodal/specs/{name}",
+            delete(remove_multimodal_spec),
+        )
+        .route("/admin/tenants/{tenant}/prompts", get(admin_prompts_list))
+        .route(
+            "/admin/tenants/{tenant}/prompts/{prompt_id}",
+            get(admin_prompts_get).delete(admin_prompts_delete),
+        )
+        .route(
+            "/admin/tenants/{tenant}/prompts/{prompt_id}/versions",
+            get(admin_prompt_versions_list).post(admin_prompt_versions_publish),
+        )
+        .route(
+            "/admin/tenants/{tenant}/mcp/servers",
+            get(admin_mcp_servers_list),
+        )
+        .route(
+            "/admin/tenants/{tenant}/mcp/servers/{label}",
+            get(admin_mcp_servers_get)
+                .put(admin_mcp_servers_put)
+                .delete(admin_mcp_servers_delete),
+        )
+        .route(
+            "/admin/events/stream",
+            get(move |request: Request| {
+                event_stream::events_stream(event_sources.clone(), request)
+            }),
+        )
         // Tokenizer management endpoints
         .route(
             "/v1/tokenizers",
@@ -916,16 +2463,18 @@ pub fn build_app(
             get(v1_tokenizers_status),
         );
 
-    // Build worker routes
-    let worker_routes = Router::new()
-        .route("/workers", post(create_worker).get(list_workers_rest))
-        .route(
-            "/workers/{worker_id}",
-            get(get_worker)
-                .put(replace_worker)
-                .patch(update_worker)
-                .delete(delete_worker),
-        );
+    // Build worker routes; the cache only serves the `GET` listings
+    let worker_routes = with_metadata_cache(
+        Router::new()
+            .route("/workers", post(create_worker).get(list_workers_rest))
+            .route(
+                "/workers/{worker_id}",
+                get(get_worker)
+                    .put(replace_worker)
+                    .patch(update_worker)
+                    .delete(delete_worker),
+            ),
+    );
 
     // Fallback (no control-plane auth) normally uses `admin_auth_config`.
     // If only tenant keys are configured (no shared `--api-key`), there's no
@@ -1079,6 +2628,13 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
         .await?,
     );
 
+    // O(1) readiness state: maintained from WorkerRegistry events (plus a
+    // short checkpoint for broadcast-bypassing mutations) by the maintainer
+    // spawned below, read by `/readiness` on the main listener, by the
+    // optional dedicated probe listener, and by the rolling restart
+    // coordinator to decide when a restarted node is back.
+    let probe_state = crate::health::ProbeState::new(app_context.inflight_tracker.clone());
+
     // Register the CRDT namespaces and start the inbound sync adapters, then
     // start gossip. Order matters: see the note at the mesh build above.
     let mesh_adapters = mesh_handler.as_ref().map(|handler| {
@@ -1086,6 +2642,16 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
             handler.mesh_kv(),
             handler.self_name.clone(),
             app_context.worker_registry.clone(),
+            app_context.routing_rules.clone(),
+            app_context.policy_registry.clone(),
+        )
+    });
+    let rolling_restart = mesh_handler.as_ref().map(|handler| {
+        RollingRestartCoordinator::start(
+            handler.mesh_kv(),
+            handler.self_name.clone(),
+            handler.state.clone(),
+            probe_state.clone(),
         )
     });
     if let Some(mesh_server) = mesh_server {
@@ -1100,6 +2666,15 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
         });
     }
 
+    app_context.maintenance.start();
+    app_context.federation_catalog.start();
+    app_context.standby.start();
+    app_context.pd_pairs.start();
+    app_context.policy_registry.sessions().start();
+    bootstrap_rooms().start_sampler(Duration::from_secs(
+        config.router_config.pd_bootstrap.stuck_threshold_secs,
+    ));
+
     if config.prometheus_config.is_some() {
         app_context.inflight_tracker.start_sampler(20);
     }
@@ -1162,6 +2737,7 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
             chat_template_path: config.router_config.chat_template.clone(),
             cache_config: config.router_config.tokenizer_cache.to_option(),
             fail_on_duplicate: false,
+            worker_url: None,
         };
 
         let job = Job::AddTokenizer {
@@ -1225,6 +2801,12 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
     let router_manager = RouterManager::from_config(&config, &app_context).await?;
     let router: Arc<dyn RouterTrait> = router_manager.clone();
 
+    // MCP servers granted sampling call back into the gateway's own routing.
+    if let Some(orchestrator) = app_context.mcp_orchestrator.get() {
+        orchestrator
+            .set_sampling_backend(Arc::new(RouterSamplingBackend::new(Arc::clone(&router))));
+    }
+
     // WorkerManager owns the background health check loop. Its handle must
     // outlive the server to keep the task alive — bind it here so its Drop
     // (which aborts the task) runs at server shutdown.
@@ -1294,11 +2876,7 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
         .as_ref()
         .map(|c| c.advertise_addr.port());
 
-    // O(1) readiness state: maintained from WorkerRegistry events (plus a
-    // short checkpoint for broadcast-bypassing mutations), read by
-    // `/readiness` on the main listener and on the optional dedicated
-    // probe listener below. Dropping the JoinHandle detaches the task.
-    let probe_state = crate::health::ProbeState::new(app_context.inflight_tracker.clone());
+    // Dropping the JoinHandle detaches the task.
     let _readiness_maintainer = crate::health::spawn_readiness_maintainer(
         probe_state.clone(),
         app_context.worker_registry.clone(),
@@ -1324,6 +2902,7 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
         router_manager: Some(router_manager),
         mesh_handler,
         mesh_adapters,
+        rolling_restart: rolling_restart.clone(),
         probe_state,
     });
     if let Some(service_discovery_config) = config.service_discovery_config {
@@ -1425,7 +3004,15 @@ pub async fn startup(config: ServerConfig) -> Result<(), Box<dyn std::error::Err
         reason = "shutdown signal handler must outlive the server to trigger graceful shutdown"
     )]
     spawn(async move {
-        shutdown_signal().await;
+        match rolling_restart {
+            Some(coordinator) => tokio::select! {
+                () = shutdown_signal() => {},
+                () = coordinator.drain_requested() => {
+                    info!("Rolling restart turn reached, starting graceful shutdown");
+                },
+            },
+            None => shutdown_signal().await,
+        }
 
         // Phase 1: Flip readiness to 503, hold the listener open through the
         // settle window so propagation-lagged requests still land, then gate
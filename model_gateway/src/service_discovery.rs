@@ -1302,9 +1302,14 @@ mod tests {
 
     fn create_test_app_context() -> Arc<AppContext> {
         use crate::{
-            config::RouterConfig, middleware::TokenBucket,
+            config::RouterConfig,
+            middleware::{RoutingRules, TokenBucket},
             observability::inflight_tracker::InFlightRequestTracker,
-            routers::common::realtime::RealtimeRegistry, worker::WorkerService,
+            routers::{common::realtime::RealtimeRegistry, http::pd_pairs::PdPairTracker},
+            worker::{
+                FederationCatalog, MaintenanceController, StandbyController, WorkerDebugTracer,
+                WorkerService,
+            },
         };
 
         let router_config = RouterConfig::builder()
@@ -1333,6 +1338,13 @@ mod tests {
             conversation_item_storage: Arc::new(
                 smg_data_connector::MemoryConversationItemStorage::new(),
             ),
+            debug_capture_storage: None,
+            file_storage: None,
+            vector_store_storage: None,
+            chat_completion_storage: None,
+            tenant_mcp_server_storage: None,
+            prompt_storage: None,
+            generation_job_storage: None,
             worker_monitor: None,
             configured_reasoning_parser: None,
             configured_tool_parser: None,
@@ -1342,12 +1354,24 @@ mod tests {
             mcp_format_registry: openai_bridge::FormatRegistry::new(),
             tokenizer_registry: Arc::new(llm_tokenizer::registry::TokenizerRegistry::new()),
             multimodal_config_registry: Arc::new(MultimodalConfigRegistry::new()),
+            multimodal_model_registry: Arc::new(llm_multimodal::ModelRegistry::default()),
             wasm_manager: None,
             worker_service: Arc::new(WorkerService::new(
-                worker_registry,
+                worker_registry.clone(),
                 worker_job_queue,
-                router_config,
+                router_config.clone(),
             )),
+            federation_catalog: FederationCatalog::new(
+                &router_config.federation,
+                Arc::clone(&worker_registry),
+                reqwest::Client::new(),
+            ),
+            standby: StandbyController::new(&router_config.standby, Arc::clone(&worker_registry)),
+            maintenance: MaintenanceController::new(&router_config.maintenance, worker_registry),
+            worker_debug_tracer: WorkerDebugTracer::new(),
+            prompt_guard: None,
+            routing_rules: Arc::new(RoutingRules::new(&router_config.routing_rules)),
+            pd_pairs: PdPairTracker::new(&router_config.pd_pairs),
             inflight_tracker: InFlightRequestTracker::new(),
             kv_event_monitor: None,
             realtime_registry: Arc::new(RealtimeRegistry::new()),
@@ -0,0 +1,48 @@
+//! Controllable clock for simulation tests.
+
+use std::time::Duration;
+
+/// Moves tokio's clock forward on demand.
+///
+/// Circuit breakers and retry backoff read tokio's clock, so advancing it
+/// expires an open circuit or a pending sleep immediately. The clock keeps
+/// running in real time afterwards, offset by everything advanced so far.
+///
+/// The runtime is only paused while advancing: a runtime paused for the whole
+/// test auto-advances while it waits on loopback I/O, which fires request
+/// timeouts at random. Requires a current-thread runtime, the default for
+/// `#[tokio::test]`, and panics on one started with `start_paused`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimClock;
+
+impl SimClock {
+    /// Advance the clock by `duration`, firing every timer that falls due.
+    pub async fn advance(duration: Duration) {
+        tokio::time::pause();
+        tokio::time::advance(duration).await;
+        tokio::time::resume();
+    }
+
+    /// Current time on the simulated clock.
+    pub fn now() -> tokio::time::Instant {
+        tokio::time::Instant::now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_advance_moves_clock_and_fires_timers() {
+        let start = SimClock::now();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "Test helper: short-lived task joined before test ends"
+        )]
+        let sleep = tokio::spawn(tokio::time::sleep(Duration::from_secs(3600)));
+        SimClock::advance(Duration::from_secs(3600)).await;
+        sleep.await.unwrap();
+        assert!(SimClock::now() - start >= Duration::from_secs(3600));
+    }
+}
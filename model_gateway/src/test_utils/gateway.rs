@@ -0,0 +1,425 @@
+//! The full gateway app over simulated workers.
+
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, Request},
+    response::{IntoResponse, Response},
+};
+use http_body_util::BodyExt;
+use openai_protocol::{
+    model_card::ModelCard,
+    worker::{HealthCheckConfig, ResilienceUpdate},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use super::SimWorker;
+use crate::{
+    app_context::{AppContext, AppContextBuilder},
+    config::{RouterConfig, RoutingMode},
+    health,
+    middleware::AuthConfig,
+    routers::{router_manager::RouterManager, RouterFactory, RouterTrait},
+    server::{build_app, AppState},
+    worker::{
+        resilience::resolve_resilience, BasicWorkerBuilder, CircuitBreakerConfig, RuntimeType,
+        Worker, WorkerType,
+    },
+    workflow::{JobQueue, JobQueueConfig, WorkflowEngines},
+};
+
+/// Model every simulated worker serves.
+pub const SIM_MODEL: &str = "sim-model";
+
+/// The gateway's axum app, wired as `smg` wires it, over [`SimWorker`]s.
+///
+/// The routing mode of the config is replaced by one listing the given
+/// workers, which are registered in order with health checks disabled.
+/// Requests run through the app in process via [`SimGateway::send`].
+pub struct SimGateway {
+    app: axum::Router,
+    context: Arc<AppContext>,
+}
+
+impl SimGateway {
+    /// A regular HTTP router over `workers`.
+    pub async fn regular(mut config: RouterConfig, workers: &[&SimWorker]) -> Result<Self, String> {
+        config.mode = RoutingMode::Regular {
+            worker_urls: workers.iter().map(|w| w.url().to_string()).collect(),
+        };
+        let registrations = workers
+            .iter()
+            .map(|w| (w.url(), WorkerType::Regular))
+            .collect::<Vec<_>>();
+        Self::start(config, &registrations).await
+    }
+
+    /// A prefill/decode HTTP router over `prefill` and `decode` workers.
+    pub async fn prefill_decode(
+        mut config: RouterConfig,
+        prefill: &[&SimWorker],
+        decode: &[&SimWorker],
+    ) -> Result<Self, String> {
+        let (prefill_policy, decode_policy) = match config.mode {
+            RoutingMode::PrefillDecode {
+                prefill_policy,
+                decode_policy,
+                ..
+            } => (prefill_policy, decode_policy),
+            _ => (None, None),
+        };
+        config.mode = RoutingMode::PrefillDecode {
+            prefill_urls: prefill
+                .iter()
+                .map(|w| (w.url().to_string(), None))
+                .collect(),
+            decode_urls: decode.iter().map(|w| w.url().to_string()).collect(),
+            prefill_policy,
+            decode_policy,
+        };
+        let registrations = prefill
+            .iter()
+            .map(|w| (w.url(), WorkerType::Prefill))
+            .chain(decode.iter().map(|w| (w.url(), WorkerType::Decode)))
+            .collect::<Vec<_>>();
+        Self::start(config, &registrations).await
+    }
+
+    async fn start(config: RouterConfig, workers: &[(&str, WorkerType)]) -> Result<Self, String> {
+        let timeout_secs = config.request_timeout_secs;
+        let context = Arc::new(
+            AppContextBuilder::from_config(config, timeout_secs, None, None)
+                .await?
+                .build()
+                .map_err(|e| e.to_string())?,
+        );
+        let weak_context = Arc::downgrade(&context);
+        context
+            .worker_job_queue
+            .set(JobQueue::new(JobQueueConfig::default(), weak_context))
+            .map_err(|_| "job queue already initialized".to_string())?;
+        context
+            .workflow_engines
+            .set(WorkflowEngines::new(&context.router_config))
+            .map_err(|_| "workflow engines already initialized".to_string())?;
+
+        let router = Arc::from(RouterFactory::create_router(&context).await?);
+        let manager = RouterManager::new(context.worker_registry.clone(), context.client.clone());
+        manager.register_router(
+            RouterManager::determine_router_id(
+                &context.router_config.mode,
+                context.router_config.connection_mode,
+            ),
+            router,
+        );
+        let router: Arc<dyn RouterTrait> = Arc::new(manager);
+
+        register_workers(&context, workers);
+
+        let probe_state = health::ProbeState::new(context.inflight_tracker.clone());
+        let _maintainer = health::spawn_readiness_maintainer(
+            probe_state.clone(),
+            context.worker_registry.clone(),
+            context.tokenizer_registry.clone(),
+            context.router_config.clone(),
+        );
+        let config = &context.router_config;
+        let app = build_app(
+            Arc::new(AppState {
+                router,
+                context: context.clone(),
+                concurrency_queue_tx: None,
+                router_manager: None,
+                mesh_handler: None,
+                mesh_adapters: None,
+                rolling_restart: None,
+                probe_state,
+            }),
+            AuthConfig::with_tenant_keys(config.api_key.clone(), &config.tenant_api_keys),
+            AuthConfig::new(config.api_key.clone()),
+            None,
+            config.max_payload_size,
+            config.request_id_headers.clone().unwrap_or_else(|| {
+                vec![
+                    "x-request-id".to_string(),
+                    "x-correlation-id".to_string(),
+                    "x-trace-id".to_string(),
+                    "request-id".to_string(),
+                ]
+            }),
+            config.cors_allowed_origins.clone(),
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self { app, context })
+    }
+
+    pub fn context(&self) -> &Arc<AppContext> {
+        &self.context
+    }
+
+    /// The registered worker at `url`.
+    pub fn worker(&self, url: &str) -> Option<Arc<dyn Worker>> {
+        self.context.worker_registry.get_by_url(url)
+    }
+
+    /// Run `request` through the app.
+    pub async fn send(&self, request: Request<Body>) -> Response {
+        match self.app.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        }
+    }
+
+    /// POST `body` as JSON to `path`.
+    pub async fn post_json(&self, path: &str, body: Value) -> Response {
+        let request = Request::post(path)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()));
+        match request {
+            Ok(request) => self.send(request).await,
+            Err(e) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("invalid request: {e}"),
+            )
+                .into_response(),
+        }
+    }
+
+    /// Read a response body to the end, as text. A body that breaks off
+    /// yields what arrived before the break.
+    pub async fn body_text(response: Response) -> String {
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(Ok(frame)) = body.frame().await {
+            if let Some(data) = frame.data_ref() {
+                bytes.extend_from_slice(data);
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// Register workers as the worker workflow would after detecting them as
+/// SGLang workers serving [`SIM_MODEL`], then notify the policies.
+fn register_workers(context: &Arc<AppContext>, workers: &[(&str, WorkerType)]) {
+    let config = &context.router_config;
+    let cb = config.effective_circuit_breaker_config();
+    let (resilience, circuit_breaker) = resolve_resilience(
+        &config.effective_retry_config(),
+        &CircuitBreakerConfig {
+            failure_threshold: cb.failure_threshold,
+            success_threshold: cb.success_threshold,
+            timeout_duration: Duration::from_secs(cb.timeout_duration_secs),
+            window_duration: Duration::from_secs(cb.window_duration_secs),
+        },
+        !config.disable_retries,
+        !config.disable_circuit_breaker,
+        &ResilienceUpdate::default(),
+    );
+
+    for (url, worker_type) in workers {
+        let worker: Arc<dyn Worker> = Arc::new(
+            BasicWorkerBuilder::new(*url)
+                .model(ModelCard::new(SIM_MODEL))
+                .worker_type(*worker_type)
+                .runtime_type(RuntimeType::Sglang)
+                .circuit_breaker_config(circuit_breaker.clone())
+                .resilience(resilience.clone())
+                .http_client(context.client.clone())
+                .health_config(HealthCheckConfig {
+                    disable_health_check: true,
+                    ..config.health_check.to_protocol_config()
+                })
+                .build(),
+        );
+        context.worker_registry.register_or_replace(worker);
+        context.policy_registry.on_worker_added(SIM_MODEL, None);
+    }
+
+    let registered = context.worker_registry.get_by_model(SIM_MODEL);
+    if let Some(policy) = context.policy_registry.get_policy(SIM_MODEL) {
+        if policy.name() == "cache_aware" {
+            context
+                .policy_registry
+                .init_cache_aware_policy(SIM_MODEL, &registered);
+        }
+    }
+    context.policy_registry.init_pd_cache_aware_policies(
+        &context.worker_registry.get_prefill_workers(),
+        &context.worker_registry.get_decode_workers(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        config::{self, RetryConfig, StreamRecoveryConfig},
+        test_utils::{Fault, SimClock},
+        worker::circuit_breaker::CircuitState,
+    };
+
+    fn retries(max_retries: u32) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 1,
+            backoff_multiplier: 1.0,
+            jitter_factor: 0.0,
+        }
+    }
+
+    fn chat(stream: bool) -> Value {
+        json!({
+            "model": SIM_MODEL,
+            "messages": [{"role": "user", "content": "hi"}],
+            "stream": stream,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retry_moves_to_next_worker() {
+        let (a, b) = (
+            SimWorker::start().await.unwrap(),
+            SimWorker::start().await.unwrap(),
+        );
+        a.script([Fault::Status(503)]);
+        let config = RouterConfig::builder()
+            .round_robin_policy()
+            .retry_config(retries(3))
+            .build_unchecked();
+        let gateway = SimGateway::regular(config, &[&a, &b]).await.unwrap();
+
+        let response = gateway.post_json("/v1/chat/completions", chat(false)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!((a.request_count(), b.request_count()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_recovers_on_clock() {
+        let worker = SimWorker::start().await.unwrap();
+        worker.script([Fault::Status(500), Fault::Status(500)]);
+        let config = RouterConfig::builder()
+            .round_robin_policy()
+            .disable_retries()
+            .circuit_breaker_config(config::CircuitBreakerConfig {
+                failure_threshold: 2,
+                success_threshold: 1,
+                timeout_duration_secs: 30,
+                window_duration_secs: 60,
+            })
+            .build_unchecked();
+        let gateway = SimGateway::regular(config, &[&worker]).await.unwrap();
+        let registered = gateway.worker(worker.url()).unwrap();
+
+        for _ in 0..2 {
+            let response = gateway.post_json("/v1/chat/completions", chat(false)).await;
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        assert_eq!(registered.circuit_breaker_state(), CircuitState::Open);
+        let rejected = gateway.post_json("/v1/chat/completions", chat(false)).await;
+        assert!(!rejected.status().is_success());
+        assert_eq!(worker.request_count(), 2);
+
+        SimClock::advance(Duration::from_secs(30)).await;
+        assert_eq!(registered.circuit_breaker_state(), CircuitState::HalfOpen);
+        let response = gateway.post_json("/v1/chat/completions", chat(false)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(registered.circuit_breaker_state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_prefill_decode_retries_pair_after_decode_failure() {
+        let (prefill, decode) = (
+            SimWorker::start().await.unwrap(),
+            SimWorker::start().await.unwrap(),
+        );
+        decode.script([Fault::Status(503)]);
+        let config = RouterConfig::builder()
+            .prefill_decode_mode(vec![], vec![])
+            .round_robin_policy()
+            .retry_config(retries(3))
+            .build_unchecked();
+        let gateway = SimGateway::prefill_decode(config, &[&prefill], &[&decode])
+            .await
+            .unwrap();
+
+        let response = gateway
+            .post_json("/generate", json!({"model": SIM_MODEL, "text": "hi"}))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!((prefill.request_count(), decode.request_count()), (2, 2));
+        assert!(prefill.requests()[0].body.get("bootstrap_room").is_some());
+
+        let findings = gateway.context().pd_pairs.evaluate();
+        let pair = &findings.pairs[0];
+        assert_eq!((pair.requests, pair.errors), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_cut_stream_resumes_on_another_worker() {
+        let (a, b) = (
+            SimWorker::start().await.unwrap(),
+            SimWorker::start().await.unwrap(),
+        );
+        a.script([Fault::CutStream { after: 2 }]);
+        let config = RouterConfig::builder()
+            .round_robin_policy()
+            .stream_recovery(StreamRecoveryConfig {
+                models: vec![SIM_MODEL.to_string()],
+                max_resumes: 1,
+            })
+            .build_unchecked();
+        let gateway = SimGateway::regular(config, &[&a, &b]).await.unwrap();
+
+        let response = gateway.post_json("/v1/chat/completions", chat(true)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = SimGateway::body_text(response).await;
+        assert!(body.contains("event: smg.stream_resumed"), "{body}");
+        assert!(body.ends_with("data: [DONE]\n\n"), "{body}");
+        assert_eq!((a.request_count(), b.request_count()), (1, 1));
+        assert_eq!(b.requests()[0].body["continue_final_message"], true);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_feature_opts_single_request_in() {
+        let cut_stream = |features: config::RequestFeaturesConfig| async move {
+            let (a, b) = (
+                SimWorker::start().await.unwrap(),
+                SimWorker::start().await.unwrap(),
+            );
+            a.script([Fault::CutStream { after: 2 }]);
+            let config = RouterConfig::builder()
+                .round_robin_policy()
+                .request_features(features)
+                .build_unchecked();
+            let gateway = SimGateway::regular(config, &[&a, &b]).await.unwrap();
+            let request = Request::post("/v1/chat/completions")
+                .header(CONTENT_TYPE, "application/json")
+                .header("x-smg-features", "recovery")
+                .body(Body::from(chat(true).to_string()))
+                .unwrap();
+            let response = gateway.send(request).await;
+            let echoed = response.headers().get("x-smg-features").cloned();
+            (echoed, SimGateway::body_text(response).await)
+        };
+
+        let (echoed, body) = cut_stream(config::RequestFeaturesConfig {
+            default: vec!["recovery".to_string()],
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(echoed.unwrap(), "recovery");
+        assert!(body.contains("event: smg.stream_resumed"), "{body}");
+
+        let (echoed, body) = cut_stream(config::RequestFeaturesConfig::default()).await;
+        assert!(echoed.is_none());
+        assert!(!body.contains("event: smg.stream_resumed"), "{body}");
+    }
+}
@@ -0,0 +1,32 @@
+//! Deterministic simulation harness for router integration tests.
+//!
+//! Enabled by the `test-utils` feature, so crates embedding the gateway can
+//! test their routing setups without model servers:
+//!
+//! - [`SimWorker`]: an in-process HTTP worker on a loopback port that answers
+//!   each generation request with the next [`Fault`] of its script.
+//! - [`SimGateway`]: the full axum app over a regular or prefill/decode HTTP
+//!   router, with the workers registered directly (no discovery and no
+//!   health checks), so the first request goes to the first worker under the
+//!   round-robin policy.
+//! - [`SimClock`]: moves the clock read by circuit breakers and tokio timers,
+//!   so open circuits time out without waiting.
+//!
+//! Outcomes depend only on the scripts and the clock. Tests must run on a
+//! current-thread runtime (plain `#[tokio::test]`) and should set
+//! `jitter_factor` to 0 to keep retry backoff exact.
+//!
+//! ```ignore
+//! let worker = SimWorker::start().await?;
+//! worker.script([Fault::Status(503)]);
+//! let gateway = SimGateway::regular(config, &[&worker]).await?;
+//! let response = gateway.post_json("/generate", json!({"text": "hi"})).await;
+//! ```
+
+mod clock;
+mod gateway;
+mod worker;
+
+pub use clock::SimClock;
+pub use gateway::{SimGateway, SIM_MODEL};
+pub use worker::{Fault, SimRequest, SimWorker, SIM_STREAM_TOKENS};
@@ -0,0 +1,341 @@
+//! Scripted in-process worker for simulation tests.
+
+use std::{collections::VecDeque, io, net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Json, State},
+    http::{header::CONTENT_TYPE, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use futures_util::stream;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle};
+
+/// Content events in a streamed answer, before the finish event and `[DONE]`.
+pub const SIM_STREAM_TOKENS: usize = 4;
+
+/// What a [`SimWorker`] does with one generation request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// Answer normally.
+    Pass,
+    /// Answer with this HTTP status and an error body.
+    Status(u16),
+    /// Wait this long on tokio's clock, then answer normally.
+    Delay(Duration),
+    /// Send `after` content events of a streamed answer, then drop the
+    /// connection without `[DONE]`. A non-streaming request, or `after: 0`,
+    /// loses the connection before the first byte of the body.
+    CutStream { after: usize },
+}
+
+/// A generation request a [`SimWorker`] received.
+#[derive(Debug, Clone)]
+pub struct SimRequest {
+    pub path: String,
+    pub body: Value,
+}
+
+#[derive(Default)]
+struct WorkerState {
+    script: VecDeque<Fault>,
+    received: Vec<SimRequest>,
+}
+
+/// An HTTP worker on a loopback port that answers `/generate`,
+/// `/v1/chat/completions` and `/v1/completions` from a script.
+///
+/// Each generation request takes the next [`Fault`] of the script and gets
+/// [`Fault::Pass`] once the script runs out. Answers carry fixed text and
+/// token counts, so they are the same in every run. Health and info
+/// endpoints always succeed.
+pub struct SimWorker {
+    url: String,
+    state: Arc<Mutex<WorkerState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SimWorker {
+    /// Bind a free loopback port and start serving.
+    pub async fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(WorkerState::default()));
+        let app = Router::new()
+            .route("/health", get(ok))
+            .route("/health_generate", get(ok))
+            .route("/get_server_info", get(server_info))
+            .route("/server_info", get(server_info))
+            .route("/get_model_info", get(model_info))
+            .route("/model_info", get(model_info))
+            .route("/v1/models", get(models))
+            .route("/generate", post(generate))
+            .route("/v1/chat/completions", post(generate))
+            .route("/v1/completions", post(generate))
+            .with_state(state.clone());
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "test worker task; stopped by SimWorker::stop or aborted on drop"
+        )]
+        let handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            url,
+            state,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Append faults to the script.
+    pub fn script(&self, faults: impl IntoIterator<Item = Fault>) {
+        self.state.lock().script.extend(faults);
+    }
+
+    /// Generation requests received so far, in arrival order.
+    pub fn requests(&self) -> Vec<SimRequest> {
+        self.state.lock().received.clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.state.lock().received.len()
+    }
+
+    /// Stop serving and wait for the server task to exit.
+    pub async fn stop(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.await;
+        }
+    }
+}
+
+impl Drop for SimWorker {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl std::fmt::Debug for SimWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimWorker")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+async fn ok() -> StatusCode {
+    StatusCode::OK
+}
+
+async fn server_info() -> Json<Value> {
+    Json(json!({"model_path": super::SIM_MODEL, "served_model_name": super::SIM_MODEL}))
+}
+
+async fn model_info() -> Json<Value> {
+    Json(json!({"model_path": super::SIM_MODEL, "is_generation": true}))
+}
+
+async fn models() -> Json<Value> {
+    Json(json!({
+        "object": "list",
+        "data": [{"id": super::SIM_MODEL, "object": "model", "owned_by": "sim"}],
+    }))
+}
+
+async fn generate(
+    State(state): State<Arc<Mutex<WorkerState>>>,
+    uri: Uri,
+    Json(body): Json<Value>,
+) -> Response {
+    let path = uri.path().to_string();
+    let fault = {
+        let mut state = state.lock();
+        state.received.push(SimRequest {
+            path: path.clone(),
+            body: body.clone(),
+        });
+        state.script.pop_front().unwrap_or(Fault::Pass)
+    };
+    let stream = body.get("stream").and_then(Value::as_bool).unwrap_or(false);
+
+    match fault {
+        Fault::Pass => answer(&path, stream, None),
+        Fault::Status(code) => {
+            let status = StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            (
+                status,
+                Json(json!({"error": {"message": "scripted fault", "code": code}})),
+            )
+                .into_response()
+        }
+        Fault::Delay(delay) => {
+            tokio::time::sleep(delay).await;
+            answer(&path, stream, None)
+        }
+        Fault::CutStream { after } => answer(&path, stream, Some(if stream { after } else { 0 })),
+    }
+}
+
+/// A complete answer for `path`, or one that breaks off after `cut_after`
+/// content events.
+fn answer(path: &str, stream: bool, cut_after: Option<usize>) -> Response {
+    if !stream && cut_after.is_none() {
+        return Json(completion(path)).into_response();
+    }
+
+    let mut events: Vec<String> = (0..SIM_STREAM_TOKENS)
+        .map(|i| stream_event(path, &format!("t{i} "), None))
+        .collect();
+    events.push(stream_event(path, "", Some("stop")));
+    events.push("[DONE]".to_string());
+    let mut chunks: Vec<io::Result<Bytes>> = events
+        .into_iter()
+        .map(|event| Ok(Bytes::from(format!("data: {event}\n\n"))))
+        .collect();
+    if let Some(after) = cut_after {
+        chunks.truncate(after.min(SIM_STREAM_TOKENS));
+        chunks.push(Err(io::Error::new(
+            io::ErrorKind::ConnectionReset,
+            "scripted stream cut",
+        )));
+    }
+
+    let content_type = if stream {
+        "text/event-stream"
+    } else {
+        "application/json"
+    };
+    Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from_stream(stream::iter(chunks)))
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+fn completion(path: &str) -> Value {
+    let text: String = (0..SIM_STREAM_TOKENS).map(|i| format!("t{i} ")).collect();
+    let usage = json!({
+        "prompt_tokens": 8,
+        "completion_tokens": SIM_STREAM_TOKENS,
+        "total_tokens": 8 + SIM_STREAM_TOKENS,
+    });
+    match path {
+        "/v1/chat/completions" => json!({
+            "id": "chatcmpl-sim",
+            "object": "chat.completion",
+            "created": 0,
+            "model": super::SIM_MODEL,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": text},
+                "finish_reason": "stop",
+            }],
+            "usage": usage,
+        }),
+        "/v1/completions" => json!({
+            "id": "cmpl-sim",
+            "object": "text_completion",
+            "created": 0,
+            "model": super::SIM_MODEL,
+            "choices": [{"index": 0, "text": text, "finish_reason": "stop"}],
+            "usage": usage,
+        }),
+        _ => json!({
+            "text": text,
+            "meta_info": {
+                "prompt_tokens": 8,
+                "completion_tokens": SIM_STREAM_TOKENS,
+                "finish_reason": {"type": "stop"},
+            },
+        }),
+    }
+}
+
+fn stream_event(path: &str, content: &str, finish_reason: Option<&str>) -> String {
+    let event = match path {
+        "/v1/chat/completions" => json!({
+            "id": "chatcmpl-sim",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": super::SIM_MODEL,
+            "choices": [{
+                "index": 0,
+                "delta": {"content": content},
+                "finish_reason": finish_reason,
+            }],
+        }),
+        "/v1/completions" => json!({
+            "id": "cmpl-sim",
+            "object": "text_completion",
+            "created": 0,
+            "model": super::SIM_MODEL,
+            "choices": [{"index": 0, "text": content, "finish_reason": finish_reason}],
+        }),
+        _ => json!({
+            "text": content,
+            "meta_info": {
+                "prompt_tokens": 8,
+                "completion_tokens": SIM_STREAM_TOKENS,
+                "finish_reason": finish_reason.map(|reason| json!({"type": reason})),
+            },
+        }),
+    };
+    event.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn post(worker: &SimWorker, body: Value) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/v1/chat/completions", worker.url()))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_script_is_consumed_in_order() {
+        let worker = SimWorker::start().await.unwrap();
+        worker.script([Fault::Status(503), Fault::CutStream { after: 2 }]);
+        let body = json!({"model": "m", "messages": [], "stream": true});
+
+        assert_eq!(post(&worker, body.clone()).await.status(), 503);
+
+        let cut = post(&worker, body.clone()).await;
+        assert_eq!(cut.status(), 200);
+        let err = cut.bytes().await.unwrap_err();
+        assert!(err.is_body() || err.is_decode(), "{err}");
+
+        let full = post(&worker, body).await.text().await.unwrap();
+        assert_eq!(full.matches("data: ").count(), SIM_STREAM_TOKENS + 2);
+        assert!(full.ends_with("data: [DONE]\n\n"));
+
+        assert_eq!(worker.request_count(), 3);
+        assert_eq!(worker.requests()[0].path, "/v1/chat/completions");
+        worker.stop().await;
+    }
+}
@@ -4,6 +4,8 @@
 //! - POST /wasm - Add modules
 //! - DELETE /wasm/:uuid - Remove a module
 //! - GET /wasm - List all modules with metrics
+//! - GET /wasm/stats - Runtime statistics of every module
+//! - GET /wasm/:uuid/stats - Runtime statistics and last failure of a module
 
 use std::{sync::Arc, time::Duration};
 
@@ -202,6 +204,37 @@ pub async fn remove_wasm_module(
     }
 }
 
+pub async fn list_wasm_module_stats(State(state): State<Arc<AppState>>) -> Response {
+    let Some(wasm_manager) = state.context.wasm_manager.as_ref() else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    match wasm_manager.get_all_module_stats() {
+        Ok(modules) => (StatusCode::OK, Json(modules)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+pub async fn get_wasm_module_stats(
+    State(state): State<Arc<AppState>>,
+    Path(module_uuid_str): Path<String>,
+) -> Response {
+    let Ok(module_uuid) = Uuid::parse_str(&module_uuid_str) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let Some(wasm_manager) = state.context.wasm_manager.as_ref() else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    match wasm_manager.get_module_stats(module_uuid) {
+        Ok(Some(stats)) => (StatusCode::OK, Json(stats)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("Module {module_uuid} not found"),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
 pub async fn list_wasm_modules(State(state): State<Arc<AppState>>) -> Response {
     let Some(wasm_manager) = state.context.wasm_manager.as_ref() else {
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
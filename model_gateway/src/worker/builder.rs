@@ -208,6 +208,12 @@ impl BasicWorkerBuilder {
         self
     }
 
+    /// Register as a standby, kept out of rotation until promoted
+    pub fn standby(mut self, standby: bool) -> Self {
+        self.spec.standby = standby;
+        self
+    }
+
     /// Set models this worker can serve
     pub fn models(mut self, models: impl Into<WorkerModels>) -> Self {
         self.spec.models = models.into();
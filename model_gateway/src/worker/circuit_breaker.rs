@@ -86,12 +86,17 @@ impl CircuitState {
 
 /// Get current time as milliseconds since an arbitrary epoch.
 /// Uses Instant for monotonic time, converting to ms for atomic storage.
+///
+/// Reads tokio's clock, which is the system clock unless a test moves it
+/// (see `test_utils::SimClock`).
 #[inline]
 fn now_ms() -> u64 {
     // Use a static reference point for consistent timing
     static START: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
-    let start = START.get_or_init(Instant::now);
-    start.elapsed().as_millis() as u64
+    let start = tokio::time::Instant::from_std(*START.get_or_init(Instant::now));
+    tokio::time::Instant::now()
+        .saturating_duration_since(start)
+        .as_millis() as u64
 }
 
 /// Circuit breaker implementation using lock-free atomics for hot paths.
@@ -0,0 +1,425 @@
+//! Targeted payload tracing for a single worker.
+//!
+//! `POST /admin/workers/{worker_id}/debug?duration=5m` opens a trace session
+//! for one worker. While it is active, the HTTP router records the exact JSON
+//! body it sends to that worker and the raw body it gets back (streamed
+//! responses included) into a bounded ring buffer, which the admin API
+//! returns on `GET`. This isolates backend-specific serialization bugs without
+//! turning on verbose logging for every worker.
+//!
+//! Payloads pass through [`redaction`] before they are buffered: credential
+//! keys in the request JSON are replaced and bearer tokens, keys and PII
+//! patterns are masked in response text. The buffer is kept after the
+//! session expires so it can still be read, until it is deleted or a new
+//! session replaces it.
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::observability::redaction;
+
+/// Session length when `duration` is omitted.
+pub const DEFAULT_TRACE_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Longest session that can be opened.
+pub const MAX_TRACE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Exchanges kept per worker when `capacity` is omitted.
+pub const DEFAULT_TRACE_CAPACITY: usize = 100;
+
+/// Largest ring buffer that can be requested.
+pub const MAX_TRACE_CAPACITY: usize = 1000;
+
+/// Bodies are truncated to this many bytes.
+const MAX_PAYLOAD_BYTES: usize = 256 * 1024;
+
+/// Parse `300`, `90s`, `5m` or `1h`.
+pub fn parse_trace_duration(raw: &str) -> Result<Duration, String> {
+    let raw = raw.trim();
+    let (digits, unit) = match raw.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => raw.split_at(pos),
+        None => (raw, "s"),
+    };
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid duration '{raw}'"))?;
+    let secs = match unit {
+        "s" => value,
+        "m" => value.saturating_mul(60),
+        "h" => value.saturating_mul(3600),
+        _ => return Err(format!("invalid duration unit in '{raw}'; use s, m or h")),
+    };
+    let duration = Duration::from_secs(secs);
+    if duration.is_zero() || duration > MAX_TRACE_DURATION {
+        return Err(format!(
+            "duration must be between 1s and {}s",
+            MAX_TRACE_DURATION.as_secs()
+        ));
+    }
+    Ok(duration)
+}
+
+/// One request/response exchange with the traced worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadExchange {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub route: String,
+    /// Request body as serialized to the worker, with credentials redacted.
+    pub request: String,
+    pub request_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub streamed: bool,
+    /// Raw response body (the SSE byte stream for streamed responses).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    pub response_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub latency_ms: u64,
+}
+
+/// State of a worker's trace session, as reported by the admin API.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceSessionInfo {
+    pub worker_id: String,
+    pub worker_url: String,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub active: bool,
+    pub capacity: usize,
+    pub captured: u64,
+}
+
+struct TraceSession {
+    worker_id: String,
+    worker_url: String,
+    started_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    deadline: Instant,
+    capacity: usize,
+    next_seq: AtomicU64,
+    exchanges: Mutex<VecDeque<PayloadExchange>>,
+}
+
+impl TraceSession {
+    fn is_active(&self) -> bool {
+        Instant::now() < self.deadline
+    }
+
+    fn info(&self) -> TraceSessionInfo {
+        TraceSessionInfo {
+            worker_id: self.worker_id.clone(),
+            worker_url: self.worker_url.clone(),
+            started_at: self.started_at,
+            expires_at: self.expires_at,
+            active: self.is_active(),
+            capacity: self.capacity,
+            captured: self.next_seq.load(Ordering::Relaxed),
+        }
+    }
+
+    fn push(&self, exchange: PayloadExchange) {
+        let mut exchanges = self.exchanges.lock();
+        if exchanges.len() >= self.capacity {
+            exchanges.pop_front();
+        }
+        exchanges.push_back(exchange);
+    }
+}
+
+/// Trace sessions keyed by worker URL.
+#[derive(Default)]
+pub struct WorkerDebugTracer {
+    sessions: DashMap<String, Arc<TraceSession>>,
+}
+
+impl std::fmt::Debug for WorkerDebugTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerDebugTracer")
+            .field("sessions", &self.sessions.len())
+            .finish()
+    }
+}
+
+impl WorkerDebugTracer {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Open (or replace) the trace session for a worker, discarding any
+    /// previously buffered exchanges.
+    pub fn start(
+        &self,
+        worker_id: &str,
+        worker_url: &str,
+        duration: Duration,
+        capacity: usize,
+    ) -> TraceSessionInfo {
+        let started_at = Utc::now();
+        let session = Arc::new(TraceSession {
+            worker_id: worker_id.to_string(),
+            worker_url: worker_url.to_string(),
+            started_at,
+            expires_at: started_at + chrono::Duration::milliseconds(duration.as_millis() as i64),
+            deadline: Instant::now() + duration,
+            capacity: capacity.clamp(1, MAX_TRACE_CAPACITY),
+            next_seq: AtomicU64::new(0),
+            exchanges: Mutex::new(VecDeque::new()),
+        });
+        let info = session.info();
+        self.sessions.insert(worker_url.to_string(), session);
+        info
+    }
+
+    /// Drop a worker's session and its buffer. Returns whether one existed.
+    pub fn stop(&self, worker_url: &str) -> bool {
+        self.sessions.remove(worker_url).is_some()
+    }
+
+    /// Session state and buffered exchanges, oldest first.
+    pub fn exchanges(&self, worker_url: &str) -> Option<(TraceSessionInfo, Vec<PayloadExchange>)> {
+        let session = self.sessions.get(worker_url)?;
+        let exchanges = session.exchanges.lock().iter().cloned().collect();
+        Some((session.info(), exchanges))
+    }
+
+    /// Begin recording an exchange if the worker has an active session.
+    pub fn begin(&self, worker_url: &str, route: &str, request: &Value) -> Option<TraceRecorder> {
+        if self.sessions.is_empty() {
+            return None;
+        }
+        let session = self.sessions.get(worker_url)?.clone();
+        if !session.is_active() {
+            return None;
+        }
+        let mut redacted = request.clone();
+        redaction::redact_json(&mut redacted);
+        let (request, request_truncated) = truncate(redacted.to_string());
+        Some(TraceRecorder {
+            session,
+            route: route.to_string(),
+            timestamp: Utc::now(),
+            started: Instant::now(),
+            request,
+            request_truncated,
+        })
+    }
+}
+
+/// An exchange in flight; finished with the worker's response.
+pub struct TraceRecorder {
+    session: Arc<TraceSession>,
+    route: String,
+    timestamp: DateTime<Utc>,
+    started: Instant,
+    request: String,
+    request_truncated: bool,
+}
+
+impl TraceRecorder {
+    /// Record a failure before any response was received.
+    pub fn fail(self, error: impl std::fmt::Display) {
+        self.finish(None, false, None, error.to_string().into());
+    }
+
+    /// Record a fully buffered response.
+    pub fn respond(self, status: u16, body: &[u8]) {
+        self.finish(Some(status), false, Some(body), None);
+    }
+
+    /// Tee a response stream into the buffer; the exchange is recorded when
+    /// the stream ends or is dropped.
+    pub fn stream<S, E>(self, status: u16, stream: S) -> impl Stream<Item = Result<Bytes, E>>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        E: std::fmt::Display,
+    {
+        let mut capture = StreamCapture {
+            recorder: Some(self),
+            status,
+            body: Vec::new(),
+            truncated: false,
+            error: None,
+        };
+        stream.inspect(move |chunk| match chunk {
+            Ok(bytes) => {
+                let room = MAX_PAYLOAD_BYTES.saturating_sub(capture.body.len());
+                capture.truncated |= bytes.len() > room;
+                capture
+                    .body
+                    .extend_from_slice(&bytes[..bytes.len().min(room)]);
+            }
+            Err(e) => capture.error = Some(e.to_string()),
+        })
+    }
+
+    fn finish(
+        self,
+        status: Option<u16>,
+        streamed: bool,
+        body: Option<&[u8]>,
+        error: Option<String>,
+    ) {
+        let (response, response_truncated) = match body {
+            Some(body) => {
+                let (text, truncated) = truncate(String::from_utf8_lossy(body).into_owned());
+                (Some(redaction::redact_text(&text).into_owned()), truncated)
+            }
+            None => (None, false),
+        };
+        let seq = self.session.next_seq.fetch_add(1, Ordering::Relaxed);
+        self.session.push(PayloadExchange {
+            seq,
+            timestamp: self.timestamp,
+            route: self.route,
+            request: self.request,
+            request_truncated: self.request_truncated,
+            status,
+            streamed,
+            response,
+            response_truncated,
+            error,
+            latency_ms: self.started.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+struct StreamCapture {
+    recorder: Option<TraceRecorder>,
+    status: u16,
+    body: Vec<u8>,
+    truncated: bool,
+    error: Option<String>,
+}
+
+impl Drop for StreamCapture {
+    fn drop(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            let body = std::mem::take(&mut self.body);
+            let error = self.error.take();
+            recorder.finish(Some(self.status), true, Some(&body), error);
+        }
+    }
+}
+
+/// Truncate to [`MAX_PAYLOAD_BYTES`] on a char boundary.
+fn truncate(mut text: String) -> (String, bool) {
+    if text.len() <= MAX_PAYLOAD_BYTES {
+        return (text, false);
+    }
+    let mut end = MAX_PAYLOAD_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    (text, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+
+    use super::*;
+
+    const URL: &str = "http://w1:8000";
+
+    #[test]
+    fn test_parse_trace_duration() {
+        assert_eq!(parse_trace_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_trace_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_trace_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_trace_duration("2h").is_err());
+        assert!(parse_trace_duration("0s").is_err());
+        assert!(parse_trace_duration("5d").is_err());
+        assert!(parse_trace_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_records_only_traced_worker_with_redaction() {
+        let tracer = WorkerDebugTracer::new();
+        let request = serde_json::json!({"model": "m", "api_key": "hunter2"});
+        assert!(tracer
+            .begin(URL, "/v1/chat/completions", &request)
+            .is_none());
+
+        tracer.start("id-1", URL, Duration::from_secs(60), 10);
+        assert!(tracer
+            .begin("http://w2:8000", "/v1/chat/completions", &request)
+            .is_none());
+        tracer
+            .begin(URL, "/v1/chat/completions", &request)
+            .unwrap()
+            .respond(200, br#"{"token":"Bearer abcdef123456"}"#);
+
+        let (info, exchanges) = tracer.exchanges(URL).unwrap();
+        assert!(info.active);
+        assert_eq!(info.captured, 1);
+        assert_eq!(exchanges.len(), 1);
+        assert!(!exchanges[0].request.contains("hunter2"));
+        assert!(exchanges[0].request.contains(r#""model":"m""#));
+        assert_eq!(exchanges[0].status, Some(200));
+        assert!(!exchanges[0]
+            .response
+            .as_deref()
+            .unwrap()
+            .contains("abcdef123456"));
+
+        assert!(tracer.stop(URL));
+        assert!(tracer.exchanges(URL).is_none());
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_latest() {
+        let tracer = WorkerDebugTracer::new();
+        tracer.start("id-1", URL, Duration::from_secs(60), 2);
+        for i in 0..3 {
+            tracer
+                .begin(URL, "/generate", &serde_json::json!({ "i": i }))
+                .unwrap()
+                .fail("connection refused");
+        }
+        let (info, exchanges) = tracer.exchanges(URL).unwrap();
+        assert_eq!(info.captured, 3);
+        let seqs: Vec<u64> = exchanges.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
+        assert_eq!(exchanges[1].error.as_deref(), Some("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_is_recorded_when_drained() {
+        let tracer = WorkerDebugTracer::new();
+        tracer.start("id-1", URL, Duration::from_secs(60), 10);
+        let recorder = tracer
+            .begin(URL, "/v1/completions", &serde_json::json!({}))
+            .unwrap();
+        let chunks = vec![
+            Ok::<_, String>(Bytes::from_static(b"data: {\"a\":1}\n\n")),
+            Ok(Bytes::from_static(b"data: [DONE]\n\n")),
+        ];
+        let forwarded: Vec<_> = recorder.stream(200, stream::iter(chunks)).collect().await;
+        assert_eq!(forwarded.len(), 2);
+
+        let (_, exchanges) = tracer.exchanges(URL).unwrap();
+        assert!(exchanges[0].streamed);
+        assert_eq!(
+            exchanges[0].response.as_deref(),
+            Some("data: {\"a\":1}\n\ndata: [DONE]\n\n")
+        );
+    }
+}
@@ -43,4 +43,11 @@ pub enum WorkerEvent {
         old_status: WorkerStatus,
         new_status: WorkerStatus,
     },
+
+    /// A standby worker was promoted into or demoted out of rotation.
+    StandbyChanged {
+        worker_id: WorkerId,
+        worker: Arc<dyn Worker>,
+        promoted: bool,
+    },
 }
@@ -0,0 +1,277 @@
+//! Model catalog import from federated smg gateways.
+//!
+//! A worker with `runtime_type: smg` fronts a whole remote cluster, so the
+//! models it serves are whatever that cluster's `/v1/models` lists.
+//! [`FederationCatalog`] fetches the list when such a worker is registered
+//! and then every `federation.catalog_refresh_interval_secs`, and rebuilds
+//! the worker with one model card per remote model, so routing and
+//! `/v1/models` treat the remote models like local ones. A
+//! `federation_prefix` worker label additionally aliases each model as
+//! `<prefix>/<model>`, letting clients pin a request to one remote cluster.
+//!
+//! A failed or empty fetch leaves the current cards in place. Health is
+//! tracked by the ordinary health checker against the remote's `/health`.
+
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use openai_protocol::models::ListModelsResponse;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use super::{
+    event::WorkerEvent, registry::WorkerId, BasicWorkerBuilder, ModelCard, RuntimeType, Worker,
+    WorkerOrigin, WorkerRegistry,
+};
+use crate::{config::FederationConfig, observability::metrics::Metrics};
+
+/// Worker label whose value prefixes an alias for every imported model.
+pub const FEDERATION_PREFIX_LABEL: &str = "federation_prefix";
+
+const CATALOG_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn is_federated(worker: &Arc<dyn Worker>) -> bool {
+    worker.metadata().spec.runtime_type == RuntimeType::Smg
+}
+
+/// Model cards for a remote `/v1/models` response.
+fn catalog_cards(body: &serde_json::Value, prefix: Option<&str>) -> Vec<ModelCard> {
+    let cards = ListModelsResponse::parse_upstream(body, None);
+    match prefix.filter(|p| !p.is_empty()) {
+        Some(prefix) => cards
+            .into_iter()
+            .map(|card| {
+                let alias = format!("{prefix}/{}", card.id);
+                card.with_alias(alias)
+            })
+            .collect(),
+        None => cards,
+    }
+}
+
+fn same_catalog(current: &[ModelCard], imported: &[ModelCard]) -> bool {
+    current.len() == imported.len()
+        && current
+            .iter()
+            .zip(imported)
+            .all(|(a, b)| a.id == b.id && a.aliases == b.aliases)
+}
+
+/// Keeps the model cards of federated workers in sync with their remotes.
+pub struct FederationCatalog {
+    registry: Arc<WorkerRegistry>,
+    client: reqwest::Client,
+    refresh_interval: Duration,
+}
+
+impl std::fmt::Debug for FederationCatalog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FederationCatalog")
+            .field("refresh_interval", &self.refresh_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FederationCatalog {
+    pub fn new(
+        config: &FederationConfig,
+        registry: Arc<WorkerRegistry>,
+        client: reqwest::Client,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            registry,
+            client,
+            refresh_interval: Duration::from_secs(config.catalog_refresh_interval_secs.max(1)),
+        })
+    }
+
+    /// Spawn the import loop: newly registered federated workers are
+    /// imported right away, all of them on every refresh tick. It holds
+    /// only a `Weak<Self>` and exits when the catalog is dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        let mut events = self.registry.subscribe_events();
+        let refresh_interval = self.refresh_interval;
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the catalog is dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(refresh_interval);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                let registered = tokio::select! {
+                    _ = tick.tick() => None,
+                    event = events.recv() => match event {
+                        Ok(WorkerEvent::Registered { worker_id, worker }) if is_federated(&worker) => {
+                            Some((worker_id, worker))
+                        }
+                        Ok(_) => continue,
+                        // Missed registrations are picked up by a full refresh.
+                        Err(RecvError::Lagged(_)) => None,
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                let Some(catalog) = weak.upgrade() else {
+                    break;
+                };
+                match registered {
+                    Some((worker_id, worker)) if catalog.owns(&worker_id) => {
+                        catalog.import(&worker_id, &worker).await;
+                    }
+                    Some(_) => {}
+                    None => catalog.refresh_all().await,
+                }
+            }
+            debug!("FederationCatalog loop exited");
+        });
+    }
+
+    /// Import the catalog of every federated worker this node owns.
+    pub async fn refresh_all(&self) {
+        let workers: Vec<_> = self
+            .registry
+            .get_all_with_ids()
+            .into_iter()
+            .filter(|(worker_id, worker)| is_federated(worker) && self.owns(worker_id))
+            .collect();
+        for (worker_id, worker) in workers {
+            self.import(&worker_id, &worker).await;
+        }
+    }
+
+    /// Workers imported from a mesh peer are kept in sync by that peer.
+    fn owns(&self, worker_id: &WorkerId) -> bool {
+        self.registry.origin_of(worker_id) == Some(WorkerOrigin::Local)
+    }
+
+    async fn import(&self, worker_id: &WorkerId, worker: &Arc<dyn Worker>) {
+        let result = match self.fetch(worker).await {
+            Ok(cards) if cards.is_empty() => "empty",
+            Ok(cards) if same_catalog(&worker.models(), &cards) => "unchanged",
+            Ok(cards) => {
+                info!(
+                    worker_url = %worker.url(),
+                    models = cards.len(),
+                    "Imported federated model catalog"
+                );
+                self.apply(worker_id, worker, cards);
+                "updated"
+            }
+            Err(e) => {
+                warn!(worker_url = %worker.url(), "Failed to import federated model catalog: {e}");
+                "error"
+            }
+        };
+        Metrics::record_federation_catalog_import(result);
+    }
+
+    async fn fetch(&self, worker: &Arc<dyn Worker>) -> Result<Vec<ModelCard>, String> {
+        let mut request = self
+            .client
+            .get(format!("{}/v1/models", worker.base_url()))
+            .timeout(CATALOG_FETCH_TIMEOUT);
+        if let Some(key) = worker.api_key() {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        let labels = &worker.metadata().spec.labels;
+        Ok(catalog_cards(
+            &body,
+            labels.get(FEDERATION_PREFIX_LABEL).map(String::as_str),
+        ))
+    }
+
+    /// Replace the worker with one carrying the imported cards, keeping
+    /// everything else, including its current status.
+    fn apply(&self, worker_id: &WorkerId, worker: &Arc<dyn Worker>, cards: Vec<ModelCard>) {
+        let spec = &worker.metadata().spec;
+        let mut builder = BasicWorkerBuilder::new(worker.base_url())
+            .worker_type(*worker.worker_type())
+            .connection_mode(*worker.connection_mode())
+            .runtime_type(spec.runtime_type)
+            .labels(spec.labels.clone())
+            .health_config(worker.metadata().health_config.clone())
+            .health_endpoint(&worker.metadata().health_endpoint)
+            .models(cards)
+            .http_client(worker.http_client().clone())
+            .resilience(worker.resilience().clone())
+            .priority(worker.priority())
+            .cost(worker.cost())
+            .status(worker.status());
+        if let Some(key) = worker.api_key() {
+            builder = builder.api_key(key.clone());
+        }
+        if !self.registry.replace(worker_id, Arc::new(builder.build())) {
+            debug!(worker_url = %worker.url(), "Federated worker changed during catalog import");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_catalog_cards_add_prefix_alias() {
+        let body = json!({
+            "object": "list",
+            "data": [
+                {"id": "llama-70b", "object": "model", "created": 0, "owned_by": "smg"},
+                {"id": "qwen-32b", "object": "model", "created": 0, "owned_by": "smg"}
+            ]
+        });
+        let cards = catalog_cards(&body, Some("eu-west"));
+        assert_eq!(cards.len(), 2);
+        assert!(cards[0].matches("llama-70b"));
+        assert!(cards[0].matches("eu-west/llama-70b"));
+        assert!(cards[1].matches("eu-west/qwen-32b"));
+
+        let cards = catalog_cards(&body, None);
+        assert!(cards[0].aliases.is_empty());
+        assert!(catalog_cards(&json!({"error": "nope"}), None).is_empty());
+    }
+
+    #[test]
+    fn test_same_catalog_compares_ids_and_aliases() {
+        let current = vec![ModelCard::new("m").with_alias("eu/m")];
+        assert!(same_catalog(
+            &current,
+            &[ModelCard::new("m").with_alias("eu/m")]
+        ));
+        assert!(!same_catalog(&current, &[ModelCard::new("m")]));
+        assert!(!same_catalog(&current, &[]));
+    }
+
+    #[tokio::test]
+    async fn test_apply_replaces_models_and_keeps_status() {
+        let registry = Arc::new(WorkerRegistry::new());
+        let worker: Arc<dyn Worker> = Arc::new(
+            BasicWorkerBuilder::new("http://eu.example.com")
+                .runtime_type(RuntimeType::Smg)
+                .api_key("remote-key")
+                .status(openai_protocol::worker::WorkerStatus::Ready)
+                .build(),
+        );
+        let worker_id = registry.register(worker.clone()).unwrap();
+        let catalog = FederationCatalog::new(
+            &FederationConfig::default(),
+            registry.clone(),
+            reqwest::Client::new(),
+        );
+
+        catalog.apply(&worker_id, &worker, vec![ModelCard::new("llama-70b")]);
+        let workers = registry.get_by_model("llama-70b");
+        assert_eq!(workers.len(), 1);
+        assert!(workers[0].is_healthy());
+        assert_eq!(workers[0].api_key().map(String::as_str), Some("remote-key"));
+    }
+}
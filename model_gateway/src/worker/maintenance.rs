@@ -0,0 +1,737 @@
+//! Scheduled and ad-hoc worker maintenance windows.
+//!
+//! A window covers either one worker (by URL) or every worker that lists a
+//! model (by ID or alias). While a window is open, [`MaintenanceController`]
+//! moves the covered workers to `Draining`: in-flight requests finish, new
+//! requests are routed to the remaining workers for the model, and health
+//! probing pauses. When the window closes each worker is put back in the
+//! status it had before, and the health checker takes over again.
+//!
+//! Windows come from `maintenance.windows` (cron-scheduled) or from the
+//! `/admin/maintenance` API (one-off). The controller re-evaluates them on a
+//! fixed interval and immediately after every admin change.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use openai_protocol::{models::ModelAvailability, worker::WorkerStatus};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{registry::WorkerId, Worker, WorkerRegistry};
+use crate::config::MaintenanceConfig;
+
+/// Longest window accepted, in seconds (7 days).
+pub const MAX_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// How far ahead [`CronSchedule::next_start`] searches.
+const MAX_LOOKAHEAD_MINUTES: i64 = 366 * 24 * 60;
+
+/// Five-field cron expression: `minute hour day-of-month month day-of-week`.
+///
+/// Each field accepts `*`, values, ranges (`a-b`), steps (`*/n`, `a-b/n`)
+/// and comma-separated lists of those. Day-of-week is `0`-`7` with both `0`
+/// and `7` meaning Sunday. As in classic cron, when both day fields are
+/// restricted a day matches if either does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!("expected 5 fields, got {}", fields.len()));
+        };
+
+        let days_of_week = parse_field(day_of_week, 0, 7, "day-of-week")?;
+        // Fold 7 (Sunday) onto 0.
+        let days_of_week = ((days_of_week | (days_of_week >> 7)) & 0x7f) as u8;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")? as u32,
+            days_of_month: parse_field(day_of_month, 1, 31, "day-of-month")? as u32,
+            months: parse_field(month, 1, 12, "month")? as u16,
+            days_of_week,
+            any_day_of_month: day_of_month.starts_with('*'),
+            any_day_of_week: day_of_week.starts_with('*'),
+        })
+    }
+}
+
+/// Parse one cron field into a bitmask of allowed values.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or_else(|| format!("invalid {name} step '{step}'"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let parse_value = |value: &str| -> Result<u32, String> {
+            value
+                .parse()
+                .ok()
+                .filter(|value| (min..=max).contains(value))
+                .ok_or_else(|| format!("{name} value '{value}' is outside {min}-{max}"))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+                // `a/n` runs from `a` to the field maximum.
+                None if part.contains('/') => (parse_value(range)?, max),
+                None => {
+                    let value = parse_value(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("{name} range '{range}' is reversed"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronSchedule {
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        if self.months & (1 << t.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << t.day()) != 0;
+        let dow = self.days_of_week & (1 << t.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// Whether the schedule fires at the minute containing `t`.
+    pub fn matches(&self, t: &DateTime<Utc>) -> bool {
+        self.minutes & (1 << t.minute()) != 0
+            && self.hours & (1 << t.hour()) != 0
+            && self.day_matches(t)
+    }
+
+    /// Latest firing at or before `now` that is less than `window` ago, i.e.
+    /// the start of a window of that length that is still open at `now`.
+    pub fn latest_start(&self, now: DateTime<Utc>, window: Duration) -> Option<DateTime<Utc>> {
+        let window = chrono::Duration::from_std(window).ok()?;
+        let mut candidate = floor_minute(now)?;
+        while now - candidate < window {
+            if self.matches(&candidate) {
+                return Some(candidate);
+            }
+            candidate -= chrono::Duration::minutes(1);
+        }
+        None
+    }
+
+    /// First firing strictly after `after`, searching up to a year ahead.
+    pub fn next_start(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut candidate = floor_minute(after)? + chrono::Duration::minutes(1);
+        let limit = after + chrono::Duration::minutes(MAX_LOOKAHEAD_MINUTES);
+        while candidate <= limit {
+            if !self.day_matches(&candidate) {
+                let minutes_left = 24 * 60 - i64::from(candidate.hour() * 60 + candidate.minute());
+                candidate += chrono::Duration::minutes(minutes_left);
+            } else if self.hours & (1 << candidate.hour()) == 0 {
+                candidate += chrono::Duration::minutes(60 - i64::from(candidate.minute()));
+            } else if self.minutes & (1 << candidate.minute()) == 0 {
+                candidate += chrono::Duration::minutes(1);
+            } else {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+}
+
+fn floor_minute(t: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(t.timestamp().div_euclid(60) * 60, 0)
+}
+
+/// Workers a window applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTarget {
+    WorkerUrl(String),
+    /// Model ID or alias.
+    Model(String),
+}
+
+impl MaintenanceTarget {
+    fn from_parts(worker_url: Option<String>, model: Option<String>) -> Option<Self> {
+        match (worker_url, model) {
+            (Some(url), None) => Some(Self::WorkerUrl(url)),
+            (None, Some(model)) => Some(Self::Model(model)),
+            _ => None,
+        }
+    }
+
+    /// Wildcard workers list no models, so a model window never covers them.
+    fn covers(&self, worker: &Arc<dyn Worker>) -> bool {
+        match self {
+            Self::WorkerUrl(url) => worker.url().trim_end_matches('/') == url.trim_end_matches('/'),
+            Self::Model(model) => worker.models().iter().any(|card| card.matches(model)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceSource {
+    /// From `maintenance.windows`; cannot be cancelled through the API.
+    Scheduled,
+    /// Opened through `/admin/maintenance`.
+    Manual,
+}
+
+/// A window occurrence as reported by `GET /admin/maintenance`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MaintenanceWindow {
+    /// Config `name` for scheduled windows, `mw_<uuid>` for manual ones.
+    pub id: String,
+    pub source: MaintenanceSource,
+    #[serde(flatten)]
+    pub target: MaintenanceTarget,
+    /// Unix timestamp (seconds)
+    pub starts_at: i64,
+    /// Unix timestamp (seconds)
+    pub ends_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub active: bool,
+}
+
+/// Body of `POST /admin/maintenance`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceWindowRequest {
+    #[serde(default)]
+    pub worker_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Unix timestamp (seconds); defaults to now.
+    #[serde(default)]
+    pub starts_at: Option<i64>,
+    pub duration_secs: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum MaintenanceError {
+    #[error("exactly one of worker_url or model must be set")]
+    InvalidTarget,
+    #[error("duration_secs must be between 1 and {}", MAX_WINDOW_SECS)]
+    InvalidDuration,
+}
+
+struct ScheduledWindow {
+    name: String,
+    target: MaintenanceTarget,
+    schedule: CronSchedule,
+    duration: Duration,
+}
+
+/// A worker this controller moved to `Draining`.
+struct DrainedWorker {
+    previous_status: WorkerStatus,
+    until: i64,
+}
+
+/// Applies maintenance windows to the worker registry.
+pub struct MaintenanceController {
+    registry: Arc<WorkerRegistry>,
+    scheduled: Vec<ScheduledWindow>,
+    check_interval: Duration,
+    manual: Mutex<Vec<MaintenanceWindow>>,
+    drained: Mutex<HashMap<WorkerId, DrainedWorker>>,
+}
+
+impl std::fmt::Debug for MaintenanceController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaintenanceController")
+            .field("scheduled", &self.scheduled.len())
+            .field("drained", &self.drained.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MaintenanceController {
+    /// Build the controller. Windows that fail to parse (config validation
+    /// rejects them first) are logged and skipped.
+    pub fn new(config: &MaintenanceConfig, registry: Arc<WorkerRegistry>) -> Arc<Self> {
+        let scheduled = config
+            .windows
+            .iter()
+            .filter_map(|window| {
+                let target =
+                    MaintenanceTarget::from_parts(window.worker_url.clone(), window.model.clone());
+                let schedule = window.schedule.parse::<CronSchedule>();
+                match (target, schedule) {
+                    (Some(target), Ok(schedule)) => Some(ScheduledWindow {
+                        name: window.name.clone(),
+                        target,
+                        schedule,
+                        duration: Duration::from_secs(window.duration_secs.min(MAX_WINDOW_SECS)),
+                    }),
+                    _ => {
+                        warn!(window = %window.name, "Skipping invalid maintenance window");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Arc::new(Self {
+            registry,
+            scheduled,
+            check_interval: Duration::from_secs(config.check_interval_secs.max(1)),
+            manual: Mutex::new(Vec::new()),
+            drained: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Spawn the re-evaluation loop. It holds only a `Weak<Self>` and exits
+    /// when the controller is dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        let check_interval = self.check_interval;
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the controller is dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(check_interval);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(controller) = weak.upgrade() else {
+                    break;
+                };
+                controller.reconcile();
+            }
+        });
+    }
+
+    /// Open a one-off window and apply it immediately if it has started.
+    pub fn open_window(
+        &self,
+        request: MaintenanceWindowRequest,
+    ) -> Result<MaintenanceWindow, MaintenanceError> {
+        let window = self.open_window_at(request, Utc::now())?;
+        self.reconcile();
+        Ok(window)
+    }
+
+    fn open_window_at(
+        &self,
+        request: MaintenanceWindowRequest,
+        now: DateTime<Utc>,
+    ) -> Result<MaintenanceWindow, MaintenanceError> {
+        let target = MaintenanceTarget::from_parts(request.worker_url, request.model)
+            .ok_or(MaintenanceError::InvalidTarget)?;
+        if !(1..=MAX_WINDOW_SECS).contains(&request.duration_secs) {
+            return Err(MaintenanceError::InvalidDuration);
+        }
+        let starts_at = request.starts_at.unwrap_or(now.timestamp());
+        let window = MaintenanceWindow {
+            id: format!("mw_{}", uuid::Uuid::now_v7().simple()),
+            source: MaintenanceSource::Manual,
+            target,
+            starts_at,
+            ends_at: starts_at.saturating_add(request.duration_secs as i64),
+            reason: request.reason,
+            active: starts_at <= now.timestamp(),
+        };
+        self.manual.lock().push(window.clone());
+        info!(
+            window_id = %window.id,
+            target = ?window.target,
+            starts_at = window.starts_at,
+            ends_at = window.ends_at,
+            "Maintenance window scheduled"
+        );
+        Ok(window)
+    }
+
+    /// Cancel a manual window; covered workers are restored right away.
+    pub fn cancel_window(&self, id: &str) -> Option<MaintenanceWindow> {
+        let window = {
+            let mut manual = self.manual.lock();
+            let index = manual.iter().position(|window| window.id == id)?;
+            manual.remove(index)
+        };
+        info!(window_id = %id, "Maintenance window cancelled");
+        self.reconcile();
+        Some(window)
+    }
+
+    /// Current and upcoming windows: the open or next occurrence of every
+    /// scheduled window, plus all pending manual windows.
+    pub fn windows(&self) -> Vec<MaintenanceWindow> {
+        self.windows_at(Utc::now())
+    }
+
+    fn windows_at(&self, now: DateTime<Utc>) -> Vec<MaintenanceWindow> {
+        let ts = now.timestamp();
+        let mut windows: Vec<MaintenanceWindow> = self
+            .scheduled
+            .iter()
+            .filter_map(|window| {
+                let (start, active) = match window.schedule.latest_start(now, window.duration) {
+                    Some(start) => (start, true),
+                    None => (window.schedule.next_start(now)?, false),
+                };
+                let starts_at = start.timestamp();
+                Some(MaintenanceWindow {
+                    id: window.name.clone(),
+                    source: MaintenanceSource::Scheduled,
+                    target: window.target.clone(),
+                    starts_at,
+                    ends_at: starts_at + window.duration.as_secs() as i64,
+                    reason: None,
+                    active,
+                })
+            })
+            .collect();
+        windows.extend(
+            self.manual
+                .lock()
+                .iter()
+                .filter(|window| window.ends_at > ts)
+                .map(|window| MaintenanceWindow {
+                    active: window.starts_at <= ts,
+                    ..window.clone()
+                }),
+        );
+        windows
+    }
+
+    /// Drain workers covered by an open window and restore the ones whose
+    /// windows have closed.
+    pub fn reconcile(&self) {
+        self.reconcile_at(Utc::now());
+    }
+
+    fn reconcile_at(&self, now: DateTime<Utc>) {
+        let ts = now.timestamp();
+        self.manual.lock().retain(|window| window.ends_at > ts);
+        let open: Vec<MaintenanceWindow> = self
+            .windows_at(now)
+            .into_iter()
+            .filter(|window| window.active)
+            .collect();
+
+        let mut covered: HashMap<WorkerId, i64> = HashMap::new();
+        if !open.is_empty() {
+            for (worker_id, worker) in self.registry.get_all_with_ids() {
+                for window in open.iter().filter(|window| window.target.covers(&worker)) {
+                    let until = covered.entry(worker_id.clone()).or_insert(window.ends_at);
+                    *until = (*until).max(window.ends_at);
+                }
+            }
+        }
+
+        let mut drained = self.drained.lock();
+        for (worker_id, until) in &covered {
+            if let Some(entry) = drained.get_mut(worker_id) {
+                entry.until = *until;
+                continue;
+            }
+            let Some(worker) = self.registry.get(worker_id) else {
+                continue;
+            };
+            let previous_status = worker.status();
+            // Already leaving (removal drain) or dead: nothing to restore.
+            if matches!(
+                previous_status,
+                WorkerStatus::Draining | WorkerStatus::Failed
+            ) {
+                continue;
+            }
+            if self
+                .registry
+                .transition_status(worker_id, WorkerStatus::Draining)
+                .is_some()
+            {
+                info!(
+                    worker_url = %worker.url(),
+                    until,
+                    "Worker entered maintenance window, draining"
+                );
+                drained.insert(
+                    worker_id.clone(),
+                    DrainedWorker {
+                        previous_status,
+                        until: *until,
+                    },
+                );
+            }
+        }
+
+        drained.retain(|worker_id, entry| {
+            if covered.contains_key(worker_id) {
+                return true;
+            }
+            if let Some(worker) = self.registry.get(worker_id) {
+                if worker.status() == WorkerStatus::Draining {
+                    self.registry
+                        .transition_status(worker_id, entry.previous_status);
+                    info!(
+                        worker_url = %worker.url(),
+                        status = ?entry.previous_status,
+                        "Worker left maintenance window"
+                    );
+                }
+            }
+            false
+        });
+    }
+
+    /// `/v1/models` availability for models served by drained workers:
+    /// `maintenance` when every worker listing the model is in a window,
+    /// `degraded` when only some are.
+    pub fn model_availability(&self) -> HashMap<String, ModelAvailability> {
+        let drained = self.drained.lock();
+        if drained.is_empty() {
+            return HashMap::new();
+        }
+
+        // model -> (workers, drained workers, latest window end)
+        let mut counts: HashMap<String, (usize, usize, i64)> = HashMap::new();
+        for (worker_id, worker) in self.registry.get_all_with_ids() {
+            let entry = drained.get(&worker_id);
+            for model_id in WorkerRegistry::worker_model_ids(&worker) {
+                let count = counts.entry(model_id).or_insert((0, 0, 0));
+                count.0 += 1;
+                if let Some(entry) = entry {
+                    count.1 += 1;
+                    count.2 = count.2.max(entry.until);
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(_, (_, drained, _))| *drained > 0)
+            .map(|(model_id, (total, drained, until))| {
+                let status = if drained == total {
+                    "maintenance"
+                } else {
+                    "degraded"
+                };
+                (
+                    model_id,
+                    ModelAvailability {
+                        status: status.to_string(),
+                        until: Some(until),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openai_protocol::{model_card::ModelCard, worker::HealthCheckConfig};
+
+    use super::*;
+    use crate::{
+        config::MaintenanceWindowConfig,
+        worker::{BasicWorkerBuilder, WorkerType},
+    };
+
+    fn no_health_check() -> HealthCheckConfig {
+        HealthCheckConfig {
+            disable_health_check: true,
+            ..Default::default()
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn worker(url: &str, model: &str) -> Arc<dyn Worker> {
+        let worker: Arc<dyn Worker> = Arc::new(
+            BasicWorkerBuilder::new(url)
+                .worker_type(WorkerType::Regular)
+                .model(ModelCard::new(model).with_aliases(vec![format!("{model}-latest")]))
+                .health_config(no_health_check())
+                .build(),
+        );
+        worker.set_status(WorkerStatus::Ready);
+        worker
+    }
+
+    #[test]
+    fn test_cron_parsing() {
+        let schedule: CronSchedule = "*/15 2-4 * * 0,6".parse().unwrap();
+        assert!(schedule.matches(&at("2026-10-17T03:45:00Z"))); // Saturday
+        assert!(!schedule.matches(&at("2026-10-17T03:44:00Z")));
+        assert!(!schedule.matches(&at("2026-10-16T03:45:00Z"))); // Friday
+        assert!(!schedule.matches(&at("2026-10-17T05:00:00Z")));
+
+        let sunday: CronSchedule = "0 0 * * 7".parse().unwrap();
+        assert!(sunday.matches(&at("2026-10-18T00:00:30Z")));
+
+        // Both day fields restricted: either may match.
+        let either: CronSchedule = "0 0 1 * 1".parse().unwrap();
+        assert!(either.matches(&at("2026-10-01T00:00:00Z"))); // Thursday the 1st
+        assert!(either.matches(&at("2026-10-19T00:00:00Z"))); // Monday
+
+        for invalid in [
+            "* * * *",
+            "60 * * * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "x * * * *",
+        ] {
+            assert!(invalid.parse::<CronSchedule>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_latest_and_next_start() {
+        let daily: CronSchedule = "@daily".parse().unwrap();
+        let hour = Duration::from_secs(3600);
+        assert_eq!(
+            daily.latest_start(at("2026-10-16T00:59:59Z"), hour),
+            Some(at("2026-10-16T00:00:00Z"))
+        );
+        assert_eq!(daily.latest_start(at("2026-10-16T01:00:00Z"), hour), None);
+        assert_eq!(
+            daily.next_start(at("2026-10-16T00:00:00Z")),
+            Some(at("2026-10-17T00:00:00Z"))
+        );
+
+        let feb_29: CronSchedule = "30 12 29 2 *".parse().unwrap();
+        assert_eq!(
+            feb_29.next_start(at("2026-10-16T00:00:00Z")),
+            None,
+            "next Feb 29 is more than a year away"
+        );
+        assert_eq!(
+            feb_29.next_start(at("2027-10-16T00:00:00Z")),
+            Some(at("2028-02-29T12:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_scheduled_model_window_drains_and_restores() {
+        let registry = Arc::new(WorkerRegistry::new());
+        let a = registry.register(worker("http://a:8000", "llama")).unwrap();
+        let b = registry.register(worker("http://b:8000", "llama")).unwrap();
+        let c = registry.register(worker("http://c:8000", "qwen")).unwrap();
+        let controller = MaintenanceController::new(
+            &MaintenanceConfig {
+                windows: vec![MaintenanceWindowConfig {
+                    name: "nightly".to_string(),
+                    worker_url: None,
+                    model: Some("llama-latest".to_string()),
+                    schedule: "0 2 * * *".to_string(),
+                    duration_secs: 1800,
+                }],
+                ..Default::default()
+            },
+            registry.clone(),
+        );
+        let status = |id: &WorkerId| registry.get(id).unwrap().status();
+
+        controller.reconcile_at(at("2026-10-16T02:10:00Z"));
+        assert_eq!(status(&a), WorkerStatus::Draining);
+        assert_eq!(status(&b), WorkerStatus::Draining);
+        assert_eq!(status(&c), WorkerStatus::Ready);
+        let availability = controller.model_availability();
+        assert_eq!(availability["llama"].status, "maintenance");
+        assert_eq!(
+            availability["llama"].until,
+            Some(at("2026-10-16T02:30:00Z").timestamp())
+        );
+        assert!(!availability.contains_key("qwen"));
+
+        controller.reconcile_at(at("2026-10-16T02:30:00Z"));
+        assert_eq!(status(&a), WorkerStatus::Ready);
+        assert_eq!(status(&b), WorkerStatus::Ready);
+        assert!(controller.model_availability().is_empty());
+    }
+
+    #[test]
+    fn test_manual_worker_window() {
+        let registry = Arc::new(WorkerRegistry::new());
+        let a = registry.register(worker("http://a:8000", "llama")).unwrap();
+        registry.register(worker("http://b:8000", "llama")).unwrap();
+        let controller =
+            MaintenanceController::new(&MaintenanceConfig::default(), registry.clone());
+        let now = at("2026-10-16T12:00:00Z");
+
+        assert_eq!(
+            controller.open_window_at(
+                MaintenanceWindowRequest {
+                    worker_url: Some("http://a:8000".to_string()),
+                    model: Some("llama".to_string()),
+                    starts_at: None,
+                    duration_secs: 60,
+                    reason: None,
+                },
+                now,
+            ),
+            Err(MaintenanceError::InvalidTarget)
+        );
+
+        let window = controller
+            .open_window_at(
+                MaintenanceWindowRequest {
+                    worker_url: Some("http://a:8000/".to_string()),
+                    model: None,
+                    starts_at: None,
+                    duration_secs: 600,
+                    reason: Some("driver upgrade".to_string()),
+                },
+                now,
+            )
+            .unwrap();
+        assert!(window.active);
+        controller.reconcile_at(now);
+        assert_eq!(registry.get(&a).unwrap().status(), WorkerStatus::Draining);
+        assert_eq!(controller.model_availability()["llama"].status, "degraded");
+        assert_eq!(controller.windows_at(now), vec![window.clone()]);
+
+        assert!(controller.cancel_window(&window.id).is_some());
+        assert_eq!(registry.get(&a).unwrap().status(), WorkerStatus::Ready);
+        assert!(controller.cancel_window(&window.id).is_none());
+    }
+}
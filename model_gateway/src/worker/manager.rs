@@ -17,8 +17,8 @@ use futures::{
 };
 use http::StatusCode;
 use openai_protocol::worker::{
-    FlushCacheResult, HealthCheckConfig, ProfileOptions, ProfileResult, WorkerLoadInfo,
-    WorkerLoadsResult, WorkerStatus,
+    CacheAdminResult, FlushCacheResult, HealthCheckConfig, ProfileOptions, ProfileResult,
+    WorkerLoadInfo, WorkerLoadsResult, WorkerStatus,
 };
 use tokio::{
     sync::{broadcast, Notify},
@@ -329,6 +329,9 @@ async fn run_health_loop(
                     Ok(WorkerEvent::StatusChanged { .. }) => {
                         // Self-published; nothing to do.
                     }
+                    Ok(WorkerEvent::StandbyChanged { .. }) => {
+                        // Standbys are probed like any worker; no reschedule.
+                    }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         warn!(
                             "WorkerManager lagged {n} events; rebuilding schedule from registry"
@@ -820,6 +823,84 @@ impl WorkerManager {
         }
     }
 
+    /// Fan a cache operation out to every worker serving `model_id`.
+    async fn model_cache_op<F, Fut>(
+        worker_registry: &WorkerRegistry,
+        model_id: &str,
+        operation: &str,
+        op: F,
+    ) -> CacheAdminResult
+    where
+        F: Fn(Arc<dyn Worker>) -> Fut,
+        Fut: Future<Output = WorkerResult<()>>,
+    {
+        let workers = worker_registry.get_by_model(model_id);
+        let total_workers = workers.len();
+        if workers.is_empty() {
+            return CacheAdminResult {
+                model: model_id.to_string(),
+                operation: operation.to_string(),
+                successful: vec![],
+                failed: vec![],
+                total_workers,
+                routing_reset: false,
+                message: format!("No workers serve model '{model_id}'"),
+            };
+        }
+
+        info!(
+            model_id,
+            operation, "Running cache operation on {} workers", total_workers
+        );
+        let (successful, failed) = Self::admin_fan_out(workers, op).await;
+        let message = if failed.is_empty() {
+            format!("{operation} succeeded on all {} workers", successful.len())
+        } else {
+            format!(
+                "{operation}: {} succeeded, {} failed",
+                successful.len(),
+                failed.len()
+            )
+        };
+        info!(model_id, "{}", message);
+
+        CacheAdminResult {
+            model: model_id.to_string(),
+            operation: operation.to_string(),
+            successful,
+            failed,
+            total_workers,
+            routing_reset: false,
+            message,
+        }
+    }
+
+    /// Flush the KV cache on every worker serving `model_id`.
+    pub async fn flush_cache_for_model(
+        worker_registry: &WorkerRegistry,
+        model_id: &str,
+    ) -> CacheAdminResult {
+        Self::model_cache_op(worker_registry, model_id, "flush_cache", |w| async move {
+            w.flush_cache().await
+        })
+        .await
+    }
+
+    /// Apply runtime cache parameters on every worker serving `model_id`.
+    pub async fn set_cache_params_for_model(
+        worker_registry: &WorkerRegistry,
+        model_id: &str,
+        params: &serde_json::Value,
+    ) -> CacheAdminResult {
+        Self::model_cache_op(
+            worker_registry,
+            model_id,
+            "set_internal_state",
+            |w| async move { w.set_internal_state(params).await },
+        )
+        .await
+    }
+
     /// Start a profiling run on all workers, or on the single worker
     /// matching `worker_url`.
     pub async fn start_profile_all(
@@ -1023,7 +1104,9 @@ mod tests {
     use openai_protocol::worker::{HealthCheckConfig, WorkerStatus};
 
     use super::*;
-    use crate::worker::{BasicWorkerBuilder, Worker, WorkerError, WorkerRegistry, WorkerType};
+    use crate::worker::{
+        BasicWorkerBuilder, ModelCard, Worker, WorkerError, WorkerRegistry, WorkerType,
+    };
 
     fn make_worker(url: &str, success_threshold: u32, failure_threshold: u32) -> Arc<dyn Worker> {
         Arc::new(
@@ -1398,6 +1481,10 @@ mod tests {
             .route(
                 "/stop_profile",
                 axum::routing::post(move || async move { status }),
+            )
+            .route(
+                "/set_internal_state",
+                axum::routing::post(move || async move { status }),
             );
         #[expect(
             clippy::disallowed_methods,
@@ -1433,6 +1520,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_model_cache_ops_target_only_the_model() {
+        let ok_url = spawn_admin_stub(StatusCode::OK).await;
+        let other_url = spawn_admin_stub(StatusCode::OK).await;
+
+        let registry = WorkerRegistry::new();
+        let worker = |url: &str, model: &str| -> Arc<dyn Worker> {
+            Arc::new(
+                BasicWorkerBuilder::new(url)
+                    .model(ModelCard::new(model))
+                    .build(),
+            )
+        };
+        registry.register(worker(&ok_url, "llama")).unwrap();
+        registry.register(worker(&other_url, "qwen")).unwrap();
+
+        let result = WorkerManager::flush_cache_for_model(&registry, "llama").await;
+        assert_eq!(result.total_workers, 1);
+        assert_eq!(result.successful, vec![ok_url.clone()]);
+
+        let params = serde_json::json!({"max_running_requests": 64});
+        let result = WorkerManager::set_cache_params_for_model(&registry, "llama", &params).await;
+        assert_eq!(result.operation, "set_internal_state");
+        assert_eq!(result.successful, vec![ok_url]);
+
+        let result = WorkerManager::flush_cache_for_model(&registry, "missing").await;
+        assert_eq!(result.total_workers, 0);
+    }
+
     #[tokio::test]
     async fn test_flush_cache_all_no_workers() {
         let registry = WorkerRegistry::new();
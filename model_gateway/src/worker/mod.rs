@@ -3,18 +3,23 @@
 pub mod builder;
 pub mod capacity;
 pub mod circuit_breaker;
+pub mod debug_trace;
 pub mod error;
 pub mod event;
+pub mod federation;
 pub mod hash_ring;
 pub mod http_client;
 pub mod kv_event_monitor;
+pub mod maintenance;
 pub mod manager;
 pub mod metrics_aggregator;
+pub mod model_card_history;
 pub mod monitor;
 pub mod registry;
 pub mod resilience;
 pub mod sampling_defaults;
 pub mod service;
+pub mod standby;
 // FIXME: worker.rs is a 1800-line monolith containing the Worker trait,
 // BasicWorker impl, HealthChecker, WorkerType, ConnectionMode, and more.
 // Break it apart into focused modules (e.g. health_checker.rs, types.rs).
@@ -28,11 +33,15 @@ pub mod worker;
 pub use builder::BasicWorkerBuilder;
 pub use capacity::{CapacitySource, CapacityTrackerSettings, WorkerCapacity};
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+pub use debug_trace::WorkerDebugTracer;
 pub use error::{WorkerError, WorkerResult};
+pub use federation::FederationCatalog;
 pub use hash_ring::HashRing;
 pub use http_client::build_worker_http_client;
 pub use kv_event_monitor::KvEventMonitor;
+pub use maintenance::MaintenanceController;
 pub use manager::WorkerManager;
+pub use model_card_history::ModelCardHistory;
 pub use monitor::{WorkerLoadManager, WorkerMonitor};
 // Re-export UNKNOWN_MODEL_ID from protocols
 pub use openai_protocol::UNKNOWN_MODEL_ID;
@@ -45,6 +54,7 @@ pub use registry::{WorkerOrigin, WorkerRegistry};
 pub use resilience::{resolve_resilience, ResolvedResilience, DEFAULT_RETRYABLE_STATUS_CODES};
 pub use sampling_defaults::DEFAULT_SAMPLING_PARAMS_LABEL;
 pub use service::WorkerService;
+pub use standby::StandbyController;
 pub use worker::{
     AttachedBody, BasicWorker, ConnectionMode, RuntimeType, Worker, WorkerLoadGuard, WorkerType,
     DEFAULT_BOOTSTRAP_PORT, MOONCAKE_CONNECTOR, NIXL_CONNECTOR,
@@ -0,0 +1,206 @@
+//! Revision history of model cards.
+//!
+//! Every model ID has a current card revision. When a worker registers (or
+//! is replaced) with a card for the model that matches no retained revision,
+//! the revision is bumped, the new card is recorded next to the previous
+//! ones and an audit event is logged under the `smg::audit` target. At most
+//! [`MAX_RETAINED_REVISIONS`] revisions are kept per model.
+//!
+//! Each worker keeps the revision of the card it registered with, so while a
+//! parser or template change rolls out across a model's workers, a request
+//! carrying `X-SMG-Model-Card-Revision` can be pinned to the workers still
+//! serving a given revision.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use dashmap::DashMap;
+use openai_protocol::model_card::ModelCard;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::info;
+
+use crate::observability::metrics::Metrics;
+
+/// Revisions retained per model, oldest dropped first.
+pub const MAX_RETAINED_REVISIONS: usize = 16;
+
+/// One recorded version of a model's card.
+#[derive(Debug, Clone, Serialize)]
+pub struct CardRevision {
+    pub revision: u64,
+    /// Unix seconds at which the revision was recorded.
+    pub recorded_at: u64,
+    /// Worker whose registration introduced the revision.
+    pub worker_url: String,
+    /// Top-level card fields that differ from the previous revision.
+    pub changed_fields: Vec<String>,
+    pub card: Value,
+}
+
+/// A model's retained revisions as returned by the admin API.
+#[derive(Debug, Clone, Serialize)]
+pub struct CardHistory {
+    pub model: String,
+    pub current_revision: u64,
+    pub revisions: Vec<CardRevision>,
+}
+
+/// Card revisions per model and the revision each worker serves.
+#[derive(Debug, Default)]
+pub struct ModelCardHistory {
+    /// Model ID -> retained revisions, oldest first.
+    models: DashMap<String, VecDeque<CardRevision>>,
+    /// Worker URL -> model ID -> revision of the card it registered with.
+    served: DashMap<String, HashMap<String, u64>>,
+}
+
+impl ModelCardHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the cards `worker_url` registered with, replacing what it
+    /// served before.
+    pub fn record_worker<'a>(
+        &self,
+        worker_url: &str,
+        cards: impl IntoIterator<Item = &'a ModelCard>,
+    ) {
+        let served = cards
+            .into_iter()
+            .map(|card| (card.id.clone(), self.record(worker_url, card)))
+            .collect();
+        self.served.insert(worker_url.to_string(), served);
+    }
+
+    /// Forget the revisions a removed worker served. The history is kept.
+    pub fn forget_worker(&self, worker_url: &str) {
+        self.served.remove(worker_url);
+    }
+
+    /// Revision of `card` for its model: a retained revision with the same
+    /// content, or a new one.
+    fn record(&self, worker_url: &str, card: &ModelCard) -> u64 {
+        let value = match serde_json::to_value(card) {
+            Ok(value) => value,
+            Err(_) => return 0,
+        };
+        let mut revisions = self.models.entry(card.id.clone()).or_default();
+        if let Some(existing) = revisions.iter().find(|r| r.card == value) {
+            return existing.revision;
+        }
+
+        let previous = revisions.back();
+        let revision = previous.map_or(1, |p| p.revision + 1);
+        let changed_fields = changed_fields(previous.map(|p| &p.card), &value);
+        info!(
+            target: "smg::audit",
+            model = %card.id,
+            revision,
+            previous_revision = previous.map(|p| p.revision),
+            worker_url,
+            changed_fields = %changed_fields.join(","),
+            "model_card_revision"
+        );
+        Metrics::record_model_card_revision(&card.id);
+
+        revisions.push_back(CardRevision {
+            revision,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            worker_url: worker_url.to_string(),
+            changed_fields,
+            card: value,
+        });
+        while revisions.len() > MAX_RETAINED_REVISIONS {
+            revisions.pop_front();
+        }
+        revision
+    }
+
+    /// Revision of `model_id`'s card that `worker_url` serves.
+    pub fn served_revision(&self, worker_url: &str, model_id: &str) -> Option<u64> {
+        self.served.get(worker_url)?.get(model_id).copied()
+    }
+
+    /// Retained revisions of `model_id`, oldest first.
+    pub fn history(&self, model_id: &str) -> Option<CardHistory> {
+        let revisions = self.models.get(model_id)?;
+        Some(CardHistory {
+            model: model_id.to_string(),
+            current_revision: revisions.back().map_or(0, |r| r.revision),
+            revisions: revisions.iter().cloned().collect(),
+        })
+    }
+}
+
+/// Top-level keys whose values differ between two serialized cards.
+fn changed_fields(previous: Option<&Value>, current: &Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let previous = previous.and_then(Value::as_object).unwrap_or(&empty);
+    let current = current.as_object().unwrap_or(&empty);
+    let mut fields: Vec<String> = previous
+        .keys()
+        .chain(current.keys())
+        .filter(|key| previous.get(*key) != current.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_cards_bump_the_revision() {
+        let history = ModelCardHistory::new();
+        let v1 = ModelCard::new("llama").with_context_length(8192);
+        let v2 = v1.clone().with_tool_parser("llama");
+
+        history.record_worker("http://w1", [&v1]);
+        history.record_worker("http://w2", [&v1]);
+        assert_eq!(history.served_revision("http://w2", "llama"), Some(1));
+
+        // w1 picks up the new parser; w2 still serves revision 1.
+        history.record_worker("http://w1", [&v2]);
+        assert_eq!(history.served_revision("http://w1", "llama"), Some(2));
+        assert_eq!(history.served_revision("http://w2", "llama"), Some(1));
+
+        // A worker coming back with the old card does not bump again.
+        history.record_worker("http://w3", [&v1]);
+        assert_eq!(history.served_revision("http://w3", "llama"), Some(1));
+
+        let recorded = history.history("llama").unwrap();
+        assert_eq!(recorded.current_revision, 2);
+        assert_eq!(recorded.revisions.len(), 2);
+        assert_eq!(recorded.revisions[1].changed_fields, vec!["tool_parser"]);
+        assert_eq!(recorded.revisions[1].worker_url, "http://w1");
+
+        history.forget_worker("http://w1");
+        assert_eq!(history.served_revision("http://w1", "llama"), None);
+        assert!(history.history("llama").is_some());
+    }
+
+    #[test]
+    fn test_retained_revisions_are_bounded() {
+        let history = ModelCardHistory::new();
+        for length in 0..(MAX_RETAINED_REVISIONS as u32 + 4) {
+            history.record_worker(
+                "http://w1",
+                [&ModelCard::new("m").with_context_length(length + 1)],
+            );
+        }
+        let recorded = history.history("m").unwrap();
+        assert_eq!(recorded.revisions.len(), MAX_RETAINED_REVISIONS);
+        assert_eq!(recorded.revisions[0].revision, 5);
+        assert_eq!(recorded.current_revision, MAX_RETAINED_REVISIONS as u64 + 4);
+    }
+}
@@ -56,6 +56,7 @@ use openai_protocol::worker::{
     RuntimeType, SchedulerLoadSnapshot, WorkerGroupKey, WorkerLoadResponse, WorkerStatus,
 };
 use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
 use tokio::{
     sync::{broadcast, watch},
     task::JoinHandle,
@@ -70,6 +71,34 @@ use crate::{
 
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// One entry of llama.cpp's `/slots`. Builds predating `is_processing`
+/// report `state` instead (0 idle, 1 processing).
+#[derive(Debug, Deserialize)]
+struct LlamaCppSlot {
+    is_processing: Option<bool>,
+    state: Option<u8>,
+}
+
+impl LlamaCppSlot {
+    fn is_busy(&self) -> bool {
+        self.is_processing
+            .unwrap_or_else(|| self.state.is_some_and(|state| state != 0))
+    }
+}
+
+/// Running requests and slot occupancy of a llama.cpp server.
+fn llamacpp_snapshot(slots: &[LlamaCppSlot]) -> Option<SchedulerLoadSnapshot> {
+    if slots.is_empty() {
+        return None;
+    }
+    let busy = slots.iter().filter(|slot| slot.is_busy()).count();
+    Some(SchedulerLoadSnapshot {
+        num_running_reqs: busy as i32,
+        token_usage: busy as f64 / slots.len() as f64,
+        ..Default::default()
+    })
+}
+
 /// Minimal Prometheus text-format scraper: metric name -> sample values
 /// (one per label-set). Only the flat `name{labels} value` / `name value`
 /// gauge lines that the engine load fetchers look up are collected;
@@ -213,6 +242,9 @@ pub struct WorkerMonitor {
     /// When set, poll loads and re-export `smg_engine_*` gauges even if no
     /// load-aware routing policy is active (`--engine-metrics`).
     engine_metrics: bool,
+    /// When set, poll loads for request handlers that read them
+    /// (`latency_budget`) even if no load-aware routing policy is active.
+    load_polling: bool,
     load_tx: watch::Sender<HashMap<String, WorkerLoadResponse>>,
     load_rx: watch::Receiver<HashMap<String, WorkerLoadResponse>>,
     group_handles: Mutex<HashMap<WorkerGroupKey, GroupState>>,
@@ -248,6 +280,7 @@ impl WorkerMonitor {
             client,
             default_interval: Duration::from_secs(default_interval_secs.max(1)),
             engine_metrics,
+            load_polling: false,
             load_tx,
             load_rx,
             group_handles: Mutex::new(HashMap::new()),
@@ -255,6 +288,13 @@ impl WorkerMonitor {
         }
     }
 
+    /// Keep polling loads regardless of the active routing policies.
+    #[must_use]
+    pub fn with_load_polling(mut self, load_polling: bool) -> Self {
+        self.load_polling = load_polling;
+        self
+    }
+
     /// Subscribe to the snapshot of per-worker loads.
     ///
     /// The watch receiver returns the most recent fully merged map;
@@ -478,6 +518,10 @@ impl WorkerMonitor {
         match worker.metadata().spec.runtime_type {
             RuntimeType::Vllm => Self::fetch_http_load_vllm(client, worker).await,
             RuntimeType::Sglang => Self::fetch_http_load_sglang(client, worker).await,
+            RuntimeType::LlamaCpp => Self::fetch_http_load_llamacpp(client, worker).await,
+            // Ollama lists loaded models (`/api/ps`) but not in-flight
+            // requests, so there is no load to poll.
+            RuntimeType::Ollama => None,
             // Unspecified / custom engines that do serve `/v1/loads`, plus
             // the mock worker used in tests.
             _ => Self::fetch_http_load_native(client, worker).await,
@@ -576,6 +620,24 @@ impl WorkerMonitor {
         }))
     }
 
+    /// llama.cpp HTTP: derive load from `/slots`, one entry per parallel
+    /// slot (`--parallel`). Busy slots are the running requests, and slot
+    /// occupancy stands in for `token_usage`: each slot owns a fixed share of
+    /// the KV cache, so a server without a free slot queues new requests.
+    /// Returns `None` when slots are disabled (`--no-slots`).
+    async fn fetch_http_load_llamacpp(
+        client: &reqwest::Client,
+        worker: &Arc<dyn Worker>,
+    ) -> Option<WorkerLoadResponse> {
+        let url = format!("{}/slots", worker.url());
+        let slots: Vec<LlamaCppSlot> = Self::authed_get(client, worker, &url)
+            .await?
+            .json()
+            .await
+            .ok()?;
+        llamacpp_snapshot(&slots).map(Self::single_rank)
+    }
+
     /// Shared authenticated GET with the standard timeout. Returns `None` on
     /// transport error or non-success status.
     async fn authed_get(
@@ -751,6 +813,9 @@ async fn run_event_loop(
                 // group's polling loop reads from the registry on every
                 // tick and will pick the worker up automatically.
             }
+            Ok(WorkerEvent::StandbyChanged { .. }) => {
+                // Rotation does not change group membership.
+            }
             Err(broadcast::error::RecvError::Lagged(n)) => {
                 warn!(
                     skipped = n,
@@ -791,11 +856,12 @@ async fn group_monitor_loop(
         };
 
         // Poll when a load-aware policy needs the data OR engine-metrics
-        // re-export is on; the latter decouples observability from routing.
+        // re-export is on OR a handler reads loads; the latter two decouple
+        // polling from routing.
         let load_aware_policies = monitor.policy_registry.get_all_load_aware_policies();
         let routing_needs_load = !load_aware_policies.is_empty()
             || monitor.policy_registry.get_dp_rank_policy().is_some();
-        if !routing_needs_load && !monitor.engine_metrics {
+        if !routing_needs_load && !monitor.engine_metrics && !monitor.load_polling {
             debug!("No load-aware policies and engine metrics off, skipping load fetch for group {group_key}");
             drop(monitor);
             continue;
@@ -1288,6 +1354,23 @@ sglang:utilization{model="llama"} 0.9
         assert_eq!(resp.loads[0].num_waiting_reqs, 5);
     }
 
+    #[test]
+    fn llamacpp_slots_map_onto_occupancy_snapshot() {
+        let slots: Vec<LlamaCppSlot> = serde_json::from_str(
+            r#"[
+                {"id": 0, "n_ctx": 4096, "is_processing": true},
+                {"id": 1, "n_ctx": 4096, "is_processing": false},
+                {"id": 2, "state": 1},
+                {"id": 3, "state": 0}
+            ]"#,
+        )
+        .unwrap();
+        let snap = llamacpp_snapshot(&slots).unwrap();
+        assert_eq!(snap.num_running_reqs, 2);
+        assert_eq!(snap.token_usage, 0.5);
+        assert!(llamacpp_snapshot(&[]).is_none());
+    }
+
     #[test]
     fn sglang_metrics_map_onto_token_usage_snapshot() {
         let m = PromScrape::parse(SGLANG_METRICS);
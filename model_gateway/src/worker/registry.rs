@@ -29,6 +29,7 @@ use crate::{
         circuit_breaker::CircuitState,
         event::WorkerEvent,
         hash_ring::HashRing,
+        model_card_history::ModelCardHistory,
         worker::{RuntimeType, WorkerType},
         ConnectionMode, Worker, DEFAULT_SAMPLING_PARAMS_LABEL,
     },
@@ -122,6 +123,9 @@ pub struct WorkerRegistry {
     /// removed in `remove()` teardown.
     worker_origins: Arc<DashMap<WorkerId, WorkerOrigin>>,
 
+    /// Model card revisions, recorded on register/replace.
+    model_cards: Arc<ModelCardHistory>,
+
     /// Broadcast channel for worker state change events.
     event_tx: broadcast::Sender<WorkerEvent>,
 }
@@ -146,6 +150,7 @@ impl WorkerRegistry {
             worker_mutation_locks: Arc::new(DashMap::new()),
             model_retry_configs: Arc::new(DashMap::new()),
             worker_origins: Arc::new(DashMap::new()),
+            model_cards: Arc::new(ModelCardHistory::new()),
             // Sized for fleet-scale bursts (startup registration, probe
             // storms): a lagged subscriber forces a full state resync, so
             // the capacity should comfortably exceed realistic worker
@@ -161,6 +166,11 @@ impl WorkerRegistry {
         self.worker_origins.get(worker_id).map(|entry| *entry)
     }
 
+    /// Revision history of the model cards workers registered with.
+    pub fn model_cards(&self) -> &ModelCardHistory {
+        &self.model_cards
+    }
+
     /// Subscribe to the `WorkerEvent` broadcast stream.
     ///
     /// Returns a `broadcast::Receiver` that observes every future mutation
@@ -728,6 +738,8 @@ impl WorkerRegistry {
 
         // Overwrite worker object atomically
         self.workers.insert(worker_id.clone(), new_worker.clone());
+        self.model_cards
+            .record_worker(new_worker.url(), new_worker.metadata().spec.models.iter());
 
         // Diff model indexes: remove stale, add new
         for removed_model in old_models.difference(&new_models) {
@@ -817,6 +829,35 @@ impl WorkerRegistry {
         self.transition_status_inner(worker_id, None, new_status)
     }
 
+    /// Promote a standby worker into rotation or demote it back out.
+    ///
+    /// Returns `true` if the worker's state changed; `false` if it is gone,
+    /// is not a standby, or was already in the requested state.
+    ///
+    /// Emits [`WorkerEvent::StandbyChanged`] on change. Holds the
+    /// per-worker mutation lock.
+    pub fn set_standby_promoted(&self, worker_id: &WorkerId, promoted: bool) -> bool {
+        let lock = self
+            .worker_mutation_locks
+            .entry(worker_id.clone())
+            .or_insert_with(|| Arc::new(parking_lot::Mutex::new(())))
+            .clone();
+        let _guard = lock.lock();
+
+        let Some(worker) = self.workers.get(worker_id).map(|w| w.clone()) else {
+            return false;
+        };
+        if !worker.set_standby_promoted(promoted) {
+            return false;
+        }
+        let _ = self.event_tx.send(WorkerEvent::StandbyChanged {
+            worker_id: worker_id.clone(),
+            worker,
+            promoted,
+        });
+        true
+    }
+
     /// Same as [`Self::transition_status`], but becomes a no-op if the
     /// currently installed worker revision no longer matches
     /// `expected_revision`.
@@ -971,6 +1012,7 @@ impl WorkerRegistry {
 
         if let Some((_, worker)) = self.workers.remove(worker_id) {
             self.url_to_id.remove(worker.url());
+            self.model_cards.forget_worker(worker.url());
             // We hold _guard; drop the DashMap entry but the Mutex stays alive via Arc.
             self.worker_mutation_locks.remove(worker_id);
             self.worker_origins.remove(worker_id);
@@ -1198,6 +1240,8 @@ impl WorkerRegistry {
         self.worker_origins.insert(worker_id.clone(), origin);
 
         self.workers.insert(worker_id.clone(), worker.clone());
+        self.model_cards
+            .record_worker(worker.url(), worker.metadata().spec.models.iter());
 
         // Update model index for O(1) lookups using copy-on-write.
         for model_id in Self::worker_model_ids(&worker) {
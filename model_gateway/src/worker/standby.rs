@@ -0,0 +1,243 @@
+//! Warm spare workers.
+//!
+//! A worker registered with `standby: true` is health-checked like any
+//! other but is left out of routing. [`StandbyController`] watches each
+//! model's pool (per worker type, so a decode standby never covers for a
+//! prefill worker): when fewer than `standby.min_active_workers` regular
+//! workers are healthy, it promotes enough healthy standbys to make up the
+//! difference, highest priority first. Once the regular workers recover it
+//! demotes them again. Promoted standbys never count towards the threshold,
+//! so a pool does not flap between the two states.
+//!
+//! Pools are re-evaluated on every registry change and on a fixed interval.
+//! Each promotion and demotion is published as a
+//! [`WorkerEvent::StandbyChanged`] on the registry channel.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use openai_protocol::worker::WorkerStatus;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info};
+
+use super::{event::WorkerEvent, registry::WorkerId, Worker, WorkerRegistry, WorkerType};
+use crate::{config::StandbyConfig, observability::metrics::Metrics};
+
+/// Model IDs a worker serves; a wildcard worker forms its own pool.
+fn pool_models(worker: &Arc<dyn Worker>) -> Vec<String> {
+    let cards = worker.models();
+    if cards.is_empty() {
+        return vec![worker.model_id().to_string()];
+    }
+    cards.into_iter().map(|card| card.id).collect()
+}
+
+#[derive(Default)]
+struct Pool {
+    active: usize,
+    standbys: Vec<(WorkerId, Arc<dyn Worker>)>,
+}
+
+/// Promotes and demotes standby workers as regular pools lose and regain
+/// healthy workers.
+pub struct StandbyController {
+    registry: Arc<WorkerRegistry>,
+    config: StandbyConfig,
+    check_interval: Duration,
+}
+
+impl std::fmt::Debug for StandbyController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StandbyController")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StandbyController {
+    pub fn new(config: &StandbyConfig, registry: Arc<WorkerRegistry>) -> Arc<Self> {
+        Arc::new(Self {
+            registry,
+            config: config.clone(),
+            check_interval: Duration::from_secs(config.check_interval_secs.max(1)),
+        })
+    }
+
+    /// Spawn the re-evaluation loop, driven by registry events and the
+    /// check interval. It holds only a `Weak<Self>` and exits when the
+    /// controller is dropped.
+    pub fn start(self: &Arc<Self>) {
+        let weak: Weak<Self> = Arc::downgrade(self);
+        let mut events = self.registry.subscribe_events();
+        let check_interval = self.check_interval;
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "loop holds only a Weak<Self> and exits when the controller is dropped"
+        )]
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(check_interval);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = tick.tick() => {}
+                    event = events.recv() => match event {
+                        // Our own promotions change no pool's regular workers.
+                        Ok(WorkerEvent::StandbyChanged { .. }) => continue,
+                        Ok(_) | Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+                let Some(controller) = weak.upgrade() else {
+                    break;
+                };
+                controller.reconcile();
+            }
+            debug!("StandbyController loop exited");
+        });
+    }
+
+    /// Promote the standbys each pool needs and demote all others.
+    pub fn reconcile(&self) {
+        let workers = self.registry.get_all_with_ids();
+        let mut pools: HashMap<(String, WorkerType), Pool> = HashMap::new();
+        for (worker_id, worker) in &workers {
+            let standby = worker.metadata().spec.standby;
+            // Standbys are judged on their probe result alone; `is_healthy`
+            // is false for every standby that is not yet promoted.
+            if standby && worker.status() != WorkerStatus::Ready {
+                continue;
+            }
+            for model in pool_models(worker) {
+                let pool = pools.entry((model, *worker.worker_type())).or_default();
+                if standby {
+                    pool.standbys.push((worker_id.clone(), Arc::clone(worker)));
+                } else if worker.is_healthy() {
+                    pool.active += 1;
+                }
+            }
+        }
+
+        let mut wanted: HashMap<WorkerId, String> = HashMap::new();
+        for ((model, _), mut pool) in pools {
+            let missing = self
+                .config
+                .min_active_for(&model)
+                .saturating_sub(pool.active);
+            if missing == 0 {
+                continue;
+            }
+            pool.standbys.sort_by(|(_, a), (_, b)| {
+                b.priority()
+                    .cmp(&a.priority())
+                    .then_with(|| a.url().cmp(b.url()))
+            });
+            for (worker_id, _) in pool.standbys.into_iter().take(missing) {
+                wanted.entry(worker_id).or_insert_with(|| model.clone());
+            }
+        }
+
+        for (worker_id, worker) in &workers {
+            if !worker.metadata().spec.standby {
+                continue;
+            }
+            let model = wanted.get(worker_id);
+            if !self
+                .registry
+                .set_standby_promoted(worker_id, model.is_some())
+            {
+                continue;
+            }
+            match model {
+                Some(model) => {
+                    info!(
+                        worker_url = %worker.url(),
+                        model = %model,
+                        "Promoted standby worker: too few healthy regular workers"
+                    );
+                    Metrics::record_standby_transition(model, "promoted");
+                }
+                None => {
+                    info!(
+                        worker_url = %worker.url(),
+                        "Demoted standby worker: regular workers recovered"
+                    );
+                    Metrics::record_standby_transition(worker.model_id(), "demoted");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::{BasicWorkerBuilder, ModelCard};
+
+    fn register(
+        registry: &WorkerRegistry,
+        url: &str,
+        standby: bool,
+        priority: u32,
+    ) -> (WorkerId, Arc<dyn Worker>) {
+        let worker: Arc<dyn Worker> = Arc::new(
+            BasicWorkerBuilder::new(url)
+                .model(ModelCard::new("llama-70b"))
+                .standby(standby)
+                .priority(priority)
+                .status(WorkerStatus::Ready)
+                .build(),
+        );
+        let worker_id = registry.register(Arc::clone(&worker)).unwrap();
+        (worker_id, worker)
+    }
+
+    #[tokio::test]
+    async fn test_standby_promoted_and_demoted_with_regular_pool() {
+        let registry = Arc::new(WorkerRegistry::new());
+        let (primary_id, _) = register(&registry, "http://primary:8000", false, 50);
+        let (_, standby) = register(&registry, "http://standby:8000", true, 50);
+        let controller = StandbyController::new(&StandbyConfig::default(), registry.clone());
+        let mut events = registry.subscribe_events();
+
+        controller.reconcile();
+        assert!(!standby.is_healthy());
+
+        registry.transition_status(&primary_id, WorkerStatus::NotReady);
+        controller.reconcile();
+        assert!(standby.is_healthy());
+        assert!(standby.routing_state().healthy);
+        let promoted = loop {
+            if let WorkerEvent::StandbyChanged { promoted, .. } = events.recv().await.unwrap() {
+                break promoted;
+            }
+        };
+        assert!(promoted);
+
+        registry.transition_status(&primary_id, WorkerStatus::Ready);
+        controller.reconcile();
+        assert!(!standby.is_healthy());
+        assert!(!standby.standby_promoted());
+    }
+
+    #[test]
+    fn test_highest_priority_healthy_standbys_fill_the_gap() {
+        let registry = Arc::new(WorkerRegistry::new());
+        let (_, low) = register(&registry, "http://low:8000", true, 10);
+        let (_, high) = register(&registry, "http://high:8000", true, 90);
+        let (down_id, down) = register(&registry, "http://down:8000", true, 100);
+        registry.transition_status(&down_id, WorkerStatus::NotReady);
+
+        let config = StandbyConfig {
+            model_min_active_workers: HashMap::from([("llama-70b".to_string(), 1)]),
+            min_active_workers: 3,
+            ..Default::default()
+        };
+        StandbyController::new(&config, registry).reconcile();
+        assert!(high.standby_promoted());
+        assert!(!low.standby_promoted());
+        assert!(!down.standby_promoted());
+    }
+}
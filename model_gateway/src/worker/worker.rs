@@ -2,7 +2,7 @@ use std::{
     any::Any,
     fmt,
     sync::{
-        atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
         Arc,
     },
     time::Duration,
@@ -34,6 +34,9 @@ pub const DEFAULT_WORKER_HTTP_TIMEOUT_SECS: u64 = 30;
 /// client's local flush deadline.
 const FLUSH_HTTP_TIMEOUT: Duration = Duration::from_secs(45);
 
+/// Timeout for worker HTTP `set_internal_state` requests.
+const INTERNAL_STATE_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Timeout for worker HTTP profile requests. Stopping a profile can take
 /// a long time while the backend serializes large traces. Matches the
 /// gRPC client's profile deadline.
@@ -230,10 +233,27 @@ pub trait Worker: Send + Sync + fmt::Debug + 'static {
 
     /// Check if the worker is currently healthy (status == Ready).
     ///
-    /// This is a routing predicate — returns true only for `Ready` workers.
-    /// A `Pending` worker is not "unhealthy", just unverified.
+    /// This is a routing predicate — returns true only for `Ready` workers
+    /// that are in rotation. A `Pending` worker is not "unhealthy", just
+    /// unverified; a standby is health-checked but excluded until promoted.
     fn is_healthy(&self) -> bool {
-        self.status() == WorkerStatus::Ready
+        self.status() == WorkerStatus::Ready && self.in_rotation()
+    }
+
+    /// Whether the worker may receive traffic: always for a regular worker,
+    /// and for a standby only while promoted (see `StandbyController`).
+    fn in_rotation(&self) -> bool {
+        !self.metadata().spec.standby
+    }
+
+    /// Whether this standby is currently promoted into rotation.
+    fn standby_promoted(&self) -> bool {
+        false
+    }
+
+    /// Promote or demote a standby. Returns `true` if the state changed.
+    fn set_standby_promoted(&self, _promoted: bool) -> bool {
+        false
     }
 
     /// Perform an async health check on the worker.
@@ -607,6 +627,29 @@ pub trait Worker: Send + Sync + fmt::Debug + 'static {
             }
         }
     }
+
+    /// Update runtime server parameters, such as cache settings, through
+    /// SGLang's `/set_internal_state`. HTTP workers only.
+    async fn set_internal_state(&self, state: &serde_json::Value) -> WorkerResult<()> {
+        match self.connection_mode() {
+            ConnectionMode::Http => {
+                admin_http_post(
+                    self.http_client(),
+                    self.endpoint_url("/set_internal_state"),
+                    self.api_key(),
+                    Some(state.clone()),
+                    "set_internal_state",
+                    INTERNAL_STATE_HTTP_TIMEOUT,
+                )
+                .await
+            }
+            ConnectionMode::Grpc => Err(WorkerError::OperationFailed {
+                url: self.url().to_string(),
+                operation: "set_internal_state".to_string(),
+                reason: "not supported for gRPC workers".to_string(),
+            }),
+        }
+    }
 }
 
 /// Extension trait for model_gateway-specific ConnectionMode methods.
@@ -803,7 +846,7 @@ impl WorkerMetadata {
 /// One-shot routing snapshot — see [`Worker::routing_state`].
 #[derive(Clone, Copy, Debug)]
 pub struct RoutingState {
-    /// `status == Ready`.
+    /// `status == Ready` and in rotation.
     pub healthy: bool,
     /// Circuit breaker permits execution (closed or half-open).
     pub can_execute: bool,
@@ -824,6 +867,7 @@ pub struct WorkerRuntime {
     processed_counter: AtomicUsize,
     worker_routing_key_load: WorkerRoutingKeyLoad,
     revision: AtomicU64,
+    standby_promoted: AtomicBool,
 }
 
 impl WorkerRuntime {
@@ -837,6 +881,7 @@ impl WorkerRuntime {
             processed_counter: AtomicUsize::new(0),
             worker_routing_key_load: WorkerRoutingKeyLoad::new(url),
             revision: AtomicU64::new(0),
+            standby_promoted: AtomicBool::new(false),
         }
     }
 
@@ -858,6 +903,17 @@ impl WorkerRuntime {
         self.revision.fetch_add(1, Ordering::AcqRel) + 1
     }
 
+    // ── Standby promotion ───────────────────────────────────────────
+
+    pub fn standby_promoted(&self) -> bool {
+        self.standby_promoted.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the flag changed.
+    pub fn set_standby_promoted(&self, promoted: bool) -> bool {
+        self.standby_promoted.swap(promoted, Ordering::AcqRel) != promoted
+    }
+
     // ── Health-check counters ───────────────────────────────────────
 
     pub fn consecutive_failures_increment(&self) -> usize {
@@ -1046,6 +1102,18 @@ impl Worker for BasicWorker {
         true
     }
 
+    fn in_rotation(&self) -> bool {
+        !self.metadata.spec.standby || self.runtime.load().standby_promoted()
+    }
+
+    fn standby_promoted(&self) -> bool {
+        self.metadata.spec.standby && self.runtime.load().standby_promoted()
+    }
+
+    fn set_standby_promoted(&self, promoted: bool) -> bool {
+        self.metadata.spec.standby && self.runtime.load().set_standby_promoted(promoted)
+    }
+
     async fn check_health_async(&self) -> WorkerResult<()> {
         if self.metadata.health_config.disable_health_check {
             return Ok(());
@@ -1151,7 +1219,8 @@ impl Worker for BasicWorker {
         // its own guard.
         let rt = self.runtime.load();
         RoutingState {
-            healthy: rt.status() == WorkerStatus::Ready,
+            healthy: rt.status() == WorkerStatus::Ready
+                && (!self.metadata.spec.standby || rt.standby_promoted()),
             can_execute: self.circuit_breaker.load().can_execute(),
             load: rt.load(),
             processed: rt.processed_requests(),
@@ -1218,7 +1287,13 @@ impl Worker for BasicWorker {
                             self.metadata.spec.url
                         );
                         // DP-expanded workers carry a `{base}@{rank}` URL; connect to the base
-                        match GrpcClient::connect(self.metadata.base_url(), &runtime_str).await {
+                        match GrpcClient::connect_negotiated(
+                            self.metadata.base_url(),
+                            &runtime_str,
+                            self.metadata.spec.grpc_tls.as_ref(),
+                        )
+                        .await
+                        {
                             Ok(client) => {
                                 tracing::info!(
                                     "Successfully connected gRPC client ({}) for worker: {}",
@@ -1420,6 +1495,7 @@ pub fn worker_to_info(worker: &Arc<dyn Worker>) -> WorkerInfo {
     let metadata = worker.metadata();
     let spec = metadata.spec.clone();
     let status = worker.status();
+    let promoted = spec.standby.then(|| worker.standby_promoted());
 
     WorkerInfo {
         id: worker.url().to_string(),
@@ -1428,6 +1504,7 @@ pub fn worker_to_info(worker: &Arc<dyn Worker>) -> WorkerInfo {
         is_healthy: status == WorkerStatus::Ready,
         status: Some(status),
         load: worker.load(),
+        promoted,
         job_status: None,
     }
 }
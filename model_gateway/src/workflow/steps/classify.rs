@@ -131,7 +131,10 @@ impl StepExecutor<WorkerWorkflowData> for ClassifyWorkerTypeStep {
         }
 
         // 5. gRPC health → Local (external APIs never use gRPC)
-        if try_grpc_reachable(&config.url, timeout).await.is_ok() {
+        if try_grpc_reachable(&config.url, timeout, config.grpc_tls.as_ref())
+            .await
+            .is_ok()
+        {
             debug!("Worker {} responded to gRPC health → Local", config.url);
             context.data.worker_kind = Some(WorkerKind::Local);
             return Ok(StepResult::Success);
@@ -109,6 +109,7 @@ pub fn infer_model_type_from_id(id: &str) -> ModelType {
     // Image generation models
     if id_lower.starts_with("dall-e")
         || id_lower.starts_with("sora")
+        || id_lower.starts_with("stable-diffusion")
         || (id_lower.contains("image") && !id_lower.contains("vision"))
     {
         return ModelType::IMAGE_MODEL;
@@ -177,6 +178,11 @@ fn infer_provider_from_id(id: &str) -> Option<ProviderType> {
         return Some(ProviderType::Gemini);
     }
 
+    // Stability AI models
+    if id_lower.starts_with("stable-diffusion") {
+        return Some(ProviderType::Stability);
+    }
+
     None
 }
 
@@ -273,6 +279,14 @@ impl StepExecutor<WorkerWorkflowData> for DiscoverModelsStep {
         let config = &context.data.config;
         let provider = ProviderType::from_url(&config.url);
 
+        if provider == Some(ProviderType::Stability) {
+            info!(
+                "{} has no /v1/models listing - using wildcard mode (accepts any model)",
+                config.url
+            );
+            return Ok(StepResult::Success);
+        }
+
         // Resolve discovery API key: env var admin key > config.api_key > None (wildcard)
         let discovery_key =
             resolve_discovery_api_key(provider.as_ref(), &config.url, config.api_key.as_deref());
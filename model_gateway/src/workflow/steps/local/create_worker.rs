@@ -73,8 +73,6 @@ impl StepExecutor<WorkerWorkflowData> for CreateLocalWorkerStep {
             .or_else(|| labels.get("model_path").cloned())
             .unwrap_or_else(|| UNKNOWN_MODEL_ID.to_string());
 
-        let model_card = build_model_card(&model_id, config, &labels);
-
         let runtime_type = match context.data.detected_runtime_type.as_deref() {
             Some(s) => s.parse::<RuntimeType>().unwrap_or(config.runtime_type),
             None => config.runtime_type,
@@ -93,6 +91,8 @@ impl StepExecutor<WorkerWorkflowData> for CreateLocalWorkerStep {
             RuntimeType::Sglang
         };
 
+        let model_card = build_model_card(&model_id, config, &labels, runtime_type);
+
         // Normalize URL
         let url = normalize_url(&config.url, *connection_mode);
 
@@ -200,6 +200,7 @@ fn build_model_card(
     model_id: &str,
     config: &WorkerSpec,
     labels: &HashMap<String, String>,
+    runtime_type: RuntimeType,
 ) -> ModelCard {
     let user_provided = config.models.find(model_id).is_some();
     let mut card = config
@@ -273,8 +274,14 @@ fn build_model_card(
 
         if is_embedding || is_non_generation {
             card.model_type = infer_non_generation_type(labels);
-        } else if has_vision && !card.model_type.supports_vision() {
-            card.model_type |= ModelType::VISION;
+        } else {
+            if has_vision && !card.model_type.supports_vision() {
+                card.model_type |= ModelType::VISION;
+            }
+            // SGLang scores label-token probabilities with generation models.
+            if runtime_type == RuntimeType::Sglang {
+                card.model_type |= ModelType::SCORE;
+            }
         }
     } else if has_vision && !card.model_type.supports_vision() {
         card.model_type |= ModelType::VISION;
@@ -283,24 +290,44 @@ fn build_model_card(
     card
 }
 
-/// Determine embedding vs rerank from architecture/model_type hints.
+/// Determine embedding vs rerank vs classification from architecture/model_type hints.
 fn infer_non_generation_type(labels: &HashMap<String, String>) -> ModelType {
-    if let Some(archs_json) = labels.get("architectures") {
-        if let Ok(archs) = serde_json::from_str::<Vec<String>>(archs_json) {
-            let joined = archs.join(" ").to_lowercase();
-            if joined.contains("rerank") || joined.contains("crossencoder") {
-                return ModelType::RERANK;
-            }
-        }
+    let archs = labels
+        .get("architectures")
+        .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+        .map(|archs| archs.join(" ").to_lowercase())
+        .unwrap_or_default();
+    if archs.contains("rerank") || archs.contains("crossencoder") {
+        return ModelType::RERANK;
     }
     if let Some(mt) = labels.get("model_type") {
         if mt.to_lowercase().contains("rerank") {
             return ModelType::RERANK;
         }
     }
+    if archs.contains("forsequenceclassification") {
+        // Single-logit sequence classifiers are cross-encoder rerankers.
+        return if num_labels(labels) == Some(1) {
+            ModelType::RERANK
+        } else {
+            ModelType::CLASSIFY_MODEL
+        };
+    }
     ModelType::EMBEDDINGS
 }
 
+fn num_labels(labels: &HashMap<String, String>) -> Option<usize> {
+    labels
+        .get("num_labels")
+        .and_then(|s| s.parse().ok())
+        .or_else(|| {
+            labels
+                .get("id2label_json")
+                .and_then(|json| serde_json::from_str::<HashMap<String, String>>(json).ok())
+                .map(|map| map.len())
+        })
+}
+
 fn normalize_url(url: &str, connection_mode: ConnectionMode) -> String {
     if url.starts_with("http://")
         || url.starts_with("https://")
@@ -340,6 +367,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn infer_non_generation_type_detects_classifiers() {
+        let labels = |archs: &str, num_labels: &str| {
+            HashMap::from([
+                ("architectures".to_string(), archs.to_string()),
+                ("num_labels".to_string(), num_labels.to_string()),
+            ])
+        };
+        assert_eq!(
+            infer_non_generation_type(&labels(r#"["BertForSequenceClassification"]"#, "3")),
+            ModelType::CLASSIFY_MODEL
+        );
+        assert_eq!(
+            infer_non_generation_type(&labels(r#"["XLMRobertaForSequenceClassification"]"#, "1")),
+            ModelType::RERANK
+        );
+        assert_eq!(
+            infer_non_generation_type(&labels(r#"["BertModel"]"#, "2")),
+            ModelType::EMBEDDINGS
+        );
+    }
+
     #[test]
     fn normalize_url_adds_scheme_for_bare_urls() {
         assert_eq!(
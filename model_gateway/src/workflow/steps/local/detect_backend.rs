@@ -1,12 +1,14 @@
 //! Backend runtime detection step.
 //!
-//! Detects the runtime type (sglang, vllm, trtllm, tokenspeed, mlx) for both HTTP and gRPC workers.
+//! Detects the runtime type (sglang, vllm, trtllm, tokenspeed, mlx, llamacpp, ollama) for both
+//! HTTP and gRPC workers.
 //! - HTTP: probes `/v1/models` (owned_by field), falls back to unique endpoints.
 //! - gRPC: tries sglang → vllm → trtllm → tokenspeed → mlx health checks sequentially.
 
 use std::time::Duration;
 
 use async_trait::async_trait;
+use openai_protocol::worker::GrpcTlsConfig;
 use reqwest::Client;
 use tracing::debug;
 use wfaas::{StepExecutor, StepResult, WorkflowContext, WorkflowError, WorkflowResult};
@@ -30,12 +32,13 @@ async fn detect_grpc_backend(
     url: &str,
     timeout_secs: u64,
     runtime_hint: Option<&str>,
+    tls: Option<&GrpcTlsConfig>,
 ) -> Result<String, String> {
     let grpc_url = grpc_base_url(url);
 
     // If we have a hint, try it first (fast path)
     if let Some(hint) = runtime_hint {
-        if do_grpc_health_check(&grpc_url, timeout_secs, hint)
+        if do_grpc_health_check(&grpc_url, timeout_secs, hint, tls)
             .await
             .is_ok()
         {
@@ -48,7 +51,7 @@ async fn detect_grpc_backend(
         if Some(*runtime) == runtime_hint {
             continue;
         }
-        if do_grpc_health_check(&grpc_url, timeout_secs, runtime)
+        if do_grpc_health_check(&grpc_url, timeout_secs, runtime, tls)
             .await
             .is_ok()
         {
@@ -105,21 +108,23 @@ async fn detect_via_models_endpoint(
     match first_model.owned_by.as_deref() {
         Some("sglang" | "nvidia") => Ok("sglang".to_string()),
         Some("vllm") => Ok("vllm".to_string()),
+        Some("llamacpp") => Ok("llamacpp".to_string()),
         other => Err(format!("Unrecognized owned_by value: {other:?}")),
     }
 }
 
-/// Probe vLLM's `/version` endpoint.
-async fn try_vllm_version(
+/// Probe a runtime-specific endpoint; succeeds on any 2xx.
+async fn probe_endpoint(
     url: &str,
+    path: &str,
     timeout_secs: u64,
     client: &Client,
     api_key: Option<&str>,
 ) -> Result<(), String> {
-    let version_url = format!("{}/version", http_base_url(url));
+    let probe_url = format!("{}{path}", http_base_url(url));
 
     let mut req = client
-        .get(&version_url)
+        .get(&probe_url)
         .timeout(Duration::from_secs(timeout_secs));
     if let Some(key) = api_key {
         req = req.bearer_auth(key);
@@ -128,38 +133,10 @@ async fn try_vllm_version(
     let response = req
         .send()
         .await
-        .map_err(|e| format!("Failed to reach {version_url}: {e}"))?;
+        .map_err(|e| format!("Failed to reach {probe_url}: {e}"))?;
 
     if !response.status().is_success() {
-        return Err(format!("/version returned {}", response.status()));
-    }
-
-    Ok(())
-}
-
-/// Probe SGLang's `/server_info` endpoint.
-async fn try_sglang_server_info(
-    url: &str,
-    timeout_secs: u64,
-    client: &Client,
-    api_key: Option<&str>,
-) -> Result<(), String> {
-    let info_url = format!("{}/server_info", http_base_url(url));
-
-    let mut req = client
-        .get(&info_url)
-        .timeout(Duration::from_secs(timeout_secs));
-    if let Some(key) = api_key {
-        req = req.bearer_auth(key);
-    }
-
-    let response = req
-        .send()
-        .await
-        .map_err(|e| format!("Failed to reach {info_url}: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("/server_info returned {}", response.status()));
+        return Err(format!("{path} returned {}", response.status()));
     }
 
     Ok(())
@@ -169,7 +146,8 @@ async fn try_sglang_server_info(
 ///
 /// Strategy:
 /// 1. Primary: `GET /v1/models` → check `owned_by` field
-/// 2. Fallback: probe `/version` (vLLM) and `/server_info` (SGLang) in parallel
+/// 2. Fallback: probe `/version` (vLLM), `/props` (llama.cpp), `/api/version`
+///    (Ollama) and `/server_info` (SGLang) in parallel
 async fn detect_http_backend(
     url: &str,
     timeout_secs: u64,
@@ -193,11 +171,14 @@ async fn detect_http_backend(
     // Strategy 2: probe unique endpoints in parallel.
     // /version is unique to vLLM. /server_info is NOT unique to SGLang — vLLM can
     // also expose it. So /version takes priority: if it succeeds, it's definitely vLLM
-    // regardless of whether /server_info also succeeds. We only conclude SGLang if
-    // /server_info succeeds and /version does not.
-    let (vllm_result, sglang_result) = tokio::join!(
-        try_vllm_version(url, timeout_secs, client, api_key),
-        try_sglang_server_info(url, timeout_secs, client, api_key),
+    // regardless of whether /server_info also succeeds. /props (llama.cpp) and
+    // /api/version (Ollama) are unique to their servers. We only conclude SGLang if
+    // /server_info succeeds and nothing more specific does.
+    let (vllm_result, llamacpp_result, ollama_result, sglang_result) = tokio::join!(
+        probe_endpoint(url, "/version", timeout_secs, client, api_key),
+        probe_endpoint(url, "/props", timeout_secs, client, api_key),
+        probe_endpoint(url, "/api/version", timeout_secs, client, api_key),
+        probe_endpoint(url, "/server_info", timeout_secs, client, api_key),
     );
 
     if vllm_result.is_ok() {
@@ -209,19 +190,27 @@ async fn detect_http_backend(
         }
         return Ok("vllm".to_string());
     }
+    if llamacpp_result.is_ok() {
+        debug!("Detected HTTP backend via /props: llamacpp");
+        return Ok("llamacpp".to_string());
+    }
+    if ollama_result.is_ok() {
+        debug!("Detected HTTP backend via /api/version: ollama");
+        return Ok("ollama".to_string());
+    }
     if sglang_result.is_ok() {
         debug!("Detected HTTP backend via /server_info (no /version): sglang");
         return Ok("sglang".to_string());
     }
 
     Err(format!(
-        "Could not detect HTTP backend for {url} (tried /v1/models, /version, /server_info)"
+        "Could not detect HTTP backend for {url} (tried /v1/models, /version, /props, /api/version, /server_info)"
     ))
 }
 
 // ─── Step implementation ───────────────────────────────────────────────────
 
-/// Step 2: Detect backend runtime type (sglang, vllm, trtllm, mlx).
+/// Step 2: Detect backend runtime type (sglang, vllm, trtllm, mlx, llamacpp, ollama).
 ///
 /// Runs after `detect_connection_mode` and before `discover_metadata`.
 /// Sets `detected_runtime_type` in workflow data for all downstream steps.
@@ -279,12 +268,14 @@ impl StepExecutor<WorkerWorkflowData> for DetectBackendStep {
                         message: format!("HTTP backend detection failed for {}: {}", config.url, e),
                     })?
             }
-            ConnectionMode::Grpc => detect_grpc_backend(&config.url, timeout, None)
-                .await
-                .map_err(|e| WorkflowError::StepFailed {
-                    step_id: wfaas::StepId::new("detect_backend"),
-                    message: format!("gRPC backend detection failed for {}: {}", config.url, e),
-                })?,
+            ConnectionMode::Grpc => {
+                detect_grpc_backend(&config.url, timeout, None, config.grpc_tls.as_ref())
+                    .await
+                    .map_err(|e| WorkflowError::StepFailed {
+                        step_id: wfaas::StepId::new("detect_backend"),
+                        message: format!("gRPC backend detection failed for {}: {}", config.url, e),
+                    })?
+            }
         };
 
         debug!(
@@ -307,7 +298,7 @@ mod tests {
     use serde_json::json;
     use tokio::net::TcpListener;
 
-    use super::detect_via_models_endpoint;
+    use super::{detect_http_backend, detect_via_models_endpoint};
 
     #[tokio::test]
     async fn detect_via_models_endpoint_maps_nvidia_to_sglang() {
@@ -342,4 +333,37 @@ mod tests {
 
         assert_eq!(runtime, "sglang");
     }
+
+    #[tokio::test]
+    async fn detect_http_backend_probes_llamacpp_props() {
+        async fn models() -> Json<serde_json::Value> {
+            Json(json!({
+                "data": [{"id": "qwen2.5-7b-instruct-q4_k_m.gguf", "object": "model", "owned_by": "library"}],
+                "object": "list"
+            }))
+        }
+        async fn props() -> Json<serde_json::Value> {
+            Json(json!({"total_slots": 4, "build_info": "b5000-abcdef"}))
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        #[expect(
+            clippy::disallowed_methods,
+            reason = "test-only mock llama.cpp server; handle is aborted at test end"
+        )]
+        let server = tokio::spawn(async move {
+            let app = Router::new()
+                .route("/v1/models", get(models))
+                .route("/props", get(props));
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let runtime = detect_http_backend(&format!("http://{addr}"), 5, &Client::new(), None)
+            .await
+            .unwrap();
+        server.abort();
+
+        assert_eq!(runtime, "llamacpp");
+    }
 }
@@ -65,7 +65,9 @@ impl StepExecutor<WorkerWorkflowData> for DetectConnectionModeStep {
         if let Some(connection_mode) = explicit_connection_mode(&url) {
             let result = match connection_mode {
                 ConnectionMode::Http => try_http_reachable(&url, timeout, client).await,
-                ConnectionMode::Grpc => try_grpc_reachable(&url, timeout).await,
+                ConnectionMode::Grpc => {
+                    try_grpc_reachable(&url, timeout, config.grpc_tls.as_ref()).await
+                }
             };
 
             match result {
@@ -91,7 +93,7 @@ impl StepExecutor<WorkerWorkflowData> for DetectConnectionModeStep {
 
         let (http_result, grpc_result) = tokio::join!(
             try_http_reachable(&url, timeout, client),
-            try_grpc_reachable(&url, timeout)
+            try_grpc_reachable(&url, timeout, config.grpc_tls.as_ref())
         );
 
         let connection_mode = match (http_result, grpc_result) {
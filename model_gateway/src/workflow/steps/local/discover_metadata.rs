@@ -4,6 +4,7 @@ use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
+use openai_protocol::worker::GrpcTlsConfig;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
@@ -30,7 +31,8 @@ static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
 });
 
 // ---------------------------------------------------------------------------
-// HTTP response structs (sglang /server_info, /model_info; vllm /v1/models)
+// HTTP response structs (sglang /server_info, /model_info; vllm /v1/models;
+// llama.cpp /props)
 // ---------------------------------------------------------------------------
 
 /// SGLang `/server_info` response — curated subset of the full response (~800 fields).
@@ -85,12 +87,28 @@ pub(super) struct ModelsResponse {
     pub data: Vec<ModelsResponseEntry>,
 }
 
-/// vLLM `/version` response.
+/// vLLM `/version` and Ollama `/api/version` response.
 #[derive(Debug, Deserialize)]
 struct VersionResponse {
     version: String,
 }
 
+/// llama.cpp `/props` response — the fields worth labels.
+#[derive(Debug, Deserialize)]
+struct LlamaCppProps {
+    /// Parallel decoding slots (`--parallel`); requests beyond them queue.
+    total_slots: Option<usize>,
+    model_path: Option<String>,
+    build_info: Option<String>,
+    default_generation_settings: Option<LlamaCppGenerationSettings>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlamaCppGenerationSettings {
+    /// Context of one slot: the server's context split across its slots.
+    n_ctx: Option<usize>,
+}
+
 // ---------------------------------------------------------------------------
 // HTTP fetchers
 // ---------------------------------------------------------------------------
@@ -243,26 +261,97 @@ async fn fetch_vllm_http_metadata(url: &str, api_key: Option<&str>) -> HashMap<S
     labels
 }
 
+/// Served model identity from `/v1/models`, for servers whose `id` is the
+/// served name and that report no model path there.
+async fn models_identity_labels(base: &str, api_key: Option<&str>) -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    if let Ok(models) = http_get_json::<ModelsResponse>(&format!("{base}/v1/models"), api_key).await
+    {
+        if let Some(id) = models
+            .data
+            .first()
+            .and_then(|m| m.id.as_ref())
+            .filter(|id| !id.is_empty())
+        {
+            labels.insert("served_model_name".to_string(), id.clone());
+        }
+    }
+    labels
+}
+
+fn llamacpp_labels(props: LlamaCppProps) -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    // Slots are llama.cpp's concurrency cap, so they feed the same capacity
+    // label SGLang's `--max-running-requests` does.
+    if let Some(slots) = props.total_slots.filter(|&n| n > 0) {
+        labels.insert("max_running_requests".to_string(), slots.to_string());
+    }
+    if let Some(path) = props.model_path.filter(|p| !p.is_empty()) {
+        labels.insert("model_path".to_string(), path);
+    }
+    if let Some(build) = props.build_info.filter(|b| !b.is_empty()) {
+        labels.insert("version".to_string(), build);
+    }
+    if let Some(n_ctx) = props
+        .default_generation_settings
+        .and_then(|settings| settings.n_ctx)
+        .filter(|&n| n > 0)
+    {
+        labels.insert("max_model_len".to_string(), n_ctx.to_string());
+    }
+    labels
+}
+
+async fn fetch_llamacpp_http_metadata(url: &str, api_key: Option<&str>) -> HashMap<String, String> {
+    let base = http_base_url(url);
+    let mut labels = models_identity_labels(&base, api_key).await;
+    if let Ok(props) = http_get_json::<LlamaCppProps>(&format!("{base}/props"), api_key).await {
+        labels.extend(llamacpp_labels(props));
+    }
+    labels
+}
+
+async fn fetch_ollama_http_metadata(url: &str, api_key: Option<&str>) -> HashMap<String, String> {
+    let base = http_base_url(url);
+    let mut labels = models_identity_labels(&base, api_key).await;
+    if let Ok(v) = http_get_json::<VersionResponse>(&format!("{base}/api/version"), api_key).await {
+        if !v.version.is_empty() {
+            labels.insert("version".to_string(), v.version);
+        }
+    }
+    labels
+}
+
 async fn fetch_grpc_metadata(
     url: &str,
     runtime_type: &str,
+    tls: Option<&GrpcTlsConfig>,
 ) -> Result<(HashMap<String, String>, String), String> {
     let grpc_url = grpc_base_url(url);
 
-    let client = GrpcClient::connect(&grpc_url, runtime_type)
+    let mut client = GrpcClient::connect(&grpc_url, runtime_type, tls)
         .await
         .map_err(|e| format!("Failed to connect to gRPC: {e}"))?;
 
+    // Server info first: it carries the proto revision that decides which
+    // compatibility shims apply to the model info below.
+    let server_labels = match client.get_server_info().await {
+        Ok(info) => {
+            client.negotiate_proto(&info);
+            info.to_labels()
+        }
+        Err(e) => {
+            warn!("Failed to fetch gRPC server info: {}", e);
+            HashMap::new()
+        }
+    };
+
     let mut labels = client
         .get_model_info()
         .await
         .map_err(|e| format!("Failed to fetch gRPC model info: {e}"))?
         .to_labels();
-
-    match client.get_server_info().await {
-        Ok(info) => labels.extend(info.to_labels()),
-        Err(e) => warn!("Failed to fetch gRPC server info: {}", e),
-    }
+    labels.extend(server_labels);
 
     normalize_grpc_keys(&mut labels);
     Ok((labels, runtime_type.to_string()))
@@ -358,6 +447,15 @@ impl StepExecutor<WorkerWorkflowData> for DiscoverMetadataStep {
                     "vllm" => {
                         fetch_vllm_http_metadata(&config.url, config.api_key.as_deref()).await
                     }
+                    "llamacpp" => {
+                        fetch_llamacpp_http_metadata(&config.url, config.api_key.as_deref()).await
+                    }
+                    "ollama" => {
+                        fetch_ollama_http_metadata(&config.url, config.api_key.as_deref()).await
+                    }
+                    // A federated gateway has no engine metadata; its models
+                    // arrive through the federation catalog import.
+                    "smg" => HashMap::new(),
                     _ => fetch_sglang_http_metadata(&config.url, config.api_key.as_deref()).await,
                 };
                 Ok((labels, None))
@@ -369,7 +467,7 @@ impl StepExecutor<WorkerWorkflowData> for DiscoverMetadataStep {
                     .detected_runtime_type
                     .as_deref()
                     .unwrap_or(&config_runtime);
-                fetch_grpc_metadata(&config.url, runtime_type)
+                fetch_grpc_metadata(&config.url, runtime_type, config.grpc_tls.as_ref())
                     .await
                     .map(|(labels, rt)| (labels, Some(rt)))
             }
@@ -432,7 +530,7 @@ mod tests {
     #[tokio::test]
     #[ignore]
     async fn test_sglang_grpc_metadata() {
-        let (labels, _) = fetch_grpc_metadata("grpc://0.0.0.0:30001", "sglang")
+        let (labels, _) = fetch_grpc_metadata("grpc://0.0.0.0:30001", "sglang", None)
             .await
             .expect("grpc metadata");
         dump_labels("SGLang gRPC", &labels);
@@ -442,7 +540,7 @@ mod tests {
     #[tokio::test]
     #[ignore]
     async fn test_vllm_grpc_metadata() {
-        let (labels, _) = fetch_grpc_metadata("grpc://0.0.0.0:20001", "vllm")
+        let (labels, _) = fetch_grpc_metadata("grpc://0.0.0.0:20001", "vllm", None)
             .await
             .expect("grpc metadata");
         dump_labels("vLLM gRPC", &labels);
@@ -485,6 +583,44 @@ mod tests {
         assert!(!labels.contains_key("max_running_requests"));
     }
 
+    #[test]
+    fn test_llamacpp_props_map_slots_to_capacity() {
+        let body = serde_json::json!({
+            "total_slots": 4,
+            "model_path": "/models/qwen2.5-7b-instruct-q4_k_m.gguf",
+            "build_info": "b5000-abcdef",
+            "default_generation_settings": {"n_ctx": 8192, "temperature": 0.8},
+            "chat_template": "...",
+        });
+        let props: LlamaCppProps = serde_json::from_value(body).expect("deserialize props");
+        let labels = llamacpp_labels(props);
+
+        assert_eq!(
+            labels.get("max_running_requests").map(String::as_str),
+            Some("4")
+        );
+        assert_eq!(
+            labels.get("max_model_len").map(String::as_str),
+            Some("8192")
+        );
+        assert_eq!(
+            labels.get("version").map(String::as_str),
+            Some("b5000-abcdef")
+        );
+        assert_eq!(
+            labels.get("model_path").map(String::as_str),
+            Some("/models/qwen2.5-7b-instruct-q4_k_m.gguf")
+        );
+
+        let labels = llamacpp_labels(LlamaCppProps {
+            total_slots: Some(0),
+            model_path: None,
+            build_info: None,
+            default_generation_settings: None,
+        });
+        assert!(labels.is_empty());
+    }
+
     #[tokio::test]
     async fn test_sglang_http_metadata_uses_models_identity() {
         use axum::{routing::get, Json, Router};
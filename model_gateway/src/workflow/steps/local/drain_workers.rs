@@ -127,13 +127,17 @@ mod tests {
     fn make_app_context(workers: &[Arc<dyn Worker>]) -> Arc<AppContext> {
         use crate::{
             config::RouterConfig,
-            middleware::TokenBucket,
+            middleware::{RoutingRules, TokenBucket},
             observability::inflight_tracker::InFlightRequestTracker,
             routers::{
                 common::{openai_bridge, realtime::RealtimeRegistry},
                 grpc::multimodal::MultimodalConfigRegistry,
+                http::pd_pairs::PdPairTracker,
+            },
+            worker::{
+                FederationCatalog, MaintenanceController, StandbyController, WorkerDebugTracer,
+                WorkerRegistry, WorkerService,
             },
-            worker::{WorkerRegistry, WorkerService},
         };
 
         let router_config = RouterConfig::builder()
@@ -161,6 +165,13 @@ mod tests {
             conversation_item_storage: Arc::new(
                 smg_data_connector::MemoryConversationItemStorage::new(),
             ),
+            debug_capture_storage: None,
+            file_storage: None,
+            vector_store_storage: None,
+            chat_completion_storage: None,
+            tenant_mcp_server_storage: None,
+            prompt_storage: None,
+            generation_job_storage: None,
             worker_monitor: None,
             configured_reasoning_parser: None,
             configured_tool_parser: None,
@@ -170,8 +181,24 @@ mod tests {
             mcp_format_registry: openai_bridge::FormatRegistry::new(),
             tokenizer_registry: Arc::new(llm_tokenizer::registry::TokenizerRegistry::new()),
             multimodal_config_registry: Arc::new(MultimodalConfigRegistry::new()),
+            multimodal_model_registry: Arc::new(llm_multimodal::ModelRegistry::default()),
             wasm_manager: None,
-            worker_service: Arc::new(WorkerService::new(registry, job_queue, router_config)),
+            worker_service: Arc::new(WorkerService::new(
+                Arc::clone(&registry),
+                job_queue,
+                router_config.clone(),
+            )),
+            federation_catalog: FederationCatalog::new(
+                &router_config.federation,
+                Arc::clone(&registry),
+                reqwest::Client::new(),
+            ),
+            standby: StandbyController::new(&router_config.standby, Arc::clone(&registry)),
+            maintenance: MaintenanceController::new(&router_config.maintenance, registry),
+            worker_debug_tracer: WorkerDebugTracer::new(),
+            prompt_guard: None,
+            routing_rules: Arc::new(RoutingRules::new(&router_config.routing_rules)),
+            pd_pairs: PdPairTracker::new(&router_config.pd_pairs),
             inflight_tracker: InFlightRequestTracker::new(),
             kv_event_monitor: None,
             realtime_registry: Arc::new(RealtimeRegistry::new()),
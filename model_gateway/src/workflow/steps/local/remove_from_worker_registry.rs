@@ -67,6 +67,11 @@ impl StepExecutor<WorkerRemovalWorkflowData> for RemoveFromWorkerRegistryStep {
             {
                 removed_count += 1;
             }
+            // Tokenizers are shared by fingerprint; the last worker holding
+            // one takes it (and its multimodal config) with it.
+            for entry in app_context.tokenizer_registry.release(worker_url) {
+                app_context.multimodal_config_registry.remove(&entry.id);
+            }
         }
 
         // Log if some workers were already removed (e.g., by another process)
@@ -111,8 +111,9 @@ impl StepExecutor<WorkerWorkflowData> for SubmitTokenizerJobStep {
             };
 
             // Note: We don't check if tokenizer already exists here.
-            // The registry.load() handles deduplication gracefully (returns AlreadyExists).
-            // This simplifies the code and ensures consistent behavior.
+            // The registry handles deduplication gracefully (returns AlreadyExists),
+            // shares tokenizers across workers with the same fingerprint, and
+            // records this worker as a holder so removal can evict it.
 
             info!(
                 "Submitting tokenizer registration job for model {} from {}",
@@ -127,6 +128,7 @@ impl StepExecutor<WorkerWorkflowData> for SubmitTokenizerJobStep {
                 chat_template_path: chat_template.clone(),
                 cache_config: cache_config.clone(),
                 fail_on_duplicate: false,
+                worker_url: Some(worker.url().to_string()),
             };
 
             // Submit job (fire-and-forget, don't wait for completion)
@@ -2,6 +2,7 @@
 
 use std::time::Duration;
 
+use openai_protocol::worker::GrpcTlsConfig;
 use reqwest::Client;
 
 use crate::routers::grpc::client::GrpcClient;
@@ -97,8 +98,9 @@ pub(crate) async fn do_grpc_health_check(
     grpc_url: &str,
     timeout_secs: u64,
     runtime_type: &str,
+    tls: Option<&GrpcTlsConfig>,
 ) -> Result<(), String> {
-    let connect_future = GrpcClient::connect(grpc_url, runtime_type);
+    let connect_future = GrpcClient::connect(grpc_url, runtime_type, tls);
     let client = tokio::time::timeout(Duration::from_secs(timeout_secs), connect_future)
         .await
         .map_err(|_| "gRPC connection timeout".to_string())?
@@ -117,15 +119,19 @@ pub(crate) async fn do_grpc_health_check(
 ///
 /// We don't care which runtime it is here — that's `DetectBackendStep`'s job.
 /// We just need to know: does this endpoint speak gRPC at all?
-pub(crate) async fn try_grpc_reachable(url: &str, timeout_secs: u64) -> Result<(), String> {
+pub(crate) async fn try_grpc_reachable(
+    url: &str,
+    timeout_secs: u64,
+    tls: Option<&GrpcTlsConfig>,
+) -> Result<(), String> {
     let grpc_url = grpc_reachable_url(url)?;
 
     let (sglang, vllm, trtllm, mlx, tokenspeed) = tokio::join!(
-        do_grpc_health_check(&grpc_url, timeout_secs, "sglang"),
-        do_grpc_health_check(&grpc_url, timeout_secs, "vllm"),
-        do_grpc_health_check(&grpc_url, timeout_secs, "trtllm"),
-        do_grpc_health_check(&grpc_url, timeout_secs, "mlx"),
-        do_grpc_health_check(&grpc_url, timeout_secs, "tokenspeed"),
+        do_grpc_health_check(&grpc_url, timeout_secs, "sglang", tls),
+        do_grpc_health_check(&grpc_url, timeout_secs, "vllm", tls),
+        do_grpc_health_check(&grpc_url, timeout_secs, "trtllm", tls),
+        do_grpc_health_check(&grpc_url, timeout_secs, "mlx", tls),
+        do_grpc_health_check(&grpc_url, timeout_secs, "tokenspeed", tls),
     );
 
     match (sglang, vllm, trtllm, mlx, tokenspeed) {
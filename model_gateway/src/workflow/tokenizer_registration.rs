@@ -7,7 +7,7 @@
 //! (startup, worker connection, API) should use this workflow to ensure consistent
 //! behavior (validation, caching, deduplication).
 
-use std::{sync::Arc, time::Duration};
+use std::{fmt::Write as _, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use llm_tokenizer::{
@@ -17,6 +17,7 @@ use llm_tokenizer::{
     traits::Tokenizer,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use smg_grpc_client::{tokenizer_bundle, tokenizer_bundle::StreamBundle};
 use tracing::{debug, error, info, warn};
 use wfaas::{
@@ -53,6 +54,31 @@ pub struct TokenizerConfigRequest {
     /// API callers should set this to true.
     #[serde(default)]
     pub fail_on_duplicate: bool,
+    /// Worker this tokenizer is loaded for. When set, the tokenizer is shared
+    /// with every other request of the same fingerprint and evicted once no
+    /// worker holds it; when unset (API, startup) it stays until removed.
+    #[serde(default)]
+    pub worker_url: Option<String>,
+}
+
+impl TokenizerConfigRequest {
+    /// Identity of what this request would load: source, chat template, and
+    /// cache layering. Requests with equal fingerprints share one tokenizer.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.source.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.chat_template_path.as_deref().unwrap_or_default());
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(&self.cache_config).unwrap_or_default());
+        hasher
+            .finalize()
+            .iter()
+            .fold(String::with_capacity(64), |mut acc, b| {
+                let _ = write!(acc, "{b:02x}");
+                acc
+            })
+    }
 }
 
 /// Configuration for removing a tokenizer
@@ -110,43 +136,62 @@ impl StepExecutor<TokenizerWorkflowData> for LoadTokenizerStep {
 
         // Load the tokenizer using the registry's load method
         // This handles: validation, deduplication, and loading
-        let result = app_context
-            .tokenizer_registry
-            .load(&id, &name, &source, || {
-                let source = source.clone();
-                let chat_template = chat_template.clone();
-                let cache_cfg = cache_config.clone();
-                let app_context = app_context.clone();
-                let name = name.clone();
-                let id = id.clone();
-                async move {
-                    let base_tokenizer = match factory::create_tokenizer_async_with_chat_template(
-                        &source,
-                        chat_template.as_deref(),
-                    )
-                    .await
-                    {
-                        Ok(tok) => tok,
-                        Err(local_err) => {
-                            debug!(
+        let loader = || {
+            let source = source.clone();
+            let chat_template = chat_template.clone();
+            let cache_cfg = cache_config.clone();
+            let app_context = app_context.clone();
+            let name = name.clone();
+            let id = id.clone();
+            async move {
+                let base_tokenizer = match factory::create_tokenizer_async_with_chat_template(
+                    &source,
+                    chat_template.as_deref(),
+                )
+                .await
+                {
+                    Ok(tok) => tok,
+                    Err(local_err) => {
+                        debug!(
                                 "Local tokenizer load failed for source '{}', attempting to fetch from worker. Error: {:?}",
                                 source, local_err
                             );
 
-                            fetch_tokenizer_from_worker(&app_context, &id, &name).await.map_err(
+                        fetch_tokenizer_from_worker(&app_context, &id, &name).await.map_err(
                                 |worker_err| {
                                     format!(
                                         "Failed to load tokenizer locally ({local_err}) and remotely from worker ({worker_err})"
                                     )
                                 },
                             )?
-                        }
-                    };
+                    }
+                };
 
-                    Ok(with_optional_cache(base_tokenizer, cache_cfg))
-                }
-            })
-            .await;
+                Ok(with_optional_cache(base_tokenizer, cache_cfg))
+            }
+        };
+        // Worker-driven loads are shared by fingerprint across workers
+        let result = match &config.worker_url {
+            Some(worker_url) => {
+                app_context
+                    .tokenizer_registry
+                    .load_shared(
+                        &id,
+                        &name,
+                        &source,
+                        &config.fingerprint(),
+                        worker_url,
+                        loader,
+                    )
+                    .await
+            }
+            None => {
+                app_context
+                    .tokenizer_registry
+                    .load(&id, &name, &source, loader)
+                    .await
+            }
+        };
 
         match result {
             Ok(outcome) => {
@@ -165,6 +210,13 @@ impl StepExecutor<TokenizerWorkflowData> for LoadTokenizerStep {
                             name, id, vocab_size
                         );
                     }
+                    LoadOutcome::Shared { id } => {
+                        share_multimodal_config(&app_context, id);
+                        info!(
+                            "Registered tokenizer '{}' (id: {}) sharing an identical loaded tokenizer",
+                            name, id
+                        );
+                    }
                     LoadOutcome::AlreadyExists { id } => {
                         if config.fail_on_duplicate {
                             return Err(WorkflowError::StepFailed {
@@ -201,6 +253,25 @@ impl StepExecutor<TokenizerWorkflowData> for LoadTokenizerStep {
     }
 }
 
+/// Copy a bundle-preloaded multimodal config to a tokenizer registered by
+/// sharing, whose loader (and so whose bundle preload) never ran.
+fn share_multimodal_config(app_context: &AppContext, id: &str) {
+    let registry = &app_context.tokenizer_registry;
+    let Some(fingerprint) = registry.get_by_id(id).and_then(|e| e.fingerprint) else {
+        return;
+    };
+    let config = registry
+        .list()
+        .into_iter()
+        .filter(|e| e.id != id && e.fingerprint.as_deref() == Some(fingerprint.as_str()))
+        .find_map(|e| app_context.multimodal_config_registry.get(&e.id));
+    if let Some(config) = config {
+        app_context
+            .multimodal_config_registry
+            .insert(id.to_string(), config);
+    }
+}
+
 fn with_optional_cache(
     tokenizer: Arc<dyn Tokenizer>,
     cache_cfg: Option<TokenizerCacheConfig>,
@@ -447,6 +518,7 @@ mod tests {
             chat_template_path: None,
             cache_config: None,
             fail_on_duplicate: false,
+            worker_url: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -481,6 +553,7 @@ mod tests {
             chat_template_path: None,
             cache_config: None,
             fail_on_duplicate: true,
+            worker_url: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -502,6 +575,7 @@ mod tests {
                 l1_max_memory: 0,
             }),
             fail_on_duplicate: false,
+            worker_url: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -514,6 +588,27 @@ mod tests {
         assert!(!cache.enable_l1);
     }
 
+    #[test]
+    fn test_fingerprint_ignores_name_and_id() {
+        let request = |id: &str, name: &str, chat_template: Option<&str>| TokenizerConfigRequest {
+            id: id.to_string(),
+            name: name.to_string(),
+            source: "meta-llama/Llama-2-7b-hf".to_string(),
+            chat_template_path: chat_template.map(str::to_string),
+            cache_config: None,
+            fail_on_duplicate: false,
+            worker_url: Some(format!("http://{name}:8000")),
+        };
+
+        let a = request("id-a", "replica-a", None);
+        let b = request("id-b", "replica-b", None);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.fingerprint().len(), 64);
+
+        let templated = request("id-c", "replica-a", Some("/templates/chat.jinja"));
+        assert_ne!(a.fingerprint(), templated.fingerprint());
+    }
+
     #[test]
     fn test_workflow_creation() {
         let mut workflow = create_tokenizer_registration_workflow();
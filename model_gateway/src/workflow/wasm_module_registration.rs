@@ -17,7 +17,11 @@ use wfaas::{
 use super::data::WasmRegistrationWorkflowData;
 use crate::{
     app_context::AppContext,
-    wasm::module::{WasmModule, WasmModuleDescriptor, WasmModuleMeta},
+    wasm::{
+        body_fields::validate_body_fields,
+        guest_kv::validate_tenant,
+        module::{WasmModule, WasmModuleDescriptor, WasmModuleMeta},
+    },
 };
 
 /// WASM module registration request
@@ -62,6 +66,7 @@ fn has_wasm_extension(path: &Path) -> bool {
 /// - File path is not empty
 /// - File exists and is readable
 /// - File size is not zero
+/// - Declared request body fields are JSON pointers
 pub struct ValidateDescriptorStep;
 
 #[async_trait]
@@ -148,6 +153,20 @@ impl StepExecutor<WasmRegistrationWorkflowData> for ValidateDescriptorStep {
             });
         }
 
+        if let Some(fields) = &descriptor.request_body_fields {
+            validate_body_fields(fields).map_err(|message| WorkflowError::StepFailed {
+                step_id: StepId::new("validate_descriptor"),
+                message,
+            })?;
+        }
+
+        if let Some(tenant) = &descriptor.tenant {
+            validate_tenant(tenant).map_err(|message| WorkflowError::StepFailed {
+                step_id: StepId::new("validate_descriptor"),
+                message,
+            })?;
+        }
+
         // Check if file exists and get size
         let metadata = tokio::fs::metadata(&descriptor.file_path)
             .await
@@ -496,6 +515,8 @@ impl StepExecutor<WasmRegistrationWorkflowData> for RegisterModuleStep {
                 last_accessed_at: now,
                 access_count: 0,
                 attach_points: descriptor.attach_points.clone(),
+                request_body_fields: descriptor.request_body_fields.clone(),
+                tenant: descriptor.tenant.clone(),
                 wasm_bytes,
             },
         };
@@ -119,6 +119,7 @@ async fn test_non_streaming_mcp_minimal_e2e_with_persistence() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -321,6 +322,7 @@ async fn test_non_streaming_mcp_e2e_accepts_forwardable_request_headers() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -464,6 +466,7 @@ async fn test_non_streaming_mcp_returns_approval_request_when_required() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -598,6 +601,7 @@ async fn test_final_response_hides_internal_mcp_trace_items() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -733,6 +737,7 @@ async fn test_previous_response_id_does_not_repeat_mcp_list_tools_for_existing_b
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -788,6 +793,7 @@ async fn test_previous_response_id_does_not_repeat_mcp_list_tools_for_existing_b
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -912,6 +918,7 @@ async fn test_final_response_hides_internal_mcp_error_details() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -1055,6 +1062,7 @@ fn test_responses_request_creation() {
         top_k: -1,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -1104,6 +1112,7 @@ fn test_responses_request_sglang_extensions() {
         top_k: 10,
         min_p: 0.05,
         repetition_penalty: 1.1,
+        seed: None,
         conversation: None,
     };
 
@@ -1249,6 +1258,7 @@ fn test_json_serialization() {
         top_k: 50,
         min_p: 0.1,
         repetition_penalty: 1.2,
+        seed: None,
         conversation: None,
     };
 
@@ -1363,6 +1373,7 @@ async fn test_multi_turn_loop_with_mcp() {
         top_k: 50,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -1526,6 +1537,7 @@ async fn test_max_tool_calls_limit() {
         top_k: 50,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -1716,6 +1728,7 @@ async fn test_streaming_with_mcp_tool_calls() {
         top_k: 50,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -2004,6 +2017,7 @@ async fn test_streaming_multi_turn_with_mcp() {
         top_k: 50,
         min_p: 0.0,
         repetition_penalty: 1.0,
+        seed: None,
         conversation: None,
     };
 
@@ -436,6 +436,8 @@ pub fn create_test_context(
             warmup: vec![],
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
         let mcp_orchestrator = McpOrchestrator::new(empty_config)
             .await
@@ -583,6 +585,8 @@ pub fn create_test_context_with_parsers(
             warmup: vec![],
             inventory: Default::default(),
             policy: Default::default(),
+            limits: Default::default(),
+            sampling: Default::default(),
         };
         let mcp_orchestrator = McpOrchestrator::new(empty_config)
             .await
@@ -107,6 +107,7 @@ pub fn create_test_app(
         router_manager: None,
         mesh_handler: None,
         mesh_adapters: None,
+        rolling_restart: None,
     });
 
     // Configure request ID headers (use defaults if not specified)
@@ -172,6 +173,7 @@ pub fn create_test_app_with_context(
         router_manager: None,
         mesh_handler: None,
         mesh_adapters: None,
+        rolling_restart: None,
     });
 
     // Get config from the context
@@ -233,6 +235,8 @@ pub async fn create_test_app_context() -> Arc<AppContext> {
         warmup: vec![],
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
     let mcp_orchestrator = McpOrchestrator::new(empty_config)
         .await
@@ -16,9 +16,9 @@ use openai_protocol::responses::{ResponseOutputItem, WebSearchAction};
 use serde_json::json;
 use smg::routers::common::openai_bridge::{transform_tool_output, ResponseFormat};
 use smg_mcp::{
-    core::config::{ResponseFormatConfig, ToolConfig},
+    core::config::{McpPoolConfig, ResponseFormatConfig, ToolConfig},
     McpConfig, McpOrchestrator, McpServerBinding, McpServerConfig, McpToolSession, McpTransport,
-    ToolExecutionInput,
+    TenantContext, ToolExecutionInput,
 };
 
 /// Create a new mock server for testing (each test gets its own)
@@ -49,6 +49,8 @@ async fn test_mcp_server_initialization() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     // Should succeed but with no connected servers (empty config is allowed)
@@ -86,6 +88,8 @@ async fn test_server_connection_with_mock() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let result = McpOrchestrator::new(config).await;
@@ -130,6 +134,8 @@ async fn test_tool_availability_checking() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -198,6 +204,8 @@ async fn test_multi_server_connection() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     // Note: This will fail to connect to both servers in the current implementation
@@ -239,6 +247,8 @@ async fn test_tool_execution_with_mock() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -310,6 +320,8 @@ async fn test_web_search_transform_handles_openai_search_response_with_mock() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -385,6 +397,8 @@ async fn test_web_search_transform_sets_action_query_for_brave_search_with_mock(
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -450,6 +464,8 @@ async fn test_concurrent_tool_execution() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -513,6 +529,8 @@ async fn test_tool_execution_errors() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -565,6 +583,8 @@ async fn test_connection_without_server() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let result = McpOrchestrator::new(config).await;
@@ -605,6 +625,8 @@ async fn test_tool_info_structure() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     let manager = McpOrchestrator::new(config).await.unwrap();
@@ -652,6 +674,8 @@ async fn test_sse_connection() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     // Manager succeeds but no servers are connected (errors are logged)
@@ -722,6 +746,86 @@ async fn test_transport_types() {
     assert_eq!(stdio_config.name, "stdio_server");
 }
 
+#[tokio::test]
+async fn test_dynamic_server_tools_follow_tenant_connections() {
+    let mock_server = create_mock_server().await;
+
+    // A single pooled connection: each tenant's connect evicts the other's.
+    let manager = McpOrchestrator::new(McpConfig {
+        pool: McpPoolConfig {
+            max_connections: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await
+    .expect("Should create orchestrator");
+    let server_config = McpServerConfig {
+        name: "dynamic".to_string(),
+        transport: McpTransport::Streamable {
+            url: mock_server.url(),
+            token: None,
+            headers: HashMap::new(),
+        },
+        proxy: None,
+        required: false,
+        tools: None,
+        builtin_type: None,
+        builtin_tool_name: None,
+        internal: false,
+    };
+    let (acme, globex) = (TenantContext::new("acme"), TenantContext::new("globex"));
+    let session_tools = |server_key: &str, tenant: &TenantContext| {
+        let binding = McpServerBinding {
+            label: "dynamic".to_string(),
+            server_key: server_key.to_string(),
+            allowed_tools: None,
+        };
+        McpToolSession::new_for_tenant(
+            &manager,
+            vec![binding],
+            "test-request",
+            HashMap::new(),
+            tenant.clone(),
+        )
+        .mcp_tools()
+        .len()
+    };
+
+    let server_key = manager
+        .connect_dynamic_server_with_tenant(server_config.clone(), Some("acme".to_string()))
+        .await
+        .expect("acme should connect");
+    assert_eq!(session_tools(&server_key, &acme), 2);
+    assert_eq!(session_tools(&server_key, &globex), 0);
+    assert!(manager
+        .get_tool(&server_key, "brave_web_search", Some(&globex))
+        .is_none());
+
+    // globex's connection evicts acme's through the pool eviction callback.
+    manager
+        .connect_dynamic_server_with_tenant(server_config.clone(), Some("globex".to_string()))
+        .await
+        .expect("globex should connect");
+    assert_eq!(session_tools(&server_key, &globex), 2);
+    assert_eq!(session_tools(&server_key, &acme), 0);
+    assert!(manager
+        .get_tool(&server_key, "brave_web_search", Some(&acme))
+        .is_none());
+
+    // Reconnecting reloads the tools for acme only.
+    manager
+        .connect_dynamic_server_with_tenant(server_config, Some("acme".to_string()))
+        .await
+        .expect("acme should reconnect");
+    assert_eq!(session_tools(&server_key, &acme), 2);
+    assert_eq!(session_tools(&server_key, &globex), 0);
+    assert_eq!(manager.list_tools(Some(&acme)).len(), 2);
+    assert!(manager.list_tools(Some(&globex)).is_empty());
+
+    manager.shutdown().await;
+}
+
 // Integration Pattern Tests
 
 #[tokio::test]
@@ -749,6 +853,8 @@ async fn test_complete_workflow() {
         warmup: Vec::new(),
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
 
     // 2. Connect to server
@@ -8,12 +8,15 @@ use std::sync::{
 };
 
 use axum::{
-    body::Body,
+    body::{to_bytes, Body},
     extract::Request,
-    http::{header::CONTENT_TYPE, StatusCode},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        StatusCode,
+    },
 };
 use serde_json::json;
-use smg::config::{CircuitBreakerConfig, RetryConfig};
+use smg::config::{CircuitBreakerConfig, FaultKind, FaultRule, RetryConfig};
 use tower::ServiceExt;
 
 use crate::common::{AppTestContext, TestRouterConfig, TestWorkerConfig};
@@ -305,4 +308,41 @@ mod fault_tolerance_tests {
 
         ctx.shutdown().await;
     }
+
+    /// Test that an injected truncation of a non-streaming body also fixes
+    /// up its Content-Length
+    #[tokio::test]
+    async fn test_injected_truncation_updates_content_length() {
+        let mut config = TestRouterConfig::round_robin(4110);
+        config.fault_injection.enabled = true;
+        config.fault_injection.rules = vec![FaultRule {
+            worker_url: None,
+            route: None,
+            probability: 1.0,
+            fault: FaultKind::TruncateStream { after_bytes: 10 },
+        }];
+
+        let ctx =
+            AppTestContext::new_with_config(config, vec![TestWorkerConfig::healthy(20110)]).await;
+        let app = ctx.create_app();
+
+        let payload = json!({
+            "text": "Truncation test",
+            "stream": false
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&payload).unwrap()))
+            .unwrap();
+
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(CONTENT_LENGTH).unwrap(), "10");
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body.len(), 10);
+
+        ctx.shutdown().await;
+    }
 }
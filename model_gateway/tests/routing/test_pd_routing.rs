@@ -168,6 +168,8 @@ mod pd_routing_unit_tests {
                     block_size: 16,
                     balance_token_usage_threshold: 1.0,
                     overload_token_usage_threshold: 1.0,
+                    tree_window_secs: 0,
+                    cache_decay_half_life_secs: 0,
                 },
             ),
             (
@@ -0,0 +1,63 @@
+use openai_protocol::{
+    common::GenerationRequest,
+    images::{ImageGenerationRequest, ImageResponseFormat},
+};
+use serde_json::{from_value, json};
+use validator::Validate;
+
+#[test]
+fn test_image_generation_request_defaults() {
+    let req: ImageGenerationRequest = from_value(json!({
+        "model": "dall-e-3",
+        "prompt": "a lighthouse at dusk"
+    }))
+    .unwrap();
+
+    assert_eq!(req.n, 1);
+    assert_eq!(req.response_format(), ImageResponseFormat::Url);
+    assert!(!req.is_stream());
+    assert_eq!(req.extract_text_for_routing(), "a lighthouse at dusk");
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn test_image_generation_request_diffusion_extensions() {
+    let req: ImageGenerationRequest = from_value(json!({
+        "model": "stable-diffusion-xl-1024-v1-0",
+        "prompt": "a lighthouse at dusk",
+        "n": 2,
+        "size": "1024x768",
+        "response_format": "b64_json",
+        "negative_prompt": "blurry",
+        "num_inference_steps": 30,
+        "guidance_scale": 7.5,
+        "seed": 42
+    }))
+    .unwrap();
+
+    assert_eq!(req.response_format(), ImageResponseFormat::B64Json);
+    assert_eq!(req.negative_prompt.as_deref(), Some("blurry"));
+    assert_eq!(req.num_inference_steps, Some(30));
+    assert_eq!(req.seed, Some(42));
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn test_image_generation_request_validation() {
+    let invalid = [
+        json!({ "model": "m", "prompt": "" }),
+        json!({ "model": "m", "prompt": "p", "n": 0 }),
+        json!({ "model": "m", "prompt": "p", "n": 11 }),
+        json!({ "model": "m", "prompt": "p", "size": "large" }),
+        json!({ "model": "m", "prompt": "p", "size": "0x512" }),
+        json!({ "model": "m", "prompt": "p", "num_inference_steps": 0 }),
+    ];
+    for body in invalid {
+        let req: ImageGenerationRequest = from_value(body.clone()).unwrap();
+        assert!(req.validate().is_err(), "expected invalid: {body}");
+    }
+
+    let auto: ImageGenerationRequest =
+        from_value(json!({ "model": "gpt-image-1", "prompt": "p", "size": "auto" })).unwrap();
+    assert!(auto.validate().is_ok());
+}
@@ -5,5 +5,7 @@
 mod chat_completion;
 mod chat_message;
 mod embedding;
+mod images;
 mod rerank;
 mod responses;
+mod score;
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use openai_protocol::{
     common::{GenerationRequest, StringOrArray, UsageInfo},
-    rerank::{RerankRequest, RerankResponse, RerankResult, V1RerankReqInput},
+    rerank::{RerankRequest, RerankResponse, RerankResult, ScoreNormalization, V1RerankReqInput},
 };
 use serde_json::{from_str, to_string, Number, Value};
 use validator::Validate;
@@ -17,6 +17,7 @@ fn test_rerank_request_serialization() {
         return_documents: true,
         rid: Some(StringOrArray::String("req-123".to_string())),
         user: Some("user-456".to_string()),
+        score_normalization: None,
     };
 
     let serialized = to_string(&request).unwrap();
@@ -65,6 +66,7 @@ fn test_rerank_request_validation_success() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert!(request.validate().is_ok());
@@ -80,6 +82,7 @@ fn test_rerank_request_validation_empty_query() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     let result = request.validate();
@@ -96,6 +99,7 @@ fn test_rerank_request_validation_whitespace_query() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     let result = request.validate();
@@ -112,6 +116,7 @@ fn test_rerank_request_validation_empty_documents() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     let result = request.validate();
@@ -128,6 +133,7 @@ fn test_rerank_request_validation_top_k_zero() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     let result = request.validate();
@@ -144,6 +150,7 @@ fn test_rerank_request_validation_top_k_greater_than_docs() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     // This should pass but log a warning
@@ -160,6 +167,7 @@ fn test_rerank_request_effective_top_k() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert_eq!(request.effective_top_k(), 2);
@@ -175,6 +183,7 @@ fn test_rerank_request_effective_top_k_none() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert_eq!(request.effective_top_k(), 3);
@@ -396,6 +405,7 @@ fn test_rerank_request_generation_request_trait() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert_eq!(request.get_model(), Some("test-model"));
@@ -414,6 +424,7 @@ fn test_rerank_request_very_long_query() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert!(request.validate().is_ok());
@@ -430,6 +441,7 @@ fn test_rerank_request_many_documents() {
         return_documents: true,
         rid: None,
         user: None,
+        score_normalization: None,
     };
 
     assert!(request.validate().is_ok());
@@ -449,6 +461,7 @@ fn test_rerank_request_special_characters() {
         return_documents: true,
         rid: Some(StringOrArray::String("req-🚀-123".to_string())),
         user: Some("user-🎉-456".to_string()),
+        score_normalization: None,
     };
 
     assert!(request.validate().is_ok());
@@ -467,6 +480,7 @@ fn test_rerank_request_rid_array() {
             "req2".to_string(),
         ])),
         user: None,
+        score_normalization: None,
     };
 
     assert!(request.validate().is_ok());
@@ -521,6 +535,7 @@ fn test_full_rerank_workflow() {
         return_documents: true,
         rid: Some(StringOrArray::String("req-123".to_string())),
         user: Some("user-456".to_string()),
+        score_normalization: None,
     };
 
     // Validate request
@@ -573,3 +588,62 @@ fn test_full_rerank_workflow() {
     assert_eq!(deserialized.results.len(), 2);
     assert_eq!(deserialized.model, response.model);
 }
+
+fn scored(scores: &[f32]) -> RerankResponse {
+    let results = scores
+        .iter()
+        .enumerate()
+        .map(|(index, &score)| RerankResult {
+            score,
+            document: None,
+            index,
+            meta_info: None,
+        })
+        .collect();
+    RerankResponse::new(results, "m".to_string(), None)
+}
+
+#[test]
+fn test_rerank_request_accepts_top_n_and_normalization() {
+    let json = r#"{
+        "query": "q",
+        "documents": ["a", "b", "c"],
+        "model": "m",
+        "top_n": 2,
+        "score_normalization": "min_max"
+    }"#;
+    let request: RerankRequest = from_str(json).unwrap();
+    assert_eq!(request.top_k, Some(2));
+    assert_eq!(
+        request.score_normalization,
+        Some(ScoreNormalization::MinMax)
+    );
+}
+
+#[test]
+fn test_rerank_response_softmax_normalization() {
+    let mut response = scored(&[2.0, -1.0, 0.5]);
+    response.normalize_scores(ScoreNormalization::Softmax);
+    let sum: f32 = response.results.iter().map(|r| r.score).sum();
+    assert!((sum - 1.0).abs() < 1e-6);
+
+    response.sort_by_score();
+    let order: Vec<usize> = response.results.iter().map(|r| r.index).collect();
+    assert_eq!(order, vec![0, 2, 1]);
+}
+
+#[test]
+fn test_rerank_response_min_max_normalization_before_top_k() {
+    let mut response = scored(&[3.0, 1.0, 2.0]);
+    response.normalize_scores(ScoreNormalization::MinMax);
+    response.sort_by_score();
+    response.apply_top_k(2);
+
+    let scores: Vec<f32> = response.results.iter().map(|r| r.score).collect();
+    assert_eq!(scores, vec![1.0, 0.5]);
+
+    // Identical scores collapse to 1 rather than dividing by zero.
+    let mut flat = scored(&[0.3, 0.3]);
+    flat.normalize_scores(ScoreNormalization::MinMax);
+    assert!(flat.results.iter().all(|r| r.score == 1.0));
+}
@@ -0,0 +1,71 @@
+use openai_protocol::{
+    common::GenerationRequest,
+    score::{ScoreItems, ScoreQuery, ScoreRequest},
+};
+use serde_json::{from_value, json};
+use validator::Validate;
+
+#[test]
+fn test_score_request_text_items() {
+    let req: ScoreRequest = from_value(json!({
+        "model": "qwen",
+        "query": "Is this positive?",
+        "items": ["great", "awful"],
+        "label_token_ids": [9454, 2753],
+        "apply_softmax": true
+    }))
+    .unwrap();
+
+    assert!(matches!(req.query, ScoreQuery::Text(_)));
+    assert!(matches!(req.items, ScoreItems::Texts(_)));
+    assert_eq!(req.items.len(), 2);
+    assert!(req.apply_softmax);
+    assert!(!req.item_first);
+    assert_eq!(req.extract_text_for_routing(), "Is this positive?");
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn test_score_request_token_items() {
+    let req: ScoreRequest = from_value(json!({
+        "model": "qwen",
+        "query": [1, 2, 3],
+        "items": [[4, 5], [6]],
+        "label_token_ids": [7]
+    }))
+    .unwrap();
+
+    assert!(matches!(req.items, ScoreItems::Tokens(_)));
+    assert_eq!(req.extract_text_for_routing(), "");
+    assert!(req.validate().is_ok());
+}
+
+#[test]
+fn test_score_request_validation() {
+    let mixed: ScoreRequest = from_value(json!({
+        "model": "qwen",
+        "query": "text",
+        "items": [[4, 5]],
+        "label_token_ids": [7]
+    }))
+    .unwrap();
+    assert!(mixed.validate().is_err());
+
+    let no_items: ScoreRequest = from_value(json!({
+        "model": "qwen",
+        "query": "text",
+        "items": [],
+        "label_token_ids": [7]
+    }))
+    .unwrap();
+    assert!(no_items.validate().is_err());
+
+    let no_labels: ScoreRequest = from_value(json!({
+        "model": "qwen",
+        "query": "text",
+        "items": "item",
+        "label_token_ids": []
+    }))
+    .unwrap();
+    assert!(no_labels.validate().is_err());
+}
@@ -0,0 +1,135 @@
+//! Golden-file tests for the protocol translations in `smg::routers::translation`.
+//!
+//! Fixtures live in `tests/fixtures/translation/<direction>/`: `<case>.json`
+//! holds `{"fixture_version", "description", "input"}` and
+//! `<case>.golden.json` the expected output (`{"error": ...}` for an input the
+//! translation rejects). A golden file pins the fields it lists; objects in
+//! the output may carry more fields, arrays must match in length.
+//!
+//! Regenerate the golden files from the current converters with
+//! `UPDATE_GOLDEN=1 cargo test --test translation_golden_test`, then review
+//! the diff.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+use smg::routers::translation::{translate, Direction, FIXTURE_VERSION};
+
+fn fixtures_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/translation")
+}
+
+/// Input fixtures of `direction`, sorted by name.
+fn cases(direction: Direction) -> Vec<PathBuf> {
+    let dir = fixtures_root().join(direction.name());
+    let mut cases: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| {
+                    path.extension().is_some_and(|ext| ext == "json")
+                        && !path.to_string_lossy().ends_with(".golden.json")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    cases.sort();
+    cases
+}
+
+fn read_json(path: &Path) -> Value {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+}
+
+/// Record where `actual` departs from the fields `expected` pins.
+fn compare(expected: &Value, actual: &Value, path: &str, mismatches: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let field = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(actual) => compare(value, actual, &field, mismatches),
+                    None => mismatches.push(format!("{field}: missing, expected {value}")),
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                compare(expected, actual, &format!("{path}[{i}]"), mismatches);
+            }
+        }
+        _ if expected == actual => {}
+        _ => mismatches.push(format!("{path}: expected {expected}, got {actual}")),
+    }
+}
+
+#[test]
+fn every_direction_has_fixtures() {
+    for direction in Direction::ALL {
+        assert!(
+            !cases(direction).is_empty(),
+            "no fixtures for translation direction {}",
+            direction.name()
+        );
+    }
+    assert!(Direction::ALL.iter().any(|d| d.is_streaming()));
+    assert!(Direction::ALL.iter().any(|d| !d.is_streaming()));
+
+    for entry in fs::read_dir(fixtures_root()).unwrap() {
+        let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+        assert!(
+            Direction::from_name(&name).is_some(),
+            "fixture directory {name} matches no translation direction"
+        );
+    }
+}
+
+#[test]
+fn translations_match_golden_files() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+    let mut failures = Vec::new();
+
+    for direction in Direction::ALL {
+        for case in cases(direction) {
+            let fixture = read_json(&case);
+            let name = format!(
+                "{}/{}",
+                direction.name(),
+                case.file_name().unwrap().to_string_lossy()
+            );
+            assert_eq!(
+                fixture["fixture_version"],
+                json!(FIXTURE_VERSION),
+                "{name}: fixture written for another fixture version"
+            );
+
+            let actual = translate(direction, &fixture["input"])
+                .unwrap_or_else(|error| json!({ "error": error }));
+            let golden_path = case.with_extension("golden.json");
+            if update {
+                let mut text = serde_json::to_string_pretty(&actual).unwrap();
+                text.push('\n');
+                fs::write(&golden_path, text).unwrap();
+                continue;
+            }
+
+            let expected = read_json(&golden_path);
+            let mut mismatches = Vec::new();
+            compare(&expected, &actual, "$", &mut mismatches);
+            if !mismatches.is_empty() {
+                failures.push(format!("{name}:\n  {}", mismatches.join("\n  ")));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "translations differ from their golden files \
+         (rerun with UPDATE_GOLDEN=1 if the change is intended):\n{}",
+        failures.join("\n")
+    );
+}
@@ -133,6 +133,8 @@ async fn create_test_context_with_wasm() -> Arc<AppContext> {
         warmup: vec![],
         inventory: Default::default(),
         policy: Default::default(),
+        limits: Default::default(),
+        sampling: Default::default(),
     };
     let mcp_orchestrator = McpOrchestrator::new(empty_config)
         .await
@@ -197,6 +199,7 @@ async fn create_test_app_with_wasm() -> (axum::Router, Arc<AppContext>, TempDir)
         router_manager: None,
         mesh_handler: None,
         mesh_adapters: None,
+        rolling_restart: None,
     });
 
     let request_id_headers = vec!["x-request-id".to_string(), "x-correlation-id".to_string()];
@@ -236,6 +239,8 @@ async fn test_wasm_api_add_module() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -301,6 +306,8 @@ async fn test_wasm_api_add_module_invalid_file() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -356,6 +363,8 @@ async fn test_wasm_api_add_module_invalid_wasm() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -407,6 +416,8 @@ async fn test_wasm_api_list_modules() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -475,6 +486,8 @@ async fn test_wasm_api_remove_module() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -593,6 +606,8 @@ async fn test_wasm_module_duplicate_sha256() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -624,6 +639,8 @@ async fn test_wasm_module_duplicate_sha256() {
             attach_points: vec![WasmModuleAttachPoint::Middleware(
                 MiddlewareAttachPoint::OnRequest,
             )],
+            request_body_fields: None,
+            tenant: None,
             add_result: None,
         }],
     };
@@ -692,6 +709,8 @@ async fn test_wasm_module_execution() {
         attach_points: vec![WasmModuleAttachPoint::Middleware(
             MiddlewareAttachPoint::OnRequest,
         )],
+        request_body_fields: None,
+        tenant: None,
         add_result: None,
     };
 
@@ -766,6 +785,7 @@ async fn test_wasm_module_execution() {
         body: vec![],
         request_id: "test-request-id".to_string(),
         now_epoch_ms: 1000,
+        body_fields: vec![],
     };
 
     let input = WasmComponentInput::MiddlewareRequest(request);